@@ -109,6 +109,9 @@ struct SimSettings {
     n_steps: usize,
     /// Multiple of 0.01
     dt: usize,
+    /// Send all messages between threads over a single channel
+    #[serde(default)]
+    multiplex_communicator: bool,
 }
 
 fn run_simulation(sim_settings: &SimSettings) -> Result<(), chili::SimulationError> {
@@ -165,12 +168,26 @@ fn run_simulation(sim_settings: &SimSettings) -> Result<(), chili::SimulationErr
         show_progressbar: false,
     };
 
-    chili::run_simulation!(
-        agents: cells,
-        domain: domain,
-        settings: settings,
-        aspects: [Mechanics, Interaction],
-    )?;
+    // Both layouts generate distinct types which is why results are not returned
+    match sim_settings.multiplex_communicator {
+        false => {
+            chili::run_simulation!(
+                agents: cells,
+                domain: domain,
+                settings: settings,
+                aspects: [Mechanics, Interaction],
+            )?;
+        }
+        true => {
+            chili::run_simulation!(
+                agents: cells,
+                domain: domain,
+                settings: settings,
+                aspects: [Mechanics, Interaction],
+                multiplex_communicator: true,
+            )?;
+        }
+    }
     Ok(())
 }
 
@@ -300,6 +317,7 @@ fn problem_size_scaling(
                 domain_size,
                 n_steps: 10,
                 dt: 10,
+                multiplex_communicator: false,
             }
         })
         .collect();
@@ -336,6 +354,7 @@ fn thread_scaling(
                 domain_size,
                 n_steps: 5,
                 dt: 10,
+                multiplex_communicator: false,
             }
         })
         .collect();
@@ -352,6 +371,49 @@ fn thread_scaling(
     )
 }
 
+fn communicator_layout(
+    args: &CLIArgs,
+    threads: Vec<usize>,
+    n_domain_size: usize,
+) -> Vec<BenchmarkResult> {
+    let (domain_size, n_cells) = n_domain_size_to_domain_size_and_n_cells(n_domain_size);
+    // Compare one channel per message type against a single multiplexed channel
+    let simulation_settings: Vec<_> = threads
+        .into_iter()
+        .flat_map(|n_threads| {
+            [false, true].map(|multiplex_communicator| SimSettings {
+                n_cells_1: n_cells,
+                n_cells_2: n_cells,
+                n_threads: n_threads.try_into().unwrap(),
+                domain_size,
+                n_steps: 5,
+                dt: 10,
+                multiplex_communicator,
+            })
+        })
+        .collect();
+    run_sim(
+        args,
+        simulation_settings,
+        |setting: &SimSettings, n_sample: usize| {
+            let layout = match setting.multiplex_communicator {
+                true => "multiplexed",
+                false => "per message",
+            };
+            format!(
+                "Threads: {} Channels: {} Sample: {}",
+                setting.n_threads,
+                layout,
+                n_sample + 1
+            )
+        },
+        |settings: &SimSettings| {
+            run_simulation(settings).unwrap();
+        },
+        "communicator-layout",
+    )
+}
+
 impl BenchmarkResult {
     fn get_next_index_value(
         storage_path: &std::path::Path,
@@ -446,6 +508,13 @@ enum SubCommand {
         #[arg(short, default_value_t = 1)]
         n_threads: usize,
     },
+    /// Compares communicators with one channel per message type and a single channel
+    Communicator {
+        /// List of thread configurations to benchmark
+        threads: Vec<usize>,
+        #[arg(short, default_value_t = 5)]
+        n_domain_size: usize,
+    },
 }
 
 /// Create new cell_sorting benchmark for thread or domain_size scaling
@@ -508,6 +577,12 @@ fn main() {
             } => {
                 problem_size_scaling(&args, problem_sizes.clone(), *n_threads);
             }
+            SubCommand::Communicator {
+                threads,
+                n_domain_size,
+            } => {
+                communicator_layout(&args, threads.clone(), *n_domain_size);
+            }
         }
     }
 }
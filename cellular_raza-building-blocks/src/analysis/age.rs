@@ -0,0 +1,149 @@
+use cellular_raza_concepts::Age;
+use serde::{Deserialize, Serialize};
+
+/// Distribution of the [Age] of cells at a single save point.
+///
+/// Ages are binned into a histogram whose `i`-th bin counts cells with
+/// $i w \leq a < (i+1)w$ where $w$ is the bin width.
+/// All statistics are zero if no cells are given.
+/// ```
+/// # use cellular_raza_building_blocks::*;
+/// # use cellular_raza_concepts::Age;
+/// let cells: Vec<_> = [0.5, 1.5, 2.0, 3.5, 12.0]
+///     .into_iter()
+///     .map(|age| CellAge {
+///         age,
+///         senescence_age: 10.0,
+///         senescent: age >= 10.0,
+///     })
+///     .collect();
+/// let distribution = AgeDistribution::from_cells(&cells, 1.0);
+/// assert_eq!(distribution.n_cells, 5);
+/// assert_eq!(distribution.n_senescent, 1);
+/// assert_eq!(distribution.mean, 3.9);
+/// assert_eq!(distribution.quantile(0.5), 2.0);
+/// assert_eq!(distribution.histogram[..4], [1, 1, 1, 1]);
+/// assert_eq!(distribution.histogram.len(), 13);
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct AgeDistribution {
+    /// Ages of all cells in ascending order
+    pub ages: Vec<f64>,
+    /// Total number of cells
+    pub n_cells: usize,
+    /// Number of senescent cells
+    pub n_senescent: usize,
+    /// Mean age
+    pub mean: f64,
+    /// Standard deviation of the ages
+    pub std_dev: f64,
+    /// Width $w$ of the bins of the histogram
+    pub bin_width: f64,
+    /// Number of cells within every bin
+    pub histogram: Vec<usize>,
+}
+
+impl AgeDistribution {
+    /// Calculates the distribution of the given cells.
+    ///
+    /// # Panics
+    /// The bin width needs to be positive.
+    pub fn from_cells<'a, C>(cells: impl IntoIterator<Item = &'a C>, bin_width: f64) -> Self
+    where
+        C: Age<f64> + 'a,
+    {
+        assert!(bin_width > 0.0, "bin width needs to be positive");
+        let mut n_senescent = 0;
+        let mut ages: Vec<_> = cells
+            .into_iter()
+            .map(|cell| {
+                n_senescent += cell.is_senescent() as usize;
+                cell.get_age()
+            })
+            .collect();
+        ages.sort_by(f64::total_cmp);
+        let n_cells = ages.len();
+        let (mean, std_dev) = match n_cells {
+            0 => (0.0, 0.0),
+            n => {
+                let mean = ages.iter().sum::<f64>() / n as f64;
+                let variance = ages.iter().map(|a| (a - mean).powi(2)).sum::<f64>() / n as f64;
+                (mean, variance.sqrt())
+            }
+        };
+        let n_bins = ages
+            .last()
+            .map_or(0, |max| (max.max(0.0) / bin_width).floor() as usize + 1);
+        let mut histogram = vec![0; n_bins];
+        for age in ages.iter() {
+            histogram[(age.max(0.0) / bin_width).floor() as usize] += 1;
+        }
+        Self {
+            ages,
+            n_cells,
+            n_senescent,
+            mean,
+            std_dev,
+            bin_width,
+            histogram,
+        }
+    }
+
+    /// Fraction of senescent cells
+    pub fn senescent_fraction(&self) -> f64 {
+        match self.n_cells {
+            0 => 0.0,
+            n => self.n_senescent as f64 / n as f64,
+        }
+    }
+
+    /// Age below which the given fraction `q` of all cells lies.
+    ///
+    /// Uses the nearest rank of the sorted ages.
+    pub fn quantile(&self, q: f64) -> f64 {
+        match self.ages.len() {
+            0 => 0.0,
+            n => {
+                let rank = (q.clamp(0.0, 1.0) * (n - 1) as f64).round() as usize;
+                self.ages[rank]
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_age_distribution {
+    use super::*;
+    use crate::CellAge;
+
+    #[test]
+    fn empty_population() {
+        let distribution = AgeDistribution::from_cells(&Vec::<CellAge>::new(), 0.5);
+        assert_eq!(distribution.n_cells, 0);
+        assert_eq!(distribution.mean, 0.0);
+        assert_eq!(distribution.senescent_fraction(), 0.0);
+        assert_eq!(distribution.quantile(0.9), 0.0);
+        assert!(distribution.histogram.is_empty());
+    }
+
+    #[test]
+    fn statistics_of_uniform_ages() {
+        let cells: Vec<_> = (0..100)
+            .map(|n| {
+                let mut cell = CellAge::new(75.0);
+                cell.set_age(n as f64);
+                cell.update_senescence().unwrap();
+                cell
+            })
+            .collect();
+        let distribution = AgeDistribution::from_cells(&cells, 10.0);
+        assert_eq!(distribution.n_senescent, 25);
+        assert_eq!(distribution.senescent_fraction(), 0.25);
+        assert!((distribution.mean - 49.5).abs() < 1e-12);
+        // Variance of the discrete uniform distribution
+        assert!((distribution.std_dev.powi(2) - (100f64.powi(2) - 1.0) / 12.0).abs() < 1e-9);
+        assert_eq!(distribution.histogram, vec![10; 10]);
+        assert_eq!(distribution.quantile(0.0), 0.0);
+        assert_eq!(distribution.quantile(1.0), 99.0);
+    }
+}
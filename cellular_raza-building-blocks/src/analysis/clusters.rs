@@ -0,0 +1,227 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{ContactGraph, ContactNode};
+
+impl<Id> ContactGraph<Id>
+where
+    Id: Clone + Ord,
+{
+    /// Connected components of all cells which satisfy the given filter.
+    ///
+    /// Only contacts between two selected cells connect them.
+    /// Every cluster is sorted and the clusters are ordered by their smallest id.
+    pub fn clusters(&self, filter: impl Fn(&Id, &ContactNode) -> bool) -> Vec<Vec<Id>> {
+        let ids: Vec<&Id> = self
+            .nodes
+            .iter()
+            .filter(|(id, node)| filter(id, node))
+            .map(|(id, _)| id)
+            .collect();
+        let index: BTreeMap<&Id, usize> = ids.iter().enumerate().map(|(n, id)| (*id, n)).collect();
+
+        // Union-find with path halving
+        let mut parents: Vec<usize> = (0..ids.len()).collect();
+        fn root(parents: &mut [usize], mut n: usize) -> usize {
+            while parents[n] != n {
+                parents[n] = parents[parents[n]];
+                n = parents[n];
+            }
+            n
+        }
+        for edge in self.edges.iter() {
+            if let (Some(&n), Some(&m)) = (index.get(&edge.source), index.get(&edge.target)) {
+                let (root_n, root_m) = (root(&mut parents, n), root(&mut parents, m));
+                parents[root_n.max(root_m)] = root_n.min(root_m);
+            }
+        }
+
+        let mut clusters = BTreeMap::<usize, Vec<Id>>::new();
+        for (n, id) in ids.into_iter().enumerate() {
+            clusters
+                .entry(root(&mut parents, n))
+                .or_default()
+                .push(id.clone());
+        }
+        clusters.into_values().collect()
+    }
+}
+
+/// Distribution of the sizes of connected clusters of cells at a single save point.
+///
+/// Clusters are the connected components of the [ContactGraph] and are a common readout of
+/// aggregation models.
+/// The `histogram` counts the clusters of every size, ie. its `i`-th entry is the number of
+/// clusters which consist of `i` cells.
+///
+/// ```
+/// # use cellular_raza_building_blocks::*;
+/// # use std::collections::BTreeMap;
+/// let node = |species: f64| ContactNode {
+///     position: vec![0.0, 0.0],
+///     attributes: BTreeMap::from([("species".to_owned(), species)]),
+/// };
+/// let edge = |source, target| ContactEdge {
+///     source,
+///     target,
+///     distance: 1.0,
+///     force: 0.0,
+///     normal_force: 0.0,
+/// };
+/// let graph = ContactGraph {
+///     nodes: BTreeMap::from([(0, node(0.0)), (1, node(0.0)), (2, node(1.0)), (3, node(0.0))]),
+///     edges: vec![edge(0, 1), edge(1, 2)],
+/// };
+/// let sizes = ClusterSizes::from_graph(&graph, |_, _| true);
+/// assert_eq!(sizes.sizes, vec![3, 1]);
+/// assert_eq!(sizes.histogram, vec![0, 1, 0, 1]);
+///
+/// // Restrict the clusters to a single species
+/// let sizes = ClusterSizes::from_graph(&graph, |_, node| node.attributes["species"] == 0.0);
+/// assert_eq!(sizes.sizes, vec![2, 1]);
+/// assert_eq!(sizes.mean_size, 1.5);
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ClusterSizes {
+    /// Sizes of all clusters in descending order
+    pub sizes: Vec<usize>,
+    /// Number of cells in all clusters
+    pub n_cells: usize,
+    /// Number of clusters
+    pub n_clusters: usize,
+    /// Mean number of cells per cluster
+    pub mean_size: f64,
+    /// Number of clusters of every size
+    pub histogram: Vec<usize>,
+}
+
+impl ClusterSizes {
+    /// Calculates the cluster sizes of all cells in the graph which satisfy the given filter.
+    ///
+    /// See [ContactGraph::clusters].
+    pub fn from_graph<Id>(
+        graph: &ContactGraph<Id>,
+        filter: impl Fn(&Id, &ContactNode) -> bool,
+    ) -> Self
+    where
+        Id: Clone + Ord,
+    {
+        let mut sizes: Vec<usize> = graph
+            .clusters(filter)
+            .iter()
+            .map(|cluster| cluster.len())
+            .collect();
+        sizes.sort_by(|a, b| b.cmp(a));
+        let n_cells = sizes.iter().sum();
+        let n_clusters = sizes.len();
+        let mean_size = match n_clusters {
+            0 => 0.0,
+            n => n_cells as f64 / n as f64,
+        };
+        let mut histogram = vec![0; sizes.first().map_or(0, |max| max + 1)];
+        for size in sizes.iter() {
+            histogram[*size] += 1;
+        }
+        Self {
+            sizes,
+            n_cells,
+            n_clusters,
+            mean_size,
+            histogram,
+        }
+    }
+
+    /// Size of the largest cluster
+    pub fn largest(&self) -> usize {
+        self.sizes.first().copied().unwrap_or(0)
+    }
+
+    /// Mean size of the cluster which contains a randomly chosen cell.
+    ///
+    /// In contrast to the [mean_size](ClusterSizes::mean_size), this average is weighted by
+    /// the number of cells in every cluster and thus less sensitive to isolated cells.
+    pub fn weighted_mean_size(&self) -> f64 {
+        match self.n_cells {
+            0 => 0.0,
+            n => self.sizes.iter().map(|s| s * s).sum::<usize>() as f64 / n as f64,
+        }
+    }
+}
+
+/// Time series of the [ClusterSizes] of the graphs at every save point.
+///
+/// The graphs are given together with their iteration and all cells are selected by the same
+/// filter.
+pub fn cluster_size_series<'a, Id>(
+    graphs: impl IntoIterator<Item = (u64, &'a ContactGraph<Id>)>,
+    filter: impl Fn(&Id, &ContactNode) -> bool,
+) -> BTreeMap<u64, ClusterSizes>
+where
+    Id: 'a + Clone + Ord,
+{
+    graphs
+        .into_iter()
+        .map(|(iteration, graph)| (iteration, ClusterSizes::from_graph(graph, &filter)))
+        .collect()
+}
+
+#[cfg(test)]
+mod test_clusters {
+    use super::*;
+    use crate::ContactEdge;
+
+    fn chain_graph(n_cells: usize, broken: &[usize]) -> ContactGraph<usize> {
+        let nodes = (0..n_cells)
+            .map(|n| {
+                let node = ContactNode {
+                    position: vec![n as f64],
+                    attributes: BTreeMap::new(),
+                };
+                (n, node)
+            })
+            .collect();
+        let edges = (1..n_cells)
+            .filter(|n| !broken.contains(n))
+            .map(|n| ContactEdge {
+                source: n - 1,
+                target: n,
+                distance: 1.0,
+                force: 0.0,
+                normal_force: 0.0,
+            })
+            .collect();
+        ContactGraph { nodes, edges }
+    }
+
+    #[test]
+    fn aggregation_over_time() {
+        // Cells of a chain successively connect into a single cluster
+        let graphs = [
+            chain_graph(6, &[1, 2, 3, 4, 5]),
+            chain_graph(6, &[2, 4]),
+            chain_graph(6, &[]),
+        ];
+        let series = cluster_size_series(
+            graphs.iter().enumerate().map(|(n, g)| (10 * n as u64, g)),
+            |_, _| true,
+        );
+        let largest: Vec<_> = series.values().map(|s| s.largest()).collect();
+        assert_eq!(largest, vec![1, 2, 6]);
+        assert_eq!(series[&0].n_clusters, 6);
+        assert_eq!(series[&10].sizes, vec![2, 2, 2]);
+        assert_eq!(series[&20].weighted_mean_size(), 6.0);
+        assert!(series.values().all(|s| s.n_cells == 6));
+    }
+
+    #[test]
+    fn filter_splits_clusters() {
+        let graph = chain_graph(5, &[]);
+        let clusters = graph.clusters(|id, _| *id != 2);
+        assert_eq!(clusters, vec![vec![0, 1], vec![3, 4]]);
+        let sizes = ClusterSizes::from_graph(&graph, |_, _| false);
+        assert_eq!(sizes.n_clusters, 0);
+        assert_eq!(sizes.largest(), 0);
+        assert!(sizes.histogram.is_empty());
+    }
+}
@@ -0,0 +1,313 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::io::Write;
+
+use cellular_raza_concepts::{CalcError, Interaction, Position, Velocity};
+use nalgebra::SVector;
+use serde::{Deserialize, Serialize};
+
+/// Cell within a [ContactGraph]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ContactNode {
+    /// Position of the cell
+    pub position: Vec<f64>,
+    /// User-defined attributes such as the volume or species of the cell
+    pub attributes: BTreeMap<String, f64>,
+}
+
+/// Contact between two cells of a [ContactGraph]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ContactEdge<Id> {
+    /// First cell of the contact
+    pub source: Id,
+    /// Second cell of the contact
+    pub target: Id,
+    /// Distance between both cells
+    pub distance: f64,
+    /// Magnitude of the force which the target exerts on the source
+    pub force: f64,
+    /// Component of the force along the line from the target to the source.
+    ///
+    /// Positive values indicate that both cells overlap and repel each other.
+    pub normal_force: f64,
+}
+
+/// Instantaneous network of cell-cell contacts.
+///
+/// Every cell is a node and two cells are connected by an undirected edge if they are
+/// neighbors according to [Interaction::is_neighbor] or exert a non-zero force onto each other.
+/// The graph is usually calculated from the cells of every save point and exported in the
+/// [GraphML](http://graphml.graphdrawing.org/) format by [ContactGraph::write_graphml] or as a
+/// plain edge list by [ContactGraph::write_edge_list] for network analyses with external tools.
+/// All pairs of cells are compared such that the cost grows quadratically with the number of
+/// cells.
+///
+/// ```
+/// # use cellular_raza_building_blocks::*;
+/// # use nalgebra::Vector2;
+/// # use std::collections::BTreeMap;
+/// let interaction = MorsePotential {
+///     radius: 1.0,
+///     potential_stiffness: 0.5,
+///     cutoff: 2.5,
+///     strength: 1.0,
+/// };
+/// let cells: Vec<_> = [[0.0, 0.0], [1.5, 0.0], [6.0, 0.0]]
+///     .into_iter()
+///     .map(|pos| MyCell {
+///         mechanics: NewtonDamped2D {
+///             pos: Vector2::from(pos),
+///             vel: Vector2::zeros(),
+///             damping_constant: 1.0,
+///             mass: 1.0,
+///         },
+///         interaction: interaction.clone(),
+///     })
+///     .collect();
+/// # use cellular_raza_concepts::*;
+/// # #[derive(CellAgent)]
+/// # struct MyCell {
+/// #     #[Mechanics]
+/// #     mechanics: NewtonDamped2D,
+/// #     #[Interaction]
+/// #     interaction: MorsePotential,
+/// # }
+/// let graph = ContactGraph::from_cells(cells.iter().enumerate(), |cell| {
+///     BTreeMap::from([("radius".to_owned(), cell.interaction.radius)])
+/// })?;
+/// assert_eq!(graph.nodes.len(), 3);
+/// assert_eq!(graph.edges.len(), 1);
+/// assert!(graph.edges[0].normal_force > 0.0);
+///
+/// let mut edge_list = Vec::new();
+/// graph.write_edge_list(&mut edge_list)?;
+/// assert!(String::from_utf8(edge_list)?.starts_with("0\t1\t1.5\t"));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ContactGraph<Id: Ord> {
+    /// All cells identified by their id
+    pub nodes: BTreeMap<Id, ContactNode>,
+    /// All contacts between cells
+    pub edges: Vec<ContactEdge<Id>>,
+}
+
+impl<Id> ContactGraph<Id>
+where
+    Id: Clone + Ord,
+{
+    /// Calculates the contact graph of the given cells.
+    ///
+    /// The `attributes` of every node are obtained from the given function.
+    pub fn from_cells<'a, C, Inf, const D: usize>(
+        cells: impl IntoIterator<Item = (Id, &'a C)>,
+        attributes: impl Fn(&C) -> BTreeMap<String, f64>,
+    ) -> Result<Self, CalcError>
+    where
+        C: 'a
+            + Position<SVector<f64, D>>
+            + Velocity<SVector<f64, D>>
+            + Interaction<SVector<f64, D>, SVector<f64, D>, SVector<f64, D>, Inf>,
+    {
+        let cells: Vec<_> = cells
+            .into_iter()
+            .map(|(id, cell)| {
+                let inf = cell.get_interaction_information();
+                (id, cell, cell.pos(), cell.velocity(), inf)
+            })
+            .collect();
+        let mut edges = Vec::new();
+        for (n, (id1, cell1, pos1, vel1, _)) in cells.iter().enumerate() {
+            for (id2, _, pos2, vel2, inf2) in cells.iter().skip(n + 1) {
+                let (force, _) = cell1.calculate_force_between(pos1, vel1, pos2, vel2, inf2)?;
+                if cell1.is_neighbor(pos1, pos2, inf2)? || force.norm() > 0.0 {
+                    let distance = (pos1 - pos2).norm();
+                    let normal_force = if distance > 0.0 {
+                        force.dot(&(pos1 - pos2)) / distance
+                    } else {
+                        0.0
+                    };
+                    edges.push(ContactEdge {
+                        source: id1.clone(),
+                        target: id2.clone(),
+                        distance,
+                        force: force.norm(),
+                        normal_force,
+                    });
+                }
+            }
+        }
+        let nodes = cells
+            .into_iter()
+            .map(|(id, cell, pos, _, _)| {
+                let node = ContactNode {
+                    position: pos.iter().copied().collect(),
+                    attributes: attributes(cell),
+                };
+                (id, node)
+            })
+            .collect();
+        Ok(Self { nodes, edges })
+    }
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Names of the coordinates which are recognized by most graph visualization tools
+fn coordinate_name(index: usize) -> String {
+    match index {
+        0 => "x".to_owned(),
+        1 => "y".to_owned(),
+        2 => "z".to_owned(),
+        n => format!("x{n}"),
+    }
+}
+
+impl<Id> ContactGraph<Id>
+where
+    Id: Display + Ord,
+{
+    /// Writes the graph in the [GraphML](http://graphml.graphdrawing.org/) format.
+    ///
+    /// The coordinates of cells are stored as the node attributes `x`, `y` and `z` followed by
+    /// all user-defined attributes.
+    /// Edges carry the `distance`, `force` and `normal_force` of the contact.
+    pub fn write_graphml(&self, mut writer: impl Write) -> std::io::Result<()> {
+        let n_coordinates = self
+            .nodes
+            .values()
+            .map(|node| node.position.len())
+            .max()
+            .unwrap_or(0);
+        let mut node_keys: Vec<String> = (0..n_coordinates).map(coordinate_name).collect();
+        for node in self.nodes.values() {
+            for key in node.attributes.keys() {
+                if !node_keys.contains(key) {
+                    node_keys.push(key.clone());
+                }
+            }
+        }
+        let edge_keys = ["distance", "force", "normal_force"];
+
+        writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            writer,
+            r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#
+        )?;
+        for (n, key) in node_keys.iter().enumerate() {
+            writeln!(
+                writer,
+                r#"  <key id="n{n}" for="node" attr.name="{}" attr.type="double"/>"#,
+                escape_xml(key)
+            )?;
+        }
+        for (n, key) in edge_keys.iter().enumerate() {
+            writeln!(
+                writer,
+                r#"  <key id="e{n}" for="edge" attr.name="{key}" attr.type="double"/>"#
+            )?;
+        }
+        writeln!(writer, r#"  <graph edgedefault="undirected">"#)?;
+        for (id, node) in self.nodes.iter() {
+            writeln!(writer, r#"    <node id="{}">"#, escape_xml(&id.to_string()))?;
+            let values = node
+                .position
+                .iter()
+                .enumerate()
+                .map(|(i, x)| (i, *x))
+                .chain(
+                    node_keys
+                        .iter()
+                        .enumerate()
+                        .skip(n_coordinates)
+                        .filter_map(|(i, key)| node.attributes.get(key).map(|value| (i, *value))),
+                );
+            for (i, value) in values {
+                writeln!(writer, r#"      <data key="n{i}">{value}</data>"#)?;
+            }
+            writeln!(writer, "    </node>")?;
+        }
+        for edge in self.edges.iter() {
+            writeln!(
+                writer,
+                r#"    <edge source="{}" target="{}">"#,
+                escape_xml(&edge.source.to_string()),
+                escape_xml(&edge.target.to_string())
+            )?;
+            for (i, value) in [edge.distance, edge.force, edge.normal_force]
+                .into_iter()
+                .enumerate()
+            {
+                writeln!(writer, r#"      <data key="e{i}">{value}</data>"#)?;
+            }
+            writeln!(writer, "    </edge>")?;
+        }
+        writeln!(writer, "  </graph>")?;
+        writeln!(writer, "</graphml>")
+    }
+
+    /// Writes all edges as tab-separated lines.
+    ///
+    /// Every line contains the source, target, distance, force and normal force of a contact.
+    pub fn write_edge_list(&self, mut writer: impl Write) -> std::io::Result<()> {
+        for edge in self.edges.iter() {
+            writeln!(
+                writer,
+                "{}\t{}\t{}\t{}\t{}",
+                edge.source, edge.target, edge.distance, edge.force, edge.normal_force
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_contact_graph {
+    use super::*;
+
+    #[test]
+    fn graphml_contains_all_elements() -> Result<(), Box<dyn std::error::Error>> {
+        let graph = ContactGraph {
+            nodes: BTreeMap::from([
+                (
+                    "a<1>".to_owned(),
+                    ContactNode {
+                        position: vec![0.0, 1.0],
+                        attributes: BTreeMap::from([("volume".to_owned(), 2.0)]),
+                    },
+                ),
+                (
+                    "b".to_owned(),
+                    ContactNode {
+                        position: vec![1.0, 1.0],
+                        attributes: BTreeMap::new(),
+                    },
+                ),
+            ]),
+            edges: vec![ContactEdge {
+                source: "a<1>".to_owned(),
+                target: "b".to_owned(),
+                distance: 1.0,
+                force: 0.5,
+                normal_force: -0.5,
+            }],
+        };
+        let mut output = Vec::new();
+        graph.write_graphml(&mut output)?;
+        let output = String::from_utf8(output)?;
+        assert!(output.contains(r#"<key id="n2" for="node" attr.name="volume""#));
+        assert!(output.contains(r#"<node id="a&lt;1&gt;">"#));
+        assert!(output.contains(r#"<data key="n2">2</data>"#));
+        assert!(output.contains(r#"<edge source="a&lt;1&gt;" target="b">"#));
+        assert!(output.contains(r#"<data key="e2">-0.5</data>"#));
+        assert_eq!(output.matches("<node ").count(), 2);
+        assert!(output.ends_with("</graphml>\n"));
+        Ok(())
+    }
+}
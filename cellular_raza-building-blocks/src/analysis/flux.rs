@@ -0,0 +1,207 @@
+use std::collections::BTreeMap;
+
+use cellular_raza_concepts::PositionLike;
+use nalgebra::SVector;
+use serde::{Deserialize, Serialize};
+
+use crate::TargetRegion;
+
+/// Crossings of cells through the boundary of a region during a single save interval.
+///
+/// Planes are described by a [TargetRegion::HalfSpace] and spherical shells by a
+/// [TargetRegion::Sphere].
+/// A cell enters the region if it was outside at the previous save point and inside at the
+/// current one.
+/// For planes, entering thus corresponds to crossing in the direction of the normal vector.
+/// Cells are matched between both save points by their id.
+/// Cells which were created or removed in between are not counted and cells which cross
+/// multiple times within one interval are only counted by their net crossing.
+/// Besides the number of cells, the `net_amount` integrates an arbitrary quantity carried by
+/// the crossing cells such as the amount of an intracellular species.
+///
+/// ```
+/// # use cellular_raza_building_blocks::*;
+/// # use nalgebra::Vector2;
+/// // Membrane of a transwell at y=0 which cells cross towards negative y
+/// let membrane = TargetRegion::HalfSpace {
+///     point: Vector2::zeros(),
+///     normal: Vector2::from([0.0, -1.0]),
+/// };
+/// let previous = [(0, [0.0, 1.0]), (1, [1.0, 1.0]), (2, [2.0, -1.0])];
+/// let current = [(0, [0.0, -0.5]), (1, [1.0, 0.5]), (2, [2.0, 0.5])];
+/// let flux = FluxMeasurement::from_cells(
+///     &membrane,
+///     previous.iter().map(|(id, pos)| (*id, pos)),
+///     current.iter().map(|(id, pos)| (*id, pos)),
+///     |_| "migrating",
+///     |_| 1.0,
+/// );
+/// assert_eq!(flux.entering["migrating"], 1);
+/// assert_eq!(flux.leaving["migrating"], 1);
+/// assert_eq!(flux.net_crossings("migrating"), 0);
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct FluxMeasurement<S: Ord> {
+    /// Number of cells of every species which entered the region
+    pub entering: BTreeMap<S, usize>,
+    /// Number of cells of every species which left the region
+    pub leaving: BTreeMap<S, usize>,
+    /// Quantity carried into the region minus the quantity carried out of it
+    pub net_amount: BTreeMap<S, f64>,
+}
+
+impl<S> FluxMeasurement<S>
+where
+    S: Clone + Ord,
+{
+    /// Compares the cells of two consecutive save points.
+    ///
+    /// The species of every cell and the quantity which it carries are determined by the
+    /// given functions from its state at the current save point.
+    pub fn from_cells<'a, Id, C, const D: usize>(
+        region: &TargetRegion<D>,
+        previous: impl IntoIterator<Item = (Id, &'a C)>,
+        current: impl IntoIterator<Item = (Id, &'a C)>,
+        species: impl Fn(&C) -> S,
+        amount: impl Fn(&C) -> f64,
+    ) -> Self
+    where
+        Id: Ord,
+        C: 'a + PositionLike<f64, D>,
+    {
+        let inside =
+            |cell: &C| region.contains(&SVector::<f64, D>::from(cell.representative_point()));
+        let previous: BTreeMap<Id, bool> = previous
+            .into_iter()
+            .map(|(id, cell)| (id, inside(cell)))
+            .collect();
+        let mut flux = Self {
+            entering: BTreeMap::new(),
+            leaving: BTreeMap::new(),
+            net_amount: BTreeMap::new(),
+        };
+        for (id, cell) in current {
+            let Some(was_inside) = previous.get(&id) else {
+                continue;
+            };
+            let (counter, sign) = match (was_inside, inside(cell)) {
+                (false, true) => (&mut flux.entering, 1.0),
+                (true, false) => (&mut flux.leaving, -1.0),
+                _ => continue,
+            };
+            let species = species(cell);
+            *counter.entry(species.clone()).or_default() += 1;
+            *flux.net_amount.entry(species).or_default() += sign * amount(cell);
+        }
+        flux
+    }
+
+    /// Number of cells of the given species which entered minus those which left the region
+    pub fn net_crossings<Q>(&self, species: &Q) -> i64
+    where
+        S: std::borrow::Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.entering.get(species).copied().unwrap_or(0) as i64
+            - self.leaving.get(species).copied().unwrap_or(0) as i64
+    }
+}
+
+/// Time series of the [FluxMeasurement]s between all consecutive save points.
+///
+/// Every measurement is stored at the iteration which ends its save interval.
+/// The save points need to be given in ascending order.
+pub fn flux_series<'a, Id, C, S, I, const D: usize>(
+    region: &TargetRegion<D>,
+    save_points: impl IntoIterator<Item = (u64, I)>,
+    species: impl Fn(&C) -> S,
+    amount: impl Fn(&C) -> f64,
+) -> BTreeMap<u64, FluxMeasurement<S>>
+where
+    Id: Clone + Ord,
+    C: 'a + PositionLike<f64, D>,
+    S: Clone + Ord,
+    I: IntoIterator<Item = (Id, &'a C)>,
+{
+    let mut series = BTreeMap::new();
+    let mut previous: Option<Vec<(Id, &'a C)>> = None;
+    for (iteration, cells) in save_points {
+        let cells: Vec<_> = cells.into_iter().collect();
+        if let Some(previous) = previous.take() {
+            let flux = FluxMeasurement::from_cells(
+                region,
+                previous,
+                cells.iter().map(|(id, cell)| (id.clone(), *cell)),
+                &species,
+                &amount,
+            );
+            series.insert(iteration, flux);
+        }
+        previous = Some(cells);
+    }
+    series
+}
+
+#[cfg(test)]
+mod test_flux {
+    use super::*;
+    use nalgebra::Vector3;
+
+    /// Saved iterations together with the identifiers and positions of all cells
+    type SavePoints<const D: usize> = Vec<(u64, Vec<(usize, [f64; D])>)>;
+
+    #[test]
+    fn shell_crossings_over_time() {
+        // Cells move radially outwards with different speeds through a shell of radius 5
+        let shell = TargetRegion::Sphere {
+            center: Vector3::zeros(),
+            radius: 5.0,
+        };
+        let save_points: SavePoints<3> = (0..4)
+            .map(|t| {
+                let cells = (1..4)
+                    .map(|n| (n, [(n * t) as f64 + 1.0, 0.0, 0.0]))
+                    .collect();
+                (10 * t as u64, cells)
+            })
+            .collect();
+        let series = flux_series(
+            &shell,
+            save_points
+                .iter()
+                .map(|(t, cells)| (*t, cells.iter().map(|(id, pos)| (*id, pos)))),
+            |pos| pos[1] as usize,
+            |_| 2.0,
+        );
+        assert_eq!(series.keys().copied().collect::<Vec<_>>(), vec![10, 20, 30]);
+        // Faster cells leave the sphere earlier while the slowest one stays inside
+        let leaving: Vec<_> = series
+            .values()
+            .map(|flux| flux.leaving.get(&0).copied().unwrap_or(0))
+            .collect();
+        assert_eq!(leaving, vec![0, 1, 1]);
+        assert_eq!(series[&20].net_crossings(&0), -1);
+        assert_eq!(series[&20].net_amount[&0], -2.0);
+        assert!(series.values().all(|flux| flux.entering.is_empty()));
+    }
+
+    #[test]
+    fn new_and_removed_cells_are_ignored() {
+        let plane = TargetRegion::HalfSpace {
+            point: Vector3::zeros(),
+            normal: Vector3::from([1.0, 0.0, 0.0]),
+        };
+        let previous = [(0, [-1.0, 0.0, 0.0]), (1, [-1.0, 0.0, 0.0])];
+        let current = [(0, [1.0, 0.0, 0.0]), (2, [1.0, 0.0, 0.0])];
+        let flux = FluxMeasurement::from_cells(
+            &plane,
+            previous.iter().map(|(id, pos)| (*id, pos)),
+            current.iter().map(|(id, pos)| (*id, pos)),
+            |_| (),
+            |pos| pos[0],
+        );
+        assert_eq!(flux.entering[&()], 1);
+        assert_eq!(flux.net_amount[&()], 1.0);
+        assert!(flux.leaving.is_empty());
+    }
+}
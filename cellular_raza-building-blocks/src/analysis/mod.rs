@@ -0,0 +1,21 @@
+//! Statistics of cell populations which are calculated from stored results.
+//!
+//! In contrast to the analyses of the [scenarios](crate::SpheroidScenario), these statistics
+//! only rely on the concepts which are implemented by the cells and can thus be used for
+//! arbitrary models.
+
+mod age;
+mod clusters;
+mod contact_graph;
+mod flux;
+mod passage_times;
+mod radial_profile;
+mod topology;
+
+pub use age::*;
+pub use clusters::*;
+pub use contact_graph::*;
+pub use flux::*;
+pub use passage_times::*;
+pub use radial_profile::*;
+pub use topology::*;
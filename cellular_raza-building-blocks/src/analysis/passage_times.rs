@@ -0,0 +1,145 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::FirstPassage;
+
+/// Distribution of the first-passage times of a population of cells.
+///
+/// The records of all cells are collected from every save point and merged by the id of the
+/// cell.
+/// Thus cells which were absorbed and removed after a save point are still counted.
+/// Statistics are calculated for every target of the [FirstPassage] building block in the same
+/// order.
+///
+/// ```
+/// # use cellular_raza_building_blocks::*;
+/// # use nalgebra::Vector1;
+/// let target = PassageTarget {
+///     region: TargetRegion::Sphere {
+///         center: Vector1::from([0.0]),
+///         radius: 1.0,
+///     },
+///     absorbing: true,
+/// };
+/// let mut passage = FirstPassage::new(vec![target]);
+/// // Records of two cells with ids 0 and 1 at the first save point
+/// let mut records = vec![(0, passage.clone()), (1, passage.clone())];
+/// // At a later save point, the first cell has reached the target
+/// passage.passage_times[0] = Some(4.0);
+/// records.push((0, passage.clone()));
+/// let times = PassageTimes::from_records(records.iter().map(|(id, p)| (*id, p)));
+/// assert_eq!(times.n_cells, 2);
+/// assert_eq!(times.times[0], vec![4.0]);
+/// assert_eq!(times.fraction_reached(0), 0.5);
+/// assert_eq!(times.mean(0), Some(4.0));
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PassageTimes {
+    /// Total number of recorded cells
+    pub n_cells: usize,
+    /// First-passage times of all cells which reached the target in ascending order
+    pub times: Vec<Vec<f64>>,
+}
+
+impl PassageTimes {
+    /// Merges the records of all cells which are given together with their id.
+    pub fn from_records<'a, Id, const D: usize>(
+        records: impl IntoIterator<Item = (Id, &'a FirstPassage<D>)>,
+    ) -> Self
+    where
+        Id: Ord,
+    {
+        let mut cells = BTreeMap::<Id, Vec<Option<f64>>>::new();
+        for (id, record) in records {
+            let times = cells.entry(id).or_default();
+            if times.len() < record.passage_times.len() {
+                times.resize(record.passage_times.len(), None);
+            }
+            for (time, recorded) in times.iter_mut().zip(record.passage_times.iter()) {
+                // The first passage never changes once it was recorded
+                *time = time.or(*recorded);
+            }
+        }
+        let n_targets = cells.values().map(|times| times.len()).max().unwrap_or(0);
+        let mut times = vec![Vec::new(); n_targets];
+        for cell_times in cells.values() {
+            for (target, time) in cell_times.iter().enumerate() {
+                times[target].extend(*time);
+            }
+        }
+        for target_times in times.iter_mut() {
+            target_times.sort_by(f64::total_cmp);
+        }
+        Self {
+            n_cells: cells.len(),
+            times,
+        }
+    }
+
+    /// Fraction of all cells which reached the given target
+    pub fn fraction_reached(&self, target: usize) -> f64 {
+        match self.n_cells {
+            0 => 0.0,
+            n => self.times.get(target).map_or(0, |t| t.len()) as f64 / n as f64,
+        }
+    }
+
+    /// Mean first-passage time of all cells which reached the given target
+    pub fn mean(&self, target: usize) -> Option<f64> {
+        let times = self.times.get(target).filter(|t| !t.is_empty())?;
+        Some(times.iter().sum::<f64>() / times.len() as f64)
+    }
+
+    /// Fraction of all cells which did not reach the given target until the given time.
+    ///
+    /// This is the empirical survival function of the first-passage time.
+    pub fn survival(&self, target: usize, time: f64) -> f64 {
+        match self.n_cells {
+            0 => 0.0,
+            n => {
+                let reached = self
+                    .times
+                    .get(target)
+                    .map_or(0, |t| t.partition_point(|s| *s <= time));
+                1.0 - reached as f64 / n as f64
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_passage_times {
+    use super::*;
+    use crate::{PassageTarget, TargetRegion};
+    use nalgebra::Vector2;
+
+    #[test]
+    fn survival_of_escaping_cells() {
+        // Most cells reach the unit disk at different times
+        let template = FirstPassage::new(vec![PassageTarget {
+            region: TargetRegion::Sphere {
+                center: Vector2::zeros(),
+                radius: 1.0,
+            },
+            absorbing: false,
+        }]);
+        let records: Vec<_> = (0..10)
+            .map(|n| {
+                let mut record = template.clone();
+                record.passage_times[0] = (n < 8).then_some(n as f64);
+                (n, record)
+            })
+            .collect();
+        let times = PassageTimes::from_records(records.iter().map(|(id, r)| (*id, r)));
+        assert_eq!(times.n_cells, 10);
+        assert_eq!(times.fraction_reached(0), 0.8);
+        assert_eq!(times.mean(0), Some(3.5));
+        assert_eq!(times.survival(0, -1.0), 1.0);
+        assert_eq!(times.survival(0, 2.5), 0.7);
+        assert!((times.survival(0, 100.0) - 0.2).abs() < 1e-12);
+        // Unknown targets
+        assert_eq!(times.fraction_reached(1), 0.0);
+        assert_eq!(times.mean(1), None);
+    }
+}
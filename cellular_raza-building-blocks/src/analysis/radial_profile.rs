@@ -0,0 +1,265 @@
+use std::collections::BTreeMap;
+
+use cellular_raza_concepts::PositionLike;
+use serde::{Deserialize, Serialize};
+
+/// Center of a [RadialProfile]
+#[derive(Clone, Debug, PartialEq)]
+pub enum RadialCenter<Id, const D: usize> {
+    /// Fixed point in space
+    Point([f64; D]),
+    /// Current position of the cell with the given id such as a cargo or target cell
+    Cell(Id),
+}
+
+/// Volume of the unit ball in `D` dimensions
+fn unit_ball_volume(dim: usize) -> f64 {
+    match dim {
+        0 => 1.0,
+        1 => 2.0,
+        d => unit_ball_volume(d - 2) * 2.0 * std::f64::consts::PI / d as f64,
+    }
+}
+
+/// Radial density profiles of every species around a center.
+///
+/// The `i`-th bin of every profile contains the cells whose distance $r$ to the center
+/// satisfies $i w \leq r < (i+1)w$ where $w$ is the bin width.
+/// Densities are obtained by dividing the counts by the volume of the corresponding spherical
+/// shell in `D` dimensions.
+/// All profiles have the same number of bins which is determined by the most distant cell.
+/// Calculating the profile at every save point quantifies how cells accumulate around the
+/// center, for example during the engulfment of a cargo.
+///
+/// ```
+/// # use cellular_raza_building_blocks::*;
+/// # use cellular_raza_concepts::PositionLike;
+/// struct Cell {
+///     pos: [f64; 2],
+///     species: &'static str,
+/// }
+/// # impl PositionLike<f64, 2> for Cell {
+/// #     fn representative_point(&self) -> [f64; 2] {
+/// #         self.pos
+/// #     }
+/// #     fn bounding_box(&self) -> ([f64; 2], [f64; 2]) {
+/// #         (self.pos, self.pos)
+/// #     }
+/// # }
+/// let cells = [
+///     Cell { pos: [0.0, 0.0], species: "cargo" },
+///     Cell { pos: [0.5, 0.0], species: "a" },
+///     Cell { pos: [0.0, 1.5], species: "a" },
+///     Cell { pos: [-1.2, 0.0], species: "b" },
+/// ];
+/// // Profiles around the cargo cell with index 0
+/// let profile = RadialProfile::from_cells(
+///     cells.iter().enumerate(),
+///     &RadialCenter::Cell(0),
+///     1.0,
+///     |cell| cell.species,
+/// )
+/// .unwrap();
+/// // The cargo cell itself is not counted
+/// assert!(!profile.counts.contains_key("cargo"));
+/// assert_eq!(profile.counts["a"], vec![1, 1]);
+/// assert_eq!(profile.counts["b"], vec![0, 1]);
+/// let pi = std::f64::consts::PI;
+/// assert_eq!(profile.densities["a"], vec![1.0 / pi, 1.0 / (3.0 * pi)]);
+/// assert_eq!(profile.mean_distances["a"], 1.0);
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct RadialProfile<S: Ord> {
+    /// Position of the center
+    pub center: Vec<f64>,
+    /// Width $w$ of the radial bins
+    pub bin_width: f64,
+    /// Number of cells of every species within every bin
+    pub counts: BTreeMap<S, Vec<usize>>,
+    /// Number of cells per volume of every species within every bin
+    pub densities: BTreeMap<S, Vec<f64>>,
+    /// Mean distance of the cells of every species to the center
+    pub mean_distances: BTreeMap<S, f64>,
+}
+
+impl<S> RadialProfile<S>
+where
+    S: Clone + Ord,
+{
+    /// Calculates the profiles of the given positions and species around the center.
+    ///
+    /// # Panics
+    /// The bin width needs to be positive.
+    pub fn from_positions<const D: usize>(
+        positions: impl IntoIterator<Item = ([f64; D], S)>,
+        center: [f64; D],
+        bin_width: f64,
+    ) -> Self {
+        assert!(bin_width > 0.0, "bin width needs to be positive");
+        let mut distances = BTreeMap::<S, Vec<f64>>::new();
+        for (position, species) in positions {
+            let distance = (0..D)
+                .map(|i| (position[i] - center[i]).powi(2))
+                .sum::<f64>()
+                .sqrt();
+            distances.entry(species).or_default().push(distance);
+        }
+        let n_bins = distances
+            .values()
+            .flatten()
+            .map(|r| (r / bin_width).floor() as usize + 1)
+            .max()
+            .unwrap_or(0);
+        let shell_volumes: Vec<f64> = (0..n_bins)
+            .map(|i| {
+                let (inner, outer) = (i as f64 * bin_width, (i + 1) as f64 * bin_width);
+                unit_ball_volume(D) * (outer.powi(D as i32) - inner.powi(D as i32))
+            })
+            .collect();
+        let mut counts = BTreeMap::new();
+        let mut densities = BTreeMap::new();
+        let mut mean_distances = BTreeMap::new();
+        for (species, distances) in distances {
+            let mut histogram = vec![0; n_bins];
+            for r in distances.iter() {
+                histogram[(r / bin_width).floor() as usize] += 1;
+            }
+            densities.insert(
+                species.clone(),
+                histogram
+                    .iter()
+                    .zip(shell_volumes.iter())
+                    .map(|(count, volume)| *count as f64 / volume)
+                    .collect(),
+            );
+            counts.insert(species.clone(), histogram);
+            mean_distances.insert(
+                species,
+                distances.iter().sum::<f64>() / distances.len() as f64,
+            );
+        }
+        Self {
+            center: center.to_vec(),
+            bin_width,
+            counts,
+            densities,
+            mean_distances,
+        }
+    }
+
+    /// Calculates the profiles of the given cells around the center.
+    ///
+    /// Cells are given together with their id and their species is determined by the given
+    /// function.
+    /// If the center is a cell, this cell is excluded from the profiles and [None] is returned
+    /// if it is not among the given cells.
+    pub fn from_cells<'a, Id, C, const D: usize>(
+        cells: impl IntoIterator<Item = (Id, &'a C)>,
+        center: &RadialCenter<Id, D>,
+        bin_width: f64,
+        species: impl Fn(&C) -> S,
+    ) -> Option<Self>
+    where
+        Id: PartialEq,
+        C: 'a + PositionLike<f64, D>,
+    {
+        let mut reference = None;
+        let mut positions = Vec::new();
+        for (id, cell) in cells {
+            let position = cell.representative_point();
+            match center {
+                RadialCenter::Cell(center_id) if *center_id == id => reference = Some(position),
+                _ => positions.push((position, species(cell))),
+            }
+        }
+        let center = match center {
+            RadialCenter::Point(point) => *point,
+            RadialCenter::Cell(_) => reference?,
+        };
+        Some(Self::from_positions(positions, center, bin_width))
+    }
+}
+
+/// Time series of the [RadialProfile]s at every save point.
+///
+/// Save points at which the center cell does not exist are omitted.
+pub fn radial_profile_series<'a, Id, C, S, I, const D: usize>(
+    save_points: impl IntoIterator<Item = (u64, I)>,
+    center: &RadialCenter<Id, D>,
+    bin_width: f64,
+    species: impl Fn(&C) -> S,
+) -> BTreeMap<u64, RadialProfile<S>>
+where
+    Id: PartialEq,
+    C: 'a + PositionLike<f64, D>,
+    S: Clone + Ord,
+    I: IntoIterator<Item = (Id, &'a C)>,
+{
+    save_points
+        .into_iter()
+        .filter_map(|(iteration, cells)| {
+            RadialProfile::from_cells(cells, center, bin_width, &species)
+                .map(|profile| (iteration, profile))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test_radial_profile {
+    use super::*;
+
+    /// Saved iterations together with the identifiers and positions of all cells
+    type SavePoints<const D: usize> = Vec<(u64, Vec<(usize, [f64; D])>)>;
+
+    #[test]
+    fn uniform_density_in_3d() {
+        // Points on a cubic lattice have approximately unit density in all outer shells
+        let positions = (-20..=20).flat_map(|i| {
+            (-20..=20).flat_map(move |j| (-20..=20).map(move |k| ([i, j, k].map(f64::from), ())))
+        });
+        let profile = RadialProfile::from_positions(positions, [0.0; 3], 2.0);
+        for density in profile.densities[&()][2..10].iter() {
+            assert!((density - 1.0).abs() < 0.05, "{density}");
+        }
+        assert_eq!(unit_ball_volume(3), 4.0 / 3.0 * std::f64::consts::PI);
+    }
+
+    #[test]
+    fn corona_formation_series() {
+        // Cells of species 1 approach the cargo at the origin over time
+        let cargo = [0.0, 0.0];
+        let save_points: SavePoints<2> = (0..3)
+            .map(|t| {
+                let radius = 4.0 - t as f64;
+                let cells = std::iter::once((0, cargo))
+                    .chain((1..5).map(|n| {
+                        let angle = n as f64 * std::f64::consts::FRAC_PI_2;
+                        (n, [radius * angle.cos(), radius * angle.sin()])
+                    }))
+                    .collect();
+                (t, cells)
+            })
+            .collect();
+        let series = radial_profile_series(
+            save_points
+                .iter()
+                .map(|(t, cells)| (*t, cells.iter().map(|(id, pos)| (*id, pos)))),
+            &RadialCenter::Cell(0),
+            1.0,
+            |_| 1,
+        );
+        let mean_distances: Vec<_> = series.values().map(|p| p.mean_distances[&1]).collect();
+        assert_eq!(mean_distances, vec![4.0, 3.0, 2.0]);
+        assert_eq!(series[&2].counts[&1], vec![0, 0, 4]);
+        // Missing center cells are skipped
+        let missing = radial_profile_series(
+            save_points
+                .iter()
+                .map(|(t, cells)| (*t, cells.iter().map(|(id, pos)| (*id, pos)))),
+            &RadialCenter::Cell(7),
+            1.0,
+            |_| 1,
+        );
+        assert!(missing.is_empty());
+    }
+}
@@ -0,0 +1,291 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use cellular_raza_concepts::PositionLike;
+use nalgebra::{SMatrix, SVector};
+
+/// Simplex of the triangulation together with its circumsphere
+struct Simplex<const D: usize> {
+    vertices: Vec<usize>,
+    center: SVector<f64, D>,
+    radius_squared: f64,
+}
+
+impl<const D: usize> Simplex<D> {
+    /// Calculates the circumsphere of the given vertices.
+    ///
+    /// Returns [None] if the vertices are degenerate, ie. they do not span a simplex.
+    fn new(points: &[SVector<f64, D>], vertices: Vec<usize>) -> Option<Self> {
+        // The center c solves 2 (p_i - p_0) * c = |p_i|^2 - |p_0|^2 for all i
+        let p0 = points[vertices[0]];
+        let mut matrix = SMatrix::<f64, D, D>::zeros();
+        let mut rhs = SVector::<f64, D>::zeros();
+        for i in 0..D {
+            let pi = points[vertices[i + 1]];
+            matrix.set_row(i, &(2.0 * (pi - p0)).transpose());
+            rhs[i] = pi.norm_squared() - p0.norm_squared();
+        }
+        let center = solve_linear(matrix, rhs)?;
+        Some(Self {
+            radius_squared: (center - p0).norm_squared(),
+            center,
+            vertices,
+        })
+    }
+}
+
+/// Gaussian elimination with partial pivoting
+///
+/// Returns [None] if the matrix is singular.
+fn solve_linear<const D: usize>(
+    mut matrix: SMatrix<f64, D, D>,
+    mut rhs: SVector<f64, D>,
+) -> Option<SVector<f64, D>> {
+    let scale = matrix.amax();
+    for col in 0..D {
+        let pivot =
+            (col..D).max_by(|a, b| matrix[(*a, col)].abs().total_cmp(&matrix[(*b, col)].abs()))?;
+        if matrix[(pivot, col)].abs() <= f64::EPSILON * scale {
+            return None;
+        }
+        matrix.swap_rows(col, pivot);
+        rhs.swap_rows(col, pivot);
+        for row in col + 1..D {
+            let factor = matrix[(row, col)] / matrix[(col, col)];
+            for k in col..D {
+                matrix[(row, k)] -= factor * matrix[(col, k)];
+            }
+            rhs[row] -= factor * rhs[col];
+        }
+    }
+    let mut solution = SVector::<f64, D>::zeros();
+    for row in (0..D).rev() {
+        let sum: f64 = (row + 1..D).map(|k| matrix[(row, k)] * solution[k]).sum();
+        solution[row] = (rhs[row] - sum) / matrix[(row, row)];
+    }
+    Some(solution)
+}
+
+/// Neighbors of every point in the Delaunay triangulation of the given points.
+///
+/// Two points are neighbors if they share an edge of the triangulation.
+/// In contrast to neighbors which are determined by a cutoff distance, Delaunay neighbors
+/// describe the topology of the tissue and are independent of the density of cells.
+///
+/// The triangulation is calculated by the Bowyer-Watson algorithm for any dimension `D`,
+/// typically `2` or `3`.
+/// All points are inserted into a large simplex which initially encloses them.
+/// Its cost grows quadratically with the number of points in the worst case.
+/// Degenerate configurations such as many points on a common sphere are triangulated
+/// arbitrarily.
+/// Duplicate points do not obtain any neighbors.
+///
+/// ```
+/// # use cellular_raza_building_blocks::delaunay_neighbors;
+/// // Four points forming a rhombus which is longer along the x-axis
+/// let points = [[-2.0, 0.0], [0.0, -1.0], [2.0, 0.0], [0.0, 1.0]];
+/// let neighbors = delaunay_neighbors(&points);
+/// // The shorter diagonal is part of the triangulation
+/// assert!(neighbors[1].contains(&3));
+/// assert!(!neighbors[0].contains(&2));
+/// assert_eq!(neighbors[0].len(), 2);
+/// assert_eq!(neighbors[1].len(), 3);
+/// ```
+pub fn delaunay_neighbors<const D: usize>(points: &[[f64; D]]) -> Vec<BTreeSet<usize>> {
+    let n_points = points.len();
+    let mut neighbors = vec![BTreeSet::new(); n_points];
+    if n_points < 2 || D == 0 {
+        return neighbors;
+    }
+
+    // Shift and scale points for numerical stability
+    let mut lower = [f64::INFINITY; D];
+    let mut upper = [f64::NEG_INFINITY; D];
+    for point in points.iter() {
+        for i in 0..D {
+            lower[i] = lower[i].min(point[i]);
+            upper[i] = upper[i].max(point[i]);
+        }
+    }
+    let middle = SVector::<f64, D>::from_fn(|i, _| 0.5 * (lower[i] + upper[i]));
+    let extent = (0..D)
+        .map(|i| upper[i] - lower[i])
+        .fold(0.0, f64::max)
+        .max(f64::EPSILON);
+    let mut vertices: Vec<SVector<f64, D>> = points
+        .iter()
+        .map(|p| (SVector::from(*p) - middle) / extent)
+        .collect();
+
+    // The simplex {x_i >= -r, sum_i x_i <= D r} contains the cube [-r, r]^D
+    let r = 1e3;
+    vertices.push(SVector::from_element(-r));
+    for i in 0..D {
+        let mut vertex = SVector::from_element(-r);
+        vertex[i] = (2 * D) as f64 * r - r;
+        vertices.push(vertex);
+    }
+    let mut simplices: Vec<Simplex<D>> =
+        Simplex::new(&vertices, (n_points..=n_points + D).collect())
+            .into_iter()
+            .collect();
+
+    for n in 0..n_points {
+        let point = vertices[n];
+        let (bad, good): (Vec<_>, Vec<_>) = simplices
+            .into_iter()
+            .partition(|simplex| (point - simplex.center).norm_squared() < simplex.radius_squared);
+        simplices = good;
+
+        // Faces which are not shared by two removed simplices form the boundary of the cavity
+        let mut faces = BTreeMap::<Vec<usize>, usize>::new();
+        for simplex in bad.iter() {
+            for k in 0..=D {
+                let mut face: Vec<usize> = simplex
+                    .vertices
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != k)
+                    .map(|(_, v)| *v)
+                    .collect();
+                face.sort();
+                *faces.entry(face).or_default() += 1;
+            }
+        }
+        for (mut face, _) in faces.into_iter().filter(|(_, count)| *count == 1) {
+            face.push(n);
+            simplices.extend(Simplex::new(&vertices, face));
+        }
+    }
+
+    // Edges which connect two of the original points
+    for simplex in simplices.iter() {
+        for &v1 in simplex.vertices.iter().filter(|v| **v < n_points) {
+            for &v2 in simplex.vertices.iter().filter(|v| **v < n_points) {
+                if v1 != v2 {
+                    neighbors[v1].insert(v2);
+                }
+            }
+        }
+    }
+    neighbors
+}
+
+/// Delaunay neighbors of cells which are identified by their keys.
+///
+/// Uses the [PositionLike::representative_point] of every cell.
+/// See [delaunay_neighbors].
+///
+/// ```
+/// # use cellular_raza_building_blocks::delaunay_neighbor_map;
+/// # use std::collections::BTreeMap;
+/// let cells = BTreeMap::from([("a", [0.0, 0.0]), ("b", [1.0, 0.0]), ("c", [0.0, 1.0])]);
+/// let neighbors = delaunay_neighbor_map(&cells);
+/// assert_eq!(neighbors["a"].len(), 2);
+/// assert!(neighbors["b"].contains("c"));
+/// ```
+pub fn delaunay_neighbor_map<Id, C, const D: usize>(
+    cells: &BTreeMap<Id, C>,
+) -> BTreeMap<Id, BTreeSet<Id>>
+where
+    Id: Clone + Ord,
+    C: PositionLike<f64, D>,
+{
+    let ids: Vec<&Id> = cells.keys().collect();
+    let points: Vec<[f64; D]> = cells
+        .values()
+        .map(|cell| cell.representative_point())
+        .collect();
+    delaunay_neighbors(&points)
+        .into_iter()
+        .enumerate()
+        .map(|(n, neighbors)| {
+            (
+                ids[n].clone(),
+                neighbors.into_iter().map(|m| ids[m].clone()).collect(),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test_delaunay {
+    use super::*;
+
+    #[test]
+    fn hexagonal_lattice_2d() {
+        // Interior points of a hexagonal lattice have six neighbors
+        let points: Vec<[f64; 2]> = (0..7)
+            .flat_map(|i| {
+                (0..7).map(move |j| [i as f64 + 0.5 * (j % 2) as f64, j as f64 * 0.75f64.sqrt()])
+            })
+            .collect();
+        let neighbors = delaunay_neighbors(&points);
+        for (n, point) in points.iter().enumerate() {
+            let interior = (1..6).contains(&(n / 7)) && (1..6).contains(&(n % 7));
+            if interior {
+                assert_eq!(neighbors[n].len(), 6, "{:?}", point);
+            }
+            // Neighbors are symmetric
+            for m in neighbors[n].iter() {
+                assert!(neighbors[*m].contains(&n));
+            }
+        }
+    }
+
+    #[test]
+    fn delaunay_property_2d() {
+        use rand::{Rng, SeedableRng};
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(1);
+        let points: Vec<[f64; 2]> = (0..40)
+            .map(|_| [rng.gen_range(0.0..10.0), rng.gen_range(0.0..10.0)])
+            .collect();
+        let neighbors = delaunay_neighbors(&points);
+        // Euler formula for planar triangulations: E = 3N - 3 - H <= 3N - 6
+        let n_edges: usize = neighbors.iter().map(|n| n.len()).sum::<usize>() / 2;
+        assert!(n_edges <= 3 * points.len() - 6);
+        assert!(n_edges >= 2 * points.len() - 3);
+        // The nearest neighbor of every point is always a Delaunay neighbor
+        for (n, p) in points.iter().enumerate() {
+            let nearest = (0..points.len())
+                .filter(|m| *m != n)
+                .min_by(|a, b| {
+                    let da = (points[*a][0] - p[0]).powi(2) + (points[*a][1] - p[1]).powi(2);
+                    let db = (points[*b][0] - p[0]).powi(2) + (points[*b][1] - p[1]).powi(2);
+                    da.total_cmp(&db)
+                })
+                .unwrap();
+            assert!(neighbors[n].contains(&nearest));
+        }
+    }
+
+    #[test]
+    fn tetrahedron_3d() {
+        let points = [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+            [0.25, 0.25, 0.25],
+        ];
+        let neighbors = delaunay_neighbors(&points);
+        // The inner point is connected to all corners
+        assert_eq!(neighbors[4].len(), 4);
+        for (n, corner) in neighbors.iter().enumerate().take(4) {
+            assert_eq!(corner.len(), 4, "corner {n}");
+        }
+    }
+
+    #[test]
+    fn collinear_and_few_points() {
+        let neighbors = delaunay_neighbors(&[[0.0, 0.0], [1.0, 0.0], [2.0, 0.0]]);
+        assert_eq!(
+            neighbors,
+            vec![
+                BTreeSet::from([1]),
+                BTreeSet::from([0, 2]),
+                BTreeSet::from([1])
+            ]
+        );
+        assert!(delaunay_neighbors(&[[1.0, 2.0, 3.0]])[0].is_empty());
+    }
+}
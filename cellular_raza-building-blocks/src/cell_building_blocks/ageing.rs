@@ -0,0 +1,65 @@
+use cellular_raza_concepts::*;
+
+use serde::{Deserialize, Serialize};
+
+/// Age of a cell which becomes senescent irreversibly after reaching the `senescence_age`.
+///
+/// The age is advanced by the backend when the `Age` aspect is included in the simulation.
+/// Other building blocks can query [Age::is_senescent] of the cell to alter its behaviour.
+/// Cells which should never become senescent use an infinite `senescence_age`.
+///
+/// ```
+/// # use cellular_raza_building_blocks::*;
+/// # use cellular_raza_concepts::*;
+/// let mut age = CellAge::new(10.0);
+/// for _ in 0..10 {
+///     // This is done by the backend
+///     age.set_age(age.get_age() + 1.5);
+///     age.update_senescence()?;
+/// }
+/// assert!(age.is_senescent());
+/// // Senescence is irreversible
+/// age.set_age(0.0);
+/// age.update_senescence()?;
+/// assert!(age.is_senescent());
+/// # Ok::<(), CalcError>(())
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CellAge {
+    /// Current age of the cell
+    pub age: f64,
+    /// Age at which the cell becomes senescent
+    pub senescence_age: f64,
+    /// Indicates if the cell has become senescent
+    pub senescent: bool,
+}
+
+impl CellAge {
+    /// Newborn cell which becomes senescent at the given age
+    pub fn new(senescence_age: f64) -> Self {
+        Self {
+            age: 0.0,
+            senescence_age,
+            senescent: false,
+        }
+    }
+}
+
+impl Age<f64> for CellAge {
+    fn get_age(&self) -> f64 {
+        self.age
+    }
+
+    fn set_age(&mut self, age: f64) {
+        self.age = age;
+    }
+
+    fn update_senescence(&mut self) -> Result<(), CalcError> {
+        self.senescent |= self.age >= self.senescence_age;
+        Ok(())
+    }
+
+    fn is_senescent(&self) -> bool {
+        self.senescent
+    }
+}
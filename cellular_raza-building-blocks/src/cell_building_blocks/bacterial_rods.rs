@@ -205,6 +205,19 @@ impl<F: Clone, const D: usize> Position<Matrix<F, Dyn, Const<D>, VecStorage<F, D
     }
 }
 
+impl<F, const D: usize> PositionLike<F, D> for RodMechanics<F, D>
+where
+    F: num::Float + nalgebra::Scalar,
+{
+    fn representative_point(&self) -> [F; D] {
+        super::mechanics::vertices_center_and_bounding_box(&self.pos).0
+    }
+
+    fn bounding_box(&self) -> ([F; D], [F; D]) {
+        super::mechanics::vertices_center_and_bounding_box(&self.pos).1
+    }
+}
+
 impl<F: Clone, const D: usize> Velocity<Matrix<F, Dyn, Const<D>, VecStorage<F, Dyn, Const<D>>>>
     for RodMechanics<F, D>
 {
@@ -0,0 +1,154 @@
+use nalgebra::{SMatrix, SVector};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Axis along which a cell divides.
+///
+/// The daughter cells are placed along this axis such that the division plane is perpendicular
+/// to it.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum DivisionAxis<const D: usize> {
+    /// Uniformly distributed random direction
+    Random,
+    /// Along the given polarity of the cell.
+    /// A vanishing polarity results in a random direction.
+    Polarity(SVector<f64, D>),
+    /// Along the axis of the largest principal stress of the given symmetric stress tensor.
+    /// Cells thus divide along the direction in which they are stretched the most
+    /// (Hertwig's rule).
+    /// Isotropic stresses result in a random direction.
+    Stress(SMatrix<f64, D, D>),
+}
+
+impl<const D: usize> DivisionAxis<D> {
+    /// Normalized direction of the axis
+    ///
+    /// Since the division axis has no orientation, the sign of the direction is arbitrary.
+    pub fn direction(&self, rng: &mut impl Rng) -> SVector<f64, D> {
+        let direction = match self {
+            DivisionAxis::Random => None,
+            DivisionAxis::Polarity(polarity) => polarity.try_normalize(0.0),
+            DivisionAxis::Stress(stress) => principal_stress_axis(stress),
+        };
+        direction.unwrap_or_else(|| random_direction(rng))
+    }
+}
+
+/// Draws a direction which is uniformly distributed on the unit sphere.
+pub fn random_direction<const D: usize>(rng: &mut impl Rng) -> SVector<f64, D> {
+    loop {
+        let direction =
+            SVector::<f64, D>::from_fn(|_, _| rng.sample::<f64, _>(rand_distr::StandardNormal));
+        if let Some(direction) = direction.try_normalize(f64::EPSILON) {
+            return direction;
+        }
+    }
+}
+
+/// Eigenvector of the largest eigenvalue of a symmetric matrix obtained by power iteration.
+///
+/// The deviatoric part is normalized by its Frobenius norm and shifted by the identity such
+/// that all eigenvalues are non-negative.
+/// Returns [None] if the deviatoric part of the matrix vanishes.
+fn principal_stress_axis<const D: usize>(stress: &SMatrix<f64, D, D>) -> Option<SVector<f64, D>> {
+    let identity = SMatrix::<f64, D, D>::identity();
+    let deviatoric = stress - identity * stress.trace() / D as f64;
+    let norm = deviatoric.norm();
+    if norm <= f64::EPSILON * stress.norm() {
+        return None;
+    }
+    // Shifting the deviatoric part does not change the eigenvectors
+    let shifted = deviatoric / norm + identity;
+    let column = (0..D).max_by(|i, j| shifted[(*i, *i)].total_cmp(&shifted[(*j, *j)]))?;
+    let mut direction: SVector<f64, D> = shifted.column(column).clone_owned();
+    for _ in 0..256 {
+        direction = shifted * direction.try_normalize(0.0)?;
+    }
+    direction.try_normalize(0.0)
+}
+
+/// Places the two daughter cells of a division symmetrically around the center of the mother.
+///
+/// Daughters are displaced by the `offset` along the [DivisionAxis] in opposite directions.
+/// Usually, the offset equals the radius of the daughter cells such that they touch but do
+/// not overlap.
+/// Whether the daughters end up inside the simulation domain is checked by the backend after
+/// the division.
+///
+/// ```
+/// # use cellular_raza_building_blocks::*;
+/// # use nalgebra::{Matrix2, Vector2};
+/// # use rand::SeedableRng;
+/// let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(0);
+/// // Cells stretched along the y-axis divide along this axis
+/// let placement = DaughterPlacement {
+///     axis: DivisionAxis::Stress(Matrix2::new(0.5, 0.0, 0.0, 2.0)),
+///     offset: 1.5,
+/// };
+/// let center = Vector2::from([10.0, 10.0]);
+/// let [pos1, pos2] = placement.place(&mut rng, &center);
+/// assert!((pos1 - pos2).x.abs() < 1e-12);
+/// assert!(((pos1 - pos2).y.abs() - 3.0).abs() < 1e-12);
+/// assert!(((pos1 + pos2) / 2.0 - center).norm() < 1e-12);
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DaughterPlacement<const D: usize> {
+    /// Axis along which the daughters are placed
+    pub axis: DivisionAxis<D>,
+    /// Distance of each daughter to the center of the mother cell
+    pub offset: f64,
+}
+
+impl<const D: usize> DaughterPlacement<D> {
+    /// Positions of both daughter cells
+    pub fn place(&self, rng: &mut impl Rng, center: &SVector<f64, D>) -> [SVector<f64, D>; 2] {
+        let displacement = self.axis.direction(rng) * self.offset;
+        [center + displacement, center - displacement]
+    }
+}
+
+#[cfg(test)]
+mod test_division {
+    use super::*;
+    use nalgebra::{Matrix3, Vector2, Vector3};
+    use rand::SeedableRng;
+
+    #[test]
+    fn random_directions_are_isotropic() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(3);
+        let n_samples = 20_000;
+        let mean: Vector3<f64> = (0..n_samples)
+            .map(|_| DivisionAxis::Random.direction(&mut rng))
+            .sum::<Vector3<f64>>()
+            / n_samples as f64;
+        assert!(mean.norm() < 0.03);
+        let direction: Vector3<f64> = random_direction(&mut rng);
+        assert!((direction.norm() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn division_along_polarity() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(0);
+        let axis = DivisionAxis::Polarity(Vector2::from([3.0, 4.0]));
+        assert_eq!(axis.direction(&mut rng), Vector2::from([0.6, 0.8]));
+        // Cells without polarity divide in a random direction
+        let axis = DivisionAxis::Polarity(Vector2::zeros());
+        assert!((axis.direction(&mut rng).norm() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn division_along_largest_principal_stress() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(0);
+        // Rotate a diagonal stress tensor with compression along one axis
+        let rotation = nalgebra::Rotation3::from_euler_angles(0.3, -0.7, 1.1);
+        let diagonal = Matrix3::from_diagonal(&Vector3::from([-3.0, 1.0, 0.5]));
+        let stress = rotation.matrix() * diagonal * rotation.matrix().transpose();
+        let direction = DivisionAxis::Stress(stress).direction(&mut rng);
+        let expected = rotation.matrix().column(1).clone_owned();
+        assert!((direction.dot(&expected).abs() - 1.0).abs() < 1e-9);
+        // Isotropic stress does not determine a direction
+        assert_eq!(principal_stress_axis(&(2.0 * Matrix3::identity())), None);
+        let direction = DivisionAxis::Stress(Matrix3::identity()).direction(&mut rng);
+        assert!((direction.norm() - 1.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,210 @@
+use cellular_raza_concepts::*;
+use nalgebra::SVector;
+
+use serde::{Deserialize, Serialize};
+
+/// Spatial region which cells can enter.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum TargetRegion<const D: usize> {
+    /// Ball around the `center` with the given `radius`
+    Sphere {
+        /// Center of the ball
+        center: SVector<f64, D>,
+        /// Radius of the ball
+        radius: f64,
+    },
+    /// Axis-aligned cuboid between the lower corner `min` and the upper corner `max`
+    Cuboid {
+        /// Lower corner
+        min: SVector<f64, D>,
+        /// Upper corner
+        max: SVector<f64, D>,
+    },
+    /// All points on the side of the plane through `point` to which the `normal` points
+    HalfSpace {
+        /// Any point of the bounding plane
+        point: SVector<f64, D>,
+        /// Normal vector of the bounding plane
+        normal: SVector<f64, D>,
+    },
+}
+
+impl<const D: usize> TargetRegion<D> {
+    /// Checks if the given position lies within the region including its boundary.
+    pub fn contains(&self, pos: &SVector<f64, D>) -> bool {
+        match self {
+            Self::Sphere { center, radius } => (pos - center).norm() <= *radius,
+            Self::Cuboid { min, max } => (0..D).all(|i| min[i] <= pos[i] && pos[i] <= max[i]),
+            Self::HalfSpace { point, normal } => (pos - point).dot(normal) >= 0.0,
+        }
+    }
+}
+
+/// Region whose first entry is recorded by [FirstPassage]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PassageTarget<const D: usize> {
+    /// Spatial extent of the target
+    pub region: TargetRegion<D>,
+    /// Cells are absorbed and subsequently removed when entering the target
+    pub absorbing: bool,
+}
+
+/// Records the first time at which a cell enters every target region.
+///
+/// First-passage times are the basis of escape-time and homing-time analyses.
+/// The [FirstPassage::update] method needs to be called with the current position of the cell
+/// from its [Cycle::update_cycle] implementation.
+/// It returns a [CycleEvent] if the cell enters an absorbing target.
+/// Absorbed cells are removed after the `removal_delay` via the [CycleEvent::PhasedDeath]
+/// event whose condition is given by [FirstPassage::update_removal].
+/// A delay of at least one save interval ensures that every absorbed cell is stored together
+/// with its passage times before it is removed.
+/// Without delay, the cell is removed immediately.
+/// The recorded times are summarized by the [PassageTimes] statistics.
+///
+/// ```
+/// # use cellular_raza_building_blocks::*;
+/// # use cellular_raza_concepts::*;
+/// # use nalgebra::Vector2;
+/// let mut passage = FirstPassage::new(vec![
+///     PassageTarget {
+///         region: TargetRegion::HalfSpace {
+///             point: Vector2::from([1.0, 0.0]),
+///             normal: Vector2::from([1.0, 0.0]),
+///         },
+///         absorbing: false,
+///     },
+///     PassageTarget {
+///         region: TargetRegion::Sphere {
+///             center: Vector2::from([3.0, 0.0]),
+///             radius: 0.5,
+///         },
+///         absorbing: true,
+///     },
+/// ]);
+/// // The cell moves along the x-axis with unit speed
+/// let mut event = None;
+/// let mut n = 0;
+/// while event.is_none() {
+///     n += 1;
+///     event = passage.update(&Vector2::from([0.1 * n as f64, 0.0]), 0.1);
+/// }
+/// assert_eq!(event, Some(CycleEvent::Remove));
+/// assert!((passage.passage_times[0].unwrap() - 1.0).abs() < 1e-9);
+/// assert!((passage.passage_times[1].unwrap() - 2.5).abs() < 1e-9);
+/// assert!(passage.is_absorbed());
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct FirstPassage<const D: usize> {
+    /// All target regions
+    pub targets: Vec<PassageTarget<D>>,
+    /// Time at which the cell first entered every target
+    pub passage_times: Vec<Option<f64>>,
+    /// Time since the recording started
+    pub time: f64,
+    /// Time after absorption at which the cell is removed
+    pub removal_delay: f64,
+    /// Time at which the cell was absorbed
+    pub absorption_time: Option<f64>,
+}
+
+impl<const D: usize> FirstPassage<D> {
+    /// Starts recording the given targets without removal delay
+    pub fn new(targets: Vec<PassageTarget<D>>) -> Self {
+        Self {
+            passage_times: vec![None; targets.len()],
+            targets,
+            time: 0.0,
+            removal_delay: 0.0,
+            absorption_time: None,
+        }
+    }
+
+    /// Checks if the cell has been absorbed by any target
+    pub fn is_absorbed(&self) -> bool {
+        self.absorption_time.is_some()
+    }
+
+    /// Advances the time and records all targets which contain the current position.
+    ///
+    /// Returns [CycleEvent::Remove] or [CycleEvent::PhasedDeath] if the cell was absorbed
+    /// depending on the `removal_delay`.
+    pub fn update(&mut self, pos: &SVector<f64, D>, dt: f64) -> Option<CycleEvent> {
+        self.time += dt;
+        if self.is_absorbed() {
+            return None;
+        }
+        for (target, passage_time) in self.targets.iter().zip(self.passage_times.iter_mut()) {
+            if passage_time.is_none() && target.region.contains(pos) {
+                *passage_time = Some(self.time);
+                if target.absorbing {
+                    self.absorption_time = Some(self.time);
+                }
+            }
+        }
+        match self.absorption_time {
+            Some(_) if self.removal_delay > 0.0 => Some(CycleEvent::PhasedDeath),
+            Some(_) => Some(CycleEvent::Remove),
+            None => None,
+        }
+    }
+
+    /// Advances the time of an absorbed cell and checks if it should be removed.
+    ///
+    /// Should be called from the [Cycle::update_conditional_phased_death] method.
+    pub fn update_removal(&mut self, dt: f64) -> bool {
+        self.time += dt;
+        self.absorption_time
+            .is_some_and(|absorption_time| self.time - absorption_time >= self.removal_delay)
+    }
+}
+
+#[cfg(test)]
+mod test_first_passage {
+    use super::*;
+    use nalgebra::Vector3;
+
+    #[test]
+    fn regions_contain_boundary() {
+        let sphere = TargetRegion::Sphere {
+            center: Vector3::zeros(),
+            radius: 1.0,
+        };
+        assert!(sphere.contains(&Vector3::from([0.0, 1.0, 0.0])));
+        assert!(!sphere.contains(&Vector3::from([0.0, 1.0, 0.1])));
+        let cuboid = TargetRegion::Cuboid {
+            min: Vector3::zeros(),
+            max: Vector3::from([1.0, 2.0, 3.0]),
+        };
+        assert!(cuboid.contains(&Vector3::from([1.0, 0.0, 3.0])));
+        assert!(!cuboid.contains(&Vector3::from([1.0, -0.1, 3.0])));
+        let half_space = TargetRegion::HalfSpace {
+            point: Vector3::from([0.0, 0.0, 2.0]),
+            normal: Vector3::from([0.0, 0.0, -1.0]),
+        };
+        assert!(half_space.contains(&Vector3::from([5.0, 5.0, 2.0])));
+        assert!(!half_space.contains(&Vector3::from([5.0, 5.0, 2.1])));
+    }
+
+    #[test]
+    fn delayed_removal() {
+        let mut passage = FirstPassage::new(vec![PassageTarget {
+            region: TargetRegion::Sphere {
+                center: Vector3::zeros(),
+                radius: 1.0,
+            },
+            absorbing: true,
+        }]);
+        passage.removal_delay = 0.35;
+        assert_eq!(passage.update(&Vector3::from([2.0, 0.0, 0.0]), 0.1), None);
+        assert_eq!(
+            passage.update(&Vector3::zeros(), 0.1),
+            Some(CycleEvent::PhasedDeath)
+        );
+        assert_eq!(passage.absorption_time, Some(0.2));
+        // Absorbed cells do not record any further events
+        assert_eq!(passage.update(&Vector3::zeros(), 0.1), None);
+        let removed: Vec<_> = (0..4).map(|_| passage.update_removal(0.1)).collect();
+        assert_eq!(removed, vec![false, false, true, true]);
+    }
+}
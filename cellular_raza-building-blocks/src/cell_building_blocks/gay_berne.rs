@@ -0,0 +1,462 @@
+use cellular_raza_concepts::*;
+
+use nalgebra::SVector;
+use serde::{Deserialize, Serialize};
+
+/// Anisotropic interaction between ellipsoidal cells introduced by Gay and Berne.
+///
+/// # Parameters & Variables
+/// | Symbol | Struct Field | Description |
+/// |:---:| --- | --- |
+/// | $\hat{u}$ | `orientation` | Unit vector along the long axis of the cell |
+/// | $\sigma_0$ | `sigma` | Width of the cell perpendicular to its long axis |
+/// | $\kappa$ | `aspect_ratio` | Ratio $\sigma_e/\sigma_0$ of length and width of the cell |
+/// | $\epsilon_0$ | `epsilon` | Interaction strength |
+/// | $\kappa'$ | `well_depth_ratio` | Ratio $\epsilon_s/\epsilon_e$ of the side-by-side and end-to-end well depths |
+/// | $\mu,\nu$ | `mu`, `nu` | Exponents of the anisotropic well depth. Commonly $\mu=2,\nu=1$. |
+/// | $\beta$ | `bound` | Upper bound on the magnitude of forces and torques |
+/// | $\zeta$ | `cutoff` | Cutoff after which the interaction strength is identically 0 |
+/// | | | |
+/// | $\bm{r}$ | | Distance vector between interacting cells |
+///
+/// # Equations
+/// With $\hat{r}=\bm{r}/r$, $\chi=(\kappa^2-1)/(\kappa^2+1)$ and
+/// $\chi'=(\kappa'^{1/\mu}-1)/(\kappa'^{1/\mu}+1)$ we define
+/// \\begin{align}
+///     H_\chi &= \frac{(\hat{r}\cdot\hat{u}_1 + \hat{r}\cdot\hat{u}_2)^2}{1+\chi\hat{u}_1\cdot\hat{u}_2}
+///         + \frac{(\hat{r}\cdot\hat{u}_1 - \hat{r}\cdot\hat{u}_2)^2}{1-\chi\hat{u}_1\cdot\hat{u}_2}\\\\
+///     \sigma &= \sigma_0\left(1 - \frac{\chi}{2}H_\chi\right)^{-1/2}\\\\
+///     \epsilon &= \epsilon_0\left(1-\chi^2(\hat{u}_1\cdot\hat{u}_2)^2\right)^{-\nu/2}
+///         \left(1 - \frac{\chi'}{2}H_{\chi'}\right)^\mu\\\\
+///     R &= \frac{r - \sigma + \sigma_0}{\sigma_0}\\\\
+///     U &= 4\epsilon\left(R^{-12} - R^{-6}\right)\theta(\zeta-r)
+/// \\end{align}
+/// For $\kappa=\kappa'=1$, this reduces to the [BoundLennardJones](crate::BoundLennardJones)
+/// potential.
+///
+/// Forces are the negative gradient of $U$ with respect to the position.
+/// Since the orientation is a unit vector, the [InteractionTorque] is given by the negative
+/// gradient of $U$ with respect to $\hat{u}$ projected onto the plane perpendicular to
+/// $\hat{u}$.
+/// In 3D, the torque in the usual sense can be obtained by $\hat{u}\times\bm{g}$.
+/// When the cores of two cells overlap ($R\leq0$), they are pushed apart with the maximal
+/// force $\beta$.
+///
+/// The [Interaction] trait requires the orientation of the cell which is stored in this struct.
+/// It needs to be kept identical to the orientation used by the mechanics of the cell.
+/// The orientation is shared with other cells via
+/// [get_interaction_information](Interaction::get_interaction_information).
+/// ```
+/// # use cellular_raza_building_blocks::GayBerne;
+/// # use cellular_raza_concepts::Interaction;
+/// # use nalgebra::Vector2;
+/// let rod = GayBerne {
+///     orientation: Vector2::from([1.0, 0.0]),
+///     sigma: 1.0,
+///     aspect_ratio: 3.0,
+///     epsilon: 1.0,
+///     well_depth_ratio: 5.0,
+///     mu: 2.0,
+///     nu: 1.0,
+///     bound: 10.0,
+///     cutoff: 5.0,
+/// };
+/// // Side-by-side cells which are slightly too close repel each other
+/// let (force, _) = rod.calculate_force_between(
+///     &Vector2::from([0.0, 1.0]),
+///     &Vector2::zeros(),
+///     &Vector2::zeros(),
+///     &Vector2::zeros(),
+///     &rod.get_interaction_information(),
+/// )?;
+/// assert!(force[1] > 0.0);
+/// # Ok::<(), cellular_raza_concepts::CalcError>(())
+/// ```
+///
+/// # References
+/// [1]
+/// J. G. Gay and B. J. Berne,
+/// “Modification of the overlap potential to mimic a linear site–site potential,”
+/// The Journal of Chemical Physics, vol. 74, no. 6. AIP Publishing, pp. 3316–3319, Mar. 1981.
+/// doi: [10.1063/1.441483](https://doi.org/10.1063/1.441483).
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(bound(
+    serialize = "F: nalgebra::Scalar + Serialize",
+    deserialize = "F: nalgebra::Scalar + Deserialize<'de>"
+))]
+pub struct GayBerne<F, const D: usize> {
+    /// Unit vector $\hat{u}$ along the long axis of the cell
+    pub orientation: SVector<F, D>,
+    /// Width $\sigma_0$ of the cell
+    pub sigma: F,
+    /// Ratio $\kappa$ of length and width of the cell
+    pub aspect_ratio: F,
+    /// Interaction strength $\epsilon_0$
+    pub epsilon: F,
+    /// Ratio $\kappa'$ of the side-by-side and end-to-end well depths
+    pub well_depth_ratio: F,
+    /// Exponent $\mu$ of the orientation-dependent well depth
+    pub mu: F,
+    /// Exponent $\nu$ of the well depth depending on the relative orientation
+    pub nu: F,
+    /// Numerical bound $\beta$ of forces and torques
+    pub bound: F,
+    /// Defines a cutoff $\zeta$ after which the potential will be fixed to exactly zero.
+    pub cutoff: F,
+}
+
+/// Energy, force and torques between two cells obtained from the [GayBerne] potential.
+struct GayBerneTerms<F, const D: usize> {
+    /// The cores of both cells overlap. The energy is not defined in this case.
+    overlap: bool,
+    energy: F,
+    force: SVector<F, D>,
+    torque_own: SVector<F, D>,
+    torque_ext: SVector<F, D>,
+}
+
+impl<F, const D: usize> GayBerne<F, D>
+where
+    F: nalgebra::RealField + Copy,
+{
+    /// Shape anisotropy $\chi$ and energy anisotropy $\chi'$
+    fn anisotropies(&self) -> (F, F) {
+        let one = F::one();
+        let k2 = self.aspect_ratio * self.aspect_ratio;
+        let kp = self.well_depth_ratio.powf(one / self.mu);
+        ((k2 - one) / (k2 + one), (kp - one) / (kp + one))
+    }
+
+    /// Returns [None] if the cells are further apart than the cutoff.
+    fn terms(
+        &self,
+        z: &SVector<F, D>,
+        u1: &SVector<F, D>,
+        u2: &SVector<F, D>,
+    ) -> Result<Option<GayBerneTerms<F, D>>, CalcError> {
+        let zero = SVector::<F, D>::zeros();
+        let d = z.norm();
+        if d == F::zero() {
+            return Err(CalcError(
+                "identical position for two objects. Cannot calculate Gay-Berne interaction"
+                    .to_owned(),
+            ));
+        }
+        if d > self.cutoff {
+            return Ok(None);
+        }
+        let (u1, u2) = match (u1.try_normalize(F::zero()), u2.try_normalize(F::zero())) {
+            (Some(u1), Some(u2)) => (u1, u2),
+            _ => {
+                return Err(CalcError(
+                    "orientation of Gay-Berne interaction is not allowed to be zero".to_owned(),
+                ))
+            }
+        };
+        let one = F::one();
+        let two = one + one;
+        let half = one / two;
+        let s0 = self.sigma;
+        let (chi, chip) = self.anisotropies();
+
+        let rhat = z / d;
+        let a = rhat.dot(&u1);
+        let b = rhat.dot(&u2);
+        let c = u1.dot(&u2);
+
+        // H_x together with its derivatives with respect to a, b and c
+        let h = |x: F| {
+            let p = one + x * c;
+            let m = one - x * c;
+            let (s, t) = (a + b, a - b);
+            (
+                s * s / p + t * t / m,
+                two * (s / p + t / m),
+                two * (s / p - t / m),
+                x * (t * t / (m * m) - s * s / (p * p)),
+            )
+        };
+        let (hs, hs_a, hs_b, hs_c) = h(chi);
+        let (he, he_a, he_b, he_c) = h(chip);
+
+        let sigma = s0 * (one - half * chi * hs).powf(-half);
+        let dsigma_dh = chi / (two + two) * sigma.powi(3) / (s0 * s0);
+        let eps1 = (one - chi * chi * c * c).powf(-half);
+        let deps1_dc = chi * chi * c * eps1.powi(3);
+        let eps2 = one - half * chip * he;
+        let deps2_dh = -half * chip;
+        let eps = self.epsilon * eps1.powf(self.nu) * eps2.powf(self.mu);
+
+        let rr = (d - sigma + s0) / s0;
+        if rr <= F::zero() {
+            return Ok(Some(GayBerneTerms {
+                overlap: true,
+                energy: F::zero(),
+                force: rhat * self.bound,
+                torque_own: zero,
+                torque_ext: zero,
+            }));
+        }
+        let four: F = nalgebra::convert(4.0);
+        let six: F = nalgebra::convert(6.0);
+        let twelve: F = nalgebra::convert(12.0);
+        let l = four * (rr.powi(-12) - rr.powi(-6));
+        let dl = four * (-twelve * rr.powi(-13) + six * rr.powi(-7));
+
+        // Partial derivatives of the energy
+        let u_d = eps * dl / s0;
+        let u_sigma = -u_d;
+        let u_a = u_sigma * dsigma_dh * hs_a + l * eps * self.mu / eps2 * deps2_dh * he_a;
+        let u_b = u_sigma * dsigma_dh * hs_b + l * eps * self.mu / eps2 * deps2_dh * he_b;
+        let u_c = u_sigma * dsigma_dh * hs_c
+            + l * eps * (self.nu / eps1 * deps1_dc + self.mu / eps2 * deps2_dh * he_c);
+
+        let gradient = rhat * u_d + (u1 - rhat * a) * (u_a / d) + (u2 - rhat * b) * (u_b / d);
+        let g1 = -(rhat * u_a + u2 * u_c);
+        let g2 = -(rhat * u_b + u1 * u_c);
+        let bounded = |v: SVector<F, D>| {
+            let norm = v.norm();
+            if norm > self.bound {
+                v * (self.bound / norm)
+            } else {
+                v
+            }
+        };
+        Ok(Some(GayBerneTerms {
+            overlap: false,
+            energy: eps * l,
+            force: bounded(-gradient),
+            torque_own: bounded(g1 - u1 * g1.dot(&u1)),
+            torque_ext: bounded(g2 - u2 * g2.dot(&u2)),
+        }))
+    }
+
+    /// Potential energy with the cell at the external position and orientation.
+    ///
+    /// The energy is not shifted and thus discontinuous at the cutoff.
+    /// Returns an error if the cores of both cells overlap.
+    fn energy(
+        &self,
+        own_pos: &SVector<F, D>,
+        ext_pos: &SVector<F, D>,
+        ext_orientation: &SVector<F, D>,
+    ) -> Result<F, CalcError> {
+        match self.terms(&(own_pos - ext_pos), &self.orientation, ext_orientation)? {
+            Some(terms) if terms.overlap => Err(CalcError(
+                "cores of two objects overlap. Cannot calculate energy in Gay-Berne potential"
+                    .to_owned(),
+            )),
+            Some(terms) => Ok(terms.energy),
+            None => Ok(F::zero()),
+        }
+    }
+}
+
+impl<F, const D: usize> Orientation<SVector<F, D>> for GayBerne<F, D>
+where
+    F: Clone,
+{
+    fn orientation(&self) -> SVector<F, D> {
+        self.orientation.clone()
+    }
+
+    fn set_orientation(&mut self, orientation: &SVector<F, D>) {
+        self.orientation = orientation.clone();
+    }
+}
+
+impl<F, const D: usize> Interaction<SVector<F, D>, SVector<F, D>, SVector<F, D>, SVector<F, D>>
+    for GayBerne<F, D>
+where
+    F: nalgebra::RealField + Copy,
+{
+    fn get_interaction_information(&self) -> SVector<F, D> {
+        self.orientation
+    }
+
+    fn calculate_force_between(
+        &self,
+        own_pos: &SVector<F, D>,
+        _own_vel: &SVector<F, D>,
+        ext_pos: &SVector<F, D>,
+        _ext_vel: &SVector<F, D>,
+        ext_orientation: &SVector<F, D>,
+    ) -> Result<(SVector<F, D>, SVector<F, D>), CalcError> {
+        match self.terms(&(own_pos - ext_pos), &self.orientation, ext_orientation)? {
+            Some(terms) => Ok((terms.force, -terms.force)),
+            None => Ok((SVector::zeros(), SVector::zeros())),
+        }
+    }
+
+    fn potential_energy_between(
+        &self,
+        own_pos: &SVector<F, D>,
+        ext_pos: &SVector<F, D>,
+        ext_orientation: &SVector<F, D>,
+    ) -> Result<Option<f64>, CalcError> {
+        Ok(self.energy(own_pos, ext_pos, ext_orientation)?.to_subset())
+    }
+}
+
+impl<F, const D: usize>
+    InteractionTorque<SVector<F, D>, SVector<F, D>, SVector<F, D>, SVector<F, D>> for GayBerne<F, D>
+where
+    F: nalgebra::RealField + Copy,
+{
+    fn calculate_torque_between(
+        &self,
+        own_pos: &SVector<F, D>,
+        own_ang: &SVector<F, D>,
+        ext_pos: &SVector<F, D>,
+        ext_ang: &SVector<F, D>,
+        _ext_info: &SVector<F, D>,
+    ) -> Result<(SVector<F, D>, SVector<F, D>), CalcError> {
+        match self.terms(&(own_pos - ext_pos), own_ang, ext_ang)? {
+            Some(terms) => Ok((terms.torque_own, terms.torque_ext)),
+            None => Ok((SVector::zeros(), SVector::zeros())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_gay_berne {
+    use super::*;
+    use nalgebra::{Vector2, Vector3};
+
+    fn ellipsoid(aspect_ratio: f64, well_depth_ratio: f64) -> GayBerne<f64, 3> {
+        GayBerne {
+            orientation: Vector3::x(),
+            sigma: 1.0,
+            aspect_ratio,
+            epsilon: 1.0,
+            well_depth_ratio,
+            mu: 2.0,
+            nu: 1.0,
+            bound: 1e6,
+            cutoff: 10.0,
+        }
+    }
+
+    #[test]
+    fn spherical_limit() {
+        let sphere = ellipsoid(1.0, 1.0);
+        // Analytic force of the Lennard-Jones potential with sigma=epsilon=1
+        let lennard_jones = |r: f64| 4.0 * (12.0 * r.powi(-13) - 6.0 * r.powi(-7));
+        let orientations = [
+            Vector3::x(),
+            Vector3::y(),
+            Vector3::new(1.0, 2.0, -0.5).normalize(),
+        ];
+        for (n, ext_ang) in orientations.iter().enumerate() {
+            for d in [0.9, 1.0, 1.12, 1.5, 3.0] {
+                let own_pos = Vector3::new(0.3, -0.2, 0.1);
+                let dir = -Vector3::new(1.0, n as f64, 1.0).normalize();
+                let ext_pos = own_pos - d * dir;
+                let v = Vector3::zeros();
+                let (f1, f2) = sphere
+                    .calculate_force_between(&own_pos, &v, &ext_pos, &v, ext_ang)
+                    .unwrap();
+                let force = dir * lennard_jones(d);
+                assert!((f1 - force).norm() < 1e-10 * force.norm().max(1.0));
+                assert_eq!(f2, -f1);
+                let (t1, t2) = sphere
+                    .calculate_torque_between(
+                        &own_pos,
+                        &sphere.orientation,
+                        &ext_pos,
+                        ext_ang,
+                        ext_ang,
+                    )
+                    .unwrap();
+                assert!(t1.norm() < 1e-12);
+                assert!(t2.norm() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn well_depth_ratio() {
+        // Minimum of the Lennard-Jones potential
+        let r_min = 2f64.powf(1.0 / 6.0) - 1.0;
+        for (aspect_ratio, well_depth_ratio) in [(3.0, 5.0), (2.0, 0.5), (4.0, 1.0)] {
+            let gb = ellipsoid(aspect_ratio, well_depth_ratio);
+            let u = Vector3::x();
+            let side_by_side = gb
+                .energy(&Vector3::zeros(), &Vector3::new(0.0, 1.0 + r_min, 0.0), &u)
+                .unwrap();
+            let end_to_end = gb
+                .energy(
+                    &Vector3::zeros(),
+                    &Vector3::new(aspect_ratio + r_min, 0.0, 0.0),
+                    &u,
+                )
+                .unwrap();
+            // Parallel cells are additionally bound by a factor (1-chi^2)^(-nu/2)
+            let chi = (aspect_ratio.powi(2) - 1.0) / (aspect_ratio.powi(2) + 1.0);
+            assert!((side_by_side + (1.0 - chi * chi).powf(-0.5)).abs() < 1e-10);
+            assert!((side_by_side / end_to_end - well_depth_ratio).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn overlapping_cores() {
+        let gb = ellipsoid(3.0, 5.0);
+        let own_pos = Vector3::zeros();
+        let ext_pos = Vector3::new(2.0, 0.0, 0.0);
+        let v = Vector3::zeros();
+        let (f1, f2) = gb
+            .calculate_force_between(&own_pos, &v, &ext_pos, &v, &gb.orientation)
+            .unwrap();
+        assert_eq!(f1, -Vector3::x() * gb.bound);
+        assert_eq!(f2, -f1);
+        assert!(gb.energy(&own_pos, &ext_pos, &gb.orientation).is_err());
+    }
+
+    #[test]
+    fn forces_and_torques_are_gradients() {
+        let gb = GayBerne {
+            orientation: Vector2::new(0.8, 0.6),
+            sigma: 1.0,
+            aspect_ratio: 2.5,
+            epsilon: 1.0,
+            well_depth_ratio: 3.0,
+            mu: 2.0,
+            nu: 1.0,
+            bound: 1e6,
+            cutoff: 10.0,
+        };
+        let energy = |z: Vector2<f64>, u1: Vector2<f64>, u2: Vector2<f64>| {
+            let mut gb = gb.clone();
+            gb.orientation = u1;
+            gb.energy(&z, &Vector2::zeros(), &u2).unwrap()
+        };
+        let z = Vector2::new(0.4, 2.1);
+        let u1 = gb.orientation;
+        let u2 = Vector2::new(-0.3, 1.0).normalize();
+        let h = 1e-6;
+        let gradient = |f: &dyn Fn(Vector2<f64>) -> f64, x: Vector2<f64>| {
+            Vector2::from_fn(|i, _| {
+                let dx = Vector2::from_fn(|j, _| if i == j { h } else { 0.0 });
+                (f(x + dx) - f(x - dx)) / (2.0 * h)
+            })
+        };
+        let v = Vector2::zeros();
+        let (force, _) = gb
+            .calculate_force_between(&z, &v, &Vector2::zeros(), &v, &u2)
+            .unwrap();
+        let (torque_own, torque_ext) = gb
+            .calculate_torque_between(&z, &u1, &Vector2::zeros(), &u2, &u2)
+            .unwrap();
+        let force_num = -gradient(&|z| energy(z, u1, u2), z);
+        assert!((force - force_num).norm() < 1e-6);
+
+        // Torques are the projected gradients with respect to the orientations
+        let g1 = -gradient(&|u| energy(z, u, u2), u1);
+        let g2 = -gradient(&|u| energy(z, u1, u), u2);
+        assert!((torque_own - (g1 - u1 * g1.dot(&u1))).norm() < 1e-6);
+        assert!((torque_ext - (g2 - u2 * g2.dot(&u2))).norm() < 1e-6);
+        assert!(torque_own.dot(&u1).abs() < 1e-12);
+        assert!(torque_ext.dot(&u2).abs() < 1e-12);
+        assert!(torque_own.norm() > 1e-3);
+    }
+}
@@ -27,6 +27,10 @@ where
     }
 
     fn get_interaction_information(&self) -> () {}
+
+    fn potential_energy_between(&self, _: &Pos, _: &Pos, _: &()) -> Result<Option<f64>, CalcError> {
+        Ok(Some(0.0))
+    }
 }
 
 /// Lennard-Jones interaction potential with numerical upper and lower limit.
@@ -103,6 +107,28 @@ pub struct BoundLennardJonesF32 {
     pub cutoff: f32,
 }
 
+/// Calculates the component of `force` along the direction pointing from `ext_pos` to
+/// `own_pos`.
+///
+/// This is the [Interaction::normal_force] of the potentials in this module.
+/// Forces pushing the two positions apart are positive.
+/// For identical positions, the normal component is zero.
+pub fn calculate_normal_force<F, const D: usize>(
+    own_pos: &nalgebra::SVector<F, D>,
+    ext_pos: &nalgebra::SVector<F, D>,
+    force: &nalgebra::SVector<F, D>,
+) -> F
+where
+    F: Copy + nalgebra::RealField,
+{
+    let dir = own_pos - ext_pos;
+    let dist = dir.norm();
+    if dist.is_zero() {
+        return F::zero();
+    }
+    force.dot(&dir) / dist
+}
+
 macro_rules! implement_bound_lennard_jones(
     ($struct_name:ident, $float_type:ident) => {
         impl<const D: usize> Interaction<SVector<$float_type, D>, SVector<$float_type, D>, SVector<$float_type, D>>
@@ -127,6 +153,15 @@ macro_rules! implement_bound_lennard_jones(
             }
 
             fn get_interaction_information(&self) -> () {}
+
+            fn normal_force(
+                &self,
+                own_pos: &SVector<$float_type, D>,
+                ext_pos: &SVector<$float_type, D>,
+                force: &SVector<$float_type, D>,
+            ) -> Result<f64, CalcError> {
+                Ok(calculate_normal_force(own_pos, ext_pos, force) as f64)
+            }
         }
     };
 );
@@ -169,6 +204,33 @@ where
     Ok((dir * force, -dir * force))
 }
 
+/// Calculates the potential energy of the [MorsePotential] and [MorsePotentialF32] structs.
+///
+/// The potential is shifted such that it is continuous at the cutoff.
+pub fn calculate_morse_energy<F, const D: usize>(
+    own_pos: &nalgebra::SVector<F, D>,
+    ext_pos: &nalgebra::SVector<F, D>,
+    own_radius: F,
+    ext_radius: F,
+    cutoff: F,
+    strength: F,
+    potential_stiffness: F,
+) -> F
+where
+    F: Copy + nalgebra::RealField,
+{
+    let dist = (own_pos - ext_pos).norm();
+    if dist > cutoff {
+        return F::zero();
+    }
+    let r = own_radius + ext_radius;
+    let v = |x: F| {
+        let e = F::one() - (-potential_stiffness * (x - r)).exp();
+        strength * e * e
+    };
+    v(dist) - v(cutoff)
+}
+
 macro_rules! implement_morse_potential(
     ($struct_name:ident, $float_type:ident) => {
         /// Famous [Morse](https://doi.org/10.1103/PhysRev.34.57) potential for diatomic molecules.
@@ -243,6 +305,33 @@ macro_rules! implement_morse_potential(
                     self.potential_stiffness,
                 )
             }
+
+            fn normal_force(
+                &self,
+                own_pos: &nalgebra::SVector<$float_type, D>,
+                ext_pos: &nalgebra::SVector<$float_type, D>,
+                force: &nalgebra::SVector<$float_type, D>,
+            ) -> Result<f64, CalcError> {
+                Ok(calculate_normal_force(own_pos, ext_pos, force) as f64)
+            }
+
+            fn potential_energy_between(
+                &self,
+                own_pos: &nalgebra::SVector<$float_type, D>,
+                ext_pos: &nalgebra::SVector<$float_type, D>,
+                ext_info: &$float_type,
+            ) -> Result<Option<f64>, CalcError> {
+                let energy = calculate_morse_energy(
+                    own_pos,
+                    ext_pos,
+                    self.radius,
+                    *ext_info,
+                    self.cutoff,
+                    self.strength,
+                    self.potential_stiffness,
+                );
+                Ok(Some(energy as f64))
+            }
         }
 
         #[cfg(feature = "pyo3")]
@@ -363,6 +452,44 @@ macro_rules! implement_mie_potential(
             fn get_interaction_information(&self) -> $float_type {
                 self.radius
             }
+
+            fn normal_force(
+                &self,
+                own_pos: &SVector<$float_type, D>,
+                ext_pos: &SVector<$float_type, D>,
+                force: &SVector<$float_type, D>,
+            ) -> Result<f64, CalcError> {
+                Ok(calculate_normal_force(own_pos, ext_pos, force) as f64)
+            }
+
+            /// The energy is given by $U(r)-U(\zeta)$ which is continuous at the cutoff.
+            /// The numerical bound $\beta$ of the force is not taken into account.
+            fn potential_energy_between(
+                &self,
+                own_pos: &SVector<$float_type, D>,
+                ext_pos: &SVector<$float_type, D>,
+                ext_radius: &$float_type,
+            ) -> Result<Option<f64>, CalcError> {
+                let r = (own_pos - ext_pos).norm();
+                if r == 0.0 {
+                    return Err(CalcError(format!(
+                        "identical position for two objects. Cannot Calculate energy in Mie potential"
+                    )));
+                }
+                if r > self.cutoff {
+                    return Ok(Some(0.0));
+                }
+                let sigma = self.radius_to_sigma_factor() * (self.radius + *ext_radius);
+                let mie_constant =
+                    self.en / (self.en - self.em)
+                        * (self.en / self.em).powf(self.em / (self.en - self.em));
+                let u = |x: $float_type| {
+                    mie_constant
+                        * self.strength
+                        * ((sigma / x).powf(self.en) - (sigma / x).powf(self.em))
+                };
+                Ok(Some((u(r) - u(self.cutoff)) as f64))
+            }
         }
 
         impl $name {
@@ -931,4 +1058,89 @@ mod test {
             assert_eq!(n_intersections % 2 == 0, false);
         }
     }
+
+    #[test]
+    fn normal_force_of_repelling_potential_is_positive() {
+        use cellular_raza_concepts::Interaction;
+        let morse = super::MorsePotential {
+            radius: 1.0,
+            potential_stiffness: 0.8,
+            cutoff: 5.0,
+            strength: 0.3,
+        };
+        let own_pos = nalgebra::Vector2::from([1.0, 1.0]);
+        let zero = nalgebra::Vector2::zeros();
+        // Overlapping cells repel each other
+        let (force, ext_force) = morse
+            .calculate_force_between(&own_pos, &zero, &zero, &zero, &1.0)
+            .unwrap();
+        let normal_force = morse.normal_force(&own_pos, &zero, &force).unwrap();
+        assert!(normal_force > 0.0);
+        assert!((normal_force - force.norm()).abs() < 1e-12);
+        assert_eq!(
+            morse.normal_force(&zero, &own_pos, &ext_force).unwrap(),
+            normal_force
+        );
+        // Distant cells attract each other
+        let own_pos = nalgebra::Vector2::from([3.0, 0.0]);
+        let (force, _) = morse
+            .calculate_force_between(&own_pos, &zero, &zero, &zero, &1.0)
+            .unwrap();
+        assert!(morse.normal_force(&own_pos, &zero, &force).unwrap() < 0.0);
+    }
+
+    #[test]
+    fn potential_energy_matches_force() {
+        use cellular_raza_concepts::Interaction;
+        let morse = super::MorsePotential {
+            radius: 1.0,
+            potential_stiffness: 0.8,
+            cutoff: 5.0,
+            strength: 0.3,
+        };
+        let mie = super::MiePotential {
+            radius: 1.0,
+            strength: 0.3,
+            bound: f64::INFINITY,
+            cutoff: 5.0,
+            en: 6.0,
+            em: 3.0,
+        };
+        let zero = nalgebra::Vector2::zeros();
+        let energy = |interaction: &dyn Interaction<_, _, _, f64>, x| {
+            interaction
+                .potential_energy_between(&x, &zero, &1.0)
+                .unwrap()
+                .unwrap()
+        };
+        let h = 1e-6;
+        for r in [1.5, 2.0, 3.0, 4.5] {
+            let x = nalgebra::Vector2::from([r, 0.0]);
+            let dx = nalgebra::Vector2::from([h, 0.0]);
+            let (f_morse, _) = morse
+                .calculate_force_between(&x, &zero, &zero, &zero, &1.0)
+                .unwrap();
+            let de_morse = (energy(&morse, x + dx) - energy(&morse, x - dx)) / (2.0 * h);
+            assert!((f_morse[0] + de_morse).abs() < 1e-6);
+
+            let (f_mie, _) = mie
+                .calculate_force_between(&x, &zero, &zero, &zero, &1.0)
+                .unwrap();
+            let de_mie = (energy(&mie, x + dx) - energy(&mie, x - dx)) / (2.0 * h);
+            assert!((f_mie[0] + de_mie).abs() < 1e-6);
+        }
+        let far = nalgebra::Vector2::from([6.0, 0.0]);
+        assert_eq!(energy(&morse, far), 0.0);
+        let no_interaction = super::NoInteraction;
+        assert_eq!(
+            Interaction::<f64, f64, f64>::potential_energy_between(
+                &no_interaction,
+                &1.0,
+                &0.0,
+                &()
+            )
+            .unwrap(),
+            Some(0.0)
+        );
+    }
 }
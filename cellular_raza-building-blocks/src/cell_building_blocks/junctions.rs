@@ -0,0 +1,196 @@
+use cellular_raza_concepts::*;
+use nalgebra::SVector;
+
+use serde::{Deserialize, Serialize};
+
+/// Viscoelastic junction between two spherical cells modelled by a Kelvin-Voigt element.
+///
+/// Two cells with radii $R_i$ and $R_j$ are connected by a spring in parallel with a dashpot
+/// whose rest length is $L=R_i+R_j$.
+/// The junction strain $\varepsilon$ and its rate of change are calculated from the
+/// distance $d$ and the relative velocity of both cells
+/// \\begin{align}
+///     \varepsilon &= \frac{d-L}{L}\\\\
+///     \dot{\varepsilon} &= \frac{(\vec{v}_i-\vec{v}_j)\cdot\vec{n}}{L}
+/// \\end{align}
+/// where $\vec{n}=(\vec{x}_i-\vec{x}_j)/d$ is the normal of the contact.
+/// The force acting on the cell is given by
+/// \\begin{equation}
+///     \vec{F} = -\left(k\varepsilon + \eta\dot{\varepsilon}\right)\vec{n}.
+/// \\end{equation}
+/// Compressed junctions push cells apart while stretched junctions pull them together.
+/// The dashpot damps relative motion along the normal such that stresses do not propagate
+/// instantaneously through a tissue and deformations creep towards their equilibrium.
+/// When the strain exceeds `rupture_strain`, the junction ruptures and no force is
+/// exerted anymore.
+///
+/// The [Interaction] trait calculates forces of cells which can not be modified during this
+/// calculation.
+/// Thus the junction can not carry additional internal state such as the remodeled rest
+/// length of a Maxwell element.
+///
+/// ```
+/// # use cellular_raza_building_blocks::*;
+/// # use cellular_raza_concepts::*;
+/// # use nalgebra::Vector2;
+/// let junction = KelvinVoigtJunction {
+///     radius: 1.0,
+///     stiffness: 2.0,
+///     viscosity: 0.5,
+///     rupture_strain: 0.2,
+/// };
+/// // Cells move apart from each other while their junction is stretched
+/// let (f1, f2) = junction.calculate_force_between(
+///     &Vector2::from([0.0, 0.0]),
+///     &Vector2::from([-0.1, 0.0]),
+///     &Vector2::from([2.2, 0.0]),
+///     &Vector2::from([0.1, 0.0]),
+///     &1.0,
+/// )?;
+/// // strain = 0.1, strain rate = 0.1
+/// assert!((f1 - Vector2::from([0.25, 0.0])).norm() < 1e-12);
+/// assert_eq!(f1, -f2);
+/// # Ok::<(), CalcError>(())
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct KelvinVoigtJunction {
+    /// Radius $R$ of the cell
+    pub radius: f64,
+    /// Stiffness $k$ of the spring
+    pub stiffness: f64,
+    /// Viscosity $\eta$ of the dashpot
+    pub viscosity: f64,
+    /// Junctions rupture when their strain exceeds this value
+    pub rupture_strain: f64,
+}
+
+impl KelvinVoigtJunction {
+    /// Strain and strain rate of the junction between two cells.
+    ///
+    /// Returns [None] if the cells are not connected.
+    pub fn strain<const D: usize>(
+        &self,
+        own_pos: &SVector<f64, D>,
+        own_vel: &SVector<f64, D>,
+        ext_pos: &SVector<f64, D>,
+        ext_vel: &SVector<f64, D>,
+        ext_radius: f64,
+    ) -> Option<(f64, f64)> {
+        let rest_length = self.radius + ext_radius;
+        let dir = own_pos - ext_pos;
+        let dist = dir.norm();
+        let strain = (dist - rest_length) / rest_length;
+        if dist == 0.0 || strain > self.rupture_strain {
+            return None;
+        }
+        let strain_rate = (own_vel - ext_vel).dot(&dir) / dist / rest_length;
+        Some((strain, strain_rate))
+    }
+}
+
+impl<const D: usize> Interaction<SVector<f64, D>, SVector<f64, D>, SVector<f64, D>, f64>
+    for KelvinVoigtJunction
+{
+    fn get_interaction_information(&self) -> f64 {
+        self.radius
+    }
+
+    fn calculate_force_between(
+        &self,
+        own_pos: &SVector<f64, D>,
+        own_vel: &SVector<f64, D>,
+        ext_pos: &SVector<f64, D>,
+        ext_vel: &SVector<f64, D>,
+        ext_radius: &f64,
+    ) -> Result<(SVector<f64, D>, SVector<f64, D>), CalcError> {
+        match self.strain(own_pos, own_vel, ext_pos, ext_vel, *ext_radius) {
+            Some((strain, strain_rate)) => {
+                let normal = (own_pos - ext_pos).normalize();
+                let force = -(self.stiffness * strain + self.viscosity * strain_rate) * normal;
+                Ok((force, -force))
+            }
+            None => Ok((SVector::zeros(), SVector::zeros())),
+        }
+    }
+
+    fn is_neighbor(
+        &self,
+        own_pos: &SVector<f64, D>,
+        ext_pos: &SVector<f64, D>,
+        ext_radius: &f64,
+    ) -> Result<bool, CalcError> {
+        let zero = SVector::zeros();
+        Ok(self
+            .strain(own_pos, &zero, ext_pos, &zero, *ext_radius)
+            .is_some())
+    }
+}
+
+#[cfg(test)]
+mod test_junctions {
+    use super::*;
+    use nalgebra::Vector1;
+
+    fn creep(viscosity: f64) -> Result<Vec<f64>, CalcError> {
+        // Two overdamped cells are pulled apart by a constant external force
+        let junction = KelvinVoigtJunction {
+            radius: 1.0,
+            stiffness: 1.0,
+            viscosity,
+            rupture_strain: 1.0,
+        };
+        let (load, damping, dt) = (0.1, 1.0, 0.001);
+        let mut x = [Vector1::from([0.0]), Vector1::from([2.0])];
+        let mut v = [Vector1::zeros(), Vector1::zeros()];
+        let mut strains = vec![];
+        for n in 0..15_000 {
+            // The velocities enter the force such that we iterate until they are consistent
+            for _ in 0..20 {
+                let (f1, f2) =
+                    junction.calculate_force_between(&x[0], &v[0], &x[1], &v[1], &1.0)?;
+                v[0] = (f1 - Vector1::from([load])) / damping;
+                v[1] = (f2 + Vector1::from([load])) / damping;
+            }
+            x[0] += dt * v[0];
+            x[1] += dt * v[1];
+            if n % 1000 == 0 {
+                strains.push((x[1] - x[0]).norm() / 2.0 - 1.0);
+            }
+        }
+        Ok(strains)
+    }
+
+    #[test]
+    fn creep_under_constant_load() -> Result<(), CalcError> {
+        let elastic = creep(0.0)?;
+        let viscoelastic = creep(0.5)?;
+        // The strain increases monotonically towards the equilibrium load/stiffness
+        for strains in [&elastic, &viscoelastic] {
+            assert!(strains.windows(2).all(|w| w[1] > w[0]));
+            let last = strains.last().unwrap();
+            assert!(*last > 0.099 && *last < 0.1);
+        }
+        // The dashpot delays the deformation
+        assert!(viscoelastic[1] < 0.85 * elastic[1]);
+        Ok(())
+    }
+
+    #[test]
+    fn junction_ruptures() -> Result<(), CalcError> {
+        let junction = KelvinVoigtJunction {
+            radius: 1.0,
+            stiffness: 1.0,
+            viscosity: 1.0,
+            rupture_strain: 0.1,
+        };
+        let zero = Vector1::zeros();
+        let (f1, _) =
+            junction.calculate_force_between(&zero, &zero, &Vector1::from([2.1]), &zero, &1.0)?;
+        assert!(f1[0] > 0.0);
+        let (f1, _) =
+            junction.calculate_force_between(&zero, &zero, &Vector1::from([2.3]), &zero, &1.0)?;
+        assert_eq!(f1[0], 0.0);
+        assert!(!junction.is_neighbor(&zero, &Vector1::from([2.3]), &1.0)?);
+        Ok(())
+    }
+}
@@ -0,0 +1,214 @@
+use cellular_raza_concepts::*;
+use nalgebra::{SVector, Vector3};
+
+use serde::{Deserialize, Serialize};
+
+/// Lateral inhibition by Delta-Notch signalling between cells in direct contact.
+///
+/// The intracellular vector $(N, D, S)$ contains the Notch activity $N$, the Delta level $D$ and
+/// the trans-activation signal $S$ which a cell receives from the Delta ligands of its
+/// neighbors.
+/// We follow the model by Collier et al.
+/// [(1996)](https://doi.org/10.1006/jtbi.1996.0233)
+/// \\begin{align}
+///     \dot{N} &= \frac{S^k}{a+S^k} - N\\\\
+///     \dot{D} &= v\left(\frac{1}{1+bN^h} - D\right)\\\\
+///     \dot{S} &= k_S\left(w\sum\limits_{j}D_j - S\right)
+/// \\end{align}
+/// where the sum runs over all cells $j$ which are closer than the `contact_range`.
+/// The signal $S$ relaxes quickly towards the weighted sum of the Delta levels of all
+/// neighbors.
+/// When choosing the weight $w$ as the inverse of the typical number of neighbors, $S$
+/// approximates the mean Delta level of the neighbors as in the original model.
+/// The contributions of the neighbors are calculated by the [ReactionsContact] trait while the
+/// remaining terms are given by the [Reactions] trait.
+/// Thus simulations need to include both the `Reactions` and `ReactionsContact` aspects.
+///
+/// ```
+/// # use cellular_raza_building_blocks::*;
+/// # use cellular_raza_concepts::*;
+/// # use nalgebra::{Vector2, Vector3};
+/// let cell = DeltaNotch {
+///     intracellular: Vector3::from([0.0, 1.0, 0.0]),
+///     ..Default::default()
+/// };
+/// // Neighbors increase the trans-activation signal by their Delta level
+/// let (dintra, _) = cell.calculate_contact_increment(
+///     &cell.get_intracellular(),
+///     &Vector3::from([0.0, 0.5, 0.0]),
+///     &Vector2::from([0.0, 0.0]),
+///     &Vector2::from([1.0, 0.0]),
+///     &(),
+/// )?;
+/// assert_eq!(dintra, Vector3::from([0.0, 0.0, 0.5 * cell.signal_rate * cell.contact_weight]));
+/// # Ok::<(), CalcError>(())
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DeltaNotch {
+    /// Notch activity $N$, Delta level $D$ and trans-activation signal $S$
+    pub intracellular: Vector3<f64>,
+    /// Threshold $a$ of Notch activation
+    pub notch_activation: f64,
+    /// Hill coefficient $k$ of Notch activation
+    pub notch_hill: f64,
+    /// Strength $b$ of Delta inhibition by Notch
+    pub delta_inhibition: f64,
+    /// Hill coefficient $h$ of Delta inhibition
+    pub delta_hill: f64,
+    /// Ratio $v$ of Delta and Notch timescales
+    pub delta_rate: f64,
+    /// Rate $k_S$ at which the signal follows the Delta levels of neighbors
+    pub signal_rate: f64,
+    /// Weight $w$ of every neighbor
+    pub contact_weight: f64,
+    /// Cells which are closer than this distance exchange signals
+    pub contact_range: f64,
+}
+
+impl Default for DeltaNotch {
+    /// Parameters of Collier et al. for cells with unit distance on a hexagonal lattice
+    fn default() -> Self {
+        Self {
+            intracellular: Vector3::zeros(),
+            notch_activation: 0.01,
+            notch_hill: 2.0,
+            delta_inhibition: 100.0,
+            delta_hill: 2.0,
+            delta_rate: 1.0,
+            signal_rate: 10.0,
+            contact_weight: 1.0 / 6.0,
+            contact_range: 1.5,
+        }
+    }
+}
+
+impl DeltaNotch {
+    /// Notch activity $N$
+    pub fn notch(&self) -> f64 {
+        self.intracellular[0]
+    }
+
+    /// Delta level $D$
+    pub fn delta(&self) -> f64 {
+        self.intracellular[1]
+    }
+}
+
+impl Intracellular<Vector3<f64>> for DeltaNotch {
+    fn set_intracellular(&mut self, intracellular: Vector3<f64>) {
+        self.intracellular = intracellular;
+    }
+
+    fn get_intracellular(&self) -> Vector3<f64> {
+        self.intracellular
+    }
+}
+
+impl Reactions<Vector3<f64>> for DeltaNotch {
+    fn calculate_intracellular_increment(
+        &self,
+        intracellular: &Vector3<f64>,
+    ) -> Result<Vector3<f64>, CalcError> {
+        let [notch, delta, signal]: [f64; 3] = (*intracellular).into();
+        let activation = signal.max(0.0).powf(self.notch_hill);
+        let dnotch = activation / (self.notch_activation + activation) - notch;
+        let ddelta = self.delta_rate
+            * (1.0 / (1.0 + self.delta_inhibition * notch.max(0.0).powf(self.delta_hill)) - delta);
+        let dsignal = -self.signal_rate * signal;
+        Ok([dnotch, ddelta, dsignal].into())
+    }
+}
+
+impl<const D: usize> ReactionsContact<Vector3<f64>, SVector<f64, D>> for DeltaNotch {
+    fn get_contact_information(&self) {}
+
+    fn calculate_contact_increment(
+        &self,
+        own_intracellular: &Vector3<f64>,
+        ext_intracellular: &Vector3<f64>,
+        own_pos: &SVector<f64, D>,
+        ext_pos: &SVector<f64, D>,
+        _rinf: &(),
+    ) -> Result<(Vector3<f64>, Vector3<f64>), CalcError> {
+        if (own_pos - ext_pos).norm() >= self.contact_range {
+            return Ok((Vector3::zeros(), Vector3::zeros()));
+        }
+        let strength = self.signal_rate * self.contact_weight;
+        Ok((
+            Vector3::from([0.0, 0.0, strength * ext_intracellular[1]]),
+            Vector3::from([0.0, 0.0, strength * own_intracellular[1]]),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test_juxtacrine {
+    use super::*;
+    use nalgebra::Vector1;
+
+    #[test]
+    fn lateral_inhibition_on_chain() -> Result<(), CalcError> {
+        // Cells on a line with unit spacing and slightly perturbed initial Delta levels
+        let n_cells = 8;
+        let mut cells: Vec<_> = (0..n_cells)
+            .map(|i| DeltaNotch {
+                intracellular: [0.0, 0.5 + 0.01 * ((7 * i) % 5) as f64, 0.0].into(),
+                contact_weight: 0.5,
+                ..Default::default()
+            })
+            .collect();
+        let positions: Vec<_> = (0..n_cells).map(|i| Vector1::from([i as f64])).collect();
+        let dt = 0.005;
+        for _ in 0..10_000 {
+            let mut increments = cells
+                .iter()
+                .map(|c| c.calculate_intracellular_increment(&c.intracellular))
+                .collect::<Result<Vec<_>, _>>()?;
+            for i in 0..n_cells {
+                for j in i + 1..n_cells {
+                    let (di, dj) = cells[i].calculate_contact_increment(
+                        &cells[i].intracellular,
+                        &cells[j].intracellular,
+                        &positions[i],
+                        &positions[j],
+                        &(),
+                    )?;
+                    increments[i] += di;
+                    increments[j] += dj;
+                }
+            }
+            for (cell, incr) in cells.iter_mut().zip(increments) {
+                cell.intracellular += dt * incr;
+            }
+        }
+        // Every cell with high Delta is next to cells with low Delta
+        let high: Vec<_> = cells.iter().map(|c| c.delta() > 0.5).collect();
+        assert!(high.iter().any(|h| *h));
+        for i in 0..n_cells - 1 {
+            assert!(!(high[i] && high[i + 1]));
+            assert!(high[i] || high[i + 1] || (i > 0 && high[i - 1]));
+        }
+        for cell in cells.iter() {
+            // Both levels are no NaN such that the comparisons below are well-defined
+            assert!(cell.delta() > 0.9 || cell.delta() < 0.1);
+            assert!(!cell.notch().is_nan());
+            assert_eq!(cell.notch() > 0.5, cell.delta() <= 0.5);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn no_signal_beyond_contact_range() -> Result<(), CalcError> {
+        let cell = DeltaNotch::default();
+        let (d1, d2) = cell.calculate_contact_increment(
+            &[0.0, 1.0, 0.0].into(),
+            &[0.0, 1.0, 0.0].into(),
+            &Vector1::from([0.0]),
+            &Vector1::from([2.0]),
+            &(),
+        )?;
+        assert_eq!(d1, Vector3::zeros());
+        assert_eq!(d2, Vector3::zeros());
+        Ok(())
+    }
+}
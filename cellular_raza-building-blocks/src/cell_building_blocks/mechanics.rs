@@ -1,4 +1,4 @@
-use cellular_raza_concepts::{CalcError, Mechanics, RngError};
+use cellular_raza_concepts::{CalcError, GlobalParameters, Mechanics, RngError, TimeFunction};
 
 use itertools::Itertools;
 use nalgebra::{SMatrix, SVector};
@@ -176,6 +176,16 @@ macro_rules! implement_newton_damped_mechanics(
             }
         }
 
+        impl cellular_raza_concepts::PositionLike<$float_type, $d> for $struct_name {
+            fn representative_point(&self) -> [$float_type; $d] {
+                self.pos.into()
+            }
+
+            fn bounding_box(&self) -> ([$float_type; $d], [$float_type; $d]) {
+                (self.pos.into(), self.pos.into())
+            }
+        }
+
         impl cellular_raza_concepts::Velocity<SVector<$float_type, $d>> for $struct_name {
             fn velocity(&self) -> SVector<$float_type, $d> {
                 self.vel
@@ -185,6 +195,18 @@ macro_rules! implement_newton_damped_mechanics(
                 self.vel = *velocity;
             }
         }
+
+        impl cellular_raza_concepts::KineticEnergy<SVector<$float_type, $d>, $float_type>
+            for $struct_name
+        {
+            fn kinetic_energy(&self) -> $float_type {
+                0.5 * self.mass * self.vel.norm_squared()
+            }
+
+            fn momentum(&self) -> SVector<$float_type, $d> {
+                self.mass * self.vel
+            }
+        }
     }
 );
 
@@ -218,6 +240,71 @@ where
     Ok(random_dir / dt)
 }
 
+/// Time-dependent temperature of stochastic mechanics.
+///
+/// The schedule describes the relative temperature $s(t)$ such that the effective temperature is
+/// given by $s(t)k_BT$.
+/// Since the noise amplitude is proportional to $\sqrt{k_BT}$, all random contributions of the
+/// [Brownian3D] and [Langevin3D] families of mechanics are scaled by $\sqrt{s(t)}$.
+/// The schedule is configured for the whole simulation by inserting it into the
+/// [GlobalParameters].
+/// Lowering the temperature over time helps to relax initial configurations while sudden changes
+/// can be used to model temperature-jump experiments.
+/// Without a schedule, the temperature of the individual mechanics is used unchanged.
+///
+/// ```
+/// # use cellular_raza_building_blocks::*;
+/// # use cellular_raza_concepts::*;
+/// use rand::SeedableRng;
+/// let schedule = AnnealingSchedule(TimeFunction::LinearRamp {
+///     t_start: 0.0,
+///     t_end: 100.0,
+///     value_start: 4.0,
+///     value_end: 1.0,
+/// });
+/// let parameters = GlobalParameters::new()
+///     .insert(schedule)
+///     .at_time(0.0);
+/// assert_eq!(AnnealingSchedule::<f64>::noise_factor(&parameters)?, 2.0);
+///
+/// let mechanics = Brownian2D::new([0.0; 2], 1.0, 1.0);
+/// let mut rng1 = rand_chacha::ChaCha8Rng::seed_from_u64(0);
+/// let mut rng2 = rng1.clone();
+/// let (dx1, _) = mechanics.get_random_contribution(&mut rng1, 0.1)?;
+/// let (dx2, _) = mechanics.get_random_contribution_with_parameters(&mut rng2, 0.1, &parameters)?;
+/// assert!((dx2 - 2.0 * dx1).norm() < 1e-12);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct AnnealingSchedule<F>(pub TimeFunction<F>);
+
+impl<F> AnnealingSchedule<F>
+where
+    F: num::Float + Send + Sync + 'static,
+{
+    /// Factor $\sqrt{s(t)}$ by which random contributions are scaled at the current
+    /// [simulation time](GlobalParameters::simulation_time).
+    ///
+    /// Returns one if no schedule was inserted into the [GlobalParameters].
+    pub fn noise_factor(parameters: &GlobalParameters) -> Result<F, RngError> {
+        let schedule = match parameters.get::<Self>() {
+            Some(schedule) => schedule,
+            None => return Ok(F::one()),
+        };
+        let relative_temperature = schedule
+            .0
+            .evaluate(parameters)
+            .map_err(|e| RngError(format!("{e}")))?;
+        if relative_temperature < F::zero() {
+            return Err(RngError(format!(
+                "annealing schedule yielded negative relative temperature at time {:?}",
+                parameters.simulation_time::<f64>()
+            )));
+        }
+        Ok(relative_temperature.sqrt())
+    }
+}
+
 macro_rules! implement_brownian_mechanics(
     ($struct_name:ident, $d:literal, $float_type:ty) => {
         /// Brownian motion of particles
@@ -337,6 +424,17 @@ macro_rules! implement_brownian_mechanics(
                 Ok((dpos, dvel))
             }
 
+            fn get_random_contribution_with_parameters(
+                &self,
+                rng: &mut rand_chacha::ChaCha8Rng,
+                dt: $float_type,
+                parameters: &GlobalParameters,
+            ) -> Result<(SVector<$float_type, $d>, SVector<$float_type, $d>), RngError> {
+                let factor = AnnealingSchedule::<$float_type>::noise_factor(parameters)?;
+                let (dpos, dvel) = self.get_random_contribution(rng, dt)?;
+                Ok((dpos * factor, dvel))
+            }
+
             fn calculate_increment(
                 &self,
                 force: SVector<$float_type, $d>,
@@ -358,6 +456,16 @@ macro_rules! implement_brownian_mechanics(
 
         }
 
+        impl cellular_raza_concepts::PositionLike<$float_type, $d> for $struct_name {
+            fn representative_point(&self) -> [$float_type; $d] {
+                self.pos.into()
+            }
+
+            fn bounding_box(&self) -> ([$float_type; $d], [$float_type; $d]) {
+                (self.pos.into(), self.pos.into())
+            }
+        }
+
         impl cellular_raza_concepts::Velocity<SVector<$float_type, $d>> for $struct_name {
             fn velocity(&self) -> SVector<$float_type, $d> {
                 use num::Zero;
@@ -376,6 +484,242 @@ implement_brownian_mechanics!(Brownian1DF32, 1, f32);
 implement_brownian_mechanics!(Brownian2DF32, 2, f32);
 implement_brownian_mechanics!(Brownian3DF32, 3, f32);
 
+/// Self-propelled particle whose orientation undergoes rotational diffusion.
+///
+/// This is the standard model of active brownian particles which is commonly used to describe
+/// motile bacteria and amoeboid cells.
+///
+/// # Parameters & Variables
+/// | Symbol | Struct Field | Description |
+/// |:---:| --- | --- |
+/// | $v_0$ | `self_propulsion_speed` | Speed at which the particle moves along its orientation. |
+/// | $D$ | `diffusion_constant` | Translational diffusion constant. |
+/// | $D_r$ | `rotational_diffusion_constant` | Rotational diffusion constant. |
+/// | $k_BT$ | `kb_temperature` | Product of temperature $T$ and Boltzmann constant $k_B$. |
+/// | | | |
+/// | $\vec{x}$ | `pos` | Position of the particle. |
+/// | $\hat{u}$ | `orientation` | Unit vector along which the particle propels itself. |
+/// | $R(t),R_r(t)$ | (automatically generated) | Gaussian processes |
+///
+/// # Equations
+/// \\begin{align}
+///     \dot{\vec{x}} &= v_0\hat{u} + \frac{D}{k_BT}\vec{F} + \sqrt{2D}R(t)\\\\
+///     \dot{\hat{u}} &= \left(1 - \hat{u}\hat{u}^T\right)
+///         \left(\frac{D_r}{k_BT}\vec{g} + \sqrt{2D_r}R_r(t)\right)
+/// \\end{align}
+/// The translational part is described by the [Mechanics] trait and the rotational part by
+/// the [RotationalMechanics](cellular_raza_concepts::RotationalMechanics) trait.
+/// The torque $\vec{g}$ is given as the projected gradient of the potential with respect to
+/// the orientation as calculated for example by the [GayBerne](crate::GayBerne) interaction.
+/// After every update, the orientation is normalized by
+/// [set_orientation](cellular_raza_concepts::Orientation::set_orientation) such that it
+/// stays on the unit sphere.
+/// Since the particle is overdamped, its velocity and angular velocity are always zero.
+///
+/// For free particles, the orientation decorrelates as
+/// $\langle\hat{u}(t)\cdot\hat{u}(0)\rangle = e^{-(d-1)D_rt}$ where $d$ is the dimension.
+/// ```
+/// # use cellular_raza_building_blocks::ActiveBrownianMechanics;
+/// # use cellular_raza_concepts::{Mechanics, Orientation};
+/// # use nalgebra::Vector2;
+/// let mut particle =
+///     ActiveBrownianMechanics::<f64, 2>::new([0.0; 2], [0.0, 2.0], 3.0, 0.1, 0.5, 1.0);
+/// // The orientation is always normalized
+/// assert_eq!(particle.orientation(), Vector2::from([0.0, 1.0]));
+///
+/// // Without any forces, the particle moves along its orientation
+/// let (dx, _) = particle.calculate_increment(Vector2::zeros())?;
+/// assert_eq!(dx, Vector2::from([0.0, 3.0]));
+/// # Ok::<(), cellular_raza_concepts::CalcError>(())
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(bound(
+    serialize = "F: nalgebra::Scalar + Serialize",
+    deserialize = "F: nalgebra::Scalar + Deserialize<'de>"
+))]
+pub struct ActiveBrownianMechanics<F, const D: usize> {
+    /// Current position of the particle $\vec{x}$.
+    pub pos: SVector<F, D>,
+    /// Current orientation $\hat{u}$ of the particle.
+    pub orientation: SVector<F, D>,
+    /// Self-propulsion speed $v_0$.
+    pub self_propulsion_speed: F,
+    /// Translational diffusion constant $D$.
+    pub diffusion_constant: F,
+    /// Rotational diffusion constant $D_r$.
+    pub rotational_diffusion_constant: F,
+    /// The product of temperature and boltzmann constant $k_B T$.
+    pub kb_temperature: F,
+}
+
+impl<F, const D: usize> ActiveBrownianMechanics<F, D>
+where
+    F: nalgebra::RealField + Copy,
+{
+    /// Constructs a new [ActiveBrownianMechanics] and normalizes the given orientation.
+    pub fn new(
+        pos: [F; D],
+        orientation: [F; D],
+        self_propulsion_speed: F,
+        diffusion_constant: F,
+        rotational_diffusion_constant: F,
+        kb_temperature: F,
+    ) -> Self {
+        let mut particle = Self {
+            pos: pos.into(),
+            orientation: orientation.into(),
+            self_propulsion_speed,
+            diffusion_constant,
+            rotational_diffusion_constant,
+            kb_temperature,
+        };
+        particle.orientation = particle.normalized(&particle.orientation);
+        particle
+    }
+
+    /// Normalizes the orientation and keeps the previous one if it has zero length
+    fn normalized(&self, orientation: &SVector<F, D>) -> SVector<F, D> {
+        orientation
+            .try_normalize(F::zero())
+            .unwrap_or(self.orientation)
+    }
+
+    /// Removes the component parallel to the current orientation
+    fn project(&self, v: SVector<F, D>) -> SVector<F, D> {
+        v - self.orientation * self.orientation.dot(&v)
+    }
+}
+
+impl<F, const D: usize> Mechanics<SVector<F, D>, SVector<F, D>, SVector<F, D>, F>
+    for ActiveBrownianMechanics<F, D>
+where
+    F: nalgebra::RealField + num::Float + Copy,
+    rand_distr::StandardNormal: rand_distr::Distribution<F>,
+{
+    fn get_random_contribution(
+        &self,
+        rng: &mut rand_chacha::ChaCha8Rng,
+        dt: F,
+    ) -> Result<(SVector<F, D>, SVector<F, D>), RngError> {
+        let two = F::one() + F::one();
+        let dpos = wiener_process(rng, dt)? * num::Float::sqrt(two * self.diffusion_constant);
+        Ok((dpos, SVector::zeros()))
+    }
+
+    fn get_random_contribution_with_parameters(
+        &self,
+        rng: &mut rand_chacha::ChaCha8Rng,
+        dt: F,
+        parameters: &GlobalParameters,
+    ) -> Result<(SVector<F, D>, SVector<F, D>), RngError> {
+        let factor = AnnealingSchedule::<F>::noise_factor(parameters)?;
+        let (dpos, dvel) = self.get_random_contribution(rng, dt)?;
+        Ok((dpos * factor, dvel))
+    }
+
+    fn calculate_increment(
+        &self,
+        force: SVector<F, D>,
+    ) -> Result<(SVector<F, D>, SVector<F, D>), CalcError> {
+        let dx = self.orientation * self.self_propulsion_speed
+            + force * (self.diffusion_constant / self.kb_temperature);
+        Ok((dx, SVector::zeros()))
+    }
+}
+
+impl<F, const D: usize>
+    cellular_raza_concepts::RotationalMechanics<SVector<F, D>, SVector<F, D>, SVector<F, D>, F>
+    for ActiveBrownianMechanics<F, D>
+where
+    F: nalgebra::RealField + num::Float + Copy,
+    rand_distr::StandardNormal: rand_distr::Distribution<F>,
+{
+    fn get_random_rotational_contribution(
+        &self,
+        rng: &mut rand_chacha::ChaCha8Rng,
+        dt: F,
+    ) -> Result<(SVector<F, D>, SVector<F, D>), RngError> {
+        let two = F::one() + F::one();
+        let dang = self.project(wiener_process(rng, dt)?)
+            * num::Float::sqrt(two * self.rotational_diffusion_constant);
+        Ok((dang, SVector::zeros()))
+    }
+
+    fn calculate_rotational_increment(
+        &self,
+        torque: SVector<F, D>,
+    ) -> Result<(SVector<F, D>, SVector<F, D>), CalcError> {
+        let dang =
+            self.project(torque) * (self.rotational_diffusion_constant / self.kb_temperature);
+        Ok((dang, SVector::zeros()))
+    }
+}
+
+impl<F, const D: usize> cellular_raza_concepts::Position<SVector<F, D>>
+    for ActiveBrownianMechanics<F, D>
+where
+    F: nalgebra::Scalar + Copy,
+{
+    fn pos(&self) -> SVector<F, D> {
+        self.pos
+    }
+
+    fn set_pos(&mut self, pos: &SVector<F, D>) {
+        self.pos = *pos;
+    }
+}
+
+impl<F, const D: usize> cellular_raza_concepts::PositionLike<F, D> for ActiveBrownianMechanics<F, D>
+where
+    F: nalgebra::Scalar + Copy,
+{
+    fn representative_point(&self) -> [F; D] {
+        self.pos.into()
+    }
+
+    fn bounding_box(&self) -> ([F; D], [F; D]) {
+        (self.pos.into(), self.pos.into())
+    }
+}
+
+impl<F, const D: usize> cellular_raza_concepts::Velocity<SVector<F, D>>
+    for ActiveBrownianMechanics<F, D>
+where
+    F: nalgebra::Scalar + num::Zero,
+{
+    fn velocity(&self) -> SVector<F, D> {
+        SVector::zeros()
+    }
+
+    fn set_velocity(&mut self, _velocity: &SVector<F, D>) {}
+}
+
+impl<F, const D: usize> cellular_raza_concepts::Orientation<SVector<F, D>>
+    for ActiveBrownianMechanics<F, D>
+where
+    F: nalgebra::RealField + Copy,
+{
+    fn orientation(&self) -> SVector<F, D> {
+        self.orientation
+    }
+
+    fn set_orientation(&mut self, orientation: &SVector<F, D>) {
+        self.orientation = self.normalized(orientation);
+    }
+}
+
+impl<F, const D: usize> cellular_raza_concepts::AngularVelocity<SVector<F, D>>
+    for ActiveBrownianMechanics<F, D>
+where
+    F: nalgebra::Scalar + num::Zero,
+{
+    fn angular_velocity(&self) -> SVector<F, D> {
+        SVector::zeros()
+    }
+
+    fn set_angular_velocity(&mut self, _angular_velocity: &SVector<F, D>) {}
+}
+
 macro_rules! define_langevin_nd(
     ($struct_name:ident, $d:literal, $float_type:ident) => {
         /// Langevin dynamics
@@ -435,6 +779,17 @@ macro_rules! define_langevin_nd(
                 Ok((dpos, dvel))
             }
 
+            fn get_random_contribution_with_parameters(
+                &self,
+                rng: &mut rand_chacha::ChaCha8Rng,
+                dt: $float_type,
+                parameters: &GlobalParameters,
+            ) -> Result<(SVector<$float_type, $d>, SVector<$float_type, $d>), RngError> {
+                let factor = AnnealingSchedule::<$float_type>::noise_factor(parameters)?;
+                let (dpos, dvel) = self.get_random_contribution(rng, dt)?;
+                Ok((dpos, dvel * factor))
+            }
+
             fn calculate_increment(
                 &self,
                 force: SVector<$float_type, $d>,
@@ -459,6 +814,16 @@ macro_rules! define_langevin_nd(
             }
         }
 
+        impl cellular_raza_concepts::PositionLike<$float_type, $d> for $struct_name {
+            fn representative_point(&self) -> [$float_type; $d] {
+                self.pos.into()
+            }
+
+            fn bounding_box(&self) -> ([$float_type; $d], [$float_type; $d]) {
+                (self.pos.into(), self.pos.into())
+            }
+        }
+
         impl cellular_raza_concepts::Velocity<SVector<$float_type, $d>> for $struct_name {
             fn velocity(&self) -> SVector<$float_type, $d> {
                 self.vel
@@ -469,6 +834,18 @@ macro_rules! define_langevin_nd(
             }
         }
 
+        impl cellular_raza_concepts::KineticEnergy<SVector<$float_type, $d>, $float_type>
+            for $struct_name
+        {
+            fn kinetic_energy(&self) -> $float_type {
+                0.5 * self.mass * self.vel.norm_squared()
+            }
+
+            fn momentum(&self) -> SVector<$float_type, $d> {
+                self.mass * self.vel
+            }
+        }
+
         #[cfg(feature = "pyo3")]
         #[pymethods]
         #[cfg_attr(docsrs, doc(cfg(feature = "pyo3")))]
@@ -751,6 +1128,37 @@ impl<const D: usize> VertexMechanics2D<D> {
     pub fn set_cell_area(&mut self, cell_area: f64) {
         self.cell_area = cell_area;
     }
+
+    /// Creates a new vertex model in equilibrium from the given vertices.
+    ///
+    /// The vertices need to be ordered counter-clockwise.
+    /// Their current boundary lengths and area are used as the equilibrium values.
+    pub fn from_points(
+        points: SMatrix<f64, D, 2>,
+        spring_tensions: f64,
+        central_pressure: f64,
+        damping_constant: f64,
+        diffusion_constant: f64,
+    ) -> Self {
+        let cell_boundary_lengths = SVector::<f64, D>::from_iterator(
+            points
+                .row_iter()
+                .circular_tuple_windows()
+                .map(|(p1, p2)| (p1 - p2).norm()),
+        );
+        let mut model = VertexMechanics2D {
+            points,
+            velocity: SMatrix::zeros(),
+            cell_boundary_lengths,
+            spring_tensions: SVector::<f64, D>::from_element(spring_tensions),
+            cell_area: 0.0,
+            central_pressure,
+            damping_constant,
+            diffusion_constant,
+        };
+        model.cell_area = model.get_current_cell_area();
+        model
+    }
 }
 
 impl VertexMechanics2D<6> {
@@ -831,8 +1239,55 @@ impl VertexMechanics2D<6> {
     }
 }
 
+/// Center of mass and bounding box of positions which store one vertex per row
+pub(crate) fn vertices_center_and_bounding_box<F, R, S, const D: usize>(
+    vertices: &nalgebra::Matrix<F, R, nalgebra::Const<D>, S>,
+) -> ([F; D], ([F; D], [F; D]))
+where
+    F: num::Float + nalgebra::Scalar,
+    R: nalgebra::Dim,
+    S: nalgebra::RawStorage<F, R, nalgebra::Const<D>>,
+{
+    let mut center = [F::zero(); D];
+    let mut lower = [F::infinity(); D];
+    let mut upper = [F::neg_infinity(); D];
+    for row in vertices.row_iter() {
+        for i in 0..D {
+            center[i] = center[i] + row[i];
+            lower[i] = lower[i].min(row[i]);
+            upper[i] = upper[i].max(row[i]);
+        }
+    }
+    let n = F::from(vertices.nrows()).unwrap_or(F::one());
+    (center.map(|x| x / n), (lower, upper))
+}
+
 #[cfg(test)]
 mod test_vertex_mechanics_6n {
+    #[test]
+    fn test_position_like() {
+        use crate::VertexMechanics2D;
+        use cellular_raza_concepts::PositionLike;
+        use nalgebra::Vector2;
+        let models = VertexMechanics2D::<6>::fill_rectangle_flat_top(
+            36.0,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            [Vector2::from([0.0, 0.0]), Vector2::from([100.0, 100.0])],
+        );
+        assert!(!models.is_empty());
+        for model in models {
+            let center = model.representative_point();
+            let (lower, upper) = model.bounding_box();
+            for i in 0..2 {
+                assert!(lower[i] < center[i] && center[i] < upper[i]);
+                assert!(lower[i] >= 0.0 && upper[i] <= 100.0);
+            }
+        }
+    }
+
     #[test]
     fn test_fill_too_small() {
         use crate::VertexMechanics2D;
@@ -1064,6 +1519,16 @@ impl<const D: usize> cellular_raza_concepts::Position<nalgebra::SMatrix<f64, D,
     }
 }
 
+impl<const D: usize> cellular_raza_concepts::PositionLike<f64, 2> for VertexMechanics2D<D> {
+    fn representative_point(&self) -> [f64; 2] {
+        vertices_center_and_bounding_box(&self.points).0
+    }
+
+    fn bounding_box(&self) -> ([f64; 2], [f64; 2]) {
+        vertices_center_and_bounding_box(&self.points).1
+    }
+}
+
 impl<const D: usize> cellular_raza_concepts::Velocity<nalgebra::SMatrix<f64, D, 2>>
     for VertexMechanics2D<D>
 {
@@ -1075,3 +1540,65 @@ impl<const D: usize> cellular_raza_concepts::Velocity<nalgebra::SMatrix<f64, D,
         self.velocity = velocity.clone();
     }
 }
+
+#[cfg(test)]
+mod test_active_brownian {
+    use super::*;
+    use cellular_raza_concepts::{Orientation, Position, RotationalMechanics};
+    use rand::SeedableRng;
+
+    #[test]
+    fn torque_is_projected() {
+        let particle =
+            ActiveBrownianMechanics::<f64, 3>::new([0.0; 3], [1.0, 1.0, 0.0], 1.0, 0.0, 2.0, 0.5);
+        let (dang, dangvel) = particle
+            .calculate_rotational_increment([1.0, 2.0, 3.0].into())
+            .unwrap();
+        assert!(dang.dot(&particle.orientation).abs() < 1e-12);
+        assert_eq!(dangvel, SVector::<f64, 3>::zeros());
+        let expected = 4.0 * SVector::<f64, 3>::from([-0.5, 0.5, 3.0]);
+        assert!((dang - expected).norm() < 1e-12);
+    }
+
+    #[test]
+    fn persistent_random_walk() {
+        // Free active brownian particles in 2D decorrelate with exp(-D_r t) and travel
+        // v0 / D_r * (1 - exp(-D_r t)) along their initial orientation on average.
+        let dt = 0.01;
+        let n_steps = 100;
+        let n_particles = 2000;
+        let rotational_diffusion_constant = 0.5;
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(1);
+        let mut correlation = 0.0;
+        let mut displacement = 0.0;
+        for _ in 0..n_particles {
+            let mut particle = ActiveBrownianMechanics::<f64, 2>::new(
+                [0.0; 2],
+                [1.0, 0.0],
+                1.0,
+                0.0,
+                rotational_diffusion_constant,
+                1.0,
+            );
+            for _ in 0..n_steps {
+                let (dx, _) = particle.calculate_increment(SVector::zeros()).unwrap();
+                let (dx_rand, _) = particle.get_random_contribution(&mut rng, dt).unwrap();
+                let (dang, _) = particle
+                    .calculate_rotational_increment(SVector::zeros())
+                    .unwrap();
+                let (dang_rand, _) = particle
+                    .get_random_rotational_contribution(&mut rng, dt)
+                    .unwrap();
+                particle.set_pos(&(particle.pos + (dx + dx_rand) * dt));
+                particle.set_orientation(&(particle.orientation + (dang + dang_rand) * dt));
+                assert!((particle.orientation.norm() - 1.0).abs() < 1e-12);
+            }
+            correlation += particle.orientation[0] / n_particles as f64;
+            displacement += particle.pos[0] / n_particles as f64;
+        }
+        let t = dt * n_steps as f64;
+        let decay = (-rotational_diffusion_constant * t).exp();
+        assert!((correlation - decay).abs() < 0.05);
+        assert!((displacement - (1.0 - decay) / rotational_diffusion_constant).abs() < 0.05);
+    }
+}
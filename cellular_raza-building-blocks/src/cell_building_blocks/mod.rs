@@ -1,9 +1,35 @@
+mod ageing;
 mod bacterial_rods;
 mod cycle;
+mod division;
+mod first_passage;
+mod gay_berne;
 mod interaction;
+mod junctions;
+mod juxtacrine;
 mod mechanics;
+mod pressure;
+mod protrusions;
+mod receptors;
+mod remodeling;
+mod tessellation;
+mod time_dependent;
+mod topological;
 
+pub use ageing::*;
 pub use bacterial_rods::*;
 pub use cycle::*;
+pub use division::*;
+pub use first_passage::*;
+pub use gay_berne::*;
 pub use interaction::*;
+pub use junctions::*;
+pub use juxtacrine::*;
 pub use mechanics::*;
+pub use pressure::*;
+pub use protrusions::*;
+pub use receptors::*;
+pub use remodeling::*;
+pub use tessellation::*;
+pub use time_dependent::*;
+pub use topological::*;
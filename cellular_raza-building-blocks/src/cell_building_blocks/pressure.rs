@@ -0,0 +1,195 @@
+use cellular_raza_concepts::*;
+use nalgebra::SVector;
+
+use serde::{Deserialize, Serialize};
+
+/// Records the mechanical pressure which neighboring cells exert on a cell.
+///
+/// The backend sums the normal components
+/// \\begin{equation}
+///     p = \sum\limits_j \vec{F}_{ij}\cdot\frac{\vec{x}_i-\vec{x}_j}{|\vec{x}_i-\vec{x}_j|}
+/// \\end{equation}
+/// of all forces $\vec{F}_{ij}$ which the wrapped interaction calculates for the cell at
+/// $\vec{x}_i$ and its neighbors at $\vec{x}_j$.
+/// Repulsive forces compress the cell and increase the pressure while attractive forces
+/// decrease it.
+/// The result is stored in the `pressure` field after every step such that it is saved
+/// together with the cell and can be used by other aspects such as the
+/// [Cycle] or [Reactions] traits to model stress-dependent growth.
+/// To obtain a pressure in the physical sense, divide this value by the surface of the cell.
+///
+/// ```
+/// # use cellular_raza_building_blocks::*;
+/// # use cellular_raza_concepts::*;
+/// # use nalgebra::Vector2;
+/// let mut interaction = PressureSensing::new(MorsePotential {
+///     radius: 1.0,
+///     potential_stiffness: 0.5,
+///     cutoff: 3.0,
+///     strength: 1.0,
+/// });
+/// let own_pos = Vector2::from([0.0, 0.0]);
+/// let ext_pos = Vector2::from([1.0, 0.0]);
+/// let (force, _) = interaction.calculate_force_between(
+///     &own_pos,
+///     &Vector2::zeros(),
+///     &ext_pos,
+///     &Vector2::zeros(),
+///     &1.0,
+/// )?;
+/// // Overlapping cells push each other apart
+/// let normal_force = interaction.normal_force(&own_pos, &ext_pos, &force)?;
+/// assert!(normal_force > 0.0);
+/// // The backend sums the normal forces and passes them to the cell after every step
+/// type Vec2 = Vector2<f64>;
+/// Interaction::<Vec2, Vec2, Vec2, f64>::react_to_pressure(&mut interaction, normal_force)?;
+/// assert_eq!(interaction.pressure, normal_force);
+/// # Ok::<(), CalcError>(())
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PressureSensing<I> {
+    /// Interaction which calculates the forces between cells
+    pub interaction: I,
+    /// Pressure which was measured in the last step
+    pub pressure: f64,
+}
+
+impl<I> PressureSensing<I> {
+    /// Wraps the given interaction with zero initial pressure
+    pub fn new(interaction: I) -> Self {
+        Self {
+            interaction,
+            pressure: 0.0,
+        }
+    }
+}
+
+impl<I, Vel, Inf, Par, const D: usize> Interaction<SVector<f64, D>, Vel, SVector<f64, D>, Inf, Par>
+    for PressureSensing<I>
+where
+    I: Interaction<SVector<f64, D>, Vel, SVector<f64, D>, Inf, Par>,
+{
+    fn get_interaction_information(&self) -> Inf {
+        self.interaction.get_interaction_information()
+    }
+
+    fn calculate_force_between(
+        &self,
+        own_pos: &SVector<f64, D>,
+        own_vel: &Vel,
+        ext_pos: &SVector<f64, D>,
+        ext_vel: &Vel,
+        ext_info: &Inf,
+    ) -> Result<(SVector<f64, D>, SVector<f64, D>), CalcError> {
+        self.interaction
+            .calculate_force_between(own_pos, own_vel, ext_pos, ext_vel, ext_info)
+    }
+
+    fn calculate_force_between_with_parameters(
+        &self,
+        own_pos: &SVector<f64, D>,
+        own_vel: &Vel,
+        ext_pos: &SVector<f64, D>,
+        ext_vel: &Vel,
+        ext_info: &Inf,
+        parameters: &Par,
+    ) -> Result<(SVector<f64, D>, SVector<f64, D>), CalcError> {
+        self.interaction.calculate_force_between_with_parameters(
+            own_pos, own_vel, ext_pos, ext_vel, ext_info, parameters,
+        )
+    }
+
+    fn is_neighbor(
+        &self,
+        own_pos: &SVector<f64, D>,
+        ext_pos: &SVector<f64, D>,
+        ext_inf: &Inf,
+    ) -> Result<bool, CalcError> {
+        self.interaction.is_neighbor(own_pos, ext_pos, ext_inf)
+    }
+
+    fn react_to_neighbors(&mut self, neighbors: usize) -> Result<(), CalcError> {
+        self.interaction.react_to_neighbors(neighbors)
+    }
+
+    fn reacts_to_neighbors(&self) -> bool {
+        self.interaction.reacts_to_neighbors()
+    }
+
+    fn normal_force(
+        &self,
+        own_pos: &SVector<f64, D>,
+        ext_pos: &SVector<f64, D>,
+        force: &SVector<f64, D>,
+    ) -> Result<f64, CalcError> {
+        Ok(super::calculate_normal_force(own_pos, ext_pos, force))
+    }
+
+    fn react_to_pressure(&mut self, pressure: f64) -> Result<(), CalcError> {
+        self.pressure = pressure;
+        self.interaction.react_to_pressure(pressure)
+    }
+
+    fn potential_energy_between(
+        &self,
+        own_pos: &SVector<f64, D>,
+        ext_pos: &SVector<f64, D>,
+        ext_inf: &Inf,
+    ) -> Result<Option<f64>, CalcError> {
+        self.interaction
+            .potential_energy_between(own_pos, ext_pos, ext_inf)
+    }
+
+    fn potential_energy_between_with_parameters(
+        &self,
+        own_pos: &SVector<f64, D>,
+        ext_pos: &SVector<f64, D>,
+        ext_inf: &Inf,
+        parameters: &Par,
+    ) -> Result<Option<f64>, CalcError> {
+        self.interaction
+            .potential_energy_between_with_parameters(own_pos, ext_pos, ext_inf, parameters)
+    }
+}
+
+#[cfg(test)]
+mod test_pressure {
+    use super::*;
+    use crate::MorsePotential;
+    use nalgebra::Vector2;
+
+    #[test]
+    fn pressure_increases_with_compression() -> Result<(), CalcError> {
+        // A cell surrounded by neighbors at decreasing distances
+        let interaction = PressureSensing::new(MorsePotential {
+            radius: 1.0,
+            potential_stiffness: 0.5,
+            cutoff: 3.0,
+            strength: 1.0,
+        });
+        let own_pos = Vector2::zeros();
+        let pressure = |distance: f64| -> Result<f64, CalcError> {
+            let mut pressure = 0.0;
+            for n in 0..6 {
+                let angle = n as f64 * std::f64::consts::PI / 3.0;
+                let ext_pos = distance * Vector2::from([angle.cos(), angle.sin()]);
+                let (force, _) = interaction.calculate_force_between(
+                    &own_pos,
+                    &Vector2::zeros(),
+                    &ext_pos,
+                    &Vector2::zeros(),
+                    &1.0,
+                )?;
+                // Forces of symmetrically placed neighbors cancel but their pressure does not
+                pressure += interaction.normal_force(&own_pos, &ext_pos, &force)?;
+            }
+            Ok(pressure)
+        };
+        assert!(pressure(1.5)? > 0.0);
+        assert!(pressure(1.0)? > pressure(1.5)?);
+        assert!(pressure(0.5)? > pressure(1.0)?);
+        // Neighbors beyond twice the radius attract the cell
+        assert!(pressure(2.5)? < 0.0);
+        Ok(())
+    }
+}
@@ -0,0 +1,342 @@
+use cellular_raza_concepts::*;
+
+use nalgebra::SVector;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Rectangular region of the substrate with a modified attachment probability.
+///
+/// Used by [ProtrusionMechanics] to describe patterned substrates.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(bound(
+    serialize = "F: nalgebra::Scalar + Serialize",
+    deserialize = "F: nalgebra::Scalar + Deserialize<'de>"
+))]
+pub struct SubstrateRegion<F, const D: usize> {
+    /// Lower corner of the region
+    pub min: SVector<F, D>,
+    /// Upper corner of the region
+    pub max: SVector<F, D>,
+    /// Probability that a protrusion whose tip lies in this region attaches to the substrate
+    pub attachment_probability: F,
+}
+
+impl<F, const D: usize> SubstrateRegion<F, D>
+where
+    F: nalgebra::Scalar + PartialOrd,
+{
+    /// Checks if the point lies inside the region including its boundary
+    pub fn contains(&self, point: &SVector<F, D>) -> bool {
+        (0..D).all(|i| self.min[i] <= point[i] && point[i] <= self.max[i])
+    }
+}
+
+/// Protrusion which is attached to the substrate and pulls the cell towards its anchor.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(bound(
+    serialize = "F: nalgebra::Scalar + Serialize",
+    deserialize = "F: nalgebra::Scalar + Deserialize<'de>"
+))]
+pub struct Protrusion<F, const D: usize> {
+    /// Point at which the protrusion is attached to the substrate
+    pub anchor: SVector<F, D>,
+    /// Time until the protrusion retracts
+    pub remaining_lifetime: F,
+}
+
+/// Transient, randomly oriented protrusions such as filopodia which pull the cell along.
+///
+/// # Parameters & Variables
+/// | Symbol | Struct Field | Description |
+/// |:---:| --- | --- |
+/// | $\lambda$ | `formation_rate` | Rate at which new protrusions are extended |
+/// | $\tau$ | `mean_lifetime` | Mean lifetime of attached protrusions |
+/// | $l$ | `protrusion_length` | Length of newly extended protrusions |
+/// | $f_p$ | `protrusion_strength` | Magnitude of the pulling force of a single protrusion |
+/// | $p_0$ | `attachment_probability` | Attachment probability outside of all substrate regions |
+/// | | `substrate_regions` | Regions of the substrate with modified attachment probability |
+/// | | | |
+/// | $\vec{a}_i$ | `protrusions` | Anchors of all attached protrusions |
+///
+/// # Equations
+/// Within a time step $\Delta t$, a new protrusion is extended with probability
+/// $1-e^{-\lambda\Delta t}$ along a uniformly distributed direction $\hat{n}$.
+/// Its tip $\vec{x}+l\hat{n}$ attaches to the substrate with the probability $p$ of the first
+/// [SubstrateRegion] containing it or $p_0$ if there is none.
+/// Unattached protrusions are retracted immediately while attached ones persist for an
+/// exponentially distributed lifetime with mean $\tau$.
+/// Every attached protrusion pulls the cell towards its anchor
+/// \\begin{equation}
+///     \vec{F}_p = f_p\sum_i\frac{\vec{a}_i - \vec{x}}{|\vec{a}_i - \vec{x}|}
+/// \\end{equation}
+/// which is added to the force passed to the wrapped [Mechanics] implementation.
+/// Protrusions also retract once the cell has reached their anchor.
+///
+/// The protrusions are updated by
+/// [update_protrusions](ProtrusionMechanics::update_protrusions) which needs to be called
+/// once every time step, for example from the [Cycle] implementation of the agent.
+/// ```
+/// # use cellular_raza_building_blocks::*;
+/// # use cellular_raza_concepts::*;
+/// # use nalgebra::Vector2;
+/// # use rand::SeedableRng;
+/// let mut mechanics = ProtrusionMechanics {
+///     mechanics: Brownian2D::new([0.0; 2], 0.0, 1.0),
+///     formation_rate: 1e6,
+///     mean_lifetime: 5.0,
+///     protrusion_length: 2.0,
+///     protrusion_strength: 1.5,
+///     attachment_probability: 0.0,
+///     substrate_regions: vec![SubstrateRegion {
+///         min: Vector2::from([1.0, -3.0]),
+///         max: Vector2::from([3.0, 3.0]),
+///         attachment_probability: 1.0,
+///     }],
+///     protrusions: Vec::new(),
+/// };
+/// let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(0);
+/// while mechanics.protrusions.is_empty() {
+///     mechanics.update_protrusions(&mut rng, 0.1)?;
+/// }
+/// // Protrusions can only attach in the region to the right of the cell
+/// let force = mechanics.protrusion_force();
+/// assert!(force[0] > 0.0);
+/// assert!((force.norm() - 1.5).abs() < 1e-10);
+/// # Ok::<(), RngError>(())
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(bound(
+    serialize = "F: nalgebra::Scalar + Serialize, Mec: Serialize",
+    deserialize = "F: nalgebra::Scalar + Deserialize<'de>, Mec: Deserialize<'de>"
+))]
+pub struct ProtrusionMechanics<Mec, F, const D: usize> {
+    /// Mechanics which are driven by the protrusions
+    pub mechanics: Mec,
+    /// Rate $\lambda$ at which new protrusions are extended
+    pub formation_rate: F,
+    /// Mean lifetime $\tau$ of attached protrusions
+    pub mean_lifetime: F,
+    /// Length $l$ of newly extended protrusions
+    pub protrusion_length: F,
+    /// Magnitude $f_p$ of the pulling force of a single protrusion
+    pub protrusion_strength: F,
+    /// Attachment probability $p_0$ outside of all substrate regions
+    pub attachment_probability: F,
+    /// Regions of the substrate with modified attachment probability
+    pub substrate_regions: Vec<SubstrateRegion<F, D>>,
+    /// Currently attached protrusions
+    pub protrusions: Vec<Protrusion<F, D>>,
+}
+
+impl<Mec, F, const D: usize> ProtrusionMechanics<Mec, F, D>
+where
+    Mec: Position<SVector<F, D>>,
+    F: nalgebra::RealField + Copy,
+{
+    /// Probability that a protrusion attaches at the given point of the substrate
+    pub fn attachment_probability_at(&self, point: &SVector<F, D>) -> F {
+        self.substrate_regions
+            .iter()
+            .find(|region| region.contains(point))
+            .map_or(self.attachment_probability, |region| {
+                region.attachment_probability
+            })
+    }
+
+    /// Sum of the pulling forces of all attached protrusions
+    pub fn protrusion_force(&self) -> SVector<F, D> {
+        let pos = self.mechanics.pos();
+        self.protrusions
+            .iter()
+            .filter_map(|protrusion| (protrusion.anchor - pos).try_normalize(F::zero()))
+            .fold(SVector::zeros(), |acc, dir| {
+                acc + dir * self.protrusion_strength
+            })
+    }
+
+    /// Advances the lifetime of all protrusions by `dt`, retracts expired ones and extends
+    /// new ones.
+    pub fn update_protrusions<R>(&mut self, rng: &mut R, dt: F) -> Result<(), RngError>
+    where
+        R: Rng,
+        F: num::Float,
+        rand_distr::Standard: rand_distr::Distribution<F>,
+        rand_distr::StandardNormal: rand_distr::Distribution<F>,
+        rand_distr::Exp1: rand_distr::Distribution<F>,
+    {
+        let pos = self.mechanics.pos();
+        let length = self.protrusion_length;
+        self.protrusions.retain_mut(|protrusion| {
+            protrusion.remaining_lifetime -= dt;
+            protrusion.remaining_lifetime > F::zero()
+                && (protrusion.anchor - pos).norm() > length * F::default_epsilon()
+        });
+
+        let p_extend = F::one() - num::Float::exp(-self.formation_rate * dt);
+        if rng.gen::<F>() >= p_extend {
+            return Ok(());
+        }
+        let direction = SVector::<F, D>::from_distribution(&rand_distr::StandardNormal, rng)
+            .try_normalize(F::zero())
+            .ok_or(RngError(
+                "could not sample direction of new protrusion".to_owned(),
+            ))?;
+        let anchor = pos + direction * length;
+        if rng.gen::<F>() < self.attachment_probability_at(&anchor) {
+            let lifetime: F = rng.sample(rand_distr::Exp1);
+            self.protrusions.push(Protrusion {
+                anchor,
+                remaining_lifetime: lifetime * self.mean_lifetime,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl<Mec, F, const D: usize> Mechanics<SVector<F, D>, SVector<F, D>, SVector<F, D>, F>
+    for ProtrusionMechanics<Mec, F, D>
+where
+    Mec: Mechanics<SVector<F, D>, SVector<F, D>, SVector<F, D>, F>,
+    Mec: Position<SVector<F, D>>,
+    F: nalgebra::RealField + Copy,
+{
+    fn get_random_contribution(
+        &self,
+        rng: &mut rand_chacha::ChaCha8Rng,
+        dt: F,
+    ) -> Result<(SVector<F, D>, SVector<F, D>), RngError> {
+        self.mechanics.get_random_contribution(rng, dt)
+    }
+
+    fn get_random_contribution_with_parameters(
+        &self,
+        rng: &mut rand_chacha::ChaCha8Rng,
+        dt: F,
+        parameters: &GlobalParameters,
+    ) -> Result<(SVector<F, D>, SVector<F, D>), RngError> {
+        self.mechanics
+            .get_random_contribution_with_parameters(rng, dt, parameters)
+    }
+
+    fn calculate_increment(
+        &self,
+        force: SVector<F, D>,
+    ) -> Result<(SVector<F, D>, SVector<F, D>), CalcError> {
+        self.mechanics
+            .calculate_increment(force + self.protrusion_force())
+    }
+}
+
+impl<Mec, Pos, F, const D: usize> Position<Pos> for ProtrusionMechanics<Mec, F, D>
+where
+    Mec: Position<Pos>,
+{
+    fn pos(&self) -> Pos {
+        self.mechanics.pos()
+    }
+
+    fn set_pos(&mut self, pos: &Pos) {
+        self.mechanics.set_pos(pos)
+    }
+}
+
+impl<Mec, Vel, F, const D: usize> Velocity<Vel> for ProtrusionMechanics<Mec, F, D>
+where
+    Mec: Velocity<Vel>,
+{
+    fn velocity(&self) -> Vel {
+        self.mechanics.velocity()
+    }
+
+    fn set_velocity(&mut self, velocity: &Vel) {
+        self.mechanics.set_velocity(velocity)
+    }
+}
+
+impl<Mec, F, const D: usize> PositionLike<F, D> for ProtrusionMechanics<Mec, F, D>
+where
+    Mec: PositionLike<F, D>,
+{
+    fn representative_point(&self) -> [F; D] {
+        self.mechanics.representative_point()
+    }
+
+    fn bounding_box(&self) -> ([F; D], [F; D]) {
+        self.mechanics.bounding_box()
+    }
+}
+
+#[cfg(test)]
+mod test_protrusions {
+    use super::*;
+    use crate::Brownian2D;
+    use nalgebra::Vector2;
+    use rand::SeedableRng;
+
+    fn mechanics(attachment_probability: f64) -> ProtrusionMechanics<Brownian2D, f64, 2> {
+        ProtrusionMechanics {
+            mechanics: Brownian2D::new([0.0; 2], 1.0, 1.0),
+            formation_rate: 2.0,
+            mean_lifetime: 0.5,
+            protrusion_length: 1.0,
+            protrusion_strength: 1.0,
+            attachment_probability,
+            substrate_regions: Vec::new(),
+            protrusions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn no_attachment_no_force() {
+        let mut mechanics = mechanics(0.0);
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(0);
+        for _ in 0..1000 {
+            mechanics.update_protrusions(&mut rng, 0.1).unwrap();
+            assert!(mechanics.protrusions.is_empty());
+        }
+        let (dx, _) = mechanics.calculate_increment(Vector2::zeros()).unwrap();
+        assert_eq!(dx, Vector2::zeros());
+    }
+
+    #[test]
+    fn mean_number_of_protrusions() {
+        // In steady state, the number of protrusions is Poisson distributed with mean
+        // formation_rate * attachment_probability * mean_lifetime
+        let mut mechanics = mechanics(0.5);
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(1);
+        let dt = 0.01;
+        let n_steps = 200_000;
+        let mut mean = 0.0;
+        for _ in 0..n_steps {
+            mechanics.update_protrusions(&mut rng, dt).unwrap();
+            mean += mechanics.protrusions.len() as f64 / n_steps as f64;
+        }
+        assert!((mean - 0.5).abs() < 0.05);
+    }
+
+    #[test]
+    fn substrate_regions_bias_migration() {
+        let mut mechanics = mechanics(0.0);
+        mechanics.formation_rate = 10.0;
+        mechanics.substrate_regions.push(SubstrateRegion {
+            min: Vector2::from([-5.0, 0.5]),
+            max: Vector2::from([5.0, 5.0]),
+            attachment_probability: 1.0,
+        });
+        assert_eq!(
+            mechanics.attachment_probability_at(&Vector2::from([0.0, 1.0])),
+            1.0
+        );
+        assert_eq!(
+            mechanics.attachment_probability_at(&Vector2::from([0.0, -1.0])),
+            0.0
+        );
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
+        for _ in 0..1000 {
+            mechanics.update_protrusions(&mut rng, 0.01).unwrap();
+            assert!(mechanics.protrusion_force()[1] >= 0.0);
+        }
+        let (dx, _) = mechanics.calculate_increment(Vector2::zeros()).unwrap();
+        assert_eq!(dx, mechanics.protrusion_force());
+    }
+}
@@ -0,0 +1,287 @@
+use cellular_raza_concepts::*;
+use nalgebra::SVector;
+
+use serde::{Deserialize, Serialize};
+
+/// Kinetics of a surface receptor which binds an extracellular ligand.
+///
+/// Every cell carries pools of free receptors $R$, ligand-bound receptors $C$ and internalized
+/// receptors $R_i$ which are stored in its intracellular vector at the given indices.
+/// Receptors bind the ligand with extracellular concentration $L$ at the position of the cell
+/// and the resulting complexes are internalized.
+/// Internalized receptors release their ligand into the cell and are recycled to the surface.
+/// \\begin{align}
+///     J_b &= k_\text{on}LR - k_\text{off}C\\\\
+///     \dot{R} &= -J_b + k_\text{rec}R_i\\\\
+///     \dot{C} &= J_b - k_\text{int}C\\\\
+///     \dot{R}_i &= k_\text{int}C - k_\text{rec}R_i\\\\
+///     \dot{L}_i &= k_\text{int}C
+/// \\end{align}
+/// The ligand $L_i$ which was taken up by the cell is added to the optional
+/// `internalized_ligand` species and can be used by an intracellular reaction network.
+/// The extracellular increment $-J_b$ is an amount per time and needs to be converted to a
+/// concentration by the
+/// [SubDomainReactions::treat_increments](cellular_raza_concepts::SubDomainReactions) method,
+/// for example by dividing it by the volume of the voxel containing the cell.
+/// The total number of receptors $R+C+R_i$ is conserved.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ReceptorLigandKinetics {
+    /// Index of the ligand in the extracellular vector
+    pub ligand: usize,
+    /// Index of free surface receptors $R$ in the intracellular vector
+    pub free_receptors: usize,
+    /// Index of ligand-bound surface receptors $C$ in the intracellular vector
+    pub bound_receptors: usize,
+    /// Index of internalized receptors $R_i$ in the intracellular vector
+    pub internalized_receptors: usize,
+    /// Index of ligand which was internalized by the cell in the intracellular vector
+    pub internalized_ligand: Option<usize>,
+    /// Binding rate $k_\text{on}$
+    pub binding_rate: f64,
+    /// Unbinding rate $k_\text{off}$
+    pub unbinding_rate: f64,
+    /// Internalization rate $k_\text{int}$ of bound receptors
+    pub internalization_rate: f64,
+    /// Recycling rate $k_\text{rec}$ of internalized receptors
+    pub recycling_rate: f64,
+}
+
+impl ReceptorLigandKinetics {
+    /// Calculates the increments of the intracellular and extracellular vectors.
+    pub fn calculate_increment<const NI: usize, const NE: usize>(
+        &self,
+        intracellular: &SVector<f64, NI>,
+        extracellular: &SVector<f64, NE>,
+    ) -> Result<(SVector<f64, NI>, SVector<f64, NE>), CalcError> {
+        let index_error = |name: &str, index: usize, n: usize| {
+            CalcError(format!(
+                "index {index} of {name} exceeds the length {n} of the concentration vector"
+            ))
+        };
+        let intracellular_indices = [
+            ("free receptors", self.free_receptors),
+            ("bound receptors", self.bound_receptors),
+            ("internalized receptors", self.internalized_receptors),
+        ]
+        .into_iter()
+        .chain(self.internalized_ligand.map(|i| ("internalized ligand", i)));
+        for (name, index) in intracellular_indices {
+            if index >= NI {
+                return Err(index_error(name, index, NI));
+            }
+        }
+        if self.ligand >= NE {
+            return Err(index_error("ligand", self.ligand, NE));
+        }
+
+        let ligand = extracellular[self.ligand];
+        let free = intracellular[self.free_receptors];
+        let bound = intracellular[self.bound_receptors];
+        let internalized = intracellular[self.internalized_receptors];
+
+        let binding = self.binding_rate * ligand * free - self.unbinding_rate * bound;
+        let internalization = self.internalization_rate * bound;
+        let recycling = self.recycling_rate * internalized;
+
+        let mut dintra = SVector::<f64, NI>::zeros();
+        dintra[self.free_receptors] += recycling - binding;
+        dintra[self.bound_receptors] += binding - internalization;
+        dintra[self.internalized_receptors] += internalization - recycling;
+        if let Some(index) = self.internalized_ligand {
+            dintra[index] += internalization;
+        }
+        let mut dextra = SVector::<f64, NE>::zeros();
+        dextra[self.ligand] = -binding;
+        Ok((dintra, dextra))
+    }
+}
+
+/// Couples an intracellular reaction network to an extracellular ligand via surface receptors.
+///
+/// The [Intracellular], [Reactions] and [ReactionsContact] traits are forwarded to the wrapped
+/// reactions while the [ReactionsExtra] trait is implemented by the
+/// [ReceptorLigandKinetics].
+/// Thus the simulation needs to include the `Reactions` and `ReactionsExtra` aspects such that
+/// both increments are combined by the backend.
+///
+/// ```
+/// # use cellular_raza_building_blocks::*;
+/// # use cellular_raza_concepts::*;
+/// # use nalgebra::{Vector1, Vector4};
+/// // Intracellular vector: free, bound and internalized receptors and internalized ligand
+/// struct Degradation {
+///     intracellular: Vector4<f64>,
+/// }
+/// # impl Intracellular<Vector4<f64>> for Degradation {
+/// #     fn set_intracellular(&mut self, intracellular: Vector4<f64>) {
+/// #         self.intracellular = intracellular;
+/// #     }
+/// #     fn get_intracellular(&self) -> Vector4<f64> {
+/// #         self.intracellular
+/// #     }
+/// # }
+/// impl Reactions<Vector4<f64>> for Degradation {
+///     fn calculate_intracellular_increment(
+///         &self,
+///         intracellular: &Vector4<f64>,
+///     ) -> Result<Vector4<f64>, CalcError> {
+///         Ok(Vector4::from([0.0, 0.0, 0.0, -0.1 * intracellular[3]]))
+///     }
+/// }
+///
+/// let cell = ReceptorLigand {
+///     reactions: Degradation {
+///         intracellular: Vector4::from([100.0, 0.0, 0.0, 0.0]),
+///     },
+///     kinetics: ReceptorLigandKinetics {
+///         ligand: 0,
+///         free_receptors: 0,
+///         bound_receptors: 1,
+///         internalized_receptors: 2,
+///         internalized_ligand: Some(3),
+///         binding_rate: 0.01,
+///         unbinding_rate: 0.1,
+///         internalization_rate: 0.05,
+///         recycling_rate: 0.02,
+///     },
+/// };
+/// let (dintra, dextra) = cell.calculate_combined_increment(
+///     &cell.get_intracellular(),
+///     &Vector1::from([2.0]),
+/// )?;
+/// // Ligand is removed from the extracellular space and bound by receptors
+/// assert_eq!(dextra[0], -2.0);
+/// assert_eq!(dintra[1], 2.0);
+/// assert_eq!(dintra.rows(0, 3).sum(), 0.0);
+/// # Ok::<(), CalcError>(())
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ReceptorLigand<R> {
+    /// Intracellular reactions which also store the receptor pools
+    pub reactions: R,
+    /// Binding, internalization and recycling of receptors
+    pub kinetics: ReceptorLigandKinetics,
+}
+
+impl<Ri, R> Intracellular<Ri> for ReceptorLigand<R>
+where
+    R: Intracellular<Ri>,
+{
+    fn set_intracellular(&mut self, intracellular: Ri) {
+        self.reactions.set_intracellular(intracellular)
+    }
+
+    fn get_intracellular(&self) -> Ri {
+        self.reactions.get_intracellular()
+    }
+}
+
+impl<Ri, Params, R> Reactions<Ri, Params> for ReceptorLigand<R>
+where
+    R: Reactions<Ri, Params>,
+{
+    fn calculate_intracellular_increment(&self, intracellular: &Ri) -> Result<Ri, CalcError> {
+        self.reactions
+            .calculate_intracellular_increment(intracellular)
+    }
+
+    fn calculate_intracellular_increment_with_parameters(
+        &self,
+        intracellular: &Ri,
+        parameters: &Params,
+    ) -> Result<Ri, CalcError> {
+        self.reactions
+            .calculate_intracellular_increment_with_parameters(intracellular, parameters)
+    }
+}
+
+impl<R, const NI: usize, const NE: usize> ReactionsExtra<SVector<f64, NI>, SVector<f64, NE>>
+    for ReceptorLigand<R>
+{
+    fn calculate_combined_increment(
+        &self,
+        intracellular: &SVector<f64, NI>,
+        extracellular: &SVector<f64, NE>,
+    ) -> Result<(SVector<f64, NI>, SVector<f64, NE>), CalcError> {
+        self.kinetics
+            .calculate_increment(intracellular, extracellular)
+    }
+}
+
+impl<Ri, Pos, Float, RInf, R> ReactionsContact<Ri, Pos, Float, RInf> for ReceptorLigand<R>
+where
+    R: ReactionsContact<Ri, Pos, Float, RInf>,
+{
+    fn get_contact_information(&self) -> RInf {
+        self.reactions.get_contact_information()
+    }
+
+    fn calculate_contact_increment(
+        &self,
+        own_intracellular: &Ri,
+        ext_intracellular: &Ri,
+        own_pos: &Pos,
+        ext_pos: &Pos,
+        rinf: &RInf,
+    ) -> Result<(Ri, Ri), CalcError> {
+        self.reactions.calculate_contact_increment(
+            own_intracellular,
+            ext_intracellular,
+            own_pos,
+            ext_pos,
+            rinf,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test_receptors {
+    use super::*;
+
+    fn kinetics() -> ReceptorLigandKinetics {
+        ReceptorLigandKinetics {
+            ligand: 1,
+            free_receptors: 0,
+            bound_receptors: 1,
+            internalized_receptors: 2,
+            internalized_ligand: Some(3),
+            binding_rate: 0.2,
+            unbinding_rate: 0.1,
+            internalization_rate: 0.05,
+            recycling_rate: 0.02,
+        }
+    }
+
+    #[test]
+    fn conserves_receptors_and_ligand() -> Result<(), CalcError> {
+        // A single cell in a well-mixed voxel of unit volume
+        let kinetics = kinetics();
+        let mut intra = SVector::<f64, 4>::from([10.0, 0.0, 0.0, 0.0]);
+        let mut extra = SVector::<f64, 2>::from([3.0, 5.0]);
+        let dt = 0.01;
+        for _ in 0..20_000 {
+            let (dintra, dextra) = kinetics.calculate_increment(&intra, &extra)?;
+            intra += dt * dintra;
+            extra += dt * dextra;
+        }
+        // Receptors are conserved and other extracellular species are not touched
+        assert!((intra.rows(0, 3).sum() - 10.0).abs() < 1e-9);
+        assert_eq!(extra[0], 3.0);
+        // Ligand is either in the extracellular space, bound or taken up by the cell
+        assert!((extra[1] + intra[1] + intra[3] - 5.0).abs() < 1e-9);
+        // Receptors continuously internalize the ligand
+        assert!(extra[1] < 0.1);
+        assert!(intra[3] > 4.5);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_index() {
+        let kinetics = ReceptorLigandKinetics {
+            internalized_ligand: Some(4),
+            ..kinetics()
+        };
+        let result = kinetics.calculate_increment(&SVector::<f64, 4>::zeros(), &[0.0; 2].into());
+        assert!(result.is_err());
+    }
+}
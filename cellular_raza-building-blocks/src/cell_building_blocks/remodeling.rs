@@ -0,0 +1,143 @@
+use cellular_raza_concepts::*;
+use nalgebra::{SMatrix, SVector};
+
+use serde::{Deserialize, Serialize};
+
+use crate::FiberVoxel;
+
+/// Degradation, deposition and alignment of extracellular matrix fibers by cells.
+///
+/// Cells secrete proteases which degrade the fibers of the voxel in which they are located while
+/// simultaneously depositing new fibers.
+/// Furthermore, cells exert traction along their polarity $\vec{p}$ and thus align the fibers
+/// along this direction.
+/// The increments of the fiber density $\rho$ and orientation tensor $Q$ (see [FiberVoxel])
+/// are given by
+/// \\begin{align}
+///     \dot{\rho} &= k_\text{dep} - k_\text{deg}\rho\\\\
+///     \dot{Q} &= k_\text{al}\left(\hat{p}\otimes\hat{p} - Q\right)
+/// \\end{align}
+/// where $\hat{p}=\vec{p}/|\vec{p}|$.
+/// Cells with zero polarity do not align fibers.
+/// The [ReactionsExtra] trait does not provide the velocity of the cell.
+/// Thus the polarity needs to be updated by the user, for example by other aspects of the cell.
+/// The increments of all cells in one voxel are summed up by the
+/// [FiberSubDomain](crate::FiberSubDomain).
+/// In order for $Q$ to remain a valid orientation tensor, the sum of all alignment rates
+/// multiplied by the time increment must not exceed 1.
+///
+/// ```
+/// # use cellular_raza_building_blocks::*;
+/// # use cellular_raza_concepts::*;
+/// # use nalgebra::{Vector1, Vector2};
+/// let remodeling = MatrixRemodeling {
+///     polarity: Vector2::from([0.0, 2.0]),
+///     degradation_rate: 0.1,
+///     deposition_rate: 0.05,
+///     alignment_rate: 0.2,
+/// };
+/// let fibers = FiberVoxel::isotropic(1.0);
+/// let (_, dfibers) = remodeling.calculate_combined_increment(&Vector1::from([0.0]), &fibers)?;
+/// assert!((dfibers.density + 0.05).abs() < 1e-12);
+/// // Fibers are aligned along the polarity of the cell
+/// assert!(dfibers.orientation[(1, 1)] > 0.0);
+/// assert!(dfibers.orientation[(0, 0)] < 0.0);
+/// # Ok::<(), CalcError>(())
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct MatrixRemodeling<const D: usize> {
+    /// Polarity $\vec{p}$ along which the cell aligns fibers
+    pub polarity: SVector<f64, D>,
+    /// Degradation rate $k_\text{deg}$ of fibers
+    pub degradation_rate: f64,
+    /// Deposition rate $k_\text{dep}$ of new fibers
+    pub deposition_rate: f64,
+    /// Alignment rate $k_\text{al}$ of fibers along the polarity
+    pub alignment_rate: f64,
+}
+
+impl<Ri, const D: usize> ReactionsExtra<Ri, FiberVoxel<D>> for MatrixRemodeling<D>
+where
+    Ri: Xapy<f64>,
+{
+    fn calculate_combined_increment(
+        &self,
+        intracellular: &Ri,
+        fibers: &FiberVoxel<D>,
+    ) -> Result<(Ri, FiberVoxel<D>), CalcError> {
+        let density = self.deposition_rate - self.degradation_rate * fibers.density;
+        let orientation = match self.polarity.try_normalize(0.0) {
+            Some(p) => self.alignment_rate * (p * p.transpose() - fibers.orientation),
+            None => SMatrix::zeros(),
+        };
+        Ok((
+            intracellular.xa(0.0),
+            FiberVoxel {
+                density,
+                orientation,
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test_remodeling {
+    use super::*;
+    use crate::{CartesianCuboid, ContactGuidance, FiberCuboid};
+    use nalgebra::{Vector1, Vector2};
+
+    #[test]
+    fn cells_align_fibers() -> Result<(), Box<dyn std::error::Error>> {
+        let cuboid =
+            CartesianCuboid::from_boundaries_and_interaction_range([0.0; 2], [20.0; 2], 10.0)?;
+        let domain = FiberCuboid::from_fn(
+            cuboid,
+            |_: &Vector2<f64>| FiberVoxel::isotropic(2.0),
+            ContactGuidance {
+                base_friction: 1.0,
+                fiber_friction: 1.0,
+            },
+        );
+        let (_, mut subdomain, _) = domain
+            .create_subdomains(1.try_into().unwrap())?
+            .into_iter()
+            .next()
+            .unwrap();
+        // Two cells within the same voxel
+        let remodeling = MatrixRemodeling {
+            polarity: Vector2::from([1.0, 1.0]),
+            degradation_rate: 0.1,
+            deposition_rate: 0.05,
+            alignment_rate: 0.2,
+        };
+        let cells = [Vector2::from([2.0, 3.0]), Vector2::from([7.0, 4.0])];
+        let dt = 0.1;
+        for _ in 0..1_000 {
+            let sources = cells
+                .iter()
+                .map(|pos| {
+                    let fibers = subdomain.get_extracellular_at_pos(pos)?;
+                    let (_, dfibers) =
+                        remodeling.calculate_combined_increment(&Vector1::from([0.0]), &fibers)?;
+                    Ok((*pos, dfibers))
+                })
+                .collect::<Result<Vec<_>, CalcError>>()?;
+            subdomain.treat_increments([], sources)?;
+            subdomain.update_fluid_dynamics(dt)?;
+        }
+        // Fibers of the occupied voxel approach their steady state
+        let fibers = subdomain.get_fibers_at(&cells[0])?;
+        assert!((fibers.density - 0.5).abs() < 1e-6);
+        assert!(fibers.alignment() > 0.999);
+        let direction = fibers.principal_direction().unwrap();
+        assert!((direction.dot(&remodeling.polarity.normalize()).abs() - 1.0).abs() < 1e-6);
+        // All other voxels are not modified
+        let untouched = subdomain.get_fibers_at(&Vector2::from([15.0, 15.0]))?;
+        assert_eq!(untouched, &FiberVoxel::isotropic(2.0));
+        // Aligned fibers guide cells along the polarity
+        let f_along = subdomain.calculate_custom_force(&cells[0], &Vector2::from([1.0, 1.0]))?;
+        let f_across = subdomain.calculate_custom_force(&cells[0], &Vector2::from([1.0, -1.0]))?;
+        assert!(f_across.norm() > 1.5 * f_along.norm());
+        Ok(())
+    }
+}
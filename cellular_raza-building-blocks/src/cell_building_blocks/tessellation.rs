@@ -0,0 +1,210 @@
+use cellular_raza_concepts::PositionLike;
+use itertools::Itertools;
+use nalgebra::SMatrix;
+
+use super::VertexMechanics2D;
+
+/// Voronoi cells of the given points inside of a rectangle.
+///
+/// Every cell is returned as a convex polygon whose vertices are ordered counter-clockwise.
+/// The polygons are obtained by successively clipping the rectangle with the perpendicular
+/// bisectors between the point and all other points.
+/// The cost thus grows quadratically with the number of points.
+/// Points outside of the rectangle may yield empty polygons.
+///
+/// ```
+/// use cellular_raza_building_blocks::voronoi_polygons;
+/// let polygons = voronoi_polygons(&[[1.0, 1.0], [3.0, 1.0]], [[0.0, 0.0], [4.0, 2.0]]);
+/// assert_eq!(polygons[0].len(), 4);
+/// assert!(polygons[0].iter().all(|p| p[0] <= 2.0));
+/// assert!(polygons[1].iter().all(|p| p[0] >= 2.0));
+/// ```
+pub fn voronoi_polygons(points: &[[f64; 2]], rectangle: [[f64; 2]; 2]) -> Vec<Vec<[f64; 2]>> {
+    let [lower, upper] = rectangle;
+    let corners = vec![
+        [lower[0], lower[1]],
+        [upper[0], lower[1]],
+        [upper[0], upper[1]],
+        [lower[0], upper[1]],
+    ];
+    points
+        .iter()
+        .enumerate()
+        .map(|(n, p)| {
+            points.iter().enumerate().filter(|(m, _)| *m != n).fold(
+                corners.clone(),
+                |polygon, (_, q)| {
+                    // Keep all points x which are closer to p than to q, ie. (x - mid) * (q - p) <= 0
+                    let normal = [q[0] - p[0], q[1] - p[1]];
+                    let mid = [0.5 * (p[0] + q[0]), 0.5 * (p[1] + q[1])];
+                    let side =
+                        |x: &[f64; 2]| (x[0] - mid[0]) * normal[0] + (x[1] - mid[1]) * normal[1];
+                    clip_polygon(&polygon, side)
+                },
+            )
+        })
+        .collect()
+}
+
+/// Sutherland-Hodgman clipping of a convex polygon by the half-plane `side(x) <= 0`
+fn clip_polygon<S>(polygon: &[[f64; 2]], side: S) -> Vec<[f64; 2]>
+where
+    S: Fn(&[f64; 2]) -> f64,
+{
+    let mut clipped = Vec::with_capacity(polygon.len() + 1);
+    for (a, b) in polygon.iter().circular_tuple_windows() {
+        let (sa, sb) = (side(a), side(b));
+        if sa <= 0.0 {
+            clipped.push(*a);
+        }
+        if (sa < 0.0 && sb > 0.0) || (sa > 0.0 && sb < 0.0) {
+            let t = sa / (sa - sb);
+            clipped.push([a[0] + t * (b[0] - a[0]), a[1] + t * (b[1] - a[1])]);
+        }
+    }
+    clipped
+}
+
+/// Distributes `D` points along the boundary of the polygon with equal spacing.
+fn resample_polygon<const D: usize>(polygon: &[[f64; 2]]) -> SMatrix<f64, D, 2> {
+    let edges: Vec<_> = polygon
+        .iter()
+        .circular_tuple_windows()
+        .map(|(a, b)| {
+            (
+                *a,
+                *b,
+                ((b[0] - a[0]).powi(2) + (b[1] - a[1]).powi(2)).sqrt(),
+            )
+        })
+        .collect();
+    let perimeter: f64 = edges.iter().map(|(_, _, length)| length).sum();
+    let mut points = SMatrix::<f64, D, 2>::zeros();
+    let mut edge_iter = edges.iter().peekable();
+    let mut travelled = 0.0;
+    for n in 0..D {
+        let target = perimeter * n as f64 / D as f64;
+        while let Some((_, _, length)) = edge_iter.peek() {
+            if travelled + length < target && edge_iter.len() > 1 {
+                travelled += length;
+                edge_iter.next();
+            } else {
+                break;
+            }
+        }
+        if let Some((a, b, length)) = edge_iter.peek() {
+            let t = if *length > 0.0 {
+                ((target - travelled) / length).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            points[(n, 0)] = a[0] + t * (b[0] - a[0]);
+            points[(n, 1)] = a[1] + t * (b[1] - a[1]);
+        }
+    }
+    points
+}
+
+impl<const D: usize> VertexMechanics2D<D> {
+    /// Initializes vertex models from the Voronoi tessellation of center-based positions.
+    ///
+    /// The Voronoi cells are calculated by [voronoi_polygons] and their boundaries are
+    /// resampled with `D` equally spaced vertices.
+    /// Every model is in equilibrium as described by [VertexMechanics2D::from_points].
+    /// Positions of vertex models can be converted back to center-based models with their
+    /// [PositionLike::representative_point].
+    ///
+    /// ```
+    /// use cellular_raza_building_blocks::VertexMechanics2D;
+    /// use cellular_raza_concepts::PositionLike;
+    /// let positions = [[1.0, 1.0], [3.0, 1.0], [2.0, 3.0]];
+    /// let models = VertexMechanics2D::<8>::from_voronoi(
+    ///     &positions,
+    ///     [[0.0, 0.0], [4.0, 4.0]],
+    ///     0.5,
+    ///     1.0,
+    ///     0.1,
+    ///     0.0,
+    /// );
+    /// let total_area: f64 = models.iter().map(|m| m.get_cell_area()).sum();
+    /// assert!(total_area <= 16.0);
+    /// assert!(total_area > 12.0);
+    /// ```
+    pub fn from_voronoi<P>(
+        positions: &[P],
+        rectangle: [[f64; 2]; 2],
+        spring_tensions: f64,
+        central_pressure: f64,
+        damping_constant: f64,
+        diffusion_constant: f64,
+    ) -> Vec<Self>
+    where
+        P: PositionLike<f64, 2>,
+    {
+        let points: Vec<_> = positions.iter().map(|p| p.representative_point()).collect();
+        voronoi_polygons(&points, rectangle)
+            .into_iter()
+            .map(|polygon| {
+                Self::from_points(
+                    resample_polygon(&polygon),
+                    spring_tensions,
+                    central_pressure,
+                    damping_constant,
+                    diffusion_constant,
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test_tessellation {
+    use super::*;
+
+    fn area(polygon: &[[f64; 2]]) -> f64 {
+        0.5 * polygon
+            .iter()
+            .circular_tuple_windows()
+            .map(|(a, b)| a[0] * b[1] - a[1] * b[0])
+            .sum::<f64>()
+    }
+
+    #[test]
+    fn polygons_cover_rectangle() {
+        let points = [[1.0, 1.0], [4.0, 2.0], [2.0, 4.0], [4.5, 4.5], [0.5, 3.0]];
+        let polygons = voronoi_polygons(&points, [[0.0, 0.0], [5.0, 5.0]]);
+        let total: f64 = polygons.iter().map(|p| area(p)).sum();
+        assert!((total - 25.0).abs() < 1e-9);
+        // Every polygon contains its point and is ordered counter-clockwise
+        for (polygon, point) in polygons.iter().zip(points) {
+            assert!(area(polygon) > 0.0);
+            for (a, b) in polygon.iter().circular_tuple_windows() {
+                let cross = (b[0] - a[0]) * (point[1] - a[1]) - (b[1] - a[1]) * (point[0] - a[0]);
+                assert!(cross >= -1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn roundtrip_centers() {
+        use cellular_raza_concepts::PositionLike;
+        // Regular grid whose Voronoi cells are squares centered at the points
+        let positions: Vec<_> = (0..3)
+            .flat_map(|i| (0..3).map(move |j| [2.0 * i as f64 + 1.0, 2.0 * j as f64 + 1.0]))
+            .collect();
+        let models = VertexMechanics2D::<4>::from_voronoi(
+            &positions,
+            [[0.0; 2], [6.0; 2]],
+            1.0,
+            1.0,
+            1.0,
+            0.0,
+        );
+        for (model, position) in models.iter().zip(positions) {
+            let center = model.representative_point();
+            assert!((center[0] - position[0]).abs() < 1e-9);
+            assert!((center[1] - position[1]).abs() < 1e-9);
+            assert!((model.get_cell_area() - 4.0).abs() < 1e-9);
+        }
+    }
+}
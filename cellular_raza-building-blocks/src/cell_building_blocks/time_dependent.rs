@@ -0,0 +1,315 @@
+use cellular_raza_concepts::*;
+
+use serde::{Deserialize, Serialize};
+
+/// Scales the forces of an existing interaction by a time-dependent strength.
+///
+/// The strength $s(t)$ is evaluated at the current
+/// [simulation time](GlobalParameters::simulation_time) which the backend provides via the
+/// [GlobalParameters].
+/// The resulting force is given by
+/// \\begin{equation}
+///     \vec{F}(t) = s(t)\vec{F}_I
+/// \\end{equation}
+/// where $\vec{F}_I$ is the force of the wrapped interaction.
+/// The [potential energy](Interaction::potential_energy_between) of the wrapped interaction is
+/// scaled by the same strength.
+/// When called without parameters, only a [TimeFunction::Constant] strength can be evaluated.
+///
+/// ```
+/// # use cellular_raza_building_blocks::*;
+/// # use cellular_raza_concepts::*;
+/// # use nalgebra::Vector2;
+/// let interaction = TimeDependentInteraction {
+///     interaction: MorsePotential {
+///         radius: 1.0,
+///         potential_stiffness: 0.5,
+///         cutoff: 3.0,
+///         strength: 1.0,
+///     },
+///     // Slowly switch on the interaction during the first 10 time units
+///     strength: TimeFunction::LinearRamp {
+///         t_start: 0.0,
+///         t_end: 10.0,
+///         value_start: 0.0,
+///         value_end: 1.0,
+///     },
+/// };
+/// let parameters = GlobalParameters::new().at_time(0.0);
+/// let (f1, _) = interaction.calculate_force_between_with_parameters(
+///     &Vector2::from([0.0, 0.0]),
+///     &Vector2::zeros(),
+///     &Vector2::from([1.0, 0.0]),
+///     &Vector2::zeros(),
+///     &1.0,
+///     &parameters,
+/// )?;
+/// assert_eq!(f1.norm(), 0.0);
+/// # Ok::<(), CalcError>(())
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct TimeDependentInteraction<I, F> {
+    /// Interaction whose forces are scaled
+    pub interaction: I,
+    /// Strength $s(t)$ by which forces are multiplied
+    pub strength: TimeFunction<F>,
+}
+
+impl<Pos, Vel, For, Inf, I, F> Interaction<Pos, Vel, For, Inf> for TimeDependentInteraction<I, F>
+where
+    I: Interaction<Pos, Vel, For, Inf>,
+    For: core::ops::Mul<F, Output = For>,
+    F: num::Float + Send + Sync + 'static,
+{
+    fn get_interaction_information(&self) -> Inf {
+        self.interaction.get_interaction_information()
+    }
+
+    fn calculate_force_between(
+        &self,
+        own_pos: &Pos,
+        own_vel: &Vel,
+        ext_pos: &Pos,
+        ext_vel: &Vel,
+        ext_info: &Inf,
+    ) -> Result<(For, For), CalcError> {
+        self.calculate_force_between_with_parameters(
+            own_pos,
+            own_vel,
+            ext_pos,
+            ext_vel,
+            ext_info,
+            &GlobalParameters::new(),
+        )
+    }
+
+    fn calculate_force_between_with_parameters(
+        &self,
+        own_pos: &Pos,
+        own_vel: &Vel,
+        ext_pos: &Pos,
+        ext_vel: &Vel,
+        ext_info: &Inf,
+        parameters: &GlobalParameters,
+    ) -> Result<(For, For), CalcError> {
+        let strength = self.strength.evaluate(parameters)?;
+        let (f1, f2) = self.interaction.calculate_force_between_with_parameters(
+            own_pos, own_vel, ext_pos, ext_vel, ext_info, parameters,
+        )?;
+        Ok((f1 * strength, f2 * strength))
+    }
+
+    fn is_neighbor(&self, own_pos: &Pos, ext_pos: &Pos, ext_inf: &Inf) -> Result<bool, CalcError> {
+        self.interaction.is_neighbor(own_pos, ext_pos, ext_inf)
+    }
+
+    fn react_to_neighbors(&mut self, neighbors: usize) -> Result<(), CalcError> {
+        self.interaction.react_to_neighbors(neighbors)
+    }
+
+    fn reacts_to_neighbors(&self) -> bool {
+        self.interaction.reacts_to_neighbors()
+    }
+
+    fn normal_force(&self, own_pos: &Pos, ext_pos: &Pos, force: &For) -> Result<f64, CalcError> {
+        self.interaction.normal_force(own_pos, ext_pos, force)
+    }
+
+    fn react_to_pressure(&mut self, pressure: f64) -> Result<(), CalcError> {
+        self.interaction.react_to_pressure(pressure)
+    }
+
+    fn potential_energy_between(
+        &self,
+        own_pos: &Pos,
+        ext_pos: &Pos,
+        ext_inf: &Inf,
+    ) -> Result<Option<f64>, CalcError> {
+        self.potential_energy_between_with_parameters(
+            own_pos,
+            ext_pos,
+            ext_inf,
+            &GlobalParameters::new(),
+        )
+    }
+
+    fn potential_energy_between_with_parameters(
+        &self,
+        own_pos: &Pos,
+        ext_pos: &Pos,
+        ext_inf: &Inf,
+        parameters: &GlobalParameters,
+    ) -> Result<Option<f64>, CalcError> {
+        let strength = self
+            .strength
+            .evaluate(parameters)?
+            .to_f64()
+            .ok_or(CalcError(
+                "strength can not be represented as f64".to_owned(),
+            ))?;
+        let energy = self
+            .interaction
+            .potential_energy_between_with_parameters(own_pos, ext_pos, ext_inf, parameters)?;
+        Ok(energy.map(|energy| energy * strength))
+    }
+}
+
+/// Intracellular species which are degraded with a time-dependent rate.
+///
+/// \\begin{equation}
+///     \frac{\partial c}{\partial t} = -\lambda(t) c
+/// \\end{equation}
+///
+/// # Parameters & Variables
+/// | Symbol | Struct Field | Description |
+/// |:---:| --- | --- |
+/// | $\lambda(t)$ | `degradation_rate` | Rate at which all species are degraded |
+/// | | | |
+/// | $c$ | `intracellular` | Intracellular concentrations |
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct TimeDependentDegradation<Ri, F> {
+    /// Intracellular concentrations
+    pub intracellular: Ri,
+    /// Degradation rate $\lambda(t)$
+    pub degradation_rate: TimeFunction<F>,
+}
+
+impl<Ri, F> Intracellular<Ri> for TimeDependentDegradation<Ri, F>
+where
+    Ri: Clone,
+{
+    fn set_intracellular(&mut self, intracellular: Ri) {
+        self.intracellular = intracellular;
+    }
+
+    fn get_intracellular(&self) -> Ri {
+        self.intracellular.clone()
+    }
+}
+
+impl<Ri, F> Reactions<Ri> for TimeDependentDegradation<Ri, F>
+where
+    Ri: Clone + core::ops::Mul<F, Output = Ri>,
+    F: num::Float + Send + Sync + 'static,
+{
+    fn calculate_intracellular_increment(&self, intracellular: &Ri) -> Result<Ri, CalcError> {
+        self.calculate_intracellular_increment_with_parameters(
+            intracellular,
+            &GlobalParameters::new(),
+        )
+    }
+
+    fn calculate_intracellular_increment_with_parameters(
+        &self,
+        intracellular: &Ri,
+        parameters: &GlobalParameters,
+    ) -> Result<Ri, CalcError> {
+        let rate = self.degradation_rate.evaluate(parameters)?;
+        Ok(intracellular.clone() * (-rate))
+    }
+}
+
+#[cfg(test)]
+mod test_time_dependent {
+    use super::*;
+
+    struct Spring;
+
+    impl Interaction<f64, f64, f64> for Spring {
+        fn get_interaction_information(&self) {}
+
+        fn calculate_force_between(
+            &self,
+            own_pos: &f64,
+            _own_vel: &f64,
+            ext_pos: &f64,
+            _ext_vel: &f64,
+            _ext_info: &(),
+        ) -> Result<(f64, f64), CalcError> {
+            Ok((ext_pos - own_pos, own_pos - ext_pos))
+        }
+
+        fn potential_energy_between(
+            &self,
+            own_pos: &f64,
+            ext_pos: &f64,
+            _ext_inf: &(),
+        ) -> Result<Option<f64>, CalcError> {
+            Ok(Some(0.5 * (ext_pos - own_pos).powi(2)))
+        }
+    }
+
+    #[test]
+    fn interaction_follows_step() {
+        let interaction = TimeDependentInteraction {
+            interaction: Spring,
+            strength: TimeFunction::Step {
+                t_switch: 1.0,
+                value_before: 2.0,
+                value_after: 0.5,
+            },
+        };
+        let force_at = |t: f64| {
+            let parameters = GlobalParameters::new().at_time(t);
+            interaction
+                .calculate_force_between_with_parameters(&0.0, &0.0, &1.0, &0.0, &(), &parameters)
+                .unwrap()
+        };
+        assert_eq!(force_at(0.0), (2.0, -2.0));
+        assert_eq!(force_at(1.5), (0.5, -0.5));
+        // Without the simulation time, the strength can not be evaluated
+        assert!(interaction
+            .calculate_force_between(&0.0, &0.0, &1.0, &0.0, &())
+            .is_err());
+    }
+
+    #[test]
+    fn energy_follows_step() {
+        let interaction = TimeDependentInteraction {
+            interaction: Spring,
+            strength: TimeFunction::Step {
+                t_switch: 1.0,
+                value_before: 2.0,
+                value_after: 0.5,
+            },
+        };
+        let energy_at = |t: f64| {
+            let parameters = GlobalParameters::new().at_time(t);
+            interaction
+                .potential_energy_between_with_parameters(&0.0, &2.0, &(), &parameters)
+                .unwrap()
+        };
+        assert_eq!(energy_at(0.0), Some(4.0));
+        assert_eq!(energy_at(1.5), Some(1.0));
+        assert!(interaction
+            .potential_energy_between(&0.0, &2.0, &())
+            .is_err());
+    }
+
+    #[test]
+    fn constant_strength_without_parameters() {
+        let interaction = TimeDependentInteraction {
+            interaction: Spring,
+            strength: TimeFunction::from(3.0),
+        };
+        let forces = interaction
+            .calculate_force_between(&0.0, &0.0, &1.0, &0.0, &())
+            .unwrap();
+        assert_eq!(forces, (3.0, -3.0));
+    }
+
+    #[test]
+    fn degradation_uses_custom_function() {
+        let reactions = TimeDependentDegradation {
+            intracellular: 4.0,
+            degradation_rate: TimeFunction::<f64>::Custom("decay".to_owned()),
+        };
+        let parameters = GlobalParameters::new()
+            .insert(TimeFunctionRegistry::new().register("decay", |t: f64| 0.25 * t))
+            .at_time(2.0);
+        let increment = reactions
+            .calculate_intracellular_increment_with_parameters(&4.0, &parameters)
+            .unwrap();
+        assert_eq!(increment, -2.0);
+    }
+}
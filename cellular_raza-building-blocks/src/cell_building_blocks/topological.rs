@@ -0,0 +1,283 @@
+use std::collections::BTreeSet;
+
+use cellular_raza_concepts::*;
+use nalgebra::SVector;
+
+use serde::{Deserialize, Serialize};
+
+use crate::delaunay_neighbors;
+
+/// Identifies a cell within a group of cells which were triangulated together.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct DelaunayLabel {
+    /// Group of cells, for example the index of the subdomain which contains them
+    pub batch: u64,
+    /// Index of the cell within its group
+    pub index: usize,
+}
+
+/// Restricts the wrapped interaction to topological neighbors in the Delaunay triangulation.
+///
+/// Cutoff distances only determine neighbors reliably if cells are roughly equally sized and
+/// evenly packed.
+/// This wrapper instead lets cells interact only if they are connected by an edge of the
+/// Delaunay triangulation of all cell centers.
+/// Neighbors are assigned by [DelaunayInteraction::assign_delaunay_neighbors] which should be
+/// called regularly, for example by a custom update step of the backend which iterates over
+/// all cells of a subdomain and uses the index of the subdomain as `batch`.
+/// Pairs of cells which were not triangulated together, such as cells in different
+/// subdomains or cells without an assigned label, fall back to the wrapped interaction and its
+/// cutoff.
+/// The triangulation itself is calculated by [delaunay_neighbors] and can also be used for
+/// analysis.
+///
+/// ```
+/// # use cellular_raza_building_blocks::*;
+/// # use cellular_raza_concepts::*;
+/// # use nalgebra::Vector2;
+/// let morse = MorsePotential {
+///     radius: 1.0,
+///     potential_stiffness: 0.5,
+///     cutoff: 5.0,
+///     strength: 1.0,
+/// };
+/// // Three cells on a line. The outer ones are shielded from each other by the middle one.
+/// let positions = [[0.0, 0.0], [1.0, 0.0], [2.0, 0.0]];
+/// let mut interactions = vec![DelaunayInteraction::new(morse); 3];
+/// DelaunayInteraction::assign_delaunay_neighbors(
+///     0,
+///     interactions.iter_mut().zip(positions),
+/// );
+/// assert_eq!(interactions[1].neighbors.len(), 2);
+///
+/// type Vec2 = Vector2<f64>;
+/// let inf = Interaction::<Vec2, Vec2, Vec2, _>::get_interaction_information(&interactions[2]);
+/// let (force, _) = interactions[0].calculate_force_between(
+///     &Vec2::from(positions[0]),
+///     &Vec2::zeros(),
+///     &Vec2::from(positions[2]),
+///     &Vec2::zeros(),
+///     &inf,
+/// )?;
+/// assert_eq!(force, Vec2::zeros());
+/// # Ok::<(), CalcError>(())
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DelaunayInteraction<I> {
+    /// Interaction which calculates the forces between neighboring cells
+    pub interaction: I,
+    /// Label which was assigned during the last triangulation
+    pub label: Option<DelaunayLabel>,
+    /// Indices of all neighbors within the same batch
+    pub neighbors: BTreeSet<usize>,
+}
+
+impl<I> DelaunayInteraction<I> {
+    /// Wraps the given interaction without any assigned neighbors
+    pub fn new(interaction: I) -> Self {
+        Self {
+            interaction,
+            label: None,
+            neighbors: BTreeSet::new(),
+        }
+    }
+
+    /// Triangulates the given cells and assigns their labels and neighbors.
+    ///
+    /// Every cell is given together with its position.
+    /// The `batch` distinguishes cells from those of other calls to this function.
+    pub fn assign_delaunay_neighbors<'a, const D: usize>(
+        batch: u64,
+        cells: impl IntoIterator<Item = (&'a mut Self, [f64; D])>,
+    ) where
+        I: 'a,
+    {
+        let (cells, points): (Vec<_>, Vec<_>) = cells.into_iter().unzip();
+        for (index, (cell, neighbors)) in cells
+            .into_iter()
+            .zip(delaunay_neighbors(&points))
+            .enumerate()
+        {
+            cell.label = Some(DelaunayLabel { batch, index });
+            cell.neighbors = neighbors;
+        }
+    }
+
+    /// Determines if the topology decides about the interaction with the other cell.
+    ///
+    /// Returns [None] if the cells were not triangulated together.
+    fn is_topological_neighbor(&self, ext_label: &Option<DelaunayLabel>) -> Option<bool> {
+        match (&self.label, ext_label) {
+            (Some(own), Some(ext)) if own.batch == ext.batch => {
+                Some(self.neighbors.contains(&ext.index))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<I, Vel, Inf, Par, const D: usize>
+    Interaction<SVector<f64, D>, Vel, SVector<f64, D>, (Option<DelaunayLabel>, Inf), Par>
+    for DelaunayInteraction<I>
+where
+    I: Interaction<SVector<f64, D>, Vel, SVector<f64, D>, Inf, Par>,
+{
+    fn get_interaction_information(&self) -> (Option<DelaunayLabel>, Inf) {
+        (self.label, self.interaction.get_interaction_information())
+    }
+
+    fn calculate_force_between(
+        &self,
+        own_pos: &SVector<f64, D>,
+        own_vel: &Vel,
+        ext_pos: &SVector<f64, D>,
+        ext_vel: &Vel,
+        ext_info: &(Option<DelaunayLabel>, Inf),
+    ) -> Result<(SVector<f64, D>, SVector<f64, D>), CalcError> {
+        if self.is_topological_neighbor(&ext_info.0) == Some(false) {
+            return Ok((SVector::zeros(), SVector::zeros()));
+        }
+        self.interaction
+            .calculate_force_between(own_pos, own_vel, ext_pos, ext_vel, &ext_info.1)
+    }
+
+    fn calculate_force_between_with_parameters(
+        &self,
+        own_pos: &SVector<f64, D>,
+        own_vel: &Vel,
+        ext_pos: &SVector<f64, D>,
+        ext_vel: &Vel,
+        ext_info: &(Option<DelaunayLabel>, Inf),
+        parameters: &Par,
+    ) -> Result<(SVector<f64, D>, SVector<f64, D>), CalcError> {
+        if self.is_topological_neighbor(&ext_info.0) == Some(false) {
+            return Ok((SVector::zeros(), SVector::zeros()));
+        }
+        self.interaction.calculate_force_between_with_parameters(
+            own_pos,
+            own_vel,
+            ext_pos,
+            ext_vel,
+            &ext_info.1,
+            parameters,
+        )
+    }
+
+    fn is_neighbor(
+        &self,
+        own_pos: &SVector<f64, D>,
+        ext_pos: &SVector<f64, D>,
+        ext_inf: &(Option<DelaunayLabel>, Inf),
+    ) -> Result<bool, CalcError> {
+        match self.is_topological_neighbor(&ext_inf.0) {
+            Some(neighbor) => Ok(neighbor),
+            None => self.interaction.is_neighbor(own_pos, ext_pos, &ext_inf.1),
+        }
+    }
+
+    fn react_to_neighbors(&mut self, neighbors: usize) -> Result<(), CalcError> {
+        self.interaction.react_to_neighbors(neighbors)
+    }
+
+    fn reacts_to_neighbors(&self) -> bool {
+        self.interaction.reacts_to_neighbors()
+    }
+
+    fn normal_force(
+        &self,
+        own_pos: &SVector<f64, D>,
+        ext_pos: &SVector<f64, D>,
+        force: &SVector<f64, D>,
+    ) -> Result<f64, CalcError> {
+        self.interaction.normal_force(own_pos, ext_pos, force)
+    }
+
+    fn react_to_pressure(&mut self, pressure: f64) -> Result<(), CalcError> {
+        self.interaction.react_to_pressure(pressure)
+    }
+
+    fn potential_energy_between(
+        &self,
+        own_pos: &SVector<f64, D>,
+        ext_pos: &SVector<f64, D>,
+        ext_inf: &(Option<DelaunayLabel>, Inf),
+    ) -> Result<Option<f64>, CalcError> {
+        if self.is_topological_neighbor(&ext_inf.0) == Some(false) {
+            return Ok(Some(0.0));
+        }
+        self.interaction
+            .potential_energy_between(own_pos, ext_pos, &ext_inf.1)
+    }
+
+    fn potential_energy_between_with_parameters(
+        &self,
+        own_pos: &SVector<f64, D>,
+        ext_pos: &SVector<f64, D>,
+        ext_inf: &(Option<DelaunayLabel>, Inf),
+        parameters: &Par,
+    ) -> Result<Option<f64>, CalcError> {
+        if self.is_topological_neighbor(&ext_inf.0) == Some(false) {
+            return Ok(Some(0.0));
+        }
+        self.interaction
+            .potential_energy_between_with_parameters(own_pos, ext_pos, &ext_inf.1, parameters)
+    }
+}
+
+#[cfg(test)]
+mod test_topological {
+    use super::*;
+    use crate::MorsePotential;
+    use nalgebra::Vector2;
+
+    #[test]
+    fn other_batches_use_cutoff() -> Result<(), CalcError> {
+        let morse = MorsePotential {
+            radius: 1.0,
+            potential_stiffness: 0.5,
+            cutoff: 5.0,
+            strength: 1.0,
+        };
+        let positions = [[0.0, 0.0], [1.0, 0.0], [2.0, 0.0]];
+        let mut interactions = vec![DelaunayInteraction::new(morse.clone()); 3];
+        DelaunayInteraction::assign_delaunay_neighbors(0, interactions.iter_mut().zip(positions));
+        let mut other = DelaunayInteraction::new(morse.clone());
+        DelaunayInteraction::assign_delaunay_neighbors(1, [(&mut other, positions[2])]);
+
+        let own_pos = Vector2::from(positions[0]);
+        let ext_pos = Vector2::from(positions[2]);
+        let label = |i: &DelaunayInteraction<MorsePotential>| (i.label, 1.0);
+        // Same batch but no Delaunay neighbors
+        assert!(!interactions[0].is_neighbor(&own_pos, &ext_pos, &label(&interactions[2]))?);
+        assert_eq!(
+            interactions[0].potential_energy_between(
+                &own_pos,
+                &ext_pos,
+                &label(&interactions[2])
+            )?,
+            Some(0.0)
+        );
+        // Different batch or no label
+        let wrapped = interactions[0]
+            .interaction
+            .is_neighbor(&own_pos, &ext_pos, &1.0)?;
+        assert_eq!(
+            interactions[0].is_neighbor(&own_pos, &ext_pos, &label(&other))?,
+            wrapped
+        );
+        assert_eq!(
+            interactions[0].is_neighbor(&own_pos, &ext_pos, &(None, 1.0))?,
+            wrapped
+        );
+        assert!(interactions[0].is_neighbor(&own_pos, &own_pos, &label(&interactions[1]))?);
+        let (force, _) = interactions[0].calculate_force_between(
+            &own_pos,
+            &Vector2::zeros(),
+            &Vector2::from([1.5, 0.0]),
+            &Vector2::zeros(),
+            &label(&other),
+        )?;
+        assert!(force.norm() > 0.0);
+        Ok(())
+    }
+}
@@ -10,6 +10,28 @@ use num::Zero;
 /// The [ModularCell] is a struct with fields that implement the various
 /// [concepts](cellular_raza_concepts). The concepts are afterwards derived automatically for the
 /// [ModularCell] struct.
+///
+/// Besides the legacy [CellularReactions] trait, the [ModularCell] also forwards the
+/// [Intracellular], [Reactions], [ReactionsExtra] and [ReactionsContact] traits as well as
+/// [KineticEnergy].
+/// Together with its [Position] this allows to use it directly with the
+/// [CartesianCuboid](crate::CartesianCuboid) domain and the `chili` backend.
+/// ```
+/// # use cellular_raza_building_blocks::*;
+/// # use cellular_raza_concepts::*;
+/// let cell = ModularCell {
+///     mechanics: NewtonDamped2D::new([1.0, 2.0], [0.0; 2], 0.1, 1.0),
+///     interaction: NoInteraction,
+///     interaction_extracellular: NoExtracellularGradientSensing,
+///     cycle: NoCycle,
+///     cellular_reactions: NoCellularReactions,
+///     volume: 1.0,
+/// };
+/// let domain = CartesianCuboid::from_boundaries_and_interaction_range([0.0; 2], [10.0; 2], 2.0)?;
+/// let decomposed = domain.decompose(1.try_into()?, vec![cell])?;
+/// assert_eq!(decomposed.index_subdomain_cells[0].2.len(), 1);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ModularCell<Mec, Int, Cyc, React, IntExtracellular> {
@@ -51,20 +73,53 @@ where
     fn set_intracellular(&mut self, _concentration_vector: Nothing) {}
 }
 
+impl Intracellular<Nothing> for NoCellularReactions {
+    fn set_intracellular(&mut self, _intracellular: Nothing) {}
+
+    fn get_intracellular(&self) -> Nothing {
+        <Nothing>::zero()
+    }
+}
+
+impl Reactions<Nothing> for NoCellularReactions {
+    fn calculate_intracellular_increment(
+        &self,
+        _intracellular: &Nothing,
+    ) -> Result<Nothing, CalcError> {
+        Ok(<Nothing>::zero())
+    }
+}
+
+impl ReactionsExtra<Nothing, Nothing> for NoCellularReactions {
+    fn calculate_combined_increment(
+        &self,
+        _intracellular: &Nothing,
+        _extracellular: &Nothing,
+    ) -> Result<(Nothing, Nothing), CalcError> {
+        Ok((<Nothing>::zero(), <Nothing>::zero()))
+    }
+}
+
 /// Type alias used when not wanting to simulate any cellular reactions for example.
 pub type Nothing = nalgebra::SVector<f64, 0>;
 
-impl<Pos, Vel, For, Float, Mec, Int, Cyc, React, IntExtracellular> Mechanics<Pos, Vel, For, Float>
-    for ModularCell<Mec, Int, Cyc, React, IntExtracellular>
+impl<Pos, Vel, For, Float, R, Par, Mec, Int, Cyc, React, IntExtracellular>
+    Mechanics<Pos, Vel, For, Float, R, Par> for ModularCell<Mec, Int, Cyc, React, IntExtracellular>
 where
-    Mec: Mechanics<Pos, Vel, For, Float>,
+    Mec: Mechanics<Pos, Vel, For, Float, R, Par>,
 {
-    fn get_random_contribution(
+    fn get_random_contribution(&self, rng: &mut R, dt: Float) -> Result<(Pos, Vel), RngError> {
+        self.mechanics.get_random_contribution(rng, dt)
+    }
+
+    fn get_random_contribution_with_parameters(
         &self,
-        rng: &mut rand_chacha::ChaCha8Rng,
+        rng: &mut R,
         dt: Float,
+        parameters: &Par,
     ) -> Result<(Pos, Vel), RngError> {
-        self.mechanics.get_random_contribution(rng, dt)
+        self.mechanics
+            .get_random_contribution_with_parameters(rng, dt, parameters)
     }
 
     fn calculate_increment(&self, force: For) -> Result<(Pos, Vel), CalcError> {
@@ -72,6 +127,20 @@ where
     }
 }
 
+impl<Mom, Float, Mec, Int, Cyc, React, IntExtracellular> KineticEnergy<Mom, Float>
+    for ModularCell<Mec, Int, Cyc, React, IntExtracellular>
+where
+    Mec: KineticEnergy<Mom, Float>,
+{
+    fn kinetic_energy(&self) -> Float {
+        self.mechanics.kinetic_energy()
+    }
+
+    fn momentum(&self) -> Mom {
+        self.mechanics.momentum()
+    }
+}
+
 impl<Pos, Mec, Int, Cyc, React, InteractionExtracellular> cellular_raza_concepts::Position<Pos>
     for ModularCell<Mec, Int, Cyc, React, InteractionExtracellular>
 where
@@ -100,10 +169,10 @@ where
     }
 }
 
-impl<Pos, Vel, For, Inf, Mec, Int, Cyc, React, IntExtracellular> Interaction<Pos, Vel, For, Inf>
-    for ModularCell<Mec, Int, Cyc, React, IntExtracellular>
+impl<Pos, Vel, For, Inf, Par, Mec, Int, Cyc, React, IntExtracellular>
+    Interaction<Pos, Vel, For, Inf, Par> for ModularCell<Mec, Int, Cyc, React, IntExtracellular>
 where
-    Int: Interaction<Pos, Vel, For, Inf>,
+    Int: Interaction<Pos, Vel, For, Inf, Par>,
 {
     fn get_interaction_information(&self) -> Inf {
         self.interaction.get_interaction_information()
@@ -126,6 +195,25 @@ where
         )
     }
 
+    fn calculate_force_between_with_parameters(
+        &self,
+        own_pos: &Pos,
+        own_vel: &Vel,
+        ext_pos: &Pos,
+        ext_vel: &Vel,
+        ext_information: &Inf,
+        parameters: &Par,
+    ) -> Result<(For, For), CalcError> {
+        self.interaction.calculate_force_between_with_parameters(
+            own_pos,
+            own_vel,
+            ext_pos,
+            ext_vel,
+            ext_information,
+            parameters,
+        )
+    }
+
     fn is_neighbor(&self, own_pos: &Pos, ext_pos: &Pos, ext_inf: &Inf) -> Result<bool, CalcError> {
         self.interaction.is_neighbor(own_pos, ext_pos, ext_inf)
     }
@@ -133,6 +221,39 @@ where
     fn react_to_neighbors(&mut self, neighbors: usize) -> Result<(), CalcError> {
         self.interaction.react_to_neighbors(neighbors)
     }
+
+    fn reacts_to_neighbors(&self) -> bool {
+        self.interaction.reacts_to_neighbors()
+    }
+
+    fn potential_energy_between(
+        &self,
+        own_pos: &Pos,
+        ext_pos: &Pos,
+        ext_inf: &Inf,
+    ) -> Result<Option<f64>, CalcError> {
+        self.interaction
+            .potential_energy_between(own_pos, ext_pos, ext_inf)
+    }
+
+    fn potential_energy_between_with_parameters(
+        &self,
+        own_pos: &Pos,
+        ext_pos: &Pos,
+        ext_inf: &Inf,
+        parameters: &Par,
+    ) -> Result<Option<f64>, CalcError> {
+        self.interaction
+            .potential_energy_between_with_parameters(own_pos, ext_pos, ext_inf, parameters)
+    }
+
+    fn normal_force(&self, own_pos: &Pos, ext_pos: &Pos, force: &For) -> Result<f64, CalcError> {
+        self.interaction.normal_force(own_pos, ext_pos, force)
+    }
+
+    fn react_to_pressure(&mut self, pressure: f64) -> Result<(), CalcError> {
+        self.interaction.react_to_pressure(pressure)
+    }
 }
 
 impl<Mec, Int, Cyc, React, IntExtracellular> Volume
@@ -143,25 +264,30 @@ impl<Mec, Int, Cyc, React, IntExtracellular> Volume
     }
 }
 
-impl<Mec, Int, Cyc, Float, React, IntExtracellular> Cycle<Self, Float>
+impl<Mec, Int, Cyc, Float, R, Par, React, IntExtracellular> Cycle<Self, Float, R, Par>
     for ModularCell<Mec, Int, Cyc, React, IntExtracellular>
 where
-    Cyc: Cycle<Self, Float>,
+    Cyc: Cycle<Self, Float, R, Par>,
 {
-    fn update_cycle(
-        rng: &mut rand_chacha::ChaCha8Rng,
+    fn update_cycle(rng: &mut R, dt: &Float, cell: &mut Self) -> Option<CycleEvent> {
+        Cyc::update_cycle(rng, dt, cell)
+    }
+
+    fn update_cycle_with_parameters(
+        rng: &mut R,
         dt: &Float,
         cell: &mut Self,
+        parameters: &Par,
     ) -> Option<CycleEvent> {
-        Cyc::update_cycle(rng, dt, cell)
+        Cyc::update_cycle_with_parameters(rng, dt, cell, parameters)
     }
 
-    fn divide(rng: &mut rand_chacha::ChaCha8Rng, cell: &mut Self) -> Result<Self, DivisionError> {
+    fn divide(rng: &mut R, cell: &mut Self) -> Result<Self, DivisionError> {
         Cyc::divide(rng, cell)
     }
 
     fn update_conditional_phased_death(
-        rng: &mut rand_chacha::ChaCha8Rng,
+        rng: &mut R,
         dt: &Float,
         cell: &mut Self,
     ) -> Result<bool, DeathError> {
@@ -197,6 +323,138 @@ where
     }
 }
 
+impl<Ri, Mec, Int, Cyc, React, IntExtracellular> Intracellular<Ri>
+    for ModularCell<Mec, Int, Cyc, React, IntExtracellular>
+where
+    React: Intracellular<Ri>,
+{
+    fn set_intracellular(&mut self, intracellular: Ri) {
+        Intracellular::set_intracellular(&mut self.cellular_reactions, intracellular)
+    }
+
+    fn get_intracellular(&self) -> Ri {
+        Intracellular::get_intracellular(&self.cellular_reactions)
+    }
+}
+
+impl<Ri, Par, Mec, Int, Cyc, React, IntExtracellular> Reactions<Ri, Par>
+    for ModularCell<Mec, Int, Cyc, React, IntExtracellular>
+where
+    React: Reactions<Ri, Par>,
+{
+    fn calculate_intracellular_increment(&self, intracellular: &Ri) -> Result<Ri, CalcError> {
+        self.cellular_reactions
+            .calculate_intracellular_increment(intracellular)
+    }
+
+    fn calculate_intracellular_increment_with_parameters(
+        &self,
+        intracellular: &Ri,
+        parameters: &Par,
+    ) -> Result<Ri, CalcError> {
+        self.cellular_reactions
+            .calculate_intracellular_increment_with_parameters(intracellular, parameters)
+    }
+}
+
+impl<Ri, Re, Mec, Int, Cyc, React, IntExtracellular> ReactionsExtra<Ri, Re>
+    for ModularCell<Mec, Int, Cyc, React, IntExtracellular>
+where
+    React: ReactionsExtra<Ri, Re>,
+{
+    fn calculate_combined_increment(
+        &self,
+        intracellular: &Ri,
+        extracellular: &Re,
+    ) -> Result<(Ri, Re), CalcError> {
+        self.cellular_reactions
+            .calculate_combined_increment(intracellular, extracellular)
+    }
+}
+
+impl<Ri, Pos, Float, RInf, Mec, Int, Cyc, React, IntExtracellular>
+    ReactionsContact<Ri, Pos, Float, RInf> for ModularCell<Mec, Int, Cyc, React, IntExtracellular>
+where
+    React: ReactionsContact<Ri, Pos, Float, RInf>,
+{
+    fn get_contact_information(&self) -> RInf {
+        self.cellular_reactions.get_contact_information()
+    }
+
+    fn calculate_contact_increment(
+        &self,
+        own_intracellular: &Ri,
+        ext_intracellular: &Ri,
+        own_pos: &Pos,
+        ext_pos: &Pos,
+        rinf: &RInf,
+    ) -> Result<(Ri, Ri), CalcError> {
+        self.cellular_reactions.calculate_contact_increment(
+            own_intracellular,
+            ext_intracellular,
+            own_pos,
+            ext_pos,
+            rinf,
+        )
+    }
+}
+
+impl<F, const D: usize, Mec, Int, Cyc, React, IntExtracellular> PositionLike<F, D>
+    for ModularCell<Mec, Int, Cyc, React, IntExtracellular>
+where
+    Mec: PositionLike<F, D>,
+{
+    fn representative_point(&self) -> [F; D] {
+        self.mechanics.representative_point()
+    }
+
+    fn bounding_box(&self) -> ([F; D], [F; D]) {
+        self.mechanics.bounding_box()
+    }
+}
+
+/// Draws the cell as a circle whose area equals the [volume](ModularCell::volume) of the cell.
+///
+/// The circle is centered at the [representative_point](PositionLike::representative_point) of
+/// the mechanics.
+/// ```
+/// # use cellular_raza_building_blocks::*;
+/// # use cellular_raza_concepts::PlotCell;
+/// let cell = ModularCell {
+///     mechanics: NewtonDamped2D::new([1.0, 2.0], [0.0; 2], 0.1, 1.0),
+///     interaction: NoInteraction,
+///     interaction_extracellular: NoExtracellularGradientSensing,
+///     cycle: NoCycle,
+///     cellular_reactions: NoCellularReactions,
+///     volume: core::f64::consts::PI,
+/// };
+/// assert_eq!(cell.glyph_position(), [1.0, 2.0]);
+/// assert!((cell.glyph_radius() - 1.0).abs() < 1e-12);
+/// ```
+impl<Mec, Int, Cyc, React, IntExtracellular> PlotCell
+    for ModularCell<Mec, Int, Cyc, React, IntExtracellular>
+where
+    Mec: PositionLike<f64, 2>,
+{
+    fn glyph_position(&self) -> [f64; 2] {
+        self.mechanics.representative_point()
+    }
+
+    fn glyph_radius(&self) -> f64 {
+        (self.volume / core::f64::consts::PI).sqrt()
+    }
+
+    fn glyph_bounding_box(&self) -> ([f64; 2], [f64; 2]) {
+        let [x, y] = self.glyph_position();
+        let r = self.glyph_radius();
+        let (lower, upper) = self.mechanics.bounding_box();
+        (
+            [lower[0].min(x - r), lower[1].min(y - r)],
+            [upper[0].max(x + r), upper[1].max(y + r)],
+        )
+    }
+}
+
 /// Type which allows to simply not model gradients.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct NoExtracellularGradientSensing;
@@ -227,3 +485,98 @@ where
         IntExtracellular::sense_gradient(cell, extracellular_gradient)
     }
 }
+
+#[cfg(test)]
+mod test_modular_cell {
+    use super::*;
+    use crate::{NewtonDamped2D, NoCycle, NoInteraction};
+    use nalgebra::Vector2;
+
+    #[derive(Clone, Debug)]
+    struct LegacyReactions(f64);
+
+    impl CellularReactions<f64> for LegacyReactions {
+        fn calculate_intra_and_extracellular_reaction_increment(
+            &self,
+            internal: &f64,
+            external: &f64,
+        ) -> Result<(f64, f64), CalcError> {
+            Ok((external - internal, internal - external))
+        }
+
+        fn get_intracellular(&self) -> f64 {
+            self.0
+        }
+
+        fn set_intracellular(&mut self, concentration: f64) {
+            self.0 = concentration;
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    struct Decay(f64);
+
+    impl Intracellular<f64> for Decay {
+        fn set_intracellular(&mut self, intracellular: f64) {
+            self.0 = intracellular;
+        }
+
+        fn get_intracellular(&self) -> f64 {
+            self.0
+        }
+    }
+
+    impl Reactions<f64> for Decay {
+        fn calculate_intracellular_increment(&self, intracellular: &f64) -> Result<f64, CalcError> {
+            Ok(-intracellular)
+        }
+    }
+
+    fn new_cell<R>(reactions: R) -> ModularCell<NewtonDamped2D, NoInteraction, NoCycle, R, ()> {
+        ModularCell {
+            mechanics: NewtonDamped2D::new([1.0, 2.0], [0.0; 2], 0.1, 1.0),
+            interaction: NoInteraction,
+            interaction_extracellular: (),
+            cycle: NoCycle,
+            cellular_reactions: reactions,
+            volume: 1.0,
+        }
+    }
+
+    fn chili_bounds<C, Ri>(cell: &C) -> Result<Ri, CalcError>
+    where
+        C: Mechanics<Vector2<f64>, Vector2<f64>, Vector2<f64>, f64>,
+        C: Interaction<Vector2<f64>, Vector2<f64>, Vector2<f64>>,
+        C: Cycle<C, f64>,
+        C: Reactions<Ri>,
+    {
+        cell.calculate_intracellular_increment(&cell.get_intracellular())
+    }
+
+    #[test]
+    fn legacy_reactions_unambiguous() {
+        let mut cell = new_cell(LegacyReactions(3.0));
+        assert_eq!(cell.get_intracellular(), 3.0);
+        cell.set_intracellular(1.0);
+        assert_eq!(cell.cellular_reactions.0, 1.0);
+    }
+
+    #[test]
+    fn forward_new_concepts() {
+        let mut cell = new_cell(Decay(2.0));
+        assert_eq!(chili_bounds(&cell).unwrap(), -2.0);
+        cell.set_intracellular(4.0);
+        assert_eq!(cell.cellular_reactions.0, 4.0);
+        let energy =
+            Interaction::<Vector2<f64>, Vector2<f64>, Vector2<f64>>::potential_energy_between(
+                &cell,
+                &Vector2::zeros(),
+                &Vector2::zeros(),
+                &(),
+            )
+            .unwrap();
+        assert_eq!(energy, Some(0.0));
+        let cell = new_cell(NoCellularReactions);
+        assert_eq!(chili_bounds(&cell).unwrap(), Nothing::zeros());
+    }
+}
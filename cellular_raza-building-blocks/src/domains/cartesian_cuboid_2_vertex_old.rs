@@ -2,7 +2,8 @@
 use cellular_raza_concepts::domain_old::*;
 use cellular_raza_concepts::reactions_old::Volume;
 use cellular_raza_concepts::{
-    BoundaryError, CalcError, CreatePlottingRoot, DrawingError, IndexError, RequestError,
+    BoundaryError, CalcError, ChartConfig, CreatePlottingRoot, DrawingError, IndexError,
+    RequestError,
 };
 
 use super::cartesian_cuboid_n::get_decomp_res;
@@ -16,7 +17,7 @@ use itertools::Itertools;
 
 use serde::{Deserialize, Serialize};
 
-use plotters::backend::BitMapBackend;
+use plotters::backend::{BitMapBackend, SVGBackend};
 use plotters::coord::cartesian::Cartesian2d;
 use plotters::coord::types::RangedCoordf64;
 use plotters::prelude::DrawingArea;
@@ -170,6 +171,18 @@ impl<const D: usize, const N: usize> CartesianCuboidVoxel2Vertex<D, N> {
         self.dx
     }
 
+    /// Sets the extracellular concentrations to the value of the given field at the middle of
+    /// the voxel.
+    ///
+    /// This is intended to be used as a voxel definition strategy which is applied when
+    /// decomposing the domain.
+    pub fn initialize_extracellular<C>(&mut self, field: &C)
+    where
+        C: super::ConcentrationField<SVector<f64, 2>, SVector<f64, N>>,
+    {
+        self.extracellular_concentrations = field.concentration_at(&self.middle.into());
+    }
+
     fn position_is_in_domain(
         &self,
         pos: &nalgebra::SMatrix<f64, D, 2>,
@@ -189,6 +202,14 @@ impl<const D: usize, const N: usize> CartesianCuboidVoxel2Vertex<D, N> {
         Ok(())
     }
 
+    #[cfg(feature = "gradients")]
+    fn index_to_displacement(&self, index: &[i64; 2]) -> SVector<f64, 2> {
+        SVector::from([
+            (index[0] - self.index[0]) as f64 * self.dx[0],
+            (index[1] - self.index[1]) as f64 * self.dx[1],
+        ])
+    }
+
     fn index_to_distance_squared(&self, index: &[i64; 2]) -> f64 {
         let mut diffs = [0; 2];
         for i in 0..2 {
@@ -243,25 +264,19 @@ impl<const D: usize, const N: usize>
         &mut self,
         boundaries: &[([i64; 2], BoundaryCondition<SVector<f64, N>>)],
     ) -> Result<(), CalcError> {
-        let mut new_gradient =
-            SVector::<SVector<f64, 2>, N>::from_element(SVector::from_element(0.0));
-        boundaries.iter().for_each(|(index, boundary_condition)| {
-            let extracellular_difference = match boundary_condition {
-                BoundaryCondition::Neumann(value) => *value,
-                BoundaryCondition::Dirichlet(value) => self.extracellular_concentrations - value,
-                BoundaryCondition::Value(value) => self.extracellular_concentrations - value,
-            };
-            let pointer = SVector::from([
-                self.index[0] as f64 - index[0] as f64,
-                self.index[1] as f64 - index[1] as f64,
-            ]);
-            let dist = pointer.norm();
-            let gradient = pointer.normalize() / dist;
-            new_gradient
-                .iter_mut()
-                .zip(extracellular_difference.into_iter())
-                .for_each(|(component, diff)| *component += *diff * gradient);
-        });
+        let neighbors = self.domain_boundaries.iter().chain(boundaries.iter()).map(
+            |(index, boundary_condition)| {
+                let displacement = self.index_to_displacement(index);
+                let concentrations = super::gradient::boundary_concentrations(
+                    &self.extracellular_concentrations,
+                    &displacement,
+                    boundary_condition,
+                );
+                (displacement, concentrations)
+            },
+        );
+        let new_gradient =
+            super::gradient::least_squares_gradient(&self.extracellular_concentrations, neighbors)?;
         self.extracellular_gradient = new_gradient;
         Ok(())
     }
@@ -557,6 +572,23 @@ impl CreatePlottingRoot for CartesianCuboid2Vertex {
 
         Ok(chart.plotting_area().clone())
     }
+
+    fn create_svg_root<'a, T>(
+        &self,
+        config: &ChartConfig,
+        filename: &'a T,
+    ) -> Result<
+        DrawingArea<SVGBackend<'a>, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+        DrawingError,
+    >
+    where
+        T: AsRef<std::path::Path> + ?Sized,
+    {
+        use plotters::drawing::IntoDrawingArea;
+        let size = config.chart_size(self.min, self.max);
+        let root = SVGBackend::new(filename, size).into_drawing_area();
+        config.build_chart(root, self.min, self.max, true)
+    }
 }
 
 #[cfg(test)]
@@ -76,6 +76,7 @@ pub struct CartesianCuboid<F, const D: usize> {
     max: SVector<F, D>,
     dx: SVector<F, D>,
     n_voxels: SVector<usize, D>,
+    periodic: bool,
     /// Seed from which all random numbers will be initially drawn
     pub rng_seed: u64,
 }
@@ -103,6 +104,86 @@ where
     pub fn get_n_voxels(&self) -> SVector<usize, D> {
         self.n_voxels.clone()
     }
+
+    /// Returns whether the boundaries of the domain are periodic
+    pub fn get_periodic(&self) -> bool {
+        self.periodic
+    }
+
+    /// Makes the boundaries of the domain periodic.
+    ///
+    /// Cells which leave the domain on one side re-enter it on the opposite side.
+    /// Voxels at opposite sides of the domain are neighbors such that cells also interact
+    /// across the boundary even if they belong to different subdomains.
+    /// Interactions are calculated with the closest image given by the [PeriodicMetric].
+    ///
+    /// ```
+    /// # use cellular_raza_building_blocks::CartesianCuboid;
+    /// let domain = CartesianCuboid::from_boundaries_and_n_voxels([0.0; 2], [10.0; 2], [5; 2])?
+    ///     .periodic(true);
+    /// assert!(domain.get_periodic());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn periodic(mut self, periodic: bool) -> Self {
+        self.periodic = periodic;
+        self
+    }
+}
+
+/// Bitmap images only show the domain while vector graphics are drawn with the full
+/// [ChartConfig].
+impl CreatePlottingRoot for CartesianCuboid<f64, 2> {
+    fn create_bitmap_root<'a, T>(
+        &self,
+        image_size: u32,
+        filename: &'a T,
+    ) -> Result<
+        plotters::prelude::DrawingArea<
+            plotters::prelude::BitMapBackend<'a>,
+            plotters::coord::cartesian::Cartesian2d<
+                plotters::coord::types::RangedCoordf64,
+                plotters::coord::types::RangedCoordf64,
+            >,
+        >,
+        DrawingError,
+    >
+    where
+        T: AsRef<std::path::Path> + ?Sized,
+    {
+        use plotters::drawing::IntoDrawingArea;
+        let config = ChartConfig {
+            margin: 0,
+            ..ChartConfig::new(image_size)
+        };
+        let (min, max) = (self.min.into(), self.max.into());
+        let size = config.chart_size(min, max);
+        let root = plotters::prelude::BitMapBackend::new(filename, size).into_drawing_area();
+        config.build_chart(root, min, max, false)
+    }
+
+    fn create_svg_root<'a, T>(
+        &self,
+        config: &ChartConfig,
+        filename: &'a T,
+    ) -> Result<
+        plotters::prelude::DrawingArea<
+            plotters::prelude::SVGBackend<'a>,
+            plotters::coord::cartesian::Cartesian2d<
+                plotters::coord::types::RangedCoordf64,
+                plotters::coord::types::RangedCoordf64,
+            >,
+        >,
+        DrawingError,
+    >
+    where
+        T: AsRef<std::path::Path> + ?Sized,
+    {
+        use plotters::drawing::IntoDrawingArea;
+        let (min, max) = (self.min.into(), self.max.into());
+        let size = config.chart_size(min, max);
+        let root = plotters::prelude::SVGBackend::new(filename, size).into_drawing_area();
+        config.build_chart(root, min, max, true)
+    }
 }
 
 impl<C, Ci, F, const D: usize> Domain<C, CartesianSubDomain<F, D>, Ci> for CartesianCuboid<F, D>
@@ -225,6 +306,7 @@ where
             max: max.into(),
             dx: dx.into(),
             n_voxels: n_voxels.into(),
+            periodic: false,
             rng_seed: 0,
         })
     }
@@ -259,6 +341,7 @@ where
             max: max.into(),
             dx,
             n_voxels: n_voxels.into(),
+            periodic: false,
             rng_seed: 0,
         })
     }
@@ -310,6 +393,37 @@ mod test_domain_setup {
         let _ = CartesianCuboid::from_boundaries_and_n_voxels(min, max, n_voxels).unwrap();
         // TODO add actual test case here
     }
+
+    #[test]
+    fn create_plotting_roots() {
+        use crate::CartesianCuboid;
+        use cellular_raza_concepts::{ChartConfig, CreatePlottingRoot};
+        let domain =
+            CartesianCuboid::from_boundaries_and_interaction_range([0.0; 2], [20.0, 10.0], 1.0)
+                .unwrap();
+        let dir = std::env::temp_dir().join(format!("cr_plotting_roots_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let path_svg = dir.join("domain.svg");
+        let config = ChartConfig {
+            axes: true,
+            axis_descriptions: Some(("x".into(), "y".into())),
+            ..ChartConfig::new(100)
+        };
+        let root = domain.create_svg_root(&config, &path_svg).unwrap();
+        root.present().unwrap();
+        drop(root);
+        let svg = std::fs::read_to_string(&path_svg).unwrap();
+        assert!(svg.contains("<text"));
+        assert!(svg.contains("width=\"250\""));
+
+        let path_png = dir.join("domain.png");
+        let root = domain.create_bitmap_root(100, &path_png).unwrap();
+        root.present().unwrap();
+        drop(root);
+        assert!(path_png.exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
 
 impl<F, const D: usize> CartesianCuboid<F, D>
@@ -421,6 +535,7 @@ pub struct CartesianSubDomain<F, const D: usize> {
     domain_min: SVector<F, D>,
     domain_max: SVector<F, D>,
     domain_n_voxels: SVector<usize, D>,
+    metric: Option<PeriodicMetric<F, D>>,
 }
 
 #[derive(Deserialize)]
@@ -434,6 +549,8 @@ struct __CartesianSubDomainSerde<F: 'static + Clone + core::fmt::Debug + Partial
     domain_min: SVector<F, D>,
     domain_max: SVector<F, D>,
     domain_n_voxels: SVector<usize, D>,
+    #[serde(default)]
+    periodic: bool,
 }
 
 impl<F, const D: usize> From<__CartesianSubDomainSerde<F, D>> for CartesianSubDomain<F, D>
@@ -441,6 +558,10 @@ where
     F: 'static + Clone + core::fmt::Debug + PartialEq,
 {
     fn from(s: __CartesianSubDomainSerde<F, D>) -> Self {
+        let metric = s.periodic.then(|| PeriodicMetric {
+            min: s.domain_min.clone(),
+            max: s.domain_max.clone(),
+        });
         CartesianSubDomain {
             min: s.min,
             max: s.max,
@@ -453,6 +574,7 @@ where
             domain_min: s.domain_min,
             domain_max: s.domain_max,
             domain_n_voxels: s.domain_n_voxels,
+            metric,
         }
     }
 }
@@ -465,7 +587,7 @@ where
     where
         S: serde::Serializer,
     {
-        let mut state = serializer.serialize_struct("CartesianSubDomain", 7)?;
+        let mut state = serializer.serialize_struct("CartesianSubDomain", 8)?;
         state.serialize_field("min", &self.min)?;
         state.serialize_field("max", &self.max)?;
         state.serialize_field("dx", &self.dx)?;
@@ -478,6 +600,7 @@ where
         state.serialize_field("domain_min", &self.domain_min)?;
         state.serialize_field("domain_max", &self.domain_max)?;
         state.serialize_field("domain_n_voxels", &self.domain_n_voxels)?;
+        state.serialize_field("periodic", &self.metric.is_some())?;
         state.end()
     }
 }
@@ -506,13 +629,14 @@ fn serialize_cartesiansubdomain() {
         domain_min: [-30.0, 10.0].into(),
         domain_max: [55.33, 22.38].into(),
         domain_n_voxels: [1, 2].into(),
+        metric: None,
     };
     // TODO finish this test
     use serde_test::{assert_de_tokens, assert_ser_tokens, Token};
     let tokens = [
         Token::Struct {
             name: "CartesianSubDomain",
-            len: 7,
+            len: 8,
         },
         // subdomain.min
         Token::Str("min"),
@@ -566,6 +690,9 @@ fn serialize_cartesiansubdomain() {
         Token::U64(subdomain.domain_n_voxels[0] as u64),
         Token::U64(subdomain.domain_n_voxels[1] as u64),
         Token::TupleEnd,
+        // Periodic boundaries
+        Token::Str("periodic"),
+        Token::Bool(false),
         Token::StructEnd,
     ];
     assert_ser_tokens(&subdomain, &tokens);
@@ -726,6 +853,10 @@ where
                 domain_min: self.min,
                 domain_max: self.max,
                 domain_n_voxels: self.n_voxels.clone(),
+                metric: self.periodic.then_some(PeriodicMetric {
+                    min: self.min,
+                    max: self.max,
+                }),
             };
             res.push((n_subdomain, subdomain, voxels));
         }
@@ -742,6 +873,10 @@ where
     F: num::Float,
 {
     fn apply_boundary(&self, pos: &mut Coord, vel: &mut Coord) -> Result<(), BoundaryError> {
+        // Cells re-enter periodic domains on the opposite side
+        if self.metric.is_some() {
+            return self.wrap_into_domain(pos, vel);
+        }
         let mut velocity: [F; D] = vel.clone().into();
         let mut position: [F; D] = pos.clone().into();
 
@@ -763,8 +898,12 @@ where
         }
 
         // If new position is still out of boundary return error
-        for i in 0..D {
-            if position[i] < self.domain_min[i] || position[i] > self.domain_max[i] {
+        for ((p, min), max) in position
+            .iter()
+            .zip(self.domain_min.iter())
+            .zip(self.domain_max.iter())
+        {
+            if p < min || p > max {
                 return Err(BoundaryError(format!(
                     "Particle is out of domain at position {:?}",
                     pos
@@ -777,6 +916,262 @@ where
         *vel = velocity.into();
         Ok(())
     }
+
+    fn clamp_to_domain(&self, pos: &mut Coord, vel: &mut Coord) -> Result<(), BoundaryError> {
+        let mut velocity: [F; D] = vel.clone().into();
+        let mut position: [F; D] = pos.clone().into();
+        for (((p, v), min), max) in position
+            .iter_mut()
+            .zip(velocity.iter_mut())
+            .zip(self.domain_min.iter())
+            .zip(self.domain_max.iter())
+        {
+            if *p < *min {
+                *v = v.abs();
+            }
+            if *p > *max {
+                *v = -v.abs();
+            }
+            *p = p.max(*min).min(*max);
+        }
+        *pos = position.into();
+        *vel = velocity.into();
+        Ok(())
+    }
+
+    fn wrap_into_domain(&self, pos: &mut Coord, _vel: &mut Coord) -> Result<(), BoundaryError> {
+        let mut position: [F; D] = pos.clone().into();
+        for ((p, min), max) in position
+            .iter_mut()
+            .zip(self.domain_min.iter())
+            .zip(self.domain_max.iter())
+        {
+            let length = *max - *min;
+            let shifted = *p - *min;
+            *p = *min + shifted - length * (shifted / length).floor();
+        }
+        *pos = position.into();
+        Ok(())
+    }
+
+    fn domain_metric(&self) -> Option<&dyn DomainMetric<Coord>> {
+        self.metric
+            .as_ref()
+            .map(|metric| metric as &dyn DomainMetric<Coord>)
+    }
+}
+
+impl<F, const D: usize> CartesianSubDomain<F, D>
+where
+    F: num::Float + core::fmt::Debug,
+{
+    fn check_contains<Coord>(&self, pos: &Coord) -> Result<(), CalcError>
+    where
+        Coord: Clone,
+        [F; D]: From<Coord>,
+    {
+        let position: [F; D] = pos.clone().into();
+        for i in 0..D {
+            if position[i] < self.min[i] || position[i] > self.max[i] {
+                return Err(CalcError(format!(
+                    "position {:?} is outside of the subdomain with boundaries {:?} {:?}",
+                    position, self.min, self.max
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The [CartesianSubDomain] does not store any extracellular fields.
+///
+/// Concentrations, neighbor values and border information are thus given by the unit type `()`
+/// and nothing is exchanged between subdomains.
+/// This allows to use the plain [CartesianCuboid] in simulations which include the
+/// `ReactionsExtra` aspect.
+/// Positions outside of the subdomain are rejected.
+/// Fields can be added by combining the subdomain with [CoupledFields](super::CoupledFields)
+/// as is done by the [DiffusionSubDomain](super::DiffusionSubDomain).
+impl<Coord, F, const D: usize> SubDomainReactions<Coord, (), F> for CartesianSubDomain<F, D>
+where
+    Coord: Clone,
+    [F; D]: From<Coord>,
+    F: num::Float + core::fmt::Debug,
+{
+    type NeighborValue = ();
+    type BorderInfo = ();
+
+    fn treat_increments<I, J>(&mut self, neighbors: I, sources: J) -> Result<(), CalcError>
+    where
+        I: IntoIterator<Item = Self::NeighborValue>,
+        J: IntoIterator<Item = (Coord, ())>,
+    {
+        let _ = neighbors;
+        for (pos, ()) in sources {
+            self.check_contains(&pos)?;
+        }
+        Ok(())
+    }
+
+    fn update_fluid_dynamics(&mut self, _dt: F) -> Result<(), CalcError> {
+        Ok(())
+    }
+
+    fn get_extracellular_at_pos(&self, pos: &Coord) -> Result<(), CalcError> {
+        self.check_contains(pos)
+    }
+
+    fn set_extracellular_at_pos(&mut self, pos: &Coord, _value: ()) -> Result<(), CalcError> {
+        self.check_contains(pos)
+    }
+
+    fn get_neighbor_value(&self, _border_info: ()) {}
+
+    fn get_border_info(&self) {}
+}
+
+#[test]
+fn cartesian_subdomain_reactions() {
+    use DomainCreateSubDomains;
+    let domain =
+        CartesianCuboid::from_boundaries_and_interaction_range([0.0; 2], [10.0; 2], 2.0).unwrap();
+    let mut subdomains: Vec<_> = domain
+        .create_subdomains(2.try_into().unwrap())
+        .unwrap()
+        .into_iter()
+        .map(|(_, subdomain, _)| subdomain)
+        .collect();
+    let inside = subdomains[1].get_min();
+    let outside = SVector::from([-1.0, 3.0]);
+    fn exchange<S>(sender: &S, receiver: &S) -> S::NeighborValue
+    where
+        S: SubDomainReactions<SVector<f64, 2>, (), f64>,
+    {
+        sender.get_neighbor_value(receiver.get_border_info())
+    }
+    exchange(&subdomains[0], &subdomains[1]);
+    let subdomain = &mut subdomains[1];
+    subdomain.treat_increments([()], [(inside, ())]).unwrap();
+    assert!(subdomain.treat_increments([], [(outside, ())]).is_err());
+    SubDomainReactions::<SVector<f64, 2>, (), f64>::update_fluid_dynamics(subdomain, 0.1).unwrap();
+    assert!(subdomain.get_extracellular_at_pos(&inside).is_ok());
+    assert!(subdomain.get_extracellular_at_pos(&outside).is_err());
+    assert!(subdomain.set_extracellular_at_pos(&outside, ()).is_err());
+}
+
+#[test]
+fn clamp_and_wrap_into_domain() {
+    use DomainCreateSubDomains;
+    let domain =
+        CartesianCuboid::from_boundaries_and_interaction_range([0.0; 2], [10.0; 2], 2.0).unwrap();
+    let (_, subdomain, _) = domain
+        .create_subdomains(1.try_into().unwrap())
+        .unwrap()
+        .into_iter()
+        .next()
+        .unwrap();
+    // This position can not be reflected back into the domain
+    let mut pos = SVector::from([-25.0, 3.0]);
+    let mut vel = SVector::from([-1.0, 1.0]);
+    assert!(subdomain.apply_boundary(&mut pos, &mut vel).is_err());
+
+    let (mut p, mut v) = (pos, vel);
+    subdomain.clamp_to_domain(&mut p, &mut v).unwrap();
+    assert_eq!(p, SVector::from([0.0, 3.0]));
+    assert_eq!(v, SVector::from([1.0, 1.0]));
+
+    let (mut p, mut v) = (pos, vel);
+    subdomain.wrap_into_domain(&mut p, &mut v).unwrap();
+    assert!((p - SVector::from([5.0, 3.0])).norm() < 1e-12);
+    assert_eq!(v, vel);
+}
+
+#[test]
+fn periodic_subdomains() {
+    use DomainCreateSubDomains;
+    let domain = CartesianCuboid::from_boundaries_and_n_voxels([0.0; 2], [10.0, 4.0], [5, 2])
+        .unwrap()
+        .periodic(true);
+    let subdomains: Vec<_> = domain
+        .create_subdomains(2.try_into().unwrap())
+        .unwrap()
+        .into_iter()
+        .map(|(_, subdomain, _)| subdomain)
+        .collect();
+    assert_eq!(subdomains.len(), 2);
+    let subdomain = &subdomains[0];
+
+    // Voxels at opposite sides are neighbors without duplicates along the short dimension
+    assert_eq!(
+        subdomain.get_neighbor_voxel_indices(&[0, 0]),
+        vec![[0, 1], [1, 0], [1, 1], [4, 0], [4, 1]]
+    );
+
+    // Cells leave the domain on one side and re-enter on the other one
+    let mut pos = SVector::from([-0.5, 4.5]);
+    let mut vel = SVector::from([-1.0, 1.0]);
+    subdomain.apply_boundary(&mut pos, &mut vel).unwrap();
+    assert!((pos - SVector::from([9.5, 0.5])).norm() < 1e-12);
+    assert_eq!(vel, SVector::from([-1.0, 1.0]));
+
+    let metric = SubDomainMechanics::<SVector<f64, 2>, _>::domain_metric(subdomain).unwrap();
+    let image = metric.nearest_image(&SVector::from([0.5, 1.0]), &SVector::from([9.5, 1.0]));
+    assert_eq!(image, SVector::from([-0.5, 1.0]));
+
+    // Periodicity is preserved when the subdomain is stored
+    let json = serde_json::to_string(subdomain).unwrap();
+    let loaded: CartesianSubDomain<f64, 2> = serde_json::from_str(&json).unwrap();
+    assert_eq!(&loaded, subdomain);
+}
+
+/// Periodic [DomainMetric] of a cuboid.
+///
+/// Every position is identified with all of its images shifted by multiples of the side lengths
+/// of the cuboid.
+/// Interactions are thus calculated between a cell and the closest image of its partner.
+/// This also applies to positions which are exchanged between subdomains since the receiving
+/// subdomain maps them onto the image next to each of its own cells.
+/// ```
+/// # use cellular_raza_building_blocks::PeriodicMetric;
+/// # use cellular_raza_concepts::DomainMetric;
+/// # use nalgebra::Vector2;
+/// let metric = PeriodicMetric {
+///     min: Vector2::from([0.0, 0.0]),
+///     max: Vector2::from([10.0, 5.0]),
+/// };
+/// let own_pos = Vector2::from([9.5, 2.0]);
+/// let ext_pos = Vector2::from([0.5, 4.5]);
+/// assert_eq!(metric.nearest_image(&own_pos, &ext_pos), Vector2::from([10.5, -0.5]));
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(bound(
+    serialize = "F: nalgebra::Scalar + Serialize",
+    deserialize = "F: nalgebra::Scalar + Deserialize<'de>"
+))]
+pub struct PeriodicMetric<F, const D: usize> {
+    /// Lower corner of the periodic cuboid
+    pub min: SVector<F, D>,
+    /// Upper corner of the periodic cuboid
+    pub max: SVector<F, D>,
+}
+
+impl<Coord, F, const D: usize> DomainMetric<Coord> for PeriodicMetric<F, D>
+where
+    Coord: Clone,
+    [F; D]: From<Coord>,
+    Coord: From<[F; D]>,
+    F: num::Float,
+{
+    fn nearest_image(&self, own_pos: &Coord, ext_pos: &Coord) -> Coord {
+        let own_pos: [F; D] = own_pos.clone().into();
+        let mut image: [F; D] = ext_pos.clone().into();
+        for i in 0..D {
+            let length = self.max[i] - self.min[i];
+            let diff = image[i] - own_pos[i];
+            image[i] = own_pos[i] + diff - length * (diff / length).round();
+        }
+        image.into()
+    }
 }
 
 impl<F, const D: usize> SubDomain for CartesianSubDomain<F, D> {
@@ -787,18 +1182,27 @@ impl<F, const D: usize> SubDomain for CartesianSubDomain<F, D> {
     }
 
     fn get_neighbor_voxel_indices(&self, voxel_index: &Self::VoxelIndex) -> Vec<Self::VoxelIndex> {
-        // Create the bounds for the following creation of all the voxel indices
-        let mut bounds = [[0; 2]; D];
-        for i in 0..D {
-            bounds[i] = [
-                (voxel_index[i] as i64 - 1).max(0) as usize,
-                (voxel_index[i] + 2).min(self.domain_n_voxels[i]),
-            ];
-        }
+        // Create the neighboring indices along every dimension
+        let indices = (0..D).map(|i| {
+            let n_voxels = self.domain_n_voxels[i] as i64;
+            let index = voxel_index[i] as i64;
+            let mut indices: Vec<_> = match self.metric.is_some() {
+                // Voxels at opposite sides of a periodic domain are neighbors
+                true => (index - 1..index + 2)
+                    .map(|j| j.rem_euclid(n_voxels) as usize)
+                    .collect(),
+                false => ((index - 1).max(0)..(index + 2).min(n_voxels))
+                    .map(|j| j as usize)
+                    .collect(),
+            };
+            // Domains with less than three voxels would otherwise contain duplicates
+            indices.sort();
+            indices.dedup();
+            indices
+        });
 
         // Create voxel indices
-        (0..D)
-            .map(|i| (bounds[i][0]..bounds[i][1]))
+        indices
             .multi_cartesian_product()
             .map(|ind_v| {
                 let mut res = [0; D];
@@ -1267,6 +1671,35 @@ macro_rules! implement_cartesian_cuboid_domain {
                 }
                 Ok(())
             }
+
+            fn clamp_to_domain(
+                &self,
+                pos: &mut SVector<$float_type, $d>,
+                velocity: &mut SVector<$float_type, $d>
+            ) -> Result<(), BoundaryError> {
+                for i in 0..$d {
+                    if pos[i] < self.domain_min[i] {
+                        velocity[i] = velocity[i].abs();
+                    }
+                    if pos[i] > self.domain_max[i] {
+                        velocity[i] = - velocity[i].abs();
+                    }
+                    pos[i] = pos[i].clamp(self.domain_min[i], self.domain_max[i]);
+                }
+                Ok(())
+            }
+
+            fn wrap_into_domain(
+                &self,
+                pos: &mut SVector<$float_type, $d>,
+                _velocity: &mut SVector<$float_type, $d>
+            ) -> Result<(), BoundaryError> {
+                for i in 0..$d {
+                    let length = self.domain_max[i] - self.domain_min[i];
+                    pos[i] = self.domain_min[i] + (pos[i] - self.domain_min[i]).rem_euclid(length);
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -1,7 +1,8 @@
 use cellular_raza_concepts::domain_old::*;
 use cellular_raza_concepts::reactions_old::Volume;
 use cellular_raza_concepts::{
-    BoundaryError, CalcError, CreatePlottingRoot, IndexError, RequestError,
+    BoundaryError, CalcError, ChartConfig, CreatePlottingRoot, DrawingError, IndexError,
+    RequestError,
 };
 
 use super::get_decomp_res;
@@ -17,7 +18,7 @@ use itertools::Itertools;
 use core::cmp::{max, min};
 use std::usize;
 
-use plotters::backend::BitMapBackend;
+use plotters::backend::{BitMapBackend, SVGBackend};
 use plotters::coord::cartesian::Cartesian2d;
 use plotters::coord::types::RangedCoordf64;
 use plotters::prelude::DrawingArea;
@@ -192,6 +193,18 @@ macro_rules! implement_cartesian_cuboid_voxel_fluid_mechanics{
             /// Get side lengths of voxel
             pub fn get_dx(&self) -> [f64; $d] {self.dx}
 
+            /// Sets the extracellular concentrations to the value of the given field at the
+            /// middle of the voxel.
+            ///
+            /// This is intended to be used as a voxel definition strategy which is applied when
+            /// decomposing the domain.
+            pub fn initialize_extracellular<C>(&mut self, field: &C)
+            where
+                C: super::ConcentrationField<SVector<f64, $d>, SVector<f64, N>>,
+            {
+                self.extracellular_concentrations = field.concentration_at(&self.middle.into());
+            }
+
             fn position_is_in_domain(&self, pos: &SVector<f64, $d>) -> Result<(), RequestError> {
                 match pos.iter().enumerate().any(|(i, p)| !(self.min[i] <= *p && *p <= self.max[i])) {
                     true => Err(RequestError(format!(
@@ -204,6 +217,11 @@ macro_rules! implement_cartesian_cuboid_voxel_fluid_mechanics{
                 }
             }
 
+            #[cfg(feature = "gradients")]
+            fn index_to_displacement(&self, index: &[i64; $d]) -> SVector<f64, $d> {
+                SVector::from([$((index[$k] - self.index[$k]) as f64 * self.dx[$k]),+])
+            }
+
             fn index_to_distance_squared(&self, index: &[i64; $d]) -> f64 {
                 let mut diffs = [0; $d];
                 for i in 0..$d {
@@ -250,30 +268,22 @@ macro_rules! implement_cartesian_cuboid_voxel_fluid_mechanics{
                 &mut self,
                 boundaries: &[([i64; $d], BoundaryCondition<SVector<f64, N>>)]
             ) -> Result<(), CalcError> {
-                let mut new_gradient = SVector::<SVector<f64, $d>, N>::from_element(
-                    SVector::<f64, $d>::from_element(0.0)
-                );
-                boundaries.iter()
-                    .for_each(|(index, boundary_condition)| {
-                        let extracellular_difference = match boundary_condition {
-                            BoundaryCondition::Neumann(value) => {*value},
-                            BoundaryCondition::Dirichlet(value) => {
-                                self.extracellular_concentrations-value
-                            },
-                            BoundaryCondition::Value(value) => {
-                                self.extracellular_concentrations-value
-                            },
-                        };
-                        let pointer = SVector::from(
-                            [$(self.index[$k] as f64 - index[$k] as f64),+]
+                let neighbors = self.domain_boundaries
+                    .iter()
+                    .chain(boundaries.iter())
+                    .map(|(index, boundary_condition)| {
+                        let displacement = self.index_to_displacement(index);
+                        let concentrations = super::gradient::boundary_concentrations(
+                            &self.extracellular_concentrations,
+                            &displacement,
+                            boundary_condition,
                         );
-                        let dist = pointer.norm();
-                        let gradient = pointer.normalize()/dist;
-                        new_gradient
-                            .iter_mut()
-                            .zip(extracellular_difference.into_iter())
-                            .for_each(|(component, diff)| *component += *diff*gradient);
+                        (displacement, concentrations)
                     });
+                let new_gradient = super::gradient::least_squares_gradient(
+                    &self.extracellular_concentrations,
+                    neighbors,
+                )?;
                 self.extracellular_gradient = new_gradient;
                 Ok(())
             }
@@ -541,4 +551,70 @@ impl CreatePlottingRoot for CartesianCuboid2 {
 
         Ok(root)
     }
+
+    fn create_svg_root<'a, T>(
+        &self,
+        config: &ChartConfig,
+        filename: &'a T,
+    ) -> Result<
+        DrawingArea<SVGBackend<'a>, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+        DrawingError,
+    >
+    where
+        T: AsRef<std::path::Path> + ?Sized,
+    {
+        use plotters::drawing::IntoDrawingArea;
+        let size = config.chart_size(self.min, self.max);
+        let root = SVGBackend::new(filename, size).into_drawing_area();
+        config.build_chart(root, self.min, self.max, true)
+    }
+}
+
+#[cfg(all(test, feature = "gradients"))]
+mod test_gradient {
+    use super::*;
+
+    fn linear_field(middle: [f64; 2]) -> SVector<f64, 1> {
+        SVector::from([2.0 * middle[0] - 0.5 * middle[1]])
+    }
+
+    fn build_voxel(index: [i64; 2], dx: [f64; 2]) -> CartesianCuboidVoxel2<1> {
+        let min = [index[0] as f64 * dx[0], index[1] as f64 * dx[1]];
+        let max = [min[0] + dx[0], min[1] + dx[1]];
+        let domain_boundaries = [[-1, 0], [-1, 1], [-1, -1], [0, -1], [1, -1]]
+            .into_iter()
+            .map(|i| [index[0] + i[0], index[1] + i[1]])
+            .filter(|i| i[0] < 0 || i[1] < 0)
+            .map(|i| {
+                let middle = [(i[0] as f64 + 0.5) * dx[0], (i[1] as f64 + 0.5) * dx[1]];
+                (i, BoundaryCondition::Dirichlet(linear_field(middle)))
+            })
+            .collect();
+        let mut voxel = CartesianCuboidVoxel2::new(min, max, index, domain_boundaries);
+        voxel.extracellular_concentrations = linear_field(voxel.get_middle());
+        voxel
+    }
+
+    #[test]
+    fn linear_field_anisotropic_voxels() {
+        let dx = [0.1, 3.0];
+        for index in [[4, 5], [0, 2], [0, 0]] {
+            let mut voxel = build_voxel(index, dx);
+            let boundaries: Vec<_> = (-1..2)
+                .flat_map(|i| (-1..2).map(move |j| [index[0] + i, index[1] + j]))
+                .filter(|i| *i != index && i[0] >= 0 && i[1] >= 0)
+                .map(|i| {
+                    let neighbor = build_voxel(i, dx);
+                    let value = neighbor
+                        .boundary_condition_to_neighbor_voxel(&index)
+                        .unwrap();
+                    (i, value)
+                })
+                .collect();
+            voxel.update_extracellular_gradient(&boundaries).unwrap();
+            let gradient = voxel.get_extracellular_gradient_at_point(&voxel.get_middle().into());
+            let difference = gradient.unwrap()[0] - SVector::from([2.0, -0.5]);
+            assert!(difference.norm() < 1e-10);
+        }
+    }
 }
@@ -0,0 +1,439 @@
+use nalgebra::SVector;
+use serde::{Deserialize, Serialize};
+
+use core::fmt::Display;
+use std::error::Error;
+
+/// Spatial profile which is used to initialize extracellular concentrations.
+///
+/// Instead of defining the concentrations of every voxel by hand, domains evaluate this field
+/// at the middle of each voxel while being decomposed.
+/// Every closure `Fn(&Pos) -> Conc` can be used directly.
+/// Multiple species can be combined by returning a vector of concentrations.
+/// ```
+/// # use cellular_raza_building_blocks::*;
+/// # use nalgebra::{SVector, Vector2};
+/// let blob = GaussianBlob {
+///     center: Vector2::from([5.0, 5.0]),
+///     width: 1.0,
+///     amplitude: 2.0,
+/// };
+/// let gradient = LinearGradient {
+///     origin: Vector2::zeros(),
+///     slope: Vector2::from([0.1, 0.0]),
+///     value_at_origin: 1.0,
+/// };
+/// let field = |pos: &Vector2<f64>| {
+///     SVector::from([blob.concentration_at(pos), gradient.concentration_at(pos)])
+/// };
+/// let concentrations = field.concentration_at(&Vector2::from([5.0, 5.0]));
+/// assert_eq!(concentrations, SVector::from([2.0, 1.5]));
+/// ```
+pub trait ConcentrationField<Pos, Conc> {
+    /// Concentrations at the given position
+    fn concentration_at(&self, pos: &Pos) -> Conc;
+}
+
+impl<Pos, Conc, F> ConcentrationField<Pos, Conc> for F
+where
+    F: Fn(&Pos) -> Conc,
+{
+    fn concentration_at(&self, pos: &Pos) -> Conc {
+        self(pos)
+    }
+}
+
+/// Gaussian profile around a center.
+///
+/// \\begin{equation}
+///     c(\vec{x}) = A\exp\left(-\frac{|\vec{x}-\vec{x}_0|^2}{2\sigma^2}\right)
+/// \\end{equation}
+///
+/// # Parameters
+/// | Symbol | Struct Field | Description |
+/// |:---:| --- | --- |
+/// | $\vec{x}_0$ | `center` | Center of the blob |
+/// | $\sigma$ | `width` | Standard deviation of the profile |
+/// | $A$ | `amplitude` | Concentration at the center |
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(bound(
+    serialize = "F: nalgebra::Scalar + Serialize",
+    deserialize = "F: nalgebra::Scalar + Deserialize<'de>"
+))]
+pub struct GaussianBlob<F, const D: usize> {
+    /// Center of the blob
+    pub center: SVector<F, D>,
+    /// Standard deviation of the profile
+    pub width: F,
+    /// Concentration at the center
+    pub amplitude: F,
+}
+
+impl<F, const D: usize> ConcentrationField<SVector<F, D>, F> for GaussianBlob<F, D>
+where
+    F: nalgebra::RealField + Copy,
+{
+    fn concentration_at(&self, pos: &SVector<F, D>) -> F {
+        let two = F::one() + F::one();
+        let dist_squared = (pos - self.center).norm_squared();
+        self.amplitude * (-dist_squared / (two * self.width.powi(2))).exp()
+    }
+}
+
+/// Concentrations which change linearly in space.
+///
+/// \\begin{equation}
+///     c(\vec{x}) = c_0 + \vec{s}\cdot(\vec{x}-\vec{x}_0)
+/// \\end{equation}
+///
+/// # Parameters
+/// | Symbol | Struct Field | Description |
+/// |:---:| --- | --- |
+/// | $\vec{x}_0$ | `origin` | Reference point of the gradient |
+/// | $\vec{s}$ | `slope` | Gradient of the concentrations |
+/// | $c_0$ | `value_at_origin` | Concentration at the reference point |
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(bound(
+    serialize = "F: nalgebra::Scalar + Serialize",
+    deserialize = "F: nalgebra::Scalar + Deserialize<'de>"
+))]
+pub struct LinearGradient<F, const D: usize> {
+    /// Reference point of the gradient
+    pub origin: SVector<F, D>,
+    /// Gradient of the concentrations
+    pub slope: SVector<F, D>,
+    /// Concentration at the reference point
+    pub value_at_origin: F,
+}
+
+impl<F, const D: usize> ConcentrationField<SVector<F, D>, F> for LinearGradient<F, D>
+where
+    F: nalgebra::RealField + Copy,
+{
+    fn concentration_at(&self, pos: &SVector<F, D>) -> F {
+        self.value_at_origin + self.slope.dot(&(pos - self.origin))
+    }
+}
+
+/// Errors which can occur while loading a [GridField] from a `.npy` file.
+#[derive(Debug)]
+pub enum NpyImportError {
+    /// Reading the underlying file failed.
+    IoError(std::io::Error),
+    /// The file does not start with a valid `.npy` header.
+    InvalidHeader(String),
+    /// Only little-endian `f4` and `f8` arrays in C-order are supported.
+    UnsupportedFormat(String),
+    /// The stored array does not match the number of spatial dimensions.
+    ShapeMismatch {
+        /// Number of spatial dimensions of the [GridField]
+        expected: usize,
+        /// Shape of the stored array
+        shape: Vec<usize>,
+    },
+}
+
+impl Display for NpyImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            NpyImportError::IoError(e) => write!(f, "{e}"),
+            NpyImportError::InvalidHeader(message) => write!(f, "invalid npy header: {message}"),
+            NpyImportError::UnsupportedFormat(format) => {
+                write!(f, "unsupported npy format: {format}")
+            }
+            NpyImportError::ShapeMismatch { expected, shape } => write!(
+                f,
+                "expected array with {expected} dimensions but got shape {shape:?}"
+            ),
+        }
+    }
+}
+
+impl Error for NpyImportError {}
+
+impl From<std::io::Error> for NpyImportError {
+    fn from(err: std::io::Error) -> Self {
+        NpyImportError::IoError(err)
+    }
+}
+
+impl From<NpyImportError> for cellular_raza_concepts::SetupError {
+    fn from(err: NpyImportError) -> Self {
+        cellular_raza_concepts::SetupError(format!("{err}"))
+    }
+}
+
+/// Concentrations given on a regular grid which spans the rectangle between `min` and `max`.
+///
+/// The value of the grid cell which contains the requested position is returned.
+/// Positions outside of the grid obtain the value of the nearest cell.
+/// Values are stored in C-order, meaning that the last index changes fastest.
+/// ```
+/// # use cellular_raza_building_blocks::*;
+/// # use nalgebra::Vector2;
+/// let grid = GridField::new([0.0; 2], [2.0, 3.0], [2, 3], vec![0., 1., 2., 3., 4., 5.])?;
+/// assert_eq!(grid.concentration_at(&Vector2::from([0.5, 2.5])), 2.0);
+/// assert_eq!(grid.concentration_at(&Vector2::from([1.5, 0.5])), 3.0);
+/// assert_eq!(grid.concentration_at(&Vector2::from([10.0, -1.0])), 3.0);
+/// # Ok::<(), NpyImportError>(())
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct GridField<const D: usize> {
+    min: SVector<f64, D>,
+    max: SVector<f64, D>,
+    shape: SVector<usize, D>,
+    values: Vec<f64>,
+}
+
+impl<const D: usize> GridField<D> {
+    /// Constructs a new [GridField] and checks that the number of values matches the shape.
+    pub fn new(
+        min: [f64; D],
+        max: [f64; D],
+        shape: [usize; D],
+        values: Vec<f64>,
+    ) -> Result<Self, NpyImportError> {
+        if shape.iter().product::<usize>() != values.len() || shape.contains(&0) {
+            return Err(NpyImportError::ShapeMismatch {
+                expected: D,
+                shape: shape.to_vec(),
+            });
+        }
+        Ok(Self {
+            min: min.into(),
+            max: max.into(),
+            shape: shape.into(),
+            values,
+        })
+    }
+
+    /// Loads the values from a `.npy` file as written by `numpy.save`.
+    pub fn from_npy(
+        path: impl AsRef<std::path::Path>,
+        min: [f64; D],
+        max: [f64; D],
+    ) -> Result<Self, NpyImportError> {
+        let bytes = std::fs::read(path)?;
+        Self::from_npy_bytes(&bytes, min, max)
+    }
+
+    /// Similar to [from_npy](GridField::from_npy) but parses the contents of the file directly.
+    pub fn from_npy_bytes(
+        bytes: &[u8],
+        min: [f64; D],
+        max: [f64; D],
+    ) -> Result<Self, NpyImportError> {
+        let (descr, shape, data) = parse_npy(bytes)?;
+        let shape: [usize; D] = shape
+            .clone()
+            .try_into()
+            .map_err(|_| NpyImportError::ShapeMismatch { expected: D, shape })?;
+        let n_values = shape.iter().product::<usize>();
+        let values = match descr.as_str() {
+            "<f8" => data
+                .chunks_exact(8)
+                .map(|c| f64::from_le_bytes(c.try_into().unwrap()))
+                .collect::<Vec<_>>(),
+            "<f4" => data
+                .chunks_exact(4)
+                .map(|c| f32::from_le_bytes(c.try_into().unwrap()) as f64)
+                .collect::<Vec<_>>(),
+            _ => return Err(NpyImportError::UnsupportedFormat(descr)),
+        };
+        if values.len() < n_values {
+            return Err(NpyImportError::InvalidHeader(format!(
+                "expected {n_values} values but file only contains {}",
+                values.len()
+            )));
+        }
+        Self::new(min, max, shape, values[..n_values].to_vec())
+    }
+
+    /// Number of grid cells in every dimension
+    pub fn shape(&self) -> [usize; D] {
+        self.shape.into()
+    }
+}
+
+impl<const D: usize> ConcentrationField<SVector<f64, D>, f64> for GridField<D> {
+    fn concentration_at(&self, pos: &SVector<f64, D>) -> f64 {
+        let mut plain_index = 0;
+        for i in 0..D {
+            let dx = (self.max[i] - self.min[i]) / self.shape[i] as f64;
+            let index = ((pos[i] - self.min[i]) / dx).floor().max(0.0) as usize;
+            plain_index = plain_index * self.shape[i] + index.min(self.shape[i] - 1);
+        }
+        self.values[plain_index]
+    }
+}
+
+/// Splits a `.npy` file into its data type, shape and raw data.
+fn parse_npy(bytes: &[u8]) -> Result<(String, Vec<usize>, &[u8]), NpyImportError> {
+    let invalid = |message: &str| NpyImportError::InvalidHeader(message.to_owned());
+    if bytes.len() < 10 || &bytes[..6] != b"\x93NUMPY" {
+        return Err(invalid("missing magic string"));
+    }
+    let (header_len, header_start) = match bytes[6] {
+        1 => (u16::from_le_bytes([bytes[8], bytes[9]]) as usize, 10),
+        2 | 3 if bytes.len() >= 12 => (
+            u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]) as usize,
+            12,
+        ),
+        _ => return Err(invalid("unknown version")),
+    };
+    let header = bytes
+        .get(header_start..header_start + header_len)
+        .and_then(|header| std::str::from_utf8(header).ok())
+        .ok_or(invalid("header is not readable"))?;
+
+    // Extracts the value of the given key in the header dictionary
+    let value_of = |key: &str| -> Result<&str, NpyImportError> {
+        let start = header
+            .find(&format!("'{key}':"))
+            .ok_or(invalid(&format!("missing key {key}")))?
+            + key.len()
+            + 3;
+        Ok(header[start..].trim_start())
+    };
+    let descr = value_of("descr")?
+        .split('\'')
+        .nth(1)
+        .ok_or(invalid("descr"))?
+        .to_owned();
+    if !value_of("fortran_order")?.starts_with("False") {
+        return Err(NpyImportError::UnsupportedFormat(
+            "fortran_order".to_owned(),
+        ));
+    }
+    let shape = value_of("shape")?;
+    let shape = shape
+        .strip_prefix('(')
+        .and_then(|shape| shape.split(')').next())
+        .ok_or(invalid("shape"))?
+        .split(',')
+        .map(|n| n.trim())
+        .filter(|n| !n.is_empty())
+        .map(|n| n.parse::<usize>().map_err(|_| invalid("shape")))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((descr, shape, &bytes[header_start + header_len..]))
+}
+
+#[cfg(test)]
+mod test_concentration_field {
+    use super::*;
+    use nalgebra::Vector2;
+
+    fn npy_bytes(descr: &str, shape: &str, data: &[u8]) -> Vec<u8> {
+        let mut header =
+            format!("{{'descr': '{descr}', 'fortran_order': False, 'shape': {shape}, }}");
+        // Header is padded with spaces and terminated by a newline
+        while (10 + header.len() + 1) % 64 != 0 {
+            header.push(' ');
+        }
+        header.push('\n');
+        let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+        bytes.extend((header.len() as u16).to_le_bytes());
+        bytes.extend(header.as_bytes());
+        bytes.extend(data);
+        bytes
+    }
+
+    #[test]
+    fn gaussian_blob() {
+        let blob = GaussianBlob {
+            center: Vector2::from([1.0, 1.0]),
+            width: 2.0,
+            amplitude: 3.0,
+        };
+        assert_eq!(blob.concentration_at(&Vector2::from([1.0, 1.0])), 3.0);
+        let value = blob.concentration_at(&Vector2::from([3.0, 1.0]));
+        assert!((value - 3.0 * (-0.5f64).exp()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn load_npy_f8() {
+        let data: Vec<u8> = (0..6)
+            .flat_map(|i| (i as f64 * 0.5).to_le_bytes())
+            .collect();
+        let bytes = npy_bytes("<f8", "(3, 2)", &data);
+        let grid = GridField::from_npy_bytes(&bytes, [0.0; 2], [3.0, 1.0]).unwrap();
+        assert_eq!(grid.shape(), [3, 2]);
+        assert_eq!(grid.concentration_at(&Vector2::from([0.1, 0.9])), 0.5);
+        assert_eq!(grid.concentration_at(&Vector2::from([2.9, 0.1])), 2.0);
+    }
+
+    #[test]
+    fn load_npy_f4_1d() {
+        let data: Vec<u8> = [1.0f32, 2.0, 4.0]
+            .into_iter()
+            .flat_map(|x| x.to_le_bytes())
+            .collect();
+        let bytes = npy_bytes("<f4", "(3,)", &data);
+        let grid = GridField::<1>::from_npy_bytes(&bytes, [0.0], [3.0]).unwrap();
+        let values: Vec<_> = [0.5, 1.5, 2.5]
+            .map(|x| grid.concentration_at(&[x].into()))
+            .to_vec();
+        assert_eq!(values, vec![1.0, 2.0, 4.0]);
+    }
+
+    #[test]
+    fn reject_invalid_npy() {
+        let bytes = npy_bytes("<f8", "(2, 2)", &[0; 32]);
+        assert!(matches!(
+            GridField::<1>::from_npy_bytes(&bytes, [0.0], [1.0]),
+            Err(NpyImportError::ShapeMismatch { .. })
+        ));
+        let bytes = npy_bytes("<i8", "(2,)", &[0; 16]);
+        assert!(matches!(
+            GridField::<1>::from_npy_bytes(&bytes, [0.0], [1.0]),
+            Err(NpyImportError::UnsupportedFormat(_))
+        ));
+        let bytes = npy_bytes("<f8", "(4,)", &[0; 16]);
+        assert!(GridField::<1>::from_npy_bytes(&bytes, [0.0], [1.0]).is_err());
+        assert!(GridField::<1>::from_npy_bytes(b"not a file", [0.0], [1.0]).is_err());
+    }
+
+    #[test]
+    fn initialize_voxels_at_decomposition() {
+        use crate::cartesian_cuboid_n_old::*;
+        use cellular_raza_concepts::domain_old::Domain;
+        use cellular_raza_concepts::{Position, Velocity};
+
+        #[derive(Clone)]
+        struct Agent(Vector2<f64>);
+        impl Position<Vector2<f64>> for Agent {
+            fn pos(&self) -> Vector2<f64> {
+                self.0
+            }
+            fn set_pos(&mut self, pos: &Vector2<f64>) {
+                self.0 = *pos;
+            }
+        }
+        impl Velocity<Vector2<f64>> for Agent {
+            fn velocity(&self) -> Vector2<f64> {
+                Vector2::zeros()
+            }
+            fn set_velocity(&mut self, _velocity: &Vector2<f64>) {}
+        }
+
+        let domain =
+            CartesianCuboid2::from_boundaries_and_n_voxels([0.0; 2], [4.0, 2.0], [4, 2]).unwrap();
+        let gradient = LinearGradient {
+            origin: Vector2::zeros(),
+            slope: Vector2::from([1.0, 10.0]),
+            value_at_origin: 0.0,
+        };
+        let field = |pos: &Vector2<f64>| SVector::from([gradient.concentration_at(pos)]);
+        let regions = <CartesianCuboid2 as Domain<Agent, _, CartesianCuboidVoxel2<1>>>::
+            generate_contiguous_multi_voxel_regions(&domain, 2)
+            .unwrap();
+        for (index, mut voxel) in regions.into_iter().flatten() {
+            voxel.initialize_extracellular(&field);
+            let expected = index[0] as f64 + 0.5 + 10.0 * (index[1] as f64 + 0.5);
+            assert_eq!(
+                voxel.extracellular_concentrations,
+                SVector::from([expected])
+            );
+        }
+    }
+}
@@ -0,0 +1,469 @@
+use cellular_raza_concepts::*;
+use nalgebra::SVector;
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeMap;
+
+use super::{
+    CartesianCuboid, CartesianSubDomain, ContinuumField, CoupledFields, DosingSchedule,
+    SubDomainVoxels,
+};
+
+/// Concentration of a single diffusing species such as oxygen or a drug.
+///
+/// The concentration $u$ follows
+/// \\begin{equation}
+///     \partial_t u = D\Delta u - \lambda u + \frac{1}{V}\sum\limits_{j} q_j
+/// \\end{equation}
+/// where the sum runs over all cells $j$ within a voxel of volume $V$ and $q_j$ is the amount
+/// per time which they secrete (positive) or take up (negative).
+/// The Laplacian is discretized by central differences and integrated by the explicit Euler
+/// method which is only stable for sufficiently small time increments (see [max_stable_dt]).
+/// Negative concentrations which may be caused by the uptake of cells are set to zero.
+///
+/// At the border of the domain, the concentration either takes a fixed `boundary_value` or the
+/// border is impermeable if no value is given.
+/// Alternatively, the value at the border can follow a [DosingSchedule] over time which takes
+/// precedence over the fixed `boundary_value`.
+/// Since subdomains are not informed about the absolute time, the field keeps track of the
+/// time which has passed since its creation.
+/// Concentrations of voxels at the border of the subdomain are exchanged with neighboring
+/// subdomains in every step.
+///
+/// [max_stable_dt]: crate::max_stable_dt
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DiffusionField<const D: usize> {
+    voxels: SubDomainVoxels<D>,
+    halo_indices: Vec<usize>,
+    values: Vec<f64>,
+    #[serde(skip)]
+    sources: Vec<f64>,
+    #[serde(skip)]
+    halo: BTreeMap<usize, f64>,
+    /// Diffusion constant $D$
+    pub diffusion_constant: f64,
+    /// Rate $\lambda$ of the decay of the species
+    pub decay_rate: f64,
+    /// Fixed concentration at the border of the domain
+    pub boundary_value: Option<f64>,
+    /// Time-dependent concentration at the border of the domain
+    pub dosing: Option<DosingSchedule>,
+    time: f64,
+}
+
+impl<const D: usize> DiffusionField<D> {
+    /// Evaluates the given function at the center of every voxel to obtain the initial
+    /// concentrations.
+    pub fn new(
+        subdomain: &CartesianSubDomain<f64, D>,
+        diffusion_constant: f64,
+        decay_rate: f64,
+        boundary_value: Option<f64>,
+        initial_value: impl Fn(&SVector<f64, D>) -> f64,
+    ) -> Self {
+        let voxels = SubDomainVoxels::new(subdomain);
+        let values = voxels
+            .get_voxel_centers()
+            .iter()
+            .map(initial_value)
+            .collect();
+        let mut field = Self {
+            voxels,
+            halo_indices: Vec::new(),
+            values,
+            sources: Vec::new(),
+            halo: BTreeMap::new(),
+            diffusion_constant,
+            decay_rate,
+            boundary_value,
+            dosing: None,
+            time: 0.0,
+        };
+        let mut halo_indices: Vec<_> = field
+            .voxels
+            .get_plain_indices()
+            .iter()
+            .flat_map(|plain_index| field.neighbors(*plain_index))
+            .flatten()
+            .filter(|plain_index| field.own_position(*plain_index).is_none())
+            .collect();
+        halo_indices.sort();
+        halo_indices.dedup();
+        field.halo_indices = halo_indices;
+        field
+    }
+
+    /// Plain indices of the lower and upper neighbors along every dimension.
+    ///
+    /// Neighbors outside of the domain are [None].
+    fn neighbors(&self, plain_index: usize) -> Vec<Option<usize>> {
+        let n_voxels = self.voxels.get_domain_n_voxels();
+        let mut stride = 1;
+        let mut neighbors = Vec::with_capacity(2 * D);
+        for i in (0..D).rev() {
+            let index = (plain_index / stride) % n_voxels[i];
+            neighbors.push((index > 0).then(|| plain_index - stride));
+            neighbors.push((index + 1 < n_voxels[i]).then(|| plain_index + stride));
+            stride *= n_voxels[i];
+        }
+        neighbors
+    }
+
+    fn own_position(&self, plain_index: usize) -> Option<usize> {
+        self.voxels
+            .get_plain_indices()
+            .binary_search(&plain_index)
+            .ok()
+    }
+
+    /// Concentration of the voxel which contains the given position
+    pub fn get_concentration_at(&self, pos: &SVector<f64, D>) -> Result<f64, CalcError> {
+        Ok(self.values[self.voxels.position_of(pos)?])
+    }
+
+    /// Concentrations of all voxels of this subdomain together with their voxel index
+    pub fn get_concentrations(&self) -> Vec<([usize; D], f64)> {
+        self.voxels
+            .get_voxel_indices()
+            .into_iter()
+            .zip(self.values.iter().copied())
+            .collect()
+    }
+
+    /// Time which has passed since the creation of the field
+    pub fn get_time(&self) -> f64 {
+        self.time
+    }
+
+    /// Concentration at the border of the domain at the current time or [None] if the border
+    /// is impermeable
+    pub fn get_boundary_value(&self) -> Option<f64> {
+        match &self.dosing {
+            Some(schedule) => Some(schedule.concentration_at(self.time)),
+            None => self.boundary_value,
+        }
+    }
+
+    /// Total amount of the species within this subdomain
+    pub fn get_total_amount(&self) -> f64 {
+        self.values.iter().sum::<f64>() * self.voxels.get_dx().product()
+    }
+}
+
+impl<const D: usize> FieldNorm for DiffusionField<D> {
+    fn squared_field_norm(&self) -> f64 {
+        self.values.iter().map(|value| value.powi(2)).sum()
+    }
+}
+
+impl<const D: usize> ContinuumField<SVector<f64, D>> for DiffusionField<D> {
+    type Value = f64;
+    type NeighborValue = Vec<(usize, f64)>;
+    type BorderInfo = Vec<usize>;
+
+    fn couple_agent(&mut self, pos: &SVector<f64, D>, increment: f64) -> Result<(), CalcError> {
+        let n = self.voxels.position_of(pos)?;
+        if self.sources.len() != self.values.len() {
+            self.sources = vec![0.0; self.values.len()];
+        }
+        self.sources[n] += increment / self.voxels.get_dx().product();
+        Ok(())
+    }
+
+    fn couple_neighbor(&mut self, neighbor: Vec<(usize, f64)>) -> Result<(), CalcError> {
+        self.halo.extend(neighbor);
+        Ok(())
+    }
+
+    fn update(&mut self, dt: f64) -> Result<(), CalcError> {
+        let dx = self.voxels.get_dx();
+        let inverse_squares: Vec<_> = (0..D).rev().map(|i| dx[i].powi(-2)).collect();
+        let boundary_value = self.get_boundary_value();
+        let values = self
+            .voxels
+            .get_plain_indices()
+            .iter()
+            .enumerate()
+            .map(|(n, plain_index)| {
+                let u = self.values[n];
+                let mut laplace = 0.0;
+                for (k, neighbor) in self.neighbors(*plain_index).into_iter().enumerate() {
+                    let v = match neighbor {
+                        Some(m) => match self.own_position(m) {
+                            Some(m) => self.values[m],
+                            None => *self.halo.get(&m).ok_or(CalcError(format!(
+                                "concentration of neighboring voxel {m} was not received"
+                            )))?,
+                        },
+                        None => boundary_value.unwrap_or(u),
+                    };
+                    laplace += (v - u) * inverse_squares[k / 2];
+                }
+                let source = self.sources.get(n).copied().unwrap_or(0.0);
+                let du = self.diffusion_constant * laplace - self.decay_rate * u + source;
+                Ok((u + dt * du).max(0.0))
+            })
+            .collect::<Result<Vec<_>, CalcError>>()?;
+        self.values = values;
+        self.sources.iter_mut().for_each(|s| *s = 0.0);
+        self.halo.clear();
+        self.time += dt;
+        Ok(())
+    }
+
+    fn get_value_at(&self, pos: &SVector<f64, D>) -> Result<f64, CalcError> {
+        self.get_concentration_at(pos)
+    }
+
+    fn set_value_at(&mut self, pos: &SVector<f64, D>, value: f64) -> Result<(), CalcError> {
+        let n = self.voxels.position_of(pos)?;
+        self.values[n] = value;
+        Ok(())
+    }
+
+    fn get_neighbor_value(&self, border_info: Vec<usize>) -> Vec<(usize, f64)> {
+        border_info
+            .into_iter()
+            .filter_map(|m| self.own_position(m).map(|n| (m, self.values[n])))
+            .collect()
+    }
+
+    fn get_border_info(&self) -> Vec<usize> {
+        self.halo_indices.clone()
+    }
+}
+
+/// [CartesianCuboid] with a single diffusing species given by the [DiffusionField].
+///
+/// Cells take up or secrete the species via the [ReactionsExtra] trait with the extracellular
+/// concentration given as `f64`.
+/// Simulations thus need to include the `ReactionsExtra` aspect.
+///
+/// ```
+/// # use cellular_raza_building_blocks::*;
+/// let cuboid =
+///     CartesianCuboid::from_boundaries_and_interaction_range([0.0; 2], [100.0; 2], 10.0)?;
+/// // Oxygen is supplied from the border of the domain
+/// let domain = DiffusionCuboid {
+///     cuboid,
+///     diffusion_constant: 20.0,
+///     decay_rate: 0.0,
+///     boundary_value: Some(1.0),
+///     dosing: None,
+///     initial_value: 1.0,
+/// };
+/// # Ok::<(), cellular_raza_concepts::BoundaryError>(())
+/// ```
+#[derive(Clone, Debug, Domain)]
+pub struct DiffusionCuboid<const D: usize> {
+    /// Underlying domain which sorts cells into voxels
+    #[DomainRngSeed]
+    #[SortCells]
+    pub cuboid: CartesianCuboid<f64, D>,
+    /// Diffusion constant of the species
+    pub diffusion_constant: f64,
+    /// Rate of the decay of the species
+    pub decay_rate: f64,
+    /// Fixed concentration at the border of the domain
+    pub boundary_value: Option<f64>,
+    /// Time-dependent concentration at the border of the domain which overrides the
+    /// `boundary_value`
+    pub dosing: Option<DosingSchedule>,
+    /// Initial concentration in all voxels
+    pub initial_value: f64,
+}
+
+/// Subdomain of the [DiffusionCuboid].
+#[derive(Clone, Debug, Serialize, SubDomain)]
+pub struct DiffusionSubDomain<const D: usize> {
+    /// Subdomain of the underlying [CartesianCuboid]
+    #[Base]
+    #[SortCells]
+    #[Mechanics]
+    pub subdomain: CartesianSubDomain<f64, D>,
+    /// Concentration of the diffusing species
+    #[Reactions]
+    pub field: CoupledFields<DiffusionField<D>>,
+}
+
+impl<const D: usize> FieldNorm for DiffusionSubDomain<D> {
+    fn squared_field_norm(&self) -> f64 {
+        self.field.squared_field_norm()
+    }
+}
+
+impl<const D: usize> DomainCreateSubDomains<DiffusionSubDomain<D>> for DiffusionCuboid<D> {
+    type VoxelIndex = [usize; D];
+    type SubDomainIndex = usize;
+
+    fn create_subdomains(
+        &self,
+        n_subdomains: core::num::NonZeroUsize,
+    ) -> Result<
+        impl IntoIterator<
+            Item = (
+                Self::SubDomainIndex,
+                DiffusionSubDomain<D>,
+                Vec<Self::VoxelIndex>,
+            ),
+        >,
+        DecomposeError,
+    > {
+        Ok(self
+            .cuboid
+            .create_subdomains(n_subdomains)?
+            .into_iter()
+            .map(|(index, subdomain, voxels)| {
+                let mut field = DiffusionField::new(
+                    &subdomain,
+                    self.diffusion_constant,
+                    self.decay_rate,
+                    self.boundary_value,
+                    |_| self.initial_value,
+                );
+                field.dosing = self.dosing.clone();
+                let subdomain = DiffusionSubDomain {
+                    subdomain,
+                    field: CoupledFields { fields: field },
+                };
+                (index, subdomain, voxels)
+            }))
+    }
+}
+
+#[cfg(test)]
+mod test_diffusion_field {
+    use super::*;
+    use nalgebra::Vector2;
+
+    fn fields(
+        n_subdomains: usize,
+        boundary_value: Option<f64>,
+    ) -> Result<Vec<DiffusionField<2>>, Box<dyn std::error::Error>> {
+        let domain = DiffusionCuboid {
+            cuboid: CartesianCuboid::from_boundaries_and_interaction_range(
+                [0.0; 2],
+                [50.0, 30.0],
+                5.0,
+            )?,
+            diffusion_constant: 2.0,
+            decay_rate: 0.0,
+            boundary_value,
+            dosing: None,
+            initial_value: 1.0,
+        };
+        let fields = domain
+            .create_subdomains(n_subdomains.try_into()?)?
+            .into_iter()
+            .map(|(_, subdomain, _)| subdomain.field.fields)
+            .collect();
+        Ok(fields)
+    }
+
+    /// Performs one step for all fields while a cell secretes at the given position
+    fn step(fields: &mut [DiffusionField<2>], source: &Vector2<f64>) -> Result<(), CalcError> {
+        let neighbor_values: Vec<Vec<_>> = fields
+            .iter()
+            .map(|field| {
+                fields
+                    .iter()
+                    .map(|other| other.get_neighbor_value(field.get_border_info()))
+                    .collect()
+            })
+            .collect();
+        for (field, neighbors) in fields.iter_mut().zip(neighbor_values) {
+            for neighbor in neighbors {
+                field.couple_neighbor(neighbor)?;
+            }
+            if field.voxels.position_of(source).is_ok() {
+                field.couple_agent(source, 5.0)?;
+            }
+            field.update(0.5)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn subdomains_agree_with_single_domain() -> Result<(), Box<dyn std::error::Error>> {
+        let source = Vector2::from([12.0, 17.0]);
+        let mut single = fields(1, None)?;
+        let mut multiple = fields(4, None)?;
+        assert!(multiple
+            .iter()
+            .all(|field| !field.get_border_info().is_empty()));
+        for _ in 0..100 {
+            step(&mut single, &source)?;
+            step(&mut multiple, &source)?;
+        }
+        let mut combined: Vec<_> = multiple
+            .iter()
+            .flat_map(|field| field.get_concentrations())
+            .collect();
+        combined.sort_by_key(|(index, _)| *index);
+        for ((i1, u1), (i2, u2)) in single[0].get_concentrations().into_iter().zip(combined) {
+            assert_eq!(i1, i2);
+            assert!((u1 - u2).abs() < 1e-12);
+        }
+        // The secreted amount stays within the impermeable domain
+        let total: f64 = multiple.iter().map(|field| field.get_total_amount()).sum();
+        assert!((total - 1500.0 - 100.0 * 0.5 * 5.0).abs() < 1e-9);
+        // Subdomains contribute to the norm of the whole field
+        let squared_norm: f64 = multiple
+            .iter()
+            .map(|field| field.squared_field_norm())
+            .sum();
+        assert!((squared_norm - single[0].squared_field_norm()).abs() < 1e-9);
+        // The concentration decreases with the distance to the source
+        let field = &single[0];
+        assert!(
+            field.get_concentration_at(&source)?
+                > field.get_concentration_at(&[40.0, 25.0].into())?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn relaxation_towards_boundary_value() -> Result<(), Box<dyn std::error::Error>> {
+        let mut fields = fields(2, Some(0.0))?;
+        for _ in 0..2_000 {
+            step(&mut fields, &[-10.0; 2].into())?;
+        }
+        for field in fields.iter() {
+            assert!(field.values.iter().all(|u| *u >= 0.0 && *u < 1e-3));
+        }
+        // Missing values of neighbors are detected
+        assert!(fields[0].update(0.5).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn boundary_follows_dosing_schedule() -> Result<(), Box<dyn std::error::Error>> {
+        let mut schedule = DosingSchedule::new(crate::PharmacokineticProfile::ExponentialDecay {
+            elimination_rate: 0.01,
+        });
+        schedule.add_dose(25.0, 2.0);
+        let mut fields = fields(2, Some(1.0))?;
+        fields
+            .iter_mut()
+            .for_each(|field| field.dosing = Some(schedule.clone()));
+        let total = |fields: &[DiffusionField<2>]| -> f64 {
+            fields.iter().map(|field| field.get_total_amount()).sum()
+        };
+        // Before the dose, the schedule overrides the fixed boundary value
+        assert_eq!(fields[0].get_boundary_value(), Some(0.0));
+        let initial = total(&fields);
+        for _ in 0..50 {
+            step(&mut fields, &[-10.0; 2].into())?;
+        }
+        let before_dose = total(&fields);
+        assert!(before_dose < initial);
+        // The drug enters the domain from its border after being administered
+        assert_eq!(fields[1].get_time(), 25.0);
+        assert_eq!(fields[1].get_boundary_value(), Some(2.0));
+        for _ in 0..50 {
+            step(&mut fields, &[-10.0; 2].into())?;
+        }
+        assert!(total(&fields) > before_dose);
+        Ok(())
+    }
+}
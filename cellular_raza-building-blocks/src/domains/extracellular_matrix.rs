@@ -0,0 +1,454 @@
+use cellular_raza_concepts::*;
+use nalgebra::{SMatrix, SVector};
+use serde::{Deserialize, Serialize};
+
+use super::{CartesianCuboid, CartesianSubDomain, ContinuumField, CoupledFields, SubDomainVoxels};
+
+/// State of the extracellular matrix (ECM) within a single voxel.
+///
+/// The fibers of the matrix are described by their density $\rho$ and their orientation tensor
+/// \\begin{equation}
+///     Q = \left\langle\vec{n}\otimes\vec{n}\right\rangle
+/// \\end{equation}
+/// which averages over the directions $\vec{n}$ of all fibers in the voxel.
+/// Since fibers have no head or tail, $\vec{n}$ and $-\vec{n}$ describe the same fiber and
+/// can thus not be stored as a vector.
+/// The tensor is symmetric with unit trace.
+/// Isotropic networks are given by $Q=I/D$ while perfectly aligned fibers result in
+/// $Q=\vec{n}\otimes\vec{n}$.
+///
+/// ```
+/// # use cellular_raza_building_blocks::*;
+/// # use nalgebra::Vector2;
+/// let fibers = FiberVoxel::aligned(2.0, [1.0, 1.0].into());
+/// let direction = fibers.principal_direction().unwrap();
+/// assert!((direction.dot(&Vector2::from([1.0, 1.0]).normalize()).abs() - 1.0).abs() < 1e-12);
+/// assert!((fibers.alignment() - 1.0).abs() < 1e-12);
+/// assert!(FiberVoxel::<2>::isotropic(2.0).alignment().abs() < 1e-12);
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct FiberVoxel<const D: usize> {
+    /// Density $\rho$ of fibers
+    pub density: f64,
+    /// Orientation tensor $Q$ of fibers
+    pub orientation: SMatrix<f64, D, D>,
+}
+
+impl<const D: usize> FiberVoxel<D> {
+    /// Fibers without preferred direction
+    pub fn isotropic(density: f64) -> Self {
+        Self {
+            density,
+            orientation: SMatrix::identity() / D as f64,
+        }
+    }
+
+    /// Fibers which are all aligned along the given direction.
+    ///
+    /// Returns isotropic fibers if the direction is zero.
+    pub fn aligned(density: f64, direction: SVector<f64, D>) -> Self {
+        match direction.try_normalize(0.0) {
+            Some(n) => Self {
+                density,
+                orientation: n * n.transpose(),
+            },
+            None => Self::isotropic(density),
+        }
+    }
+
+    fn zeros() -> Self {
+        Self {
+            density: 0.0,
+            orientation: SMatrix::zeros(),
+        }
+    }
+
+    /// Direction along which most fibers are aligned.
+    ///
+    /// Calculates the eigenvector of the largest eigenvalue of the orientation tensor by power
+    /// iteration.
+    /// Returns [None] if the orientation tensor vanishes.
+    /// Since fibers have no head or tail, the sign of the direction is arbitrary.
+    pub fn principal_direction(&self) -> Option<SVector<f64, D>> {
+        let column = (0..D)
+            .max_by(|i, j| self.orientation[(*i, *i)].total_cmp(&self.orientation[(*j, *j)]))?;
+        let mut direction: SVector<f64, D> = self.orientation.column(column).clone_owned();
+        for _ in 0..64 {
+            direction = self.orientation * direction.try_normalize(0.0)?;
+        }
+        direction.try_normalize(0.0)
+    }
+
+    /// Degree of alignment which is 0 for isotropic and 1 for perfectly aligned fibers
+    pub fn alignment(&self) -> f64 {
+        match (D, self.principal_direction()) {
+            (1, _) => 1.0,
+            (_, Some(n)) => {
+                let largest_eigenvalue = n.dot(&(self.orientation * n));
+                (D as f64 * largest_eigenvalue - 1.0) / (D as f64 - 1.0)
+            }
+            (_, None) => 0.0,
+        }
+    }
+}
+
+/// Contact guidance of migrating cells by the fibers of the extracellular matrix.
+///
+/// Fibers hinder the motion of cells perpendicular to their orientation while cells can move
+/// freely along them.
+/// This is modeled by an anisotropic friction
+/// \\begin{equation}
+///     \vec{F} = -\left(\gamma_0 I + \gamma_1\rho\left(I - Q\right)\right)\vec{v}
+/// \\end{equation}
+/// which increases with the density $\rho$ of the fibers.
+/// Cells which are propelled by other forces are thus steered along the local fiber
+/// orientation $Q$ (see [FiberVoxel]).
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ContactGuidance {
+    /// Friction $\gamma_0$ in the absence of fibers
+    pub base_friction: f64,
+    /// Additional friction $\gamma_1$ per fiber density perpendicular to fibers
+    pub fiber_friction: f64,
+}
+
+impl ContactGuidance {
+    /// Force acting on a cell which moves with the given velocity through the given fibers
+    pub fn force<const D: usize>(
+        &self,
+        fibers: &FiberVoxel<D>,
+        velocity: &SVector<f64, D>,
+    ) -> SVector<f64, D> {
+        let identity = SMatrix::<f64, D, D>::identity();
+        let friction = self.base_friction * identity
+            + self.fiber_friction * fibers.density * (identity - fibers.orientation);
+        -(friction * velocity)
+    }
+}
+
+/// [CartesianCuboid] whose voxels contain fibers of the extracellular matrix.
+///
+/// Every voxel stores its own [FiberVoxel] which is remodeled by cells via the [ReactionsExtra]
+/// trait (see [MatrixRemodeling](crate::MatrixRemodeling)).
+/// The increments of all cells within a voxel are summed up and applied in every step.
+/// Cells are guided by the fibers via [ContactGuidance].
+/// Simulations thus need to include the `ReactionsExtra` and `DomainForce` aspects.
+/// The fibers are saved together with the subdomains and can thus be analyzed afterwards.
+/// To combine fibers with other continuum fields, custom subdomains can use a tuple of the
+/// [FiberField] and other [ContinuumField]s.
+///
+/// ```
+/// # use cellular_raza_building_blocks::*;
+/// # use nalgebra::Vector2;
+/// let cuboid =
+///     CartesianCuboid::from_boundaries_and_interaction_range([0.0; 2], [100.0; 2], 10.0)?;
+/// // Fibers are aligned along concentric circles around the center
+/// let domain = FiberCuboid::from_fn(
+///     cuboid,
+///     |pos: &Vector2<f64>| {
+///         let r = pos - Vector2::from([50.0; 2]);
+///         FiberVoxel::aligned(1.0, [-r.y, r.x].into())
+///     },
+///     ContactGuidance {
+///         base_friction: 0.1,
+///         fiber_friction: 1.0,
+///     },
+/// );
+/// # Ok::<(), cellular_raza_concepts::BoundaryError>(())
+/// ```
+#[derive(Clone, Debug, Domain)]
+pub struct FiberCuboid<const D: usize> {
+    /// Underlying domain which sorts cells into voxels
+    #[DomainRngSeed]
+    #[SortCells]
+    pub cuboid: CartesianCuboid<f64, D>,
+    fibers: Vec<FiberVoxel<D>>,
+    /// Guidance of cells by the fibers
+    pub guidance: ContactGuidance,
+}
+
+impl<const D: usize> FiberCuboid<D> {
+    /// Evaluates the given function at the center of every voxel to obtain the initial fibers.
+    pub fn from_fn(
+        cuboid: CartesianCuboid<f64, D>,
+        fibers: impl Fn(&SVector<f64, D>) -> FiberVoxel<D>,
+        guidance: ContactGuidance,
+    ) -> Self {
+        let n_voxels = cuboid.get_n_voxels();
+        let fibers = (0..n_voxels.product())
+            .map(|plain_index| {
+                let mut remainder = plain_index;
+                let mut center = cuboid.get_min();
+                for i in (0..D).rev() {
+                    let index = (remainder % n_voxels[i]) as f64 + 0.5;
+                    center[i] += index * cuboid.get_dx()[i];
+                    remainder /= n_voxels[i];
+                }
+                fibers(&center)
+            })
+            .collect();
+        Self {
+            cuboid,
+            fibers,
+            guidance,
+        }
+    }
+}
+
+/// Fibers of the extracellular matrix within the voxels of a subdomain.
+///
+/// The increments of all cells within a voxel are summed up and applied in the
+/// [update](ContinuumField::update) step.
+/// Since fibers do not diffuse, no values are exchanged with neighboring subdomains.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct FiberField<const D: usize> {
+    voxels: SubDomainVoxels<D>,
+    fibers: Vec<FiberVoxel<D>>,
+    #[serde(skip)]
+    increments: Vec<FiberVoxel<D>>,
+}
+
+impl<const D: usize> FiberField<D> {
+    /// Evaluates the given function at the center of every voxel of the subdomain.
+    pub fn new(
+        subdomain: &CartesianSubDomain<f64, D>,
+        fibers: impl Fn(&SVector<f64, D>) -> FiberVoxel<D>,
+    ) -> Self {
+        let voxels = SubDomainVoxels::new(subdomain);
+        let fibers = voxels.get_voxel_centers().iter().map(fibers).collect();
+        Self {
+            voxels,
+            fibers,
+            increments: Vec::new(),
+        }
+    }
+
+    /// Fibers of the voxel which contains the given position
+    pub fn get_fibers_at(&self, pos: &SVector<f64, D>) -> Result<&FiberVoxel<D>, CalcError> {
+        Ok(&self.fibers[self.voxels.position_of(pos)?])
+    }
+
+    /// Fibers of all voxels of this subdomain together with their voxel index
+    pub fn get_fibers(&self) -> Vec<([usize; D], &FiberVoxel<D>)> {
+        self.voxels
+            .get_voxel_indices()
+            .into_iter()
+            .zip(self.fibers.iter())
+            .collect()
+    }
+}
+
+impl<const D: usize> ContinuumField<SVector<f64, D>> for FiberField<D> {
+    type Value = FiberVoxel<D>;
+    type NeighborValue = ();
+    type BorderInfo = ();
+
+    fn couple_agent(
+        &mut self,
+        pos: &SVector<f64, D>,
+        increment: FiberVoxel<D>,
+    ) -> Result<(), CalcError> {
+        let n = self.voxels.position_of(pos)?;
+        if self.increments.len() != self.fibers.len() {
+            self.increments = vec![FiberVoxel::zeros(); self.fibers.len()];
+        }
+        self.increments[n].density += increment.density;
+        self.increments[n].orientation += increment.orientation;
+        Ok(())
+    }
+
+    fn couple_neighbor(&mut self, _neighbor: ()) -> Result<(), CalcError> {
+        Ok(())
+    }
+
+    fn update(&mut self, dt: f64) -> Result<(), CalcError> {
+        for (fibers, increment) in self.fibers.iter_mut().zip(self.increments.iter_mut()) {
+            fibers.density = (fibers.density + dt * increment.density).max(0.0);
+            fibers.orientation += dt * increment.orientation;
+            *increment = FiberVoxel::zeros();
+        }
+        Ok(())
+    }
+
+    fn get_value_at(&self, pos: &SVector<f64, D>) -> Result<FiberVoxel<D>, CalcError> {
+        self.get_fibers_at(pos).cloned()
+    }
+
+    fn set_value_at(
+        &mut self,
+        pos: &SVector<f64, D>,
+        value: FiberVoxel<D>,
+    ) -> Result<(), CalcError> {
+        let n = self.voxels.position_of(pos)?;
+        self.fibers[n] = value;
+        Ok(())
+    }
+
+    fn get_neighbor_value(&self, _border_info: ()) {}
+
+    fn get_border_info(&self) {}
+}
+
+/// Subdomain of the [FiberCuboid].
+#[derive(Clone, Debug, Serialize, SubDomain)]
+pub struct FiberSubDomain<const D: usize> {
+    /// Subdomain of the underlying [CartesianCuboid]
+    #[Base]
+    #[SortCells]
+    #[Mechanics]
+    pub subdomain: CartesianSubDomain<f64, D>,
+    /// Fibers which are remodeled by cells
+    #[Reactions]
+    pub fibers: CoupledFields<FiberField<D>>,
+    /// Guidance of cells by the fibers
+    pub guidance: ContactGuidance,
+}
+
+impl<const D: usize> FiberSubDomain<D> {
+    /// Fibers of the voxel which contains the given position
+    pub fn get_fibers_at(&self, pos: &SVector<f64, D>) -> Result<&FiberVoxel<D>, CalcError> {
+        self.fibers.fields.get_fibers_at(pos)
+    }
+
+    /// Fibers of all voxels of this subdomain together with their voxel index
+    pub fn get_fibers(&self) -> Vec<([usize; D], &FiberVoxel<D>)> {
+        self.fibers.fields.get_fibers()
+    }
+}
+
+impl<const D: usize> SubDomainForce<SVector<f64, D>, SVector<f64, D>, SVector<f64, D>>
+    for FiberSubDomain<D>
+{
+    fn calculate_custom_force(
+        &self,
+        pos: &SVector<f64, D>,
+        vel: &SVector<f64, D>,
+    ) -> Result<SVector<f64, D>, CalcError> {
+        Ok(self.guidance.force(self.get_fibers_at(pos)?, vel))
+    }
+}
+
+impl<const D: usize> DomainCreateSubDomains<FiberSubDomain<D>> for FiberCuboid<D> {
+    type VoxelIndex = [usize; D];
+    type SubDomainIndex = usize;
+
+    fn create_subdomains(
+        &self,
+        n_subdomains: core::num::NonZeroUsize,
+    ) -> Result<
+        impl IntoIterator<
+            Item = (
+                Self::SubDomainIndex,
+                FiberSubDomain<D>,
+                Vec<Self::VoxelIndex>,
+            ),
+        >,
+        DecomposeError,
+    > {
+        Ok(self
+            .cuboid
+            .create_subdomains(n_subdomains)?
+            .into_iter()
+            .map(|(index, subdomain, voxels)| {
+                let subdomain_voxels = SubDomainVoxels::new(&subdomain);
+                let fibers = subdomain_voxels
+                    .get_plain_indices()
+                    .iter()
+                    .map(|n| self.fibers[*n].clone())
+                    .collect();
+                let fibers = FiberField {
+                    voxels: subdomain_voxels,
+                    fibers,
+                    increments: Vec::new(),
+                };
+                let subdomain = FiberSubDomain {
+                    subdomain,
+                    fibers: CoupledFields { fields: fibers },
+                    guidance: self.guidance.clone(),
+                };
+                (index, subdomain, voxels)
+            }))
+    }
+}
+
+#[cfg(test)]
+mod test_extracellular_matrix {
+    use super::*;
+    use nalgebra::{Vector2, Vector3};
+
+    #[test]
+    fn principal_direction() {
+        let fibers = FiberVoxel {
+            density: 1.0,
+            orientation: 0.3 * SMatrix::<f64, 3, 3>::identity() / 3.0
+                + 0.7 * FiberVoxel::aligned(1.0, Vector3::from([0.0, 1.0, -1.0])).orientation,
+        };
+        let direction = fibers.principal_direction().unwrap();
+        let expected = Vector3::from([0.0, 1.0, -1.0]).normalize();
+        assert!((direction.dot(&expected).abs() - 1.0).abs() < 1e-12);
+        assert!((fibers.alignment() - 0.7).abs() < 1e-12);
+        assert_eq!(
+            FiberVoxel::aligned(1.0, Vector3::zeros()),
+            FiberVoxel::isotropic(1.0)
+        );
+        let empty = FiberVoxel::<3>::zeros();
+        assert_eq!(empty.principal_direction(), None);
+        assert_eq!(empty.alignment(), 0.0);
+    }
+
+    #[test]
+    fn guidance_along_fibers() {
+        let guidance = ContactGuidance {
+            base_friction: 0.5,
+            fiber_friction: 2.0,
+        };
+        let fibers = FiberVoxel::aligned(1.0, Vector2::from([1.0, 0.0]));
+        let along = guidance.force(&fibers, &Vector2::from([1.0, 0.0]));
+        let across = guidance.force(&fibers, &Vector2::from([0.0, 1.0]));
+        assert_eq!(along, Vector2::from([-0.5, 0.0]));
+        assert_eq!(across, Vector2::from([0.0, -2.5]));
+        // Isotropic fibers hinder motion in all directions equally
+        let isotropic = FiberVoxel::isotropic(1.0);
+        let force = guidance.force(&isotropic, &Vector2::from([1.0, 1.0]));
+        assert_eq!(force, Vector2::from([-1.5, -1.5]));
+    }
+
+    #[test]
+    fn subdomains_store_fibers() -> Result<(), Box<dyn std::error::Error>> {
+        let cuboid =
+            CartesianCuboid::from_boundaries_and_interaction_range([0.0; 2], [40.0, 20.0], 10.0)?;
+        let domain = FiberCuboid::from_fn(
+            cuboid,
+            |pos: &Vector2<f64>| FiberVoxel::isotropic(pos.x + 2.0 * pos.y),
+            ContactGuidance {
+                base_friction: 1.0,
+                fiber_friction: 1.0,
+            },
+        );
+        let subdomains: Vec<_> = domain
+            .create_subdomains(3.try_into().unwrap())?
+            .into_iter()
+            .collect();
+        let mut n_voxels = 0;
+        for (_, subdomain, voxels) in subdomains.iter() {
+            let fibers = subdomain.get_fibers();
+            assert_eq!(fibers.len(), voxels.len());
+            for (index, fibers) in fibers {
+                assert!(voxels.contains(&index));
+                let center = Vector2::from([index[0] as f64 + 0.5, index[1] as f64 + 0.5]) * 10.0;
+                assert_eq!(fibers.density, center.x + 2.0 * center.y);
+                assert_eq!(subdomain.get_fibers_at(&center)?, fibers);
+            }
+            n_voxels += voxels.len();
+        }
+        assert_eq!(n_voxels, 8);
+        // Positions outside of the subdomain can not be resolved
+        let (_, subdomain, _) = &subdomains[0];
+        assert!(subdomain
+            .get_fibers_at(&Vector2::from([35.0, 15.0]))
+            .is_err());
+        assert!(subdomain
+            .get_fibers_at(&Vector2::from([-15.0, 5.0]))
+            .is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,183 @@
+use cellular_raza_concepts::domain_old::BoundaryCondition;
+use cellular_raza_concepts::CalcError;
+
+use nalgebra::{SMatrix, SVector};
+
+/// Estimates the gradient of concentrations from the values of neighboring voxels.
+///
+/// The neighbors are given by the displacement $\vec{r}_j$ from the center of the current
+/// voxel to the center of the neighbor and the concentrations $c_j$ at this point.
+/// We determine the gradient $\nabla c$ which minimizes the weighted squared deviation of the
+/// linear approximation
+/// \\begin{equation}
+///     \sum\limits_j w_j\left(\vec{r}_j\cdot\nabla c - (c_j - c)\right)^2
+///     \hspace{1cm} w_j = \frac{1}{|\vec{r}_j|^2}
+/// \\end{equation}
+/// where $c$ is the concentration of the current voxel.
+/// Since the physical displacements are used, the estimate is also correct on anisotropic
+/// grids.
+/// For symmetric neighborhoods, this reduces to the classical central difference while voxels
+/// at the border of the domain obtain a one-sided estimate.
+/// Linear fields are reproduced exactly in both cases.
+/// If no neighbor spans a given direction (eg. a domain which is only one voxel wide), the
+/// corresponding component of the gradient is zero.
+///
+/// ```
+/// # use cellular_raza_building_blocks::least_squares_gradient;
+/// # use nalgebra::{SVector, Vector2};
+/// // Linear field c(x, y) = 3x - y sampled on a grid with dx=0.5 and dy=2.0
+/// let field = |r: Vector2<f64>| SVector::from([3.0 * r.x - r.y]);
+/// let neighbors = [[0.5, 0.0], [-0.5, 0.0], [0.0, 2.0], [0.5, 2.0]]
+///     .map(|r| (Vector2::from(r), field(Vector2::from(r))));
+/// let gradient = least_squares_gradient(&field(Vector2::zeros()), neighbors)?;
+/// assert!((gradient[0] - Vector2::from([3.0, -1.0])).norm() < 1e-12);
+/// # Ok::<(), cellular_raza_concepts::CalcError>(())
+/// ```
+pub fn least_squares_gradient<const D: usize, const N: usize>(
+    concentrations: &SVector<f64, N>,
+    neighbors: impl IntoIterator<Item = (SVector<f64, D>, SVector<f64, N>)>,
+) -> Result<SVector<SVector<f64, D>, N>, CalcError> {
+    let mut normal_matrix = SMatrix::<f64, D, D>::zeros();
+    let mut rhs = SMatrix::<f64, D, N>::zeros();
+    for (displacement, neighbor_concentrations) in neighbors {
+        let dist_squared = displacement.norm_squared();
+        if dist_squared == 0.0 {
+            return Err(CalcError(format!(
+                "neighbor with concentrations {neighbor_concentrations:?} has the same position \
+                as the current voxel"
+            )));
+        }
+        let weight = 1.0 / dist_squared;
+        normal_matrix += weight * displacement * displacement.transpose();
+        rhs += weight * displacement * (neighbor_concentrations - concentrations).transpose();
+    }
+    // Directions without any information do not contribute to the gradient
+    for i in 0..D {
+        if normal_matrix[(i, i)] == 0.0 {
+            normal_matrix[(i, i)] = 1.0;
+        }
+    }
+    let inverse = normal_matrix.try_inverse().ok_or(CalcError(format!(
+        "neighbors do not determine the gradient: singular matrix {normal_matrix:?}"
+    )))?;
+    let solution = inverse * rhs;
+    Ok(SVector::<SVector<f64, D>, N>::from_fn(|n, _| {
+        solution.column(n).into_owned()
+    }))
+}
+
+/// Converts a [BoundaryCondition] at the given displacement into the concentrations which
+/// are used by [least_squares_gradient].
+///
+/// [Dirichlet](BoundaryCondition::Dirichlet) and [Value](BoundaryCondition::Value) conditions
+/// prescribe the concentrations directly.
+/// [Neumann](BoundaryCondition::Neumann) conditions prescribe the derivative in the direction
+/// of the boundary.
+/// This matches the sign convention of the flux used when calculating the increment.
+pub(crate) fn boundary_concentrations<const D: usize, const N: usize>(
+    concentrations: &SVector<f64, N>,
+    displacement: &SVector<f64, D>,
+    boundary_condition: &BoundaryCondition<SVector<f64, N>>,
+) -> SVector<f64, N> {
+    match boundary_condition {
+        BoundaryCondition::Neumann(value) => concentrations + value * displacement.norm(),
+        BoundaryCondition::Dirichlet(value) => *value,
+        BoundaryCondition::Value(value) => *value,
+    }
+}
+
+#[cfg(test)]
+mod test_gradient {
+    use super::*;
+    use nalgebra::{Vector2, Vector3};
+
+    fn stencil_3d(dx: [f64; 3]) -> Vec<Vector3<f64>> {
+        let mut displacements = Vec::new();
+        for i in -1..2 {
+            for j in -1..2 {
+                for k in -1..2 {
+                    if (i, j, k) != (0, 0, 0) {
+                        displacements.push(Vector3::from([
+                            i as f64 * dx[0],
+                            j as f64 * dx[1],
+                            k as f64 * dx[2],
+                        ]));
+                    }
+                }
+            }
+        }
+        displacements
+    }
+
+    #[test]
+    fn linear_field_anisotropic() {
+        let slope = Vector3::from([1.5, -0.25, 4.0]);
+        let field = |r: &Vector3<f64>| SVector::from([slope.dot(r) + 2.0, -slope.dot(r)]);
+        let center = Vector3::from([1.0, 2.0, 3.0]);
+        let neighbors = stencil_3d([0.1, 2.0, 0.7])
+            .into_iter()
+            .map(|r| (r, field(&(center + r))));
+        let gradient = least_squares_gradient(&field(&center), neighbors).unwrap();
+        assert!((gradient[0] - slope).norm() < 1e-10);
+        assert!((gradient[1] + slope).norm() < 1e-10);
+    }
+
+    #[test]
+    fn linear_field_one_sided() {
+        // Voxel in the corner of the domain only has neighbors in positive direction
+        let slope = Vector2::from([-2.0, 0.5]);
+        let field = |r: &Vector2<f64>| SVector::from([slope.dot(r)]);
+        let neighbors = [[0.3, 0.0], [0.0, 1.1], [0.3, 1.1]]
+            .map(|r| (Vector2::from(r), field(&Vector2::from(r))));
+        let gradient = least_squares_gradient(&field(&Vector2::zeros()), neighbors).unwrap();
+        assert!((gradient[0] - slope).norm() < 1e-10);
+    }
+
+    #[test]
+    fn radial_field() {
+        // c(r) = |r|^2 has the gradient 2r which is reproduced exactly by central differences
+        let field = |r: &Vector3<f64>| SVector::from([r.norm_squared()]);
+        let dx = [0.2, 0.4, 0.1];
+        for center in [[1.0, 0.0, 0.0], [-0.5, 2.0, 1.5], [0.0, 0.0, 0.0]] {
+            let center = Vector3::from(center);
+            let neighbors = stencil_3d(dx)
+                .into_iter()
+                .map(|r| (r, field(&(center + r))));
+            let gradient = least_squares_gradient(&field(&center), neighbors).unwrap();
+            assert!((gradient[0] - 2.0 * center).norm() < 1e-10);
+        }
+        // c(r) = |r| is only approximated but points outwards
+        let field = |r: &Vector3<f64>| SVector::from([r.norm()]);
+        let center = Vector3::from([3.0, -4.0, 0.0]);
+        let neighbors = stencil_3d(dx)
+            .into_iter()
+            .map(|r| (r, field(&(center + r))));
+        let gradient = least_squares_gradient(&field(&center), neighbors).unwrap();
+        assert!((gradient[0] - center.normalize()).norm() < 1e-2);
+    }
+
+    #[test]
+    fn missing_direction_is_zero() {
+        let neighbors =
+            [[1.0, 0.0], [-1.0, 0.0]].map(|r| (Vector2::from(r), SVector::from([2.0 * r[0]])));
+        let gradient = least_squares_gradient(&SVector::from([0.0]), neighbors).unwrap();
+        assert_eq!(gradient[0], Vector2::from([2.0, 0.0]));
+        let gradient = least_squares_gradient::<2, 1>(&SVector::from([1.0]), []).unwrap();
+        assert_eq!(gradient[0], Vector2::zeros());
+    }
+
+    #[test]
+    fn boundary_conditions() {
+        let concentrations = SVector::from([1.0]);
+        let displacement = Vector2::from([0.0, -0.5]);
+        let neumann = BoundaryCondition::Neumann(SVector::from([2.0]));
+        let ghost = boundary_concentrations(&concentrations, &displacement, &neumann);
+        assert_eq!(ghost, SVector::from([2.0]));
+        // The prescribed derivative in the direction of the boundary is recovered
+        let gradient = least_squares_gradient(&concentrations, [(displacement, ghost)]).unwrap();
+        assert_eq!(gradient[0].dot(&displacement.normalize()), 2.0);
+        let dirichlet = BoundaryCondition::Dirichlet(SVector::from([0.0]));
+        let ghost = boundary_concentrations(&concentrations, &displacement, &dirichlet);
+        assert_eq!(ghost, SVector::from([0.0]));
+    }
+}
@@ -0,0 +1,390 @@
+use cellular_raza_concepts::*;
+use nalgebra::SVector;
+use serde::{Deserialize, Serialize};
+
+use super::CartesianSubDomain;
+
+/// Continuum field which is stored in the voxels of a subdomain and coupled to agents.
+///
+/// Every field defines its own update rule and how agents are coupled to it.
+/// Multiple fields such as the extracellular matrix, oxygen or drugs can be combined as a tuple
+/// and are then wrapped by [CoupledFields] which implements the [SubDomainReactions] trait.
+/// The value of a tuple of fields is the tuple of the individual values.
+/// Cells thus implement the [ReactionsExtra] trait for the tuple of values and return one
+/// increment per field.
+pub trait ContinuumField<Pos, Float = f64> {
+    /// Value of the field which is given to agents and increment which is returned by them
+    type Value;
+    /// Value which is exchanged with neighboring subdomains
+    type NeighborValue;
+    /// Information which is sent to neighboring subdomains in order to obtain their
+    /// [NeighborValue](ContinuumField::NeighborValue)
+    type BorderInfo;
+
+    /// Coupling rule which adds the increment of an agent at the given position.
+    fn couple_agent(&mut self, pos: &Pos, increment: Self::Value) -> Result<(), CalcError>;
+
+    /// Takes into account the value of a neighboring subdomain
+    fn couple_neighbor(&mut self, neighbor: Self::NeighborValue) -> Result<(), CalcError>;
+
+    /// Update rule which advances the field in time and applies all coupled increments.
+    fn update(&mut self, dt: Float) -> Result<(), CalcError>;
+
+    /// Value of the field at the given position
+    fn get_value_at(&self, pos: &Pos) -> Result<Self::Value, CalcError>;
+
+    /// Overwrites the value of the field at the given position.
+    ///
+    /// By default, setting values is not supported and an error is returned.
+    fn set_value_at(&mut self, pos: &Pos, value: Self::Value) -> Result<(), CalcError> {
+        let _ = (pos, value);
+        Err(CalcError(
+            "setting values is not supported by this field".into(),
+        ))
+    }
+
+    /// Value which should be sent to the neighbor which has exposed the given
+    /// [BorderInfo](ContinuumField::BorderInfo)
+    fn get_neighbor_value(&self, border_info: Self::BorderInfo) -> Self::NeighborValue;
+
+    /// Information which is sent to neighboring subdomains
+    fn get_border_info(&self) -> Self::BorderInfo;
+}
+
+macro_rules! impl_continuum_field_tuple {
+    ($($field:ident $n:tt),+) => {
+        impl<Pos, Float, $($field),+> ContinuumField<Pos, Float> for ($($field,)+)
+        where
+            Float: Copy,
+            $($field: ContinuumField<Pos, Float>,)+
+        {
+            type Value = ($(<$field as ContinuumField<Pos, Float>>::Value,)+);
+            type NeighborValue = ($(<$field as ContinuumField<Pos, Float>>::NeighborValue,)+);
+            type BorderInfo = ($(<$field as ContinuumField<Pos, Float>>::BorderInfo,)+);
+
+            fn couple_agent(&mut self, pos: &Pos, increment: Self::Value) -> Result<(), CalcError> {
+                $(self.$n.couple_agent(pos, increment.$n)?;)+
+                Ok(())
+            }
+
+            fn couple_neighbor(&mut self, neighbor: Self::NeighborValue) -> Result<(), CalcError> {
+                $(self.$n.couple_neighbor(neighbor.$n)?;)+
+                Ok(())
+            }
+
+            fn update(&mut self, dt: Float) -> Result<(), CalcError> {
+                $(self.$n.update(dt)?;)+
+                Ok(())
+            }
+
+            fn get_value_at(&self, pos: &Pos) -> Result<Self::Value, CalcError> {
+                Ok(($(self.$n.get_value_at(pos)?,)+))
+            }
+
+            fn set_value_at(&mut self, pos: &Pos, value: Self::Value) -> Result<(), CalcError> {
+                $(self.$n.set_value_at(pos, value.$n)?;)+
+                Ok(())
+            }
+
+            fn get_neighbor_value(&self, border_info: Self::BorderInfo) -> Self::NeighborValue {
+                ($(self.$n.get_neighbor_value(border_info.$n),)+)
+            }
+
+            fn get_border_info(&self) -> Self::BorderInfo {
+                ($(self.$n.get_border_info(),)+)
+            }
+        }
+    };
+}
+
+impl_continuum_field_tuple!(F0 0);
+impl_continuum_field_tuple!(F0 0, F1 1);
+impl_continuum_field_tuple!(F0 0, F1 1, F2 2);
+impl_continuum_field_tuple!(F0 0, F1 1, F2 2, F3 3);
+impl_continuum_field_tuple!(F0 0, F1 1, F2 2, F3 3, F4 4);
+impl_continuum_field_tuple!(F0 0, F1 1, F2 2, F3 3, F4 4, F5 5);
+
+/// Couples one or multiple [ContinuumField]s to agents.
+///
+/// This struct implements the [SubDomainReactions] trait and can thus be used as the
+/// `#[Reactions]` field of custom subdomains.
+/// In every step, the fields are treated in a fixed order.
+/// 1. All values of neighboring subdomains are coupled by
+///    [couple_neighbor](ContinuumField::couple_neighbor).
+/// 2. The increments of all agents are coupled by [couple_agent](ContinuumField::couple_agent).
+/// 3. The fields are updated by [update](ContinuumField::update).
+///
+/// When combining multiple fields as a tuple, every step is executed for all fields in the order
+/// in which they appear in the tuple before continuing with the next step.
+///
+/// ```
+/// # use cellular_raza_building_blocks::*;
+/// # use cellular_raza_concepts::*;
+/// # use nalgebra::Vector2;
+/// let cuboid =
+///     CartesianCuboid::from_boundaries_and_interaction_range([0.0; 2], [100.0; 2], 10.0)?;
+/// let (_, subdomain, _) = cuboid
+///     .create_subdomains(1.try_into().unwrap())?
+///     .into_iter()
+///     .next()
+///     .unwrap();
+/// // Two independent fields of fibers
+/// let fields = CoupledFields {
+///     fields: (
+///         FiberField::new(&subdomain, |_| FiberVoxel::isotropic(1.0)),
+///         FiberField::new(&subdomain, |_| FiberVoxel::aligned(2.0, [1.0, 0.0].into())),
+///     ),
+/// };
+/// let (fibers1, fibers2) = fields.get_extracellular_at_pos(&Vector2::from([20.0, 30.0]))?;
+/// assert_eq!(fibers1.density, 1.0);
+/// assert_eq!(fibers2.density, 2.0);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CoupledFields<F> {
+    /// Field or tuple of fields
+    pub fields: F,
+}
+
+impl<Pos, Float, F> SubDomainReactions<Pos, F::Value, Float> for CoupledFields<F>
+where
+    F: ContinuumField<Pos, Float>,
+{
+    type NeighborValue = F::NeighborValue;
+    type BorderInfo = F::BorderInfo;
+
+    fn treat_increments<I, J>(&mut self, neighbors: I, sources: J) -> Result<(), CalcError>
+    where
+        I: IntoIterator<Item = Self::NeighborValue>,
+        J: IntoIterator<Item = (Pos, F::Value)>,
+    {
+        for neighbor in neighbors {
+            self.fields.couple_neighbor(neighbor)?;
+        }
+        for (pos, increment) in sources {
+            self.fields.couple_agent(&pos, increment)?;
+        }
+        Ok(())
+    }
+
+    fn update_fluid_dynamics(&mut self, dt: Float) -> Result<(), CalcError> {
+        self.fields.update(dt)
+    }
+
+    fn get_extracellular_at_pos(&self, pos: &Pos) -> Result<F::Value, CalcError> {
+        self.fields.get_value_at(pos)
+    }
+
+    fn set_extracellular_at_pos(&mut self, pos: &Pos, value: F::Value) -> Result<(), CalcError> {
+        self.fields.set_value_at(pos, value)
+    }
+
+    fn get_neighbor_value(&self, border_info: Self::BorderInfo) -> Self::NeighborValue {
+        self.fields.get_neighbor_value(border_info)
+    }
+
+    fn get_border_info(&self) -> Self::BorderInfo {
+        self.fields.get_border_info()
+    }
+}
+
+impl<Float, F> FieldNorm<Float> for CoupledFields<F>
+where
+    F: FieldNorm<Float>,
+{
+    fn squared_field_norm(&self) -> Float {
+        self.fields.squared_field_norm()
+    }
+}
+
+/// Locates positions within the voxels of a [CartesianSubDomain].
+///
+/// Voxels are stored in ascending order of their plain index where the last dimension changes
+/// fastest.
+/// Fields can thus store their values in a vector of the same order.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SubDomainVoxels<const D: usize> {
+    domain_min: SVector<f64, D>,
+    dx: SVector<f64, D>,
+    domain_n_voxels: SVector<usize, D>,
+    plain_indices: Vec<usize>,
+}
+
+impl<const D: usize> SubDomainVoxels<D> {
+    /// Collects all voxels of the given subdomain
+    pub fn new(subdomain: &CartesianSubDomain<f64, D>) -> Self {
+        let domain_n_voxels = subdomain.get_domain_n_voxels();
+        let mut plain_indices: Vec<_> = subdomain
+            .get_voxels()
+            .iter()
+            .map(|index| (0..D).fold(0, |acc, i| acc * domain_n_voxels[i] + index[i]))
+            .collect();
+        plain_indices.sort();
+        Self {
+            domain_min: subdomain.get_domain_min(),
+            dx: subdomain.get_dx(),
+            domain_n_voxels,
+            plain_indices,
+        }
+    }
+
+    /// Position of the voxel which contains the given position in the list of voxels
+    pub fn position_of(&self, pos: &SVector<f64, D>) -> Result<usize, CalcError> {
+        let mut plain_index = 0;
+        for i in 0..D {
+            let index = ((pos[i] - self.domain_min[i]) / self.dx[i]).floor();
+            if !(index >= 0.0 && index < self.domain_n_voxels[i] as f64) {
+                return Err(CalcError(format!(
+                    "position {pos:?} is outside of the domain"
+                )));
+            }
+            plain_index = plain_index * self.domain_n_voxels[i] + index as usize;
+        }
+        self.plain_indices
+            .binary_search(&plain_index)
+            .map_err(|_| CalcError(format!("position {pos:?} is outside of the subdomain")))
+    }
+
+    /// Plain indices of all voxels
+    pub fn get_plain_indices(&self) -> &[usize] {
+        &self.plain_indices
+    }
+
+    /// Size of every voxel
+    pub fn get_dx(&self) -> SVector<f64, D> {
+        self.dx
+    }
+
+    /// Number of voxels of the whole domain in every dimension
+    pub fn get_domain_n_voxels(&self) -> SVector<usize, D> {
+        self.domain_n_voxels
+    }
+
+    /// Indices of all voxels
+    pub fn get_voxel_indices(&self) -> Vec<[usize; D]> {
+        self.plain_indices
+            .iter()
+            .map(|plain_index| {
+                let mut remainder = *plain_index;
+                let mut index = [0; D];
+                for i in (0..D).rev() {
+                    index[i] = remainder % self.domain_n_voxels[i];
+                    remainder /= self.domain_n_voxels[i];
+                }
+                index
+            })
+            .collect()
+    }
+
+    /// Centers of all voxels
+    pub fn get_voxel_centers(&self) -> Vec<SVector<f64, D>> {
+        self.get_voxel_indices()
+            .into_iter()
+            .map(|index| {
+                SVector::from_fn(|i, _| self.domain_min[i] + (index[i] as f64 + 0.5) * self.dx[i])
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test_hybrid {
+    use super::*;
+    use crate::CartesianCuboid;
+    use nalgebra::Vector2;
+    use std::{cell::RefCell, rc::Rc};
+
+    /// Records the order in which its methods are called
+    struct Recorder {
+        name: &'static str,
+        log: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl ContinuumField<f64> for Recorder {
+        type Value = f64;
+        type NeighborValue = ();
+        type BorderInfo = ();
+
+        fn couple_agent(&mut self, pos: &f64, increment: f64) -> Result<(), CalcError> {
+            let entry = format!("{} agent {pos} {increment}", self.name);
+            self.log.borrow_mut().push(entry);
+            Ok(())
+        }
+
+        fn couple_neighbor(&mut self, _: ()) -> Result<(), CalcError> {
+            self.log
+                .borrow_mut()
+                .push(format!("{} neighbor", self.name));
+            Ok(())
+        }
+
+        fn update(&mut self, dt: f64) -> Result<(), CalcError> {
+            self.log
+                .borrow_mut()
+                .push(format!("{} update {dt}", self.name));
+            Ok(())
+        }
+
+        fn get_value_at(&self, pos: &f64) -> Result<f64, CalcError> {
+            Ok(2.0 * pos)
+        }
+
+        fn get_neighbor_value(&self, _: ()) {}
+
+        fn get_border_info(&self) {}
+    }
+
+    #[test]
+    fn fields_are_treated_in_order() -> Result<(), CalcError> {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let recorder = |name| Recorder {
+            name,
+            log: log.clone(),
+        };
+        let mut fields = CoupledFields {
+            fields: (recorder("a"), recorder("b")),
+        };
+        assert_eq!(fields.get_extracellular_at_pos(&1.5)?, (3.0, 3.0));
+        fields.treat_increments([((), ())], [(1.0, (0.1, 0.2)), (2.0, (0.3, 0.4))])?;
+        fields.update_fluid_dynamics(0.5)?;
+        assert_eq!(
+            *log.borrow(),
+            vec![
+                "a neighbor",
+                "b neighbor",
+                "a agent 1 0.1",
+                "b agent 1 0.2",
+                "a agent 2 0.3",
+                "b agent 2 0.4",
+                "a update 0.5",
+                "b update 0.5",
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn locate_voxels() -> Result<(), Box<dyn std::error::Error>> {
+        let cuboid =
+            CartesianCuboid::from_boundaries_and_interaction_range([0.0; 2], [40.0, 20.0], 10.0)?;
+        let subdomains: Vec<_> = cuboid
+            .create_subdomains(3.try_into().unwrap())?
+            .into_iter()
+            .map(|(_, subdomain, voxels)| (SubDomainVoxels::new(&subdomain), voxels))
+            .collect();
+        for (voxels, indices) in subdomains.iter() {
+            let mut indices = indices.clone();
+            indices.sort();
+            assert_eq!(voxels.get_voxel_indices(), indices);
+            for (n, center) in voxels.get_voxel_centers().into_iter().enumerate() {
+                assert_eq!(voxels.position_of(&center)?, n);
+            }
+        }
+        let (voxels, _) = &subdomains[0];
+        assert_eq!(voxels.position_of(&Vector2::from([0.0, 19.9]))?, 1);
+        assert!(voxels.position_of(&Vector2::from([35.0, 15.0])).is_err());
+        assert!(voxels.position_of(&Vector2::from([-0.5, 5.0])).is_err());
+        assert!(voxels.position_of(&Vector2::from([5.0, 20.0])).is_err());
+        Ok(())
+    }
+}
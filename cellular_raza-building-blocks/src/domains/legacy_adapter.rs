@@ -0,0 +1,305 @@
+use cellular_raza_concepts::domain_old;
+use cellular_raza_concepts::{
+    BoundaryError, DecomposeError, DecomposedDomain, Domain, IndexError, Position, SortCells,
+    SubDomain, SubDomainMechanics, Velocity,
+};
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::marker::PhantomData;
+
+/// Wraps a domain which implements the legacy [Domain](domain_old::Domain) trait such that it
+/// can be used with backends which require the new [Domain] trait.
+///
+/// The voxel regions generated by
+/// [generate_contiguous_multi_voxel_regions](domain_old::Domain::generate_contiguous_multi_voxel_regions)
+/// become the [LegacySubDomain]s.
+/// Voxels of type `V` are discarded during the decomposition.
+/// Thus custom forces of voxels and extracellular reactions are not supported by this
+/// adapter and need to be migrated manually.
+///
+/// Boundary conditions are translated to the [SubDomainMechanics] trait if the legacy domain
+/// can apply them to a [BoundaryProbe].
+/// This is the case for all domains which only access the [Position] and [Velocity] of cells.
+/// ```
+/// # use cellular_raza_building_blocks::*;
+/// # use cellular_raza_building_blocks::cartesian_cuboid_n_old::*;
+/// # use cellular_raza_concepts::*;
+/// let legacy = CartesianCuboid2::from_boundaries_and_interaction_ranges(
+///     [0.0; 2],
+///     [10.0; 2],
+///     [2.0; 2],
+/// )?;
+/// let domain = LegacyDomain::<_, _, CartesianCuboidVoxel2<1>>::new(legacy, 1);
+/// let cells = vec![NewtonDamped2D::new([1.0, 2.0], [0.0; 2], 0.1, 1.0)];
+/// let decomposed = domain.decompose(2.try_into()?, cells)?;
+/// assert_eq!(decomposed.n_subdomains.get(), 2);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct LegacyDomain<D, I, V> {
+    /// Domain implementing the legacy [Domain](domain_old::Domain) trait
+    pub domain: D,
+    /// Seed from which all random numbers will be initially drawn
+    pub rng_seed: u64,
+    phantom: PhantomData<fn() -> (I, V)>,
+}
+
+impl<D, I, V> LegacyDomain<D, I, V> {
+    /// Wraps the given legacy domain.
+    ///
+    /// Since the legacy [Domain](domain_old::Domain) trait does not provide a seed for random
+    /// numbers, it needs to be specified here.
+    pub fn new(domain: D, rng_seed: u64) -> Self {
+        Self {
+            domain,
+            rng_seed,
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// Subdomain produced by decomposing a [LegacyDomain].
+///
+/// Every subdomain holds a copy of the legacy domain and the neighbors of all of its voxels.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(bound(deserialize = "D: Deserialize<'de>, I: Deserialize<'de> + Ord"))]
+pub struct LegacySubDomain<D, I, V> {
+    /// Domain implementing the legacy [Domain](domain_old::Domain) trait
+    pub domain: D,
+    neighbors: BTreeMap<I, Vec<I>>,
+    #[serde(skip)]
+    phantom: PhantomData<fn() -> V>,
+}
+
+/// Cell-like object which only consists of a position and velocity.
+///
+/// Legacy domains apply boundary conditions to whole cells.
+/// The [LegacySubDomain] passes this probe to the legacy domain in order to implement the
+/// [SubDomainMechanics] trait.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct BoundaryProbe<Pos, Vel> {
+    /// Position of the probe
+    pub pos: Pos,
+    /// Velocity of the probe
+    pub vel: Vel,
+}
+
+impl<Pos: Clone, Vel> Position<Pos> for BoundaryProbe<Pos, Vel> {
+    fn pos(&self) -> Pos {
+        self.pos.clone()
+    }
+
+    fn set_pos(&mut self, position: &Pos) {
+        self.pos = position.clone();
+    }
+}
+
+impl<Pos, Vel: Clone> Velocity<Vel> for BoundaryProbe<Pos, Vel> {
+    fn velocity(&self) -> Vel {
+        self.vel.clone()
+    }
+
+    fn set_velocity(&mut self, velocity: &Vel) {
+        self.vel = velocity.clone();
+    }
+}
+
+impl<C, Ci, D, I, V> Domain<C, LegacySubDomain<D, I, V>, Ci> for LegacyDomain<D, I, V>
+where
+    D: domain_old::Domain<C, I, V> + Clone,
+    I: domain_old::Index,
+    Ci: IntoIterator<Item = C>,
+{
+    type SubDomainIndex = usize;
+    type VoxelIndex = I;
+
+    fn decompose(
+        self,
+        n_subdomains: core::num::NonZeroUsize,
+        cells: Ci,
+    ) -> Result<DecomposedDomain<Self::SubDomainIndex, LegacySubDomain<D, I, V>, C>, DecomposeError>
+    {
+        let regions: Vec<Vec<I>> = self
+            .domain
+            .generate_contiguous_multi_voxel_regions(n_subdomains.get())
+            .map_err(|e| DecomposeError::Generic(e.to_string()))?
+            .into_iter()
+            .map(|region| {
+                region
+                    .into_iter()
+                    .map(|(index, _)| index)
+                    .collect::<Vec<_>>()
+            })
+            .filter(|region| !region.is_empty())
+            .collect();
+        let n_subdomains = core::num::NonZeroUsize::new(regions.len()).ok_or(
+            DecomposeError::Generic("legacy domain did not generate any voxels".to_owned()),
+        )?;
+        let voxel_to_subdomain: BTreeMap<I, usize> = regions
+            .iter()
+            .enumerate()
+            .flat_map(|(n, region)| region.iter().map(move |index| (index.clone(), n)))
+            .collect();
+
+        // Sort cells into their subdomains
+        let mut subdomain_cells: Vec<Vec<C>> = (0..regions.len()).map(|_| Vec::new()).collect();
+        for cell in cells {
+            let index = self.domain.get_voxel_index(&cell);
+            let n = voxel_to_subdomain.get(&index).ok_or_else(|| {
+                IndexError(format!("could not find voxel {index:?} in any subdomain"))
+            })?;
+            subdomain_cells[*n].push(cell);
+        }
+
+        // Subdomains are neighbors if any of their voxels are neighbors
+        let mut neighbor_map = BTreeMap::new();
+        let mut index_subdomain_cells = Vec::with_capacity(regions.len());
+        for ((n, region), cells) in regions.into_iter().enumerate().zip(subdomain_cells) {
+            let mut subdomain_neighbors = BTreeSet::new();
+            let mut neighbors = BTreeMap::new();
+            for index in region {
+                let voxel_neighbors = self.domain.get_neighbor_voxel_indices(&index);
+                subdomain_neighbors.extend(
+                    voxel_neighbors
+                        .iter()
+                        .filter_map(|neighbor| voxel_to_subdomain.get(neighbor))
+                        .filter(|m| **m != n),
+                );
+                neighbors.insert(index, voxel_neighbors);
+            }
+            neighbor_map.insert(n, subdomain_neighbors);
+            let subdomain = LegacySubDomain {
+                domain: self.domain.clone(),
+                neighbors,
+                phantom: PhantomData,
+            };
+            index_subdomain_cells.push((n, subdomain, cells));
+        }
+
+        Ok(DecomposedDomain {
+            n_subdomains,
+            index_subdomain_cells,
+            neighbor_map,
+            rng_seed: self.rng_seed,
+        })
+    }
+}
+
+impl<D, I, V> SubDomain for LegacySubDomain<D, I, V>
+where
+    I: Clone + Ord,
+{
+    type VoxelIndex = I;
+
+    fn get_neighbor_voxel_indices(&self, voxel_index: &Self::VoxelIndex) -> Vec<Self::VoxelIndex> {
+        self.neighbors.get(voxel_index).cloned().unwrap_or_default()
+    }
+
+    fn get_all_indices(&self) -> Vec<Self::VoxelIndex> {
+        self.neighbors.keys().cloned().collect()
+    }
+}
+
+impl<C, D, I, V> SortCells<C> for LegacySubDomain<D, I, V>
+where
+    D: domain_old::Domain<C, I, V>,
+{
+    type VoxelIndex = I;
+
+    fn get_voxel_index_of(&self, cell: &C) -> Result<Self::VoxelIndex, BoundaryError> {
+        Ok(self.domain.get_voxel_index(cell))
+    }
+}
+
+impl<Pos, Vel, D, I, V> SubDomainMechanics<Pos, Vel> for LegacySubDomain<D, I, V>
+where
+    D: domain_old::Domain<BoundaryProbe<Pos, Vel>, I, V>,
+    Pos: Clone,
+    Vel: Clone,
+{
+    fn apply_boundary(&self, pos: &mut Pos, vel: &mut Vel) -> Result<(), BoundaryError> {
+        let mut probe = BoundaryProbe {
+            pos: pos.clone(),
+            vel: vel.clone(),
+        };
+        self.domain.apply_boundary(&mut probe)?;
+        *pos = probe.pos;
+        *vel = probe.vel;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_legacy_adapter {
+    use super::*;
+    use crate::cartesian_cuboid_n_old::{CartesianCuboid2, CartesianCuboidVoxel2};
+    use nalgebra::Vector2;
+
+    type Probe = BoundaryProbe<Vector2<f64>, Vector2<f64>>;
+
+    fn legacy_domain() -> LegacyDomain<CartesianCuboid2, [i64; 2], CartesianCuboidVoxel2<1>> {
+        let legacy =
+            CartesianCuboid2::from_boundaries_and_interaction_ranges([0.0; 2], [10.0; 2], [2.0; 2])
+                .unwrap();
+        LegacyDomain::new(legacy, 3)
+    }
+
+    #[test]
+    fn decompose_legacy_domain() {
+        let cells: Vec<_> = (0..25)
+            .map(|n| Probe {
+                pos: Vector2::new(0.2 + 0.4 * n as f64, 9.8 - 0.4 * n as f64),
+                vel: Vector2::zeros(),
+            })
+            .collect();
+        let domain = legacy_domain();
+        let n_voxels = domain_old::Domain::<Probe, _, CartesianCuboidVoxel2<1>>::get_all_indices(
+            &domain.domain,
+        )
+        .len();
+        let decomposed = domain.decompose(4.try_into().unwrap(), cells).unwrap();
+        assert_eq!(decomposed.rng_seed, 3);
+        assert_eq!(decomposed.n_subdomains.get(), 4);
+        let n_cells: usize = decomposed
+            .index_subdomain_cells
+            .iter()
+            .map(|(_, _, cells)| cells.len())
+            .sum();
+        assert_eq!(n_cells, 25);
+
+        // Every voxel is contained in exactly one subdomain
+        let mut all_indices = BTreeSet::new();
+        for (_, subdomain, cells) in decomposed.index_subdomain_cells.iter() {
+            let indices = subdomain.get_all_indices();
+            for cell in cells {
+                assert!(indices.contains(&subdomain.get_voxel_index_of(cell).unwrap()));
+            }
+            for index in indices {
+                assert!(all_indices.insert(index));
+            }
+        }
+        assert_eq!(all_indices.len(), n_voxels);
+
+        // Neighbor map is symmetric
+        for (n, neighbors) in decomposed.neighbor_map.iter() {
+            for m in neighbors {
+                assert!(decomposed.neighbor_map[m].contains(n));
+            }
+        }
+    }
+
+    #[test]
+    fn apply_legacy_boundary() {
+        let decomposed = legacy_domain()
+            .decompose(1.try_into().unwrap(), Vec::<Probe>::new())
+            .unwrap();
+        let subdomain = &decomposed.index_subdomain_cells[0].1;
+        let mut pos = Vector2::new(-1.0, 5.0);
+        let mut vel = Vector2::new(-1.0, 0.0);
+        subdomain.apply_boundary(&mut pos, &mut vel).unwrap();
+        assert!(pos[0] >= 0.0);
+        assert!(vel[0] > 0.0);
+    }
+}
@@ -1,4 +1,14 @@
 mod cartesian_cuboid_n;
+mod concentration_field;
+mod diffusion_field;
+mod extracellular_matrix;
+#[cfg(feature = "gradients")]
+mod gradient;
+mod hybrid;
+mod legacy_adapter;
+mod pharmacokinetics;
+mod stability;
+mod substrate;
 
 /// Contains deprecated cartesian cuboid implementations for an older vertex model
 // TODO #[allow(deprecated)]
@@ -9,3 +19,14 @@ pub mod cartesian_cuboid_2_vertex_old;
 pub mod cartesian_cuboid_n_old;
 
 pub use cartesian_cuboid_n::*;
+pub use concentration_field::*;
+pub use diffusion_field::*;
+pub use extracellular_matrix::*;
+#[cfg(feature = "gradients")]
+#[cfg_attr(docsrs, doc(cfg(feature = "gradients")))]
+pub use gradient::*;
+pub use hybrid::*;
+pub use legacy_adapter::*;
+pub use pharmacokinetics::*;
+pub use stability::*;
+pub use substrate::*;
@@ -0,0 +1,197 @@
+use serde::{Deserialize, Serialize};
+
+/// Concentration of a drug over time after a single dose of unit amount.
+///
+/// The profiles describe the concentration in the medium which surrounds the domain, for
+/// example the plasma concentration of a drug which is supplied to a tissue via its
+/// vasculature.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum PharmacokineticProfile {
+    /// Bolus injection followed by first-order elimination
+    /// \\begin{equation}
+    ///     c(t) = e^{-k_e t}
+    /// \\end{equation}
+    ExponentialDecay {
+        /// Elimination rate $k_e$
+        elimination_rate: f64,
+    },
+    /// One-compartment model with first-order absorption (Bateman function)
+    /// \\begin{equation}
+    ///     c(t) = \frac{k_a}{k_a - k_e}\left(e^{-k_e t} - e^{-k_a t}\right)
+    /// \\end{equation}
+    /// which is given by $c(t)=k_ate^{-k_at}$ for $k_a=k_e$.
+    FirstOrderAbsorption {
+        /// Absorption rate $k_a$
+        absorption_rate: f64,
+        /// Elimination rate $k_e$
+        elimination_rate: f64,
+    },
+    /// Measured or otherwise user-defined profile given by pairs of time since dosing and
+    /// concentration.
+    /// Values in between are linearly interpolated and the concentration is zero outside of
+    /// the given times.
+    /// The pairs need to be ordered by their time.
+    Tabulated(Vec<(f64, f64)>),
+}
+
+impl PharmacokineticProfile {
+    /// Concentration at the given time after a dose of unit amount
+    pub fn concentration(&self, time_since_dose: f64) -> f64 {
+        let t = time_since_dose;
+        if t < 0.0 {
+            return 0.0;
+        }
+        match self {
+            PharmacokineticProfile::ExponentialDecay { elimination_rate } => {
+                (-elimination_rate * t).exp()
+            }
+            PharmacokineticProfile::FirstOrderAbsorption {
+                absorption_rate: ka,
+                elimination_rate: ke,
+            } => {
+                if (ka - ke).abs() <= f64::EPSILON * ka.abs().max(ke.abs()) {
+                    ka * t * (-ka * t).exp()
+                } else {
+                    ka / (ka - ke) * ((-ke * t).exp() - (-ka * t).exp())
+                }
+            }
+            PharmacokineticProfile::Tabulated(points) => points
+                .windows(2)
+                .find(|window| window[0].0 <= t && t <= window[1].0)
+                .map(|window| {
+                    let ((t0, c0), (t1, c1)) = (window[0], window[1]);
+                    match t1 > t0 {
+                        true => c0 + (c1 - c0) * (t - t0) / (t1 - t0),
+                        false => c0,
+                    }
+                })
+                .unwrap_or(0.0),
+        }
+    }
+}
+
+/// Single administration of a drug
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DosingEvent {
+    /// Time of the administration
+    pub time: f64,
+    /// Administered amount which scales the [PharmacokineticProfile]
+    pub dose: f64,
+}
+
+/// Concentration of a drug over the course of a treatment with multiple doses.
+///
+/// The concentrations of all doses which were administered until time $t$ are superimposed
+/// \\begin{equation}
+///     c(t) = c_0 + \sum\limits_{t_i\leq t} d_i\,c_1(t-t_i)
+/// \\end{equation}
+/// where $c_0$ is the `baseline` concentration, $d_i$ the dose of the [DosingEvent] at time
+/// $t_i$ and $c_1$ the [PharmacokineticProfile] of a unit dose.
+/// The schedule can drive the value at the border of a [DiffusionField](crate::DiffusionField)
+/// in order to simulate the response of a tissue to a treatment.
+///
+/// ```
+/// # use cellular_raza_building_blocks::*;
+/// let mut schedule = DosingSchedule::new(PharmacokineticProfile::ExponentialDecay {
+///     elimination_rate: 2f64.ln(),
+/// });
+/// // Three doses given once every day
+/// schedule.add_repeated_doses(0.0, 1.0, 3, 2.0);
+/// assert_eq!(schedule.events.len(), 3);
+/// assert_eq!(schedule.concentration_at(-1.0), 0.0);
+/// assert!((schedule.concentration_at(0.0) - 2.0).abs() < 1e-12);
+/// // The second dose adds to the remainder of the first dose
+/// assert!((schedule.concentration_at(1.0) - 2.0 * (1.0 + 0.5)).abs() < 1e-12);
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DosingSchedule {
+    /// Concentration of a unit dose over time
+    pub profile: PharmacokineticProfile,
+    /// All administrations of the drug
+    pub events: Vec<DosingEvent>,
+    /// Concentration $c_0$ which is present independently of any dose
+    pub baseline: f64,
+}
+
+impl DosingSchedule {
+    /// Schedule without any doses and zero baseline concentration
+    pub fn new(profile: PharmacokineticProfile) -> Self {
+        Self {
+            profile,
+            events: Vec::new(),
+            baseline: 0.0,
+        }
+    }
+
+    /// Administers the dose at the given time
+    pub fn add_dose(&mut self, time: f64, dose: f64) {
+        self.events.push(DosingEvent { time, dose });
+    }
+
+    /// Administers the same dose `n_doses` times with a fixed interval starting at `first_time`
+    pub fn add_repeated_doses(
+        &mut self,
+        first_time: f64,
+        interval: f64,
+        n_doses: usize,
+        dose: f64,
+    ) {
+        self.events.extend((0..n_doses).map(|n| DosingEvent {
+            time: first_time + n as f64 * interval,
+            dose,
+        }));
+    }
+
+    /// Concentration at the given time
+    pub fn concentration_at(&self, time: f64) -> f64 {
+        self.baseline
+            + self
+                .events
+                .iter()
+                .filter(|event| event.time <= time)
+                .map(|event| event.dose * self.profile.concentration(time - event.time))
+                .sum::<f64>()
+    }
+}
+
+#[cfg(test)]
+mod test_pharmacokinetics {
+    use super::*;
+
+    #[test]
+    fn absorption_profile() {
+        let profile = PharmacokineticProfile::FirstOrderAbsorption {
+            absorption_rate: 2.0,
+            elimination_rate: 0.5,
+        };
+        assert_eq!(profile.concentration(0.0), 0.0);
+        assert_eq!(profile.concentration(-1.0), 0.0);
+        // The maximum is reached at ln(ka/ke)/(ka-ke)
+        let t_max = 4f64.ln() / 1.5;
+        let c_max = profile.concentration(t_max);
+        assert!(c_max > profile.concentration(0.99 * t_max));
+        assert!(c_max > profile.concentration(1.01 * t_max));
+        // The limit of equal rates is continuous
+        let equal = |ka: f64| PharmacokineticProfile::FirstOrderAbsorption {
+            absorption_rate: ka,
+            elimination_rate: 1.0,
+        };
+        assert!(
+            (equal(1.0).concentration(2.0) - equal(1.0 + 1e-7).concentration(2.0)).abs() < 1e-6
+        );
+    }
+
+    #[test]
+    fn tabulated_profile() {
+        let profile = PharmacokineticProfile::Tabulated(vec![(0.0, 0.0), (1.0, 2.0), (3.0, 1.0)]);
+        assert_eq!(profile.concentration(0.5), 1.0);
+        assert_eq!(profile.concentration(2.0), 1.5);
+        assert_eq!(profile.concentration(4.0), 0.0);
+        let mut schedule = DosingSchedule::new(profile);
+        schedule.baseline = 0.1;
+        schedule.add_dose(1.0, 2.0);
+        schedule.add_dose(2.0, 1.0);
+        assert_eq!(schedule.concentration_at(0.0), 0.1);
+        assert!((schedule.concentration_at(3.0) - 0.1 - 2.0 * 1.5 - 1.0 * 2.0).abs() < 1e-12);
+    }
+}
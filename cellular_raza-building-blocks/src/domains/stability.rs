@@ -0,0 +1,191 @@
+use serde::{Deserialize, Serialize};
+
+use core::fmt::Display;
+use std::error::Error;
+
+/// Reasons why an explicit reaction-diffusion scheme cannot be used with the given parameters.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum StabilityError {
+    /// The time increment exceeds the largest stable time increment of one species.
+    Unstable {
+        /// Index of the species which violates the stability criterion
+        species: usize,
+        /// Specified time increment
+        dt: f64,
+        /// Largest time increment for which the species is updated stably
+        max_dt: f64,
+        /// Number of sub-steps into which every step needs to be divided
+        n_substeps: usize,
+    },
+    /// Diffusion constants, reaction rates or voxel sizes are negative or not finite.
+    InvalidParameter(String),
+}
+
+impl Display for StabilityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            StabilityError::Unstable {
+                species,
+                dt,
+                max_dt,
+                n_substeps,
+            } => write!(
+                f,
+                "explicit update of species {species} is unstable for dt={dt} and will produce \
+                oscillating negative concentrations: choose dt<={max_dt} or divide every step \
+                into {n_substeps} sub-steps"
+            ),
+            StabilityError::InvalidParameter(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl Error for StabilityError {}
+
+/// Largest time increment of the explicit Euler scheme for a single species.
+///
+/// The concentration $u$ of a species is assumed to follow
+/// \\begin{equation}
+///     \partial_t u = D\Delta u - \lambda u
+/// \\end{equation}
+/// where the Laplacian is discretized by central differences on a grid with voxel sizes
+/// $\Delta x_i$.
+/// The updated value of every voxel is a positive combination of the previous values if
+/// \\begin{equation}
+///     \Delta t\left(2D\sum\limits_{i=1}^d\frac{1}{\Delta x_i^2} + \lambda\right) \leq 1
+/// \\end{equation}
+/// which guarantees that concentrations can neither oscillate nor become negative.
+/// Returns infinity if the species neither diffuses nor reacts.
+///
+/// ```
+/// # use cellular_raza_building_blocks::max_stable_dt;
+/// let max_dt = max_stable_dt(1.0, 0.0, [0.5, 0.5]);
+/// assert!((max_dt - 1.0 / 16.0).abs() < 1e-12);
+/// ```
+pub fn max_stable_dt<const D: usize>(
+    diffusion_constant: f64,
+    reaction_rate: f64,
+    dx: impl Into<[f64; D]>,
+) -> f64 {
+    let inverse_squares: f64 = dx.into().iter().map(|dx| dx.powi(-2)).sum();
+    1.0 / (2.0 * diffusion_constant * inverse_squares + reaction_rate)
+}
+
+/// Checks that the explicit Euler scheme is stable for all species.
+///
+/// Every species is given by its diffusion constant $D$ and the rate $\lambda$ of its fastest
+/// linear reaction such as degradation or uptake (see [max_stable_dt]).
+/// This function should be called when setting up a simulation such that unsuitable time
+/// increments are detected before the simulation starts.
+/// Returns the largest stable time increment of all species.
+///
+/// ```
+/// # use cellular_raza_building_blocks::{check_reaction_diffusion_stability, StabilityError};
+/// let species = [(1.0, 0.0), (0.1, 2.0)];
+/// let max_dt = check_reaction_diffusion_stability(species, [1.0, 1.0], 0.1)?;
+/// assert!((max_dt - 0.25).abs() < 1e-12);
+/// let error = check_reaction_diffusion_stability(species, [1.0, 1.0], 0.6).unwrap_err();
+/// assert_eq!(
+///     error,
+///     StabilityError::Unstable {
+///         species: 0,
+///         dt: 0.6,
+///         max_dt: 0.25,
+///         n_substeps: 3,
+///     }
+/// );
+/// # Ok::<(), StabilityError>(())
+/// ```
+pub fn check_reaction_diffusion_stability<const D: usize>(
+    species: impl IntoIterator<Item = (f64, f64)>,
+    dx: impl Into<[f64; D]>,
+    dt: f64,
+) -> Result<f64, StabilityError> {
+    let dx = dx.into();
+    if dx.iter().any(|dx| !(dx.is_finite() && *dx > 0.0)) {
+        return Err(StabilityError::InvalidParameter(format!(
+            "voxel sizes {dx:?} must be positive and finite"
+        )));
+    }
+    let mut max_dt_all = f64::INFINITY;
+    for (index, (diffusion_constant, reaction_rate)) in species.into_iter().enumerate() {
+        let is_valid = |x: f64| x.is_finite() && x >= 0.0;
+        if !is_valid(diffusion_constant) || !is_valid(reaction_rate) {
+            return Err(StabilityError::InvalidParameter(format!(
+                "diffusion constant {diffusion_constant} and reaction rate {reaction_rate} of \
+                species {index} must be non-negative and finite"
+            )));
+        }
+        let max_dt = max_stable_dt(diffusion_constant, reaction_rate, dx);
+        if dt > max_dt {
+            return Err(StabilityError::Unstable {
+                species: index,
+                dt,
+                max_dt,
+                n_substeps: (dt / max_dt).ceil() as usize,
+            });
+        }
+        max_dt_all = max_dt_all.min(max_dt);
+    }
+    Ok(max_dt_all)
+}
+
+/// Checks that the explicit Euler scheme is stable for species which only diffuse.
+///
+/// See [check_reaction_diffusion_stability].
+pub fn check_diffusion_stability<const D: usize>(
+    diffusion_constants: impl IntoIterator<Item = f64>,
+    dx: impl Into<[f64; D]>,
+    dt: f64,
+) -> Result<f64, StabilityError> {
+    check_reaction_diffusion_stability(diffusion_constants.into_iter().map(|d| (d, 0.0)), dx, dt)
+}
+
+#[cfg(test)]
+mod test_stability {
+    use super::*;
+
+    #[test]
+    fn explicit_scheme_stays_positive_at_max_dt() {
+        // Integrate a single peak on a periodic 1D grid at and above the stability limit
+        let integrate = |dt: f64| {
+            let (diffusion_constant, reaction_rate, dx) = (2.0, 0.5, 0.5);
+            let mut u = vec![0.0; 20];
+            u[10] = 1.0;
+            for _ in 0..100 {
+                let n = u.len();
+                u = (0..n)
+                    .map(|i| {
+                        let laplace = (u[(i + 1) % n] - 2.0 * u[i] + u[(i + n - 1) % n]) / dx / dx;
+                        u[i] + dt * (diffusion_constant * laplace - reaction_rate * u[i])
+                    })
+                    .collect();
+            }
+            u.into_iter().fold(f64::INFINITY, f64::min)
+        };
+        let max_dt = check_diffusion_stability([2.0], [0.5], 0.01).unwrap();
+        assert!((max_dt - 1.0 / 16.0).abs() < 1e-12);
+        let max_dt = check_reaction_diffusion_stability([(2.0, 0.5)], [0.5], 0.01).unwrap();
+        assert!(integrate(max_dt) > -1e-12);
+        assert!(integrate(1.5 * max_dt) < 0.0);
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        assert!(matches!(
+            check_diffusion_stability([1.0], [0.0, 1.0], 0.1),
+            Err(StabilityError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            check_diffusion_stability([-1.0], [1.0], 0.1),
+            Err(StabilityError::InvalidParameter(_))
+        ));
+        // Species which neither diffuse nor react are always stable
+        assert_eq!(
+            check_diffusion_stability([0.0], [1.0], 1e6),
+            Ok(f64::INFINITY)
+        );
+        let error = check_diffusion_stability([0.0, 1.0], [1.0], 1.0).unwrap_err();
+        assert!(format!("{error}").contains("species 1"));
+    }
+}
@@ -0,0 +1,335 @@
+use cellular_raza_concepts::*;
+use nalgebra::SVector;
+use serde::{Deserialize, Serialize};
+
+use super::{CartesianCuboid, CartesianSubDomain, ConcentrationField};
+
+/// Stiffness of a substrate which is stored on a regular grid of voxels.
+///
+/// The stiffness is constant within each voxel.
+/// Positions outside of the grid obtain the value of the closest voxel.
+///
+/// ```
+/// # use cellular_raza_building_blocks::*;
+/// # use cellular_raza_concepts::BoundaryError;
+/// # use nalgebra::Vector2;
+/// // Substrate which becomes stiffer along the x-axis
+/// let substrate = StiffnessMap::from_field(
+///     [0.0; 2],
+///     [100.0, 50.0],
+///     [20, 10],
+///     |pos: &Vector2<f64>| 1.0 + 0.1 * pos.x,
+/// )?;
+/// assert_eq!(substrate.stiffness_at(&Vector2::from([51.0, 3.0])), 6.25);
+/// let gradient = substrate.gradient_at(&Vector2::from([51.0, 3.0]));
+/// assert!((gradient - Vector2::from([0.1, 0.0])).norm() < 1e-12);
+/// # Ok::<(), BoundaryError>(())
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct StiffnessMap<const D: usize> {
+    min: SVector<f64, D>,
+    dx: SVector<f64, D>,
+    n_voxels: SVector<usize, D>,
+    values: Vec<f64>,
+}
+
+impl<const D: usize> StiffnessMap<D> {
+    /// Evaluates the given field at the center of every voxel.
+    pub fn from_field(
+        min: impl Into<[f64; D]>,
+        max: impl Into<[f64; D]>,
+        n_voxels: impl Into<[usize; D]>,
+        field: impl ConcentrationField<SVector<f64, D>, f64>,
+    ) -> Result<Self, BoundaryError> {
+        let min: SVector<f64, D> = min.into().into();
+        let max: SVector<f64, D> = max.into().into();
+        let n_voxels: SVector<usize, D> = n_voxels.into().into();
+        for i in 0..D {
+            if min[i].is_nan() || max[i].is_nan() || min[i] >= max[i] || n_voxels[i] == 0 {
+                return Err(BoundaryError(format!(
+                    "stiffness map requires min<max and at least one voxel in every dimension \
+                    but got min={min:?}, max={max:?} and n_voxels={n_voxels:?}"
+                )));
+            }
+        }
+        let dx = (max - min).component_div(&n_voxels.cast::<f64>());
+        let mut map = Self {
+            min,
+            dx,
+            n_voxels,
+            values: Vec::new(),
+        };
+        map.values = (0..n_voxels.product())
+            .map(|plain_index| {
+                let mut remainder = plain_index;
+                let mut center = min;
+                for i in (0..D).rev() {
+                    center[i] += (remainder % n_voxels[i]) as f64 * dx[i] + dx[i] / 2.0;
+                    remainder /= n_voxels[i];
+                }
+                field.concentration_at(&center)
+            })
+            .collect();
+        Ok(map)
+    }
+
+    /// Creates a map with the same voxels as the given [CartesianCuboid].
+    pub fn from_cuboid(
+        cuboid: &CartesianCuboid<f64, D>,
+        field: impl ConcentrationField<SVector<f64, D>, f64>,
+    ) -> Result<Self, BoundaryError> {
+        let n_voxels: [usize; D] = cuboid.get_n_voxels().into();
+        Self::from_field(cuboid.get_min(), cuboid.get_max(), n_voxels, field)
+    }
+
+    fn voxel_index(&self, pos: &SVector<f64, D>) -> SVector<usize, D> {
+        SVector::<usize, D>::from_fn(|i, _| {
+            let index = ((pos[i] - self.min[i]) / self.dx[i]).floor().max(0.0) as usize;
+            index.min(self.n_voxels[i] - 1)
+        })
+    }
+
+    fn value(&self, index: &SVector<usize, D>) -> f64 {
+        let plain_index = (0..D).fold(0, |acc, i| acc * self.n_voxels[i] + index[i]);
+        self.values[plain_index]
+    }
+
+    /// Stiffness of the voxel which contains the given position
+    pub fn stiffness_at(&self, pos: &SVector<f64, D>) -> f64 {
+        self.value(&self.voxel_index(pos))
+    }
+
+    /// Gradient of the stiffness obtained by central differences of neighboring voxels.
+    ///
+    /// Voxels at the border of the map use one-sided differences.
+    /// Dimensions which only contain a single voxel have zero gradient.
+    pub fn gradient_at(&self, pos: &SVector<f64, D>) -> SVector<f64, D> {
+        let index = self.voxel_index(pos);
+        SVector::<f64, D>::from_fn(|i, _| {
+            let mut lower = index;
+            let mut upper = index;
+            lower[i] = index[i].saturating_sub(1);
+            upper[i] = (index[i] + 1).min(self.n_voxels[i] - 1);
+            match upper[i] - lower[i] {
+                0 => 0.0,
+                n => (self.value(&upper) - self.value(&lower)) / (n as f64 * self.dx[i]),
+            }
+        })
+    }
+}
+
+/// Migration of cells whose propulsion and adhesion depend on the stiffness of the substrate.
+///
+/// Cells sense the stiffness $E$ and its gradient at their position and migrate towards stiffer
+/// regions (durotaxis).
+/// The resulting force consists of a propulsion along the relative gradient and an adhesive
+/// friction which increases with the stiffness of the substrate
+/// \\begin{equation}
+///     \vec{F} = \chi\frac{\nabla E}{K + E} - \left(\gamma_0 + \gamma_1\frac{E}{K+E}\right)\vec{v}.
+/// \\end{equation}
+///
+/// # Parameters
+/// | Symbol | Struct Field | Description |
+/// |:---:| --- | --- |
+/// | $\chi$ | `sensitivity` | Strength of propulsion along the stiffness gradient |
+/// | $K$ | `half_saturation` | Stiffness at which sensing and adhesion are half-saturated |
+/// | $\gamma_0$ | `base_adhesion` | Friction on infinitely soft substrates |
+/// | $\gamma_1$ | `stiffness_adhesion` | Additional friction on infinitely stiff substrates |
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DurotacticMigration {
+    /// Strength $\chi$ of propulsion along the stiffness gradient
+    pub sensitivity: f64,
+    /// Stiffness $K$ at which sensing and adhesion are half-saturated
+    pub half_saturation: f64,
+    /// Friction $\gamma_0$ on infinitely soft substrates
+    pub base_adhesion: f64,
+    /// Additional friction $\gamma_1$ on infinitely stiff substrates
+    pub stiffness_adhesion: f64,
+}
+
+impl DurotacticMigration {
+    /// Force acting on a cell with the given velocity on a substrate with given stiffness and
+    /// gradient
+    pub fn force<const D: usize>(
+        &self,
+        stiffness: f64,
+        gradient: &SVector<f64, D>,
+        velocity: &SVector<f64, D>,
+    ) -> SVector<f64, D> {
+        let saturation = self.half_saturation + stiffness;
+        let adhesion = self.base_adhesion + self.stiffness_adhesion * stiffness / saturation;
+        self.sensitivity / saturation * gradient - adhesion * velocity
+    }
+}
+
+/// Applies [DurotacticMigration] on a substrate given by a [StiffnessMap] to all cells.
+///
+/// This struct implements the [SubDomainForce] trait and can thus be used as `#[Force]` field
+/// of custom subdomains.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SubstrateForce<const D: usize> {
+    /// Stiffness of the substrate
+    pub substrate: StiffnessMap<D>,
+    /// Response of cells to the substrate
+    pub migration: DurotacticMigration,
+}
+
+impl<const D: usize> SubDomainForce<SVector<f64, D>, SVector<f64, D>, SVector<f64, D>>
+    for SubstrateForce<D>
+{
+    fn calculate_custom_force(
+        &self,
+        pos: &SVector<f64, D>,
+        vel: &SVector<f64, D>,
+    ) -> Result<SVector<f64, D>, CalcError> {
+        let stiffness = self.substrate.stiffness_at(pos);
+        let gradient = self.substrate.gradient_at(pos);
+        Ok(self.migration.force(stiffness, &gradient, vel))
+    }
+}
+
+/// [CartesianCuboid] with a substrate on which cells migrate by [DurotacticMigration].
+///
+/// Simulations need to include the `DomainForce` aspect.
+///
+/// ```
+/// # use cellular_raza_building_blocks::*;
+/// # use nalgebra::Vector2;
+/// let cuboid =
+///     CartesianCuboid::from_boundaries_and_interaction_range([0.0; 2], [100.0; 2], 10.0)?;
+/// // Stiff stripe in the middle of the domain
+/// let substrate = StiffnessMap::from_cuboid(&cuboid, |pos: &Vector2<f64>| {
+///     if (pos.x - 50.0).abs() < 20.0 { 10.0 } else { 1.0 }
+/// })?;
+/// let domain = SubstrateCuboid {
+///     cuboid,
+///     force: SubstrateForce {
+///         substrate,
+///         migration: DurotacticMigration {
+///             sensitivity: 1.0,
+///             half_saturation: 5.0,
+///             base_adhesion: 0.1,
+///             stiffness_adhesion: 1.0,
+///         },
+///     },
+/// };
+/// # Ok::<(), cellular_raza_concepts::BoundaryError>(())
+/// ```
+#[derive(Clone, Debug, Domain)]
+pub struct SubstrateCuboid<const D: usize> {
+    /// Underlying domain which sorts cells into voxels
+    #[DomainRngSeed]
+    #[SortCells]
+    pub cuboid: CartesianCuboid<f64, D>,
+    /// Force which is applied to all cells
+    pub force: SubstrateForce<D>,
+}
+
+/// Subdomain of the [SubstrateCuboid].
+#[derive(Clone, Debug, Serialize, SubDomain)]
+pub struct SubstrateSubDomain<const D: usize> {
+    /// Subdomain of the underlying [CartesianCuboid]
+    #[Base]
+    #[SortCells]
+    #[Mechanics]
+    pub subdomain: CartesianSubDomain<f64, D>,
+    /// Force which is applied to all cells
+    #[Force]
+    pub force: SubstrateForce<D>,
+}
+
+impl<const D: usize> DomainCreateSubDomains<SubstrateSubDomain<D>> for SubstrateCuboid<D> {
+    type VoxelIndex = [usize; D];
+    type SubDomainIndex = usize;
+
+    fn create_subdomains(
+        &self,
+        n_subdomains: core::num::NonZeroUsize,
+    ) -> Result<
+        impl IntoIterator<
+            Item = (
+                Self::SubDomainIndex,
+                SubstrateSubDomain<D>,
+                Vec<Self::VoxelIndex>,
+            ),
+        >,
+        DecomposeError,
+    > {
+        Ok(self
+            .cuboid
+            .create_subdomains(n_subdomains)?
+            .into_iter()
+            .map(|(index, subdomain, voxels)| {
+                let subdomain = SubstrateSubDomain {
+                    subdomain,
+                    force: self.force.clone(),
+                };
+                (index, subdomain, voxels)
+            }))
+    }
+}
+
+#[cfg(test)]
+mod test_substrate {
+    use super::*;
+    use nalgebra::{Vector1, Vector2, Vector3};
+
+    #[test]
+    fn linear_stiffness() -> Result<(), BoundaryError> {
+        let slope = Vector3::from([0.5, -1.0, 2.0]);
+        let substrate =
+            StiffnessMap::from_field([-1.0; 3], [3.0; 3], [8, 4, 16], |x: &_| 10.0 + slope.dot(x))?;
+        for pos in [[0.0, 0.0, 0.0], [-1.0, 2.9, 1.2], [2.99, 2.99, -0.99]] {
+            let pos = Vector3::from(pos);
+            assert!((substrate.gradient_at(&pos) - slope).norm() < 1e-12);
+        }
+        // Values are taken at the center of voxels
+        let center = Vector3::from([-0.75, -0.5, -0.875]);
+        assert!((substrate.stiffness_at(&center) - 10.0 - slope.dot(&center)).abs() < 1e-12);
+        // Outside of the map, the closest voxel is used
+        let outside = Vector3::from([-5.0, 1.0, 1.0]);
+        assert_eq!(
+            substrate.stiffness_at(&outside),
+            substrate.stiffness_at(&Vector3::from([-0.9, 1.0, 1.0]))
+        );
+        assert!(StiffnessMap::from_field([0.0], [1.0], [0], |_: &Vector1<f64>| 1.0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn cells_migrate_to_stiff_region() -> Result<(), Box<dyn std::error::Error>> {
+        let force = SubstrateForce {
+            substrate: StiffnessMap::from_field(
+                [0.0; 2],
+                [100.0; 2],
+                [50, 50],
+                |x: &Vector2<_>| 1.0 + 0.2 * x.x,
+            )?,
+            migration: DurotacticMigration {
+                sensitivity: 50.0,
+                half_saturation: 5.0,
+                base_adhesion: 0.5,
+                stiffness_adhesion: 2.0,
+            },
+        };
+        let mut cells = vec![
+            (Vector2::from([20.0, 50.0]), Vector2::zeros()),
+            (Vector2::from([40.0, 10.0]), Vector2::zeros()),
+        ];
+        let dt = 0.1;
+        for _ in 0..1_000 {
+            for (pos, vel) in cells.iter_mut() {
+                *vel += dt * force.calculate_custom_force(pos, vel)?;
+                *pos += dt * *vel;
+            }
+        }
+        let positions: Vec<_> = cells.into_iter().map(|(pos, _)| pos).collect();
+        assert!(positions[0].x > 30.0 && (positions[0].y - 50.0).abs() < 1e-9);
+        assert!(positions[1].x > 50.0);
+        // Adhesion slows cells down
+        let f =
+            force.calculate_custom_force(&Vector2::from([50.0; 2]), &Vector2::from([1.0, 0.0]))?;
+        assert!(f.x < 0.0);
+        Ok(())
+    }
+}
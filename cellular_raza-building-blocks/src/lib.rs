@@ -60,10 +60,24 @@
 #![deny(missing_docs)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+mod analysis;
 mod cell_building_blocks;
 mod cell_models;
 mod domains;
+mod morpheus;
+mod population;
+mod positivity;
+mod scenarios;
+mod species;
+mod validation;
 
+pub use analysis::*;
 pub use cell_building_blocks::*;
 pub use cell_models::*;
 pub use domains::*;
+pub use morpheus::*;
+pub use population::*;
+pub use positivity::*;
+pub use scenarios::*;
+pub use species::*;
+pub use validation::*;
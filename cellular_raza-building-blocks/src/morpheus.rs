@@ -0,0 +1,500 @@
+use crate::{CartesianCuboid, MorsePotential};
+use cellular_raza_concepts::BoundaryError;
+
+use quick_xml::events::{BytesStart, Event};
+use serde::{Deserialize, Serialize};
+
+use core::fmt::Display;
+use std::collections::BTreeMap;
+use std::error::Error;
+
+/// Errors which can occur while importing a [MorpheusModel].
+#[derive(Debug)]
+pub enum MorpheusImportError {
+    /// Reading the underlying file failed.
+    IoError(std::io::Error),
+    /// The document is not valid xml.
+    XmlError(String),
+    /// A required attribute of a supported element is missing.
+    MissingAttribute {
+        /// Name of the element
+        element: String,
+        /// Name of the missing attribute
+        attribute: String,
+    },
+    /// An attribute could not be parsed.
+    InvalidValue {
+        /// Name of the element
+        element: String,
+        /// Name of the attribute
+        attribute: String,
+        /// The value which could not be parsed
+        value: String,
+    },
+}
+
+impl Display for MorpheusImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            MorpheusImportError::IoError(e) => write!(f, "{e}"),
+            MorpheusImportError::XmlError(message) => write!(f, "invalid xml: {message}"),
+            MorpheusImportError::MissingAttribute { element, attribute } => {
+                write!(
+                    f,
+                    "element <{element}> is missing attribute \"{attribute}\""
+                )
+            }
+            MorpheusImportError::InvalidValue {
+                element,
+                attribute,
+                value,
+            } => write!(
+                f,
+                "could not parse value \"{value}\" of attribute \"{attribute}\" in <{element}>"
+            ),
+        }
+    }
+}
+
+impl Error for MorpheusImportError {}
+
+impl From<std::io::Error> for MorpheusImportError {
+    fn from(err: std::io::Error) -> Self {
+        MorpheusImportError::IoError(err)
+    }
+}
+
+impl From<quick_xml::Error> for MorpheusImportError {
+    fn from(err: quick_xml::Error) -> Self {
+        MorpheusImportError::XmlError(format!("{err}"))
+    }
+}
+
+impl From<MorpheusImportError> for cellular_raza_concepts::SetupError {
+    fn from(err: MorpheusImportError) -> Self {
+        cellular_raza_concepts::SetupError(format!("{err}"))
+    }
+}
+
+/// Cell type as specified in the `<CellTypes>` section of a Morpheus model.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct MorpheusCellType {
+    /// Name of the cell type
+    pub name: String,
+    /// Class of the cell type, typically `"biological"` or `"medium"`
+    pub class: String,
+    /// Target volume of the `<VolumeConstraint>`
+    pub target_volume: Option<f64>,
+    /// Strength of the `<VolumeConstraint>`
+    pub volume_strength: Option<f64>,
+    /// Constant values given by `<Property symbol=".." value=".."/>`.
+    /// Properties defined by expressions are reported as unsupported.
+    pub properties: BTreeMap<String, f64>,
+}
+
+impl MorpheusCellType {
+    /// Radius of a sphere in `D` dimensions with the target volume of this cell type.
+    ///
+    /// Returns `None` if no volume was specified or `D` is not 1, 2 or 3.
+    pub fn radius<const D: usize>(&self) -> Option<f64> {
+        let volume = self.target_volume?;
+        match D {
+            1 => Some(volume / 2.0),
+            2 => Some((volume / core::f64::consts::PI).sqrt()),
+            3 => Some((3.0 * volume / (4.0 * core::f64::consts::PI)).cbrt()),
+            _ => None,
+        }
+    }
+}
+
+/// Diffusible field as specified by `<Field>` in the `<Global>` section.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct MorpheusField {
+    /// Symbol of the field
+    pub symbol: String,
+    /// Initial (homogeneous) value of the field
+    pub initial_value: f64,
+    /// Rate of the `<Diffusion>` child element
+    pub diffusion_rate: Option<f64>,
+}
+
+/// Subset of a [Morpheus](https://morpheus.gitlab.io/) model description.
+///
+/// Only components which have a counterpart in `cellular_raza` are parsed.
+/// All other elements are collected in [unsupported](MorpheusModel::unsupported) such that users
+/// can check explicitly which parts of the original model were not translated.
+///
+/// | Morpheus | cellular_raza |
+/// | --- | --- |
+/// | `Space/Lattice/Size` | [CartesianCuboid] via [MorpheusModel::cartesian_cuboid] |
+/// | `CellTypes/CellType` | [MorpheusCellType] |
+/// | `CPM/Interaction/Contact` | [MorsePotential] via [MorpheusModel::morse_potential] |
+/// | `Global/Field` | [MorpheusField] |
+///
+/// ```
+/// # use cellular_raza_building_blocks::MorpheusModel;
+/// let xml = r#"
+/// <MorpheusModel version="4">
+///     <Space><Lattice class="square"><Size symbol="size" value="100, 50, 0"/></Lattice></Space>
+///     <Time><StartTime value="0"/><StopTime value="500"/></Time>
+///     <CellTypes>
+///         <CellType class="biological" name="epithelial">
+///             <VolumeConstraint target="314.15" strength="1"/>
+///         </CellType>
+///         <CellType class="medium" name="medium"/>
+///     </CellTypes>
+///     <CPM><Interaction>
+///         <Contact type1="epithelial" type2="epithelial" value="2"/>
+///         <Contact type1="epithelial" type2="medium" value="6"/>
+///     </Interaction></CPM>
+/// </MorpheusModel>"#;
+/// let model = MorpheusModel::from_str(xml)?;
+/// assert_eq!(model.domain_size, vec![100.0, 50.0, 0.0]);
+/// let _domain = model.cartesian_cuboid::<2>(20.0)?;
+/// let potential = model.morse_potential::<2>("epithelial", 0.5, 30.0).unwrap();
+/// assert_eq!(potential.strength, 5.0);
+/// assert!(model.unsupported.is_empty());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct MorpheusModel {
+    /// Size of the lattice
+    pub domain_size: Vec<f64>,
+    /// Value of `<StartTime>`
+    pub start_time: Option<f64>,
+    /// Value of `<StopTime>`
+    pub stop_time: Option<f64>,
+    /// All cell types including the medium
+    pub cell_types: Vec<MorpheusCellType>,
+    /// Contact energies between two cell types
+    pub contact_energies: Vec<(String, String, f64)>,
+    /// Diffusible fields
+    pub fields: Vec<MorpheusField>,
+    /// Paths of all elements which could not be translated, such as `CPM/MonteCarloSampler`
+    pub unsupported: Vec<String>,
+}
+
+const SUPPORTED_PATHS: &[&str] = &[
+    "MorpheusModel",
+    "MorpheusModel/Description",
+    "MorpheusModel/Space",
+    "MorpheusModel/Space/Lattice",
+    "MorpheusModel/Space/Lattice/Size",
+    "MorpheusModel/Space/Lattice/BoundaryConditions",
+    "MorpheusModel/Space/Lattice/Neighborhood",
+    "MorpheusModel/Space/SpaceSymbol",
+    "MorpheusModel/Time",
+    "MorpheusModel/Time/StartTime",
+    "MorpheusModel/Time/StopTime",
+    "MorpheusModel/Time/TimeSymbol",
+    "MorpheusModel/CellTypes",
+    "MorpheusModel/CellTypes/CellType",
+    "MorpheusModel/CellTypes/CellType/VolumeConstraint",
+    "MorpheusModel/CellTypes/CellType/Property",
+    "MorpheusModel/CPM",
+    "MorpheusModel/CPM/Interaction",
+    "MorpheusModel/CPM/Interaction/Contact",
+    "MorpheusModel/Global",
+    "MorpheusModel/Global/Field",
+    "MorpheusModel/Global/Field/Diffusion",
+];
+
+fn is_supported(path: &str) -> bool {
+    SUPPORTED_PATHS.contains(&path)
+        || path.starts_with("MorpheusModel/Description/")
+        || path.starts_with("MorpheusModel/Space/Lattice/BoundaryConditions/")
+        || path.starts_with("MorpheusModel/Space/Lattice/Neighborhood/")
+}
+
+fn get_attribute(
+    element: &BytesStart,
+    attribute: &str,
+) -> Result<Option<String>, MorpheusImportError> {
+    let element_name = String::from_utf8_lossy(element.name().as_ref()).to_string();
+    match element
+        .try_get_attribute(attribute)
+        .map_err(|e| MorpheusImportError::XmlError(format!("{e}")))?
+    {
+        Some(attr) => Ok(Some(
+            attr.unescape_value()
+                .map_err(|e| MorpheusImportError::XmlError(format!("{e} in <{element_name}>")))?
+                .to_string(),
+        )),
+        None => Ok(None),
+    }
+}
+
+fn require_attribute(element: &BytesStart, attribute: &str) -> Result<String, MorpheusImportError> {
+    get_attribute(element, attribute)?.ok_or(MorpheusImportError::MissingAttribute {
+        element: String::from_utf8_lossy(element.name().as_ref()).to_string(),
+        attribute: attribute.to_owned(),
+    })
+}
+
+fn parse_f64(
+    element: &BytesStart,
+    attribute: &str,
+    value: &str,
+) -> Result<f64, MorpheusImportError> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| MorpheusImportError::InvalidValue {
+            element: String::from_utf8_lossy(element.name().as_ref()).to_string(),
+            attribute: attribute.to_owned(),
+            value: value.to_owned(),
+        })
+}
+
+fn require_f64(element: &BytesStart, attribute: &str) -> Result<f64, MorpheusImportError> {
+    let value = require_attribute(element, attribute)?;
+    parse_f64(element, attribute, &value)
+}
+
+fn optional_f64(element: &BytesStart, attribute: &str) -> Result<Option<f64>, MorpheusImportError> {
+    get_attribute(element, attribute)?
+        .map(|value| parse_f64(element, attribute, &value))
+        .transpose()
+}
+
+impl MorpheusModel {
+    /// Reads and parses a Morpheus model file.
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self, MorpheusImportError> {
+        let contents = std::fs::read_to_string(path)?;
+        Self::from_str(&contents)
+    }
+
+    /// Parses the contents of a Morpheus model file.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(xml: &str) -> Result<Self, MorpheusImportError> {
+        let mut reader = quick_xml::Reader::from_str(xml);
+        reader.config_mut().trim_text(true);
+        let mut model = MorpheusModel::default();
+        let mut path: Vec<String> = Vec::new();
+        // Depth of the outermost unsupported element which we are currently inside of
+        let mut unsupported_depth: Option<usize> = None;
+        loop {
+            let (element, is_empty) = match reader.read_event()? {
+                Event::Start(e) => (e, false),
+                Event::Empty(e) => (e, true),
+                Event::End(_) => {
+                    path.pop();
+                    if unsupported_depth.is_some_and(|d| d > path.len()) {
+                        unsupported_depth = None;
+                    }
+                    continue;
+                }
+                Event::Eof => break,
+                _ => continue,
+            };
+            path.push(String::from_utf8_lossy(element.name().as_ref()).to_string());
+            let full_path = path.join("/");
+            if unsupported_depth.is_none() {
+                if is_supported(&full_path) {
+                    model.handle_element(&full_path, &element)?;
+                } else {
+                    model.unsupported.push(
+                        full_path
+                            .strip_prefix("MorpheusModel/")
+                            .unwrap_or(&full_path)
+                            .to_owned(),
+                    );
+                    unsupported_depth = Some(path.len());
+                }
+            }
+            if is_empty {
+                path.pop();
+                if unsupported_depth.is_some_and(|d| d > path.len()) {
+                    unsupported_depth = None;
+                }
+            }
+        }
+        Ok(model)
+    }
+
+    fn handle_element(&mut self, path: &str, e: &BytesStart) -> Result<(), MorpheusImportError> {
+        match path {
+            "MorpheusModel/Space/Lattice/Size" => {
+                let value = require_attribute(e, "value")?;
+                self.domain_size = value
+                    .split([',', ' '])
+                    .filter(|s| !s.is_empty())
+                    .map(|s| parse_f64(e, "value", s))
+                    .collect::<Result<_, _>>()?;
+            }
+            "MorpheusModel/Time/StartTime" => self.start_time = Some(require_f64(e, "value")?),
+            "MorpheusModel/Time/StopTime" => self.stop_time = Some(require_f64(e, "value")?),
+            "MorpheusModel/CellTypes/CellType" => self.cell_types.push(MorpheusCellType {
+                name: require_attribute(e, "name")?,
+                class: get_attribute(e, "class")?.unwrap_or("biological".to_owned()),
+                target_volume: None,
+                volume_strength: None,
+                properties: BTreeMap::new(),
+            }),
+            "MorpheusModel/CellTypes/CellType/VolumeConstraint" => {
+                if let Some(cell_type) = self.cell_types.last_mut() {
+                    cell_type.target_volume = Some(require_f64(e, "target")?);
+                    cell_type.volume_strength = optional_f64(e, "strength")?;
+                }
+            }
+            "MorpheusModel/CellTypes/CellType/Property" => {
+                // Properties may also be given by expressions which we can not evaluate
+                let symbol = require_attribute(e, "symbol")?;
+                let value = require_attribute(e, "value")?;
+                match (value.trim().parse(), self.cell_types.last_mut()) {
+                    (Ok(value), Some(cell_type)) => {
+                        cell_type.properties.insert(symbol, value);
+                    }
+                    _ => self
+                        .unsupported
+                        .push(format!("CellTypes/CellType/Property[{symbol}]")),
+                }
+            }
+            "MorpheusModel/CPM/Interaction/Contact" => self.contact_energies.push((
+                require_attribute(e, "type1")?,
+                require_attribute(e, "type2")?,
+                require_f64(e, "value")?,
+            )),
+            "MorpheusModel/Global/Field" => self.fields.push(MorpheusField {
+                symbol: require_attribute(e, "symbol")?,
+                initial_value: optional_f64(e, "value")?.unwrap_or(0.0),
+                diffusion_rate: None,
+            }),
+            "MorpheusModel/Global/Field/Diffusion" => {
+                if let Some(field) = self.fields.last_mut() {
+                    field.diffusion_rate = Some(require_f64(e, "rate")?);
+                }
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    /// Returns the cell type with the given name.
+    pub fn cell_type(&self, name: &str) -> Option<&MorpheusCellType> {
+        self.cell_types.iter().find(|c| c.name == name)
+    }
+
+    /// Contact energy between the two cell types regardless of their order.
+    pub fn contact_energy(&self, type1: &str, type2: &str) -> Option<f64> {
+        self.contact_energies
+            .iter()
+            .find(|(t1, t2, _)| (t1 == type1 && t2 == type2) || (t1 == type2 && t2 == type1))
+            .map(|(_, _, value)| *value)
+    }
+
+    /// Constructs a domain spanning from the origin to the size of the lattice.
+    pub fn cartesian_cuboid<const D: usize>(
+        &self,
+        interaction_range: f64,
+    ) -> Result<CartesianCuboid<f64, D>, BoundaryError> {
+        if self.domain_size.len() < D {
+            return Err(BoundaryError(format!(
+                "lattice size {:?} has fewer than {} dimensions",
+                self.domain_size, D
+            )));
+        }
+        let mut max = [0.0; D];
+        max.copy_from_slice(&self.domain_size[..D]);
+        CartesianCuboid::from_boundaries_and_interaction_range([0.0; D], max, interaction_range)
+    }
+
+    /// Constructs a [MorsePotential] for the given cell type.
+    ///
+    /// The radius is derived from the target volume (see [MorpheusCellType::radius]).
+    /// The strength is given by the effective surface tension
+    /// $\gamma = J_{c,m} - J_{c,c}/2$ between the cell type $c$ and the medium $m$ which is
+    /// clamped to be non-negative.
+    /// Returns `None` if the cell type, its volume or the required contact energies are missing.
+    pub fn morse_potential<const D: usize>(
+        &self,
+        cell_type: &str,
+        potential_stiffness: f64,
+        cutoff: f64,
+    ) -> Option<MorsePotential> {
+        let radius = self.cell_type(cell_type)?.radius::<D>()?;
+        let medium = self.cell_types.iter().find(|c| c.class == "medium")?;
+        let j_cm = self.contact_energy(cell_type, &medium.name)?;
+        let j_cc = self.contact_energy(cell_type, cell_type)?;
+        Some(MorsePotential {
+            radius,
+            potential_stiffness,
+            cutoff,
+            strength: (j_cm - j_cc / 2.0).max(0.0),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test_morpheus {
+    use super::*;
+
+    const MODEL: &str = r#"<?xml version='1.0' encoding='UTF-8'?>
+<MorpheusModel version="4">
+    <Description><Title>Test</Title></Description>
+    <Space>
+        <Lattice class="cubic"><Size symbol="size" value="200, 100, 50"/></Lattice>
+    </Space>
+    <Time><StartTime value="0"/><StopTime value="1e3"/></Time>
+    <CellTypes>
+        <CellType class="biological" name="ct1">
+            <VolumeConstraint target="4188.79" strength="1"/>
+            <Property symbol="p" value="0.5"/>
+            <Chemotaxis field="U" strength="1"/>
+        </CellType>
+        <CellType class="medium" name="medium"/>
+    </CellTypes>
+    <CPM>
+        <Interaction><Contact type1="ct1" type2="medium" value="4"/></Interaction>
+        <MonteCarloSampler stepper="edgelist"><MCSDuration value="1"/></MonteCarloSampler>
+    </CPM>
+    <Global>
+        <Field symbol="U" value="0.1"><Diffusion rate="2.5"/></Field>
+    </Global>
+    <Analysis><Logger/></Analysis>
+</MorpheusModel>"#;
+
+    #[test]
+    fn parse_supported_components() {
+        let model = MorpheusModel::from_str(MODEL).unwrap();
+        assert_eq!(model.domain_size, vec![200.0, 100.0, 50.0]);
+        assert_eq!(model.stop_time, Some(1e3));
+        assert_eq!(model.cell_types.len(), 2);
+        let ct1 = model.cell_type("ct1").unwrap();
+        assert_eq!(ct1.properties["p"], 0.5);
+        assert!((ct1.radius::<3>().unwrap() - 10.0).abs() < 1e-4);
+        assert_eq!(model.contact_energy("medium", "ct1"), Some(4.0));
+        assert_eq!(
+            model.fields,
+            vec![MorpheusField {
+                symbol: "U".into(),
+                initial_value: 0.1,
+                diffusion_rate: Some(2.5),
+            }]
+        );
+    }
+
+    #[test]
+    fn report_unsupported() {
+        let model = MorpheusModel::from_str(MODEL).unwrap();
+        assert_eq!(
+            model.unsupported,
+            vec![
+                "CellTypes/CellType/Chemotaxis",
+                "CPM/MonteCarloSampler",
+                "Analysis"
+            ]
+        );
+        // Missing self-contact energy
+        assert!(model.morse_potential::<3>("ct1", 1.0, 20.0).is_none());
+    }
+
+    #[test]
+    fn invalid_value() {
+        let xml = r#"<MorpheusModel><Time><StopTime value="abc"/></Time></MorpheusModel>"#;
+        match MorpheusModel::from_str(xml) {
+            Err(MorpheusImportError::InvalidValue { value, .. }) => assert_eq!(value, "abc"),
+            x => panic!("unexpected result {x:?}"),
+        }
+    }
+}
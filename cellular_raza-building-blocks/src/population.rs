@@ -0,0 +1,529 @@
+use nalgebra::SVector;
+use serde::{Deserialize, Serialize};
+
+use core::fmt::Display;
+use std::error::Error;
+use std::io::BufRead;
+
+/// Errors which can occur while importing a [Population] from a file.
+#[derive(Debug)]
+pub enum PopulationImportError {
+    /// Reading the underlying file failed.
+    IoError(std::io::Error),
+    /// A row of the file could not be parsed.
+    MalformedRow {
+        /// Line number (starting at 1) of the row in the original file.
+        line: usize,
+        /// Description of what went wrong.
+        message: String,
+    },
+    /// The header or the [ColumnMapping] is inconsistent.
+    MappingError(String),
+    /// The user-supplied template returned an error when constructing an agent.
+    TemplateError {
+        /// Line number (starting at 1) of the row in the original file.
+        line: usize,
+        /// Error returned by the template.
+        message: String,
+    },
+}
+
+impl Display for PopulationImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PopulationImportError::IoError(e) => write!(f, "{e}"),
+            PopulationImportError::MalformedRow { line, message } => {
+                write!(f, "malformed row at line {line}: {message}")
+            }
+            PopulationImportError::MappingError(message) => write!(f, "{message}"),
+            PopulationImportError::TemplateError { line, message } => {
+                write!(f, "could not construct agent from line {line}: {message}")
+            }
+        }
+    }
+}
+
+impl Error for PopulationImportError {}
+
+impl From<std::io::Error> for PopulationImportError {
+    fn from(err: std::io::Error) -> Self {
+        PopulationImportError::IoError(err)
+    }
+}
+
+impl From<PopulationImportError> for cellular_raza_concepts::SetupError {
+    fn from(err: PopulationImportError) -> Self {
+        cellular_raza_concepts::SetupError(format!("{err}"))
+    }
+}
+
+/// Describes which columns (CSV) or keys (NDJSON) contain which property of a cell.
+///
+/// ```
+/// # use cellular_raza_building_blocks::ColumnMapping;
+/// let mapping = ColumnMapping::new(["x", "y"])
+///     .velocity(["vx", "vy"])
+///     .radius("r")
+///     .species("type");
+/// ```
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ColumnMapping {
+    /// Names of the columns holding the components of the position.
+    pub position: Vec<String>,
+    /// Names of the columns holding the components of the velocity.
+    pub velocity: Option<Vec<String>>,
+    /// Name of the column holding the radius.
+    pub radius: Option<String>,
+    /// Name of the column holding the species tag.
+    pub species: Option<String>,
+    /// Delimiter used to separate values in CSV files.
+    pub delimiter: char,
+}
+
+impl ColumnMapping {
+    /// Creates a new mapping which only reads positions from the given columns.
+    pub fn new<S: Into<String>>(position: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            position: position.into_iter().map(Into::into).collect(),
+            velocity: None,
+            radius: None,
+            species: None,
+            delimiter: ',',
+        }
+    }
+
+    /// Also read velocities from the given columns.
+    pub fn velocity<S: Into<String>>(self, velocity: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            velocity: Some(velocity.into_iter().map(Into::into).collect()),
+            ..self
+        }
+    }
+
+    /// Also read the radius from the given column.
+    pub fn radius(self, radius: impl Into<String>) -> Self {
+        Self {
+            radius: Some(radius.into()),
+            ..self
+        }
+    }
+
+    /// Also read a species tag from the given column.
+    pub fn species(self, species: impl Into<String>) -> Self {
+        Self {
+            species: Some(species.into()),
+            ..self
+        }
+    }
+
+    /// Use a different delimiter than `','` for CSV files.
+    pub fn delimiter(self, delimiter: char) -> Self {
+        Self { delimiter, ..self }
+    }
+
+    /// Checks that the position and velocity are given by `D` columns each.
+    ///
+    /// If `allow_arrays` is set, a single key which contains an array of `D` entries is also
+    /// accepted.
+    fn check_dimension<const D: usize>(
+        &self,
+        allow_arrays: bool,
+    ) -> Result<(), PopulationImportError> {
+        let check = |name: &str, keys: &Vec<String>| {
+            let is_array = allow_arrays && keys.len() == 1 && D != 1;
+            if keys.len() != D && !is_array {
+                return Err(PopulationImportError::MappingError(format!(
+                    "expected {D} {name} columns but mapping specifies {}",
+                    keys.len()
+                )));
+            }
+            Ok(())
+        };
+        check("position", &self.position)?;
+        match &self.velocity {
+            Some(v) => check("velocity", v),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Properties of a single cell as read from one row of an input file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CellRecord<const D: usize> {
+    /// Line number (starting at 1) of the row in the original file.
+    pub line: usize,
+    /// Position of the cell
+    pub pos: SVector<f64, D>,
+    /// Velocity of the cell if specified by the [ColumnMapping]
+    pub vel: Option<SVector<f64, D>>,
+    /// Radius of the cell if specified by the [ColumnMapping]
+    pub radius: Option<f64>,
+    /// Species tag of the cell if specified by the [ColumnMapping]
+    pub species: Option<String>,
+}
+
+/// A collection of agents which can be used as initial condition of a simulation.
+///
+/// Agents can be imported from tracked experimental data in
+/// [CSV](Population::from_csv) or [NDJSON](Population::from_ndjson) format.
+/// Every row is converted into a [CellRecord] which is handed to a user-defined template that
+/// constructs the final agent.
+///
+/// ```
+/// # use cellular_raza_building_blocks::*;
+/// let data = "\
+/// x,y,r,type
+/// 1.0,2.0,0.5,A
+/// 3.0,4.0,0.7,B
+/// ";
+/// let mapping = ColumnMapping::new(["x", "y"]).radius("r").species("type");
+/// let population = Population::from_csv_reader(data.as_bytes(), &mapping, |record| {
+///     Ok::<_, String>(NewtonDamped2D {
+///         pos: record.pos,
+///         vel: [0.0; 2].into(),
+///         damping_constant: 0.1,
+///         mass: record.radius.unwrap_or(1.0),
+///     })
+/// })?;
+/// assert_eq!(population.len(), 2);
+/// # Ok::<(), PopulationImportError>(())
+/// ```
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Population<C> {
+    /// All agents of this population
+    pub cells: Vec<C>,
+}
+
+impl<C> Population<C> {
+    /// Number of agents in the population
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Returns `true` if the population does not contain any agents.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Reads a CSV file with a header line and constructs agents from every row.
+    pub fn from_csv<const D: usize, E>(
+        path: impl AsRef<std::path::Path>,
+        mapping: &ColumnMapping,
+        template: impl FnMut(CellRecord<D>) -> Result<C, E>,
+    ) -> Result<Self, PopulationImportError>
+    where
+        E: Display,
+    {
+        let file = std::fs::File::open(path)?;
+        Self::from_csv_reader(std::io::BufReader::new(file), mapping, template)
+    }
+
+    /// Similar to [Population::from_csv] but reads from an arbitrary reader.
+    pub fn from_csv_reader<const D: usize, E>(
+        reader: impl BufRead,
+        mapping: &ColumnMapping,
+        mut template: impl FnMut(CellRecord<D>) -> Result<C, E>,
+    ) -> Result<Self, PopulationImportError>
+    where
+        E: Display,
+    {
+        mapping.check_dimension::<D>(false)?;
+        let mut lines = reader
+            .lines()
+            .enumerate()
+            .map(|(n, line)| (n + 1, line))
+            .filter(|(_, line)| match line {
+                Ok(l) => !l.trim().is_empty() && !l.trim_start().starts_with('#'),
+                Err(_) => true,
+            });
+        let header = match lines.next() {
+            Some((_, line)) => line?,
+            None => return Ok(Self { cells: Vec::new() }),
+        };
+        let header: Vec<_> = header
+            .split(mapping.delimiter)
+            .map(|s| s.trim().to_owned())
+            .collect();
+        let find_column = |name: &String| -> Result<usize, PopulationImportError> {
+            header.iter().position(|h| h == name).ok_or_else(|| {
+                PopulationImportError::MappingError(format!(
+                    "column \"{name}\" is not present in header {header:?}"
+                ))
+            })
+        };
+        let pos_columns = mapping
+            .position
+            .iter()
+            .map(find_column)
+            .collect::<Result<Vec<_>, _>>()?;
+        let vel_columns = mapping
+            .velocity
+            .as_ref()
+            .map(|v| v.iter().map(find_column).collect::<Result<Vec<_>, _>>())
+            .transpose()?;
+        let radius_column = mapping.radius.as_ref().map(find_column).transpose()?;
+        let species_column = mapping.species.as_ref().map(find_column).transpose()?;
+
+        let mut cells = Vec::new();
+        for (line, content) in lines {
+            let content = content?;
+            let entries: Vec<_> = content.split(mapping.delimiter).map(str::trim).collect();
+            if entries.len() != header.len() {
+                return Err(PopulationImportError::MalformedRow {
+                    line,
+                    message: format!(
+                        "expected {} entries but found {}",
+                        header.len(),
+                        entries.len()
+                    ),
+                });
+            }
+            let parse = |column: usize| -> Result<f64, PopulationImportError> {
+                entries[column]
+                    .parse::<f64>()
+                    .map_err(|e| PopulationImportError::MalformedRow {
+                        line,
+                        message: format!(
+                            "could not parse value \"{}\" of column \"{}\": {e}",
+                            entries[column], header[column]
+                        ),
+                    })
+            };
+            let parse_vector = |columns: &Vec<usize>| {
+                Ok::<_, PopulationImportError>(SVector::<f64, D>::from_iterator(
+                    columns
+                        .iter()
+                        .map(|&c| parse(c))
+                        .collect::<Result<Vec<_>, _>>()?,
+                ))
+            };
+            let record = CellRecord {
+                line,
+                pos: parse_vector(&pos_columns)?,
+                vel: vel_columns.as_ref().map(parse_vector).transpose()?,
+                radius: radius_column.map(parse).transpose()?,
+                species: species_column.map(|c| entries[c].to_owned()),
+            };
+            cells.push(
+                template(record).map_err(|e| PopulationImportError::TemplateError {
+                    line,
+                    message: format!("{e}"),
+                })?,
+            );
+        }
+        Ok(Self { cells })
+    }
+
+    /// Reads a newline-delimited json file where every line contains one object describing a
+    /// single cell.
+    ///
+    /// Vector-valued properties can either be given by one key per component (as specified
+    /// by the [ColumnMapping]) or, if only a single key is given, by an array.
+    pub fn from_ndjson<const D: usize, E>(
+        path: impl AsRef<std::path::Path>,
+        mapping: &ColumnMapping,
+        template: impl FnMut(CellRecord<D>) -> Result<C, E>,
+    ) -> Result<Self, PopulationImportError>
+    where
+        E: Display,
+    {
+        let file = std::fs::File::open(path)?;
+        Self::from_ndjson_reader(std::io::BufReader::new(file), mapping, template)
+    }
+
+    /// Similar to [Population::from_ndjson] but reads from an arbitrary reader.
+    pub fn from_ndjson_reader<const D: usize, E>(
+        reader: impl BufRead,
+        mapping: &ColumnMapping,
+        mut template: impl FnMut(CellRecord<D>) -> Result<C, E>,
+    ) -> Result<Self, PopulationImportError>
+    where
+        E: Display,
+    {
+        mapping.check_dimension::<D>(true)?;
+        let single_key_vector = |keys: &Vec<String>| keys.len() == 1 && D != 1;
+        let mut cells = Vec::new();
+        for (n, content) in reader.lines().enumerate() {
+            let line = n + 1;
+            let content = content?;
+            if content.trim().is_empty() {
+                continue;
+            }
+            let malformed = |message: String| PopulationImportError::MalformedRow { line, message };
+            let object: serde_json::Map<String, serde_json::Value> =
+                serde_json::from_str(&content).map_err(|e| malformed(format!("{e}")))?;
+            let get = |key: &String| {
+                object
+                    .get(key)
+                    .ok_or_else(|| malformed(format!("key \"{key}\" is missing")))
+            };
+            let as_float = |key: &String, value: &serde_json::Value| {
+                value
+                    .as_f64()
+                    .ok_or_else(|| malformed(format!("value of key \"{key}\" is not a number")))
+            };
+            let parse_vector = |keys: &Vec<String>| -> Result<_, PopulationImportError> {
+                let values = if single_key_vector(keys) {
+                    let array = get(&keys[0])?.as_array().ok_or_else(|| {
+                        malformed(format!("value of key \"{}\" is not an array", keys[0]))
+                    })?;
+                    if array.len() != D {
+                        return Err(malformed(format!(
+                            "expected {D} entries for key \"{}\" but found {}",
+                            keys[0],
+                            array.len()
+                        )));
+                    }
+                    array
+                        .iter()
+                        .map(|v| as_float(&keys[0], v))
+                        .collect::<Result<Vec<_>, _>>()?
+                } else {
+                    keys.iter()
+                        .map(|k| as_float(k, get(k)?))
+                        .collect::<Result<Vec<_>, _>>()?
+                };
+                Ok(SVector::<f64, D>::from_iterator(values))
+            };
+            let record = CellRecord {
+                line,
+                pos: parse_vector(&mapping.position)?,
+                vel: mapping.velocity.as_ref().map(parse_vector).transpose()?,
+                radius: mapping
+                    .radius
+                    .as_ref()
+                    .map(|k| as_float(k, get(k)?))
+                    .transpose()?,
+                species: mapping
+                    .species
+                    .as_ref()
+                    .map(|k| {
+                        let value = get(k)?;
+                        Ok::<_, PopulationImportError>(match value.as_str() {
+                            Some(s) => s.to_owned(),
+                            None => value.to_string(),
+                        })
+                    })
+                    .transpose()?,
+            };
+            cells.push(
+                template(record).map_err(|e| PopulationImportError::TemplateError {
+                    line,
+                    message: format!("{e}"),
+                })?,
+            );
+        }
+        Ok(Self { cells })
+    }
+}
+
+impl<C> IntoIterator for Population<C> {
+    type Item = C;
+    type IntoIter = std::vec::IntoIter<C>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cells.into_iter()
+    }
+}
+
+impl<C> From<Vec<C>> for Population<C> {
+    fn from(cells: Vec<C>) -> Self {
+        Self { cells }
+    }
+}
+
+#[cfg(test)]
+mod test_population {
+    use super::*;
+
+    fn identity<const D: usize>(record: CellRecord<D>) -> Result<CellRecord<D>, String> {
+        Ok(record)
+    }
+
+    #[test]
+    fn csv_all_columns() {
+        let data = "\
+            # comment
+            id;x;y;vx;vy;r;type\n\
+            0;1.0;2.0;0.1;0.2;3.0;A\n\
+            \n\
+            1;4.0;5.0;0.3;0.4;6.0;B\n";
+        let mapping = ColumnMapping::new(["x", "y"])
+            .velocity(["vx", "vy"])
+            .radius("r")
+            .species("type")
+            .delimiter(';');
+        let population =
+            Population::from_csv_reader(data.as_bytes(), &mapping, identity::<2>).unwrap();
+        assert_eq!(population.len(), 2);
+        assert_eq!(population.cells[0].pos, SVector::from([1.0, 2.0]));
+        assert_eq!(population.cells[0].vel, Some(SVector::from([0.1, 0.2])));
+        assert_eq!(population.cells[1].radius, Some(6.0));
+        assert_eq!(population.cells[1].species, Some("B".to_owned()));
+        assert_eq!(population.cells[1].line, 5);
+    }
+
+    #[test]
+    fn csv_malformed_row() {
+        let data = "x,y\n1.0,2.0\n1.0,abc\n";
+        let mapping = ColumnMapping::new(["x", "y"]);
+        let res = Population::<CellRecord<2>>::from_csv_reader(data.as_bytes(), &mapping, identity);
+        match res {
+            Err(PopulationImportError::MalformedRow { line, .. }) => assert_eq!(line, 3),
+            _ => panic!("expected malformed row"),
+        }
+    }
+
+    #[test]
+    fn csv_missing_column() {
+        let data = "x,y\n1.0,2.0\n";
+        let mapping = ColumnMapping::new(["x", "z"]);
+        let res = Population::<CellRecord<2>>::from_csv_reader(data.as_bytes(), &mapping, identity);
+        assert!(matches!(res, Err(PopulationImportError::MappingError(_))));
+    }
+
+    #[test]
+    fn ndjson_arrays_and_keys() {
+        let data = "\
+            {\"pos\": [1.0, 2.0, 3.0], \"r\": 1.5, \"type\": 2}\n\
+            {\"pos\": [4.0, 5.0, 6.0], \"r\": 0.5, \"type\": \"B\"}\n";
+        let mapping = ColumnMapping::new(["pos"]).radius("r").species("type");
+        let population =
+            Population::from_ndjson_reader(data.as_bytes(), &mapping, identity::<3>).unwrap();
+        assert_eq!(population.cells[0].pos, SVector::from([1.0, 2.0, 3.0]));
+        assert_eq!(population.cells[0].species, Some("2".to_owned()));
+        assert_eq!(population.cells[1].species, Some("B".to_owned()));
+    }
+
+    #[test]
+    fn ndjson_missing_key() {
+        let data = "{\"x\": 1.0}\n";
+        let mapping = ColumnMapping::new(["x", "y"]);
+        let res =
+            Population::<CellRecord<2>>::from_ndjson_reader(data.as_bytes(), &mapping, identity);
+        match res {
+            Err(PopulationImportError::MalformedRow { line, .. }) => assert_eq!(line, 1),
+            _ => panic!("expected malformed row"),
+        }
+    }
+
+    #[test]
+    fn ndjson_position_and_velocity_checked_independently() {
+        let data = "{\"x\": 1.0, \"y\": 2.0, \"vel\": [0.1, 0.2]}\n";
+        // Keys for the position but an array for the velocity
+        let mapping = ColumnMapping::new(["x", "y"]).velocity(["vel"]);
+        let population =
+            Population::from_ndjson_reader(data.as_bytes(), &mapping, identity::<2>).unwrap();
+        assert_eq!(population.cells[0].vel, Some(SVector::from([0.1, 0.2])));
+        // Wrong number of velocity keys is rejected instead of panicking
+        let data = "{\"pos\": [1.0, 2.0], \"vx\": 0.1, \"vy\": 0.2, \"vz\": 0.3}\n";
+        let mapping = ColumnMapping::new(["pos"]).velocity(["vx", "vy", "vz"]);
+        let res =
+            Population::<CellRecord<2>>::from_ndjson_reader(data.as_bytes(), &mapping, identity);
+        assert!(matches!(res, Err(PopulationImportError::MappingError(_))));
+        let mapping = ColumnMapping::new(["x", "y", "z"]);
+        let res =
+            Population::<CellRecord<2>>::from_ndjson_reader(data.as_bytes(), &mapping, identity);
+        assert!(matches!(res, Err(PopulationImportError::MappingError(_))));
+    }
+}
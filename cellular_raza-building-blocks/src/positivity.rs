@@ -0,0 +1,286 @@
+//! Keep concentrations non-negative during explicit updates.
+//!
+//! Explicit solvers can drive concentrations below zero if degradation or uptake is fast
+//! compared to the time increment.
+//! The [NonNegativity] struct corrects updated values with a chosen [PositivityMethod] and
+//! records how much mass had to be added with a [ClippingCounter].
+//! Intracellular concentrations are corrected by wrapping the reactions of a cell in
+//! [NonNegative] while extracellular concentrations can be corrected by calling
+//! [NonNegativity::enforce] in the
+//! [SubDomainReactions](cellular_raza_concepts::SubDomainReactions) implementation.
+//!
+//! ```
+//! # use cellular_raza_building_blocks::{NonNegativity, PositivityMethod};
+//! let mut positivity = NonNegativity::new(PositivityMethod::Clamp);
+//! let old = [1.0, 0.5];
+//! let mut new = [0.8, -0.2];
+//! positivity.enforce(&old, &mut new);
+//! assert_eq!(new, [0.8, 0.0]);
+//! assert_eq!(positivity.counter.n_clipped, 1);
+//! assert!((positivity.counter.clipped_mass - 0.2).abs() < 1e-12);
+//! ```
+
+use cellular_raza_concepts::*;
+use nalgebra::SVector;
+use serde::{Deserialize, Serialize};
+
+/// Correction which is applied to the updated value $v$ of a concentration with previous value
+/// $u$.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+pub enum PositivityMethod {
+    /// Negative values are set to zero while all other values are unchanged.
+    #[default]
+    Clamp,
+    /// Losses are weighted by the ratio of new and old value as proposed by Patankar
+    /// (Numerical Heat Transfer and Fluid Flow, 1980).
+    ///
+    /// For the explicit Euler scheme, the net change $\delta=v-u<0$ is treated implicitly
+    /// which yields
+    /// \\begin{equation}
+    ///     v' = \frac{u^2}{u-\delta}
+    /// \\end{equation}
+    /// The result is always positive and smoothly approaches zero for fast degradation.
+    /// In contrast to [PositivityMethod::Clamp], every loss is altered slightly.
+    Patankar,
+}
+
+impl PositivityMethod {
+    /// Corrected value of a concentration which changed from `old` to `new`
+    ///
+    /// ```
+    /// # use cellular_raza_building_blocks::PositivityMethod;
+    /// assert_eq!(PositivityMethod::Clamp.apply(1.0, -0.5), 0.0);
+    /// assert_eq!(PositivityMethod::Patankar.apply(1.0, -1.0), 1.0 / 3.0);
+    /// // Gains are not changed
+    /// assert_eq!(PositivityMethod::Patankar.apply(1.0, 2.0), 2.0);
+    /// ```
+    pub fn apply(&self, old: f64, new: f64) -> f64 {
+        match self {
+            PositivityMethod::Clamp => new.max(0.0),
+            PositivityMethod::Patankar => {
+                if new >= old {
+                    new
+                } else if old > 0.0 {
+                    old.powi(2) / (2.0 * old - new)
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+/// Reports how often and by how much concentrations were corrected.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ClippingCounter {
+    /// Number of values which would have become negative without correction
+    pub n_clipped: u64,
+    /// Total amount which was added to all values by the corrections
+    pub clipped_mass: f64,
+}
+
+/// Enforces non-negative concentrations and counts the applied corrections.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct NonNegativity {
+    /// Correction which is applied to updated values
+    pub method: PositivityMethod,
+    /// Corrections which were applied so far
+    pub counter: ClippingCounter,
+}
+
+impl NonNegativity {
+    /// Creates a new [NonNegativity] with empty counter
+    pub fn new(method: PositivityMethod) -> Self {
+        Self {
+            method,
+            counter: ClippingCounter::default(),
+        }
+    }
+
+    /// Corrects the updated values elementwise given their previous values.
+    ///
+    /// Works with any container which can iterate over its values such as slices, [SVector]s
+    /// or `ndarray` arrays.
+    pub fn enforce<'a, 'b>(
+        &mut self,
+        old: impl IntoIterator<Item = &'a f64>,
+        new: impl IntoIterator<Item = &'b mut f64>,
+    ) {
+        for (old, new) in old.into_iter().zip(new) {
+            let corrected = self.method.apply(*old, *new);
+            if *new < 0.0 {
+                self.counter.n_clipped += 1;
+            }
+            self.counter.clipped_mass += corrected - *new;
+            *new = corrected;
+        }
+    }
+}
+
+/// Wraps reactions of a cell such that its intracellular concentrations stay non-negative.
+///
+/// Every time the backend sets new intracellular values, they are corrected with respect to
+/// the previous values.
+/// All other reaction concepts are forwarded to the wrapped reactions.
+/// The counter of the corrections is stored together with the cell and can thus be inspected
+/// in the stored results.
+/// Note that solvers with multiple stages such as Runge-Kutta methods only call
+/// [Intracellular::set_intracellular] once per step.
+///
+/// ```
+/// # use cellular_raza_building_blocks::{NonNegative, NonNegativity, PositivityMethod};
+/// # use cellular_raza_concepts::{CalcError, Intracellular, Reactions};
+/// # use nalgebra::Vector1;
+/// struct Decay {
+///     concentration: Vector1<f64>,
+/// }
+/// # impl Intracellular<Vector1<f64>> for Decay {
+/// #     fn set_intracellular(&mut self, c: Vector1<f64>) {
+/// #         self.concentration = c;
+/// #     }
+/// #     fn get_intracellular(&self) -> Vector1<f64> {
+/// #         self.concentration
+/// #     }
+/// # }
+/// impl Reactions<Vector1<f64>> for Decay {
+///     fn calculate_intracellular_increment(
+///         &self,
+///         c: &Vector1<f64>,
+///     ) -> Result<Vector1<f64>, CalcError> {
+///         Ok(-5.0 * c)
+///     }
+/// }
+///
+/// let mut reactions = NonNegative {
+///     reactions: Decay { concentration: Vector1::from([1.0]) },
+///     positivity: NonNegativity::new(PositivityMethod::Patankar),
+/// };
+/// // An explicit Euler step with dt=0.5 would yield a concentration of -1.5
+/// let c = reactions.get_intracellular();
+/// let dc = reactions.calculate_intracellular_increment(&c)?;
+/// reactions.set_intracellular(c + 0.5 * dc);
+/// assert!((reactions.get_intracellular()[0] - 1.0 / 3.5).abs() < 1e-12);
+/// assert_eq!(reactions.positivity.counter.n_clipped, 1);
+/// # Ok::<(), CalcError>(())
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct NonNegative<R> {
+    /// Reactions whose intracellular values are corrected
+    pub reactions: R,
+    /// Correction of the intracellular values
+    pub positivity: NonNegativity,
+}
+
+impl<R, const N: usize> Intracellular<SVector<f64, N>> for NonNegative<R>
+where
+    R: Intracellular<SVector<f64, N>>,
+{
+    fn set_intracellular(&mut self, mut intracellular: SVector<f64, N>) {
+        let old = self.reactions.get_intracellular();
+        self.positivity
+            .enforce(old.iter(), intracellular.iter_mut());
+        self.reactions.set_intracellular(intracellular);
+    }
+
+    fn get_intracellular(&self) -> SVector<f64, N> {
+        self.reactions.get_intracellular()
+    }
+}
+
+impl<R, Params, const N: usize> Reactions<SVector<f64, N>, Params> for NonNegative<R>
+where
+    R: Reactions<SVector<f64, N>, Params>,
+{
+    fn calculate_intracellular_increment(
+        &self,
+        intracellular: &SVector<f64, N>,
+    ) -> Result<SVector<f64, N>, CalcError> {
+        self.reactions
+            .calculate_intracellular_increment(intracellular)
+    }
+
+    fn calculate_intracellular_increment_with_parameters(
+        &self,
+        intracellular: &SVector<f64, N>,
+        parameters: &Params,
+    ) -> Result<SVector<f64, N>, CalcError> {
+        self.reactions
+            .calculate_intracellular_increment_with_parameters(intracellular, parameters)
+    }
+}
+
+impl<R, Re, const N: usize> ReactionsExtra<SVector<f64, N>, Re> for NonNegative<R>
+where
+    R: ReactionsExtra<SVector<f64, N>, Re>,
+{
+    fn calculate_combined_increment(
+        &self,
+        intracellular: &SVector<f64, N>,
+        extracellular: &Re,
+    ) -> Result<(SVector<f64, N>, Re), CalcError> {
+        self.reactions
+            .calculate_combined_increment(intracellular, extracellular)
+    }
+}
+
+impl<R, Pos, Float, RInf, const N: usize> ReactionsContact<SVector<f64, N>, Pos, Float, RInf>
+    for NonNegative<R>
+where
+    R: ReactionsContact<SVector<f64, N>, Pos, Float, RInf>,
+{
+    fn get_contact_information(&self) -> RInf {
+        self.reactions.get_contact_information()
+    }
+
+    fn calculate_contact_increment(
+        &self,
+        own_intracellular: &SVector<f64, N>,
+        ext_intracellular: &SVector<f64, N>,
+        own_pos: &Pos,
+        ext_pos: &Pos,
+        rinf: &RInf,
+    ) -> Result<(SVector<f64, N>, SVector<f64, N>), CalcError> {
+        self.reactions.calculate_contact_increment(
+            own_intracellular,
+            ext_intracellular,
+            own_pos,
+            ext_pos,
+            rinf,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test_positivity {
+    use super::*;
+
+    #[test]
+    fn patankar_is_positive_and_consistent() {
+        let method = PositivityMethod::Patankar;
+        for new in [-100.0, -1.0, 0.0, 0.5, 0.99] {
+            let corrected = method.apply(1.0, new);
+            assert!(corrected > 0.0 && corrected <= 1.0);
+        }
+        // Small losses are almost unchanged
+        assert!((method.apply(1.0, 0.999) - 0.999).abs() < 1e-5);
+        assert_eq!(method.apply(0.0, -1.0), 0.0);
+    }
+
+    #[test]
+    fn decay_never_becomes_negative() {
+        // Explicit Euler steps of fast exponential decay
+        let (rate, dt) = (10.0, 0.3);
+        for method in [PositivityMethod::Clamp, PositivityMethod::Patankar] {
+            let mut positivity = NonNegativity::new(method);
+            let mut values = SVector::<f64, 3>::from([1.0, 2.0, 0.0]);
+            for _ in 0..10 {
+                let old = values;
+                values = old - rate * dt * old;
+                positivity.enforce(old.iter(), values.iter_mut());
+                assert!(values.iter().all(|v| *v >= 0.0));
+            }
+            assert!(positivity.counter.n_clipped >= 2);
+            assert!(positivity.counter.clipped_mass > 0.0);
+        }
+    }
+}
@@ -0,0 +1,486 @@
+use cellular_raza_concepts::*;
+use nalgebra::SVector;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use super::ScenarioError;
+use crate::{CartesianCuboid, NewtonDamped2D, NewtonDamped3D};
+
+/// Short-ranged adhesion whose strength depends on the species of both interacting cells.
+///
+/// Two cells with radii $r_i,r_j$ at distance $d$ are in contact if $d<R=r_i+r_j$.
+/// Overlapping cells repel each other linearly while cells within the interaction range
+/// $\lambda R$ attract each other.
+/// The attraction increases from zero at contact up to its maximum in the middle of the
+/// interaction range and then decreases to zero again.
+/// \\begin{equation}
+///     F(d) = \begin{cases}
+///         k(R-d) & d<R\\\\
+///         -a_{ij}\min(d-R, \lambda R-d) & R\leq d<\lambda R\\\\
+///         0 & \text{else}
+///     \end{cases}
+/// \\end{equation}
+/// Positive values push both cells apart.
+/// The adhesion matrix $a_{ij}$ is indexed by the species of both cells and should be symmetric.
+///
+/// ```
+/// # use cellular_raza_building_blocks::DifferentialAdhesion;
+/// # use cellular_raza_concepts::Interaction;
+/// # use nalgebra::Vector2;
+/// let interaction = DifferentialAdhesion {
+///     species: 0,
+///     radius: 1.0,
+///     stiffness: 10.0,
+///     adhesion: [[1.0, 0.2], [0.2, 1.0]],
+///     relative_interaction_range: 1.5,
+/// };
+/// let x = Vector2::from([0.0, 0.0]);
+/// let y = Vector2::from([2.5, 0.0]);
+/// let v = Vector2::zeros();
+/// // The cells attract each other stronger if they belong to the same species
+/// let (f_same, _) = interaction.calculate_force_between(&x, &v, &y, &v, &(1.0, 0))?;
+/// let (f_diff, _) = interaction.calculate_force_between(&x, &v, &y, &v, &(1.0, 1))?;
+/// assert!(f_same[0] > f_diff[0]);
+/// assert!(f_diff[0] > 0.0);
+/// # Ok::<(), cellular_raza_concepts::CalcError>(())
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DifferentialAdhesion {
+    /// Species of the cell which is used as index into the adhesion matrix
+    pub species: usize,
+    /// Radius of the cell
+    pub radius: f64,
+    /// Stiffness $k$ of the repulsion between overlapping cells
+    pub stiffness: f64,
+    /// Adhesion strengths $a_{ij}$ between the species $i$ and $j$
+    pub adhesion: [[f64; 2]; 2],
+    /// Range $\lambda$ of the attraction relative to the sum of both radii
+    pub relative_interaction_range: f64,
+}
+
+impl<const D: usize> Interaction<SVector<f64, D>, SVector<f64, D>, SVector<f64, D>, (f64, usize)>
+    for DifferentialAdhesion
+{
+    fn calculate_force_between(
+        &self,
+        own_pos: &SVector<f64, D>,
+        _own_vel: &SVector<f64, D>,
+        ext_pos: &SVector<f64, D>,
+        _ext_vel: &SVector<f64, D>,
+        ext_info: &(f64, usize),
+    ) -> Result<(SVector<f64, D>, SVector<f64, D>), CalcError> {
+        let (ext_radius, ext_species) = *ext_info;
+        let adhesion = self
+            .adhesion
+            .get(self.species)
+            .and_then(|row| row.get(ext_species))
+            .ok_or(CalcError(format!(
+                "species {} and {} exceed the adhesion matrix",
+                self.species, ext_species
+            )))?;
+        let z = own_pos - ext_pos;
+        let dist = z.norm();
+        // Cells at identical positions do not have a defined direction
+        if dist == 0.0 {
+            return Ok((SVector::zeros(), SVector::zeros()));
+        }
+        let dir = z / dist;
+        let contact = self.radius + ext_radius;
+        let range = self.relative_interaction_range * contact;
+        let strength = if dist < contact {
+            self.stiffness * (contact - dist)
+        } else if dist < range {
+            -adhesion * (dist - contact).min(range - dist)
+        } else {
+            0.0
+        };
+        let force = dir * strength;
+        Ok((force, -force))
+    }
+
+    fn get_interaction_information(&self) -> (f64, usize) {
+        (self.radius, self.species)
+    }
+}
+
+/// Agent of the [CellSortingScenario]
+#[derive(CellAgent, Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SortingCell<M> {
+    /// Mechanical model of the cell
+    #[Mechanics]
+    pub mechanics: M,
+    /// Species dependent adhesion of the cell
+    #[Interaction]
+    pub interaction: DifferentialAdhesion,
+}
+
+/// Domain and agents of the [CellSortingScenario] together with its time stepping.
+#[derive(Clone, Debug)]
+pub struct CellSortingSetup<M, const D: usize> {
+    /// Cubic domain which contains the initial aggregate in its center
+    pub domain: CartesianCuboid<f64, D>,
+    /// Randomly mixed cells of both species
+    pub agents: Vec<SortingCell<M>>,
+    /// Time increment
+    pub dt: f64,
+    /// Total number of steps
+    pub n_steps: u64,
+    /// Number of steps between two save points
+    pub save_interval: u64,
+}
+
+/// Measures of how far two species have segregated.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SortingMetrics {
+    /// Number of pairs of cells within interaction range
+    pub n_contacts: usize,
+    /// Fraction of contacts between cells of the same species
+    pub homotypic_fraction: f64,
+    /// Mean distance of the cells of each species to the center of mass of all cells
+    pub mean_distance_to_center: [f64; 2],
+}
+
+impl SortingMetrics {
+    /// Calculates the metrics from the positions and species of all cells.
+    ///
+    /// Two cells are in contact if their distance is smaller than `contact_distance`.
+    /// If there are no contacts, the homotypic fraction is zero.
+    pub fn from_positions<const D: usize>(
+        cells: impl IntoIterator<Item = (SVector<f64, D>, usize)>,
+        contact_distance: f64,
+    ) -> Self {
+        let cells: Vec<_> = cells.into_iter().collect();
+        let mut n_contacts = 0;
+        let mut n_homotypic = 0;
+        for (i, (p1, s1)) in cells.iter().enumerate() {
+            for (p2, s2) in cells.iter().skip(i + 1) {
+                if (p1 - p2).norm() < contact_distance {
+                    n_contacts += 1;
+                    n_homotypic += (s1 == s2) as usize;
+                }
+            }
+        }
+        let center = cells
+            .iter()
+            .fold(SVector::<f64, D>::zeros(), |acc, (p, _)| acc + p)
+            / cells.len().max(1) as f64;
+        let mut distances = [0.0; 2];
+        let mut counts = [0usize; 2];
+        for (p, s) in cells.iter().filter(|(_, s)| *s < 2) {
+            distances[*s] += (p - center).norm();
+            counts[*s] += 1;
+        }
+        Self {
+            n_contacts,
+            homotypic_fraction: match n_contacts {
+                0 => 0.0,
+                n => n_homotypic as f64 / n as f64,
+            },
+            mean_distance_to_center: [
+                distances[0] / counts[0].max(1) as f64,
+                distances[1] / counts[1].max(1) as f64,
+            ],
+        }
+    }
+}
+
+/// Two populations which sort themselves by differential adhesion.
+///
+/// Both species are randomly mixed inside a spherical aggregate in the center of the domain.
+/// The cells interact via [DifferentialAdhesion] and move according to damped Newtonian
+/// mechanics.
+/// Following the differential adhesion hypothesis of Steinberg (Science 141, 1963), the final
+/// configuration is determined by the hierarchy of adhesion strengths.
+///
+/// | Adhesion strengths | Final configuration |
+/// | --- | --- |
+/// | $a_{00}>a_{01}>a_{11}$ | Species `0` is engulfed by species `1` |
+/// | $a_{01}<a_{00},a_{11}$ | Both species separate into homotypic clusters |
+/// | $a_{01}>a_{00},a_{11}$ | Both species stay mixed |
+///
+/// The default parameters describe the engulfment of species `0`.
+/// The progress of sorting is quantified by [SortingMetrics] which should be compared with
+/// [random_homotypic_fraction](CellSortingScenario::random_homotypic_fraction) of the initial
+/// mixture.
+///
+/// ```
+/// # use cellular_raza_building_blocks::CellSortingScenario;
+/// let scenario = CellSortingScenario {
+///     n_cells: [20, 20],
+///     ..Default::default()
+/// };
+/// let setup = scenario.build_2d()?;
+/// assert_eq!(setup.agents.len(), 40);
+/// let metrics = scenario.measure(setup.agents.iter());
+/// // Initially, the species are mixed
+/// assert!(metrics.homotypic_fraction < 0.8);
+/// # Ok::<(), cellular_raza_building_blocks::ScenarioError>(())
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CellSortingScenario {
+    /// Number of cells of both species
+    pub n_cells: [usize; 2],
+    /// Radius of every cell
+    pub cell_radius: f64,
+    /// Stiffness of the repulsion between overlapping cells
+    pub stiffness: f64,
+    /// Symmetric adhesion strengths between both species
+    pub adhesion: [[f64; 2]; 2],
+    /// Range of the attraction relative to the sum of the radii of two cells
+    pub relative_interaction_range: f64,
+    /// Fraction of the initial aggregate which is covered by cells
+    pub packing_fraction: f64,
+    /// Side length of the cubic domain
+    pub domain_size: f64,
+    /// Damping constant of the mechanics
+    pub damping: f64,
+    /// Mass of every cell
+    pub mass: f64,
+    /// Time increment
+    pub dt: f64,
+    /// Total number of steps
+    pub n_steps: u64,
+    /// Number of steps between two save points
+    pub save_interval: u64,
+    /// Seed used to place the cells
+    pub seed: u64,
+}
+
+impl Default for CellSortingScenario {
+    fn default() -> Self {
+        Self {
+            n_cells: [100, 100],
+            cell_radius: 1.0,
+            stiffness: 10.0,
+            adhesion: [[1.0, 0.6], [0.6, 0.3]],
+            relative_interaction_range: 1.5,
+            packing_fraction: 0.6,
+            domain_size: 60.0,
+            damping: 1.0,
+            mass: 1.0,
+            dt: 0.01,
+            n_steps: 20_000,
+            save_interval: 200,
+            seed: 0,
+        }
+    }
+}
+
+impl CellSortingScenario {
+    /// Distance up to which two cells interact
+    pub fn interaction_range(&self) -> f64 {
+        2.0 * self.relative_interaction_range * self.cell_radius
+    }
+
+    /// Radius of the initial aggregate in `D` dimensions
+    pub fn aggregate_radius<const D: usize>(&self) -> f64 {
+        let n_cells = (self.n_cells[0] + self.n_cells[1]) as f64;
+        self.cell_radius * (n_cells / self.packing_fraction).powf(1.0 / D as f64)
+    }
+
+    /// Expected [homotypic_fraction](SortingMetrics::homotypic_fraction) of randomly mixed cells
+    pub fn random_homotypic_fraction(&self) -> f64 {
+        let [n0, n1] = self.n_cells.map(|n| n as f64);
+        let n = n0 + n1;
+        (n0 * (n0 - 1.0) + n1 * (n1 - 1.0)) / (n * (n - 1.0)).max(1.0)
+    }
+
+    /// Calculates the [SortingMetrics] of the given cells.
+    pub fn measure<'a, M, const D: usize>(
+        &self,
+        cells: impl IntoIterator<Item = &'a SortingCell<M>>,
+    ) -> SortingMetrics
+    where
+        M: 'a + Position<SVector<f64, D>>,
+    {
+        SortingMetrics::from_positions(
+            cells
+                .into_iter()
+                .map(|cell| (cell.mechanics.pos(), cell.interaction.species)),
+            self.interaction_range(),
+        )
+    }
+
+    fn check_parameters<const D: usize>(&self) -> Result<(), ScenarioError> {
+        let error = |message: &str| Err(ScenarioError::ParameterError(message.to_owned()));
+        if self.n_cells[0] + self.n_cells[1] == 0 {
+            return error("scenario requires at least one cell");
+        }
+        if !(self.packing_fraction > 0.0 && self.packing_fraction <= 1.0) {
+            return error("packing fraction must be within (0, 1]");
+        }
+        if self.adhesion[0][1] != self.adhesion[1][0] {
+            return error("adhesion matrix must be symmetric");
+        }
+        if self.aggregate_radius::<D>() + self.cell_radius > self.domain_size / 2.0 {
+            return error("initial aggregate does not fit into domain");
+        }
+        Ok(())
+    }
+
+    /// Builds the setup with a user-defined mechanical model.
+    ///
+    /// The given function creates the mechanics of a cell at the specified initial position.
+    pub fn build_with_mechanics<M, const D: usize>(
+        &self,
+        mut mechanics: impl FnMut(SVector<f64, D>) -> M,
+    ) -> Result<CellSortingSetup<M, D>, ScenarioError> {
+        self.check_parameters::<D>()?;
+        let domain = CartesianCuboid::from_boundaries_and_interaction_range(
+            [0.0; D],
+            [self.domain_size; D],
+            self.interaction_range(),
+        )?;
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(self.seed);
+        let aggregate_radius = self.aggregate_radius::<D>();
+        let center = SVector::<f64, D>::from_element(self.domain_size / 2.0);
+        let agents = (0..2)
+            .flat_map(|species| std::iter::repeat_n(species, self.n_cells[species]))
+            .map(|species| {
+                // Sample uniformly inside the aggregate by rejection
+                let offset = loop {
+                    let offset = SVector::<f64, D>::from_fn(|_, _| {
+                        rng.gen_range(-aggregate_radius..aggregate_radius)
+                    });
+                    if offset.norm() <= aggregate_radius {
+                        break offset;
+                    }
+                };
+                SortingCell {
+                    mechanics: mechanics(center + offset),
+                    interaction: DifferentialAdhesion {
+                        species,
+                        radius: self.cell_radius,
+                        stiffness: self.stiffness,
+                        adhesion: self.adhesion,
+                        relative_interaction_range: self.relative_interaction_range,
+                    },
+                }
+            })
+            .collect();
+        Ok(CellSortingSetup {
+            domain,
+            agents,
+            dt: self.dt,
+            n_steps: self.n_steps,
+            save_interval: self.save_interval,
+        })
+    }
+
+    /// Builds the setup in two dimensions with [NewtonDamped2D] mechanics.
+    pub fn build_2d(&self) -> Result<CellSortingSetup<NewtonDamped2D, 2>, ScenarioError> {
+        self.build_with_mechanics(|pos| NewtonDamped2D {
+            pos,
+            vel: SVector::zeros(),
+            damping_constant: self.damping,
+            mass: self.mass,
+        })
+    }
+
+    /// Builds the setup in three dimensions with [NewtonDamped3D] mechanics.
+    pub fn build_3d(&self) -> Result<CellSortingSetup<NewtonDamped3D, 3>, ScenarioError> {
+        self.build_with_mechanics(|pos| NewtonDamped3D {
+            pos,
+            vel: SVector::zeros(),
+            damping_constant: self.damping,
+            mass: self.mass,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test_cell_sorting {
+    use super::*;
+    use nalgebra::Vector2;
+
+    fn force(interaction: &DifferentialAdhesion, dist: f64, ext_species: usize) -> f64 {
+        let zero = Vector2::zeros();
+        let (f1, f2) = interaction
+            .calculate_force_between(
+                &Vector2::from([dist, 0.0]),
+                &zero,
+                &zero,
+                &zero,
+                &(1.0, ext_species),
+            )
+            .unwrap();
+        assert_eq!(f1, -f2);
+        f1[0]
+    }
+
+    #[test]
+    fn force_law() {
+        let interaction = DifferentialAdhesion {
+            species: 1,
+            radius: 1.0,
+            stiffness: 10.0,
+            adhesion: [[1.0, 0.5], [0.5, 2.0]],
+            relative_interaction_range: 1.5,
+        };
+        // Repulsion of overlapping cells
+        assert!((force(&interaction, 1.5, 0) - 5.0).abs() < 1e-12);
+        // Maximal attraction in the middle of the interaction range
+        assert!((force(&interaction, 2.5, 0) + 0.25).abs() < 1e-12);
+        assert!((force(&interaction, 2.5, 1) + 1.0).abs() < 1e-12);
+        assert_eq!(force(&interaction, 2.0, 1), 0.0);
+        assert_eq!(force(&interaction, 3.5, 1), 0.0);
+        assert_eq!(force(&interaction, 0.0, 1), 0.0);
+        let zero = Vector2::zeros();
+        assert!(interaction
+            .calculate_force_between(&zero, &zero, &zero, &zero, &(1.0, 2))
+            .is_err());
+    }
+
+    #[test]
+    fn build_setup() {
+        let scenario = CellSortingScenario {
+            n_cells: [30, 10],
+            ..Default::default()
+        };
+        let setup = scenario.build_3d().unwrap();
+        assert_eq!(setup.agents.len(), 40);
+        let n_species_0 = setup
+            .agents
+            .iter()
+            .filter(|cell| cell.interaction.species == 0)
+            .count();
+        assert_eq!(n_species_0, 30);
+        let radius = scenario.aggregate_radius::<3>();
+        for cell in setup.agents.iter() {
+            let offset = cell.mechanics.pos - SVector::from_element(30.0);
+            assert!(offset.norm() <= radius);
+        }
+        // Building twice yields identical agents
+        assert_eq!(setup.agents, scenario.build_3d().unwrap().agents);
+        let scenario = CellSortingScenario {
+            adhesion: [[1.0, 0.5], [0.2, 1.0]],
+            ..Default::default()
+        };
+        assert!(matches!(
+            scenario.build_2d(),
+            Err(ScenarioError::ParameterError(_))
+        ));
+    }
+
+    #[test]
+    fn sorting_metrics() {
+        let positions = [
+            (Vector2::from([0.0, 0.0]), 0),
+            (Vector2::from([2.0, 0.0]), 0),
+            (Vector2::from([10.0, 0.0]), 1),
+            (Vector2::from([12.0, 0.0]), 1),
+        ];
+        let metrics = SortingMetrics::from_positions(positions, 3.0);
+        assert_eq!(metrics.n_contacts, 2);
+        assert_eq!(metrics.homotypic_fraction, 1.0);
+        assert_eq!(metrics.mean_distance_to_center, [5.0, 5.0]);
+        let metrics = SortingMetrics::from_positions(positions, 11.0);
+        assert_eq!(metrics.n_contacts, 5);
+        assert!((metrics.homotypic_fraction - 0.4).abs() < 1e-12);
+        let scenario = CellSortingScenario {
+            n_cells: [2, 2],
+            ..Default::default()
+        };
+        assert!((scenario.random_homotypic_fraction() - 1.0 / 3.0).abs() < 1e-12);
+    }
+}
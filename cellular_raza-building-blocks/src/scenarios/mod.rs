@@ -0,0 +1,53 @@
+//! Canonical simulation setups which are ready to run.
+//!
+//! In contrast to the [validation](crate::ValidationReport) scenarios, these setups do not have
+//! an analytical solution.
+//! They reproduce well-studied experiments and can be used to benchmark models against
+//! published results or as starting point for new simulations.
+//! Every scenario builds the domain and agents which can be passed to a backend directly and
+//! provides routines to analyze the stored results.
+//!
+//! | Scenario | Experiment | Measured Quantity |
+//! | --- | --- | --- |
+//! | [CellSortingScenario] | Differential adhesion cell sorting | [SortingMetrics] |
+//! | [MonolayerScenario] | Expansion of a contact-inhibited monolayer | [MonolayerAnalysis] |
+//! | [SpheroidScenario] | Tumor spheroid with oxygen-limited growth | [SpheroidAnalysis] |
+
+use cellular_raza_concepts::BoundaryError;
+
+use core::fmt::Display;
+use std::error::Error;
+
+mod cell_sorting;
+mod monolayer;
+mod spheroid;
+
+pub use cell_sorting::*;
+pub use monolayer::*;
+pub use spheroid::*;
+
+/// Errors which can occur while building a scenario.
+#[derive(Debug)]
+pub enum ScenarioError {
+    /// Creating the domain of the scenario failed.
+    BoundaryError(BoundaryError),
+    /// The parameters of the scenario are inconsistent.
+    ParameterError(String),
+}
+
+impl Display for ScenarioError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ScenarioError::BoundaryError(e) => write!(f, "{e}"),
+            ScenarioError::ParameterError(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl Error for ScenarioError {}
+
+impl From<BoundaryError> for ScenarioError {
+    fn from(err: BoundaryError) -> Self {
+        ScenarioError::BoundaryError(err)
+    }
+}
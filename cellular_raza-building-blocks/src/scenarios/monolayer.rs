@@ -0,0 +1,462 @@
+use cellular_raza_concepts::*;
+use nalgebra::{SVector, Vector2};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::ScenarioError;
+use crate::{CartesianCuboid, NewtonDamped2D};
+
+/// Repulsion between overlapping cells which counts the neighbors of every cell.
+///
+/// Two cells with radii $r_i,r_j$ at distance $d<R=r_i+r_j$ repel each other with force
+/// $k(R-d)$.
+/// Cells are counted as neighbors if $d<\mu R$ where $\mu$ is the relative contact range.
+/// The number of neighbors of the last step is stored and used by the [MonolayerCell] to
+/// inhibit its growth.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ContactInhibition {
+    /// Radius of the cell
+    pub radius: f64,
+    /// Stiffness $k$ of the repulsion
+    pub stiffness: f64,
+    /// Range $\mu$ up to which cells count as neighbors relative to the sum of both radii
+    pub relative_contact_range: f64,
+    /// Number of neighbors which were found in the last step
+    pub n_neighbors: usize,
+}
+
+impl Interaction<Vector2<f64>, Vector2<f64>, Vector2<f64>, f64> for ContactInhibition {
+    fn calculate_force_between(
+        &self,
+        own_pos: &Vector2<f64>,
+        _own_vel: &Vector2<f64>,
+        ext_pos: &Vector2<f64>,
+        _ext_vel: &Vector2<f64>,
+        ext_radius: &f64,
+    ) -> Result<(Vector2<f64>, Vector2<f64>), CalcError> {
+        let z = own_pos - ext_pos;
+        let dist = z.norm();
+        let contact = self.radius + ext_radius;
+        if dist == 0.0 || dist >= contact {
+            return Ok((Vector2::zeros(), Vector2::zeros()));
+        }
+        let force = z / dist * self.stiffness * (contact - dist);
+        Ok((force, -force))
+    }
+
+    fn get_interaction_information(&self) -> f64 {
+        self.radius
+    }
+
+    fn is_neighbor(
+        &self,
+        own_pos: &Vector2<f64>,
+        ext_pos: &Vector2<f64>,
+        ext_radius: &f64,
+    ) -> Result<bool, CalcError> {
+        Ok((own_pos - ext_pos).norm() < self.relative_contact_range * (self.radius + ext_radius))
+    }
+
+    fn react_to_neighbors(&mut self, neighbors: usize) -> Result<(), CalcError> {
+        self.n_neighbors = neighbors;
+        Ok(())
+    }
+}
+
+/// Agent of the [MonolayerScenario] whose growth is inhibited by contact with other cells.
+///
+/// As long as the cell has less than `max_neighbors` neighbors, its area grows exponentially
+/// with the given rate.
+/// Once its radius exceeds the `division_radius`, the cell divides into two daughter cells of
+/// equal area which are placed along a random axis.
+#[derive(CellAgent, Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct MonolayerCell {
+    /// Mechanical model of the cell
+    #[Mechanics]
+    pub mechanics: NewtonDamped2D,
+    /// Repulsion between cells which also counts the neighbors
+    #[Interaction]
+    pub interaction: ContactInhibition,
+    /// Growth rate of the area of the cell
+    pub growth_rate: f64,
+    /// Radius at which the cell divides
+    pub division_radius: f64,
+    /// Smallest number of neighbors which stops the growth of the cell
+    pub max_neighbors: usize,
+}
+
+impl Cycle<MonolayerCell> for MonolayerCell {
+    fn update_cycle(
+        _rng: &mut rand_chacha::ChaCha8Rng,
+        dt: &f64,
+        cell: &mut MonolayerCell,
+    ) -> Option<CycleEvent> {
+        if cell.interaction.n_neighbors < cell.max_neighbors {
+            cell.interaction.radius *= (cell.growth_rate * dt / 2.0).exp();
+        }
+        match cell.interaction.radius >= cell.division_radius {
+            true => Some(CycleEvent::Division),
+            false => None,
+        }
+    }
+
+    fn divide(
+        rng: &mut rand_chacha::ChaCha8Rng,
+        cell: &mut MonolayerCell,
+    ) -> Result<MonolayerCell, DivisionError> {
+        let radius = cell.interaction.radius / std::f64::consts::SQRT_2;
+        let angle = rng.gen_range(0.0..std::f64::consts::TAU);
+        let offset = Vector2::from([angle.cos(), angle.sin()]) * radius / 2.0;
+        let center = cell.mechanics.pos;
+        cell.interaction.radius = radius;
+        cell.mechanics.pos = center + offset;
+        let mut daughter = cell.clone();
+        daughter.mechanics.pos = center - offset;
+        Ok(daughter)
+    }
+}
+
+/// Domain and the initial cell of the [MonolayerScenario] together with its time stepping.
+#[derive(Clone, Debug)]
+pub struct MonolayerSetup {
+    /// Square domain with the seeded cell in its center
+    pub domain: CartesianCuboid<f64, 2>,
+    /// Single seeded cell
+    pub agents: Vec<MonolayerCell>,
+    /// Time increment
+    pub dt: f64,
+    /// Total number of steps
+    pub n_steps: u64,
+    /// Number of steps between two save points
+    pub save_interval: u64,
+}
+
+/// State of the monolayer at a single save point.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct MonolayerSnapshot {
+    /// Time of the save point
+    pub time: f64,
+    /// Total number of cells
+    pub n_cells: usize,
+    /// Largest distance of any cell to the position of the seeded cell
+    pub front_radius: f64,
+    /// Number of cells per area in concentric rings around the seeded cell.
+    /// The ring with index `i` contains all cells with distance between `i` and `i+1` times the
+    /// bin width.
+    pub density_profile: Vec<f64>,
+}
+
+impl MonolayerSnapshot {
+    /// Calculates the snapshot from the positions of all cells.
+    pub fn from_positions(
+        time: f64,
+        positions: impl IntoIterator<Item = Vector2<f64>>,
+        center: Vector2<f64>,
+        bin_width: f64,
+    ) -> Self {
+        let distances: Vec<_> = positions.into_iter().map(|p| (p - center).norm()).collect();
+        let front_radius = distances.iter().fold(0.0f64, |acc, d| acc.max(*d));
+        let n_bins = (front_radius / bin_width).floor() as usize + 1;
+        let mut density_profile = vec![0.0; n_bins];
+        for d in distances.iter() {
+            density_profile[((d / bin_width).floor() as usize).min(n_bins - 1)] += 1.0;
+        }
+        for (i, density) in density_profile.iter_mut().enumerate() {
+            let area = std::f64::consts::PI * bin_width.powi(2) * (2 * i + 1) as f64;
+            *density /= area;
+        }
+        Self {
+            time,
+            n_cells: distances.len(),
+            front_radius,
+            density_profile,
+        }
+    }
+}
+
+/// Results of the [MonolayerScenario] at all save points.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct MonolayerAnalysis {
+    /// Snapshots ordered by their time
+    pub snapshots: Vec<MonolayerSnapshot>,
+    /// Speed of the front obtained by a linear fit of the front radius over the second half of
+    /// all snapshots
+    pub front_speed: f64,
+}
+
+impl MonolayerAnalysis {
+    /// Sorts the snapshots by their time and fits the speed of the front.
+    ///
+    /// The first half of all snapshots is dominated by the initial exponential growth of the
+    /// colony and thus not used to determine the front speed.
+    /// If less than two snapshots remain, the front speed is zero.
+    pub fn from_snapshots(mut snapshots: Vec<MonolayerSnapshot>) -> Self {
+        snapshots.sort_by(|s1, s2| s1.time.total_cmp(&s2.time));
+        let late = &snapshots[snapshots.len() / 2..];
+        let n = late.len() as f64;
+        let t_mean = late.iter().map(|s| s.time).sum::<f64>() / n;
+        let r_mean = late.iter().map(|s| s.front_radius).sum::<f64>() / n;
+        let covariance: f64 = late
+            .iter()
+            .map(|s| (s.time - t_mean) * (s.front_radius - r_mean))
+            .sum();
+        let variance: f64 = late.iter().map(|s| (s.time - t_mean).powi(2)).sum();
+        Self {
+            front_speed: match late.len() >= 2 && variance > 0.0 {
+                true => covariance / variance,
+                false => 0.0,
+            },
+            snapshots,
+        }
+    }
+}
+
+/// Expansion of a two-dimensional monolayer from a single seeded cell.
+///
+/// The cell is placed in the center of a square domain and proliferates while its growth is
+/// inhibited by contact with other cells (see [MonolayerCell]).
+/// Cells inside the colony become quiescent such that proliferation is confined to the
+/// border.
+/// After an initial phase of exponential growth, the front of the colony thus advances with
+/// constant speed while the density profile behind it becomes flat, as is observed in
+/// experiments with epithelial cell lines
+/// (Bru et al., Biophys. J. 85, 2003; Drasdo and Hoehme, Phys. Biol. 2, 2005).
+///
+/// The scenario needs the [Mechanics], [Interaction] and [Cycle] aspects.
+/// Stored cells are evaluated by [analyze](MonolayerScenario::analyze) which yields the
+/// [MonolayerAnalysis].
+///
+/// ```
+/// # use cellular_raza_building_blocks::MonolayerScenario;
+/// let scenario = MonolayerScenario::default();
+/// let setup = scenario.build()?;
+/// assert_eq!(setup.agents.len(), 1);
+/// // Usually the stored cells of all save points are analyzed
+/// let analysis = scenario.analyze([(0, setup.agents.iter())]);
+/// assert_eq!(analysis.snapshots[0].n_cells, 1);
+/// assert_eq!(analysis.snapshots[0].front_radius, 0.0);
+/// # Ok::<(), cellular_raza_building_blocks::ScenarioError>(())
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct MonolayerScenario {
+    /// Radius of newly divided cells
+    pub cell_radius: f64,
+    /// Growth rate of the area of non-inhibited cells
+    pub growth_rate: f64,
+    /// Stiffness of the repulsion between overlapping cells
+    pub stiffness: f64,
+    /// Range up to which cells count as neighbors relative to the sum of their radii
+    pub relative_contact_range: f64,
+    /// Smallest number of neighbors which stops the growth of a cell
+    pub max_neighbors: usize,
+    /// Side length of the square domain
+    pub domain_size: f64,
+    /// Damping constant of the mechanics
+    pub damping: f64,
+    /// Mass of every cell
+    pub mass: f64,
+    /// Width of the rings of the density profile
+    pub profile_bin_width: f64,
+    /// Time increment
+    pub dt: f64,
+    /// Total number of steps
+    pub n_steps: u64,
+    /// Number of steps between two save points
+    pub save_interval: u64,
+    /// Seed of the domain from which the division axes are drawn
+    pub seed: u64,
+}
+
+impl Default for MonolayerScenario {
+    fn default() -> Self {
+        Self {
+            cell_radius: 1.0,
+            growth_rate: 0.1,
+            stiffness: 10.0,
+            relative_contact_range: 1.1,
+            max_neighbors: 5,
+            domain_size: 200.0,
+            damping: 1.0,
+            mass: 1.0,
+            profile_bin_width: 4.0,
+            dt: 0.01,
+            n_steps: 10_000,
+            save_interval: 200,
+            seed: 0,
+        }
+    }
+}
+
+impl MonolayerScenario {
+    /// Position of the seeded cell in the center of the domain
+    pub fn center(&self) -> Vector2<f64> {
+        Vector2::from_element(self.domain_size / 2.0)
+    }
+
+    /// Builds the domain and the seeded cell.
+    pub fn build(&self) -> Result<MonolayerSetup, ScenarioError> {
+        if !(self.cell_radius > 0.0 && self.growth_rate >= 0.0) {
+            return Err(ScenarioError::ParameterError(
+                "cell radius must be positive and growth rate non-negative".to_owned(),
+            ));
+        }
+        let division_radius = std::f64::consts::SQRT_2 * self.cell_radius;
+        let interaction_range = 2.0 * self.relative_contact_range.max(1.0) * division_radius;
+        let mut domain = CartesianCuboid::from_boundaries_and_interaction_range(
+            [0.0; 2],
+            [self.domain_size; 2],
+            interaction_range,
+        )?;
+        domain.rng_seed = self.seed;
+        let cell = MonolayerCell {
+            mechanics: NewtonDamped2D {
+                pos: self.center(),
+                vel: SVector::zeros(),
+                damping_constant: self.damping,
+                mass: self.mass,
+            },
+            interaction: ContactInhibition {
+                radius: self.cell_radius,
+                stiffness: self.stiffness,
+                relative_contact_range: self.relative_contact_range,
+                n_neighbors: 0,
+            },
+            growth_rate: self.growth_rate,
+            division_radius,
+            max_neighbors: self.max_neighbors,
+        };
+        Ok(MonolayerSetup {
+            domain,
+            agents: vec![cell],
+            dt: self.dt,
+            n_steps: self.n_steps,
+            save_interval: self.save_interval,
+        })
+    }
+
+    /// Calculates the [MonolayerSnapshot] of the given cells at the specified iteration.
+    pub fn measure<'a>(
+        &self,
+        iteration: u64,
+        cells: impl IntoIterator<Item = &'a MonolayerCell>,
+    ) -> MonolayerSnapshot {
+        MonolayerSnapshot::from_positions(
+            iteration as f64 * self.dt,
+            cells.into_iter().map(|cell| cell.mechanics.pos),
+            self.center(),
+            self.profile_bin_width,
+        )
+    }
+
+    /// Analyzes the cells of all save points given by their iteration.
+    pub fn analyze<'a, I>(
+        &self,
+        save_points: impl IntoIterator<Item = (u64, I)>,
+    ) -> MonolayerAnalysis
+    where
+        I: IntoIterator<Item = &'a MonolayerCell>,
+    {
+        MonolayerAnalysis::from_snapshots(
+            save_points
+                .into_iter()
+                .map(|(iteration, cells)| self.measure(iteration, cells))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test_monolayer {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn contact_inhibited_growth_and_division() {
+        let mut cell = MonolayerScenario::default()
+            .build()
+            .unwrap()
+            .agents
+            .pop()
+            .unwrap();
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(0);
+        // Inhibited cells do not grow
+        cell.interaction.n_neighbors = 5;
+        assert_eq!(MonolayerCell::update_cycle(&mut rng, &1.0, &mut cell), None);
+        assert_eq!(cell.interaction.radius, 1.0);
+        cell.interaction.n_neighbors = 4;
+        let mut event = None;
+        let mut n_steps = 0;
+        while event.is_none() {
+            event = MonolayerCell::update_cycle(&mut rng, &0.01, &mut cell);
+            n_steps += 1;
+        }
+        // The area doubles after ln(2)/growth_rate
+        assert_eq!(event, Some(CycleEvent::Division));
+        assert!((n_steps as f64 * 0.01 - 2f64.ln() / 0.1).abs() < 0.02);
+        let center = cell.mechanics.pos;
+        let daughter = MonolayerCell::divide(&mut rng, &mut cell).unwrap();
+        assert!((cell.interaction.radius - 1.0).abs() < 1e-3);
+        assert_eq!(cell.interaction.radius, daughter.interaction.radius);
+        let midpoint = (cell.mechanics.pos + daughter.mechanics.pos) / 2.0;
+        assert!((midpoint - center).norm() < 1e-12);
+    }
+
+    #[test]
+    fn neighbors_and_repulsion() {
+        let interaction = ContactInhibition {
+            radius: 1.0,
+            stiffness: 2.0,
+            relative_contact_range: 1.1,
+            n_neighbors: 0,
+        };
+        let zero = Vector2::zeros();
+        let ext = Vector2::from([1.5, 0.0]);
+        let (f1, f2) = interaction
+            .calculate_force_between(&zero, &zero, &ext, &zero, &1.0)
+            .unwrap();
+        assert!((f1[0] + 1.0).abs() < 1e-12);
+        assert_eq!(f1, -f2);
+        let far = Vector2::from([2.1, 0.0]);
+        let (f1, _) = interaction
+            .calculate_force_between(&zero, &zero, &far, &zero, &1.0)
+            .unwrap();
+        assert_eq!(f1, zero);
+        assert!(interaction.is_neighbor(&zero, &far, &1.0).unwrap());
+        assert!(!interaction.is_neighbor(&zero, &(far * 2.0), &1.0).unwrap());
+    }
+
+    #[test]
+    fn density_profile_and_front_speed() {
+        let center = Vector2::zeros();
+        let snapshot = MonolayerSnapshot::from_positions(
+            0.0,
+            [[0.5, 0.0], [0.0, 1.5], [-1.5, 0.0], [2.5, 0.0]].map(Vector2::from),
+            center,
+            1.0,
+        );
+        assert_eq!(snapshot.n_cells, 4);
+        assert_eq!(snapshot.front_radius, 2.5);
+        let pi = std::f64::consts::PI;
+        assert_eq!(
+            snapshot.density_profile,
+            vec![1.0 / pi, 2.0 / (3.0 * pi), 1.0 / (5.0 * pi)]
+        );
+        let snapshots = (0..10)
+            .map(|i| MonolayerSnapshot {
+                time: i as f64,
+                n_cells: 1,
+                // Exponential phase followed by linear growth
+                front_radius: if i < 5 {
+                    0.1 * 2f64.powi(i)
+                } else {
+                    3.0 * i as f64
+                },
+                density_profile: vec![],
+            })
+            .rev()
+            .collect();
+        let analysis = MonolayerAnalysis::from_snapshots(snapshots);
+        assert_eq!(analysis.snapshots[0].time, 0.0);
+        assert!((analysis.front_speed - 3.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,564 @@
+use cellular_raza_concepts::*;
+use nalgebra::{SVector, Vector2};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::ScenarioError;
+use crate::{
+    check_reaction_diffusion_stability, CartesianCuboid, ContactInhibition, DiffusionCuboid,
+    NewtonDamped2D,
+};
+
+/// State of a cell in the [SpheroidScenario] which is determined by the available oxygen.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum SpheroidCellState {
+    /// The cell grows and divides.
+    Proliferating,
+    /// Hypoxic cells stop to grow but resume growth once oxygen becomes available again.
+    Quiescent,
+    /// Cells which have run out of oxygen die irreversibly and are lysed.
+    Necrotic,
+}
+
+/// Agent of the [SpheroidScenario] whose growth and survival depend on oxygen.
+///
+/// The cell consumes oxygen with the Michaelis-Menten rate
+/// \\begin{equation}
+///     q = q_\text{max}\frac{c}{K + c}
+/// \\end{equation}
+/// where $c$ is the extracellular concentration at its position.
+/// The oxygen level $c_i$ which is sensed by the cell follows the extracellular concentration
+/// with the given sensing rate $k_s$ such that
+/// \\begin{equation}
+///     \dot{c}_i = k_s(c - c_i).
+/// \\end{equation}
+/// Depending on the sensed oxygen, the cell is in one of the [SpheroidCellState]s.
+/// Proliferating cells grow exponentially and divide into two daughter cells of equal area
+/// once their radius exceeds the `division_radius`.
+/// Cells whose oxygen level drops below the `hypoxia_threshold` become quiescent.
+/// Below the `necrosis_threshold`, cells become necrotic, stop to consume oxygen and their
+/// area decays with the `lysis_rate`.
+/// Necrotic cells are removed once their radius has shrunk to half the radius of a newly
+/// divided cell.
+#[derive(CellAgent, Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SpheroidCell {
+    /// Mechanical model of the cell
+    #[Mechanics]
+    pub mechanics: NewtonDamped2D,
+    /// Repulsion between overlapping cells
+    #[Interaction]
+    pub interaction: ContactInhibition,
+    /// Current state of the cell
+    pub state: SpheroidCellState,
+    /// Oxygen level $c_i$ which is sensed by the cell
+    pub oxygen: f64,
+    /// Growth rate of the area of proliferating cells
+    pub growth_rate: f64,
+    /// Radius at which the cell divides
+    pub division_radius: f64,
+    /// Maximal uptake $q_\text{max}$ of oxygen per time
+    pub max_uptake: f64,
+    /// Concentration $K$ at which the uptake is half of its maximum
+    pub uptake_half_saturation: f64,
+    /// Rate $k_s$ at which the sensed oxygen level follows the extracellular concentration
+    pub sensing_rate: f64,
+    /// Oxygen level below which the cell becomes quiescent
+    pub hypoxia_threshold: f64,
+    /// Oxygen level below which the cell becomes necrotic
+    pub necrosis_threshold: f64,
+    /// Rate at which the area of necrotic cells decays
+    pub lysis_rate: f64,
+}
+
+impl Intracellular<f64> for SpheroidCell {
+    fn set_intracellular(&mut self, intracellular: f64) {
+        self.oxygen = intracellular;
+    }
+
+    fn get_intracellular(&self) -> f64 {
+        self.oxygen
+    }
+}
+
+impl ReactionsExtra<f64, f64> for SpheroidCell {
+    fn calculate_combined_increment(
+        &self,
+        intracellular: &f64,
+        extracellular: &f64,
+    ) -> Result<(f64, f64), CalcError> {
+        let uptake = match self.state {
+            SpheroidCellState::Necrotic => 0.0,
+            _ => self.max_uptake * extracellular / (self.uptake_half_saturation + extracellular),
+        };
+        Ok((self.sensing_rate * (extracellular - intracellular), -uptake))
+    }
+}
+
+impl Cycle<SpheroidCell> for SpheroidCell {
+    fn update_cycle(
+        _rng: &mut rand_chacha::ChaCha8Rng,
+        dt: &f64,
+        cell: &mut SpheroidCell,
+    ) -> Option<CycleEvent> {
+        if cell.oxygen < cell.necrosis_threshold {
+            cell.state = SpheroidCellState::Necrotic;
+            return Some(CycleEvent::PhasedDeath);
+        }
+        if cell.oxygen < cell.hypoxia_threshold {
+            cell.state = SpheroidCellState::Quiescent;
+            return None;
+        }
+        cell.state = SpheroidCellState::Proliferating;
+        cell.interaction.radius *= (cell.growth_rate * dt / 2.0).exp();
+        match cell.interaction.radius >= cell.division_radius {
+            true => Some(CycleEvent::Division),
+            false => None,
+        }
+    }
+
+    fn divide(
+        rng: &mut rand_chacha::ChaCha8Rng,
+        cell: &mut SpheroidCell,
+    ) -> Result<SpheroidCell, DivisionError> {
+        let radius = cell.interaction.radius / std::f64::consts::SQRT_2;
+        let angle = rng.gen_range(0.0..std::f64::consts::TAU);
+        let offset = Vector2::from([angle.cos(), angle.sin()]) * radius / 2.0;
+        let center = cell.mechanics.pos;
+        cell.interaction.radius = radius;
+        cell.mechanics.pos = center + offset;
+        let mut daughter = cell.clone();
+        daughter.mechanics.pos = center - offset;
+        Ok(daughter)
+    }
+
+    fn update_conditional_phased_death(
+        _rng: &mut rand_chacha::ChaCha8Rng,
+        dt: &f64,
+        cell: &mut SpheroidCell,
+    ) -> Result<bool, DeathError> {
+        cell.state = SpheroidCellState::Necrotic;
+        cell.interaction.radius *= (-cell.lysis_rate * dt / 2.0).exp();
+        let removal_radius = cell.division_radius / std::f64::consts::SQRT_2 / 2.0;
+        Ok(cell.interaction.radius <= removal_radius)
+    }
+}
+
+/// Domain with the oxygen field and the initial cells of the [SpheroidScenario] together with
+/// its time stepping.
+#[derive(Clone, Debug)]
+pub struct SpheroidSetup {
+    /// Square domain which is supplied with oxygen from its border
+    pub domain: DiffusionCuboid<2>,
+    /// Initial cluster of cells in the center of the domain
+    pub agents: Vec<SpheroidCell>,
+    /// Time increment
+    pub dt: f64,
+    /// Total number of steps
+    pub n_steps: u64,
+    /// Number of steps between two save points
+    pub save_interval: u64,
+}
+
+/// Composition and size of the spheroid at a single save point.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SpheroidSnapshot {
+    /// Time of the save point
+    pub time: f64,
+    /// Number of proliferating cells
+    pub n_proliferating: usize,
+    /// Number of quiescent cells
+    pub n_quiescent: usize,
+    /// Number of necrotic cells
+    pub n_necrotic: usize,
+    /// Largest distance of any cell to the center of the spheroid
+    pub radius: f64,
+    /// Largest distance of any necrotic cell to the center or zero without necrotic cells
+    pub necrotic_radius: f64,
+}
+
+impl SpheroidSnapshot {
+    /// Calculates the snapshot from the positions and states of all cells.
+    pub fn from_cells(
+        time: f64,
+        cells: impl IntoIterator<Item = (Vector2<f64>, SpheroidCellState)>,
+        center: Vector2<f64>,
+    ) -> Self {
+        let mut snapshot = Self {
+            time,
+            n_proliferating: 0,
+            n_quiescent: 0,
+            n_necrotic: 0,
+            radius: 0.0,
+            necrotic_radius: 0.0,
+        };
+        for (pos, state) in cells {
+            let distance = (pos - center).norm();
+            snapshot.radius = snapshot.radius.max(distance);
+            match state {
+                SpheroidCellState::Proliferating => snapshot.n_proliferating += 1,
+                SpheroidCellState::Quiescent => snapshot.n_quiescent += 1,
+                SpheroidCellState::Necrotic => {
+                    snapshot.n_necrotic += 1;
+                    snapshot.necrotic_radius = snapshot.necrotic_radius.max(distance);
+                }
+            }
+        }
+        snapshot
+    }
+
+    /// Total number of cells
+    pub fn n_cells(&self) -> usize {
+        self.n_proliferating + self.n_quiescent + self.n_necrotic
+    }
+}
+
+/// Results of the [SpheroidScenario] at all save points.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SpheroidAnalysis {
+    /// Snapshots ordered by their time
+    pub snapshots: Vec<SpheroidSnapshot>,
+    /// Time of the first snapshot which contains necrotic cells
+    pub onset_of_necrosis: Option<f64>,
+}
+
+impl SpheroidAnalysis {
+    /// Sorts the snapshots by their time and determines the onset of necrosis.
+    pub fn from_snapshots(mut snapshots: Vec<SpheroidSnapshot>) -> Self {
+        snapshots.sort_by(|s1, s2| s1.time.total_cmp(&s2.time));
+        let onset_of_necrosis = snapshots
+            .iter()
+            .find(|snapshot| snapshot.n_necrotic > 0)
+            .map(|snapshot| snapshot.time);
+        Self {
+            snapshots,
+            onset_of_necrosis,
+        }
+    }
+}
+
+/// Growth of a tumor spheroid which is limited by the supply of oxygen.
+///
+/// A small cluster of cells is placed in the center of a square domain whose border is
+/// supplied with oxygen.
+/// Oxygen diffuses into the domain (see [DiffusionCuboid]) and is consumed by the cells (see
+/// [SpheroidCell]).
+/// Initially all cells proliferate and the spheroid grows exponentially.
+/// As the spheroid becomes larger, oxygen is depleted in its center such that cells become
+/// quiescent and finally necrotic.
+/// The spheroid thus develops the characteristic layered structure of a proliferating rim
+/// around a quiescent layer and a necrotic core, as observed in multicellular tumor
+/// spheroids (Sutherland, Science 240, 1988; Grimes et al., J. R. Soc. Interface 11, 2014).
+/// This scenario couples the diffusion of oxygen with the reactions, cycle and death of cells.
+///
+/// The scenario needs the [Mechanics], [Interaction], [Cycle] and [ReactionsExtra] aspects.
+/// Stored cells are evaluated by [analyze](SpheroidScenario::analyze) which yields the
+/// [SpheroidAnalysis].
+///
+/// ```
+/// # use cellular_raza_building_blocks::{SpheroidCellState, SpheroidScenario};
+/// let scenario = SpheroidScenario::default();
+/// let setup = scenario.build()?;
+/// assert_eq!(setup.agents.len(), 7);
+/// // Usually the stored cells of all save points are analyzed
+/// let analysis = scenario.analyze([(0, setup.agents.iter())]);
+/// assert_eq!(analysis.snapshots[0].n_proliferating, 7);
+/// assert_eq!(analysis.onset_of_necrosis, None);
+/// # Ok::<(), cellular_raza_building_blocks::ScenarioError>(())
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SpheroidScenario {
+    /// Radius of newly divided cells
+    pub cell_radius: f64,
+    /// Radius of the initial cluster of cells
+    pub initial_radius: f64,
+    /// Growth rate of the area of proliferating cells
+    pub growth_rate: f64,
+    /// Stiffness of the repulsion between overlapping cells
+    pub stiffness: f64,
+    /// Damping constant of the mechanics
+    pub damping: f64,
+    /// Mass of every cell
+    pub mass: f64,
+    /// Diffusion constant of oxygen
+    pub diffusion_constant: f64,
+    /// Oxygen concentration at the border of the domain which is also the initial value
+    pub oxygen_supply: f64,
+    /// Maximal uptake of oxygen per cell and time
+    pub max_uptake: f64,
+    /// Concentration at which the uptake is half of its maximum
+    pub uptake_half_saturation: f64,
+    /// Rate at which cells sense the extracellular oxygen concentration
+    pub sensing_rate: f64,
+    /// Oxygen level below which cells become quiescent
+    pub hypoxia_threshold: f64,
+    /// Oxygen level below which cells become necrotic
+    pub necrosis_threshold: f64,
+    /// Rate at which the area of necrotic cells decays
+    pub lysis_rate: f64,
+    /// Side length of the square domain
+    pub domain_size: f64,
+    /// Time increment
+    pub dt: f64,
+    /// Total number of steps
+    pub n_steps: u64,
+    /// Number of steps between two save points
+    pub save_interval: u64,
+    /// Seed of the domain from which the division axes are drawn
+    pub seed: u64,
+}
+
+impl Default for SpheroidScenario {
+    fn default() -> Self {
+        Self {
+            cell_radius: 1.0,
+            initial_radius: 2.0,
+            growth_rate: 0.1,
+            stiffness: 10.0,
+            damping: 1.0,
+            mass: 1.0,
+            diffusion_constant: 100.0,
+            oxygen_supply: 1.0,
+            max_uptake: 1.0,
+            uptake_half_saturation: 0.05,
+            sensing_rate: 10.0,
+            hypoxia_threshold: 0.4,
+            necrosis_threshold: 0.2,
+            lysis_rate: 0.1,
+            domain_size: 100.0,
+            dt: 0.01,
+            n_steps: 6_000,
+            save_interval: 250,
+            seed: 0,
+        }
+    }
+}
+
+impl SpheroidScenario {
+    /// Center of the domain in which the initial cluster is placed
+    pub fn center(&self) -> Vector2<f64> {
+        Vector2::from_element(self.domain_size / 2.0)
+    }
+
+    /// Builds the domain with its oxygen field and the initial cluster of cells.
+    ///
+    /// Cells are placed on a hexagonal lattice with distance of two cell radii within the
+    /// initial radius.
+    /// Fails if the explicit integration of the diffusion of oxygen is not stable for the given
+    /// time increment (see [check_reaction_diffusion_stability]).
+    pub fn build(&self) -> Result<SpheroidSetup, ScenarioError> {
+        if !(self.cell_radius > 0.0 && self.initial_radius >= 0.0 && self.growth_rate >= 0.0) {
+            return Err(ScenarioError::ParameterError(
+                "cell radius must be positive, initial radius and growth rate non-negative"
+                    .to_owned(),
+            ));
+        }
+        if self.necrosis_threshold.is_nan()
+            || self.hypoxia_threshold.is_nan()
+            || self.necrosis_threshold > self.hypoxia_threshold
+        {
+            return Err(ScenarioError::ParameterError(format!(
+                "necrosis threshold {} must not exceed hypoxia threshold {}",
+                self.necrosis_threshold, self.hypoxia_threshold
+            )));
+        }
+        let division_radius = std::f64::consts::SQRT_2 * self.cell_radius;
+        let mut cuboid = CartesianCuboid::from_boundaries_and_interaction_range(
+            [0.0; 2],
+            [self.domain_size; 2],
+            2.0 * division_radius,
+        )?;
+        cuboid.rng_seed = self.seed;
+        let dx: [f64; 2] = cuboid.get_dx().into();
+        check_reaction_diffusion_stability([(self.diffusion_constant, 0.0)], dx, self.dt)
+            .map_err(|e| ScenarioError::ParameterError(format!("{e}")))?;
+        let domain = DiffusionCuboid {
+            cuboid,
+            diffusion_constant: self.diffusion_constant,
+            decay_rate: 0.0,
+            boundary_value: Some(self.oxygen_supply),
+            dosing: None,
+            initial_value: self.oxygen_supply,
+        };
+
+        let spacing = 2.0 * self.cell_radius;
+        let n_max = (self.initial_radius / spacing).ceil() as i64 + 1;
+        let agents = (-n_max..=n_max)
+            .flat_map(|i| (-n_max..=n_max).map(move |j| (i, j)))
+            .map(|(i, j)| {
+                let offset = Vector2::from([
+                    spacing * (i as f64 + 0.5 * j as f64),
+                    spacing * 3f64.sqrt() / 2.0 * j as f64,
+                ]);
+                self.center() + offset
+            })
+            .filter(|pos| (pos - self.center()).norm() <= self.initial_radius + 1e-9)
+            .map(|pos| SpheroidCell {
+                mechanics: NewtonDamped2D {
+                    pos,
+                    vel: SVector::zeros(),
+                    damping_constant: self.damping,
+                    mass: self.mass,
+                },
+                interaction: ContactInhibition {
+                    radius: self.cell_radius,
+                    stiffness: self.stiffness,
+                    relative_contact_range: 1.0,
+                    n_neighbors: 0,
+                },
+                state: SpheroidCellState::Proliferating,
+                oxygen: self.oxygen_supply,
+                growth_rate: self.growth_rate,
+                division_radius,
+                max_uptake: self.max_uptake,
+                uptake_half_saturation: self.uptake_half_saturation,
+                sensing_rate: self.sensing_rate,
+                hypoxia_threshold: self.hypoxia_threshold,
+                necrosis_threshold: self.necrosis_threshold,
+                lysis_rate: self.lysis_rate,
+            })
+            .collect();
+        Ok(SpheroidSetup {
+            domain,
+            agents,
+            dt: self.dt,
+            n_steps: self.n_steps,
+            save_interval: self.save_interval,
+        })
+    }
+
+    /// Calculates the [SpheroidSnapshot] of the given cells at the specified iteration.
+    pub fn measure<'a>(
+        &self,
+        iteration: u64,
+        cells: impl IntoIterator<Item = &'a SpheroidCell>,
+    ) -> SpheroidSnapshot {
+        SpheroidSnapshot::from_cells(
+            iteration as f64 * self.dt,
+            cells
+                .into_iter()
+                .map(|cell| (cell.mechanics.pos, cell.state)),
+            self.center(),
+        )
+    }
+
+    /// Analyzes the cells of all save points given by their iteration.
+    pub fn analyze<'a, I>(
+        &self,
+        save_points: impl IntoIterator<Item = (u64, I)>,
+    ) -> SpheroidAnalysis
+    where
+        I: IntoIterator<Item = &'a SpheroidCell>,
+    {
+        SpheroidAnalysis::from_snapshots(
+            save_points
+                .into_iter()
+                .map(|(iteration, cells)| self.measure(iteration, cells))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test_spheroid {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn oxygen_determines_state() {
+        let mut cell = SpheroidScenario::default()
+            .build()
+            .unwrap()
+            .agents
+            .pop()
+            .unwrap();
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(0);
+        // Cells sense and consume oxygen
+        let (doxygen, dextra) = cell.calculate_combined_increment(&1.0, &0.5).unwrap();
+        assert!((doxygen + 5.0).abs() < 1e-12);
+        assert!((dextra + 0.5 / 0.55).abs() < 1e-12);
+        // Hypoxic cells do not grow
+        cell.oxygen = 0.3;
+        assert_eq!(SpheroidCell::update_cycle(&mut rng, &1.0, &mut cell), None);
+        assert_eq!(cell.state, SpheroidCellState::Quiescent);
+        assert_eq!(cell.interaction.radius, 1.0);
+        // Quiescence is reversible
+        cell.oxygen = 0.8;
+        assert_eq!(SpheroidCell::update_cycle(&mut rng, &1.0, &mut cell), None);
+        assert_eq!(cell.state, SpheroidCellState::Proliferating);
+        assert!(cell.interaction.radius > 1.0);
+        // Necrotic cells stop consuming oxygen and are lysed
+        cell.oxygen = 0.1;
+        assert_eq!(
+            SpheroidCell::update_cycle(&mut rng, &1.0, &mut cell),
+            Some(CycleEvent::PhasedDeath)
+        );
+        assert_eq!(cell.state, SpheroidCellState::Necrotic);
+        let (_, dextra) = cell.calculate_combined_increment(&0.1, &0.1).unwrap();
+        assert_eq!(dextra, 0.0);
+        let mut n_steps = 0;
+        while !SpheroidCell::update_conditional_phased_death(&mut rng, &0.1, &mut cell).unwrap() {
+            n_steps += 1;
+        }
+        // The area decays to a quarter after ln(4)/lysis_rate
+        let t = n_steps as f64 * 0.1;
+        assert!((t - (4.0 * 1.05f64.powi(2)).ln() / 0.1).abs() < 0.2);
+    }
+
+    #[test]
+    fn division_conserves_area() {
+        let mut cell = SpheroidScenario::default()
+            .build()
+            .unwrap()
+            .agents
+            .pop()
+            .unwrap();
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(1);
+        cell.interaction.radius = cell.division_radius;
+        let center = cell.mechanics.pos;
+        let daughter = SpheroidCell::divide(&mut rng, &mut cell).unwrap();
+        assert!((cell.interaction.radius - 1.0).abs() < 1e-12);
+        assert_eq!(daughter.interaction.radius, cell.interaction.radius);
+        let midpoint = (cell.mechanics.pos + daughter.mechanics.pos) / 2.0;
+        assert!((midpoint - center).norm() < 1e-12);
+    }
+
+    #[test]
+    fn snapshot_of_layers() {
+        let center = Vector2::zeros();
+        let cells = [
+            ([0.5, 0.0], SpheroidCellState::Necrotic),
+            ([0.0, 2.0], SpheroidCellState::Quiescent),
+            ([-3.0, 0.0], SpheroidCellState::Proliferating),
+            ([0.0, -4.0], SpheroidCellState::Proliferating),
+        ]
+        .map(|(pos, state)| (Vector2::from(pos), state));
+        let snapshot = SpheroidSnapshot::from_cells(1.0, cells, center);
+        assert_eq!(snapshot.n_proliferating, 2);
+        assert_eq!(snapshot.n_quiescent, 1);
+        assert_eq!(snapshot.n_necrotic, 1);
+        assert_eq!(snapshot.n_cells(), 4);
+        assert_eq!(snapshot.radius, 4.0);
+        assert_eq!(snapshot.necrotic_radius, 0.5);
+        let early = SpheroidSnapshot::from_cells(0.0, cells.into_iter().skip(1), center);
+        let analysis = SpheroidAnalysis::from_snapshots(vec![snapshot, early]);
+        assert_eq!(analysis.snapshots[0].time, 0.0);
+        assert_eq!(analysis.onset_of_necrosis, Some(1.0));
+    }
+
+    #[test]
+    fn unstable_diffusion_is_rejected() {
+        let scenario = SpheroidScenario {
+            dt: 1.0,
+            ..Default::default()
+        };
+        assert!(matches!(
+            scenario.build(),
+            Err(ScenarioError::ParameterError(_))
+        ));
+        let scenario = SpheroidScenario {
+            necrosis_threshold: 0.6,
+            ..Default::default()
+        };
+        assert!(scenario.build().is_err());
+    }
+}
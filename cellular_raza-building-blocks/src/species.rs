@@ -0,0 +1,456 @@
+//! Concentration vectors whose entries are addressed by named species.
+//!
+//! Reactions are usually formulated with [SVector]s where every entry corresponds to one
+//! chemical species.
+//! Accessing these entries via plain indices is error-prone, especially when species are
+//! added or reordered.
+//! The [define_species](crate::define_species) macro declares the names of all species once
+//! and generates a constant [SpeciesIndex] for each of them.
+//! A [SpeciesMap] can only be indexed by indices of its own species and is serialized with the
+//! names of all species such that stored results are self-describing.
+//!
+//! ```
+//! # use cellular_raza_building_blocks::{define_species, SpeciesMap};
+//! define_species!(
+//!     /// Nutrients in the medium
+//!     pub Nutrients {
+//!         GLUCOSE = "glucose",
+//!         OXYGEN = "oxygen",
+//!     }
+//! );
+//!
+//! let mut concentrations = SpeciesMap::<Nutrients, f64, 2>::zeros();
+//! concentrations[Nutrients::OXYGEN] = 0.2;
+//! assert_eq!(concentrations.values()[1], 0.2);
+//! assert_eq!(
+//!     serde_json::to_string(&concentrations)?,
+//!     "{\"glucose\":0.0,\"oxygen\":0.2}"
+//! );
+//! # Ok::<(), serde_json::Error>(())
+//! ```
+
+use nalgebra::SVector;
+use serde::{Deserialize, Serialize};
+
+use core::marker::PhantomData;
+
+/// Names of a fixed set of `N` species.
+///
+/// This trait is usually implemented via the [define_species](crate::define_species) macro.
+pub trait SpeciesNames<const N: usize> {
+    /// Names of all species in the order of their indices
+    const NAMES: [&'static str; N];
+}
+
+/// Index of a single species which can only be used with [SpeciesMap]s of the same species.
+#[derive(Debug)]
+pub struct SpeciesIndex<S> {
+    index: usize,
+    species: PhantomData<S>,
+}
+
+impl<S> SpeciesIndex<S> {
+    /// Creates the index without checking that it is in bounds.
+    ///
+    /// Prefer the constants generated by the [define_species](crate::define_species) macro.
+    pub const fn new(index: usize) -> Self {
+        Self {
+            index,
+            species: PhantomData,
+        }
+    }
+
+    /// Position of the species inside the underlying vector
+    pub const fn index(&self) -> usize {
+        self.index
+    }
+}
+
+// Implemented manually such that S does not need to implement these traits
+impl<S> Clone for SpeciesIndex<S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S> Copy for SpeciesIndex<S> {}
+
+impl<S> PartialEq for SpeciesIndex<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl<S> Eq for SpeciesIndex<S> {}
+
+/// Declares a set of species and generates a constant [SpeciesIndex] for each of them.
+///
+/// The generated unit struct implements [SpeciesNames] and can be used as the first generic
+/// parameter of a [SpeciesMap].
+/// Additionally, the number of species is available as the associated constant `N`.
+///
+/// ```
+/// # use cellular_raza_building_blocks::{define_species, SpeciesMap, SpeciesNames};
+/// define_species!(pub Morphogens { BMP = "bmp", NOGGIN = "noggin", WNT = "wnt" });
+/// assert_eq!(Morphogens::N, 3);
+/// assert_eq!(Morphogens::WNT.index(), 2);
+/// assert_eq!(<Morphogens as SpeciesNames<3>>::NAMES, ["bmp", "noggin", "wnt"]);
+/// let concentrations = SpeciesMap::<Morphogens, f64, { Morphogens::N }>::zeros();
+/// assert_eq!(concentrations[Morphogens::BMP], 0.0);
+/// ```
+#[macro_export]
+macro_rules! define_species {
+    (
+        $(#[$meta:meta])*
+        $vis:vis $name:ident { $($constant:ident = $species:literal),+ $(,)? }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+        $vis struct $name;
+
+        #[allow(unused)]
+        impl $name {
+            /// Number of species
+            pub const N: usize = [$($species),+].len();
+            $crate::define_species!(@indices $name, 0usize, $($constant = $species),+);
+        }
+
+        impl $crate::SpeciesNames<{ [$($species),+].len() }> for $name {
+            const NAMES: [&'static str; { [$($species),+].len() }] = [$($species),+];
+        }
+    };
+    (
+        @indices $name:ident,
+        $index:expr,
+        $constant:ident = $species:literal
+        $(, $rest:ident = $rest_species:literal)*
+    ) => {
+        #[doc = concat!("Index of the species `", $species, "`")]
+        pub const $constant: $crate::SpeciesIndex<$name> = $crate::SpeciesIndex::new($index);
+        $crate::define_species!(@indices $name, $index + 1, $($rest = $rest_species),*);
+    };
+    (@indices $name:ident, $index:expr,) => {};
+}
+
+/// Vector of values for every species which is indexed by [SpeciesIndex].
+///
+/// The map supports the arithmetic operations of the underlying [SVector] such that it can be
+/// used directly as intracellular or extracellular concentrations of reactions.
+/// Looking up entries by their name is only available in debug builds since it is
+/// considerably slower than using the generated constants.
+/// ```
+/// # use cellular_raza_building_blocks::{define_species, SpeciesMap};
+/// # use nalgebra::Vector2;
+/// define_species!(pub Signals { ACTIVATOR = "activator", INHIBITOR = "inhibitor" });
+/// let x = SpeciesMap::<Signals, f64, 2>::new(Vector2::from([1.0, 2.0]));
+/// let y = SpeciesMap::new(Vector2::from([0.5, 0.5]));
+/// let z = &x * 2.0 + &y;
+/// assert_eq!(z[Signals::INHIBITOR], 4.5);
+/// #[cfg(debug_assertions)]
+/// {
+///     assert_eq!(z.get("activator"), Some(&2.5));
+///     assert_eq!(z.get("unknown"), None);
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpeciesMap<S, F, const N: usize> {
+    values: SVector<F, N>,
+    species: PhantomData<S>,
+}
+
+impl<S, F, const N: usize> SpeciesMap<S, F, N>
+where
+    S: SpeciesNames<N>,
+    F: nalgebra::Scalar,
+{
+    /// Wraps the given values which are ordered as [SpeciesNames::NAMES].
+    pub fn new(values: SVector<F, N>) -> Self {
+        Self {
+            values,
+            species: PhantomData,
+        }
+    }
+
+    /// Map where all entries are zero
+    pub fn zeros() -> Self
+    where
+        F: num::Zero,
+    {
+        Self::new(SVector::zeros())
+    }
+
+    /// Names of all species
+    pub fn names(&self) -> [&'static str; N] {
+        S::NAMES
+    }
+
+    /// Underlying vector of values
+    pub fn values(&self) -> &SVector<F, N> {
+        &self.values
+    }
+
+    /// Mutable access to the underlying vector of values
+    pub fn values_mut(&mut self) -> &mut SVector<F, N> {
+        &mut self.values
+    }
+
+    /// Returns the underlying vector of values
+    pub fn into_inner(self) -> SVector<F, N> {
+        self.values
+    }
+
+    /// Iterates over the names and values of all species
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &F)> {
+        S::NAMES.into_iter().zip(self.values.iter())
+    }
+
+    /// Value of the species with the given name
+    #[cfg(debug_assertions)]
+    pub fn get(&self, name: &str) -> Option<&F> {
+        let index = S::NAMES.iter().position(|n| *n == name)?;
+        self.values.get(index)
+    }
+
+    /// Mutable value of the species with the given name
+    #[cfg(debug_assertions)]
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut F> {
+        let index = S::NAMES.iter().position(|n| *n == name)?;
+        self.values.get_mut(index)
+    }
+}
+
+impl<S, F, const N: usize> From<SVector<F, N>> for SpeciesMap<S, F, N>
+where
+    S: SpeciesNames<N>,
+    F: nalgebra::Scalar,
+{
+    fn from(values: SVector<F, N>) -> Self {
+        Self::new(values)
+    }
+}
+
+impl<S, F, const N: usize> core::ops::Index<SpeciesIndex<S>> for SpeciesMap<S, F, N>
+where
+    F: nalgebra::Scalar,
+{
+    type Output = F;
+
+    fn index(&self, index: SpeciesIndex<S>) -> &F {
+        &self.values[index.index]
+    }
+}
+
+impl<S, F, const N: usize> core::ops::IndexMut<SpeciesIndex<S>> for SpeciesMap<S, F, N>
+where
+    F: nalgebra::Scalar,
+{
+    fn index_mut(&mut self, index: SpeciesIndex<S>) -> &mut F {
+        &mut self.values[index.index]
+    }
+}
+
+macro_rules! impl_binary_op(
+    ($trait:ident, $method:ident) => {
+        impl<S, F, const N: usize> core::ops::$trait for SpeciesMap<S, F, N>
+        where
+            SVector<F, N>: core::ops::$trait<Output = SVector<F, N>>,
+        {
+            type Output = Self;
+
+            fn $method(self, rhs: Self) -> Self {
+                Self {
+                    values: self.values.$method(rhs.values),
+                    species: PhantomData,
+                }
+            }
+        }
+
+        impl<'a, S, F, const N: usize> core::ops::$trait<&'a Self> for SpeciesMap<S, F, N>
+        where
+            SVector<F, N>: core::ops::$trait<&'a SVector<F, N>, Output = SVector<F, N>>,
+        {
+            type Output = Self;
+
+            fn $method(self, rhs: &'a Self) -> Self {
+                Self {
+                    values: self.values.$method(&rhs.values),
+                    species: PhantomData,
+                }
+            }
+        }
+    }
+);
+
+impl_binary_op!(Add, add);
+impl_binary_op!(Sub, sub);
+
+impl<S, F, const N: usize> core::ops::Mul<F> for SpeciesMap<S, F, N>
+where
+    SVector<F, N>: core::ops::Mul<F, Output = SVector<F, N>>,
+{
+    type Output = Self;
+
+    fn mul(self, rhs: F) -> Self {
+        Self {
+            values: self.values * rhs,
+            species: PhantomData,
+        }
+    }
+}
+
+impl<'a, S, F, const N: usize> core::ops::Mul<F> for &'a SpeciesMap<S, F, N>
+where
+    &'a SVector<F, N>: core::ops::Mul<F, Output = SVector<F, N>>,
+{
+    type Output = SpeciesMap<S, F, N>;
+
+    fn mul(self, rhs: F) -> SpeciesMap<S, F, N> {
+        SpeciesMap {
+            values: &self.values * rhs,
+            species: PhantomData,
+        }
+    }
+}
+
+impl<S, F, const N: usize> num::Zero for SpeciesMap<S, F, N>
+where
+    SVector<F, N>: num::Zero,
+{
+    fn zero() -> Self {
+        Self {
+            values: SVector::zero(),
+            species: PhantomData,
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.values.is_zero()
+    }
+}
+
+impl<S, F, const N: usize> Serialize for SpeciesMap<S, F, N>
+where
+    S: SpeciesNames<N>,
+    F: nalgebra::Scalar + Serialize,
+{
+    fn serialize<Se>(&self, serializer: Se) -> Result<Se::Ok, Se::Error>
+    where
+        Se: serde::Serializer,
+    {
+        use serde::ser::SerializeMap;
+        let mut map = serializer.serialize_map(Some(N))?;
+        for (name, value) in self.iter() {
+            map.serialize_entry(name, value)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de, S, F, const N: usize> Deserialize<'de> for SpeciesMap<S, F, N>
+where
+    S: SpeciesNames<N>,
+    F: nalgebra::Scalar + Deserialize<'de>,
+{
+    fn deserialize<De>(deserializer: De) -> Result<Self, De::Error>
+    where
+        De: serde::Deserializer<'de>,
+    {
+        struct SpeciesMapVisitor<S, F, const N: usize>(PhantomData<(S, F)>);
+
+        impl<'de, S, F, const N: usize> serde::de::Visitor<'de> for SpeciesMapVisitor<S, F, N>
+        where
+            S: SpeciesNames<N>,
+            F: nalgebra::Scalar + Deserialize<'de>,
+        {
+            type Value = SpeciesMap<S, F, N>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(formatter, "a map with the species {:?}", S::NAMES)
+            }
+
+            fn visit_map<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                use serde::de::Error;
+                let mut values: [Option<F>; N] = core::array::from_fn(|_| None);
+                while let Some(name) = access.next_key::<String>()? {
+                    let index = S::NAMES.iter().position(|n| *n == name).ok_or_else(|| {
+                        A::Error::custom(format!(
+                            "unknown species `{name}`, expected one of {:?}",
+                            S::NAMES
+                        ))
+                    })?;
+                    if values[index].is_some() {
+                        return Err(A::Error::custom(format!("duplicate species `{name}`")));
+                    }
+                    values[index] = Some(access.next_value()?);
+                }
+                let values = values
+                    .into_iter()
+                    .zip(S::NAMES)
+                    .map(|(value, name)| value.ok_or_else(|| A::Error::missing_field(name)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(SpeciesMap::new(SVector::from_iterator(values)))
+            }
+        }
+
+        deserializer.deserialize_map(SpeciesMapVisitor::<S, F, N>(PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod test_species {
+    use super::*;
+    use cellular_raza_concepts::Xapy;
+
+    define_species!(Ions {
+        CALCIUM = "calcium",
+        SODIUM = "sodium",
+        POTASSIUM = "potassium",
+    });
+
+    type IonMap = SpeciesMap<Ions, f64, { Ions::N }>;
+
+    #[test]
+    fn generated_constants() {
+        assert_eq!(Ions::N, 3);
+        assert_eq!(Ions::CALCIUM.index(), 0);
+        assert_eq!(Ions::POTASSIUM.index(), 2);
+        let mut ions = IonMap::zeros();
+        ions[Ions::SODIUM] = 1.5;
+        assert_eq!(ions.values(), &SVector::from([0.0, 1.5, 0.0]));
+        #[cfg(debug_assertions)]
+        {
+            *ions.get_mut("potassium").unwrap() = 3.0;
+            assert_eq!(ions[Ions::POTASSIUM], 3.0);
+        }
+        let entries: Vec<_> = ions.iter().collect();
+        assert_eq!(entries[1], ("sodium", &1.5));
+    }
+
+    #[test]
+    fn arithmetic_for_solvers() {
+        let x = IonMap::new(SVector::from([1.0, 2.0, 3.0]));
+        let y = IonMap::new(SVector::from([1.0, 1.0, 1.0]));
+        assert_eq!(x.xapy(2.0, &y).into_inner(), SVector::from([3.0, 5.0, 7.0]));
+        assert_eq!(x.xa(0.5).into_inner(), SVector::from([0.5, 1.0, 1.5]));
+        assert_eq!((x - y)[Ions::CALCIUM], 0.0);
+        assert_eq!(<IonMap as num::Zero>::zero(), IonMap::zeros());
+    }
+
+    #[test]
+    fn serialize_with_names() {
+        let ions = IonMap::new(SVector::from([1.0, 2.0, 3.0]));
+        let json = serde_json::to_string(&ions).unwrap();
+        assert_eq!(json, r#"{"calcium":1.0,"sodium":2.0,"potassium":3.0}"#);
+        // The order of the entries does not matter
+        let reordered: IonMap =
+            serde_json::from_str(r#"{"potassium":3.0,"calcium":1.0,"sodium":2.0}"#).unwrap();
+        assert_eq!(reordered, ions);
+        let missing = serde_json::from_str::<IonMap>(r#"{"calcium":1.0,"sodium":2.0}"#);
+        assert!(missing.unwrap_err().to_string().contains("potassium"));
+        let unknown = serde_json::from_str::<IonMap>(r#"{"chloride":1.0}"#);
+        assert!(unknown.unwrap_err().to_string().contains("chloride"));
+    }
+}
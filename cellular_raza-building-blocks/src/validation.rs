@@ -0,0 +1,698 @@
+//! Ready-made scenarios with known analytical solutions.
+//!
+//! Every scenario integrates a small system with the provided building blocks and compares the
+//! result against its analytical solution.
+//! They can be used to verify an installation or to check custom implementations of
+//! [Mechanics], [Interaction], [SubDomainReactions] or [Cycle].
+//!
+//! | Scenario | Tested Concept | Compared Quantity |
+//! | --- | --- | --- |
+//! | [HarmonicOscillation] | [Mechanics] and [Interaction] | Distance between two cells |
+//! | [BrownianMsd] | [Mechanics] | Mean squared displacement |
+//! | [PointSourceDiffusion] | [SubDomainReactions] | Concentration at sample points |
+//! | [ExponentialGrowth] | [Cycle] | Mean number of cells |
+//!
+//! All scenarios return a [ValidationReport] which contains the measured and expected values
+//! together with error metrics.
+
+use cellular_raza_concepts::*;
+use nalgebra::SVector;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+use core::fmt::Display;
+use std::error::Error;
+
+/// Errors which can occur while executing a validation scenario.
+#[derive(Debug)]
+pub enum ValidationError {
+    /// A calculation of the tested building block failed.
+    CalcError(CalcError),
+    /// Sampling random numbers failed.
+    RngError(RngError),
+    /// Dividing a cell failed.
+    DivisionError(DivisionError),
+    /// Updating a dying cell failed.
+    DeathError(DeathError),
+    /// The parameters of the scenario are inconsistent.
+    ParameterError(String),
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ValidationError::CalcError(e) => write!(f, "{e}"),
+            ValidationError::RngError(e) => write!(f, "{e}"),
+            ValidationError::DivisionError(e) => write!(f, "{e}"),
+            ValidationError::DeathError(e) => write!(f, "{e}"),
+            ValidationError::ParameterError(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl Error for ValidationError {}
+
+macro_rules! impl_from_error(
+    ($($error:ident),*) => {
+        $(
+            impl From<$error> for ValidationError {
+                fn from(err: $error) -> Self {
+                    ValidationError::$error(err)
+                }
+            }
+        )*
+    }
+);
+
+impl_from_error!(CalcError, RngError, DivisionError, DeathError);
+
+/// Measured and expected values of a validation scenario together with error metrics.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ValidationReport {
+    /// Values obtained by the numerical solution
+    pub measured: Vec<f64>,
+    /// Values of the analytical solution
+    pub expected: Vec<f64>,
+    /// Largest absolute difference between measured and expected values
+    pub max_abs_error: f64,
+    /// Largest absolute difference divided by the absolute expected value
+    pub max_rel_error: f64,
+    /// Root mean squared difference between measured and expected values
+    pub rmse: f64,
+}
+
+impl ValidationReport {
+    /// Calculates the error metrics of the given values.
+    ///
+    /// ```
+    /// # use cellular_raza_building_blocks::ValidationReport;
+    /// let report = ValidationReport::from_values(vec![1.0, 2.2], vec![1.0, 2.0]);
+    /// assert!((report.max_abs_error - 0.2).abs() < 1e-12);
+    /// assert!((report.max_rel_error - 0.1).abs() < 1e-12);
+    /// assert!(report.is_within(0.11));
+    /// ```
+    pub fn from_values(measured: Vec<f64>, expected: Vec<f64>) -> Self {
+        let mut max_abs_error = 0.0f64;
+        let mut max_rel_error = 0.0f64;
+        let mut sum_squares = 0.0;
+        for (m, e) in measured.iter().zip(expected.iter()) {
+            let abs_error = (m - e).abs();
+            max_abs_error = max_abs_error.max(abs_error);
+            if e.abs() > 0.0 {
+                max_rel_error = max_rel_error.max(abs_error / e.abs());
+            }
+            sum_squares += abs_error.powi(2);
+        }
+        let n_values = measured.len().min(expected.len()).max(1);
+        Self {
+            measured,
+            expected,
+            max_abs_error,
+            max_rel_error,
+            rmse: (sum_squares / n_values as f64).sqrt(),
+        }
+    }
+
+    /// Checks if the largest relative error is below the given tolerance.
+    pub fn is_within(&self, rel_tolerance: f64) -> bool {
+        self.max_rel_error <= rel_tolerance
+    }
+}
+
+/// Two cells connected by a linear spring which oscillate around their resting distance.
+///
+/// The first cell is placed at the origin and the second one at distance $r_0 + A$ along the
+/// first axis while both are at rest.
+/// The deviation $x$ from the resting distance $r_0$ follows
+/// \\begin{equation}
+///     \ddot{x} + \gamma\dot{x} + \frac{2k}{m}x = 0
+/// \\end{equation}
+/// where $\gamma$ is the damping constant, $k$ the spring constant and $m$ the mass of each cell.
+/// The given [Interaction] should behave like a linear spring with these parameters.
+/// Potentials with a minimum at $r_0$ can be tested with small amplitudes $A$ by using their
+/// curvature $V''(r_0)$ as spring constant.
+///
+/// The equations of motion are solved with Heun's method.
+/// The report compares the distance between the cells at every step.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct HarmonicOscillation {
+    /// Mass $m$ of each cell
+    pub mass: f64,
+    /// Damping constant $\gamma$
+    pub damping: f64,
+    /// Spring constant $k$
+    pub spring_constant: f64,
+    /// Resting distance $r_0$
+    pub rest_length: f64,
+    /// Initial deviation $A$ from the resting distance
+    pub amplitude: f64,
+    /// Time increment
+    pub dt: f64,
+    /// Final time
+    pub t_max: f64,
+}
+
+impl Default for HarmonicOscillation {
+    fn default() -> Self {
+        Self {
+            mass: 1.0,
+            damping: 0.1,
+            spring_constant: 0.5,
+            rest_length: 1.0,
+            amplitude: 0.2,
+            dt: 1e-3,
+            t_max: 10.0,
+        }
+    }
+}
+
+impl HarmonicOscillation {
+    /// Analytical distance between the two cells at time `t`
+    pub fn analytic_distance(&self, t: f64) -> f64 {
+        let a = self.amplitude;
+        let omega0_sq = 2.0 * self.spring_constant / self.mass;
+        let half_damping = self.damping / 2.0;
+        let discriminant = half_damping.powi(2) - omega0_sq;
+        let decay = (-half_damping * t).exp();
+        let x = if discriminant.abs() < 1e-12 {
+            a * decay * (1.0 + half_damping * t)
+        } else if discriminant < 0.0 {
+            let omega = (-discriminant).sqrt();
+            a * decay * ((omega * t).cos() + half_damping / omega * (omega * t).sin())
+        } else {
+            let lambda_plus = -half_damping + discriminant.sqrt();
+            let lambda_minus = -half_damping - discriminant.sqrt();
+            a * (lambda_plus * (lambda_minus * t).exp() - lambda_minus * (lambda_plus * t).exp())
+                / (lambda_plus - lambda_minus)
+        };
+        self.rest_length + x
+    }
+
+    /// Integrates the two cells and compares their distance to the analytical solution.
+    ///
+    /// Positions and velocities of the given cells are overwritten by the initial values of the
+    /// scenario.
+    pub fn validate<M, I, Inf, const D: usize>(
+        &self,
+        cells: [M; 2],
+        interaction: &I,
+    ) -> Result<ValidationReport, ValidationError>
+    where
+        M: Mechanics<SVector<f64, D>, SVector<f64, D>, SVector<f64, D>, f64>,
+        M: Position<SVector<f64, D>>,
+        M: Velocity<SVector<f64, D>>,
+        M: Clone,
+        I: Interaction<SVector<f64, D>, SVector<f64, D>, SVector<f64, D>, Inf>,
+    {
+        if D == 0 {
+            return Err(ValidationError::ParameterError(
+                "the harmonic oscillation requires at least one dimension".into(),
+            ));
+        }
+        let mut cells = cells;
+        let mut pos1 = SVector::<f64, D>::zeros();
+        pos1[0] = self.rest_length + self.amplitude;
+        cells[0].set_pos(&SVector::zeros());
+        cells[1].set_pos(&pos1);
+        for cell in cells.iter_mut() {
+            cell.set_velocity(&SVector::zeros());
+        }
+
+        let increments = |cells: &[M; 2]| -> Result<[_; 2], CalcError> {
+            let (force0, force1) = interaction.calculate_force_between(
+                &cells[0].pos(),
+                &cells[0].velocity(),
+                &cells[1].pos(),
+                &cells[1].velocity(),
+                &interaction.get_interaction_information(),
+            )?;
+            Ok([
+                cells[0].calculate_increment(force0)?,
+                cells[1].calculate_increment(force1)?,
+            ])
+        };
+
+        let n_steps = (self.t_max / self.dt).round() as usize;
+        let mut measured = Vec::with_capacity(n_steps + 1);
+        let mut expected = Vec::with_capacity(n_steps + 1);
+        for n in 0..=n_steps {
+            measured.push((cells[1].pos() - cells[0].pos()).norm());
+            expected.push(self.analytic_distance(n as f64 * self.dt));
+            if n == n_steps {
+                break;
+            }
+            let k1 = increments(&cells)?;
+            let mut predicted = cells.clone();
+            for (cell, (dx, dv)) in predicted.iter_mut().zip(k1.iter()) {
+                cell.set_pos(&(cell.pos() + dx * self.dt));
+                cell.set_velocity(&(cell.velocity() + dv * self.dt));
+            }
+            let k2 = increments(&predicted)?;
+            for ((cell, (dx1, dv1)), (dx2, dv2)) in cells.iter_mut().zip(k1.iter()).zip(k2.iter()) {
+                cell.set_pos(&(cell.pos() + (dx1 + dx2) * self.dt / 2.0));
+                cell.set_velocity(&(cell.velocity() + (dv1 + dv2) * self.dt / 2.0));
+            }
+        }
+        Ok(ValidationReport::from_values(measured, expected))
+    }
+}
+
+/// Mean squared displacement of freely diffusing particles.
+///
+/// Independent copies of one particle are moved by their random contribution and the force-free
+/// increment of [Mechanics].
+/// Their mean squared displacement should follow
+/// \\begin{equation}
+///     \langle|\vec{x}(t)-\vec{x}(0)|^2\rangle = 2dDt
+/// \\end{equation}
+/// where $d$ is the spatial dimension and $D$ the diffusion constant.
+/// The report compares the mean squared displacement after every step.
+/// Due to the finite number of particles, relative errors of the order
+/// $\sqrt{2/(dN)}$ are expected.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct BrownianMsd {
+    /// Diffusion constant $D$ of the particles
+    pub diffusion_constant: f64,
+    /// Number of independent particles $N$
+    pub n_particles: usize,
+    /// Time increment
+    pub dt: f64,
+    /// Number of steps
+    pub n_steps: usize,
+    /// Seed of the random number generator
+    pub seed: u64,
+}
+
+impl Default for BrownianMsd {
+    fn default() -> Self {
+        Self {
+            diffusion_constant: 1.0,
+            n_particles: 1000,
+            dt: 1e-2,
+            n_steps: 100,
+            seed: 0,
+        }
+    }
+}
+
+impl BrownianMsd {
+    /// Analytical mean squared displacement at time `t` in `d` dimensions
+    pub fn analytic_msd(&self, t: f64, d: usize) -> f64 {
+        2.0 * d as f64 * self.diffusion_constant * t
+    }
+
+    /// Moves copies of the given particle and compares their mean squared displacement to the
+    /// analytical solution.
+    pub fn validate<M, const D: usize>(
+        &self,
+        particle: M,
+    ) -> Result<ValidationReport, ValidationError>
+    where
+        M: Mechanics<SVector<f64, D>, SVector<f64, D>, SVector<f64, D>, f64>,
+        M: Position<SVector<f64, D>>,
+        M: Velocity<SVector<f64, D>>,
+        M: Clone,
+    {
+        if self.n_particles == 0 {
+            return Err(ValidationError::ParameterError(
+                "at least one particle is required".into(),
+            ));
+        }
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(self.seed);
+        let initial_pos = particle.pos();
+        let mut particles = vec![particle; self.n_particles];
+        let mut measured = Vec::with_capacity(self.n_steps);
+        let mut expected = Vec::with_capacity(self.n_steps);
+        for n in 1..=self.n_steps {
+            for p in particles.iter_mut() {
+                let (dx, dv) = p.calculate_increment(SVector::zeros())?;
+                let (dx_rand, dv_rand) = p.get_random_contribution(&mut rng, self.dt)?;
+                p.set_pos(&(p.pos() + (dx + dx_rand) * self.dt));
+                p.set_velocity(&(p.velocity() + (dv + dv_rand) * self.dt));
+            }
+            let msd = particles
+                .iter()
+                .map(|p| (p.pos() - initial_pos).norm_squared())
+                .sum::<f64>()
+                / self.n_particles as f64;
+            measured.push(msd);
+            expected.push(self.analytic_msd(n as f64 * self.dt, D));
+        }
+        Ok(ValidationReport::from_values(measured, expected))
+    }
+}
+
+/// Diffusion of a substance released from a point source.
+///
+/// In free space, the concentration follows the Green's function of the diffusion equation
+/// \\begin{equation}
+///     u(\vec{x},t) = \frac{M}{(4\pi Dt)^{d/2}}\exp\left(-\frac{|\vec{x}-\vec{x}_0|^2}{4Dt}\right)
+/// \\end{equation}
+/// where $M$ is the released amount, $D$ the diffusion constant and $\vec{x}_0$ the position of
+/// the source.
+/// Since a delta distribution can not be represented numerically, the tested subdomain needs to
+/// be initialized with [PointSourceDiffusion::initial_condition] which is the Green's function at
+/// time $t_0>0$.
+/// The domain should be large enough such that boundaries do not influence the result.
+///
+/// The report compares the concentration at the sample points after the given duration.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PointSourceDiffusion<const D: usize> {
+    /// Released amount $M$
+    pub amount: f64,
+    /// Diffusion constant $D$
+    pub diffusion_constant: f64,
+    /// Position $\vec{x}_0$ of the source
+    pub source: SVector<f64, D>,
+    /// Time $t_0$ of the initial condition
+    pub t0: f64,
+    /// Duration which is integrated by the subdomain
+    pub duration: f64,
+    /// Time increment
+    pub dt: f64,
+    /// Positions at which the concentration is compared
+    pub sample_points: Vec<SVector<f64, D>>,
+}
+
+impl<const D: usize> PointSourceDiffusion<D> {
+    /// Green's function of the diffusion equation at the given position and time
+    pub fn greens_function(&self, pos: &SVector<f64, D>, t: f64) -> f64 {
+        let four_dt = 4.0 * self.diffusion_constant * t;
+        self.amount / (core::f64::consts::PI * four_dt).powf(D as f64 / 2.0)
+            * (-(pos - self.source).norm_squared() / four_dt).exp()
+    }
+
+    /// Concentration with which the subdomain needs to be initialized
+    pub fn initial_condition(&self, pos: &SVector<f64, D>) -> f64 {
+        self.greens_function(pos, self.t0)
+    }
+
+    /// Integrates the subdomain and compares its concentrations to the Green's function.
+    ///
+    /// The subdomain is updated in the same order as done by the backend without any cellular
+    /// sources or neighbors.
+    pub fn validate<S>(&self, subdomain: &mut S) -> Result<ValidationReport, ValidationError>
+    where
+        S: SubDomainReactions<SVector<f64, D>, f64, f64>,
+    {
+        if self.t0 <= 0.0 {
+            return Err(ValidationError::ParameterError(format!(
+                "initial time t0={} must be positive",
+                self.t0
+            )));
+        }
+        let n_steps = (self.duration / self.dt).round() as usize;
+        for _ in 0..n_steps {
+            subdomain.treat_increments(
+                core::iter::empty(),
+                core::iter::empty::<(SVector<f64, D>, f64)>(),
+            )?;
+            subdomain.update_fluid_dynamics(self.dt)?;
+        }
+        let t_end = self.t0 + n_steps as f64 * self.dt;
+        let measured = self
+            .sample_points
+            .iter()
+            .map(|pos| subdomain.get_extracellular_at_pos(pos))
+            .collect::<Result<Vec<_>, CalcError>>()?;
+        let expected = self
+            .sample_points
+            .iter()
+            .map(|pos| self.greens_function(pos, t_end))
+            .collect();
+        Ok(ValidationReport::from_values(measured, expected))
+    }
+}
+
+/// Exponential growth of a dividing population.
+///
+/// When cells divide with a constant rate $\lambda$, the mean number of cells follows
+/// \\begin{equation}
+///     \langle N(t)\rangle = N_0e^{\lambda t}.
+/// \\end{equation}
+/// Independent replicates are started from a single cell and updated by their [Cycle].
+/// Removed cells are discarded.
+/// The report compares the mean number of cells over all replicates after every step.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ExponentialGrowth {
+    /// Rate $\lambda$ at which cells divide
+    pub growth_rate: f64,
+    /// Time increment
+    pub dt: f64,
+    /// Final time
+    pub t_max: f64,
+    /// Number of independent replicates
+    pub n_replicates: usize,
+    /// Seed of the random number generator
+    pub seed: u64,
+}
+
+impl Default for ExponentialGrowth {
+    fn default() -> Self {
+        Self {
+            growth_rate: 0.5,
+            dt: 1e-2,
+            t_max: 4.0,
+            n_replicates: 1000,
+            seed: 0,
+        }
+    }
+}
+
+impl ExponentialGrowth {
+    /// Analytical mean number of cells at time `t` when starting from a single cell
+    pub fn analytic_population(&self, t: f64) -> f64 {
+        (self.growth_rate * t).exp()
+    }
+
+    /// Grows replicates of the given cell and compares the mean population size to the
+    /// analytical solution.
+    pub fn validate<C>(&self, cell: C) -> Result<ValidationReport, ValidationError>
+    where
+        C: Cycle<C, f64> + Clone,
+    {
+        if self.n_replicates == 0 {
+            return Err(ValidationError::ParameterError(
+                "at least one replicate is required".into(),
+            ));
+        }
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(self.seed);
+        let n_steps = (self.t_max / self.dt).round() as usize;
+        let mut totals = vec![0usize; n_steps];
+        for _ in 0..self.n_replicates {
+            // Cells together with a flag indicating if they are dying
+            let mut cells = vec![(cell.clone(), false)];
+            for total in totals.iter_mut() {
+                let mut new_cells = Vec::new();
+                let mut remaining = Vec::with_capacity(cells.len());
+                for (mut c, dying) in cells.drain(..) {
+                    if dying {
+                        if !C::update_conditional_phased_death(&mut rng, &self.dt, &mut c)? {
+                            remaining.push((c, true));
+                        }
+                        continue;
+                    }
+                    match C::update_cycle(&mut rng, &self.dt, &mut c) {
+                        Some(CycleEvent::Division) => {
+                            let daughter = C::divide(&mut rng, &mut c)?;
+                            new_cells.push((daughter, false));
+                            remaining.push((c, false));
+                        }
+                        Some(CycleEvent::Remove) => (),
+                        Some(CycleEvent::PhasedDeath) => remaining.push((c, true)),
+                        None => remaining.push((c, false)),
+                    }
+                }
+                remaining.extend(new_cells);
+                cells = remaining;
+                *total += cells.len();
+            }
+        }
+        let measured = totals
+            .into_iter()
+            .map(|total| total as f64 / self.n_replicates as f64)
+            .collect();
+        let expected = (1..=n_steps)
+            .map(|n| self.analytic_population(n as f64 * self.dt))
+            .collect();
+        Ok(ValidationReport::from_values(measured, expected))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Brownian2D, NewtonDamped2D};
+    use rand::Rng;
+
+    #[derive(Clone)]
+    struct Spring {
+        spring_constant: f64,
+        rest_length: f64,
+    }
+
+    impl Interaction<SVector<f64, 2>, SVector<f64, 2>, SVector<f64, 2>> for Spring {
+        fn get_interaction_information(&self) {}
+
+        fn calculate_force_between(
+            &self,
+            own_pos: &SVector<f64, 2>,
+            _own_vel: &SVector<f64, 2>,
+            ext_pos: &SVector<f64, 2>,
+            _ext_vel: &SVector<f64, 2>,
+            _ext_info: &(),
+        ) -> Result<(SVector<f64, 2>, SVector<f64, 2>), CalcError> {
+            let z = own_pos - ext_pos;
+            let dist = z.norm();
+            let force = -self.spring_constant * (dist - self.rest_length) * z / dist;
+            Ok((force, -force))
+        }
+    }
+
+    #[test]
+    fn harmonic_oscillation() {
+        let case = HarmonicOscillation::default();
+        let cell = NewtonDamped2D::new([0.0; 2], [0.0; 2], case.damping, case.mass);
+        let spring = Spring {
+            spring_constant: case.spring_constant,
+            rest_length: case.rest_length,
+        };
+        let report = case.validate([cell.clone(), cell], &spring).unwrap();
+        assert!(report.is_within(1e-4));
+
+        // A wrong spring constant has to be detected
+        let spring = Spring {
+            spring_constant: 1.5 * case.spring_constant,
+            ..spring
+        };
+        let cell = NewtonDamped2D::new([0.0; 2], [0.0; 2], case.damping, case.mass);
+        let report = case.validate([cell.clone(), cell], &spring).unwrap();
+        assert!(!report.is_within(1e-2));
+    }
+
+    #[test]
+    fn overdamped_oscillation() {
+        let case = HarmonicOscillation {
+            damping: 5.0,
+            ..Default::default()
+        };
+        assert!((case.analytic_distance(0.0) - case.rest_length - case.amplitude).abs() < 1e-12);
+        assert!(case.analytic_distance(case.t_max) > case.rest_length);
+        let cell = NewtonDamped2D::new([0.0; 2], [0.0; 2], case.damping, case.mass);
+        let spring = Spring {
+            spring_constant: case.spring_constant,
+            rest_length: case.rest_length,
+        };
+        let report = case.validate([cell.clone(), cell], &spring).unwrap();
+        assert!(report.is_within(1e-4));
+    }
+
+    #[test]
+    fn brownian_msd() {
+        let case = BrownianMsd::default();
+        let particle = Brownian2D::new([0.0; 2], case.diffusion_constant, 1.0);
+        let report = case.validate(particle).unwrap();
+        assert!(report.is_within(0.15));
+    }
+
+    /// Explicit finite differences on a one-dimensional grid
+    struct Grid1D {
+        min: f64,
+        dx: f64,
+        diffusion_constant: f64,
+        values: Vec<f64>,
+    }
+
+    impl SubDomainReactions<SVector<f64, 1>, f64, f64> for Grid1D {
+        type NeighborValue = ();
+        type BorderInfo = ();
+
+        fn treat_increments<I, J>(&mut self, _: I, _: J) -> Result<(), CalcError>
+        where
+            I: IntoIterator<Item = ()>,
+            J: IntoIterator<Item = (SVector<f64, 1>, f64)>,
+        {
+            Ok(())
+        }
+
+        fn update_fluid_dynamics(&mut self, dt: f64) -> Result<(), CalcError> {
+            let n = self.values.len();
+            let alpha = self.diffusion_constant * dt / self.dx.powi(2);
+            let old = self.values.clone();
+            for i in 1..n - 1 {
+                self.values[i] = old[i] + alpha * (old[i - 1] - 2.0 * old[i] + old[i + 1]);
+            }
+            Ok(())
+        }
+
+        fn get_extracellular_at_pos(&self, pos: &SVector<f64, 1>) -> Result<f64, CalcError> {
+            let index = ((pos[0] - self.min) / self.dx).round() as usize;
+            self.values
+                .get(index)
+                .copied()
+                .ok_or(CalcError(format!("position {pos} outside of grid")))
+        }
+
+        fn get_neighbor_value(&self, _: ()) {}
+
+        fn get_border_info(&self) {}
+    }
+
+    #[test]
+    fn point_source_diffusion() {
+        let case = PointSourceDiffusion {
+            amount: 1.0,
+            diffusion_constant: 1.0,
+            source: [0.0].into(),
+            t0: 0.1,
+            duration: 0.5,
+            dt: 1e-4,
+            sample_points: (-4..=4).map(|i| [0.25 * i as f64].into()).collect(),
+        };
+        let (min, dx) = (-10.0, 0.025);
+        let values = (0..801)
+            .map(|i| case.initial_condition(&[min + i as f64 * dx].into()))
+            .collect();
+        let mut grid = Grid1D {
+            min,
+            dx,
+            diffusion_constant: case.diffusion_constant,
+            values,
+        };
+        let report = case.validate(&mut grid).unwrap();
+        assert!(report.is_within(1e-2));
+    }
+
+    #[derive(Clone)]
+    struct ConstantRateDivision {
+        rate: f64,
+    }
+
+    impl Cycle for ConstantRateDivision {
+        fn update_cycle(
+            rng: &mut rand_chacha::ChaCha8Rng,
+            dt: &f64,
+            cell: &mut Self,
+        ) -> Option<CycleEvent> {
+            if rng.gen_bool((cell.rate * dt).min(1.0)) {
+                Some(CycleEvent::Division)
+            } else {
+                None
+            }
+        }
+
+        fn divide(_: &mut rand_chacha::ChaCha8Rng, cell: &mut Self) -> Result<Self, DivisionError> {
+            Ok(cell.clone())
+        }
+    }
+
+    #[test]
+    fn exponential_growth() {
+        let case = ExponentialGrowth::default();
+        let cell = ConstantRateDivision {
+            rate: case.growth_rate,
+        };
+        let report = case.validate(cell).unwrap();
+        assert!(report.is_within(0.1));
+    }
+}
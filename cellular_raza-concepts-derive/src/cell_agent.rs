@@ -71,12 +71,15 @@ impl syn::parse::Parse for AgentParser {
 
 #[derive(Clone)]
 enum CellAspect {
+    Age,
     Mechanics,
     MechanicsRaw,
     Position,
     Velocity,
     Cycle,
     Interaction,
+    InteractionForce,
+    NeighbourReactive,
     Intracellular,
     Reactions,
     ReactionsRaw,
@@ -85,6 +88,7 @@ enum CellAspect {
     ReactionsContact,
     ExtracellularGradient,
     Volume,
+    CustomData,
 }
 
 impl CellAspect {
@@ -94,12 +98,15 @@ impl CellAspect {
         if let Some(p) = path {
             let path_str = p.to_string();
             match path_str.as_str() {
+                "Age" => Some(CellAspect::Age),
                 "Mechanics" => Some(CellAspect::Mechanics),
                 "MechanicsRaw" => Some(CellAspect::MechanicsRaw),
                 "Position" => Some(CellAspect::Position),
                 "Velocity" => Some(CellAspect::Velocity),
                 "Cycle" => Some(CellAspect::Cycle),
                 "Interaction" => Some(CellAspect::Interaction),
+                "InteractionForce" => Some(CellAspect::InteractionForce),
+                "NeighbourReactive" => Some(CellAspect::NeighbourReactive),
                 "Intracellular" => Some(CellAspect::Intracellular),
                 "Reactions" => Some(CellAspect::Reactions),
                 "ReactionsRaw" => Some(CellAspect::ReactionsRaw),
@@ -108,6 +115,7 @@ impl CellAspect {
                 "ReactionsContact" => Some(CellAspect::ReactionsContact),
                 "ExtracellularGradient" => Some(CellAspect::ExtracellularGradient),
                 "Volume" => Some(CellAspect::Volume),
+                "CustomData" => Some(CellAspect::CustomData),
                 _ => None,
             }
         } else {
@@ -180,32 +188,40 @@ pub struct FieldInfo {
 pub struct AgentImplementer {
     name: syn::Ident,
     generics: syn::Generics,
+    age: Option<FieldInfo>,
     cycle: Option<FieldInfo>,
     mechanics_raw: Option<FieldInfo>,
     position: Option<FieldInfo>,
     velocity: Option<FieldInfo>,
     interaction: Option<FieldInfo>,
+    interaction_force: Option<FieldInfo>,
+    neighbour_reactive: Option<FieldInfo>,
     intracellular: Option<FieldInfo>,
     reactions_raw: Option<FieldInfo>,
     reactions_extra_raw: Option<FieldInfo>,
     reactions_contact: Option<FieldInfo>,
     extracellular_gradient: Option<FieldInfo>,
     volume: Option<FieldInfo>,
+    custom_data: Vec<FieldInfo>,
 }
 
 impl From<AgentParser> for AgentImplementer {
     fn from(value: AgentParser) -> Self {
+        let mut age = None;
         let mut cycle = None;
         let mut mechanics_raw = None;
         let mut position = None;
         let mut velocity = None;
         let mut interaction = None;
+        let mut interaction_force = None;
+        let mut neighbour_reactive = None;
         let mut intracellular = None;
         let mut reactions_raw = None;
         let mut reactions_extra_raw = None;
         let mut reactions_contact = None;
         let mut extracellular_gradient = None;
         let mut volume = None;
+        let mut custom_data = Vec::new();
 
         value.aspects.into_iter().for_each(|aspect_field| {
             aspect_field
@@ -221,6 +237,9 @@ impl From<AgentParser> for AgentImplementer {
                         },
                     };
                     match aspect {
+                        CellAspect::Age => {
+                            age = Some(field_info);
+                        }
                         CellAspect::Cycle => {
                             cycle = Some(field_info);
                         }
@@ -235,6 +254,12 @@ impl From<AgentParser> for AgentImplementer {
                         CellAspect::Interaction => {
                             interaction = Some(field_info);
                         }
+                        CellAspect::InteractionForce => {
+                            interaction_force = Some(field_info);
+                        }
+                        CellAspect::NeighbourReactive => {
+                            neighbour_reactive = Some(field_info);
+                        }
                         CellAspect::Intracellular => {
                             intracellular = Some(field_info);
                         }
@@ -263,6 +288,9 @@ impl From<AgentParser> for AgentImplementer {
                         CellAspect::Volume => {
                             volume = Some(field_info);
                         }
+                        CellAspect::CustomData => {
+                            custom_data.push(field_info);
+                        }
                     }
                 })
         });
@@ -270,17 +298,21 @@ impl From<AgentParser> for AgentImplementer {
         Self {
             name: value.name,
             generics: value.generics,
+            age,
             cycle,
             mechanics_raw,
             position,
             velocity,
             interaction,
+            interaction_force,
+            neighbour_reactive,
             intracellular,
             reactions_raw,
             reactions_extra_raw,
             reactions_contact,
             extracellular_gradient,
             volume,
+            custom_data,
         }
     }
 }
@@ -314,14 +346,21 @@ impl AgentImplementer {
         if let Some(field_info) = &self.cycle {
             let field_type = &field_info.field_type;
             new_ident!(float_type, "__cr_private_Float");
+            new_ident!(parameters, "__cr_private_Par");
 
-            let tokens = quote!(#struct_name #struct_ty_generics, #float_type);
+            let tokens = quote!(
+                #struct_name #struct_ty_generics,
+                #float_type,
+                rand_chacha::ChaCha8Rng,
+                #parameters
+            );
 
             let where_clause =
                 append_where_clause!(struct_where_clause @clause field_type, Cycle, tokens);
 
             let mut generics = self.generics.clone();
             push_ident!(generics, float_type);
+            push_ident!(generics, parameters);
             let impl_generics = generics.split_for_impl().0;
 
             let new_stream = quote!(
@@ -337,6 +376,21 @@ impl AgentImplementer {
                         <#field_type as Cycle<#tokens>>::update_cycle(rng, dt, cell)
                     }
 
+                    #[inline]
+                    fn update_cycle_with_parameters(
+                        rng: &mut rand_chacha::ChaCha8Rng,
+                        dt: &#float_type,
+                        cell: &mut Self,
+                        parameters: &#parameters,
+                    ) -> Option<CycleEvent> {
+                        <#field_type as Cycle<#tokens>>::update_cycle_with_parameters(
+                            rng,
+                            dt,
+                            cell,
+                            parameters,
+                        )
+                    }
+
                     #[inline]
                     fn divide(
                         rng: &mut rand_chacha::ChaCha8Rng,
@@ -372,8 +426,16 @@ impl AgentImplementer {
             new_ident!(velocity, "__cr_private_Vel");
             new_ident!(force, "__cr_private_For");
             new_ident!(float_type, "__cr_private_Float");
-
-            let tokens = quote!(#position, #velocity, #force, #float_type);
+            new_ident!(parameters, "__cr_private_Par");
+
+            let tokens = quote!(
+                #position,
+                #velocity,
+                #force,
+                #float_type,
+                rand_chacha::ChaCha8Rng,
+                #parameters
+            );
             let field_type = &field_info.field_type;
             let field_name = &field_info.field_name;
 
@@ -385,6 +447,7 @@ impl AgentImplementer {
             push_ident!(generics, velocity);
             push_ident!(generics, force);
             push_ident!(generics, float_type);
+            push_ident!(generics, parameters);
             let impl_generics = generics.split_for_impl().0;
 
             let res = quote! {
@@ -412,6 +475,20 @@ impl AgentImplementer {
                             dt
                         )
                     }
+                    #[inline]
+                    fn get_random_contribution_with_parameters(
+                        &self,
+                        rng: &mut rand_chacha::ChaCha8Rng,
+                        dt: #float_type,
+                        parameters: &#parameters,
+                    ) -> Result<(#position, #velocity), RngError> {
+                        <#field_type as Mechanics<#tokens>>::get_random_contribution_with_parameters(
+                            &self.#field_name,
+                            rng,
+                            dt,
+                            parameters,
+                        )
+                    }
                 }
             };
             return TokenStream::from(res);
@@ -504,7 +581,8 @@ impl AgentImplementer {
             new_ident!(velocity, "__cr_private_Vel");
             new_ident!(force, "__cr_private_For");
             new_ident!(information, "__cr_private_Inf");
-            let tokens = quote!(#position, #velocity, #force, #information);
+            new_ident!(parameters, "__cr_private_Par");
+            let tokens = quote!(#position, #velocity, #force, #information, #parameters);
 
             let where_clause =
                 append_where_clause!(struct_where_clause @clause field_type, Interaction, tokens);
@@ -514,6 +592,7 @@ impl AgentImplementer {
             push_ident!(generics, velocity);
             push_ident!(generics, force);
             push_ident!(generics, information);
+            push_ident!(generics, parameters);
             let impl_generics = generics.split_for_impl().0;
 
             let res = quote! {
@@ -547,6 +626,28 @@ impl AgentImplementer {
                         )
                     }
 
+                    #[inline]
+                    fn calculate_force_between_with_parameters(
+                        &self,
+                        own_pos: &#position,
+                        own_vel: &#velocity,
+                        ext_pos: &#position,
+                        ext_vel: &#velocity,
+                        ext_info: &#information,
+                        parameters: &#parameters,
+                    ) -> Result<(#force, #force), CalcError> {
+                        <#field_type as Interaction<#tokens>>
+                            ::calculate_force_between_with_parameters(
+                                &self.#field_name,
+                                own_pos,
+                                own_vel,
+                                ext_pos,
+                                ext_vel,
+                                ext_info,
+                                parameters,
+                            )
+                    }
+
                     #[inline]
                     fn is_neighbor(
                         &self,
@@ -572,6 +673,72 @@ impl AgentImplementer {
                             neighbors
                         )
                     }
+
+                    #[inline]
+                    fn reacts_to_neighbors(&self) -> bool {
+                        <#field_type as Interaction<#tokens>>::reacts_to_neighbors(
+                            &self.#field_name
+                        )
+                    }
+
+                    #[inline]
+                    fn normal_force(
+                        &self,
+                        own_pos: &#position,
+                        ext_pos: &#position,
+                        force: &#force,
+                    ) -> Result<f64, CalcError> {
+                        <#field_type as Interaction<#tokens>>::normal_force(
+                            &self.#field_name,
+                            own_pos,
+                            ext_pos,
+                            force
+                        )
+                    }
+
+                    #[inline]
+                    fn react_to_pressure(
+                        &mut self,
+                        pressure: f64
+                    ) -> Result<(), CalcError> {
+                        <#field_type as Interaction<#tokens>>::react_to_pressure(
+                            &mut self.#field_name,
+                            pressure
+                        )
+                    }
+
+                    #[inline]
+                    fn potential_energy_between(
+                        &self,
+                        own_pos: &#position,
+                        ext_pos: &#position,
+                        ext_inf: &#information,
+                    ) -> Result<Option<f64>, CalcError> {
+                        <#field_type as Interaction<#tokens>>::potential_energy_between(
+                            &self.#field_name,
+                            own_pos,
+                            ext_pos,
+                            ext_inf
+                        )
+                    }
+
+                    #[inline]
+                    fn potential_energy_between_with_parameters(
+                        &self,
+                        own_pos: &#position,
+                        ext_pos: &#position,
+                        ext_inf: &#information,
+                        parameters: &#parameters,
+                    ) -> Result<Option<f64>, CalcError> {
+                        <#field_type as Interaction<#tokens>>
+                            ::potential_energy_between_with_parameters(
+                                &self.#field_name,
+                                own_pos,
+                                ext_pos,
+                                ext_inf,
+                                parameters,
+                            )
+                    }
                 }
             };
             return TokenStream::from(res);
@@ -579,6 +746,269 @@ impl AgentImplementer {
         TokenStream::new()
     }
 
+    // Only forwards the force-related methods of the `#[InteractionForce]` field and combines
+    // them with the `#[NeighbourReactive]` field if present. The field only needs to implement
+    // `Interaction` which includes all types implementing `InteractionForce`.
+    pub fn implement_interaction_force(&self) -> TokenStream {
+        let struct_name = &self.name;
+        let (_, struct_ty_generics, struct_where_clause) = &self.generics.split_for_impl();
+
+        if let Some(field_info) = &self.interaction_force {
+            let field_name = &field_info.field_name;
+            let field_type = &field_info.field_type;
+            new_ident!(position, "__cr_private_Pos");
+            new_ident!(velocity, "__cr_private_Vel");
+            new_ident!(force, "__cr_private_For");
+            new_ident!(information, "__cr_private_Inf");
+            new_ident!(parameters, "__cr_private_Par");
+            let tokens = quote!(#position, #velocity, #force, #information, #parameters);
+
+            let mut generics = self.generics.clone();
+            push_ident!(generics, position);
+            push_ident!(generics, velocity);
+            push_ident!(generics, force);
+            push_ident!(generics, information);
+            push_ident!(generics, parameters);
+            let impl_generics = generics.split_for_impl().0;
+
+            let force_methods = quote! {
+                #[inline]
+                fn get_interaction_information(&self) -> #information {
+                    <#field_type as Interaction<#tokens>>::get_interaction_information(
+                        &self.#field_name
+                    )
+                }
+
+                #[inline]
+                fn calculate_force_between(
+                    &self,
+                    own_pos: &#position,
+                    own_vel: &#velocity,
+                    ext_pos: &#position,
+                    ext_vel: &#velocity,
+                    ext_info: &#information,
+                ) -> Result<(#force, #force), CalcError> {
+                    <#field_type as Interaction<#tokens>>::calculate_force_between(
+                        &self.#field_name,
+                        own_pos,
+                        own_vel,
+                        ext_pos,
+                        ext_vel,
+                        ext_info
+                    )
+                }
+
+                #[inline]
+                fn calculate_force_between_with_parameters(
+                    &self,
+                    own_pos: &#position,
+                    own_vel: &#velocity,
+                    ext_pos: &#position,
+                    ext_vel: &#velocity,
+                    ext_info: &#information,
+                    parameters: &#parameters,
+                ) -> Result<(#force, #force), CalcError> {
+                    <#field_type as Interaction<#tokens>>
+                        ::calculate_force_between_with_parameters(
+                            &self.#field_name,
+                            own_pos,
+                            own_vel,
+                            ext_pos,
+                            ext_vel,
+                            ext_info,
+                            parameters,
+                        )
+                }
+
+                #[inline]
+                fn normal_force(
+                    &self,
+                    own_pos: &#position,
+                    ext_pos: &#position,
+                    force: &#force,
+                ) -> Result<f64, CalcError> {
+                    <#field_type as Interaction<#tokens>>::normal_force(
+                        &self.#field_name,
+                        own_pos,
+                        ext_pos,
+                        force
+                    )
+                }
+
+                #[inline]
+                fn react_to_pressure(
+                    &mut self,
+                    pressure: f64
+                ) -> Result<(), CalcError> {
+                    <#field_type as Interaction<#tokens>>::react_to_pressure(
+                        &mut self.#field_name,
+                        pressure
+                    )
+                }
+
+                #[inline]
+                fn potential_energy_between(
+                    &self,
+                    own_pos: &#position,
+                    ext_pos: &#position,
+                    ext_inf: &#information,
+                ) -> Result<Option<f64>, CalcError> {
+                    <#field_type as Interaction<#tokens>>::potential_energy_between(
+                        &self.#field_name,
+                        own_pos,
+                        ext_pos,
+                        ext_inf
+                    )
+                }
+
+                #[inline]
+                fn potential_energy_between_with_parameters(
+                    &self,
+                    own_pos: &#position,
+                    ext_pos: &#position,
+                    ext_inf: &#information,
+                    parameters: &#parameters,
+                ) -> Result<Option<f64>, CalcError> {
+                    <#field_type as Interaction<#tokens>>
+                        ::potential_energy_between_with_parameters(
+                            &self.#field_name,
+                            own_pos,
+                            ext_pos,
+                            ext_inf,
+                            parameters,
+                        )
+                }
+            };
+
+            let res = match &self.neighbour_reactive {
+                Some(reactive_info) => {
+                    let reactive_name = &reactive_info.field_name;
+                    let reactive_type = &reactive_info.field_type;
+                    let reactive_tokens = quote!(#position, #information);
+                    let where_clause = append_where_clause!(
+                        struct_where_clause
+                        @clause field_type, Interaction, tokens,
+                        @clause reactive_type, NeighbourReactive, reactive_tokens
+                    );
+                    quote! {
+                        #[automatically_derived]
+                        impl #impl_generics Interaction<#tokens>
+                            for #struct_name #struct_ty_generics #where_clause {
+                            #force_methods
+
+                            #[inline]
+                            fn is_neighbor(
+                                &self,
+                                own_pos: &#position,
+                                ext_pos: &#position,
+                                ext_inf: &#information
+                            ) -> Result<bool, CalcError> {
+                                <#reactive_type as NeighbourReactive<#reactive_tokens>>
+                                    ::is_neighbor(
+                                        &self.#reactive_name,
+                                        own_pos,
+                                        ext_pos,
+                                        ext_inf
+                                    )
+                            }
+
+                            #[inline]
+                            fn react_to_neighbors(
+                                &mut self,
+                                neighbors: usize
+                            ) -> Result<(), CalcError> {
+                                <#reactive_type as NeighbourReactive<#reactive_tokens>>
+                                    ::react_to_neighbors(
+                                        &mut self.#reactive_name,
+                                        neighbors
+                                    )
+                            }
+                        }
+                    }
+                }
+                None => {
+                    let where_clause = append_where_clause!(
+                        struct_where_clause @clause field_type, Interaction, tokens
+                    );
+                    quote! {
+                        #[automatically_derived]
+                        impl #impl_generics Interaction<#tokens>
+                            for #struct_name #struct_ty_generics #where_clause {
+                            #force_methods
+
+                            #[inline]
+                            fn reacts_to_neighbors(&self) -> bool {
+                                false
+                            }
+                        }
+                    }
+                }
+            };
+            return res;
+        }
+        TokenStream::new()
+    }
+
+    pub fn implement_neighbour_reactive(&self) -> TokenStream {
+        let struct_name = &self.name;
+        let (_, struct_ty_generics, struct_where_clause) = &self.generics.split_for_impl();
+
+        // Combined with forces in implement_interaction_force
+        if self.interaction_force.is_some() {
+            return TokenStream::new();
+        }
+        if let Some(field_info) = &self.neighbour_reactive {
+            let field_name = &field_info.field_name;
+            let field_type = &field_info.field_type;
+            new_ident!(position, "__cr_private_Pos");
+            new_ident!(information, "__cr_private_Inf");
+            let tokens = quote!(#position, #information);
+
+            let where_clause = append_where_clause!(
+                struct_where_clause @clause field_type, NeighbourReactive, tokens
+            );
+
+            let mut generics = self.generics.clone();
+            push_ident!(generics, position);
+            push_ident!(generics, information);
+            let impl_generics = generics.split_for_impl().0;
+
+            let res = quote! {
+                #[automatically_derived]
+                impl #impl_generics NeighbourReactive<#tokens>
+                    for #struct_name #struct_ty_generics #where_clause {
+                    #[inline]
+                    fn is_neighbor(
+                        &self,
+                        own_pos: &#position,
+                        ext_pos: &#position,
+                        ext_inf: &#information
+                    ) -> Result<bool, CalcError> {
+                        <#field_type as NeighbourReactive<#tokens>>::is_neighbor(
+                            &self.#field_name,
+                            own_pos,
+                            ext_pos,
+                            ext_inf
+                        )
+                    }
+
+                    #[inline]
+                    fn react_to_neighbors(
+                        &mut self,
+                        neighbors: usize
+                    ) -> Result<(), CalcError> {
+                        <#field_type as NeighbourReactive<#tokens>>::react_to_neighbors(
+                            &mut self.#field_name,
+                            neighbors
+                        )
+                    }
+                }
+            };
+            return res;
+        }
+        TokenStream::new()
+    }
+
     pub fn implement_intracellular(&self) -> TokenStream {
         let struct_name = &self.name;
         let (_, struct_ty_generics, struct_where_clause) = &self.generics.split_for_impl();
@@ -634,21 +1064,24 @@ impl AgentImplementer {
             let field_name = &field_info.field_name;
             let field_type = &field_info.field_type;
             new_ident!(rintra, "__cr_private_Ri");
+            new_ident!(parameters, "__cr_private_Par");
             let tokens = quote!(#rintra);
+            let reactions_tokens = quote!(#rintra, #parameters);
             let full_struct_ty = quote::quote!(#struct_name #struct_ty_generics);
 
             let where_clause = append_where_clause!(struct_where_clause
-                @clause field_type, Reactions, tokens,
+                @clause field_type, Reactions, reactions_tokens,
                 @clause full_struct_ty, Intracellular, tokens
             );
 
             let mut generics = self.generics.clone();
             push_ident!(generics, rintra);
+            push_ident!(generics, parameters);
             let impl_generics = generics.split_for_impl().0;
 
             let res = quote! {
                 #[automatically_derived]
-                impl #impl_generics Reactions<#rintra>
+                impl #impl_generics Reactions<#rintra, #parameters>
                 for #struct_name #struct_ty_generics #where_clause {
                     #[inline]
                     fn calculate_intracellular_increment(
@@ -657,11 +1090,28 @@ impl AgentImplementer {
                     ) -> Result<#rintra, CalcError> {
                         <#field_type as Reactions<
                             #rintra,
+                            #parameters,
                         >>::calculate_intracellular_increment(
                             &self.#field_name,
                             intracellular
                         )
                     }
+
+                    #[inline]
+                    fn calculate_intracellular_increment_with_parameters(
+                        &self,
+                        intracellular: &#rintra,
+                        parameters: &#parameters,
+                    ) -> Result<#rintra, CalcError> {
+                        <#field_type as Reactions<
+                            #rintra,
+                            #parameters,
+                        >>::calculate_intracellular_increment_with_parameters(
+                            &self.#field_name,
+                            intracellular,
+                            parameters,
+                        )
+                    }
                 }
             };
             return TokenStream::from(res);
@@ -849,6 +1299,97 @@ impl AgentImplementer {
     }
 }
 
+impl AgentImplementer {
+    pub fn implement_age(&self) -> TokenStream {
+        let struct_name = &self.name;
+        let (_, struct_ty_generics, struct_where_clause) = &self.generics.split_for_impl();
+
+        if let Some(field_info) = &self.age {
+            let field_type = &field_info.field_type;
+            let field_name = &field_info.field_name;
+            new_ident!(float_type, "__cr_private_Float");
+
+            let where_clause =
+                append_where_clause!(struct_where_clause @clause field_type, Age, float_type);
+
+            let mut generics = self.generics.clone();
+            push_ident!(generics, float_type);
+            let impl_generics = generics.split_for_impl().0;
+
+            let res = quote! {
+                #[automatically_derived]
+                impl #impl_generics Age<#float_type> for #struct_name #struct_ty_generics
+                    #where_clause
+                {
+                    #[inline]
+                    fn get_age(&self) -> #float_type {
+                        <#field_type as Age<#float_type>>::get_age(&self.#field_name)
+                    }
+
+                    #[inline]
+                    fn set_age(&mut self, age: #float_type) {
+                        <#field_type as Age<#float_type>>::set_age(&mut self.#field_name, age)
+                    }
+
+                    #[inline]
+                    fn update_senescence(&mut self) -> Result<(), CalcError> {
+                        <#field_type as Age<#float_type>>::update_senescence(
+                            &mut self.#field_name
+                        )
+                    }
+
+                    #[inline]
+                    fn is_senescent(&self) -> bool {
+                        <#field_type as Age<#float_type>>::is_senescent(&self.#field_name)
+                    }
+                }
+            };
+            return res;
+        }
+        TokenStream::new()
+    }
+
+    pub fn implement_custom_data(&self) -> TokenStream {
+        let struct_name = &self.name;
+        let (impl_generics, struct_ty_generics, struct_where_clause) =
+            &self.generics.split_for_impl();
+
+        let mut res = TokenStream::new();
+        for field_info in self.custom_data.iter() {
+            let field_type = &field_info.field_type;
+            let field_name = &field_info.field_name;
+            let where_clause = match struct_where_clause {
+                Some(clause) => {
+                    let punct = if clause.predicates.trailing_punct() {
+                        quote!()
+                    } else {
+                        quote!(,)
+                    };
+                    quote!(#clause #punct #field_type: serde::Serialize)
+                }
+                None => quote!(where #field_type: serde::Serialize),
+            };
+            res.extend(quote! {
+                #[automatically_derived]
+                impl #impl_generics CustomData<#field_type> for #struct_name #struct_ty_generics
+                    #where_clause
+                {
+                    #[inline]
+                    fn custom_data(&self) -> &#field_type {
+                        &self.#field_name
+                    }
+
+                    #[inline]
+                    fn custom_data_mut(&mut self) -> &mut #field_type {
+                        &mut self.#field_name
+                    }
+                }
+            });
+        }
+        res
+    }
+}
+
 pub fn derive_cell_agent(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     // Parse the input tokens into a syntax tree
     let agent_parsed = syn::parse_macro_input!(input as AgentParser);
@@ -864,8 +1405,12 @@ pub fn derive_cell_agent(input: proc_macro::TokenStream) -> proc_macro::TokenStr
     res.extend(agent.implement_reactions_contact());
     res.extend(agent.implement_reactions_extra_raw());
     res.extend(agent.implement_interaction());
+    res.extend(agent.implement_interaction_force());
+    res.extend(agent.implement_neighbour_reactive());
     res.extend(agent.implement_extracellular_gradient());
     res.extend(agent.implement_volume());
+    res.extend(agent.implement_custom_data());
+    res.extend(agent.implement_age());
 
     wrap(res).into()
 }
@@ -18,8 +18,10 @@ pub struct DomainImplementer {
     partial_derive: bool,
 }
 
-impl From<DomainParser> for DomainImplementer {
-    fn from(value: DomainParser) -> Self {
+impl TryFrom<DomainParser> for DomainImplementer {
+    type Error = syn::Error;
+
+    fn try_from(value: DomainParser) -> syn::Result<Self> {
         let mut base = None;
         let mut sort_cells = None;
         let mut rng_seed = None;
@@ -34,36 +36,37 @@ impl From<DomainParser> for DomainImplementer {
                 _ => (),
             });
 
-        value
-            .elements
-            .into_iter()
-            .enumerate()
-            .for_each(|(number, domain_property_field)| {
-                domain_property_field
-                    .elements
-                    .into_iter()
-                    .for_each(|domain_property| {
-                        let field_info = FieldInfo {
-                            field_type: domain_property_field.field.ty.clone(),
-                            field_name: match domain_property_field.field.ident.clone() {
-                                Some(ident) => crate::cell_agent::FieldIdent::Ident(ident),
-                                None => crate::cell_agent::FieldIdent::Int(
-                                    proc_macro2::Literal::usize_unsuffixed(number),
-                                ),
-                            },
-                        };
-                        use DomainProperty::*;
-                        match domain_property {
-                            Base => base = Some(field_info),
-                            DomainRngSeed => rng_seed = Some(field_info),
-                            DomainCreateSubDomains => create_subdomains = Some(field_info),
-                            SortCells => sort_cells = Some(field_info),
-                            _ => (),
-                        }
-                    })
-            });
+        for (number, domain_property_field) in value.elements.into_iter().enumerate() {
+            for (domain_property, ident) in domain_property_field.elements.into_iter() {
+                let field_info = FieldInfo {
+                    field_type: domain_property_field.field.ty.clone(),
+                    field_name: match domain_property_field.field.ident.clone() {
+                        Some(ident) => crate::cell_agent::FieldIdent::Ident(ident),
+                        None => crate::cell_agent::FieldIdent::Int(
+                            proc_macro2::Literal::usize_unsuffixed(number),
+                        ),
+                    },
+                };
+                use DomainProperty::*;
+                let entry = match domain_property {
+                    Base => &mut base,
+                    DomainRngSeed => &mut rng_seed,
+                    DomainCreateSubDomains => &mut create_subdomains,
+                    SortCells => &mut sort_cells,
+                    // Older versions silently ignored this attribute on fields
+                    DomainPartialDerive => continue,
+                };
+                if entry.is_some() {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        format!("Attribute #[{ident}] can only be specified once"),
+                    ));
+                }
+                *entry = Some(field_info);
+            }
+        }
 
-        DomainImplementer {
+        Ok(DomainImplementer {
             name: value.name,
             generics: value.generics,
             base,
@@ -71,7 +74,7 @@ impl From<DomainParser> for DomainImplementer {
             rng_seed,
             create_subdomains,
             partial_derive,
-        }
+        })
     }
 }
 
@@ -425,7 +428,10 @@ impl DomainImplementer {
 
 pub fn derive_domain(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let domain_parser = syn::parse_macro_input!(input as DomainParser);
-    let domain_implementer = DomainImplementer::from(domain_parser);
+    let domain_implementer = match DomainImplementer::try_from(domain_parser) {
+        Ok(implementer) => implementer,
+        Err(error) => return error.to_compile_error().into(),
+    };
 
     let mut res = proc_macro2::TokenStream::new();
     res.extend(domain_implementer.implement_base());
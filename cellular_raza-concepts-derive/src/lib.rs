@@ -25,12 +25,15 @@ mod domain;
 #[proc_macro_derive(
     CellAgent,
     attributes(
+        Age,
         Cycle,
         Mechanics,
         MechanicsRaw,
         Position,
         Velocity,
         Interaction,
+        InteractionForce,
+        NeighbourReactive,
         Reactions,
         ReactionsContact,
         ReactionsRaw,
@@ -38,6 +41,7 @@ mod domain;
         ReactionsExtraRaw,
         Intracellular,
         ExtracellularGradient,
+        CustomData,
     )
 )]
 pub fn derive_cell_agent(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
@@ -12,7 +12,7 @@ macro_rules! implement_parsing_of_derive_attributes(
         }
 
         pub struct $field_struct {
-            elements: Vec<$enum_name>,
+            elements: Vec<($enum_name, syn::Ident)>,
             field: syn::Field,
         }
 
@@ -59,7 +59,10 @@ macro_rules! implement_parsing_of_derive_attributes(
                 let elements = field
                     .attrs
                     .iter()
-                    .filter_map($enum_name::from_attribute)
+                    .filter_map(|attr| {
+                        let ident = attr.meta.path().get_ident()?.clone();
+                        $enum_name::from_attribute(attr).map(|element| (element, ident))
+                    })
                     .collect::<Vec<_>>();
                 Self { elements, field }
             }
@@ -122,40 +125,52 @@ pub struct SubDomainImplementer {
     reactions: Option<FieldInfo>,
 }
 
-impl From<SubDomainParser> for SubDomainImplementer {
-    fn from(value: SubDomainParser) -> Self {
+impl TryFrom<SubDomainParser> for SubDomainImplementer {
+    type Error = syn::Error;
+
+    fn try_from(value: SubDomainParser) -> syn::Result<Self> {
         let mut base = None;
         let mut sort_cells = None;
         let mut mechanics = None;
         let mut force = None;
         let mut reactions = None;
 
-        value
-            .elements
-            .into_iter()
-            .enumerate()
-            .for_each(|(number, aspect_field)| {
-                aspect_field.elements.into_iter().for_each(|aspect| {
-                    let field_info = FieldInfo {
-                        field_type: aspect_field.field.ty.clone(),
-                        field_name: match aspect_field.field.ident.clone() {
-                            Some(ident) => crate::cell_agent::FieldIdent::Ident(ident),
-                            None => crate::cell_agent::FieldIdent::Int(
-                                proc_macro2::Literal::usize_unsuffixed(number),
-                            ),
-                        },
-                    };
-                    match aspect {
-                        SubDomainAspect::Base => base = Some(field_info),
-                        SubDomainAspect::SortCells => sort_cells = Some(field_info),
-                        SubDomainAspect::Mechanics => mechanics = Some(field_info),
-                        SubDomainAspect::Force => force = Some(field_info),
-                        SubDomainAspect::Reactions => reactions = Some(field_info),
-                    }
-                })
-            });
+        for (number, aspect_field) in value.elements.into_iter().enumerate() {
+            for (aspect, ident) in aspect_field.elements.into_iter() {
+                let field_info = FieldInfo {
+                    field_type: aspect_field.field.ty.clone(),
+                    field_name: match aspect_field.field.ident.clone() {
+                        Some(ident) => crate::cell_agent::FieldIdent::Ident(ident),
+                        None => crate::cell_agent::FieldIdent::Int(
+                            proc_macro2::Literal::usize_unsuffixed(number),
+                        ),
+                    },
+                };
+                let entry = match aspect {
+                    SubDomainAspect::Base => &mut base,
+                    SubDomainAspect::SortCells => &mut sort_cells,
+                    SubDomainAspect::Mechanics => &mut mechanics,
+                    SubDomainAspect::Force => &mut force,
+                    SubDomainAspect::Reactions => &mut reactions,
+                };
+                if entry.is_some() {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        format!("Attribute #[{ident}] can only be specified once"),
+                    ));
+                }
+                *entry = Some(field_info);
+            }
+        }
+
+        if base.is_none() {
+            return Err(syn::Error::new(
+                value.name.span(),
+                "Deriving SubDomain requires a field which is annotated with #[Base]",
+            ));
+        }
 
-        SubDomainImplementer {
+        Ok(SubDomainImplementer {
             name: value.name,
             generics: value.generics,
             base,
@@ -163,7 +178,7 @@ impl From<SubDomainParser> for SubDomainImplementer {
             mechanics,
             force,
             reactions,
-        }
+        })
     }
 }
 
@@ -274,6 +289,39 @@ impl SubDomainImplementer {
                             vel,
                         )
                     }
+
+                    #[inline]
+                    fn domain_metric(&self) -> Option<&dyn DomainMetric<#position>> {
+                        <#field_type as SubDomainMechanics<#position, #velocity>>::domain_metric(
+                            &self.#field_name,
+                        )
+                    }
+
+                    #[inline]
+                    fn clamp_to_domain(
+                        &self,
+                        pos: &mut #position,
+                        vel: &mut #velocity,
+                    ) -> Result<(), BoundaryError> {
+                        <#field_type as SubDomainMechanics<#position, #velocity>>::clamp_to_domain(
+                            &self.#field_name,
+                            pos,
+                            vel,
+                        )
+                    }
+
+                    #[inline]
+                    fn wrap_into_domain(
+                        &self,
+                        pos: &mut #position,
+                        vel: &mut #velocity,
+                    ) -> Result<(), BoundaryError> {
+                        <#field_type as SubDomainMechanics<#position, #velocity>>::wrap_into_domain(
+                            &self.#field_name,
+                            pos,
+                            vel,
+                        )
+                    }
                 }
             )
         } else {
@@ -344,6 +392,16 @@ impl SubDomainImplementer {
                             get_extracellular_at_pos(&self.#field_name, pos)
                     }
 
+                    #[inline]
+                    fn set_extracellular_at_pos(
+                        &mut self,
+                        pos: &#position,
+                        value: #react_extra,
+                    ) -> Result<(), CalcError> {
+                        <#field_type as SubDomainReactions<#position, #react_extra, #float>>::
+                            set_extracellular_at_pos(&mut self.#field_name, pos, value)
+                    }
+
                     #[inline]
                     fn get_neighbor_value(
                         &self,
@@ -411,7 +469,10 @@ impl SubDomainImplementer {
 
 pub fn derive_subdomain(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let subdomain_parser = syn::parse_macro_input!(input as SubDomainParser);
-    let subdomain_implementer: SubDomainImplementer = subdomain_parser.into();
+    let subdomain_implementer = match SubDomainImplementer::try_from(subdomain_parser) {
+        Ok(implementer) => implementer,
+        Err(error) => return error.to_compile_error().into(),
+    };
 
     let mut res = proc_macro2::TokenStream::new();
     res.extend(subdomain_implementer.implement_base());
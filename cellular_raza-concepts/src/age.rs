@@ -0,0 +1,85 @@
+use crate::CalcError;
+
+/// Age of a cell which is advanced automatically by the backend in every step.
+///
+/// After increasing the age by the time increment, the backend calls the
+/// [update_senescence](Age::update_senescence) hook.
+/// It can be used to transition into a senescent state in which the cell for example stops
+/// to divide, becomes less motile or secretes different factors (senescence-associated
+/// secretory phenotype).
+/// The age is not reset automatically upon division.
+/// Models which track the age since the last division need to reset the age of both cells in
+/// their [Cycle::divide](crate::Cycle::divide) method.
+///
+/// When deriving this trait via the [CellAgent](crate::CellAgent) macro, the hook only has
+/// access to the annotated field.
+/// Cells whose other properties change with senescence should thus implement this trait
+/// directly.
+/// ```
+/// use cellular_raza_concepts::{Age, CalcError};
+///
+/// struct Fibroblast {
+///     age: f64,
+///     senescent: bool,
+///     secretion_rate: f64,
+///     damping: f64,
+/// }
+///
+/// impl Age for Fibroblast {
+///     fn get_age(&self) -> f64 {
+///         self.age
+///     }
+///
+///     fn set_age(&mut self, age: f64) {
+///         self.age = age;
+///     }
+///
+///     fn update_senescence(&mut self) -> Result<(), CalcError> {
+///         if !self.senescent && self.age > 30.0 {
+///             self.senescent = true;
+///             // Senescent cells secrete more and move less
+///             self.secretion_rate *= 5.0;
+///             self.damping *= 2.0;
+///         }
+///         Ok(())
+///     }
+///
+///     fn is_senescent(&self) -> bool {
+///         self.senescent
+///     }
+/// }
+///
+/// let mut cell = Fibroblast {
+///     age: 29.5,
+///     senescent: false,
+///     secretion_rate: 1.0,
+///     damping: 1.0,
+/// };
+/// // This is done by the backend in every step
+/// cell.set_age(cell.get_age() + 1.0);
+/// cell.update_senescence()?;
+/// assert!(cell.is_senescent());
+/// assert_eq!(cell.secretion_rate, 5.0);
+/// # Ok::<(), CalcError>(())
+/// ```
+pub trait Age<F = f64> {
+    /// Current age of the cell
+    fn get_age(&self) -> F;
+
+    /// Sets the age of the cell.
+    fn set_age(&mut self, age: F);
+
+    /// Called by the backend after the age was advanced.
+    ///
+    /// The default implementation does nothing.
+    fn update_senescence(&mut self) -> Result<(), CalcError> {
+        Ok(())
+    }
+
+    /// Indicates if the cell is senescent.
+    ///
+    /// The default implementation always returns `false`.
+    fn is_senescent(&self) -> bool {
+        false
+    }
+}
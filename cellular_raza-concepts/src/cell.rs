@@ -1,3 +1,4 @@
+use crate::age::Age;
 use crate::errors::{CalcError, RngError};
 use crate::interaction::*;
 use crate::mechanics::{Mechanics, Position, Velocity};
@@ -25,6 +26,44 @@ pub trait Id {
     fn ref_id(&self) -> &Self::Identifier;
 }
 
+/// Typed access to additional per-cell quantities such as age, labels or lineage markers.
+///
+/// In contrast to the other concepts, this trait does not change how the agent is updated by
+/// the backend.
+/// Since cells are stored as a whole, every field which can be serialized is automatically
+/// saved together with the cell.
+/// This trait can be derived for every field marked with `#[CustomData]`.
+/// Multiple fields can be marked as long as their types differ.
+/// ```
+/// # use cellular_raza_concepts::*;
+/// # use serde::Serialize;
+/// #[derive(Clone, Debug, PartialEq, Serialize)]
+/// struct Age(f64);
+///
+/// #[derive(CellAgent)]
+/// struct MyAgent {
+///     #[CustomData]
+///     age: Age,
+///     #[CustomData]
+///     label: String,
+/// }
+/// let mut agent = MyAgent {
+///     age: Age(0.0),
+///     label: "founder".to_owned(),
+/// };
+/// CustomData::<Age>::custom_data_mut(&mut agent).0 += 1.5;
+/// let age: &Age = agent.custom_data();
+/// let label: &String = agent.custom_data();
+/// assert_eq!(age, &Age(1.5));
+/// assert_eq!(label, "founder");
+/// ```
+pub trait CustomData<T: Serialize> {
+    /// Obtains a reference to the stored data
+    fn custom_data(&self) -> &T;
+    /// Obtains a mutable reference to the stored data
+    fn custom_data_mut(&mut self) -> &mut T;
+}
+
 /// A container struct containing meta-information of a given Cell
 /// Some variables such as id are not required and not desired to be
 /// initialized by the user. This [CellAgentBox] acts as a container around the cell
@@ -57,9 +96,9 @@ impl<Cel> CellAgentBox<Cel> {
 }
 
 // Auto-implement traits for CellAgentBox which where also implemented for Agent
-impl<Pos, Vel, For, Inf, A> Interaction<Pos, Vel, For, Inf> for CellAgentBox<A>
+impl<Pos, Vel, For, Inf, Par, A> Interaction<Pos, Vel, For, Inf, Par> for CellAgentBox<A>
 where
-    A: Interaction<Pos, Vel, For, Inf> + Serialize + for<'a> Deserialize<'a>,
+    A: Interaction<Pos, Vel, For, Inf, Par> + Serialize + for<'a> Deserialize<'a>,
 {
     fn get_interaction_information(&self) -> Inf {
         self.cell.get_interaction_information()
@@ -77,6 +116,25 @@ where
             .calculate_force_between(own_pos, own_vel, ext_pos, ext_vel, ext_information)
     }
 
+    fn calculate_force_between_with_parameters(
+        &self,
+        own_pos: &Pos,
+        own_vel: &Vel,
+        ext_pos: &Pos,
+        ext_vel: &Vel,
+        ext_information: &Inf,
+        parameters: &Par,
+    ) -> Result<(For, For), CalcError> {
+        self.cell.calculate_force_between_with_parameters(
+            own_pos,
+            own_vel,
+            ext_pos,
+            ext_vel,
+            ext_information,
+            parameters,
+        )
+    }
+
     fn is_neighbor(&self, own_pos: &Pos, ext_pos: &Pos, ext_inf: &Inf) -> Result<bool, CalcError> {
         self.cell.is_neighbor(own_pos, ext_pos, ext_inf)
     }
@@ -84,6 +142,39 @@ where
     fn react_to_neighbors(&mut self, neighbors: usize) -> Result<(), CalcError> {
         self.cell.react_to_neighbors(neighbors)
     }
+
+    fn reacts_to_neighbors(&self) -> bool {
+        self.cell.reacts_to_neighbors()
+    }
+
+    fn normal_force(&self, own_pos: &Pos, ext_pos: &Pos, force: &For) -> Result<f64, CalcError> {
+        self.cell.normal_force(own_pos, ext_pos, force)
+    }
+
+    fn react_to_pressure(&mut self, pressure: f64) -> Result<(), CalcError> {
+        self.cell.react_to_pressure(pressure)
+    }
+
+    fn potential_energy_between(
+        &self,
+        own_pos: &Pos,
+        ext_pos: &Pos,
+        ext_inf: &Inf,
+    ) -> Result<Option<f64>, CalcError> {
+        self.cell
+            .potential_energy_between(own_pos, ext_pos, ext_inf)
+    }
+
+    fn potential_energy_between_with_parameters(
+        &self,
+        own_pos: &Pos,
+        ext_pos: &Pos,
+        ext_inf: &Inf,
+        parameters: &Par,
+    ) -> Result<Option<f64>, CalcError> {
+        self.cell
+            .potential_energy_between_with_parameters(own_pos, ext_pos, ext_inf, parameters)
+    }
 }
 
 impl<A, Pos> Position<Pos> for CellAgentBox<A>
@@ -112,9 +203,9 @@ where
     }
 }
 
-impl<Pos, Vel, For, Float, A> Mechanics<Pos, Vel, For, Float> for CellAgentBox<A>
+impl<Pos, Vel, For, Float, R, Par, A> Mechanics<Pos, Vel, For, Float, R, Par> for CellAgentBox<A>
 where
-    A: Mechanics<Pos, Vel, For, Float>,
+    A: Mechanics<Pos, Vel, For, Float, R, Par>,
 {
     /* fn pos(&self) -> Pos {
         self.cell.pos()
@@ -132,12 +223,18 @@ where
         self.cell.set_velocity(velocity);
     }*/
 
-    fn get_random_contribution(
+    fn get_random_contribution(&self, rng: &mut R, dt: Float) -> Result<(Pos, Vel), RngError> {
+        self.cell.get_random_contribution(rng, dt)
+    }
+
+    fn get_random_contribution_with_parameters(
         &self,
-        rng: &mut rand_chacha::ChaCha8Rng,
+        rng: &mut R,
         dt: Float,
+        parameters: &Par,
     ) -> Result<(Pos, Vel), RngError> {
-        self.cell.get_random_contribution(rng, dt)
+        self.cell
+            .get_random_contribution_with_parameters(rng, dt, parameters)
     }
 
     fn calculate_increment(&self, force: For) -> Result<(Pos, Vel), CalcError> {
@@ -145,6 +242,41 @@ where
     }
 }
 
+impl<A, T> CustomData<T> for CellAgentBox<A>
+where
+    A: CustomData<T>,
+    T: Serialize,
+{
+    fn custom_data(&self) -> &T {
+        self.cell.custom_data()
+    }
+
+    fn custom_data_mut(&mut self) -> &mut T {
+        self.cell.custom_data_mut()
+    }
+}
+
+impl<A, F> Age<F> for CellAgentBox<A>
+where
+    A: Age<F>,
+{
+    fn get_age(&self) -> F {
+        self.cell.get_age()
+    }
+
+    fn set_age(&mut self, age: F) {
+        self.cell.set_age(age)
+    }
+
+    fn update_senescence(&mut self) -> Result<(), CalcError> {
+        self.cell.update_senescence()
+    }
+
+    fn is_senescent(&self) -> bool {
+        self.cell.is_senescent()
+    }
+}
+
 impl<Cel> CellAgentBox<Cel> {
     /// Create a new [CellAgentBox] at a specific voxel with a voxel-unique number
     /// of cells that has already been created at this position.
@@ -165,3 +297,56 @@ impl<Cel> CellAgentBox<Cel> {
 
 #[doc(inline)]
 pub use cellular_raza_concepts_derive::CellAgent;
+
+#[cfg(test)]
+mod test_cell_agent_box {
+    use super::*;
+
+    /// Remembers the pressure which was acting on it
+    #[derive(Debug, Default, Deserialize, Serialize)]
+    struct PressureSensor {
+        /// Pressure of the last step
+        pressure: f64,
+    }
+
+    impl Interaction<f64, f64, f64> for PressureSensor {
+        fn get_interaction_information(&self) {}
+
+        fn calculate_force_between(
+            &self,
+            _: &f64,
+            _: &f64,
+            _: &f64,
+            _: &f64,
+            _: &(),
+        ) -> Result<(f64, f64), CalcError> {
+            Ok((0.0, 0.0))
+        }
+
+        fn normal_force(
+            &self,
+            own_pos: &f64,
+            ext_pos: &f64,
+            force: &f64,
+        ) -> Result<f64, CalcError> {
+            Ok((own_pos - ext_pos).signum() * force)
+        }
+
+        fn react_to_pressure(&mut self, pressure: f64) -> Result<(), CalcError> {
+            self.pressure = pressure;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn forward_pressure() {
+        let mut cbox = CellAgentBox {
+            id: (0, 0),
+            parent_id: None,
+            cell: PressureSensor::default(),
+        };
+        assert_eq!(cbox.normal_force(&1.0, &0.0, &2.0).unwrap(), 2.0);
+        cbox.react_to_pressure(3.0).unwrap();
+        assert_eq!(cbox.cell.pressure, 3.0);
+    }
+}
@@ -104,22 +104,44 @@ pub enum CycleEvent {
 ///     }
 /// }
 /// ```
-pub trait Cycle<Cell = Self, Float = f64> {
+///
+/// The random number generator `R` defaults to [ChaCha8Rng](rand_chacha::ChaCha8Rng).
+/// The `chili` backend uses the generator which is chosen by the `rng` argument of
+/// `run_simulation!` while the `cpu_os_threads` backend always uses the default.
+/// Similarly, the simulation-wide parameters `Params` default to
+/// [GlobalParameters](crate::GlobalParameters).
+pub trait Cycle<
+    Cell = Self,
+    Float = f64,
+    R = rand_chacha::ChaCha8Rng,
+    Params = crate::GlobalParameters,
+>
+{
     /// Continuously updates cellular properties and may spawn a [CycleEvent] which
     /// then calls the corresponding functions (see also [CycleEvent]).
     #[must_use]
-    fn update_cycle(
-        rng: &mut rand_chacha::ChaCha8Rng,
+    fn update_cycle(rng: &mut R, dt: &Float, cell: &mut Cell) -> Option<CycleEvent>;
+
+    /// Identical to [update_cycle](Cycle::update_cycle) but with access to the simulation-wide
+    /// parameters which are given as [GlobalParameters](crate::GlobalParameters) by default.
+    /// Backends call this method which by default ignores the parameters.
+    #[allow(unused)]
+    #[must_use]
+    fn update_cycle_with_parameters(
+        rng: &mut R,
         dt: &Float,
         cell: &mut Cell,
-    ) -> Option<CycleEvent>;
+        parameters: &Params,
+    ) -> Option<CycleEvent> {
+        Self::update_cycle(rng, dt, cell)
+    }
 
     /// Performs division of the cell by modifying the existing one and spawning an additional cell.
     /// The user is responsible for correctly adjusting cell-specific values such as intracellular
     /// concentrations or position of the two resulting cells.
     /// Corresponds to [CycleEvent::Division].
     #[must_use]
-    fn divide(rng: &mut rand_chacha::ChaCha8Rng, cell: &mut Cell) -> Result<Cell, DivisionError>;
+    fn divide(rng: &mut R, cell: &mut Cell) -> Result<Cell, DivisionError>;
 
     /// Method corresponding to the [CycleEvent::PhasedDeath] event.
     /// Update the cell while returning a boolean which indicates if the updating procedure has
@@ -127,7 +149,7 @@ pub trait Cycle<Cell = Self, Float = f64> {
     #[allow(unused)]
     #[must_use]
     fn update_conditional_phased_death(
-        rng: &mut rand_chacha::ChaCha8Rng,
+        rng: &mut R,
         dt: &Float,
         cell: &mut Cell,
     ) -> Result<bool, DeathError> {
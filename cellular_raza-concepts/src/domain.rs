@@ -190,12 +190,25 @@ pub trait SortCells<C> {
 ///     }
 /// }
 ///
+/// # struct MyBase;
+/// # impl SubDomain for MyBase {
+/// #     type VoxelIndex = usize;
+/// #     fn get_neighbor_voxel_indices(&self, _: &usize) -> Vec<usize> {
+/// #         Vec::new()
+/// #     }
+/// #     fn get_all_indices(&self) -> Vec<usize> {
+/// #         Vec::new()
+/// #     }
+/// # }
 /// #[derive(SubDomain)]
 /// struct MySubDomain {
+///     #[Base]
+///     base: MyBase,
 ///     #[Mechanics]
 ///     mechanics: MyMechanics,
 /// }
 /// # let _my_sdm = MySubDomain {
+/// #     base: MyBase,
 /// #     mechanics: MyMechanics {
 /// #         x_min: 1.0,
 /// #         x_max: 33.0,
@@ -212,6 +225,84 @@ pub trait SubDomainMechanics<Pos, Vel> {
     /// For the future, we plan to replace this function to additionally obtain information
     /// about the previous and current location of the cell.
     fn apply_boundary(&self, pos: &mut Pos, vel: &mut Vel) -> Result<(), BoundaryError>;
+
+    /// Metric which is used to calculate interactions between cells.
+    ///
+    /// By default, positions are handed to the [Interaction](crate::Interaction) unchanged.
+    /// Subdomains with periodic boundaries or curved geometries should return their
+    /// [DomainMetric] here.
+    fn domain_metric(&self) -> Option<&dyn DomainMetric<Pos>> {
+        None
+    }
+
+    /// Moves a position which lies outside of the domain onto its closest point inside.
+    ///
+    /// This is used as a fallback when [apply_boundary](SubDomainMechanics::apply_boundary)
+    /// failed.
+    /// By default, clamping is not supported and an error is returned.
+    fn clamp_to_domain(&self, pos: &mut Pos, vel: &mut Vel) -> Result<(), BoundaryError> {
+        let _ = (pos, vel);
+        Err(BoundaryError(
+            "clamping positions into the domain is not supported by this subdomain".into(),
+        ))
+    }
+
+    /// Maps a position which lies outside of the domain periodically into it.
+    ///
+    /// This is used as a fallback when [apply_boundary](SubDomainMechanics::apply_boundary)
+    /// failed.
+    /// By default, wrapping is not supported and an error is returned.
+    fn wrap_into_domain(&self, pos: &mut Pos, vel: &mut Vel) -> Result<(), BoundaryError> {
+        let _ = (pos, vel);
+        Err(BoundaryError(
+            "wrapping positions into the domain is not supported by this subdomain".into(),
+        ))
+    }
+}
+
+/// Describes how positions of two cells relate to each other inside a domain.
+///
+/// [Interaction](crate::Interaction)s calculate the difference `own_pos - ext_pos` directly.
+/// This is only correct in Euclidean space without periodic boundaries.
+/// Before calculating the interaction between two cells, the backend replaces the external
+/// position by its [nearest image](DomainMetric::nearest_image).
+/// Thus, the same [Interaction](crate::Interaction) can be used in periodic or curved domains.
+///
+/// ```
+/// # use cellular_raza_concepts::*;
+/// /// Periodic interval [0, 10)
+/// struct Ring;
+///
+/// impl DomainMetric<f64> for Ring {
+///     fn nearest_image(&self, own_pos: &f64, ext_pos: &f64) -> f64 {
+///         let d = ext_pos - own_pos;
+///         own_pos + d - 10.0 * (d / 10.0).round()
+///     }
+/// }
+///
+/// struct RingMechanics;
+///
+/// impl SubDomainMechanics<f64, f64> for RingMechanics {
+///     fn apply_boundary(&self, pos: &mut f64, _: &mut f64) -> Result<(), BoundaryError> {
+///         *pos = pos.rem_euclid(10.0);
+///         Ok(())
+///     }
+///
+///     fn domain_metric(&self) -> Option<&dyn DomainMetric<f64>> {
+///         Some(&Ring)
+///     }
+/// }
+/// let metric = RingMechanics.domain_metric().unwrap();
+/// assert_eq!(metric.nearest_image(&9.5, &0.5), 10.5);
+/// assert_eq!(metric.nearest_image(&0.5, &9.5), -0.5);
+/// assert_eq!(metric.nearest_image(&3.0, &4.0), 4.0);
+/// ```
+pub trait DomainMetric<Pos> {
+    /// Returns the image of `ext_pos` which is closest to `own_pos`.
+    ///
+    /// The difference between the returned position and `own_pos` is the shortest connection
+    /// between both points within the domain.
+    fn nearest_image(&self, own_pos: &Pos, ext_pos: &Pos) -> Pos;
 }
 
 /// Apply a force on a cell depending on its position and velocity.
@@ -229,12 +320,25 @@ pub trait SubDomainMechanics<Pos, Vel> {
 ///     }
 /// }
 ///
+/// # struct MyBase;
+/// # impl SubDomain for MyBase {
+/// #     type VoxelIndex = usize;
+/// #     fn get_neighbor_voxel_indices(&self, _: &usize) -> Vec<usize> {
+/// #         Vec::new()
+/// #     }
+/// #     fn get_all_indices(&self) -> Vec<usize> {
+/// #         Vec::new()
+/// #     }
+/// # }
 /// #[derive(SubDomain)]
 /// struct MySubDomain {
+///     #[Base]
+///     base: MyBase,
 ///     #[Force]
 ///     force: MyForce,
 /// }
 /// # let _my_sdm = MySubDomain {
+/// #     base: MyBase,
 /// #     force: MyForce {
 /// #         damping: 0.1,
 /// #     }
@@ -249,6 +353,15 @@ pub trait SubDomainForce<Pos, Vel, For> {
 
 /// Describes extracellular reactions and fluid dynamics
 ///
+/// In every step, subdomains exchange the values at their borders before updating their fields.
+/// 1. Every subdomain sends its [BorderInfo](SubDomainReactions::BorderInfo) to its neighbors.
+/// 2. Neighbors answer with the [NeighborValue](SubDomainReactions::NeighborValue) obtained by
+///    [get_neighbor_value](SubDomainReactions::get_neighbor_value).
+/// 3. The values of all neighbors and the increments of all cells inside the subdomain are
+///    combined by [treat_increments](SubDomainReactions::treat_increments).
+/// 4. The fields are advanced in time by
+///    [update_fluid_dynamics](SubDomainReactions::update_fluid_dynamics).
+///
 /// # Derivation
 /// ```
 /// # use cellular_raza_concepts::*;
@@ -283,6 +396,15 @@ pub trait SubDomainForce<Pos, Vel, For> {
 ///         Ok(self.values.clone())
 ///     }
 ///
+///     fn set_extracellular_at_pos(
+///         &mut self,
+///         pos: &[f32; N],
+///         value: Vec<f32>,
+///     ) -> Result<(), CalcError> {
+///         self.values = value;
+///         Ok(())
+///     }
+///
 ///     fn get_neighbor_value(&self, border_info: Self::BorderInfo) -> Self::NeighborValue {
 ///         self.values.clone()
 ///     }
@@ -292,11 +414,32 @@ pub trait SubDomainForce<Pos, Vel, For> {
 ///     }
 /// }
 ///
+/// # struct MyBase;
+/// # impl SubDomain for MyBase {
+/// #     type VoxelIndex = usize;
+/// #     fn get_neighbor_voxel_indices(&self, _: &usize) -> Vec<usize> {
+/// #         Vec::new()
+/// #     }
+/// #     fn get_all_indices(&self) -> Vec<usize> {
+/// #         Vec::new()
+/// #     }
+/// # }
 /// #[derive(SubDomain)]
 /// struct DerivedSubDomain<const N: usize> {
+///     #[Base]
+///     base: MyBase,
 ///     #[Reactions]
 ///     reactions: MyReactions<N>,
 /// }
+/// # let mut subdomain = DerivedSubDomain {
+/// #     base: MyBase,
+/// #     reactions: MyReactions {
+/// #         values: vec![1.0],
+/// #         pos: [0.0; 2],
+/// #     },
+/// # };
+/// # subdomain.set_extracellular_at_pos(&[0.0; 2], vec![2.0]).unwrap();
+/// # assert_eq!(subdomain.get_extracellular_at_pos(&[1.0; 2]).unwrap(), vec![2.0]);
 /// ```
 pub trait SubDomainReactions<Pos, Re, Float> {
     /// Extracellular value of neighbor
@@ -315,6 +458,19 @@ pub trait SubDomainReactions<Pos, Re, Float> {
 
     /// Obtain extracellular concentrations at given point.
     fn get_extracellular_at_pos(&self, pos: &Pos) -> Result<Re, crate::CalcError>;
+
+    /// Overwrites the extracellular concentrations at the given point.
+    ///
+    /// This can be used to initialize fields or to apply external perturbations in between
+    /// steps.
+    /// By default, setting values is not supported and an error is returned.
+    fn set_extracellular_at_pos(&mut self, pos: &Pos, value: Re) -> Result<(), crate::CalcError> {
+        let _ = (pos, value);
+        Err(crate::CalcError(
+            "setting extracellular values is not supported by this subdomain".into(),
+        ))
+    }
+
     /// Obtains the [NeighborValue] which should be sent to the neighbor which has exposed the given
     /// [BorderInfo].
     fn get_neighbor_value(&self, border_info: Self::BorderInfo) -> Self::NeighborValue;
@@ -322,6 +478,16 @@ pub trait SubDomainReactions<Pos, Re, Float> {
     fn get_border_info(&self) -> Self::BorderInfo;
 }
 
+/// Magnitude of the extracellular fields of a subdomain.
+///
+/// It is used to monitor if the fields of a simulation have reached a steady state.
+/// Since the contributions of all subdomains are summed up, this trait returns the squared
+/// $L^2$ norm of the values stored in the subdomain.
+pub trait FieldNorm<Float = f64> {
+    /// Sum of the squares of all values of the fields in this subdomain
+    fn squared_field_norm(&self) -> Float;
+}
+
 /// This trait derives the different aspects of a [SubDomain].
 ///
 /// It serves similarly as the [cellular_raza_concepts_derive::CellAgent] trait to quickly
@@ -333,7 +499,10 @@ pub trait SubDomainReactions<Pos, Re, Float> {
 /// | `SortCells` | [SortCells] | ✅ |
 /// | `Mechanics` | [SubDomainMechanics] | ✅ |
 /// | `Force` | [SubDomainForce] | ✅  |
-/// | `Reactions` | [SubDomainReactions] | ❌ |
+/// | `Reactions` | [SubDomainReactions] | ✅ |
+///
+/// Every derived subdomain needs exactly one field which is annotated with `#[Base]`.
+/// Each attribute can only be specified once while one field may carry multiple attributes.
 ///
 /// # Example Usage
 /// ```
@@ -362,6 +531,79 @@ pub trait SubDomainReactions<Pos, Re, Float> {
 #[doc(inline)]
 pub use cellular_raza_concepts_derive::SubDomain;
 
-// TODO
+/// This trait derives the different aspects of a [Domain].
+///
+/// Attributes delegate the respective trait to the annotated field.
+///
+/// | Attribute | Trait | Implemented |
+/// | ---  | --- |:---:|
+/// | `Base` | [Domain] | ✅ |
+/// | `SortCells` | [SortCells] | ✅ |
+/// | `DomainRngSeed` | [DomainRngSeed] | ✅ |
+/// | `DomainCreateSubDomains` | [DomainCreateSubDomains] | ✅ |
+///
+/// If no field is annotated with `#[Base]`, the [Domain] trait is implemented from the
+/// [SortCells], [DomainRngSeed] and [DomainCreateSubDomains] traits of the struct itself.
+/// These traits can be delegated to an inner field or be implemented by hand.
+/// The struct attribute `#[DomainPartialDerive]` disables this implementation.
+/// On a field, `#[DomainPartialDerive]` has no effect and is only accepted for compatibility.
+/// Each attribute can only be specified once while one field may carry multiple attributes.
+///
+/// # Example Usage
+/// A domain which wraps another domain and stores additional parameters does not need any
+/// hand-written implementations.
+/// ```
+/// # use cellular_raza_concepts::*;
+/// # struct Agent;
+/// # #[derive(Clone)]
+/// # struct Interval;
+/// # impl SubDomain for Interval {
+/// #     type VoxelIndex = usize;
+/// #     fn get_neighbor_voxel_indices(&self, _: &usize) -> Vec<usize> {
+/// #         Vec::new()
+/// #     }
+/// #     fn get_all_indices(&self) -> Vec<usize> {
+/// #         vec![0]
+/// #     }
+/// # }
+/// # struct InnerDomain;
+/// # impl DomainRngSeed for InnerDomain {
+/// #     fn get_rng_seed(&self) -> u64 {
+/// #         1
+/// #     }
+/// # }
+/// # impl SortCells<Agent> for InnerDomain {
+/// #     type VoxelIndex = usize;
+/// #     fn get_voxel_index_of(&self, _: &Agent) -> Result<usize, BoundaryError> {
+/// #         Ok(0)
+/// #     }
+/// # }
+/// # impl DomainCreateSubDomains<Interval> for InnerDomain {
+/// #     type SubDomainIndex = usize;
+/// #     type VoxelIndex = usize;
+/// #     fn create_subdomains(
+/// #         &self,
+/// #         _: core::num::NonZeroUsize,
+/// #     ) -> Result<impl IntoIterator<Item = (usize, Interval, Vec<usize>)>, DecomposeError> {
+/// #         Ok([(0, Interval, vec![0])])
+/// #     }
+/// # }
+/// #[derive(Domain)]
+/// struct MyDomain {
+///     #[DomainRngSeed]
+///     #[SortCells]
+///     #[DomainCreateSubDomains]
+///     inner: InnerDomain,
+///     parameter: f64,
+/// }
+/// # let domain = MyDomain {
+/// #     inner: InnerDomain,
+/// #     parameter: 1.0,
+/// # };
+/// let decomposed: DecomposedDomain<usize, Interval, Agent> =
+///     domain.decompose(1.try_into().unwrap(), vec![Agent, Agent])?;
+/// assert_eq!(decomposed.index_subdomain_cells[0].2.len(), 2);
+/// # Ok::<(), DecomposeError>(())
+/// ```
 #[doc(inline)]
 pub use cellular_raza_concepts_derive::Domain;
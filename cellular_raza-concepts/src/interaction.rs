@@ -1,7 +1,17 @@
 use crate::errors::CalcError;
+use crate::GlobalParameters;
+
+use serde::{Deserialize, Serialize};
 
 /// Trait describing force-interactions between cellular agents.
-pub trait Interaction<Pos, Vel, Force, Inf = ()> {
+///
+/// This trait combines the calculation of forces with counting and reacting to neighbors.
+/// Agents which only exert forces can implement the smaller [InteractionForce] trait instead
+/// and obtain this trait via a blanket implementation.
+/// Neighbor-reactive behaviour can be implemented separately with the [NeighbourReactive]
+/// trait and combined with forces by the `CellAgent` derive macro or the [WithNeighbours]
+/// struct.
+pub trait Interaction<Pos, Vel, Force, Inf = (), Params = GlobalParameters> {
     /// Get additional information of cellular properties (ie. for cell-specific interactions).
     /// For now, this can also be used to get the mass of the other cell-agent.
     /// In the future, we will probably provide a custom function for this.
@@ -21,6 +31,22 @@ pub trait Interaction<Pos, Vel, Force, Inf = ()> {
         ext_info: &Inf,
     ) -> Result<(Force, Force), CalcError>;
 
+    /// Identical to [calculate_force_between](Interaction::calculate_force_between) but with
+    /// access to the simulation-wide parameters which are given as [GlobalParameters] by default.
+    /// Backends call this method which by default ignores the parameters.
+    #[allow(unused)]
+    fn calculate_force_between_with_parameters(
+        &self,
+        own_pos: &Pos,
+        own_vel: &Vel,
+        ext_pos: &Pos,
+        ext_vel: &Vel,
+        ext_info: &Inf,
+        parameters: &Params,
+    ) -> Result<(Force, Force), CalcError> {
+        self.calculate_force_between(own_pos, own_vel, ext_pos, ext_vel, ext_info)
+    }
+
     /// Checks if the other cell represented by position and information is a neighbor to the current one or not.
     #[allow(unused)]
     fn is_neighbor(&self, own_pos: &Pos, ext_pos: &Pos, ext_inf: &Inf) -> Result<bool, CalcError> {
@@ -33,10 +59,411 @@ pub trait Interaction<Pos, Vel, Force, Inf = ()> {
     fn react_to_neighbors(&mut self, neighbors: usize) -> Result<(), CalcError> {
         Ok(())
     }
+
+    /// Indicates if the agent reacts to its neighbors at all.
+    ///
+    /// Backends skip calling [Interaction::is_neighbor] and [Interaction::react_to_neighbors]
+    /// for agents which return `false`.
+    /// By default, this method returns `true` such that existing implementations of these
+    /// methods are always respected.
+    fn reacts_to_neighbors(&self) -> bool {
+        true
+    }
+
+    /// Normal component of a force acting on the current agent which was exerted by the agent
+    /// at the external position.
+    ///
+    /// Backends sum these values over all interaction partners to obtain an approximate
+    /// mechanical pressure of the agent.
+    /// Compressive forces should yield positive values.
+    /// Implementors should only rely on the given arguments since backends may also use this
+    /// method to calculate the normal component of the force acting on the external agent.
+    /// By default, this method returns zero and no pressure is accumulated.
+    #[allow(unused)]
+    fn normal_force(&self, own_pos: &Pos, ext_pos: &Pos, force: &Force) -> Result<f64, CalcError> {
+        Ok(0.0)
+    }
+
+    /// Reacts to the pressure which was gathered by the [Interaction::normal_force] method in
+    /// the last step.
+    #[allow(unused)]
+    fn react_to_pressure(&mut self, pressure: f64) -> Result<(), CalcError> {
+        Ok(())
+    }
+
+    /// Potential energy stored in the interaction with the agent at the external position.
+    ///
+    /// Implementing this method is optional.
+    /// The force calculated by [Interaction::calculate_force_between] should be the negative
+    /// gradient of this energy.
+    /// When it returns a value, backends can accumulate the total potential energy of all
+    /// pairs of agents as an observable.
+    /// Together with [KineticEnergy](crate::KineticEnergy), this allows to check the
+    /// conservation of energy in a simulation and to use the energy in Monte Carlo-style
+    /// analyses.
+    /// By default, no energy is reported.
+    #[allow(unused)]
+    fn potential_energy_between(
+        &self,
+        own_pos: &Pos,
+        ext_pos: &Pos,
+        ext_inf: &Inf,
+    ) -> Result<Option<f64>, CalcError> {
+        Ok(None)
+    }
+
+    /// Identical to [potential_energy_between](Interaction::potential_energy_between) but with
+    /// access to the simulation-wide parameters.
+    /// Backends call this method which by default ignores the parameters.
+    #[allow(unused)]
+    fn potential_energy_between_with_parameters(
+        &self,
+        own_pos: &Pos,
+        ext_pos: &Pos,
+        ext_inf: &Inf,
+        parameters: &Params,
+    ) -> Result<Option<f64>, CalcError> {
+        self.potential_energy_between(own_pos, ext_pos, ext_inf)
+    }
     // TODO
     // fn contact_function(&mut self, other_cell: &C, environment: &mut Env) -> Result<(), SimulationError>;
 }
 
+/// Force-interactions between cellular agents without any reaction to neighbors.
+///
+/// Every type which implements this trait also implements [Interaction] such that it can be
+/// used with all backends.
+/// The simulation-wide parameters are ignored and [Interaction::reacts_to_neighbors] returns
+/// `false` such that backends skip counting neighbors.
+/// Thus, implementing this trait fixes [Interaction::reacts_to_neighbors] to `false` and a type
+/// can not both implement this trait and react to its neighbors.
+/// Agents which need to react to their neighbors can implement the [Interaction] trait
+/// directly, combine this trait with a [NeighbourReactive] via the [WithNeighbours] struct or
+/// combine a field marked with `#[InteractionForce]` with a field marked with
+/// `#[NeighbourReactive]` when deriving the `CellAgent` trait.
+/// ```
+/// use cellular_raza_concepts::{CalcError, Interaction, InteractionForce};
+///
+/// struct Spring(f64);
+///
+/// impl InteractionForce for Spring {
+///     type Pos = f64;
+///     type Vel = f64;
+///     type Force = f64;
+///     type Inf = ();
+///
+///     fn get_interaction_information(&self) {}
+///
+///     fn calculate_force_between(
+///         &self,
+///         own_pos: &f64,
+///         _own_vel: &f64,
+///         ext_pos: &f64,
+///         _ext_vel: &f64,
+///         _ext_info: &(),
+///     ) -> Result<(f64, f64), CalcError> {
+///         let force = self.0 * (ext_pos - own_pos);
+///         Ok((force, -force))
+///     }
+/// }
+///
+/// fn uses_interaction<I: Interaction<f64, f64, f64>>(interaction: &I) -> Result<f64, CalcError> {
+///     let (force, _) = interaction.calculate_force_between(&0.0, &0.0, &1.5, &0.0, &())?;
+///     assert!(!interaction.reacts_to_neighbors());
+///     Ok(force)
+/// }
+///
+/// assert_eq!(uses_interaction(&Spring(2.0))?, 3.0);
+/// # Ok::<(), CalcError>(())
+/// ```
+pub trait InteractionForce {
+    /// Position of the agent
+    type Pos;
+    /// Velocity of the agent
+    type Vel;
+    /// Force acting on the agent
+    type Force;
+    /// Information which is shared with other agents
+    type Inf;
+
+    /// See [Interaction::get_interaction_information]
+    fn get_interaction_information(&self) -> Self::Inf;
+
+    /// See [Interaction::calculate_force_between]
+    fn calculate_force_between(
+        &self,
+        own_pos: &Self::Pos,
+        own_vel: &Self::Vel,
+        ext_pos: &Self::Pos,
+        ext_vel: &Self::Vel,
+        ext_info: &Self::Inf,
+    ) -> Result<(Self::Force, Self::Force), CalcError>;
+
+    /// See [Interaction::normal_force]
+    #[allow(unused)]
+    fn normal_force(
+        &self,
+        own_pos: &Self::Pos,
+        ext_pos: &Self::Pos,
+        force: &Self::Force,
+    ) -> Result<f64, CalcError> {
+        Ok(0.0)
+    }
+
+    /// See [Interaction::react_to_pressure]
+    #[allow(unused)]
+    fn react_to_pressure(&mut self, pressure: f64) -> Result<(), CalcError> {
+        Ok(())
+    }
+
+    /// See [Interaction::potential_energy_between]
+    #[allow(unused)]
+    fn potential_energy_between(
+        &self,
+        own_pos: &Self::Pos,
+        ext_pos: &Self::Pos,
+        ext_inf: &Self::Inf,
+    ) -> Result<Option<f64>, CalcError> {
+        Ok(None)
+    }
+}
+
+/// Counts neighboring agents and changes the state of the agent accordingly.
+///
+/// This trait contains the neighbor-related methods of the [Interaction] trait.
+/// When deriving the `CellAgent` trait, a field marked with `#[NeighbourReactive]` is combined
+/// with the forces of a field marked with `#[InteractionForce]`.
+/// Outside of the derive macro, the [WithNeighbours] struct combines both traits.
+/// Thus, neighbor-reactive behaviour can be reused with different force potentials.
+pub trait NeighbourReactive<Pos, Inf = ()> {
+    /// See [Interaction::is_neighbor]
+    fn is_neighbor(&self, own_pos: &Pos, ext_pos: &Pos, ext_inf: &Inf) -> Result<bool, CalcError>;
+
+    /// See [Interaction::react_to_neighbors]
+    fn react_to_neighbors(&mut self, neighbors: usize) -> Result<(), CalcError>;
+}
+
+impl<T, Params> Interaction<T::Pos, T::Vel, T::Force, T::Inf, Params> for T
+where
+    T: InteractionForce,
+{
+    #[inline]
+    fn get_interaction_information(&self) -> T::Inf {
+        <T as InteractionForce>::get_interaction_information(self)
+    }
+
+    #[inline]
+    fn calculate_force_between(
+        &self,
+        own_pos: &T::Pos,
+        own_vel: &T::Vel,
+        ext_pos: &T::Pos,
+        ext_vel: &T::Vel,
+        ext_info: &T::Inf,
+    ) -> Result<(T::Force, T::Force), CalcError> {
+        <T as InteractionForce>::calculate_force_between(
+            self, own_pos, own_vel, ext_pos, ext_vel, ext_info,
+        )
+    }
+
+    #[inline]
+    fn reacts_to_neighbors(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn normal_force(
+        &self,
+        own_pos: &T::Pos,
+        ext_pos: &T::Pos,
+        force: &T::Force,
+    ) -> Result<f64, CalcError> {
+        <T as InteractionForce>::normal_force(self, own_pos, ext_pos, force)
+    }
+
+    #[inline]
+    fn react_to_pressure(&mut self, pressure: f64) -> Result<(), CalcError> {
+        <T as InteractionForce>::react_to_pressure(self, pressure)
+    }
+
+    #[inline]
+    fn potential_energy_between(
+        &self,
+        own_pos: &T::Pos,
+        ext_pos: &T::Pos,
+        ext_inf: &T::Inf,
+    ) -> Result<Option<f64>, CalcError> {
+        <T as InteractionForce>::potential_energy_between(self, own_pos, ext_pos, ext_inf)
+    }
+}
+
+/// Combines an [InteractionForce] with a [NeighbourReactive] to obtain an [Interaction].
+///
+/// All force-related methods are taken from the [InteractionForce] while neighbors are counted
+/// and reacted to by the [NeighbourReactive].
+/// In contrast to the [InteractionForce] alone, [Interaction::reacts_to_neighbors] returns
+/// `true`.
+/// ```
+/// use cellular_raza_concepts::*;
+///
+/// struct Spring(f64);
+///
+/// impl InteractionForce for Spring {
+///     type Pos = f64;
+///     type Vel = f64;
+///     type Force = f64;
+///     type Inf = ();
+///
+///     fn get_interaction_information(&self) {}
+///
+///     fn calculate_force_between(
+///         &self,
+///         own_pos: &f64,
+///         _own_vel: &f64,
+///         ext_pos: &f64,
+///         _ext_vel: &f64,
+///         _ext_info: &(),
+///     ) -> Result<(f64, f64), CalcError> {
+///         let force = self.0 * (ext_pos - own_pos);
+///         Ok((force, -force))
+///     }
+/// }
+///
+/// /// Remembers the number of other agents within unit distance
+/// #[derive(Default)]
+/// struct Crowding(usize);
+///
+/// impl NeighbourReactive<f64> for Crowding {
+///     fn is_neighbor(&self, own_pos: &f64, ext_pos: &f64, _: &()) -> Result<bool, CalcError> {
+///         Ok((own_pos - ext_pos).abs() < 1.0)
+///     }
+///
+///     fn react_to_neighbors(&mut self, neighbors: usize) -> Result<(), CalcError> {
+///         self.0 = neighbors;
+///         Ok(())
+///     }
+/// }
+///
+/// let mut interaction = WithNeighbours {
+///     force: Spring(2.0),
+///     neighbours: Crowding::default(),
+/// };
+///
+/// fn uses_interaction<I: Interaction<f64, f64, f64>>(
+///     interaction: &mut I,
+/// ) -> Result<f64, CalcError> {
+///     let (force, _) = interaction.calculate_force_between(&0.0, &0.0, &1.5, &0.0, &())?;
+///     assert!(interaction.reacts_to_neighbors());
+///     assert!(interaction.is_neighbor(&0.0, &0.5, &())?);
+///     interaction.react_to_neighbors(2)?;
+///     Ok(force)
+/// }
+///
+/// assert_eq!(uses_interaction(&mut interaction)?, 3.0);
+/// assert_eq!(interaction.neighbours.0, 2);
+/// # Ok::<(), CalcError>(())
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct WithNeighbours<F, N> {
+    /// Calculates the forces between agents
+    pub force: F,
+    /// Counts and reacts to neighboring agents
+    pub neighbours: N,
+}
+
+impl<F, N, Params> Interaction<F::Pos, F::Vel, F::Force, F::Inf, Params> for WithNeighbours<F, N>
+where
+    F: InteractionForce,
+    N: NeighbourReactive<F::Pos, F::Inf>,
+{
+    #[inline]
+    fn get_interaction_information(&self) -> F::Inf {
+        self.force.get_interaction_information()
+    }
+
+    #[inline]
+    fn calculate_force_between(
+        &self,
+        own_pos: &F::Pos,
+        own_vel: &F::Vel,
+        ext_pos: &F::Pos,
+        ext_vel: &F::Vel,
+        ext_info: &F::Inf,
+    ) -> Result<(F::Force, F::Force), CalcError> {
+        self.force
+            .calculate_force_between(own_pos, own_vel, ext_pos, ext_vel, ext_info)
+    }
+
+    #[inline]
+    fn is_neighbor(
+        &self,
+        own_pos: &F::Pos,
+        ext_pos: &F::Pos,
+        ext_inf: &F::Inf,
+    ) -> Result<bool, CalcError> {
+        self.neighbours.is_neighbor(own_pos, ext_pos, ext_inf)
+    }
+
+    #[inline]
+    fn react_to_neighbors(&mut self, neighbors: usize) -> Result<(), CalcError> {
+        self.neighbours.react_to_neighbors(neighbors)
+    }
+
+    #[inline]
+    fn reacts_to_neighbors(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn normal_force(
+        &self,
+        own_pos: &F::Pos,
+        ext_pos: &F::Pos,
+        force: &F::Force,
+    ) -> Result<f64, CalcError> {
+        self.force.normal_force(own_pos, ext_pos, force)
+    }
+
+    #[inline]
+    fn react_to_pressure(&mut self, pressure: f64) -> Result<(), CalcError> {
+        self.force.react_to_pressure(pressure)
+    }
+
+    #[inline]
+    fn potential_energy_between(
+        &self,
+        own_pos: &F::Pos,
+        ext_pos: &F::Pos,
+        ext_inf: &F::Inf,
+    ) -> Result<Option<f64>, CalcError> {
+        self.force
+            .potential_energy_between(own_pos, ext_pos, ext_inf)
+    }
+}
+
+/// Torques between agents with rotational degrees of freedom.
+///
+/// This trait complements the [Interaction] trait for agents which implement the
+/// [Orientation](crate::Orientation) and [RotationalMechanics](crate::RotationalMechanics)
+/// traits.
+/// Information about other agents is obtained by
+/// [get_interaction_information](Interaction::get_interaction_information) such that both
+/// traits share the same information type `Inf`.
+pub trait InteractionTorque<Pos, Ang, Tor, Inf = ()> {
+    /// Calculates the torques acting on the current and the external agent.
+    ///
+    /// Similarly to [Interaction::calculate_force_between], the first value of the returned
+    /// tuple acts on the current agent and the second value on the external agent.
+    fn calculate_torque_between(
+        &self,
+        own_pos: &Pos,
+        own_ang: &Ang,
+        ext_pos: &Pos,
+        ext_ang: &Ang,
+        ext_info: &Inf,
+    ) -> Result<(Tor, Tor), CalcError>;
+}
+
 impl<Pos, Vel, For, Inf> Interaction<Pos, Vel, For, Inf>
     for Box<dyn Interaction<Pos, Vel, For, Inf>>
 {
@@ -56,6 +483,20 @@ impl<Pos, Vel, For, Inf> Interaction<Pos, Vel, For, Inf>
         self.deref()
             .calculate_force_between(own_pos, own_vel, ext_pos, ext_vel, ext_info)
     }
+    fn calculate_force_between_with_parameters(
+        &self,
+        own_pos: &Pos,
+        own_vel: &Vel,
+        ext_pos: &Pos,
+        ext_vel: &Vel,
+        ext_info: &Inf,
+        parameters: &GlobalParameters,
+    ) -> Result<(For, For), CalcError> {
+        use core::ops::Deref;
+        self.deref().calculate_force_between_with_parameters(
+            own_pos, own_vel, ext_pos, ext_vel, ext_info, parameters,
+        )
+    }
     fn is_neighbor(&self, own_pos: &Pos, ext_pos: &Pos, ext_inf: &Inf) -> Result<bool, CalcError> {
         use core::ops::Deref;
         self.deref().is_neighbor(own_pos, ext_pos, ext_inf)
@@ -64,4 +505,140 @@ impl<Pos, Vel, For, Inf> Interaction<Pos, Vel, For, Inf>
         use core::ops::DerefMut;
         self.deref_mut().react_to_neighbors(neighbors)
     }
+    fn reacts_to_neighbors(&self) -> bool {
+        use core::ops::Deref;
+        self.deref().reacts_to_neighbors()
+    }
+    fn normal_force(&self, own_pos: &Pos, ext_pos: &Pos, force: &For) -> Result<f64, CalcError> {
+        use core::ops::Deref;
+        self.deref().normal_force(own_pos, ext_pos, force)
+    }
+    fn react_to_pressure(&mut self, pressure: f64) -> Result<(), CalcError> {
+        use core::ops::DerefMut;
+        self.deref_mut().react_to_pressure(pressure)
+    }
+    fn potential_energy_between(
+        &self,
+        own_pos: &Pos,
+        ext_pos: &Pos,
+        ext_inf: &Inf,
+    ) -> Result<Option<f64>, CalcError> {
+        use core::ops::Deref;
+        self.deref()
+            .potential_energy_between(own_pos, ext_pos, ext_inf)
+    }
+    fn potential_energy_between_with_parameters(
+        &self,
+        own_pos: &Pos,
+        ext_pos: &Pos,
+        ext_inf: &Inf,
+        parameters: &GlobalParameters,
+    ) -> Result<Option<f64>, CalcError> {
+        use core::ops::Deref;
+        self.deref()
+            .potential_energy_between_with_parameters(own_pos, ext_pos, ext_inf, parameters)
+    }
+}
+
+#[cfg(test)]
+mod test_boxed_interaction {
+    use super::*;
+
+    /// Spring whose stiffness is given as a global parameter
+    struct ParametrizedSpring;
+
+    /// Shares the pressure which was acting on it
+    #[derive(Default)]
+    struct PressureSensor(std::rc::Rc<std::cell::Cell<f64>>);
+
+    impl Interaction<f64, f64, f64> for PressureSensor {
+        fn get_interaction_information(&self) {}
+
+        fn calculate_force_between(
+            &self,
+            _: &f64,
+            _: &f64,
+            _: &f64,
+            _: &f64,
+            _: &(),
+        ) -> Result<(f64, f64), CalcError> {
+            Ok((0.0, 0.0))
+        }
+
+        fn normal_force(
+            &self,
+            own_pos: &f64,
+            ext_pos: &f64,
+            force: &f64,
+        ) -> Result<f64, CalcError> {
+            Ok((own_pos - ext_pos).signum() * force)
+        }
+
+        fn react_to_pressure(&mut self, pressure: f64) -> Result<(), CalcError> {
+            self.0.set(pressure);
+            Ok(())
+        }
+    }
+
+    /// Stiffness of the [ParametrizedSpring]
+    struct Stiffness(f64);
+
+    impl Interaction<f64, f64, f64> for ParametrizedSpring {
+        fn get_interaction_information(&self) {}
+
+        fn calculate_force_between(
+            &self,
+            _: &f64,
+            _: &f64,
+            _: &f64,
+            _: &f64,
+            _: &(),
+        ) -> Result<(f64, f64), CalcError> {
+            Ok((0.0, 0.0))
+        }
+
+        fn calculate_force_between_with_parameters(
+            &self,
+            own_pos: &f64,
+            _: &f64,
+            ext_pos: &f64,
+            _: &f64,
+            _: &(),
+            parameters: &GlobalParameters,
+        ) -> Result<(f64, f64), CalcError> {
+            let force = parameters.require::<Stiffness>()?.0 * (ext_pos - own_pos);
+            Ok((force, -force))
+        }
+    }
+
+    #[test]
+    fn forward_parameters() {
+        let boxed: Box<dyn Interaction<f64, f64, f64>> = Box::new(ParametrizedSpring);
+        let parameters = GlobalParameters::new().insert(Stiffness(2.0));
+        let forces = boxed
+            .calculate_force_between_with_parameters(&0.0, &0.0, &1.5, &0.0, &(), &parameters)
+            .unwrap();
+        assert_eq!(forces, (3.0, -3.0));
+        assert!(boxed
+            .calculate_force_between_with_parameters(
+                &0.0,
+                &0.0,
+                &1.5,
+                &0.0,
+                &(),
+                &GlobalParameters::new()
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn forward_pressure() {
+        let sensor = PressureSensor::default();
+        let pressure = sensor.0.clone();
+        let mut boxed: Box<dyn Interaction<f64, f64, f64>> = Box::new(sensor);
+        assert_eq!(boxed.normal_force(&1.0, &0.0, &2.0).unwrap(), 2.0);
+        assert_eq!(boxed.normal_force(&0.0, &1.0, &2.0).unwrap(), -2.0);
+        boxed.react_to_pressure(3.0).unwrap();
+        assert_eq!(pressure.get(), 3.0);
+    }
 }
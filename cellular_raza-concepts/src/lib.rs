@@ -5,6 +5,8 @@
 //! To learn more about the math and philosophy behind these concepts please refer to
 //! [cellular-raza.com](https://cellular-raza.com).
 
+/// Age of cells and the senescence hook which is called after advancing it
+mod age;
 mod cell;
 mod cycle;
 mod domain;
@@ -19,13 +21,20 @@ pub mod domain_old;
 mod errors;
 mod interaction;
 mod mechanics;
+/// Registry of read-only parameters which are shared by all cells of a simulation
+mod parameters;
 mod plotting;
+/// Functions of the simulation time which are evaluated by cells and the domain
+mod time_function;
 
+pub use age::*;
 pub use cell::*;
 pub use cycle::*;
 pub use domain::*;
 pub use errors::*;
 pub use interaction::*;
 pub use mechanics::*;
+pub use parameters::*;
 pub use plotting::*;
 pub use reactions::*;
+pub use time_function::*;
@@ -8,6 +8,54 @@ pub trait Position<Pos> {
     fn set_pos(&mut self, position: &Pos);
 }
 
+/// Summarizes positions which are not given by a single point.
+///
+/// Mechanics such as vertex models or rods describe a cell by multiple points.
+/// Exporting results and plotting only requires a single representative point together with
+/// the spatial extent of the cell.
+/// ```
+/// # use cellular_raza_concepts::PositionLike;
+/// struct Rod {
+///     vertices: Vec<[f64; 2]>,
+/// }
+///
+/// impl PositionLike<f64, 2> for Rod {
+///     fn representative_point(&self) -> [f64; 2] {
+///         let n = self.vertices.len() as f64;
+///         let sum = self.vertices.iter().fold([0.0; 2], |a, v| [a[0] + v[0], a[1] + v[1]]);
+///         [sum[0] / n, sum[1] / n]
+///     }
+///
+///     fn bounding_box(&self) -> ([f64; 2], [f64; 2]) {
+///         self.vertices.iter().fold(
+///             ([f64::INFINITY; 2], [f64::NEG_INFINITY; 2]),
+///             |(l, u), v| ([l[0].min(v[0]), l[1].min(v[1])], [u[0].max(v[0]), u[1].max(v[1])]),
+///         )
+///     }
+/// }
+/// let rod = Rod { vertices: vec![[0.0, 0.0], [1.0, 0.0], [2.0, 1.0]] };
+/// assert_eq!(rod.representative_point(), [1.0, 1.0 / 3.0]);
+/// assert_eq!(rod.bounding_box(), ([0.0, 0.0], [2.0, 1.0]));
+/// assert_eq!([3.0, 4.0].bounding_box(), ([3.0, 4.0], [3.0, 4.0]));
+/// ```
+pub trait PositionLike<F, const D: usize> {
+    /// Point which represents the whole position, typically its center
+    fn representative_point(&self) -> [F; D];
+
+    /// Lower and upper corner of the axis-aligned box which contains the position
+    fn bounding_box(&self) -> ([F; D], [F; D]);
+}
+
+impl<F: Copy, const D: usize> PositionLike<F, D> for [F; D] {
+    fn representative_point(&self) -> [F; D] {
+        *self
+    }
+
+    fn bounding_box(&self) -> ([F; D], [F; D]) {
+        (*self, *self)
+    }
+}
+
 /// Methods for accessing the velocity of an agent
 pub trait Velocity<Vel> {
     /// Gets the cells current velocity.
@@ -18,16 +66,43 @@ pub trait Velocity<Vel> {
 
 /// Describes the position of a cell-agent and allows to calculate increments and set/get
 /// information of the agent.
-pub trait Mechanics<Pos, Vel, For, Float = f64> {
+///
+/// The random number generator `R` defaults to [ChaCha8Rng](rand_chacha::ChaCha8Rng).
+/// The `chili` backend uses the generator which is chosen by the `rng` argument of
+/// `run_simulation!` while the `cpu_os_threads` backend always uses the default.
+/// Implementations which are generic over any `R: rand::Rng` can additionally be called with
+/// other generators outside of the backends.
+/// Similarly, the simulation-wide parameters `Params` default to
+/// [GlobalParameters](crate::GlobalParameters).
+pub trait Mechanics<
+    Pos,
+    Vel,
+    For,
+    Float = f64,
+    R = rand_chacha::ChaCha8Rng,
+    Params = crate::GlobalParameters,
+>
+{
     /// Define a new random variable in case that the mechanics type contains a random aspect to
     /// its motion.
     /// By default this function does nothing.
     #[allow(unused)]
-    fn get_random_contribution(
+    fn get_random_contribution(&self, rng: &mut R, dt: Float) -> Result<(Pos, Vel), RngError>;
+
+    /// Identical to [get_random_contribution](Mechanics::get_random_contribution) but with
+    /// access to the simulation-wide parameters.
+    /// This allows to modify the noise over the course of a simulation, for example with an
+    /// annealing schedule.
+    /// Backends call this method which by default ignores the parameters.
+    #[allow(unused)]
+    fn get_random_contribution_with_parameters(
         &self,
-        rng: &mut rand_chacha::ChaCha8Rng,
+        rng: &mut R,
         dt: Float,
-    ) -> Result<(Pos, Vel), RngError>;
+        parameters: &Params,
+    ) -> Result<(Pos, Vel), RngError> {
+        self.get_random_contribution(rng, dt)
+    }
 
     /// Calculate the time-derivative of force and velocity given all the forces that act on the
     /// cell.
@@ -35,3 +110,83 @@ pub trait Mechanics<Pos, Vel, For, Float = f64> {
     /// [SubDomainForce](super::SubDomainForce) trait.
     fn calculate_increment(&self, force: For) -> Result<(Pos, Vel), CalcError>;
 }
+
+/// Methods for accessing the orientation of an agent.
+///
+/// Together with [AngularVelocity] and [RotationalMechanics], this trait describes optional
+/// rotational degrees of freedom.
+/// Agents such as self-propelled rods or ellipsoids can implement these traits in addition to
+/// the translational [Position], [Velocity] and [Mechanics] traits.
+pub trait Orientation<Ang> {
+    /// Gets the current orientation of the agent
+    fn orientation(&self) -> Ang;
+    /// Sets the current orientation of the agent
+    fn set_orientation(&mut self, orientation: &Ang);
+}
+
+/// Methods for accessing the angular velocity of an agent. See also [Orientation].
+pub trait AngularVelocity<AngVel> {
+    /// Gets the current angular velocity of the agent
+    fn angular_velocity(&self) -> AngVel;
+    /// Sets the current angular velocity of the agent
+    fn set_angular_velocity(&mut self, angular_velocity: &AngVel);
+}
+
+/// Describes the rotational motion of an agent given the torque acting on it.
+///
+/// This is the rotational analogue of the [Mechanics] trait.
+/// The torque is gathered from the
+/// [InteractionTorque](crate::InteractionTorque) trait.
+/// ```
+/// # use cellular_raza_concepts::*;
+/// // An overdamped particle in 2D whose orientation is given by a single angle
+/// struct ActiveParticle {
+///     angle: f64,
+///     rotational_damping: f64,
+/// }
+///
+/// impl<R> RotationalMechanics<f64, f64, f64, f64, R> for ActiveParticle {
+///     fn get_random_rotational_contribution(
+///         &self,
+///         _rng: &mut R,
+///         _dt: f64,
+///     ) -> Result<(f64, f64), RngError> {
+///         Ok((0.0, 0.0))
+///     }
+///
+///     fn calculate_rotational_increment(&self, torque: f64) -> Result<(f64, f64), CalcError> {
+///         Ok((torque / self.rotational_damping, 0.0))
+///     }
+/// }
+/// let particle = ActiveParticle { angle: 0.0, rotational_damping: 2.0 };
+/// let (dangle, _) = RotationalMechanics::<_, _, _, _, ()>::calculate_rotational_increment(
+///     &particle,
+///     1.0,
+/// )?;
+/// assert_eq!(dangle, 0.5);
+/// # Ok::<(), CalcError>(())
+/// ```
+pub trait RotationalMechanics<Ang, AngVel, Tor, Float = f64, R = rand_chacha::ChaCha8Rng> {
+    /// Random contribution to the orientation and angular velocity such as rotational
+    /// diffusion.
+    fn get_random_rotational_contribution(
+        &self,
+        rng: &mut R,
+        dt: Float,
+    ) -> Result<(Ang, AngVel), RngError>;
+
+    /// Calculate the time-derivative of orientation and angular velocity given the total torque
+    /// acting on the agent.
+    fn calculate_rotational_increment(&self, torque: Tor) -> Result<(Ang, AngVel), CalcError>;
+}
+
+/// Kinetic energy and momentum of an agent.
+///
+/// These quantities are used to validate solvers and boundary implementations.
+/// See also [Interaction::potential_energy_between](crate::Interaction::potential_energy_between).
+pub trait KineticEnergy<Mom, Float = f64> {
+    /// Kinetic energy $\frac{1}{2}mv^2$ of the agent
+    fn kinetic_energy(&self) -> Float;
+    /// Momentum $mv$ of the agent
+    fn momentum(&self) -> Mom;
+}
@@ -0,0 +1,119 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::CalcError;
+
+/// Values of parameters stored by their type together with the name of their type
+type ParameterValues = HashMap<TypeId, (&'static str, Arc<dyn Any + Send + Sync>)>;
+
+/// Read-only collection of simulation-wide parameters.
+///
+/// Parameters are stored by their type such that every type can be inserted at most once.
+/// It is thus recommended to wrap values in descriptive newtypes.
+/// A copy of this collection is distributed to every subdomain when setting up the simulation
+/// and passed as an explicit argument to the
+/// [calculate_force_between_with_parameters](crate::Interaction::calculate_force_between_with_parameters),
+/// [update_cycle_with_parameters](crate::Cycle::update_cycle_with_parameters) and
+/// [calculate_intracellular_increment_with_parameters](crate::Reactions::calculate_intracellular_increment_with_parameters)
+/// methods.
+/// This way, global knobs do not have to be duplicated into every cell.
+/// Copying is cheap since the stored values are shared between all copies.
+///
+/// Besides the stored values, the backend provides the current simulation time which is
+/// obtained via [simulation_time](GlobalParameters::simulation_time).
+/// ```
+/// # use cellular_raza_concepts::GlobalParameters;
+/// #[derive(Debug, PartialEq)]
+/// struct Temperature(f64);
+///
+/// let parameters = GlobalParameters::new()
+///     .insert(Temperature(310.15))
+///     .insert(String::from("experiment-1"));
+/// assert_eq!(parameters.get::<Temperature>(), Some(&Temperature(310.15)));
+/// assert_eq!(parameters.get::<String>().unwrap(), "experiment-1");
+/// assert!(parameters.get::<u8>().is_none());
+/// ```
+#[derive(Clone, Default)]
+pub struct GlobalParameters {
+    /// Stored values together with the name of their type
+    values: Arc<ParameterValues>,
+    /// Current time of the simulation which is not part of the stored values
+    time: Option<f64>,
+}
+
+impl GlobalParameters {
+    /// Constructs an empty collection of parameters
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts a new parameter and replaces any previous value of the same type
+    pub fn insert<T: Any + Send + Sync>(mut self, value: T) -> Self {
+        Arc::make_mut(&mut self.values).insert(
+            TypeId::of::<T>(),
+            (std::any::type_name::<T>(), Arc::new(value)),
+        );
+        self
+    }
+
+    /// Copy of these parameters at the given simulation time.
+    ///
+    /// The stored values are shared with the original such that no memory is allocated.
+    /// ```
+    /// # use cellular_raza_concepts::GlobalParameters;
+    /// let parameters = GlobalParameters::new().insert(3_u8);
+    /// assert_eq!(parameters.simulation_time::<f64>(), None);
+    /// let parameters = parameters.at_time(0.5);
+    /// assert_eq!(parameters.simulation_time(), Some(0.5_f32));
+    /// assert_eq!(parameters.get::<u8>(), Some(&3));
+    /// ```
+    pub fn at_time<F: num::ToPrimitive>(&self, time: F) -> Self {
+        Self {
+            values: self.values.clone(),
+            time: time.to_f64(),
+        }
+    }
+
+    /// Current time of the simulation if it was provided by the backend
+    pub fn simulation_time<F: num::NumCast>(&self) -> Option<F> {
+        self.time.and_then(F::from)
+    }
+
+    /// Obtains the parameter of type `T` if it was inserted previously
+    pub fn get<T: Any>(&self) -> Option<&T> {
+        self.values
+            .get(&TypeId::of::<T>())
+            .and_then(|(_, value)| value.downcast_ref::<T>())
+    }
+
+    /// Similar to [get](GlobalParameters::get) but returns an error if the parameter is
+    /// missing
+    pub fn require<T: Any>(&self) -> Result<&T, CalcError> {
+        self.get::<T>().ok_or(CalcError(format!(
+            "global parameter of type {} was not specified",
+            std::any::type_name::<T>()
+        )))
+    }
+
+    /// Number of stored parameters
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Checks if no parameters are stored
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl core::fmt::Debug for GlobalParameters {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut names: Vec<_> = self.values.values().map(|(name, _)| *name).collect();
+        names.sort();
+        f.debug_struct("GlobalParameters")
+            .field("types", &names)
+            .field("time", &self.time)
+            .finish()
+    }
+}
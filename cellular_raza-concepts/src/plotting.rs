@@ -1,16 +1,130 @@
 use plotters::backend::DrawingBackend;
 use plotters::coord::cartesian::Cartesian2d;
 use plotters::coord::types::RangedCoordf64;
+use plotters::coord::Shift;
 use plotters::prelude::BitMapBackend;
+use plotters::prelude::ChartBuilder;
 use plotters::prelude::DrawingArea;
 use plotters::prelude::SVGBackend;
+use plotters::style::RGBColor;
 
 use crate::errors::DrawingError;
+use serde::{Deserialize, Serialize};
+
+/// Configures the chart onto which the domain is drawn.
+///
+/// The default configuration only fills the background and does not draw any axes.
+/// ```
+/// # use cellular_raza_concepts::ChartConfig;
+/// let config = ChartConfig {
+///     axes: true,
+///     axis_descriptions: Some(("x [µm]".into(), "y [µm]".into())),
+///     ..ChartConfig::new(400)
+/// };
+/// assert_eq!(config.chart_size([0.0; 2], [100.0, 200.0]), (450, 850));
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ChartConfig {
+    /// Size in pixels of the shorter side of the plotted region
+    pub image_size: u32,
+    /// Color of the background
+    pub background: [u8; 3],
+    /// Margin in pixels around the chart
+    pub margin: u32,
+    /// Draw axes with ticks and labels at the left and bottom of the chart.
+    ///
+    /// Text can only be rendered by the [SVGBackend].
+    /// Bitmap images only contain the axis lines without ticks.
+    pub axes: bool,
+    /// Size in pixels of the area which is reserved for axes
+    pub axis_area_size: u32,
+    /// Descriptions of the horizontal and vertical axis
+    pub axis_descriptions: Option<(String, String)>,
+    /// Draw grid lines at every tick.
+    /// Like ticks, these are only drawn by backends which can render text.
+    pub mesh: bool,
+}
+
+impl ChartConfig {
+    /// Plain chart without axes whose shorter side has the given size.
+    pub fn new(image_size: u32) -> Self {
+        Self {
+            image_size,
+            background: [255; 3],
+            margin: 5,
+            axes: false,
+            axis_area_size: 40,
+            axis_descriptions: None,
+            mesh: false,
+        }
+    }
+
+    /// Total size of the image which keeps the aspect ratio of the plotted region.
+    pub fn chart_size(&self, min: [f64; 2], max: [f64; 2]) -> (u32, u32) {
+        let dx = (max[0] - min[0]).abs();
+        let dy = (max[1] - min[1]).abs();
+        let q = dx.min(dy);
+        let padding = 2 * self.margin + if self.axes { self.axis_area_size } else { 0 };
+        (
+            (self.image_size as f64 * dx / q).round() as u32 + padding,
+            (self.image_size as f64 * dy / q).round() as u32 + padding,
+        )
+    }
+
+    /// Builds the chart on the given drawing area and returns its plotting area.
+    ///
+    /// This can be used to implement [CreatePlottingRoot] for any [DrawingBackend].
+    /// The size of the drawing area should be obtained from [ChartConfig::chart_size].
+    /// Tick labels and axis descriptions are only drawn if the backend is able to
+    /// `render_text` which is the case for the [SVGBackend] but not for the [BitMapBackend].
+    pub fn build_chart<Db>(
+        &self,
+        area: DrawingArea<Db, Shift>,
+        min: [f64; 2],
+        max: [f64; 2],
+        render_text: bool,
+    ) -> Result<DrawingArea<Db, Cartesian2d<RangedCoordf64, RangedCoordf64>>, DrawingError>
+    where
+        Db: DrawingBackend,
+    {
+        let [r, g, b] = self.background;
+        area.fill(&RGBColor(r, g, b))?;
+        let axis_area_size = if self.axes { self.axis_area_size } else { 0 };
+        let mut chart = ChartBuilder::on(&area)
+            .margin(self.margin)
+            .x_label_area_size(axis_area_size)
+            .y_label_area_size(axis_area_size)
+            .build_cartesian_2d(min[0]..max[0], min[1]..max[1])?;
+        if self.axes || self.mesh {
+            let mut mesh = chart.configure_mesh();
+            if !self.mesh {
+                mesh.disable_mesh();
+            }
+            if !self.axes {
+                mesh.disable_axes();
+            }
+            if !self.axes || !render_text {
+                mesh.x_labels(0).y_labels(0);
+            }
+            if let (true, true, Some((x_desc, y_desc))) =
+                (self.axes, render_text, &self.axis_descriptions)
+            {
+                mesh.x_desc(x_desc).y_desc(y_desc);
+            }
+            mesh.draw()?;
+        }
+        Ok(chart.plotting_area().clone())
+    }
+}
 
 /// Creates a new plotting root which can then be drawn upon.
-pub trait CreatePlottingRoot //, E>
-// E: std::error::Error + std::marker::Sync + std::marker::Send,
-{
+///
+/// Only [CreatePlottingRoot::create_bitmap_root] is required.
+/// Domains which support vector graphics additionally implement
+/// [CreatePlottingRoot::create_svg_root], typically via [ChartConfig::build_chart].
+/// Plotters does not provide a PDF backend.
+/// SVG files can be converted to PDF with external tools such as `rsvg-convert` or `inkscape`.
+pub trait CreatePlottingRoot {
     /// Creates a bitmap plotting root.
     fn create_bitmap_root<'a, T>(
         &self,
@@ -22,15 +136,26 @@ pub trait CreatePlottingRoot //, E>
     >
     where
         T: AsRef<std::path::Path> + ?Sized;
-    // TODO implement this as well
-    /* fn create_svg_root<'a>(
+
+    /// Creates a vector graphics plotting root with the given chart configuration.
+    ///
+    /// By default, this returns an error.
+    fn create_svg_root<'a, T>(
         &self,
-        image_size: u32,
-        filename: &'a String,
+        config: &ChartConfig,
+        filename: &'a T,
     ) -> Result<
         DrawingArea<SVGBackend<'a>, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
         DrawingError,
-    >;*/
+    >
+    where
+        T: AsRef<std::path::Path> + ?Sized,
+    {
+        let _ = (config, filename);
+        Err(DrawingError(
+            "this domain does not support plotting to vector graphics".into(),
+        ))
+    }
 }
 
 /// Allows elements of the simulation such as cells and voxels to draw themselves onto a plotting root.
@@ -63,8 +188,121 @@ pub trait PlotSelf {
     }
 }
 
+/// Allows extracellular fields of subdomains to be drawn as heatmaps.
+///
+/// The field of every species is described by rectangles which tile the subdomain.
+/// Each rectangle is drawn in a single color which is determined by its value.
+/// ```
+/// # use cellular_raza_concepts::PlotField;
+/// struct Grid {
+///     dx: f64,
+///     concentrations: Vec<[f64; 2]>,
+/// }
+///
+/// impl PlotField for Grid {
+///     fn n_species(&self) -> usize {
+///         2
+///     }
+///
+///     fn field_rectangles(&self, species: usize) -> Vec<([f64; 2], [f64; 2], f64)> {
+///         self.concentrations
+///             .iter()
+///             .enumerate()
+///             .map(|(i, c)| {
+///                 let x = i as f64 * self.dx;
+///                 ([x, 0.0], [x + self.dx, self.dx], c[species])
+///             })
+///             .collect()
+///     }
+/// }
+/// # let grid = Grid { dx: 1.0, concentrations: vec![[0.0, 1.0]; 3] };
+/// # assert_eq!(grid.field_rectangles(1)[2], ([2.0, 0.0], [3.0, 1.0], 1.0));
+/// ```
+pub trait PlotField {
+    /// Number of species which can be plotted
+    fn n_species(&self) -> usize;
+
+    /// Rectangles given by their lower and upper corner together with the value of the field
+    /// for the given species
+    fn field_rectangles(&self, species: usize) -> Vec<([f64; 2], [f64; 2], f64)>;
+}
+
+/// Shape of the glyph which represents a cell in plots. See [PlotCell].
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+pub enum CellShape {
+    /// Circle around the position of the cell
+    #[default]
+    Circle,
+    /// Axis-aligned square with half side length given by the radius
+    Square,
+    /// Upwards pointing triangle inscribed in the circle of the given radius
+    Triangle,
+}
+
+/// Describes how a cell is drawn as a simple glyph.
+///
+/// In contrast to [PlotSelf], implementors do not need to write any plotting code themselves.
+/// Only the position, radius, color and shape of the glyph are derived from the state of the
+/// cell.
+/// ```
+/// # use cellular_raza_concepts::{CellShape, PlotCell};
+/// struct Bacterium {
+///     pos: [f64; 2],
+///     length: f64,
+///     infected: bool,
+/// }
+///
+/// impl PlotCell for Bacterium {
+///     fn glyph_position(&self) -> [f64; 2] {
+///         self.pos
+///     }
+///
+///     fn glyph_radius(&self) -> f64 {
+///         0.5 * self.length
+///     }
+///
+///     fn glyph_color(&self) -> [u8; 3] {
+///         match self.infected {
+///             true => [214, 39, 40],
+///             false => [31, 119, 180],
+///         }
+///     }
+/// }
+/// # let b = Bacterium { pos: [1.0, 2.0], length: 2.0, infected: true };
+/// # assert_eq!(b.glyph_shape(), CellShape::Circle);
+/// # assert_eq!(b.glyph_color(), [214, 39, 40]);
+/// ```
+pub trait PlotCell {
+    /// Position of the center of the glyph
+    fn glyph_position(&self) -> [f64; 2];
+
+    /// Radius of the glyph in the same units as the position
+    fn glyph_radius(&self) -> f64;
+
+    /// Fill color of the glyph as RGB values
+    fn glyph_color(&self) -> [u8; 3] {
+        [31, 119, 180]
+    }
+
+    /// Shape of the glyph
+    fn glyph_shape(&self) -> CellShape {
+        CellShape::Circle
+    }
+
+    /// Lower and upper corner of the region which needs to be visible in order to show the
+    /// whole cell.
+    ///
+    /// Defaults to the square around the glyph.
+    /// Cells with extended shapes such as rods should return the
+    /// [bounding_box](crate::PositionLike::bounding_box) of their position.
+    fn glyph_bounding_box(&self) -> ([f64; 2], [f64; 2]) {
+        let [x, y] = self.glyph_position();
+        let r = self.glyph_radius();
+        ([x - r, y - r], [x + r, y + r])
+    }
+}
+
 use crate::cell::CellAgentBox;
-use serde::{Deserialize, Serialize};
 
 impl<Cel> PlotSelf for CellAgentBox<Cel>
 where
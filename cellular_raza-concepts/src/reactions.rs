@@ -1,4 +1,5 @@
 use crate::CalcError;
+use crate::GlobalParameters;
 use crate::Position;
 
 /// Setter and Getter for intracellular values of a cellagent.
@@ -15,11 +16,25 @@ pub trait Intracellular<Ri> {
 /// throughout the entire cell.
 /// We can then describe them by a list of values $\vec{u}=(u_0,\dots,u_N)$.
 // TODO implement random contributions
-pub trait Reactions<Ri/*, Float = f64*/>: Intracellular<Ri> {
+pub trait Reactions<Ri, Params = GlobalParameters>: Intracellular<Ri> {
     /// Calculates the purely intracellular reaction increment.
     /// Users who implement this trait should always use the given argument instead of relying on
     /// values obtained via `self`.
     fn calculate_intracellular_increment(&self, intracellular: &Ri) -> Result<Ri, CalcError>;
+
+    /// Identical to
+    /// [calculate_intracellular_increment](Reactions::calculate_intracellular_increment) but
+    /// with access to the simulation-wide parameters which are given as [GlobalParameters] by
+    /// default.
+    /// Backends call this method which by default ignores the parameters.
+    #[allow(unused)]
+    fn calculate_intracellular_increment_with_parameters(
+        &self,
+        intracellular: &Ri,
+        parameters: &Params,
+    ) -> Result<Ri, CalcError> {
+        self.calculate_intracellular_increment(intracellular)
+    }
 }
 
 /// This trait models extracellular reactions which interact with agents.
@@ -0,0 +1,274 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{CalcError, GlobalParameters};
+
+/// Value of a parameter which changes over the course of the simulation.
+///
+/// Parameters of building blocks such as the strength of a potential or a degradation rate can
+/// be declared as functions of time.
+/// They are evaluated at the
+/// [simulation time](GlobalParameters::simulation_time) which is provided by the backend via
+/// the [GlobalParameters].
+/// Custom functions can not be serialized directly.
+/// They are thus referred to by an identifier and looked up in a [TimeFunctionRegistry] which
+/// also needs to be inserted into the [GlobalParameters].
+///
+/// ```
+/// # use cellular_raza_concepts::*;
+/// let ramp = TimeFunction::LinearRamp {
+///     t_start: 1.0,
+///     t_end: 3.0,
+///     value_start: 0.0,
+///     value_end: 10.0,
+/// };
+/// assert_eq!(ramp.value_at(0.0, None)?, 0.0);
+/// assert_eq!(ramp.value_at(2.0, None)?, 5.0);
+/// assert_eq!(ramp.value_at(4.0, None)?, 10.0);
+///
+/// // Custom functions are looked up by their identifier
+/// let parameters = GlobalParameters::new()
+///     .insert(TimeFunctionRegistry::new().register("square", |t: f64| t * t))
+///     .at_time(2.0);
+/// let custom = TimeFunction::<f64>::Custom("square".to_owned());
+/// assert_eq!(custom.evaluate(&parameters)?, 4.0);
+/// # Ok::<(), CalcError>(())
+/// ```
+///
+/// The function can be used to implement time-dependent aspects.
+/// ```
+/// # use cellular_raza_concepts::*;
+/// # use rand::Rng;
+/// struct DivisionCycle {
+///     division_rate: TimeFunction<f64>,
+/// }
+///
+/// impl Cycle<DivisionCycle> for DivisionCycle {
+///     fn update_cycle(
+///         rng: &mut rand_chacha::ChaCha8Rng,
+///         dt: &f64,
+///         cell: &mut DivisionCycle,
+///     ) -> Option<CycleEvent> {
+///         Self::update_cycle_with_parameters(rng, dt, cell, &GlobalParameters::new())
+///     }
+///
+///     fn update_cycle_with_parameters(
+///         rng: &mut rand_chacha::ChaCha8Rng,
+///         dt: &f64,
+///         cell: &mut DivisionCycle,
+///         parameters: &GlobalParameters,
+///     ) -> Option<CycleEvent> {
+///         let rate = cell.division_rate.evaluate(parameters).ok()?;
+///         if rng.gen_bool((rate * dt).clamp(0.0, 1.0)) {
+///             Some(CycleEvent::Division)
+///         } else {
+///             None
+///         }
+///     }
+///
+///     fn divide(
+///         _rng: &mut rand_chacha::ChaCha8Rng,
+///         cell: &mut DivisionCycle,
+///     ) -> Result<DivisionCycle, DivisionError> {
+///         Ok(DivisionCycle {
+///             division_rate: cell.division_rate.clone(),
+///         })
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum TimeFunction<F> {
+    /// Value which does not change over time
+    Constant(F),
+    /// Linear interpolation between `value_start` at `t_start` and `value_end` at `t_end`.
+    /// The value is held constant before and after the ramp.
+    LinearRamp {
+        /// Time at which the ramp starts
+        t_start: F,
+        /// Time at which the ramp ends
+        t_end: F,
+        /// Value before and at the start of the ramp
+        value_start: F,
+        /// Value at the end of and after the ramp
+        value_end: F,
+    },
+    /// Sudden change of the value at `t_switch`
+    Step {
+        /// Time at which the value changes
+        t_switch: F,
+        /// Value before the switch
+        value_before: F,
+        /// Value at and after the switch
+        value_after: F,
+    },
+    /// Custom function which is stored in a [TimeFunctionRegistry] under the given identifier
+    Custom(String),
+}
+
+impl<F: num::Float> TimeFunction<F> {
+    /// Evaluates the function at the given time.
+    ///
+    /// Returns an error if the function is [TimeFunction::Custom] and could not be found in the
+    /// registry.
+    pub fn value_at(
+        &self,
+        time: F,
+        registry: Option<&TimeFunctionRegistry<F>>,
+    ) -> Result<F, CalcError> {
+        Ok(match self {
+            TimeFunction::Constant(value) => *value,
+            TimeFunction::LinearRamp {
+                t_start,
+                t_end,
+                value_start,
+                value_end,
+            } => {
+                if time <= *t_start {
+                    *value_start
+                } else if time >= *t_end {
+                    *value_end
+                } else {
+                    let q = (time - *t_start) / (*t_end - *t_start);
+                    *value_start + q * (*value_end - *value_start)
+                }
+            }
+            TimeFunction::Step {
+                t_switch,
+                value_before,
+                value_after,
+            } => {
+                if time < *t_switch {
+                    *value_before
+                } else {
+                    *value_after
+                }
+            }
+            TimeFunction::Custom(id) => {
+                let function = registry
+                    .and_then(|registry| registry.get(id))
+                    .ok_or(CalcError(format!(
+                        "time function with id \"{id}\" was not registered"
+                    )))?;
+                function(time)
+            }
+        })
+    }
+
+    /// Evaluates the function at the current
+    /// [simulation time](GlobalParameters::simulation_time) of the [GlobalParameters].
+    ///
+    /// [TimeFunction::Constant] can be evaluated without any parameters.
+    pub fn evaluate(&self, parameters: &GlobalParameters) -> Result<F, CalcError>
+    where
+        F: Send + Sync + 'static,
+    {
+        if let TimeFunction::Constant(value) = self {
+            return Ok(*value);
+        }
+        let time = parameters
+            .simulation_time()
+            .ok_or(CalcError("simulation time was not provided".to_owned()))?;
+        self.value_at(time, parameters.get::<TimeFunctionRegistry<F>>())
+    }
+}
+
+impl<F> From<F> for TimeFunction<F> {
+    fn from(value: F) -> Self {
+        TimeFunction::Constant(value)
+    }
+}
+
+/// Collection of custom time functions referred to by [TimeFunction::Custom]
+pub struct TimeFunctionRegistry<F> {
+    /// Maps the identifier of a function to its implementation
+    functions: HashMap<String, Arc<dyn Fn(F) -> F + Send + Sync>>,
+}
+
+impl<F> TimeFunctionRegistry<F> {
+    /// Constructs an empty registry
+    pub fn new() -> Self {
+        Self {
+            functions: HashMap::new(),
+        }
+    }
+
+    /// Registers a new function under the given identifier and replaces any previous function
+    /// with the same identifier.
+    pub fn register(
+        mut self,
+        id: impl Into<String>,
+        function: impl Fn(F) -> F + Send + Sync + 'static,
+    ) -> Self {
+        self.functions.insert(id.into(), Arc::new(function));
+        self
+    }
+
+    /// Obtains the function with the given identifier
+    pub fn get(&self, id: &str) -> Option<&(dyn Fn(F) -> F + Send + Sync)> {
+        self.functions.get(id).map(|function| function.as_ref())
+    }
+}
+
+impl<F> Clone for TimeFunctionRegistry<F> {
+    fn clone(&self) -> Self {
+        Self {
+            functions: self.functions.clone(),
+        }
+    }
+}
+
+impl<F> Default for TimeFunctionRegistry<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F> core::fmt::Debug for TimeFunctionRegistry<F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut ids: Vec<_> = self.functions.keys().collect();
+        ids.sort();
+        f.debug_struct("TimeFunctionRegistry")
+            .field("ids", &ids)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test_time_function {
+    use super::*;
+
+    #[test]
+    fn step_switches_at_time() {
+        let step = TimeFunction::Step {
+            t_switch: 2.0,
+            value_before: 1.0,
+            value_after: 3.0,
+        };
+        assert_eq!(step.value_at(1.9, None).unwrap(), 1.0);
+        assert_eq!(step.value_at(2.0, None).unwrap(), 3.0);
+    }
+
+    #[test]
+    fn evaluate_requires_time() {
+        let parameters = GlobalParameters::new();
+        assert_eq!(TimeFunction::from(4.0).evaluate(&parameters).unwrap(), 4.0);
+        let step = TimeFunction::Step {
+            t_switch: 2.0,
+            value_before: 1.0,
+            value_after: 3.0,
+        };
+        assert!(step.evaluate(&parameters).is_err());
+        let parameters = parameters.at_time(5.0);
+        assert_eq!(step.evaluate(&parameters).unwrap(), 3.0);
+    }
+
+    #[test]
+    fn missing_custom_function() {
+        let custom = TimeFunction::Custom("missing".to_owned());
+        let registry = TimeFunctionRegistry::new().register("other", |t: f32| t);
+        assert!(custom.value_at(1.0, Some(&registry)).is_err());
+        assert!(custom.value_at(1.0, None).is_err());
+    }
+}
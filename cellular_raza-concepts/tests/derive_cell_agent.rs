@@ -23,6 +23,8 @@ fn derive_cycle() {
     struct NewAgent2(#[Cycle] Agent);
 
     let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(1);
+    let mut new_agent = NewAgent1 { _old_agent: Agent };
+    assert!(<NewAgent1 as Cycle>::update_cycle(&mut rng, &0.1, &mut new_agent).is_none());
     let mut new_agent = NewAgent2(Agent);
     assert!(<NewAgent2 as Cycle>::update_cycle(&mut rng, &0.1, &mut new_agent).is_none());
 }
@@ -111,7 +113,7 @@ fn derive_interaction() {
     use cellular_raza_concepts_derive::CellAgent;
     struct InteractionModel;
     impl cellular_raza_concepts::Interaction<f32, f32, f32> for InteractionModel {
-        fn get_interaction_information(&self) -> () {}
+        fn get_interaction_information(&self) {}
         fn calculate_force_between(
             &self,
             _own_pos: &f32,
@@ -146,7 +148,7 @@ fn derive_interaction_generics() {
 
     impl<const D: usize> Interaction<f32, f32, f32, [usize; D]> for InteractionModel<D> {
         fn get_interaction_information(&self) -> [usize; D] {
-            self.index.clone()
+            self.index
         }
         fn calculate_force_between(
             &self,
@@ -171,3 +173,275 @@ fn derive_interaction_generics() {
     };
     assert_eq!(my_agent.get_interaction_information(), [1, 2, 3]);
 }
+
+#[test]
+fn derive_interaction_force() {
+    use cellular_raza_concepts::*;
+    use cellular_raza_concepts_derive::CellAgent;
+
+    struct Spring;
+    impl InteractionForce for Spring {
+        type Pos = f32;
+        type Vel = f32;
+        type Force = f32;
+        type Inf = ();
+
+        fn get_interaction_information(&self) {}
+        fn calculate_force_between(
+            &self,
+            own_pos: &f32,
+            _own_vel: &f32,
+            ext_pos: &f32,
+            _ext_vel: &f32,
+            _ext_info: &(),
+        ) -> Result<(f32, f32), CalcError> {
+            Ok((ext_pos - own_pos, own_pos - ext_pos))
+        }
+    }
+
+    #[derive(CellAgent)]
+    struct NewAgent {
+        #[InteractionForce]
+        interaction: Spring,
+    }
+
+    let agent = NewAgent {
+        interaction: Spring,
+    };
+    let (f1, f2) =
+        Interaction::<f32, f32, f32>::calculate_force_between(&agent, &0.0, &0.0, &2.0, &0.0, &())
+            .unwrap();
+    assert_eq!((f1, f2), (2.0, -2.0));
+    assert!(!Interaction::<f32, f32, f32>::reacts_to_neighbors(&agent));
+}
+
+#[test]
+fn derive_interaction_reacts_to_neighbors() {
+    use cellular_raza_concepts::*;
+    use cellular_raza_concepts_derive::CellAgent;
+
+    struct Passive;
+    impl Interaction<f32, f32, f32> for Passive {
+        fn get_interaction_information(&self) {}
+        fn calculate_force_between(
+            &self,
+            _own_pos: &f32,
+            _own_vel: &f32,
+            _ext_pos: &f32,
+            _ext_vel: &f32,
+            _ext_info: &(),
+        ) -> Result<(f32, f32), CalcError> {
+            Ok((0.0, 0.0))
+        }
+
+        fn reacts_to_neighbors(&self) -> bool {
+            false
+        }
+    }
+
+    #[derive(CellAgent)]
+    struct NewAgent {
+        #[Interaction]
+        interaction: Passive,
+    }
+
+    let agent = NewAgent {
+        interaction: Passive,
+    };
+    assert!(!Interaction::<f32, f32, f32>::reacts_to_neighbors(&agent));
+}
+
+#[test]
+fn derive_interaction_force_neighbour_reactive() {
+    use cellular_raza_concepts::*;
+    use cellular_raza_concepts_derive::CellAgent;
+
+    struct Spring;
+    impl InteractionForce for Spring {
+        type Pos = f32;
+        type Vel = f32;
+        type Force = f32;
+        type Inf = ();
+
+        fn get_interaction_information(&self) {}
+        fn calculate_force_between(
+            &self,
+            own_pos: &f32,
+            _own_vel: &f32,
+            ext_pos: &f32,
+            _ext_vel: &f32,
+            _ext_info: &(),
+        ) -> Result<(f32, f32), CalcError> {
+            Ok((ext_pos - own_pos, own_pos - ext_pos))
+        }
+    }
+
+    struct ContactCounter {
+        range: f32,
+        neighbors: usize,
+    }
+    impl NeighbourReactive<f32> for ContactCounter {
+        fn is_neighbor(&self, own_pos: &f32, ext_pos: &f32, _: &()) -> Result<bool, CalcError> {
+            Ok((own_pos - ext_pos).abs() <= self.range)
+        }
+        fn react_to_neighbors(&mut self, neighbors: usize) -> Result<(), CalcError> {
+            self.neighbors = neighbors;
+            Ok(())
+        }
+    }
+
+    #[derive(CellAgent)]
+    struct NewAgent {
+        #[InteractionForce]
+        interaction: Spring,
+        #[NeighbourReactive]
+        counter: ContactCounter,
+    }
+
+    let mut agent = NewAgent {
+        interaction: Spring,
+        counter: ContactCounter {
+            range: 1.0,
+            neighbors: 0,
+        },
+    };
+    use Interaction as I;
+    let (f1, _) =
+        I::<f32, f32, f32>::calculate_force_between(&agent, &0.0, &0.0, &2.0, &0.0, &()).unwrap();
+    assert_eq!(f1, 2.0);
+    assert!(I::<f32, f32, f32>::reacts_to_neighbors(&agent));
+    assert!(I::<f32, f32, f32>::is_neighbor(&agent, &0.0, &0.5, &()).unwrap());
+    assert!(!I::<f32, f32, f32>::is_neighbor(&agent, &0.0, &1.5, &()).unwrap());
+    I::<f32, f32, f32>::react_to_neighbors(&mut agent, 3).unwrap();
+    assert_eq!(agent.counter.neighbors, 3);
+}
+
+#[test]
+fn derive_custom_data_generics() {
+    use cellular_raza_concepts::CustomData;
+    use cellular_raza_concepts_derive::CellAgent;
+
+    #[derive(CellAgent)]
+    struct NewAgent<T>
+    where
+        T: Clone,
+    {
+        #[CustomData]
+        lineage: Vec<T>,
+    }
+
+    let mut my_agent = NewAgent {
+        lineage: vec![0u64],
+    };
+    my_agent.custom_data_mut().push(3);
+    assert_eq!(my_agent.custom_data(), &vec![0, 3]);
+}
+
+#[test]
+fn derive_interaction_with_parameters() {
+    use cellular_raza_concepts::{CalcError, GlobalParameters, Interaction};
+    use cellular_raza_concepts_derive::CellAgent;
+
+    struct Strength(f32);
+
+    struct InteractionModel;
+
+    impl Interaction<f32, f32, f32> for InteractionModel {
+        fn get_interaction_information(&self) {}
+
+        fn calculate_force_between(
+            &self,
+            own_pos: &f32,
+            _own_vel: &f32,
+            ext_pos: &f32,
+            _ext_vel: &f32,
+            _ext_info: &(),
+        ) -> Result<(f32, f32), CalcError> {
+            Ok((own_pos - ext_pos, ext_pos - own_pos))
+        }
+
+        fn calculate_force_between_with_parameters(
+            &self,
+            own_pos: &f32,
+            own_vel: &f32,
+            ext_pos: &f32,
+            ext_vel: &f32,
+            ext_info: &(),
+            parameters: &GlobalParameters,
+        ) -> Result<(f32, f32), CalcError> {
+            let strength = parameters.require::<Strength>()?.0;
+            let (f1, f2) =
+                self.calculate_force_between(own_pos, own_vel, ext_pos, ext_vel, ext_info)?;
+            Ok((strength * f1, strength * f2))
+        }
+    }
+
+    #[derive(CellAgent)]
+    struct NewAgent {
+        #[Interaction]
+        interaction: InteractionModel,
+    }
+
+    let my_agent = NewAgent {
+        interaction: InteractionModel,
+    };
+    let parameters = GlobalParameters::new().insert(Strength(3.0));
+    let (f1, f2) = my_agent
+        .calculate_force_between_with_parameters(&1.0, &0.0, &0.0, &0.0, &(), &parameters)
+        .unwrap();
+    assert_eq!((f1, f2), (3.0, -3.0));
+    assert!(my_agent
+        .calculate_force_between_with_parameters(
+            &1.0,
+            &0.0,
+            &0.0,
+            &0.0,
+            &(),
+            &GlobalParameters::new()
+        )
+        .is_err());
+}
+
+#[test]
+fn derive_age() {
+    use cellular_raza_concepts::*;
+
+    struct Clock {
+        age: f32,
+        senescence_age: f32,
+    }
+
+    impl Age<f32> for Clock {
+        fn get_age(&self) -> f32 {
+            self.age
+        }
+
+        fn set_age(&mut self, age: f32) {
+            self.age = age;
+        }
+
+        fn is_senescent(&self) -> bool {
+            self.age >= self.senescence_age
+        }
+    }
+
+    #[derive(CellAgent)]
+    struct NewAgent {
+        #[Age]
+        clock: Clock,
+    }
+
+    let mut agent = NewAgent {
+        clock: Clock {
+            age: 0.0,
+            senescence_age: 2.0,
+        },
+    };
+    for _ in 0..4 {
+        agent.set_age(agent.get_age() + 0.5);
+        agent.update_senescence().unwrap();
+    }
+    assert_eq!(agent.get_age(), 2.0);
+    assert!(agent.is_senescent());
+    assert_eq!(agent.clock.age, 2.0);
+}
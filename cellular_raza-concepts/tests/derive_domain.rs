@@ -161,8 +161,8 @@ impl DomainCreateSubDomains<MySubDomain> for MyDomain {
             (
                 0,
                 MySubDomain {
-                    x_min: self.x_min.clone() + n as f32 * dx,
-                    x_max: self.x_min.clone() + (n + 1) as f32 * dx,
+                    x_min: self.x_min + n as f32 * dx,
+                    x_max: self.x_min + (n + 1) as f32 * dx,
                 },
                 Vec::new(),
             )
@@ -198,3 +198,62 @@ fn derive_create_subdomains() {
         .collect();
     assert_eq!(new_domains.len(), n_subdomains);
 }
+
+#[derive(Domain)]
+struct DerivedDomain5 {
+    #[DomainRngSeed]
+    #[SortCells]
+    #[DomainCreateSubDomains]
+    domain: MyDomain,
+    #[allow(unused)]
+    parameter: f32,
+}
+
+#[test]
+fn derive_total_from_field() {
+    let derived_domain = DerivedDomain5 {
+        domain: MyDomain {
+            x_min: 0.0,
+            x_max: 10.0,
+        },
+        parameter: 1.0,
+    };
+    let decomposed_domain: DecomposedDomain<_, MySubDomain, _> = derived_domain
+        .decompose(
+            1.try_into().unwrap(),
+            vec![Agent { pos: 1.0 }, Agent { pos: 2.0 }],
+        )
+        .unwrap();
+    assert_eq!(decomposed_domain.rng_seed, 0);
+    assert_eq!(decomposed_domain.index_subdomain_cells.len(), 1);
+    assert_eq!(decomposed_domain.index_subdomain_cells[0].2.len(), 2);
+}
+
+#[derive(Domain)]
+struct DerivedDomain6 {
+    #[DomainRngSeed]
+    #[DomainPartialDerive]
+    #[SortCells]
+    #[DomainCreateSubDomains]
+    domain: MyDomain,
+}
+
+#[test]
+fn partial_derive_on_field_is_ignored() {
+    let derived_domain = DerivedDomain6 {
+        domain: MyDomain {
+            x_min: 0.0,
+            x_max: 10.0,
+        },
+    };
+    let decomposed_domain: DecomposedDomain<_, MySubDomain, _> = derived_domain
+        .decompose(1.try_into().unwrap(), vec![Agent { pos: 1.0 }])
+        .unwrap();
+    assert_eq!(decomposed_domain.index_subdomain_cells[0].2.len(), 1);
+}
+
+#[test]
+fn derive_domain_errors() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/domain_*.rs");
+}
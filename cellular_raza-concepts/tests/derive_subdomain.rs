@@ -59,3 +59,117 @@ fn derive_mechanics_tuple() {
     assert_eq!(x, 0.0);
     assert_eq!(y, 1.0);
 }
+
+struct DomainForce<F> {
+    damping: F,
+}
+
+impl<F> SubDomainForce<F, F, F> for DomainForce<F>
+where
+    F: Copy + core::ops::Mul<Output = F> + core::ops::Neg<Output = F>,
+{
+    fn calculate_custom_force(&self, _pos: &F, vel: &F) -> Result<F, CalcError> {
+        Ok(-self.damping * *vel)
+    }
+}
+
+struct DomainReactions<const N: usize> {
+    values: [f64; N],
+}
+
+impl<const N: usize> SubDomainReactions<f64, [f64; N], f64> for DomainReactions<N> {
+    type NeighborValue = [f64; N];
+    type BorderInfo = ();
+
+    fn treat_increments<I, J>(&mut self, neighbors: I, sources: J) -> Result<(), CalcError>
+    where
+        I: IntoIterator<Item = Self::NeighborValue>,
+        J: IntoIterator<Item = (f64, [f64; N])>,
+    {
+        for increment in neighbors
+            .into_iter()
+            .chain(sources.into_iter().map(|(_, increment)| increment))
+        {
+            self.values
+                .iter_mut()
+                .zip(increment)
+                .for_each(|(value, increment)| *value += increment);
+        }
+        Ok(())
+    }
+
+    fn update_fluid_dynamics(&mut self, _dt: f64) -> Result<(), CalcError> {
+        Ok(())
+    }
+
+    fn get_extracellular_at_pos(&self, _pos: &f64) -> Result<[f64; N], CalcError> {
+        Ok(self.values)
+    }
+
+    fn set_extracellular_at_pos(&mut self, _pos: &f64, value: [f64; N]) -> Result<(), CalcError> {
+        self.values = value;
+        Ok(())
+    }
+
+    fn get_neighbor_value(&self, _border_info: ()) -> [f64; N] {
+        self.values
+    }
+
+    fn get_border_info(&self) {}
+}
+
+#[derive(SubDomain)]
+struct DeriveDomainGeneric<F, const N: usize>
+where
+    F: Copy,
+{
+    #[Base]
+    #[Mechanics]
+    mechanics: DomainMechanics,
+    #[Force]
+    force: DomainForce<F>,
+    #[Reactions]
+    reactions: DomainReactions<N>,
+}
+
+#[test]
+fn derive_multiple_aspects_generic() {
+    let mut derived_domain = DeriveDomainGeneric {
+        mechanics: DomainMechanics,
+        force: DomainForce { damping: 0.5 },
+        reactions: DomainReactions { values: [1.0; 3] },
+    };
+    assert!(derived_domain.get_all_indices().is_empty());
+    let mut x = 1000.0;
+    let mut y = 1000.0;
+    derived_domain.apply_boundary(&mut x, &mut y).unwrap();
+    assert_eq!(x, 0.0);
+    assert_eq!(
+        derived_domain.calculate_custom_force(&x, &2.0).unwrap(),
+        -1.0
+    );
+
+    derived_domain.get_border_info();
+    let neighbor_value = derived_domain.get_neighbor_value(());
+    derived_domain
+        .treat_increments([neighbor_value], [(0.0, [0.5, 0.0, 1.0])])
+        .unwrap();
+    derived_domain.update_fluid_dynamics(0.1).unwrap();
+    assert_eq!(
+        derived_domain.get_extracellular_at_pos(&0.0).unwrap(),
+        [2.5, 2.0, 3.0]
+    );
+    derived_domain
+        .set_extracellular_at_pos(&0.0, [0.0; 3])
+        .unwrap();
+    assert_eq!(
+        derived_domain.get_extracellular_at_pos(&0.0).unwrap(),
+        [0.0; 3]
+    );
+}
+
+#[test]
+fn derive_errors() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/subdomain_*.rs");
+}
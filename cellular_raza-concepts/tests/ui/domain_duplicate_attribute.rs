@@ -0,0 +1,20 @@
+use cellular_raza_concepts::*;
+
+struct InnerDomain;
+
+impl DomainRngSeed for InnerDomain {
+    fn get_rng_seed(&self) -> u64 {
+        0
+    }
+}
+
+#[derive(Domain)]
+#[DomainPartialDerive]
+struct DerivedDomain {
+    #[DomainRngSeed]
+    domain1: InnerDomain,
+    #[DomainRngSeed]
+    domain2: InnerDomain,
+}
+
+fn main() {}
@@ -0,0 +1,32 @@
+use cellular_raza_concepts::*;
+
+struct DomainMechanics;
+
+impl SubDomain for DomainMechanics {
+    type VoxelIndex = usize;
+
+    fn get_neighbor_voxel_indices(&self, _voxel_index: &usize) -> Vec<usize> {
+        Vec::new()
+    }
+
+    fn get_all_indices(&self) -> Vec<usize> {
+        Vec::new()
+    }
+}
+
+impl SubDomainMechanics<f64, f64> for DomainMechanics {
+    fn apply_boundary(&self, _pos: &mut f64, _vel: &mut f64) -> Result<(), BoundaryError> {
+        Ok(())
+    }
+}
+
+#[derive(SubDomain)]
+struct DeriveDomain {
+    #[Base]
+    #[Mechanics]
+    mechanics1: DomainMechanics,
+    #[Mechanics]
+    mechanics2: DomainMechanics,
+}
+
+fn main() {}
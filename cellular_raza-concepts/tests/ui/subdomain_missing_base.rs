@@ -0,0 +1,17 @@
+use cellular_raza_concepts::*;
+
+struct DomainMechanics;
+
+impl SubDomainMechanics<f64, f64> for DomainMechanics {
+    fn apply_boundary(&self, _pos: &mut f64, _vel: &mut f64) -> Result<(), BoundaryError> {
+        Ok(())
+    }
+}
+
+#[derive(SubDomain)]
+struct DeriveDomain {
+    #[Mechanics]
+    mechanics: DomainMechanics,
+}
+
+fn main() {}
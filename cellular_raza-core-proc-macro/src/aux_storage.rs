@@ -120,6 +120,11 @@ impl Aspect {
             return Ok(Some(Aspect::UpdateMechanics(parsed)));
         }
 
+        if cmp("UpdateRotationalMechanics") {
+            let parsed: UpdateRotationalMechanicsParser = syn::parse(stream)?;
+            return Ok(Some(Aspect::UpdateRotationalMechanics(parsed)));
+        }
+
         if cmp("UpdateCycle") {
             let parsed: UpdateCycleParser = syn::parse(stream)?;
             return Ok(Some(Aspect::UpdateCycle(parsed)));
@@ -140,16 +145,37 @@ impl Aspect {
             return Ok(Some(Aspect::UpdateReactionsContact(parsed)));
         }
 
+        if cmp("ResetScratch") {
+            let parsed: ResetScratchParser = syn::parse(stream)?;
+            return Ok(Some(Aspect::ResetScratch(parsed)));
+        }
+
+        if cmp("CustomAuxStorage") {
+            let parsed: CustomAuxStorageParser = syn::parse(stream)?;
+            return Ok(Some(Aspect::CustomAuxStorage(parsed)));
+        }
+
         Ok(None)
     }
 }
 
 enum Aspect {
+    /// Field marked with `#[UpdateMechanics(..)]`
     UpdateMechanics(UpdateMechanicsParser),
+    /// Field marked with `#[UpdateRotationalMechanics(..)]`
+    UpdateRotationalMechanics(UpdateRotationalMechanicsParser),
+    /// Field marked with `#[UpdateCycle]`
     UpdateCycle(UpdateCycleParser),
+    /// Field marked with `#[UpdateInteraction]`
     UpdateInteraction(UpdateInteractionParser),
+    /// Field marked with `#[UpdateReactions(..)]`
     UpdateReactions(UpdateReactionsParser),
+    /// Field marked with `#[UpdateReactionsContact(..)]`
     UpdateReactionsContact(UpdateReactionsContactParser),
+    /// Field marked with `#[CustomAuxStorage]`
+    CustomAuxStorage(CustomAuxStorageParser),
+    /// Field marked with `#[ResetScratch(..)]`
+    ResetScratch(ResetScratchParser),
 }
 
 // --------------------------------- UPDATE-MECHANICS --------------------------------
@@ -180,6 +206,42 @@ impl syn::parse::Parse for UpdateMechanicsParser {
     }
 }
 
+// ---------------------------- UPDATE-ROTATIONAL-MECHANICS ---------------------------
+/// Arguments of the `#[UpdateRotationalMechanics(Ang, AngVel, Tor, N)]` attribute
+struct UpdateRotationalMechanicsParser {
+    /// Type of the orientation
+    orientation: syn::GenericParam,
+    /// First separator
+    _comma_1: syn::token::Comma,
+    /// Type of the angular velocity
+    angular_velocity: syn::GenericParam,
+    /// Second separator
+    _comma_2: syn::token::Comma,
+    /// Type of the torque
+    torque: syn::GenericParam,
+    /// Third separator
+    _comma_3: syn::token::Comma,
+    /// Number of previous increments which are stored
+    n_saves: syn::GenericParam,
+}
+
+impl syn::parse::Parse for UpdateRotationalMechanicsParser {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let _update_rotational_mechanics: syn::Ident = input.parse()?;
+        let content;
+        syn::parenthesized!(content in input);
+        Ok(Self {
+            orientation: content.parse()?,
+            _comma_1: content.parse()?,
+            angular_velocity: content.parse()?,
+            _comma_2: content.parse()?,
+            torque: content.parse()?,
+            _comma_3: content.parse()?,
+            n_saves: content.parse()?,
+        })
+    }
+}
+
 // ----------------------------------- UPDATE-CYCLE ----------------------------------
 struct UpdateCycleParser;
 
@@ -237,14 +299,46 @@ impl syn::parse::Parse for UpdateReactionsContactParser {
     }
 }
 
+// -------------------------------- CUSTOM-AUX-STORAGE -------------------------------
+/// Marker of the `#[CustomAuxStorage]` attribute
+struct CustomAuxStorageParser;
+
+impl syn::parse::Parse for CustomAuxStorageParser {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let _custom_aux_storage: syn::Ident = input.parse()?;
+        Ok(Self)
+    }
+}
+
+// ---------------------------------- RESET-SCRATCH ----------------------------------
+/// Argument of the `#[ResetScratch(default)]` attribute
+struct ResetScratchParser {
+    /// Value to which the field is reset
+    default: syn::Expr,
+}
+
+impl syn::parse::Parse for ResetScratchParser {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let _reset_scratch: syn::Ident = input.parse()?;
+        let content;
+        syn::parenthesized!(content in input);
+        Ok(Self {
+            default: content.parse()?,
+        })
+    }
+}
+
 // ################################### CONVERSION ####################################
 impl From<AuxStorageParser> for AuxStorageImplementer {
     fn from(value: AuxStorageParser) -> Self {
         let mut update_cycle = None;
         let mut update_mechanics = None;
+        let mut update_rotational_mechanics = None;
         let mut update_interaction = None;
         let mut update_reactions = None;
         let mut update_reactions_contact = None;
+        let mut custom_aux_storage = vec![];
+        let mut reset_scratch = vec![];
 
         value
             .aspects
@@ -272,6 +366,17 @@ impl From<AuxStorageParser> for AuxStorageImplementer {
                                 field_type: aspect_field.field.ty.clone(),
                             })
                         }
+                        Aspect::UpdateRotationalMechanics(p) => {
+                            update_rotational_mechanics =
+                                Some(UpdateRotationalMechanicsImplementer {
+                                    orientation: p.orientation,
+                                    angular_velocity: p.angular_velocity,
+                                    torque: p.torque,
+                                    n_saves: p.n_saves,
+                                    field_name: aspect_field.field.ident.clone(),
+                                    field_type: aspect_field.field.ty.clone(),
+                                })
+                        }
                         Aspect::UpdateInteraction(_) => {
                             update_interaction = Some(UpdateInteractionImplementer {
                                 field_type: aspect_field.field.ty.clone(),
@@ -293,6 +398,16 @@ impl From<AuxStorageParser> for AuxStorageImplementer {
                                 field_name: aspect_field.field.ident.clone(),
                             })
                         }
+                        Aspect::CustomAuxStorage(_) => {
+                            custom_aux_storage.push(CustomAuxStorageImplementer {
+                                field_type: aspect_field.field.ty.clone(),
+                                field_name: aspect_field.field.ident.clone(),
+                            })
+                        }
+                        Aspect::ResetScratch(p) => reset_scratch.push(ResetScratchImplementer {
+                            field_name: aspect_field.field.ident.clone(),
+                            default: p.default,
+                        }),
                     })
             });
 
@@ -301,9 +416,12 @@ impl From<AuxStorageParser> for AuxStorageImplementer {
             generics: value.generics,
             update_cycle,
             update_mechanics,
+            update_rotational_mechanics,
             update_interaction,
             update_reactions,
             update_reactions_contact,
+            custom_aux_storage,
+            reset_scratch,
             core_path: value.core_path,
         }
     }
@@ -315,10 +433,16 @@ struct AuxStorageImplementer {
     name: syn::Ident,
     generics: syn::Generics,
     update_mechanics: Option<UpdateMechanicsImplementer>,
+    /// Field which stores the rotational mechanics
+    update_rotational_mechanics: Option<UpdateRotationalMechanicsImplementer>,
     update_cycle: Option<UpdateCycleImplementer>,
     update_interaction: Option<UpdateInteractionImplementer>,
     update_reactions: Option<UpdateReactionsImplementer>,
     update_reactions_contact: Option<UpdateReactionsContactImplementer>,
+    /// Fields which store values of custom aspects
+    custom_aux_storage: Vec<CustomAuxStorageImplementer>,
+    /// Scratch fields which are reset in every step
+    reset_scratch: Vec<ResetScratchImplementer>,
     core_path: Option<syn::Path>,
 }
 
@@ -429,6 +553,112 @@ impl AuxStorageImplementer {
     }
 }
 
+// ---------------------------- UPDATE-ROTATIONAL-MECHANICS ---------------------------
+/// Field which implements `UpdateRotationalMechanics` for the AuxStorage
+struct UpdateRotationalMechanicsImplementer {
+    /// Type of the orientation
+    orientation: syn::GenericParam,
+    /// Type of the angular velocity
+    angular_velocity: syn::GenericParam,
+    /// Type of the torque
+    torque: syn::GenericParam,
+    /// Number of previous increments which are stored
+    n_saves: syn::GenericParam,
+    /// Name of the field
+    field_name: Option<syn::Ident>,
+    /// Type of the field
+    field_type: syn::Type,
+}
+
+impl AuxStorageImplementer {
+    /// Implements `UpdateRotationalMechanics` by forwarding to the marked field
+    fn implement_update_rotational_mechanics(&self) -> TokenStream {
+        if let Some(update_rotational_mechanics) = &self.update_rotational_mechanics {
+            let orientation = &update_rotational_mechanics.orientation;
+            let angular_velocity = &update_rotational_mechanics.angular_velocity;
+            let torque = &update_rotational_mechanics.torque;
+            let n_saves = &update_rotational_mechanics.n_saves;
+
+            let backend_path = match &self.core_path {
+                Some(p) => quote!(#p ::backend::chili::),
+                None => quote!(),
+            };
+
+            let field_generics = quote!(#orientation, #angular_velocity, #torque, #n_saves);
+
+            let struct_name = &self.name;
+            let (struct_impl_generics, struct_ty_generics, struct_where_clause) =
+                &self.generics.split_for_impl();
+            let where_clause = match struct_where_clause {
+                Some(s_where) => {
+                    let pred = s_where.predicates.iter();
+                    quote!(
+                        where
+                        #(#pred,)*
+                        #torque: Clone + core::ops::AddAssign<#torque>,
+                    )
+                }
+                None => quote!(
+                    where
+                    #torque: Clone + core::ops::AddAssign<#torque>,
+                ),
+            };
+
+            let field_name = &update_rotational_mechanics.field_name;
+            let field_type = &update_rotational_mechanics.field_type;
+            let update_trait = quote!(#backend_path UpdateRotationalMechanics<#field_generics>);
+
+            let new_stream = wrap_pre_flags(quote!(
+                impl #struct_impl_generics #update_trait
+                for #struct_name #struct_ty_generics #where_clause
+                {
+                    #[inline]
+                    fn set_last_orientation(&mut self, ang: #orientation) {
+                        <#field_type as #update_trait>
+                            ::set_last_orientation(&mut self.#field_name, ang)
+                    }
+                    #[inline]
+                    fn previous_orientations<'a>(
+                        &'a self
+                    ) -> #backend_path RingBufferIterRef<'a, #orientation, #n_saves> {
+                        <#field_type as #update_trait>
+                            ::previous_orientations(&self.#field_name)
+                    }
+                    #[inline]
+                    fn set_last_angular_velocity(&mut self, ang_vel: #angular_velocity) {
+                        <#field_type as #update_trait>
+                            ::set_last_angular_velocity(&mut self.#field_name, ang_vel)
+                    }
+                    #[inline]
+                    fn previous_angular_velocities<'a>(
+                        &'a self
+                    ) -> #backend_path RingBufferIterRef<'a, #angular_velocity, #n_saves> {
+                        <#field_type as #update_trait>
+                            ::previous_angular_velocities(&self.#field_name)
+                    }
+                    #[inline]
+                    fn n_previous_rotational_values(&self) -> usize {
+                        <#field_type as #update_trait>
+                            ::n_previous_rotational_values(&self.#field_name)
+                    }
+                    #[inline]
+                    fn add_torque(&mut self, torque: #torque) {
+                        <#field_type as #update_trait>::add_torque(&mut self.#field_name, torque);
+                    }
+                    #[inline]
+                    fn get_current_torque_and_reset(&mut self) -> #torque {
+                        <#field_type as #update_trait>
+                            ::get_current_torque_and_reset(&mut self.#field_name)
+                    }
+                }
+            ));
+            return TokenStream::from(new_stream);
+        }
+
+        TokenStream::new()
+    }
+}
+
 // ----------------------------------- UPDATE-CYCLE ----------------------------------
 struct UpdateCycleImplementer {
     field_name: Option<syn::Ident>,
@@ -693,6 +923,29 @@ impl AuxStorageImplementer {
                             neighbors
                         )
                     }
+
+                    #[inline]
+                    fn get_current_pressure(&self) -> f64 {
+                        <#field_type as #backend_path UpdateInteraction>::get_current_pressure(
+                            &self.#field_name
+                        )
+                    }
+
+                    #[inline]
+                    fn incr_current_pressure(&mut self, pressure: f64) {
+                        <#field_type as #backend_path UpdateInteraction>::incr_current_pressure(
+                            &mut self.#field_name,
+                            pressure
+                        )
+                    }
+
+                    #[inline]
+                    fn set_current_pressure(&mut self, pressure: f64) {
+                        <#field_type as #backend_path UpdateInteraction>::set_current_pressure(
+                            &mut self.#field_name,
+                            pressure
+                        )
+                    }
                 }
             ));
             return TokenStream::from(new_stream);
@@ -701,6 +954,89 @@ impl AuxStorageImplementer {
     }
 }
 
+// -------------------------------- CUSTOM-AUX-STORAGE -------------------------------
+/// Field which stores the values of a custom aspect
+struct CustomAuxStorageImplementer {
+    /// Name of the field
+    field_name: Option<syn::Ident>,
+    /// Type of the field
+    field_type: syn::Type,
+}
+
+impl AuxStorageImplementer {
+    /// Implements `CustomAuxStorage` for every field of a custom aspect
+    fn implement_custom_aux_storage(&self) -> TokenStream {
+        let struct_name = &self.name;
+        let (impl_generics, ty_generics, where_clause) = &self.generics.split_for_impl();
+
+        let backend_path = match &self.core_path {
+            Some(p) => quote!(#p ::backend::chili::),
+            None => quote!(),
+        };
+
+        let mut new_stream = proc_macro2::TokenStream::new();
+        for custom in self.custom_aux_storage.iter() {
+            let field_name = &custom.field_name;
+            let field_type = &custom.field_type;
+            new_stream.extend(wrap_pre_flags(quote!(
+                impl #impl_generics #backend_path CustomAuxStorage<#field_type>
+                    for #struct_name #ty_generics #where_clause
+                {
+                    #[inline]
+                    fn custom_storage(&self) -> &#field_type {
+                        &self.#field_name
+                    }
+
+                    #[inline]
+                    fn custom_storage_mut(&mut self) -> &mut #field_type {
+                        &mut self.#field_name
+                    }
+                }
+            )));
+        }
+        TokenStream::from(new_stream)
+    }
+}
+
+// ---------------------------------- RESET-SCRATCH ----------------------------------
+/// Scratch field which is reset in every step
+struct ResetScratchImplementer {
+    /// Name of the field
+    field_name: Option<syn::Ident>,
+    /// Value to which the field is reset
+    default: syn::Expr,
+}
+
+impl AuxStorageImplementer {
+    /// Implements `ResetScratch` by assigning the default to all reset fields
+    fn implement_reset_scratch_fields(&self) -> TokenStream {
+        if self.reset_scratch.is_empty() {
+            return TokenStream::new();
+        }
+        let struct_name = &self.name;
+        let (impl_generics, ty_generics, where_clause) = &self.generics.split_for_impl();
+
+        let backend_path = match &self.core_path {
+            Some(p) => quote!(#p ::backend::chili::),
+            None => quote!(),
+        };
+
+        let field_names = self.reset_scratch.iter().map(|reset| &reset.field_name);
+        let defaults = self.reset_scratch.iter().map(|reset| &reset.default);
+        let new_stream = wrap_pre_flags(quote!(
+            impl #impl_generics #backend_path ResetScratchFields
+                for #struct_name #ty_generics #where_clause
+            {
+                #[inline]
+                fn reset_scratch_fields(&mut self) {
+                    #(self.#field_names = #defaults;)*
+                }
+            }
+        ));
+        TokenStream::from(new_stream)
+    }
+}
+
 pub fn generics_placeholders(
     kwargs: impl Into<KwargsAuxStorage>,
     mechanics_solver_order: usize,
@@ -735,9 +1071,12 @@ pub fn derive_aux_storage(input: TokenStream) -> TokenStream {
     let mut res = TokenStream::new();
     res.extend(aux_storage.implement_update_cycle());
     res.extend(aux_storage.implement_update_mechanics());
+    res.extend(aux_storage.implement_update_rotational_mechanics());
     res.extend(aux_storage.implement_update_reactions());
     res.extend(aux_storage.implement_update_reactions_contact());
     res.extend(aux_storage.implement_update_interaction());
+    res.extend(aux_storage.implement_custom_aux_storage());
+    res.extend(aux_storage.implement_reset_scratch_fields());
 
     res
 }
@@ -756,16 +1095,139 @@ define_kwargs!(
     aux_storage_name: syn::Ident | crate::aux_storage::default_aux_storage_name(),
     zero_force_default: syn::ExprClosure | crate::aux_storage::zero_force_default(),
     zero_reactions_default: syn::ExprClosure | crate::aux_storage::zero_reactions_default(),
+    scratch_fields: ScratchFields | Default::default(),
     @from
     KwargsSim,
     KwargsMain,
     KwargsPrepareTypes
 );
 
+/// Determines when a [ScratchField] is reset to its default value.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum ResetPolicy {
+    /// The value is only set when the cell is created
+    #[default]
+    Never,
+    /// The value is reset at the beginning of every step
+    Step,
+}
+
+/// User-defined field which is stored for every cell.
+///
+/// ## Example Usage
+/// ```ignore
+/// scratch_fields: [
+///     #[reset(step)]
+///     #[skip_serializing]
+///     neighbor_cache: NeighborCache = NeighborCache::with_capacity(8),
+///     bonds: Vec<(u64, f64)> = Vec::new(),
+/// ]
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScratchField {
+    /// When the field is reset to its default
+    pub reset: ResetPolicy,
+    /// Whether the field is serialized
+    pub serialize: bool,
+    /// Name of the field
+    pub name: syn::Ident,
+    /// Type of the field
+    pub ty: syn::Type,
+    /// Initial value of the field
+    pub default: syn::Expr,
+}
+
+impl syn::parse::Parse for ScratchField {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut reset = ResetPolicy::Never;
+        let mut serialize = true;
+        for attr in input.call(syn::Attribute::parse_outer)? {
+            if attr.path().is_ident("reset") {
+                let policy: syn::Ident = attr.parse_args()?;
+                reset = match policy.to_string().as_str() {
+                    "never" => ResetPolicy::Never,
+                    "step" => ResetPolicy::Step,
+                    _ => {
+                        return Err(syn::Error::new(
+                            policy.span(),
+                            format!("Expected reset policy never or step but found {}", policy),
+                        ))
+                    }
+                };
+            } else if attr.path().is_ident("skip_serializing") {
+                attr.meta.require_path_only()?;
+                serialize = false;
+            } else {
+                return Err(syn::Error::new(
+                    attr.span(),
+                    "Expected one of the attributes #[reset(..)] or #[skip_serializing]",
+                ));
+            }
+        }
+        let name = input.parse()?;
+        let _: syn::Token![:] = input.parse()?;
+        let ty = input.parse()?;
+        let _: syn::Token![=] = input.parse()?;
+        Ok(Self {
+            reset,
+            serialize,
+            name,
+            ty,
+            default: input.parse()?,
+        })
+    }
+}
+
+/// A list of [ScratchField]s.
+///
+/// ## Example Usage
+/// ```ignore
+/// my_macro!(
+///     ...
+///     scratch_fields: [bonds: Vec<(u64, f64)> = Vec::new(), ...],
+///     ...
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ScratchFields {
+    /// All parsed fields in the given order
+    pub fields: Vec<ScratchField>,
+}
+
+impl syn::parse::Parse for ScratchFields {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let content;
+        syn::bracketed!(content in input);
+        let fields = syn::punctuated::Punctuated::<ScratchField, syn::Token![,]>::parse_terminated(
+            &content,
+        )?;
+        if let Some(duplicate) = fields.iter().duplicates_by(|field| &field.name).next() {
+            return Err(syn::Error::new(
+                duplicate.name.span(),
+                format!("Found duplicate scratch field: {}", duplicate.name),
+            ));
+        }
+        Ok(Self {
+            fields: fields.into_iter().collect(),
+        })
+    }
+}
+
+impl ScratchFields {
+    /// Checks if any field needs to be reset in every step
+    pub fn reset_every_step(&self) -> bool {
+        self.fields
+            .iter()
+            .any(|field| field.reset == ResetPolicy::Step)
+    }
+}
+
 pub struct Builder {
     pub struct_name: syn::Ident,
     pub core_path: syn::Path,
     pub aspects: SimulationAspects,
+    /// User-defined fields which are added to the AuxStorage
+    pub scratch_fields: ScratchFields,
 }
 
 impl From<KwargsAuxStorage> for Builder {
@@ -774,6 +1236,7 @@ impl From<KwargsAuxStorage> for Builder {
             struct_name: kwargs.aux_storage_name,
             core_path: kwargs.core_path,
             aspects: kwargs.aspects,
+            scratch_fields: kwargs.scratch_fields,
         }
     }
 }
@@ -828,6 +1291,24 @@ impl Builder {
             });
         }
 
+        // Shares the order of the solver with the mechanics aspect
+        if self.aspects.contains(&RotationalMechanics) {
+            let field_name = syn::parse_quote!(rotational_mechanics);
+            let field_type = syn::parse_quote!(#backend_path AuxStorageRotationalMechanics);
+            let generics = syn::parse_quote!(<Ang, AngVel, Tor, const NMec: usize>);
+            let fully_formatted_field = quote!(
+                #[UpdateRotationalMechanics(Ang, AngVel, Tor, NMec)]
+                #field_name: #backend_path AuxStorageRotationalMechanics<Ang, AngVel, Tor, NMec>,
+            );
+            fields.push(FieldInfo {
+                aspects: vec![RotationalMechanics],
+                field_name,
+                field_type,
+                generics,
+                fully_formatted_field,
+            });
+        }
+
         if self
             .aspects
             .contains_any([&Reactions, &ReactionsContact, &ReactionsExtra])
@@ -881,6 +1362,25 @@ impl Builder {
                 fully_formatted_field,
             });
         }
+
+        // Fields of custom aspects are initialized with their default value
+        for custom in self.aspects.custom_aspects() {
+            for field in custom.aux_storage.iter() {
+                let field_name = field.name.clone();
+                let field_type = field.ty.clone();
+                let fully_formatted_field = quote!(
+                    #[CustomAuxStorage]
+                    #field_name: #field_type,
+                );
+                fields.push(FieldInfo {
+                    aspects: vec![Custom(custom.clone())],
+                    field_name,
+                    field_type,
+                    generics: syn::parse_quote!(),
+                    fully_formatted_field,
+                });
+            }
+        }
         fields
     }
 
@@ -891,6 +1391,53 @@ impl Builder {
             .unique()
     }
 
+    /// Formats the user-defined [ScratchFields] and generates functions which provide their
+    /// default values when they are not serialized.
+    fn build_scratch_fields(&self) -> (Vec<proc_macro2::TokenStream>, proc_macro2::TokenStream) {
+        let mut default_functions = quote!();
+        let fields = self
+            .scratch_fields
+            .fields
+            .iter()
+            .map(|field| {
+                let ScratchField {
+                    reset,
+                    serialize,
+                    name,
+                    ty,
+                    default,
+                } = field;
+                let reset = match reset {
+                    ResetPolicy::Never => quote!(),
+                    ResetPolicy::Step => quote!(#[ResetScratch(#default)]),
+                };
+                let serde = match serialize {
+                    true => quote!(),
+                    false => {
+                        let default_fn =
+                            quote::format_ident!("__cr_default_{}_{}", self.struct_name, name);
+                        let default_fn_name = default_fn.to_string();
+                        default_functions.extend(quote!(
+                            #[allow(non_snake_case)]
+                            #[doc(hidden)]
+                            fn #default_fn() -> #ty {
+                                #default
+                            }
+                        ));
+                        quote!(#[serde(skip, default = #default_fn_name)])
+                    }
+                };
+                quote!(
+                    #[CustomAuxStorage]
+                    #reset
+                    #serde
+                    #name: #ty,
+                )
+            })
+            .collect();
+        (fields, default_functions)
+    }
+
     pub fn build_aux_storage(self) -> proc_macro2::TokenStream {
         let struct_name = &self.struct_name;
         let core_path = &self.core_path;
@@ -899,6 +1446,8 @@ impl Builder {
             .get_field_information()
             .into_iter()
             .map(|x| x.fully_formatted_field);
+        let (scratch_fields, default_functions) = self.build_scratch_fields();
+        let aspects_constant = self.aspects.to_constant(struct_name, quote!(pub));
 
         let stream = quote!(
             #[allow(non_camel_case_types)]
@@ -908,7 +1457,11 @@ impl Builder {
             #[AuxStorageCorePath(#core_path)]
             pub struct #struct_name<#(#generics),*> {
                 #(#fields)*
+                #(#scratch_fields)*
             }
+
+            #default_functions
+            #aspects_constant
         );
         stream
     }
@@ -928,25 +1481,6 @@ pub fn zero_reactions_default() -> syn::ExprClosure {
     syn::parse_quote!(|_c| { num::Zero::zero() })
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
-
-    #[test]
-    fn test_zero_reactions_default_parses() {
-        let default = zero_reactions_default();
-        assert_eq!(default.inputs.len(), 1);
-        assert!(format!("{}", quote::quote!(#(default.body))).len() > 0);
-    }
-
-    #[test]
-    fn test_zero_force_default_parses() {
-        let default = zero_force_default();
-        assert_eq!(default.inputs.len(), 1);
-        assert!(format!("{}", quote::quote!(#(default.body))).len() > 0);
-    }
-}
-
 pub fn default_aux_storage_initializer(
     kwargs: &(impl Into<KwargsAuxStorage> + Clone),
 ) -> syn::ExprClosure {
@@ -986,9 +1520,63 @@ pub fn default_aux_storage_initializer(
             }
         },
     );
+    let scratch_fields = builder
+        .scratch_fields
+        .fields
+        .iter()
+        .map(|ScratchField { name, default, .. }| quote::quote!(#name: #default,));
     syn::parse_quote!(|c| {
         #struct_name {
             #(#fields)*
+            #(#scratch_fields)*
         }
     })
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_zero_reactions_default_parses() {
+        let default = zero_reactions_default();
+        assert_eq!(default.inputs.len(), 1);
+        assert!(!format!("{}", quote::quote!(#(default.body))).is_empty());
+    }
+
+    #[test]
+    fn test_zero_force_default_parses() {
+        let default = zero_force_default();
+        assert_eq!(default.inputs.len(), 1);
+        assert!(!format!("{}", quote::quote!(#(default.body))).is_empty());
+    }
+
+    #[test]
+    fn test_scratch_fields_parse() {
+        let scratch_fields: ScratchFields = syn::parse_quote!([
+            bonds: Vec<(u64, f64)> = Vec::new(),
+            #[reset(step)]
+            #[skip_serializing]
+            neighbors: NeighborCache = NeighborCache::with_capacity(8),
+        ]);
+        assert_eq!(scratch_fields.fields.len(), 2);
+        assert_eq!(scratch_fields.fields[0].reset, ResetPolicy::Never);
+        assert!(scratch_fields.fields[0].serialize);
+        assert_eq!(scratch_fields.fields[1].reset, ResetPolicy::Step);
+        assert!(!scratch_fields.fields[1].serialize);
+        assert!(scratch_fields.reset_every_step());
+    }
+
+    #[test]
+    fn test_scratch_fields_reject_invalid() {
+        let invalid = [
+            quote::quote!([bonds: Vec<u64>]),
+            quote::quote!([#[reset(division)] bonds: Vec<u64> = Vec::new()]),
+            quote::quote!([#[serialize] bonds: Vec<u64> = Vec::new()]),
+            quote::quote!([a: u8 = 0, a: u16 = 0]),
+        ];
+        for stream in invalid {
+            assert!(syn::parse2::<ScratchFields>(stream).is_err());
+        }
+    }
+}
@@ -149,7 +149,10 @@ impl Communicator {
             let index = &comm.index;
             let message = &comm.message;
 
-            let addendum = quote!(#index: Clone + core::hash::Hash + Eq + Ord,);
+            let addendum = quote!(
+                #index: Clone + core::hash::Hash + Eq + Ord,
+                #field_type: #backend_path Communicator<#index, #message>,
+            );
             let where_clause = match where_clause {
                 Some(w) => quote!(where #(#w.predicates), #addendum),
                 None => quote!(where #addendum),
@@ -170,6 +173,41 @@ impl Communicator {
                 }
             ))
         }));
+
+        // Statistics of all fields are combined.
+        // Fields with multiple #[Comm(I, T)] attributes are only counted once.
+        let backend_path = quote!(#core_path ::backend::chili::);
+        let mut stat_comms: Vec<&CommField> = vec![];
+        for comm in self.comms.iter() {
+            if !stat_comms.iter().any(|c| c.field_name == comm.field_name) {
+                stat_comms.push(comm);
+            }
+        }
+        let field_names = stat_comms.iter().map(|comm| &comm.field_name);
+        let field_types: Vec<_> = stat_comms.iter().map(|comm| &comm.field_type).collect();
+        let addendum = quote!(#(#field_types: #backend_path CommunicationStatistics,)*);
+        let predicates = where_clause.iter().flat_map(|w| w.predicates.iter());
+        let where_clause = quote!(where #(#predicates,)* #addendum);
+        res.extend(wrap_pre_flags(
+            &quote!(#core_path),
+            quote!(
+                #[automatically_derived]
+                impl #impl_generics #backend_path CommunicationStatistics
+                for #struct_name #ty_generics #where_clause
+                {
+                    fn communication_statistics(&self) -> Vec<#backend_path MessageStatistics> {
+                        let mut statistics = Vec::new();
+                        #(
+                            statistics.extend(
+                                <#field_types as #backend_path CommunicationStatistics>
+                                    ::communication_statistics(&self.#field_names)
+                            );
+                        )*
+                        statistics
+                    }
+                }
+            ),
+        ));
         res
     }
 }
@@ -189,6 +227,7 @@ define_kwargs!(
     @optionals
     core_path: syn::Path | crate::kwargs::convert_core_path(None),
     communicator_name: syn::Ident | crate::communicator::default_communicator_name(),
+    multiplex_communicator: bool | false,
     @from
     KwargsSim,
     KwargsMain,
@@ -199,9 +238,30 @@ fn index_type() -> syn::Type {
     syn::parse2(quote!(I)).unwrap()
 }
 
+/// A single type of message which is exchanged for one simulation aspect
+struct CommEntry {
+    /// Name of the field in the communicator with one channel per message type
+    field_name: syn::Ident,
+    /// Name of the variant in the enum of the multiplexed communicator
+    variant: syn::Ident,
+    /// Type of the message
+    message: proc_macro2::TokenStream,
+}
+
+impl CommEntry {
+    /// Creates a new entry from the names of its field and variant
+    fn new(field_name: &str, variant: &str, message: proc_macro2::TokenStream) -> Self {
+        Self {
+            field_name: syn::Ident::new(field_name, proc_macro2::Span::call_site()),
+            variant: syn::Ident::new(variant, proc_macro2::Span::call_site()),
+            message,
+        }
+    }
+}
+
 impl SimulationAspect {
-    fn build_comm(&self, core_path: &syn::Path) -> (Vec<syn::Type>, Vec<proc_macro2::TokenStream>) {
-        let index_type = index_type();
+    /// Returns the generic message types and the channels which this aspect requires
+    fn build_comm(&self, core_path: &syn::Path) -> (Vec<syn::Type>, Vec<CommEntry>) {
         let backend_path = quote!(#core_path ::backend::chili::);
         match self {
             SimulationAspect::Cycle => (vec![], vec![]),
@@ -212,25 +272,15 @@ impl SimulationAspect {
                     syn::parse2(quote!(NValue)).unwrap(),
                 ],
                 vec![
-                    quote!(
-                        #[Comm(
-                            #index_type,
-                            #backend_path ReactionsExtraBorderInfo<Binfo>
-                        )]
-                        comm_reactions_extra: #backend_path ChannelComm<
-                            #index_type,
-                            #backend_path ReactionsExtraBorderInfo<Binfo>
-                        >
+                    CommEntry::new(
+                        "comm_reactions_extra",
+                        "ReactionsExtraBorderInfo",
+                        quote!(#backend_path ReactionsExtraBorderInfo<Binfo>),
                     ),
-                    quote!(
-                        #[Comm(
-                            #index_type,
-                            #backend_path ReactionsExtraBorderReturn<NValue>
-                        )]
-                        comm_reactions_extra_return: #backend_path ChannelComm<
-                            #index_type,
-                            #backend_path ReactionsExtraBorderReturn<NValue>
-                        >
+                    CommEntry::new(
+                        "comm_reactions_extra_return",
+                        "ReactionsExtraBorderReturn",
+                        quote!(#backend_path ReactionsExtraBorderReturn<NValue>),
                     ),
                 ],
             ),
@@ -241,19 +291,15 @@ impl SimulationAspect {
                     syn::parse2(quote!(RInf)).unwrap(),
                 ],
                 vec![
-                    quote!(
-                        #[Comm(I, #backend_path ReactionsContactInformation<Pos, Ri, RInf>)]
-                        comm_reactions_contact: #backend_path ChannelComm<
-                            #index_type,
-                            #backend_path ReactionsContactInformation<Pos, Ri, RInf>
-                        >
+                    CommEntry::new(
+                        "comm_reactions_contact",
+                        "ReactionsContactInformation",
+                        quote!(#backend_path ReactionsContactInformation<Pos, Ri, RInf>),
                     ),
-                    quote!(
-                        #[Comm(I, #backend_path ReactionsContactReturn<Ri>)]
-                        comm_reactions_contact_return: #backend_path ChannelComm<
-                            #index_type,
-                            #backend_path ReactionsContactReturn<Ri>
-                        >
+                    CommEntry::new(
+                        "comm_reactions_contact_return",
+                        "ReactionsContactReturn",
+                        quote!(#backend_path ReactionsContactReturn<Ri>),
                     ),
                 ],
             ),
@@ -262,13 +308,19 @@ impl SimulationAspect {
                     syn::parse2(quote!(Cel)).unwrap(),
                     syn::parse2(quote!(Aux)).unwrap(),
                 ],
-                vec![quote!(
-                    #[Comm(I, #backend_path SendCell<Cel, Aux>)]
-                    comm_cell: #backend_path ChannelComm<
-                        #index_type,
-                        #backend_path SendCell<Cel, Aux>
-                    >
-                )],
+                vec![
+                    CommEntry::new(
+                        "comm_cell",
+                        "SendCell",
+                        quote!(#backend_path SendCell<Cel, Aux>),
+                    ),
+                    // Ghost cells of the halo exchange are mirrored in the same way as cells
+                    CommEntry::new(
+                        "comm_halo",
+                        "HaloCells",
+                        quote!(#backend_path HaloCells<Cel>),
+                    ),
+                ],
             ),
             SimulationAspect::Interaction => (
                 vec![
@@ -278,23 +330,57 @@ impl SimulationAspect {
                     syn::parse2(quote!(Inf)).unwrap(),
                 ],
                 vec![
-                    quote!(
-                        #[Comm(I, #backend_path PosInformation<Pos, Vel, Inf>)]
-                        comm_pos: #backend_path ChannelComm<
-                            #index_type,
-                            #backend_path PosInformation<Pos, Vel, Inf>
-                        >
+                    CommEntry::new(
+                        "comm_pos",
+                        "PosInformation",
+                        quote!(#backend_path PosInformation<Pos, Vel, Inf>),
                     ),
-                    quote!(
-                        #[Comm(I, #backend_path ForceInformation<For>)]
-                        comm_force: #backend_path ChannelComm<
-                            #index_type,
-                            #backend_path ForceInformation<For>
-                        >
+                    CommEntry::new(
+                        "comm_force",
+                        "ForceInformation",
+                        quote!(#backend_path ForceInformation<For>),
                     ),
                 ],
             ),
             SimulationAspect::DomainForce => (vec![], vec![]),
+            SimulationAspect::Age => (vec![], vec![]),
+            // Torques are calculated from the same information which is used for forces
+            SimulationAspect::RotationalMechanics => (
+                vec![
+                    syn::parse2(quote!(Pos)).unwrap(),
+                    syn::parse2(quote!(Ang)).unwrap(),
+                    syn::parse2(quote!(Inf)).unwrap(),
+                    syn::parse2(quote!(Tor)).unwrap(),
+                ],
+                vec![
+                    CommEntry::new(
+                        "comm_orientation",
+                        "OrientationInformation",
+                        quote!(#backend_path OrientationInformation<Pos, Ang, Inf>),
+                    ),
+                    CommEntry::new(
+                        "comm_torque",
+                        "TorqueInformation",
+                        quote!(#backend_path TorqueInformation<Tor>),
+                    ),
+                ],
+            ),
+            // Messages of custom aspects have concrete types and are named after their field
+            SimulationAspect::Custom(custom) => (
+                vec![],
+                custom
+                    .communicator
+                    .iter()
+                    .map(|field| {
+                        let message = &field.ty;
+                        CommEntry {
+                            field_name: field.name.clone(),
+                            variant: field.name.clone(),
+                            message: quote!(#message),
+                        }
+                    })
+                    .collect(),
+            ),
         }
     }
 }
@@ -302,7 +388,7 @@ impl SimulationAspect {
 fn generics_and_fields(
     simulation_aspects: &SimulationAspects,
     core_path: &syn::Path,
-) -> (Vec<syn::Type>, Vec<proc_macro2::TokenStream>) {
+) -> (Vec<syn::Type>, Vec<CommEntry>) {
     let index_type = index_type();
     let generics_fields: Vec<_> = simulation_aspects
         .items
@@ -332,17 +418,44 @@ pub struct CommunicatorBuilder {
     pub struct_name: syn::Ident,
     pub core_path: syn::Path,
     pub aspects: SimulationAspects,
+    /// Sends all messages over a single channel
+    pub multiplex_communicator: bool,
 }
 
 impl CommunicatorBuilder {
     pub fn build_communicator(self) -> proc_macro2::TokenStream {
-        let struct_name = self.struct_name;
+        let struct_name = &self.struct_name;
         let index_type = index_type();
         let core_path = &self.core_path;
-        let (generics, fields) = generics_and_fields(&self.aspects, &core_path);
+        let backend_path = quote!(#core_path ::backend::chili::);
+        let (generics, entries) = generics_and_fields(&self.aspects, &core_path);
         // In the following code, we assume that I
         // is the index as implemented above in the build_comm function
+        let (fields, message_enum) = match self.multiplex_communicator && !entries.is_empty() {
+            false => (
+                entries
+                    .iter()
+                    .map(|entry| {
+                        let CommEntry {
+                            field_name,
+                            message,
+                            ..
+                        } = entry;
+                        quote!(
+                            #[Comm(#index_type, #message)]
+                            #field_name: #backend_path ChannelComm<#index_type, #message>
+                        )
+                    })
+                    .collect(),
+                quote!(),
+            ),
+            true => self.build_message_enum(&generics, &entries),
+        };
+        let aspects_constant = self.aspects.to_constant(struct_name, quote!());
         quote!(
+            #message_enum
+            #aspects_constant
+
             #[derive(#core_path ::backend::chili::Communicator)]
             #[CommunicatorCorePath(#core_path)]
             #[derive(#core_path ::backend::chili::FromMap)]
@@ -355,6 +468,62 @@ impl CommunicatorBuilder {
             }
         )
     }
+
+    /// Generates an enum with one variant per type of message and a single field which sends
+    /// all of them over one [MultiplexComm] channel.
+    fn build_message_enum(
+        &self,
+        generics: &[syn::Type],
+        entries: &[CommEntry],
+    ) -> (Vec<proc_macro2::TokenStream>, proc_macro2::TokenStream) {
+        let index_type = index_type();
+        let core_path = &self.core_path;
+        let backend_path = quote!(#core_path ::backend::chili::);
+        let enum_name = quote::format_ident!("{}Message", self.struct_name);
+        // The index is not part of any message
+        let enum_generics: Vec<_> = generics.iter().filter(|g| **g != index_type).collect();
+        let enum_type = quote!(#enum_name <#(#enum_generics),*>);
+
+        let variants = entries.iter().map(|entry| entry.variant.clone());
+        let messages = entries.iter().map(|entry| entry.message.clone());
+        let mut message_enum = quote!(
+            #[allow(non_camel_case_types)]
+            enum #enum_type {
+                #(#variants(#messages),)*
+            }
+        );
+        message_enum.extend(entries.iter().map(|entry| {
+            let CommEntry {
+                variant, message, ..
+            } = entry;
+            quote!(
+                #[automatically_derived]
+                impl <#(#enum_generics),*> From<#message> for #enum_type {
+                    fn from(message: #message) -> Self {
+                        #enum_name::#variant(message)
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unreachable_patterns)]
+                impl <#(#enum_generics),*> #backend_path Multiplex<#message> for #enum_type {
+                    fn demultiplex(self) -> Result<#message, Self> {
+                        match self {
+                            #enum_name::#variant(message) => Ok(message),
+                            other => Err(other),
+                        }
+                    }
+                }
+            )
+        }));
+
+        let messages = entries.iter().map(|entry| &entry.message);
+        let field = quote!(
+            #(#[Comm(#index_type, #messages)])*
+            comm: #backend_path MultiplexComm<#index_type, #enum_type>
+        );
+        (vec![field], message_enum)
+    }
 }
 
 impl From<KwargsCommunicator> for CommunicatorBuilder {
@@ -363,6 +532,7 @@ impl From<KwargsCommunicator> for CommunicatorBuilder {
             struct_name: input.communicator_name,
             core_path: input.core_path,
             aspects: input.aspects,
+            multiplex_communicator: input.multiplex_communicator,
         }
     }
 }
@@ -25,122 +25,474 @@ use crate::simulation_aspects::SimulationAspects;
 #[derive(Clone, PartialEq, Debug)]
 #[allow(non_camel_case_types)]
 pub enum Kwarg {
+    /// Domain of the simulation
     domain {
+        /// The `domain` keyword
         #[allow(unused)]
         domain_kw: syn::Ident,
+        /// Optional separator between keyword and value
         #[allow(unused)]
         double_colon: Option<syn::Token![:]>,
+        /// Name of the domain variable
         domain: syn::Ident,
     },
+    /// Initial cell-agents
     agents {
+        /// The `agents` keyword
         #[allow(unused)]
         agents_kw: syn::Ident,
+        /// Optional separator between keyword and value
         #[allow(unused)]
         double_colon: Option<syn::Token![:]>,
+        /// Name of the variable containing the agents
         #[allow(unused)]
         agents: syn::Ident,
     },
+    /// Settings of the simulation
     settings {
+        /// The `settings` keyword
         #[allow(unused)]
         settings_kw: syn::Ident,
+        /// Optional separator between keyword and value
         #[allow(unused)]
         double_colon: Option<syn::Token![:]>,
+        /// Name of the settings variable
         settings: syn::Ident,
     },
+    /// Simulation aspects which should be solved
     aspects {
+        /// Parsed list of aspects
         aspects: SimulationAspects,
     },
+    /// Path to the core module of `cellular_raza`
     core_path {
+        /// The `core_path` keyword
         #[allow(unused)]
         core_path_kw: syn::Ident,
+        /// Separator between keyword and value
         #[allow(unused)]
         double_colon: syn::Token![:],
+        /// Path to the module
         core_path: syn::Path,
     },
+    /// Method to parallelize the simulation
     parallelizer {
+        /// The `parallelizer` keyword
         #[allow(unused)]
         parallelizer_kw: syn::Ident,
+        /// Separator between keyword and value
         #[allow(unused)]
         double_colon: syn::Token![:],
+        /// Chosen parallelizer
         parallelizer: crate::run_sim::Parallelizer,
     },
+    /// Enforces sorting of received values
     determinism {
+        /// The `determinism` keyword
         #[allow(unused)]
         determinism_kw: syn::Ident,
+        /// Separator between keyword and value
         #[allow(unused)]
         double_colon: syn::Token![:],
+        /// Whether sorting is enabled
         determinism: bool,
     },
+    /// Name of the generated AuxStorage struct
     aux_storage_name {
+        /// The `aux_storage_name` keyword
         #[allow(unused)]
         aux_storage_name_kw: syn::Ident,
+        /// Separator between keyword and value
         #[allow(unused)]
         double_colon: syn::Token![:],
+        /// Identifier of the struct
         aux_storage_name: syn::Ident,
     },
+    /// Name of the generated communicator struct
     communicator_name {
+        /// The `communicator_name` keyword
         #[allow(unused)]
         communicator_name_kw: syn::Ident,
+        /// Separator between keyword and value
         #[allow(unused)]
         double_colon: syn::Token![:],
+        /// Identifier of the struct
         communicator_name: syn::Ident,
     },
+    /// Sends all messages over a single channel
+    multiplex_communicator {
+        /// The `multiplex_communicator` keyword
+        #[allow(unused)]
+        multiplex_communicator_kw: syn::Ident,
+        /// Separator between keyword and value
+        #[allow(unused)]
+        double_colon: syn::Token![:],
+        /// Whether channels are multiplexed
+        multiplex_communicator: bool,
+    },
+    /// Order of the mechanics solver
     mechanics_solver_order {
+        /// The `mechanics_solver_order` keyword
         #[allow(unused)]
         mechanics_solver_order_kw: syn::Ident,
+        /// Separator between keyword and value
         #[allow(unused)]
         double_colon: syn::Token![:],
+        /// Order of the solver
         mechanics_solver_order: usize,
     },
+    /// Order of the intracellular reactions solver
     reactions_intra_solver_order {
+        /// The `reactions_intra_solver_order` keyword
         #[allow(unused)]
         reactions_intra_solver_order_kw: syn::Ident,
+        /// Separator between keyword and value
         #[allow(unused)]
         double_colon: syn::Token![:],
+        /// Order of the solver
         reactions_intra_solver_order: usize,
     },
+    /// Order of the contact reactions solver
     reactions_contact_solver_order {
+        /// The `reactions_contact_solver_order` keyword
         #[allow(unused)]
         reactions_contact_solver_order_kw: syn::Ident,
+        /// Separator between keyword and value
         #[allow(unused)]
         double_colon: syn::Token![:],
+        /// Order of the solver
         reactions_contact_solver_order: usize,
     },
+    /// Closure returning the zero value of the force
     zero_force_default {
+        /// The `zero_force_default` keyword
         #[allow(unused)]
         zero_force_default_kw: syn::Ident,
+        /// Separator between keyword and value
         #[allow(unused)]
         double_colon: syn::Token![:],
+        /// The closure
         zero_force_default: syn::ExprClosure,
     },
+    /// Closure returning the zero value of the reactions
     zero_reactions_default {
+        /// The `zero_reactions_default` keyword
         #[allow(unused)]
         zero_reactions_default_kw: syn::Ident,
+        /// Separator between keyword and value
         #[allow(unused)]
         double_colon: syn::Token![:],
+        /// The closure
         zero_reactions_default: syn::ExprClosure,
     },
+    /// User-defined values which are stored for every cell
+    scratch_fields {
+        /// The `scratch_fields` keyword
+        #[allow(unused)]
+        scratch_fields_kw: syn::Ident,
+        /// Separator between keyword and value
+        #[allow(unused)]
+        double_colon: syn::Token![:],
+        /// Parsed list of fields
+        scratch_fields: crate::aux_storage::ScratchFields,
+    },
+    /// Function for the first step of the mechanics update
     update_mechanics_interaction_step_1 {
+        /// The `update_mechanics_interaction_step_1` keyword
         #[allow(unused)]
         update_mechanics_interaction_step_1_kw: syn::Ident,
+        /// Separator between keyword and value
         #[allow(unused)]
         double_colon: syn::Token![:],
+        /// Name of the function
         update_mechanics_interaction_step_1: syn::Ident,
     },
+    /// Function for the second step of the mechanics update
     update_mechanics_interaction_step_2 {
+        /// The `update_mechanics_interaction_step_2` keyword
         #[allow(unused)]
         update_mechanics_interaction_step_2_kw: syn::Ident,
+        /// Separator between keyword and value
         #[allow(unused)]
         double_colon: syn::Token![:],
+        /// Name of the function
         update_mechanics_interaction_step_2: syn::Ident,
     },
+    /// Function for the third step of the mechanics update
     update_mechanics_interaction_step_3 {
+        /// The `update_mechanics_interaction_step_3` keyword
         #[allow(unused)]
         update_mechanics_interaction_step_3_kw: syn::Ident,
+        /// Separator between keyword and value
         #[allow(unused)]
         double_colon: syn::Token![:],
+        /// Name of the function
         update_mechanics_interaction_step_3: syn::Ident,
     },
+    /// Limits simultaneous divisions
+    division_throttle {
+        /// The `division_throttle` keyword
+        #[allow(unused)]
+        division_throttle_kw: syn::Ident,
+        /// Separator between keyword and value
+        #[allow(unused)]
+        double_colon: syn::Token![:],
+        /// Expression evaluating to the throttle
+        division_throttle: syn::Expr,
+    },
+    /// Seeding of the random number generators of cells
+    rng_mode {
+        /// The `rng_mode` keyword
+        #[allow(unused)]
+        rng_mode_kw: syn::Ident,
+        /// Separator between keyword and value
+        #[allow(unused)]
+        double_colon: syn::Token![:],
+        /// Expression evaluating to the mode
+        rng_mode: syn::Expr,
+    },
+    /// Random number generator of cells
+    rng {
+        /// The `rng` keyword
+        #[allow(unused)]
+        rng_kw: syn::Ident,
+        /// Separator between keyword and value
+        #[allow(unused)]
+        double_colon: syn::Token![:],
+        /// Path to the type of the generator
+        rng: syn::Path,
+    },
+    /// Relaxes mechanics after divisions
+    division_relaxation {
+        /// The `division_relaxation` keyword
+        #[allow(unused)]
+        division_relaxation_kw: syn::Ident,
+        /// Separator between keyword and value
+        #[allow(unused)]
+        double_colon: syn::Token![:],
+        /// Name of the relaxation variable
+        division_relaxation: Option<syn::Ident>,
+    },
+    /// Monitors the overlap of cells
+    overlap_diagnostics {
+        /// The `overlap_diagnostics` keyword
+        #[allow(unused)]
+        overlap_diagnostics_kw: syn::Ident,
+        /// Separator between keyword and value
+        #[allow(unused)]
+        double_colon: syn::Token![:],
+        /// Name of the diagnostics variable
+        overlap_diagnostics: Option<syn::Ident>,
+    },
+    /// Records energy and momentum
+    energy_accounting {
+        /// The `energy_accounting` keyword
+        #[allow(unused)]
+        energy_accounting_kw: syn::Ident,
+        /// Separator between keyword and value
+        #[allow(unused)]
+        double_colon: syn::Token![:],
+        /// Name of the accounting variable
+        energy_accounting: Option<syn::Ident>,
+    },
+    /// Mechanics-only steps before the simulation
+    equilibration {
+        /// The `equilibration` keyword
+        #[allow(unused)]
+        equilibration_kw: syn::Ident,
+        /// Separator between keyword and value
+        #[allow(unused)]
+        double_colon: syn::Token![:],
+        /// Expression evaluating to the equilibration
+        equilibration: Option<syn::Expr>,
+    },
+    /// Limits forces and displacements
+    mechanics_clamp {
+        /// The `mechanics_clamp` keyword
+        #[allow(unused)]
+        mechanics_clamp_kw: syn::Ident,
+        /// Separator between keyword and value
+        #[allow(unused)]
+        double_colon: syn::Token![:],
+        /// Name of the clamp variable
+        mechanics_clamp: Option<syn::Ident>,
+    },
+    /// Recovers from boundary errors
+    boundary_recovery {
+        /// The `boundary_recovery` keyword
+        #[allow(unused)]
+        boundary_recovery_kw: syn::Ident,
+        /// Separator between keyword and value
+        #[allow(unused)]
+        double_colon: syn::Token![:],
+        /// Name of the recovery variable
+        boundary_recovery: Option<syn::Ident>,
+    },
+    /// Simulation-wide parameters
+    global_parameters {
+        /// The `global_parameters` keyword
+        #[allow(unused)]
+        global_parameters_kw: syn::Ident,
+        /// Separator between keyword and value
+        #[allow(unused)]
+        double_colon: syn::Token![:],
+        /// Name of the parameters variable
+        global_parameters: Option<syn::Ident>,
+    },
+    /// Terminates the simulation early
+    stopping_criteria {
+        /// The `stopping_criteria` keyword
+        #[allow(unused)]
+        stopping_criteria_kw: syn::Ident,
+        /// Separator between keyword and value
+        #[allow(unused)]
+        double_colon: syn::Token![:],
+        /// Name of the criteria variable
+        stopping_criteria: Option<syn::Ident>,
+    },
+    /// Terminates the simulation once converged
+    steady_state {
+        /// The `steady_state` keyword
+        #[allow(unused)]
+        steady_state_kw: syn::Ident,
+        /// Separator between keyword and value
+        #[allow(unused)]
+        double_colon: syn::Token![:],
+        /// Name of the monitor variable
+        steady_state: Option<syn::Ident>,
+    },
+    /// Streams results to the rerun viewer
+    rerun {
+        /// The `rerun` keyword
+        #[allow(unused)]
+        rerun_kw: syn::Ident,
+        /// Separator between keyword and value
+        #[allow(unused)]
+        double_colon: syn::Token![:],
+        /// Name of the logger variable
+        rerun: Option<syn::Ident>,
+    },
+    /// Records the number of cells per voxel
+    voxel_occupancy {
+        /// The `voxel_occupancy` keyword
+        #[allow(unused)]
+        voxel_occupancy_kw: syn::Ident,
+        /// Separator between keyword and value
+        #[allow(unused)]
+        double_colon: syn::Token![:],
+        /// Name of the occupancy variable
+        voxel_occupancy: Option<syn::Ident>,
+    },
+    /// Subdivides overcrowded voxels
+    voxel_refinement {
+        /// The `voxel_refinement` keyword
+        #[allow(unused)]
+        voxel_refinement_kw: syn::Ident,
+        /// Separator between keyword and value
+        #[allow(unused)]
+        double_colon: syn::Token![:],
+        /// Name of the refinement variable
+        voxel_refinement: Option<syn::Ident>,
+    },
+    /// Mirrors boundary cells as ghosts
+    halo_exchange {
+        /// The `halo_exchange` keyword
+        #[allow(unused)]
+        halo_exchange_kw: syn::Ident,
+        /// Separator between keyword and value
+        #[allow(unused)]
+        double_colon: syn::Token![:],
+        /// Name of the halo exchange variable
+        halo_exchange: Option<syn::Ident>,
+    },
+    /// Counts messages and bytes of every subdomain
+    communication_profiler {
+        /// The `communication_profiler` keyword
+        #[allow(unused)]
+        communication_profiler_kw: syn::Ident,
+        /// Separator between keyword and value
+        #[allow(unused)]
+        double_colon: syn::Token![:],
+        /// Name of the profiler variable
+        communication_profiler: Option<syn::Ident>,
+    },
+    /// Strategy to synchronize subdomains
+    syncer {
+        /// The `syncer` keyword
+        #[allow(unused)]
+        syncer_kw: syn::Ident,
+        /// Separator between keyword and value
+        #[allow(unused)]
+        double_colon: syn::Token![:],
+        /// Path to the type of the syncer
+        syncer: Option<syn::Path>,
+    },
+    /// Updates voxels of a subdomain in parallel
+    voxel_parallelism {
+        /// The `voxel_parallelism` keyword
+        #[allow(unused)]
+        voxel_parallelism_kw: syn::Ident,
+        /// Separator between keyword and value
+        #[allow(unused)]
+        double_colon: syn::Token![:],
+        /// Name of the parallelism variable
+        voxel_parallelism: Option<syn::Ident>,
+    },
+    /// Pins threads to cores
+    thread_affinity {
+        /// The `thread_affinity` keyword
+        #[allow(unused)]
+        thread_affinity_kw: syn::Ident,
+        /// Separator between keyword and value
+        #[allow(unused)]
+        double_colon: syn::Token![:],
+        /// Name of the affinity variable
+        thread_affinity: Option<syn::Ident>,
+    },
+    /// Verifies invariants in every step
+    safety_audit {
+        /// The `safety_audit` keyword
+        #[allow(unused)]
+        safety_audit_kw: syn::Ident,
+        /// Separator between keyword and value
+        #[allow(unused)]
+        double_colon: syn::Token![:],
+        /// Name of the audit variable
+        safety_audit: Option<syn::Ident>,
+    },
+    /// Dumps cells with non-finite values
+    forensic_dump {
+        /// The `forensic_dump` keyword
+        #[allow(unused)]
+        forensic_dump_kw: syn::Ident,
+        /// Separator between keyword and value
+        #[allow(unused)]
+        double_colon: syn::Token![:],
+        /// Name of the dump variable
+        forensic_dump: Option<syn::Ident>,
+    },
+    /// Only stores results inside a region
+    storage_region {
+        /// The `storage_region` keyword
+        #[allow(unused)]
+        storage_region_kw: syn::Ident,
+        /// Separator between keyword and value
+        #[allow(unused)]
+        double_colon: syn::Token![:],
+        /// Name of the region variable
+        storage_region: Option<syn::Ident>,
+    },
+    /// Hands the state of the simulation to a callback
+    snapshot_hook {
+        /// The `snapshot_hook` keyword
+        #[allow(unused)]
+        snapshot_hook_kw: syn::Ident,
+        /// Separator between keyword and value
+        #[allow(unused)]
+        double_colon: syn::Token![:],
+        /// Name of the hook variable
+        snapshot_hook: Option<syn::Ident>,
+    },
 }
 
 macro_rules! parse_optional_kw(
@@ -202,6 +554,11 @@ impl syn::parse::Parse for Kwarg {
                 double_colon: input.parse()?,
                 communicator_name: input.parse()?,
             }),
+            "multiplex_communicator" => Ok(Kwarg::multiplex_communicator {
+                multiplex_communicator_kw: keyword,
+                double_colon: input.parse()?,
+                multiplex_communicator: input.parse::<syn::LitBool>()?.value,
+            }),
             "mechanics_solver_order" => Ok(Kwarg::mechanics_solver_order {
                 mechanics_solver_order_kw: keyword,
                 double_colon: input.parse()?,
@@ -241,6 +598,11 @@ impl syn::parse::Parse for Kwarg {
                 double_colon: input.parse()?,
                 zero_reactions_default: input.parse()?,
             }),
+            "scratch_fields" => Ok(Kwarg::scratch_fields {
+                scratch_fields_kw: keyword,
+                double_colon: input.parse()?,
+                scratch_fields: input.parse()?,
+            }),
             "update_mechanics_interaction_step_1" => {
                 Ok(Kwarg::update_mechanics_interaction_step_1 {
                     update_mechanics_interaction_step_1_kw: keyword,
@@ -262,6 +624,126 @@ impl syn::parse::Parse for Kwarg {
                     update_mechanics_interaction_step_3: input.parse()?,
                 })
             }
+            "division_throttle" => Ok(Kwarg::division_throttle {
+                division_throttle_kw: keyword,
+                double_colon: input.parse()?,
+                division_throttle: input.parse()?,
+            }),
+            "rng_mode" => Ok(Kwarg::rng_mode {
+                rng_mode_kw: keyword,
+                double_colon: input.parse()?,
+                rng_mode: input.parse()?,
+            }),
+            "rng" => Ok(Kwarg::rng {
+                rng_kw: keyword,
+                double_colon: input.parse()?,
+                rng: input.parse()?,
+            }),
+            "division_relaxation" => Ok(Kwarg::division_relaxation {
+                division_relaxation_kw: keyword,
+                double_colon: input.parse()?,
+                division_relaxation: Some(input.parse()?),
+            }),
+            "overlap_diagnostics" => Ok(Kwarg::overlap_diagnostics {
+                overlap_diagnostics_kw: keyword,
+                double_colon: input.parse()?,
+                overlap_diagnostics: Some(input.parse()?),
+            }),
+            "energy_accounting" => Ok(Kwarg::energy_accounting {
+                energy_accounting_kw: keyword,
+                double_colon: input.parse()?,
+                energy_accounting: Some(input.parse()?),
+            }),
+            "equilibration" => Ok(Kwarg::equilibration {
+                equilibration_kw: keyword,
+                double_colon: input.parse()?,
+                equilibration: Some(input.parse()?),
+            }),
+            "mechanics_clamp" => Ok(Kwarg::mechanics_clamp {
+                mechanics_clamp_kw: keyword,
+                double_colon: input.parse()?,
+                mechanics_clamp: Some(input.parse()?),
+            }),
+            "boundary_recovery" => Ok(Kwarg::boundary_recovery {
+                boundary_recovery_kw: keyword,
+                double_colon: input.parse()?,
+                boundary_recovery: Some(input.parse()?),
+            }),
+            "global_parameters" => Ok(Kwarg::global_parameters {
+                global_parameters_kw: keyword,
+                double_colon: input.parse()?,
+                global_parameters: Some(input.parse()?),
+            }),
+            "stopping_criteria" => Ok(Kwarg::stopping_criteria {
+                stopping_criteria_kw: keyword,
+                double_colon: input.parse()?,
+                stopping_criteria: Some(input.parse()?),
+            }),
+            "steady_state" => Ok(Kwarg::steady_state {
+                steady_state_kw: keyword,
+                double_colon: input.parse()?,
+                steady_state: Some(input.parse()?),
+            }),
+            "rerun" => Ok(Kwarg::rerun {
+                rerun_kw: keyword,
+                double_colon: input.parse()?,
+                rerun: Some(input.parse()?),
+            }),
+            "voxel_occupancy" => Ok(Kwarg::voxel_occupancy {
+                voxel_occupancy_kw: keyword,
+                double_colon: input.parse()?,
+                voxel_occupancy: Some(input.parse()?),
+            }),
+            "voxel_refinement" => Ok(Kwarg::voxel_refinement {
+                voxel_refinement_kw: keyword,
+                double_colon: input.parse()?,
+                voxel_refinement: Some(input.parse()?),
+            }),
+            "halo_exchange" => Ok(Kwarg::halo_exchange {
+                halo_exchange_kw: keyword,
+                double_colon: input.parse()?,
+                halo_exchange: Some(input.parse()?),
+            }),
+            "communication_profiler" => Ok(Kwarg::communication_profiler {
+                communication_profiler_kw: keyword,
+                double_colon: input.parse()?,
+                communication_profiler: Some(input.parse()?),
+            }),
+            "syncer" => Ok(Kwarg::syncer {
+                syncer_kw: keyword,
+                double_colon: input.parse()?,
+                syncer: Some(input.parse()?),
+            }),
+            "voxel_parallelism" => Ok(Kwarg::voxel_parallelism {
+                voxel_parallelism_kw: keyword,
+                double_colon: input.parse()?,
+                voxel_parallelism: Some(input.parse()?),
+            }),
+            "thread_affinity" => Ok(Kwarg::thread_affinity {
+                thread_affinity_kw: keyword,
+                double_colon: input.parse()?,
+                thread_affinity: Some(input.parse()?),
+            }),
+            "safety_audit" => Ok(Kwarg::safety_audit {
+                safety_audit_kw: keyword,
+                double_colon: input.parse()?,
+                safety_audit: Some(input.parse()?),
+            }),
+            "forensic_dump" => Ok(Kwarg::forensic_dump {
+                forensic_dump_kw: keyword,
+                double_colon: input.parse()?,
+                forensic_dump: Some(input.parse()?),
+            }),
+            "storage_region" => Ok(Kwarg::storage_region {
+                storage_region_kw: keyword,
+                double_colon: input.parse()?,
+                storage_region: Some(input.parse()?),
+            }),
+            "snapshot_hook" => Ok(Kwarg::snapshot_hook {
+                snapshot_hook_kw: keyword,
+                double_colon: input.parse()?,
+                snapshot_hook: Some(input.parse()?),
+            }),
             _ => Err(syn::Error::new(
                 keyword.span(),
                 format!("{keyword} is not a valid keyword for this macro"),
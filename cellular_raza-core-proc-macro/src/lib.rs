@@ -25,9 +25,12 @@ mod testing;
         AuxStorageCorePath,
         UpdateCycle,
         UpdateMechanics,
+        UpdateRotationalMechanics,
         UpdateInteraction,
         UpdateReactions,
         UpdateReactionsContact,
+        CustomAuxStorage,
+        ResetScratch,
     )
 )]
 pub fn _aux_storage(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
@@ -260,6 +263,14 @@ pub fn test_compatibility(input: proc_macro::TokenStream) -> proc_macro::TokenSt
     run_sim::test_compatibility(kwargs).into()
 }
 
+/// Checks that agents, AuxStorage and Communicator are consistent with the given aspects.
+#[proc_macro]
+pub fn check_aspects(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let kwargs = syn::parse_macro_input!(input as run_sim::KwargsCheckAspectsParsed);
+    let kwargs = run_sim::KwargsCheckAspects::from(kwargs);
+    run_sim::check_aspects(kwargs).into()
+}
+
 #[allow(missing_docs)]
 #[proc_macro]
 pub fn run_main(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
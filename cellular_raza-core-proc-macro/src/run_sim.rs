@@ -23,8 +23,12 @@ impl Parallelizer {
         code: &proc_macro2::TokenStream,
         core_path: &syn::Path,
         settings: &syn::Ident,
+        shared_observers: &[&syn::Ident],
+        thread_affinity: Option<&syn::Ident>,
     ) -> proc_macro2::TokenStream {
         let core_path = &core_path;
+        // Only dedicated threads can be pinned to cores
+        let pin_thread = thread_affinity.map(|affinity| quote::quote!(#affinity.pin(&mut sbox)?;));
         match &self {
             Self::OsThreads => quote::quote!({
                 let mut handles = vec![];
@@ -33,10 +37,14 @@ impl Parallelizer {
                     .into_iter()
                 {
                     let #settings = #settings.clone();
+                    #(let #shared_observers = #shared_observers.clone();)*
                     let handle = std::thread::Builder::new()
                         .name(format!("cellular_raza-worker_thread-{:03.0}", key))
                         .spawn(move ||
-                            -> Result<_, #core_path::backend::chili::SimulationError> {#code})?;
+                            -> Result<_, #core_path::backend::chili::SimulationError> {
+                                #pin_thread
+                                #code
+                            })?;
                     handles.push(handle);
                 }
                 let mut storage_accesses = vec![];
@@ -120,7 +128,9 @@ define_kwargs!(
     aux_storage_name: syn::Ident | crate::aux_storage::default_aux_storage_name(),
     zero_force_default: syn::ExprClosure | crate::aux_storage::zero_force_default(),
     zero_reactions_default: syn::ExprClosure | crate::aux_storage::zero_reactions_default(),
+    scratch_fields: crate::aux_storage::ScratchFields | Default::default(),
     communicator_name: syn::Ident | crate::communicator::default_communicator_name(),
+    multiplex_communicator: bool | false,
     mechanics_solver_order: usize | crate::run_sim::DEFAULT_MECHANICS_SOLVER_ORDER,
     reactions_intra_solver_order: usize | crate::run_sim::DEFAULT_REACTIONS_SOLVER_ORDER_INTRA,
     reactions_contact_solver_order: usize | crate::run_sim::DEFAULT_REACTIONS_SOLVER_ORDER_CONTACT,
@@ -132,6 +142,30 @@ define_kwargs!(
         crate::run_sim::default_update_mechanics_interaction_step_2_fn_name(),
     update_mechanics_interaction_step_3: syn::Ident |
         crate::run_sim::default_update_mechanics_interaction_step_3_fn_name(),
+    division_throttle: syn::Expr | crate::run_sim::default_division_throttle(),
+    division_relaxation: Option<syn::Ident> | None,
+    rng_mode: syn::Expr | crate::run_sim::default_rng_mode(),
+    rng: syn::Path | crate::run_sim::default_rng(),
+    overlap_diagnostics: Option<syn::Ident> | None,
+    energy_accounting: Option<syn::Ident> | None,
+    equilibration: Option<syn::Expr> | None,
+    mechanics_clamp: Option<syn::Ident> | None,
+    boundary_recovery: Option<syn::Ident> | None,
+    global_parameters: Option<syn::Ident> | None,
+    stopping_criteria: Option<syn::Ident> | None,
+    steady_state: Option<syn::Ident> | None,
+    rerun: Option<syn::Ident> | None,
+    voxel_occupancy: Option<syn::Ident> | None,
+    voxel_refinement: Option<syn::Ident> | None,
+    halo_exchange: Option<syn::Ident> | None,
+    communication_profiler: Option<syn::Ident> | None,
+    syncer: Option<syn::Path> | None,
+    voxel_parallelism: Option<syn::Ident> | None,
+    thread_affinity: Option<syn::Ident> | None,
+    safety_audit: Option<syn::Ident> | None,
+    forensic_dump: Option<syn::Ident> | None,
+    storage_region: Option<syn::Ident> | None,
+    snapshot_hook: Option<syn::Ident> | None,
 );
 
 define_kwargs!(
@@ -143,10 +177,23 @@ define_kwargs!(
     aspects: SimulationAspects,
     @optionals
     core_path: syn::Path | crate::kwargs::convert_core_path(None),
+    rng: syn::Path | crate::run_sim::default_rng(),
     @from
     KwargsSim
 );
 
+define_kwargs!(
+    KwargsCheckAspects,
+    KwargsCheckAspectsParsed,
+    agents: syn::Ident,
+    aspects: SimulationAspects,
+    @optionals
+    core_path: syn::Path | crate::kwargs::convert_core_path(None),
+    aux_storage_name: syn::Ident | crate::aux_storage::default_aux_storage_name(),
+    communicator_name: syn::Ident | crate::communicator::default_communicator_name(),
+    rng: syn::Path | crate::run_sim::default_rng(),
+);
+
 define_kwargs!(
     KwargsPrepareTypes,
     KwargsPrepareTypesParsed,
@@ -156,7 +203,9 @@ define_kwargs!(
     aux_storage_name: syn::Ident | crate::aux_storage::default_aux_storage_name(),
     zero_force_default: syn::ExprClosure | crate::aux_storage::zero_force_default(),
     zero_reactions_default: syn::ExprClosure | crate::aux_storage::zero_reactions_default(),
+    scratch_fields: crate::aux_storage::ScratchFields | Default::default(),
     communicator_name: syn::Ident | crate::communicator::default_communicator_name(),
+    multiplex_communicator: bool | false,
     @from
     KwargsSim,
 );
@@ -175,7 +224,9 @@ define_kwargs!(
     aux_storage_name: syn::Ident | crate::aux_storage::default_aux_storage_name(),
     zero_force_default: syn::ExprClosure | crate::aux_storage::zero_force_default(),
     zero_reactions_default: syn::ExprClosure | crate::aux_storage::zero_reactions_default(),
+    scratch_fields: crate::aux_storage::ScratchFields | Default::default(),
     communicator_name: syn::Ident | crate::communicator::default_communicator_name(),
+    multiplex_communicator: bool | false,
     mechanics_solver_order: usize | crate::run_sim::DEFAULT_MECHANICS_SOLVER_ORDER,
     reactions_intra_solver_order: usize | crate::run_sim::DEFAULT_REACTIONS_SOLVER_ORDER_INTRA,
     reactions_contact_solver_order: usize | crate::run_sim::DEFAULT_REACTIONS_SOLVER_ORDER_CONTACT,
@@ -187,10 +238,49 @@ define_kwargs!(
         crate::run_sim::default_update_mechanics_interaction_step_2_fn_name(),
     update_mechanics_interaction_step_3: syn::Ident |
         crate::run_sim::default_update_mechanics_interaction_step_3_fn_name(),
+    division_throttle: syn::Expr | crate::run_sim::default_division_throttle(),
+    division_relaxation: Option<syn::Ident> | None,
+    rng_mode: syn::Expr | crate::run_sim::default_rng_mode(),
+    rng: syn::Path | crate::run_sim::default_rng(),
+    overlap_diagnostics: Option<syn::Ident> | None,
+    energy_accounting: Option<syn::Ident> | None,
+    equilibration: Option<syn::Expr> | None,
+    mechanics_clamp: Option<syn::Ident> | None,
+    boundary_recovery: Option<syn::Ident> | None,
+    global_parameters: Option<syn::Ident> | None,
+    stopping_criteria: Option<syn::Ident> | None,
+    steady_state: Option<syn::Ident> | None,
+    rerun: Option<syn::Ident> | None,
+    voxel_occupancy: Option<syn::Ident> | None,
+    voxel_refinement: Option<syn::Ident> | None,
+    halo_exchange: Option<syn::Ident> | None,
+    communication_profiler: Option<syn::Ident> | None,
+    syncer: Option<syn::Path> | None,
+    voxel_parallelism: Option<syn::Ident> | None,
+    thread_affinity: Option<syn::Ident> | None,
+    safety_audit: Option<syn::Ident> | None,
+    forensic_dump: Option<syn::Ident> | None,
+    storage_region: Option<syn::Ident> | None,
+    snapshot_hook: Option<syn::Ident> | None,
     @from
     KwargsSim
 );
 
+/// Divisions are not throttled by default
+pub fn default_division_throttle() -> syn::Expr {
+    syn::parse_quote!(None)
+}
+
+/// Uses the default `RngMode` of the backend
+pub fn default_rng_mode() -> syn::Expr {
+    syn::parse_quote!(None)
+}
+
+/// Cells draw their random numbers from `ChaCha8Rng` by default
+pub fn default_rng() -> syn::Path {
+    syn::parse_quote!(rand_chacha::ChaCha8Rng)
+}
+
 pub fn run_main_update(kwargs: KwargsMain) -> proc_macro2::TokenStream {
     use quote::quote;
     use SimulationAspect::*;
@@ -202,10 +292,19 @@ pub fn run_main_update(kwargs: KwargsMain) -> proc_macro2::TokenStream {
     let mut step_5 = proc_macro2::TokenStream::new();
     let mut local_func_names = Vec::<proc_macro2::TokenStream>::new();
     let mut local_subdomain_func_names = Vec::<proc_macro2::TokenStream>::new();
+    // Only mechanical updates are performed during the equilibration phase
+    let mut eq_step_1 = proc_macro2::TokenStream::new();
+    let mut eq_step_2 = proc_macro2::TokenStream::new();
+    let mut eq_step_3 = proc_macro2::TokenStream::new();
+    let mut eq_step_4 = proc_macro2::TokenStream::new();
+    let mut eq_step_5 = proc_macro2::TokenStream::new();
+    let mut eq_local_func_names = Vec::<proc_macro2::TokenStream>::new();
 
     let core_path = &kwargs.core_path;
     let settings = &kwargs.settings;
     let determinism = &kwargs.determinism;
+    let division_throttle = &kwargs.division_throttle;
+    let rng_mode = &kwargs.rng_mode;
 
     let mechanics_solver_order = kwargs.mechanics_solver_order;
     let reactions_intra_solver_order = kwargs.reactions_intra_solver_order;
@@ -215,17 +314,66 @@ pub fn run_main_update(kwargs: KwargsMain) -> proc_macro2::TokenStream {
         .aspects
         .contains_multiple(vec![&Mechanics, &Interaction])
     {
-        let umis_fn_name_1 = &kwargs.update_mechanics_interaction_step_1;
-        let umis_fn_name_2 = &kwargs.update_mechanics_interaction_step_2;
+        // Overcrowded voxels are subdivided by a dedicated variant of the first step.
+        // Otherwise voxels may be distributed over multiple threads.
+        let umis_fn_name_1 = match (&kwargs.voxel_refinement, &kwargs.voxel_parallelism) {
+            (Some(_), _) => syn::Ident::new(
+                "update_mechanics_interaction_step_1_refined",
+                proc_macro2::Span::call_site(),
+            ),
+            (None, Some(_)) => syn::Ident::new(
+                "update_mechanics_interaction_step_1_parallel",
+                proc_macro2::Span::call_site(),
+            ),
+            (None, None) => kwargs.update_mechanics_interaction_step_1.clone(),
+        };
+        let umis_fn_name_2 = match &kwargs.voxel_parallelism {
+            Some(_) => syn::Ident::new(
+                "update_mechanics_interaction_step_2_parallel",
+                proc_macro2::Span::call_site(),
+            ),
+            None => kwargs.update_mechanics_interaction_step_2.clone(),
+        };
         let umis_fn_name_3 = &kwargs.update_mechanics_interaction_step_3;
         step_1.extend(quote!(sbox. #umis_fn_name_1 ()?;));
         step_2.extend(quote!(sbox. #umis_fn_name_2 (#determinism)?;));
         step_3.extend(quote!(sbox. #umis_fn_name_3 (#determinism)?;));
+        eq_step_1.extend(quote!(sbox. #umis_fn_name_1 ()?;));
+        eq_step_2.extend(quote!(sbox. #umis_fn_name_2 (#determinism)?;));
+        eq_step_3.extend(quote!(sbox. #umis_fn_name_3 (#determinism)?;));
+        // Boundary cells are mirrored as ghosts instead of exchanging positions and forces
+        if kwargs.halo_exchange.is_some() {
+            step_1.extend(quote!(sbox.send_halo_cells()?;));
+            step_2.extend(quote!(sbox.receive_halo_cells(#determinism)?;));
+            eq_step_1.extend(quote!(sbox.send_halo_cells()?;));
+            eq_step_2.extend(quote!(sbox.receive_halo_cells(#determinism)?;));
+        }
     }
 
     if kwargs.aspects.contains(&Mechanics) {
+        let mechanics_update = quote!(
+            #core_path::backend::chili::local_mechanics_update_with_parameters::<
+                _,
+                _,
+                _,
+                _,
+                _,
+                _,
+                _,
+                #mechanics_solver_order
+            >(&__cr_private_global_parameters));
+        local_func_names.push(mechanics_update.clone());
+        eq_local_func_names.push(mechanics_update);
+    }
+
+    // Torques are exchanged after the forces such that both use the same positions
+    if kwargs.aspects.contains(&RotationalMechanics) {
+        step_1.extend(quote!(sbox.update_rotational_interaction_step_1()?;));
+        step_2.extend(quote!(sbox.update_rotational_interaction_step_2(#determinism)?;));
+        step_3.extend(quote!(sbox.update_rotational_interaction_step_3(#determinism)?;));
         local_func_names.push(quote!(
-            #core_path::backend::chili::local_mechanics_update::<
+            #core_path::backend::chili::local_rotational_mechanics_update::<
+                _,
                 _,
                 _,
                 _,
@@ -233,44 +381,88 @@ pub fn run_main_update(kwargs: KwargsMain) -> proc_macro2::TokenStream {
                 _,
                 _,
                 #mechanics_solver_order
-            >));
-        step_4.extend(quote!(sbox.apply_boundary()?;));
+            >
+        ));
     }
 
     if kwargs.aspects.contains(&Interaction) {
         local_func_names
             .push(quote!(#core_path::backend::chili::local_interaction_react_to_neighbors));
+        // Equilibration only updates the mechanics of cells
+        eq_local_func_names
+            .push(quote!(#core_path::backend::chili::local_interaction_clear_neighbors));
     }
 
     if kwargs.aspects.contains(&DomainForce) {
         step_1.extend(quote!(sbox.calculate_custom_domain_force()?;));
+        eq_step_1.extend(quote!(sbox.calculate_custom_domain_force()?;));
     }
 
     if kwargs.aspects.contains(&Cycle) {
-        local_func_names.push(quote!(#core_path::backend::chili::local_cycle_update));
+        local_func_names.push(quote!(
+            #core_path::backend::chili::local_cycle_update_with_parameters(
+                &__cr_private_global_parameters
+            )
+        ));
         step_4.extend(quote!(sbox.update_cell_cycle_4(&#aux_storage_constructor)?;));
+        // Daughter cells are recorded before the boundary is applied and cells are sorted
+        let division_position = match kwargs.aspects.contains(&Mechanics) {
+            true => quote!(|cell: &_| #core_path::backend::chili::division_position(cell)),
+            false => quote!(|_: &_| ()),
+        };
+        step_4.extend(quote!(sbox.save_divisions(
+            &mut _storage_manager_divisions,
+            &next_time_point,
+            #division_position,
+        )?;));
+    }
+
+    if kwargs.aspects.contains(&Age) {
+        local_func_names.push(quote!(#core_path::backend::chili::local_age_update));
     }
 
     if kwargs.aspects.contains(&Mechanics) {
+        // Recover from boundary errors if specified
+        let apply_boundary = match &kwargs.boundary_recovery {
+            Some(recovery) => quote!(#recovery.apply_boundary(&mut sbox, &next_time_point)?;),
+            None => quote!(sbox.apply_boundary()?;),
+        };
+        // The boundary is applied after cell divisions such that daughter cells which were
+        // placed outside of the domain are caught before being sorted into voxels.
+        step_4.extend(apply_boundary.clone());
+        eq_step_4.extend(apply_boundary);
         step_4.extend(quote!(sbox.sort_cells_in_voxels_step_1()?;));
         step_5.extend(quote!(sbox.sort_cells_in_voxels_step_2(#determinism)?;));
+        eq_step_4.extend(quote!(sbox.sort_cells_in_voxels_step_1()?;));
+        eq_step_5.extend(quote!(sbox.sort_cells_in_voxels_step_2(#determinism)?;));
     }
 
     if kwargs.aspects.contains(&Reactions) {
         local_func_names.push(
-            quote!(#core_path::backend::chili::local_reactions_intracellular::<
+            quote!(#core_path::backend::chili::local_reactions_intracellular_with_parameters::<
+            _,
             _,
             _,
             _,
             _,
             #reactions_intra_solver_order,
-        >),
+        >(&__cr_private_global_parameters)),
         );
     }
 
     if kwargs.aspects.contains(&ReactionsContact) {
-        step_1.extend(quote!(sbox.update_contact_reactions_step_1()?;));
-        step_2.extend(quote!(sbox.update_contact_reactions_step_2(#determinism)?;));
+        match &kwargs.voxel_parallelism {
+            Some(_) => {
+                step_1.extend(quote!(sbox.update_contact_reactions_step_1_parallel()?;));
+                step_2.extend(quote!(
+                    sbox.update_contact_reactions_step_2_parallel(#determinism)?;
+                ));
+            }
+            None => {
+                step_1.extend(quote!(sbox.update_contact_reactions_step_1()?;));
+                step_2.extend(quote!(sbox.update_contact_reactions_step_2(#determinism)?;));
+            }
+        }
         step_3.extend(quote!(sbox.update_contact_reactions_step_3(#determinism)?;));
         local_func_names.push(quote!(#core_path::backend::chili::local_update_contact_reactions));
     }
@@ -290,7 +482,194 @@ pub fn run_main_update(kwargs: KwargsMain) -> proc_macro2::TokenStream {
             .push(quote!(#core_path::backend::chili::local_subdomain_update_reactions_extra));
     }
 
+    // Functions of custom aspects are executed after all aspects provided by cellular_raza
+    for custom in kwargs.aspects.custom_aspects() {
+        for (step, stream) in [
+            ("step_1", &mut step_1),
+            ("step_2", &mut step_2),
+            ("step_3", &mut step_3),
+            ("step_4", &mut step_4),
+            ("step_5", &mut step_5),
+        ] {
+            for function in custom.functions_of_step(step) {
+                stream.extend(quote!(#function(&mut sbox)?;));
+            }
+        }
+        for function in custom.functions_of_step("cell") {
+            local_func_names.push(quote!(#function));
+        }
+    }
+
+    // Scratch fields are reset before any other update of the step
+    if kwargs.scratch_fields.reset_every_step() {
+        step_1 = quote!(sbox.reset_scratch_fields(); #step_1);
+        eq_step_1 = quote!(sbox.reset_scratch_fields(); #eq_step_1);
+    }
+
+    // Monitor displacement and overlap of cells after updating the mechanics
+    let (record_positions, check_overlap, reduce_dt) = match &kwargs.overlap_diagnostics {
+        Some(diagnostics) if kwargs.aspects.contains(&Mechanics) => (
+            quote!(let __cr_private_previous_positions = sbox.cell_positions();),
+            quote!(#diagnostics.check(&sbox, &__cr_private_previous_positions, &next_time_point)?;),
+            quote!(#diagnostics.apply_dt_reduction(&mut _time_stepper, &next_time_point);),
+        ),
+        _ => (quote!(), quote!(), quote!()),
+    };
+
+    // Limit forces and displacements of cells
+    let (clamp_forces, clamp_displacements) = match &kwargs.mechanics_clamp {
+        Some(clamp) if kwargs.aspects.contains(&Mechanics) => (
+            quote!(#clamp.clamp_forces(&mut sbox);),
+            quote!(
+                #clamp.clamp_displacements(&mut sbox, &__cr_private_clamp_positions);
+            ),
+        ),
+        _ => (quote!(), quote!()),
+    };
+    let record_clamp_positions = match &kwargs.mechanics_clamp {
+        Some(_) if kwargs.aspects.contains(&Mechanics) => {
+            quote!(let __cr_private_clamp_positions = sbox.cell_positions();)
+        }
+        _ => quote!(),
+    };
+
+    // Verify invariants of the simulation state in every step
+    let (audit_forces, audit_cells) = match &kwargs.safety_audit {
+        Some(audit) if kwargs.aspects.contains(&Mechanics) => (
+            quote!(#audit.check_forces(&mut sbox, &next_time_point)?;),
+            quote!(#audit.check_cells(&sbox, &next_time_point)?;),
+        ),
+        Some(audit) => (
+            quote!(),
+            quote!(#audit.check_identifiers(&sbox, &next_time_point)?;),
+        ),
+        None => (quote!(), quote!()),
+    };
+
+    // Dump the state of cells whose forces or positions are no longer finite
+    let (record_forensics, check_forensics) = match &kwargs.forensic_dump {
+        Some(dump) if kwargs.aspects.contains(&Mechanics) => (
+            quote!(let __cr_private_forensic_record = #dump.record(&mut sbox, &next_time_point)?;),
+            quote!(
+                #dump.check_positions(&sbox, &__cr_private_forensic_record, &next_time_point)?;
+            ),
+        ),
+        _ => (quote!(), quote!()),
+    };
+
+    // Record energy and momentum at save points
+    let record_energy = match &kwargs.energy_accounting {
+        Some(accounting)
+            if kwargs
+                .aspects
+                .contains_multiple(vec![&Mechanics, &Interaction]) =>
+        {
+            quote!(#accounting.record(&sbox, &next_time_point)?;)
+        }
+        Some(accounting) if kwargs.aspects.contains(&Mechanics) => {
+            quote!(#accounting.record_kinetic(&sbox, &next_time_point)?;)
+        }
+        _ => quote!(),
+    };
+
+    // Hand the state of all subdomains to the hook at save points
+    let record_snapshot = match &kwargs.snapshot_hook {
+        Some(hook) => quote!(#hook.record(&sbox, &next_time_point)?;),
+        None => quote!(),
+    };
+
+    // Record the distribution of cells over voxels and report overcrowded voxels
+    let record_occupancy = match &kwargs.voxel_occupancy {
+        Some(occupancy) => quote!(
+            if next_time_point.event.is_some() {
+                #occupancy.record(&sbox, &next_time_point)?;
+                sbox.sync_all()?;
+                #occupancy.check_hot_spots(&sbox, &next_time_point)?;
+            }
+        ),
+        None => quote!(),
+    };
+
+    // Check if the simulation should be stopped and save its final state in this case
+    let check_stopping_criteria = match &kwargs.stopping_criteria {
+        Some(criteria) => quote!(
+            #criteria.record(&sbox, &next_time_point);
+            sbox.sync_all()?;
+            let mut next_time_point = next_time_point.clone();
+            if #criteria.evaluate(&next_time_point)?.is_some() {
+                __cr_private_abort = true;
+                next_time_point
+                    .event
+                    .get_or_insert(#core_path::time::TimeEvent::PartialSave);
+            }
+        ),
+        None => quote!(),
+    };
+
+    // Terminate the simulation once it has converged and save its final state
+    let check_steady_state = match &kwargs.steady_state {
+        Some(monitor) => {
+            let record_cells = match kwargs.aspects.contains(&Mechanics) {
+                true => quote!(#monitor.record_cells(&sbox, &next_time_point)?;),
+                false => quote!(#monitor.record_population(&sbox, &next_time_point)?;),
+            };
+            let record_fields = match kwargs.aspects.contains(&ReactionsExtra) {
+                true => quote!(#monitor.record_fields(&sbox, &next_time_point)?;),
+                false => quote!(),
+            };
+            quote!(
+                let mut next_time_point = next_time_point.clone();
+                if #monitor.is_checkpoint(&next_time_point) {
+                    #record_cells
+                    #record_fields
+                    sbox.sync_all()?;
+                    if #monitor.evaluate(&next_time_point)?.is_some() {
+                        __cr_private_abort = true;
+                        next_time_point
+                            .event
+                            .get_or_insert(#core_path::time::TimeEvent::PartialSave);
+                    }
+                }
+            )
+        }
+        None => quote!(),
+    };
+
+    // Only store results inside the region of interest
+    let save_results = match &kwargs.storage_region {
+        Some(region) => quote!(
+            sbox.save_subdomains_in_region(
+                &mut _storage_manager_subdomains,
+                &next_time_point,
+                &#region,
+            )?;
+            sbox.save_cells_in_region(&mut _storage_manager_cells, &next_time_point, &#region)?;
+        ),
+        None => quote!(
+            sbox.save_subdomains(&mut _storage_manager_subdomains, &next_time_point)?;
+            sbox.save_cells(&mut _storage_manager_cells, &next_time_point)?;
+        ),
+    };
+
+    // Stream cells and fields to the rerun viewer at save points
+    let log_rerun = match &kwargs.rerun {
+        Some(logger) => {
+            let log_fields = match kwargs.aspects.contains(&ReactionsExtra) {
+                true => quote!(#logger.log_subdomain_field(&sbox, &next_time_point)?;),
+                false => quote!(),
+            };
+            quote!(
+                #logger.log_subdomain_cells(&sbox, &next_time_point)?;
+                #log_fields
+            )
+        }
+        None => quote!(),
+    };
+
     let update_local_funcs = quote!(
+        // Simulation-wide parameters which are passed to the cells
+        #[allow(unused)]
+        let __cr_private_global_parameters = sbox.global_parameters().clone();
         let __cr_private_combined_local_subdomain_funcs = |
             subdomain: &mut _,
             dt,
@@ -308,7 +687,7 @@ pub fn run_main_update(kwargs: KwargsMain) -> proc_macro2::TokenStream {
             cell: &mut _,
             aux_storage: &mut _,
             dt,
-            rng: &mut rand_chacha::ChaCha8Rng
+            rng: &mut _Rng
         | -> Result<(), #core_path::backend::chili::SimulationError> {
             #(
                 #local_func_names(cell, aux_storage, dt, rng)?;
@@ -318,23 +697,211 @@ pub fn run_main_update(kwargs: KwargsMain) -> proc_macro2::TokenStream {
         sbox.run_local_cell_funcs(__cr_private_combined_local_cell_funcs, &next_time_point)?;
     );
 
+    // Mechanics-only step which is shared by the equilibration and relaxation after divisions
+    let relax_mechanics = |relaxation: proc_macro2::TokenStream,
+                           run_cell_funcs: proc_macro2::TokenStream| {
+        quote!(
+            #eq_step_1
+            sbox.sync()?;
+            #eq_step_2
+            sbox.sync()?;
+            #eq_step_3
+            let __cr_private_previous_positions = sbox.cell_positions();
+            #[allow(unused)]
+            let __cr_private_global_parameters = sbox.global_parameters().clone();
+            let __cr_private_equilibration_cell_funcs = |
+                cell: &mut _,
+                aux_storage: &mut _,
+                dt,
+                rng: &mut _Rng
+            | -> Result<(), #core_path::backend::chili::SimulationError> {
+                #(
+                    #eq_local_func_names(cell, aux_storage, dt, rng)?;
+                )*
+                Ok(())
+            };
+            sbox.#run_cell_funcs(__cr_private_equilibration_cell_funcs, &next_time_point)?;
+            if let Some(max_displacement) = #relaxation.max_displacement {
+                sbox.limit_displacements(&__cr_private_previous_positions, max_displacement);
+            }
+            #eq_step_4
+            sbox.sync()?;
+            #eq_step_5
+        )
+    };
+
+    // Relax the initial configuration before the recorded simulation starts
+    let equilibrate = match &kwargs.equilibration {
+        Some(equilibration) if kwargs.aspects.contains(&Mechanics) => {
+            let relax = relax_mechanics(
+                quote!(__cr_private_equilibration),
+                quote!(run_equilibration_cell_funcs),
+            );
+            quote!(
+                let __cr_private_equilibration = #equilibration;
+                if __cr_private_equilibration.repulsive_only {
+                    sbox.start_repulsive_only()?;
+                }
+                for step in 0..__cr_private_equilibration.n_steps {
+                    let next_time_point = __cr_private_equilibration.time_point(step);
+                    let mut f = || -> Result<(), #core_path::backend::chili::SimulationError> {
+                        sbox.set_simulation_time(next_time_point.time);
+                        #relax
+                        Ok(())
+                    };
+                    let e = f();
+                    if sbox.store_error(e)? {
+                        __cr_private_abort = true;
+                        break;
+                    }
+                }
+                if __cr_private_equilibration.repulsive_only {
+                    sbox.set_repulsive_only(false);
+                }
+            )
+        }
+        _ => quote!(),
+    };
+
+    // Relax the mechanics in additional sub-steps after mass division events
+    let (report_divisions, relax_divisions) = match &kwargs.division_relaxation {
+        Some(relaxation)
+            if kwargs.aspects.contains(&Mechanics) && kwargs.aspects.contains(&Cycle) =>
+        {
+            let relax = relax_mechanics(quote!(#relaxation), quote!(run_relaxation_cell_funcs));
+            (
+                quote!(#relaxation.report(&sbox, &next_time_point);),
+                quote!(
+                    // All subdomains need to agree on the number of sub-steps
+                    sbox.sync_all()?;
+                    for substep in 0..#relaxation.n_substeps_at(&next_time_point) {
+                        let next_time_point = #relaxation.time_point(&next_time_point, substep);
+                        #relax
+                    }
+                ),
+            )
+        }
+        _ => (quote!(), quote!()),
+    };
+
+    // Subdivide overcrowded voxels when calculating interactions
+    let set_voxel_refinement = match &kwargs.voxel_refinement {
+        Some(refinement) => quote!(sbox.set_voxel_refinement(Some(#refinement.clone()));),
+        None => quote!(),
+    };
+
+    // Distribute the voxels of every subdomain over multiple threads
+    let set_voxel_parallelism = match &kwargs.voxel_parallelism {
+        Some(parallelism) => quote!(sbox.set_voxel_parallelism(Some(#parallelism.clone()));),
+        None => quote!(),
+    };
+
+    // Mirror boundary cells into neighboring subdomains
+    let set_halo_exchange = match &kwargs.halo_exchange {
+        Some(halo_exchange) => quote!(sbox.set_halo_exchange(Some(#halo_exchange.clone()));),
+        None => quote!(),
+    };
+
+    // Count the messages exchanged by the subdomain in every step
+    let (start_profiler, record_communication) = match &kwargs.communication_profiler {
+        Some(profiler) => (
+            quote!(#profiler.start(&sbox)?;),
+            quote!(#profiler.record(&sbox)?;),
+        ),
+        None => (quote!(), quote!()),
+    };
+
+    // Decisions which concern the whole simulation require all subdomains to wait for each
+    // other. The first sync of the next step then ensures that no subdomain has moved on
+    // before all of them have evaluated their shared observers.
+    let sync_all = quote!(sbox.sync_all()?;);
+    let sync_neighbors = quote!(sbox.sync()?;);
+    let has_global_observers = kwargs.stopping_criteria.is_some()
+        || kwargs.steady_state.is_some()
+        || kwargs.voxel_occupancy.is_some()
+        || !reduce_dt.is_empty();
+    let sync_step_1 = match has_global_observers {
+        true => &sync_all,
+        false => &sync_neighbors,
+    };
+    let sync_step_4 = match reduce_dt.is_empty() {
+        true => &sync_neighbors,
+        false => &sync_all,
+    };
+
+    // Division events are only stored if cells can divide such that simulations without the
+    // Cycle aspect do not create an empty storage
+    let (open_divisions_storage, finish_divisions_storage) = match kwargs.aspects.contains(&Cycle) {
+        true => (
+            quote!(
+                let builder_divisions = builder
+                    .clone()
+                    .suffix(builder.get_suffix().join("divisions"))
+                    .delta_encoding(None);
+                let _storage_manager_divisions: #core_path::storage::StorageManager<_, _> =
+                    #core_path::storage::StorageManager::open_or_create(
+                        builder_divisions,
+                        key as u64
+                    )?;
+                let mut _storage_manager_divisions =
+                    #core_path::storage::WriteBehindStorage::from_manager(
+                        _storage_manager_divisions
+                    )?;
+            ),
+            quote!(Some(_storage_manager_divisions.finish()?)),
+        ),
+        false => (
+            quote!(),
+            quote!(None::<
+                #core_path::storage::StorageManager<#core_path::backend::chili::CellIdentifier, ()>
+            >),
+        ),
+    };
+
     quote!(
         let builder = #settings.storage.clone().init();
-        let builder_subdomains = builder.clone().suffix(builder.get_suffix().join("subdomains"));
+        // Only cells are delta encoded since subdomains and divisions are not stored in batches
+        let builder_subdomains = builder
+            .clone()
+            .suffix(builder.get_suffix().join("subdomains"))
+            .delta_encoding(None);
         let builder_cells = builder.clone().suffix(builder.get_suffix().join("cells"));
 
-        let mut _storage_manager_subdomains: #core_path::storage::StorageManager<
+        let _storage_manager_subdomains: #core_path::storage::StorageManager<
             #core_path::backend::chili::SubDomainPlainIndex,
             _
         > =
            #core_path::storage::StorageManager::open_or_create(builder_subdomains, key as u64)?;
-        let mut _storage_manager_cells: #core_path::storage::StorageManager<_, _> =
+        let _storage_manager_cells: #core_path::storage::StorageManager<_, _> =
            #core_path::storage::StorageManager::open_or_create(builder_cells, key as u64)?;
+        // Hand off results to a dedicated thread if specified by the storage builder
+        let mut _storage_manager_subdomains =
+            #core_path::storage::WriteBehindStorage::from_manager(_storage_manager_subdomains)?;
+        let mut _storage_manager_cells =
+            #core_path::storage::WriteBehindStorage::from_manager(_storage_manager_cells)?;
+        #open_divisions_storage
+
+        // Limit the number of simultaneous divisions
+        sbox.set_division_throttle(
+            Option::<#core_path::backend::chili::DivisionThrottle>::from(#division_throttle)
+        );
+
+        // Determine how random numbers are generated for cells
+        sbox.set_rng_mode(Option::<#core_path::backend::chili::RngMode>::from(#rng_mode));
+        #set_voxel_refinement
+        #set_voxel_parallelism
+        #set_halo_exchange
+
+        #[allow(unused_mut)]
+        let mut __cr_private_abort = false;
+        #equilibrate
 
         // Set up the time stepper
         let mut _time_stepper = #settings.time.clone();
         use #core_path::time::TimeStepper;
 
+        #start_profiler
+
         // Initialize the progress bar
         #[allow(unused)]
         let mut pb = match (key, #settings.show_progressbar) {
@@ -343,41 +910,65 @@ pub fn run_main_update(kwargs: KwargsMain) -> proc_macro2::TokenStream {
         };
 
         while let Some(next_time_point) = _time_stepper.advance()? {
+            if __cr_private_abort {break}
             let mut f = || -> Result<(), #core_path::backend::chili::SimulationError> {
+                // Time-dependent parameters are evaluated at the current time
+                sbox.set_simulation_time(next_time_point.time);
                 #step_1
-                sbox.sync()?;
+                #sync_step_1
                 #step_2
                 sbox.sync()?;
                 #step_3
+                #audit_forces
+                #clamp_forces
+                #record_positions
+                #record_clamp_positions
+                #record_forensics
                 #update_local_funcs
+                #check_forensics
+                #clamp_displacements
+                #check_overlap
                 #step_4
-                sbox.sync()?;
+                #report_divisions
+                #sync_step_4
+                #reduce_dt
                 #step_5
+                #relax_divisions
+                #audit_cells
+                #record_communication
+                #record_energy
+                #record_snapshot
+                #record_occupancy
+                #check_stopping_criteria
+                #check_steady_state
 
                 match (&mut pb, #settings.show_progressbar) {
                     (Some(bar), true) => _time_stepper.update_bar(bar)?,
                     _ => (),
                 };
-                sbox.save_subdomains(&mut _storage_manager_subdomains, &next_time_point)?;
-                sbox.save_cells(&mut _storage_manager_cells, &next_time_point)?;
+                #save_results
+                #log_rerun
                 Ok(())
             };
             let e = f();
             if sbox.store_error(e)? {break}
         }
         Ok(#core_path::backend::chili::StorageAccess {
-            cells: _storage_manager_cells.clone(),
-            subdomains: _storage_manager_subdomains.clone(),
+            cells: _storage_manager_cells.finish()?,
+            subdomains: _storage_manager_subdomains.finish()?,
+            divisions: #finish_divisions_storage,
         })
     )
 }
 
 ///
 pub fn run_main(kwargs: KwargsMain) -> proc_macro2::TokenStream {
+    // Custom aspects do not introduce generic parameters to the communicator
     let asp = &kwargs
         .aspects
         .items
         .iter()
+        .filter(|asp| !matches!(asp.aspect, SimulationAspect::Custom(_)))
         .map(|asp| asp.ident.clone())
         .collect::<Vec<_>>();
     let domain = &kwargs.domain;
@@ -386,6 +977,12 @@ pub fn run_main(kwargs: KwargsMain) -> proc_macro2::TokenStream {
     let core_path = &kwargs.core_path;
     let aux_storage_name = &kwargs.aux_storage_name;
     let communicator_name = &kwargs.communicator_name;
+    let rng = &kwargs.rng;
+    // Global barriers are used unless another strategy is specified
+    let syncer = match &kwargs.syncer {
+        Some(syncer) => quote::quote!(#syncer),
+        None => quote::quote!(#core_path::backend::chili::BarrierSync),
+    };
     let aux_storage_placeholders = crate::aux_storage::generics_placeholders(
         kwargs.clone(),
         kwargs.mechanics_solver_order,
@@ -393,16 +990,56 @@ pub fn run_main(kwargs: KwargsMain) -> proc_macro2::TokenStream {
     );
     let aux_storage_constructor = crate::aux_storage::default_aux_storage_initializer(&kwargs);
 
+    // Distribute the simulation-wide parameters to every subdomain
+    let set_global_parameters = match &kwargs.global_parameters {
+        Some(parameters) => quote::quote!(
+            for sbox in runner.subdomain_boxes.values_mut() {
+                sbox.set_global_parameters(Some(#parameters.clone()));
+            }
+        ),
+        None => quote::quote!(),
+    };
+
+    // The hook combines the contributions of all subdomains
+    let set_snapshot_subdomains = match &kwargs.snapshot_hook {
+        Some(hook) => quote::quote!(#hook.set_n_subdomains(runner.subdomain_boxes.len());),
+        None => quote::quote!(),
+    };
+
     let update_func = run_main_update(kwargs.clone());
-    let parallelized_update_func =
-        kwargs
-            .parallelizer
-            .parallelize_execution(&update_func, &core_path, settings);
+    let parallelized_update_func = kwargs.parallelizer.parallelize_execution(
+        &update_func,
+        &core_path,
+        settings,
+        &kwargs
+            .overlap_diagnostics
+            .iter()
+            .chain(kwargs.division_relaxation.iter())
+            .chain(kwargs.energy_accounting.iter())
+            .chain(kwargs.mechanics_clamp.iter())
+            .chain(kwargs.boundary_recovery.iter())
+            .chain(kwargs.stopping_criteria.iter())
+            .chain(kwargs.steady_state.iter())
+            .chain(kwargs.rerun.iter())
+            .chain(kwargs.voxel_occupancy.iter())
+            .chain(kwargs.voxel_refinement.iter())
+            .chain(kwargs.halo_exchange.iter())
+            .chain(kwargs.communication_profiler.iter())
+            .chain(kwargs.voxel_parallelism.iter())
+            .chain(kwargs.thread_affinity.iter())
+            .chain(kwargs.safety_audit.iter())
+            .chain(kwargs.forensic_dump.iter())
+            .chain(kwargs.storage_region.iter())
+            .chain(kwargs.snapshot_hook.iter())
+            .collect::<Vec<_>>(),
+        kwargs.thread_affinity.as_ref(),
+    );
 
     quote::quote!({
-        type _Syncer = #core_path::backend::chili::BarrierSync;
+        type _Syncer = #syncer;
+        type _Rng = #rng;
         let __run_sim = || -> Result<
-                #core_path::backend::chili::StorageAccess<_, _>,
+                #core_path::backend::chili::StorageAccess<_, _, _>,
                 #core_path::backend::chili::SimulationError
         > {
             let mut runner = #core_path::backend::chili::construct_simulation_runner::<
@@ -415,13 +1052,16 @@ pub fn run_main(kwargs: KwargsMain) -> proc_macro2::TokenStream {
                     aspects: [#(#asp),*]
                 ),
                 _Syncer,
-                _
+                _,
+                _Rng
             >(
                 #domain,
                 #agents,
                 #settings.n_threads,
                 #aux_storage_constructor,
             )?;
+            #set_global_parameters
+            #set_snapshot_subdomains
 
             let res = #parallelized_update_func?;
             Result::<_, #core_path::backend::chili::SimulationError>::Ok(res)
@@ -436,6 +1076,7 @@ pub fn prepare_types(kwargs: KwargsPrepareTypes) -> proc_macro2::TokenStream {
         struct_name: kwargs.aux_storage_name.clone(),
         core_path: kwargs.core_path.clone(),
         aspects: kwargs.aspects.clone(),
+        scratch_fields: kwargs.scratch_fields.clone(),
     };
     let mut output = aux_storage_builder.build_aux_storage();
 
@@ -444,6 +1085,7 @@ pub fn prepare_types(kwargs: KwargsPrepareTypes) -> proc_macro2::TokenStream {
         struct_name: kwargs.communicator_name.clone(),
         core_path: kwargs.core_path.clone(),
         aspects: kwargs.aspects.clone(),
+        multiplex_communicator: kwargs.multiplex_communicator,
     };
     output.extend(communicator_builder.build_communicator());
 
@@ -458,6 +1100,7 @@ pub fn test_compatibility(kwargs: KwargsCompatibility) -> proc_macro2::TokenStre
     let domain = &kwargs.domain;
     let agents = &kwargs.agents;
     let settings = &kwargs.settings;
+    let rng = &kwargs.rng;
     let mut output = quote::quote!(
         #core_path::backend::chili::compatibility_tests::domain_agents(
             &#domain,
@@ -471,7 +1114,9 @@ pub fn test_compatibility(kwargs: KwargsCompatibility) -> proc_macro2::TokenStre
         .contains_multiple(vec![&Mechanics, &Interaction])
     {
         output.extend(quote::quote!(
-            #core_path::backend::chili::compatibility_tests::mechanics_interaction(
+            #core_path::backend::chili::compatibility_tests::mechanics_interaction::<
+                _, _, _, _, _, _, _, #rng
+            >(
                 &#agents
             );
         ));
@@ -479,11 +1124,15 @@ pub fn test_compatibility(kwargs: KwargsCompatibility) -> proc_macro2::TokenStre
 
     if kwargs.aspects.contains(&Mechanics) {
         output.extend(quote::quote!(
-            #core_path::backend::chili::compatibility_tests::time_stepper_mechanics(
+            #core_path::backend::chili::compatibility_tests::time_stepper_mechanics::<
+                _, _, _, _, _, _, _, #rng
+            >(
                 &#settings.time,
                 &#agents,
             );
-            #core_path::backend::chili::compatibility_tests::mechanics_implemented(
+            #core_path::backend::chili::compatibility_tests::mechanics_implemented::<
+                _, _, _, _, _, _, #rng
+            >(
                 &#agents,
             );
         ));
@@ -500,7 +1149,27 @@ pub fn test_compatibility(kwargs: KwargsCompatibility) -> proc_macro2::TokenStre
 
     if kwargs.aspects.contains(&Cycle) {
         output.extend(quote::quote!(
-            #core_path::backend::chili::compatibility_tests::cycle_implemented(
+            #core_path::backend::chili::compatibility_tests::cycle_implemented::<
+                _, _, _, #rng
+            >(
+                &#agents,
+            );
+        ));
+    }
+
+    if kwargs.aspects.contains(&Age) {
+        output.extend(quote::quote!(
+            #core_path::backend::chili::compatibility_tests::age_implemented(
+                &#agents,
+            );
+        ));
+    }
+
+    if kwargs.aspects.contains(&RotationalMechanics) {
+        output.extend(quote::quote!(
+            #core_path::backend::chili::compatibility_tests::rotational_mechanics_implemented::<
+                _, _, _, _, _, _, _, _, #rng
+            >(
                 &#agents,
             );
         ));
@@ -533,6 +1202,80 @@ pub fn test_compatibility(kwargs: KwargsCompatibility) -> proc_macro2::TokenStre
     output
 }
 
+/// Verifies that the agents implement the concepts required by the given aspects and that the
+/// AuxStorage and Communicator were built with all of them.
+///
+/// The types are compared by the constants generated by [SimulationAspects::to_constant] such
+/// that a mismatch results in a compile-time panic with a readable message.
+pub fn check_aspects(kwargs: KwargsCheckAspects) -> proc_macro2::TokenStream {
+    let core_path = &kwargs.core_path;
+    let agents = &kwargs.agents;
+    let rng = &kwargs.rng;
+    let compat = quote::quote!(#core_path::backend::chili::compatibility_tests);
+
+    let mut assertions = proc_macro2::TokenStream::new();
+    for (kind, struct_name, macro_name) in [
+        ("AuxStorage", &kwargs.aux_storage_name, "build_aux_storage!"),
+        (
+            "Communicator",
+            &kwargs.communicator_name,
+            "build_communicator!",
+        ),
+    ] {
+        let constant_name = SimulationAspects::constant_name(struct_name);
+        for parsed_aspect in kwargs.aspects.items.iter() {
+            let aspect = parsed_aspect.ident.to_string();
+            let message = format!(
+                "{kind} {struct_name} was built without the simulation aspect {aspect}. \
+                Add {aspect} to the aspects given to {macro_name} or prepare_types!"
+            );
+            assertions.extend(quote::quote!(
+                assert!(#compat::aspects_contain(#constant_name, #aspect), #message);
+            ));
+        }
+    }
+
+    use SimulationAspect::*;
+    let mut output = quote::quote!(
+        const _: () = {
+            #assertions
+        };
+        let _ = &#agents;
+    );
+    if kwargs
+        .aspects
+        .contains_multiple(vec![&Mechanics, &Interaction])
+    {
+        output.extend(quote::quote!(
+            #compat::mechanics_interaction::<_, _, _, _, _, _, _, #rng>(&#agents);
+        ));
+    }
+    // Concepts which draw random numbers are checked for the chosen generator
+    for (aspect, check) in [
+        (
+            Mechanics,
+            quote::quote!(mechanics_implemented::<_, _, _, _, _, _, #rng>),
+        ),
+        (Cycle, quote::quote!(cycle_implemented::<_, _, _, #rng>)),
+        (Age, quote::quote!(age_implemented)),
+        (
+            RotationalMechanics,
+            quote::quote!(rotational_mechanics_implemented::<_, _, _, _, _, _, _, _, #rng>),
+        ),
+        (Reactions, quote::quote!(reactions_implemented)),
+        (
+            ReactionsContact,
+            quote::quote!(reactions_contact_implemented),
+        ),
+        (ReactionsExtra, quote::quote!(reactions_extra_implemented)),
+    ] {
+        if kwargs.aspects.contains(&aspect) {
+            output.extend(quote::quote!(#compat::#check(&#agents);));
+        }
+    }
+    output
+}
+
 pub fn run_simulation(kwargs: KwargsSim) -> proc_macro2::TokenStream {
     let types = prepare_types(KwargsPrepareTypes::from(kwargs.clone()));
 
@@ -1,3 +1,5 @@
+use syn::spanned::Spanned;
+
 pub struct NameToken;
 
 impl syn::parse::Parse for NameToken {
@@ -36,13 +38,170 @@ impl syn::parse::Parse for AspectsToken {
 pub enum SimulationAspect {
     // TODO add generic aspect which should always be present
     // None,
+    /// Motion of cells, see `Mechanics`
     Mechanics,
+    /// Forces between cells, see `Interaction`
     Interaction,
+    /// Growth and division of cells, see `Cycle`
     Cycle,
+    /// External forces of the domain, see `SubDomainForce`
     DomainForce,
+    /// Intracellular reactions, see `Reactions`
     Reactions,
+    /// Reactions with extracellular fields, see `ReactionsExtra`
     ReactionsExtra,
+    /// Reactions between neighboring cells, see `ReactionsContact`
     ReactionsContact,
+    /// Age of cells, see `Age`
+    Age,
+    /// Orientation of cells, see `RotationalMechanics`
+    RotationalMechanics,
+    /// Aspect which is defined outside of `cellular_raza`. See [CustomAspect].
+    Custom(CustomAspect),
+}
+
+/// Simulation aspect which is provided by a downstream crate.
+///
+/// Custom aspects are specified in the list of aspects by an identifier which is preceded by at
+/// least one of the following attributes.
+/// ```ignore
+/// aspects: [
+///     Mechanics,
+///     #[Communicator(comm_bonds: BondMessage)]
+///     #[AuxStorage(bonds: BondStorage)]
+///     #[Update(step_1: send_bonds, step_2: receive_bonds, cell: update_bonds)]
+///     Bonds,
+/// ]
+/// ```
+/// The [Communicator](CustomAspect::communicator) and
+/// [AuxStorage](CustomAspect::aux_storage) attributes define additional fields of the generated
+/// communicator and auxiliary storage.
+/// The [Update](CustomAspect::update) attribute inserts functions into the update steps.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct CustomAspect {
+    /// Name of the aspect
+    pub name: syn::Ident,
+    /// Field names and types of messages which are exchanged between subdomains
+    pub communicator: Vec<CustomField>,
+    /// Field names and types which are stored for every cell
+    pub aux_storage: Vec<CustomField>,
+    /// Functions which are executed in the given update steps
+    pub update: Vec<CustomUpdate>,
+}
+
+/// Field of the form `name: Type`
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct CustomField {
+    /// Name of the field
+    pub name: syn::Ident,
+    /// Separator between name and type
+    #[allow(unused)]
+    colon: syn::Token![:],
+    /// Type of the field
+    pub ty: syn::Type,
+}
+
+impl syn::parse::Parse for CustomField {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        Ok(Self {
+            name: input.parse()?,
+            colon: input.parse()?,
+            ty: input.parse()?,
+        })
+    }
+}
+
+/// Function of the form `step: path::to::function`
+///
+/// The steps `step_1` to `step_5` execute `function(&mut sbox)?` for every subdomain while
+/// `cell` executes `function(&mut cell, &mut aux_storage, dt, &mut rng)?` for every cell.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct CustomUpdate {
+    /// Step in which the function is executed
+    pub step: syn::Ident,
+    /// Separator between step and function
+    #[allow(unused)]
+    colon: syn::Token![:],
+    /// Path to the function
+    pub function: syn::Path,
+}
+
+impl CustomUpdate {
+    /// All steps into which functions can be inserted
+    pub const STEPS: [&'static str; 6] = ["step_1", "step_2", "step_3", "step_4", "step_5", "cell"];
+}
+
+impl syn::parse::Parse for CustomUpdate {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let step: syn::Ident = input.parse()?;
+        if !Self::STEPS.iter().any(|s| step == s) {
+            return Err(syn::Error::new(
+                step.span(),
+                format!(
+                    "Expected one of the update steps {} but found {}",
+                    Self::STEPS.join(", "),
+                    step
+                ),
+            ));
+        }
+        Ok(Self {
+            step,
+            colon: input.parse()?,
+            function: input.parse()?,
+        })
+    }
+}
+
+impl CustomAspect {
+    /// Parses the attributes which precede the name of the aspect
+    fn from_attributes(name: syn::Ident, attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        use syn::punctuated::Punctuated;
+        let mut custom = Self {
+            name,
+            communicator: vec![],
+            aux_storage: vec![],
+            update: vec![],
+        };
+        for attr in attrs.iter() {
+            let path = attr.path();
+            if path.is_ident("Communicator") {
+                custom.communicator.extend(attr.parse_args_with(
+                    Punctuated::<CustomField, syn::Token![,]>::parse_terminated,
+                )?);
+            } else if path.is_ident("AuxStorage") {
+                custom.aux_storage.extend(attr.parse_args_with(
+                    Punctuated::<CustomField, syn::Token![,]>::parse_terminated,
+                )?);
+            } else if path.is_ident("Update") {
+                custom.update.extend(attr.parse_args_with(
+                    Punctuated::<CustomUpdate, syn::Token![,]>::parse_terminated,
+                )?);
+            } else {
+                return Err(syn::Error::new(
+                    path.span(),
+                    "Expected one of the attributes #[Communicator(..)], #[AuxStorage(..)] or \
+                    #[Update(..)]",
+                ));
+            }
+        }
+        use itertools::*;
+        let field_names = custom.communicator.iter().chain(custom.aux_storage.iter());
+        if let Some(duplicate) = field_names.duplicates_by(|field| &field.name).next() {
+            return Err(syn::Error::new(
+                duplicate.name.span(),
+                format!("Found duplicate field: {}", duplicate.name),
+            ));
+        }
+        Ok(custom)
+    }
+
+    /// All functions which are inserted into the given step
+    pub fn functions_of_step<'a>(&'a self, step: &'a str) -> impl Iterator<Item = &'a syn::Path> {
+        self.update
+            .iter()
+            .filter(move |update| update.step == step)
+            .map(|update| &update.function)
+    }
 }
 
 // TODO add option to specify type parameters for individual aspects
@@ -59,16 +218,37 @@ pub struct ParsedSimulationAspect {
 
 impl syn::parse::Parse for ParsedSimulationAspect {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(syn::Attribute::parse_outer)?;
         let ident: syn::Ident = input.parse()?;
-        for aspect in SimulationAspect::get_aspects() {
-            if ident == format!("{:?}", aspect) {
-                return Ok(Self { aspect, ident });
-            }
+        let builtin = SimulationAspect::get_aspects()
+            .into_iter()
+            .find(|aspect| ident == format!("{:?}", aspect));
+        match (builtin, attrs.first()) {
+            (Some(aspect), None) => Ok(Self { aspect, ident }),
+            (Some(_), Some(attr)) => Err(syn::Error::new(
+                attr.span(),
+                format!(
+                    "Simulation aspect {} is provided by cellular_raza and can not be customized",
+                    ident
+                ),
+            )),
+            (None, Some(_)) => Ok(Self {
+                aspect: SimulationAspect::Custom(CustomAspect::from_attributes(
+                    ident.clone(),
+                    &attrs,
+                )?),
+                ident,
+            }),
+            (None, None) => Err(syn::Error::new(
+                ident.span(),
+                format!(
+                    "Could not find simulation aspect {}. Custom aspects need to be specified \
+                    with at least one of the attributes #[Communicator(..)], #[AuxStorage(..)] \
+                    or #[Update(..)]",
+                    ident
+                ),
+            )),
         }
-        Err(syn::Error::new(
-            ident.span(),
-            format!("Could not find simulation aspect {}", ident),
-        ))
     }
 }
 
@@ -127,6 +307,42 @@ impl SimulationAspects {
             .collect::<Vec<_>>()
     }
 
+    /// Iterates over all [CustomAspect]s in this list.
+    pub fn custom_aspects(&self) -> impl Iterator<Item = &CustomAspect> {
+        self.items
+            .iter()
+            .filter_map(|parsed_aspect| match &parsed_aspect.aspect {
+                SimulationAspect::Custom(custom) => Some(custom),
+                _ => None,
+            })
+    }
+
+    /// Name of the constant which lists the aspects of a struct built by one of the macros.
+    pub fn constant_name(struct_name: &syn::Ident) -> syn::Ident {
+        quote::format_ident!("__CR_ASPECTS_{}", struct_name)
+    }
+
+    /// Defines a constant which contains the names of all aspects in this list.
+    ///
+    /// The constant is used by the `check_aspects!` macro to verify that a struct was built
+    /// with the correct aspects.
+    pub fn to_constant(
+        &self,
+        struct_name: &syn::Ident,
+        visibility: proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        let constant_name = Self::constant_name(struct_name);
+        let names = self
+            .items
+            .iter()
+            .map(|parsed_aspect| parsed_aspect.ident.to_string());
+        quote::quote!(
+            #[doc(hidden)]
+            #[allow(non_upper_case_globals, unused)]
+            #visibility const #constant_name: &[&str] = &[#(#names),*];
+        )
+    }
+
     /// Checks if the specified [SimulationAspect] is contained in this list.
     pub fn contains(&self, aspect: &SimulationAspect) -> bool {
         self.to_aspect_list().contains(aspect)
@@ -171,10 +387,11 @@ impl SimulationAspects {
         syn::bracketed!(content in input);
         let items = syn::punctuated::Punctuated::<ParsedSimulationAspect, syn::token::Comma>::parse_terminated(&content)?;
         use itertools::*;
-        for duplicate in items.iter().duplicates_by(|pa| &pa.aspect).into_iter() {
+        // Custom aspects are compared by their name
+        if let Some(duplicate) = items.iter().duplicates_by(|pa| &pa.ident).next() {
             return Err(syn::Error::new(
                 duplicate.ident.span(),
-                format!("Found duplicate simulation aspect: {:?}", duplicate.aspect),
+                format!("Found duplicate simulation aspect: {}", duplicate.ident),
             ));
         }
         Ok(Self {
@@ -196,6 +413,8 @@ impl SimulationAspect {
             SimulationAspect::ReactionsExtra,
             SimulationAspect::ReactionsContact,
             SimulationAspect::DomainForce,
+            SimulationAspect::Age,
+            SimulationAspect::RotationalMechanics,
         ]
     }
 
@@ -217,6 +436,12 @@ impl SimulationAspect {
             SimulationAspect::ReactionsExtra => quote::quote!(ReactionsExtra),
             SimulationAspect::ReactionsContact => quote::quote!(ReactionsContact),
             SimulationAspect::DomainForce => quote::quote!(DomainForce),
+            SimulationAspect::Age => quote::quote!(Age),
+            SimulationAspect::RotationalMechanics => quote::quote!(RotationalMechanics),
+            SimulationAspect::Custom(custom) => {
+                let name = &custom.name;
+                quote::quote!(#name)
+            }
         }
     }
 
@@ -231,6 +456,12 @@ impl SimulationAspect {
             SimulationAspect::ReactionsExtra => quote::quote!(reactionsextra),
             SimulationAspect::ReactionsContact => quote::quote!(reactionscontact),
             SimulationAspect::DomainForce => quote::quote!(domainforce),
+            SimulationAspect::Age => quote::quote!(age),
+            SimulationAspect::RotationalMechanics => quote::quote!(rotationalmechanics),
+            SimulationAspect::Custom(custom) => {
+                let name = quote::format_ident!("{}", custom.name.to_string().to_lowercase());
+                quote::quote!(#name)
+            }
         }
     }
 }
@@ -245,6 +476,9 @@ impl<'a> From<&'a SimulationAspect> for String {
             SimulationAspect::ReactionsExtra => "ReactionsExtra",
             SimulationAspect::ReactionsContact => "ReactionsContact",
             SimulationAspect::DomainForce => "DomainForce",
+            SimulationAspect::Age => "Age",
+            SimulationAspect::RotationalMechanics => "RotationalMechanics",
+            SimulationAspect::Custom(custom) => return custom.name.to_string(),
         }
         .to_owned()
     }
@@ -282,3 +516,64 @@ impl syn::parse::Parse for CorePath {
         })
     }
 }
+
+#[cfg(test)]
+mod test_custom_aspects {
+    use super::*;
+
+    #[test]
+    fn parse_custom_aspect() {
+        let aspects: SimulationAspects = syn::parse_quote!(aspects: [
+            Mechanics,
+            #[Communicator(comm_bonds: BondMessage)]
+            #[AuxStorage(bonds: BondStorage, polarity: Polarity<f64>)]
+            #[Update(step_1: send_bonds, cell: my_crate::update_bonds)]
+            Bonds,
+        ]);
+        assert!(aspects.contains(&SimulationAspect::Mechanics));
+        let custom: Vec<_> = aspects.custom_aspects().collect();
+        assert_eq!(custom.len(), 1);
+        assert_eq!(custom[0].name, "Bonds");
+        assert_eq!(custom[0].communicator.len(), 1);
+        assert_eq!(custom[0].aux_storage.len(), 2);
+        assert_eq!(custom[0].functions_of_step("step_1").count(), 1);
+        assert_eq!(custom[0].functions_of_step("step_2").count(), 0);
+        assert_eq!(custom[0].functions_of_step("cell").count(), 1);
+        assert_eq!(String::from(&aspects.to_aspect_list()[1]), "Bonds");
+    }
+
+    #[test]
+    fn constant_contains_custom_aspects() {
+        let aspects: SimulationAspects = syn::parse_quote!(aspects: [
+            Cycle,
+            #[Update(cell: update_bonds)]
+            Bonds,
+        ]);
+        let struct_name: syn::Ident = syn::parse_quote!(MyAuxStorage);
+        let constant: syn::ItemConst =
+            syn::parse2(aspects.to_constant(&struct_name, quote::quote!())).unwrap();
+        assert_eq!(constant.ident, "__CR_ASPECTS_MyAuxStorage");
+        let expr = &constant.expr;
+        assert_eq!(
+            quote::quote!(#expr).to_string(),
+            quote::quote!(&["Cycle", "Bonds"]).to_string()
+        );
+    }
+
+    #[test]
+    fn reject_invalid_custom_aspects() {
+        let invalid = [
+            // Aspects of cellular_raza can not be customized
+            quote::quote!(aspects: [#[Update(step_1: f)] Mechanics]),
+            // Unknown aspects without attributes
+            quote::quote!(aspects: [Bonds]),
+            quote::quote!(aspects: [#[Update(step_6: f)] Bonds]),
+            quote::quote!(aspects: [#[Storage(bonds: Bonds)] Bonds]),
+            quote::quote!(aspects: [#[AuxStorage(bonds: A)] #[Communicator(bonds: B)] Bonds]),
+            quote::quote!(aspects: [#[Update(cell: f)] Bonds, #[Update(cell: g)] Bonds]),
+        ];
+        for stream in invalid {
+            assert!(syn::parse2::<SimulationAspects>(stream).is_err());
+        }
+    }
+}
@@ -0,0 +1,282 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, Mutex};
+
+use cellular_raza_concepts::SubDomain;
+use serde::{Deserialize, Serialize};
+
+use super::{SimulationError, SubDomainBox, SubDomainPlainIndex};
+
+/// Cores of the machine grouped by their NUMA node.
+///
+/// On dual-socket machines, every socket forms its own node with its own memory.
+/// Accessing memory of another node is considerably slower.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CpuTopology {
+    /// Indices of the cores of every node
+    pub nodes: Vec<Vec<usize>>,
+}
+
+impl CpuTopology {
+    /// Reads the NUMA nodes from `/sys/devices/system/node`.
+    ///
+    /// Falls back to a single node containing all available cores if the nodes can not be
+    /// determined.
+    pub fn detect() -> Self {
+        let mut nodes: Vec<(usize, Vec<usize>)> = std::fs::read_dir("/sys/devices/system/node")
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().into_string().ok()?;
+                let node: usize = name.strip_prefix("node")?.parse().ok()?;
+                let cpu_list = std::fs::read_to_string(entry.path().join("cpulist")).ok()?;
+                Some((node, parse_cpu_list(&cpu_list)?))
+            })
+            .filter(|(_, cores)| !cores.is_empty())
+            .collect();
+        nodes.sort();
+        match nodes.is_empty() {
+            false => Self {
+                nodes: nodes.into_iter().map(|(_, cores)| cores).collect(),
+            },
+            true => {
+                let n_cores = std::thread::available_parallelism().map_or(1, |n| n.get());
+                Self {
+                    nodes: vec![(0..n_cores).collect()],
+                }
+            }
+        }
+    }
+
+    /// Total number of cores
+    pub fn n_cores(&self) -> usize {
+        self.nodes.iter().map(|cores| cores.len()).sum()
+    }
+
+    /// Core on which the subdomain with the given index is placed.
+    ///
+    /// Subdomains are distributed evenly over all cores.
+    /// Consecutive subdomains are placed on the same node since they are usually neighbors in
+    /// the decomposed domain and thus exchange most messages.
+    /// If there are more subdomains than cores, consecutive subdomains share a core.
+    pub fn core(&self, subdomain_index: usize, n_subdomains: usize) -> Option<usize> {
+        let cores: Vec<_> = self.nodes.iter().flatten().collect();
+        match cores.is_empty() || n_subdomains == 0 {
+            true => None,
+            false => Some(*cores[(subdomain_index * cores.len() / n_subdomains) % cores.len()]),
+        }
+    }
+}
+
+/// Parses lists of cores such as `0-3,8-11`.
+fn parse_cpu_list(cpu_list: &str) -> Option<Vec<usize>> {
+    let mut cores = Vec::new();
+    for range in cpu_list.trim().split(',').filter(|range| !range.is_empty()) {
+        match range.split_once('-') {
+            Some((start, end)) => {
+                cores.extend(start.parse::<usize>().ok()?..=end.parse::<usize>().ok()?)
+            }
+            None => cores.push(range.parse().ok()?),
+        }
+    }
+    Some(cores)
+}
+
+/// Pins the calling thread to the given core.
+#[cfg(target_os = "linux")]
+fn pin_current_thread(core: usize) -> Result<(), std::io::Error> {
+    // The set only holds a fixed number of cores and CPU_SET panics for larger indices
+    if core >= libc::CPU_SETSIZE as usize {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "Core {core} exceeds the maximum number of {} cores which threads can be pinned to",
+                libc::CPU_SETSIZE
+            ),
+        ));
+    }
+    // SAFETY: The set is fully initialized by zeroing it and only accessed via the provided
+    // macros of libc. The pointer passed to sched_setaffinity is valid for the given size.
+    unsafe {
+        let mut set: libc::cpu_set_t = core::mem::zeroed();
+        libc::CPU_SET(core, &mut set);
+        match libc::sched_setaffinity(0, core::mem::size_of::<libc::cpu_set_t>(), &set) {
+            0 => Ok(()),
+            _ => Err(std::io::Error::last_os_error()),
+        }
+    }
+}
+
+/// Pins the calling thread to the given core.
+#[cfg(not(target_os = "linux"))]
+fn pin_current_thread(_: usize) -> Result<(), std::io::Error> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "Pinning threads to cores is only supported on linux",
+    ))
+}
+
+/// Pins the thread of every subdomain to a core.
+///
+/// By default, the operating system may move threads between cores at any time.
+/// On machines with multiple NUMA nodes, this means that a thread frequently accesses memory
+/// of another node.
+/// When pinned, every subdomain stays on the core chosen by [CpuTopology::core] and
+/// neighboring subdomains are placed on the same node.
+/// Afterwards, the voxels of the subdomain are moved to memory which is allocated by the
+/// pinned thread such that the operating system places them on the node of the core.
+///
+/// The affinity is passed to the [run_simulation](crate::backend::chili::run_simulation) macro
+/// via the `thread_affinity` argument.
+/// It requires the `affinity` feature and is only effective with the `OsThreads`
+/// parallelizer on linux.
+/// All clones share the resulting placement.
+/// ```
+/// # use cellular_raza_core::backend::chili::{CpuTopology, ThreadAffinity};
+/// let topology = CpuTopology {
+///     nodes: vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7]],
+/// };
+/// // Subdomains 0 and 1 are placed on the first node, 2 and 3 on the second
+/// assert_eq!(topology.core(1, 4), Some(2));
+/// assert_eq!(topology.core(2, 4), Some(4));
+/// let affinity = ThreadAffinity::from_topology(topology);
+/// // Pass `thread_affinity: affinity` to the run_simulation macro
+/// assert!(affinity.placement().is_empty());
+/// ```
+#[derive(Clone, Debug)]
+pub struct ThreadAffinity {
+    /// Cores on which the threads are placed
+    pub topology: CpuTopology,
+    /// Moves the voxels to memory allocated by the pinned thread
+    pub relocate_voxels: bool,
+    /// Core of every pinned subdomain
+    placement: Arc<Mutex<BTreeMap<SubDomainPlainIndex, usize>>>,
+}
+
+impl Default for ThreadAffinity {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ThreadAffinity {
+    /// Uses the [CpuTopology::detect]ed topology of the machine.
+    pub fn new() -> Self {
+        Self::from_topology(CpuTopology::detect())
+    }
+
+    /// Places the threads on the cores of the given topology.
+    pub fn from_topology(topology: CpuTopology) -> Self {
+        Self {
+            topology,
+            relocate_voxels: true,
+            placement: Default::default(),
+        }
+    }
+
+    /// Sets [ThreadAffinity::relocate_voxels]
+    pub fn relocate_voxels(self, relocate_voxels: bool) -> Self {
+        Self {
+            relocate_voxels,
+            ..self
+        }
+    }
+
+    /// Core of every subdomain which was pinned so far
+    pub fn placement(&self) -> BTreeMap<SubDomainPlainIndex, usize> {
+        self.placement
+            .lock()
+            .map(|placement| placement.clone())
+            .unwrap_or_default()
+    }
+
+    /// Pins the calling thread to the core of the subdomain.
+    ///
+    /// Must be called from the thread which executes the subdomain.
+    pub fn pin<I, S, C, A, Com, Sy, R>(
+        &self,
+        sbox: &mut SubDomainBox<I, S, C, A, Com, Sy, R>,
+    ) -> Result<(), SimulationError>
+    where
+        S: SubDomain,
+    {
+        let n_subdomains = sbox
+            .plain_index_to_subdomain
+            .values()
+            .collect::<BTreeSet<_>>()
+            .len();
+        let core = match self
+            .topology
+            .core(sbox.subdomain_plain_index.0, n_subdomains)
+        {
+            Some(core) => core,
+            None => return Ok(()),
+        };
+        pin_current_thread(core)?;
+        if self.relocate_voxels {
+            sbox.relocate_voxels();
+        }
+        self.placement
+            .lock()
+            .map_err(|e| SimulationError::OtherThreadError(e.to_string()))?
+            .insert(sbox.subdomain_plain_index, core);
+        Ok(())
+    }
+}
+
+impl<I, S, C, A, Com, Sy, R> SubDomainBox<I, S, C, A, Com, Sy, R>
+where
+    S: SubDomain,
+{
+    /// Moves all voxels and their cells into newly allocated memory.
+    ///
+    /// Operating systems usually place memory on the NUMA node of the thread which first
+    /// writes to it.
+    /// Since subdomains are constructed by the main thread, their voxels are initially located
+    /// on its node.
+    /// Heap allocations owned by the cells themselves are not moved.
+    pub fn relocate_voxels(&mut self) {
+        self.voxels = core::mem::take(&mut self.voxels)
+            .into_iter()
+            .map(|(index, mut vox)| {
+                vox.cells = vox.cells.drain(..).collect();
+                vox.new_cells = vox.new_cells.drain(..).collect();
+                (index, vox)
+            })
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod test_affinity {
+    use super::*;
+
+    #[test]
+    fn parse_cpu_lists() {
+        assert_eq!(parse_cpu_list("0-3,8-9\n"), Some(vec![0, 1, 2, 3, 8, 9]));
+        assert_eq!(parse_cpu_list("5"), Some(vec![5]));
+        assert_eq!(parse_cpu_list("\n"), Some(vec![]));
+        assert_eq!(parse_cpu_list("a-3"), None);
+    }
+
+    #[test]
+    fn place_subdomains() {
+        let topology = CpuTopology {
+            nodes: vec![vec![0, 2, 4], vec![1, 3, 5]],
+        };
+        let cores: Vec<_> = (0..3).map(|i| topology.core(i, 3).unwrap()).collect();
+        assert_eq!(cores, vec![0, 4, 3]);
+        // More subdomains than cores
+        let cores: Vec<_> = (0..12).map(|i| topology.core(i, 12).unwrap()).collect();
+        assert_eq!(cores, vec![0, 0, 2, 2, 4, 4, 1, 1, 3, 3, 5, 5]);
+        assert_eq!(topology.core(0, 0), None);
+        assert!(CpuTopology::detect().n_cores() > 0);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn pin_to_invalid_core() {
+        let error = pin_current_thread(libc::CPU_SETSIZE as usize).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,400 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use cellular_raza_concepts::{Position, SortCells, SubDomain};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    CellIdentifier, ForceMeasure, OverlapMeasure, SimulationError, SubDomainBox,
+    SubDomainPlainIndex, UpdateMechanics, VoxelPlainIndex,
+};
+use crate::time::NextTimePoint;
+
+/// Invariant of the simulation state which was found to be violated by the [SafetyAudit].
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum InvariantViolation {
+    /// The position of the cell contains NaN or infinite values
+    NonFinitePosition,
+    /// The total force acting on the cell contains NaN or infinite values
+    NonFiniteForce,
+    /// The cell is located outside of the simulation domain
+    OutsideDomain(String),
+    /// The cell is stored in another voxel than the one in which it is located
+    WrongVoxel {
+        /// Voxel in which the cell is located
+        expected: VoxelPlainIndex,
+    },
+    /// Another cell with the same identifier exists
+    DuplicateIdentifier {
+        /// Subdomain which contains the other cell
+        other_subdomain: SubDomainPlainIndex,
+    },
+}
+
+impl core::fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            InvariantViolation::NonFinitePosition => write!(f, "position is not finite"),
+            InvariantViolation::NonFiniteForce => write!(f, "total force is not finite"),
+            InvariantViolation::OutsideDomain(message) => {
+                write!(f, "located outside of the domain: {message}")
+            }
+            InvariantViolation::WrongVoxel { expected } => {
+                write!(
+                    f,
+                    "stored in the wrong voxel, expected voxel {}",
+                    expected.0
+                )
+            }
+            InvariantViolation::DuplicateIdentifier { other_subdomain } => write!(
+                f,
+                "identifier is also used in subdomain {}",
+                other_subdomain.0
+            ),
+        }
+    }
+}
+
+/// Single cell which violates an invariant of the simulation state.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct AuditViolation {
+    /// Identifier of the affected cell
+    pub cell: CellIdentifier,
+    /// Voxel in which the cell is stored
+    pub voxel: VoxelPlainIndex,
+    /// Invariant which is violated
+    pub violation: InvariantViolation,
+}
+
+/// All violations which were found in one subdomain after a single step.
+///
+/// This report is returned as [SimulationError::AuditError] and aborts the simulation.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct AuditReport {
+    /// Iteration after which the violations were found
+    pub iteration: usize,
+    /// Subdomain which was audited
+    pub subdomain: SubDomainPlainIndex,
+    /// Cells which violate an invariant
+    pub violations: Vec<AuditViolation>,
+}
+
+impl core::fmt::Display for AuditReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Safety audit failed in subdomain {} at iteration {} with {} violation(s):",
+            self.subdomain.0,
+            self.iteration,
+            self.violations.len()
+        )?;
+        for violation in self.violations.iter() {
+            write!(
+                f,
+                "\n  cell {:?} in voxel {}: {}",
+                violation.cell, violation.voxel.0, violation.violation
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Verifies invariants of the simulation state after every step.
+///
+/// Bugs in the backend as well as in user-defined models often remain unnoticed for many
+/// steps until the simulation either explodes or produces wrong results.
+/// The audit detects them in the step in which they occur by checking that
+/// - all positions and forces are finite,
+/// - all cells are located inside of the simulation domain,
+/// - all cells are stored in the voxel which contains their position and
+/// - no identifier is used by more than one cell.
+///
+/// At the first violation, the simulation is aborted with an [AuditReport] which lists all
+/// affected cells of the subdomain.
+/// Positions, forces and voxels are only checked if the simulation contains the `Mechanics`
+/// aspect.
+/// In this case, cells need to implement the [ForceMeasure] and [OverlapMeasure] traits.
+/// Since every cell is checked in every step, the audit is intended for debugging and
+/// considerably slows down large simulations.
+///
+/// The audit is passed to the [run_simulation](crate::backend::chili::run_simulation) macro
+/// via the `safety_audit` argument.
+/// All clones share their reports such that they can be inspected after the simulation has
+/// been aborted.
+/// ```
+/// # use cellular_raza_core::backend::chili::SafetyAudit;
+/// let audit = SafetyAudit::new();
+/// // Pass `safety_audit: audit` to the run_simulation macro
+/// assert!(audit.reports().is_empty());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SafetyAudit {
+    /// Reports of all subdomains which violated an invariant
+    reports: Arc<Mutex<Vec<AuditReport>>>,
+    /// Last iteration and subdomain in which every identifier was encountered
+    identifiers: Arc<Mutex<BTreeMap<CellIdentifier, (usize, SubDomainPlainIndex)>>>,
+}
+
+impl SafetyAudit {
+    /// Constructs a new [SafetyAudit] without any reports.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// All reports which have been emitted so far
+    pub fn reports(&self) -> Vec<AuditReport> {
+        self.reports
+            .lock()
+            .map(|reports| reports.clone())
+            .unwrap_or_default()
+    }
+
+    /// Checks that the total forces acting on all cells are finite.
+    ///
+    /// This needs to be called after all forces have been gathered and before the mechanics
+    /// are updated.
+    pub fn check_forces<I, S, C, A, Com, Sy, R, Pos, Vel, For, F, const N: usize>(
+        &self,
+        sbox: &mut SubDomainBox<I, S, C, A, Com, Sy, R>,
+        next_time_point: &NextTimePoint<F>,
+    ) -> Result<(), SimulationError>
+    where
+        S: SubDomain,
+        A: UpdateMechanics<Pos, Vel, For, N>,
+        C: ForceMeasure<For, F>,
+        F: num::Float,
+    {
+        let mut violations = Vec::new();
+        for voxel in sbox.voxels.values_mut() {
+            for (cbox, aux_storage) in voxel.cells.iter_mut() {
+                let force = aux_storage.get_current_force_and_reset();
+                if !C::force_magnitude(&force).is_finite() {
+                    violations.push(AuditViolation {
+                        cell: cbox.identifier,
+                        voxel: voxel.plain_index,
+                        violation: InvariantViolation::NonFiniteForce,
+                    });
+                }
+                aux_storage.add_force(force);
+            }
+        }
+        self.report(sbox.subdomain_plain_index, violations, next_time_point)
+    }
+
+    /// Checks positions, voxels and identifiers of all cells.
+    ///
+    /// This needs to be called after the cells have been sorted into their voxels.
+    pub fn check_cells<I, S, C, A, Com, Sy, R, Pos, F>(
+        &self,
+        sbox: &SubDomainBox<I, S, C, A, Com, Sy, R>,
+        next_time_point: &NextTimePoint<F>,
+    ) -> Result<(), SimulationError>
+    where
+        S: SubDomain,
+        S: SortCells<C, VoxelIndex = <S as SubDomain>::VoxelIndex>,
+        <S as SubDomain>::VoxelIndex: Ord,
+        C: Position<Pos>,
+        C: OverlapMeasure<Pos, F>,
+        F: num::Float,
+    {
+        let mut violations = self.duplicate_identifiers(sbox, next_time_point.iteration)?;
+        for voxel in sbox.voxels.values() {
+            for (cbox, _) in voxel.cells.iter() {
+                let pos = cbox.cell.pos();
+                // The distance of a position to itself is only finite for finite positions
+                let violation = if !C::distance(&pos, &pos).is_finite() {
+                    Some(InvariantViolation::NonFinitePosition)
+                } else {
+                    match sbox.subdomain.get_voxel_index_of(&cbox.cell) {
+                        Err(error) => Some(InvariantViolation::OutsideDomain(error.0)),
+                        Ok(index) => match sbox.voxel_index_to_plain_index.get(&index) {
+                            None => Some(InvariantViolation::OutsideDomain(
+                                "no voxel with this index exists".into(),
+                            )),
+                            Some(expected) if *expected != voxel.plain_index => {
+                                Some(InvariantViolation::WrongVoxel {
+                                    expected: *expected,
+                                })
+                            }
+                            Some(_) => None,
+                        },
+                    }
+                };
+                violations.extend(violation.map(|violation| AuditViolation {
+                    cell: cbox.identifier,
+                    voxel: voxel.plain_index,
+                    violation,
+                }));
+            }
+        }
+        self.report(sbox.subdomain_plain_index, violations, next_time_point)
+    }
+
+    /// Checks that no identifier is used by more than one cell.
+    ///
+    /// This is used instead of [SafetyAudit::check_cells] when cells have no position.
+    pub fn check_identifiers<I, S, C, A, Com, Sy, R, F>(
+        &self,
+        sbox: &SubDomainBox<I, S, C, A, Com, Sy, R>,
+        next_time_point: &NextTimePoint<F>,
+    ) -> Result<(), SimulationError>
+    where
+        S: SubDomain,
+    {
+        let violations = self.duplicate_identifiers(sbox, next_time_point.iteration)?;
+        self.report(sbox.subdomain_plain_index, violations, next_time_point)
+    }
+
+    /// Registers all identifiers of the subdomain for the given iteration.
+    ///
+    /// Identifiers which were already registered for the same iteration by this or any other
+    /// subdomain are returned as violations.
+    fn duplicate_identifiers<I, S, C, A, Com, Sy, R>(
+        &self,
+        sbox: &SubDomainBox<I, S, C, A, Com, Sy, R>,
+        iteration: usize,
+    ) -> Result<Vec<AuditViolation>, SimulationError>
+    where
+        S: SubDomain,
+    {
+        let cells = sbox.voxels.values().flat_map(|voxel| {
+            voxel
+                .cells
+                .iter()
+                .map(|(cbox, _)| (cbox.identifier, voxel.plain_index))
+        });
+        self.register_identifiers(cells, sbox.subdomain_plain_index, iteration)
+    }
+
+    /// Registers the identifiers of the given cells and their voxels.
+    fn register_identifiers(
+        &self,
+        cells: impl IntoIterator<Item = (CellIdentifier, VoxelPlainIndex)>,
+        subdomain: SubDomainPlainIndex,
+        iteration: usize,
+    ) -> Result<Vec<AuditViolation>, SimulationError> {
+        let mut identifiers = self
+            .identifiers
+            .lock()
+            .map_err(|e| SimulationError::OtherThreadError(e.to_string()))?;
+        let mut violations = Vec::new();
+        for (cell, voxel) in cells {
+            match identifiers.get(&cell).copied() {
+                Some((last_iteration, other_subdomain)) if last_iteration == iteration => {
+                    violations.push(AuditViolation {
+                        cell,
+                        voxel,
+                        violation: InvariantViolation::DuplicateIdentifier { other_subdomain },
+                    })
+                }
+                // Neighboring subdomains may already have advanced to the next iteration
+                Some((last_iteration, _)) if last_iteration > iteration => (),
+                _ => {
+                    identifiers.insert(cell, (iteration, subdomain));
+                }
+            }
+        }
+        Ok(violations)
+    }
+
+    /// Stores the violations and aborts the simulation if any occurred.
+    fn report<F>(
+        &self,
+        subdomain: SubDomainPlainIndex,
+        violations: Vec<AuditViolation>,
+        next_time_point: &NextTimePoint<F>,
+    ) -> Result<(), SimulationError> {
+        if violations.is_empty() {
+            return Ok(());
+        }
+        let report = AuditReport {
+            iteration: next_time_point.iteration,
+            subdomain,
+            violations,
+        };
+        self.reports
+            .lock()
+            .map_err(|e| SimulationError::OtherThreadError(e.to_string()))?
+            .push(report.clone());
+        Err(SimulationError::AuditError(report))
+    }
+}
+
+#[cfg(test)]
+mod test_audit {
+    use super::*;
+
+    fn cell(voxel: usize, counter: u64) -> (CellIdentifier, VoxelPlainIndex) {
+        (
+            CellIdentifier(VoxelPlainIndex(voxel), counter),
+            VoxelPlainIndex(voxel),
+        )
+    }
+
+    #[test]
+    fn detect_duplicate_identifiers() {
+        let audit = SafetyAudit::new();
+        let sub0 = SubDomainPlainIndex(0);
+        let sub1 = SubDomainPlainIndex(1);
+        let violations = audit.register_identifiers([cell(0, 0), cell(0, 1)], sub0, 0);
+        assert!(violations.unwrap().is_empty());
+        // Cells migrate between subdomains in the next iteration
+        let violations = audit.register_identifiers([cell(0, 0)], sub0, 1);
+        assert!(violations.unwrap().is_empty());
+        let violations = audit.register_identifiers([cell(0, 1)], sub1, 1);
+        assert!(violations.unwrap().is_empty());
+        // A subdomain which lags behind does not report cells which have moved on
+        let violations = audit.register_identifiers([cell(0, 0)], sub1, 0);
+        assert!(violations.unwrap().is_empty());
+
+        let violations = audit
+            .register_identifiers([cell(3, 5), cell(0, 1)], sub0, 1)
+            .unwrap();
+        assert_eq!(
+            violations,
+            vec![AuditViolation {
+                cell: CellIdentifier(VoxelPlainIndex(0), 1),
+                voxel: VoxelPlainIndex(0),
+                violation: InvariantViolation::DuplicateIdentifier {
+                    other_subdomain: sub1
+                },
+            }]
+        );
+        // The same identifier twice within one subdomain
+        let violations = audit
+            .register_identifiers([cell(4, 0), cell(4, 0)], sub0, 2)
+            .unwrap();
+        assert_eq!(violations.len(), 1);
+    }
+
+    #[test]
+    fn report_aborts_simulation() {
+        let audit = SafetyAudit::new();
+        let time_point = NextTimePoint {
+            increment: 0.1,
+            time: 1.0,
+            iteration: 10,
+            event: None,
+        };
+        assert!(audit
+            .report(SubDomainPlainIndex(2), vec![], &time_point)
+            .is_ok());
+        let violation = AuditViolation {
+            cell: CellIdentifier(VoxelPlainIndex(7), 3),
+            voxel: VoxelPlainIndex(7),
+            violation: InvariantViolation::WrongVoxel {
+                expected: VoxelPlainIndex(8),
+            },
+        };
+        let clone = audit.clone();
+        match clone.report(SubDomainPlainIndex(2), vec![violation], &time_point) {
+            Err(SimulationError::AuditError(report)) => {
+                let message = report.to_string();
+                assert!(message.contains("subdomain 2 at iteration 10"));
+                assert!(message.contains("voxel 7: stored in the wrong voxel, expected voxel 8"));
+            }
+            _ => panic!("violations need to abort the simulation"),
+        }
+        assert_eq!(audit.reports().len(), 1);
+    }
+}
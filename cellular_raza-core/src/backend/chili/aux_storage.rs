@@ -102,9 +102,13 @@ pub trait UpdateMechanics<Pos, Vel, For, const N: usize> {
 /// Stores intermediate information about the mechanics of a cell.
 #[derive(Clone, Deserialize, Serialize)]
 pub struct AuxStorageMechanics<Pos, Vel, For, const N: usize> {
+    /// Previous positions of the cell
     positions: RingBuffer<Pos, N>,
+    /// Previous velocities of the cell
     velocities: RingBuffer<Vel, N>,
+    /// Force which has been accumulated in the current step
     current_force: For,
+    /// Value to which the force is reset after every step
     zero_force: For,
 }
 
@@ -192,6 +196,133 @@ where
     }
 }
 
+// ---------------------------- UPDATE-ROTATIONAL-MECHANICS ---------------------------
+/// Rotational analogue of [UpdateMechanics].
+///
+/// Stores up to `N` previous increments of the orientation and angular velocity together with
+/// the torque which is currently acting on the cell.
+/// See also [RotationalMechanics](cellular_raza_concepts::RotationalMechanics).
+pub trait UpdateRotationalMechanics<Ang, AngVel, Tor, const N: usize> {
+    /// Stores the last increment of the orientation.
+    fn set_last_orientation(&mut self, ang: Ang);
+
+    /// Get all previous increments of the orientation.
+    fn previous_orientations<'a>(&'a self) -> RingBufferIterRef<'a, Ang, N>;
+
+    /// Stores the last increment of the angular velocity.
+    fn set_last_angular_velocity(&mut self, ang_vel: AngVel);
+
+    /// Get all previous increments of the angular velocity.
+    fn previous_angular_velocities<'a>(&'a self) -> RingBufferIterRef<'a, AngVel, N>;
+
+    /// Get the number of previous values currently stored
+    ///
+    /// This number is by definition between 0 and `N`.
+    fn n_previous_rotational_values(&self) -> usize;
+
+    /// Add torque to currently stored torques
+    fn add_torque(&mut self, torque: Tor);
+
+    /// Obtain current torque on cell
+    fn get_current_torque_and_reset(&mut self) -> Tor;
+}
+
+/// Stores intermediate information about the rotational mechanics of a cell.
+///
+/// ```
+/// use cellular_raza_core::backend::chili::*;
+///
+/// let mut aux_storage = AuxStorageRotationalMechanics::<f64, f64, f64, 2>::default();
+/// aux_storage.add_torque(1.5);
+/// aux_storage.add_torque(0.5);
+/// assert_eq!(aux_storage.get_current_torque_and_reset(), 2.0);
+/// assert_eq!(aux_storage.get_current_torque_and_reset(), 0.0);
+/// ```
+#[derive(Clone, Deserialize, Serialize)]
+pub struct AuxStorageRotationalMechanics<Ang, AngVel, Tor, const N: usize> {
+    /// Previous orientations of the cell
+    orientations: RingBuffer<Ang, N>,
+    /// Previous angular velocities of the cell
+    angular_velocities: RingBuffer<AngVel, N>,
+    /// Torque which has been accumulated in the current step
+    current_torque: Tor,
+    /// Value to which the torque is reset after every step
+    zero_torque: Tor,
+}
+
+impl<Ang, AngVel, Tor, const N: usize> Default
+    for AuxStorageRotationalMechanics<Ang, AngVel, Tor, N>
+where
+    Tor: num::Zero,
+{
+    fn default() -> Self {
+        Self {
+            orientations: RingBuffer::default(),
+            angular_velocities: RingBuffer::default(),
+            current_torque: num::Zero::zero(),
+            zero_torque: num::Zero::zero(),
+        }
+    }
+}
+
+impl<Ang, AngVel, Tor, const N: usize> DefaultFrom<Tor>
+    for AuxStorageRotationalMechanics<Ang, AngVel, Tor, N>
+where
+    Tor: Clone,
+{
+    fn default_from(value: &Tor) -> Self {
+        Self {
+            orientations: RingBuffer::default(),
+            angular_velocities: RingBuffer::default(),
+            current_torque: value.clone(),
+            zero_torque: value.clone(),
+        }
+    }
+}
+
+impl<Ang, AngVel, Tor, const N: usize> UpdateRotationalMechanics<Ang, AngVel, Tor, N>
+    for AuxStorageRotationalMechanics<Ang, AngVel, Tor, N>
+where
+    Tor: Clone + core::ops::AddAssign<Tor>,
+{
+    #[inline]
+    fn set_last_orientation(&mut self, ang: Ang) {
+        self.orientations.push(ang);
+    }
+
+    #[inline]
+    fn previous_orientations<'a>(&'a self) -> RingBufferIterRef<'a, Ang, N> {
+        self.orientations.iter()
+    }
+
+    #[inline]
+    fn set_last_angular_velocity(&mut self, ang_vel: AngVel) {
+        self.angular_velocities.push(ang_vel);
+    }
+
+    #[inline]
+    fn previous_angular_velocities<'a>(&'a self) -> RingBufferIterRef<'a, AngVel, N> {
+        self.angular_velocities.iter()
+    }
+
+    #[inline]
+    fn n_previous_rotational_values(&self) -> usize {
+        self.orientations.get_size()
+    }
+
+    #[inline]
+    fn add_torque(&mut self, torque: Tor) {
+        self.current_torque += torque;
+    }
+
+    #[inline]
+    fn get_current_torque_and_reset(&mut self) -> Tor {
+        let t = self.current_torque.clone();
+        self.current_torque = self.zero_torque.clone();
+        t
+    }
+}
+
 // ----------------------------------- UPDATE-CYCLE ----------------------------------
 /// Trait which describes how to store intermediate
 /// information on the cell cycle.
@@ -232,6 +363,7 @@ pub trait UpdateCycle {
 /// ```
 #[derive(Clone, Default, Deserialize, Serialize)]
 pub struct AuxStorageCycle {
+    /// Events which have not yet been handled by the backend
     cycle_events: Vec<CycleEvent>,
 }
 
@@ -272,6 +404,7 @@ pub trait UpdateReactions<Ri> {
 /// [Reactions](cellular_raza_concepts::Reactions) trait.
 #[derive(Clone, Default, Deserialize, Serialize)]
 pub struct AuxStorageReactions<Ri> {
+    /// Current intracellular concentrations
     concentration: Ri,
 }
 
@@ -326,7 +459,9 @@ pub trait UpdateReactionsContact<Ri, const N: usize> {
 /// Implementor of the [UpdateReactionsContact] trait.
 #[derive(Clone, Default, Deserialize, Serialize)]
 pub struct AuxStorageReactionsContact<Ri, const N: usize> {
+    /// Increment which has been accumulated in the current step
     current_increment: Ri,
+    /// Previous increments of the intracellular concentrations
     increments: RingBuffer<Ri, N>,
 }
 
@@ -386,13 +521,25 @@ pub trait UpdateInteraction {
     fn set_current_neighbors(&mut self, neighbors: usize);
     /// Increment the number of current neighbors by the provided value
     fn incr_current_neighbors(&mut self, neighbors: usize);
+    /// Obtain current pressure gathered by [Interaction::normal_force]
+    ///
+    /// [Interaction::normal_force]: cellular_raza_concepts::Interaction::normal_force
+    fn get_current_pressure(&self) -> f64;
+    /// Set the current pressure
+    fn set_current_pressure(&mut self, pressure: f64);
+    /// Increment the current pressure by the provided value
+    fn incr_current_pressure(&mut self, pressure: f64);
 }
 
-/// Helper storage for number of neighbors of
+/// Helper storage for number of neighbors and pressure of
 /// [Interaction](cellular_raza_concepts::Interaction) trait.
 #[derive(Clone, Default, Deserialize, Serialize)]
 pub struct AuxStorageInteraction {
+    /// Number of neighbors in the current step
     neighbor_count: usize,
+    /// Sum of the normal forces which neighbors exert on the cell
+    #[serde(default)]
+    pressure: f64,
 }
 
 impl UpdateInteraction for AuxStorageInteraction {
@@ -410,6 +557,21 @@ impl UpdateInteraction for AuxStorageInteraction {
     fn set_current_neighbors(&mut self, neighbors: usize) {
         self.neighbor_count = neighbors;
     }
+
+    #[inline]
+    fn get_current_pressure(&self) -> f64 {
+        self.pressure
+    }
+
+    #[inline]
+    fn set_current_pressure(&mut self, pressure: f64) {
+        self.pressure = pressure;
+    }
+
+    #[inline]
+    fn incr_current_pressure(&mut self, pressure: f64) {
+        self.pressure += pressure;
+    }
 }
 
 #[allow(unused)]
@@ -527,6 +689,26 @@ mod test_derive_aux_storage_compile {
     /// ```
     fn mechanics_other_attributes() {}
 
+    /// ```
+    /// use cellular_raza_core::backend::chili::AuxStorage;
+    /// use cellular_raza_core::backend::chili::*;
+    ///
+    /// #[derive(AuxStorage)]
+    /// struct TestStructRotational<Pos, Vel, For, Ang, AngVel, Tor, const N: usize> {
+    ///     #[UpdateMechanics(Pos, Vel, For, N)]
+    ///     aux_mechanics: AuxStorageMechanics<Pos, Vel, For, N>,
+    ///     #[UpdateRotationalMechanics(Ang, AngVel, Tor, N)]
+    ///     aux_rotation: AuxStorageRotationalMechanics<Ang, AngVel, Tor, N>,
+    /// }
+    /// fn use_impl<T, Ang, AngVel, Tor, const N: usize>(mut aux_storage: T) -> Tor
+    /// where
+    ///     T: UpdateRotationalMechanics<Ang, AngVel, Tor, N>,
+    /// {
+    ///     aux_storage.get_current_torque_and_reset()
+    /// }
+    /// ```
+    fn rotational_mechanics_default() {}
+
     /// ```
     /// use cellular_raza_core::backend::chili::AuxStorage;
     /// use cellular_raza_core::backend::chili::*;
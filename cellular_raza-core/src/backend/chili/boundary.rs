@@ -0,0 +1,274 @@
+use std::sync::{Arc, Mutex};
+
+use cellular_raza_concepts::{BoundaryError, SubDomain, SubDomainMechanics};
+use serde::{Deserialize, Serialize};
+
+use super::{CellIdentifier, SimulationError, SubDomainBox, SubDomainPlainIndex};
+use crate::time::NextTimePoint;
+
+/// Determines what happens when a cell can not be moved back into the domain.
+///
+/// The [SubDomainMechanics::apply_boundary] function of a subdomain returns an error when a
+/// cell travelled so far outside of the domain that it can not be reflected back into it.
+/// By default, this error aborts the simulation.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+pub enum BoundaryPolicy {
+    /// Abort the simulation with the [BoundaryError]
+    #[default]
+    Error,
+    /// Move the cell onto the closest point inside the domain.
+    /// See [SubDomainMechanics::clamp_to_domain].
+    ClampToDomain,
+    /// Remove the cell from the simulation
+    RemoveCell,
+    /// Map the cell periodically into the domain.
+    /// See [SubDomainMechanics::wrap_into_domain].
+    Wrap,
+}
+
+/// Recorded whenever a [BoundaryPolicy] recovered from a [BoundaryError].
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct BoundaryIncident {
+    /// Iteration at which the error occurred
+    pub iteration: usize,
+    /// Subdomain in which the cell was located
+    pub subdomain: SubDomainPlainIndex,
+    /// Identifier of the affected cell
+    pub cell: CellIdentifier,
+    /// Policy which was applied to the cell
+    pub policy: BoundaryPolicy,
+    /// Message of the original error
+    pub message: String,
+}
+
+/// Recovers from errors when applying boundary conditions.
+///
+/// Long simulations can be aborted by rare numerical excursions of single cells.
+/// Instead of failing, the given [BoundaryPolicy] is applied to the affected cells and a
+/// [BoundaryIncident] is recorded.
+///
+/// The recovery is passed to the
+/// [run_simulation](crate::backend::chili::run_simulation) macro via the `boundary_recovery`
+/// argument.
+/// All clones share their incidents such that they can be inspected after the simulation has
+/// finished.
+/// ```
+/// # use cellular_raza_core::backend::chili::{BoundaryPolicy, BoundaryRecovery};
+/// let recovery = BoundaryRecovery::new(BoundaryPolicy::ClampToDomain);
+/// // Pass `boundary_recovery: recovery` to the run_simulation macro
+/// assert!(recovery.incidents().is_empty());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct BoundaryRecovery {
+    /// Policy which is applied when a cell can not be moved back into the domain
+    pub policy: BoundaryPolicy,
+    /// Incidents which are shared by all clones
+    incidents: Arc<Mutex<Vec<BoundaryIncident>>>,
+}
+
+impl BoundaryRecovery {
+    /// Recover from boundary errors with the given policy.
+    pub fn new(policy: BoundaryPolicy) -> Self {
+        Self {
+            policy,
+            incidents: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// All incidents which have been recorded so far
+    pub fn incidents(&self) -> Vec<BoundaryIncident> {
+        self.incidents
+            .lock()
+            .map(|incidents| incidents.clone())
+            .unwrap_or_default()
+    }
+
+    /// Applies the boundary conditions to all cells of the subdomain and records incidents.
+    ///
+    /// This replaces the [SubDomainBox::apply_boundary] method during the simulation.
+    pub fn apply_boundary<I, S, C, A, Com, Sy, R, Pos, Vel, F>(
+        &self,
+        sbox: &mut SubDomainBox<I, S, C, A, Com, Sy, R>,
+        next_time_point: &NextTimePoint<F>,
+    ) -> Result<(), SimulationError>
+    where
+        S: SubDomain,
+        S: SubDomainMechanics<Pos, Vel>,
+        C: cellular_raza_concepts::Position<Pos>,
+        C: cellular_raza_concepts::Velocity<Vel>,
+    {
+        let failed = sbox.apply_boundary_with_policy(self.policy)?;
+        if failed.is_empty() {
+            return Ok(());
+        }
+        let subdomain = sbox.subdomain_plain_index;
+        self.incidents
+            .lock()
+            .map_err(|e| SimulationError::OtherThreadError(e.to_string()))?
+            .extend(failed.into_iter().map(|(cell, error)| BoundaryIncident {
+                iteration: next_time_point.iteration,
+                subdomain,
+                cell,
+                policy: self.policy,
+                message: error.0,
+            }));
+        Ok(())
+    }
+}
+
+impl<I, S, C, A, Com, Sy, R> SubDomainBox<I, S, C, A, Com, Sy, R>
+where
+    S: SubDomain,
+{
+    /// Applies boundary conditions to cells and recovers from errors with the given policy.
+    ///
+    /// Returns the identifiers of all cells for which [SubDomainMechanics::apply_boundary]
+    /// failed together with the original error.
+    /// With the [BoundaryPolicy::Error] policy, the first error is returned instead.
+    pub fn apply_boundary_with_policy<Pos, Vel>(
+        &mut self,
+        policy: BoundaryPolicy,
+    ) -> Result<Vec<(CellIdentifier, BoundaryError)>, BoundaryError>
+    where
+        C: cellular_raza_concepts::Position<Pos>,
+        C: cellular_raza_concepts::Velocity<Vel>,
+        S: SubDomainMechanics<Pos, Vel>,
+    {
+        let mut failed = Vec::new();
+        for voxel in self.voxels.values_mut() {
+            let mut removed = Vec::new();
+            for (n, (cbox, _)) in voxel.cells.iter_mut().enumerate() {
+                let mut pos = cbox.cell.pos();
+                let mut vel = cbox.cell.velocity();
+                if let Err(error) = self.subdomain.apply_boundary(&mut pos, &mut vel) {
+                    // Start again from the unmodified position and velocity
+                    let mut pos = cbox.cell.pos();
+                    let mut vel = cbox.cell.velocity();
+                    match policy {
+                        BoundaryPolicy::Error => return Err(error),
+                        BoundaryPolicy::ClampToDomain => {
+                            self.subdomain.clamp_to_domain(&mut pos, &mut vel)?
+                        }
+                        BoundaryPolicy::Wrap => {
+                            self.subdomain.wrap_into_domain(&mut pos, &mut vel)?
+                        }
+                        BoundaryPolicy::RemoveCell => removed.push(n),
+                    }
+                    cbox.cell.set_pos(&pos);
+                    cbox.cell.set_velocity(&vel);
+                    failed.push((cbox.identifier, error));
+                } else {
+                    cbox.cell.set_pos(&pos);
+                    cbox.cell.set_velocity(&vel);
+                }
+            }
+            for n in removed.into_iter().rev() {
+                voxel.cells.remove(n);
+            }
+        }
+        Ok(failed)
+    }
+}
+
+#[cfg(test)]
+mod test_boundary_policy {
+    use super::*;
+    use crate::backend::chili::test_fixtures::*;
+    use crate::backend::chili::VoxelPlainIndex;
+
+    /// One cell can be reflected back into the domain while the other one is too far outside
+    fn escaped_cells() -> LineBox {
+        let mut sbox = subdomain_box(3, &[0.5, 1.5, 2.5]);
+        for (_, cell) in sbox.iter_cells_mut() {
+            if cell.pos == 0.5 {
+                cell.pos = -0.5;
+            }
+            if cell.pos == 2.5 {
+                cell.pos = 7.0;
+            }
+        }
+        sbox
+    }
+
+    fn positions(sbox: &LineBox) -> Vec<f64> {
+        sbox.iter_cells().map(|cbox| cbox.cell.pos).collect()
+    }
+
+    const ESCAPED: CellIdentifier = CellIdentifier(VoxelPlainIndex(2), 0);
+
+    #[test]
+    fn policy_error() {
+        let mut sbox = escaped_cells();
+        let error = sbox
+            .apply_boundary_with_policy(BoundaryPolicy::Error)
+            .unwrap_err();
+        assert!(error.0.contains("too far outside"));
+    }
+
+    #[test]
+    fn policy_clamp_to_domain() {
+        let mut sbox = escaped_cells();
+        let failed = sbox
+            .apply_boundary_with_policy(BoundaryPolicy::ClampToDomain)
+            .unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].0, ESCAPED);
+        assert_eq!(positions(&sbox), vec![0.5, 1.5, 3.0]);
+    }
+
+    #[test]
+    fn policy_remove_cell() {
+        let mut sbox = escaped_cells();
+        let failed = sbox
+            .apply_boundary_with_policy(BoundaryPolicy::RemoveCell)
+            .unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].0, ESCAPED);
+        assert_eq!(positions(&sbox), vec![0.5, 1.5]);
+        assert!(sbox.get_cell_by_id(&ESCAPED).is_none());
+    }
+
+    #[test]
+    fn policy_wrap() {
+        let mut sbox = escaped_cells();
+        let failed = sbox
+            .apply_boundary_with_policy(BoundaryPolicy::Wrap)
+            .unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].0, ESCAPED);
+        assert_eq!(positions(&sbox), vec![0.5, 1.5, 1.0]);
+    }
+
+    #[test]
+    fn recovery_records_incidents() {
+        let recovery = BoundaryRecovery::new(BoundaryPolicy::Wrap);
+        let time_point = NextTimePoint {
+            increment: 0.1,
+            time: 0.5,
+            iteration: 5,
+            event: None,
+        };
+        let mut sbox = subdomain_box(3, &[0.5, 1.5, 2.5]);
+        recovery.apply_boundary(&mut sbox, &time_point).unwrap();
+        assert!(recovery.incidents().is_empty());
+
+        let mut sbox = escaped_cells();
+        recovery
+            .clone()
+            .apply_boundary(&mut sbox, &time_point)
+            .unwrap();
+        assert_eq!(positions(&sbox), vec![0.5, 1.5, 1.0]);
+        let incidents = recovery.incidents();
+        assert_eq!(incidents.len(), 1);
+        assert_eq!(incidents[0].iteration, 5);
+        assert_eq!(incidents[0].subdomain, SubDomainPlainIndex(0));
+        assert_eq!(incidents[0].cell, ESCAPED);
+        assert_eq!(incidents[0].policy, BoundaryPolicy::Wrap);
+        assert!(incidents[0].message.contains("too far outside"));
+
+        let recovery = BoundaryRecovery::new(BoundaryPolicy::Error);
+        let mut sbox = escaped_cells();
+        assert!(recovery.apply_boundary(&mut sbox, &time_point).is_err());
+        assert!(recovery.incidents().is_empty());
+    }
+}
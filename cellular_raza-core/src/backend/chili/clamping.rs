@@ -0,0 +1,232 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use cellular_raza_concepts::{Position, SubDomain, Xapy};
+
+use super::{CellIdentifier, OverlapMeasure, SubDomainBox, UpdateMechanics};
+
+/// Measures the magnitude of forces acting on cells.
+///
+/// This trait is required by [MechanicsClamp] in order to limit the total force acting on a
+/// cell.
+pub trait ForceMeasure<For, F> {
+    /// Magnitude of the given force
+    fn force_magnitude(force: &For) -> F;
+}
+
+/// Limits forces and displacements of cells in every step.
+///
+/// A single bad interaction (for example between two almost completely overlapping cells) can
+/// produce forces which eject cells out of the simulation domain.
+/// The subsequent [BoundaryError](cellular_raza_concepts::BoundaryError) aborts the whole run.
+/// When [MechanicsClamp::max_force] is set, the total force acting on a cell is rescaled such
+/// that its magnitude does not exceed the given value before updating the mechanics.
+/// Afterwards, every cell which travelled further than [MechanicsClamp::max_displacement] is
+/// moved back onto the line between its previous and new position.
+///
+/// The total force includes the contributions of the
+/// [SubDomainForce](cellular_raza_concepts::SubDomainForce) of the domain.
+/// Cells need to implement the [ForceMeasure] and [OverlapMeasure] traits.
+/// By default, no clamping is applied.
+///
+/// The clamp is passed to the
+/// [run_simulation](crate::backend::chili::run_simulation) macro via the `mechanics_clamp`
+/// argument.
+/// All clones share their counters such that they can be inspected after the simulation has
+/// finished.
+/// ```
+/// # use cellular_raza_core::backend::chili::MechanicsClamp;
+/// let clamp = MechanicsClamp::new().max_displacement(0.5).max_force(100.0);
+/// // Pass `mechanics_clamp: clamp` to the run_simulation macro
+/// assert_eq!(clamp.n_displacement_clamps(), 0);
+/// assert_eq!(clamp.n_force_clamps(), 0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct MechanicsClamp<F> {
+    /// Maximum distance which any cell may travel in one step
+    pub max_displacement: Option<F>,
+    /// Maximum magnitude of the total force acting on any cell
+    pub max_force: Option<F>,
+    /// Number of clamped displacements which is shared by all clones
+    n_displacement_clamps: Arc<AtomicUsize>,
+    /// Number of clamped forces which is shared by all clones
+    n_force_clamps: Arc<AtomicUsize>,
+}
+
+impl<F> Default for MechanicsClamp<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F> MechanicsClamp<F> {
+    /// Constructs a new clamp which does not limit anything.
+    pub fn new() -> Self {
+        Self {
+            max_displacement: None,
+            max_force: None,
+            n_displacement_clamps: Arc::new(AtomicUsize::new(0)),
+            n_force_clamps: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Limit the distance which any cell may travel in one step.
+    pub fn max_displacement(self, max_displacement: F) -> Self {
+        Self {
+            max_displacement: Some(max_displacement),
+            ..self
+        }
+    }
+
+    /// Limit the magnitude of the total force acting on any cell.
+    pub fn max_force(self, max_force: F) -> Self {
+        Self {
+            max_force: Some(max_force),
+            ..self
+        }
+    }
+
+    /// Number of times the displacement of a cell was limited by all threads
+    pub fn n_displacement_clamps(&self) -> usize {
+        self.n_displacement_clamps.load(Ordering::SeqCst)
+    }
+
+    /// Number of times the force acting on a cell was limited by all threads
+    pub fn n_force_clamps(&self) -> usize {
+        self.n_force_clamps.load(Ordering::SeqCst)
+    }
+
+    /// Rescales the forces acting on cells which exceed [MechanicsClamp::max_force].
+    ///
+    /// This needs to be called after all forces have been gathered and before the mechanics
+    /// are updated.
+    /// Returns the number of clamped forces.
+    pub fn clamp_forces<I, S, C, A, Com, Sy, R, Pos, Vel, For, const N: usize>(
+        &self,
+        sbox: &mut SubDomainBox<I, S, C, A, Com, Sy, R>,
+    ) -> usize
+    where
+        S: SubDomain,
+        A: UpdateMechanics<Pos, Vel, For, N>,
+        C: ForceMeasure<For, F>,
+        For: Xapy<F>,
+        F: num::Float,
+    {
+        let max_force = match self.max_force {
+            Some(max_force) => max_force,
+            None => return 0,
+        };
+        let mut n_clamped = 0;
+        for (_, aux_storage) in sbox
+            .voxels
+            .values_mut()
+            .flat_map(|voxel| voxel.cells.iter_mut())
+        {
+            let force = aux_storage.get_current_force_and_reset();
+            let magnitude = C::force_magnitude(&force);
+            if magnitude > max_force {
+                aux_storage.add_force(force.xa(max_force / magnitude));
+                n_clamped += 1;
+            } else {
+                aux_storage.add_force(force);
+            }
+        }
+        self.n_force_clamps.fetch_add(n_clamped, Ordering::SeqCst);
+        n_clamped
+    }
+
+    /// Limits the displacement of cells to [MechanicsClamp::max_displacement].
+    ///
+    /// The `previous_positions` should be obtained via [SubDomainBox::cell_positions] before
+    /// updating the mechanics.
+    /// Returns the number of clamped displacements.
+    pub fn clamp_displacements<I, S, C, A, Com, Sy, R, Pos>(
+        &self,
+        sbox: &mut SubDomainBox<I, S, C, A, Com, Sy, R>,
+        previous_positions: &BTreeMap<CellIdentifier, Pos>,
+    ) -> usize
+    where
+        S: SubDomain,
+        C: Position<Pos>,
+        C: OverlapMeasure<Pos, F>,
+        Pos: Xapy<F>,
+        F: num::Float,
+    {
+        let n_clamped = match self.max_displacement {
+            Some(max_displacement) => {
+                sbox.limit_displacements(previous_positions, max_displacement)
+            }
+            None => 0,
+        };
+        self.n_displacement_clamps
+            .fetch_add(n_clamped, Ordering::SeqCst);
+        n_clamped
+    }
+}
+
+#[cfg(test)]
+mod test_mechanics_clamp {
+    use super::*;
+    use crate::backend::chili::test_fixtures::*;
+    use crate::backend::chili::VoxelPlainIndex;
+
+    #[test]
+    fn clamp_oversized_forces() {
+        let forces = |max_force: f64| {
+            let mut sbox = subdomain_box(3, &[0.5, 1.5, 2.5]);
+            // The force of the domain acts on all cells in addition to the interaction
+            sbox.subdomain.domain_force = 5.0;
+            sbox.calculate_custom_domain_force().unwrap();
+            let voxel = sbox.voxels.get_mut(&VoxelPlainIndex(0)).unwrap();
+            voxel.cells[0].1.add_force(-20.0);
+            let clamp = MechanicsClamp::new().max_force(max_force);
+            let n_clamped = clamp.clamp_forces(&mut sbox);
+            assert_eq!(clamp.n_force_clamps(), n_clamped);
+            let forces: Vec<_> = sbox
+                .voxels
+                .values_mut()
+                .flat_map(|voxel| voxel.cells.iter_mut())
+                .map(|(_, aux)| aux.get_current_force_and_reset())
+                .collect();
+            (n_clamped, forces)
+        };
+        assert_eq!(forces(2.0), (3, vec![-2.0, 2.0, 2.0]));
+        assert_eq!(forces(10.0), (1, vec![-10.0, 5.0, 5.0]));
+        assert_eq!(forces(20.0), (0, vec![-15.0, 5.0, 5.0]));
+    }
+
+    #[test]
+    fn clamp_oversized_displacements() {
+        let mut sbox = subdomain_box(3, &[0.5, 1.5]);
+        let previous_positions = sbox.cell_positions();
+        for (_, cell) in sbox.iter_cells_mut() {
+            cell.pos += match cell.pos < 1.0 {
+                true => 2.0,
+                false => 0.1,
+            };
+        }
+        assert_eq!(
+            MechanicsClamp::new().clamp_displacements(&mut sbox, &previous_positions),
+            0
+        );
+        let clamp = MechanicsClamp::new().max_displacement(0.5);
+        assert_eq!(clamp.clamp_displacements(&mut sbox, &previous_positions), 1);
+        assert_eq!(clamp.n_displacement_clamps(), 1);
+        let positions: Vec<_> = sbox.iter_cells().map(|cbox| cbox.cell.pos).collect();
+        assert!((positions[0] - 1.0).abs() < 1e-12);
+        assert!((positions[1] - 1.6).abs() < 1e-12);
+    }
+
+    #[test]
+    fn clones_share_counters() {
+        let clamp = MechanicsClamp::new().max_force(1.0);
+        assert_eq!(clamp.max_force, Some(1.0));
+        assert_eq!(clamp.max_displacement, None);
+        let clone = clamp.clone();
+        clone.n_force_clamps.fetch_add(3, Ordering::SeqCst);
+        clone.n_displacement_clamps.fetch_add(1, Ordering::SeqCst);
+        assert_eq!(clamp.n_force_clamps(), 3);
+        assert_eq!(clamp.n_displacement_clamps(), 1);
+    }
+}
@@ -0,0 +1,414 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use cellular_raza_concepts::IndexError;
+use serde::{Deserialize, Serialize};
+
+use super::{CellBox, CellIdentifier, SimulationError, StorageAccess, SubDomainPlainIndex};
+use crate::storage::StorageInterfaceLoad;
+
+/// Differences between two runs at a single save point.
+///
+/// See [compare_runs].
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SavePointDifference {
+    /// Iteration at which both runs stored their results
+    pub iteration: u64,
+    /// Number of cells in the first and second run
+    pub n_cells: [usize; 2],
+    /// Number of cells whose identifier is only present in one of the two runs
+    pub unmatched_cells: usize,
+    /// Largest distance between the positions of two cells with identical identifiers
+    pub max_position_deviation: f64,
+    /// Cell which showed the [max_position_deviation](Self::max_position_deviation)
+    pub max_deviation_cell: Option<CellIdentifier>,
+    /// L2 norm of the difference between the fields of all subdomains
+    pub field_l2_difference: f64,
+}
+
+impl SavePointDifference {
+    /// Number of cells in the second run minus the number of cells in the first run
+    pub fn cell_count_delta(&self) -> i64 {
+        self.n_cells[1] as i64 - self.n_cells[0] as i64
+    }
+}
+
+/// Report of all differences between two runs obtained by [compare_runs].
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct RunComparison {
+    /// Differences at every iteration which was stored by both runs
+    pub save_points: Vec<SavePointDifference>,
+    /// Iterations which were only stored by the first run
+    pub only_in_first: Vec<u64>,
+    /// Iterations which were only stored by the second run
+    pub only_in_second: Vec<u64>,
+}
+
+impl RunComparison {
+    /// Largest deviation of cell positions over all save points
+    pub fn max_position_deviation(&self) -> f64 {
+        self.save_points
+            .iter()
+            .map(|s| s.max_position_deviation)
+            .fold(0.0, f64::max)
+    }
+
+    /// Largest difference of fields over all save points
+    pub fn max_field_l2_difference(&self) -> f64 {
+        self.save_points
+            .iter()
+            .map(|s| s.field_l2_difference)
+            .fold(0.0, f64::max)
+    }
+
+    /// Checks if both runs stored identical cells at identical iterations and positions and
+    /// fields agree within the given tolerances.
+    pub fn agrees_within(&self, position_tolerance: f64, field_tolerance: f64) -> bool {
+        self.only_in_first.is_empty()
+            && self.only_in_second.is_empty()
+            && self.save_points.iter().all(|s| {
+                s.unmatched_cells == 0
+                    && s.max_position_deviation <= position_tolerance
+                    && s.field_l2_difference <= field_tolerance
+            })
+    }
+}
+
+impl core::fmt::Display for RunComparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{:>10} {:>8} {:>8} {:>6} {:>9} {:>14} {:>14}",
+            "iteration", "cells 1", "cells 2", "delta", "unmatched", "max pos dev", "field L2"
+        )?;
+        for s in self.save_points.iter() {
+            writeln!(
+                f,
+                "{:>10} {:>8} {:>8} {:>6} {:>9} {:>14.6e} {:>14.6e}",
+                s.iteration,
+                s.n_cells[0],
+                s.n_cells[1],
+                s.cell_count_delta(),
+                s.unmatched_cells,
+                s.max_position_deviation,
+                s.field_l2_difference,
+            )?;
+        }
+        if !self.only_in_first.is_empty() {
+            writeln!(f, "iterations only in first run: {:?}", self.only_in_first)?;
+        }
+        if !self.only_in_second.is_empty() {
+            writeln!(
+                f,
+                "iterations only in second run: {:?}",
+                self.only_in_second
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares the results of two runs at every save point.
+///
+/// Cells are matched by their [CellIdentifier] and their positions are compared via the
+/// euclidean distance of the values returned by `position`.
+/// The values returned by `field` are compared for subdomains with identical
+/// [SubDomainPlainIndex].
+/// Subdomains which are only present in one run or fields of different length result in an
+/// [IndexError] since the two runs can not be compared in this case.
+///
+/// This is useful to validate that a refactor or a different backend did not change the
+/// results of a simulation.
+/// Runs stored with self-describing formats can also be compared without knowing the types
+/// of cells via the `cr-diff` binary.
+/// ```no_run
+/// # use cellular_raza_core::backend::chili::*;
+/// # use cellular_raza_core::storage::StorageOption;
+/// use serde::de::IgnoredAny;
+/// # #[derive(Clone, serde::Deserialize)]
+/// # struct Agent {
+/// #     pos: [f64; 2],
+/// # }
+/// type Access = StorageAccess<(CellBox<Agent>, IgnoredAny), IgnoredAny>;
+/// let first = Access::open("out/run_a", [StorageOption::SerdeJson])?;
+/// let second = Access::open("out/run_b", [StorageOption::SerdeJson])?;
+/// let comparison = compare_runs(&first, &second, |cell| cell.pos.to_vec(), |_| Vec::new())?;
+/// println!("{comparison}");
+/// assert!(comparison.agrees_within(1e-10, 0.0));
+/// # Ok::<(), SimulationError>(())
+/// ```
+pub fn compare_runs<C, A, S, D>(
+    first: &StorageAccess<(CellBox<C>, A), S, D>,
+    second: &StorageAccess<(CellBox<C>, A), S, D>,
+    position: impl Fn(&C) -> Vec<f64>,
+    field: impl Fn(&S) -> Vec<f64>,
+) -> Result<RunComparison, SimulationError>
+where
+    C: Clone + for<'a> Deserialize<'a>,
+    A: Clone + for<'a> Deserialize<'a>,
+    S: Clone + for<'a> Deserialize<'a>,
+{
+    let iterations = |access: &StorageAccess<(CellBox<C>, A), S, D>| -> Result<_, SimulationError> {
+        let mut iterations: BTreeSet<u64> =
+            access.cells.get_all_iterations()?.into_iter().collect();
+        iterations.extend(access.subdomains.get_all_iterations()?);
+        Ok(iterations)
+    };
+    let iterations_first = iterations(first)?;
+    let iterations_second = iterations(second)?;
+
+    let mut comparison = RunComparison {
+        only_in_first: iterations_first
+            .difference(&iterations_second)
+            .copied()
+            .collect(),
+        only_in_second: iterations_second
+            .difference(&iterations_first)
+            .copied()
+            .collect(),
+        ..Default::default()
+    };
+    for &iteration in iterations_first.intersection(&iterations_second) {
+        let positions = |access: &StorageAccess<(CellBox<C>, A), S, D>| {
+            Ok::<_, SimulationError>(
+                load_cells_at_iteration(access, iteration)?
+                    .into_iter()
+                    .map(|(identifier, cell)| (identifier, position(&cell)))
+                    .collect::<BTreeMap<_, _>>(),
+            )
+        };
+        let positions_first = positions(first)?;
+        let positions_second = positions(second)?;
+        let mut difference = SavePointDifference {
+            iteration,
+            n_cells: [positions_first.len(), positions_second.len()],
+            unmatched_cells: 0,
+            max_position_deviation: 0.0,
+            max_deviation_cell: None,
+            field_l2_difference: 0.0,
+        };
+        for (identifier, p1) in positions_first.iter() {
+            match positions_second.get(identifier) {
+                Some(p2) => {
+                    let deviation = euclidean_distance(p1, p2);
+                    if deviation > difference.max_position_deviation
+                        || deviation.is_nan()
+                        || difference.max_deviation_cell.is_none()
+                    {
+                        difference.max_position_deviation = deviation;
+                        difference.max_deviation_cell = Some(*identifier);
+                    }
+                }
+                None => difference.unmatched_cells += 1,
+            }
+        }
+        difference.unmatched_cells += positions_second
+            .keys()
+            .filter(|identifier| !positions_first.contains_key(identifier))
+            .count();
+
+        let fields = |access: &StorageAccess<(CellBox<C>, A), S, D>| {
+            Ok::<_, SimulationError>(
+                load_subdomains_at_iteration(access, iteration)?
+                    .into_iter()
+                    .map(|(index, subdomain)| (index, field(&subdomain)))
+                    .collect::<BTreeMap<_, _>>(),
+            )
+        };
+        let fields_first = fields(first)?;
+        let fields_second = fields(second)?;
+        let mut squared_sum = 0.0;
+        for (index, f1) in fields_first.iter() {
+            match fields_second.get(index) {
+                Some(f2) if f1.len() == f2.len() => {
+                    squared_sum += f1
+                        .iter()
+                        .zip(f2.iter())
+                        .map(|(a, b)| (a - b).powi(2))
+                        .sum::<f64>();
+                }
+                _ => {
+                    return Err(IndexError(format!(
+                        "fields of subdomain {index:?} at iteration {iteration} can not be \
+                        compared"
+                    ))
+                    .into())
+                }
+            }
+        }
+        if fields_second.len() != fields_first.len() {
+            return Err(IndexError(format!(
+                "runs stored {} and {} subdomains at iteration {iteration}",
+                fields_first.len(),
+                fields_second.len()
+            ))
+            .into());
+        }
+        difference.field_l2_difference = squared_sum.sqrt();
+        comparison.save_points.push(difference);
+    }
+    Ok(comparison)
+}
+
+/// Loads all cells which were stored at the given iteration
+fn load_cells_at_iteration<C, A, S, D>(
+    access: &StorageAccess<(CellBox<C>, A), S, D>,
+    iteration: u64,
+) -> Result<Vec<(CellIdentifier, C)>, SimulationError>
+where
+    C: Clone + for<'a> Deserialize<'a>,
+    A: Clone + for<'a> Deserialize<'a>,
+{
+    // Runs which only stored subdomains at this iteration do not contain any cells
+    if !access.cells.get_all_iterations()?.contains(&iteration) {
+        return Ok(Vec::new());
+    }
+    Ok(access
+        .cells
+        .load_all_elements_at_iteration(iteration)?
+        .into_iter()
+        .map(|(identifier, (cbox, _))| (identifier, cbox.cell))
+        .collect())
+}
+
+/// Loads all subdomains which were stored at the given iteration
+fn load_subdomains_at_iteration<C, A, S, D>(
+    access: &StorageAccess<(CellBox<C>, A), S, D>,
+    iteration: u64,
+) -> Result<Vec<(SubDomainPlainIndex, S)>, SimulationError>
+where
+    S: Clone + for<'a> Deserialize<'a>,
+{
+    if !access.subdomains.get_all_iterations()?.contains(&iteration) {
+        return Ok(Vec::new());
+    }
+    Ok(access
+        .subdomains
+        .load_all_elements_at_iteration(iteration)?
+        .into_iter()
+        .collect())
+}
+
+/// Euclidean distance between two positions of possibly different dimension.
+///
+/// Missing components are treated as infinitely far apart.
+fn euclidean_distance(p1: &[f64], p2: &[f64]) -> f64 {
+    if p1.len() != p2.len() {
+        return f64::INFINITY;
+    }
+    p1.iter()
+        .zip(p2.iter())
+        .map(|(a, b)| (a - b).powi(2))
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// Collects all numbers contained in a [serde_json::Value] in the order of their appearance.
+///
+/// This is used by the `cr-diff` binary to extract positions and fields from cells and
+/// subdomains whose exact type is not known.
+/// ```
+/// # use cellular_raza_core::backend::chili::json_numbers;
+/// let value = serde_json::json!({"pos": [1.0, [2.0, 3]], "name": "a"});
+/// assert_eq!(json_numbers(&value), vec![1.0, 2.0, 3.0]);
+/// ```
+pub fn json_numbers(value: &serde_json::Value) -> Vec<f64> {
+    let mut numbers = Vec::new();
+    let mut stack = vec![value];
+    while let Some(value) = stack.pop() {
+        match value {
+            serde_json::Value::Number(n) => numbers.extend(n.as_f64()),
+            serde_json::Value::Array(values) => stack.extend(values.iter().rev()),
+            serde_json::Value::Object(map) => stack.extend(map.values().rev()),
+            _ => (),
+        }
+    }
+    numbers
+}
+
+#[cfg(test)]
+mod test_comparison {
+    use super::*;
+    use crate::backend::chili::VoxelPlainIndex;
+    use crate::storage::{StorageInterfaceStore, StorageOption};
+
+    type Access = StorageAccess<(CellBox<[f64; 2]>, ()), Vec<f64>>;
+
+    fn store_run(
+        path: &std::path::Path,
+        cells: &[(u64, u64, [f64; 2])],
+        fields: &[(u64, usize, Vec<f64>)],
+    ) -> Access {
+        std::fs::create_dir_all(path).unwrap();
+        let mut access = Access::open(path, [StorageOption::SerdeJson]).unwrap();
+        for (iteration, counter, pos) in cells.iter() {
+            let identifier = CellIdentifier(VoxelPlainIndex::new(0), *counter);
+            let cbox = CellBox {
+                identifier,
+                parent: None,
+                cell: *pos,
+            };
+            access
+                .cells
+                .store_single_element(*iteration, &identifier, &(cbox, ()))
+                .unwrap();
+        }
+        for (iteration, index, field) in fields.iter() {
+            access
+                .subdomains
+                .store_single_element(*iteration, &SubDomainPlainIndex(*index), field)
+                .unwrap();
+        }
+        access
+    }
+
+    #[test]
+    fn identical_runs_agree() {
+        let dir = tempfile::tempdir().unwrap();
+        let cells = [(0, 0, [0.0, 1.0]), (0, 1, [2.0, 1.0]), (10, 0, [0.5, 1.0])];
+        let fields = [(0, 0, vec![1.0, 2.0]), (10, 0, vec![1.5, 2.5])];
+        let first = store_run(&dir.path().join("a"), &cells, &fields);
+        let second = store_run(&dir.path().join("b"), &cells, &fields);
+        let comparison = compare_runs(&first, &second, |p| p.to_vec(), |f| f.clone()).unwrap();
+        assert_eq!(comparison.save_points.len(), 2);
+        assert_eq!(comparison.save_points[0].n_cells, [2, 2]);
+        assert!(comparison.agrees_within(0.0, 0.0));
+    }
+
+    #[test]
+    fn report_differences() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = store_run(
+            &dir.path().join("a"),
+            &[(0, 0, [0.0, 0.0]), (0, 1, [1.0, 0.0]), (5, 0, [0.0, 0.0])],
+            &[(0, 0, vec![1.0, 2.0])],
+        );
+        let second = store_run(
+            &dir.path().join("b"),
+            &[(0, 0, [3.0, 4.0]), (0, 2, [1.0, 0.0]), (0, 3, [1.0, 0.0])],
+            &[(0, 0, vec![1.0, 4.0])],
+        );
+        let comparison = compare_runs(&first, &second, |p| p.to_vec(), |f| f.clone()).unwrap();
+        assert_eq!(comparison.only_in_first, vec![5]);
+        assert!(comparison.only_in_second.is_empty());
+        let difference = &comparison.save_points[0];
+        assert_eq!(difference.cell_count_delta(), 1);
+        assert_eq!(difference.unmatched_cells, 3);
+        assert_eq!(difference.max_position_deviation, 5.0);
+        assert_eq!(
+            difference.max_deviation_cell,
+            Some(CellIdentifier(VoxelPlainIndex::new(0), 0))
+        );
+        assert_eq!(difference.field_l2_difference, 2.0);
+        assert!(!comparison.agrees_within(f64::INFINITY, f64::INFINITY));
+        assert!(format!("{comparison}").contains("only in first run: [5]"));
+    }
+
+    #[test]
+    fn incompatible_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = store_run(&dir.path().join("a"), &[], &[(0, 0, vec![1.0, 2.0])]);
+        let second = store_run(&dir.path().join("b"), &[], &[(0, 1, vec![1.0, 2.0])]);
+        let result = compare_runs(&first, &second, |p| p.to_vec(), |f| f.clone());
+        assert!(matches!(result, Err(SimulationError::IndexError(_))));
+    }
+}
@@ -16,10 +16,10 @@ where
 }
 
 #[allow(unused)]
-pub fn mechanics_interaction<C, Ci, Pos, Vel, For, Inf, Float>(agents: &Ci)
+pub fn mechanics_interaction<C, Ci, Pos, Vel, For, Inf, Float, R>(agents: &Ci)
 where
     Ci: IntoIterator<Item = C>,
-    C: cellular_raza_concepts::Mechanics<Pos, Vel, For, Float>,
+    C: cellular_raza_concepts::Mechanics<Pos, Vel, For, Float, R>,
     C: cellular_raza_concepts::Interaction<Pos, Vel, For, Inf>,
 {
 }
@@ -32,19 +32,19 @@ where
 }
 
 #[allow(unused)]
-pub fn time_stepper_mechanics<Pos, Vel, For, T, C, Ci, Float>(time_stepper: &T, agents: &Ci)
+pub fn time_stepper_mechanics<Pos, Vel, For, T, C, Ci, Float, R>(time_stepper: &T, agents: &Ci)
 where
     T: crate::time::TimeStepper<Float>,
     Ci: IntoIterator<Item = C>,
-    C: cellular_raza_concepts::Mechanics<Pos, Vel, For, Float>,
+    C: cellular_raza_concepts::Mechanics<Pos, Vel, For, Float, R>,
 {
 }
 
 #[allow(unused)]
-pub fn mechanics_implemented<Pos, Vel, For, Float, C, Ci>(agents: &Ci)
+pub fn mechanics_implemented<Pos, Vel, For, Float, C, Ci, R>(agents: &Ci)
 where
     Ci: IntoIterator<Item = C>,
-    C: cellular_raza_concepts::Mechanics<Pos, Vel, For, Float>,
+    C: cellular_raza_concepts::Mechanics<Pos, Vel, For, Float, R>,
 {
 }
 
@@ -59,10 +59,29 @@ where
 }
 
 #[allow(unused)]
-pub fn cycle_implemented<Float, C, Ci>(agents: &Ci)
+pub fn cycle_implemented<Float, C, Ci, R>(agents: &Ci)
 where
     Ci: IntoIterator<Item = C>,
-    C: cellular_raza_concepts::Cycle<C, Float>,
+    C: cellular_raza_concepts::Cycle<C, Float, R>,
+{
+}
+
+#[allow(unused)]
+pub fn age_implemented<Float, C, Ci>(agents: &Ci)
+where
+    Ci: IntoIterator<Item = C>,
+    C: cellular_raza_concepts::Age<Float>,
+{
+}
+
+#[allow(unused)]
+pub fn rotational_mechanics_implemented<Pos, Ang, AngVel, Tor, Inf, Float, C, Ci, R>(agents: &Ci)
+where
+    Ci: IntoIterator<Item = C>,
+    C: cellular_raza_concepts::RotationalMechanics<Ang, AngVel, Tor, Float, R>,
+    C: cellular_raza_concepts::Orientation<Ang>,
+    C: cellular_raza_concepts::AngularVelocity<AngVel>,
+    C: cellular_raza_concepts::InteractionTorque<Pos, Ang, Tor, Inf>,
 {
 }
 
@@ -92,3 +111,49 @@ where
     C: cellular_raza_concepts::ReactionsExtra<Ri, Re>,
 {
 }
+
+#[allow(unused)]
+pub fn reactions_extra_implemented<Ri, Re, C, Ci>(agents: &Ci)
+where
+    Ci: IntoIterator<Item = C>,
+    C: cellular_raza_concepts::ReactionsExtra<Ri, Re>,
+{
+}
+
+/// Checks if the list of aspects of a struct built by one of the macros contains the given
+/// aspect.
+///
+/// This function is evaluated at compile time by the [check_aspects](super::check_aspects) macro.
+pub const fn aspects_contain(aspects: &[&str], aspect: &str) -> bool {
+    let mut i = 0;
+    while i < aspects.len() {
+        let candidate = aspects[i].as_bytes();
+        let aspect = aspect.as_bytes();
+        if candidate.len() == aspect.len() {
+            let mut j = 0;
+            while j < aspect.len() && candidate[j] == aspect[j] {
+                j += 1;
+            }
+            if j == aspect.len() {
+                return true;
+            }
+        }
+        i += 1;
+    }
+    false
+}
+
+#[cfg(test)]
+mod test_aspects_contain {
+    use super::aspects_contain;
+
+    #[test]
+    fn find_aspects() {
+        const ASPECTS: &[&str] = &["Mechanics", "Interaction", "Bonds"];
+        assert!(aspects_contain(ASPECTS, "Mechanics"));
+        assert!(aspects_contain(ASPECTS, "Bonds"));
+        assert!(!aspects_contain(ASPECTS, "Cycle"));
+        assert!(!aspects_contain(ASPECTS, "Mechanic"));
+        assert!(!aspects_contain(&[], "Mechanics"));
+    }
+}
@@ -0,0 +1,114 @@
+use std::collections::BTreeSet;
+
+use cellular_raza_concepts::SubDomain;
+
+use super::{CellBox, SubDomainBox, SubDomainPlainIndex};
+
+/// Gives access to fields of the AuxStorage which were defined by custom simulation aspects.
+///
+/// Custom aspects can request additional fields with the `#[AuxStorage(name: Type)]` attribute.
+/// The [AuxStorage](super::AuxStorage) derive implements this trait for every field which is
+/// marked by `#[CustomAuxStorage]`.
+/// Every field is initialized with its [Default] value and needs to be
+/// [Clone], [Serialize](serde::Serialize) and [Deserialize](serde::Deserialize).
+/// Since the trait is implemented per type, the types of all fields need to be distinct.
+///
+/// ```
+/// use cellular_raza_core::backend::chili::*;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+/// struct BondStorage {
+///     partners: Vec<u64>,
+/// }
+///
+/// build_aux_storage!(
+///     aspects: [Cycle, #[AuxStorage(bonds: BondStorage)] Bonds],
+///     core_path: cellular_raza_core,
+/// );
+///
+/// let mut aux_storage = (aux_storage_constructor!(
+///     aspects: [Cycle, #[AuxStorage(bonds: BondStorage)] Bonds],
+///     core_path: cellular_raza_core,
+/// ))(());
+/// aux_storage.custom_storage_mut().partners.push(3);
+/// assert_eq!(aux_storage.custom_storage().partners, vec![3]);
+/// ```
+pub trait CustomAuxStorage<T> {
+    /// Immutable reference to the stored value
+    fn custom_storage(&self) -> &T;
+    /// Mutable reference to the stored value
+    fn custom_storage_mut(&mut self) -> &mut T;
+}
+
+/// Resets user-defined scratch fields of the AuxStorage.
+///
+/// Scratch fields are specified with the `scratch_fields` keyword of the
+/// [build_aux_storage](super::build_aux_storage) macro.
+/// Fields which are annotated by `#[reset(step)]` are set to their default value at the
+/// beginning of every step.
+/// The [AuxStorage](super::AuxStorage) derive implements this trait for all fields marked by
+/// `#[ResetScratch(default)]`.
+pub trait ResetScratchFields {
+    /// Sets all scratch fields with a reset policy to their default values
+    fn reset_scratch_fields(&mut self);
+}
+
+/// Accessors for functions of custom simulation aspects.
+///
+/// Functions which are specified with `#[Update(step_1: function)]` are called as
+/// `function(&mut sbox)?` and can use these methods to exchange messages with neighboring
+/// subdomains.
+impl<I, S, C, A, Com, Sy, R> SubDomainBox<I, S, C, A, Com, Sy, R>
+where
+    S: SubDomain,
+{
+    /// Index of this subdomain
+    pub fn subdomain_plain_index(&self) -> SubDomainPlainIndex {
+        self.subdomain_plain_index
+    }
+
+    /// Indices of all neighboring subdomains
+    pub fn neighbor_subdomains(&self) -> &BTreeSet<SubDomainPlainIndex> {
+        &self.neighbors
+    }
+
+    /// The subdomain which contains the cells
+    pub fn subdomain(&self) -> &S {
+        &self.subdomain
+    }
+
+    /// Communicator which sends messages to other subdomains.
+    ///
+    /// Messages of custom aspects which were specified with `#[Communicator(name: Message)]`
+    /// can be sent and received with the [Communicator](super::Communicator) trait.
+    pub fn communicator_mut(&mut self) -> &mut Com {
+        &mut self.communicator
+    }
+
+    /// Resets the scratch fields of all cells. See [ResetScratchFields].
+    pub fn reset_scratch_fields(&mut self)
+    where
+        A: ResetScratchFields,
+    {
+        for voxel in self.voxels.values_mut() {
+            for (_, aux_storage) in voxel.cells.iter_mut() {
+                aux_storage.reset_scratch_fields();
+            }
+        }
+    }
+
+    /// Iterates mutably over all cells of this subdomain together with their AuxStorage.
+    ///
+    /// The same invariants as for [SubDomainBox::iter_cells_mut] apply.
+    pub fn iter_cells_aux_storage_mut(
+        &mut self,
+    ) -> impl Iterator<Item = (&mut CellBox<C>, &mut A)> {
+        self.voxels.values_mut().flat_map(|voxel| {
+            voxel
+                .cells
+                .iter_mut()
+                .map(|(cbox, aux_storage)| (cbox, aux_storage))
+        })
+    }
+}
@@ -28,9 +28,55 @@ pub struct SimulationRunner<I, Sb> {
     pub subdomain_boxes: BTreeMap<I, Sb>,
 }
 
+/// Cloned state of all cells and subdomains at one point in time.
+///
+/// Obtained via [SimulationRunner::snapshot] or during a run via the
+/// [SnapshotHook](super::SnapshotHook).
+#[derive(Clone, Deserialize, Serialize)]
+pub struct SimulationSnapshot<I, S, C>
+where
+    I: Ord,
+{
+    /// All cells ordered by their identifier
+    pub cells: BTreeMap<CellIdentifier, CellBox<C>>,
+    /// All subdomains (including their extracellular fields) ordered by their index
+    pub subdomains: BTreeMap<I, S>,
+}
+
+impl<I, S, C, A, Com, Sy, R> SimulationRunner<I, SubDomainBox<I, S, C, A, Com, Sy, R>>
+where
+    S: SubDomain,
+{
+    /// Gathers a copy of all cells and subdomains without writing to the storage.
+    ///
+    /// This allows embedding applications such as GUIs or optimizers to inspect the current
+    /// state of the simulation directly.
+    /// Since the runner is consumed once the simulation starts, the
+    /// [SnapshotHook](super::SnapshotHook) provides the same information at every save point.
+    pub fn snapshot(&self) -> SimulationSnapshot<I, S, C>
+    where
+        I: Clone + Ord,
+        S: Clone,
+        C: Clone,
+    {
+        let cells = self
+            .subdomain_boxes
+            .values()
+            .flat_map(|sbox| sbox.iter_cells())
+            .map(|cbox| (cbox.identifier, cbox.clone()))
+            .collect();
+        let subdomains = self
+            .subdomain_boxes
+            .iter()
+            .map(|(index, sbox)| (index.clone(), sbox.subdomain.clone()))
+            .collect();
+        SimulationSnapshot { cells, subdomains }
+    }
+}
+
 /// Stores information related to a voxel of the physical simulation domain.
 #[derive(Clone, Deserialize, Serialize)]
-pub struct Voxel<C, A> {
+pub struct Voxel<C, A, R = rand_chacha::ChaCha8Rng> {
     /// The index which is given when decomposing the domain and all indices are counted.
     pub plain_index: VoxelPlainIndex,
     /// Indices of neighboring voxels
@@ -43,19 +89,23 @@ pub struct Voxel<C, A> {
     pub id_counter: u64,
     /// A random number generator which is unique to this voxel and thus able
     /// to produce repeatable results even for parallelized simulations.
-    pub rng: rand_chacha::ChaCha8Rng,
+    pub rng: R,
+    /// Discard attractive forces between cells, see
+    /// [SubDomainBox::set_repulsive_only].
+    #[serde(skip)]
+    pub(crate) repulsive_only: bool,
 }
 
 /// Construct a new [SimulationRunner] from a given auxiliary storage and communicator object
 #[allow(unused)]
 #[cfg_attr(feature = "tracing", instrument(skip_all))]
-pub fn construct_simulation_runner<D, S, C, A, Com, Sy, Ci>(
+pub fn construct_simulation_runner<D, S, C, A, Com, Sy, Ci, R>(
     domain: D,
     agents: Ci,
     n_subdomains: NonZeroUsize,
     init_aux_storage: impl Fn(&C) -> A,
 ) -> Result<
-    SimulationRunner<D::SubDomainIndex, SubDomainBox<D::SubDomainIndex, S, C, A, Com, Sy>>,
+    SimulationRunner<D::SubDomainIndex, SubDomainBox<D::SubDomainIndex, S, C, A, Com, Sy, R>>,
     SimulationError,
 >
 where
@@ -66,6 +116,7 @@ where
     S: SortCells<C, VoxelIndex = <S as SubDomain>::VoxelIndex> + SubDomain,
     Sy: super::simulation_flow::FromMap<SubDomainPlainIndex>,
     Com: super::simulation_flow::FromMap<SubDomainPlainIndex>,
+    R: SeedableRng,
 {
     #[cfg(feature = "tracing")]
     tracing::info!("Decomposing");
@@ -178,9 +229,8 @@ where
                         cells: Vec::new(),
                         new_cells: Vec::new(),
                         id_counter: 0,
-                        rng: rand_chacha::ChaCha8Rng::seed_from_u64(
-                            decomposed_domain.rng_seed + plain_index.0 as u64,
-                        ),
+                        rng: R::seed_from_u64(decomposed_domain.rng_seed + plain_index.0 as u64),
+                        repulsive_only: false,
                     },
                 ))
             });
@@ -208,6 +258,17 @@ where
                 plain_index_to_subdomain: plain_index_to_subdomain.clone(),
                 communicator,
                 syncer,
+                division_throttle: Default::default(),
+                relaxation_remaining: BTreeMap::new(),
+                divisions: Vec::new(),
+                rng_mode: Default::default(),
+                rng_seed: decomposed_domain.rng_seed,
+                iteration: 0,
+                global_parameters: Default::default(),
+                voxel_refinement: None,
+                refined_voxels: BTreeSet::new(),
+                halo_exchange: None,
+                voxel_parallelism: None,
             };
             subdomain_box.insert_cells(&mut cells, &init_aux_storage)?;
             Ok((index, subdomain_box))
@@ -218,24 +279,60 @@ where
 }
 
 /// Encapsulates a subdomain with cells and other simulation aspects.
-pub struct SubDomainBox<I, S, C, A, Com, Sy = BarrierSync>
+///
+/// Cells draw their random numbers from generators of type `R` which default to
+/// [ChaCha8Rng](rand_chacha::ChaCha8Rng).
+/// The generator is chosen via the `rng` argument of the
+/// [run_simulation](super::run_simulation) macro.
+pub struct SubDomainBox<I, S, C, A, Com, Sy = BarrierSync, R = rand_chacha::ChaCha8Rng>
 where
     S: SubDomain,
 {
+    /// Index of the subdomain which was given when decomposing the domain
     #[allow(unused)]
     pub(crate) index: I,
+    /// Plain index of the subdomain
     pub(crate) subdomain_plain_index: SubDomainPlainIndex,
+    /// Plain indices of neighboring subdomains
     pub(crate) neighbors: BTreeSet<SubDomainPlainIndex>,
+    /// The subdomain itself
     pub(crate) subdomain: S,
-    pub(crate) voxels: std::collections::BTreeMap<VoxelPlainIndex, Voxel<C, A>>,
+    /// All voxels of the subdomain
+    pub(crate) voxels: std::collections::BTreeMap<VoxelPlainIndex, Voxel<C, A, R>>,
+    /// Maps voxel indices of the subdomain to their plain indices
     pub(crate) voxel_index_to_plain_index: BTreeMap<S::VoxelIndex, VoxelPlainIndex>,
+    /// Maps all voxels of the domain to the subdomain which contains them
     pub(crate) plain_index_to_subdomain:
         std::collections::BTreeMap<VoxelPlainIndex, SubDomainPlainIndex>,
+    /// Sends and receives messages from other subdomains
     pub(crate) communicator: Com,
+    /// Synchronizes the subdomain with other subdomains
     pub(crate) syncer: Sy,
+    /// Limits the number of simultaneous divisions
+    pub(crate) division_throttle: super::DivisionThrottle,
+    /// Remaining steps in which divisions are suspended for each voxel
+    pub(crate) relaxation_remaining: BTreeMap<VoxelPlainIndex, usize>,
+    /// Divisions of the last cycle update given by the voxel, parent and daughters
+    pub(crate) divisions: Vec<(VoxelPlainIndex, CellIdentifier, [CellIdentifier; 2])>,
+    /// Determines how the generators of cells are seeded
+    pub(crate) rng_mode: super::RngMode,
+    /// Seed of the domain which is used as key for counter-based seeding of cell generators
+    pub(crate) rng_seed: u64,
+    /// Iteration of the last call to [SubDomainBox::run_local_cell_funcs]
+    pub(crate) iteration: usize,
+    /// Simulation-wide parameters which are passed to the cells
+    pub(crate) global_parameters: cellular_raza_concepts::GlobalParameters,
+    /// Subdivides overcrowded voxels when calculating interactions
+    pub(crate) voxel_refinement: Option<super::VoxelRefinement>,
+    /// Voxels which are currently refined
+    pub(crate) refined_voxels: BTreeSet<VoxelPlainIndex>,
+    /// Mirrors boundary cells into neighboring subdomains instead of requesting forces
+    pub(crate) halo_exchange: Option<super::HaloExchange>,
+    /// Distributes the voxels of the subdomain over multiple threads
+    pub(crate) voxel_parallelism: Option<super::VoxelParallelism>,
 }
 
-impl<I, S, C, A, Com, Sy> SubDomainBox<I, S, C, A, Com, Sy>
+impl<I, S, C, A, Com, Sy, R> SubDomainBox<I, S, C, A, Com, Sy, R>
 where
     S: SubDomain,
 {
@@ -249,6 +346,15 @@ where
         self.syncer.sync()
     }
 
+    /// Waits for all other threads regardless of the chosen [SyncSubDomains] strategy.
+    #[cfg_attr(feature = "tracing", instrument(skip_all))]
+    pub fn sync_all(&mut self) -> Result<(), SimulationError>
+    where
+        Sy: SyncSubDomains,
+    {
+        self.syncer.sync_all()
+    }
+
     /// Stores an error which has occurred and notifies other running threads to wind down.
     #[cfg_attr(feature = "tracing", instrument(skip_all))]
     pub fn store_error(
@@ -289,6 +395,47 @@ where
         Ok(())
     }
 
+    /// Iterates over all cells in this subdomain.
+    pub fn iter_cells(&self) -> impl Iterator<Item = &CellBox<C>> {
+        self.voxels
+            .values()
+            .flat_map(|voxel| voxel.cells.iter().map(|(cbox, _)| cbox))
+    }
+
+    /// Iterates mutably over all cells in this subdomain together with their identifiers.
+    ///
+    /// # Invariants
+    /// Cells are only sorted into their voxels during the regular update steps of the
+    /// simulation.
+    /// When changing the position of a cell, the following should thus be ensured:
+    /// - The new position lies inside the simulation domain.
+    /// - The cell does not move further than the interaction range of the voxels.
+    ///   Otherwise, interactions with cells which are not in neighboring voxels are missed
+    ///   until the cell has been sorted again.
+    /// - Solvers of multiple steps store previous positions and velocities.
+    ///   Large jumps will thus affect the next few increments.
+    ///
+    /// Identifiers can not be changed since they are required to be unique.
+    pub fn iter_cells_mut(&mut self) -> impl Iterator<Item = (CellIdentifier, &mut C)> {
+        self.voxels.values_mut().flat_map(|voxel| {
+            voxel
+                .cells
+                .iter_mut()
+                .map(|(cbox, _)| (cbox.identifier, &mut cbox.cell))
+        })
+    }
+
+    /// Total number of cells in this subdomain
+    pub fn cell_count(&self) -> usize {
+        self.voxels.values().map(|voxel| voxel.cells.len()).sum()
+    }
+
+    /// Obtains the cell with the given identifier if it is located in this subdomain.
+    pub fn get_cell_by_id(&self, identifier: &CellIdentifier) -> Option<&CellBox<C>> {
+        self.iter_cells()
+            .find(|cbox| &cbox.identifier == identifier)
+    }
+
     /// Update all purely local functions
     ///
     /// Used to iterate over all cells in the current subdomain and running local functions which
@@ -299,23 +446,79 @@ where
         next_time_point: &crate::time::NextTimePoint<F>,
     ) -> Result<(), super::SimulationError>
     where
-        Func: Fn(
-            &mut C,
-            &mut A,
-            F,
-            &mut rand_chacha::ChaCha8Rng,
-        ) -> Result<(), super::SimulationError>,
+        Func: Fn(&mut C, &mut A, F, &mut R) -> Result<(), super::SimulationError>,
         F: Copy,
+        R: SeedableRng,
+    {
+        self.run_cell_funcs_in_stream(func, next_time_point, super::RngStream::Local)
+    }
+
+    /// Runs local functions for all cells with random numbers of the given stream.
+    pub(crate) fn run_cell_funcs_in_stream<Func, F>(
+        &mut self,
+        func: Func,
+        next_time_point: &crate::time::NextTimePoint<F>,
+        stream: super::RngStream,
+    ) -> Result<(), super::SimulationError>
+    where
+        Func: Fn(&mut C, &mut A, F, &mut R) -> Result<(), super::SimulationError>,
+        F: Copy,
+        R: SeedableRng,
     {
         let dt = next_time_point.increment;
+        self.iteration = next_time_point.iteration;
         for (_, voxel) in self.voxels.iter_mut() {
             for (cellbox, aux_storage) in voxel.cells.iter_mut() {
-                func(&mut cellbox.cell, aux_storage, dt, &mut voxel.rng)?;
+                match self.rng_mode {
+                    super::RngMode::Voxel => {
+                        func(&mut cellbox.cell, aux_storage, dt, &mut voxel.rng)?
+                    }
+                    super::RngMode::CounterBased => {
+                        let mut rng = super::Philox4x32::for_cell_stream(
+                            self.rng_seed,
+                            &cellbox.identifier,
+                            self.iteration as u64,
+                            stream,
+                        )
+                        .to_rng();
+                        func(&mut cellbox.cell, aux_storage, dt, &mut rng)?
+                    }
+                }
             }
         }
         Ok(())
     }
 
+    /// Determines how random numbers are generated for cells. See [RngMode](super::RngMode).
+    pub fn set_rng_mode(&mut self, rng_mode: Option<super::RngMode>) {
+        self.rng_mode = rng_mode.unwrap_or_default();
+    }
+
+    /// Distributes the simulation-wide
+    /// [GlobalParameters](cellular_raza_concepts::GlobalParameters) to this subdomain.
+    pub fn set_global_parameters(
+        &mut self,
+        global_parameters: Option<cellular_raza_concepts::GlobalParameters>,
+    ) {
+        self.global_parameters = global_parameters.unwrap_or_default();
+    }
+
+    /// Simulation-wide parameters which are passed to the cells of this subdomain
+    pub fn global_parameters(&self) -> &cellular_raza_concepts::GlobalParameters {
+        &self.global_parameters
+    }
+
+    /// Provides the time of the current [NextTimePoint](crate::time::NextTimePoint) to the
+    /// global parameters such that time-dependent parameters can be evaluated.
+    ///
+    /// The stored values of the parameters are neither modified nor copied.
+    pub fn set_simulation_time<F>(&mut self, time: F)
+    where
+        F: num::ToPrimitive,
+    {
+        self.global_parameters = self.global_parameters.at_time(time);
+    }
+
     /// TODO
     pub fn run_local_subdomain_funcs<Func, F>(
         &mut self,
@@ -336,20 +539,24 @@ where
     }
 
     /// Save all subdomains with the given storage manager.
+    ///
+    /// The storage manager can be a [StorageManager](crate::storage::StorageManager) or
+    /// [WriteBehindStorage](crate::storage::WriteBehindStorage).
     #[cfg_attr(feature = "tracing", instrument(skip(self, storage_manager)))]
     pub fn save_subdomains<
         #[cfg(feature = "tracing")] F: core::fmt::Debug,
         #[cfg(not(feature = "tracing"))] F,
+        Sto,
     >(
         &self,
-        storage_manager: &mut crate::storage::StorageManager<SubDomainPlainIndex, S>,
+        storage_manager: &mut Sto,
         next_time_point: &crate::time::NextTimePoint<F>,
     ) -> Result<(), StorageError>
     where
         S: Clone + Serialize,
+        Sto: crate::storage::StorageInterfaceStore<SubDomainPlainIndex, S>,
     {
         if let Some(crate::time::TimeEvent::PartialSave) = next_time_point.event {
-            use crate::storage::StorageInterfaceStore;
             storage_manager.store_single_element(
                 next_time_point.iteration as u64,
                 &self.subdomain_plain_index,
@@ -364,18 +571,19 @@ where
     pub fn save_cells<
         #[cfg(feature = "tracing")] F: core::fmt::Debug,
         #[cfg(not(feature = "tracing"))] F,
+        Sto,
     >(
         &self,
-        storage_manager: &mut crate::storage::StorageManager<CellIdentifier, (CellBox<C>, A)>,
+        storage_manager: &mut Sto,
         next_time_point: &crate::time::NextTimePoint<F>,
     ) -> Result<(), StorageError>
     where
         A: Clone + Serialize,
         C: Clone + Serialize,
         CellBox<C>: cellular_raza_concepts::Id<Identifier = CellIdentifier>,
+        Sto: crate::storage::StorageInterfaceStore<CellIdentifier, (CellBox<C>, A)>,
     {
         if let Some(crate::time::TimeEvent::PartialSave) = next_time_point.event {
-            use crate::storage::StorageInterfaceStore;
             let cells = self
                 .voxels
                 .iter()
@@ -386,3 +594,57 @@ where
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test_cell_access {
+    use super::*;
+    use crate::backend::chili::test_fixtures::*;
+
+    #[test]
+    fn iterate_and_count_cells() {
+        let sbox = subdomain_box(3, &[0.5, 2.5, 0.2, 1.5]);
+        assert_eq!(sbox.cell_count(), 4);
+        // Cells are iterated in the order of their voxels
+        let positions: Vec<_> = sbox.iter_cells().map(|cbox| cbox.cell.pos).collect();
+        assert_eq!(positions, vec![0.5, 0.2, 1.5, 2.5]);
+        let identifiers: Vec<_> = sbox.iter_cells().map(|cbox| cbox.identifier).collect();
+        assert_eq!(identifiers[1], CellIdentifier(VoxelPlainIndex(0), 1));
+
+        let empty = subdomain_box::<f64>(2, &[]);
+        assert_eq!(empty.cell_count(), 0);
+        assert_eq!(empty.iter_cells().count(), 0);
+    }
+
+    #[test]
+    fn modify_cells() {
+        let mut sbox = subdomain_box(2, &[0.5, 1.5]);
+        for (identifier, cell) in sbox.iter_cells_mut() {
+            if identifier.0 == VoxelPlainIndex(1) {
+                cell.strength = 3.0;
+            }
+            cell.pos += 0.25;
+        }
+        let cells: Vec<_> = sbox
+            .iter_cells()
+            .map(|cbox| (cbox.cell.pos, cbox.cell.strength))
+            .collect();
+        assert_eq!(cells, vec![(0.75, 1.0), (1.75, 3.0)]);
+        // Cells stay in their voxels until they are sorted again
+        assert_eq!(sbox.voxels[&VoxelPlainIndex(0)].cells.len(), 1);
+    }
+
+    #[test]
+    fn find_cell_by_id() {
+        let sbox = subdomain_box(2, &[0.5, 1.5, 1.7]);
+        let identifier = CellIdentifier(VoxelPlainIndex(1), 1);
+        let cbox = sbox.get_cell_by_id(&identifier).unwrap();
+        assert_eq!(cbox.identifier, identifier);
+        assert_eq!(cbox.cell.pos, 1.7);
+        assert!(sbox
+            .get_cell_by_id(&CellIdentifier(VoxelPlainIndex(0), 1))
+            .is_none());
+        assert!(sbox
+            .get_cell_by_id(&CellIdentifier(VoxelPlainIndex(2), 0))
+            .is_none());
+    }
+}
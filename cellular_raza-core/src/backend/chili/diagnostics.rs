@@ -0,0 +1,278 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use cellular_raza_concepts::SubDomain;
+use serde::{Deserialize, Serialize};
+
+use super::{CellIdentifier, SimulationError, SubDomainBox, SubDomainPlainIndex};
+use crate::time::{NextTimePoint, TimeStepper};
+
+/// Provides the information required to detect overlapping cells.
+///
+/// Two cells overlap when their distance is smaller than the sum of their radii.
+pub trait OverlapMeasure<Pos, F> {
+    /// Radius of the cell which is used to calculate overlaps
+    fn overlap_radius(&self) -> F;
+
+    /// Distance between two positions
+    fn distance(pos1: &Pos, pos2: &Pos) -> F;
+}
+
+/// Emitted when one of the thresholds of [OverlapDiagnostics] was exceeded.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct OverlapWarning<F> {
+    /// Iteration at which the thresholds were exceeded
+    pub iteration: usize,
+    /// Time at which the thresholds were exceeded
+    pub time: F,
+    /// Subdomain in which the measurement was taken
+    pub subdomain: SubDomainPlainIndex,
+    /// Largest distance travelled by any cell in this step
+    pub max_displacement: F,
+    /// Largest overlap between two cells after this step
+    pub max_overlap: F,
+}
+
+/// Monitors displacement and overlap of cells during the simulation.
+///
+/// Stiff contact forces combined with a too large time increment can lead to cells which
+/// jump across each other or penetrate deeply into their neighbors.
+/// Such simulations often explode without any obvious error.
+/// After every update of the mechanics, the largest displacement of any cell and the largest
+/// overlap between any two cells within the same subdomain are compared to the thresholds.
+/// Overlaps between cells of different subdomains are not considered.
+///
+/// When a threshold is exceeded, an [OverlapWarning] is recorded.
+/// If specified via [OverlapDiagnostics::reduce_dt_for], all threads additionally request the
+/// [TimeStepper] to reduce its increment.
+/// Only steppers such as the [AdaptiveStepsize](crate::time::AdaptiveStepsize) support this.
+///
+/// The diagnostics are passed to the
+/// [run_simulation](crate::backend::chili::run_simulation) macro via the
+/// `overlap_diagnostics` argument.
+/// All clones share their warnings such that they can be inspected after the simulation has
+/// finished.
+/// ```
+/// # use cellular_raza_core::backend::chili::OverlapDiagnostics;
+/// let diagnostics = OverlapDiagnostics::new(0.5, 1.0).reduce_dt_for(10);
+/// // Pass `overlap_diagnostics: diagnostics` to the run_simulation macro
+/// assert!(diagnostics.warnings().is_empty());
+/// ```
+#[derive(Clone, Debug)]
+pub struct OverlapDiagnostics<F> {
+    /// Maximum distance which any cell should travel in one step
+    pub max_displacement: F,
+    /// Maximum overlap between two cells
+    pub max_overlap: F,
+    /// Number of steps for which the time increment should be reduced
+    pub reduce_dt_steps: Option<usize>,
+    /// Warnings emitted by all threads
+    warnings: Arc<Mutex<Vec<OverlapWarning<F>>>>,
+    /// Last iteration (plus one) in which any thread exceeded a threshold
+    last_exceeded: Arc<AtomicUsize>,
+}
+
+impl<F> OverlapDiagnostics<F> {
+    /// Monitor the simulation with the given thresholds without changing the time increment.
+    pub fn new(max_displacement: F, max_overlap: F) -> Self {
+        Self {
+            max_displacement,
+            max_overlap,
+            reduce_dt_steps: None,
+            warnings: Arc::new(Mutex::new(Vec::new())),
+            last_exceeded: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Reduce the time increment for the next `n_steps` steps when a threshold is exceeded.
+    pub fn reduce_dt_for(self, n_steps: usize) -> Self {
+        Self {
+            reduce_dt_steps: Some(n_steps),
+            ..self
+        }
+    }
+
+    /// All warnings which have been emitted so far
+    pub fn warnings(&self) -> Vec<OverlapWarning<F>>
+    where
+        F: Clone,
+    {
+        self.warnings
+            .lock()
+            .map(|warnings| warnings.clone())
+            .unwrap_or_default()
+    }
+
+    /// Compares the current state of the subdomain against the thresholds.
+    ///
+    /// The `previous_positions` should be obtained via [SubDomainBox::cell_positions] before
+    /// updating the mechanics.
+    pub fn check<I, S, C, A, Com, Sy, R, Pos>(
+        &self,
+        sbox: &SubDomainBox<I, S, C, A, Com, Sy, R>,
+        previous_positions: &BTreeMap<CellIdentifier, Pos>,
+        next_time_point: &NextTimePoint<F>,
+    ) -> Result<Option<OverlapWarning<F>>, SimulationError>
+    where
+        S: SubDomain,
+        C: cellular_raza_concepts::Position<Pos>,
+        C: OverlapMeasure<Pos, F>,
+        F: num::Float,
+    {
+        let max_displacement = sbox
+            .voxels
+            .values()
+            .flat_map(|voxel| voxel.cells.iter())
+            .filter_map(|(cbox, _)| {
+                previous_positions
+                    .get(&cbox.identifier)
+                    .map(|pos| C::distance(pos, &cbox.cell.pos()))
+            })
+            .fold(F::zero(), F::max);
+        let max_overlap = sbox.max_overlap::<Pos, F>();
+        if max_displacement <= self.max_displacement && max_overlap <= self.max_overlap {
+            return Ok(None);
+        }
+        let warning = OverlapWarning {
+            iteration: next_time_point.iteration,
+            time: next_time_point.time,
+            subdomain: sbox.subdomain_plain_index,
+            max_displacement,
+            max_overlap,
+        };
+        self.warnings
+            .lock()
+            .map_err(|e| SimulationError::OtherThreadError(e.to_string()))?
+            .push(warning.clone());
+        self.last_exceeded
+            .fetch_max(next_time_point.iteration + 1, Ordering::SeqCst);
+        Ok(Some(warning))
+    }
+
+    /// Reduces the time increment of the stepper if any thread exceeded a threshold in the
+    /// current iteration.
+    ///
+    /// All threads need to be synchronized after calling [OverlapDiagnostics::check] and before
+    /// calling this function such that every thread takes the same decision.
+    pub fn apply_dt_reduction<T>(&self, time_stepper: &mut T, next_time_point: &NextTimePoint<F>)
+    where
+        T: TimeStepper<F>,
+    {
+        if let Some(n_steps) = self.reduce_dt_steps {
+            if self.last_exceeded.load(Ordering::SeqCst) == next_time_point.iteration + 1 {
+                time_stepper.reduce_increment(n_steps);
+            }
+        }
+    }
+}
+
+impl<I, S, C, A, Com, Sy, R> SubDomainBox<I, S, C, A, Com, Sy, R>
+where
+    S: SubDomain,
+{
+    /// Positions of all cells in this subdomain
+    pub fn cell_positions<Pos>(&self) -> BTreeMap<CellIdentifier, Pos>
+    where
+        C: cellular_raza_concepts::Position<Pos>,
+    {
+        self.voxels
+            .values()
+            .flat_map(|voxel| voxel.cells.iter())
+            .map(|(cbox, _)| (cbox.identifier, cbox.cell.pos()))
+            .collect()
+    }
+
+    /// Largest overlap between two cells in the same or neighboring voxels of this subdomain.
+    pub fn max_overlap<Pos, F>(&self) -> F
+    where
+        C: cellular_raza_concepts::Position<Pos>,
+        C: OverlapMeasure<Pos, F>,
+        F: num::Float,
+    {
+        let mut max_overlap = F::zero();
+        for (plain_index, voxel) in self.voxels.iter() {
+            let others = voxel
+                .neighbors
+                .iter()
+                .filter(|neighbor| *neighbor > plain_index)
+                .filter_map(|neighbor| self.voxels.get(neighbor));
+            for (n, (cbox1, _)) in voxel.cells.iter().enumerate() {
+                let pos1 = cbox1.cell.pos();
+                let candidates = voxel.cells[n + 1..]
+                    .iter()
+                    .chain(others.clone().flat_map(|other| other.cells.iter()));
+                for (cbox2, _) in candidates {
+                    let overlap = cbox1.cell.overlap_radius() + cbox2.cell.overlap_radius()
+                        - C::distance(&pos1, &cbox2.cell.pos());
+                    max_overlap = max_overlap.max(overlap);
+                }
+            }
+        }
+        max_overlap
+    }
+}
+
+#[cfg(test)]
+mod test_overlap_diagnostics {
+    use super::*;
+    use crate::backend::chili::{test_fixtures::*, VoxelPlainIndex};
+    use crate::time::{AdaptiveStepsize, SaveSchedule};
+
+    #[test]
+    fn check_subdomain_box() {
+        // Cells of unit diameter where only the first two overlap considerably
+        let mut sbox = subdomain_box(3, &[0.2, 0.6, 1.5, 2.9]);
+        assert!((sbox.max_overlap::<f64, f64>() - 0.6).abs() < 1e-12);
+        let previous_positions = sbox.cell_positions();
+        assert_eq!(previous_positions.len(), 4);
+
+        // Move the cell of the second voxel away from the others
+        let voxel = sbox.voxels.get_mut(&VoxelPlainIndex(1)).unwrap();
+        voxel.cells[0].0.cell.pos = 1.8;
+        let time_point = NextTimePoint {
+            increment: 0.1,
+            time: 0.3,
+            iteration: 3,
+            event: None,
+        };
+        let diagnostics = OverlapDiagnostics::new(0.5, 1.0);
+        let warning = diagnostics
+            .check(&sbox, &previous_positions, &time_point)
+            .unwrap();
+        assert_eq!(warning, None);
+
+        let diagnostics = OverlapDiagnostics::new(0.2, 1.0);
+        let warning = diagnostics
+            .check(&sbox, &previous_positions, &time_point)
+            .unwrap()
+            .unwrap();
+        assert_eq!(warning.iteration, 3);
+        assert_eq!(warning.subdomain, SubDomainPlainIndex(0));
+        assert!((warning.max_displacement - 0.3).abs() < 1e-12);
+        assert!((warning.max_overlap - 0.6).abs() < 1e-12);
+        assert_eq!(diagnostics.warnings(), vec![warning]);
+    }
+
+    #[test]
+    fn reduce_dt_after_exceeding() {
+        let diagnostics = OverlapDiagnostics::new(0.1, 0.1).reduce_dt_for(2);
+        let mut stepper =
+            AdaptiveStepsize::from_save_schedule(0.0, 1.0, 10.0, &SaveSchedule::EveryDt(5.0))
+                .unwrap();
+        let next = stepper.advance().unwrap().unwrap();
+        diagnostics.apply_dt_reduction(&mut stepper, &next);
+        assert_eq!(stepper.advance().unwrap().unwrap().increment, 1.0);
+
+        // Mimic a thread which exceeded the thresholds in this iteration
+        let next = stepper.advance().unwrap().unwrap();
+        diagnostics
+            .last_exceeded
+            .fetch_max(next.iteration + 1, Ordering::SeqCst);
+        diagnostics.apply_dt_reduction(&mut stepper, &next);
+        let increments: Vec<_> = (0..3)
+            .map(|_| stepper.advance().unwrap().unwrap().increment)
+            .collect();
+        assert_eq!(increments, vec![0.5, 0.5, 1.0]);
+    }
+}
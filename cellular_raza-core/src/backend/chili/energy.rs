@@ -0,0 +1,291 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use cellular_raza_concepts::{CalcError, Interaction, KineticEnergy, Position, SubDomain};
+use serde::{Deserialize, Serialize};
+
+use super::{SimulationError, SubDomainBox};
+use crate::storage::{StorageError, StorageInterfaceStore};
+use crate::time::NextTimePoint;
+
+/// Total energy and momentum of all cells at one save point.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct EnergyRecord<F, Mom> {
+    /// Iteration of the save point
+    pub iteration: usize,
+    /// Time of the save point
+    pub time: F,
+    /// Number of cells which were considered
+    pub n_cells: usize,
+    /// Sum of the kinetic energies of all cells
+    pub kinetic_energy: F,
+    /// Sum of the potential energies of all interacting pairs of cells
+    pub potential_energy: F,
+    /// Total momentum of all cells
+    pub momentum: Mom,
+}
+
+impl<F, Mom> EnergyRecord<F, Mom>
+where
+    F: core::ops::Add<Output = F> + Copy,
+{
+    /// Sum of kinetic and potential energy
+    pub fn total_energy(&self) -> F {
+        self.kinetic_energy + self.potential_energy
+    }
+}
+
+/// Records energy and momentum of the simulated cells at every save point.
+///
+/// This instrumentation helps to quantitatively validate solvers as well as implementations of
+/// interactions and boundaries.
+/// At every save point, each subdomain calculates
+/// - the kinetic energy and momentum of its cells via the
+///   [KineticEnergy](cellular_raza_concepts::KineticEnergy) trait
+/// - the potential energy between cells in the same and neighboring voxels via the optional
+///   [Interaction::potential_energy_between](cellular_raza_concepts::Interaction::potential_energy_between)
+///   method. Pairs of cells which do not report any energy are skipped.
+///
+/// The results of all subdomains are summed up.
+/// Pairs of cells which live in different subdomains are not considered.
+/// For exact validation, the simulation should thus be executed with a single thread.
+///
+/// The accounting is passed to the
+/// [run_simulation](crate::backend::chili::run_simulation) macro via the
+/// `energy_accounting` argument.
+/// All clones share their records.
+/// ```
+/// # use cellular_raza_core::backend::chili::EnergyAccounting;
+/// let accounting = EnergyAccounting::<f64, f64>::new();
+/// // Pass `energy_accounting: accounting` to the run_simulation macro
+/// assert!(accounting.records().is_empty());
+/// ```
+#[derive(Clone, Debug)]
+pub struct EnergyAccounting<F, Mom> {
+    /// Records of all save points ordered by their iteration
+    records: Arc<Mutex<BTreeMap<usize, EnergyRecord<F, Mom>>>>,
+}
+
+impl<F, Mom> Default for EnergyAccounting<F, Mom> {
+    fn default() -> Self {
+        Self {
+            records: Arc::new(Mutex::new(BTreeMap::new())),
+        }
+    }
+}
+
+impl<F, Mom> EnergyAccounting<F, Mom> {
+    /// Constructs a new empty accounting
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// All records ordered by their iteration
+    pub fn records(&self) -> Vec<EnergyRecord<F, Mom>>
+    where
+        F: Clone,
+        Mom: Clone,
+    {
+        self.records
+            .lock()
+            .map(|records| records.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Stores all records in the given storage.
+    pub fn store<S>(&self, storage: &mut S) -> Result<(), StorageError>
+    where
+        S: StorageInterfaceStore<usize, EnergyRecord<F, Mom>>,
+        F: Clone + Serialize,
+        Mom: Clone + Serialize,
+    {
+        for record in self.records() {
+            storage.store_single_element(record.iteration as u64, &0, &record)?;
+        }
+        Ok(())
+    }
+
+    /// Adds the contribution of one subdomain to the record of the current iteration.
+    fn add(
+        &self,
+        next_time_point: &NextTimePoint<F>,
+        n_cells: usize,
+        kinetic_energy: F,
+        potential_energy: F,
+        momentum: Mom,
+    ) -> Result<(), SimulationError>
+    where
+        F: num::Float,
+        Mom: core::ops::Add<Output = Mom> + Clone,
+    {
+        let mut records = self
+            .records
+            .lock()
+            .map_err(|e| SimulationError::OtherThreadError(e.to_string()))?;
+        let iteration = next_time_point.iteration;
+        match records.get_mut(&iteration) {
+            Some(record) => {
+                record.n_cells += n_cells;
+                record.kinetic_energy = record.kinetic_energy + kinetic_energy;
+                record.potential_energy = record.potential_energy + potential_energy;
+                record.momentum = record.momentum.clone() + momentum;
+            }
+            None => {
+                records.insert(
+                    iteration,
+                    EnergyRecord {
+                        iteration,
+                        time: next_time_point.time,
+                        n_cells,
+                        kinetic_energy,
+                        potential_energy,
+                        momentum,
+                    },
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Records kinetic energy and momentum of all cells in the subdomain if the current
+    /// iteration is a save point.
+    pub fn record_kinetic<I, S, C, A, Com, Sy, R>(
+        &self,
+        sbox: &SubDomainBox<I, S, C, A, Com, Sy, R>,
+        next_time_point: &NextTimePoint<F>,
+    ) -> Result<(), SimulationError>
+    where
+        S: SubDomain,
+        C: KineticEnergy<Mom, F>,
+        F: num::Float,
+        Mom: core::ops::Add<Output = Mom> + num::Zero + Clone,
+    {
+        if next_time_point.event.is_none() {
+            return Ok(());
+        }
+        let (n_cells, kinetic_energy, momentum) = sbox.kinetic_energy_and_momentum();
+        self.add(
+            next_time_point,
+            n_cells,
+            kinetic_energy,
+            F::zero(),
+            momentum,
+        )
+    }
+
+    /// Records kinetic energy, potential energy and momentum of all cells in the subdomain if
+    /// the current iteration is a save point.
+    pub fn record<I, S, C, A, Com, Sy, R, Pos, Vel, For, Inf>(
+        &self,
+        sbox: &SubDomainBox<I, S, C, A, Com, Sy, R>,
+        next_time_point: &NextTimePoint<F>,
+    ) -> Result<(), SimulationError>
+    where
+        S: SubDomain,
+        C: KineticEnergy<Mom, F>,
+        C: Position<Pos>,
+        C: Interaction<Pos, Vel, For, Inf>,
+        F: num::Float,
+        Mom: core::ops::Add<Output = Mom> + num::Zero + Clone,
+    {
+        if next_time_point.event.is_none() {
+            return Ok(());
+        }
+        let (n_cells, kinetic_energy, momentum) = sbox.kinetic_energy_and_momentum();
+        let potential_energy = sbox
+            .potential_energy::<Pos, Vel, For, Inf>()?
+            .and_then(F::from)
+            .unwrap_or(F::zero());
+        self.add(
+            next_time_point,
+            n_cells,
+            kinetic_energy,
+            potential_energy,
+            momentum,
+        )
+    }
+}
+
+impl<I, S, C, A, Com, Sy, R> SubDomainBox<I, S, C, A, Com, Sy, R>
+where
+    S: SubDomain,
+{
+    /// Number of cells, total kinetic energy and momentum of all cells in this subdomain
+    pub fn kinetic_energy_and_momentum<F, Mom>(&self) -> (usize, F, Mom)
+    where
+        C: KineticEnergy<Mom, F>,
+        F: num::Float,
+        Mom: core::ops::Add<Output = Mom> + num::Zero,
+    {
+        self.voxels
+            .values()
+            .flat_map(|voxel| voxel.cells.iter())
+            .fold(
+                (0, F::zero(), Mom::zero()),
+                |(n, kinetic_energy, momentum), (cbox, _)| {
+                    (
+                        n + 1,
+                        kinetic_energy + cbox.cell.kinetic_energy(),
+                        momentum + cbox.cell.momentum(),
+                    )
+                },
+            )
+    }
+
+    /// Total potential energy reported by
+    /// [Interaction::potential_energy_between_with_parameters](cellular_raza_concepts::Interaction::potential_energy_between_with_parameters)
+    /// for all pairs of cells in the same or neighboring voxels of this subdomain.
+    ///
+    /// Every pair is counted once.
+    /// Pairs which do not report any energy are skipped.
+    /// Returns `None` if no pair reported an energy.
+    pub fn potential_energy<Pos, Vel, For, Inf>(&self) -> Result<Option<f64>, CalcError>
+    where
+        C: Position<Pos>,
+        C: Interaction<Pos, Vel, For, Inf>,
+    {
+        let mut potential_energy = None;
+        self.for_each_interacting_pair::<Pos, Vel, For, Inf, _>(|cell, pos, ext_pos, ext_inf| {
+            if let Some(energy) = cell.potential_energy_between_with_parameters(
+                pos,
+                ext_pos,
+                ext_inf,
+                &self.global_parameters,
+            )? {
+                *potential_energy.get_or_insert(0.0) += energy;
+            }
+            Ok(())
+        })?;
+        Ok(potential_energy)
+    }
+
+    /// Calls the given function once for every pair of cells in the same or neighboring voxels.
+    fn for_each_interacting_pair<Pos, Vel, For, Inf, Func>(
+        &self,
+        mut func: Func,
+    ) -> Result<(), CalcError>
+    where
+        C: Position<Pos>,
+        C: Interaction<Pos, Vel, For, Inf>,
+        Func: FnMut(&C, &Pos, &Pos, &Inf) -> Result<(), CalcError>,
+    {
+        for (plain_index, voxel) in self.voxels.iter() {
+            let others = voxel
+                .neighbors
+                .iter()
+                .filter(|neighbor| *neighbor > plain_index)
+                .filter_map(|neighbor| self.voxels.get(neighbor));
+            for (n, (cbox1, _)) in voxel.cells.iter().enumerate() {
+                let pos1 = cbox1.cell.pos();
+                let candidates = voxel.cells[n + 1..]
+                    .iter()
+                    .chain(others.clone().flat_map(|other| other.cells.iter()));
+                for (cbox2, _) in candidates {
+                    let info = cbox2.cell.get_interaction_information();
+                    func(&cbox1.cell, &pos1, &cbox2.cell.pos(), &info)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
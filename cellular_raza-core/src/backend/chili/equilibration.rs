@@ -0,0 +1,384 @@
+use std::collections::BTreeMap;
+
+use cellular_raza_concepts::{
+    CalcError, GlobalParameters, Interaction, Position, SubDomain, Velocity, Xapy,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{CellIdentifier, OverlapMeasure, RngStream, SimulationError, SubDomainBox};
+use crate::time::NextTimePoint;
+
+/// Relaxes the initial configuration of cells before the recorded simulation starts.
+///
+/// Randomly placed cells often overlap strongly which leads to large forces in the first steps
+/// of a simulation.
+/// During the equilibration phase, only the mechanics of the cells is updated for
+/// [Equilibration::n_steps] steps with the increment [Equilibration::dt].
+/// Thus only the `Mechanics`, `Interaction` and `DomainForce` aspects are considered while
+/// cycles and reactions are paused.
+/// Time does not advance and no results are stored.
+/// By default, only repulsive forces between cells are considered such that overlapping cells
+/// separate without being pulled towards their neighbors.
+/// See [SubDomainBox::set_repulsive_only].
+/// A warning is emitted at the start of the equilibration if the cells do not implement
+/// [Interaction::normal_force] and attractive forces can thus not be discarded.
+///
+/// Optionally, the distance which any cell may travel in a single step is limited by
+/// [Equilibration::max_displacement].
+/// Displacements are measured via the [OverlapMeasure] trait which needs to be implemented by
+/// the cells.
+///
+/// The equilibration is passed to the
+/// [run_simulation](crate::backend::chili::run_simulation) macro via the `equilibration`
+/// argument.
+/// ```
+/// # use cellular_raza_core::backend::chili::Equilibration;
+/// let equilibration = Equilibration::new(100, 0.01).max_displacement(0.1);
+/// // Pass `equilibration: equilibration` to the run_simulation macro
+/// assert_eq!(equilibration.n_steps, 100);
+/// assert!(equilibration.repulsive_only);
+/// ```
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Equilibration<F> {
+    /// Number of equilibration steps
+    pub n_steps: usize,
+    /// Time increment of every equilibration step
+    pub dt: F,
+    /// Maximum distance which any cell may travel in one step
+    pub max_displacement: Option<F>,
+    /// Discard attractive forces between cells
+    pub repulsive_only: bool,
+}
+
+impl<F> Equilibration<F> {
+    /// Equilibrate for the given number of steps without limiting displacements.
+    pub fn new(n_steps: usize, dt: F) -> Self {
+        Self {
+            n_steps,
+            dt,
+            max_displacement: None,
+            repulsive_only: true,
+        }
+    }
+
+    /// Limit the distance which any cell may travel in one step.
+    pub fn max_displacement(self, max_displacement: F) -> Self {
+        Self {
+            max_displacement: Some(max_displacement),
+            ..self
+        }
+    }
+
+    /// Also consider attractive forces between cells during the equilibration.
+    pub fn include_attraction(self) -> Self {
+        Self {
+            repulsive_only: false,
+            ..self
+        }
+    }
+
+    /// Time point which is used for the given equilibration step
+    ///
+    /// The time is fixed at zero and no events are scheduled.
+    pub fn time_point(&self, step: usize) -> NextTimePoint<F>
+    where
+        F: num::Zero + Copy,
+    {
+        NextTimePoint {
+            increment: self.dt,
+            time: F::zero(),
+            iteration: step,
+            event: None,
+        }
+    }
+}
+
+/// Calculates the forces between a cell and an external cell.
+///
+/// Both forces are discarded if they are attractive and `repulsive_only` is set.
+/// See [SubDomainBox::set_repulsive_only].
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn calculate_pair_forces<C, Pos, Vel, For, Float, Inf>(
+    cell: &C,
+    own_pos: &Pos,
+    own_vel: &Vel,
+    ext_pos: &Pos,
+    ext_vel: &Vel,
+    ext_inf: &Inf,
+    parameters: &GlobalParameters,
+    repulsive_only: bool,
+) -> Result<(For, For), CalcError>
+where
+    C: Interaction<Pos, Vel, For, Inf>,
+    For: Xapy<Float>,
+    Float: num::Float,
+{
+    let (own_force, ext_force) = cell.calculate_force_between_with_parameters(
+        own_pos, own_vel, ext_pos, ext_vel, ext_inf, parameters,
+    )?;
+    if repulsive_only && cell.normal_force(own_pos, ext_pos, &own_force)? < 0.0 {
+        return Ok((own_force.xa(Float::zero()), ext_force.xa(Float::zero())));
+    }
+    Ok((own_force, ext_force))
+}
+
+impl<I, S, C, A, Com, Sy, R> SubDomainBox<I, S, C, A, Com, Sy, R>
+where
+    S: SubDomain,
+{
+    /// Considers only repulsive forces between cells while active.
+    ///
+    /// Forces whose [normal component](Interaction::normal_force) is negative pull both cells
+    /// towards each other and are thus discarded.
+    /// The stock potentials of `cellular_raza-building-blocks` implement
+    /// [Interaction::normal_force] while cells relying on its default implementation are not
+    /// affected, see [SubDomainBox::discards_attraction].
+    pub fn set_repulsive_only(&mut self, repulsive_only: bool) {
+        for voxel in self.voxels.values_mut() {
+            voxel.repulsive_only = repulsive_only;
+        }
+    }
+
+    /// Checks if attractive forces between the cells of this subdomain can be discarded.
+    ///
+    /// Calculates the forces between all cells which share a voxel.
+    /// Returns `false` if any such forces exist but none of them has a non-zero
+    /// [normal component](Interaction::normal_force).
+    /// This is the case for interactions which rely on the default implementation of
+    /// [Interaction::normal_force] such that [SubDomainBox::set_repulsive_only] has no effect.
+    pub fn discards_attraction<Pos, Vel, For, Inf>(&self) -> Result<bool, CalcError>
+    where
+        C: Position<Pos>,
+        C: Velocity<Vel>,
+        C: Interaction<Pos, Vel, For, Inf>,
+    {
+        let mut n_pairs = 0;
+        for voxel in self.voxels.values() {
+            for (n, (c1, _)) in voxel.cells.iter().enumerate() {
+                let p1 = c1.pos();
+                for (c2, _) in voxel.cells.iter().skip(n + 1) {
+                    let p2 = c2.pos();
+                    let (force, _) = c1.calculate_force_between_with_parameters(
+                        &p1,
+                        &c1.velocity(),
+                        &p2,
+                        &c2.velocity(),
+                        &c2.get_interaction_information(),
+                        &self.global_parameters,
+                    )?;
+                    if c1.normal_force(&p1, &p2, &force)? != 0.0 {
+                        return Ok(true);
+                    }
+                    n_pairs += 1;
+                }
+            }
+        }
+        Ok(n_pairs == 0)
+    }
+
+    /// Discards attractive forces for the equilibration and warns if this has no effect.
+    ///
+    /// See [SubDomainBox::set_repulsive_only] and [SubDomainBox::discards_attraction].
+    pub fn start_repulsive_only<Pos, Vel, For, Inf>(&mut self) -> Result<(), SimulationError>
+    where
+        C: Position<Pos>,
+        C: Velocity<Vel>,
+        C: Interaction<Pos, Vel, For, Inf>,
+    {
+        self.set_repulsive_only(true);
+        if !self.discards_attraction()? {
+            let message = format!(
+                "Subdomain {}: none of the forces between cells has a normal component. \
+                Attractive forces are only discarded during the equilibration if the \
+                Interaction implements normal_force.",
+                self.subdomain_plain_index.0
+            );
+            #[cfg(feature = "tracing")]
+            tracing::warn!("{message}");
+            #[cfg(not(feature = "tracing"))]
+            eprintln!("Warning: {message}");
+        }
+        Ok(())
+    }
+
+    /// Runs the local mechanics functions of all cells during the equilibration phase.
+    ///
+    /// This is identical to [SubDomainBox::run_local_cell_funcs] but seeds counter-based
+    /// generators from a separate stream such that they do not repeat in the recorded simulation.
+    pub fn run_equilibration_cell_funcs<Func, F>(
+        &mut self,
+        func: Func,
+        next_time_point: &NextTimePoint<F>,
+    ) -> Result<(), SimulationError>
+    where
+        Func: Fn(&mut C, &mut A, F, &mut R) -> Result<(), SimulationError>,
+        F: Copy,
+        R: rand::SeedableRng,
+    {
+        self.run_cell_funcs_in_stream(func, next_time_point, RngStream::Equilibration)
+    }
+
+    /// Moves cells back towards their previous positions such that no cell travelled further
+    /// than the given distance.
+    ///
+    /// The `previous_positions` should be obtained via [SubDomainBox::cell_positions] before
+    /// updating the mechanics.
+    /// Returns the number of cells whose displacement was limited.
+    pub fn limit_displacements<Pos, F>(
+        &mut self,
+        previous_positions: &BTreeMap<CellIdentifier, Pos>,
+        max_displacement: F,
+    ) -> usize
+    where
+        C: Position<Pos>,
+        C: OverlapMeasure<Pos, F>,
+        Pos: Xapy<F>,
+        F: num::Float,
+    {
+        let mut n_limited = 0;
+        for (cbox, _) in self
+            .voxels
+            .values_mut()
+            .flat_map(|voxel| voxel.cells.iter_mut())
+        {
+            if let Some(previous) = previous_positions.get(&cbox.identifier) {
+                let current = cbox.cell.pos();
+                let displacement = C::distance(previous, &current);
+                if displacement > max_displacement {
+                    // Interpolate linearly between the previous and current position
+                    let s = max_displacement / displacement;
+                    let limited = current.xapy(s, &previous.xa(F::one() - s));
+                    cbox.cell.set_pos(&limited);
+                    n_limited += 1;
+                }
+            }
+        }
+        n_limited
+    }
+}
+
+#[cfg(test)]
+mod test_equilibration {
+    use super::*;
+    use crate::backend::chili::test_fixtures::*;
+    use crate::backend::chili::UpdateMechanics;
+
+    /// Pushes other cells away within unit distance
+    fn repulsive(pos: f64) -> Agent {
+        Agent {
+            pos,
+            strength: -10.0,
+            range: 1.0,
+        }
+    }
+
+    #[test]
+    fn repulsive_only_discards_attraction() {
+        // The last cell attracts the other two which repel each other
+        let cells = [repulsive(0.9), repulsive(1.1), Agent::from(2.9)];
+        let forces = |repulsive_only: bool| {
+            let mut vox = voxel(0, &[], &cells);
+            vox.repulsive_only = repulsive_only;
+            vox.calculate_force_between_cells_internally::<_, _, _, f64, _, 1>(
+                None,
+                &GlobalParameters::new(),
+            )
+            .unwrap();
+            vox.cells
+                .iter_mut()
+                .map(|(_, aux)| aux.get_current_force_and_reset())
+                .collect::<Vec<_>>()
+        };
+        let all = forces(false);
+        let repulsive = forces(true);
+        for (force, expected) in all.iter().zip([-1.0, 2.9, -1.9]) {
+            assert!((force - expected).abs() < 1e-12);
+        }
+        for (force, expected) in repulsive.iter().zip([-2.0, 2.0, 0.0]) {
+            assert!((force - expected).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn detect_missing_normal_forces() {
+        // Without any pairs of cells, there is nothing to discard
+        let sbox = subdomain_box(3, &[Agent::from(0.5)]);
+        assert!(sbox.discards_attraction::<f64, f64, f64, ()>().unwrap());
+        let sbox = subdomain_box(3, &[repulsive(0.9), repulsive(1.1)]);
+        assert!(sbox.discards_attraction::<f64, f64, f64, ()>().unwrap());
+        // Cells which do not interact look like cells without normal forces
+        let sbox = subdomain_box(
+            3,
+            &[Agent::from(0.1), Agent::from(0.9)].map(|mut agent| {
+                agent.range = 0.5;
+                agent
+            }),
+        );
+        assert!(!sbox.discards_attraction::<f64, f64, f64, ()>().unwrap());
+    }
+
+    #[test]
+    fn overlapping_cells_separate_with_limited_displacements() {
+        let mut sbox = subdomain_box(3, &[repulsive(1.45), repulsive(1.55)]);
+        sbox.set_repulsive_only(true);
+        let max_displacement = 0.1;
+        for _ in 0..10 {
+            let previous_positions = sbox.cell_positions();
+            let overlapping = sbox.max_overlap::<f64, f64>() > 0.0;
+            let parameters = sbox.global_parameters().clone();
+            // Explicit Euler step with a far too large increment
+            for vox in sbox.voxels.values_mut() {
+                vox.calculate_force_between_cells_internally::<_, _, _, f64, _, 1>(
+                    None,
+                    &parameters,
+                )
+                .unwrap();
+                for (cbox, aux) in vox.cells.iter_mut() {
+                    cbox.cell.pos += aux.get_current_force_and_reset();
+                }
+            }
+            let n_limited = sbox.limit_displacements(&previous_positions, max_displacement);
+            assert_eq!(n_limited, if overlapping { 2 } else { 0 });
+            for cbox in sbox.iter_cells() {
+                let displacement = (cbox.cell.pos - previous_positions[&cbox.identifier]).abs();
+                assert!(displacement <= max_displacement + 1e-12);
+            }
+        }
+        // Cells stop once they do not overlap anymore
+        let positions: Vec<_> = sbox.iter_cells().map(|cbox| cbox.cell.pos).collect();
+        assert!((positions[0] - 0.95).abs() < 1e-12);
+        assert!((positions[1] - 2.05).abs() < 1e-12);
+    }
+
+    #[test]
+    fn equilibration_clears_pressure() {
+        use crate::backend::chili::{local_interaction_clear_neighbors, UpdateInteraction};
+        use rand::SeedableRng;
+        let mut vox = voxel(0, &[], &[repulsive(0.9), repulsive(1.1)]);
+        vox.calculate_force_between_cells_internally::<_, _, _, f64, _, 1>(
+            None,
+            &GlobalParameters::new(),
+        )
+        .unwrap();
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(0);
+        for (cbox, aux) in vox.cells.iter_mut() {
+            assert!(aux.get_current_pressure() > 0.0);
+            local_interaction_clear_neighbors(&mut cbox.cell, aux, 0.1, &mut rng).unwrap();
+            assert_eq!(aux.get_current_pressure(), 0.0);
+            assert_eq!(aux.get_current_neighbors(), 0);
+        }
+    }
+
+    #[test]
+    fn time_point_does_not_advance() {
+        let equilibration = Equilibration::new(10, 0.5).max_displacement(0.1);
+        assert_eq!(equilibration.max_displacement, Some(0.1));
+        for step in 0..equilibration.n_steps {
+            let next_time_point = equilibration.time_point(step);
+            assert_eq!(next_time_point.increment, 0.5);
+            assert_eq!(next_time_point.time, 0.0);
+            assert_eq!(next_time_point.iteration, step);
+            assert!(next_time_point.event.is_none());
+        }
+    }
+}
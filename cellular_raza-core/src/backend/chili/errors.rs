@@ -56,6 +56,7 @@ macro_rules! impl_from_error {
 /// | [BoundaryError](SimulationError::BoundaryError) | 7/10 | 1/10 | 2/10 |
 /// | [DrawingError](SimulationError::DrawingError) | 9/10 | 1/10 | 0/10 |
 /// | [IndexError](SimulationError::IndexError) | 6/10 | 2/10 | 2/10 |
+/// | [AuditError](SimulationError::AuditError) | 5/10 | 4/10 | 1/10 |
 /// | [SendError](SimulationError::SendError) | 3/10 | 6/10 | 1/10 |
 /// | [ReceiveError](SimulationError::ReceiveError) | 3/10 | 6/10 | 1/10 |
 /// | [StorageError](SimulationError::StorageError) | 3/10 | 7/10 | 0/10 |
@@ -92,6 +93,9 @@ pub enum SimulationError {
     /// Mostly caused by trying to find a voxel by its index.
     /// This error can also occcurr when applying too large simulation-steps.
     IndexError(IndexError),
+    /// Invariants of the simulation state were violated.
+    /// See [SafetyAudit](super::SafetyAudit).
+    AuditError(super::AuditReport),
 
     // Less likely but possible to be user errors
     /// Sending information between threads fails
@@ -123,6 +127,7 @@ impl_from_error! {SimulationError,
     (DeathError, DeathError),
     (BoundaryError, BoundaryError),
     (IndexError, IndexError),
+    (AuditError, super::AuditReport),
     (IoError, std::io::Error),
     (DrawingError, DrawingError),
     (StorageError, StorageError),
@@ -140,6 +145,7 @@ impl_error_variant! {SimulationError,
     DeathError,
     BoundaryError,
     IndexError,
+    AuditError,
     IoError,
     DrawingError,
     StorageError,
@@ -207,6 +213,7 @@ pub trait ErrorHandler: Sized {
             DeathError(_) => RevertChangeAccuracy,
             BoundaryError(_) => Abort,
             IndexError(_) => RevertChangeAccuracy,
+            AuditError(_) => Abort,
             IoError(_) => Ignore,
             DrawingError(_) => Ignore,
             StorageError(_) => Ignore,
@@ -269,6 +276,7 @@ impl From<SimulationError> for pyo3::PyErr {
             BoundaryError(e) => pyo3::PyErr::new::<PyValueError, _>(format!("cr_err: {e:?}")),
             DrawingError(e) => pyo3::PyErr::new::<PyValueError, _>(format!("cr_err: {e:?}")),
             IndexError(e) => pyo3::PyErr::new::<PyValueError, _>(format!("cr_err: {e:?}")),
+            AuditError(e) => pyo3::PyErr::new::<PyValueError, _>(format!("cr_err: {e}")),
             SendError(e) => pyo3::PyErr::new::<PyValueError, _>(format!("cr_err: {e:?}")),
             ReceiveError(e) => pyo3::PyErr::new::<PyValueError, _>(format!("cr_err: {e:?}")),
             StorageError(e) => pyo3::PyErr::new::<PyValueError, _>(format!("cr_err: {e:?}")),
@@ -0,0 +1,334 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use cellular_raza_concepts::{CalcError, Position, SubDomain};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    CellBox, CellIdentifier, ForceMeasure, IndexError, OverlapMeasure, SimulationError,
+    SubDomainBox, SubDomainPlainIndex, UpdateMechanics, Voxel, VoxelPlainIndex,
+};
+use crate::time::NextTimePoint;
+
+/// State of a single cell at the time at which a non-finite value was detected.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ForensicCell<C, Pos, For> {
+    /// Identifier of the cell
+    pub identifier: CellIdentifier,
+    /// Voxel in which the cell is stored
+    pub voxel: VoxelPlainIndex,
+    /// Full state of the cell
+    pub cell: C,
+    /// Position of the cell before the increment was applied
+    pub previous_position: Option<Pos>,
+    /// Total force which acted on the cell in the last step
+    pub last_force: Option<For>,
+}
+
+/// Content of a single file written by the [ForensicDump].
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ForensicReport<C, Pos, For, F> {
+    /// Description of the detected problem
+    pub reason: String,
+    /// Iteration at which the problem was detected
+    pub iteration: usize,
+    /// Time at which the problem was detected
+    pub time: F,
+    /// Subdomain in which the cell is located
+    pub subdomain: SubDomainPlainIndex,
+    /// The offending cell
+    pub cell: ForensicCell<C, Pos, For>,
+    /// All cells in the same and neighboring voxels of the subdomain
+    pub neighbors: Vec<ForensicCell<C, Pos, For>>,
+}
+
+/// Positions and forces of all cells before the increments of a step are applied.
+///
+/// Obtained via [ForensicDump::record].
+#[derive(Clone, Debug)]
+pub struct ForensicRecord<Pos, For> {
+    /// Positions of all cells
+    positions: BTreeMap<CellIdentifier, Pos>,
+    /// Total forces acting on all cells
+    forces: BTreeMap<CellIdentifier, For>,
+}
+
+/// Detects non-finite forces and positions and dumps the state of the offending cell.
+///
+/// A single NaN produced by an interaction or by the random contribution of a cell spreads
+/// to all of its neighbors within a few steps.
+/// The simulation then either fails with an unrelated error or silently continues with
+/// corrupted values.
+/// Before the increments of a step are applied, the total force acting on every cell is
+/// checked.
+/// Afterwards, the new position of every cell is checked.
+/// When a non-finite value is found, the full state of the first offending cell together with
+/// its previous position, its last force and all cells in the same and neighboring voxels is
+/// written as [ForensicReport] in json format to the given directory.
+/// Non-finite numbers are stored as `null`.
+/// The simulation is then aborted with a [CalcError] which describes the cell and names the
+/// file.
+///
+/// Only the `Mechanics` aspect is checked.
+/// Cells need to implement the [ForceMeasure] and [OverlapMeasure] traits and need to be
+/// serializable.
+///
+/// The dump is passed to the [run_simulation](crate::backend::chili::run_simulation) macro via
+/// the `forensic_dump` argument.
+/// All clones share the list of written files.
+/// ```
+/// # use cellular_raza_core::backend::chili::ForensicDump;
+/// let dump = ForensicDump::new("out/forensics");
+/// // Pass `forensic_dump: dump` to the run_simulation macro
+/// assert!(dump.files().is_empty());
+/// ```
+#[derive(Clone, Debug)]
+pub struct ForensicDump {
+    /// Directory in which the reports are stored
+    pub directory: PathBuf,
+    /// Files which have been written by all threads
+    files: Arc<Mutex<Vec<PathBuf>>>,
+}
+
+impl ForensicDump {
+    /// Writes reports into the given directory which is created if it does not exist.
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            files: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// All files which have been written so far
+    pub fn files(&self) -> Vec<PathBuf> {
+        self.files
+            .lock()
+            .map(|files| files.clone())
+            .unwrap_or_default()
+    }
+
+    /// Records positions and forces of all cells and checks that all forces are finite.
+    ///
+    /// This needs to be called after all forces have been gathered and before the increments
+    /// are applied.
+    pub fn record<I, S, C, A, Com, Sy, R, Pos, Vel, For, F, const N: usize>(
+        &self,
+        sbox: &mut SubDomainBox<I, S, C, A, Com, Sy, R>,
+        next_time_point: &NextTimePoint<F>,
+    ) -> Result<ForensicRecord<Pos, For>, SimulationError>
+    where
+        S: SubDomain,
+        A: UpdateMechanics<Pos, Vel, For, N>,
+        C: Position<Pos>,
+        C: ForceMeasure<For, F>,
+        C: Clone + Serialize,
+        Pos: Clone + Serialize,
+        For: Clone + Serialize,
+        F: num::Float + Serialize,
+    {
+        let mut forces = BTreeMap::new();
+        let mut offending = Vec::new();
+        for voxel in sbox.voxels.values_mut() {
+            for (cbox, aux_storage) in voxel.cells.iter_mut() {
+                let force = aux_storage.get_current_force_and_reset();
+                if !C::force_magnitude(&force).is_finite() {
+                    offending.push(cbox.identifier);
+                }
+                aux_storage.add_force(force.clone());
+                forces.insert(cbox.identifier, force);
+            }
+        }
+        let record = ForensicRecord {
+            positions: sbox.cell_positions(),
+            forces,
+        };
+        let subdomain = sbox.subdomain_plain_index;
+        self.dump(
+            &sbox.voxels,
+            subdomain,
+            &record,
+            offending,
+            "total force",
+            next_time_point,
+        )?;
+        Ok(record)
+    }
+
+    /// Checks that the positions of all cells are finite after the increments were applied.
+    pub fn check_positions<I, S, C, A, Com, Sy, R, Pos, For, F>(
+        &self,
+        sbox: &SubDomainBox<I, S, C, A, Com, Sy, R>,
+        record: &ForensicRecord<Pos, For>,
+        next_time_point: &NextTimePoint<F>,
+    ) -> Result<(), SimulationError>
+    where
+        S: SubDomain,
+        C: Position<Pos>,
+        C: OverlapMeasure<Pos, F>,
+        C: Clone + Serialize,
+        Pos: Clone + Serialize,
+        For: Clone + Serialize,
+        F: num::Float + Serialize,
+    {
+        let offending = sbox
+            .iter_cells()
+            .filter(|cbox| {
+                let pos = cbox.cell.pos();
+                // The distance of a position to itself is only finite for finite positions
+                !C::distance(&pos, &pos).is_finite()
+            })
+            .map(|cbox| cbox.identifier)
+            .collect();
+        let subdomain = sbox.subdomain_plain_index;
+        self.dump(
+            &sbox.voxels,
+            subdomain,
+            record,
+            offending,
+            "position",
+            next_time_point,
+        )
+    }
+
+    /// Writes the report of the first offending cell and returns an error describing it.
+    fn dump<C, A, R, Pos, For, F>(
+        &self,
+        voxels: &BTreeMap<VoxelPlainIndex, Voxel<C, A, R>>,
+        subdomain: SubDomainPlainIndex,
+        record: &ForensicRecord<Pos, For>,
+        offending: Vec<CellIdentifier>,
+        quantity: &str,
+        next_time_point: &NextTimePoint<F>,
+    ) -> Result<(), SimulationError>
+    where
+        C: Clone + Serialize,
+        Pos: Clone + Serialize,
+        For: Clone + Serialize,
+        F: num::Float + Serialize,
+    {
+        let identifier = match offending.first() {
+            Some(identifier) => *identifier,
+            None => return Ok(()),
+        };
+        let forensic_cell = |voxel: VoxelPlainIndex, cbox: &CellBox<C>| ForensicCell {
+            identifier: cbox.identifier,
+            voxel,
+            cell: cbox.cell.clone(),
+            previous_position: record.positions.get(&cbox.identifier).cloned(),
+            last_force: record.forces.get(&cbox.identifier).cloned(),
+        };
+        let (voxel, cbox) = voxels
+            .values()
+            .flat_map(|voxel| voxel.cells.iter().map(move |(cbox, _)| (voxel, cbox)))
+            .find(|(_, cbox)| cbox.identifier == identifier)
+            .ok_or(IndexError(format!(
+                "could not find cell {identifier:?} in subdomain"
+            )))?;
+        let neighbors = std::iter::once(&voxel.plain_index)
+            .chain(voxel.neighbors.iter())
+            .filter_map(|index| voxels.get(index))
+            .flat_map(|neighbor| {
+                neighbor
+                    .cells
+                    .iter()
+                    .filter(|(other, _)| other.identifier != identifier)
+                    .map(move |(other, _)| forensic_cell(neighbor.plain_index, other))
+            })
+            .collect();
+        let reason = format!(
+            "non-finite {quantity} of cell {identifier:?} ({} affected cells in subdomain)",
+            offending.len(),
+        );
+        let report = ForensicReport {
+            reason: reason.clone(),
+            iteration: next_time_point.iteration,
+            time: next_time_point.time,
+            subdomain,
+            cell: forensic_cell(voxel.plain_index, cbox),
+            neighbors,
+        };
+        let path = self.directory.join(format!(
+            "forensics_{:010}_{}_{}.json",
+            next_time_point.iteration, identifier.0 .0, identifier.1,
+        ));
+        std::fs::create_dir_all(&self.directory)?;
+        std::fs::write(
+            &path,
+            serde_json::to_string_pretty(&report).map_err(std::io::Error::from)?,
+        )?;
+        self.files
+            .lock()
+            .map_err(|e| SimulationError::OtherThreadError(e.to_string()))?
+            .push(path.clone());
+        Err(CalcError(format!(
+            "Detected {reason} at iteration {} in subdomain {}. \
+            The state of the cell and its neighbors was written to {}",
+            next_time_point.iteration,
+            subdomain.0,
+            path.display(),
+        ))
+        .into())
+    }
+}
+
+#[cfg(test)]
+mod test_forensics {
+    use super::*;
+    use crate::backend::chili::test_fixtures::*;
+
+    #[test]
+    fn dump_offending_cell() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let dump = ForensicDump::new(dir.path().join("forensics"));
+        let voxels: BTreeMap<_, _> = [
+            voxel(0, &[1], &[0.5]),
+            voxel(1, &[0, 2], &[1.2, f64::NAN]),
+            voxel(2, &[1], &[2.5]),
+            voxel(3, &[], &[3.5]),
+        ]
+        .into_iter()
+        .map(|vox| (vox.plain_index, vox))
+        .collect();
+        let offending = CellIdentifier(VoxelPlainIndex(1), 1);
+        let record = ForensicRecord {
+            positions: [(offending, 1.7)].into_iter().collect(),
+            forces: [(offending, 4.0)].into_iter().collect(),
+        };
+        let time_point = NextTimePoint {
+            increment: 0.1,
+            time: 2.0,
+            iteration: 20,
+            event: None,
+        };
+        let subdomain = SubDomainPlainIndex(0);
+        dump.dump(&voxels, subdomain, &record, vec![], "position", &time_point)?;
+        assert!(dump.files().is_empty());
+
+        let result = dump.dump(
+            &voxels,
+            subdomain,
+            &record,
+            vec![offending],
+            "position",
+            &time_point,
+        );
+        let message = result.unwrap_err().to_string();
+        assert!(message.contains("non-finite position of cell"));
+        assert_eq!(dump.files().len(), 1);
+        assert!(message.contains(&dump.files()[0].display().to_string()));
+
+        let contents = std::fs::read_to_string(&dump.files()[0])?;
+        let report: ForensicReport<serde_json::Value, f64, f64, f64> =
+            serde_json::from_str(&contents)?;
+        assert_eq!(report.iteration, 20);
+        assert_eq!(report.cell.identifier, offending);
+        assert_eq!(report.cell.previous_position, Some(1.7));
+        assert_eq!(report.cell.last_force, Some(4.0));
+        // Cells of the own and neighboring voxels
+        let mut neighbors: Vec<_> = report.neighbors.iter().map(|n| n.voxel.0).collect();
+        neighbors.sort();
+        assert_eq!(neighbors, vec![0, 1, 2]);
+        Ok(())
+    }
+}
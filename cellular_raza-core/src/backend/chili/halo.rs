@@ -0,0 +1,393 @@
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex};
+
+use cellular_raza_concepts::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    calculate_pair_forces, CellBox, Communicator, SimulationError, SubDomainBox,
+    SubDomainPlainIndex, UpdateInteraction, UpdateMechanics, Voxel, VoxelPlainIndex,
+};
+
+/// Mirrors all cells of a voxel into a neighboring subdomain.
+///
+/// This type is used by [SubDomainBox::send_halo_cells] and
+/// [SubDomainBox::receive_halo_cells] when the [HaloExchange] mode is active.
+/// The receiving subdomain treats the cells as read-only ghosts: they are only used to
+/// calculate the forces acting on its own cells and are discarded afterwards.
+pub struct HaloCells<Cel> {
+    /// Voxel in which the cells are located
+    pub index_sender: VoxelPlainIndex,
+    /// Copies of all cells of the voxel
+    pub cells: Vec<Cel>,
+}
+
+/// Number of messages and force calculations of the [HaloExchange] mode.
+///
+/// Compares the messages which were sent in the halo exchange mode against the messages which
+/// would have been sent by exchanging [PosInformation](super::PosInformation) and
+/// [ForceInformation](super::ForceInformation) for the same configuration of cells.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct HaloStatistics {
+    /// Number of [HaloCells] messages
+    pub halo_messages: usize,
+    /// Number of ghost cells contained in all messages
+    pub ghost_cells: usize,
+    /// Number of [PosInformation](super::PosInformation) messages which were not sent
+    pub avoided_position_messages: usize,
+    /// Number of [ForceInformation](super::ForceInformation) messages which were not sent
+    pub avoided_force_messages: usize,
+    /// Number of pairs of ghost and local cells for which forces were calculated.
+    ///
+    /// Every pair of cells across the boundary of two subdomains is evaluated in both
+    /// subdomains.
+    /// Thus, this is also the number of force calculations which are performed additionally
+    /// compared to exchanging [PosInformation](super::PosInformation).
+    pub ghost_pairs: usize,
+}
+
+impl HaloStatistics {
+    /// Number of messages which would have been sent without the halo exchange
+    pub fn avoided_messages(&self) -> usize {
+        self.avoided_position_messages + self.avoided_force_messages
+    }
+
+    /// Ratio between the messages which were sent and the messages which would have been sent
+    /// without the halo exchange.
+    ///
+    /// Returns [None] if no messages would have been sent.
+    /// ```
+    /// # use cellular_raza_core::backend::chili::HaloStatistics;
+    /// let statistics = HaloStatistics {
+    ///     halo_messages: 10,
+    ///     ghost_cells: 60,
+    ///     avoided_position_messages: 120,
+    ///     avoided_force_messages: 80,
+    ///     ghost_pairs: 400,
+    /// };
+    /// assert_eq!(statistics.avoided_messages(), 200);
+    /// assert_eq!(statistics.message_ratio(), Some(0.05));
+    /// ```
+    pub fn message_ratio(&self) -> Option<f64> {
+        match self.avoided_messages() {
+            0 => None,
+            avoided => Some(self.halo_messages as f64 / avoided as f64),
+        }
+    }
+}
+
+/// Exchanges boundary cells as read-only ghosts instead of requesting forces.
+///
+/// By default, every cell whose voxel neighbors a voxel of another subdomain sends its
+/// [PosInformation](super::PosInformation) to this voxel.
+/// The other subdomain calculates the acting forces and returns them as
+/// [ForceInformation](super::ForceInformation).
+/// This results in up to two messages per cell and neighboring voxel in every step.
+///
+/// In the halo exchange mode, every voxel at the boundary sends a single [HaloCells] message
+/// with copies of all its cells to every neighboring subdomain.
+/// The receiving subdomain calculates the forces on its own cells locally and no forces are
+/// returned.
+/// The results are identical to the default mode.
+///
+/// # Trade-Off
+/// - Only one message per boundary voxel and neighboring subdomain is sent and the third
+///   update step has nothing to receive.
+/// - Every pair of cells across a subdomain boundary is evaluated in both subdomains, thus
+///   doubling the number of force calculations at the boundary.
+/// - Whole cells are cloned and sent instead of their position, velocity and interaction
+///   information which requires the cells to implement [Clone].
+///
+/// The halo exchange is therefore beneficial when subdomains are large compared to their
+/// boundary, when many cells share a boundary voxel or when messages are expensive.
+/// Simulations with few, large cells in many small subdomains usually profit less.
+/// The [HaloStatistics] collected during the simulation quantify both sides of this trade-off
+/// for the actual simulation.
+///
+/// The halo exchange is passed to the [run_simulation](crate::backend::chili::run_simulation)
+/// macro via the `halo_exchange` argument.
+/// All clones share their statistics.
+/// ```
+/// # use cellular_raza_core::backend::chili::HaloExchange;
+/// let halo_exchange = HaloExchange::new();
+/// // Pass `halo_exchange: halo_exchange` to the run_simulation macro
+/// assert_eq!(halo_exchange.statistics().halo_messages, 0);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct HaloExchange {
+    /// Statistics accumulated by all threads
+    statistics: Arc<Mutex<HaloStatistics>>,
+}
+
+impl HaloExchange {
+    /// Constructs a new [HaloExchange] with empty statistics.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Statistics accumulated over all subdomains and steps
+    pub fn statistics(&self) -> HaloStatistics {
+        self.statistics
+            .lock()
+            .map(|statistics| statistics.clone())
+            .unwrap_or_default()
+    }
+
+    /// Adds the given contribution to the shared statistics.
+    fn add(&self, f: impl FnOnce(&mut HaloStatistics)) -> Result<(), SimulationError> {
+        let mut statistics = self
+            .statistics
+            .lock()
+            .map_err(|e| SimulationError::OtherThreadError(e.to_string()))?;
+        f(&mut statistics);
+        Ok(())
+    }
+}
+
+impl<C, A, R> Voxel<C, A, R> {
+    /// Calculates the forces which the given ghost cells exert on the cells of this voxel.
+    ///
+    /// Both halves of the interaction are calculated locally such that the results agree with
+    /// [Voxel::calculate_force_between_cells_external] evaluated in both subdomains.
+    pub(crate) fn calculate_force_from_ghosts<Pos, Vel, For, Float, Inf, const N: usize>(
+        &mut self,
+        ghosts: &[CellBox<C>],
+        metric: Option<&dyn DomainMetric<Pos>>,
+        parameters: &GlobalParameters,
+    ) -> Result<(), CalcError>
+    where
+        C: Position<Pos>,
+        C: Velocity<Vel>,
+        C: Interaction<Pos, Vel, For, Inf>,
+        A: UpdateMechanics<Pos, Vel, For, N>,
+        A: UpdateInteraction,
+        For: Xapy<Float>,
+        Float: num::Float,
+    {
+        let one_half = Float::one() / (Float::one() + Float::one());
+        let repulsive_only = self.repulsive_only;
+        for ghost in ghosts.iter() {
+            let p2 = ghost.pos();
+            let v2 = ghost.velocity();
+            let i2 = ghost.get_interaction_information();
+            for (cell, aux_storage) in self.cells.iter_mut() {
+                let p1 = cell.pos();
+                let v1 = cell.velocity();
+                let i1 = cell.get_interaction_information();
+
+                // Use the images of the cells which are closest to each other
+                let p1_image = metric.map(|metric| metric.nearest_image(&p2, &p1));
+                let p1_image = p1_image.as_ref().unwrap_or(&p1);
+                let p2_image = metric.map(|metric| metric.nearest_image(&p1, &p2));
+                let p2_image = p2_image.as_ref().unwrap_or(&p2);
+
+                // Half of the force as calculated by the local cell
+                let (force, _) = calculate_pair_forces::<_, _, _, _, Float, _>(
+                    &cell.cell,
+                    &p1,
+                    &v1,
+                    p2_image,
+                    &v2,
+                    &i2,
+                    parameters,
+                    repulsive_only,
+                )?;
+                let force = force.xa(one_half);
+                aux_storage.incr_current_pressure(cell.normal_force(&p1, p2_image, &force)?);
+                aux_storage.add_force(force);
+
+                // Half of the force as calculated by the ghost
+                let (_, force) = calculate_pair_forces::<_, _, _, _, Float, _>(
+                    &ghost.cell,
+                    &p2,
+                    &v2,
+                    p1_image,
+                    &v1,
+                    &i1,
+                    parameters,
+                    repulsive_only,
+                )?;
+                let force = force.xa(one_half);
+                aux_storage.incr_current_pressure(cell.normal_force(&p1, p2_image, &force)?);
+                aux_storage.add_force(force);
+
+                if cell.reacts_to_neighbors() && cell.is_neighbor(&p1, p2_image, &i2)? {
+                    aux_storage.incr_current_neighbors(1);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<I, S, C, A, Com, Sy, R> SubDomainBox<I, S, C, A, Com, Sy, R>
+where
+    S: SubDomain,
+{
+    /// Activates the [HaloExchange] mode.
+    ///
+    /// While active, [SubDomainBox::update_mechanics_interaction_step_1] does not send any
+    /// [PosInformation](super::PosInformation).
+    /// Instead [SubDomainBox::send_halo_cells] and [SubDomainBox::receive_halo_cells] need to
+    /// be called in the first and second step respectively.
+    pub fn set_halo_exchange(&mut self, halo_exchange: Option<HaloExchange>) {
+        self.halo_exchange = halo_exchange;
+    }
+
+    /// Sends copies of all cells in voxels at the boundary to the neighboring subdomains.
+    ///
+    /// Does nothing unless the [HaloExchange] mode was activated via
+    /// [SubDomainBox::set_halo_exchange].
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub fn send_halo_cells(&mut self) -> Result<(), SimulationError>
+    where
+        C: Clone,
+        Com: Communicator<SubDomainPlainIndex, HaloCells<CellBox<C>>>,
+    {
+        let halo_exchange = match &self.halo_exchange {
+            Some(halo_exchange) => halo_exchange.clone(),
+            None => return Ok(()),
+        };
+        let mut halo_messages = 0;
+        let mut ghost_cells = 0;
+        let mut avoided_position_messages = 0;
+        for (voxel_index, vox) in self.voxels.iter() {
+            if vox.cells.is_empty() {
+                continue;
+            }
+            let external_neighbors: Vec<_> = vox
+                .neighbors
+                .iter()
+                .filter(|neighbor_index| !self.voxels.contains_key(neighbor_index))
+                .collect();
+            let subdomains: BTreeSet<_> = external_neighbors
+                .iter()
+                .map(|neighbor_index| self.plain_index_to_subdomain[neighbor_index])
+                .collect();
+            for subdomain in subdomains {
+                self.communicator.send(
+                    &subdomain,
+                    HaloCells {
+                        index_sender: *voxel_index,
+                        cells: vox.cells.iter().map(|(cell, _)| cell.clone()).collect(),
+                    },
+                )?;
+                halo_messages += 1;
+                ghost_cells += vox.cells.len();
+            }
+            avoided_position_messages += vox.cells.len() * external_neighbors.len();
+        }
+        halo_exchange.add(|statistics| {
+            statistics.halo_messages += halo_messages;
+            statistics.ghost_cells += ghost_cells;
+            statistics.avoided_position_messages += avoided_position_messages;
+        })
+    }
+
+    /// Receives the ghost cells of neighboring subdomains and calculates the forces acting on
+    /// the cells of this subdomain.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub fn receive_halo_cells<Pos, Vel, For, Float, Inf, const N: usize>(
+        &mut self,
+        determinism: bool,
+    ) -> Result<(), SimulationError>
+    where
+        C: Position<Pos>,
+        C: Velocity<Vel>,
+        C: Interaction<Pos, Vel, For, Inf>,
+        A: UpdateMechanics<Pos, Vel, For, N>,
+        A: UpdateInteraction,
+        For: Xapy<Float>,
+        Float: num::Float,
+        S: SubDomainMechanics<Pos, Vel>,
+        Com: Communicator<SubDomainPlainIndex, HaloCells<CellBox<C>>>,
+    {
+        let halo_exchange = match &self.halo_exchange {
+            Some(halo_exchange) => halo_exchange.clone(),
+            None => return Ok(()),
+        };
+        let metric = self.subdomain.domain_metric();
+        let mut received_halos = <Com as Communicator<
+            SubDomainPlainIndex,
+            HaloCells<CellBox<C>>,
+        >>::receive(&mut self.communicator);
+        if determinism {
+            received_halos.sort_by_key(|halo| halo.index_sender);
+        }
+        let mut avoided_force_messages = 0;
+        let mut ghost_pairs = 0;
+        for halo in received_halos.iter() {
+            for vox in self
+                .voxels
+                .values_mut()
+                .filter(|vox| vox.neighbors.contains(&halo.index_sender))
+            {
+                if vox.cells.is_empty() {
+                    continue;
+                }
+                vox.calculate_force_from_ghosts(&halo.cells, metric, &self.global_parameters)?;
+                avoided_force_messages += halo.cells.len();
+                ghost_pairs += halo.cells.len() * vox.cells.len();
+            }
+        }
+        halo_exchange.add(|statistics| {
+            statistics.avoided_force_messages += avoided_force_messages;
+            statistics.ghost_pairs += ghost_pairs;
+        })
+    }
+}
+
+#[cfg(test)]
+mod test_halo {
+    use super::*;
+    use crate::backend::chili::test_fixtures::*;
+
+    #[test]
+    fn ghosts_agree_with_force_information() {
+        let parameters = GlobalParameters::new();
+        let own_cells = [(0.2, 1.0), (0.7, 3.0), (0.9, 0.5)];
+        let other_cells = [(1.1, 2.0), (1.6, 0.1)];
+
+        // Exchange positions and forces in both directions
+        let mut own = voxel(0, &[], &own_cells);
+        let mut other = voxel(1, &[], &other_cells);
+        for (cell, aux_storage) in own.cells.iter_mut() {
+            let (force, pressure) = other
+                .calculate_force_between_cells_external::<_, _, _, _, f64, 1>(
+                    &cell.pos(),
+                    &cell.velocity(),
+                    &(),
+                    None,
+                    &parameters,
+                )
+                .unwrap()
+                .unwrap();
+            aux_storage.add_force(force);
+            aux_storage.incr_current_pressure(pressure);
+        }
+        for (cell, _) in other.cells.iter() {
+            own.calculate_force_between_cells_external::<_, _, _, _, f64, 1>(
+                &cell.pos(),
+                &cell.velocity(),
+                &(),
+                None,
+                &parameters,
+            )
+            .unwrap();
+        }
+
+        // Mirror the other cells as ghosts
+        let mut halo = voxel(0, &[], &own_cells);
+        let ghosts: Vec<_> = other.cells.drain(..).map(|(cell, _)| cell).collect();
+        halo.calculate_force_from_ghosts::<_, _, _, f64, _, 1>(&ghosts, None, &parameters)
+            .unwrap();
+
+        for ((_, aux1), (_, aux2)) in own.cells.iter_mut().zip(halo.cells.iter_mut()) {
+            let force1 = aux1.get_current_force_and_reset();
+            let force2 = aux2.get_current_force_and_reset();
+            assert!((force1 - force2).abs() < 1e-12);
+            assert!((aux1.get_current_pressure() - aux2.get_current_pressure()).abs() < 1e-12);
+            assert_eq!(aux1.get_current_neighbors(), aux2.get_current_neighbors());
+        }
+    }
+}
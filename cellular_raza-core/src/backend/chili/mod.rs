@@ -173,6 +173,7 @@
 //!     - [build_communicator] Type which handles communication between
 //!       threads
 //! - [test_compatibility] Test compatibility of all types involved
+//! - [check_aspects] Check that agents and prepared types match the given aspects
 //! - [run_main] Defines main loop and performs the simulation
 //!
 //! These macros take a subset of keyword arguments of the [run_simulation] macro.
@@ -210,6 +211,11 @@
     | [update_mechanics_interaction_step_1](SubDomainBox::update_mechanics_interaction_step_1)\
     | Send [PosInformation](PosInformation) between threads to get back \
       [ForceInformation](ForceInformation) |"]
+#![doc = "\
+    | `Mechanics && Interaction`\
+    | [send_halo_cells](SubDomainBox::send_halo_cells)\
+    | Sends [HaloCells](HaloCells) instead when the [HaloExchange](HaloExchange) mode is \
+      active. |"]
 #![doc = "\
     | `DomainForce`\
     | [calculate_custom_domain_force](SubDomainBox::calculate_custom_domain_force)\
@@ -235,6 +241,10 @@
     | [update_mechanics_interaction_step_2](SubDomainBox::update_mechanics_interaction_step_2) \
     | Calculate forces and return [ForceInformation](ForceInformation) to the original \
       sender. |"]
+#![doc = "\
+    | `Mechanics && Interaction` \
+    | [receive_halo_cells](SubDomainBox::receive_halo_cells) \
+    | Calculates forces from received [HaloCells](HaloCells) without returning them. |"]
 #![doc = "\
     | `ReactionsContact` \
     | [update_contact_reactions_step_2](SubDomainBox::update_contact_reactions_step_2) \
@@ -267,7 +277,7 @@
 //! | --- | --- | --- |
 #![doc = "\
     | `Mechanics` \
-    | [local_mechanics_update](local_mechanics_update) \
+    | [local_mechanics_update_with_parameters](local_mechanics_update_with_parameters) \
     | Performs numerical integration of the position and velocity. |"]
 #![doc = "\
     | `Interaction` \
@@ -275,12 +285,20 @@
     | Performs changes due to neighbor counting. |"]
 #![doc = "\
     | `Cycle` \
-    | [local_cycle_update](local_cycle_update) \
+    | [local_cycle_update_with_parameters](local_cycle_update_with_parameters) \
     | Advances the cycle of the cell. This may introduce a\
       [CycleEvent](cellular_raza_concepts::CycleEvent) |"]
+#![doc = "\
+    | `Age` \
+    | [local_age_update](local_age_update) \
+    | Advances the age of the cell and checks for senescence. |"]
+#![doc = "\
+    | `RotationalMechanics` \
+    | [local_rotational_mechanics_update](local_rotational_mechanics_update) \
+    | Performs numerical integration of the orientation and angular velocity. |"]
 #![doc = "\
     | `Reactions` \
-    | [local_reactions_intracellular](local_reactions_intracellular) \
+    | [local_reactions_intracellular_with_parameters](local_reactions_intracellular_with_parameters) \
     | Calculates increment from purely intracellular reactions. |"]
 #![doc = "\
     | `ReactionsContact` \
@@ -298,14 +316,18 @@
 //! #### Step 4 - Treat Cell Positions
 //! | Aspects | Function | Purpose |
 //! | --- | --- | --- |
-#![doc = "\
-    | `Mechanics` \
-    | [apply_boundary](SubDomainBox::apply_boundary) \
-    | Apply a boundary condition. |"]
 #![doc = "\
     | `Cycle` \
     | [update_cell_cycle_4](SubDomainBox::update_cell_cycle_4) \
     | Performs cell-division and other cycle events. |"]
+#![doc = "\
+    | `Cycle` \
+    | [save_divisions](SubDomainBox::save_divisions) \
+    | Stores every division together with the positions of the daughter cells. |"]
+#![doc = "\
+    | `Mechanics` \
+    | [apply_boundary](SubDomainBox::apply_boundary) \
+    | Apply a boundary condition. This also validates the positions of newly divided cells. |"]
 #![doc = "\
     | `Mechanics` \
     | [sort_cells_in_voxels_step_1](SubDomainBox::sort_cells_in_voxels_step_1) \
@@ -322,6 +344,8 @@
 //! # Return Type
 //! After the simulation is done, we return a [StorageAccess] struct to interoperate with stored
 //! results.
+//! Simulations with the `Cycle` aspect additionally record every [DivisionEvent] which can be
+//! loaded with [StorageAccess::divisions].
 
 use serde::{Deserialize, Serialize};
 
@@ -416,29 +440,120 @@ impl CellIdentifier {
     }
 }
 
+/// Pins the threads of subdomains to cores of the machine.
+#[cfg(feature = "affinity")]
+#[cfg_attr(docsrs, doc(cfg(feature = "affinity")))]
+mod affinity;
+/// Verifies invariants of the simulation state.
+mod audit;
 /// Contains structs to store aspects of the simulation and macros to construct them.
 mod aux_storage;
+/// Recovers cells from errors at the boundary of the domain.
+mod boundary;
+/// Limits forces and displacements of cells.
+mod clamping;
+/// Compares the results of two simulations.
+mod comparison;
 #[doc(hidden)]
 pub mod compatibility_tests;
+/// Traits for aspects which are defined by downstream crates.
+mod custom_aspects;
+/// Subdomains, voxels and the construction of the simulation runner.
 mod datastructures;
+/// Monitors the overlap of cells.
+mod diagnostics;
+/// Records the energy and momentum of the system.
+mod energy;
+/// Mechanics-only steps before the recorded simulation.
+mod equilibration;
+/// Errors which can occur during the simulation.
 mod errors;
+/// Dumps cells with non-finite forces or positions.
+mod forensics;
+/// Mirrors boundary cells of neighboring subdomains as ghosts.
+mod halo;
+/// Records the number of cells per voxel.
+mod occupancy;
+/// Parallel updates of the voxels of one subdomain.
+mod parallel;
+/// Re-exports and documentation of the procedural macros.
 mod proc_macro;
+/// Counts messages and bytes which are exchanged between subdomains.
+mod profiling;
+/// Subdivides overcrowded voxels.
+mod refinement;
+/// Restricts stored results to a region of interest.
+mod region;
+/// Replays stored results of a simulation.
+mod replay;
+/// Streams cells and fields to the rerun viewer.
+#[cfg(feature = "rerun")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rerun")))]
+mod rerun_logger;
+/// Access to the results of a simulation.
 mod result;
+/// Seeding of the random number generators of cells.
+mod rng;
+/// Settings of the simulation.
 mod setup;
+/// Communication and synchronization between threads.
 mod simulation_flow;
+/// Hands the state of the simulation to a callback at save points.
+mod snapshot;
+/// Numerical solvers of the mechanics.
 mod solvers;
+/// Terminates the simulation once it has converged.
+mod steady_state;
+/// Terminates the simulation early once user-defined criteria are met.
+mod stopping;
+/// Strategies to synchronize subdomains.
+mod syncers;
+/// Agents and subdomains which are shared between tests.
+#[cfg(test)]
+mod test_fixtures;
+/// Updates of the cycle and age of cells.
 mod update_cycle;
+/// Updates of the mechanics and interactions of cells.
 mod update_mechanics;
+/// Updates of the reactions of cells.
 mod update_reactions;
+/// Updates of the rotational mechanics of cells.
+mod update_rotation;
 
+#[cfg(feature = "affinity")]
+pub use affinity::*;
+pub use audit::*;
 pub use aux_storage::*;
+pub use boundary::*;
+pub use clamping::*;
+pub use comparison::*;
+pub use custom_aspects::*;
 pub use datastructures::*;
+pub use diagnostics::*;
+pub use energy::*;
+pub use equilibration::*;
 pub use errors::*;
+pub use forensics::*;
+pub use halo::*;
+pub use occupancy::*;
+pub use parallel::*;
 pub use proc_macro::*;
+pub use profiling::*;
+pub use refinement::*;
+pub use region::*;
+pub use replay::*;
+#[cfg(feature = "rerun")]
+pub use rerun_logger::*;
 pub use result::*;
+pub use rng::*;
 pub use setup::*;
 pub use simulation_flow::*;
+pub use snapshot::*;
 pub use solvers::*;
+pub use steady_state::*;
+pub use stopping::*;
+pub use syncers::*;
 pub use update_cycle::*;
 pub use update_mechanics::*;
 pub use update_reactions::*;
+pub use update_rotation::*;
@@ -0,0 +1,341 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use cellular_raza_concepts::SubDomain;
+use serde::{Deserialize, Serialize};
+
+use super::{SimulationError, SubDomainBox, SubDomainPlainIndex, VoxelPlainIndex};
+use crate::storage::{StorageError, StorageInterfaceStore};
+use crate::time::NextTimePoint;
+
+/// Distribution of cells over all voxels at one save point.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct OccupancyRecord<F> {
+    /// Iteration of the save point
+    pub iteration: usize,
+    /// Time of the save point
+    pub time: F,
+    /// Number of voxels containing `n` cells is given by the `n`-th entry
+    pub histogram: Vec<usize>,
+    /// Number of pairs of cells within the same voxel.
+    /// The cost of calculating interactions inside voxels grows with this number.
+    pub in_voxel_pairs: usize,
+}
+
+impl<F> OccupancyRecord<F> {
+    /// Total number of voxels
+    pub fn n_voxels(&self) -> usize {
+        self.histogram.iter().sum()
+    }
+
+    /// Total number of cells
+    pub fn n_cells(&self) -> usize {
+        self.histogram
+            .iter()
+            .enumerate()
+            .map(|(n, count)| n * count)
+            .sum()
+    }
+
+    /// Largest number of cells in any voxel
+    pub fn max_cells(&self) -> usize {
+        self.histogram
+            .iter()
+            .rposition(|count| *count > 0)
+            .unwrap_or(0)
+    }
+
+    /// Average number of cells per voxel
+    pub fn mean_cells(&self) -> f64 {
+        match self.n_voxels() {
+            0 => 0.0,
+            n_voxels => self.n_cells() as f64 / n_voxels as f64,
+        }
+    }
+
+    /// Factor by which the volume of voxels should be multiplied in order to obtain the given
+    /// average number of cells per voxel.
+    ///
+    /// The side length of voxels in `D` dimensions needs to be scaled by the `D`-th root of
+    /// this factor.
+    /// Values smaller than one indicate that voxels are too large and the in-voxel force
+    /// calculation dominates the runtime.
+    /// Values much larger than one indicate that the overhead of iterating over many empty or
+    /// sparsely populated voxels dominates.
+    /// Returns [None] if no cells are present.
+    /// ```
+    /// # use cellular_raza_core::backend::chili::OccupancyRecord;
+    /// let record = OccupancyRecord {
+    ///     iteration: 0,
+    ///     time: 0.0,
+    ///     // 2 empty voxels, 1 voxel with 2 cells and 1 voxel with 6 cells
+    ///     histogram: vec![2, 0, 1, 0, 0, 0, 1],
+    ///     in_voxel_pairs: 16,
+    /// };
+    /// assert_eq!(record.mean_cells(), 2.0);
+    /// assert_eq!(record.max_cells(), 6);
+    /// assert_eq!(record.suggested_volume_factor(4.0), Some(2.0));
+    /// ```
+    pub fn suggested_volume_factor(&self, target_cells_per_voxel: f64) -> Option<f64> {
+        match self.n_cells() {
+            0 => None,
+            _ => Some(target_cells_per_voxel / self.mean_cells()),
+        }
+    }
+
+    /// Merges the contribution of another subdomain into this record.
+    fn merge(&mut self, histogram: &[usize], in_voxel_pairs: usize) {
+        if self.histogram.len() < histogram.len() {
+            self.histogram.resize(histogram.len(), 0);
+        }
+        for (total, count) in self.histogram.iter_mut().zip(histogram) {
+            *total += count;
+        }
+        self.in_voxel_pairs += in_voxel_pairs;
+    }
+}
+
+/// Emitted when a single voxel contains many more cells than the average voxel.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct HotSpotWarning<F> {
+    /// Iteration of the save point
+    pub iteration: usize,
+    /// Time of the save point
+    pub time: F,
+    /// Subdomain which contains the voxel
+    pub subdomain: SubDomainPlainIndex,
+    /// The overcrowded voxel
+    pub voxel: VoxelPlainIndex,
+    /// Number of cells in the voxel
+    pub n_cells: usize,
+    /// Average number of cells per voxel in the whole simulation
+    pub mean_cells: f64,
+}
+
+/// Records how cells are distributed over voxels and reports overcrowded voxels.
+///
+/// Interactions between cells within one voxel are calculated for every pair of cells.
+/// Thus the runtime of a voxel grows quadratically with the number of its cells and a few
+/// overcrowded voxels can dominate the runtime of the whole simulation.
+/// At every save point, the histogram of the number of cells per voxel is recorded as an
+/// [OccupancyRecord].
+/// Afterwards, every voxel which contains more than [VoxelOccupancy::hot_spot_factor] times
+/// the average number of cells and at least [VoxelOccupancy::min_cells] cells emits a
+/// [HotSpotWarning].
+/// With the `tracing` feature, warnings are additionally emitted as events.
+/// The records can be used to choose voxel sizes via
+/// [OccupancyRecord::suggested_volume_factor].
+///
+/// The occupancy is passed to the
+/// [run_simulation](crate::backend::chili::run_simulation) macro via the `voxel_occupancy`
+/// argument.
+/// All clones share their records and warnings.
+/// ```
+/// # use cellular_raza_core::backend::chili::VoxelOccupancy;
+/// let occupancy = VoxelOccupancy::<f64>::new().hot_spot_factor(5.0).min_cells(20);
+/// // Pass `voxel_occupancy: occupancy` to the run_simulation macro
+/// assert!(occupancy.records().is_empty());
+/// assert!(occupancy.warnings().is_empty());
+/// ```
+#[derive(Clone, Debug)]
+pub struct VoxelOccupancy<F> {
+    /// Voxels with more than this multiple of the average number of cells are reported.
+    pub hot_spot_factor: f64,
+    /// Voxels with fewer cells are never reported.
+    pub min_cells: usize,
+    /// Records of all save points ordered by their iteration
+    records: Arc<Mutex<BTreeMap<usize, OccupancyRecord<F>>>>,
+    /// Warnings emitted by all threads
+    warnings: Arc<Mutex<Vec<HotSpotWarning<F>>>>,
+}
+
+impl<F> Default for VoxelOccupancy<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F> VoxelOccupancy<F> {
+    /// Reports voxels with more than 10 times the average number of cells and at least 8 cells.
+    pub fn new() -> Self {
+        Self {
+            hot_spot_factor: 10.0,
+            min_cells: 8,
+            records: Arc::new(Mutex::new(BTreeMap::new())),
+            warnings: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Sets the multiple of the average number of cells above which voxels are reported.
+    pub fn hot_spot_factor(self, hot_spot_factor: f64) -> Self {
+        Self {
+            hot_spot_factor,
+            ..self
+        }
+    }
+
+    /// Sets the number of cells below which voxels are never reported.
+    pub fn min_cells(self, min_cells: usize) -> Self {
+        Self { min_cells, ..self }
+    }
+
+    /// All records ordered by their iteration
+    pub fn records(&self) -> Vec<OccupancyRecord<F>>
+    where
+        F: Clone,
+    {
+        self.records
+            .lock()
+            .map(|records| records.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// All warnings which have been emitted so far
+    pub fn warnings(&self) -> Vec<HotSpotWarning<F>>
+    where
+        F: Clone,
+    {
+        self.warnings
+            .lock()
+            .map(|warnings| warnings.clone())
+            .unwrap_or_default()
+    }
+
+    /// Stores all records in the given storage.
+    pub fn store<S>(&self, storage: &mut S) -> Result<(), StorageError>
+    where
+        S: StorageInterfaceStore<usize, OccupancyRecord<F>>,
+        F: Clone + Serialize,
+    {
+        for record in self.records() {
+            storage.store_single_element(record.iteration as u64, &0, &record)?;
+        }
+        Ok(())
+    }
+
+    /// Adds the histogram of the subdomain to the record of the current iteration if it is a
+    /// save point.
+    pub fn record<I, S, C, A, Com, Sy, R>(
+        &self,
+        sbox: &SubDomainBox<I, S, C, A, Com, Sy, R>,
+        next_time_point: &NextTimePoint<F>,
+    ) -> Result<(), SimulationError>
+    where
+        S: SubDomain,
+        F: Clone,
+    {
+        if next_time_point.event.is_none() {
+            return Ok(());
+        }
+        let mut histogram = Vec::new();
+        let mut in_voxel_pairs = 0;
+        for voxel in sbox.voxels.values() {
+            let n_cells = voxel.cells.len();
+            if histogram.len() <= n_cells {
+                histogram.resize(n_cells + 1, 0);
+            }
+            histogram[n_cells] += 1;
+            in_voxel_pairs += n_cells * n_cells.saturating_sub(1) / 2;
+        }
+        self.records
+            .lock()
+            .map_err(|e| SimulationError::OtherThreadError(e.to_string()))?
+            .entry(next_time_point.iteration)
+            .or_insert_with(|| OccupancyRecord {
+                iteration: next_time_point.iteration,
+                time: next_time_point.time.clone(),
+                histogram: Vec::new(),
+                in_voxel_pairs: 0,
+            })
+            .merge(&histogram, in_voxel_pairs);
+        Ok(())
+    }
+
+    /// Compares the voxels of the subdomain against the average of the whole simulation.
+    ///
+    /// All threads need to be synchronized after calling [VoxelOccupancy::record] and before
+    /// calling this function.
+    pub fn check_hot_spots<I, S, C, A, Com, Sy, R>(
+        &self,
+        sbox: &SubDomainBox<I, S, C, A, Com, Sy, R>,
+        next_time_point: &NextTimePoint<F>,
+    ) -> Result<Vec<HotSpotWarning<F>>, SimulationError>
+    where
+        S: SubDomain,
+        F: Clone,
+    {
+        let mean_cells = match self
+            .records
+            .lock()
+            .map_err(|e| SimulationError::OtherThreadError(e.to_string()))?
+            .get(&next_time_point.iteration)
+        {
+            Some(record) => record.mean_cells(),
+            None => return Ok(Vec::new()),
+        };
+        let threshold = self.hot_spot_factor * mean_cells;
+        let warnings: Vec<_> = sbox
+            .voxels
+            .iter()
+            .filter(|(_, voxel)| {
+                let n_cells = voxel.cells.len();
+                n_cells >= self.min_cells && n_cells as f64 > threshold
+            })
+            .map(|(plain_index, voxel)| HotSpotWarning {
+                iteration: next_time_point.iteration,
+                time: next_time_point.time.clone(),
+                subdomain: sbox.subdomain_plain_index,
+                voxel: *plain_index,
+                n_cells: voxel.cells.len(),
+                mean_cells,
+            })
+            .collect();
+        #[cfg(feature = "tracing")]
+        for warning in warnings.iter() {
+            tracing::warn!(
+                "Voxel {:?} contains {} cells while the average is {:.2}",
+                warning.voxel,
+                warning.n_cells,
+                warning.mean_cells
+            );
+        }
+        self.warnings
+            .lock()
+            .map_err(|e| SimulationError::OtherThreadError(e.to_string()))?
+            .extend(warnings.iter().cloned());
+        Ok(warnings)
+    }
+}
+
+#[cfg(test)]
+mod test_occupancy {
+    use super::*;
+
+    #[test]
+    fn merge_histograms() {
+        let mut record = OccupancyRecord {
+            iteration: 3,
+            time: 0.3,
+            histogram: vec![],
+            in_voxel_pairs: 0,
+        };
+        record.merge(&[1, 2], 0);
+        record.merge(&[0, 1, 0, 1], 3);
+        assert_eq!(record.histogram, vec![1, 3, 0, 1]);
+        assert_eq!(record.n_voxels(), 5);
+        assert_eq!(record.n_cells(), 6);
+        assert_eq!(record.max_cells(), 3);
+        assert_eq!(record.in_voxel_pairs, 3);
+        assert_eq!(record.mean_cells(), 1.2);
+        assert_eq!(record.suggested_volume_factor(2.4), Some(2.0));
+
+        let empty = OccupancyRecord {
+            iteration: 0,
+            time: 0.0,
+            histogram: vec![4],
+            in_voxel_pairs: 0,
+        };
+        assert_eq!(empty.max_cells(), 0);
+        assert_eq!(empty.suggested_volume_factor(1.0), None);
+    }
+}
@@ -0,0 +1,683 @@
+use std::collections::BTreeMap;
+
+use cellular_raza_concepts::*;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    calculate_pair_forces, Communicator, ForceInformation, PosInformation,
+    ReactionsContactInformation, ReactionsContactReturn, SimulationError, SubDomainBox,
+    SubDomainPlainIndex, UpdateInteraction, UpdateMechanics, UpdateReactions,
+    UpdateReactionsContact, Voxel, VoxelPlainIndex,
+};
+
+/// Updates the voxels of a single subdomain in parallel.
+///
+/// By default, the voxels of every subdomain are updated serially by the thread which owns the
+/// subdomain.
+/// Simulations with few subdomains but many voxels can additionally distribute the calculation
+/// of forces and contact reactions over the [rayon] thread pool.
+/// Idle threads steal voxels from busy ones such that voxels with many cells do not stall the
+/// whole subdomain.
+///
+/// Every voxel is only modified by the task which owns it.
+/// Cells in neighboring voxels are only read and both halves of every interaction between
+/// voxels are calculated by the task of the receiving cell.
+/// This doubles the number of force calculations between neighboring voxels compared to the
+/// serial update and should thus only be used if enough cores are available.
+/// Since forces are summed in a different order, results agree with the serial update only up
+/// to floating-point rounding.
+/// When combined with a [VoxelRefinement](super::VoxelRefinement), interactions within voxels
+/// are calculated serially.
+///
+/// The parallelism is passed to the [run_simulation](crate::backend::chili::run_simulation)
+/// macro via the `voxel_parallelism` argument.
+/// The number of threads is controlled by the global [rayon] thread pool, for example via the
+/// `RAYON_NUM_THREADS` environment variable.
+/// ```
+/// # use cellular_raza_core::backend::chili::VoxelParallelism;
+/// let parallelism = VoxelParallelism::new().min_voxels(64).voxels_per_task(4);
+/// // Pass `voxel_parallelism: parallelism` to the run_simulation macro
+/// assert_eq!(parallelism.min_voxels, 64);
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct VoxelParallelism {
+    /// Subdomains with fewer voxels are updated serially
+    pub min_voxels: usize,
+    /// Smallest number of voxels which are updated by a single task
+    pub voxels_per_task: usize,
+}
+
+impl Default for VoxelParallelism {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VoxelParallelism {
+    /// Updates subdomains with at least 16 voxels in parallel.
+    pub fn new() -> Self {
+        Self {
+            min_voxels: 16,
+            voxels_per_task: 1,
+        }
+    }
+
+    /// Sets [VoxelParallelism::min_voxels]
+    pub fn min_voxels(self, min_voxels: usize) -> Self {
+        Self { min_voxels, ..self }
+    }
+
+    /// Sets [VoxelParallelism::voxels_per_task]
+    pub fn voxels_per_task(self, voxels_per_task: usize) -> Self {
+        Self {
+            voxels_per_task: voxels_per_task.max(1),
+            ..self
+        }
+    }
+
+    /// Decides if a subdomain with the given number of voxels is updated in parallel.
+    fn is_active(&self, n_voxels: usize) -> bool {
+        n_voxels >= self.min_voxels
+    }
+}
+
+/// Calculates the force which `other` exerts on `cell` together with the normal component.
+///
+/// Both halves of the interaction are calculated such that the result agrees with
+/// [Voxel::calculate_force_between_cells_external] evaluated for both cells.
+fn force_from_cell<C, Pos, Vel, For, Float, Inf>(
+    cell: &C,
+    other: &C,
+    metric: Option<&dyn DomainMetric<Pos>>,
+    parameters: &GlobalParameters,
+    repulsive_only: bool,
+) -> Result<(For, f64, bool), CalcError>
+where
+    C: Position<Pos>,
+    C: Velocity<Vel>,
+    C: Interaction<Pos, Vel, For, Inf>,
+    For: Xapy<Float>,
+    Float: num::Float,
+{
+    let one_half = Float::one() / (Float::one() + Float::one());
+    let p1 = cell.pos();
+    let v1 = cell.velocity();
+    let i1 = cell.get_interaction_information();
+    let p2 = other.pos();
+    let v2 = other.velocity();
+    let i2 = other.get_interaction_information();
+
+    // Use the images of the cells which are closest to each other
+    let p1_image = metric.map(|metric| metric.nearest_image(&p2, &p1));
+    let p1_image = p1_image.as_ref().unwrap_or(&p1);
+    let p2_image = metric.map(|metric| metric.nearest_image(&p1, &p2));
+    let p2_image = p2_image.as_ref().unwrap_or(&p2);
+
+    let (force1, _) = calculate_pair_forces::<_, _, _, _, Float, _>(
+        cell,
+        &p1,
+        &v1,
+        p2_image,
+        &v2,
+        &i2,
+        parameters,
+        repulsive_only,
+    )?;
+    let force1 = force1.xa(one_half);
+    let pressure1 = cell.normal_force(&p1, p2_image, &force1)?;
+    let (_, force2) = calculate_pair_forces::<_, _, _, _, Float, _>(
+        other,
+        &p2,
+        &v2,
+        p1_image,
+        &v1,
+        &i1,
+        parameters,
+        repulsive_only,
+    )?;
+    let force2 = force2.xa(one_half);
+    // Both halves act on the current cell which thus calculates their normal components
+    let pressure2 = cell.normal_force(&p1, p2_image, &force2)?;
+    let is_neighbor = cell.reacts_to_neighbors() && cell.is_neighbor(&p1, p2_image, &i2)?;
+    Ok((
+        force2.xapy(Float::one(), &force1),
+        pressure1 + pressure2,
+        is_neighbor,
+    ))
+}
+
+/// Calculates the increment of the intracellular values of `cell` due to its contact with
+/// `other`.
+///
+/// Agrees with [Voxel::calculate_contact_reactions_between_cells_external] evaluated for both
+/// cells.
+fn contact_increment_from_cell<C, Ri, Pos, RInf, Float>(
+    cell: &C,
+    other: &C,
+) -> Result<Ri, CalcError>
+where
+    C: ReactionsContact<Ri, Pos, Float, RInf>,
+    C: Intracellular<Ri>,
+    C: Position<Pos>,
+    Ri: Xapy<Float>,
+    Float: num::Float,
+{
+    let one_half = Float::one() / (Float::one() + Float::one());
+    let p1 = cell.pos();
+    let intra1 = cell.get_intracellular();
+    let rinf1 = cell.get_contact_information();
+    let p2 = other.pos();
+    let intra2 = other.get_intracellular();
+    let rinf2 = other.get_contact_information();
+
+    let (dintra, _) = cell.calculate_contact_increment(&intra1, &intra2, &p1, &p2, &rinf2)?;
+    let (_, dextra) = other.calculate_contact_increment(&intra2, &intra1, &p2, &p1, &rinf1)?;
+    Ok(dintra.xapy(one_half, &dextra.xa(one_half)))
+}
+
+/// Voxels together with the messages which are addressed to them.
+type GroupedMessages<'a, 'b, C, A, R, T> = Vec<(&'a mut Voxel<C, A, R>, Vec<&'b T>)>;
+
+/// Combined force, pressure and number of neighbors of a cell.
+type NeighborForces<For> = (For, f64, usize);
+
+/// Groups received messages by the voxel to which they are addressed.
+fn group_by_voxel<'a, 'b, C, A, R, T>(
+    voxels: &'a mut BTreeMap<VoxelPlainIndex, Voxel<C, A, R>>,
+    messages: &'b [T],
+    index_receiver: impl Fn(&T) -> VoxelPlainIndex,
+) -> Result<GroupedMessages<'a, 'b, C, A, R, T>, IndexError> {
+    let mut grouped = BTreeMap::<_, Vec<_>>::new();
+    for message in messages.iter() {
+        grouped
+            .entry(index_receiver(message))
+            .or_default()
+            .push(message);
+    }
+    if let Some(index) = grouped.keys().find(|index| !voxels.contains_key(index)) {
+        return Err(IndexError(format!(
+            "EngineError: Voxel with index {:?} of received message can not be found in this \
+            thread.",
+            index
+        )));
+    }
+    Ok(voxels
+        .iter_mut()
+        .filter_map(|(index, vox)| grouped.remove(index).map(|messages| (vox, messages)))
+        .collect())
+}
+
+impl<C, A, R> Voxel<C, A, R> {
+    /// Calculates the forces which cells in neighboring voxels of the same subdomain exert on
+    /// the cells of this voxel without modifying any voxel.
+    ///
+    /// Returns the combined force, pressure and number of neighbors for every cell.
+    pub(crate) fn forces_from_neighboring_voxels<Pos, Vel, For, Float, Inf>(
+        &self,
+        voxels: &BTreeMap<VoxelPlainIndex, Voxel<C, A, R>>,
+        metric: Option<&dyn DomainMetric<Pos>>,
+        parameters: &GlobalParameters,
+    ) -> Result<Vec<Option<NeighborForces<For>>>, CalcError>
+    where
+        C: Position<Pos>,
+        C: Velocity<Vel>,
+        C: Interaction<Pos, Vel, For, Inf>,
+        For: Xapy<Float>,
+        Float: num::Float,
+    {
+        self.cells
+            .iter()
+            .map(|(cell, _)| {
+                let mut total: Option<NeighborForces<For>> = None;
+                for vox in self.neighbors.iter().filter_map(|index| voxels.get(index)) {
+                    for (other, _) in vox.cells.iter() {
+                        let (force, pressure, is_neighbor) = force_from_cell(
+                            &cell.cell,
+                            &other.cell,
+                            metric,
+                            parameters,
+                            self.repulsive_only,
+                        )?;
+                        total = Some(match total {
+                            Some((f, p, n)) => (
+                                force.xapy(Float::one(), &f),
+                                p + pressure,
+                                n + is_neighbor as usize,
+                            ),
+                            None => (force, pressure, is_neighbor as usize),
+                        });
+                    }
+                }
+                Ok(total)
+            })
+            .collect()
+    }
+
+    /// Calculates the increments of contact reactions due to cells in neighboring voxels of
+    /// the same subdomain without modifying any voxel.
+    pub(crate) fn contact_increments_from_neighboring_voxels<Ri, Pos, RInf, Float>(
+        &self,
+        voxels: &BTreeMap<VoxelPlainIndex, Voxel<C, A, R>>,
+    ) -> Result<Vec<Ri>, CalcError>
+    where
+        C: ReactionsContact<Ri, Pos, Float, RInf>,
+        C: Intracellular<Ri>,
+        C: Position<Pos>,
+        Ri: Xapy<Float>,
+        Float: num::Float,
+    {
+        self.cells
+            .iter()
+            .map(|(cell, _)| {
+                let mut incr = cell.get_intracellular().xa(Float::zero());
+                for vox in self.neighbors.iter().filter_map(|index| voxels.get(index)) {
+                    for (other, _) in vox.cells.iter() {
+                        incr = incr.xapy(
+                            Float::one(),
+                            &contact_increment_from_cell(&cell.cell, &other.cell)?,
+                        );
+                    }
+                }
+                Ok(incr)
+            })
+            .collect()
+    }
+}
+
+impl<I, S, C, A, Com, Sy, R> SubDomainBox<I, S, C, A, Com, Sy, R>
+where
+    S: SubDomain,
+{
+    /// Sets the [VoxelParallelism] which is used by the `_parallel` variants of the update
+    /// steps.
+    pub fn set_voxel_parallelism(&mut self, voxel_parallelism: Option<VoxelParallelism>) {
+        self.voxel_parallelism = voxel_parallelism;
+    }
+
+    /// Returns the [VoxelParallelism] if this subdomain is large enough to be updated in
+    /// parallel.
+    fn active_voxel_parallelism(&self) -> Option<VoxelParallelism> {
+        self.voxel_parallelism
+            .as_ref()
+            .filter(|parallelism| parallelism.is_active(self.voxels.len()))
+            .cloned()
+    }
+
+    /// Same as [SubDomainBox::update_mechanics_interaction_step_1] but distributes the voxels
+    /// over multiple threads as specified by the [VoxelParallelism].
+    ///
+    /// Without a parallelism set via [SubDomainBox::set_voxel_parallelism], both functions are
+    /// identical.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub fn update_mechanics_interaction_step_1_parallel<Pos, Vel, For, Float, Inf, const N: usize>(
+        &mut self,
+    ) -> Result<(), SimulationError>
+    where
+        Pos: Clone,
+        Vel: Clone,
+        Inf: Clone,
+        C: Position<Pos>,
+        C: Velocity<Vel>,
+        C: Mechanics<Pos, Vel, For, Float, R>,
+        C: Interaction<Pos, Vel, For, Inf>,
+        C: Send + Sync,
+        A: UpdateMechanics<Pos, Vel, For, N>,
+        A: UpdateInteraction,
+        A: Send + Sync,
+        R: Send + Sync,
+        For: Xapy<Float> + core::ops::AddAssign + Send,
+        Float: num::Float + core::ops::AddAssign,
+        <S as SubDomain>::VoxelIndex: Ord,
+        S: SubDomainMechanics<Pos, Vel> + Sync,
+        Com: Communicator<SubDomainPlainIndex, PosInformation<Pos, Vel, Inf>>,
+    {
+        let parallelism = match self.active_voxel_parallelism() {
+            Some(parallelism) => parallelism,
+            None => return self.update_mechanics_interaction_step_1(),
+        };
+        let subdomain = &self.subdomain;
+        let parameters = &self.global_parameters;
+
+        // Interactions within voxels only modify the voxel itself
+        self.voxels
+            .values_mut()
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .with_min_len(parallelism.voxels_per_task)
+            .try_for_each(|vox| {
+                vox.calculate_force_between_cells_internally::<Pos, Vel, For, Float, Inf, N>(
+                    subdomain.domain_metric(),
+                    parameters,
+                )
+            })?;
+
+        // Neighboring voxels are only read while calculating the forces
+        let voxels = &self.voxels;
+        let forces = voxels
+            .values()
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .with_min_len(parallelism.voxels_per_task)
+            .map(|vox| {
+                vox.forces_from_neighboring_voxels::<Pos, Vel, For, Float, Inf>(
+                    voxels,
+                    subdomain.domain_metric(),
+                    parameters,
+                )
+            })
+            .collect::<Result<Vec<_>, CalcError>>()?;
+        for (vox, forces) in self.voxels.values_mut().zip(forces) {
+            for ((_, aux_storage), force) in vox.cells.iter_mut().zip(forces) {
+                if let Some((force, pressure, neighbors)) = force {
+                    aux_storage.add_force(force);
+                    aux_storage.incr_current_pressure(pressure);
+                    aux_storage.incr_current_neighbors(neighbors);
+                }
+            }
+        }
+
+        // Cells are mirrored as ghosts by the halo exchange instead
+        if self.halo_exchange.is_some() {
+            return Ok(());
+        }
+        for (voxel_index, vox) in self.voxels.iter() {
+            for (cell_index_in_vector, (cell, _)) in vox.cells.iter().enumerate() {
+                for neighbor_index in vox.neighbors.iter() {
+                    if self.voxels.contains_key(neighbor_index) {
+                        continue;
+                    }
+                    self.communicator.send(
+                        &self.plain_index_to_subdomain[neighbor_index],
+                        PosInformation {
+                            index_sender: *voxel_index,
+                            index_receiver: *neighbor_index,
+                            pos: cell.pos(),
+                            vel: cell.velocity(),
+                            info: cell.get_interaction_information(),
+                            cell_index_in_vector,
+                        },
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Same as [SubDomainBox::update_mechanics_interaction_step_2] but answers the received
+    /// [PosInformation] of different voxels in parallel.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub fn update_mechanics_interaction_step_2_parallel<Pos, Vel, For, Float, Inf, const N: usize>(
+        &mut self,
+        determinism: bool,
+    ) -> Result<(), SimulationError>
+    where
+        For: Xapy<Float>,
+        A: UpdateMechanics<Pos, Vel, For, N>,
+        A: UpdateInteraction,
+        A: Send,
+        R: Send,
+        Float: num::Float,
+        Pos: Clone + Sync,
+        Vel: Clone + Sync,
+        Inf: Sync,
+        For: Clone + core::ops::AddAssign + Send,
+        C: Position<Pos>,
+        C: Velocity<Vel>,
+        C: Mechanics<Pos, Vel, For, Float, R>,
+        C: Interaction<Pos, Vel, For, Inf>,
+        C: Send,
+        S: SubDomainMechanics<Pos, Vel> + Sync,
+        Com: Communicator<SubDomainPlainIndex, PosInformation<Pos, Vel, Inf>>,
+        Com: Communicator<SubDomainPlainIndex, ForceInformation<For>>,
+    {
+        let parallelism = match self.active_voxel_parallelism() {
+            Some(parallelism) => parallelism,
+            None => return self.update_mechanics_interaction_step_2(determinism),
+        };
+        let mut received_infos = <Com as Communicator<
+            SubDomainPlainIndex,
+            PosInformation<Pos, Vel, Inf>,
+        >>::receive(&mut self.communicator);
+        if determinism {
+            received_infos.sort_by_key(|pos_info| pos_info.index_sender);
+        }
+        let subdomain = &self.subdomain;
+        let parameters = &self.global_parameters;
+        let answers = group_by_voxel(&mut self.voxels, &received_infos, |pos_info| {
+            pos_info.index_receiver
+        })?
+        .into_par_iter()
+        .with_min_len(parallelism.voxels_per_task)
+        .map(|(vox, pos_infos)| {
+            pos_infos
+                .into_iter()
+                .map(|pos_info| {
+                    Ok(vox
+                        .calculate_force_between_cells_external::<_, _, _, _, Float, N>(
+                            &pos_info.pos,
+                            &pos_info.vel,
+                            &pos_info.info,
+                            subdomain.domain_metric(),
+                            parameters,
+                        )?
+                        .map(|(force, pressure)| (pos_info, force, pressure)))
+                })
+                .collect::<Result<Vec<_>, CalcError>>()
+        })
+        .collect::<Result<Vec<_>, CalcError>>()?;
+        for (pos_info, force, pressure) in answers.into_iter().flatten().flatten() {
+            self.communicator.send(
+                &self.plain_index_to_subdomain[&pos_info.index_sender],
+                ForceInformation {
+                    force,
+                    pressure,
+                    cell_index_in_vector: pos_info.cell_index_in_vector,
+                    index_sender: pos_info.index_sender,
+                },
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Same as [SubDomainBox::update_contact_reactions_step_1] but distributes the voxels over
+    /// multiple threads as specified by the [VoxelParallelism].
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub fn update_contact_reactions_step_1_parallel<Ri, Pos, RInf, Float, const N: usize>(
+        &mut self,
+    ) -> Result<(), SimulationError>
+    where
+        Pos: Clone,
+        C: ReactionsContact<Ri, Pos, Float, RInf>,
+        C: Intracellular<Ri>,
+        C: Position<Pos>,
+        C: Send + Sync,
+        A: UpdateReactions<Ri>,
+        A: UpdateReactionsContact<Ri, N>,
+        A: Send + Sync,
+        R: Send + Sync,
+        Ri: Xapy<Float> + Clone + Send,
+        RInf: Clone,
+        Float: num::Float,
+        Com: Communicator<SubDomainPlainIndex, ReactionsContactInformation<Pos, Ri, RInf>>,
+    {
+        let parallelism = match self.active_voxel_parallelism() {
+            Some(parallelism) => parallelism,
+            None => return self.update_contact_reactions_step_1(),
+        };
+        self.voxels
+            .values_mut()
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .with_min_len(parallelism.voxels_per_task)
+            .try_for_each(|vox| {
+                vox.calculate_contact_reactions_between_cells_internally::<Ri, Pos, RInf, Float, N>(
+                )
+            })?;
+
+        let voxels = &self.voxels;
+        let increments = voxels
+            .values()
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .with_min_len(parallelism.voxels_per_task)
+            .map(|vox| vox.contact_increments_from_neighboring_voxels(voxels))
+            .collect::<Result<Vec<_>, CalcError>>()?;
+        for (vox, increments) in self.voxels.values_mut().zip(increments) {
+            for ((_, aux_storage), incr) in vox.cells.iter_mut().zip(increments) {
+                aux_storage.incr_conc(incr);
+            }
+        }
+
+        for (voxel_index, vox) in self.voxels.iter() {
+            for (cell_index_in_vector, (cell, _)) in vox.cells.iter().enumerate() {
+                for neighbor_index in vox.neighbors.iter() {
+                    if self.voxels.contains_key(neighbor_index) {
+                        continue;
+                    }
+                    self.communicator.send(
+                        &self.plain_index_to_subdomain[neighbor_index],
+                        ReactionsContactInformation {
+                            pos: cell.pos(),
+                            intracellular: cell.get_intracellular(),
+                            info: cell.get_contact_information(),
+                            cell_index_in_vector,
+                            index_sender: *voxel_index,
+                            index_receiver: *neighbor_index,
+                        },
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Same as [SubDomainBox::update_contact_reactions_step_2] but answers the received
+    /// [ReactionsContactInformation] of different voxels in parallel.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub fn update_contact_reactions_step_2_parallel<Ri, Pos, RInf, Float, const N: usize>(
+        &mut self,
+        determinism: bool,
+    ) -> Result<(), SimulationError>
+    where
+        C: ReactionsContact<Ri, Pos, Float, RInf> + Position<Pos>,
+        C: Intracellular<Ri>,
+        C: Send,
+        A: UpdateReactions<Ri> + UpdateReactionsContact<Ri, N>,
+        A: Send,
+        R: Send,
+        Ri: Xapy<Float> + Send + Sync,
+        RInf: Sync,
+        Float: num::Float,
+        Pos: Clone + Sync,
+        Com: Communicator<SubDomainPlainIndex, ReactionsContactInformation<Pos, Ri, RInf>>,
+        Com: Communicator<SubDomainPlainIndex, ReactionsContactReturn<Ri>>,
+    {
+        let parallelism = match self.active_voxel_parallelism() {
+            Some(parallelism) => parallelism,
+            None => return self.update_contact_reactions_step_2(determinism),
+        };
+        let mut received_infos = <Com as Communicator<
+            SubDomainPlainIndex,
+            ReactionsContactInformation<Pos, Ri, RInf>,
+        >>::receive(&mut self.communicator);
+        if determinism {
+            received_infos.sort_by_key(|info| info.index_sender);
+        }
+        let answers = group_by_voxel(&mut self.voxels, &received_infos, |contact_info| {
+            contact_info.index_receiver
+        })?
+        .into_par_iter()
+        .with_min_len(parallelism.voxels_per_task)
+        .map(|(vox, contact_infos)| {
+            contact_infos
+                .into_iter()
+                .map(|contact_info| {
+                    let incr = vox
+                        .calculate_contact_reactions_between_cells_external::<_, _, _, _, N>(
+                            &contact_info.pos,
+                            &contact_info.intracellular,
+                            &contact_info.info,
+                        )?;
+                    Ok((contact_info, incr))
+                })
+                .collect::<Result<Vec<_>, CalcError>>()
+        })
+        .collect::<Result<Vec<_>, CalcError>>()?;
+        for (contact_info, incr) in answers.into_iter().flatten() {
+            self.communicator.send(
+                &self.plain_index_to_subdomain[&contact_info.index_sender],
+                ReactionsContactReturn {
+                    intracellular: incr,
+                    cell_index_in_vector: contact_info.cell_index_in_vector,
+                    index_sender: contact_info.index_sender,
+                },
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_parallel {
+    use super::*;
+    use crate::backend::chili::test_fixtures::*;
+
+    #[test]
+    fn neighboring_voxels_agree_with_serial_update() {
+        let parameters = GlobalParameters::new();
+        let cells = [
+            vec![(0.2, 1.0), (0.7, 3.0), (0.9, 0.5)],
+            vec![(1.1, 2.0), (1.6, 0.1)],
+            vec![(2.4, 1.5)],
+        ];
+        let voxels: BTreeMap<_, _> = [
+            voxel(0, &[1], &cells[0]),
+            voxel(1, &[0, 2], &cells[1]),
+            voxel(2, &[1], &cells[2]),
+        ]
+        .into_iter()
+        .map(|vox| (vox.plain_index, vox))
+        .collect();
+
+        // Serial update which modifies the neighboring voxels
+        let mut serial = voxels.clone();
+        let keys: Vec<_> = serial.keys().cloned().collect();
+        for index in keys.iter() {
+            for n in 0..serial[index].cells.len() {
+                let cell = serial[index].cells[n].0.clone();
+                for neighbor in serial[index].neighbors.clone() {
+                    let result = serial
+                        .get_mut(&neighbor)
+                        .unwrap()
+                        .calculate_force_between_cells_external::<_, _, _, _, f64, 1>(
+                            &cell.pos(),
+                            &cell.velocity(),
+                            &(),
+                            None,
+                            &parameters,
+                        )
+                        .unwrap();
+                    if let Some((force, pressure)) = result {
+                        let aux_storage = &mut serial.get_mut(index).unwrap().cells[n].1;
+                        aux_storage.add_force(force);
+                        aux_storage.incr_current_pressure(pressure);
+                    }
+                }
+            }
+        }
+
+        for (index, vox) in voxels.iter() {
+            let forces = vox
+                .forces_from_neighboring_voxels::<_, _, _, f64, _>(&voxels, None, &parameters)
+                .unwrap();
+            for ((_, aux_storage), force) in serial[index].cells.iter().zip(forces) {
+                let (force, pressure, neighbors) = force.unwrap();
+                let mut aux_storage = aux_storage.clone();
+                assert!((aux_storage.get_current_force_and_reset() - force).abs() < 1e-12);
+                assert!((aux_storage.get_current_pressure() - pressure).abs() < 1e-12);
+                assert_eq!(aux_storage.get_current_neighbors(), neighbors);
+            }
+        }
+    }
+}
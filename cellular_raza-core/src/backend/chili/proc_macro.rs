@@ -4,6 +4,62 @@ pub use cellular_raza_core_proc_macro::run_test_for_aspects;
 /// Update traits in the [chili](crate::backend::chili) backend.
 ///
 /// This proc macros purpose is to support the [build_communicator!] macro.
+///
+/// # Scratch Fields
+/// Additional values can be carried by every cell with the `scratch_fields` keyword.
+/// Every field is given by its name, type and an expression for its default value.
+/// The values can be accessed with the [CustomAuxStorage](crate::backend::chili::CustomAuxStorage)
+/// trait and thus need to be of distinct types.
+///
+/// | Attribute | Effect |
+/// | --- | --- |
+/// | `#[reset(never)]` | The default value is only assigned when the cell is created (default) |
+/// | `#[reset(step)]` | The default value is assigned at the beginning of every step, see [ResetScratchFields](crate::backend::chili::ResetScratchFields) |
+/// | `#[skip_serializing]` | The field is not stored and set to its default value when loading results |
+///
+/// ```
+/// use cellular_raza_core::backend::chili::*;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+/// struct Bonds(Vec<(u64, f64)>);
+///
+/// #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+/// struct NeighborCache(Vec<usize>);
+///
+/// build_aux_storage!(
+///     aspects: [Cycle],
+///     core_path: cellular_raza_core,
+///     scratch_fields: [
+///         bonds: Bonds = Bonds(Vec::new()),
+///         #[reset(step)]
+///         #[skip_serializing]
+///         neighbors: NeighborCache = NeighborCache(Vec::with_capacity(8)),
+///     ],
+/// );
+///
+/// let mut aux_storage = (aux_storage_constructor!(
+///     aspects: [Cycle],
+///     core_path: cellular_raza_core,
+///     scratch_fields: [
+///         bonds: Bonds = Bonds(Vec::new()),
+///         #[reset(step)]
+///         #[skip_serializing]
+///         neighbors: NeighborCache = NeighborCache(Vec::with_capacity(8)),
+///     ],
+/// ))(());
+/// let bonds: &mut Bonds = aux_storage.custom_storage_mut();
+/// bonds.0.push((3, 1.5));
+/// let neighbors: &mut NeighborCache = aux_storage.custom_storage_mut();
+/// neighbors.0.push(3);
+///
+/// // Only the neighbors are reset
+/// aux_storage.reset_scratch_fields();
+/// let neighbors: &NeighborCache = aux_storage.custom_storage();
+/// assert!(neighbors.0.is_empty());
+/// let bonds: &Bonds = aux_storage.custom_storage();
+/// assert_eq!(bonds.0, vec![(3, 1.5)]);
+/// ```
 pub use cellular_raza_core_proc_macro::build_aux_storage;
 
 /// Derives the [FromMap](crate::backend::chili::FromMap) trait.
@@ -49,8 +105,9 @@ pub use cellular_raza_core_proc_macro::aux_storage_constructor;
 /// [UpdateCycle](crate::backend::chili::UpdateCycle),
 /// [UpdateMechanics](crate::backend::chili::UpdateMechanics),
 /// [UpdateInteraction](crate::backend::chili::UpdateInteraction),
-/// [UpdateReactions](crate::backend::chili::UpdateReactions) and
-/// [UpdateReactionsContact](crate::backend::chili::UpdateReactionsContact)
+/// [UpdateReactions](crate::backend::chili::UpdateReactions),
+/// [UpdateReactionsContact](crate::backend::chili::UpdateReactionsContact) and
+/// [CustomAuxStorage](crate::backend::chili::CustomAuxStorage)
 pub use cellular_raza_core_proc_macro::AuxStorage;
 
 /// Automatically build communicator struct depending on simulation aspects.
@@ -89,6 +146,73 @@ pub use cellular_raza_core_proc_macro::AuxStorage;
 /// let communicators = MyCommunicator::from_map(&new_map).unwrap();
 /// assert_eq!(communicators.len(), 4);
 /// ```
+///
+/// # Multiplexed Layout
+/// By default, one channel is constructed per type of message.
+/// With `multiplex_communicator: true`, all messages are instead wrapped into an enum named
+/// `{communicator_name}Message` and transmitted over a single
+/// [MultiplexComm](crate::backend::chili::MultiplexComm) channel.
+/// This reduces the number of channels between neighboring subdomains while the
+/// [Communicator](crate::backend::chili::Communicator) trait is implemented for the same
+/// types of messages as before.
+/// ```
+/// use cellular_raza_core::backend::chili::*;
+///
+/// build_communicator!(
+///     aspects: [Mechanics, Interaction],
+///     core_path: cellular_raza_core,
+///     communicator_name: MyCommunicator,
+///     multiplex_communicator: true,
+/// );
+///
+/// let map = std::collections::BTreeMap::from([
+///     (0, std::collections::BTreeSet::from([1])),
+///     (1, std::collections::BTreeSet::from([0])),
+/// ]);
+/// let mut communicators =
+///     MyCommunicator::<_, _, _, f32, f32, f32, ()>::from_map(&map).unwrap();
+/// let mut comm_0 = communicators.remove(&0).unwrap();
+/// let mut comm_1 = communicators.remove(&1).unwrap();
+/// comm_0.send(&1, SendCell(VoxelPlainIndex::new(1), 1_u8, "aux")).unwrap();
+/// comm_0.send(&1, ForceInformation {
+///     force: 0.5,
+///     pressure: 0.0,
+///     cell_index_in_vector: 0,
+///     index_sender: VoxelPlainIndex::new(0),
+/// }).unwrap();
+///
+/// let forces: Vec<ForceInformation<f32>> = comm_1.receive();
+/// assert_eq!(forces.len(), 1);
+/// let cells: Vec<SendCell<u8, &str>> = comm_1.receive();
+/// assert_eq!(cells[0].1, 1);
+/// ```
+///
+/// # Custom Aspects
+/// Custom aspects add one field per message given in their `#[Communicator(..)]` attribute.
+/// See also the section on custom aspects of [run_simulation].
+/// ```
+/// use cellular_raza_core::backend::chili::*;
+///
+/// #[derive(Clone, Debug, PartialEq)]
+/// struct BondMessage(usize, usize);
+///
+/// build_communicator!(
+///     aspects: [Cycle, #[Communicator(comm_bonds: BondMessage)] Bonds],
+///     core_path: cellular_raza_core,
+///     communicator_name: MyCommunicator,
+/// );
+///
+/// let map = std::collections::BTreeMap::from([
+///     (0, std::collections::BTreeSet::from([1])),
+///     (1, std::collections::BTreeSet::from([0])),
+/// ]);
+/// let mut communicators = MyCommunicator::from_map(&map).unwrap();
+/// let mut comm_0 = communicators.remove(&0).unwrap();
+/// let mut comm_1 = communicators.remove(&1).unwrap();
+/// comm_0.send(&1, BondMessage(3, 4)).unwrap();
+/// let bonds: Vec<BondMessage> = comm_1.receive();
+/// assert_eq!(bonds, vec![BondMessage(3, 4)]);
+/// ```
 pub use cellular_raza_core_proc_macro::build_communicator;
 
 /// Derives the [Communicator](crate::backend::chili::Communicator) trait.
@@ -146,11 +270,37 @@ pub use cellular_raza_core_proc_macro::communicator_generics_placeholders;
 ///     $(aux_storage_name: $aux_storage_name:ident,)?
 ///     $(zero_force_default: $zero_force_default:closure,)?
 ///     $(zero_force_reactions_default: $zero_force_reactions_default:closure,)?
+///     $(scratch_fields: [$($field:ident: $type:ty = $default:expr),*],)?
 ///     $(communicator_name: $communicator_name:ident,)?
+///     $(multiplex_communicator: $multiplex_communicator:bool,)?
 ///     $(mechanics_solver_order: $mechanics_solver_order:NonZeroUsize,)?
 ///     $(reactions_intra_solver_order: $reactions_intra_solver_order:NonZeroUsize,)?
 ///     $(reactions_contact_solver_order: $reactions_contact_solver_order:NonZeroUsize,)?
-/// ) -> Result<StorageAccess<_, _>, SimulationError>;
+///     $(division_throttle: $division_throttle:expr,)?
+///     $(division_relaxation: $division_relaxation:ident,)?
+///     $(rng_mode: $rng_mode:expr,)?
+///     $(rng: $rng:path,)?
+///     $(overlap_diagnostics: $overlap_diagnostics:ident,)?
+///     $(energy_accounting: $energy_accounting:ident,)?
+///     $(equilibration: $equilibration:expr,)?
+///     $(mechanics_clamp: $mechanics_clamp:ident,)?
+///     $(boundary_recovery: $boundary_recovery:ident,)?
+///     $(global_parameters: $global_parameters:ident,)?
+///     $(stopping_criteria: $stopping_criteria:ident,)?
+///     $(steady_state: $steady_state:ident,)?
+///     $(rerun: $rerun:ident,)?
+///     $(voxel_occupancy: $voxel_occupancy:ident,)?
+///     $(voxel_refinement: $voxel_refinement:ident,)?
+///     $(halo_exchange: $halo_exchange:ident,)?
+///     $(communication_profiler: $communication_profiler:ident,)?
+///     $(syncer: $syncer:path,)?
+///     $(voxel_parallelism: $voxel_parallelism:ident,)?
+///     $(thread_affinity: $thread_affinity:ident,)?
+///     $(safety_audit: $safety_audit:ident,)?
+///     $(forensic_dump: $forensic_dump:ident,)?
+///     $(storage_region: $storage_region:ident,)?
+///     $(snapshot_hook: $snapshot_hook:ident,)?
+/// ) -> Result<StorageAccess<_, _, _>, SimulationError>;
 /// ```
 ///
 /// # Arguments
@@ -166,10 +316,36 @@ pub use cellular_raza_core_proc_macro::communicator_generics_placeholders;
 /// | `aux_storage_name` | Name of helper struct to store cellular information. | `_CrAuxStorage` |
 /// | `zero_force_default` | A closure returning the zero value of the force. | <code>&#124;c&#124; {num::Zero::zero()}</code> |
 /// | `zero_force_reactions_default` | A closure returning the zero value of the reactions type. | <code>&#124;c&#124; {num::Zero::zero()}</code> |
+/// | `scratch_fields` | User-defined values which are stored for every cell, see [build_aux_storage] | `[]` |
 /// | `communicator_name` | Name of the struct responsible for communication between threads. | `_CrCommunicator` |
+/// | `multiplex_communicator` | Sends all messages over a single channel, see [build_communicator] | `false` |
 /// | `mechanics_solver_order` | Order of the mechanics solver from `0` to `2` | `2` |
 /// | `reactions_intra_solver_order` | Order of the intracellular reactions solver from `1` to `4` | `4` |
 /// | `reactions_contact_solver_order` | Order of the contact reactions solver from `0` to `2` | `2` |
+/// | `division_throttle` | Limits simultaneous divisions, see [DivisionThrottle](super::DivisionThrottle) | `None` |
+/// | `division_relaxation` | Relaxes mechanics after mass divisions, see [DivisionRelaxation](super::DivisionRelaxation) | - |
+/// | `rng_mode` | Seeding of the random number generators of cells, see [RngMode](super::RngMode) | `None` |
+/// | `rng` | Random number generator of cells which implements [rand::SeedableRng] | [rand_chacha::ChaCha8Rng] |
+/// | `overlap_diagnostics` | Monitors cell overlap, see [OverlapDiagnostics](super::OverlapDiagnostics) | - |
+/// | `energy_accounting` | Records energy and momentum, see [EnergyAccounting](super::EnergyAccounting) | - |
+/// | `equilibration` | Mechanics-only steps before the recorded simulation, see [Equilibration](super::Equilibration) | `None` |
+/// | `mechanics_clamp` | Limits forces and displacements, see [MechanicsClamp](super::MechanicsClamp) | - |
+/// | `boundary_recovery` | Recovers from boundary errors, see [BoundaryRecovery](super::BoundaryRecovery) | - |
+/// | `global_parameters` | Simulation-wide parameters, see [GlobalParameters](cellular_raza_concepts::GlobalParameters) | - |
+/// | `stopping_criteria` | Terminates the simulation early, see [StoppingCriteria](super::StoppingCriteria) | - |
+/// | `steady_state` | Terminates the simulation once converged, see [SteadyStateMonitor](super::SteadyStateMonitor) | - |
+/// | `rerun` | Streams cells and fields to the rerun viewer, see `RerunLogger` (requires the `rerun` feature) | - |
+/// | `voxel_occupancy` | Records cells per voxel and reports hot spots, see [VoxelOccupancy](super::VoxelOccupancy) | - |
+/// | `voxel_refinement` | Subdivides overcrowded voxels, see [VoxelRefinement](super::VoxelRefinement) | - |
+/// | `halo_exchange` | Mirrors boundary cells as ghosts instead of requesting forces, see [HaloExchange](super::HaloExchange) | - |
+/// | `communication_profiler` | Counts messages and bytes of every subdomain, see [CommunicationProfiler](super::CommunicationProfiler) | - |
+/// | `syncer` | Strategy to synchronize subdomains, see [NeighborSync](super::NeighborSync) and [MessageSync](super::MessageSync) | [BarrierSync](super::BarrierSync) |
+/// | `voxel_parallelism` | Updates voxels of a subdomain in parallel, see [VoxelParallelism](super::VoxelParallelism) | - |
+/// | `thread_affinity` | Pins threads to cores, see `ThreadAffinity` (requires the `affinity` feature) | - |
+/// | `safety_audit` | Verifies invariants of the simulation state in every step, see [SafetyAudit](super::SafetyAudit) | - |
+/// | `forensic_dump` | Dumps cells with non-finite forces or positions, see [ForensicDump](super::ForensicDump) | - |
+/// | `storage_region` | Only stores results inside a region of interest, see [StorageRegion](super::StorageRegion) | - |
+/// | `snapshot_hook` | Hands the state of all cells and subdomains to a callback at save points, see [SnapshotHook](super::SnapshotHook) | - |
 ///
 /// The `domain`,`agents` and `settings` arguments allow for
 /// [shorthand notation](https://doc.rust-lang.org/book/ch05-01-defining-structs.html#using-the-field-init-shorthand).
@@ -184,6 +360,41 @@ pub use cellular_raza_core_proc_macro::communicator_generics_placeholders;
 /// | `ReactionsExtra` | [ReactionsExtra](cellular_raza_concepts::ReactionsExtra), [SubDomainReactions](cellular_raza_concepts::SubDomainReactions) |
 /// | `ReactionsContact` | [ReactionsContact](cellular_raza_concepts::ReactionsContact) |
 /// | `DomainForce` | [SubDomainForce](cellular_raza_concepts::SubDomainForce), [SubDomainMechanics](cellular_raza_concepts::SubDomainMechanics) |
+/// | `Age` | [Age](cellular_raza_concepts::Age) |
+/// | `RotationalMechanics` | [RotationalMechanics](cellular_raza_concepts::RotationalMechanics), [InteractionTorque](cellular_raza_concepts::InteractionTorque) (requires `Mechanics` and `Interaction`) |
+///
+/// ## Custom Aspects
+/// Downstream crates can define additional aspects without modifying `cellular_raza`.
+/// A custom aspect is given by a new name which is preceded by at least one of the following
+/// attributes.
+///
+/// | Attribute | Effect |
+/// | --- | --- |
+/// | `#[Communicator(name: Message, ..)]` | Adds a channel for `Message` to the communicator, see [build_communicator] |
+/// | `#[AuxStorage(name: Type, ..)]` | Stores a value for every cell, see [CustomAuxStorage](super::CustomAuxStorage) |
+/// | `#[Update(step: function, ..)]` | Executes `function` in the given step |
+///
+/// Steps `step_1` to `step_5` correspond to the [steps of the update](super) and execute
+/// `function(&mut sbox)?` for every [SubDomainBox](super::SubDomainBox).
+/// The `cell` step executes `function(&mut cell, &mut aux_storage, dt, &mut rng)?` for every
+/// cell together with the other purely local updates.
+/// Functions of custom aspects are executed after all other functions of the same step.
+/// ```ignore
+/// run_simulation!(
+///     domain,
+///     agents,
+///     settings,
+///     aspects: [
+///         Mechanics,
+///         Interaction,
+///         #[Communicator(comm_bonds: BondMessage)]
+///         #[AuxStorage(bonds: BondStorage)]
+///         #[Update(step_1: send_bonds, step_2: receive_bonds, cell: update_bonds)]
+///         Bonds,
+///     ],
+/// )?;
+/// ```
+/// Messages and stored values of custom aspects need to be concrete types.
 ///
 /// # Returns
 /// Returns a [StorageAccess](super::StorageAccess) to interoperate with calculted results.
@@ -212,10 +423,36 @@ pub use cellular_raza_core_proc_macro::communicator_generics_placeholders;
 /// | `aux_storage_name`                | ✅ | ✅ | ❌ | ✅ | ✅ | ❌ |
 /// | `zero_force_default`              | ✅ | ✅ | ❌ | ✅ | ✅ | ❌ |
 /// | `zero_force_reactions_default`    | ✅ | ✅ | ❌ | ✅ | ✅ | ❌ |
+/// | `scratch_fields`                  | ✅ | ✅ | ❌ | ✅ | ✅ | ❌ |
 /// | `communicator_name`               | ✅ | ✅ | ❌ | ✅ | ❌ | ✅ |
+/// | `multiplex_communicator`          | ✅ | ✅ | ❌ | ✅ | ❌ | ✅ |
 /// | `mechanics_solver_order`          | ✅ | ✅ | ❌ | ❌ | ❌ | ❌ |
 /// | `reactions_intra_solver_order`    | ✅ | ✅ | ❌ | ❌ | ❌ | ❌ |
 /// | `reactions_contact_solver_order`  | ✅ | ✅ | ❌ | ❌ | ❌ | ❌ |
+/// | `division_throttle`               | ✅ | ✅ | ❌ | ❌ | ❌ | ❌ |
+/// | `division_relaxation`             | ✅ | ✅ | ❌ | ❌ | ❌ | ❌ |
+/// | `rng_mode`                        | ✅ | ✅ | ❌ | ❌ | ❌ | ❌ |
+/// | `rng`                             | ✅ | ✅ | ✅ | ❌ | ❌ | ❌ |
+/// | `overlap_diagnostics`             | ✅ | ✅ | ❌ | ❌ | ❌ | ❌ |
+/// | `energy_accounting`               | ✅ | ✅ | ❌ | ❌ | ❌ | ❌ |
+/// | `equilibration`                   | ✅ | ✅ | ❌ | ❌ | ❌ | ❌ |
+/// | `mechanics_clamp`                 | ✅ | ✅ | ❌ | ❌ | ❌ | ❌ |
+/// | `boundary_recovery`               | ✅ | ✅ | ❌ | ❌ | ❌ | ❌ |
+/// | `global_parameters`               | ✅ | ✅ | ❌ | ❌ | ❌ | ❌ |
+/// | `stopping_criteria`               | ✅ | ✅ | ❌ | ❌ | ❌ | ❌ |
+/// | `steady_state`                    | ✅ | ✅ | ❌ | ❌ | ❌ | ❌ |
+/// | `rerun`                           | ✅ | ✅ | ❌ | ❌ | ❌ | ❌ |
+/// | `voxel_occupancy`                 | ✅ | ✅ | ❌ | ❌ | ❌ | ❌ |
+/// | `voxel_refinement`                | ✅ | ✅ | ❌ | ❌ | ❌ | ❌ |
+/// | `halo_exchange`                   | ✅ | ✅ | ❌ | ❌ | ❌ | ❌ |
+/// | `communication_profiler`          | ✅ | ✅ | ❌ | ❌ | ❌ | ❌ |
+/// | `syncer`                          | ✅ | ✅ | ❌ | ❌ | ❌ | ❌ |
+/// | `voxel_parallelism`               | ✅ | ✅ | ❌ | ❌ | ❌ | ❌ |
+/// | `thread_affinity`                 | ✅ | ✅ | ❌ | ❌ | ❌ | ❌ |
+/// | `safety_audit`                    | ✅ | ✅ | ❌ | ❌ | ❌ | ❌ |
+/// | `forensic_dump`                   | ✅ | ✅ | ❌ | ❌ | ❌ | ❌ |
+/// | `storage_region`                  | ✅ | ✅ | ❌ | ❌ | ❌ | ❌ |
+/// | `snapshot_hook`                   | ✅ | ✅ | ❌ | ❌ | ❌ | ❌ |
 ///
 /// </div>
 #[doc(inline)]
@@ -228,6 +465,84 @@ pub use cellular_raza_core_proc_macro::prepare_types;
 #[doc(inline)]
 pub use cellular_raza_core_proc_macro::test_compatibility;
 
+/// Checks that agents and prepared types are consistent with the given simulation aspects.
+///
+/// When types are prepared with [prepare_types!] and the simulation is executed by [run_main!],
+/// both macros need to be given the same aspects.
+/// Otherwise, compilation fails with errors about missing trait implementations of generated
+/// types which are hard to trace back.
+/// This macro verifies
+/// - that the `agents` implement the concepts required by the aspects (see [run_simulation])
+/// - that the AuxStorage and Communicator were built with at least all of the given aspects.
+///
+/// The AuxStorage and Communicator are identified by the optional `aux_storage_name` and
+/// `communicator_name` arguments and need to be in scope.
+/// Their aspects are compared by name which also applies to custom aspects.
+/// A mismatch results in an error during compilation which names the missing aspect.
+/// ```
+/// use cellular_raza_core::backend::chili::*;
+/// use cellular_raza_concepts::{Cycle, CycleEvent, DivisionError};
+/// use serde::{Deserialize, Serialize};
+///
+/// struct MyCell;
+///
+/// impl Cycle for MyCell {
+///     fn update_cycle(
+///         _rng: &mut rand_chacha::ChaCha8Rng,
+///         _dt: &f64,
+///         _cell: &mut Self,
+///     ) -> Option<CycleEvent> {
+///         None
+///     }
+///
+///     fn divide(
+///         _rng: &mut rand_chacha::ChaCha8Rng,
+///         _cell: &mut Self,
+///     ) -> Result<Self, DivisionError> {
+///         Ok(MyCell)
+///     }
+/// }
+///
+/// prepare_types!(
+///     aspects: [Cycle],
+///     core_path: cellular_raza_core,
+/// );
+///
+/// let agents = vec![MyCell];
+/// check_aspects!(
+///     agents,
+///     aspects: [Cycle],
+///     core_path: cellular_raza_core,
+/// );
+/// ```
+/// The following example fails to compile since the types were prepared without the custom
+/// aspect `Bonds`.
+/// ```compile_fail
+/// # use cellular_raza_core::backend::chili::*;
+/// # use serde::{Deserialize, Serialize};
+/// prepare_types!(
+///     aspects: [],
+///     core_path: cellular_raza_core,
+/// );
+///
+/// let agents = Vec::<()>::new();
+/// check_aspects!(
+///     agents,
+///     aspects: [#[Update(step_1: update_bonds)] Bonds],
+///     core_path: cellular_raza_core,
+/// );
+/// ```
+///
+/// | Keyword | Description | Default |
+/// | --- | --- | --- |
+/// | `agents` | Iterable of cell-agents | - |
+/// | `aspects` | List of simulation aspects | - |
+/// | `core_path` | Path that points to the core module of `cellular_raza` | `cellular_raza::core` |
+/// | `aux_storage_name` | Name of the AuxStorage struct | `_CrAuxStorage` |
+/// | `communicator_name` | Name of the Communicator struct | `_CrCommunicator` |
+/// | `rng` | Random number generator of cells | [rand_chacha::ChaCha8Rng] |
+pub use cellular_raza_core_proc_macro::check_aspects;
+
 /// Runs a with user-defined concepts. Assumes that types have been prepared with [prepare_types!].
 ///
 /// See the documentation of the [run_simulation] macro.
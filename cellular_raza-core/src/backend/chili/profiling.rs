@@ -0,0 +1,358 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use cellular_raza_concepts::SubDomain;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    CommunicationStatistics, MessageStatistics, SimulationError, SubDomainBox, SubDomainPlainIndex,
+};
+
+/// Messages of one type which were sent and received by a single subdomain.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct MessageProfile {
+    /// Number of sent messages
+    pub messages_sent: usize,
+    /// Combined size of all sent messages
+    pub bytes_sent: usize,
+    /// Number of received messages
+    pub messages_received: usize,
+    /// Combined size of all received messages
+    pub bytes_received: usize,
+    /// Largest number of messages sent in a single step
+    pub max_messages_sent_per_step: usize,
+}
+
+/// Profile of the communication of a single subdomain.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct SubDomainProfile {
+    /// Number of profiled steps
+    pub n_steps: usize,
+    /// Number of neighboring subdomains
+    pub n_neighbors: usize,
+    /// Number of cells summed over all profiled steps
+    pub cell_steps: usize,
+    /// Profile of every type of message
+    pub messages: BTreeMap<String, MessageProfile>,
+    /// Cumulative statistics of the communicator at the last recorded step
+    #[serde(skip)]
+    last_statistics: BTreeMap<String, MessageStatistics>,
+}
+
+impl SubDomainProfile {
+    /// Average number of cells in the subdomain
+    pub fn mean_cells(&self) -> f64 {
+        match self.n_steps {
+            0 => 0.0,
+            n_steps => self.cell_steps as f64 / n_steps as f64,
+        }
+    }
+
+    /// Number of messages of all types sent in all profiled steps
+    pub fn messages_sent(&self) -> usize {
+        self.messages.values().map(|p| p.messages_sent).sum()
+    }
+
+    /// Combined size of the messages of all types sent in all profiled steps
+    pub fn bytes_sent(&self) -> usize {
+        self.messages.values().map(|p| p.bytes_sent).sum()
+    }
+
+    /// Average number of messages of all types sent per step
+    pub fn messages_sent_per_step(&self) -> f64 {
+        match self.n_steps {
+            0 => 0.0,
+            n_steps => self.messages_sent() as f64 / n_steps as f64,
+        }
+    }
+
+    /// Average number of sent messages per cell and step.
+    ///
+    /// Relates the cost of communication to the cost of the computation which scales with the
+    /// number of cells.
+    pub fn messages_per_cell(&self) -> f64 {
+        match self.cell_steps {
+            0 => 0.0,
+            cell_steps => self.messages_sent() as f64 / cell_steps as f64,
+        }
+    }
+
+    /// Adds the messages which were sent and received since the last call.
+    fn record(&mut self, statistics: Vec<MessageStatistics>, n_cells: usize) {
+        self.n_steps += 1;
+        self.cell_steps += n_cells;
+        for current in statistics {
+            let last = self
+                .last_statistics
+                .get(&current.message_type)
+                .cloned()
+                .unwrap_or_default();
+            let profile = self
+                .messages
+                .entry(current.message_type.clone())
+                .or_default();
+            let messages_sent = current.messages_sent - last.messages_sent;
+            profile.messages_sent += messages_sent;
+            profile.bytes_sent += current.bytes_sent - last.bytes_sent;
+            profile.messages_received += current.messages_received - last.messages_received;
+            profile.bytes_received += current.bytes_received - last.bytes_received;
+            profile.max_messages_sent_per_step =
+                profile.max_messages_sent_per_step.max(messages_sent);
+            self.last_statistics
+                .insert(current.message_type.clone(), current);
+        }
+    }
+}
+
+/// Per-subdomain summary of the communication of a simulation.
+///
+/// The summary is displayed as a table with one row per subdomain followed by the messages of
+/// every type.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct CommunicationReport {
+    /// Profiles of all subdomains
+    pub subdomains: BTreeMap<SubDomainPlainIndex, SubDomainProfile>,
+}
+
+impl CommunicationReport {
+    /// Ratio between the largest and the average number of messages sent by a subdomain.
+    ///
+    /// Values much larger than one indicate that the decomposition of the domain leads to an
+    /// unbalanced communication load.
+    /// Returns [None] if no messages were sent.
+    pub fn message_imbalance(&self) -> Option<f64> {
+        Self::imbalance(self.subdomains.values().map(|p| p.messages_sent() as f64))
+    }
+
+    /// Ratio between the largest and the average number of cells of a subdomain.
+    ///
+    /// Returns [None] if no cells were present.
+    pub fn cell_imbalance(&self) -> Option<f64> {
+        Self::imbalance(self.subdomains.values().map(|p| p.mean_cells()))
+    }
+
+    /// Ratio between the largest and the average value
+    fn imbalance(values: impl IntoIterator<Item = f64>) -> Option<f64> {
+        let values: Vec<_> = values.into_iter().collect();
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let max = values.iter().cloned().fold(0.0, f64::max);
+        match mean > 0.0 {
+            true => Some(max / mean),
+            false => None,
+        }
+    }
+}
+
+/// Removes the module paths from a type name.
+fn short_type_name(type_name: &str) -> String {
+    let mut short = String::new();
+    let mut segment = String::new();
+    for c in type_name.chars() {
+        match c {
+            c if c.is_alphanumeric() || c == '_' || c == ':' => segment.push(c),
+            _ => {
+                short.push_str(segment.rsplit("::").next().unwrap_or_default());
+                segment.clear();
+                short.push(c);
+            }
+        }
+    }
+    short.push_str(segment.rsplit("::").next().unwrap_or_default());
+    short
+}
+
+impl core::fmt::Display for CommunicationReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        writeln!(
+            f,
+            "{:>9} {:>9} {:>12} {:>13} {:>13} {:>14}",
+            "Subdomain", "Neighbors", "Cells", "Messages", "Msg/step", "Bytes"
+        )?;
+        for (index, profile) in self.subdomains.iter() {
+            writeln!(
+                f,
+                "{:>9} {:>9} {:>12.1} {:>13} {:>13.1} {:>14}",
+                index.0,
+                profile.n_neighbors,
+                profile.mean_cells(),
+                profile.messages_sent(),
+                profile.messages_sent_per_step(),
+                profile.bytes_sent(),
+            )?;
+            for (message_type, message) in profile.messages.iter() {
+                writeln!(
+                    f,
+                    "{:>32} {:>13} {:>13.1} {:>14}",
+                    short_type_name(message_type),
+                    message.messages_sent,
+                    message.messages_sent as f64 / profile.n_steps.max(1) as f64,
+                    message.bytes_sent,
+                )?;
+            }
+        }
+        if let Some(imbalance) = self.message_imbalance() {
+            writeln!(f, "Message imbalance (max/mean): {imbalance:.2}")?;
+        }
+        if let Some(imbalance) = self.cell_imbalance() {
+            writeln!(f, "Cell imbalance (max/mean): {imbalance:.2}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Counts the messages and bytes exchanged by every subdomain.
+///
+/// In every step, the [MessageStatistics] of the communicator of each subdomain are recorded.
+/// The resulting [CommunicationReport] shows how many messages of every type were sent per
+/// step and relates them to the number of cells of the subdomain.
+/// This helps to choose the number of threads and the decomposition of the domain: when the
+/// number of messages per cell is large, fewer subdomains or the
+/// [HaloExchange](super::HaloExchange) mode usually improve the performance.
+/// A large imbalance between subdomains indicates that the domain should be decomposed
+/// differently.
+///
+/// The profiler is passed to the [run_simulation](crate::backend::chili::run_simulation) macro
+/// via the `communication_profiler` argument.
+/// All clones share their profiles.
+/// ```
+/// # use cellular_raza_core::backend::chili::CommunicationProfiler;
+/// let profiler = CommunicationProfiler::new();
+/// // Pass `communication_profiler: profiler` to the run_simulation macro
+/// // and print the summary after the simulation has finished.
+/// println!("{}", profiler.report());
+/// assert!(profiler.report().subdomains.is_empty());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CommunicationProfiler {
+    /// Profiles of all subdomains
+    report: Arc<Mutex<CommunicationReport>>,
+}
+
+impl CommunicationProfiler {
+    /// Constructs a new [CommunicationProfiler] without any profiles.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Summary of all recorded steps
+    pub fn report(&self) -> CommunicationReport {
+        self.report
+            .lock()
+            .map(|report| report.clone())
+            .unwrap_or_default()
+    }
+
+    /// Discards all previous messages of the subdomain such that only subsequent steps are
+    /// profiled.
+    ///
+    /// This excludes messages sent during the setup or equilibration of the simulation.
+    pub fn start<I, S, C, A, Com, Sy, R>(
+        &self,
+        sbox: &SubDomainBox<I, S, C, A, Com, Sy, R>,
+    ) -> Result<(), SimulationError>
+    where
+        S: SubDomain,
+        Com: CommunicationStatistics,
+    {
+        let mut report = self
+            .report
+            .lock()
+            .map_err(|e| SimulationError::OtherThreadError(e.to_string()))?;
+        report.subdomains.insert(
+            sbox.subdomain_plain_index,
+            SubDomainProfile {
+                n_neighbors: sbox.neighbors.len(),
+                last_statistics: sbox
+                    .communicator
+                    .communication_statistics()
+                    .into_iter()
+                    .map(|statistics| (statistics.message_type.clone(), statistics))
+                    .collect(),
+                ..Default::default()
+            },
+        );
+        Ok(())
+    }
+
+    /// Records all messages of the subdomain since the last call.
+    pub fn record<I, S, C, A, Com, Sy, R>(
+        &self,
+        sbox: &SubDomainBox<I, S, C, A, Com, Sy, R>,
+    ) -> Result<(), SimulationError>
+    where
+        S: SubDomain,
+        Com: CommunicationStatistics,
+    {
+        let n_cells = sbox.voxels.values().map(|voxel| voxel.cells.len()).sum();
+        let mut report = self
+            .report
+            .lock()
+            .map_err(|e| SimulationError::OtherThreadError(e.to_string()))?;
+        let profile = report
+            .subdomains
+            .entry(sbox.subdomain_plain_index)
+            .or_insert_with(|| SubDomainProfile {
+                n_neighbors: sbox.neighbors.len(),
+                ..Default::default()
+            });
+        profile.record(sbox.communicator.communication_statistics(), n_cells);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_profiling {
+    use super::*;
+
+    fn statistics(messages_sent: usize, messages_received: usize) -> Vec<MessageStatistics> {
+        vec![MessageStatistics {
+            message_type: "cellular_raza_core::backend::chili::SendCell<my_crate::Agent, f64>"
+                .to_owned(),
+            messages_sent,
+            bytes_sent: 16 * messages_sent,
+            messages_received,
+            bytes_received: 16 * messages_received,
+        }]
+    }
+
+    #[test]
+    fn profile_steps() {
+        let mut profile = SubDomainProfile {
+            n_neighbors: 2,
+            last_statistics: statistics(5, 5)
+                .into_iter()
+                .map(|s| (s.message_type.clone(), s))
+                .collect(),
+            ..Default::default()
+        };
+        profile.record(statistics(8, 6), 10);
+        profile.record(statistics(15, 10), 30);
+        profile.record(statistics(16, 10), 20);
+
+        assert_eq!(profile.n_steps, 3);
+        assert_eq!(profile.mean_cells(), 20.0);
+        assert_eq!(profile.messages_sent(), 11);
+        assert_eq!(profile.bytes_sent(), 176);
+        assert_eq!(profile.messages_per_cell(), 11.0 / 60.0);
+        let message = profile.messages.values().next().unwrap();
+        assert_eq!(message.max_messages_sent_per_step, 7);
+        assert_eq!(message.messages_received, 5);
+
+        let mut report = CommunicationReport::default();
+        report.subdomains.insert(SubDomainPlainIndex(0), profile);
+        report
+            .subdomains
+            .insert(SubDomainPlainIndex(1), SubDomainProfile::default());
+        assert_eq!(report.message_imbalance(), Some(2.0));
+        assert!(report.to_string().contains("SendCell<Agent, f64>"));
+    }
+
+    #[test]
+    fn shorten_type_names() {
+        assert_eq!(short_type_name("u32"), "u32");
+        assert_eq!(
+            short_type_name("a::b::PosInformation<[f64; 2], c::Vel, ()>"),
+            "PosInformation<[f64; 2], Vel, ()>"
+        );
+    }
+}
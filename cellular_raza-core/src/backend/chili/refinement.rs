@@ -0,0 +1,466 @@
+use std::collections::BTreeSet;
+
+use cellular_raza_concepts::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Communicator, PosInformation, SimulationError, SubDomainBox, SubDomainPlainIndex,
+    UpdateInteraction, UpdateMechanics, Voxel, VoxelPlainIndex,
+};
+
+/// Provides the coordinates of a cell which are used to subdivide overcrowded voxels.
+///
+/// See [VoxelRefinement].
+/// ```
+/// # use cellular_raza_core::backend::chili::RefinementCoordinates;
+/// struct Cargo {
+///     pos: [f64; 3],
+/// }
+///
+/// impl RefinementCoordinates<3> for Cargo {
+///     fn refinement_coordinates(&self) -> [f64; 3] {
+///         self.pos
+///     }
+/// }
+/// # assert_eq!(Cargo { pos: [1.0; 3] }.refinement_coordinates(), [1.0; 3]);
+/// ```
+pub trait RefinementCoordinates<const D: usize> {
+    /// Cartesian coordinates of the center of the cell
+    fn refinement_coordinates(&self) -> [f64; D];
+}
+
+/// Subdivides overcrowded voxels in order to calculate interactions efficiently.
+///
+/// Interactions between cells within one voxel are calculated for every pair of cells such
+/// that the cost of a voxel grows quadratically with its number of cells.
+/// When cells aggregate strongly, a few voxels can thus dominate the runtime of the whole
+/// simulation.
+/// Voxels with more than [VoxelRefinement::refine_above] cells are therefore refined:
+/// their cells are recursively divided into boxes until every box contains at most
+/// [VoxelRefinement::max_cells_per_leaf] cells or is smaller than twice the
+/// [VoxelRefinement::interaction_range].
+/// Interactions are then only calculated between cells of the same box and of neighboring
+/// boxes, i.e. boxes which are closer than the interaction range.
+/// The boxes and their neighbors are determined anew in every step from the current positions
+/// of the cells.
+/// Refined voxels are merged back once they contain fewer than
+/// [VoxelRefinement::merge_below] cells.
+///
+/// The results only agree with the unrefined calculation if cells do not interact at
+/// distances larger than the interaction range.
+/// Voxels of subdomains which provide a
+/// [DomainMetric](cellular_raza_concepts::DomainMetric) are never refined.
+/// Interactions with cells of neighboring voxels are not affected by the refinement.
+///
+/// The refinement is passed to the [run_simulation](crate::backend::chili::run_simulation)
+/// macro via the `voxel_refinement` argument and requires the cells to implement
+/// [RefinementCoordinates].
+/// ```
+/// # use cellular_raza_core::backend::chili::VoxelRefinement;
+/// let refinement = VoxelRefinement::new(1.5).refine_above(100).merge_below(40);
+/// // Pass `voxel_refinement: refinement` to the run_simulation macro
+/// assert_eq!(refinement.max_cells_per_leaf, 16);
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct VoxelRefinement {
+    /// Largest distance at which cells interact
+    pub interaction_range: f64,
+    /// Voxels with more cells are refined
+    pub refine_above: usize,
+    /// Refined voxels with fewer cells are merged back
+    pub merge_below: usize,
+    /// Boxes with more cells are subdivided further
+    pub max_cells_per_leaf: usize,
+    /// Maximum number of times a box is subdivided
+    pub max_depth: usize,
+}
+
+impl VoxelRefinement {
+    /// Refines voxels with more than 64 cells and merges them back below 32 cells.
+    pub fn new(interaction_range: f64) -> Self {
+        Self {
+            interaction_range,
+            refine_above: 64,
+            merge_below: 32,
+            max_cells_per_leaf: 16,
+            max_depth: 8,
+        }
+    }
+
+    /// Sets the number of cells above which voxels are refined.
+    pub fn refine_above(self, refine_above: usize) -> Self {
+        Self {
+            refine_above,
+            ..self
+        }
+    }
+
+    /// Sets the number of cells below which refined voxels are merged back.
+    pub fn merge_below(self, merge_below: usize) -> Self {
+        Self {
+            merge_below,
+            ..self
+        }
+    }
+
+    /// Sets the number of cells above which boxes are subdivided further.
+    pub fn max_cells_per_leaf(self, max_cells_per_leaf: usize) -> Self {
+        Self {
+            max_cells_per_leaf,
+            ..self
+        }
+    }
+
+    /// Sets the maximum number of times a box is subdivided.
+    pub fn max_depth(self, max_depth: usize) -> Self {
+        Self { max_depth, ..self }
+    }
+
+    /// Updates whether the voxel is refined given its current number of cells.
+    ///
+    /// Voxels between both thresholds keep their current state such that voxels near one
+    /// threshold do not alternate between both states.
+    fn update(
+        &self,
+        voxel: VoxelPlainIndex,
+        n_cells: usize,
+        refined_voxels: &mut BTreeSet<VoxelPlainIndex>,
+    ) -> bool {
+        if n_cells > self.refine_above {
+            refined_voxels.insert(voxel);
+        } else if n_cells < self.merge_below {
+            refined_voxels.remove(&voxel);
+        }
+        refined_voxels.contains(&voxel)
+    }
+}
+
+/// Box of the hierarchical subdivision of a voxel
+#[derive(Clone, Debug)]
+struct RefinementNode<const D: usize> {
+    /// Lower corner of the smallest box containing all cells of the node
+    lower: [f64; D],
+    /// Upper corner of the smallest box containing all cells of the node
+    upper: [f64; D],
+    /// Indices of the children in the tree
+    children: Vec<usize>,
+    /// Indices of the cells in the voxel if this node is a leaf
+    cells: Vec<usize>,
+}
+
+/// Hierarchical subdivision of the cells of one voxel
+#[derive(Clone, Debug)]
+struct RefinementTree<const D: usize> {
+    /// All nodes with the root at index 0
+    nodes: Vec<RefinementNode<D>>,
+}
+
+impl<const D: usize> RefinementNode<D> {
+    /// Node which contains the given cells
+    fn new(cells: Vec<usize>, coordinates: &[[f64; D]]) -> Self {
+        let mut lower = [f64::INFINITY; D];
+        let mut upper = [f64::NEG_INFINITY; D];
+        for cell in cells.iter() {
+            for i in 0..D {
+                lower[i] = lower[i].min(coordinates[*cell][i]);
+                upper[i] = upper[i].max(coordinates[*cell][i]);
+            }
+        }
+        Self {
+            lower,
+            upper,
+            children: Vec::new(),
+            cells,
+        }
+    }
+
+    /// Squared distance between the boxes of both nodes
+    fn distance_squared(&self, other: &Self) -> f64 {
+        (0..D)
+            .map(|i| {
+                let gap = (other.lower[i] - self.upper[i])
+                    .max(self.lower[i] - other.upper[i])
+                    .max(0.0);
+                gap * gap
+            })
+            .sum()
+    }
+}
+
+impl<const D: usize> RefinementTree<D> {
+    /// Subdivides the cells at the given coordinates
+    fn new(coordinates: &[[f64; D]], refinement: &VoxelRefinement) -> Self {
+        let root = RefinementNode::new((0..coordinates.len()).collect(), coordinates);
+        let mut tree = Self { nodes: vec![root] };
+        tree.subdivide(0, 0, coordinates, refinement);
+        tree
+    }
+
+    /// Splits the node at the center of its box along every dimension
+    fn subdivide(
+        &mut self,
+        node: usize,
+        depth: usize,
+        coordinates: &[[f64; D]],
+        refinement: &VoxelRefinement,
+    ) {
+        let RefinementNode {
+            lower,
+            upper,
+            cells,
+            ..
+        } = &self.nodes[node];
+        let too_small = (0..D).all(|i| upper[i] - lower[i] < 2.0 * refinement.interaction_range);
+        if cells.len() <= refinement.max_cells_per_leaf
+            || depth >= refinement.max_depth
+            || too_small
+        {
+            return;
+        }
+        let center: [f64; D] = core::array::from_fn(|i| 0.5 * (lower[i] + upper[i]));
+        let mut octants = std::collections::BTreeMap::<usize, Vec<usize>>::new();
+        for cell in std::mem::take(&mut self.nodes[node].cells) {
+            let octant = (0..D)
+                .filter(|i| coordinates[cell][*i] > center[*i])
+                .fold(0, |octant, i| octant | (1 << i));
+            octants.entry(octant).or_default().push(cell);
+        }
+        for (_, cells) in octants {
+            let child = self.nodes.len();
+            self.nodes.push(RefinementNode::new(cells, coordinates));
+            self.nodes[node].children.push(child);
+            self.subdivide(child, depth + 1, coordinates, refinement);
+        }
+    }
+
+    /// Indices of all nodes which do not have children
+    fn leaves(&self) -> Vec<usize> {
+        (0..self.nodes.len())
+            .filter(|node| self.nodes[*node].children.is_empty())
+            .collect()
+    }
+
+    /// Leaves with a larger index than the given leaf which are closer than the range
+    fn neighbors(&self, leaf: usize, range: f64) -> Vec<usize> {
+        let mut neighbors = Vec::new();
+        let mut stack = vec![0];
+        while let Some(node) = stack.pop() {
+            let candidate = &self.nodes[node];
+            if candidate.distance_squared(&self.nodes[leaf]) > range * range {
+                continue;
+            }
+            match candidate.children.is_empty() {
+                true if node > leaf => neighbors.push(node),
+                true => (),
+                false => stack.extend(candidate.children.iter()),
+            }
+        }
+        neighbors
+    }
+
+    /// All pairs of cells in the same or neighboring leaves
+    fn pairs(&self, range: f64) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        for leaf in self.leaves() {
+            let cells = &self.nodes[leaf].cells;
+            for (n, cell1) in cells.iter().enumerate() {
+                for cell2 in cells[n + 1..].iter() {
+                    pairs.push((*cell1.min(cell2), *cell1.max(cell2)));
+                }
+            }
+            for neighbor in self.neighbors(leaf, range) {
+                for cell1 in cells.iter() {
+                    for cell2 in self.nodes[neighbor].cells.iter() {
+                        pairs.push((*cell1.min(cell2), *cell1.max(cell2)));
+                    }
+                }
+            }
+        }
+        pairs
+    }
+}
+
+impl<C, A, R> Voxel<C, A, R> {
+    /// Calculates interactions between cells of the voxel after subdividing it as described
+    /// by the [VoxelRefinement].
+    pub(crate) fn calculate_force_between_cells_refined<
+        Pos,
+        Vel,
+        For,
+        Float,
+        Inf,
+        const N: usize,
+        const D: usize,
+    >(
+        &mut self,
+        refinement: &VoxelRefinement,
+        parameters: &GlobalParameters,
+    ) -> Result<(), CalcError>
+    where
+        C: Position<Pos>,
+        C: Velocity<Vel>,
+        C: Interaction<Pos, Vel, For, Inf>,
+        C: RefinementCoordinates<D>,
+        A: UpdateMechanics<Pos, Vel, For, N>,
+        A: UpdateInteraction,
+        For: Xapy<Float>,
+        Float: num::Float,
+    {
+        let coordinates: Vec<_> = self
+            .cells
+            .iter()
+            .map(|(cbox, _)| cbox.cell.refinement_coordinates())
+            .collect();
+        let tree = RefinementTree::new(&coordinates, refinement);
+        for (n, m) in tree.pairs(refinement.interaction_range) {
+            self.interact_pair(n, m, None, parameters)?;
+        }
+        Ok(())
+    }
+}
+
+impl<I, S, C, A, Com, Sy, R> SubDomainBox<I, S, C, A, Com, Sy, R>
+where
+    S: SubDomain,
+{
+    /// Sets the [VoxelRefinement] which is used by
+    /// [SubDomainBox::update_mechanics_interaction_step_1_refined].
+    pub fn set_voxel_refinement(&mut self, voxel_refinement: Option<VoxelRefinement>) {
+        self.voxel_refinement = voxel_refinement;
+        self.refined_voxels.clear();
+    }
+
+    /// Voxels which were refined in the last step
+    pub fn refined_voxels(&self) -> &BTreeSet<VoxelPlainIndex> {
+        &self.refined_voxels
+    }
+
+    /// Same as [SubDomainBox::update_mechanics_interaction_step_1] but subdivides overcrowded
+    /// voxels as specified by the [VoxelRefinement].
+    ///
+    /// Without a refinement set via [SubDomainBox::set_voxel_refinement], both functions are
+    /// identical.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub fn update_mechanics_interaction_step_1_refined<
+        Pos,
+        Vel,
+        For,
+        Float,
+        Inf,
+        const N: usize,
+        const D: usize,
+    >(
+        &mut self,
+    ) -> Result<(), SimulationError>
+    where
+        Pos: Clone,
+        Vel: Clone,
+        Inf: Clone,
+        C: Position<Pos>,
+        C: Velocity<Vel>,
+        C: Mechanics<Pos, Vel, For, Float, R>,
+        C: Interaction<Pos, Vel, For, Inf>,
+        C: RefinementCoordinates<D>,
+        A: UpdateMechanics<Pos, Vel, For, N>,
+        A: UpdateInteraction,
+        For: Xapy<Float> + core::ops::AddAssign,
+        Float: num::Float + core::ops::AddAssign,
+        <S as SubDomain>::VoxelIndex: Ord,
+        S: SubDomainMechanics<Pos, Vel>,
+        Com: Communicator<SubDomainPlainIndex, PosInformation<Pos, Vel, Inf>>,
+    {
+        let metric = self.subdomain.domain_metric();
+        let parameters = &self.global_parameters;
+        for (plain_index, vox) in self.voxels.iter_mut() {
+            match &self.voxel_refinement {
+                Some(refinement)
+                    if metric.is_none()
+                        && refinement.update(
+                            *plain_index,
+                            vox.cells.len(),
+                            &mut self.refined_voxels,
+                        ) =>
+                {
+                    vox.calculate_force_between_cells_refined::<_, _, _, _, _, N, D>(
+                        refinement, parameters,
+                    )?
+                }
+                _ => vox.calculate_force_between_cells_internally(metric, parameters)?,
+            }
+        }
+        self.calculate_forces_from_neighboring_voxels::<Pos, Vel, For, Float, Inf, N>()
+    }
+}
+
+#[cfg(test)]
+mod test_refinement {
+    use super::*;
+    use crate::backend::chili::test_fixtures::*;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn refined_forces_agree() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(1);
+        // Cells attract each other within unit distance
+        let cells: Vec<_> = (0..300)
+            .map(|_| Agent {
+                pos: rng.gen_range(0.0..40.0),
+                strength: 1.0,
+                range: 1.0,
+            })
+            .collect();
+        let mut voxel = voxel(0, &[], &cells);
+        let mut refined = voxel.clone();
+        let parameters = GlobalParameters::new();
+        voxel
+            .calculate_force_between_cells_internally::<_, _, _, f64, _, 1>(None, &parameters)
+            .unwrap();
+        refined
+            .calculate_force_between_cells_refined::<_, _, _, f64, _, 1, 1>(
+                &VoxelRefinement::new(1.0),
+                &parameters,
+            )
+            .unwrap();
+        for ((_, aux1), (_, aux2)) in voxel.cells.iter_mut().zip(refined.cells.iter_mut()) {
+            let force1 = aux1.get_current_force_and_reset();
+            let force2 = aux2.get_current_force_and_reset();
+            assert!((force1 - force2).abs() < 1e-10);
+            assert_eq!(aux1.get_current_neighbors(), aux2.get_current_neighbors());
+        }
+    }
+
+    #[test]
+    fn leaves_cover_close_pairs() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
+        let coordinates: Vec<[f64; 2]> = (0..500)
+            .map(|_| [rng.gen_range(0.0..10.0), rng.gen_range(0.0..10.0)])
+            .collect();
+        let refinement = VoxelRefinement::new(0.5).max_cells_per_leaf(8);
+        let tree = RefinementTree::new(&coordinates, &refinement);
+        assert!(tree.leaves().len() > 1);
+        let pairs: BTreeSet<_> = tree.pairs(0.5).into_iter().collect();
+        // Every pair is considered at most once
+        assert_eq!(pairs.len(), tree.pairs(0.5).len());
+        let n_close = (0..coordinates.len())
+            .flat_map(|n| (n + 1..coordinates.len()).map(move |m| (n, m)))
+            .filter(|(n, m)| {
+                let [x1, y1] = coordinates[*n];
+                let [x2, y2] = coordinates[*m];
+                let close = (x1 - x2).powi(2) + (y1 - y2).powi(2) <= 0.25;
+                assert!(!close || pairs.contains(&(*n, *m)));
+                close
+            })
+            .count();
+        // Far fewer pairs than all combinations are considered
+        assert!(n_close > 0);
+        assert!(pairs.len() < coordinates.len() * (coordinates.len() - 1) / 4);
+
+        let mut refined_voxels = BTreeSet::new();
+        let refinement = VoxelRefinement::new(1.0).refine_above(10).merge_below(5);
+        let voxel = VoxelPlainIndex(3);
+        assert!(!refinement.update(voxel, 8, &mut refined_voxels));
+        assert!(refinement.update(voxel, 11, &mut refined_voxels));
+        assert!(refinement.update(voxel, 8, &mut refined_voxels));
+        assert!(!refinement.update(voxel, 4, &mut refined_voxels));
+    }
+}
@@ -0,0 +1,259 @@
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use cellular_raza_concepts::{Position, SubDomain};
+use serde::Serialize;
+
+use super::{CellBox, CellIdentifier, OverlapMeasure, SubDomainBox, SubDomainPlainIndex};
+use crate::storage::{StorageError, StorageInterfaceStore};
+use crate::time::{NextTimePoint, TimeEvent};
+
+/// Decides if a cell is stored
+type CellFilter<C> = Arc<dyn Fn(&C) -> bool + Send + Sync>;
+
+/// Restricts a subdomain before it is stored
+type SubDomainRestriction<S> = Arc<dyn Fn(&S) -> Option<S> + Send + Sync>;
+
+/// Only stores cells and subdomains which lie inside a region of interest.
+///
+/// For large domains, often only a small part of the simulation is relevant for the analysis.
+/// Cells are stored if they satisfy all filters which were added to the region.
+/// Subdomains can be restricted to the parts which should be stored, for example by cropping
+/// their extracellular fields, or skipped entirely.
+/// Without any filters, all results are stored.
+///
+/// The region is passed to the [run_simulation](crate::backend::chili::run_simulation) macro
+/// via the `storage_region` argument.
+/// ```
+/// # use cellular_raza_core::backend::chili::StorageRegion;
+/// # use cellular_raza_concepts::Position;
+/// #[derive(Clone)]
+/// struct Bacterium {
+///     pos: [f64; 2],
+///     species: usize,
+/// }
+/// # impl Position<[f64; 2]> for Bacterium {
+/// #     fn pos(&self) -> [f64; 2] { self.pos }
+/// #     fn set_pos(&mut self, pos: &[f64; 2]) { self.pos = *pos; }
+/// # }
+/// # impl cellular_raza_core::backend::chili::OverlapMeasure<[f64; 2], f64> for Bacterium {
+/// #     fn overlap_radius(&self) -> f64 { 1.0 }
+/// #     fn distance(p1: &[f64; 2], p2: &[f64; 2]) -> f64 {
+/// #         ((p1[0] - p2[0]).powi(2) + (p1[1] - p2[1]).powi(2)).sqrt()
+/// #     }
+/// # }
+/// // Only store cells of the second species within 20µm of the origin
+/// let region = StorageRegion::<Bacterium, ()>::new()
+///     .within_distance([0.0; 2], 20.0)
+///     .with_labels(|cell| cell.species, [1]);
+/// assert!(region.contains_cell(&Bacterium { pos: [10.0, 10.0], species: 1 }));
+/// assert!(!region.contains_cell(&Bacterium { pos: [20.0, 10.0], species: 1 }));
+/// assert!(!region.contains_cell(&Bacterium { pos: [0.0, 0.0], species: 0 }));
+/// ```
+pub struct StorageRegion<C, S> {
+    /// Filters which all stored cells need to pass
+    cell_filters: Vec<CellFilter<C>>,
+    /// Restricts stored subdomains to those which intersect the region
+    subdomain_restriction: Option<SubDomainRestriction<S>>,
+}
+
+impl<C, S> Clone for StorageRegion<C, S> {
+    fn clone(&self) -> Self {
+        Self {
+            cell_filters: self.cell_filters.clone(),
+            subdomain_restriction: self.subdomain_restriction.clone(),
+        }
+    }
+}
+
+impl<C, S> Default for StorageRegion<C, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C, S> StorageRegion<C, S> {
+    /// Stores all cells and subdomains until filters are added.
+    pub fn new() -> Self {
+        Self {
+            cell_filters: Vec::new(),
+            subdomain_restriction: None,
+        }
+    }
+
+    /// Only stores cells for which the given function returns `true`.
+    pub fn filter_cells(mut self, filter: impl Fn(&C) -> bool + Send + Sync + 'static) -> Self {
+        self.cell_filters.push(Arc::new(filter));
+        self
+    }
+
+    /// Only stores cells whose position is at most `radius` away from the `center`.
+    pub fn within_distance<Pos, F>(self, center: Pos, radius: F) -> Self
+    where
+        C: Position<Pos> + OverlapMeasure<Pos, F>,
+        Pos: Send + Sync + 'static,
+        F: PartialOrd + Send + Sync + 'static,
+    {
+        self.filter_cells(move |cell| C::distance(&cell.pos(), &center) <= radius)
+    }
+
+    /// Only stores cells whose label is contained in the given set of labels.
+    ///
+    /// Labels can be any property of the cell such as its species.
+    pub fn with_labels<L>(
+        self,
+        label: impl Fn(&C) -> L + Send + Sync + 'static,
+        labels: impl IntoIterator<Item = L>,
+    ) -> Self
+    where
+        L: Ord + Send + Sync + 'static,
+    {
+        let labels: BTreeSet<L> = labels.into_iter().collect();
+        self.filter_cells(move |cell| labels.contains(&label(cell)))
+    }
+
+    /// Restricts subdomains to the parts which should be stored.
+    ///
+    /// Subdomains for which the given function returns `None` are not stored.
+    pub fn restrict_subdomains(
+        self,
+        restriction: impl Fn(&S) -> Option<S> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            subdomain_restriction: Some(Arc::new(restriction)),
+            ..self
+        }
+    }
+
+    /// Checks if the cell satisfies all filters.
+    pub fn contains_cell(&self, cell: &C) -> bool {
+        self.cell_filters.iter().all(|filter| filter(cell))
+    }
+
+    /// Part of the subdomain which should be stored.
+    pub fn restrict_subdomain(&self, subdomain: &S) -> Option<S>
+    where
+        S: Clone,
+    {
+        match &self.subdomain_restriction {
+            Some(restriction) => restriction(subdomain),
+            None => Some(subdomain.clone()),
+        }
+    }
+}
+
+impl<I, S, C, A, Com, Sy, R> SubDomainBox<I, S, C, A, Com, Sy, R>
+where
+    S: SubDomain,
+{
+    /// Stores the subdomain restricted to the given [StorageRegion].
+    ///
+    /// See [SubDomainBox::save_subdomains].
+    pub fn save_subdomains_in_region<F, Sto>(
+        &self,
+        storage_manager: &mut Sto,
+        next_time_point: &NextTimePoint<F>,
+        region: &StorageRegion<C, S>,
+    ) -> Result<(), StorageError>
+    where
+        S: Clone + Serialize,
+        Sto: StorageInterfaceStore<SubDomainPlainIndex, S>,
+    {
+        if let Some(TimeEvent::PartialSave) = next_time_point.event {
+            if let Some(subdomain) = region.restrict_subdomain(&self.subdomain) {
+                storage_manager.store_single_element(
+                    next_time_point.iteration as u64,
+                    &self.subdomain_plain_index,
+                    &subdomain,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Stores all cells of the subdomain which are contained in the given [StorageRegion].
+    ///
+    /// See [SubDomainBox::save_cells].
+    pub fn save_cells_in_region<F, Sto>(
+        &self,
+        storage_manager: &mut Sto,
+        next_time_point: &NextTimePoint<F>,
+        region: &StorageRegion<C, S>,
+    ) -> Result<(), StorageError>
+    where
+        A: Clone + Serialize,
+        C: Clone + Serialize,
+        Sto: StorageInterfaceStore<CellIdentifier, (CellBox<C>, A)>,
+    {
+        if let Some(TimeEvent::PartialSave) = next_time_point.event {
+            let cells = self
+                .voxels
+                .values()
+                .flat_map(|vox| vox.cells.iter())
+                .filter(|(cbox, _)| region.contains_cell(&cbox.cell))
+                .map(|ca| (&ca.0.identifier, ca));
+            storage_manager.store_batch_elements(next_time_point.iteration as u64, cells)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_region {
+    use super::*;
+
+    #[derive(Clone)]
+    struct Agent {
+        pos: [f64; 2],
+        species: u8,
+    }
+
+    impl Position<[f64; 2]> for Agent {
+        fn pos(&self) -> [f64; 2] {
+            self.pos
+        }
+
+        fn set_pos(&mut self, pos: &[f64; 2]) {
+            self.pos = *pos;
+        }
+    }
+
+    impl OverlapMeasure<[f64; 2], f64> for Agent {
+        fn overlap_radius(&self) -> f64 {
+            1.0
+        }
+
+        fn distance(p1: &[f64; 2], p2: &[f64; 2]) -> f64 {
+            ((p1[0] - p2[0]).powi(2) + (p1[1] - p2[1]).powi(2)).sqrt()
+        }
+    }
+
+    #[test]
+    fn filter_cells() {
+        let agent = |x, species| Agent {
+            pos: [x, 0.0],
+            species,
+        };
+        let region = StorageRegion::<Agent, Vec<f64>>::new();
+        assert!(region.contains_cell(&agent(1e6, 0)));
+        let region = region.within_distance([1.0, 0.0], 2.0);
+        assert!(region.contains_cell(&agent(3.0, 0)));
+        assert!(!region.contains_cell(&agent(-1.5, 0)));
+        let region = region.with_labels(|agent| agent.species, [1, 2]);
+        assert!(!region.contains_cell(&agent(0.0, 0)));
+        assert!(region.contains_cell(&agent(0.0, 2)));
+    }
+
+    #[test]
+    fn restrict_subdomains() {
+        let field = vec![1.0, 2.0, 3.0, 4.0];
+        let region = StorageRegion::<Agent, Vec<f64>>::new();
+        assert_eq!(region.restrict_subdomain(&field), Some(field.clone()));
+        let region = region.restrict_subdomains(|field| match field.len() {
+            0 => None,
+            n => Some(field[..n / 2].to_vec()),
+        });
+        assert_eq!(region.restrict_subdomain(&field), Some(vec![1.0, 2.0]));
+        assert_eq!(region.restrict_subdomain(&vec![]), None);
+    }
+}
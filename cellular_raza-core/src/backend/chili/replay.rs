@@ -0,0 +1,230 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Deserialize;
+
+use super::{
+    CellBox, CellIdentifier, IndexError, SimulationError, StorageAccess, SubDomainPlainIndex,
+};
+use crate::storage::StorageInterfaceLoad;
+
+/// All cells and subdomains which were stored at one iteration of a finished simulation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReplayFrame<C, S> {
+    /// Iteration at which the frame was stored
+    pub iteration: u64,
+    /// Stored cells of all subdomains
+    pub cells: BTreeMap<CellIdentifier, C>,
+    /// Stored subdomains
+    pub subdomains: BTreeMap<SubDomainPlainIndex, S>,
+}
+
+/// Derived observable which is calculated from stored frames instead of a running simulation.
+///
+/// Observers are fed by [StorageAccess::replay] and receive all frames ordered by their
+/// iteration.
+/// Closures of the form `FnMut(&ReplayFrame<C, S>) -> Result<(), SimulationError>` implement
+/// this trait directly.
+pub trait ReplayObserver<C, S> {
+    /// Updates the observable with the next stored frame.
+    fn observe(&mut self, frame: &ReplayFrame<C, S>) -> Result<(), SimulationError>;
+
+    /// Called once after the last frame, for example to write plots or summaries.
+    fn finish(&mut self) -> Result<(), SimulationError> {
+        Ok(())
+    }
+}
+
+impl<C, S, F> ReplayObserver<C, S> for F
+where
+    F: FnMut(&ReplayFrame<C, S>) -> Result<(), SimulationError>,
+{
+    fn observe(&mut self, frame: &ReplayFrame<C, S>) -> Result<(), SimulationError> {
+        self(frame)
+    }
+}
+
+impl<C, A, S, D> StorageAccess<(CellBox<C>, A), S, D>
+where
+    C: for<'a> Deserialize<'a> + Clone,
+    A: for<'a> Deserialize<'a> + Clone,
+    S: for<'a> Deserialize<'a> + Clone,
+{
+    /// Feeds every stored frame of a finished simulation through the given observers.
+    ///
+    /// This allows to calculate new statistics or plots without re-running the simulation.
+    /// Frames contain all iterations at which either cells or subdomains were stored.
+    /// Every observer sees one frame after the other and is [finished](ReplayObserver::finish)
+    /// after the last one.
+    /// Returns the number of replayed frames.
+    /// ```
+    /// # use cellular_raza_core::backend::chili::*;
+    /// # use cellular_raza_core::storage::*;
+    /// # let dir = tempfile::tempdir()?;
+    /// let mut storage =
+    ///     StorageAccess::<(CellBox<f64>, ()), f64>::open(dir.path(), [StorageOption::Memory])?;
+    /// // Store two cells at iteration 10
+    /// for counter in 0..2 {
+    ///     let identifier = CellIdentifier(VoxelPlainIndex::new(0), counter);
+    ///     let cbox = CellBox { identifier, parent: None, cell: counter as f64 };
+    ///     storage.cells.store_single_element(10, &identifier, &(cbox, ()))?;
+    /// }
+    /// // Calculate the mean of all cells at every stored iteration
+    /// let mut means = Vec::new();
+    /// let n_frames = storage.replay(&mut [&mut |frame: &ReplayFrame<f64, f64>| {
+    ///     let sum: f64 = frame.cells.values().sum();
+    ///     means.push((frame.iteration, sum / frame.cells.len() as f64));
+    ///     Ok(())
+    /// }])?;
+    /// assert_eq!(n_frames, 1);
+    /// assert_eq!(means, vec![(10, 0.5)]);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn replay(
+        &self,
+        observers: &mut [&mut dyn ReplayObserver<C, S>],
+    ) -> Result<usize, SimulationError> {
+        let mut iterations: BTreeSet<u64> = self.cells.get_all_iterations()?.into_iter().collect();
+        iterations.extend(self.subdomains.get_all_iterations()?);
+        self.replay_iterations(iterations, observers)
+    }
+
+    /// Feeds only the frames at the given iterations through the observers.
+    ///
+    /// Iterations are replayed in ascending order and duplicates are skipped.
+    /// Returns an [SimulationError::IndexError] if nothing was stored at one of the iterations.
+    pub fn replay_iterations(
+        &self,
+        iterations: impl IntoIterator<Item = u64>,
+        observers: &mut [&mut dyn ReplayObserver<C, S>],
+    ) -> Result<usize, SimulationError> {
+        let iterations: BTreeSet<u64> = iterations.into_iter().collect();
+        let cell_iterations: BTreeSet<u64> = self.cells.get_all_iterations()?.into_iter().collect();
+        let subdomain_iterations: BTreeSet<u64> =
+            self.subdomains.get_all_iterations()?.into_iter().collect();
+        if let Some(iteration) = iterations
+            .iter()
+            .find(|i| !cell_iterations.contains(i) && !subdomain_iterations.contains(i))
+        {
+            return Err(SimulationError::IndexError(IndexError(format!(
+                "no cells or subdomains were stored at iteration {iteration}"
+            ))));
+        }
+        for &iteration in iterations.iter() {
+            let cells = match cell_iterations.contains(&iteration) {
+                true => self
+                    .cells
+                    .load_all_elements_at_iteration(iteration)?
+                    .into_iter()
+                    .map(|(identifier, (cbox, _))| (identifier, cbox.cell))
+                    .collect(),
+                false => BTreeMap::new(),
+            };
+            let subdomains = match subdomain_iterations.contains(&iteration) {
+                true => self
+                    .subdomains
+                    .load_all_elements_at_iteration(iteration)?
+                    .into_iter()
+                    .collect(),
+                false => BTreeMap::new(),
+            };
+            let frame = ReplayFrame {
+                iteration,
+                cells,
+                subdomains,
+            };
+            for observer in observers.iter_mut() {
+                observer.observe(&frame)?;
+            }
+        }
+        for observer in observers.iter_mut() {
+            observer.finish()?;
+        }
+        Ok(iterations.len())
+    }
+}
+
+#[cfg(test)]
+mod test_replay {
+    use super::*;
+    use crate::backend::chili::VoxelPlainIndex;
+    use crate::storage::{StorageInterfaceStore, StorageOption};
+
+    type Access = StorageAccess<(CellBox<[f64; 2]>, ()), Vec<f64>>;
+
+    fn store_run(path: &std::path::Path) -> Access {
+        let mut access = Access::open(path, [StorageOption::SerdeJson]).unwrap();
+        for (iteration, counter, pos) in [(0, 0, [0.0, 1.0]), (0, 1, [2.0, 1.0]), (20, 0, [1.0; 2])]
+        {
+            let identifier = CellIdentifier(VoxelPlainIndex::new(0), counter);
+            let cbox = CellBox {
+                identifier,
+                parent: None,
+                cell: pos,
+            };
+            access
+                .cells
+                .store_single_element(iteration, &identifier, &(cbox, ()))
+                .unwrap();
+        }
+        for iteration in [0, 10] {
+            access
+                .subdomains
+                .store_single_element(iteration, &SubDomainPlainIndex(0), &vec![1.0, 2.0])
+                .unwrap();
+        }
+        access
+    }
+
+    /// Counts the cells of every frame and whether it was finished
+    #[derive(Default)]
+    struct CellCounter(Vec<(u64, usize)>, bool);
+
+    impl ReplayObserver<[f64; 2], Vec<f64>> for CellCounter {
+        fn observe(
+            &mut self,
+            frame: &ReplayFrame<[f64; 2], Vec<f64>>,
+        ) -> Result<(), SimulationError> {
+            self.0.push((frame.iteration, frame.cells.len()));
+            Ok(())
+        }
+
+        fn finish(&mut self) -> Result<(), SimulationError> {
+            self.1 = true;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn replay_all_frames() {
+        let dir = tempfile::tempdir().unwrap();
+        let access = store_run(dir.path());
+        let mut counter = CellCounter::default();
+        let mut n_subdomains = Vec::new();
+        let n_frames = access
+            .replay(&mut [&mut counter, &mut |frame: &ReplayFrame<
+                [f64; 2],
+                Vec<f64>,
+            >| {
+                n_subdomains.push(frame.subdomains.len());
+                Ok(())
+            }])
+            .unwrap();
+        assert_eq!(n_frames, 3);
+        assert_eq!(counter.0, vec![(0, 2), (10, 0), (20, 1)]);
+        assert!(counter.1);
+        assert_eq!(n_subdomains, vec![1, 1, 0]);
+    }
+
+    #[test]
+    fn replay_selected_iterations() {
+        let dir = tempfile::tempdir().unwrap();
+        let access = store_run(dir.path());
+        let mut counter = CellCounter::default();
+        access
+            .replay_iterations([20, 0, 20], &mut [&mut counter])
+            .unwrap();
+        assert_eq!(counter.0, vec![(0, 2), (20, 1)]);
+        let result = access.replay_iterations([5], &mut [&mut counter]);
+        assert!(matches!(result, Err(SimulationError::IndexError(_))));
+    }
+}
@@ -0,0 +1,435 @@
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use cellular_raza_concepts::{DrawingError, PlotField, SubDomain};
+use serde::{Deserialize, Serialize};
+
+use super::{CellBox, ReplayFrame, ReplayObserver, SimulationError, StorageAccess, SubDomainBox};
+use crate::plotting::Colormap;
+use crate::storage::StorageInterfaceLoad;
+use crate::time::{NextTimePoint, TimeEvent};
+
+/// Describes how a cell is displayed in the [rerun](https://rerun.io) viewer.
+///
+/// Cells are shown as spheres which are colored by their species.
+/// Two-dimensional simulations should set the last component of the position to zero.
+/// ```
+/// # use cellular_raza_core::backend::chili::RerunCell;
+/// struct Bacterium {
+///     pos: [f64; 2],
+///     radius: f64,
+///     infected: bool,
+/// }
+///
+/// impl RerunCell for Bacterium {
+///     fn rerun_position(&self) -> [f64; 3] {
+///         [self.pos[0], self.pos[1], 0.0]
+///     }
+///
+///     fn rerun_radius(&self) -> f64 {
+///         self.radius
+///     }
+///
+///     fn rerun_species(&self) -> usize {
+///         self.infected as usize
+///     }
+/// }
+/// # let b = Bacterium { pos: [1.0, 2.0], radius: 0.5, infected: true };
+/// # assert_eq!(b.rerun_species(), 1);
+/// ```
+pub trait RerunCell {
+    /// Position of the center of the cell
+    fn rerun_position(&self) -> [f64; 3];
+
+    /// Radius of the sphere which represents the cell
+    fn rerun_radius(&self) -> f64;
+
+    /// Species of the cell which selects its color from [RerunSettings::species_colors]
+    fn rerun_species(&self) -> usize {
+        0
+    }
+}
+
+/// Determines where the data is sent to.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub enum RerunSink {
+    /// Starts a new viewer which requires the `rerun` binary to be installed.
+    #[default]
+    Spawn,
+    /// Connects to a running viewer at the given address.
+    Connect(SocketAddr),
+    /// Writes an `.rrd` file which can be opened by the viewer after the simulation.
+    File(PathBuf),
+}
+
+/// Configures the [RerunLogger].
+/// ```
+/// # use cellular_raza_core::backend::chili::{RerunSettings, RerunSink};
+/// let settings = RerunSettings {
+///     sink: RerunSink::File("out/bacteria.rrd".into()),
+///     field_species: Some(0),
+///     ..RerunSettings::new("bacteria")
+/// };
+/// assert_eq!(settings.species_colors[0], [31, 119, 180]);
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct RerunSettings {
+    /// Identifies the application in the viewer
+    pub application_id: String,
+    /// Destination of the logged data
+    pub sink: RerunSink,
+    /// Root of all logged entities
+    pub entity_path: String,
+    /// Colors of the species given by [RerunCell::rerun_species].
+    /// Species with a larger index than colors are available reuse the colors in order.
+    pub species_colors: Vec<[u8; 3]>,
+    /// Species of the [PlotField] whose slices are logged.
+    /// No fields are logged if not specified.
+    pub field_species: Option<usize>,
+    /// Colormap of the field slices
+    pub colormap: Colormap,
+    /// Range of values which is mapped onto the colormap.
+    /// If not specified, every slice uses its own minimum and maximum.
+    pub value_range: Option<(f64, f64)>,
+    /// Height at which the slices of two-dimensional fields are shown
+    pub field_height: f64,
+}
+
+impl RerunSettings {
+    /// Default settings for the given application
+    pub fn new(application_id: impl Into<String>) -> Self {
+        Self {
+            application_id: application_id.into(),
+            sink: RerunSink::Spawn,
+            entity_path: "simulation".to_owned(),
+            species_colors: vec![
+                [31, 119, 180],
+                [255, 127, 14],
+                [44, 160, 44],
+                [214, 39, 40],
+                [148, 103, 189],
+                [140, 86, 75],
+                [227, 119, 194],
+                [127, 127, 127],
+                [188, 189, 34],
+                [23, 190, 207],
+            ],
+            field_species: None,
+            colormap: Colormap::Viridis,
+            value_range: None,
+            field_height: 0.0,
+        }
+    }
+}
+
+/// Streams cells and fields to the [rerun](https://rerun.io) viewer.
+///
+/// Every saved iteration is shown on the `iteration` timeline and, while the simulation is
+/// running, also on the `time` timeline.
+/// Cells are logged as spheres below `<entity_path>/cells` and slices of extracellular fields
+/// as flat boxes below `<entity_path>/fields`.
+///
+/// During a simulation, the logger is passed to the
+/// [run_simulation](crate::backend::chili::run_simulation) macro via the `rerun` argument.
+/// Each subdomain then logs its cells at every save point which requires the cells to
+/// implement [RerunCell].
+/// If the simulation contains the `ReactionsExtra` aspect, the subdomains additionally need to
+/// implement [PlotField].
+/// After a simulation, results can be loaded from its [StorageAccess] via
+/// [RerunLogger::log_stored_cells] and [RerunLogger::log_stored_fields] or by passing the
+/// logger as a [ReplayObserver] to [StorageAccess::replay].
+///
+/// This logger requires the `rerun` feature.
+#[derive(Clone)]
+pub struct RerunLogger {
+    /// Settings which were used to create the logger
+    pub settings: RerunSettings,
+    /// Recording which is shared by all clones
+    stream: rerun::RecordingStream,
+}
+
+/// Converts errors of the viewer into [SimulationError]s.
+fn rerun_error(error: impl std::fmt::Display) -> SimulationError {
+    SimulationError::DrawingError(DrawingError(error.to_string()))
+}
+
+/// Positions, radii and colors of the given cells
+fn cell_batch<'a, C: RerunCell + 'a>(
+    cells: impl IntoIterator<Item = &'a C>,
+    species_colors: &[[u8; 3]],
+) -> (Vec<[f32; 3]>, Vec<f32>, Vec<[u8; 3]>) {
+    let mut batch = (Vec::new(), Vec::new(), Vec::new());
+    for cell in cells {
+        batch.0.push(cell.rerun_position().map(|x| x as f32));
+        batch.1.push(cell.rerun_radius() as f32);
+        batch.2.push(match species_colors.len() {
+            0 => [31, 119, 180],
+            n => species_colors[cell.rerun_species() % n],
+        });
+    }
+    batch
+}
+
+/// Centers, half sizes and colors of the boxes which represent a slice of the field
+fn field_boxes(
+    rectangles: &[([f64; 2], [f64; 2], f64)],
+    settings: &RerunSettings,
+) -> (Vec<[f32; 3]>, Vec<[f32; 3]>, Vec<[u8; 3]>) {
+    let value_range = settings.value_range.unwrap_or_else(|| {
+        rectangles.iter().fold(
+            (f64::INFINITY, f64::NEG_INFINITY),
+            |(min, max), (_, _, v)| (min.min(*v), max.max(*v)),
+        )
+    });
+    let mut boxes = (Vec::new(), Vec::new(), Vec::new());
+    for (lower, upper, value) in rectangles {
+        boxes.0.push([
+            (0.5 * (lower[0] + upper[0])) as f32,
+            (0.5 * (lower[1] + upper[1])) as f32,
+            settings.field_height as f32,
+        ]);
+        boxes.1.push([
+            (0.5 * (upper[0] - lower[0])) as f32,
+            (0.5 * (upper[1] - lower[1])) as f32,
+            0.0,
+        ]);
+        boxes.2.push(settings.colormap.color(*value, value_range));
+    }
+    boxes
+}
+
+impl RerunLogger {
+    /// Starts a new recording which sends its data to the [RerunSink].
+    pub fn new(settings: RerunSettings) -> Result<Self, SimulationError> {
+        let builder = rerun::RecordingStreamBuilder::new(settings.application_id.as_str());
+        let stream = match &settings.sink {
+            RerunSink::Spawn => builder.spawn(),
+            RerunSink::Connect(address) => {
+                builder.connect_tcp_opts(*address, rerun::default_flush_timeout())
+            }
+            RerunSink::File(path) => builder.save(path),
+        }
+        .map_err(rerun_error)?;
+        Ok(Self { settings, stream })
+    }
+
+    /// Logs the given cells at the iteration and optionally at the simulated time.
+    ///
+    /// The time of the recording is set for the current thread only.
+    pub fn log_cells<'a, C>(
+        &self,
+        entity: &str,
+        iteration: u64,
+        time: Option<f64>,
+        cells: impl IntoIterator<Item = &'a C>,
+    ) -> Result<(), SimulationError>
+    where
+        C: RerunCell + 'a,
+    {
+        self.set_time(iteration, time);
+        let (positions, radii, colors) = cell_batch(cells, &self.settings.species_colors);
+        let points = rerun::Points3D::new(positions)
+            .with_radii(radii)
+            .with_colors(
+                colors
+                    .into_iter()
+                    .map(|[r, g, b]| rerun::Color::from_rgb(r, g, b)),
+            );
+        self.stream
+            .log(
+                format!("{}/cells/{entity}", self.settings.entity_path),
+                &points,
+            )
+            .map_err(rerun_error)
+    }
+
+    /// Logs the slice of the field selected by [RerunSettings::field_species].
+    ///
+    /// Does nothing if no species was selected.
+    pub fn log_field(
+        &self,
+        entity: &str,
+        iteration: u64,
+        time: Option<f64>,
+        field: &impl PlotField,
+    ) -> Result<(), SimulationError> {
+        let Some(species) = self.settings.field_species else {
+            return Ok(());
+        };
+        self.set_time(iteration, time);
+        let (centers, half_sizes, colors) =
+            field_boxes(&field.field_rectangles(species), &self.settings);
+        let boxes = rerun::Boxes3D::from_centers_and_half_sizes(centers, half_sizes).with_colors(
+            colors
+                .into_iter()
+                .map(|[r, g, b]| rerun::Color::from_rgb(r, g, b)),
+        );
+        self.stream
+            .log(
+                format!("{}/fields/{entity}", self.settings.entity_path),
+                &boxes,
+            )
+            .map_err(rerun_error)
+    }
+
+    /// Sets the time of the recording for the current thread
+    fn set_time(&self, iteration: u64, time: Option<f64>) {
+        self.stream
+            .set_time_sequence("iteration", iteration.min(i64::MAX as u64) as i64);
+        if let Some(time) = time {
+            self.stream.set_time_seconds("time", time);
+        }
+    }
+
+    /// Logs the cells of the subdomain at every save point of the simulation.
+    pub fn log_subdomain_cells<F, I, S, C, A, Com, Sy, R>(
+        &self,
+        sbox: &SubDomainBox<I, S, C, A, Com, Sy, R>,
+        next_time_point: &NextTimePoint<F>,
+    ) -> Result<(), SimulationError>
+    where
+        F: Clone + Into<f64>,
+        S: SubDomain,
+        C: RerunCell,
+    {
+        if let Some(TimeEvent::PartialSave) = next_time_point.event {
+            self.log_cells(
+                &sbox.subdomain_plain_index.0.to_string(),
+                next_time_point.iteration as u64,
+                Some(next_time_point.time.clone().into()),
+                sbox.iter_cells().map(|cbox| &cbox.cell),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Logs the field of the subdomain at every save point of the simulation.
+    pub fn log_subdomain_field<F, I, S, C, A, Com, Sy, R>(
+        &self,
+        sbox: &SubDomainBox<I, S, C, A, Com, Sy, R>,
+        next_time_point: &NextTimePoint<F>,
+    ) -> Result<(), SimulationError>
+    where
+        F: Clone + Into<f64>,
+        S: SubDomain + PlotField,
+    {
+        if let Some(TimeEvent::PartialSave) = next_time_point.event {
+            self.log_field(
+                &sbox.subdomain_plain_index.0.to_string(),
+                next_time_point.iteration as u64,
+                Some(next_time_point.time.clone().into()),
+                &sbox.subdomain,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Logs all stored cells of a finished simulation on the `iteration` timeline.
+    pub fn log_stored_cells<C, A, S, D>(
+        &self,
+        storage: &StorageAccess<(CellBox<C>, A), S, D>,
+    ) -> Result<(), SimulationError>
+    where
+        C: RerunCell + Clone + for<'a> Deserialize<'a>,
+        A: Clone + for<'a> Deserialize<'a>,
+    {
+        let mut iterations = storage.cells.get_all_iterations()?;
+        iterations.sort();
+        for iteration in iterations {
+            let cells = storage.cells.load_all_elements_at_iteration(iteration)?;
+            let cells = cells.values().map(|(cbox, _)| &cbox.cell);
+            self.log_cells("all", iteration, None, cells)?;
+        }
+        Ok(())
+    }
+
+    /// Logs the slices of all stored subdomains of a finished simulation.
+    pub fn log_stored_fields<C, S, D>(
+        &self,
+        storage: &StorageAccess<C, S, D>,
+    ) -> Result<(), SimulationError>
+    where
+        S: PlotField + Clone + for<'a> Deserialize<'a>,
+    {
+        let mut iterations = storage.subdomains.get_all_iterations()?;
+        iterations.sort();
+        for iteration in iterations {
+            for (index, subdomain) in storage
+                .subdomains
+                .load_all_elements_at_iteration(iteration)?
+            {
+                self.log_field(&index.0.to_string(), iteration, None, &subdomain)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Blocks until all logged data was sent to the [RerunSink].
+    pub fn flush(&self) {
+        self.stream.flush_blocking();
+    }
+}
+
+impl<C, S> ReplayObserver<C, S> for RerunLogger
+where
+    C: RerunCell,
+    S: PlotField,
+{
+    fn observe(&mut self, frame: &ReplayFrame<C, S>) -> Result<(), SimulationError> {
+        self.log_cells("all", frame.iteration, None, frame.cells.values())?;
+        for (index, subdomain) in frame.subdomains.iter() {
+            self.log_field(&index.0.to_string(), frame.iteration, None, subdomain)?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), SimulationError> {
+        self.flush();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_rerun_logger {
+    use super::*;
+
+    struct Cell(f64, usize);
+
+    impl RerunCell for Cell {
+        fn rerun_position(&self) -> [f64; 3] {
+            [self.0, 0.0, 0.0]
+        }
+
+        fn rerun_radius(&self) -> f64 {
+            0.5
+        }
+
+        fn rerun_species(&self) -> usize {
+            self.1
+        }
+    }
+
+    #[test]
+    fn species_colors_repeat() {
+        let cells = [Cell(0.0, 0), Cell(1.0, 1), Cell(2.0, 2)];
+        let colors = [[255, 0, 0], [0, 0, 255]];
+        let (positions, radii, colors) = cell_batch(&cells, &colors);
+        assert_eq!(positions[2], [2.0, 0.0, 0.0]);
+        assert_eq!(radii, vec![0.5; 3]);
+        assert_eq!(colors, vec![[255, 0, 0], [0, 0, 255], [255, 0, 0]]);
+    }
+
+    #[test]
+    fn field_slice_boxes() {
+        let settings = RerunSettings {
+            colormap: Colormap::BlackWhite,
+            field_height: -1.0,
+            ..RerunSettings::new("test")
+        };
+        let rectangles = [([0.0, 0.0], [1.0, 2.0], 3.0), ([1.0, 0.0], [2.0, 2.0], 5.0)];
+        let (centers, half_sizes, colors) = field_boxes(&rectangles, &settings);
+        assert_eq!(centers, vec![[0.5, 1.0, -1.0], [1.5, 1.0, -1.0]]);
+        assert_eq!(half_sizes[1], [0.5, 1.0, 0.0]);
+        assert_eq!(colors, vec![[0, 0, 0], [255, 255, 255]]);
+    }
+}
@@ -1,15 +1,61 @@
-use super::{CellIdentifier, SimulationError, SubDomainPlainIndex};
-use crate::storage::StorageManager;
+use super::{CellBox, CellIdentifier, SimulationError, SubDomainPlainIndex};
+use crate::storage::{StorageBuilder, StorageInterfaceLoad, StorageManager, StorageOption};
+
+use cellular_raza_concepts::CustomData;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 
 /// Gathers the [StorageManager] for cells and voxels of the previously run simulation
-pub struct StorageAccess<C, S> {
+pub struct StorageAccess<C, S, D = ()> {
     /// Access cells at their saved iteration steps
     pub cells: StorageManager<CellIdentifier, C>,
     /// Access voxels at their saved iteration steps
     pub subdomains: StorageManager<SubDomainPlainIndex, S>,
+    /// Access the [DivisionEvent]s of all cells at the iterations in which they occurred.
+    ///
+    /// Events are keyed by the identifier of the dividing cell.
+    /// This storage only exists if the simulation included the `Cycle` aspect and is `None`
+    /// otherwise.
+    /// In this case, `D` is given by `DivisionEvent<F, P>` where the positions `P` are `()`
+    /// if the simulation did not contain the `Mechanics` aspect.
+    pub divisions: Option<StorageManager<CellIdentifier, D>>,
 }
 
-impl<C, V> StorageAccess<C, V> {
+impl<C, V, D> StorageAccess<C, V, D> {
+    /// Opens the results of a previously run simulation.
+    ///
+    /// The given path should point to the folder returned by [StorageAccess::get_path] which
+    /// contains the `cells`, `subdomains` and `divisions` folders.
+    /// The `divisions` folder is only present if the simulation included the `Cycle` aspect.
+    /// The storage options need to match the ones which were used to store the results.
+    pub fn open(
+        path: impl AsRef<std::path::Path>,
+        priority: impl IntoIterator<Item = StorageOption>,
+    ) -> Result<Self, SimulationError> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Err(SimulationError::IoError(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("could not find stored results at {}", path.display()),
+            )));
+        }
+        let builder = StorageBuilder::new()
+            .location(path)
+            .priority(priority)
+            .init_with_date(std::path::Path::new(""));
+        Ok(Self {
+            cells: StorageManager::open_or_create(builder.clone().suffix("cells"), 0)?,
+            subdomains: StorageManager::open_or_create(builder.clone().suffix("subdomains"), 0)?,
+            divisions: match path.join("divisions").exists() {
+                true => Some(StorageManager::open_or_create(
+                    builder.suffix("divisions"),
+                    0,
+                )?),
+                false => None,
+            },
+        })
+    }
+
     /// Obtain the save path for cells and voxels of this simulation
     pub fn get_path(&self) -> Result<std::path::PathBuf, SimulationError> {
         match self.cells.extract_builder().get_full_path().parent() {
@@ -20,3 +66,47 @@ impl<C, V> StorageAccess<C, V> {
         }
     }
 }
+
+impl<C, A, S, D> StorageAccess<(CellBox<C>, A), S, D>
+where
+    C: for<'a> Deserialize<'a> + Clone,
+    A: for<'a> Deserialize<'a> + Clone,
+{
+    /// Loads the [CustomData] of type `T` of all cells at the given iteration.
+    ///
+    /// The auxiliary storage `A` can be replaced by [serde::de::IgnoredAny] if the storage
+    /// format is self-describing.
+    pub fn load_custom_data_at_iteration<T>(
+        &self,
+        iteration: u64,
+    ) -> Result<HashMap<CellIdentifier, T>, SimulationError>
+    where
+        C: CustomData<T>,
+        T: Serialize + Clone,
+    {
+        Ok(self
+            .cells
+            .load_all_elements_at_iteration(iteration)?
+            .into_iter()
+            .map(|(identifier, (cbox, _))| (identifier, cbox.cell.custom_data().clone()))
+            .collect())
+    }
+
+    /// Loads the [CustomData] of type `T` of a single cell at every iteration at which it was
+    /// stored.
+    pub fn load_custom_data_history<T>(
+        &self,
+        identifier: &CellIdentifier,
+    ) -> Result<BTreeMap<u64, T>, SimulationError>
+    where
+        C: CustomData<T>,
+        T: Serialize + Clone,
+    {
+        Ok(self
+            .cells
+            .load_element_history(identifier)?
+            .into_iter()
+            .map(|(iteration, (cbox, _))| (iteration, cbox.cell.custom_data().clone()))
+            .collect())
+    }
+}
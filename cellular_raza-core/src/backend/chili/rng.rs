@@ -0,0 +1,355 @@
+use rand::{RngCore, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use super::CellIdentifier;
+
+/// Determines how the random number generators of cells are seeded.
+///
+/// In both modes, cells draw their random numbers from the generator which is given by the
+/// `rng` argument of the [run_simulation](crate::backend::chili::run_simulation) macro and
+/// defaults to [ChaCha8Rng](rand_chacha::ChaCha8Rng).
+/// The mode is passed to the `rng_mode` argument of the
+/// [run_simulation](crate::backend::chili::run_simulation) macro.
+///
+/// ```
+/// # use cellular_raza_core::backend::chili::*;
+/// # use cellular_raza_core::{storage::*, time::*};
+/// # use cellular_raza_concepts::*;
+/// # use rand::{rngs::SmallRng, Rng};
+/// # use serde::{Deserialize, Serialize};
+/// # use std::collections::{BTreeMap, BTreeSet};
+/// #[derive(Clone, Debug, Deserialize, Serialize)]
+/// struct Walker(f64);
+/// # impl Position<f64> for Walker {
+/// #     fn pos(&self) -> f64 {
+/// #         self.0
+/// #     }
+/// #     fn set_pos(&mut self, pos: &f64) {
+/// #         self.0 = *pos
+/// #     }
+/// # }
+/// # impl Velocity<f64> for Walker {
+/// #     fn velocity(&self) -> f64 {
+/// #         0.0
+/// #     }
+/// #     fn set_velocity(&mut self, _: &f64) {}
+/// # }
+///
+/// // The agent only needs to support the chosen generator
+/// impl Mechanics<f64, f64, f64, f64, SmallRng> for Walker {
+///     fn get_random_contribution(
+///         &self,
+///         rng: &mut SmallRng,
+///         _dt: f64,
+///     ) -> Result<(f64, f64), RngError> {
+///         Ok((rng.gen_range(-0.1..0.1), 0.0))
+///     }
+/// #     fn calculate_increment(&self, _: f64) -> Result<(f64, f64), CalcError> {
+/// #         Ok((0.0, 0.0))
+/// #     }
+/// }
+/// # #[derive(Clone, Debug, Deserialize, Serialize)]
+/// # struct MyDomain;
+/// # impl<Ci> Domain<Walker, MyDomain, Ci> for MyDomain
+/// # where
+/// #     Ci: IntoIterator<Item = Walker>
+/// # {
+/// #     type VoxelIndex = usize;
+/// #     type SubDomainIndex = usize;
+/// #     fn decompose(
+/// #         self,
+/// #         _: core::num::NonZeroUsize,
+/// #         cells: Ci,
+/// #     ) -> Result<DecomposedDomain<usize, MyDomain, Walker>, DecomposeError> {
+/// #         Ok(DecomposedDomain {
+/// #             n_subdomains: 1.try_into().unwrap(),
+/// #             index_subdomain_cells: vec![(1, MyDomain, cells.into_iter().collect())],
+/// #             neighbor_map: BTreeMap::from([(1, BTreeSet::new())]),
+/// #             rng_seed: 1,
+/// #         })
+/// #     }
+/// # }
+/// # impl SubDomain for MyDomain {
+/// #     type VoxelIndex = usize;
+/// #     fn get_neighbor_voxel_indices(&self, _: &usize) -> Vec<usize> {
+/// #         Vec::new()
+/// #     }
+/// #     fn get_all_indices(&self) -> Vec<usize> {
+/// #         vec![1]
+/// #     }
+/// # }
+/// # impl SortCells<Walker> for MyDomain {
+/// #     type VoxelIndex = usize;
+/// #     fn get_voxel_index_of(&self, _: &Walker) -> Result<usize, BoundaryError> {
+/// #         Ok(1)
+/// #     }
+/// # }
+/// # impl SubDomainMechanics<f64, f64> for MyDomain {
+/// #     fn apply_boundary(&self, _: &mut f64, _: &mut f64) -> Result<(), BoundaryError> {
+/// #         Ok(())
+/// #     }
+/// # }
+/// let run = || -> Result<BTreeMap<CellIdentifier, f64>, SimulationError> {
+///     let settings = Settings {
+///         n_threads: 1.try_into().unwrap(),
+///         time: FixedStepsize::from_partial_save_interval(0.0, 0.1, 1.0, 0.5)?,
+///         storage: StorageBuilder::new().priority([StorageOption::Memory]),
+///         show_progressbar: false,
+///     };
+///     let agents = (0..5).map(|n| Walker(n as f64));
+///     let storage = run_simulation!(
+///         agents,
+///         domain: MyDomain,
+///         settings,
+///         aspects: [Mechanics],
+///         rng_mode: RngMode::CounterBased,
+///         rng: rand::rngs::SmallRng,
+///         core_path: cellular_raza_core,
+///     )?;
+///     let cells = storage.cells.load_all_elements_at_iteration(10)?;
+///     Ok(cells.into_iter().map(|(id, (cbox, _))| (id, cbox.cell.0)).collect())
+/// };
+/// // Cells have moved randomly but results are reproducible
+/// let positions = run()?;
+/// assert!(positions.values().all(|x| x.fract() != 0.0));
+/// assert_eq!(positions, run()?);
+/// # Ok::<(), SimulationError>(())
+/// ```
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+pub enum RngMode {
+    /// Every voxel owns one generator which is shared by all of its cells.
+    ///
+    /// Results are reproducible but depend on the order in which cells are updated and thus on
+    /// their migration history.
+    #[default]
+    Voxel,
+    /// Every cell obtains a new generator in every step which is seeded counter-based from the
+    /// seed of the domain, the [CellIdentifier] of the cell and the current iteration.
+    ///
+    /// Results are independent of the order in which cells are updated and of the voxel in
+    /// which they reside.
+    /// The seed is drawn from [Philox4x32::for_cell], see [Philox4x32::to_rng].
+    CounterBased,
+}
+
+/// Distinguishes random numbers used for different purposes in the same step.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u64)]
+pub(crate) enum RngStream {
+    /// Local functions such as mechanics and cycle updates
+    Local = 1,
+    /// Division of cells
+    Division = 2,
+    /// Mechanics updates during the [Equilibration](super::Equilibration) phase
+    Equilibration = 3,
+    /// Mechanics updates during the sub-steps of a [DivisionRelaxation](super::DivisionRelaxation)
+    Relaxation = 4,
+}
+
+/// First multiplier of the Philox round function
+const PHILOX_M0: u32 = 0xD2511F53;
+/// Second multiplier of the Philox round function
+const PHILOX_M1: u32 = 0xCD9E8D57;
+/// Weyl constant which is added to the first word of the key after every round
+const PHILOX_W0: u32 = 0x9E3779B9;
+/// Weyl constant which is added to the second word of the key after every round
+const PHILOX_W1: u32 = 0xBB67AE85;
+
+/// Counter-based Philox4x32-10 random number generator
+///
+/// The generator by [Salmon et al. (2011)](https://doi.org/10.1145/2063384.2063405) produces
+/// its output by encrypting a counter with a key.
+/// Thus any element of the random stream can be calculated directly without advancing a state.
+/// This makes it possible to assign independent streams to objects such as cells by encoding
+/// their identity into key and counter.
+///
+/// The first word of the counter enumerates the generated blocks of 4 values while the
+/// remaining three words are free to choose.
+/// ```
+/// # use cellular_raza_core::backend::chili::Philox4x32;
+/// use rand::Rng;
+/// let mut rng1 = Philox4x32::new(42, [0, 1, 2]);
+/// let mut rng2 = Philox4x32::new(42, [0, 1, 2]);
+/// let x: f64 = rng1.gen();
+/// assert_eq!(x, rng2.gen::<f64>());
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct Philox4x32 {
+    /// Key of the generator
+    key: [u32; 2],
+    /// Counter whose first word enumerates the generated blocks
+    counter: [u32; 4],
+    /// Values of the last generated block
+    buffer: [u32; 4],
+    /// Position of the next unused value in the buffer
+    index: usize,
+}
+
+/// Splits a 64-bit integer into its lower and upper half
+fn split(value: u64) -> [u32; 2] {
+    [value as u32, (value >> 32) as u32]
+}
+
+/// SplitMix64 finalizer used to scramble keys
+fn mix64(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E3779B97F4A7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+impl Philox4x32 {
+    /// Constructs a new generator from the given key and the free words of the counter.
+    pub fn new(key: u64, counter: [u32; 3]) -> Self {
+        Self {
+            key: split(key),
+            counter: [0, counter[0], counter[1], counter[2]],
+            buffer: [0; 4],
+            index: 4,
+        }
+    }
+
+    /// Generator which is unique to the given cell and iteration
+    ///
+    /// The seed of the domain is used as key while the identifier of the cell and the
+    /// iteration determine the counter.
+    /// Iterations are only distinguished modulo $2^{32}$.
+    pub fn for_cell(seed: u64, identifier: &CellIdentifier, iteration: u64) -> Self {
+        let id = split(mix64(mix64(identifier.0 .0 as u64) ^ identifier.1));
+        Self::new(seed, [iteration as u32, id[0], id[1]])
+    }
+
+    /// Generator which is unique to the given cell, iteration and stream
+    pub(crate) fn for_cell_stream(
+        seed: u64,
+        identifier: &CellIdentifier,
+        iteration: u64,
+        stream: RngStream,
+    ) -> Self {
+        Self::for_cell(mix64(seed ^ mix64(stream as u64)), identifier, iteration)
+    }
+
+    /// Calculates one block of the Philox4x32-10 function.
+    fn block(counter: [u32; 4], key: [u32; 2]) -> [u32; 4] {
+        let mulhilo = |a: u32, b: u32| {
+            let product = a as u64 * b as u64;
+            ((product >> 32) as u32, product as u32)
+        };
+        let mut ctr = counter;
+        let mut key = key;
+        for round in 0..10 {
+            if round > 0 {
+                key[0] = key[0].wrapping_add(PHILOX_W0);
+                key[1] = key[1].wrapping_add(PHILOX_W1);
+            }
+            let (hi0, lo0) = mulhilo(PHILOX_M0, ctr[0]);
+            let (hi1, lo1) = mulhilo(PHILOX_M1, ctr[2]);
+            ctr = [hi1 ^ ctr[1] ^ key[0], lo1, hi0 ^ ctr[3] ^ key[1], lo0];
+        }
+        ctr
+    }
+
+    /// Seeds a generator of type `R` with the first values of this generator.
+    ///
+    /// Backends use this to seed the generators of cells in [RngMode::CounterBased].
+    pub fn to_rng<R: SeedableRng>(mut self) -> R {
+        let mut seed = R::Seed::default();
+        self.fill_bytes(seed.as_mut());
+        R::from_seed(seed)
+    }
+}
+
+impl RngCore for Philox4x32 {
+    fn next_u32(&mut self) -> u32 {
+        if self.index >= 4 {
+            self.buffer = Self::block(self.counter, self.key);
+            self.counter[0] = self.counter[0].wrapping_add(1);
+            self.index = 0;
+        }
+        let value = self.buffer[self.index];
+        self.index += 1;
+        value
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let low = self.next_u32() as u64;
+        let high = self.next_u32() as u64;
+        (high << 32) | low
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(4) {
+            let bytes = self.next_u32().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for Philox4x32 {
+    type Seed = [u8; 8];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::new(u64::from_le_bytes(seed), [0; 3])
+    }
+}
+
+#[cfg(test)]
+mod test_philox {
+    use super::*;
+    use crate::backend::chili::VoxelPlainIndex;
+    use rand::Rng;
+
+    #[test]
+    fn known_answers() {
+        // Test vectors of the Random123 library
+        assert_eq!(
+            Philox4x32::block([0; 4], [0; 2]),
+            [0x6627e8d5, 0xe169c58d, 0xbc57ac4c, 0x9b00dbd8]
+        );
+        assert_eq!(
+            Philox4x32::block([u32::MAX; 4], [u32::MAX; 2]),
+            [0x408f276d, 0x41c83b0e, 0xa20bc7c6, 0x6d5451fd]
+        );
+        assert_eq!(
+            Philox4x32::block(
+                [0x243f6a88, 0x85a308d3, 0x13198a2e, 0x03707344],
+                [0xa4093822, 0x299f31d0]
+            ),
+            [0xd16cfe09, 0x94fdcceb, 0x5001e420, 0x24126ea1]
+        );
+    }
+
+    #[test]
+    fn independent_of_order() {
+        let ids: Vec<_> = (0..10)
+            .map(|n| CellIdentifier(VoxelPlainIndex(n % 3), n as u64))
+            .collect();
+        let draw = |id: &CellIdentifier| Philox4x32::for_cell(7, id, 3).gen::<u64>();
+        let forward: Vec<_> = ids.iter().map(draw).collect();
+        let mut backward: Vec<_> = ids.iter().rev().map(draw).collect();
+        backward.reverse();
+        assert_eq!(forward, backward);
+        // All cells obtain different values
+        let unique: std::collections::BTreeSet<_> = forward.iter().collect();
+        assert_eq!(unique.len(), ids.len());
+        // Different iterations and streams differ
+        assert_ne!(Philox4x32::for_cell(7, &ids[0], 4).gen::<u64>(), forward[0]);
+        assert_ne!(
+            Philox4x32::for_cell_stream(7, &ids[0], 3, RngStream::Division).gen::<u64>(),
+            Philox4x32::for_cell_stream(7, &ids[0], 3, RngStream::Local).gen::<u64>(),
+        );
+    }
+
+    #[test]
+    fn uniform_mean() {
+        let mut rng = Philox4x32::seed_from_u64(1);
+        let n = 100_000;
+        let mean = (0..n).map(|_| rng.gen::<f64>()).sum::<f64>() / n as f64;
+        assert!((mean - 0.5).abs() < 0.01);
+    }
+}
@@ -1,5 +1,6 @@
 use cellular_raza_concepts::IndexError;
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 
 use std::{
     collections::{BTreeMap, BTreeSet},
@@ -340,6 +341,14 @@ pub trait SyncSubDomains {
     /// This approach does not necessarily require all threads to wait but can mean that
     /// only depending threads wait for each other.
     fn sync(&mut self) -> Result<(), SimulationError>;
+    /// Forces all syncers to wait for each other.
+    ///
+    /// Used before decisions which need to be taken by all subdomains simultaneously such as
+    /// stopping the simulation.
+    /// Defaults to [SyncSubDomains::sync] for syncers which already wait for all threads.
+    fn sync_all(&mut self) -> Result<(), SimulationError> {
+        self.sync()
+    }
     /// TODO
     fn store_error(
         &mut self,
@@ -449,6 +458,52 @@ where
     fn receive(&mut self) -> Vec<T>;
 }
 
+/// Number and size of messages of one type which were sent and received by a [Communicator].
+///
+/// The size of a message is given by [core::mem::size_of] of its type.
+/// Heap allocations owned by the message such as the contents of a [Vec] are not included.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct MessageStatistics {
+    /// Name of the type of the message as given by [core::any::type_name]
+    pub message_type: String,
+    /// Number of sent messages
+    pub messages_sent: usize,
+    /// Combined size of all sent messages
+    pub bytes_sent: usize,
+    /// Number of received messages
+    pub messages_received: usize,
+    /// Combined size of all received messages
+    pub bytes_received: usize,
+}
+
+/// Provides [MessageStatistics] for every type of message handled by a [Communicator].
+///
+/// This trait is implemented by [ChannelComm] and derived together with the [Communicator]
+/// trait.
+/// Thus, every field of a struct which derives the [Communicator] trait needs to implement
+/// this trait as well.
+/// ```
+/// # use cellular_raza_core::backend::chili::*;
+/// let map = std::collections::BTreeMap::from([
+///     (0, std::collections::BTreeSet::from([1])),
+///     (1, std::collections::BTreeSet::from([0])),
+/// ]);
+/// let mut channel_comms = ChannelComm::<_, u32>::from_map(&map).unwrap();
+/// channel_comms.get_mut(&0).unwrap().send(&1, 3).unwrap();
+/// channel_comms.get_mut(&0).unwrap().send(&1, 4).unwrap();
+/// channel_comms.get_mut(&1).unwrap().receive();
+///
+/// let statistics = channel_comms[&0].communication_statistics();
+/// assert_eq!(statistics[0].message_type, "u32");
+/// assert_eq!(statistics[0].messages_sent, 2);
+/// assert_eq!(statistics[0].bytes_sent, 8);
+/// assert_eq!(channel_comms[&1].communication_statistics()[0].messages_received, 2);
+/// ```
+pub trait CommunicationStatistics {
+    /// Statistics for every type of message since the communicator was constructed
+    fn communication_statistics(&self) -> Vec<MessageStatistics>;
+}
+
 /// Sender-Receiver [Communicator] based on [crossbeam_channel].
 ///
 /// This struct contains one receiver and multiple senders.
@@ -474,8 +529,12 @@ where
 /// ```
 #[derive(Clone)]
 pub struct ChannelComm<I, T> {
+    /// Senders to all other communicators
     senders: std::collections::BTreeMap<I, crossbeam_channel::Sender<T>>,
+    /// Receives messages from all other communicators
     receiver: crossbeam_channel::Receiver<T>,
+    /// Number of sent and received messages and bytes
+    statistics: MessageStatistics,
 }
 
 impl<T, I> FromMap<I> for ChannelComm<I, T>
@@ -506,6 +565,10 @@ where
             let comm = ChannelComm {
                 senders,
                 receiver: channels[&key].1.clone(),
+                statistics: MessageStatistics {
+                    message_type: core::any::type_name::<T>().to_owned(),
+                    ..Default::default()
+                },
             };
             comms.insert(key.clone(), comm);
         }
@@ -691,7 +754,10 @@ where
     I: core::hash::Hash + Eq + Ord,
 {
     fn receive(&mut self) -> Vec<T> {
-        self.receiver.try_iter().collect()
+        let received: Vec<T> = self.receiver.try_iter().collect();
+        self.statistics.messages_received += received.len();
+        self.statistics.bytes_received += received.len() * core::mem::size_of::<T>();
+        received
     }
 
     fn send(&mut self, receiver: &I, message: T) -> Result<(), SimulationError> {
@@ -702,6 +768,292 @@ where
                 "could not find specified receiver"
             )))?;
         sender.send(message)?;
+        self.statistics.messages_sent += 1;
+        self.statistics.bytes_sent += core::mem::size_of::<T>();
+        Ok(())
+    }
+}
+
+impl<I, T> CommunicationStatistics for ChannelComm<I, T> {
+    fn communication_statistics(&self) -> Vec<MessageStatistics> {
+        vec![self.statistics.clone()]
+    }
+}
+
+/// Message type `M` of a [MultiplexComm] which can carry messages of type `T`.
+///
+/// This trait is usually implemented by an enum with one variant for every type of message.
+/// The [build_communicator!](super::build_communicator) macro generates such an enum when
+/// `multiplex_communicator: true` is specified.
+pub trait Multiplex<T>: From<T> {
+    /// Returns the contained message if it is of type `T` and the unchanged value otherwise.
+    fn demultiplex(self) -> Result<T, Self>;
+}
+
+/// [Communicator] which transmits every type of message over a single channel.
+///
+/// Instead of constructing one [ChannelComm] per type of message, all messages are wrapped
+/// into the type `M` which implements [Multiplex] for every type of message.
+/// This reduces the number of channels between two subdomains to one.
+/// When receiving messages of type `T`, all messages which are currently in the channel are
+/// taken out.
+/// Messages of other types are kept until they are requested.
+/// ```
+/// # use cellular_raza_core::backend::chili::{Communicator, FromMap, Multiplex, MultiplexComm};
+/// #[derive(Debug, PartialEq)]
+/// enum Message {
+///     Flag(bool),
+///     Value(f64),
+/// }
+///
+/// impl From<bool> for Message {
+///     fn from(flag: bool) -> Self {
+///         Message::Flag(flag)
+///     }
+/// }
+///
+/// impl From<f64> for Message {
+///     fn from(value: f64) -> Self {
+///         Message::Value(value)
+///     }
+/// }
+///
+/// impl Multiplex<bool> for Message {
+///     fn demultiplex(self) -> Result<bool, Self> {
+///         match self {
+///             Message::Flag(flag) => Ok(flag),
+///             other => Err(other),
+///         }
+///     }
+/// }
+///
+/// impl Multiplex<f64> for Message {
+///     fn demultiplex(self) -> Result<f64, Self> {
+///         match self {
+///             Message::Value(value) => Ok(value),
+///             other => Err(other),
+///         }
+///     }
+/// }
+///
+/// let map = std::collections::BTreeMap::from([
+///     (0, std::collections::BTreeSet::from([1])),
+///     (1, std::collections::BTreeSet::from([0])),
+/// ]);
+/// let mut comms = MultiplexComm::<_, Message>::from_map(&map).unwrap();
+/// comms.get_mut(&0).unwrap().send(&1, 2.5).unwrap();
+/// comms.get_mut(&0).unwrap().send(&1, true).unwrap();
+///
+/// // Both messages were sent over the same channel
+/// let comm = comms.get_mut(&1).unwrap();
+/// let flags: Vec<bool> = comm.receive();
+/// assert_eq!(flags, vec![true]);
+/// let values: Vec<f64> = comm.receive();
+/// assert_eq!(values, vec![2.5]);
+/// ```
+#[derive(Clone)]
+pub struct MultiplexComm<I, M> {
+    /// Senders to all neighbouring subdomains
+    senders: std::collections::BTreeMap<I, crossbeam_channel::Sender<M>>,
+    /// Receiver of the shared channel of this subdomain
+    receiver: crossbeam_channel::Receiver<M>,
+    /// Received messages which have not been demultiplexed yet
+    pending: Vec<M>,
+    /// Statistics per type of message in the order in which types were first used
+    statistics: Vec<MessageStatistics>,
+    /// Position of the statistics of each type of message
+    statistics_index: std::collections::HashMap<core::any::TypeId, usize>,
+}
+
+impl<I, M> MultiplexComm<I, M> {
+    /// Statistics of the given type of message which are created when first used
+    fn statistics_mut<T: 'static>(&mut self) -> &mut MessageStatistics {
+        let statistics = &mut self.statistics;
+        let index = *self
+            .statistics_index
+            .entry(core::any::TypeId::of::<T>())
+            .or_insert_with(|| {
+                statistics.push(MessageStatistics {
+                    message_type: core::any::type_name::<T>().to_owned(),
+                    ..Default::default()
+                });
+                statistics.len() - 1
+            });
+        &mut self.statistics[index]
+    }
+}
+
+impl<I, M> FromMap<I> for MultiplexComm<I, M>
+where
+    I: Ord,
+{
+    fn from_map(map: &BTreeMap<I, BTreeSet<I>>) -> Result<BTreeMap<I, Self>, IndexError>
+    where
+        I: Clone + core::hash::Hash + Eq,
+    {
+        Ok(ChannelComm::<I, M>::from_map(map)?
+            .into_iter()
+            .map(|(key, comm)| {
+                let comm = MultiplexComm {
+                    senders: comm.senders,
+                    receiver: comm.receiver,
+                    pending: Vec::new(),
+                    statistics: Vec::new(),
+                    statistics_index: std::collections::HashMap::new(),
+                };
+                (key, comm)
+            })
+            .collect())
+    }
+}
+
+impl<I, M, T> Communicator<I, T> for MultiplexComm<I, M>
+where
+    I: core::hash::Hash + Eq + Ord,
+    M: Multiplex<T>,
+    T: 'static,
+{
+    fn receive(&mut self) -> Vec<T> {
+        self.pending.extend(self.receiver.try_iter());
+        let mut received = Vec::new();
+        let mut remaining = Vec::new();
+        for message in self.pending.drain(..) {
+            match message.demultiplex() {
+                Ok(message) => received.push(message),
+                Err(message) => remaining.push(message),
+            }
+        }
+        self.pending = remaining;
+        let statistics = self.statistics_mut::<T>();
+        statistics.messages_received += received.len();
+        statistics.bytes_received += received.len() * core::mem::size_of::<T>();
+        received
+    }
+
+    fn send(&mut self, receiver: &I, message: T) -> Result<(), SimulationError> {
+        let sender = self.senders.get(receiver).ok_or(super::IndexError(
+            "could not find specified receiver".to_string(),
+        ))?;
+        sender.send(M::from(message))?;
+        let statistics = self.statistics_mut::<T>();
+        statistics.messages_sent += 1;
+        statistics.bytes_sent += core::mem::size_of::<T>();
+        Ok(())
+    }
+}
+
+// Only types of messages which were sent or received at least once are listed
+impl<I, M> CommunicationStatistics for MultiplexComm<I, M> {
+    fn communication_statistics(&self) -> Vec<MessageStatistics> {
+        self.statistics.clone()
+    }
+}
+
+#[cfg(test)]
+mod test_multiplex_comm {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Message {
+        Index(usize),
+        Text(String),
+    }
+
+    impl From<usize> for Message {
+        fn from(index: usize) -> Self {
+            Message::Index(index)
+        }
+    }
+
+    impl From<String> for Message {
+        fn from(text: String) -> Self {
+            Message::Text(text)
+        }
+    }
+
+    impl Multiplex<usize> for Message {
+        fn demultiplex(self) -> Result<usize, Self> {
+            match self {
+                Message::Index(index) => Ok(index),
+                other => Err(other),
+            }
+        }
+    }
+
+    impl Multiplex<String> for Message {
+        fn demultiplex(self) -> Result<String, Self> {
+            match self {
+                Message::Text(text) => Ok(text),
+                other => Err(other),
+            }
+        }
+    }
+
+    #[test]
+    fn keep_other_messages() -> Result<(), Box<dyn std::error::Error>> {
+        let map = BTreeMap::from([
+            (0_usize, BTreeSet::from([1, 2])),
+            (1_usize, BTreeSet::from([0, 2])),
+            (2_usize, BTreeSet::from([0, 1])),
+        ]);
+        let mut comms = MultiplexComm::<usize, Message>::from_map(&map)?;
+        for (index, comm) in comms.iter_mut() {
+            let next_index = (index + 1) % map.len();
+            comm.send(&next_index, *index)?;
+            comm.send(&next_index, format!("from {index}"))?;
+            comm.send(&next_index, *index + 10)?;
+        }
+
+        for (index, comm) in comms.iter_mut() {
+            let previous = (index + map.len() - 1) % map.len();
+            let indices: Vec<usize> = comm.receive();
+            assert_eq!(indices, vec![previous, previous + 10]);
+            // Messages are only returned once
+            let indices: Vec<usize> = comm.receive();
+            assert_eq!(indices, Vec::<usize>::new());
+            let texts: Vec<String> = comm.receive();
+            assert_eq!(texts, vec![format!("from {previous}")]);
+            assert!(comm.pending.is_empty());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn statistics_per_message_type() -> Result<(), Box<dyn std::error::Error>> {
+        let map = BTreeMap::from([
+            (0_usize, BTreeSet::from([1])),
+            (1_usize, BTreeSet::from([0])),
+        ]);
+        let mut comms = MultiplexComm::<usize, Message>::from_map(&map)?;
+        comms.get_mut(&0).unwrap().send(&1, 3_usize)?;
+        comms.get_mut(&0).unwrap().send(&1, 4_usize)?;
+        comms.get_mut(&0).unwrap().send(&1, "text".to_string())?;
+        let _: Vec<String> = comms.get_mut(&1).unwrap().receive();
+
+        let statistics = comms[&0].communication_statistics();
+        assert_eq!(statistics.len(), 2);
+        assert_eq!(statistics[0].message_type, "usize");
+        assert_eq!(statistics[0].messages_sent, 2);
+        assert_eq!(statistics[0].bytes_sent, 2 * core::mem::size_of::<usize>());
+        assert_eq!(statistics[1].messages_sent, 1);
+
+        // Messages which are still pending were not yet received
+        let statistics = comms[&1].communication_statistics();
+        assert_eq!(statistics.len(), 1);
+        assert_eq!(statistics[0].messages_received, 1);
+        assert_eq!(comms[&1].pending.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_receiver() -> Result<(), Box<dyn std::error::Error>> {
+        let map = BTreeMap::from([
+            (0_usize, BTreeSet::from([1])),
+            (1_usize, BTreeSet::from([0])),
+        ]);
+        let mut comms = MultiplexComm::<usize, Message>::from_map(&map)?;
+        assert!(comms.get_mut(&0).unwrap().send(&2, 1_usize).is_err());
+        assert!(comms[&0].communication_statistics().is_empty());
         Ok(())
     }
 }
@@ -831,22 +1183,36 @@ mod test_build_communicator {
             ///     core_path: cellular_raza_core,
             ///     communicator_name: __MyComm,
             /// );
+            /// build_communicator!(
+            ///     aspects: [
+            #[doc = stringify!($($asp),*)]
+            ///     ],
+            ///     core_path: cellular_raza_core,
+            ///     communicator_name: __MyMultiplexComm,
+            ///     multiplex_communicator: true,
+            /// );
             /// let mut map = std::collections::BTreeMap::new();
             /// map.insert(0, std::collections::BTreeSet::from([1]));
             /// map.insert(1, std::collections::BTreeSet::from([0]));
             /// use cellular_raza_core::backend::chili::{ReactionsContactInformation, FromMap,
             /// Communicator, PosInformation, ForceInformation, VoxelPlainIndex, SendCell};
             /// let mut communicator = __MyComm::from_map(&map).unwrap().remove(&0).unwrap();
+            /// let mut multiplexed =
+            ///     __MyMultiplexComm::from_map(&map).unwrap().remove(&0).unwrap();
             /// macro_rules! test_aspect (
-            ///     (Mechanics) => {
-            ///         communicator.send(&1, SendCell(
+            ///     ($asp:ident) => {
+            ///         test_aspect!(communicator, $asp);
+            ///         test_aspect!(multiplexed, $asp);
+            ///     };
+            ///     ($communicator:ident, Mechanics) => {
+            ///         $communicator.send(&1, SendCell(
             ///             VoxelPlainIndex::new(1),
             ///             format!("MyCell"),
             ///             format!("AuxStorage")
             ///         ));
             ///     };
-            ///     (Interaction) => {
-            ///         communicator.send(&1, PosInformation {
+            ///     ($communicator:ident, Interaction) => {
+            ///         $communicator.send(&1, PosInformation {
             ///             pos: 1u8,
             ///             vel: 1.0,
             ///             info: (),
@@ -854,14 +1220,15 @@ mod test_build_communicator {
             ///             index_sender: VoxelPlainIndex::new(0),
             ///             index_receiver: VoxelPlainIndex::new(1),
             ///         });
-            ///         communicator.send(&1, ForceInformation {
+            ///         $communicator.send(&1, ForceInformation {
             ///             force: 0.1,
+            ///             pressure: 0.0,
             ///             cell_index_in_vector: 0,
             ///             index_sender: VoxelPlainIndex::new(0),
             ///         });
             ///     };
-            ///     (ReactionsContact) => {
-            ///         communicator.send(&1, ReactionsContactInformation {
+            ///     ($communicator:ident, ReactionsContact) => {
+            ///         $communicator.send(&1, ReactionsContactInformation {
             ///             pos: 1u8,
             ///             intracellular: [0.0, 1.0],
             ///             info: "hi",
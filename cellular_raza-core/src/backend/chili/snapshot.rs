@@ -0,0 +1,185 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use cellular_raza_concepts::SubDomain;
+
+use super::{SimulationError, SimulationSnapshot, SubDomainBox};
+use crate::time::NextTimePoint;
+
+/// Callback which receives the iteration and the combined snapshot
+type SnapshotCallback<I, S, C> = Arc<dyn Fn(usize, &SimulationSnapshot<I, S, C>) + Send + Sync>;
+
+/// Partially gathered snapshot together with the number of contributing subdomains
+type PendingSnapshots<I, S, C> = BTreeMap<usize, (usize, SimulationSnapshot<I, S, C>)>;
+
+/// Hands a [SimulationSnapshot] to a callback at every save point while the simulation is
+/// running.
+///
+/// The [SimulationRunner](super::SimulationRunner) is consumed once the simulation starts such
+/// that [SimulationRunner::snapshot](super::SimulationRunner::snapshot) can not be used to
+/// inspect the state during a run.
+/// Instead, every subdomain contributes a copy of its cells and its subdomain at each save
+/// point.
+/// Once all subdomains have contributed, the callback is executed exactly once with the
+/// iteration and the combined snapshot.
+/// It is executed by the thread of the last contributing subdomain.
+///
+/// The hook is passed to the [run_simulation](crate::backend::chili::run_simulation) macro via
+/// the `snapshot_hook` argument.
+/// All clones share their state.
+/// ```
+/// # use cellular_raza_core::backend::chili::SnapshotHook;
+/// let hook = SnapshotHook::<usize, (), f64>::new(|iteration, snapshot| {
+///     println!("{iteration}: {} cells", snapshot.cells.len());
+/// });
+/// // Pass `snapshot_hook: hook` to the run_simulation macro
+/// ```
+pub struct SnapshotHook<I: Ord, S, C> {
+    /// Executed once all subdomains have contributed
+    callback: SnapshotCallback<I, S, C>,
+    /// Snapshots of save points which are still missing contributions
+    pending: Arc<Mutex<PendingSnapshots<I, S, C>>>,
+    /// Total number of subdomains which contribute to every snapshot
+    n_subdomains: Arc<AtomicUsize>,
+}
+
+impl<I: Ord, S, C> Clone for SnapshotHook<I, S, C> {
+    fn clone(&self) -> Self {
+        Self {
+            callback: self.callback.clone(),
+            pending: self.pending.clone(),
+            n_subdomains: self.n_subdomains.clone(),
+        }
+    }
+}
+
+impl<I: Ord, S, C> SnapshotHook<I, S, C> {
+    /// Constructs a new hook which executes the given callback at every save point
+    pub fn new(
+        callback: impl Fn(usize, &SimulationSnapshot<I, S, C>) + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            callback: Arc::new(callback),
+            pending: Arc::new(Mutex::new(BTreeMap::new())),
+            n_subdomains: Arc::new(AtomicUsize::new(1)),
+        }
+    }
+
+    /// Sets the number of subdomains which need to contribute before the callback is executed.
+    ///
+    /// This is done by the [run_simulation](crate::backend::chili::run_simulation) macro before
+    /// the simulation starts.
+    pub fn set_n_subdomains(&self, n_subdomains: usize) {
+        self.n_subdomains.store(n_subdomains, Ordering::SeqCst);
+    }
+
+    /// Adds the cells and subdomain of the given [SubDomainBox] to the snapshot of the current
+    /// iteration if it is a save point.
+    pub fn record<F, A, Com, Sy, R>(
+        &self,
+        sbox: &SubDomainBox<I, S, C, A, Com, Sy, R>,
+        next_time_point: &NextTimePoint<F>,
+    ) -> Result<(), SimulationError>
+    where
+        I: Clone,
+        S: SubDomain + Clone,
+        C: Clone,
+    {
+        if next_time_point.event.is_none() {
+            return Ok(());
+        }
+        let iteration = next_time_point.iteration;
+        let completed = {
+            let mut pending = self
+                .pending
+                .lock()
+                .map_err(|e| SimulationError::OtherThreadError(e.to_string()))?;
+            let (n_contributed, snapshot) = pending.entry(iteration).or_insert_with(|| {
+                (
+                    0,
+                    SimulationSnapshot {
+                        cells: BTreeMap::new(),
+                        subdomains: BTreeMap::new(),
+                    },
+                )
+            });
+            snapshot.cells.extend(
+                sbox.iter_cells()
+                    .map(|cbox| (cbox.identifier, cbox.clone())),
+            );
+            snapshot
+                .subdomains
+                .insert(sbox.index.clone(), sbox.subdomain.clone());
+            *n_contributed += 1;
+            match *n_contributed >= self.n_subdomains.load(Ordering::SeqCst) {
+                true => pending.remove(&iteration).map(|(_, snapshot)| snapshot),
+                false => None,
+            }
+        };
+        // The lock is released such that other threads are not blocked by the callback
+        if let Some(snapshot) = completed {
+            (self.callback)(iteration, &snapshot);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_snapshot_hook {
+    use super::*;
+    use crate::backend::chili::test_fixtures::*;
+    use crate::backend::chili::SimulationRunner;
+
+    fn save_point(iteration: usize) -> NextTimePoint<f64> {
+        NextTimePoint {
+            increment: 0.1,
+            time: 0.1 * iteration as f64,
+            iteration,
+            event: Some(crate::time::TimeEvent::PartialSave),
+        }
+    }
+
+    #[test]
+    fn runner_snapshot() {
+        let runner = SimulationRunner {
+            subdomain_boxes: BTreeMap::from([(0, subdomain_box(4, &[0.5, 1.5, 3.5]))]),
+        };
+        let snapshot = runner.snapshot();
+        assert_eq!(snapshot.cells.len(), 3);
+        assert_eq!(snapshot.subdomains.len(), 1);
+        let mut positions: Vec<_> = snapshot.cells.values().map(|cbox| cbox.cell.pos).collect();
+        positions.sort_by(f64::total_cmp);
+        assert_eq!(positions, vec![0.5, 1.5, 3.5]);
+    }
+
+    #[test]
+    fn callback_after_all_subdomains() -> Result<(), SimulationError> {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let calls_hook = calls.clone();
+        let hook = SnapshotHook::new(move |iteration, snapshot: &SimulationSnapshot<_, _, _>| {
+            calls_hook.lock().unwrap().push((
+                iteration,
+                snapshot.cells.len(),
+                snapshot.subdomains.len(),
+            ));
+        });
+        hook.set_n_subdomains(2);
+        let sbox_1 = subdomain_box(2, &[0.5, 1.5]);
+        let mut sbox_2 = subdomain_box(3, &[2.5]);
+        sbox_2.index = 1;
+
+        // Steps which are no save points are ignored
+        let mut step = save_point(1);
+        step.event = None;
+        hook.record(&sbox_1, &step)?;
+        hook.record(&sbox_2, &step)?;
+        assert!(calls.lock().unwrap().is_empty());
+
+        hook.clone().record(&sbox_1, &save_point(2))?;
+        assert!(calls.lock().unwrap().is_empty());
+        hook.record(&sbox_2, &save_point(2))?;
+        assert_eq!(*calls.lock().unwrap(), vec![(2, 3, 2)]);
+        Ok(())
+    }
+}
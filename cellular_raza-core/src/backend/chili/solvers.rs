@@ -1,10 +1,10 @@
-use cellular_raza_concepts::{CalcError, Xapy};
+use cellular_raza_concepts::{AngularVelocity, CalcError, Orientation, RotationalMechanics, Xapy};
 use num::FromPrimitive;
 
 #[cfg(feature = "tracing")]
 use tracing::instrument;
 
-use super::{UpdateReactions, UpdateReactionsContact};
+use super::{UpdateReactions, UpdateReactionsContact, UpdateRotationalMechanics};
 
 /// Classical euler solver for the [Mechanics](cellular_raza_concepts::Mechanics) trait.
 ///
@@ -20,15 +20,16 @@ use super::{UpdateReactions, UpdateReactionsContact};
 /// where $\Delta t$ is the step size and $dx/dt$ and $dv/dt$ are calculated by the
 /// [calculate_increment](cellular_raza_concepts::Mechanics::calculate_increment) method.
 #[cfg_attr(feature = "tracing", instrument(skip_all))]
-pub fn mechanics_euler<C, A, Pos, Vel, For, Float>(
+pub fn mechanics_euler<C, A, Pos, Vel, For, Float, R>(
     cell: &mut C,
     aux_storage: &mut A,
     dt: Float,
-    rng: &mut rand_chacha::ChaCha8Rng,
+    rng: &mut R,
+    parameters: &cellular_raza_concepts::GlobalParameters,
 ) -> Result<(), super::SimulationError>
 where
     A: super::aux_storage::UpdateMechanics<Pos, Vel, For, 0>,
-    C: cellular_raza_concepts::Mechanics<Pos, Vel, For, Float>,
+    C: cellular_raza_concepts::Mechanics<Pos, Vel, For, Float, R>,
     C: cellular_raza_concepts::Position<Pos>,
     C: cellular_raza_concepts::Velocity<Vel>,
     Pos: Xapy<Float> + Clone,
@@ -40,7 +41,7 @@ where
     let position = cell.pos();
 
     let (dx, dv) = cell.calculate_increment(force)?;
-    let (dx_rand, dv_rand) = cell.get_random_contribution(rng, dt)?;
+    let (dx_rand, dv_rand) = cell.get_random_contribution_with_parameters(rng, dt, parameters)?;
 
     // Update values in the aux_storage
     aux_storage.set_last_position(dx.clone());
@@ -60,16 +61,18 @@ where
 pub(crate) struct MechanicsAdamsBashforthSolver<const N: usize>;
 
 pub(crate) trait AdamsBashforth<const N: usize> {
+    /// Advances position and velocity of the cell by one step of size `dt`
     #[allow(unused)]
-    fn update<C, A, Pos, Vel, For, Float>(
+    fn update<C, A, Pos, Vel, For, Float, R>(
         cell: &mut C,
         aux_storage: &mut A,
         dt: Float,
-        rng: &mut rand_chacha::ChaCha8Rng,
+        rng: &mut R,
+        parameters: &cellular_raza_concepts::GlobalParameters,
     ) -> Result<(), super::SimulationError>
     where
         A: super::aux_storage::UpdateMechanics<Pos, Vel, For, N>,
-        C: cellular_raza_concepts::Mechanics<Pos, Vel, For, Float>,
+        C: cellular_raza_concepts::Mechanics<Pos, Vel, For, Float, R>,
         C: cellular_raza_concepts::Position<Pos>,
         C: cellular_raza_concepts::Velocity<Vel>,
         Pos: Xapy<Float> + Clone,
@@ -79,62 +82,65 @@ pub(crate) trait AdamsBashforth<const N: usize> {
 
 impl AdamsBashforth<2> for MechanicsAdamsBashforthSolver<2> {
     #[allow(unused)]
-    fn update<C, A, Pos, Vel, For, Float>(
+    fn update<C, A, Pos, Vel, For, Float, R>(
         cell: &mut C,
         aux_storage: &mut A,
         dt: Float,
-        rng: &mut rand_chacha::ChaCha8Rng,
+        rng: &mut R,
+        parameters: &cellular_raza_concepts::GlobalParameters,
     ) -> Result<(), super::SimulationError>
     where
         A: super::aux_storage::UpdateMechanics<Pos, Vel, For, 2>,
-        C: cellular_raza_concepts::Mechanics<Pos, Vel, For, Float>,
+        C: cellular_raza_concepts::Mechanics<Pos, Vel, For, Float, R>,
         C: cellular_raza_concepts::Position<Pos>,
         C: cellular_raza_concepts::Velocity<Vel>,
         Pos: Xapy<Float> + Clone,
         Vel: Xapy<Float> + Clone,
         Float: num::Float + FromPrimitive,
     {
-        mechanics_adams_bashforth_3(cell, aux_storage, dt, rng)
+        mechanics_adams_bashforth_3(cell, aux_storage, dt, rng, parameters)
     }
 }
 
 impl AdamsBashforth<1> for MechanicsAdamsBashforthSolver<1> {
-    fn update<C, A, Pos, Vel, For, Float>(
+    fn update<C, A, Pos, Vel, For, Float, R>(
         cell: &mut C,
         aux_storage: &mut A,
         dt: Float,
-        rng: &mut rand_chacha::ChaCha8Rng,
+        rng: &mut R,
+        parameters: &cellular_raza_concepts::GlobalParameters,
     ) -> Result<(), super::SimulationError>
     where
         A: super::aux_storage::UpdateMechanics<Pos, Vel, For, 1>,
-        C: cellular_raza_concepts::Mechanics<Pos, Vel, For, Float>,
+        C: cellular_raza_concepts::Mechanics<Pos, Vel, For, Float, R>,
         C: cellular_raza_concepts::Position<Pos>,
         C: cellular_raza_concepts::Velocity<Vel>,
         Pos: Xapy<Float> + Clone,
         Vel: Xapy<Float> + Clone,
         Float: num::Float + FromPrimitive,
     {
-        mechanics_adams_bashforth_2(cell, aux_storage, dt, rng)
+        mechanics_adams_bashforth_2(cell, aux_storage, dt, rng, parameters)
     }
 }
 
 impl AdamsBashforth<0> for MechanicsAdamsBashforthSolver<0> {
-    fn update<C, A, Pos, Vel, For, Float>(
+    fn update<C, A, Pos, Vel, For, Float, R>(
         cell: &mut C,
         aux_storage: &mut A,
         dt: Float,
-        rng: &mut rand_chacha::ChaCha8Rng,
+        rng: &mut R,
+        parameters: &cellular_raza_concepts::GlobalParameters,
     ) -> Result<(), super::SimulationError>
     where
         A: super::aux_storage::UpdateMechanics<Pos, Vel, For, 0>,
-        C: cellular_raza_concepts::Mechanics<Pos, Vel, For, Float>,
+        C: cellular_raza_concepts::Mechanics<Pos, Vel, For, Float, R>,
         C: cellular_raza_concepts::Position<Pos>,
         C: cellular_raza_concepts::Velocity<Vel>,
         Pos: Xapy<Float> + Clone,
         Vel: Xapy<Float> + Clone,
         Float: num::Float + FromPrimitive,
     {
-        mechanics_euler(cell, aux_storage, dt, rng)
+        mechanics_euler(cell, aux_storage, dt, rng, parameters)
     }
 }
 
@@ -153,15 +159,16 @@ impl AdamsBashforth<0> for MechanicsAdamsBashforthSolver<0> {
 /// In the beginning of the simulation, when not enough previous increment values are known,
 /// we resort to the [mechanics_adams_bashforth_2] and [mechanics_euler] solver.
 #[cfg_attr(feature = "tracing", instrument(skip_all))]
-pub fn mechanics_adams_bashforth_3<C, A, Pos, Vel, For, Float>(
+pub fn mechanics_adams_bashforth_3<C, A, Pos, Vel, For, Float, R>(
     cell: &mut C,
     aux_storage: &mut A,
     dt: Float,
-    rng: &mut rand_chacha::ChaCha8Rng,
+    rng: &mut R,
+    parameters: &cellular_raza_concepts::GlobalParameters,
 ) -> Result<(), super::SimulationError>
 where
     A: super::aux_storage::UpdateMechanics<Pos, Vel, For, 2>,
-    C: cellular_raza_concepts::Mechanics<Pos, Vel, For, Float>,
+    C: cellular_raza_concepts::Mechanics<Pos, Vel, For, Float, R>,
     C: cellular_raza_concepts::Position<Pos>,
     C: cellular_raza_concepts::Velocity<Vel>,
     Pos: Xapy<Float> + Clone,
@@ -173,7 +180,7 @@ where
     let position = cell.pos();
 
     let (dx, dv) = cell.calculate_increment(force)?;
-    let (dx_rand, dv_rand) = cell.get_random_contribution(rng, dt)?;
+    let (dx_rand, dv_rand) = cell.get_random_contribution_with_parameters(rng, dt, parameters)?;
 
     // Update values in the aux_storage
     aux_storage.set_last_position(dx.clone());
@@ -244,15 +251,16 @@ where
 /// for both the position and velocity.
 /// In the beginning of the simulation, when not enough previous increment values are known,
 /// we resort to the [euler](mechanics_euler) solver.
-pub fn mechanics_adams_bashforth_2<C, A, Pos, Vel, For, Float>(
+pub fn mechanics_adams_bashforth_2<C, A, Pos, Vel, For, Float, R>(
     cell: &mut C,
     aux_storage: &mut A,
     dt: Float,
-    rng: &mut rand_chacha::ChaCha8Rng,
+    rng: &mut R,
+    parameters: &cellular_raza_concepts::GlobalParameters,
 ) -> Result<(), super::SimulationError>
 where
     A: super::aux_storage::UpdateMechanics<Pos, Vel, For, 1>,
-    C: cellular_raza_concepts::Mechanics<Pos, Vel, For, Float>,
+    C: cellular_raza_concepts::Mechanics<Pos, Vel, For, Float, R>,
     C: cellular_raza_concepts::Position<Pos>,
     C: cellular_raza_concepts::Velocity<Vel>,
     Pos: Xapy<Float> + Clone,
@@ -264,7 +272,7 @@ where
     let position = cell.pos();
 
     let (dx, dv) = cell.calculate_increment(force)?;
-    let (dx_rand, dv_rand) = cell.get_random_contribution(rng, dt)?;
+    let (dx_rand, dv_rand) = cell.get_random_contribution_with_parameters(rng, dt, parameters)?;
 
     // Update values in the aux_storage
     aux_storage.set_last_position(dx.clone());
@@ -299,6 +307,159 @@ where
     Ok(())
 }
 
+/// Euler solver for the [RotationalMechanics](cellular_raza_concepts::RotationalMechanics)
+/// trait.
+///
+/// Orientation and angular velocity are updated in the same way as position and velocity in
+/// [mechanics_euler].
+/// The increments are calculated by the
+/// [calculate_rotational_increment](cellular_raza_concepts::RotationalMechanics::calculate_rotational_increment)
+/// method from the torque gathered in the [UpdateRotationalMechanics] trait.
+#[cfg_attr(feature = "tracing", instrument(skip_all))]
+pub fn rotational_mechanics_euler<C, A, Ang, AngVel, Tor, Float, R>(
+    cell: &mut C,
+    aux_storage: &mut A,
+    dt: Float,
+    rng: &mut R,
+) -> Result<(), super::SimulationError>
+where
+    A: UpdateRotationalMechanics<Ang, AngVel, Tor, 0>,
+    C: RotationalMechanics<Ang, AngVel, Tor, Float, R>,
+    C: Orientation<Ang>,
+    C: AngularVelocity<AngVel>,
+    Ang: Xapy<Float> + Clone,
+    AngVel: Xapy<Float> + Clone,
+    Float: num::Float + FromPrimitive,
+{
+    rotational_mechanics_multistep(cell, aux_storage, dt, rng)
+}
+
+/// Two-step Adams-Bashforth method for the
+/// [RotationalMechanics](cellular_raza_concepts::RotationalMechanics) trait.
+///
+/// See [mechanics_adams_bashforth_2] and [rotational_mechanics_euler].
+#[cfg_attr(feature = "tracing", instrument(skip_all))]
+pub fn rotational_mechanics_adams_bashforth_2<C, A, Ang, AngVel, Tor, Float, R>(
+    cell: &mut C,
+    aux_storage: &mut A,
+    dt: Float,
+    rng: &mut R,
+) -> Result<(), super::SimulationError>
+where
+    A: UpdateRotationalMechanics<Ang, AngVel, Tor, 1>,
+    C: RotationalMechanics<Ang, AngVel, Tor, Float, R>,
+    C: Orientation<Ang>,
+    C: AngularVelocity<AngVel>,
+    Ang: Xapy<Float> + Clone,
+    AngVel: Xapy<Float> + Clone,
+    Float: num::Float + FromPrimitive,
+{
+    rotational_mechanics_multistep(cell, aux_storage, dt, rng)
+}
+
+/// Three-step Adams-Bashforth method for the
+/// [RotationalMechanics](cellular_raza_concepts::RotationalMechanics) trait.
+///
+/// See [mechanics_adams_bashforth_3] and [rotational_mechanics_euler].
+#[cfg_attr(feature = "tracing", instrument(skip_all))]
+pub fn rotational_mechanics_adams_bashforth_3<C, A, Ang, AngVel, Tor, Float, R>(
+    cell: &mut C,
+    aux_storage: &mut A,
+    dt: Float,
+    rng: &mut R,
+) -> Result<(), super::SimulationError>
+where
+    A: UpdateRotationalMechanics<Ang, AngVel, Tor, 2>,
+    C: RotationalMechanics<Ang, AngVel, Tor, Float, R>,
+    C: Orientation<Ang>,
+    C: AngularVelocity<AngVel>,
+    Ang: Xapy<Float> + Clone,
+    AngVel: Xapy<Float> + Clone,
+    Float: num::Float + FromPrimitive,
+{
+    rotational_mechanics_multistep(cell, aux_storage, dt, rng)
+}
+
+/// Uses the highest order Adams-Bashforth method for which enough previous increments are
+/// stored.
+/// The order is limited by the number `N` of increments which the aux storage can hold.
+pub(crate) fn rotational_mechanics_multistep<C, A, Ang, AngVel, Tor, Float, R, const N: usize>(
+    cell: &mut C,
+    aux_storage: &mut A,
+    dt: Float,
+    rng: &mut R,
+) -> Result<(), super::SimulationError>
+where
+    A: UpdateRotationalMechanics<Ang, AngVel, Tor, N>,
+    C: RotationalMechanics<Ang, AngVel, Tor, Float, R>,
+    C: Orientation<Ang>,
+    C: AngularVelocity<AngVel>,
+    Ang: Xapy<Float> + Clone,
+    AngVel: Xapy<Float> + Clone,
+    Float: num::Float + FromPrimitive,
+{
+    let torque = aux_storage.get_current_torque_and_reset();
+    let angular_velocity = cell.angular_velocity();
+    let orientation = cell.orientation();
+
+    let (dang, dangvel) = cell.calculate_rotational_increment(torque)?;
+    let (dang_rand, dangvel_rand) = cell.get_random_rotational_contribution(rng, dt)?;
+
+    // Update values in the aux_storage
+    aux_storage.set_last_orientation(dang.clone());
+    aux_storage.set_last_angular_velocity(dangvel.clone());
+
+    // Calculate new orientation and angular velocity of cell
+    let n_previous_values = aux_storage.n_previous_rotational_values().min(N);
+    let mut old_ang_increments = aux_storage.previous_orientations();
+    let mut old_angvel_increments = aux_storage.previous_angular_velocities();
+    let (new_orientation, new_angular_velocity) = match n_previous_values {
+        2 => (
+            adams_bashforth_3(
+                orientation,
+                [
+                    dang,
+                    old_ang_increments.next().unwrap().clone(),
+                    old_ang_increments.next().unwrap().clone(),
+                ],
+                dt,
+                dang_rand,
+            )?,
+            adams_bashforth_3(
+                angular_velocity,
+                [
+                    dangvel,
+                    old_angvel_increments.next().unwrap().clone(),
+                    old_angvel_increments.next().unwrap().clone(),
+                ],
+                dt,
+                dangvel_rand,
+            )?,
+        ),
+        1 => (
+            adams_bashforth_2(
+                orientation,
+                [dang, old_ang_increments.next().unwrap().clone()],
+                dt,
+                dang_rand,
+            )?,
+            adams_bashforth_2(
+                angular_velocity,
+                [dangvel, old_angvel_increments.next().unwrap().clone()],
+                dt,
+                dangvel_rand,
+            )?,
+        ),
+        _ => (
+            euler(orientation, dang, dt, dang_rand)?,
+            euler(angular_velocity, dangvel, dt, dangvel_rand)?,
+        ),
+    };
+    cell.set_orientation(&new_orientation);
+    cell.set_angular_velocity(&new_angular_velocity);
+    Ok(())
+}
+
 #[inline]
 fn euler<X, F>(x: X, dx: X, dt: F, dx_rand: X) -> Result<X, CalcError>
 where
@@ -357,6 +518,7 @@ pub(crate) trait RungeKutta<const N: usize> {
         cell: &mut C,
         aux_storage: &mut A,
         dt: Float,
+        parameters: &cellular_raza_concepts::GlobalParameters,
     ) -> Result<(), super::SimulationError>
     where
         A: UpdateReactions<Ri>,
@@ -373,6 +535,7 @@ impl RungeKutta<1> for ReactionsRungeKuttaSolver<1> {
         cell: &mut C,
         aux_storage: &mut A,
         dt: Float,
+        parameters: &cellular_raza_concepts::GlobalParameters,
     ) -> Result<(), super::SimulationError>
     where
         A: UpdateReactions<Ri>,
@@ -382,9 +545,11 @@ impl RungeKutta<1> for ReactionsRungeKuttaSolver<1> {
     {
         // Constants
         let intra = cell.get_intracellular();
+        let increment =
+            |intra: &Ri| cell.calculate_intracellular_increment_with_parameters(intra, parameters);
 
         // Calculate the intermediate steps
-        let dintra = cell.calculate_intracellular_increment(&intra)?;
+        let dintra = increment(&intra)?;
 
         // Update the internal value of the cell
         aux_storage.incr_conc(dintra);
@@ -400,6 +565,7 @@ impl RungeKutta<2> for ReactionsRungeKuttaSolver<2> {
         cell: &mut C,
         aux_storage: &mut A,
         dt: Float,
+        parameters: &cellular_raza_concepts::GlobalParameters,
     ) -> Result<(), super::SimulationError>
     where
         A: UpdateReactions<Ri>,
@@ -410,10 +576,12 @@ impl RungeKutta<2> for ReactionsRungeKuttaSolver<2> {
         // Constants
         let two = Float::one() + Float::one();
         let intra = cell.get_intracellular();
+        let increment =
+            |intra: &Ri| cell.calculate_intracellular_increment_with_parameters(intra, parameters);
 
         // Calculate the intermediate steps
-        let dintra1 = cell.calculate_intracellular_increment(&intra)?;
-        let dintra = cell.calculate_intracellular_increment(&dintra1.xapy(dt / two, &intra))?;
+        let dintra1 = increment(&intra)?;
+        let dintra = increment(&dintra1.xapy(dt / two, &intra))?;
 
         // Update the internal value of the cell
         aux_storage.incr_conc(dintra);
@@ -429,6 +597,7 @@ impl RungeKutta<4> for ReactionsRungeKuttaSolver<4> {
         cell: &mut C,
         aux_storage: &mut A,
         dt: Float,
+        parameters: &cellular_raza_concepts::GlobalParameters,
     ) -> Result<(), super::SimulationError>
     where
         A: UpdateReactions<Ri>,
@@ -441,12 +610,14 @@ impl RungeKutta<4> for ReactionsRungeKuttaSolver<4> {
         let six = two + two + two;
 
         let intra = cell.get_intracellular();
+        let increment =
+            |intra: &Ri| cell.calculate_intracellular_increment_with_parameters(intra, parameters);
 
         // Calculate the intermediate steps
-        let dintra1 = cell.calculate_intracellular_increment(&intra)?;
-        let dintra2 = cell.calculate_intracellular_increment(&dintra1.xapy(dt / two, &intra))?;
-        let dintra3 = cell.calculate_intracellular_increment(&dintra2.xapy(dt / two, &intra))?;
-        let dintra4 = cell.calculate_intracellular_increment(&dintra3.xapy(dt, &intra))?;
+        let dintra1 = increment(&intra)?;
+        let dintra2 = increment(&dintra1.xapy(dt / two, &intra))?;
+        let dintra3 = increment(&dintra2.xapy(dt / two, &intra))?;
+        let dintra4 = increment(&dintra3.xapy(dt, &intra))?;
         let dintra = dintra1.xapy(
             Float::one() / six,
             &dintra2.xapy(
@@ -566,7 +737,12 @@ mod test_solvers_reactions {
         let mut results_cr = vec![(0.0, cell.get_intracellular())];
         let mut t = 0.0;
         for _ in 0..100 {
-            ReactionsRungeKuttaSolver::<4>::update(&mut cell, &mut aux_storage, dt)?;
+            ReactionsRungeKuttaSolver::<4>::update(
+                &mut cell,
+                &mut aux_storage,
+                dt,
+                &GlobalParameters::new(),
+            )?;
             // This rng is just a placeholder and will not be used.
             let mut _rng = rand_chacha::ChaCha8Rng::seed_from_u64(0);
             local_reactions_use_increment(&mut cell, &mut aux_storage, dt, &mut _rng)?;
@@ -585,6 +761,61 @@ mod test_solvers_reactions {
 mod test_solvers {
     use super::*;
 
+    #[test]
+    fn rotational_constant_torque() {
+        use crate::backend::chili::AuxStorageRotationalMechanics;
+        use cellular_raza_concepts::RngError;
+        use rand::SeedableRng;
+
+        struct Spinner {
+            angle: f64,
+            omega: f64,
+        }
+        impl Orientation<f64> for Spinner {
+            fn orientation(&self) -> f64 {
+                self.angle
+            }
+            fn set_orientation(&mut self, angle: &f64) {
+                self.angle = *angle;
+            }
+        }
+        impl AngularVelocity<f64> for Spinner {
+            fn angular_velocity(&self) -> f64 {
+                self.omega
+            }
+            fn set_angular_velocity(&mut self, omega: &f64) {
+                self.omega = *omega;
+            }
+        }
+        impl RotationalMechanics<f64, f64, f64> for Spinner {
+            fn get_random_rotational_contribution(
+                &self,
+                _rng: &mut rand_chacha::ChaCha8Rng,
+                _dt: f64,
+            ) -> Result<(f64, f64), RngError> {
+                Ok((0.0, 0.0))
+            }
+            fn calculate_rotational_increment(&self, torque: f64) -> Result<(f64, f64), CalcError> {
+                Ok((self.omega, torque))
+            }
+        }
+
+        // A constant torque of 2 leads to the orientation t^2
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(0);
+        let mut cell = Spinner {
+            angle: 0.0,
+            omega: 0.0,
+        };
+        let mut aux_storage = AuxStorageRotationalMechanics::<f64, f64, f64, 2>::default();
+        for _ in 0..100 {
+            aux_storage.add_torque(2.0);
+            rotational_mechanics_adams_bashforth_3(&mut cell, &mut aux_storage, 0.01, &mut rng)
+                .unwrap();
+        }
+        assert!((cell.omega - 2.0).abs() < 1e-10);
+        assert!((cell.angle - 1.0).abs() < 0.05);
+    }
+
     #[test]
     fn euler_exp_decay() {
         let y0 = 27.0;
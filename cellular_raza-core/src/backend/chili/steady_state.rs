@@ -0,0 +1,351 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use cellular_raza_concepts::{FieldNorm, Position, SubDomain};
+use serde::{Deserialize, Serialize};
+
+use super::{CellIdentifier, OverlapMeasure, SimulationError, SubDomainBox};
+use crate::time::NextTimePoint;
+
+/// Changes of the simulation over one window of the [SteadyStateMonitor].
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SteadyStateRecord<F> {
+    /// Iteration at the end of the window
+    pub iteration: usize,
+    /// Time at the end of the window
+    pub time: F,
+    /// Total number of cells
+    pub n_cells: usize,
+    /// Largest distance which any cell travelled during the window
+    pub max_displacement: F,
+    /// Change of the norm of all fields relative to the norm at the start of the window
+    pub relative_field_change: Option<F>,
+}
+
+/// Measurements of all subdomains at one checkpoint
+#[derive(Clone, Debug)]
+struct Checkpoint<F> {
+    /// Total number of cells
+    n_cells: usize,
+    /// Cells which were not present at the previous checkpoint
+    n_new_cells: usize,
+    /// Largest distance which any cell travelled since the previous checkpoint
+    max_displacement: F,
+    /// Sum of the squared norms of the fields of all subdomains
+    squared_field_norm: Option<F>,
+}
+
+/// Terminates the simulation once it has reached a steady state.
+///
+/// Relaxation to equilibrium often takes an unknown number of steps.
+/// Instead of guessing the final time, the monitor compares the state of the simulation at
+/// checkpoints which are `window` iterations apart.
+/// The simulation has converged when between two checkpoints
+/// - no cell was created or removed,
+/// - no cell travelled further than the `position_tolerance` and
+/// - the norm of the extracellular fields changed less than the relative `field_tolerance`.
+///
+/// Positions are only compared for simulations with the `Mechanics` aspect and require the
+/// [OverlapMeasure] trait to calculate distances.
+/// Fields are only compared for simulations with the `ReactionsExtra` aspect and require the
+/// subdomain to implement the [FieldNorm] trait.
+/// Once converged, the final state is saved and the simulation stops.
+/// The [SteadyStateRecord] of the converged window can be obtained via
+/// [SteadyStateMonitor::converged].
+///
+/// The monitor is passed to the [run_simulation](crate::backend::chili::run_simulation)
+/// macro via the `steady_state` argument.
+/// All clones share their state.
+/// ```
+/// # use cellular_raza_core::backend::chili::SteadyStateMonitor;
+/// let monitor = SteadyStateMonitor::<f64, [f64; 2]>::new(100, 1e-3).field_tolerance(1e-4);
+/// // Pass `steady_state: monitor` to the run_simulation macro
+/// assert!(monitor.converged().is_none());
+/// ```
+#[derive(Clone, Debug)]
+pub struct SteadyStateMonitor<F, Pos> {
+    /// Number of iterations between two checkpoints which is never zero
+    window: usize,
+    /// Maximum distance which any cell may travel during one window
+    pub position_tolerance: F,
+    /// Maximum change of the norm of the fields during one window relative to its value at the
+    /// start of the window
+    pub field_tolerance: Option<F>,
+    /// Measurements at the most recent checkpoints
+    checkpoints: Arc<Mutex<BTreeMap<usize, Checkpoint<F>>>>,
+    /// Positions of all cells at the most recent checkpoints
+    positions: Arc<Mutex<BTreeMap<usize, BTreeMap<CellIdentifier, Pos>>>>,
+    /// Record of the window in which the simulation has converged
+    converged: Arc<Mutex<Option<SteadyStateRecord<F>>>>,
+}
+
+impl<F, Pos> SteadyStateMonitor<F, Pos> {
+    /// Compares the positions of cells every `window` iterations.
+    ///
+    /// # Panics
+    /// Panics if the window is zero.
+    pub fn new(window: usize, position_tolerance: F) -> Self {
+        assert!(
+            window > 0,
+            "the window of the steady state monitor must not be zero"
+        );
+        Self {
+            window,
+            position_tolerance,
+            field_tolerance: None,
+            checkpoints: Arc::new(Mutex::new(BTreeMap::new())),
+            positions: Arc::new(Mutex::new(BTreeMap::new())),
+            converged: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Number of iterations between two checkpoints
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Additionally compare the norm of the extracellular fields.
+    pub fn field_tolerance(self, field_tolerance: F) -> Self {
+        Self {
+            field_tolerance: Some(field_tolerance),
+            ..self
+        }
+    }
+
+    /// The window in which the simulation has converged if any
+    pub fn converged(&self) -> Option<SteadyStateRecord<F>>
+    where
+        F: Clone,
+    {
+        self.converged
+            .lock()
+            .map(|converged| converged.clone())
+            .unwrap_or_default()
+    }
+
+    /// Checks if the state should be compared at this iteration.
+    pub fn is_checkpoint(&self, next_time_point: &NextTimePoint<F>) -> bool {
+        next_time_point.iteration.is_multiple_of(self.window)
+    }
+
+    /// Modifies the checkpoint of the given iteration and removes outdated ones.
+    fn update_checkpoint(
+        &self,
+        iteration: usize,
+        update: impl FnOnce(&mut Checkpoint<F>),
+    ) -> Result<(), SimulationError>
+    where
+        F: num::Float,
+    {
+        let mut checkpoints = self
+            .checkpoints
+            .lock()
+            .map_err(|e| SimulationError::OtherThreadError(e.to_string()))?;
+        // All threads have evaluated older checkpoints since then
+        let oldest = iteration.saturating_sub(self.window);
+        checkpoints.retain(|i, _| *i >= oldest);
+        update(checkpoints.entry(iteration).or_insert(Checkpoint {
+            n_cells: 0,
+            n_new_cells: 0,
+            max_displacement: F::zero(),
+            squared_field_norm: None,
+        }));
+        Ok(())
+    }
+
+    /// Counts the cells of the subdomain.
+    ///
+    /// Use [SteadyStateMonitor::record_cells] instead if positions should be compared.
+    pub fn record_population<I, S, C, A, Com, Sy, R>(
+        &self,
+        sbox: &SubDomainBox<I, S, C, A, Com, Sy, R>,
+        next_time_point: &NextTimePoint<F>,
+    ) -> Result<(), SimulationError>
+    where
+        S: SubDomain,
+        F: num::Float,
+    {
+        let n_cells = sbox.iter_cells().count();
+        self.update_checkpoint(next_time_point.iteration, |checkpoint| {
+            checkpoint.n_cells += n_cells
+        })
+    }
+
+    /// Counts the cells of the subdomain and calculates their displacement since the previous
+    /// checkpoint.
+    pub fn record_cells<I, S, C, A, Com, Sy, R>(
+        &self,
+        sbox: &SubDomainBox<I, S, C, A, Com, Sy, R>,
+        next_time_point: &NextTimePoint<F>,
+    ) -> Result<(), SimulationError>
+    where
+        S: SubDomain,
+        C: Position<Pos>,
+        C: OverlapMeasure<Pos, F>,
+        F: num::Float,
+    {
+        let iteration = next_time_point.iteration;
+        let current = sbox.cell_positions();
+        let n_cells = current.len();
+        let (n_new_cells, max_displacement) = {
+            let mut positions = self
+                .positions
+                .lock()
+                .map_err(|e| SimulationError::OtherThreadError(e.to_string()))?;
+            let previous_iteration = iteration.checked_sub(self.window);
+            positions.retain(|i, _| Some(*i) >= previous_iteration);
+            let previous = previous_iteration.and_then(|i| positions.get(&i));
+            let mut n_new_cells = 0;
+            let mut max_displacement = F::zero();
+            for (identifier, pos) in current.iter() {
+                match previous.and_then(|previous| previous.get(identifier)) {
+                    Some(previous) => {
+                        max_displacement = max_displacement.max(C::distance(previous, pos))
+                    }
+                    None => n_new_cells += 1,
+                }
+            }
+            positions.entry(iteration).or_default().extend(current);
+            (n_new_cells, max_displacement)
+        };
+        self.update_checkpoint(iteration, |checkpoint| {
+            checkpoint.n_cells += n_cells;
+            checkpoint.n_new_cells += n_new_cells;
+            checkpoint.max_displacement = checkpoint.max_displacement.max(max_displacement);
+        })
+    }
+
+    /// Adds the norm of the extracellular fields of the subdomain.
+    pub fn record_fields<I, S, C, A, Com, Sy, R>(
+        &self,
+        sbox: &SubDomainBox<I, S, C, A, Com, Sy, R>,
+        next_time_point: &NextTimePoint<F>,
+    ) -> Result<(), SimulationError>
+    where
+        S: SubDomain,
+        S: FieldNorm<F>,
+        F: num::Float,
+    {
+        let squared_field_norm = sbox.subdomain.squared_field_norm();
+        self.update_checkpoint(next_time_point.iteration, |checkpoint| {
+            checkpoint.squared_field_norm =
+                Some(checkpoint.squared_field_norm.unwrap_or(F::zero()) + squared_field_norm);
+        })
+    }
+
+    /// Compares the current checkpoint to the previous one.
+    ///
+    /// All threads need to be synchronized after recording the checkpoint and before calling
+    /// this function.
+    /// Returns the [SteadyStateRecord] if the simulation has converged.
+    pub fn evaluate(
+        &self,
+        next_time_point: &NextTimePoint<F>,
+    ) -> Result<Option<SteadyStateRecord<F>>, SimulationError>
+    where
+        F: num::Float,
+    {
+        let iteration = next_time_point.iteration;
+        let (current, previous) = {
+            let checkpoints = self
+                .checkpoints
+                .lock()
+                .map_err(|e| SimulationError::OtherThreadError(e.to_string()))?;
+            let previous = iteration
+                .checked_sub(self.window)
+                .and_then(|i| checkpoints.get(&i).cloned());
+            match (checkpoints.get(&iteration).cloned(), previous) {
+                (Some(current), Some(previous)) => (current, previous),
+                _ => return Ok(None),
+            }
+        };
+        let relative_field_change = match (current.squared_field_norm, previous.squared_field_norm)
+        {
+            (Some(current), Some(previous)) => {
+                let (current, previous) = (current.sqrt(), previous.sqrt());
+                Some((current - previous).abs() / previous.max(F::epsilon()))
+            }
+            _ => None,
+        };
+        let fields_converged = match (self.field_tolerance, relative_field_change) {
+            (Some(tolerance), Some(change)) => change <= tolerance,
+            _ => true,
+        };
+        if current.n_new_cells > 0
+            || current.n_cells != previous.n_cells
+            || current.max_displacement > self.position_tolerance
+            || !fields_converged
+        {
+            return Ok(None);
+        }
+        let record = SteadyStateRecord {
+            iteration,
+            time: next_time_point.time,
+            n_cells: current.n_cells,
+            max_displacement: current.max_displacement,
+            relative_field_change,
+        };
+        self.converged
+            .lock()
+            .map_err(|e| SimulationError::OtherThreadError(e.to_string()))?
+            .get_or_insert_with(|| record.clone());
+        Ok(Some(record))
+    }
+}
+
+#[cfg(test)]
+mod test_steady_state {
+    use super::*;
+
+    fn time_point(iteration: usize) -> NextTimePoint<f64> {
+        NextTimePoint {
+            increment: 0.1,
+            time: iteration as f64 * 0.1,
+            iteration,
+            event: None,
+        }
+    }
+
+    fn checkpoint(
+        monitor: &SteadyStateMonitor<f64, f64>,
+        iteration: usize,
+        n_cells: usize,
+        max_displacement: f64,
+        squared_field_norm: f64,
+    ) {
+        monitor
+            .update_checkpoint(iteration, |checkpoint| {
+                checkpoint.n_cells += n_cells;
+                checkpoint.max_displacement = checkpoint.max_displacement.max(max_displacement);
+                checkpoint.squared_field_norm = Some(squared_field_norm);
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn converges_after_relaxation() {
+        let monitor = SteadyStateMonitor::new(10, 0.1).field_tolerance(0.01);
+        assert!(!monitor.is_checkpoint(&time_point(5)));
+        assert!(monitor.is_checkpoint(&time_point(20)));
+        // The first checkpoint can not be compared
+        checkpoint(&monitor, 10, 5, 0.0, 4.0);
+        assert_eq!(monitor.evaluate(&time_point(10)).unwrap(), None);
+        // Cells still move
+        checkpoint(&monitor, 20, 5, 0.5, 4.0);
+        assert_eq!(monitor.evaluate(&time_point(20)).unwrap(), None);
+        // Fields still change
+        checkpoint(&monitor, 30, 5, 0.05, 4.2);
+        assert_eq!(monitor.evaluate(&time_point(30)).unwrap(), None);
+        // The population changes
+        checkpoint(&monitor, 40, 6, 0.05, 4.2);
+        assert_eq!(monitor.evaluate(&time_point(40)).unwrap(), None);
+        checkpoint(&monitor, 50, 6, 0.05, 4.201);
+        let record = monitor.evaluate(&time_point(50)).unwrap().unwrap();
+        assert_eq!(record.n_cells, 6);
+        assert!(record.relative_field_change.unwrap() < 1e-3);
+        assert_eq!(monitor.converged(), Some(record));
+        // Old checkpoints are discarded
+        checkpoint(&monitor, 60, 6, 0.0, 4.201);
+        assert_eq!(monitor.checkpoints.lock().unwrap().len(), 2);
+    }
+}
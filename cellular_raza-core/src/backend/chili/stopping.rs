@@ -0,0 +1,383 @@
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use cellular_raza_concepts::SubDomain;
+use serde::{Deserialize, Serialize};
+
+use super::{SimulationError, SubDomainBox};
+use crate::time::NextTimePoint;
+
+/// Quantities of the whole simulation which are evaluated by the [StoppingCriteria].
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct GlobalObservables<F> {
+    /// Current iteration
+    pub iteration: usize,
+    /// Simulated time
+    pub time: F,
+    /// Total number of cells in all subdomains
+    pub n_cells: usize,
+    /// Wall-clock time since the first step of the simulation
+    pub wall_time: Duration,
+}
+
+/// Describes which of the [StoppingCriteria] terminated the simulation.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub enum StopReason {
+    /// The simulated time reached [StoppingCriteria::max_time].
+    MaxTime,
+    /// The wall-clock time exceeded [StoppingCriteria::max_wall_time].
+    MaxWallTime,
+    /// The number of cells reached [StoppingCriteria::max_cells].
+    MaxCells,
+    /// No cells are left in the simulation.
+    Extinction,
+    /// The custom predicate given by [StoppingCriteria::stop_if] returned `true`.
+    Custom,
+}
+
+/// Emitted when the simulation was stopped by the [StoppingCriteria].
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct StopRecord<F> {
+    /// Reason for stopping the simulation
+    pub reason: StopReason,
+    /// Observables at the last iteration which was simulated
+    pub observables: GlobalObservables<F>,
+}
+
+/// User-defined criterion which is evaluated on the combined observables
+type StopPredicate<F> = Arc<dyn Fn(&GlobalObservables<F>) -> bool + Send + Sync>;
+
+/// Terminates the simulation before the [TimeStepper](crate::time::TimeStepper) is exhausted.
+///
+/// After every step, each subdomain reports its number of cells and the elapsed wall-clock
+/// time.
+/// Afterwards, all threads are synchronized and evaluate the criteria on the combined
+/// [GlobalObservables] such that every thread takes the same decision.
+/// The criteria are checked in the order of the variants of [StopReason].
+/// When the simulation is stopped, the last state is saved regardless of the save points of the
+/// time stepper and the [StopRecord] can be obtained via [StoppingCriteria::stop_record].
+///
+/// The criteria are passed to the [run_simulation](crate::backend::chili::run_simulation)
+/// macro via the `stopping_criteria` argument.
+/// All clones share their state.
+/// ```
+/// # use cellular_raza_core::backend::chili::{GlobalObservables, StoppingCriteria};
+/// let criteria = StoppingCriteria::new()
+///     .max_time(200.0)
+///     .max_wall_time(std::time::Duration::from_secs(3600))
+///     .max_cells(10_000)
+///     .stop_on_extinction()
+///     .stop_if(|observables: &GlobalObservables<f64>| {
+///         observables.time > 50.0 && observables.n_cells < 10
+///     });
+/// // Pass `stopping_criteria: criteria` to the run_simulation macro
+/// assert!(criteria.stop_record().is_none());
+/// ```
+#[derive(Clone)]
+pub struct StoppingCriteria<F> {
+    /// Maximum simulated time
+    pub max_time: Option<F>,
+    /// Maximum wall-clock time of the simulation
+    pub max_wall_time: Option<Duration>,
+    /// Maximum number of cells
+    pub max_cells: Option<usize>,
+    /// Stop the simulation when no cells are left
+    pub extinction: bool,
+    /// User-defined criterion given by [StoppingCriteria::stop_if]
+    custom: Option<StopPredicate<F>>,
+    /// Start of the first step
+    start: Arc<OnceLock<Instant>>,
+    /// Number of cells cycling over three consecutive iterations
+    n_cells: Arc<[AtomicUsize; 3]>,
+    /// Elapsed nanoseconds of the slowest subdomain cycling over three consecutive iterations
+    elapsed: Arc<[AtomicU64; 3]>,
+    /// Reason and observables once the simulation was stopped
+    record: Arc<Mutex<Option<StopRecord<F>>>>,
+}
+
+impl<F> Default for StoppingCriteria<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F> StoppingCriteria<F> {
+    /// Criteria which never stop the simulation
+    pub fn new() -> Self {
+        Self {
+            max_time: None,
+            max_wall_time: None,
+            max_cells: None,
+            extinction: false,
+            custom: None,
+            start: Arc::new(OnceLock::new()),
+            n_cells: Arc::new([
+                AtomicUsize::new(0),
+                AtomicUsize::new(0),
+                AtomicUsize::new(0),
+            ]),
+            elapsed: Arc::new([AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0)]),
+            record: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Stop when the simulated time reaches the given value.
+    pub fn max_time(self, max_time: F) -> Self {
+        Self {
+            max_time: Some(max_time),
+            ..self
+        }
+    }
+
+    /// Stop when the simulation took longer than the given duration.
+    pub fn max_wall_time(self, max_wall_time: Duration) -> Self {
+        Self {
+            max_wall_time: Some(max_wall_time),
+            ..self
+        }
+    }
+
+    /// Stop when the number of cells reaches the given value.
+    pub fn max_cells(self, max_cells: usize) -> Self {
+        Self {
+            max_cells: Some(max_cells),
+            ..self
+        }
+    }
+
+    /// Stop when no cells are left.
+    pub fn stop_on_extinction(self) -> Self {
+        Self {
+            extinction: true,
+            ..self
+        }
+    }
+
+    /// Stop when the given predicate returns `true`.
+    ///
+    /// The predicate is evaluated by every thread and thus needs to be deterministic.
+    pub fn stop_if(
+        self,
+        predicate: impl Fn(&GlobalObservables<F>) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            custom: Some(Arc::new(predicate)),
+            ..self
+        }
+    }
+
+    /// The reason and observables at which the simulation was stopped if any
+    pub fn stop_record(&self) -> Option<StopRecord<F>>
+    where
+        F: Clone,
+    {
+        self.record
+            .lock()
+            .map(|record| record.clone())
+            .unwrap_or_default()
+    }
+
+    /// Reports the number of cells of the subdomain and the elapsed wall-clock time.
+    ///
+    /// This needs to be called by every thread after each step.
+    pub fn record<I, S, C, A, Com, Sy, R>(
+        &self,
+        sbox: &SubDomainBox<I, S, C, A, Com, Sy, R>,
+        next_time_point: &NextTimePoint<F>,
+    ) where
+        S: SubDomain,
+    {
+        let start = self.start.get_or_init(Instant::now);
+        let elapsed = u64::try_from(start.elapsed().as_nanos()).unwrap_or(u64::MAX);
+        self.report(
+            next_time_point.iteration,
+            sbox.iter_cells().count(),
+            elapsed,
+        );
+    }
+
+    /// Adds the values of a single subdomain to the slot of the given iteration.
+    fn report(&self, iteration: usize, n_cells: usize, elapsed: u64) {
+        // The slot of the next iteration was last read when evaluating two iterations ago.
+        // Every thread finished this evaluation before the previous call to sync_all returned.
+        let next_slot = (iteration + 1) % 3;
+        self.n_cells[next_slot].store(0, Ordering::SeqCst);
+        self.elapsed[next_slot].store(0, Ordering::SeqCst);
+        let slot = iteration % 3;
+        self.n_cells[slot].fetch_add(n_cells, Ordering::SeqCst);
+        self.elapsed[slot].fetch_max(elapsed, Ordering::SeqCst);
+    }
+
+    /// Evaluates the criteria on the values reported by all threads.
+    ///
+    /// All threads need to be synchronized after calling [StoppingCriteria::record] and before
+    /// calling this function.
+    pub fn evaluate(
+        &self,
+        next_time_point: &NextTimePoint<F>,
+    ) -> Result<Option<StopRecord<F>>, SimulationError>
+    where
+        F: Clone + PartialOrd,
+    {
+        let slot = next_time_point.iteration % 3;
+        let observables = GlobalObservables {
+            iteration: next_time_point.iteration,
+            time: next_time_point.time.clone(),
+            n_cells: self.n_cells[slot].load(Ordering::SeqCst),
+            wall_time: Duration::from_nanos(self.elapsed[slot].load(Ordering::SeqCst)),
+        };
+
+        let reason = if self
+            .max_time
+            .as_ref()
+            .is_some_and(|max_time| &observables.time >= max_time)
+        {
+            StopReason::MaxTime
+        } else if self
+            .max_wall_time
+            .is_some_and(|max_wall_time| observables.wall_time >= max_wall_time)
+        {
+            StopReason::MaxWallTime
+        } else if self
+            .max_cells
+            .is_some_and(|max_cells| observables.n_cells >= max_cells)
+        {
+            StopReason::MaxCells
+        } else if self.extinction && observables.n_cells == 0 {
+            StopReason::Extinction
+        } else if self
+            .custom
+            .as_ref()
+            .is_some_and(|predicate| predicate(&observables))
+        {
+            StopReason::Custom
+        } else {
+            return Ok(None);
+        };
+        let record = StopRecord {
+            reason,
+            observables,
+        };
+        self.record
+            .lock()
+            .map_err(|e| SimulationError::OtherThreadError(e.to_string()))?
+            .get_or_insert_with(|| record.clone());
+        Ok(Some(record))
+    }
+}
+
+#[cfg(test)]
+mod test_stopping {
+    use super::*;
+
+    fn time_point(iteration: usize) -> NextTimePoint<f64> {
+        NextTimePoint {
+            increment: 0.1,
+            time: iteration as f64 * 0.1,
+            iteration,
+            event: None,
+        }
+    }
+
+    /// Reports the given number of cells as if they were recorded by multiple subdomains
+    fn report(criteria: &StoppingCriteria<f64>, iteration: usize, n_cells: &[usize]) {
+        for n in n_cells {
+            criteria.report(iteration, *n, 0);
+        }
+    }
+
+    #[test]
+    fn no_criteria() {
+        let criteria = StoppingCriteria::new();
+        for iteration in 0..10 {
+            report(&criteria, iteration, &[0, 0]);
+            assert_eq!(criteria.evaluate(&time_point(iteration)).unwrap(), None);
+        }
+        assert!(criteria.stop_record().is_none());
+    }
+
+    #[test]
+    fn max_cells_sums_subdomains() {
+        let criteria = StoppingCriteria::new().max_cells(10).stop_on_extinction();
+        for iteration in 0..3 {
+            report(&criteria, iteration, &[2 + iteration, 3]);
+            assert_eq!(criteria.evaluate(&time_point(iteration)).unwrap(), None);
+        }
+        report(&criteria, 3, &[6, 4]);
+        let record = criteria.evaluate(&time_point(3)).unwrap().unwrap();
+        assert_eq!(record.reason, StopReason::MaxCells);
+        assert_eq!(record.observables.n_cells, 10);
+        assert_eq!(criteria.stop_record(), Some(record));
+    }
+
+    #[test]
+    fn time_extinction_and_custom() {
+        let criteria = StoppingCriteria::new().max_time(0.45);
+        report(&criteria, 5, &[1]);
+        let record = criteria.evaluate(&time_point(5)).unwrap().unwrap();
+        assert_eq!(record.reason, StopReason::MaxTime);
+
+        let criteria = StoppingCriteria::new().stop_on_extinction();
+        report(&criteria, 1, &[0, 0]);
+        let record = criteria.evaluate(&time_point(1)).unwrap().unwrap();
+        assert_eq!(record.reason, StopReason::Extinction);
+
+        let criteria = StoppingCriteria::new()
+            .stop_if(|observables: &GlobalObservables<f64>| observables.iteration == 2);
+        report(&criteria, 1, &[4]);
+        assert_eq!(criteria.evaluate(&time_point(1)).unwrap(), None);
+        report(&criteria, 2, &[4]);
+        let record = criteria.evaluate(&time_point(2)).unwrap().unwrap();
+        assert_eq!(record.reason, StopReason::Custom);
+        assert_eq!(record.observables.n_cells, 4);
+    }
+
+    #[test]
+    fn neighbor_sync_chain() {
+        use crate::backend::chili::{FromMap, NeighborSync, SyncSubDomains};
+        use std::collections::{BTreeMap, BTreeSet};
+        // Only adjacent subdomains are neighbors such that the first and last subdomain may be
+        // multiple steps apart between two calls to sync_all
+        let n_subdomains = 5;
+        let map: BTreeMap<_, BTreeSet<_>> = (0..n_subdomains)
+            .map(|i: usize| {
+                let neighbors = [i.checked_sub(1), Some(i + 1).filter(|j| *j < n_subdomains)];
+                (i, neighbors.into_iter().flatten().collect())
+            })
+            .collect();
+        let n_cells = |key: usize, iteration: usize| (key + 1) * (iteration % 4 + 1);
+        let total = move |iteration| {
+            (0..n_subdomains)
+                .map(|key| n_cells(key, iteration))
+                .sum::<usize>()
+        };
+        let criteria =
+            StoppingCriteria::new().stop_if(move |observables: &GlobalObservables<f64>| {
+                observables.n_cells != total(observables.iteration)
+            });
+        let handles: Vec<_> = NeighborSync::from_map(&map)
+            .unwrap()
+            .into_iter()
+            .map(|(key, mut syncer)| {
+                let criteria = criteria.clone();
+                std::thread::spawn(move || {
+                    for iteration in 0..200 {
+                        syncer.sync().unwrap();
+                        criteria.report(iteration, n_cells(key, iteration), 0);
+                        syncer.sync_all().unwrap();
+                        // Delay the evaluation of the first subdomain
+                        if key == 0 && iteration % 3 == 0 {
+                            std::thread::sleep(Duration::from_millis(1));
+                        }
+                        assert_eq!(criteria.evaluate(&time_point(iteration)).unwrap(), None);
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert!(criteria.stop_record().is_none());
+    }
+}
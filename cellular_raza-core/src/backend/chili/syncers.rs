@@ -0,0 +1,442 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use cellular_raza_concepts::IndexError;
+
+use super::{FromMap, SimulationError, SyncSubDomains};
+
+/// Number of times a waiting thread spins before yielding to other threads
+const SPIN_LIMIT: usize = 1 << 10;
+
+/// Time after which a thread waiting for messages checks if another thread has failed
+const RECEIVE_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(1);
+
+/// Error which is returned when another thread stored an error
+fn other_thread_error() -> SimulationError {
+    SimulationError::OtherThreadError("Another thread returned an error. Winding down.".into())
+}
+
+/// Waits until the condition is fulfilled or another thread has stored an error.
+fn wait_until(
+    got_error: &AtomicBool,
+    mut condition: impl FnMut() -> bool,
+) -> Result<(), SimulationError> {
+    let mut n_spins = 0;
+    while !condition() {
+        if got_error.load(Ordering::Acquire) {
+            return Err(other_thread_error());
+        }
+        match n_spins < SPIN_LIMIT {
+            true => {
+                core::hint::spin_loop();
+                n_spins += 1;
+            }
+            false => std::thread::yield_now(),
+        }
+    }
+    match got_error.load(Ordering::Acquire) {
+        true => Err(other_thread_error()),
+        false => Ok(()),
+    }
+}
+
+/// Synchronizes all subdomains without blocking such that errors of other threads are noticed.
+struct GlobalSync {
+    /// Number of times any subdomain has arrived at a global synchronization point
+    arrived: Arc<AtomicUsize>,
+    /// Number of global synchronization points passed by this subdomain
+    generation: usize,
+    /// Total number of subdomains
+    n_subdomains: usize,
+    /// Set when any thread stored an error
+    got_error: Arc<AtomicBool>,
+}
+
+impl GlobalSync {
+    /// Constructs one [GlobalSync] for every key of the map.
+    fn from_map<I: Clone + Ord>(
+        map: &BTreeMap<I, BTreeSet<I>>,
+    ) -> (BTreeMap<I, Self>, Arc<AtomicBool>) {
+        let arrived = Arc::new(AtomicUsize::new(0));
+        let got_error = Arc::new(AtomicBool::new(false));
+        let syncs = map
+            .keys()
+            .map(|key| {
+                (
+                    key.clone(),
+                    Self {
+                        arrived: Arc::clone(&arrived),
+                        generation: 0,
+                        n_subdomains: map.len(),
+                        got_error: Arc::clone(&got_error),
+                    },
+                )
+            })
+            .collect();
+        (syncs, got_error)
+    }
+
+    /// Waits until all subdomains have arrived.
+    fn sync_all(&mut self) -> Result<(), SimulationError> {
+        self.generation += 1;
+        self.arrived.fetch_add(1, Ordering::AcqRel);
+        let target = self.generation * self.n_subdomains;
+        wait_until(&self.got_error, || {
+            self.arrived.load(Ordering::Acquire) >= target
+        })
+    }
+
+    /// Notifies all other threads if an error occurred.
+    fn store_error(
+        &mut self,
+        maybe_error: Result<(), SimulationError>,
+    ) -> Result<bool, SimulationError> {
+        match maybe_error {
+            Ok(_) => Ok(false),
+            Err(SimulationError::OtherThreadError(_)) => Ok(true),
+            Err(x) => {
+                self.got_error.store(true, Ordering::Release);
+                Err(x)
+            }
+        }
+    }
+}
+
+/// Only waits for neighboring subdomains instead of all subdomains.
+///
+/// Every subdomain counts how often it has called [SyncSubDomains::sync] and waits until all
+/// of its neighbors have reached the same count.
+/// Since subdomains only exchange messages with their neighbors, this is sufficient for the
+/// update steps of the simulation.
+/// In contrast to the [BarrierSync](super::BarrierSync), subdomains which are far apart can be
+/// at different steps at the same time.
+/// This reduces the time spent waiting when the number of subdomains is large or when the
+/// workload is unevenly distributed.
+///
+/// Waiting threads spin for a short time and afterwards yield to other threads.
+/// It is thus best suited for simulations with at most one thread per core.
+/// Simulation-wide decisions such as the ones of
+/// [StoppingCriteria](super::StoppingCriteria) use [SyncSubDomains::sync_all] which waits
+/// for all subdomains.
+///
+/// The strategy is chosen via the `syncer` argument of the
+/// [run_simulation](crate::backend::chili::run_simulation) macro.
+/// ```
+/// # use std::collections::{BTreeMap, BTreeSet};
+/// # use cellular_raza_core::backend::chili::{FromMap, NeighborSync, SyncSubDomains};
+/// // Subdomains 0 and 2 are not neighbors
+/// let map = BTreeMap::from([
+///     (0, BTreeSet::from([1])),
+///     (1, BTreeSet::from([0, 2])),
+///     (2, BTreeSet::from([1])),
+/// ]);
+/// let syncers = NeighborSync::from_map(&map).unwrap();
+/// let handles: Vec<_> = syncers
+///     .into_iter()
+///     .map(|(_, mut syncer)| {
+///         std::thread::spawn(move || {
+///             for _ in 0..100 {
+///                 syncer.sync().unwrap();
+///             }
+///             syncer.sync_all().unwrap();
+///         })
+///     })
+///     .collect();
+/// for handle in handles {
+///     handle.join().unwrap();
+/// }
+/// ```
+pub struct NeighborSync {
+    /// Number of times this subdomain called [SyncSubDomains::sync]
+    generation: Arc<AtomicUsize>,
+    /// Counters of all neighboring subdomains
+    neighbors: Vec<Arc<AtomicUsize>>,
+    /// Used for [SyncSubDomains::sync_all]
+    global: GlobalSync,
+}
+
+impl<I> FromMap<I> for NeighborSync {
+    fn from_map(map: &BTreeMap<I, BTreeSet<I>>) -> Result<BTreeMap<I, Self>, IndexError>
+    where
+        I: Eq + core::hash::Hash + Clone + Ord,
+    {
+        let generations: BTreeMap<_, _> = map
+            .keys()
+            .map(|key| (key.clone(), Arc::new(AtomicUsize::new(0))))
+            .collect();
+        let (mut globals, _) = GlobalSync::from_map(map);
+        map.iter()
+            .map(|(key, neighbors)| {
+                let neighbors = neighbors
+                    .iter()
+                    .map(|neighbor| {
+                        generations.get(neighbor).map(Arc::clone).ok_or(IndexError(
+                            "Neighbor of subdomain could not be found in map".into(),
+                        ))
+                    })
+                    .collect::<Result<_, _>>()?;
+                Ok((
+                    key.clone(),
+                    Self {
+                        generation: Arc::clone(&generations[key]),
+                        neighbors,
+                        global: globals.remove(key).unwrap(),
+                    },
+                ))
+            })
+            .collect()
+    }
+}
+
+impl SyncSubDomains for NeighborSync {
+    fn sync(&mut self) -> Result<(), SimulationError> {
+        let generation = self.generation.fetch_add(1, Ordering::AcqRel) + 1;
+        wait_until(&self.global.got_error, || {
+            self.neighbors
+                .iter()
+                .all(|neighbor| neighbor.load(Ordering::Acquire) >= generation)
+        })
+    }
+
+    fn sync_all(&mut self) -> Result<(), SimulationError> {
+        self.global.sync_all()
+    }
+
+    fn store_error(
+        &mut self,
+        maybe_error: Result<(), SimulationError>,
+    ) -> Result<bool, SimulationError> {
+        self.global.store_error(maybe_error)
+    }
+}
+
+/// Synchronizes neighboring subdomains by counting messages instead of spinning.
+///
+/// Every subdomain sends a message to each of its neighbors when calling
+/// [SyncSubDomains::sync] and waits until it has received as many messages from every
+/// neighbor as it has sent.
+/// Like the [NeighborSync], only neighboring subdomains wait for each other.
+/// Waiting threads block while receiving messages and do not occupy a core.
+/// This makes this strategy suitable when more subdomains than cores are used, at the cost of
+/// a higher latency compared to the [NeighborSync].
+/// Simulation-wide decisions use [SyncSubDomains::sync_all] which waits for all subdomains.
+///
+/// The strategy is chosen via the `syncer` argument of the
+/// [run_simulation](crate::backend::chili::run_simulation) macro.
+/// ```
+/// # use std::collections::{BTreeMap, BTreeSet};
+/// # use cellular_raza_core::backend::chili::{FromMap, MessageSync, SyncSubDomains};
+/// let map = BTreeMap::from([
+///     (0, BTreeSet::from([1])),
+///     (1, BTreeSet::from([0])),
+/// ]);
+/// let mut syncers = MessageSync::from_map(&map).unwrap();
+/// let mut syncer_0 = syncers.remove(&0).unwrap();
+/// let mut syncer_1 = syncers.remove(&1).unwrap();
+/// let handle = std::thread::spawn(move || syncer_0.sync().unwrap());
+/// syncer_1.sync().unwrap();
+/// handle.join().unwrap();
+/// ```
+pub struct MessageSync {
+    /// Number of times this subdomain called [SyncSubDomains::sync]
+    generation: usize,
+    /// Senders to all neighbors together with the position of this subdomain in their list of
+    /// neighbors
+    senders: Vec<(crossbeam_channel::Sender<usize>, usize)>,
+    /// Receives the positions of neighbors which have called [SyncSubDomains::sync]
+    receiver: crossbeam_channel::Receiver<usize>,
+    /// Number of received messages from every neighbor
+    received: Vec<usize>,
+    /// Used for [SyncSubDomains::sync_all]
+    global: GlobalSync,
+}
+
+impl<I> FromMap<I> for MessageSync {
+    fn from_map(map: &BTreeMap<I, BTreeSet<I>>) -> Result<BTreeMap<I, Self>, IndexError>
+    where
+        I: Eq + core::hash::Hash + Clone + Ord,
+    {
+        let channels: BTreeMap<_, _> = map
+            .keys()
+            .map(|key| (key.clone(), crossbeam_channel::unbounded()))
+            .collect();
+        let (mut globals, _) = GlobalSync::from_map(map);
+        map.iter()
+            .map(|(key, neighbors)| {
+                let senders = neighbors
+                    .iter()
+                    .map(|neighbor| {
+                        let position = map
+                            .get(neighbor)
+                            .and_then(|reverse| reverse.iter().position(|k| k == key))
+                            .ok_or(IndexError(
+                                "Neighbors of subdomains need to be symmetric".into(),
+                            ))?;
+                        Ok((channels[neighbor].0.clone(), position))
+                    })
+                    .collect::<Result<_, IndexError>>()?;
+                Ok((
+                    key.clone(),
+                    Self {
+                        generation: 0,
+                        senders,
+                        receiver: channels[key].1.clone(),
+                        received: vec![0; neighbors.len()],
+                        global: globals.remove(key).unwrap(),
+                    },
+                ))
+            })
+            .collect()
+    }
+}
+
+impl SyncSubDomains for MessageSync {
+    fn sync(&mut self) -> Result<(), SimulationError> {
+        self.generation += 1;
+        for (sender, position) in self.senders.iter() {
+            sender.send(*position).map_err(|_| other_thread_error())?;
+        }
+        while self.received.iter().any(|n| *n < self.generation) {
+            match self.receiver.recv_timeout(RECEIVE_TIMEOUT) {
+                Ok(position) => self.received[position] += 1,
+                Err(crossbeam_channel::RecvTimeoutError::Timeout) => {
+                    if self.global.got_error.load(Ordering::Acquire) {
+                        return Err(other_thread_error());
+                    }
+                }
+                Err(crossbeam_channel::RecvTimeoutError::Disconnected) => {
+                    return Err(other_thread_error())
+                }
+            }
+        }
+        match self.global.got_error.load(Ordering::Acquire) {
+            true => Err(other_thread_error()),
+            false => Ok(()),
+        }
+    }
+
+    fn sync_all(&mut self) -> Result<(), SimulationError> {
+        self.global.sync_all()
+    }
+
+    fn store_error(
+        &mut self,
+        maybe_error: Result<(), SimulationError>,
+    ) -> Result<bool, SimulationError> {
+        self.global.store_error(maybe_error)
+    }
+}
+
+#[cfg(test)]
+mod test_syncers {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Checks that neighbors are synchronized after every call to sync and all subdomains
+    /// after every call to sync_all.
+    fn test_single_map<S>(map: BTreeMap<usize, BTreeSet<usize>>)
+    where
+        S: 'static + SyncSubDomains + FromMap<usize> + Send,
+    {
+        let n_iterations = 200;
+        let counters = Arc::new(Mutex::new(vec![0_usize; map.len()]));
+        let handles: Vec<_> = S::from_map(&map)
+            .unwrap()
+            .into_iter()
+            .map(|(key, mut syncer)| {
+                let counters = Arc::clone(&counters);
+                let neighbors = map[&key].clone();
+                std::thread::spawn(move || {
+                    for n_iteration in 0..n_iterations {
+                        syncer.sync().unwrap();
+                        counters.lock().unwrap()[key] += 1;
+                        syncer.sync().unwrap();
+                        let current = counters.lock().unwrap().clone();
+                        for neighbor in neighbors.iter() {
+                            assert_eq!(current[*neighbor], n_iteration + 1);
+                        }
+                        if n_iteration % 10 == 0 {
+                            syncer.sync_all().unwrap();
+                            let current = counters.lock().unwrap().clone();
+                            assert!(current.iter().all(|c| *c > n_iteration));
+                            syncer.sync_all().unwrap();
+                        }
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+
+    /// A chain of subdomains where only adjacent ones are neighbors
+    fn chain(n: usize) -> BTreeMap<usize, BTreeSet<usize>> {
+        (0..n)
+            .map(|i| {
+                let neighbors = [i.checked_sub(1), Some(i + 1).filter(|j| *j < n)]
+                    .into_iter()
+                    .flatten()
+                    .collect();
+                (i, neighbors)
+            })
+            .collect()
+    }
+
+    fn test_multiple_maps<S>()
+    where
+        S: 'static + SyncSubDomains + FromMap<usize> + Send,
+    {
+        test_single_map::<S>(chain(1));
+        test_single_map::<S>(chain(2));
+        test_single_map::<S>(chain(6));
+        test_single_map::<S>(BTreeMap::from([
+            (0, BTreeSet::from([1, 2])),
+            (1, BTreeSet::from([0, 3])),
+            (2, BTreeSet::from([0, 3])),
+            (3, BTreeSet::from([1, 2])),
+        ]));
+    }
+
+    /// Checks that threads do not wait forever when another thread returns an error.
+    fn test_error<S>()
+    where
+        S: 'static + SyncSubDomains + FromMap<usize> + Send,
+    {
+        let handles: Vec<_> = S::from_map(&chain(4))
+            .unwrap()
+            .into_iter()
+            .map(|(key, mut syncer)| {
+                std::thread::spawn(move || {
+                    for n_iteration in 0..100 {
+                        let result = match (key, n_iteration) {
+                            (2, 10) => Err(SimulationError::IndexError(IndexError("".into()))),
+                            _ => syncer.sync(),
+                        };
+                        match syncer.store_error(result) {
+                            Ok(false) => (),
+                            Ok(true) => return false,
+                            Err(_) => return true,
+                        }
+                    }
+                    panic!("Error was not noticed by thread {key}");
+                })
+            })
+            .collect();
+        let failed: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        assert_eq!(failed, vec![false, false, true, false]);
+    }
+
+    #[test]
+    fn neighbor_sync() {
+        test_multiple_maps::<NeighborSync>();
+        test_error::<NeighborSync>();
+    }
+
+    #[test]
+    fn message_sync() {
+        test_multiple_maps::<MessageSync>();
+        test_error::<MessageSync>();
+    }
+}
@@ -0,0 +1,269 @@
+//! Cells and voxels which are shared between the tests of this backend.
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::{
+    AuxStorageInteraction, AuxStorageMechanics, CellBox, ForceMeasure, OverlapMeasure,
+    RefinementCoordinates, RingBufferIterRef, SubDomainBox, SubDomainPlainIndex, UpdateInteraction,
+    UpdateMechanics, Voxel, VoxelPlainIndex,
+};
+use cellular_raza_concepts::*;
+use cellular_raza_core_proc_macro::AuxStorage;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+/// Attracts other cells within its range with a linear spring
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub(crate) struct Agent {
+    /// Position of the cell
+    pub pos: f64,
+    /// Stiffness of the spring
+    pub strength: f64,
+    /// Cells which are further apart do not interact
+    pub range: f64,
+}
+
+impl From<f64> for Agent {
+    fn from(pos: f64) -> Self {
+        Agent {
+            pos,
+            strength: 1.0,
+            range: f64::INFINITY,
+        }
+    }
+}
+
+impl From<(f64, f64)> for Agent {
+    fn from((pos, strength): (f64, f64)) -> Self {
+        Agent {
+            pos,
+            strength,
+            range: f64::INFINITY,
+        }
+    }
+}
+
+impl Position<f64> for Agent {
+    fn pos(&self) -> f64 {
+        self.pos
+    }
+
+    fn set_pos(&mut self, pos: &f64) {
+        self.pos = *pos;
+    }
+}
+
+impl Velocity<f64> for Agent {
+    fn velocity(&self) -> f64 {
+        0.0
+    }
+
+    fn set_velocity(&mut self, _: &f64) {}
+}
+
+impl Interaction<f64, f64, f64> for Agent {
+    fn get_interaction_information(&self) {}
+
+    fn calculate_force_between(
+        &self,
+        own_pos: &f64,
+        _: &f64,
+        ext_pos: &f64,
+        _: &f64,
+        _: &(),
+    ) -> Result<(f64, f64), CalcError> {
+        let force = match (ext_pos - own_pos).abs() < self.range {
+            true => self.strength * (ext_pos - own_pos),
+            false => 0.0,
+        };
+        Ok((force, -force))
+    }
+
+    fn normal_force(&self, own_pos: &f64, ext_pos: &f64, force: &f64) -> Result<f64, CalcError> {
+        Ok((own_pos - ext_pos).signum() * force)
+    }
+
+    fn is_neighbor(&self, own_pos: &f64, ext_pos: &f64, _: &()) -> Result<bool, CalcError> {
+        Ok((own_pos - ext_pos).abs() < 1.0)
+    }
+}
+
+impl RefinementCoordinates<1> for Agent {
+    fn refinement_coordinates(&self) -> [f64; 1] {
+        [self.pos]
+    }
+}
+
+impl OverlapMeasure<f64, f64> for Agent {
+    fn overlap_radius(&self) -> f64 {
+        0.5
+    }
+
+    fn distance(pos1: &f64, pos2: &f64) -> f64 {
+        (pos1 - pos2).abs()
+    }
+}
+
+impl ForceMeasure<f64, f64> for Agent {
+    fn force_magnitude(force: &f64) -> f64 {
+        force.abs()
+    }
+}
+
+/// Auxiliary storage for the [Mechanics] and [Interaction] aspects of the [Agent]
+#[derive(AuxStorage, Clone, Default)]
+pub(crate) struct AuxN<const N: usize> {
+    /// Previous positions, velocities and the current force
+    #[UpdateMechanics(f64, f64, f64, N)]
+    mechanics: AuxStorageMechanics<f64, f64, f64, N>,
+    /// Number of neighbors and pressure
+    #[UpdateInteraction]
+    interaction: AuxStorageInteraction,
+}
+
+/// Auxiliary storage which only remembers the last position and velocity
+pub(crate) type Aux = AuxN<1>;
+
+/// Creates a voxel which contains the given cells in this order
+pub(crate) fn voxel<T>(plain_index: usize, neighbors: &[usize], cells: &[T]) -> Voxel<Agent, Aux>
+where
+    T: Clone + Into<Agent>,
+{
+    let plain_index = VoxelPlainIndex(plain_index);
+    Voxel {
+        plain_index,
+        neighbors: neighbors.iter().map(|&n| VoxelPlainIndex(n)).collect(),
+        cells: cells
+            .iter()
+            .enumerate()
+            .map(|(n, cell)| {
+                let cbox = CellBox::new(plain_index, n as u64, cell.clone().into(), None);
+                (cbox, Aux::default())
+            })
+            .collect(),
+        new_cells: Vec::new(),
+        id_counter: cells.len() as u64,
+        rng: rand_chacha::ChaCha8Rng::seed_from_u64(0),
+        repulsive_only: false,
+    }
+}
+
+/// Interval `[0, n_voxels)` which is subdivided into voxels of unit length
+#[derive(Clone, Debug)]
+pub(crate) struct Line {
+    /// Number of voxels and length of the interval
+    pub n_voxels: usize,
+    /// Constant force which acts on every cell
+    pub domain_force: f64,
+}
+
+impl SubDomain for Line {
+    type VoxelIndex = usize;
+
+    fn get_neighbor_voxel_indices(&self, voxel_index: &usize) -> Vec<usize> {
+        [voxel_index.checked_sub(1), Some(voxel_index + 1)]
+            .into_iter()
+            .flatten()
+            .filter(|n| *n < self.n_voxels)
+            .collect()
+    }
+
+    fn get_all_indices(&self) -> Vec<usize> {
+        (0..self.n_voxels).collect()
+    }
+}
+
+impl SortCells<Agent> for Line {
+    type VoxelIndex = usize;
+
+    fn get_voxel_index_of(&self, cell: &Agent) -> Result<usize, BoundaryError> {
+        match cell.pos >= 0.0 && cell.pos < self.n_voxels as f64 {
+            true => Ok(cell.pos as usize),
+            false => Err(BoundaryError(format!("cell at {} is outside", cell.pos))),
+        }
+    }
+}
+
+impl SubDomainMechanics<f64, f64> for Line {
+    /// Reflects cells which are at most one length of the interval outside of it
+    fn apply_boundary(&self, pos: &mut f64, _: &mut f64) -> Result<(), BoundaryError> {
+        let length = self.n_voxels as f64;
+        if *pos < -length || *pos > 2.0 * length {
+            return Err(BoundaryError(format!("cell at {pos} is too far outside")));
+        }
+        if *pos < 0.0 {
+            *pos = -*pos;
+        }
+        if *pos > length {
+            *pos = 2.0 * length - *pos;
+        }
+        Ok(())
+    }
+
+    fn clamp_to_domain(&self, pos: &mut f64, _: &mut f64) -> Result<(), BoundaryError> {
+        *pos = pos.clamp(0.0, self.n_voxels as f64);
+        Ok(())
+    }
+
+    fn wrap_into_domain(&self, pos: &mut f64, _: &mut f64) -> Result<(), BoundaryError> {
+        *pos = pos.rem_euclid(self.n_voxels as f64);
+        Ok(())
+    }
+}
+
+impl SubDomainForce<f64, f64, f64> for Line {
+    fn calculate_custom_force(&self, _: &f64, _: &f64) -> Result<f64, CalcError> {
+        Ok(self.domain_force)
+    }
+}
+
+/// Subdomain which is not connected to any other subdomain
+pub(crate) type LineBox = SubDomainBox<usize, Line, Agent, Aux, (), ()>;
+
+/// Creates a single subdomain of the given length and sorts the cells into its voxels
+pub(crate) fn subdomain_box<T>(n_voxels: usize, cells: &[T]) -> LineBox
+where
+    T: Clone + Into<Agent>,
+{
+    let subdomain = Line {
+        n_voxels,
+        domain_force: 0.0,
+    };
+    let voxels = subdomain
+        .get_all_indices()
+        .into_iter()
+        .map(|n| {
+            let neighbors = subdomain.get_neighbor_voxel_indices(&n);
+            (VoxelPlainIndex(n), voxel::<Agent>(n, &neighbors, &[]))
+        })
+        .collect();
+    let mut sbox = SubDomainBox {
+        index: 0,
+        subdomain_plain_index: SubDomainPlainIndex(0),
+        neighbors: BTreeSet::new(),
+        voxels,
+        voxel_index_to_plain_index: (0..n_voxels).map(|n| (n, VoxelPlainIndex(n))).collect(),
+        plain_index_to_subdomain: (0..n_voxels)
+            .map(|n| (VoxelPlainIndex(n), SubDomainPlainIndex(0)))
+            .collect(),
+        subdomain,
+        communicator: (),
+        syncer: (),
+        division_throttle: Default::default(),
+        relaxation_remaining: BTreeMap::new(),
+        divisions: Vec::new(),
+        rng_mode: Default::default(),
+        rng_seed: 0,
+        iteration: 0,
+        global_parameters: Default::default(),
+        voxel_refinement: None,
+        refined_voxels: BTreeSet::new(),
+        halo_exchange: None,
+        voxel_parallelism: None,
+    };
+    let mut cells = cells
+        .iter()
+        .map(|cell| (cell.clone().into(), None))
+        .collect();
+    sbox.insert_cells(&mut cells, |_| Aux::default()).unwrap();
+    sbox
+}
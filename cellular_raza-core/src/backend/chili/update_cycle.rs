@@ -1,13 +1,240 @@
-use super::{CellBox, CellIdentifier, SimulationError, SubDomainBox, UpdateCycle, Voxel};
+use super::{
+    CellBox, CellIdentifier, RngStream, SimulationError, SubDomainBox, UpdateCycle, Voxel,
+    VoxelPlainIndex,
+};
+use crate::time::NextTimePoint;
 use cellular_raza_concepts::SubDomain;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 pub use cellular_raza_concepts::CycleEvent;
 
 #[cfg(feature = "tracing")]
 use tracing::instrument;
 
-impl<C, A> Voxel<C, A> {
-    #[cfg_attr(feature = "tracing", instrument(skip(self, default_from)))]
+/// Limits how many cells can divide at the same time.
+///
+/// When many cells divide in the same step, a voxel can become overcrowded which in turn
+/// destabilizes the mechanical solver.
+/// Postponed divisions are kept as [CycleEvent::Division] and retried in the next step.
+/// By default, no throttling is applied.
+/// ```
+/// # use cellular_raza_core::backend::chili::DivisionThrottle;
+/// let throttle = DivisionThrottle {
+///     max_divisions_per_step: Some(5),
+///     postpone_probability: 0.2,
+///     mass_division_threshold: Some(4),
+///     relaxation_steps: 10,
+/// };
+/// ```
+/// The throttle is passed to the `division_throttle` argument of the
+/// [run_simulation](crate::backend::chili::run_simulation) macro.
+/// To additionally relax the mechanics of newly created cells within the same step, see
+/// [DivisionRelaxation].
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct DivisionThrottle {
+    /// Maximum number of divisions per voxel and step
+    pub max_divisions_per_step: Option<usize>,
+    /// Probability to postpone any division to the next step.
+    /// This spreads synchronized divisions over multiple steps.
+    pub postpone_probability: f64,
+    /// Number of divisions in one voxel and step which is considered a mass division event.
+    /// This threshold is also used by the [DivisionRelaxation].
+    pub mass_division_threshold: Option<usize>,
+    /// After a mass division event, all divisions in this voxel are suspended for the given
+    /// number of steps such that the mechanics of the newly created cells can relax.
+    pub relaxation_steps: usize,
+}
+
+impl DivisionThrottle {
+    /// Checks if any throttling is applied at all.
+    fn is_active(&self) -> bool {
+        self.max_divisions_per_step.is_some()
+            || self.postpone_probability > 0.0
+            || self.mass_division_threshold.is_some()
+    }
+
+    /// Checks if the given number of divisions in one voxel and step is a mass division event.
+    fn is_mass_division(&self, n_divisions: usize) -> bool {
+        self.mass_division_threshold
+            .is_some_and(|threshold| n_divisions > 0 && n_divisions >= threshold)
+    }
+}
+
+/// Relaxes the mechanics in additional sub-steps after mass division events.
+///
+/// Daughter cells are usually placed very close to each other such that a burst of divisions
+/// leads to strongly overlapping cells.
+/// When at least [DivisionThrottle::mass_division_threshold] cells divide within one voxel
+/// and step, the mechanics of all cells is updated for [DivisionRelaxation::n_substeps]
+/// additional sub-steps with the increment [DivisionRelaxation::dt] before results are stored.
+/// Similarly to the [Equilibration](super::Equilibration), only the `Mechanics`, `Interaction`
+/// and `DomainForce` aspects are considered and time does not advance.
+/// Optionally, the distance which any cell may travel in a single sub-step is limited by
+/// [DivisionRelaxation::max_displacement].
+///
+/// The relaxation is passed to the
+/// [run_simulation](crate::backend::chili::run_simulation) macro via the
+/// `division_relaxation` argument and requires the `Mechanics` and `Cycle` aspects.
+/// Mass division events are only detected if a [DivisionThrottle] with a
+/// [DivisionThrottle::mass_division_threshold] is passed via the `division_throttle` argument.
+/// All clones share the mass division events of the current step such that every subdomain
+/// performs the same number of sub-steps.
+/// ```
+/// # use cellular_raza_core::backend::chili::{DivisionRelaxation, DivisionThrottle};
+/// let throttle = DivisionThrottle {
+///     mass_division_threshold: Some(10),
+///     ..Default::default()
+/// };
+/// let relaxation = DivisionRelaxation::new(5, 0.01).max_displacement(0.1);
+/// // Pass `division_throttle: throttle` and `division_relaxation: relaxation` to the
+/// // run_simulation macro
+/// ```
+#[derive(Clone, Debug)]
+pub struct DivisionRelaxation<F> {
+    /// Number of relaxation sub-steps after a mass division event
+    pub n_substeps: usize,
+    /// Time increment of every relaxation sub-step
+    pub dt: F,
+    /// Maximum distance which any cell may travel in one sub-step
+    pub max_displacement: Option<F>,
+    /// Flags whether a mass division event occurred in any subdomain.
+    /// Only the last three iterations are kept, see [DivisionRelaxation::record].
+    mass_divisions: Arc<[AtomicBool; 3]>,
+}
+
+impl<F> DivisionRelaxation<F> {
+    /// Relax for the given number of sub-steps without limiting displacements.
+    pub fn new(n_substeps: usize, dt: F) -> Self {
+        Self {
+            n_substeps,
+            dt,
+            max_displacement: None,
+            mass_divisions: Arc::new(Default::default()),
+        }
+    }
+
+    /// Limit the distance which any cell may travel in one sub-step.
+    pub fn max_displacement(self, max_displacement: F) -> Self {
+        Self {
+            max_displacement: Some(max_displacement),
+            ..self
+        }
+    }
+
+    /// Records a mass division event if enough cells divided in any voxel of the subdomain.
+    ///
+    /// Divisions are taken from the last call to [SubDomainBox::update_cell_cycle_4] and compared
+    /// against the [DivisionThrottle::mass_division_threshold] of the subdomain.
+    pub fn report<I, S, C, A, Com, Sy, R, G>(
+        &self,
+        sbox: &SubDomainBox<I, S, C, A, Com, Sy, R>,
+        next_time_point: &NextTimePoint<G>,
+    ) where
+        S: SubDomain,
+    {
+        let mut divisions_per_voxel = BTreeMap::<VoxelPlainIndex, usize>::new();
+        for (voxel_index, _, _) in sbox.divisions.iter() {
+            *divisions_per_voxel.entry(*voxel_index).or_default() += 1;
+        }
+        let max_divisions = divisions_per_voxel.into_values().max().unwrap_or(0);
+        self.record(
+            next_time_point.iteration,
+            sbox.division_throttle.is_mass_division(max_divisions),
+        );
+    }
+
+    /// Flags the slot of the given iteration if a mass division event occurred.
+    ///
+    /// All subdomains synchronize between reporting and reading the flag of one iteration.
+    /// The slot of the next iteration was last read two iterations ago and can thus be reset.
+    fn record(&self, iteration: usize, mass_division: bool) {
+        self.mass_divisions[(iteration + 1) % 3].store(false, Ordering::SeqCst);
+        if mass_division {
+            self.mass_divisions[iteration % 3].store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// Number of relaxation sub-steps which are performed at the given time point.
+    ///
+    /// All subdomains need to have reported their divisions beforehand.
+    pub fn n_substeps_at<G>(&self, next_time_point: &NextTimePoint<G>) -> usize {
+        match self.mass_divisions[next_time_point.iteration % 3].load(Ordering::SeqCst) {
+            true => self.n_substeps,
+            false => 0,
+        }
+    }
+
+    /// Time point which is used for the given relaxation sub-step.
+    ///
+    /// The time is not advanced and no events are scheduled.
+    /// Its iteration enumerates all sub-steps of the simulation such that random numbers do
+    /// not repeat between them.
+    pub fn time_point(&self, next_time_point: &NextTimePoint<F>, substep: usize) -> NextTimePoint<F>
+    where
+        F: Copy,
+    {
+        NextTimePoint {
+            increment: self.dt,
+            time: next_time_point.time,
+            iteration: next_time_point.iteration * self.n_substeps + substep,
+            event: None,
+        }
+    }
+}
+
+impl<I, S, C, A, Com, Sy, R> SubDomainBox<I, S, C, A, Com, Sy, R>
+where
+    S: SubDomain,
+{
+    /// Runs the local mechanics functions of all cells during a [DivisionRelaxation] sub-step.
+    ///
+    /// This is identical to [SubDomainBox::run_local_cell_funcs] but seeds counter-based
+    /// generators from a separate stream.
+    /// The iteration of the subdomain is not changed.
+    pub fn run_relaxation_cell_funcs<Func, F>(
+        &mut self,
+        func: Func,
+        next_time_point: &NextTimePoint<F>,
+    ) -> Result<(), SimulationError>
+    where
+        Func: Fn(&mut C, &mut A, F, &mut R) -> Result<(), SimulationError>,
+        F: Copy,
+        R: rand::SeedableRng,
+    {
+        let iteration = self.iteration;
+        let result = self.run_cell_funcs_in_stream(func, next_time_point, RngStream::Relaxation);
+        self.iteration = iteration;
+        result
+    }
+}
+
+/// Division of a cell which is stored by [SubDomainBox::save_divisions].
+///
+/// Since the mother cell obtains a new identifier upon division, the `parent` identifier is
+/// unique for every division event and used as the key in the storage.
+/// Proliferation rates and spatial patterns of division can thus be analyzed without comparing
+/// subsequent snapshots of the whole population.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DivisionEvent<F, P> {
+    /// Simulation time at which the division occurred
+    pub time: F,
+    /// Identifier of the mother cell before the division
+    pub parent: CellIdentifier,
+    /// Identifiers of both daughter cells where the first one is the former mother cell
+    pub daughters: [CellIdentifier; 2],
+    /// Positions of both daughter cells directly after the division
+    pub positions: [P; 2],
+}
+
+impl<C, A, R> Voxel<C, A, R> {
+    #[cfg_attr(
+        feature = "tracing",
+        instrument(skip(self, default_from, throttle, relaxation_remaining))
+    )]
     pub(crate) fn update_cell_cycle_4<
         #[cfg(feature = "tracing")] Float: core::fmt::Debug,
         #[cfg(not(feature = "tracing"))] Float,
@@ -15,38 +242,80 @@ impl<C, A> Voxel<C, A> {
     >(
         &mut self,
         default_from: &Func,
-    ) -> Result<(), SimulationError>
+        throttle: &DivisionThrottle,
+        relaxation_remaining: &mut usize,
+        counter_key: Option<(u64, u64)>,
+    ) -> Result<Vec<(CellIdentifier, [CellIdentifier; 2])>, SimulationError>
     where
-        C: cellular_raza_concepts::Cycle<C, Float>,
+        C: cellular_raza_concepts::Cycle<C, Float, R>,
         A: UpdateCycle,
         Func: Fn(&C) -> A,
+        R: Rng + rand::SeedableRng,
     {
+        let throttled = throttle.is_active();
+        let suspended = *relaxation_remaining > 0;
+        *relaxation_remaining = relaxation_remaining.saturating_sub(1);
+        let mut n_divisions = 0;
+        // Parent, identifier of the mother after division and index of the daughter
+        let mut divisions = Vec::new();
+
         // Update the cell individual cells
-        self.cells
-            .iter_mut()
-            .map(|(cbox, aux_storage)| {
-                // Check for cycle events and take action if necessary
-                let mut remaining_events = Vec::new();
-                for event in aux_storage.drain_cycle_events() {
-                    match event {
-                        CycleEvent::Division => {
-                            let new_cell = C::divide(&mut self.rng, &mut cbox.cell)?;
-                            let parent_ident = cbox.identifier;
-                            self.id_counter += 1;
-                            cbox.identifier = CellIdentifier(self.plain_index, self.id_counter);
-                            cbox.parent = Some(parent_ident);
-                            self.new_cells.push((new_cell, Some(parent_ident)));
-                        }
-                        CycleEvent::Remove => remaining_events.push(event),
-                        CycleEvent::PhasedDeath => {
-                            remaining_events.push(event);
+        for (cbox, aux_storage) in self.cells.iter_mut() {
+            // Check for cycle events and take action if necessary
+            let mut remaining_events = Vec::new();
+            let mut divided = false;
+            // Counter-based generator which does not depend on the order of cells
+            let mut cell_rng = counter_key.map(|(seed, iteration)| {
+                super::Philox4x32::for_cell_stream(
+                    seed,
+                    &cbox.identifier,
+                    iteration,
+                    super::RngStream::Division,
+                )
+                .to_rng()
+            });
+            let rng = cell_rng.as_mut().unwrap_or(&mut self.rng);
+            for event in aux_storage.drain_cycle_events() {
+                match event {
+                    CycleEvent::Division => {
+                        if throttled {
+                            // Only divide once per step and keep at most one postponed event
+                            if divided || remaining_events.contains(&CycleEvent::Division) {
+                                continue;
+                            }
+                            let postpone = suspended
+                                || throttle
+                                    .max_divisions_per_step
+                                    .is_some_and(|max| n_divisions >= max)
+                                || (throttle.postpone_probability > 0.0
+                                    && rng.gen_range(0.0..1.0) < throttle.postpone_probability);
+                            if postpone {
+                                remaining_events.push(event);
+                                continue;
+                            }
                         }
-                    };
-                }
-                aux_storage.set_cycle_events(remaining_events);
-                Ok(())
-            })
-            .collect::<Result<(), SimulationError>>()?;
+                        let new_cell = C::divide(rng, &mut cbox.cell)?;
+                        let parent_ident = cbox.identifier;
+                        self.id_counter += 1;
+                        cbox.identifier = CellIdentifier(self.plain_index, self.id_counter);
+                        cbox.parent = Some(parent_ident);
+                        divisions.push((parent_ident, cbox.identifier, self.new_cells.len()));
+                        self.new_cells.push((new_cell, Some(parent_ident)));
+                        divided = true;
+                        n_divisions += 1;
+                    }
+                    CycleEvent::Remove => remaining_events.push(event),
+                    CycleEvent::PhasedDeath => {
+                        remaining_events.push(event);
+                    }
+                };
+            }
+            aux_storage.set_cycle_events(remaining_events);
+        }
+
+        if throttle.is_mass_division(n_divisions) {
+            *relaxation_remaining = throttle.relaxation_steps;
+        }
 
         // Remove cells which are flagged for death
         self.cells.retain(|(_, aux_storage)| {
@@ -54,6 +323,7 @@ impl<C, A> Voxel<C, A> {
         });
 
         // Include new cells
+        let first_new_id = self.id_counter + 1;
         self.cells
             .extend(self.new_cells.drain(..).map(|(cell, parent_id)| {
                 let aux_storage = default_from(&cell);
@@ -63,11 +333,17 @@ impl<C, A> Voxel<C, A> {
                     aux_storage,
                 )
             }));
-        Ok(())
+        Ok(divisions
+            .into_iter()
+            .map(|(parent, mother, n)| {
+                let daughter = CellIdentifier(self.plain_index, first_new_id + n as u64);
+                (parent, [mother, daughter])
+            })
+            .collect())
     }
 }
 
-impl<I, S, C, A, Com, Sy> SubDomainBox<I, S, C, A, Com, Sy>
+impl<I, S, C, A, Com, Sy, R> SubDomainBox<I, S, C, A, Com, Sy, R>
 where
     S: SubDomain,
 {
@@ -86,27 +362,148 @@ where
         default_from: &Func,
     ) -> Result<(), SimulationError>
     where
-        C: cellular_raza_concepts::Cycle<C, F>,
+        C: cellular_raza_concepts::Cycle<C, F, R>,
         A: UpdateCycle,
         Func: Fn(&C) -> A,
+        R: Rng + rand::SeedableRng,
     {
-        self.voxels
-            .iter_mut()
-            .map(|(_, vox)| vox.update_cell_cycle_4(default_from))
-            .collect::<Result<(), SimulationError>>()?;
+        self.divisions.clear();
+        for (plain_index, vox) in self.voxels.iter_mut() {
+            let relaxation_remaining = self.relaxation_remaining.entry(*plain_index).or_default();
+            let counter_key = match self.rng_mode {
+                super::RngMode::Voxel => None,
+                super::RngMode::CounterBased => Some((self.rng_seed, self.iteration as u64)),
+            };
+            let divisions = vox.update_cell_cycle_4(
+                default_from,
+                &self.division_throttle,
+                relaxation_remaining,
+                counter_key,
+            )?;
+            self.divisions.extend(
+                divisions
+                    .into_iter()
+                    .map(|(parent, daughters)| (*plain_index, parent, daughters)),
+            );
+        }
         Ok(())
     }
+
+    /// Stores all divisions of the last call to [SubDomainBox::update_cell_cycle_4] as
+    /// [DivisionEvent]s.
+    ///
+    /// In contrast to [SubDomainBox::save_cells], events are stored at every iteration in which
+    /// a division occurred such that no division is missed.
+    /// The given function extracts the position of the daughter cells directly after the
+    /// division.
+    #[cfg_attr(feature = "tracing", instrument(skip(self, storage_manager, position)))]
+    pub fn save_divisions<
+        #[cfg(feature = "tracing")] F: core::fmt::Debug,
+        #[cfg(not(feature = "tracing"))] F,
+        P,
+        Sto,
+    >(
+        &self,
+        storage_manager: &mut Sto,
+        next_time_point: &crate::time::NextTimePoint<F>,
+        position: impl Fn(&C) -> P,
+    ) -> Result<(), crate::storage::StorageError>
+    where
+        F: Clone + Serialize,
+        P: Serialize,
+        Sto: crate::storage::StorageInterfaceStore<CellIdentifier, DivisionEvent<F, P>>,
+    {
+        if self.divisions.is_empty() {
+            return Ok(());
+        }
+        let events = self
+            .divisions
+            .iter()
+            .filter_map(|(voxel_index, parent, daughters)| {
+                let voxel = self.voxels.get(voxel_index)?;
+                let find_position = |identifier: &CellIdentifier| {
+                    voxel
+                        .cells
+                        .iter()
+                        .find(|(cbox, _)| &cbox.identifier == identifier)
+                        .map(|(cbox, _)| position(&cbox.cell))
+                };
+                Some(DivisionEvent {
+                    time: next_time_point.time.clone(),
+                    parent: *parent,
+                    daughters: *daughters,
+                    positions: [find_position(&daughters[0])?, find_position(&daughters[1])?],
+                })
+            })
+            .collect::<Vec<_>>();
+        storage_manager.store_batch_elements(
+            next_time_point.iteration as u64,
+            events.iter().map(|event| (&event.parent, event)),
+        )
+    }
+
+    /// Limit how many cells can divide at the same time. See [DivisionThrottle].
+    pub fn set_division_throttle(&mut self, division_throttle: Option<DivisionThrottle>) {
+        self.division_throttle = division_throttle.unwrap_or_default();
+    }
+}
+
+/// Position of a cell which is stored in its [DivisionEvent] when the `Mechanics` aspect is
+/// present.
+pub fn division_position<C, Pos>(cell: &C) -> Pos
+where
+    C: cellular_raza_concepts::Position<Pos>,
+{
+    cell.pos()
 }
 
 /// Advances the cycle of a cell by a small time increment `dt`.
-pub fn local_cycle_update<C, A, Float>(
+pub fn local_cycle_update<C, A, Float, R>(
     cell: &mut C,
     aux_storage: &mut A,
     dt: Float,
-    rng: &mut rand_chacha::ChaCha8Rng,
+    rng: &mut R,
 ) -> Result<(), cellular_raza_concepts::DeathError>
 where
-    C: cellular_raza_concepts::Cycle<C, Float>,
+    C: cellular_raza_concepts::Cycle<C, Float, R>,
+    A: UpdateCycle,
+{
+    advance_cycle(
+        cell,
+        aux_storage,
+        dt,
+        rng,
+        &cellular_raza_concepts::GlobalParameters::new(),
+    )
+}
+
+/// Identical to [local_cycle_update] but passes the given
+/// [GlobalParameters](cellular_raza_concepts::GlobalParameters) to the
+/// [update_cycle_with_parameters](cellular_raza_concepts::Cycle::update_cycle_with_parameters)
+/// method.
+///
+/// Returns a function with the same signature as [local_cycle_update] such that it can be
+/// combined with the other local functions.
+pub fn local_cycle_update_with_parameters<'a, C, A, Float, R>(
+    parameters: &'a cellular_raza_concepts::GlobalParameters,
+) -> impl Fn(&mut C, &mut A, Float, &mut R) -> Result<(), cellular_raza_concepts::DeathError> + 'a
+where
+    C: cellular_raza_concepts::Cycle<C, Float, R>,
+    A: UpdateCycle,
+{
+    move |cell, aux_storage, dt, rng| advance_cycle(cell, aux_storage, dt, rng, parameters)
+}
+
+/// Advances the cycle or the conditional phased death of a cell and records resulting events.
+fn advance_cycle<C, A, Float, R>(
+    cell: &mut C,
+    aux_storage: &mut A,
+    dt: Float,
+    rng: &mut R,
+    parameters: &cellular_raza_concepts::GlobalParameters,
+) -> Result<(), cellular_raza_concepts::DeathError>
+where
+    C: cellular_raza_concepts::Cycle<C, Float, R>,
     A: UpdateCycle,
 {
     // Update the cell cycle
@@ -118,9 +515,277 @@ where
             aux_storage.add_cycle_event(CycleEvent::Remove);
         }
     } else {
-        if let Some(event) = C::update_cycle(rng, &dt, cell) {
+        if let Some(event) = C::update_cycle_with_parameters(rng, &dt, cell, parameters) {
             aux_storage.add_cycle_event(event);
         }
     }
     Ok(())
 }
+
+/// Advances the [Age](cellular_raza_concepts::Age) of the cell by the time increment and
+/// afterwards calls the [update_senescence](cellular_raza_concepts::Age::update_senescence) hook.
+pub fn local_age_update<C, A, Float, R>(
+    cell: &mut C,
+    _aux_storage: &mut A,
+    dt: Float,
+    _rng: &mut R,
+) -> Result<(), cellular_raza_concepts::CalcError>
+where
+    C: cellular_raza_concepts::Age<Float>,
+    Float: core::ops::Add<Output = Float>,
+{
+    cell.set_age(cell.get_age() + dt);
+    cell.update_senescence()
+}
+
+#[cfg(test)]
+mod test_division_throttle {
+    use super::*;
+    use crate::backend::chili::{AuxStorageCycle, VoxelPlainIndex};
+    use rand::SeedableRng;
+
+    #[derive(Clone)]
+    struct Agent;
+
+    impl cellular_raza_concepts::Cycle<Agent> for Agent {
+        fn update_cycle(
+            _: &mut rand_chacha::ChaCha8Rng,
+            _: &f64,
+            _: &mut Agent,
+        ) -> Option<CycleEvent> {
+            None
+        }
+
+        fn divide(
+            _: &mut rand_chacha::ChaCha8Rng,
+            _: &mut Agent,
+        ) -> Result<Agent, cellular_raza_concepts::DivisionError> {
+            Ok(Agent)
+        }
+    }
+
+    /// Creates a voxel in which all cells want to divide at the same time.
+    fn synchronized_voxel(n_cells: u64) -> Voxel<Agent, AuxStorageCycle> {
+        let plain_index = VoxelPlainIndex(0);
+        let cells = (0..n_cells)
+            .map(|n| {
+                let mut aux_storage = AuxStorageCycle::default();
+                aux_storage.add_cycle_event(CycleEvent::Division);
+                (CellBox::new(plain_index, n, Agent, None), aux_storage)
+            })
+            .collect();
+        Voxel {
+            plain_index,
+            neighbors: Default::default(),
+            cells,
+            new_cells: Vec::new(),
+            id_counter: n_cells,
+            rng: rand_chacha::ChaCha8Rng::seed_from_u64(0),
+            repulsive_only: false,
+        }
+    }
+
+    /// Performs one update step and returns the number of cells afterwards.
+    fn step(
+        voxel: &mut Voxel<Agent, AuxStorageCycle>,
+        throttle: &DivisionThrottle,
+        relaxation_remaining: &mut usize,
+    ) -> usize {
+        voxel
+            .update_cell_cycle_4::<f64, _>(
+                &|_| AuxStorageCycle::default(),
+                throttle,
+                relaxation_remaining,
+                None,
+            )
+            .unwrap();
+        voxel.cells.len()
+    }
+
+    #[test]
+    fn no_throttle() {
+        let mut voxel = synchronized_voxel(50);
+        assert_eq!(step(&mut voxel, &DivisionThrottle::default(), &mut 0), 100);
+    }
+
+    #[test]
+    fn max_divisions_per_step() {
+        let throttle = DivisionThrottle {
+            max_divisions_per_step: Some(20),
+            ..Default::default()
+        };
+        let mut voxel = synchronized_voxel(50);
+        let mut relaxation_remaining = 0;
+        assert_eq!(step(&mut voxel, &throttle, &mut relaxation_remaining), 70);
+        assert_eq!(step(&mut voxel, &throttle, &mut relaxation_remaining), 90);
+        assert_eq!(step(&mut voxel, &throttle, &mut relaxation_remaining), 100);
+        assert_eq!(step(&mut voxel, &throttle, &mut relaxation_remaining), 100);
+    }
+
+    #[test]
+    fn postpone_probability() {
+        let throttle = DivisionThrottle {
+            postpone_probability: 0.5,
+            ..Default::default()
+        };
+        let mut voxel = synchronized_voxel(200);
+        let n_cells = step(&mut voxel, &throttle, &mut 0);
+        assert!(n_cells > 200 && n_cells < 400);
+        for _ in 0..100 {
+            step(&mut voxel, &throttle, &mut 0);
+        }
+        assert_eq!(voxel.cells.len(), 400);
+    }
+
+    #[test]
+    fn relaxation_after_mass_division() {
+        let throttle = DivisionThrottle {
+            max_divisions_per_step: Some(10),
+            mass_division_threshold: Some(10),
+            relaxation_steps: 3,
+            ..Default::default()
+        };
+        let mut voxel = synchronized_voxel(30);
+        let mut relaxation_remaining = 0;
+        assert_eq!(step(&mut voxel, &throttle, &mut relaxation_remaining), 40);
+        assert_eq!(relaxation_remaining, 3);
+        for _ in 0..3 {
+            assert_eq!(step(&mut voxel, &throttle, &mut relaxation_remaining), 40);
+        }
+        assert_eq!(relaxation_remaining, 0);
+        assert_eq!(step(&mut voxel, &throttle, &mut relaxation_remaining), 50);
+    }
+
+    #[test]
+    fn counter_based_independent_of_order() {
+        let throttle = DivisionThrottle {
+            postpone_probability: 0.5,
+            ..Default::default()
+        };
+        let divided_parents = |voxel: &mut Voxel<Agent, AuxStorageCycle>| {
+            voxel
+                .update_cell_cycle_4::<f64, _>(
+                    &|_| AuxStorageCycle::default(),
+                    &throttle,
+                    &mut 0,
+                    Some((3, 11)),
+                )
+                .unwrap();
+            voxel
+                .cells
+                .iter()
+                .filter_map(|(cbox, _)| cbox.parent)
+                .collect::<std::collections::BTreeSet<_>>()
+        };
+        let mut voxel = synchronized_voxel(50);
+        let mut reversed = synchronized_voxel(50);
+        reversed.cells.reverse();
+        // Draws from the shared rng of the voxel would depend on the order of cells
+        reversed.rng = rand_chacha::ChaCha8Rng::seed_from_u64(1);
+        let parents = divided_parents(&mut voxel);
+        assert!(!parents.is_empty() && parents.len() < 50);
+        assert_eq!(parents, divided_parents(&mut reversed));
+    }
+
+    #[test]
+    fn division_identifiers() {
+        let mut voxel = synchronized_voxel(3);
+        let divisions = voxel
+            .update_cell_cycle_4::<f64, _>(
+                &|_| AuxStorageCycle::default(),
+                &DivisionThrottle::default(),
+                &mut 0,
+                None,
+            )
+            .unwrap();
+        assert_eq!(divisions.len(), 3);
+        for (parent, daughters) in divisions {
+            assert!(parent.1 < 3);
+            for daughter in daughters {
+                let (cbox, _) = voxel
+                    .cells
+                    .iter()
+                    .find(|(cbox, _)| cbox.identifier == daughter)
+                    .unwrap();
+                assert_eq!(cbox.parent, Some(parent));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_division_relaxation {
+    use super::*;
+
+    #[test]
+    fn substeps_only_after_mass_divisions() {
+        let relaxation = DivisionRelaxation::new(4, 0.1);
+        let shared = relaxation.clone();
+        let time_point = |iteration| NextTimePoint {
+            increment: 1.0,
+            time: iteration as f64,
+            iteration,
+            event: None,
+        };
+        relaxation.record(6, false);
+        // Mass division events are visible to all clones
+        shared.record(7, true);
+        relaxation.record(7, false);
+        assert_eq!(relaxation.n_substeps_at(&time_point(6)), 0);
+        assert_eq!(relaxation.n_substeps_at(&time_point(7)), 4);
+        // Flags of previous iterations do not leak into later ones
+        relaxation.record(8, false);
+        relaxation.record(9, false);
+        relaxation.record(10, false);
+        assert_eq!(relaxation.n_substeps_at(&time_point(10)), 0);
+
+        let substeps: Vec<_> = (0..4)
+            .map(|substep| relaxation.time_point(&time_point(7), substep))
+            .collect();
+        assert!(substeps.iter().all(|t| t.time == 7.0 && t.increment == 0.1));
+        assert!(substeps.iter().all(|t| t.event.is_none()));
+        let iterations: std::collections::BTreeSet<_> =
+            substeps.iter().map(|t| t.iteration).collect();
+        assert_eq!(iterations.len(), 4);
+    }
+}
+
+#[cfg(test)]
+mod test_age {
+    use super::*;
+    use rand::SeedableRng;
+
+    struct Agent {
+        age: f64,
+        senescent: bool,
+    }
+
+    impl cellular_raza_concepts::Age for Agent {
+        fn get_age(&self) -> f64 {
+            self.age
+        }
+
+        fn set_age(&mut self, age: f64) {
+            self.age = age;
+        }
+
+        fn update_senescence(&mut self) -> Result<(), cellular_raza_concepts::CalcError> {
+            self.senescent |= self.age >= 1.0;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn age_is_advanced_every_step() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(0);
+        let mut agent = Agent {
+            age: 0.0,
+            senescent: false,
+        };
+        for _ in 0..4 {
+            local_age_update(&mut agent, &mut (), 0.25, &mut rng).unwrap();
+        }
+        assert_eq!(agent.age, 1.0);
+        assert!(agent.senescent);
+    }
+}
@@ -2,8 +2,9 @@
 use tracing::instrument;
 
 use super::{
-    AdamsBashforth, CellBox, Communicator, MechanicsAdamsBashforthSolver, SimulationError,
-    SubDomainBox, SubDomainPlainIndex, UpdateInteraction, UpdateMechanics, Voxel, VoxelPlainIndex,
+    calculate_pair_forces, AdamsBashforth, CellBox, Communicator, MechanicsAdamsBashforthSolver,
+    SimulationError, SubDomainBox, SubDomainPlainIndex, UpdateInteraction, UpdateMechanics, Voxel,
+    VoxelPlainIndex,
 };
 use cellular_raza_concepts::*;
 
@@ -15,6 +16,13 @@ use cellular_raza_concepts::*;
 /// Upon requesting the acting force, by providing the information stored in this struct,
 /// the requester obtains the needed information about acting forces.
 /// See also the [cellular_raza_concepts::Interaction] trait.
+///
+/// # Periodic Images
+/// The position is sent unchanged.
+/// If the subdomain provides a [DomainMetric] via [SubDomainMechanics::domain_metric], the
+/// receiving subdomain replaces it by its image closest to each of its own cells.
+/// Thus, forces across periodic boundaries are also correct if the cells are located in different
+/// subdomains.
 pub struct PosInformation<Pos, Vel, Inf> {
     /// Current position
     pub pos: Pos,
@@ -47,6 +55,10 @@ pub struct ForceInformation<For> {
     ///
     /// This force is already combined in the sense that multiple forces may be added together.
     pub force: For,
+    /// Sum of the normal components of all forces
+    ///
+    /// See [Interaction::normal_force](cellular_raza_concepts::Interaction::normal_force).
+    pub pressure: f64,
     /// Index of cell in stored vector
     ///
     /// This property works in tandem with [Self::index_sender] in order to send
@@ -59,7 +71,7 @@ pub struct ForceInformation<For> {
 /// Send cell and its AuxStorage between threads.
 pub struct SendCell<Cel, Aux>(pub VoxelPlainIndex, pub Cel, pub Aux);
 
-impl<C, A> Voxel<C, A> {
+impl<C, A, R> Voxel<C, A, R> {
     #[cfg_attr(feature = "tracing", instrument(skip_all))]
     pub(crate) fn calculate_force_between_cells_internally<
         Pos,
@@ -70,6 +82,8 @@ impl<C, A> Voxel<C, A> {
         const N: usize,
     >(
         &mut self,
+        metric: Option<&dyn DomainMetric<Pos>>,
+        parameters: &GlobalParameters,
     ) -> Result<(), CalcError>
     where
         C: cellular_raza_concepts::Position<Pos>,
@@ -80,38 +94,89 @@ impl<C, A> Voxel<C, A> {
         For: Xapy<Float>,
         Float: num::Float,
     {
-        let one_half: Float = Float::one() / (Float::one() + Float::one());
-
         for n in 0..self.cells.len() {
             for m in n + 1..self.cells.len() {
-                let mut cells_mut = self.cells.iter_mut();
-                let (c1, aux1) = cells_mut.nth(n).unwrap();
-                let (c2, aux2) = cells_mut.nth(m - n - 1).unwrap();
+                self.interact_pair(n, m, metric, parameters)?;
+            }
+        }
+        Ok(())
+    }
 
-                let p1 = c1.pos();
-                let v1 = c1.velocity();
-                let i1 = c1.get_interaction_information();
+    /// Calculates the forces between the `n`-th and `m`-th cell of this voxel with `n < m`.
+    pub(crate) fn interact_pair<Pos, Vel, For, Float, Inf, const N: usize>(
+        &mut self,
+        n: usize,
+        m: usize,
+        metric: Option<&dyn DomainMetric<Pos>>,
+        parameters: &GlobalParameters,
+    ) -> Result<(), CalcError>
+    where
+        C: cellular_raza_concepts::Position<Pos>,
+        C: cellular_raza_concepts::Velocity<Vel>,
+        C: cellular_raza_concepts::Interaction<Pos, Vel, For, Inf>,
+        A: UpdateMechanics<Pos, Vel, For, N>,
+        A: UpdateInteraction,
+        For: Xapy<Float>,
+        Float: num::Float,
+    {
+        let one_half: Float = Float::one() / (Float::one() + Float::one());
+        let repulsive_only = self.repulsive_only;
+        let mut cells_mut = self.cells.iter_mut();
+        let (c1, aux1) = cells_mut.nth(n).unwrap();
+        let (c2, aux2) = cells_mut.nth(m - n - 1).unwrap();
 
-                let p2 = c2.pos();
-                let v2 = c2.velocity();
-                let i2 = c2.get_interaction_information();
+        let p1 = c1.pos();
+        let v1 = c1.velocity();
+        let i1 = c1.get_interaction_information();
 
-                let (force1, force2) = c1.calculate_force_between(&p1, &v1, &p2, &v2, &i2)?;
-                aux1.add_force(force1.xa(one_half));
-                aux2.add_force(force2.xa(one_half));
+        let p2 = c2.pos();
+        let v2 = c2.velocity();
+        let i2 = c2.get_interaction_information();
 
-                let (force2, force1) = c2.calculate_force_between(&p2, &v2, &p1, &v1, &i1)?;
-                aux1.add_force(force1.xa(one_half));
-                aux2.add_force(force2.xa(one_half));
+        // Use the images of the cells which are closest to each other
+        let p1_image = metric.map(|metric| metric.nearest_image(&p2, &p1));
+        let p1_image = p1_image.as_ref().unwrap_or(&p1);
+        let p2_image = metric.map(|metric| metric.nearest_image(&p1, &p2));
+        let p2_image = p2_image.as_ref().unwrap_or(&p2);
 
-                // Also check for neighbors
-                if c1.is_neighbor(&p1, &p2, &i2)? {
-                    aux1.incr_current_neighbors(1);
-                }
-                if c2.is_neighbor(&p2, &p1, &i1)? {
-                    aux2.incr_current_neighbors(1);
-                }
-            }
+        let (force1, force2) = calculate_pair_forces::<_, _, _, _, Float, _>(
+            &c1.cell,
+            &p1,
+            &v1,
+            p2_image,
+            &v2,
+            &i2,
+            parameters,
+            repulsive_only,
+        )?;
+        let (force1, force2) = (force1.xa(one_half), force2.xa(one_half));
+        aux1.incr_current_pressure(c1.normal_force(&p1, p2_image, &force1)?);
+        aux2.incr_current_pressure(c2.normal_force(&p2, p1_image, &force2)?);
+        aux1.add_force(force1);
+        aux2.add_force(force2);
+
+        let (force2, force1) = calculate_pair_forces::<_, _, _, _, Float, _>(
+            &c2.cell,
+            &p2,
+            &v2,
+            p1_image,
+            &v1,
+            &i1,
+            parameters,
+            repulsive_only,
+        )?;
+        let (force1, force2) = (force1.xa(one_half), force2.xa(one_half));
+        aux1.incr_current_pressure(c1.normal_force(&p1, p2_image, &force1)?);
+        aux2.incr_current_pressure(c2.normal_force(&p2, p1_image, &force2)?);
+        aux1.add_force(force1);
+        aux2.add_force(force2);
+
+        // Also check for neighbors unless the cells do not react to them
+        if c1.reacts_to_neighbors() && c1.is_neighbor(&p1, p2_image, &i2)? {
+            aux1.incr_current_neighbors(1);
+        }
+        if c2.reacts_to_neighbors() && c2.is_neighbor(&p2, p1_image, &i1)? {
+            aux2.incr_current_neighbors(1);
         }
         Ok(())
     }
@@ -129,7 +194,9 @@ impl<C, A> Voxel<C, A> {
         ext_pos: &Pos,
         ext_vel: &Vel,
         ext_inf: &Inf,
-    ) -> Result<Option<For>, CalcError>
+        metric: Option<&dyn DomainMetric<Pos>>,
+        parameters: &GlobalParameters,
+    ) -> Result<Option<(For, f64)>, CalcError>
     where
         C: cellular_raza_concepts::Interaction<Pos, Vel, For, Inf>
             + cellular_raza_concepts::Position<Pos>
@@ -141,24 +208,38 @@ impl<C, A> Voxel<C, A> {
     {
         use core::borrow::BorrowMut;
         let one_half = Float::one() / (Float::one() + Float::one());
+        let repulsive_only = self.repulsive_only;
         let mut force = None;
         for (cell, aux_storage) in self.cells.iter_mut() {
-            let (f1, f2) = cell.calculate_force_between(
-                &cell.pos(),
+            let own_pos = cell.pos();
+            let ext_image = metric.map(|metric| metric.nearest_image(&own_pos, ext_pos));
+            let ext_pos = ext_image.as_ref().unwrap_or(ext_pos);
+            let (f1, f2) = calculate_pair_forces::<_, _, _, _, Float, _>(
+                &cell.cell,
+                &own_pos,
                 &cell.velocity(),
-                &ext_pos,
-                &ext_vel,
-                &ext_inf,
+                ext_pos,
+                ext_vel,
+                ext_inf,
+                parameters,
+                repulsive_only,
             )?;
-            aux_storage.add_force(f1.xa(one_half));
-            if let Some(f) = force.borrow_mut() {
-                *f = f2.xapy(one_half, &*f);
+            let (f1, f2) = (f1.xa(one_half), f2.xa(one_half));
+            aux_storage.incr_current_pressure(cell.normal_force(&own_pos, &ext_pos, &f1)?);
+            // The external cell is not available here such that we use the method of the
+            // current cell to calculate the normal component of the force acting on it.
+            // Only this accumulated component is sent back instead of every individual force.
+            let ext_pressure = cell.normal_force(&ext_pos, &own_pos, &f2)?;
+            aux_storage.add_force(f1);
+            if let Some((f, p)) = force.borrow_mut() {
+                *f = f2.xapy(Float::one(), &*f);
+                *p += ext_pressure;
             } else {
-                force = Some(f2.xa(one_half));
+                force = Some((f2, ext_pressure));
             }
 
             // Check for neighbors
-            if cell.is_neighbor(&cell.pos(), &ext_pos, &ext_inf)? {
+            if cell.reacts_to_neighbors() && cell.is_neighbor(&own_pos, &ext_pos, &ext_inf)? {
                 aux_storage.incr_current_neighbors(1);
             }
         }
@@ -166,7 +247,7 @@ impl<C, A> Voxel<C, A> {
     }
 }
 
-impl<I, S, C, A, Com, Sy> SubDomainBox<I, S, C, A, Com, Sy>
+impl<I, S, C, A, Com, Sy, R> SubDomainBox<I, S, C, A, Com, Sy, R>
 where
     S: SubDomain,
 {
@@ -187,7 +268,7 @@ where
         Inf: Clone,
         C: cellular_raza_concepts::Position<Pos>,
         C: cellular_raza_concepts::Velocity<Vel>,
-        C: cellular_raza_concepts::Mechanics<Pos, Vel, For, Float>,
+        C: cellular_raza_concepts::Mechanics<Pos, Vel, For, Float, R>,
         C: cellular_raza_concepts::Interaction<Pos, Vel, For, Inf>,
         A: UpdateMechanics<Pos, Vel, For, N>,
         A: UpdateInteraction,
@@ -197,9 +278,47 @@ where
         S: SubDomainMechanics<Pos, Vel>,
         Com: Communicator<SubDomainPlainIndex, PosInformation<Pos, Vel, Inf>>,
     {
+        let metric = self.subdomain.domain_metric();
+        let parameters = &self.global_parameters;
         for (_, vox) in self.voxels.iter_mut() {
-            vox.calculate_force_between_cells_internally()?;
+            vox.calculate_force_between_cells_internally(metric, parameters)?;
         }
+        self.calculate_forces_from_neighboring_voxels::<Pos, Vel, For, Float, Inf, N>()
+    }
+
+    /// Calculates forces on all cells from cells in neighboring voxels.
+    ///
+    /// Positions of cells whose neighboring voxels belong to other subdomains are sent via
+    /// the [Communicator] in the [PosInformation] format unless the
+    /// [HaloExchange](super::HaloExchange) mode is active.
+    pub(crate) fn calculate_forces_from_neighboring_voxels<
+        Pos,
+        Vel,
+        For,
+        Float,
+        Inf,
+        const N: usize,
+    >(
+        &mut self,
+    ) -> Result<(), SimulationError>
+    where
+        Pos: Clone,
+        Vel: Clone,
+        Inf: Clone,
+        C: cellular_raza_concepts::Position<Pos>,
+        C: cellular_raza_concepts::Velocity<Vel>,
+        C: cellular_raza_concepts::Mechanics<Pos, Vel, For, Float, R>,
+        C: cellular_raza_concepts::Interaction<Pos, Vel, For, Inf>,
+        A: UpdateMechanics<Pos, Vel, For, N>,
+        A: UpdateInteraction,
+        For: Xapy<Float> + core::ops::AddAssign,
+        Float: num::Float + core::ops::AddAssign,
+        <S as SubDomain>::VoxelIndex: Ord,
+        S: SubDomainMechanics<Pos, Vel>,
+        Com: Communicator<SubDomainPlainIndex, PosInformation<Pos, Vel, Inf>>,
+    {
+        let metric = self.subdomain.domain_metric();
+        let parameters = &self.global_parameters;
 
         // Calculate forces for all cells from neighbors
         // TODO can we do this without memory allocation?
@@ -222,16 +341,21 @@ where
                 for neighbor_index in neighbors {
                     match self.voxels.get_mut(&neighbor_index) {
                         Some(vox) => {
-                            if let Some(f) = vox.calculate_force_between_cells_external(
-                                &cell_pos, &cell_vel, &cell_inf,
+                            if let Some((f, p)) = vox.calculate_force_between_cells_external(
+                                &cell_pos, &cell_vel, &cell_inf, metric, parameters,
                             )? {
                                 match &mut force {
-                                    Some(f2) => *f2 = f.xapy(Float::one(), &f2),
-                                    f2 @ None => *f2 = Some(f),
+                                    Some((f2, p2)) => {
+                                        *f2 = f.xapy(Float::one(), &f2);
+                                        *p2 += p;
+                                    }
+                                    f2 @ None => *f2 = Some((f, p)),
                                 }
                             }
                             Ok::<(), CalcError>(())
                         }
+                        // Cells are mirrored as ghosts by the halo exchange instead
+                        None if self.halo_exchange.is_some() => Ok(()),
                         None => Ok(self.communicator.send(
                             &self.plain_index_to_subdomain[&neighbor_index],
                             PosInformation {
@@ -245,10 +369,11 @@ where
                         )?),
                     }?;
                 }
-                if let Some(f) = force {
-                    self.voxels.get_mut(&voxel_index).unwrap().cells[cell_index_in_vector]
-                        .1
-                        .add_force(f);
+                if let Some((f, p)) = force {
+                    let vox = self.voxels.get_mut(&voxel_index).unwrap();
+                    let aux_storage = &mut vox.cells[cell_index_in_vector].1;
+                    aux_storage.add_force(f);
+                    aux_storage.incr_current_pressure(p);
                 }
             }
         }
@@ -307,11 +432,13 @@ where
         For: Clone + core::ops::AddAssign,
         C: cellular_raza_concepts::Position<Pos>,
         C: cellular_raza_concepts::Velocity<Vel>,
-        C: cellular_raza_concepts::Mechanics<Pos, Vel, For, Float>,
+        C: cellular_raza_concepts::Mechanics<Pos, Vel, For, Float, R>,
         C: cellular_raza_concepts::Interaction<Pos, Vel, For, Inf>,
+        S: SubDomainMechanics<Pos, Vel>,
         Com: Communicator<SubDomainPlainIndex, PosInformation<Pos, Vel, Inf>>,
         Com: Communicator<SubDomainPlainIndex, ForceInformation<For>>,
     {
+        let metric = self.subdomain.domain_metric();
         // Receive PositionInformation and send back ForceInformation
         let mut received_infos = <Com as Communicator<
             SubDomainPlainIndex,
@@ -329,10 +456,12 @@ where
                 )),
             )?;
             // Calculate force from cells in voxel
-            if let Some(force) = vox.calculate_force_between_cells_external(
+            if let Some((force, pressure)) = vox.calculate_force_between_cells_external(
                 &pos_info.pos,
                 &pos_info.vel,
                 &pos_info.info,
+                metric,
+                &self.global_parameters,
             )? {
                 // Send back force information
                 // let thread_index = self.plain_index_to_subdomain[&pos_info.index_sender];
@@ -340,6 +469,7 @@ where
                     &self.plain_index_to_subdomain[&pos_info.index_sender],
                     ForceInformation {
                         force,
+                        pressure,
                         cell_index_in_vector: pos_info.cell_index_in_vector,
                         index_sender: pos_info.index_sender,
                     },
@@ -357,6 +487,7 @@ where
     ) -> Result<(), SimulationError>
     where
         A: UpdateMechanics<Pos, Vel, For, N>,
+        A: UpdateInteraction,
         Com: Communicator<SubDomainPlainIndex, ForceInformation<For>>,
     {
         // Update position and velocity of all cells with new information
@@ -384,7 +515,11 @@ where
                 obt_forces.index_sender, obt_forces.cell_index_in_vector
             );
             match vox.cells.get_mut(obt_forces.cell_index_in_vector) {
-                Some((_, aux_storage)) => Ok(aux_storage.add_force(obt_forces.force)),
+                Some((_, aux_storage)) => {
+                    aux_storage.add_force(obt_forces.force);
+                    aux_storage.incr_current_pressure(obt_forces.pressure);
+                    Ok(())
+                }
                 None => Err(cellular_raza_concepts::IndexError(error_2)),
             }?;
         }
@@ -400,18 +535,7 @@ where
         C: cellular_raza_concepts::Velocity<Vel>,
         S: SubDomainMechanics<Pos, Vel>,
     {
-        for (cell, _) in self
-            .voxels
-            .iter_mut()
-            .map(|(_, voxel)| voxel.cells.iter_mut())
-            .flatten()
-        {
-            let mut pos = cell.pos();
-            let mut vel = cell.velocity();
-            self.subdomain.apply_boundary(&mut pos, &mut vel)?;
-            cell.set_pos(&pos);
-            cell.set_velocity(&vel);
-        }
+        self.apply_boundary_with_policy(super::BoundaryPolicy::Error)?;
         Ok(())
     }
 
@@ -536,16 +660,17 @@ pub fn local_mechanics_update<
     For,
     #[cfg(feature = "tracing")] Float: core::fmt::Debug,
     #[cfg(not(feature = "tracing"))] Float,
+    R,
     const N: usize,
 >(
     cell: &mut C,
     aux_storage: &mut A,
     dt: Float,
-    rng: &mut rand_chacha::ChaCha8Rng,
+    rng: &mut R,
 ) -> Result<(), SimulationError>
 where
     A: UpdateMechanics<Pos, Vel, For, N>,
-    C: cellular_raza_concepts::Mechanics<Pos, Vel, For, Float> + Clone,
+    C: cellular_raza_concepts::Mechanics<Pos, Vel, For, Float, R> + Clone,
     C: cellular_raza_concepts::Position<Pos>,
     C: cellular_raza_concepts::Velocity<Vel>,
     Float: num::Float + Copy + num::FromPrimitive,
@@ -555,23 +680,152 @@ where
     MechanicsAdamsBashforthSolver<N>: AdamsBashforth<N>,
 {
     use super::solvers::{AdamsBashforth, MechanicsAdamsBashforthSolver};
-    <MechanicsAdamsBashforthSolver<N> as AdamsBashforth<N>>::update(cell, aux_storage, dt, rng)?;
+    let parameters = cellular_raza_concepts::GlobalParameters::new();
+    <MechanicsAdamsBashforthSolver<N> as AdamsBashforth<N>>::update(
+        cell,
+        aux_storage,
+        dt,
+        rng,
+        &parameters,
+    )?;
     Ok(())
 }
 
-/// Perform the [Interaction::react_to_neighbors] function and clear current neighbors.
-pub fn local_interaction_react_to_neighbors<C, A, Pos, Vel, For, Inf, Float>(
+/// Identical to [local_mechanics_update] but passes the given
+/// [GlobalParameters](cellular_raza_concepts::GlobalParameters) to the
+/// [get_random_contribution_with_parameters](
+/// cellular_raza_concepts::Mechanics::get_random_contribution_with_parameters)
+/// method.
+///
+/// Returns a function with the same signature as [local_mechanics_update] such that it
+/// can be combined with the other local functions.
+#[allow(private_bounds)]
+pub fn local_mechanics_update_with_parameters<'a, C, A, Pos, Vel, For, Float, R, const N: usize>(
+    parameters: &'a cellular_raza_concepts::GlobalParameters,
+) -> impl Fn(&mut C, &mut A, Float, &mut R) -> Result<(), SimulationError> + 'a
+where
+    A: UpdateMechanics<Pos, Vel, For, N>,
+    C: cellular_raza_concepts::Mechanics<Pos, Vel, For, Float, R> + Clone,
+    C: cellular_raza_concepts::Position<Pos>,
+    C: cellular_raza_concepts::Velocity<Vel>,
+    Float: num::Float + Copy + num::FromPrimitive,
+    Pos: Xapy<Float> + Clone,
+    Vel: Xapy<Float> + Clone,
+    MechanicsAdamsBashforthSolver<N>: AdamsBashforth<N>,
+{
+    use super::solvers::{AdamsBashforth, MechanicsAdamsBashforthSolver};
+    move |cell, aux_storage, dt, rng| {
+        <MechanicsAdamsBashforthSolver<N> as AdamsBashforth<N>>::update(
+            cell,
+            aux_storage,
+            dt,
+            rng,
+            parameters,
+        )?;
+        Ok(())
+    }
+}
+
+/// Perform the [Interaction::react_to_neighbors] and [Interaction::react_to_pressure] functions
+/// and clear current neighbors and pressure.
+pub fn local_interaction_react_to_neighbors<C, A, Pos, Vel, For, Inf, Float, R>(
     cell: &mut C,
     aux_storage: &mut A,
     _dt: Float,
-    _rng: &mut rand_chacha::ChaCha8Rng,
+    _rng: &mut R,
 ) -> Result<(), cellular_raza_concepts::CalcError>
 where
     C: cellular_raza_concepts::Interaction<Pos, Vel, For, Inf>,
     C: cellular_raza_concepts::Position<Pos>,
     A: UpdateInteraction,
 {
-    cell.react_to_neighbors(aux_storage.get_current_neighbors())?;
+    if cell.reacts_to_neighbors() {
+        cell.react_to_neighbors(aux_storage.get_current_neighbors())?;
+        aux_storage.set_current_neighbors(0);
+    }
+    cell.react_to_pressure(aux_storage.get_current_pressure())?;
+    aux_storage.set_current_pressure(0.0);
+    Ok(())
+}
+
+/// Clear current neighbors and pressure without reacting to them.
+///
+/// An [Equilibration](super::Equilibration) only relaxes the positions of cells and thus uses
+/// this function instead of [local_interaction_react_to_neighbors].
+pub fn local_interaction_clear_neighbors<C, A, Float, R>(
+    _cell: &mut C,
+    aux_storage: &mut A,
+    _dt: Float,
+    _rng: &mut R,
+) -> Result<(), cellular_raza_concepts::CalcError>
+where
+    A: UpdateInteraction,
+{
     aux_storage.set_current_neighbors(0);
+    aux_storage.set_current_pressure(0.0);
     Ok(())
 }
+
+#[cfg(test)]
+mod test_periodic_images {
+    use super::*;
+    use crate::backend::chili::test_fixtures::*;
+
+    /// Periodic interval [0, 10)
+    struct Ring;
+
+    impl DomainMetric<f64> for Ring {
+        fn nearest_image(&self, own_pos: &f64, ext_pos: &f64) -> f64 {
+            let diff = ext_pos - own_pos;
+            own_pos + diff - 10.0 * (diff / 10.0).round()
+        }
+    }
+
+    #[test]
+    fn received_position_across_seam() {
+        // A cell of another subdomain at 0.5 sends its position to a voxel at the other end
+        let mut vox = voxel(0, &[], &[9.5]);
+        let force = vox
+            .calculate_force_between_cells_external::<_, _, _, _, f64, 1>(
+                &0.5,
+                &0.0,
+                &(),
+                Some(&Ring),
+                &GlobalParameters::new(),
+            )
+            .unwrap()
+            .unwrap();
+        // The spring pulls both cells together which results in a negative pressure
+        assert_eq!(force, (-0.5, -0.5));
+        assert_eq!(vox.cells[0].1.get_current_force_and_reset(), 0.5);
+        assert_eq!(vox.cells[0].1.get_current_pressure(), -0.5);
+
+        // Without metric, the cells are on opposite ends of the domain
+        let mut vox = voxel(0, &[], &[9.5]);
+        let force = vox
+            .calculate_force_between_cells_external::<_, _, _, _, f64, 1>(
+                &0.5,
+                &0.0,
+                &(),
+                None,
+                &GlobalParameters::new(),
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(force.0, 4.5);
+    }
+
+    #[test]
+    fn cells_within_voxel_across_seam() {
+        let mut vox = voxel(0, &[], &[0.5, 9.5]);
+        vox.calculate_force_between_cells_internally::<_, _, _, f64, _, 1>(
+            Some(&Ring),
+            &GlobalParameters::new(),
+        )
+        .unwrap();
+        assert_eq!(vox.cells[0].1.get_current_force_and_reset(), -1.0);
+        assert_eq!(vox.cells[1].1.get_current_force_and_reset(), 1.0);
+        assert_eq!(vox.cells[0].1.get_current_pressure(), -1.0);
+        assert_eq!(vox.cells[1].1.get_current_pressure(), -1.0);
+    }
+}
@@ -15,7 +15,7 @@ pub struct ReactionsExtraBorderInfo<Binfo>(pub SubDomainPlainIndex, pub Binfo);
 /// Return information of border value after having obtained the [SubDomainReactions::BorderInfo]
 pub struct ReactionsExtraBorderReturn<Bvalue>(pub SubDomainPlainIndex, pub Bvalue);
 
-impl<I, S, C, A, Com, Sy> SubDomainBox<I, S, C, A, Com, Sy>
+impl<I, S, C, A, Com, Sy, R> SubDomainBox<I, S, C, A, Com, Sy, R>
 where
     S: SubDomain,
 {
@@ -169,7 +169,7 @@ pub struct ReactionsContactReturn<Ri> {
     pub index_sender: VoxelPlainIndex,
 }
 
-impl<C, A> Voxel<C, A> {
+impl<C, A, R> Voxel<C, A, R> {
     #[cfg_attr(feature = "tracing", instrument(skip_all))]
     pub(crate) fn calculate_contact_reactions_between_cells_internally<
         Ri,
@@ -254,7 +254,7 @@ impl<C, A> Voxel<C, A> {
     }
 }
 
-impl<I, S, C, A, Com, Sy> SubDomainBox<I, S, C, A, Com, Sy>
+impl<I, S, C, A, Com, Sy, R> SubDomainBox<I, S, C, A, Com, Sy, R>
 where
     S: SubDomain,
 {
@@ -438,11 +438,12 @@ pub fn local_update_contact_reactions<
     Ri,
     #[cfg(feature = "tracing")] F: core::fmt::Debug,
     #[cfg(not(feature = "tracing"))] F,
+    R,
 >(
     cell: &mut C,
     aux_storage: &mut A,
     _dt: F,
-    _rng: &mut rand_chacha::ChaCha8Rng,
+    _rng: &mut R,
 ) -> Result<(), SimulationError>
 where
     A: UpdateReactions<Ri> + UpdateReactionsContact<Ri, 2>,
@@ -463,12 +464,13 @@ pub fn local_reactions_intracellular<
     Ri,
     #[cfg(feature = "tracing")] F: core::fmt::Debug,
     #[cfg(not(feature = "tracing"))] F,
+    R,
     const N: usize,
 >(
     cell: &mut C,
     aux_storage: &mut A,
     dt: F,
-    _rng: &mut rand_chacha::ChaCha8Rng,
+    _rng: &mut R,
 ) -> Result<(), SimulationError>
 where
     A: UpdateReactions<Ri>,
@@ -477,10 +479,36 @@ where
     Ri: Xapy<F>,
     ReactionsRungeKuttaSolver<N>: RungeKutta<N>,
 {
-    ReactionsRungeKuttaSolver::<N>::update(cell, aux_storage, dt)?;
+    let parameters = cellular_raza_concepts::GlobalParameters::new();
+    ReactionsRungeKuttaSolver::<N>::update(cell, aux_storage, dt, &parameters)?;
     Ok(())
 }
 
+/// Identical to [local_reactions_intracellular] but passes the given
+/// [GlobalParameters](cellular_raza_concepts::GlobalParameters) to the
+/// [calculate_intracellular_increment_with_parameters](
+/// cellular_raza_concepts::Reactions::calculate_intracellular_increment_with_parameters)
+/// method.
+///
+/// Returns a function with the same signature as [local_reactions_intracellular] such that it
+/// can be combined with the other local functions.
+#[allow(private_bounds)]
+pub fn local_reactions_intracellular_with_parameters<'a, C, A, Ri, F, R, const N: usize>(
+    parameters: &'a cellular_raza_concepts::GlobalParameters,
+) -> impl Fn(&mut C, &mut A, F, &mut R) -> Result<(), SimulationError> + 'a
+where
+    A: UpdateReactions<Ri>,
+    C: cellular_raza_concepts::Reactions<Ri>,
+    F: num::Float,
+    Ri: Xapy<F>,
+    ReactionsRungeKuttaSolver<N>: RungeKutta<N>,
+{
+    move |cell, aux_storage, dt, _rng| {
+        ReactionsRungeKuttaSolver::<N>::update(cell, aux_storage, dt, parameters)?;
+        Ok(())
+    }
+}
+
 /// Ensures that intracellular increments have been cleared before the next update step.
 #[cfg_attr(feature = "tracing", instrument(skip_all))]
 pub fn local_reactions_use_increment<
@@ -489,11 +517,12 @@ pub fn local_reactions_use_increment<
     Ri,
     #[cfg(feature = "tracing")] F: core::fmt::Debug,
     #[cfg(not(feature = "tracing"))] F,
+    R,
 >(
     cell: &mut C,
     aux_storage: &mut A,
     dt: F,
-    _rng: &mut rand_chacha::ChaCha8Rng,
+    _rng: &mut R,
 ) -> Result<(), SimulationError>
 where
     C: Intracellular<Ri>,
@@ -0,0 +1,381 @@
+#[cfg(feature = "tracing")]
+use tracing::instrument;
+
+use super::{
+    Communicator, SimulationError, SubDomainBox, SubDomainPlainIndex, UpdateRotationalMechanics,
+    Voxel, VoxelPlainIndex,
+};
+use cellular_raza_concepts::*;
+
+/// Send information about the orientation of cells between threads.
+///
+/// This is the rotational analogue of the [PosInformation](super::PosInformation) type.
+/// It is used in
+/// [update_rotational_interaction_step_1](SubDomainBox::update_rotational_interaction_step_1)
+/// and answered by the [TorqueInformation] type.
+pub struct OrientationInformation<Pos, Ang, Inf> {
+    /// Current position
+    pub pos: Pos,
+    /// Current orientation
+    pub ang: Ang,
+    /// Information shared between cells
+    pub info: Inf,
+    /// Index of cell in stored vector
+    pub cell_index_in_vector: usize,
+    /// Voxel index of the sending cell.
+    /// Information should be returned to this voxel.
+    pub index_sender: VoxelPlainIndex,
+    /// Voxel index of the voxel from which information is requested.
+    pub index_receiver: VoxelPlainIndex,
+}
+
+/// Return type to the requested [OrientationInformation].
+pub struct TorqueInformation<Tor> {
+    /// Overall torque acting on cell.
+    pub torque: Tor,
+    /// Index of cell in stored vector
+    pub cell_index_in_vector: usize,
+    /// The voxel index where information is returned to
+    pub index_sender: VoxelPlainIndex,
+}
+
+impl<C, A, R> Voxel<C, A, R> {
+    /// Calculates the torques between all pairs of cells inside this voxel
+    #[cfg_attr(feature = "tracing", instrument(skip_all))]
+    pub(crate) fn calculate_torque_between_cells_internally<
+        Pos,
+        Vel,
+        For,
+        Ang,
+        AngVel,
+        Tor,
+        Float,
+        Inf,
+        const N: usize,
+    >(
+        &mut self,
+        metric: Option<&dyn DomainMetric<Pos>>,
+    ) -> Result<(), CalcError>
+    where
+        C: Position<Pos>,
+        C: Orientation<Ang>,
+        C: Interaction<Pos, Vel, For, Inf>,
+        C: InteractionTorque<Pos, Ang, Tor, Inf>,
+        A: UpdateRotationalMechanics<Ang, AngVel, Tor, N>,
+        Tor: Xapy<Float>,
+        Float: num::Float,
+    {
+        let one_half: Float = Float::one() / (Float::one() + Float::one());
+
+        for n in 0..self.cells.len() {
+            for m in n + 1..self.cells.len() {
+                let mut cells_mut = self.cells.iter_mut();
+                let (c1, aux1) = cells_mut.nth(n).unwrap();
+                let (c2, aux2) = cells_mut.nth(m - n - 1).unwrap();
+
+                let p1 = c1.pos();
+                let a1 = c1.orientation();
+                let i1 = c1.get_interaction_information();
+
+                let p2 = c2.pos();
+                let a2 = c2.orientation();
+                let i2 = c2.get_interaction_information();
+
+                // Use the images of the cells which are closest to each other
+                let p1_image = metric.map(|metric| metric.nearest_image(&p2, &p1));
+                let p1_image = p1_image.as_ref().unwrap_or(&p1);
+                let p2_image = metric.map(|metric| metric.nearest_image(&p1, &p2));
+                let p2_image = p2_image.as_ref().unwrap_or(&p2);
+
+                let (torque1, torque2) =
+                    c1.calculate_torque_between(&p1, &a1, p2_image, &a2, &i2)?;
+                aux1.add_torque(torque1.xa(one_half));
+                aux2.add_torque(torque2.xa(one_half));
+
+                let (torque2, torque1) =
+                    c2.calculate_torque_between(&p2, &a2, p1_image, &a1, &i1)?;
+                aux1.add_torque(torque1.xa(one_half));
+                aux2.add_torque(torque2.xa(one_half));
+            }
+        }
+        Ok(())
+    }
+
+    /// Calculates the torques between the cells of this voxel and an external cell
+    #[cfg_attr(feature = "tracing", instrument(skip_all))]
+    pub(crate) fn calculate_torque_between_cells_external<
+        Pos,
+        Vel,
+        For,
+        Ang,
+        AngVel,
+        Tor,
+        Float,
+        Inf,
+        const N: usize,
+    >(
+        &mut self,
+        ext_pos: &Pos,
+        ext_ang: &Ang,
+        ext_inf: &Inf,
+        metric: Option<&dyn DomainMetric<Pos>>,
+    ) -> Result<Option<Tor>, CalcError>
+    where
+        C: Position<Pos>,
+        C: Orientation<Ang>,
+        C: Interaction<Pos, Vel, For, Inf>,
+        C: InteractionTorque<Pos, Ang, Tor, Inf>,
+        A: UpdateRotationalMechanics<Ang, AngVel, Tor, N>,
+        Tor: Xapy<Float>,
+        Float: num::Float,
+    {
+        let one_half = Float::one() / (Float::one() + Float::one());
+        let mut torque = None;
+        for (cell, aux_storage) in self.cells.iter_mut() {
+            let own_pos = cell.pos();
+            let ext_image = metric.map(|metric| metric.nearest_image(&own_pos, ext_pos));
+            let ext_pos = ext_image.as_ref().unwrap_or(ext_pos);
+            let (t1, t2) = cell.calculate_torque_between(
+                &own_pos,
+                &cell.orientation(),
+                ext_pos,
+                ext_ang,
+                ext_inf,
+            )?;
+            aux_storage.add_torque(t1.xa(one_half));
+            torque = Some(match torque {
+                Some(t) => t2.xapy(one_half, &t),
+                None => t2.xa(one_half),
+            });
+        }
+        Ok(torque)
+    }
+}
+
+impl<I, S, C, A, Com, Sy, R> SubDomainBox<I, S, C, A, Com, Sy, R>
+where
+    S: SubDomain,
+{
+    /// Calculate torques between cells and send [OrientationInformation] to other subdomains.
+    ///
+    /// This is the rotational analogue of
+    /// [update_mechanics_interaction_step_1](SubDomainBox::update_mechanics_interaction_step_1).
+    /// Cells need to implement the [InteractionTorque] trait in addition to the [Interaction]
+    /// trait which provides the shared information.
+    /// The gathered torques are later used by
+    /// [local_rotational_mechanics_update].
+    #[cfg_attr(feature = "tracing", instrument(skip_all))]
+    pub fn update_rotational_interaction_step_1<
+        Pos,
+        Vel,
+        For,
+        Ang,
+        AngVel,
+        Tor,
+        Float,
+        Inf,
+        const N: usize,
+    >(
+        &mut self,
+    ) -> Result<(), SimulationError>
+    where
+        Pos: Clone,
+        Ang: Clone,
+        Inf: Clone,
+        C: Position<Pos>,
+        C: Orientation<Ang>,
+        C: Interaction<Pos, Vel, For, Inf>,
+        C: InteractionTorque<Pos, Ang, Tor, Inf>,
+        C: RotationalMechanics<Ang, AngVel, Tor, Float, R>,
+        A: UpdateRotationalMechanics<Ang, AngVel, Tor, N>,
+        Tor: Xapy<Float>,
+        Float: num::Float,
+        S: SubDomainMechanics<Pos, Vel>,
+        Com: Communicator<SubDomainPlainIndex, OrientationInformation<Pos, Ang, Inf>>,
+    {
+        let metric = self.subdomain.domain_metric();
+        for (_, vox) in self.voxels.iter_mut() {
+            vox.calculate_torque_between_cells_internally::<Pos, Vel, For, _, _, _, Float, _, N>(
+                metric,
+            )?;
+        }
+
+        let key_iterator: Vec<_> = self.voxels.keys().copied().collect();
+        for voxel_index in key_iterator {
+            for cell_index_in_vector in 0..self.voxels[&voxel_index].cells.len() {
+                let cell = &self.voxels[&voxel_index].cells[cell_index_in_vector].0;
+                let cell_pos = cell.pos();
+                let cell_ang = cell.orientation();
+                let cell_inf = cell.get_interaction_information();
+                let mut torque = None;
+                let neighbors = self.voxels[&voxel_index].neighbors.clone();
+                for neighbor_index in neighbors {
+                    match self.voxels.get_mut(&neighbor_index) {
+                        Some(vox) => {
+                            if let Some(t) = vox
+                                .calculate_torque_between_cells_external::<
+                                    Pos, Vel, For, _, _, _, Float, _, N
+                                >(&cell_pos, &cell_ang, &cell_inf, metric)?
+                            {
+                                torque = Some(match torque {
+                                    Some(t2) => t.xapy(Float::one(), &t2),
+                                    None => t,
+                                });
+                            }
+                        }
+                        None => self.communicator.send(
+                            &self.plain_index_to_subdomain[&neighbor_index],
+                            OrientationInformation {
+                                index_sender: voxel_index,
+                                index_receiver: neighbor_index,
+                                pos: cell_pos.clone(),
+                                ang: cell_ang.clone(),
+                                info: cell_inf.clone(),
+                                cell_index_in_vector,
+                            },
+                        )?,
+                    }
+                }
+                if let Some(t) = torque {
+                    self.voxels.get_mut(&voxel_index).unwrap().cells[cell_index_in_vector]
+                        .1
+                        .add_torque(t);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Receive [OrientationInformation] and send back the resulting [TorqueInformation].
+    ///
+    /// See
+    /// [update_rotational_interaction_step_1](SubDomainBox::update_rotational_interaction_step_1).
+    #[cfg_attr(feature = "tracing", instrument(skip_all))]
+    pub fn update_rotational_interaction_step_2<
+        Pos,
+        Vel,
+        For,
+        Ang,
+        AngVel,
+        Tor,
+        Float,
+        Inf,
+        const N: usize,
+    >(
+        &mut self,
+        determinism: bool,
+    ) -> Result<(), SimulationError>
+    where
+        C: Position<Pos>,
+        C: Orientation<Ang>,
+        C: Interaction<Pos, Vel, For, Inf>,
+        C: InteractionTorque<Pos, Ang, Tor, Inf>,
+        C: RotationalMechanics<Ang, AngVel, Tor, Float, R>,
+        A: UpdateRotationalMechanics<Ang, AngVel, Tor, N>,
+        Tor: Xapy<Float>,
+        Float: num::Float,
+        S: SubDomainMechanics<Pos, Vel>,
+        Com: Communicator<SubDomainPlainIndex, OrientationInformation<Pos, Ang, Inf>>,
+        Com: Communicator<SubDomainPlainIndex, TorqueInformation<Tor>>,
+    {
+        let metric = self.subdomain.domain_metric();
+        let mut received_infos = <Com as Communicator<
+            SubDomainPlainIndex,
+            OrientationInformation<Pos, Ang, Inf>,
+        >>::receive(&mut self.communicator);
+        if determinism {
+            received_infos.sort_by_key(|info| info.index_sender);
+        }
+        for info in received_infos.iter() {
+            let vox = self
+                .voxels
+                .get_mut(&info.index_receiver)
+                .ok_or(IndexError(format!(
+                    "EngineError: Voxel with index {:?} of OrientationInformation can not be \
+                    found in this thread.",
+                    info.index_receiver
+                )))?;
+            if let Some(torque) = vox
+                .calculate_torque_between_cells_external::<Pos, Vel, For, _, _, _, Float, _, N>(
+                    &info.pos, &info.ang, &info.info, metric,
+                )?
+            {
+                self.communicator.send(
+                    &self.plain_index_to_subdomain[&info.index_sender],
+                    TorqueInformation {
+                        torque,
+                        cell_index_in_vector: info.cell_index_in_vector,
+                        index_sender: info.index_sender,
+                    },
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Receive all calculated torques and include them for later update steps.
+    #[cfg_attr(feature = "tracing", instrument(skip_all))]
+    pub fn update_rotational_interaction_step_3<Ang, AngVel, Tor, const N: usize>(
+        &mut self,
+        determinism: bool,
+    ) -> Result<(), SimulationError>
+    where
+        A: UpdateRotationalMechanics<Ang, AngVel, Tor, N>,
+        Com: Communicator<SubDomainPlainIndex, TorqueInformation<Tor>>,
+    {
+        let mut received_infos = <Com as Communicator<
+            SubDomainPlainIndex,
+            TorqueInformation<Tor>,
+        >>::receive(&mut self.communicator);
+        if determinism {
+            received_infos.sort_by_key(|info| info.index_sender);
+        }
+        for info in received_infos {
+            let vox = self
+                .voxels
+                .get_mut(&info.index_sender)
+                .ok_or(IndexError(format!(
+                    "EngineError: Sender with plain index {:?} was ended up in location \
+                    where index is not present anymore",
+                    info.index_sender
+                )))?;
+            match vox.cells.get_mut(info.cell_index_in_vector) {
+                Some((_, aux_storage)) => aux_storage.add_torque(info.torque),
+                None => {
+                    return Err(IndexError(format!(
+                        "EngineError: Torque Information with sender index {:?} and cell at \
+                        vector position {} could not be matched",
+                        info.index_sender, info.cell_index_in_vector
+                    ))
+                    .into())
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Updates the orientation and angular velocity of a cell with the gathered torques.
+///
+/// This is the rotational analogue of [local_mechanics_update](super::local_mechanics_update).
+/// The order `N` of the solver is given by the number of previous increments which are stored
+/// in the [UpdateRotationalMechanics] aux storage.
+/// See [rotational_mechanics_euler](super::rotational_mechanics_euler) and
+/// [rotational_mechanics_adams_bashforth_3](super::rotational_mechanics_adams_bashforth_3).
+pub fn local_rotational_mechanics_update<C, A, Ang, AngVel, Tor, Float, R, const N: usize>(
+    cell: &mut C,
+    aux_storage: &mut A,
+    dt: Float,
+    rng: &mut R,
+) -> Result<(), SimulationError>
+where
+    A: UpdateRotationalMechanics<Ang, AngVel, Tor, N>,
+    C: RotationalMechanics<Ang, AngVel, Tor, Float, R>,
+    C: Orientation<Ang>,
+    C: AngularVelocity<AngVel>,
+    Ang: Xapy<Float> + Clone,
+    AngVel: Xapy<Float> + Clone,
+    Float: num::Float + num::FromPrimitive,
+{
+    super::solvers::rotational_mechanics_multistep(cell, aux_storage, dt, rng)
+}
@@ -0,0 +1,201 @@
+use cellular_raza_concepts::PositionLike;
+
+use super::{CpmAgent, CpmDomain, CpmLattice, CpmSimulation};
+
+/// Relates the sites of a [CpmLattice] to positions of center-based models.
+///
+/// The site with index `i` along every axis covers the interval
+/// `origin + spacing * [i, i + 1)`.
+///
+/// ```
+/// # use cellular_raza_core::backend::cpm::LatticeMapping;
+/// let mapping = LatticeMapping::new([-1.0, 0.0], 0.5);
+/// assert_eq!(mapping.position_of(&[0, 3]), [-0.75, 1.75]);
+/// assert_eq!(mapping.site_of(&[-0.8, 1.9]), Some([0, 3]));
+/// assert_eq!(mapping.site_of(&[-1.2, 1.9]), None);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LatticeMapping<const D: usize> {
+    /// Position of the lower corner of the first site
+    pub origin: [f64; D],
+    /// Side length of every site
+    pub spacing: f64,
+}
+
+impl<const D: usize> LatticeMapping<D> {
+    /// Constructs a new mapping from the lower corner of the lattice and the side length of its
+    /// sites.
+    pub fn new(origin: [f64; D], spacing: f64) -> Self {
+        Self { origin, spacing }
+    }
+
+    /// Position of the center of the given site
+    pub fn position_of(&self, site: &[usize; D]) -> [f64; D] {
+        let mut position = self.origin;
+        for i in 0..D {
+            position[i] += (site[i] as f64 + 0.5) * self.spacing;
+        }
+        position
+    }
+
+    /// Site which contains the given position.
+    ///
+    /// Returns [None] for positions below the origin.
+    /// Positions beyond the upper end of the lattice are not detected by this method.
+    pub fn site_of(&self, position: &[f64; D]) -> Option<[usize; D]> {
+        let mut site = [0; D];
+        for i in 0..D {
+            let index = ((position[i] - self.origin[i]) / self.spacing).floor();
+            if index < 0.0 || !index.is_finite() {
+                return None;
+            }
+            site[i] = index as usize;
+        }
+        Some(site)
+    }
+}
+
+impl<const D: usize> CpmDomain<D> {
+    /// Assigns every site to the closest of the given center-based positions.
+    ///
+    /// This is the discrete Voronoi tessellation of the positions.
+    /// Sites which are further away than `max_distance` from every position are left to the
+    /// medium.
+    /// The returned sites are ordered like the positions and can be passed together with the
+    /// corresponding cells to [CpmSimulation::new].
+    ///
+    /// ```
+    /// # use cellular_raza_core::backend::cpm::{CpmDomain, LatticeMapping};
+    /// let domain = CpmDomain::new([10, 10]);
+    /// let mapping = LatticeMapping::new([0.0; 2], 1.0);
+    /// let sites = domain.voronoi_sites(&[[2.5, 5.0], [7.5, 5.0]], &mapping, None);
+    /// assert_eq!(sites[0].len(), 50);
+    /// assert!(sites[1].iter().all(|site| site[0] >= 5));
+    /// ```
+    pub fn voronoi_sites<P>(
+        &self,
+        positions: &[P],
+        mapping: &LatticeMapping<D>,
+        max_distance: Option<f64>,
+    ) -> Vec<Vec<[usize; D]>>
+    where
+        P: PositionLike<f64, D>,
+    {
+        let points: Vec<[f64; D]> = positions.iter().map(|p| p.representative_point()).collect();
+        let lattice = self.lattice();
+        let mut sites = vec![Vec::new(); points.len()];
+        for flat_index in 0..lattice.n_sites() {
+            let site = lattice.site(flat_index);
+            let center = mapping.position_of(&site);
+            let closest = points
+                .iter()
+                .map(|point| (0..D).map(|i| (point[i] - center[i]).powi(2)).sum::<f64>())
+                .enumerate()
+                .min_by(|(_, d1), (_, d2)| d1.total_cmp(d2));
+            if let Some((index, distance_squared)) = closest {
+                if max_distance.is_none_or(|max| distance_squared <= max.powi(2)) {
+                    sites[index].push(site);
+                }
+            }
+        }
+        sites
+    }
+}
+
+impl<const D: usize> CpmLattice<D> {
+    /// Center of mass of the sites of every cell given by the cell index
+    ///
+    /// The results can be used as positions of center-based models.
+    pub fn centroids(&self, mapping: &LatticeMapping<D>) -> Vec<(usize, [f64; D])> {
+        let mut sums: Vec<([f64; D], usize)> = Vec::new();
+        for (flat_index, occupant) in self.sites().iter().enumerate() {
+            let Some(cell_index) = occupant else {
+                continue;
+            };
+            if sums.len() <= *cell_index {
+                sums.resize(cell_index + 1, ([0.0; D], 0));
+            }
+            let position = mapping.position_of(&self.site(flat_index));
+            let (sum, count) = &mut sums[*cell_index];
+            for i in 0..D {
+                sum[i] += position[i];
+            }
+            *count += 1;
+        }
+        sums.into_iter()
+            .enumerate()
+            .filter(|(_, (_, count))| *count > 0)
+            .map(|(cell_index, (sum, count))| (cell_index, sum.map(|x| x / count as f64)))
+            .collect()
+    }
+}
+
+impl<C, const D: usize> CpmSimulation<C, D>
+where
+    C: CpmAgent,
+{
+    /// All living cells together with the centroids of their sites.
+    ///
+    /// See [CpmLattice::centroids].
+    pub fn cells_with_centroids(&self, mapping: &LatticeMapping<D>) -> Vec<(&C, [f64; D])> {
+        let centroids: std::collections::BTreeMap<_, _> =
+            self.lattice().centroids(mapping).into_iter().collect();
+        self.cells()
+            .filter_map(|(index, cell)| centroids.get(&index).map(|centroid| (cell, *centroid)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test_coupling {
+    use super::*;
+    use crate::backend::cpm::CpmSettings;
+
+    struct Cell;
+
+    impl CpmAgent for Cell {
+        fn target_volume(&self) -> f64 {
+            10.0
+        }
+
+        fn volume_stiffness(&self) -> f64 {
+            1.0
+        }
+
+        fn adhesion_energy(&self, _: Option<&Self>) -> f64 {
+            1.0
+        }
+    }
+
+    #[test]
+    fn roundtrip_positions() {
+        let domain = CpmDomain::new([20, 20]);
+        let mapping = LatticeMapping::new([-5.0, -5.0], 0.5);
+        let positions = [[-3.0, -3.0], [2.0, -1.0], [0.0, 3.0]];
+        let sites = domain.voronoi_sites(&positions, &mapping, Some(1.5));
+        let simulation = CpmSimulation::new(
+            domain,
+            sites.into_iter().map(|sites| (Cell, sites)),
+            CpmSettings::new(0, 1.0),
+        )
+        .unwrap();
+        let centroids = simulation.cells_with_centroids(&mapping);
+        assert_eq!(centroids.len(), 3);
+        for ((_, centroid), position) in centroids.iter().zip(positions) {
+            for i in 0..2 {
+                assert!((centroid[i] - position[i]).abs() < 0.3);
+            }
+        }
+    }
+
+    #[test]
+    fn max_distance_leaves_medium() {
+        let domain = CpmDomain::new([10, 10]);
+        let mapping = LatticeMapping::new([0.0; 2], 1.0);
+        let sites = domain.voronoi_sites(&[[5.0, 5.0]], &mapping, Some(2.0));
+        // Sites whose centers lie within a circle of radius 2
+        assert_eq!(sites[0].len(), 12);
+        let all = domain.voronoi_sites(&[[5.0, 5.0]], &mapping, None);
+        assert_eq!(all[0].len(), 100);
+    }
+}
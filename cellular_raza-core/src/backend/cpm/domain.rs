@@ -0,0 +1,141 @@
+use cellular_raza_concepts::{DecomposeError, DomainCreateSubDomains, SubDomain};
+
+use super::CpmLattice;
+
+/// Cuboid lattice on which the [CpmSimulation](super::CpmSimulation) takes place.
+///
+/// The domain is split into slabs along the first axis by the
+/// [DomainCreateSubDomains] trait.
+/// Neighboring slabs are updated alternately such that flips in slabs which are updated at the
+/// same time never influence each other.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CpmDomain<const D: usize> {
+    /// Number of sites along every axis
+    pub shape: [usize; D],
+}
+
+impl<const D: usize> CpmDomain<D> {
+    /// Constructs a new domain with the given number of sites along every axis
+    pub fn new(shape: [usize; D]) -> Self {
+        Self { shape }
+    }
+
+    /// Empty lattice of the domain
+    pub fn lattice(&self) -> CpmLattice<D> {
+        CpmLattice::new(self.shape)
+    }
+}
+
+/// Slab of the [CpmDomain] which contains all sites whose first coordinate lies inside
+/// `row_start..row_end`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CpmSubDomain<const D: usize> {
+    /// Number of sites of the whole domain along every axis
+    pub shape: [usize; D],
+    /// First row of this slab along the first axis
+    pub row_start: usize,
+    /// First row along the first axis which is not part of this slab
+    pub row_end: usize,
+}
+
+impl<const D: usize> CpmSubDomain<D> {
+    /// Range of flat indices of the sites of this slab in the [CpmLattice]
+    pub fn flat_range(&self) -> core::ops::Range<usize> {
+        let row_stride: usize = self.shape.iter().skip(1).product();
+        self.row_start * row_stride..self.row_end * row_stride
+    }
+}
+
+impl<const D: usize> DomainCreateSubDomains<CpmSubDomain<D>> for CpmDomain<D> {
+    type SubDomainIndex = usize;
+    type VoxelIndex = [usize; D];
+
+    fn create_subdomains(
+        &self,
+        n_subdomains: core::num::NonZeroUsize,
+    ) -> Result<
+        impl IntoIterator<Item = (Self::SubDomainIndex, CpmSubDomain<D>, Vec<Self::VoxelIndex>)>,
+        DecomposeError,
+    > {
+        let n_rows = self.shape.first().copied().unwrap_or(0);
+        if n_rows == 0 {
+            return Err(DecomposeError::Generic(
+                "Cannot decompose an empty lattice".to_owned(),
+            ));
+        }
+        // Every slab needs to contain at least one row
+        let n_subdomains = usize::from(n_subdomains).min(n_rows);
+        let lattice = self.lattice();
+        Ok((0..n_subdomains).map(move |n| {
+            let subdomain = CpmSubDomain {
+                shape: self.shape,
+                row_start: n * n_rows / n_subdomains,
+                row_end: (n + 1) * n_rows / n_subdomains,
+            };
+            let voxels = subdomain
+                .flat_range()
+                .map(|flat_index| lattice.site(flat_index))
+                .collect();
+            (n, subdomain, voxels)
+        }))
+    }
+}
+
+impl<const D: usize> SubDomain for CpmSubDomain<D> {
+    type VoxelIndex = [usize; D];
+
+    fn get_neighbor_voxel_indices(&self, voxel_index: &Self::VoxelIndex) -> Vec<Self::VoxelIndex> {
+        let lattice = CpmLattice::new(self.shape);
+        match lattice.flat_index(voxel_index) {
+            Ok(flat_index) => lattice
+                .neighbors(flat_index)
+                .map(|neighbor| lattice.site(neighbor))
+                .collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    fn get_all_indices(&self) -> Vec<Self::VoxelIndex> {
+        let lattice = CpmLattice::new(self.shape);
+        self.flat_range()
+            .map(|flat_index| lattice.site(flat_index))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test_domain {
+    use super::*;
+
+    #[test]
+    fn slabs_cover_lattice() {
+        let domain = CpmDomain::new([7, 3]);
+        let subdomains: Vec<_> = domain
+            .create_subdomains(3.try_into().unwrap())
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(subdomains.len(), 3);
+        let mut n_sites = 0;
+        for (n, (index, subdomain, voxels)) in subdomains.iter().enumerate() {
+            assert_eq!(*index, n);
+            assert_eq!(voxels.len(), subdomain.flat_range().len());
+            assert_eq!(voxels, &subdomain.get_all_indices());
+            n_sites += voxels.len();
+        }
+        assert_eq!(n_sites, 21);
+        assert_eq!(subdomains[0].1.row_start, 0);
+        assert_eq!(subdomains[2].1.row_end, 7);
+    }
+
+    #[test]
+    fn more_subdomains_than_rows() {
+        let domain = CpmDomain::new([2, 5]);
+        let subdomains: Vec<_> = domain
+            .create_subdomains(4.try_into().unwrap())
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(subdomains.len(), 2);
+    }
+}
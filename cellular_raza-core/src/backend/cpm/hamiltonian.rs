@@ -0,0 +1,61 @@
+/// Properties of cells which enter the Hamiltonian of the Cellular Potts model.
+///
+/// The energy of a configuration is given by
+/// $$\begin{equation}
+///     H = \sum_{\langle i,j\rangle} J(\sigma_i, \sigma_j)\left(1 - \delta_{\sigma_i\sigma_j}\right)
+///     + \sum_\sigma \lambda_\sigma\left(V_\sigma - V_\sigma^\text{target}\right)^2
+/// \end{equation}$$
+/// where the first sum runs over all pairs of neighboring sites $i,j$ which are occupied by
+/// the cells (or medium) $\sigma_i$ and $\sigma_j$.
+/// The adhesion energy $J$ between two cells is the mean of the values which are returned by
+/// both cells.
+///
+/// ```
+/// # use cellular_raza_core::backend::cpm::CpmAgent;
+/// struct Cell {
+///     cell_type: usize,
+/// }
+///
+/// impl CpmAgent for Cell {
+///     fn target_volume(&self) -> f64 {
+///         25.0
+///     }
+///
+///     fn volume_stiffness(&self) -> f64 {
+///         1.0
+///     }
+///
+///     fn adhesion_energy(&self, other: Option<&Self>) -> f64 {
+///         match other {
+///             Some(other) if other.cell_type == self.cell_type => 2.0,
+///             Some(_) => 11.0,
+///             None => 8.0,
+///         }
+///     }
+/// }
+/// ```
+pub trait CpmAgent {
+    /// Number of sites which the cell tries to occupy
+    fn target_volume(&self) -> f64;
+
+    /// Strength $\lambda$ of the volume constraint
+    fn volume_stiffness(&self) -> f64;
+
+    /// Energy of one contact with the given cell or the medium if [None]
+    fn adhesion_energy(&self, other: Option<&Self>) -> f64;
+}
+
+/// Symmetrized adhesion energy between two occupants of neighboring sites
+pub(super) fn adhesion_between<C: CpmAgent>(c1: Option<&C>, c2: Option<&C>) -> f64 {
+    match (c1, c2) {
+        (Some(c1), Some(c2)) => 0.5 * (c1.adhesion_energy(Some(c2)) + c2.adhesion_energy(Some(c1))),
+        (Some(c), None) | (None, Some(c)) => c.adhesion_energy(None),
+        (None, None) => 0.0,
+    }
+}
+
+/// Change of the volume energy when the volume of the cell changes by the given amount
+pub(super) fn volume_energy_change<C: CpmAgent>(cell: &C, volume: f64, change: f64) -> f64 {
+    let target = cell.target_volume();
+    cell.volume_stiffness() * ((volume + change - target).powi(2) - (volume - target).powi(2))
+}
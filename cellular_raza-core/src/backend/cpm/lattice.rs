@@ -0,0 +1,145 @@
+use cellular_raza_concepts::BoundaryError;
+use serde::{Deserialize, Serialize};
+
+/// Cartesian lattice whose sites are either occupied by a cell or by the medium.
+///
+/// Cells are referred to by their index.
+/// Sites are stored in row-major order, ie. neighboring sites along the last axis are stored
+/// next to each other.
+/// Two sites are neighbors if they differ by one along exactly one axis (von Neumann
+/// neighborhood).
+/// The lattice is not periodic.
+///
+/// ```
+/// # use cellular_raza_core::backend::cpm::CpmLattice;
+/// let mut lattice = CpmLattice::new([3, 4]);
+/// lattice.set(&[1, 2], Some(0))?;
+/// assert_eq!(lattice.occupant(&[1, 2])?, Some(0));
+/// assert_eq!(lattice.occupant(&[0, 0])?, None);
+/// assert!(lattice.occupant(&[3, 0]).is_err());
+/// # Ok::<(), cellular_raza_concepts::BoundaryError>(())
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CpmLattice<const D: usize> {
+    shape: Vec<usize>,
+    strides: Vec<usize>,
+    sites: Vec<Option<usize>>,
+}
+
+impl<const D: usize> CpmLattice<D> {
+    /// Constructs a lattice of the given shape which is filled by the medium
+    pub fn new(shape: [usize; D]) -> Self {
+        let mut strides = vec![1; D];
+        for i in (0..D.saturating_sub(1)).rev() {
+            strides[i] = strides[i + 1] * shape[i + 1];
+        }
+        Self {
+            shape: shape.to_vec(),
+            strides,
+            sites: vec![None; shape.iter().product()],
+        }
+    }
+
+    /// Number of sites along every axis
+    pub fn shape(&self) -> [usize; D] {
+        let mut shape = [0; D];
+        shape.copy_from_slice(&self.shape);
+        shape
+    }
+
+    /// Total number of sites
+    pub fn n_sites(&self) -> usize {
+        self.sites.len()
+    }
+
+    /// Occupants of all sites in row-major order
+    pub fn sites(&self) -> &[Option<usize>] {
+        &self.sites
+    }
+
+    pub(super) fn sites_mut(&mut self) -> &mut [Option<usize>] {
+        &mut self.sites
+    }
+
+    /// Position of the site in the storage order of the lattice
+    pub fn flat_index(&self, site: &[usize; D]) -> Result<usize, BoundaryError> {
+        let mut index = 0;
+        for i in 0..D {
+            if site[i] >= self.shape[i] {
+                return Err(BoundaryError(format!(
+                    "site {:?} is outside of the lattice with shape {:?}",
+                    site, self.shape
+                )));
+            }
+            index += site[i] * self.strides[i];
+        }
+        Ok(index)
+    }
+
+    /// Inverse of [CpmLattice::flat_index]
+    pub fn site(&self, flat_index: usize) -> [usize; D] {
+        let mut site = [0; D];
+        for i in 0..D {
+            site[i] = (flat_index / self.strides[i]) % self.shape[i];
+        }
+        site
+    }
+
+    /// Index of the cell which occupies the site or [None] for the medium
+    pub fn occupant(&self, site: &[usize; D]) -> Result<Option<usize>, BoundaryError> {
+        Ok(self.sites[self.flat_index(site)?])
+    }
+
+    /// Places the given cell or the medium at the site
+    pub fn set(&mut self, site: &[usize; D], occupant: Option<usize>) -> Result<(), BoundaryError> {
+        let index = self.flat_index(site)?;
+        self.sites[index] = occupant;
+        Ok(())
+    }
+
+    /// All sites which are occupied by the given cell
+    pub fn sites_of(&self, cell_index: usize) -> Vec<[usize; D]> {
+        self.sites
+            .iter()
+            .enumerate()
+            .filter(|(_, occupant)| **occupant == Some(cell_index))
+            .map(|(flat_index, _)| self.site(flat_index))
+            .collect()
+    }
+
+    /// Flat indices of all neighbors of the site with the given flat index
+    pub fn neighbors(&self, flat_index: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..D).flat_map(move |i| {
+            let coordinate = (flat_index / self.strides[i]) % self.shape[i];
+            let lower = (coordinate > 0).then(|| flat_index - self.strides[i]);
+            let upper = (coordinate + 1 < self.shape[i]).then(|| flat_index + self.strides[i]);
+            lower.into_iter().chain(upper)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test_lattice {
+    use super::*;
+
+    #[test]
+    fn flat_index_roundtrip() {
+        let lattice = CpmLattice::new([3, 4, 5]);
+        assert_eq!(lattice.n_sites(), 60);
+        for flat_index in 0..lattice.n_sites() {
+            let site = lattice.site(flat_index);
+            assert_eq!(lattice.flat_index(&site).unwrap(), flat_index);
+        }
+    }
+
+    #[test]
+    fn neighbors_at_edges() {
+        let lattice = CpmLattice::new([3, 3]);
+        let center = lattice.flat_index(&[1, 1]).unwrap();
+        assert_eq!(lattice.neighbors(center).count(), 4);
+        let corner = lattice.flat_index(&[0, 2]).unwrap();
+        let mut neighbors: Vec<_> = lattice.neighbors(corner).map(|n| lattice.site(n)).collect();
+        neighbors.sort();
+        assert_eq!(neighbors, vec![[0, 1], [1, 2]]);
+    }
+}
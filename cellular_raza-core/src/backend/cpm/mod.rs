@@ -0,0 +1,109 @@
+//! 🧩 Cellular Potts model on a Cartesian lattice
+//!
+//! In contrast to the center-based models of the other backends, cells are represented by the
+//! collection of lattice sites which they occupy.
+//! The configuration evolves by copy attempts in which a site adopts the occupant of one of its
+//! neighbors.
+//! Every attempt is accepted with the Metropolis probability
+//! $$\begin{equation}
+//!     p = \min\left(1, \exp\left(-\frac{\Delta H}{T}\right)\right)
+//! \end{equation}$$
+//! where the Hamiltonian $H$ consists of a volume constraint and the adhesion between cells
+//! as described by the [CpmAgent] trait.
+//! The connectivity of cells is not enforced.
+//!
+//! The lattice is decomposed into slabs by the
+//! [DomainCreateSubDomains](cellular_raza_concepts::DomainCreateSubDomains) implementation of
+//! the [CpmDomain].
+//! Slabs which do not touch each other propose flips in parallel.
+//!
+//! Besides the lattice, the [Cycle](cellular_raza_concepts::Cycle) and
+//! [Reactions](cellular_raza_concepts::Reactions) aspects can be updated after every Monte
+//! Carlo step.
+//! The [Cpm] backend only updates the lattice.
+//! To simulate more aspects, use the [CpmSimulation] directly.
+//!
+//! # Coupling to center-based models
+//! A [LatticeMapping] relates sites to positions in space.
+//! Positions of center-based cells can be turned into initial sites via the discrete Voronoi
+//! tessellation of [CpmDomain::voronoi_sites].
+//! Vice versa, the centroids of all cells are obtained by [CpmLattice::centroids].
+//! The corresponding conversion for vertex models is provided by the
+//! `VertexMechanics2D::from_voronoi` method of the `cellular_raza-building-blocks` crate.
+//!
+//! # Example
+//! ```
+//! use cellular_raza_concepts::{CalcError, Intracellular, Reactions};
+//! use cellular_raza_core::backend::cpm::*;
+//! use cellular_raza_core::storage::*;
+//!
+//! #[derive(Clone, serde::Deserialize, serde::Serialize)]
+//! struct Cell {
+//!     concentration: f64,
+//! }
+//!
+//! impl CpmAgent for Cell {
+//!     fn target_volume(&self) -> f64 {
+//!         16.0
+//!     }
+//!
+//!     fn volume_stiffness(&self) -> f64 {
+//!         1.0
+//!     }
+//!
+//!     fn adhesion_energy(&self, other: Option<&Self>) -> f64 {
+//!         if other.is_some() { 4.0 } else { 2.0 }
+//!     }
+//! }
+//! # impl Intracellular<f64> for Cell {
+//! #     fn get_intracellular(&self) -> f64 {
+//! #         self.concentration
+//! #     }
+//! #     fn set_intracellular(&mut self, concentration: f64) {
+//! #         self.concentration = concentration;
+//! #     }
+//! # }
+//!
+//! impl Reactions<f64> for Cell {
+//!     fn calculate_intracellular_increment(&self, c: &f64) -> Result<f64, CalcError> {
+//!         Ok(-0.1 * c)
+//!     }
+//! }
+//!
+//! // Two cells which initially occupy a single site each
+//! let cells = [
+//!     (Cell { concentration: 1.0 }, vec![[5, 5]]),
+//!     (Cell { concentration: 2.0 }, vec![[15, 15]]),
+//! ];
+//! let settings = CpmSettings::new(50, 1.0)
+//!     .n_subdomains(2.try_into().unwrap())
+//!     .dt(0.1);
+//! let mut simulation = CpmSimulation::new(CpmDomain::new([20, 20]), cells, settings)?;
+//!
+//! let storage_builder = StorageBuilder::new().priority([StorageOption::Memory]).init();
+//! let mut storage = CpmStorage::open_or_create(storage_builder)?;
+//! for _ in 0..50 {
+//!     simulation.monte_carlo_step();
+//!     simulation.update_reactions::<f64>()?;
+//! }
+//! simulation.store(&mut storage, 50)?;
+//!
+//! // Both cells have grown towards their target volume
+//! assert!(simulation.volume(0) > 8);
+//! assert!(simulation.volume(1) > 8);
+//! let cells = storage.cells.load_all_elements_at_iteration(50)?;
+//! assert!(cells[&0].concentration < 1.0);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+mod coupling;
+mod domain;
+mod hamiltonian;
+mod lattice;
+mod simulation;
+
+pub use coupling::*;
+pub use domain::*;
+pub use hamiltonian::*;
+pub use lattice::*;
+pub use simulation::*;
@@ -0,0 +1,644 @@
+use cellular_raza_concepts::{
+    BoundaryError, CalcError, Cycle, CycleEvent, DeathError, DecomposeError, DivisionError,
+    DomainCreateSubDomains, Reactions, Xapy,
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::hamiltonian::{adhesion_between, volume_energy_change};
+use super::{CpmAgent, CpmDomain, CpmLattice, CpmSubDomain};
+use crate::backend::interface::SimulationBackend;
+use crate::storage::{StorageBuilder, StorageError, StorageInterfaceStore, StorageManager};
+
+/// Error which can occur while running a [CpmSimulation]
+#[derive(Debug)]
+pub enum CpmError {
+    /// Calculating intracellular increments failed
+    CalcError(CalcError),
+    /// Cells were placed outside of the lattice or on top of each other
+    BoundaryError(BoundaryError),
+    /// The domain could not be split into slabs
+    DecomposeError(DecomposeError),
+    /// Dividing a cell failed
+    DivisionError(DivisionError),
+    /// Updating a dying cell failed
+    DeathError(DeathError),
+    /// Storing results failed
+    StorageError(StorageError),
+}
+
+macro_rules! impl_from_error {
+    ($(($err_var: ident, $err_type: ty)),+) => {
+        $(
+            impl From<$err_type> for CpmError {
+                fn from(err: $err_type) -> Self {
+                    CpmError::$err_var(err)
+                }
+            }
+        )+
+        impl core::fmt::Display for CpmError {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                match self {
+                    $(CpmError::$err_var(message) => write!(f, "{}", message),)+
+                }
+            }
+        }
+    };
+}
+
+impl_from_error!(
+    (CalcError, CalcError),
+    (BoundaryError, BoundaryError),
+    (DecomposeError, DecomposeError),
+    (DivisionError, DivisionError),
+    (DeathError, DeathError),
+    (StorageError, StorageError)
+);
+
+impl std::error::Error for CpmError {}
+
+/// Settings of a [CpmSimulation]
+///
+/// ```
+/// # use cellular_raza_core::backend::cpm::CpmSettings;
+/// let settings = CpmSettings::new(100, 10.0)
+///     .n_subdomains(4.try_into().unwrap())
+///     .save_interval(10)
+///     .seed(1);
+/// assert_eq!(settings.n_steps, 100);
+/// ```
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CpmSettings {
+    /// Number of Monte Carlo steps
+    pub n_steps: usize,
+    /// Temperature of the copy attempts in units of the Hamiltonian
+    pub temperature: f64,
+    /// Time increment which corresponds to one Monte Carlo step.
+    /// It is used to update the [Cycle] and [Reactions] aspects.
+    pub dt: f64,
+    /// Number of slabs which are updated in parallel
+    pub n_subdomains: core::num::NonZeroUsize,
+    /// Results are stored every given number of steps.
+    /// The initial and final configurations are always stored.
+    pub save_interval: Option<usize>,
+    /// Seed of the random number generators
+    pub seed: u64,
+}
+
+impl CpmSettings {
+    /// Performs the given number of Monte Carlo steps at the given temperature.
+    ///
+    /// Every step corresponds to the time increment `1.0`.
+    /// A single subdomain is used, only the initial and final configurations are stored and
+    /// the seed is `0`.
+    pub fn new(n_steps: usize, temperature: f64) -> Self {
+        Self {
+            n_steps,
+            temperature,
+            dt: 1.0,
+            n_subdomains: core::num::NonZeroUsize::MIN,
+            save_interval: None,
+            seed: 0,
+        }
+    }
+
+    /// Time increment which corresponds to one Monte Carlo step
+    pub fn dt(self, dt: f64) -> Self {
+        Self { dt, ..self }
+    }
+
+    /// Number of slabs which are updated in parallel
+    pub fn n_subdomains(self, n_subdomains: core::num::NonZeroUsize) -> Self {
+        Self {
+            n_subdomains,
+            ..self
+        }
+    }
+
+    /// Store results every given number of steps.
+    pub fn save_interval(self, save_interval: usize) -> Self {
+        Self {
+            save_interval: Some(save_interval),
+            ..self
+        }
+    }
+
+    /// Use the given seed for the random number generators.
+    pub fn seed(self, seed: u64) -> Self {
+        Self { seed, ..self }
+    }
+
+    fn is_save_point(&self, step: usize) -> bool {
+        step == self.n_steps
+            || self
+                .save_interval
+                .is_some_and(|interval| interval > 0 && step % interval == 0)
+    }
+}
+
+/// Stores cells and lattices of a [CpmSimulation]
+pub struct CpmStorage<C, const D: usize> {
+    /// Cells stored by their index
+    pub cells: StorageManager<usize, C>,
+    /// The lattice is stored with the identifier `0`
+    pub lattice: StorageManager<usize, CpmLattice<D>>,
+}
+
+impl<C, const D: usize> CpmStorage<C, D> {
+    /// Opens or creates storage for cells and lattices in the subfolders `cells` and `lattice`
+    pub fn open_or_create(builder: StorageBuilder<true>) -> Result<Self, StorageError> {
+        Ok(Self {
+            cells: StorageManager::open_or_create(builder.clone().suffix("cells"), 0)?,
+            lattice: StorageManager::open_or_create(builder.suffix("lattice"), 0)?,
+        })
+    }
+}
+
+/// State of a Cellular Potts simulation
+///
+/// The lattice is split into slabs by the [CpmDomain].
+/// One Monte Carlo step consists of as many copy attempts as there are sites.
+/// Every slab performs as many copy attempts as it has sites.
+/// First all slabs with an even index and afterwards all slabs with an odd index are updated in
+/// parallel.
+/// Volumes of cells which extend over multiple slabs are only synchronized after every half
+/// step.
+///
+/// Every aspect is updated by its own method such that users can decide which aspects are
+/// simulated.
+/// See the [module-level](super) documentation for an example.
+pub struct CpmSimulation<C, const D: usize> {
+    lattice: CpmLattice<D>,
+    cells: Vec<Option<C>>,
+    volumes: Vec<usize>,
+    dying: Vec<bool>,
+    subdomains: Vec<(CpmSubDomain<D>, ChaCha8Rng)>,
+    rng: ChaCha8Rng,
+    settings: CpmSettings,
+}
+
+/// Result of the copy attempts of one slab
+struct SlabUpdate {
+    range: core::ops::Range<usize>,
+    sites: Vec<Option<usize>>,
+    volume_changes: Vec<isize>,
+    n_accepted: usize,
+}
+
+impl<C, const D: usize> CpmSimulation<C, D>
+where
+    C: CpmAgent,
+{
+    /// Places the cells on the sites of the lattice which are given alongside of them.
+    pub fn new<Ci>(domain: CpmDomain<D>, cells: Ci, settings: CpmSettings) -> Result<Self, CpmError>
+    where
+        Ci: IntoIterator<Item = (C, Vec<[usize; D]>)>,
+    {
+        let mut lattice = domain.lattice();
+        let mut placed_cells = Vec::new();
+        let mut volumes = Vec::new();
+        for (cell_index, (cell, sites)) in cells.into_iter().enumerate() {
+            for site in sites.iter() {
+                if lattice.occupant(site)?.is_some() {
+                    return Err(
+                        BoundaryError(format!("site {:?} is already occupied", site)).into(),
+                    );
+                }
+                lattice.set(site, Some(cell_index))?;
+            }
+            placed_cells.push(Some(cell));
+            volumes.push(sites.len());
+        }
+        let subdomains = domain
+            .create_subdomains(settings.n_subdomains)?
+            .into_iter()
+            .map(|(index, subdomain, _)| {
+                let rng = ChaCha8Rng::seed_from_u64(settings.seed.wrapping_add(index as u64 + 1));
+                (subdomain, rng)
+            })
+            .collect();
+        Ok(Self {
+            lattice,
+            dying: vec![false; placed_cells.len()],
+            cells: placed_cells,
+            volumes,
+            subdomains,
+            rng: ChaCha8Rng::seed_from_u64(settings.seed),
+            settings,
+        })
+    }
+
+    /// Current lattice
+    pub fn lattice(&self) -> &CpmLattice<D> {
+        &self.lattice
+    }
+
+    /// All cells which are still alive together with their index
+    pub fn cells(&self) -> impl Iterator<Item = (usize, &C)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter_map(|(index, cell)| cell.as_ref().map(|cell| (index, cell)))
+    }
+
+    /// Number of sites which are occupied by the given cell
+    pub fn volume(&self, cell_index: usize) -> usize {
+        self.volumes.get(cell_index).copied().unwrap_or(0)
+    }
+
+    /// Settings of this simulation
+    pub fn settings(&self) -> &CpmSettings {
+        &self.settings
+    }
+
+    /// Performs one Monte Carlo step and returns the number of accepted copy attempts.
+    pub fn monte_carlo_step(&mut self) -> usize
+    where
+        C: Sync,
+    {
+        let mut n_accepted = 0;
+        for parity in [0, 1] {
+            let lattice = &self.lattice;
+            let cells = &self.cells;
+            let volumes = &self.volumes;
+            let temperature = self.settings.temperature;
+            let updates: Vec<SlabUpdate> = self
+                .subdomains
+                .par_iter_mut()
+                .enumerate()
+                .filter(|(index, _)| index % 2 == parity)
+                .map(|(_, (subdomain, rng))| {
+                    copy_attempts(lattice, cells, volumes, subdomain, rng, temperature)
+                })
+                .collect();
+            for update in updates {
+                self.lattice.sites_mut()[update.range].copy_from_slice(&update.sites);
+                for (volume, change) in self.volumes.iter_mut().zip(update.volume_changes) {
+                    *volume = (*volume as isize + change) as usize;
+                }
+                n_accepted += update.n_accepted;
+            }
+        }
+        n_accepted
+    }
+
+    /// Updates the [Cycle] of all cells with the time increment of the [CpmSettings].
+    ///
+    /// Dividing cells are split into two halves along the axis of their largest extension.
+    /// The second half is occupied by the new cell.
+    /// Removed cells are replaced by the medium.
+    pub fn update_cycle(&mut self) -> Result<(), CpmError>
+    where
+        C: Cycle<C>,
+    {
+        let dt = self.settings.dt;
+        for cell_index in 0..self.cells.len() {
+            let Some(cell) = self.cells[cell_index].as_mut() else {
+                continue;
+            };
+            if self.dying[cell_index] {
+                if C::update_conditional_phased_death(&mut self.rng, &dt, cell)? {
+                    self.remove_cell(cell_index);
+                }
+                continue;
+            }
+            match C::update_cycle(&mut self.rng, &dt, cell) {
+                Some(CycleEvent::Division) => {
+                    let new_cell = C::divide(&mut self.rng, cell)?;
+                    self.split_cell(cell_index, new_cell);
+                }
+                Some(CycleEvent::Remove) => self.remove_cell(cell_index),
+                Some(CycleEvent::PhasedDeath) => self.dying[cell_index] = true,
+                None => (),
+            }
+        }
+        Ok(())
+    }
+
+    /// Integrates the [Reactions] of all cells with the explicit Euler method and the time
+    /// increment of the [CpmSettings].
+    pub fn update_reactions<Ri>(&mut self) -> Result<(), CpmError>
+    where
+        C: Reactions<Ri>,
+        Ri: Xapy<f64>,
+    {
+        let dt = self.settings.dt;
+        for cell in self.cells.iter_mut().flatten() {
+            let intracellular = cell.get_intracellular();
+            let increment = cell.calculate_intracellular_increment(&intracellular)?;
+            cell.set_intracellular(increment.xapy(dt, &intracellular));
+        }
+        Ok(())
+    }
+
+    /// Stores all cells and the lattice at the given iteration.
+    pub fn store(&self, storage: &mut CpmStorage<C, D>, iteration: u64) -> Result<(), CpmError>
+    where
+        C: Clone + Serialize,
+    {
+        let cells: Vec<(usize, &C)> = self.cells().collect();
+        storage
+            .cells
+            .store_batch_elements(iteration, cells.iter().map(|(index, cell)| (index, *cell)))?;
+        storage
+            .lattice
+            .store_single_element(iteration, &0, &self.lattice)?;
+        Ok(())
+    }
+
+    fn remove_cell(&mut self, cell_index: usize) {
+        for site in self.lattice.sites_mut().iter_mut() {
+            if *site == Some(cell_index) {
+                *site = None;
+            }
+        }
+        self.cells[cell_index] = None;
+        self.volumes[cell_index] = 0;
+    }
+
+    fn split_cell(&mut self, cell_index: usize, new_cell: C) {
+        let new_index = self.cells.len();
+        let mut sites = self.lattice.sites_of(cell_index);
+        let axis = (0..D)
+            .max_by_key(|&i| {
+                let min = sites.iter().map(|site| site[i]).min().unwrap_or(0);
+                let max = sites.iter().map(|site| site[i]).max().unwrap_or(0);
+                max - min
+            })
+            .unwrap_or(0);
+        sites.sort_by_key(|site| site[axis]);
+        let n_kept = sites.len() / 2;
+        for site in sites[n_kept..].iter() {
+            // The sites were obtained from the lattice and are thus always valid
+            let _ = self.lattice.set(site, Some(new_index));
+        }
+        self.volumes[cell_index] = n_kept;
+        self.volumes.push(sites.len() - n_kept);
+        self.cells.push(Some(new_cell));
+        self.dying.push(false);
+    }
+}
+
+/// Performs the copy attempts of one slab on a copy of its sites.
+fn copy_attempts<C, const D: usize>(
+    lattice: &CpmLattice<D>,
+    cells: &[Option<C>],
+    volumes: &[usize],
+    subdomain: &CpmSubDomain<D>,
+    rng: &mut ChaCha8Rng,
+    temperature: f64,
+) -> SlabUpdate
+where
+    C: CpmAgent,
+{
+    let range = subdomain.flat_range();
+    let mut sites = lattice.sites()[range.clone()].to_vec();
+    let mut volume_changes = vec![0isize; volumes.len()];
+    let mut n_accepted = 0;
+    // Sites outside of this slab are not modified while it is updated
+    let occupant = |sites: &[Option<usize>], flat_index: usize| {
+        if range.contains(&flat_index) {
+            sites[flat_index - range.start]
+        } else {
+            lattice.sites()[flat_index]
+        }
+    };
+    let cell = |index: Option<usize>| index.and_then(|index| cells[index].as_ref());
+    let volume =
+        |index: usize, changes: &[isize]| (volumes[index] as isize + changes[index]) as f64;
+
+    for _ in 0..range.len() {
+        let target = rng.gen_range(range.clone());
+        let n_neighbors = lattice.neighbors(target).count();
+        if n_neighbors == 0 {
+            continue;
+        }
+        let source = lattice
+            .neighbors(target)
+            .nth(rng.gen_range(0..n_neighbors))
+            .unwrap_or(target);
+        let old = occupant(&sites, target);
+        let new = occupant(&sites, source);
+        if old == new {
+            continue;
+        }
+
+        let mut delta_energy = 0.0;
+        for neighbor in lattice.neighbors(target) {
+            let other = occupant(&sites, neighbor);
+            if other != new {
+                delta_energy += adhesion_between(cell(new), cell(other));
+            }
+            if other != old {
+                delta_energy -= adhesion_between(cell(old), cell(other));
+            }
+        }
+        if let (Some(index), Some(c)) = (old, cell(old)) {
+            delta_energy += volume_energy_change(c, volume(index, &volume_changes), -1.0);
+        }
+        if let (Some(index), Some(c)) = (new, cell(new)) {
+            delta_energy += volume_energy_change(c, volume(index, &volume_changes), 1.0);
+        }
+
+        let accept = delta_energy <= 0.0
+            || (temperature > 0.0 && rng.gen::<f64>() < (-delta_energy / temperature).exp());
+        if accept {
+            sites[target - range.start] = new;
+            if let Some(index) = old {
+                volume_changes[index] -= 1;
+            }
+            if let Some(index) = new {
+                volume_changes[index] += 1;
+            }
+            n_accepted += 1;
+        }
+    }
+    SlabUpdate {
+        range,
+        sites,
+        volume_changes,
+        n_accepted,
+    }
+}
+
+/// 🧩 Runs a [CpmSimulation] in which only the lattice is updated.
+///
+/// Agents are given together with the sites which they initially occupy.
+/// Simulations which should also update the [Cycle] or [Reactions] aspects can use the methods
+/// of the [CpmSimulation] directly.
+pub struct Cpm;
+
+impl<C, const D: usize> SimulationBackend<(C, Vec<[usize; D]>), CpmDomain<D>> for Cpm
+where
+    C: CpmAgent + Serialize + Clone + Sync,
+{
+    type Settings = (CpmSettings, StorageBuilder<true>);
+    type Output = CpmStorage<C, D>;
+    type Error = CpmError;
+
+    fn run_simulation<Ci>(
+        &mut self,
+        agents: Ci,
+        domain: CpmDomain<D>,
+        settings: Self::Settings,
+    ) -> Result<Self::Output, Self::Error>
+    where
+        Ci: IntoIterator<Item = (C, Vec<[usize; D]>)>,
+    {
+        let (settings, storage_builder) = settings;
+        let mut storage = CpmStorage::open_or_create(storage_builder)?;
+        let mut simulation = CpmSimulation::new(domain, agents, settings)?;
+        simulation.store(&mut storage, 0)?;
+        for step in 1..=settings.n_steps {
+            simulation.monte_carlo_step();
+            if settings.is_save_point(step) {
+                simulation.store(&mut storage, step as u64)?;
+            }
+        }
+        Ok(storage)
+    }
+}
+
+#[cfg(test)]
+mod test_simulation {
+    use super::*;
+    use crate::storage::{StorageInterfaceLoad, StorageOption};
+
+    #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+    struct Cell {
+        cell_type: usize,
+        target_volume: f64,
+        age: f64,
+        intracellular: f64,
+    }
+
+    impl CpmAgent for Cell {
+        fn target_volume(&self) -> f64 {
+            self.target_volume
+        }
+
+        fn volume_stiffness(&self) -> f64 {
+            1.0
+        }
+
+        fn adhesion_energy(&self, other: Option<&Self>) -> f64 {
+            match other {
+                Some(other) if other.cell_type == self.cell_type => 1.0,
+                Some(_) => 4.0,
+                None => 2.0,
+            }
+        }
+    }
+
+    impl Cycle for Cell {
+        fn update_cycle(_: &mut ChaCha8Rng, dt: &f64, cell: &mut Cell) -> Option<CycleEvent> {
+            cell.age += dt;
+            (cell.age >= 2.0).then_some(CycleEvent::Division)
+        }
+
+        fn divide(_: &mut ChaCha8Rng, cell: &mut Cell) -> Result<Cell, DivisionError> {
+            cell.age = 0.0;
+            Ok(cell.clone())
+        }
+    }
+
+    impl cellular_raza_concepts::Intracellular<f64> for Cell {
+        fn get_intracellular(&self) -> f64 {
+            self.intracellular
+        }
+
+        fn set_intracellular(&mut self, intracellular: f64) {
+            self.intracellular = intracellular;
+        }
+    }
+
+    impl Reactions<f64> for Cell {
+        fn calculate_intracellular_increment(&self, _: &f64) -> Result<f64, CalcError> {
+            Ok(1.0)
+        }
+    }
+
+    fn cell(cell_type: usize, target_volume: f64) -> Cell {
+        Cell {
+            cell_type,
+            target_volume,
+            age: 0.0,
+            intracellular: 0.0,
+        }
+    }
+
+    fn square(start: [usize; 2], size: usize) -> Vec<[usize; 2]> {
+        (0..size)
+            .flat_map(|i| (0..size).map(move |j| [start[0] + i, start[1] + j]))
+            .collect()
+    }
+
+    #[test]
+    fn reject_overlapping_cells() {
+        let cells = [
+            (cell(0, 4.0), square([0, 0], 2)),
+            (cell(0, 4.0), square([1, 1], 2)),
+        ];
+        let result = CpmSimulation::new(CpmDomain::new([5, 5]), cells, CpmSettings::new(1, 1.0));
+        assert!(matches!(result, Err(CpmError::BoundaryError(_))));
+    }
+
+    #[test]
+    fn volume_relaxes_to_target() {
+        let cells = [(cell(0, 16.0), square([8, 8], 2))];
+        let settings = CpmSettings::new(0, 1.0)
+            .n_subdomains(3.try_into().unwrap())
+            .seed(3);
+        let mut simulation = CpmSimulation::new(CpmDomain::new([20, 20]), cells, settings).unwrap();
+        for _ in 0..200 {
+            simulation.monte_carlo_step();
+        }
+        assert_eq!(simulation.volume(0), simulation.lattice().sites_of(0).len());
+        assert!((simulation.volume(0) as f64 - 16.0).abs() <= 4.0);
+    }
+
+    #[test]
+    fn divide_and_integrate_reactions() {
+        let cells = [(cell(0, 16.0), square([2, 2], 4))];
+        let settings = CpmSettings::new(0, 0.0).dt(1.0);
+        let mut simulation = CpmSimulation::new(CpmDomain::new([10, 10]), cells, settings).unwrap();
+        simulation.update_cycle().unwrap();
+        assert_eq!(simulation.cells().count(), 1);
+        simulation.update_reactions::<f64>().unwrap();
+        simulation.update_cycle().unwrap();
+        assert_eq!(simulation.cells().count(), 2);
+        assert_eq!(simulation.volume(0), 8);
+        assert_eq!(simulation.volume(1), 8);
+        assert_eq!(simulation.lattice().sites_of(1).len(), 8);
+        assert!(simulation.cells().all(|(_, c)| c.intracellular == 1.0));
+    }
+
+    #[test]
+    fn run_backend() {
+        let cells = vec![
+            (cell(0, 9.0), square([1, 1], 3)),
+            (cell(1, 9.0), square([5, 5], 3)),
+        ];
+        let settings = CpmSettings::new(20, 2.0)
+            .n_subdomains(2.try_into().unwrap())
+            .save_interval(10);
+        let storage = StorageBuilder::new()
+            .priority([StorageOption::Memory])
+            .init();
+        let results = Cpm
+            .run_simulation(cells, CpmDomain::new([10, 10]), (settings, storage))
+            .unwrap();
+        let mut iterations = results.cells.get_all_iterations().unwrap();
+        iterations.sort();
+        assert_eq!(iterations, vec![0, 10, 20]);
+        let lattice = results
+            .lattice
+            .load_single_element(20, &0)
+            .unwrap()
+            .unwrap();
+        assert_eq!(lattice.shape(), [10, 10]);
+        assert!(!lattice.sites_of(1).is_empty());
+    }
+}
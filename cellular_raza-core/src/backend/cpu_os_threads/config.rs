@@ -1,12 +1,13 @@
 use super::{Agent, ForceBound, PositionBound, VelocityBound};
 use crate::backend::cpu_os_threads::domain_decomposition::AuxiliaryCellPropertyStorage;
-use crate::storage::StorageManager;
+use crate::storage::{StorageError, StorageManager, WriteBehindStorage};
 use cellular_raza_concepts::domain_old::*;
 use cellular_raza_concepts::CellAgentBox;
 
 use super::domain_decomposition::{
-    ConcentrationBoundaryInformation, DomainBox, ForceInformation, IndexBoundaryInformation,
-    MultiVoxelContainer, PlainIndex, PosInformation, VoxelBox,
+    CellStorageSink, ConcentrationBoundaryInformation, DomainBox, ForceInformation,
+    IndexBoundaryInformation, MultiVoxelContainer, PlainIndex, PosInformation, VoxelBox,
+    VoxelStorageSink,
 };
 use super::supervisor::ControllerBox;
 use super::supervisor::SimulationSupervisor;
@@ -69,6 +70,28 @@ pub struct TimeSetup {
     pub t_eval: Vec<(f64, bool)>,
 }
 
+impl TimeSetup {
+    /// Evaluates the simulation in steps of `dt` until `t_max` and saves results at the steps
+    /// closest to the points given by the [SaveSchedule](crate::time::SaveSchedule).
+    pub fn from_save_schedule(
+        t_start: f64,
+        dt: f64,
+        t_max: f64,
+        schedule: &crate::time::SaveSchedule<f64>,
+    ) -> Result<Self, cellular_raza_concepts::TimeError> {
+        let save_iterations: std::collections::BTreeSet<usize> = schedule
+            .save_points(t_start, t_max)?
+            .into_iter()
+            .map(|t| ((t - t_start) / dt).round() as usize)
+            .collect();
+        let n_steps = ((t_max - t_start) / dt).round() as usize;
+        let t_eval = (1..n_steps + 1)
+            .map(|n| (t_start + n as f64 * dt, save_iterations.contains(&n)))
+            .collect();
+        Ok(Self { t_start, t_eval })
+    }
+}
+
 /// # Complete Set of parameters controlling execution flow of simulation
 #[derive(Clone, Serialize, Deserialize)]
 pub struct SimulationSetup<Dom, Cel, Cont = ()> {
@@ -188,9 +211,31 @@ where
     Vox: 'a + Clone,
 {
     /// Strategies for the modification of existing voxels before the simulation has started.
+    ///
+    /// Initial extracellular concentrations can be given by a `ConcentrationField` from
+    /// [cellular_raza-building-blocks](https://docs.rs/cellular_raza-building-blocks) via
+    /// `&|voxel| voxel.initialize_extracellular(&field)`.
     pub voxel_definition_strategies: &'a dyn Fn(&mut Vox),
 }
 
+/// Creates the [StorageSink](crate::storage::StorageSink)s to which worker threads store their
+/// results.
+///
+/// Every worker thread obtains its own sinks by calling both functions with its index.
+/// Without these functions, a [StorageManager] is opened for every worker thread as specified by
+/// the storage of the [SimulationSetup] and wrapped in a [WriteBehindStorage] which respects its
+/// [WriteBehind](crate::storage::WriteBehind) settings.
+/// Only results stored by a [StorageManager] can be loaded by the
+/// [SimulationResult](super::SimulationResult).
+/// Results stored in other sinks are obtained via
+/// [SimulationSupervisor::run_full_sim_into_sinks].
+pub struct StorageSinks<'a, Cel, Vbx> {
+    /// Creates the sink for the cells of the worker thread with the given index
+    pub cells: &'a dyn Fn(u64) -> Result<CellStorageSink<Cel>, StorageError>,
+    /// Creates the sink for the voxels of the worker thread with the given index
+    pub voxels: &'a dyn Fn(u64) -> Result<VoxelStorageSink<Vbx>, StorageError>,
+}
+
 impl<
         Pos,
         For,
@@ -231,9 +276,9 @@ where
     Pos: Serialize + for<'a> Deserialize<'a> + PositionBound + 'static + std::fmt::Debug,
     For: Serialize + for<'a> Deserialize<'a> + ForceBound + 'static,
     Vel: Serialize + for<'a> Deserialize<'a> + VelocityBound + 'static,
-    ConcVecExtracellular: Serialize + for<'a> Deserialize<'a>,
-    ConcBoundaryExtracellular: Serialize + for<'a> Deserialize<'a>,
-    ConcVecIntracellular: Serialize + for<'a> Deserialize<'a> + num::Zero,
+    ConcVecExtracellular: Serialize + for<'a> Deserialize<'a> + Send + 'static,
+    ConcBoundaryExtracellular: Serialize + for<'a> Deserialize<'a> + Send + 'static,
+    ConcVecIntracellular: Serialize + for<'a> Deserialize<'a> + num::Zero + Send + 'static,
     Vox: Voxel<Ind, Pos, Vel, For> + Clone + 'static,
     Cel: Agent<Pos, Vel, For, Inf> + 'static,
     VoxelBox<
@@ -284,6 +329,9 @@ where
     }
 
     /// Construct a new [SimulationSupervisor] from a given [SimulationSetup] with [Strategies].
+    ///
+    /// # Panics
+    /// Panics if the storage of the worker threads can not be opened.
     pub fn initialize_with_strategies(
         setup: SimulationSetup<Dom, Cel, Cont>,
         strategies: Strategies<Vox>,
@@ -306,6 +354,103 @@ where
         Cont,
         Obs,
     >
+    where
+        Cel: Sized,
+    {
+        Self::initialize(setup, strategies, None).unwrap()
+    }
+
+    /// Construct a new [SimulationSupervisor] from a given [SimulationSetup] with [Strategies]
+    /// whose worker threads store their results in the given [StorageSinks].
+    ///
+    /// Errors of the functions which create the sinks are returned.
+    pub fn initialize_with_storage_sinks(
+        setup: SimulationSetup<Dom, Cel, Cont>,
+        strategies: Strategies<Vox>,
+        storage_sinks: StorageSinks<
+            Cel,
+            VoxelBox<
+                Ind,
+                Pos,
+                Vel,
+                For,
+                Vox,
+                Cel,
+                ConcVecExtracellular,
+                ConcBoundaryExtracellular,
+                ConcVecIntracellular,
+            >,
+        >,
+    ) -> Result<
+        SimulationSupervisor<
+            MultiVoxelContainer<
+                Ind,
+                Pos,
+                Vel,
+                For,
+                Inf,
+                Vox,
+                Dom,
+                Cel,
+                ConcVecExtracellular,
+                ConcBoundaryExtracellular,
+                ConcVecIntracellular,
+            >,
+            Dom,
+            Cel,
+            Cont,
+            Obs,
+        >,
+        StorageError,
+    >
+    where
+        Cel: Sized,
+    {
+        Self::initialize(setup, strategies, Some(storage_sinks))
+    }
+
+    /// Opens a [WriteBehindStorage] for every worker thread if no [StorageSinks] are given.
+    fn initialize(
+        setup: SimulationSetup<Dom, Cel, Cont>,
+        strategies: Strategies<Vox>,
+        storage_sinks: Option<
+            StorageSinks<
+                Cel,
+                VoxelBox<
+                    Ind,
+                    Pos,
+                    Vel,
+                    For,
+                    Vox,
+                    Cel,
+                    ConcVecExtracellular,
+                    ConcBoundaryExtracellular,
+                    ConcVecIntracellular,
+                >,
+            >,
+        >,
+    ) -> Result<
+        SimulationSupervisor<
+            MultiVoxelContainer<
+                Ind,
+                Pos,
+                Vel,
+                For,
+                Inf,
+                Vox,
+                Dom,
+                Cel,
+                ConcVecExtracellular,
+                ConcBoundaryExtracellular,
+                ConcVecIntracellular,
+            >,
+            Dom,
+            Cel,
+            Cont,
+            Obs,
+        >,
+        StorageError,
+    >
     where
         Cel: Sized,
     {
@@ -493,8 +638,7 @@ where
             StorageManager::<(), SimulationSetup<DomainBox<Dom>, Cel, Cont>>::open_or_create(
                 meta_infos_builder,
                 0,
-            )
-            .unwrap();
+            )?;
 
         // Create all multivoxelcontainers
         use rand::{RngCore, SeedableRng};
@@ -593,36 +737,47 @@ where
                 let senders_boundary_concentrations =
                     create_senders!(sender_receiver_pairs_boundary_concentrations);
 
-                // TODO catch these errors!
-                let storage_cells_builder = builder
-                    .clone()
-                    .suffix(builder.get_suffix().join("cell_storage"));
-
-                let storage_cells =
-                    StorageManager::<CellularIdentifier, CellAgentBox<Cel>>::open_or_create(
-                        storage_cells_builder,
-                        i as u64,
-                    )
-                    .unwrap();
-                let storage_voxels_builder = builder
-                    .clone()
-                    .suffix(builder.get_suffix().join("voxel_storage"));
-                let storage_voxels =
-                    StorageManager::<
-                        PlainIndex,
-                        VoxelBox<
-                            Ind,
-                            Pos,
-                            Vel,
-                            For,
-                            Vox,
-                            Cel,
-                            ConcVecExtracellular,
-                            ConcBoundaryExtracellular,
-                            ConcVecIntracellular,
-                        >,
-                    >::open_or_create(storage_voxels_builder, i as u64)
-                    .unwrap();
+                let (storage_cells, storage_voxels) = match &storage_sinks {
+                    Some(storage_sinks) => (
+                        (storage_sinks.cells)(i as u64)?,
+                        (storage_sinks.voxels)(i as u64)?,
+                    ),
+                    None => {
+                        let storage_cells_builder = builder
+                            .clone()
+                            .suffix(builder.get_suffix().join("cell_storage"));
+                        let storage_cells: CellStorageSink<Cel> =
+                            Box::new(WriteBehindStorage::from_manager(StorageManager::<
+                                CellularIdentifier,
+                                CellAgentBox<Cel>,
+                            >::open_or_create(
+                                storage_cells_builder,
+                                i as u64,
+                            )?)?);
+                        let storage_voxels_builder = builder
+                            .clone()
+                            .suffix(builder.get_suffix().join("voxel_storage"));
+                        let storage_voxels: VoxelStorageSink<_> =
+                            Box::new(WriteBehindStorage::from_manager(StorageManager::<
+                                PlainIndex,
+                                VoxelBox<
+                                    Ind,
+                                    Pos,
+                                    Vel,
+                                    For,
+                                    Vox,
+                                    Cel,
+                                    ConcVecExtracellular,
+                                    ConcBoundaryExtracellular,
+                                    ConcVecIntracellular,
+                                >,
+                            >::open_or_create(
+                                storage_voxels_builder,
+                                i as u64,
+                            )?)?);
+                        (storage_cells, storage_voxels)
+                    }
+                };
 
                 voxels.iter_mut().for_each(|(_, voxelbox)| {
                     (strategies.voxel_definition_strategies)(&mut voxelbox.voxel)
@@ -662,11 +817,11 @@ where
                     mvc_id: i as u32,
                 };
 
-                return cont;
+                Ok(cont)
             })
-            .collect();
+            .collect::<Result<_, StorageError>>()?;
 
-        SimulationSupervisor {
+        Ok(SimulationSupervisor {
             worker_threads: Vec::new(),
             multivoxelcontainers,
 
@@ -686,6 +841,6 @@ where
             })),
             phantom_cont: PhantomData,
             phantom_obs: PhantomData,
-        }
+        })
     }
 }
@@ -4,7 +4,7 @@ use cellular_raza_concepts::reactions_old::*;
 use cellular_raza_concepts::*;
 
 use super::errors::*;
-use crate::storage::StorageManager;
+use crate::storage::StorageSink;
 
 use std::collections::{BTreeMap, HashMap};
 
@@ -22,6 +22,12 @@ use rand_chacha::ChaCha8Rng;
 #[cfg(feature = "tracing")]
 use tracing::instrument;
 
+/// [StorageSink] to which a worker thread stores its cells.
+pub type CellStorageSink<Cel> = Box<dyn StorageSink<CellularIdentifier, CellAgentBox<Cel>>>;
+
+/// [StorageSink] to which a worker thread stores its voxels given as [VoxelBox].
+pub type VoxelStorageSink<Vbx> = Box<dyn StorageSink<PlainIndex, Vbx>>;
+
 /// Wrapper around the user-defined simulation [Domain]
 #[derive(Clone, Serialize, Deserialize)]
 pub struct DomainBox<Dom> {
@@ -361,7 +367,7 @@ where
                 aux1.force += force1 * 0.5;
                 aux2.force += force2 * 0.5;
 
-                match c1.is_neighbor(&p1, &p2, &i2)? {
+                match c1.reacts_to_neighbors() && c1.is_neighbor(&p1, &p2, &i2)? {
                     true => aux1.neighbor_count += 1,
                     false => (),
                 }
@@ -370,7 +376,7 @@ where
                 aux1.force += force1 * 0.5;
                 aux2.force += force2 * 0.5;
 
-                match c2.is_neighbor(&p2, &p1, &i1)? {
+                match c2.reacts_to_neighbors() && c2.is_neighbor(&p2, &p1, &i1)? {
                     true => aux2.neighbor_count += 1,
                     false => (),
                 }
@@ -408,7 +414,7 @@ where
             aux_storage.force += f1;
             force += f2;
 
-            match cell.is_neighbor(&cell.pos(), &ext_pos, &ext_inf)? {
+            match cell.reacts_to_neighbors() && cell.is_neighbor(&cell.pos(), &ext_pos, &ext_inf)? {
                 true => aux_storage.neighbor_count += 1,
                 false => (),
             }
@@ -559,9 +565,8 @@ pub struct MultiVoxelContainer<
     // processing
     pub(crate) barrier: Barrier,
 
-    pub(crate) storage_cells: StorageManager<CellularIdentifier, CellAgentBox<Cel>>,
-    pub(crate) storage_voxels: StorageManager<
-        PlainIndex,
+    pub(crate) storage_cells: CellStorageSink<Cel>,
+    pub(crate) storage_voxels: VoxelStorageSink<
         VoxelBox<
             Ind,
             Pos,
@@ -661,8 +666,10 @@ where
                 vox.cells
                     .iter_mut()
                     .map(|(cell, aux_storage)| {
-                        cell.react_to_neighbors(aux_storage.neighbor_count)?;
-                        aux_storage.neighbor_count = 0;
+                        if cell.reacts_to_neighbors() {
+                            cell.react_to_neighbors(aux_storage.neighbor_count)?;
+                            aux_storage.neighbor_count = 0;
+                        }
                         Ok(())
                     })
                     .collect::<Result<(), SimulationError>>()?;
@@ -1207,14 +1214,14 @@ where
         CellAgentBox<Cel>: Clone,
         AuxiliaryCellPropertyStorage<Pos, Vel, For, ConcVecIntracellular>: Clone,
     {
-        use crate::storage::StorageInterfaceStore;
-        let cells = self
+        let cells: Vec<_> = self
             .voxels
             .iter()
             .map(|(_, vox)| vox.cells.iter().map(|(c, _)| (c.ref_id(), c)))
-            .flatten();
+            .flatten()
+            .collect();
 
-        self.storage_cells.store_batch_elements(*iteration, cells)
+        self.storage_cells.store_batch(*iteration, &cells)
     }
 
     #[cfg_attr(feature = "tracing", instrument(skip_all))]
@@ -1235,10 +1242,13 @@ where
             ConcVecIntracellular,
         >: Clone + Send + Sync + 'static,
     {
-        use crate::storage::StorageInterfaceStore;
-        let voxels = self.voxels.iter().map(|(_, voxel)| (voxel.ref_id(), voxel));
+        let voxels: Vec<_> = self
+            .voxels
+            .iter()
+            .map(|(_, voxel)| (voxel.ref_id(), voxel))
+            .collect();
 
-        self.storage_voxels.store_batch_elements(*iteration, voxels)
+        self.storage_voxels.store_batch(*iteration, &voxels)
     }
 
     // TODO find better function signature to have multiple time-scales
@@ -5,10 +5,11 @@ use cellular_raza_concepts::*;
 use kdam::BarExt;
 
 use super::errors::*;
-use crate::storage::{StorageBuilder, StorageManager};
+use crate::storage::{StorageBuilder, StorageManager, WriteBehindStorage};
 
 use super::domain_decomposition::{
-    AuxiliaryCellPropertyStorage, DomainBox, MultiVoxelContainer, VoxelBox,
+    AuxiliaryCellPropertyStorage, CellStorageSink, DomainBox, MultiVoxelContainer, VoxelBox,
+    VoxelStorageSink,
 };
 
 use super::config::{
@@ -297,6 +298,94 @@ where
         Ok(())
     }
 
+    /// Runs a full simulation and returns the storage of every worker thread.
+    ///
+    /// In contrast to [SimulationSupervisor::run_full_sim], this function does not require the
+    /// worker threads to store their results in a [StorageManager] and thus works with any
+    /// [StorageSinks](super::StorageSinks).
+    #[cfg_attr(feature = "tracing", instrument(skip_all))]
+    pub fn run_full_sim_into_sinks<ConcGradientExtracellular, ConcTotalExtracellular>(
+        &mut self,
+    ) -> Result<
+        Vec<
+            WorkerStorage<
+                Dom,
+                Cel,
+                VoxelBox<
+                    Ind,
+                    Pos,
+                    Vel,
+                    For,
+                    Vox,
+                    Cel,
+                    ConcVecExtracellular,
+                    ConcBoundaryExtracellular,
+                    ConcVecIntracellular,
+                >,
+            >,
+        >,
+        SimulationError,
+    >
+    where
+        Dom: cellular_raza_concepts::domain_old::Domain<Cel, Ind, Vox>,
+        Pos: PositionBound,
+        For: ForceBound,
+        Inf: InteractionInformation,
+        Vel: VelocityBound,
+        ConcVecExtracellular: Concentration,
+        ConcTotalExtracellular: Concentration,
+        ConcBoundaryExtracellular: Send + Sync + 'static,
+        ConcVecIntracellular: Send + Sync + Concentration,
+        ConcVecIntracellular: Mul<f64, Output = ConcVecIntracellular>
+            + Add<ConcVecIntracellular, Output = ConcVecIntracellular>
+            + AddAssign<ConcVecIntracellular>,
+        Ind: Index,
+        Vox: Voxel<Ind, Pos, Vel, For>,
+        Vox: ExtracellularMechanics<
+                Ind,
+                Pos,
+                ConcVecExtracellular,
+                ConcGradientExtracellular,
+                ConcTotalExtracellular,
+                ConcBoundaryExtracellular,
+            > + Volume,
+        Cel: Agent<Pos, Vel, For, Inf>
+            + CellularReactions<ConcVecIntracellular, ConcVecExtracellular>
+            + InteractionExtracellularGradient<Cel, ConcGradientExtracellular>
+            + Volume,
+        VoxelBox<
+            Ind,
+            Pos,
+            Vel,
+            For,
+            Vox,
+            Cel,
+            ConcVecExtracellular,
+            ConcBoundaryExtracellular,
+            ConcVecIntracellular,
+        >: Clone,
+        AuxiliaryCellPropertyStorage<Pos, Vel, For, ConcVecIntracellular>: Clone,
+        Cont: Controller<Cel, Obs>,
+    {
+        // Run the simulation
+        self.spawn_worker_threads_and_run_sim()?;
+
+        // Collect all threads
+        let mut worker_storages = Vec::new();
+        for thread in self.worker_threads.drain(..) {
+            let t = thread
+                .join()
+                .expect("Could not join threads after Simulation has finished")?;
+            worker_storages.push(WorkerStorage {
+                domain: t.domain,
+                storage_cells: t.storage_cells,
+                storage_voxels: t.storage_voxels,
+                mvc_id: t.mvc_id,
+            });
+        }
+        Ok(worker_storages)
+    }
+
     /// Runs a full simulation and returns a [SimulationResult] after having completed
     #[cfg_attr(feature = "tracing", instrument(skip_all))]
     pub fn run_full_sim<ConcGradientExtracellular, ConcTotalExtracellular>(
@@ -357,23 +446,25 @@ where
         AuxiliaryCellPropertyStorage<Pos, Vel, For, ConcVecIntracellular>: Clone,
         Cont: Controller<Cel, Obs>,
     {
-        // Run the simulation
-        self.spawn_worker_threads_and_run_sim()?;
-
-        // Collect all threads
-        let mut databases = Vec::new();
-        for thread in self.worker_threads.drain(..) {
-            let t = thread
-                .join()
-                .expect("Could not join threads after Simulation has finished")?;
-            databases.push((t.storage_cells, t.storage_voxels, t.domain))
+        // Finish the sinks of all workers such that none of their errors is lost
+        let mut recovered = Vec::new();
+        let mut first_error = None;
+        for worker_storage in self.run_full_sim_into_sinks()? {
+            match worker_storage.recover_storage_managers() {
+                Ok(managers) => recovered.push(managers),
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
         }
-
-        // Create a simulationresult which can then be used to further plot and analyze results
-        let (storage_cells, storage_voxels, domain) = databases.pop().ok_or(RequestError(
-            format!("The threads of the simulation did not yield any handles"),
+        if let Some(e) = first_error {
+            return Err(e);
+        }
+        let (domain, storage_cells, storage_voxels) = recovered.pop().ok_or(RequestError(
+            "The threads of the simulation did not yield any handles".to_owned(),
         ))?;
 
+        // Create a simulationresult which can then be used to further plot and analyze results
         let simulation_result = SimulationResult {
             storage: self.storage.clone(),
             domain,
@@ -415,6 +506,69 @@ where
     }
 }
 
+/// Recovers the [StorageManager] which was used as
+/// [StorageSink](crate::storage::StorageSink) by a worker thread.
+///
+/// A [WriteBehindStorage] is finished first such that all queued elements are written.
+/// Other storage sinks are not able to load their elements such that an error is returned.
+fn recover_storage_manager<Id, Element>(
+    sink: Box<dyn std::any::Any>,
+) -> Result<StorageManager<Id, Element>, SimulationError>
+where
+    Id: 'static,
+    Element: 'static,
+{
+    let sink = match sink.downcast::<WriteBehindStorage<Id, Element>>() {
+        Ok(write_behind) => return Ok(write_behind.finish()?),
+        Err(sink) => sink,
+    };
+    match sink.downcast::<StorageManager<Id, Element>>() {
+        Ok(storage_manager) => Ok(*storage_manager),
+        Err(_) => Err(RequestError(
+            "Results of the worker threads were not stored by a StorageManager and can only be \
+            obtained via SimulationSupervisor::run_full_sim_into_sinks"
+                .to_owned(),
+        )
+        .into()),
+    }
+}
+
+/// Storage of a single worker thread after the simulation has finished.
+///
+/// Returned by [SimulationSupervisor::run_full_sim_into_sinks].
+pub struct WorkerStorage<Dom, Cel, Vbx> {
+    /// Domain of the simulation
+    pub domain: DomainBox<Dom>,
+    /// Sink to which the worker thread stored its cells
+    pub storage_cells: CellStorageSink<Cel>,
+    /// Sink to which the worker thread stored its voxels
+    pub storage_voxels: VoxelStorageSink<Vbx>,
+    /// Index of the worker thread
+    pub mvc_id: u32,
+}
+
+/// Domain and [StorageManager]s of cells and voxels of a single worker thread
+type RecoveredStorage<Dom, Cel, Vbx> = (
+    DomainBox<Dom>,
+    StorageManager<CellularIdentifier, CellAgentBox<Cel>>,
+    StorageManager<PlainIndex, Vbx>,
+);
+
+impl<Dom, Cel, Vbx> WorkerStorage<Dom, Cel, Vbx>
+where
+    Cel: 'static,
+    Vbx: 'static,
+{
+    /// Finishes the cell and voxel sinks and recovers their [StorageManager]s.
+    ///
+    /// Both sinks are finished even if the first one yields an error.
+    fn recover_storage_managers(self) -> Result<RecoveredStorage<Dom, Cel, Vbx>, SimulationError> {
+        let storage_cells = recover_storage_manager(self.storage_cells.into_any());
+        let storage_voxels = recover_storage_manager(self.storage_voxels.into_any());
+        Ok((self.domain, storage_cells?, storage_voxels?))
+    }
+}
+
 use super::domain_decomposition::PlainIndex;
 
 /// Returned after finishing a full simulation
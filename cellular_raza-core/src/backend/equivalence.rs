@@ -0,0 +1,296 @@
+//! Check that different backends produce the same results for identical models.
+//!
+//! The [cpu_os_threads](super::cpu_os_threads) and [chili](super::chili) backends implement
+//! the same simulation aspects with separate code paths.
+//! To keep them from drifting apart semantically, a small model can be run on both backends
+//! and the resulting trajectories compared via [assert_trajectories_agree].
+//!
+//! Since the backends assign different identifiers to their cells, cells are matched by
+//! proximity of their observables (eg. positions) at every save point.
+//! Save points are matched by their order such that the backends do not need to agree on how
+//! they count iterations.
+//! The [chili](super::chili) backend does not store the initial state of the simulation while
+//! the [cpu_os_threads](super::cpu_os_threads) backend stores it when its first time point is a
+//! save point.
+//! This save point has to be removed from the trajectory of the latter before comparing them.
+//! Both backends use different numerical solvers, thus the tolerance needs to account for the
+//! discretization error of the chosen time increment.
+//!
+//! ```
+//! # use cellular_raza_core::backend::equivalence::*;
+//! # use std::collections::BTreeMap;
+//! // Usually obtained via trajectory_from_chili and trajectory_from_cpu_os_threads
+//! let first: Trajectory<[f64; 2]> = BTreeMap::from([(0, vec![[0.0, 0.0], [5.0, 5.0]])]);
+//! let second: Trajectory<[f64; 2]> = BTreeMap::from([(1, vec![[5.0, 5.01], [0.0, 0.0]])]);
+//! let max_deviation =
+//!     assert_trajectories_agree(&first, &second, |p| p.to_vec(), |p| p.to_vec(), 0.1)?;
+//! assert!((max_deviation - 0.01).abs() < 1e-12);
+//! # Ok::<(), EquivalenceError>(())
+//! ```
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Cells of a simulation at every save point
+pub type Trajectory<C> = BTreeMap<u64, Vec<C>>;
+
+/// Describes how the trajectories of two backends differ
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum EquivalenceError {
+    /// Both backends stored a different number of save points
+    SavePointMismatch {
+        /// Iterations stored by the first backend
+        first: Vec<u64>,
+        /// Iterations stored by the second backend
+        second: Vec<u64>,
+    },
+    /// The number of cells differs at the given save point
+    CellCountMismatch {
+        /// Iterations of both backends at which the cells were stored
+        iterations: [u64; 2],
+        /// Number of cells of both backends
+        n_cells: [usize; 2],
+    },
+    /// The observables of matched cells deviate by more than the tolerance
+    ToleranceExceeded {
+        /// Iterations of both backends at which the cells were stored
+        iterations: [u64; 2],
+        /// Largest deviation between any two matched cells
+        deviation: f64,
+        /// Tolerance which was exceeded
+        tolerance: f64,
+    },
+    /// Loading the results of one of the backends failed
+    LoadError(String),
+}
+
+impl core::fmt::Display for EquivalenceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            EquivalenceError::SavePointMismatch { first, second } => write!(
+                f,
+                "backends stored different save points {first:?} and {second:?}"
+            ),
+            EquivalenceError::CellCountMismatch {
+                iterations,
+                n_cells,
+            } => write!(
+                f,
+                "backends stored {} and {} cells at iterations {} and {}",
+                n_cells[0], n_cells[1], iterations[0], iterations[1]
+            ),
+            EquivalenceError::ToleranceExceeded {
+                iterations,
+                deviation,
+                tolerance,
+            } => write!(
+                f,
+                "deviation {deviation} at iterations {} and {} exceeds tolerance {tolerance}",
+                iterations[0], iterations[1]
+            ),
+            EquivalenceError::LoadError(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for EquivalenceError {}
+
+/// Checks that two trajectories agree within the given tolerance.
+///
+/// The observables of all cells are compared via the euclidean distance.
+/// Every cell of the first trajectory is matched with the closest unmatched cell of the
+/// second trajectory.
+/// Returns the largest deviation between any two matched cells.
+///
+/// This function can be used for custom models of users as well.
+/// The cells of both trajectories can be of different type such that models which need
+/// different implementations for both backends can also be compared.
+pub fn assert_trajectories_agree<C1, C2>(
+    first: &Trajectory<C1>,
+    second: &Trajectory<C2>,
+    observable_first: impl Fn(&C1) -> Vec<f64>,
+    observable_second: impl Fn(&C2) -> Vec<f64>,
+    tolerance: f64,
+) -> Result<f64, EquivalenceError> {
+    if first.len() != second.len() {
+        return Err(EquivalenceError::SavePointMismatch {
+            first: first.keys().copied().collect(),
+            second: second.keys().copied().collect(),
+        });
+    }
+    let mut max_deviation: f64 = 0.0;
+    for ((&iteration1, cells1), (&iteration2, cells2)) in first.iter().zip(second.iter()) {
+        let iterations = [iteration1, iteration2];
+        if cells1.len() != cells2.len() {
+            return Err(EquivalenceError::CellCountMismatch {
+                iterations,
+                n_cells: [cells1.len(), cells2.len()],
+            });
+        }
+        let observables1: Vec<_> = cells1.iter().map(&observable_first).collect();
+        let mut observables2: Vec<_> = cells2.iter().map(&observable_second).collect();
+        for o1 in observables1.iter() {
+            let (index, deviation) = observables2
+                .iter()
+                .map(|o2| distance(o1, o2))
+                .enumerate()
+                .min_by(|(_, d1), (_, d2)| d1.total_cmp(d2))
+                .unwrap_or((0, f64::INFINITY));
+            if deviation > tolerance || deviation.is_nan() {
+                return Err(EquivalenceError::ToleranceExceeded {
+                    iterations,
+                    deviation,
+                    tolerance,
+                });
+            }
+            observables2.swap_remove(index);
+            max_deviation = max_deviation.max(deviation);
+        }
+    }
+    Ok(max_deviation)
+}
+
+/// Euclidean distance which treats observables of different length as infinitely far apart
+fn distance(o1: &[f64], o2: &[f64]) -> f64 {
+    if o1.len() != o2.len() {
+        return f64::INFINITY;
+    }
+    o1.iter()
+        .zip(o2.iter())
+        .map(|(a, b)| (a - b).powi(2))
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// Loads the [Trajectory] of a simulation run with the [chili](super::chili) backend.
+///
+/// Cells are ordered by their [CellIdentifier](super::chili::CellIdentifier).
+#[cfg(feature = "chili")]
+#[cfg_attr(docsrs, doc(cfg(feature = "chili")))]
+pub fn trajectory_from_chili<C, A, S, D>(
+    storage: &super::chili::StorageAccess<(super::chili::CellBox<C>, A), S, D>,
+) -> Result<Trajectory<C>, EquivalenceError>
+where
+    C: Clone + for<'a> Deserialize<'a>,
+    A: Clone + for<'a> Deserialize<'a>,
+{
+    use crate::storage::StorageInterfaceLoad;
+    let all_elements = storage
+        .cells
+        .load_all_elements()
+        .map_err(|e| EquivalenceError::LoadError(format!("{e}")))?;
+    Ok(all_elements
+        .into_iter()
+        .map(|(iteration, cells)| {
+            let mut cells: Vec<_> = cells.into_iter().collect();
+            cells.sort_by_key(|(identifier, _)| *identifier);
+            let cells = cells.into_iter().map(|(_, (cbox, _))| cbox.cell).collect();
+            (iteration, cells)
+        })
+        .collect())
+}
+
+/// Loads the [Trajectory] of a simulation run with the [cpu_os_threads](super::cpu_os_threads)
+/// backend.
+///
+/// The `storage_cells` are obtained from the
+/// [SimulationResult](super::cpu_os_threads::SimulationResult).
+/// Cells are ordered by their [CellularIdentifier](cellular_raza_concepts::CellularIdentifier).
+#[cfg(feature = "cpu_os_threads")]
+#[cfg_attr(docsrs, doc(cfg(feature = "cpu_os_threads")))]
+pub fn trajectory_from_cpu_os_threads<C>(
+    storage_cells: &crate::storage::StorageManager<
+        cellular_raza_concepts::CellularIdentifier,
+        cellular_raza_concepts::CellAgentBox<C>,
+    >,
+) -> Result<Trajectory<C>, EquivalenceError>
+where
+    C: Clone + for<'a> Deserialize<'a>,
+{
+    use crate::storage::StorageInterfaceLoad;
+    let all_elements = storage_cells
+        .load_all_elements()
+        .map_err(|e| EquivalenceError::LoadError(format!("{e}")))?;
+    Ok(all_elements
+        .into_iter()
+        .map(|(iteration, cells)| {
+            let mut cells: Vec<_> = cells.into_iter().collect();
+            cells.sort_by_key(|(identifier, _)| *identifier);
+            let cells = cells.into_iter().map(|(_, cbox)| cbox.cell).collect();
+            (iteration, cells)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod test_equivalence {
+    use super::*;
+
+    fn trajectory(save_points: &[(u64, &[[f64; 2]])]) -> Trajectory<[f64; 2]> {
+        save_points
+            .iter()
+            .map(|(iteration, cells)| (*iteration, cells.to_vec()))
+            .collect()
+    }
+
+    fn compare(
+        first: &Trajectory<[f64; 2]>,
+        second: &Trajectory<[f64; 2]>,
+        tolerance: f64,
+    ) -> Result<f64, EquivalenceError> {
+        assert_trajectories_agree(first, second, |p| p.to_vec(), |p| p.to_vec(), tolerance)
+    }
+
+    #[test]
+    fn match_cells_by_proximity() {
+        let first = trajectory(&[
+            (0, &[[0.0, 0.0], [1.0, 0.0]]),
+            (10, &[[0.0, 0.5], [1.0, 0.5]]),
+        ]);
+        // Identical cells in different order and with different iteration numbers
+        let second = trajectory(&[
+            (1, &[[1.0, 0.0], [0.0, 0.0]]),
+            (11, &[[1.0, 0.52], [0.0, 0.5]]),
+        ]);
+        let deviation = compare(&first, &second, 0.1).unwrap();
+        assert!((deviation - 0.02).abs() < 1e-12);
+        assert_eq!(
+            compare(&first, &second, 0.01),
+            Err(EquivalenceError::ToleranceExceeded {
+                iterations: [10, 11],
+                deviation,
+                tolerance: 0.01
+            })
+        );
+    }
+
+    #[test]
+    fn cells_are_matched_only_once() {
+        let first = trajectory(&[(0, &[[0.0, 0.0], [0.1, 0.0]])]);
+        let second = trajectory(&[(0, &[[0.0, 0.0], [5.0, 0.0]])]);
+        let result = compare(&first, &second, 1.0);
+        assert!(matches!(
+            result,
+            Err(EquivalenceError::ToleranceExceeded { .. })
+        ));
+    }
+
+    #[test]
+    fn structural_mismatches() {
+        let first = trajectory(&[(0, &[[0.0, 0.0]]), (1, &[[0.0, 0.0]])]);
+        let second = trajectory(&[(0, &[[0.0, 0.0]])]);
+        assert!(matches!(
+            compare(&first, &second, 1.0),
+            Err(EquivalenceError::SavePointMismatch { .. })
+        ));
+        let second = trajectory(&[(0, &[[0.0, 0.0]]), (1, &[])]);
+        assert_eq!(
+            compare(&first, &second, 1.0),
+            Err(EquivalenceError::CellCountMismatch {
+                iterations: [1, 1],
+                n_cells: [1, 0]
+            })
+        );
+    }
+}
@@ -0,0 +1,216 @@
+//! 🔌 Stable interface for third-party backends
+//!
+//! Every backend drives the [concepts](cellular_raza_concepts) implemented by cells and domains,
+//! advances the simulation time and stores results.
+//! This module collects the minimal set of traits and types needed to do so.
+//! Alternative backends (eg. running on GPUs or distributed over multiple machines) should only
+//! build on items of this module instead of reaching into internals of existing backends such as
+//! the `MultiVoxelContainer` of the [cpu_os_threads](super::cpu_os_threads) backend or the
+//! `SubDomainBox` of the [chili](super::chili) backend.
+//!
+//! # Stability
+//! All items which are reachable through this module are covered by the semantic versioning of
+//! `cellular_raza_core`.
+//! Their signatures only change with breaking releases.
+//! Internals of the existing backends which are not exported here may change with any release.
+//!
+//! # Cells
+//! Depending on the simulated aspects, a backend calls the following methods of every cell.
+//!
+//! | Aspect | Methods | Notes |
+//! | --- | --- | --- |
+//! | [Mechanics] | [Position::pos], [Velocity::velocity], [Mechanics::calculate_increment], [Mechanics::get_random_contribution], [Position::set_pos], [Velocity::set_velocity] | The force is obtained by summing all interactions. |
+//! | [Interaction] | [Interaction::get_interaction_information], [Interaction::calculate_force_between], [Interaction::reacts_to_neighbors], [Interaction::is_neighbor], [Interaction::react_to_neighbors] | Forces are calculated for every pair of cells which may interact. Neighbors are only counted if [Interaction::reacts_to_neighbors] returns `true`. The optional [Interaction::potential_energy_between] can be summed to obtain the total potential energy. |
+//! | [Cycle] | [Cycle::update_cycle], [Cycle::divide], [Cycle::update_conditional_phased_death] | A returned [CycleEvent] determines if the cell divides or is removed. |
+//! | [Reactions] | [Intracellular::get_intracellular], [Reactions::calculate_intracellular_increment], [Intracellular::set_intracellular] | |
+//! | [ReactionsContact] | [ReactionsContact::get_contact_information], [ReactionsContact::calculate_contact_increment] | Increments are calculated for every pair of cells which may interact. |
+//! | [ReactionsExtra] | [ReactionsExtra::calculate_combined_increment] | Takes the extracellular value at the position of the cell. |
+//!
+//! # Domains
+//! The [Domain] is split into [SubDomain]s by [Domain::decompose].
+//! Backends can choose the number of subdomains freely, eg. one per thread, device or machine.
+//!
+//! | Aspect | Methods | Notes |
+//! | --- | --- | --- |
+//! | [Domain] | [Domain::decompose], [SubDomain::get_all_indices], [SubDomain::get_neighbor_voxel_indices], [SortCells::get_voxel_index_of] | Cells need to be sorted again after they have moved. |
+//! | [Mechanics] | [SubDomainMechanics::apply_boundary] | Called after every update of positions and velocities. |
+//! | DomainForce | [SubDomainForce::calculate_custom_force] | Added to the force acting on every cell. |
+//! | [ReactionsExtra] | [SubDomainReactions::get_extracellular_at_pos], [SubDomainReactions::treat_increments], [SubDomainReactions::update_fluid_dynamics] | |
+//!
+//! # Time
+//! Time is advanced by a [TimeStepper] such as [FixedStepsize](crate::time::FixedStepsize).
+//! The [TimeEvent] of the [NextTimePoint] determines when results need to be stored.
+//!
+//! # Communication
+//! Backends which split the domain into multiple subdomains exchange information between them.
+//! With the `chili` feature, this module provides the [Communicator] and [SyncSubDomains] traits
+//! together with the [ChannelComm] and [BarrierSync] implementations which can be constructed
+//! from the neighbor map of a [DecomposedDomain] via [FromMap].
+//!
+//! # Storage
+//! Results should be stored with a [StorageManager] which is constructed from a
+//! [StorageBuilder].
+//! This allows to use all [StorageOption]s and tools which read stored results.
+//!
+//! # Example
+//! The following backend integrates all agents sequentially with the explicit Euler method.
+//! ```
+//! use cellular_raza_core::backend::interface::*;
+//! use cellular_raza_core::time::FixedStepsize;
+//! use serde::{Deserialize, Serialize};
+//!
+//! struct EulerBackend;
+//!
+//! impl<C, D> SimulationBackend<C, D> for EulerBackend
+//! where
+//!     C: Mechanics<f64, f64, f64> + Position<f64> + Velocity<f64> + Clone + Serialize,
+//!     D: SubDomainMechanics<f64, f64>,
+//! {
+//!     type Settings = (FixedStepsize<f64>, StorageBuilder<true>);
+//!     type Output = StorageManager<usize, C>;
+//!     type Error = Box<dyn std::error::Error>;
+//!
+//!     fn run_simulation<Ci>(
+//!         &mut self,
+//!         agents: Ci,
+//!         domain: D,
+//!         settings: Self::Settings,
+//!     ) -> Result<Self::Output, Self::Error>
+//!     where
+//!         Ci: IntoIterator<Item = C>,
+//!     {
+//!         let (mut time_stepper, storage_builder) = settings;
+//!         let mut agents: Vec<C> = agents.into_iter().collect();
+//!         let identifiers: Vec<usize> = (0..agents.len()).collect();
+//!         let mut storage = StorageManager::open_or_create(storage_builder, 0)?;
+//!         while let Some(next) = time_stepper.advance()? {
+//!             for agent in agents.iter_mut() {
+//!                 let (dpos, dvel) = agent.calculate_increment(0.0)?;
+//!                 let mut pos = agent.pos() + next.increment * dpos;
+//!                 let mut vel = agent.velocity() + next.increment * dvel;
+//!                 domain.apply_boundary(&mut pos, &mut vel)?;
+//!                 agent.set_pos(&pos);
+//!                 agent.set_velocity(&vel);
+//!             }
+//!             if next.event.is_some() {
+//!                 storage.store_batch_elements(
+//!                     next.iteration as u64,
+//!                     identifiers.iter().zip(agents.iter()),
+//!                 )?;
+//!             }
+//!         }
+//!         Ok(storage)
+//!     }
+//! }
+//!
+//! #[derive(Clone, Deserialize, Serialize)]
+//! struct Agent {
+//!     pos: f64,
+//!     vel: f64,
+//! }
+//! # impl Position<f64> for Agent {
+//! #     fn pos(&self) -> f64 {
+//! #         self.pos
+//! #     }
+//! #     fn set_pos(&mut self, pos: &f64) {
+//! #         self.pos = *pos;
+//! #     }
+//! # }
+//! # impl Velocity<f64> for Agent {
+//! #     fn velocity(&self) -> f64 {
+//! #         self.vel
+//! #     }
+//! #     fn set_velocity(&mut self, vel: &f64) {
+//! #         self.vel = *vel;
+//! #     }
+//! # }
+//!
+//! impl Mechanics<f64, f64, f64> for Agent {
+//!     fn get_random_contribution(
+//!         &self,
+//!         _: &mut rand_chacha::ChaCha8Rng,
+//!         _: f64,
+//!     ) -> Result<(f64, f64), RngError> {
+//!         Ok((0.0, 0.0))
+//!     }
+//!
+//!     fn calculate_increment(&self, force: f64) -> Result<(f64, f64), CalcError> {
+//!         Ok((self.vel, force))
+//!     }
+//! }
+//!
+//! // Reflects agents at the upper boundary
+//! struct Interval(f64);
+//!
+//! impl SubDomainMechanics<f64, f64> for Interval {
+//!     fn apply_boundary(&self, pos: &mut f64, vel: &mut f64) -> Result<(), BoundaryError> {
+//!         if *pos > self.0 {
+//!             *pos = 2.0 * self.0 - *pos;
+//!             *vel = -*vel;
+//!         }
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let agents = (0..4).map(|n| Agent { pos: n as f64, vel: 1.0 });
+//! let time = FixedStepsize::from_partial_save_interval(0.0, 0.1, 2.0, 1.0)?;
+//! let storage = StorageBuilder::new().priority([StorageOption::Memory]).init();
+//! let results = EulerBackend.run_simulation(agents, Interval(4.5), (time, storage))?;
+//!
+//! let last_iteration = results.get_all_iterations()?.into_iter().max().unwrap();
+//! let agents = results.load_all_elements_at_iteration(last_iteration)?;
+//! assert!((agents[&0].pos - 2.0).abs() < 1e-6);
+//! assert!((agents[&3].pos - 4.0).abs() < 1e-6);
+//! assert_eq!(agents[&3].vel, -1.0);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+pub use cellular_raza_concepts::{
+    CalcError, Cycle, CycleEvent, DivisionError, Id, Interaction, InteractionForce, Intracellular,
+    Mechanics, NeighbourReactive, Position, Reactions, ReactionsContact, ReactionsExtra, RngError,
+    Velocity,
+};
+
+pub use cellular_raza_concepts::{
+    BoundaryError, DecomposeError, DecomposedDomain, Domain, SortCells, SubDomain, SubDomainForce,
+    SubDomainMechanics, SubDomainReactions,
+};
+
+pub use crate::time::{NextTimePoint, TimeEvent, TimeStepper};
+pub use cellular_raza_concepts::TimeError;
+
+pub use crate::storage::{
+    StorageBuilder, StorageError, StorageInterfaceLoad, StorageInterfaceStore, StorageManager,
+    StorageOption,
+};
+
+#[cfg(feature = "chili")]
+#[cfg_attr(docsrs, doc(cfg(feature = "chili")))]
+pub use super::chili::{
+    BarrierSync, ChannelComm, Communicator, FromMap, SimulationError, SyncSubDomains,
+};
+
+/// Runs a simulation of cells `C` inside a domain `D`.
+///
+/// This trait is the common entry point of backends which are not generated by macros.
+/// The [chili](super::chili) backend generates its types for every simulation and is thus used
+/// via its [run_simulation](super::chili::run_simulation) macro instead.
+/// See the [module-level](self) documentation for an example.
+pub trait SimulationBackend<C, D> {
+    /// Settings such as the time stepper and storage which are needed to run the simulation
+    type Settings;
+    /// Results which are returned after the simulation has finished
+    type Output;
+    /// Error which can occur during the simulation
+    type Error;
+
+    /// Numerically solves the simulation for the given agents and domain
+    fn run_simulation<Ci>(
+        &mut self,
+        agents: Ci,
+        domain: D,
+        settings: Self::Settings,
+    ) -> Result<Self::Output, Self::Error>
+    where
+        Ci: IntoIterator<Item = C>;
+}
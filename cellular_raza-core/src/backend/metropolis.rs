@@ -0,0 +1,558 @@
+//! 🎲 Monte Carlo relaxation of cell configurations with the Metropolis algorithm
+//!
+//! Instead of integrating equations of motion over time, this backend samples configurations
+//! of cells by randomly displacing them.
+//! Every proposed move is accepted with the probability
+//! $$\begin{equation}
+//!     p = \min\left(1, \exp\left(-\frac{\Delta E}{k_BT}\right)\right)
+//! \end{equation}$$
+//! where $\Delta E$ is the change of the total interaction energy reported by
+//! [Interaction::potential_energy_between].
+//! Pairs of cells which do not report any energy contribute zero.
+//! At zero temperature, only moves which do not increase the energy are accepted and the
+//! configuration relaxes into a local minimum.
+//!
+//! This is useful to generate equilibrium initial conditions for other backends or to solve
+//! quasi-static tissue models.
+//! Only the [Interaction] and the boundary of the [SubDomainMechanics] are considered.
+//! Cycles, reactions and velocities of cells are left unchanged.
+//!
+//! # Moves
+//! One sweep consists of as many proposals as there are cells.
+//! For every proposal, a cell is chosen uniformly at random and its new position is obtained
+//! from the user-given proposal function.
+//! The proposal should be symmetric, ie. moving from `a` to `b` is as likely as moving from
+//! `b` to `a`, in order to sample the Boltzmann distribution.
+//! Afterwards the boundary conditions are applied.
+//! Moves for which [SubDomainMechanics::apply_boundary] returns an error are rejected.
+//!
+//! Energies are calculated between all pairs of cells.
+//! Every pair is counted once, in the same manner as by the energy accounting of the 🌶️ chili
+//! backend.
+//! Since no domain decomposition is used, the cost of a sweep grows quadratically with the
+//! number of cells.
+//!
+//! # Example
+//! ```
+//! use cellular_raza_core::backend::interface::*;
+//! use cellular_raza_core::backend::metropolis::*;
+//! use rand::Rng;
+//! use serde::{Deserialize, Serialize};
+//!
+//! // Point-like agents which are connected by springs of length 1
+//! #[derive(Clone, Deserialize, Serialize)]
+//! struct Agent {
+//!     pos: f64,
+//! }
+//! # impl Position<f64> for Agent {
+//! #     fn pos(&self) -> f64 {
+//! #         self.pos
+//! #     }
+//! #     fn set_pos(&mut self, pos: &f64) {
+//! #         self.pos = *pos;
+//! #     }
+//! # }
+//! # impl Velocity<f64> for Agent {
+//! #     fn velocity(&self) -> f64 {
+//! #         0.0
+//! #     }
+//! #     fn set_velocity(&mut self, _: &f64) {}
+//! # }
+//!
+//! impl InteractionForce for Agent {
+//!     type Pos = f64;
+//!     type Vel = f64;
+//!     type Force = f64;
+//!     type Inf = ();
+//!
+//!     fn get_interaction_information(&self) {}
+//!
+//!     fn calculate_force_between(
+//!         &self,
+//!         own_pos: &f64,
+//!         _: &f64,
+//!         ext_pos: &f64,
+//!         _: &f64,
+//!         _: &(),
+//!     ) -> Result<(f64, f64), CalcError> {
+//!         let d = own_pos - ext_pos;
+//!         let force = -(d.abs() - 1.0) * d.signum();
+//!         Ok((force, -force))
+//!     }
+//!
+//!     fn potential_energy_between(
+//!         &self,
+//!         own_pos: &f64,
+//!         ext_pos: &f64,
+//!         _: &(),
+//!     ) -> Result<Option<f64>, CalcError> {
+//!         Ok(Some(0.5 * ((own_pos - ext_pos).abs() - 1.0).powi(2)))
+//!     }
+//! }
+//!
+//! // No boundaries
+//! struct Line;
+//!
+//! impl SubDomainMechanics<f64, f64> for Line {
+//!     fn apply_boundary(&self, _: &mut f64, _: &mut f64) -> Result<(), BoundaryError> {
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let agents = [Agent { pos: 0.0 }, Agent { pos: 3.0 }];
+//! let settings = MetropolisSettings::new(500, 0.0).save_interval(100);
+//! let storage = StorageBuilder::new().priority([StorageOption::Memory]).init();
+//!
+//! let mut backend = Metropolis::new(|pos: &f64, rng: &mut rand_chacha::ChaCha8Rng| {
+//!     pos + rng.gen_range(-0.1..0.1)
+//! });
+//! let results = backend.run_simulation(agents, Line, (settings, storage))?;
+//!
+//! let agents = results.load_all_elements_at_iteration(500)?;
+//! assert!(((agents[&1].pos - agents[&0].pos).abs() - 1.0).abs() < 0.05);
+//! assert!(backend.statistics().last().unwrap().energy < 1e-3);
+//! # Ok::<(), MetropolisError>(())
+//! ```
+
+use core::marker::PhantomData;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use super::interface::{
+    CalcError, Interaction, Position, SimulationBackend, StorageBuilder, StorageError,
+    StorageInterfaceStore, StorageManager, SubDomainMechanics, Velocity,
+};
+
+/// Error which can occur while running the [Metropolis] backend
+#[derive(Debug)]
+pub enum MetropolisError {
+    /// Calculating the interaction energy between cells failed
+    CalcError(CalcError),
+    /// Storing results failed
+    StorageError(StorageError),
+}
+
+impl From<CalcError> for MetropolisError {
+    fn from(err: CalcError) -> Self {
+        MetropolisError::CalcError(err)
+    }
+}
+
+impl From<StorageError> for MetropolisError {
+    fn from(err: StorageError) -> Self {
+        MetropolisError::StorageError(err)
+    }
+}
+
+impl core::fmt::Display for MetropolisError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            MetropolisError::CalcError(message) => write!(f, "{}", message),
+            MetropolisError::StorageError(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for MetropolisError {}
+
+/// Settings of the [Metropolis] backend
+///
+/// ```
+/// # use cellular_raza_core::backend::metropolis::MetropolisSettings;
+/// let settings = MetropolisSettings::new(1_000, 0.1).save_interval(100).seed(3);
+/// assert_eq!(settings.n_sweeps, 1_000);
+/// ```
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct MetropolisSettings {
+    /// Number of sweeps which are performed
+    pub n_sweeps: usize,
+    /// Thermal energy $k_BT$ in the units of the interaction energy
+    pub temperature: f64,
+    /// Results are stored every given number of sweeps.
+    /// The initial and final configurations are always stored.
+    pub save_interval: Option<usize>,
+    /// Seed of the random number generator
+    pub seed: u64,
+}
+
+impl MetropolisSettings {
+    /// Performs the given number of sweeps at the given temperature.
+    ///
+    /// Only the initial and final configurations are stored and the seed is `0`.
+    pub fn new(n_sweeps: usize, temperature: f64) -> Self {
+        Self {
+            n_sweeps,
+            temperature,
+            save_interval: None,
+            seed: 0,
+        }
+    }
+
+    /// Store results every given number of sweeps.
+    pub fn save_interval(self, save_interval: usize) -> Self {
+        Self {
+            save_interval: Some(save_interval),
+            ..self
+        }
+    }
+
+    /// Use the given seed for the random number generator.
+    pub fn seed(self, seed: u64) -> Self {
+        Self { seed, ..self }
+    }
+}
+
+/// Acceptance and energy of one sweep
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SweepStatistics {
+    /// Number of the sweep starting at `1`
+    pub sweep: usize,
+    /// Number of moves which were accepted
+    pub n_accepted: usize,
+    /// Number of moves which were rejected
+    pub n_rejected: usize,
+    /// Total interaction energy after the sweep
+    pub energy: f64,
+}
+
+impl SweepStatistics {
+    /// Fraction of accepted moves
+    pub fn acceptance_rate(&self) -> f64 {
+        let n_total = self.n_accepted + self.n_rejected;
+        if n_total == 0 {
+            return 0.0;
+        }
+        self.n_accepted as f64 / n_total as f64
+    }
+}
+
+/// 🎲 Relaxes cells with Metropolis moves. See the [module-level](self) documentation.
+///
+/// The proposal function calculates a new position from the current position of a cell.
+/// The statistics of every sweep of the last simulation can be obtained via
+/// [Metropolis::statistics].
+pub struct Metropolis<Pr, Pos, Vel, For, Inf> {
+    proposal: Pr,
+    statistics: Vec<SweepStatistics>,
+    phantom: PhantomData<(Pos, Vel, For, Inf)>,
+}
+
+impl<Pr, Pos, Vel, For, Inf> Metropolis<Pr, Pos, Vel, For, Inf>
+where
+    Pr: FnMut(&Pos, &mut ChaCha8Rng) -> Pos,
+{
+    /// Constructs the backend from the given proposal function
+    pub fn new(proposal: Pr) -> Self {
+        Self {
+            proposal,
+            statistics: Vec::new(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Statistics of all sweeps of the last simulation
+    pub fn statistics(&self) -> &[SweepStatistics] {
+        &self.statistics
+    }
+}
+
+/// Energy of the pair of cells `n` and `m` where the cell `moved` is placed at `moved_pos`.
+///
+/// Every pair is evaluated by the cell with the lower index.
+fn pair_energy<C, Pos, Vel, For, Inf>(
+    agents: &[C],
+    positions: &[Pos],
+    infos: &[Inf],
+    n: usize,
+    m: usize,
+    moved: usize,
+    moved_pos: &Pos,
+) -> Result<f64, CalcError>
+where
+    C: Interaction<Pos, Vel, For, Inf>,
+{
+    let (n, m) = if n < m { (n, m) } else { (m, n) };
+    let pos = |k: usize| if k == moved { moved_pos } else { &positions[k] };
+    Ok(agents[n]
+        .potential_energy_between(pos(n), pos(m), &infos[m])?
+        .unwrap_or(0.0))
+}
+
+/// Energy of the given cell at the given position with all other cells
+fn energy_of<C, Pos, Vel, For, Inf>(
+    agents: &[C],
+    positions: &[Pos],
+    infos: &[Inf],
+    n: usize,
+    pos: &Pos,
+) -> Result<f64, CalcError>
+where
+    C: Interaction<Pos, Vel, For, Inf>,
+{
+    let mut energy = 0.0;
+    for m in (0..agents.len()).filter(|m| *m != n) {
+        energy += pair_energy::<C, Pos, Vel, For, Inf>(agents, positions, infos, n, m, n, pos)?;
+    }
+    Ok(energy)
+}
+
+/// Total interaction energy of all pairs of cells
+fn total_energy<C, Pos, Vel, For, Inf>(
+    agents: &[C],
+    positions: &[Pos],
+    infos: &[Inf],
+) -> Result<f64, CalcError>
+where
+    C: Interaction<Pos, Vel, For, Inf>,
+{
+    let mut energy = 0.0;
+    for n in 0..agents.len() {
+        for m in n + 1..agents.len() {
+            energy += agents[n]
+                .potential_energy_between(&positions[n], &positions[m], &infos[m])?
+                .unwrap_or(0.0);
+        }
+    }
+    Ok(energy)
+}
+
+impl<C, D, Pr, Pos, Vel, For, Inf> SimulationBackend<C, D> for Metropolis<Pr, Pos, Vel, For, Inf>
+where
+    C: Interaction<Pos, Vel, For, Inf> + Position<Pos> + Velocity<Vel> + Clone + Serialize,
+    D: SubDomainMechanics<Pos, Vel>,
+    Pr: FnMut(&Pos, &mut ChaCha8Rng) -> Pos,
+{
+    type Settings = (MetropolisSettings, StorageBuilder<true>);
+    type Output = StorageManager<usize, C>;
+    type Error = MetropolisError;
+
+    fn run_simulation<Ci>(
+        &mut self,
+        agents: Ci,
+        domain: D,
+        settings: Self::Settings,
+    ) -> Result<Self::Output, Self::Error>
+    where
+        Ci: IntoIterator<Item = C>,
+    {
+        let (settings, storage_builder) = settings;
+        let mut agents: Vec<C> = agents.into_iter().collect();
+        let identifiers: Vec<usize> = (0..agents.len()).collect();
+        let mut positions: Vec<Pos> = agents.iter().map(|agent| agent.pos()).collect();
+        let infos: Vec<Inf> = agents
+            .iter()
+            .map(|agent| agent.get_interaction_information())
+            .collect();
+        let mut rng = ChaCha8Rng::seed_from_u64(settings.seed);
+        let mut storage = StorageManager::open_or_create(storage_builder, 0)?;
+        self.statistics.clear();
+
+        let mut energy = total_energy::<C, Pos, Vel, For, Inf>(&agents, &positions, &infos)?;
+        storage.store_batch_elements(0, identifiers.iter().zip(agents.iter()))?;
+
+        for sweep in 1..=settings.n_sweeps {
+            let mut n_accepted = 0;
+            let mut n_rejected = 0;
+            for _ in 0..agents.len() {
+                let n = rng.gen_range(0..agents.len());
+                let mut new_pos = (self.proposal)(&positions[n], &mut rng);
+                let mut vel = agents[n].velocity();
+                if domain.apply_boundary(&mut new_pos, &mut vel).is_err() {
+                    n_rejected += 1;
+                    continue;
+                }
+                let delta_energy =
+                    energy_of::<C, Pos, Vel, For, Inf>(&agents, &positions, &infos, n, &new_pos)?
+                        - energy_of::<C, Pos, Vel, For, Inf>(
+                            &agents,
+                            &positions,
+                            &infos,
+                            n,
+                            &positions[n],
+                        )?;
+                let accept = delta_energy <= 0.0
+                    || (settings.temperature > 0.0
+                        && rng.gen::<f64>() < (-delta_energy / settings.temperature).exp());
+                if accept {
+                    agents[n].set_pos(&new_pos);
+                    positions[n] = new_pos;
+                    energy += delta_energy;
+                    n_accepted += 1;
+                } else {
+                    n_rejected += 1;
+                }
+            }
+            self.statistics.push(SweepStatistics {
+                sweep,
+                n_accepted,
+                n_rejected,
+                energy,
+            });
+
+            let is_save_point = settings
+                .save_interval
+                .is_some_and(|interval| interval > 0 && sweep % interval == 0);
+            if is_save_point || sweep == settings.n_sweeps {
+                storage
+                    .store_batch_elements(sweep as u64, identifiers.iter().zip(agents.iter()))?;
+            }
+        }
+        Ok(storage)
+    }
+}
+
+#[cfg(test)]
+mod test_metropolis {
+    use super::*;
+    use crate::backend::interface::{
+        BoundaryError, InteractionForce, StorageInterfaceLoad, StorageOption,
+    };
+
+    #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+    struct Agent {
+        pos: f64,
+    }
+
+    impl Position<f64> for Agent {
+        fn pos(&self) -> f64 {
+            self.pos
+        }
+
+        fn set_pos(&mut self, pos: &f64) {
+            self.pos = *pos;
+        }
+    }
+
+    impl Velocity<f64> for Agent {
+        fn velocity(&self) -> f64 {
+            0.0
+        }
+
+        fn set_velocity(&mut self, _: &f64) {}
+    }
+
+    // Harmonic confinement which does not depend on the partner
+    impl InteractionForce for Agent {
+        type Pos = f64;
+        type Vel = f64;
+        type Force = f64;
+        type Inf = ();
+
+        fn get_interaction_information(&self) {}
+
+        fn calculate_force_between(
+            &self,
+            _: &f64,
+            _: &f64,
+            _: &f64,
+            _: &f64,
+            _: &(),
+        ) -> Result<(f64, f64), CalcError> {
+            Ok((0.0, 0.0))
+        }
+
+        fn potential_energy_between(
+            &self,
+            own_pos: &f64,
+            ext_pos: &f64,
+            _: &(),
+        ) -> Result<Option<f64>, CalcError> {
+            Ok(Some(0.5 * (own_pos - ext_pos).powi(2)))
+        }
+    }
+
+    struct Interval(f64, f64);
+
+    impl SubDomainMechanics<f64, f64> for Interval {
+        fn apply_boundary(&self, pos: &mut f64, _: &mut f64) -> Result<(), BoundaryError> {
+            if *pos < self.0 || *pos > self.1 {
+                return Err(BoundaryError("outside of interval".to_owned()));
+            }
+            Ok(())
+        }
+    }
+
+    fn run(
+        agents: Vec<Agent>,
+        domain: Interval,
+        settings: MetropolisSettings,
+    ) -> (StorageManager<usize, Agent>, Vec<SweepStatistics>) {
+        let storage = StorageBuilder::new()
+            .priority([StorageOption::Memory])
+            .init();
+        let mut backend =
+            Metropolis::new(|pos: &f64, rng: &mut ChaCha8Rng| pos + rng.gen_range(-0.2..0.2));
+        let results = backend
+            .run_simulation(agents, domain, (settings, storage))
+            .unwrap();
+        (results, backend.statistics().to_vec())
+    }
+
+    #[test]
+    fn relax_at_zero_temperature() {
+        let agents = (0..5).map(|n| Agent { pos: n as f64 }).collect();
+        let (_, statistics) = run(
+            agents,
+            Interval(-10.0, 10.0),
+            MetropolisSettings::new(400, 0.0),
+        );
+        assert_eq!(statistics.len(), 400);
+        // Energy never increases
+        for window in statistics.windows(2) {
+            assert!(window[1].energy <= window[0].energy + 1e-12);
+        }
+        assert!(statistics.last().unwrap().energy < 1e-2);
+    }
+
+    #[test]
+    fn energy_is_tracked_consistently() {
+        let agents: Vec<_> = (0..4)
+            .map(|n| Agent {
+                pos: 0.5 * n as f64,
+            })
+            .collect();
+        let (results, statistics) = run(
+            agents,
+            Interval(-10.0, 10.0),
+            MetropolisSettings::new(50, 1.0).seed(7),
+        );
+        let agents: Vec<_> = results
+            .load_all_elements_at_iteration(50)
+            .unwrap()
+            .into_values()
+            .collect();
+        let positions: Vec<_> = agents.iter().map(|a| a.pos).collect();
+        let infos = vec![(); agents.len()];
+        let energy = total_energy::<_, f64, f64, f64, ()>(&agents, &positions, &infos).unwrap();
+        assert!((energy - statistics.last().unwrap().energy).abs() < 1e-9);
+        assert!(statistics
+            .iter()
+            .any(|s| s.n_rejected > 0 || s.n_accepted > 0));
+    }
+
+    #[test]
+    fn reject_moves_outside_of_domain() {
+        let agents = vec![Agent { pos: 0.0 }, Agent { pos: 0.0 }];
+        let (results, statistics) = run(
+            agents,
+            Interval(0.0, 0.0),
+            MetropolisSettings::new(20, 1.0).save_interval(10),
+        );
+        assert!(statistics.iter().all(|s| s.n_accepted == 0));
+        assert!(statistics.iter().all(|s| s.acceptance_rate() == 0.0));
+        let mut iterations = results.get_all_iterations().unwrap();
+        iterations.sort();
+        assert_eq!(iterations, vec![0, 10, 20]);
+    }
+
+    #[test]
+    fn deterministic_with_seed() {
+        let agents: Vec<_> = (0..3).map(|n| Agent { pos: n as f64 }).collect();
+        let settings = MetropolisSettings::new(30, 0.5).seed(42);
+        let (_, statistics1) = run(agents.clone(), Interval(-5.0, 5.0), settings);
+        let (_, statistics2) = run(agents, Interval(-5.0, 5.0), settings);
+        assert_eq!(statistics1, statistics2);
+    }
+}
@@ -8,23 +8,34 @@
 //! We aim to provide one general-purpose backend able to solve any given simulation that adheres
 //! to the [cellular_raza_concepts] with the 🌶️ [chili] backend.
 //!
-//! | Aspect | 🐧 [cpu_os_threads] | 🌶️ [chili] | 🐯 [cara] | 🐺 [elli] |
-//! | --- |:---:|:---:|:---:|:---:|
-//! | [Cycle](cellular_raza_concepts::Cycle) | ✅¹ | ✅ |❌ |❌ |
-//! | [Mechanics](cellular_raza_concepts::Mechanics) | ✅¹ | ✅ |❌ |❌ |
-//! | [Interaction](cellular_raza_concepts::Interaction) | ✅ | ✅ |❌ |❌ |
-//! | [Reactions](cellular_raza_concepts::Reactions) | ❌ | ✅ |❌ |❌ |
-//! | [ReactionsContact](cellular_raza_concepts::ReactionsContact) | ❌ | ✅ |❌ |❌ |
-//! | [ReactionsExtra](cellular_raza_concepts::ReactionsExtra) | ❌ | ✅ |❌ |❌ |
-//! | [Domain](cellular_raza_concepts::Domain) | ❌ | ✅ |❌ |❌ |
-//! | [DomainForce](cellular_raza_concepts::SubDomainForce) | ❌ | ✅ |❌ |❌ |
-//! | [Controller](cellular_raza_concepts::domain_old::Controller) | ✅ | ❌ |❌ |❌ |
+//! | Aspect | 🐧 [cpu_os_threads] | 🌶️ [chili] | 🐯 [cara] | 🐺 [elli] | 🎲 [metropolis] | 🧩 [cpm] |
+//! | --- |:---:|:---:|:---:|:---:|:---:|:---:|
+//! | [Cycle](cellular_raza_concepts::Cycle) | ✅¹ | ✅ |❌ |❌ |❌ |✅ |
+//! | [Mechanics](cellular_raza_concepts::Mechanics) | ✅¹ | ✅ |❌ |❌ |❌ |❌ |
+//! | [Interaction](cellular_raza_concepts::Interaction) | ✅ | ✅ |❌ |❌ |✅² |❌ |
+//! | [Reactions](cellular_raza_concepts::Reactions) | ❌ | ✅ |❌ |❌ |❌ |✅ |
+//! | [ReactionsContact](cellular_raza_concepts::ReactionsContact) | ❌ | ✅ |❌ |❌ |❌ |❌ |
+//! | [ReactionsExtra](cellular_raza_concepts::ReactionsExtra) | ❌ | ✅ |❌ |❌ |❌ |❌ |
+//! | [Domain](cellular_raza_concepts::Domain) | ❌ | ✅ |❌ |❌ |❌ |❌ |
+//! | [DomainForce](cellular_raza_concepts::SubDomainForce) | ❌ | ✅ |❌ |❌ |❌ |❌ |
+//! | [Controller](cellular_raza_concepts::domain_old::Controller) | ✅ | ❌ |❌ |❌ |❌ |❌ |
 //! | Old Aspects |
-//! | [ReactionsOld](cellular_raza_concepts::reactions_old::CellularReactions) | ✅ | ❌ |❌ |❌ |
-//! | [DomainOld](cellular_raza_concepts::domain_old::Domain) | ✅ | ❌ |❌ |❌ |
-//! | [Plotting](cellular_raza_concepts::PlotSelf) | ✅ | ❌ |❌ |❌ |
+//! | [ReactionsOld](cellular_raza_concepts::reactions_old::CellularReactions) | ✅ | ❌ |❌ |❌ |❌ |❌ |
+//! | [DomainOld](cellular_raza_concepts::domain_old::Domain) | ✅ | ❌ |❌ |❌ |❌ |❌ |
+//! | [Plotting](cellular_raza_concepts::PlotSelf) | ✅ | ❌ |❌ |❌ |❌ |❌ |
 //!
 //! ¹Only supports `Float=f64`.
+//! ²Only uses the energy reported by
+//! [Interaction::potential_energy_between](cellular_raza_concepts::Interaction::potential_energy_between)
+//! together with the boundary of the
+//! [SubDomainMechanics](cellular_raza_concepts::SubDomainMechanics).
+//!
+//! Results of the [cpu_os_threads] and [chili] backends can be compared with the harness in
+//! [equivalence].
+//!
+//! ## Writing a Backend
+//! Third-party backends should only depend on the stable [interface] which documents which
+//! methods of cells, domains, communicators and storage need to be called.
 
 /// 🐧 Use multiple os-threads and cpu-only resources
 ///
@@ -50,6 +61,18 @@ pub mod chili;
 #[cfg_attr(docsrs, doc(cfg(feature = "cara")))]
 pub mod cara;
 
+pub mod equivalence;
+
+pub mod interface;
+
 #[cfg(feature = "elli")]
 #[cfg_attr(docsrs, doc(cfg(feature = "elli")))]
 pub mod elli;
+
+#[cfg(feature = "metropolis")]
+#[cfg_attr(docsrs, doc(cfg(feature = "metropolis")))]
+pub mod metropolis;
+
+#[cfg(feature = "cpm")]
+#[cfg_attr(docsrs, doc(cfg(feature = "cpm")))]
+pub mod cpm;
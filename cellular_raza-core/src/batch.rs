@@ -0,0 +1,370 @@
+//! Run campaigns of simulations from a directory of recipes.
+//!
+//! Simulations in `cellular_raza` are compiled programs.
+//! A [Recipe] thus describes how to execute such a program, for example a compiled example
+//! together with the arguments which select its parameters.
+//! The [JobQueue] discovers all recipes of a directory, executes them with a bounded number of
+//! workers and records the [JobStatus] of every job next to the recipes.
+//! When the queue is interrupted, running it again only executes jobs which have not finished.
+//! The `cr-batch` binary exposes this functionality on the command line.
+//!
+//! ```text
+//! campaign/
+//! ├── diffusion_low.json
+//! ├── diffusion_high.ron
+//! └── .batch/
+//!     ├── diffusion_low.status.json
+//!     ├── diffusion_low.stdout
+//!     ├── diffusion_low.stderr
+//!     └── ...
+//! ```
+
+use std::collections::{BTreeMap, VecDeque};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::storage::StorageError;
+
+/// Name of the folder inside the recipe directory which contains statuses and logs
+pub const BATCH_FOLDER: &str = ".batch";
+
+/// Describes how to execute a single simulation.
+///
+/// Recipes are stored as `.json` or `.ron` files.
+/// ```
+/// # use cellular_raza_core::batch::Recipe;
+/// let recipe: Recipe = serde_json::from_str(
+///     r#"{
+///         "command": "target/release/examples/diffusion",
+///         "args": ["--diffusion-constant", "0.5"],
+///         "env": {"RAYON_NUM_THREADS": "2"}
+///     }"#,
+/// )?;
+/// assert_eq!(recipe.args.len(), 2);
+/// assert_eq!(recipe.working_dir, None);
+/// # Ok::<(), serde_json::Error>(())
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Recipe {
+    /// Program which runs the simulation
+    pub command: PathBuf,
+    /// Arguments which are passed to the program
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Additional environment variables
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Directory in which the program is executed.
+    /// Relative paths are resolved with respect to the recipe directory which is also used if
+    /// no directory is given.
+    #[serde(default)]
+    pub working_dir: Option<PathBuf>,
+}
+
+impl Recipe {
+    /// Reads a recipe from a `.json` or `.ron` file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, StorageError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("ron") => Ok(ron::from_str(&contents)?),
+            _ => Ok(serde_json::from_str(&contents)?),
+        }
+    }
+}
+
+/// Current state of a job in the [JobQueue]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum JobStatus {
+    /// The job has not been started yet.
+    Pending,
+    /// The job was started at the given time in seconds since the unix epoch.
+    /// Jobs which are still running when the queue is started again were interrupted.
+    Running {
+        /// Start of the job
+        started: u64,
+    },
+    /// The program exited successfully.
+    Finished {
+        /// Start of the job
+        started: u64,
+        /// End of the job
+        finished: u64,
+    },
+    /// The program could not be started or exited with an error.
+    Failed {
+        /// Start of the job
+        started: u64,
+        /// End of the job
+        finished: u64,
+        /// Exit code or error message
+        reason: String,
+    },
+}
+
+/// Single recipe of the [JobQueue] together with its status
+#[derive(Clone, Debug, PartialEq)]
+pub struct Job {
+    /// Name of the job given by the file name of the recipe without its extension
+    pub name: String,
+    /// Path of the recipe file
+    pub recipe_path: PathBuf,
+    /// Status which was recorded when the queue was opened
+    pub status: JobStatus,
+}
+
+/// Number of jobs per outcome after running the [JobQueue]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct BatchSummary {
+    /// Jobs which finished successfully in this run
+    pub finished: Vec<String>,
+    /// Jobs which failed in this run
+    pub failed: Vec<String>,
+    /// Jobs which were skipped since they had finished in a previous run
+    pub skipped: Vec<String>,
+}
+
+/// Minimal local job scheduler for campaigns of simulations.
+///
+/// See the [module-level](self) documentation.
+#[derive(Clone, Debug)]
+pub struct JobQueue {
+    /// Directory which contains the recipes
+    pub directory: PathBuf,
+    /// All discovered jobs ordered by their name
+    pub jobs: Vec<Job>,
+}
+
+/// Seconds since the unix epoch
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+impl JobQueue {
+    /// Discovers all `.json` and `.ron` recipes in the directory and reads their statuses.
+    pub fn open(directory: impl AsRef<Path>) -> Result<Self, StorageError> {
+        let directory = directory.as_ref().to_path_buf();
+        std::fs::create_dir_all(directory.join(BATCH_FOLDER))?;
+        let mut jobs = Vec::new();
+        for entry in std::fs::read_dir(&directory)? {
+            let recipe_path = entry?.path();
+            let is_recipe = recipe_path.is_file()
+                && matches!(
+                    recipe_path.extension().and_then(|e| e.to_str()),
+                    Some("json" | "ron")
+                );
+            let name = recipe_path.file_stem().and_then(|stem| stem.to_str());
+            if let (true, Some(name)) = (is_recipe, name) {
+                jobs.push(Job {
+                    name: name.to_owned(),
+                    recipe_path: recipe_path.clone(),
+                    status: JobStatus::Pending,
+                });
+            }
+        }
+        jobs.sort_by(|job1, job2| job1.name.cmp(&job2.name));
+        let mut queue = Self { directory, jobs };
+        for n in 0..queue.jobs.len() {
+            let path = queue.status_path(&queue.jobs[n].name);
+            if path.is_file() {
+                queue.jobs[n].status = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+            }
+        }
+        Ok(queue)
+    }
+
+    /// Path of the file which stores the status of the given job
+    pub fn status_path(&self, name: &str) -> PathBuf {
+        self.directory
+            .join(BATCH_FOLDER)
+            .join(format!("{name}.status.json"))
+    }
+
+    /// Writes the status such that it is never partially written when interrupted.
+    fn write_status(&self, name: &str, status: &JobStatus) -> Result<(), StorageError> {
+        let path = self.status_path(name);
+        let temporary = path.with_extension("json.tmp");
+        std::fs::write(&temporary, serde_json::to_string_pretty(status)?)?;
+        std::fs::rename(temporary, path)?;
+        Ok(())
+    }
+
+    /// Jobs which still need to be executed.
+    ///
+    /// This includes pending jobs and jobs which were interrupted while running.
+    /// Failed jobs are only included if `retry_failed` is set.
+    pub fn unfinished_jobs(&self, retry_failed: bool) -> Vec<&Job> {
+        self.jobs
+            .iter()
+            .filter(|job| match job.status {
+                JobStatus::Pending | JobStatus::Running { .. } => true,
+                JobStatus::Failed { .. } => retry_failed,
+                JobStatus::Finished { .. } => false,
+            })
+            .collect()
+    }
+
+    /// Executes a single job and returns its final status.
+    ///
+    /// The standard output and error of the program are written to the batch folder.
+    fn execute(&self, job: &Job) -> Result<JobStatus, StorageError> {
+        let started = now();
+        self.write_status(&job.name, &JobStatus::Running { started })?;
+        let batch_folder = self.directory.join(BATCH_FOLDER);
+        let result = Recipe::from_file(&job.recipe_path).and_then(|recipe| {
+            let working_dir = match &recipe.working_dir {
+                Some(dir) => self.directory.join(dir),
+                None => self.directory.clone(),
+            };
+            let stdout = std::fs::File::create(batch_folder.join(format!("{}.stdout", job.name)))?;
+            let stderr = std::fs::File::create(batch_folder.join(format!("{}.stderr", job.name)))?;
+            Ok(std::process::Command::new(&recipe.command)
+                .args(&recipe.args)
+                .envs(&recipe.env)
+                .current_dir(working_dir)
+                .stdout(stdout)
+                .stderr(stderr)
+                .status()?)
+        });
+        let finished = now();
+        let status = match result {
+            Ok(exit_status) if exit_status.success() => JobStatus::Finished { started, finished },
+            Ok(exit_status) => JobStatus::Failed {
+                started,
+                finished,
+                reason: format!("{exit_status}"),
+            },
+            Err(error) => JobStatus::Failed {
+                started,
+                finished,
+                reason: format!("{error}"),
+            },
+        };
+        self.write_status(&job.name, &status)?;
+        Ok(status)
+    }
+
+    /// Executes all unfinished jobs with at most `n_workers` jobs running at the same time.
+    ///
+    /// Jobs are started in the order of their names.
+    /// Failed jobs do not stop the queue but are reported in the [BatchSummary].
+    /// Errors are only returned if statuses could not be written.
+    pub fn run(
+        &mut self,
+        n_workers: NonZeroUsize,
+        retry_failed: bool,
+    ) -> Result<BatchSummary, StorageError> {
+        let unfinished: VecDeque<Job> = self
+            .unfinished_jobs(retry_failed)
+            .into_iter()
+            .cloned()
+            .collect();
+        let mut summary = BatchSummary {
+            skipped: self
+                .jobs
+                .iter()
+                .filter(|job| !unfinished.iter().any(|other| other.name == job.name))
+                .map(|job| job.name.clone())
+                .collect(),
+            ..Default::default()
+        };
+        let n_workers = n_workers.get().min(unfinished.len());
+        let unfinished = Mutex::new(unfinished);
+        let results = Mutex::new(Vec::new());
+        let queue = &*self;
+        std::thread::scope(|scope| {
+            for _ in 0..n_workers {
+                scope.spawn(|| loop {
+                    let job = match unfinished.lock() {
+                        Ok(mut unfinished) => unfinished.pop_front(),
+                        Err(_) => None,
+                    };
+                    let Some(job) = job else { break };
+                    let status = queue.execute(&job);
+                    if let Ok(mut results) = results.lock() {
+                        results.push((job.name, status));
+                    }
+                });
+            }
+        });
+        let results = results
+            .into_inner()
+            .map_err(|e| StorageError::InitError(e.to_string()))?;
+        let mut results: BTreeMap<String, JobStatus> = results
+            .into_iter()
+            .map(|(name, status)| status.map(|status| (name, status)))
+            .collect::<Result<_, _>>()?;
+        for job in self.jobs.iter_mut() {
+            if let Some(status) = results.remove(&job.name) {
+                match status {
+                    JobStatus::Finished { .. } => summary.finished.push(job.name.clone()),
+                    _ => summary.failed.push(job.name.clone()),
+                }
+                job.status = status;
+            }
+        }
+        Ok(summary)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod test_batch {
+    use super::*;
+
+    fn write_recipe(directory: &Path, name: &str, script: &str) {
+        let recipe = Recipe {
+            command: "sh".into(),
+            args: vec!["-c".into(), script.into()],
+            env: BTreeMap::from([("JOB".into(), name.into())]),
+            working_dir: None,
+        };
+        std::fs::write(
+            directory.join(format!("{name}.json")),
+            serde_json::to_string(&recipe).unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn run_and_resume() -> Result<(), StorageError> {
+        let directory = tempfile::tempdir()?;
+        for n in 0..5 {
+            write_recipe(directory.path(), &format!("job{n}"), "echo $JOB > $JOB.out");
+        }
+        write_recipe(directory.path(), "broken", "exit 3");
+        std::fs::write(directory.path().join("notes.txt"), "not a recipe")?;
+
+        let mut queue = JobQueue::open(directory.path())?;
+        assert_eq!(queue.jobs.len(), 6);
+        let summary = queue.run(2.try_into().unwrap(), false)?;
+        assert_eq!(summary.finished.len(), 5);
+        assert_eq!(summary.failed, vec!["broken".to_owned()]);
+        assert!(summary.skipped.is_empty());
+        let output = std::fs::read_to_string(directory.path().join("job3.out"))?;
+        assert_eq!(output.trim(), "job3");
+
+        // Simulate an interruption while job1 was running
+        let queue = JobQueue::open(directory.path())?;
+        queue.write_status("job1", &JobStatus::Running { started: 0 })?;
+        let mut queue = JobQueue::open(directory.path())?;
+        let summary = queue.run(4.try_into().unwrap(), false)?;
+        assert_eq!(summary.finished, vec!["job1".to_owned()]);
+        assert_eq!(summary.skipped.len(), 5);
+
+        // Failed jobs are only executed again on request
+        let summary = queue.run(4.try_into().unwrap(), true)?;
+        assert_eq!(summary.failed, vec!["broken".to_owned()]);
+        assert!(summary.finished.is_empty());
+        match &queue.jobs[0].status {
+            JobStatus::Failed { reason, .. } => assert!(reason.contains('3')),
+            status => panic!("unexpected status {status:?}"),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+//! Runs a campaign of simulations described by a directory of recipes.
+//!
+//! ```text
+//! cr-batch <DIR> [--workers <N>] [--retry-failed] [--status]
+//! ```
+//!
+//! Every `.json` or `.ron` file in the directory is read as a
+//! [Recipe](cellular_raza_core::batch::Recipe).
+//! At most `--workers` jobs (default: number of available cores) are executed at the same time.
+//! Statuses and logs are written to the `.batch` folder inside the directory.
+//! Running the command again resumes the campaign by only executing jobs which did not finish.
+//! Failed jobs are executed again with `--retry-failed`.
+//! With `--status`, the statuses are printed without executing any jobs.
+//!
+//! Exits with status `1` if any job failed.
+
+use std::num::NonZeroUsize;
+
+use cellular_raza_core::batch::{JobQueue, JobStatus, BATCH_FOLDER};
+use cellular_raza_core::storage::StorageError;
+
+const USAGE: &str = "usage: cr-batch <DIR> [--workers <N>] [--retry-failed] [--status]";
+
+struct Arguments {
+    directory: Option<std::path::PathBuf>,
+    workers: NonZeroUsize,
+    retry_failed: bool,
+    status: bool,
+}
+
+fn parse_arguments() -> Result<Arguments, String> {
+    let mut arguments = Arguments {
+        directory: None,
+        workers: std::thread::available_parallelism().unwrap_or(NonZeroUsize::MIN),
+        retry_failed: false,
+        status: false,
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--workers" => {
+                arguments.workers = args
+                    .next()
+                    .ok_or(format!("missing value for {arg}"))?
+                    .parse()
+                    .map_err(|e| format!("invalid number of workers: {e}"))?
+            }
+            "--retry-failed" => arguments.retry_failed = true,
+            "--status" => arguments.status = true,
+            "-h" | "--help" => return Err(USAGE.to_owned()),
+            _ if arguments.directory.is_none() => arguments.directory = Some(arg.into()),
+            _ => return Err(USAGE.to_owned()),
+        }
+    }
+    if arguments.directory.is_none() {
+        return Err(USAGE.to_owned());
+    }
+    Ok(arguments)
+}
+
+fn main() -> Result<(), StorageError> {
+    let arguments = match parse_arguments() {
+        Ok(arguments) => arguments,
+        Err(message) => {
+            eprintln!("{message}");
+            std::process::exit(2);
+        }
+    };
+    let directory = arguments.directory.unwrap_or_default();
+    let mut queue = JobQueue::open(&directory)?;
+    if arguments.status {
+        for job in queue.jobs.iter() {
+            let status = match &job.status {
+                JobStatus::Pending => "pending".to_owned(),
+                JobStatus::Running { .. } => "running or interrupted".to_owned(),
+                JobStatus::Finished { started, finished } => {
+                    format!("finished after {}s", finished.saturating_sub(*started))
+                }
+                JobStatus::Failed { reason, .. } => format!("failed: {reason}"),
+            };
+            println!("{:<32} {status}", job.name);
+        }
+        return Ok(());
+    }
+    let summary = queue.run(arguments.workers, arguments.retry_failed)?;
+    println!(
+        "finished: {}, failed: {}, skipped: {}",
+        summary.finished.len(),
+        summary.failed.len(),
+        summary.skipped.len()
+    );
+    for name in summary.failed.iter() {
+        let log = directory.join(BATCH_FOLDER).join(format!("{name}.stderr"));
+        eprintln!("job {name} failed, see {}", log.display());
+    }
+    if !summary.failed.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
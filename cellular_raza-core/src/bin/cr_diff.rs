@@ -0,0 +1,106 @@
+//! Compares the stored results of two simulation runs at every save point.
+//!
+//! ```text
+//! cr-diff <FIRST> <SECOND> [--position <POINTER>] [--field <POINTER>] [--format json|ron]
+//!     [--tolerance <TOL>]
+//! ```
+//!
+//! Both paths point to the folders returned by
+//! [StorageAccess::get_path](cellular_raza_core::backend::chili::StorageAccess::get_path).
+//! Since the exact types of cells and subdomains are not known, results need to be stored in a
+//! self-describing format.
+//! Positions and fields are selected via
+//! [JSON pointers](https://datatracker.ietf.org/doc/html/rfc6901) into the serialized cells and
+//! subdomains such as `--position /mechanics/pos`.
+//! All numbers below the selected value are used.
+//! Without `--position`, only the number of cells and their identifiers are compared.
+//!
+//! Exits with status `1` if the runs differ.
+
+use cellular_raza_core::backend::chili::{
+    compare_runs, json_numbers, CellBox, SimulationError, StorageAccess,
+};
+use cellular_raza_core::storage::StorageOption;
+use serde::de::IgnoredAny;
+use serde_json::Value;
+
+const USAGE: &str = "usage: cr-diff <FIRST> <SECOND> [--position <POINTER>] [--field <POINTER>] \
+    [--format json|ron] [--tolerance <TOL>]";
+
+struct Arguments {
+    paths: Vec<std::path::PathBuf>,
+    position: Option<String>,
+    field: Option<String>,
+    format: StorageOption,
+    tolerance: f64,
+}
+
+fn parse_arguments() -> Result<Arguments, String> {
+    let mut arguments = Arguments {
+        paths: Vec::new(),
+        position: None,
+        field: None,
+        format: StorageOption::SerdeJson,
+        tolerance: 0.0,
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {arg}"));
+        match arg.as_str() {
+            "--position" => arguments.position = Some(value()?),
+            "--field" => arguments.field = Some(value()?),
+            "--format" => {
+                arguments.format = match value()?.as_str() {
+                    "json" => StorageOption::SerdeJson,
+                    "ron" => StorageOption::Ron,
+                    format => return Err(format!("unsupported format {format}")),
+                }
+            }
+            "--tolerance" => {
+                arguments.tolerance = value()?
+                    .parse()
+                    .map_err(|e| format!("invalid tolerance: {e}"))?
+            }
+            "-h" | "--help" => return Err(USAGE.to_owned()),
+            _ => arguments.paths.push(arg.into()),
+        }
+    }
+    if arguments.paths.len() != 2 {
+        return Err(USAGE.to_owned());
+    }
+    Ok(arguments)
+}
+
+/// Numbers below the pointer or no numbers if the pointer is not specified
+fn select(value: &Value, pointer: &Option<String>) -> Vec<f64> {
+    match pointer {
+        Some(pointer) => value.pointer(pointer).map(json_numbers).unwrap_or_default(),
+        None => Vec::new(),
+    }
+}
+
+fn main() -> Result<(), SimulationError> {
+    let arguments = match parse_arguments() {
+        Ok(arguments) => arguments,
+        Err(message) => {
+            eprintln!("{message}");
+            std::process::exit(2);
+        }
+    };
+    let open = |path: &std::path::PathBuf| {
+        StorageAccess::<(CellBox<Value>, IgnoredAny), Value>::open(path, [arguments.format.clone()])
+    };
+    let first = open(&arguments.paths[0])?;
+    let second = open(&arguments.paths[1])?;
+    let comparison = compare_runs(
+        &first,
+        &second,
+        |cell| select(cell, &arguments.position),
+        |subdomain| select(subdomain, &arguments.field),
+    )?;
+    print!("{comparison}");
+    if !comparison.agrees_within(arguments.tolerance, arguments.tolerance) {
+        std::process::exit(1);
+    }
+    Ok(())
+}
@@ -0,0 +1,464 @@
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use super::{run_batch, squared_error, standard_normal, CalibrationError};
+
+use core::fmt::Display;
+
+/// Prior distribution of a single parameter
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum Prior {
+    /// Uniform distribution between lower and upper bound
+    Uniform {
+        /// Lower bound
+        low: f64,
+        /// Upper bound
+        high: f64,
+    },
+    /// Uniform distribution of the logarithm between lower and upper bound
+    ///
+    /// Both bounds need to be positive.
+    LogUniform {
+        /// Lower bound
+        low: f64,
+        /// Upper bound
+        high: f64,
+    },
+    /// Normal distribution
+    Normal {
+        /// Mean
+        mean: f64,
+        /// Standard deviation
+        std: f64,
+    },
+}
+
+impl Prior {
+    /// Draws a sample from the distribution.
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> f64 {
+        match self {
+            Prior::Uniform { low, high } => low + (high - low) * rng.gen::<f64>(),
+            Prior::LogUniform { low, high } => {
+                (low.ln() + (high.ln() - low.ln()) * rng.gen::<f64>()).exp()
+            }
+            Prior::Normal { mean, std } => mean + std * standard_normal(rng),
+        }
+    }
+
+    /// Probability density at the given value
+    pub fn density(&self, x: f64) -> f64 {
+        match self {
+            Prior::Uniform { low, high } => match (*low..=*high).contains(&x) {
+                true => 1.0 / (high - low),
+                false => 0.0,
+            },
+            Prior::LogUniform { low, high } => match (*low..=*high).contains(&x) {
+                true => 1.0 / (x * (high.ln() - low.ln())),
+                false => 0.0,
+            },
+            Prior::Normal { mean, std } => {
+                (-0.5 * ((x - mean) / std).powi(2)).exp()
+                    / (std * (2.0 * core::f64::consts::PI).sqrt())
+            }
+        }
+    }
+
+    /// Checks that the parameters of the prior describe a valid distribution.
+    fn check(&self) -> Result<(), CalibrationError> {
+        let valid = match self {
+            Prior::Uniform { low, high } => low < high,
+            Prior::LogUniform { low, high } => 0.0 < *low && low < high,
+            Prior::Normal { std, .. } => *std > 0.0,
+        };
+        match valid {
+            true => Ok(()),
+            false => Err(CalibrationError::SettingsError(format!(
+                "invalid prior {self:?}"
+            ))),
+        }
+    }
+}
+
+/// Weighted particles of one generation of [AbcSmc]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct AbcGeneration {
+    /// Accepted distances are smaller or equal to this tolerance
+    pub tolerance: f64,
+    /// Accepted parameters
+    pub particles: Vec<Vec<f64>>,
+    /// Normalized weights of the particles
+    pub weights: Vec<f64>,
+    /// Distances of the summary statistics of the particles to the observed data
+    pub distances: Vec<f64>,
+    /// Number of simulations which were needed for this generation
+    pub n_simulations: usize,
+}
+
+impl AbcGeneration {
+    /// Weighted mean of the particles
+    pub fn weighted_mean(&self) -> Vec<f64> {
+        let n = self.particles.first().map_or(0, |p| p.len());
+        (0..n)
+            .map(|i| {
+                self.particles
+                    .iter()
+                    .zip(self.weights.iter())
+                    .map(|(p, w)| w * p[i])
+                    .sum()
+            })
+            .collect()
+    }
+
+    /// Weighted variance of every parameter
+    pub fn weighted_variance(&self) -> Vec<f64> {
+        let mean = self.weighted_mean();
+        (0..mean.len())
+            .map(|i| {
+                self.particles
+                    .iter()
+                    .zip(self.weights.iter())
+                    .map(|(p, w)| w * (p[i] - mean[i]).powi(2))
+                    .sum()
+            })
+            .collect()
+    }
+
+    /// Effective sample size $1/\sum_i w_i^2$ of the weighted particles
+    pub fn effective_sample_size(&self) -> f64 {
+        1.0 / self.weights.iter().map(|w| w * w).sum::<f64>()
+    }
+}
+
+/// Approximate Bayesian computation with sequential Monte Carlo (ABC-SMC)
+///
+/// The first generation samples parameters from the priors.
+/// Every following generation perturbs particles of the previous generation with a Gaussian
+/// kernel and only accepts parameters whose summary statistics are closer to the observed data
+/// than the current tolerance.
+/// The tolerance of every generation is the [AbcSmc::quantile] of the distances of the previous
+/// one.
+/// Weights correct for the difference between prior and proposal distribution
+/// (see [Beaumont et al. (2009)](https://doi.org/10.1093/biomet/asp052)).
+///
+/// Distances are Euclidean distances between simulated and observed summary statistics.
+/// Summary statistics should thus be scaled to comparable magnitudes.
+/// All candidates of one batch are simulated in parallel.
+/// ```
+/// # use cellular_raza_core::calibration::*;
+/// let priors = [Prior::Uniform { low: 0.0, high: 10.0 }];
+/// let simulate = |p: &[f64]| Ok::<_, String>(vec![p[0] * p[0]]);
+/// let result = AbcSmc::default().run(&priors, &[4.0], simulate).unwrap();
+/// let posterior = result.last().unwrap();
+/// assert!((posterior.weighted_mean()[0] - 2.0).abs() < 0.1);
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct AbcSmc {
+    /// Number of accepted particles per generation
+    pub n_particles: usize,
+    /// Maximum number of generations
+    pub n_generations: usize,
+    /// Quantile of the distances which determines the next tolerance
+    pub quantile: f64,
+    /// Tolerance of the first generation
+    ///
+    /// All samples of the prior are accepted if not specified.
+    pub initial_tolerance: Option<f64>,
+    /// Stop when the tolerance is smaller than this value
+    pub min_tolerance: f64,
+    /// Maximum number of simulations per generation
+    pub max_simulations_per_generation: usize,
+    /// Seed of the random number generator
+    pub seed: u64,
+}
+
+impl Default for AbcSmc {
+    fn default() -> Self {
+        Self {
+            n_particles: 500,
+            n_generations: 10,
+            quantile: 0.5,
+            initial_tolerance: None,
+            min_tolerance: 0.0,
+            max_simulations_per_generation: 100_000,
+            seed: 0,
+        }
+    }
+}
+
+impl AbcSmc {
+    /// Infers the posterior of the parameters given their priors and observed summary
+    /// statistics.
+    ///
+    /// The closure runs a simulation for the given parameters and returns its summary
+    /// statistics.
+    /// Returns all generations in order such that the last one approximates the posterior.
+    pub fn run<F, E>(
+        &self,
+        priors: &[Prior],
+        observed: &[f64],
+        simulate: F,
+    ) -> Result<Vec<AbcGeneration>, CalibrationError>
+    where
+        F: Fn(&[f64]) -> Result<Vec<f64>, E> + Sync,
+        E: Display,
+    {
+        self.check(priors)?;
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(self.seed);
+        let distance =
+            |p: &Vec<f64>| simulate(p).map(|summary| squared_error(&summary, observed).sqrt());
+
+        let mut generations: Vec<AbcGeneration> = Vec::new();
+        let mut tolerance = self.initial_tolerance.unwrap_or(f64::INFINITY);
+        for _ in 0..self.n_generations {
+            // Gaussian perturbation kernel with twice the weighted variance of the previous
+            // generation
+            let kernel_std: Option<Vec<f64>> = generations.last().map(|previous| {
+                previous
+                    .weighted_variance()
+                    .into_iter()
+                    .map(|v| (2.0 * v).sqrt().max(f64::MIN_POSITIVE))
+                    .collect()
+            });
+
+            let mut particles = Vec::with_capacity(self.n_particles);
+            let mut distances = Vec::with_capacity(self.n_particles);
+            let mut n_simulations = 0;
+            while particles.len() < self.n_particles {
+                if n_simulations >= self.max_simulations_per_generation {
+                    return Err(CalibrationError::EvaluationError(format!(
+                        "accepted only {} of {} particles with tolerance {} after {} simulations",
+                        particles.len(),
+                        self.n_particles,
+                        tolerance,
+                        n_simulations
+                    )));
+                }
+                let candidates: Vec<Vec<f64>> = (0..self.n_particles)
+                    .map(|_| match (generations.last(), &kernel_std) {
+                        (Some(previous), Some(kernel_std)) => {
+                            propose(previous, priors, kernel_std, &mut rng)
+                        }
+                        _ => priors.iter().map(|prior| prior.sample(&mut rng)).collect(),
+                    })
+                    .collect();
+                let candidate_distances = run_batch(&candidates, distance)?;
+                n_simulations += candidates.len();
+                for (p, d) in candidates.into_iter().zip(candidate_distances) {
+                    if d <= tolerance && particles.len() < self.n_particles {
+                        particles.push(p);
+                        distances.push(d);
+                    }
+                }
+            }
+
+            let weights = match (generations.last(), &kernel_std) {
+                (Some(previous), Some(kernel_std)) => particles
+                    .iter()
+                    .map(|p| {
+                        let prior_density: f64 = priors
+                            .iter()
+                            .zip(p.iter())
+                            .map(|(prior, x)| prior.density(*x))
+                            .product();
+                        let proposal_density: f64 = previous
+                            .particles
+                            .iter()
+                            .zip(previous.weights.iter())
+                            .map(|(q, w)| w * kernel_density(p, q, kernel_std))
+                            .sum();
+                        prior_density / proposal_density
+                    })
+                    .collect(),
+                _ => vec![1.0; particles.len()],
+            };
+            let total: f64 = weights.iter().sum();
+            let weights = weights.into_iter().map(|w| w / total).collect();
+
+            let next_tolerance = quantile(&distances, self.quantile);
+            generations.push(AbcGeneration {
+                tolerance,
+                particles,
+                weights,
+                distances,
+                n_simulations,
+            });
+            if tolerance <= self.min_tolerance {
+                break;
+            }
+            tolerance = next_tolerance.min(tolerance).max(self.min_tolerance);
+        }
+        Ok(generations)
+    }
+
+    /// Checks the settings of the algorithm and all given priors.
+    fn check(&self, priors: &[Prior]) -> Result<(), CalibrationError> {
+        if priors.is_empty() {
+            return Err(CalibrationError::SettingsError(
+                "at least one prior is required".into(),
+            ));
+        }
+        if self.n_particles == 0 {
+            return Err(CalibrationError::SettingsError(
+                "number of particles needs to be positive".into(),
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.quantile) {
+            return Err(CalibrationError::SettingsError(format!(
+                "quantile {} needs to lie between 0 and 1",
+                self.quantile
+            )));
+        }
+        priors.iter().try_for_each(Prior::check)
+    }
+}
+
+/// Picks a particle of the previous generation by its weight and perturbs it until it lies
+/// inside the support of the prior.
+fn propose<R: Rng>(
+    previous: &AbcGeneration,
+    priors: &[Prior],
+    kernel_std: &[f64],
+    rng: &mut R,
+) -> Vec<f64> {
+    loop {
+        let u: f64 = rng.gen();
+        let mut cumulative = 0.0;
+        let index = previous
+            .weights
+            .iter()
+            .position(|w| {
+                cumulative += w;
+                u < cumulative
+            })
+            .unwrap_or(previous.weights.len() - 1);
+        let candidate: Vec<f64> = previous.particles[index]
+            .iter()
+            .zip(kernel_std.iter())
+            .map(|(x, std)| x + std * standard_normal(rng))
+            .collect();
+        if priors
+            .iter()
+            .zip(candidate.iter())
+            .all(|(prior, x)| prior.density(*x) > 0.0)
+        {
+            return candidate;
+        }
+    }
+}
+
+/// Unnormalized density of the Gaussian perturbation kernel
+fn kernel_density(x: &[f64], center: &[f64], kernel_std: &[f64]) -> f64 {
+    let exponent: f64 = x
+        .iter()
+        .zip(center.iter())
+        .zip(kernel_std.iter())
+        .map(|((x, c), std)| ((x - c) / std).powi(2))
+        .sum();
+    (-0.5 * exponent).exp()
+}
+
+/// Quantile of the given values via linear interpolation
+fn quantile(values: &[f64], q: f64) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let position = q * (sorted.len() - 1) as f64;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    sorted[lower] + (position - lower as f64) * (sorted[upper] - sorted[lower])
+}
+
+#[cfg(test)]
+mod test_abc {
+    use super::*;
+
+    #[test]
+    fn prior_densities_integrate_to_one() {
+        let priors = [
+            Prior::Uniform {
+                low: -1.0,
+                high: 3.0,
+            },
+            Prior::LogUniform {
+                low: 0.1,
+                high: 10.0,
+            },
+            Prior::Normal {
+                mean: 1.0,
+                std: 0.5,
+            },
+        ];
+        let n = 200_000;
+        let (low, high) = (-5.0, 15.0);
+        let dx = (high - low) / n as f64;
+        for prior in priors {
+            let integral: f64 = (0..n)
+                .map(|i| prior.density(low + (i as f64 + 0.5) * dx) * dx)
+                .sum();
+            assert!((integral - 1.0).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn infer_two_parameters() {
+        let priors = [
+            Prior::Uniform {
+                low: 0.0,
+                high: 5.0,
+            },
+            Prior::Normal {
+                mean: 0.0,
+                std: 2.0,
+            },
+        ];
+        let simulate = |p: &[f64]| Ok::<_, String>(vec![p[0] + p[1], p[0] - p[1]]);
+        let generations = AbcSmc::default()
+            .run(&priors, &[3.0, 1.0], simulate)
+            .unwrap();
+        assert!(generations
+            .windows(2)
+            .all(|w| w[1].tolerance <= w[0].tolerance));
+        let posterior = generations.last().unwrap();
+        assert_eq!(posterior.particles.len(), 500);
+        assert!((posterior.weights.iter().sum::<f64>() - 1.0).abs() < 1e-10);
+        assert!(posterior
+            .distances
+            .iter()
+            .all(|d| *d <= posterior.tolerance));
+        let mean = posterior.weighted_mean();
+        assert!((mean[0] - 2.0).abs() < 0.05);
+        assert!((mean[1] - 1.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn stop_at_min_tolerance() {
+        let abc = AbcSmc {
+            n_particles: 100,
+            min_tolerance: 0.5,
+            ..Default::default()
+        };
+        let priors = [Prior::Uniform {
+            low: 0.0,
+            high: 10.0,
+        }];
+        let generations = abc
+            .run(&priors, &[5.0], |p| Ok::<_, String>(vec![p[0]]))
+            .unwrap();
+        assert!(generations.len() < abc.n_generations);
+        assert_eq!(generations.last().unwrap().tolerance, 0.5);
+    }
+
+    #[test]
+    fn fail_with_unreachable_tolerance() {
+        let abc = AbcSmc {
+            initial_tolerance: Some(0.1),
+            max_simulations_per_generation: 1000,
+            ..Default::default()
+        };
+        let priors = [Prior::Uniform {
+            low: 0.0,
+            high: 1.0,
+        }];
+        let result = abc.run(&priors, &[5.0], |p| Ok::<_, String>(vec![p[0]]));
+        assert!(matches!(result, Err(CalibrationError::EvaluationError(_))));
+    }
+}
@@ -0,0 +1,328 @@
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    check_bounds, clamp_to_bounds, run_batch, standard_normal, CalibrationError, OptimizationResult,
+};
+
+use core::fmt::Display;
+
+/// Covariance matrix adaptation evolution strategy (CMA-ES)
+///
+/// In every iteration, a population of parameter vectors is sampled from a multivariate normal
+/// distribution and evaluated in parallel.
+/// The mean, covariance and step size of the distribution are then adapted towards the best
+/// samples.
+/// See [Hansen (2016)](https://arxiv.org/abs/1604.00772) for a detailed description of the
+/// method and its default parameters.
+///
+/// The search stops after [CmaEs::max_iterations] or when the standard deviation of the
+/// distribution along every axis is smaller than [CmaEs::tolerance].
+///
+/// ```
+/// # use cellular_raza_core::calibration::CmaEs;
+/// let sphere = |p: &[f64]| Ok::<_, String>(p.iter().map(|x| (x - 1.0).powi(2)).sum());
+/// let result = CmaEs::default().minimize(&[0.0; 4], sphere).unwrap();
+/// assert!(result.best_loss < 1e-8);
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CmaEs {
+    /// Maximum number of iterations
+    pub max_iterations: usize,
+    /// Stop when the standard deviation along all axes is smaller than this value
+    pub tolerance: f64,
+    /// Initial step size $\sigma$
+    pub initial_sigma: f64,
+    /// Number of samples per iteration
+    ///
+    /// Defaults to $4 + \lfloor 3\ln n\rfloor$ for $n$ parameters if not specified.
+    pub population_size: Option<usize>,
+    /// Optional lower and upper bound for every parameter
+    pub bounds: Option<Vec<(f64, f64)>>,
+    /// Seed of the random number generator
+    pub seed: u64,
+}
+
+impl Default for CmaEs {
+    fn default() -> Self {
+        Self {
+            max_iterations: 1000,
+            tolerance: 1e-10,
+            initial_sigma: 0.5,
+            population_size: None,
+            bounds: None,
+            seed: 0,
+        }
+    }
+}
+
+impl CmaEs {
+    /// Minimizes the given loss starting from the initial parameters.
+    pub fn minimize<F, E>(
+        &self,
+        initial_parameters: &[f64],
+        loss: F,
+    ) -> Result<OptimizationResult, CalibrationError>
+    where
+        F: Fn(&[f64]) -> Result<f64, E> + Sync,
+        E: Display,
+    {
+        let n = initial_parameters.len();
+        check_bounds(n, &self.bounds)?;
+        let nf = n as f64;
+        let lambda = self
+            .population_size
+            .unwrap_or(4 + (3.0 * nf.ln()).floor() as usize);
+        if lambda < 2 {
+            return Err(CalibrationError::SettingsError(
+                "population size needs to be at least 2".into(),
+            ));
+        }
+        let mu = lambda / 2;
+        let weights: Vec<f64> = {
+            let w: Vec<f64> = (1..=mu)
+                .map(|i| (mu as f64 + 0.5).ln() - (i as f64).ln())
+                .collect();
+            let sum: f64 = w.iter().sum();
+            w.into_iter().map(|wi| wi / sum).collect()
+        };
+        let mu_eff = 1.0 / weights.iter().map(|w| w * w).sum::<f64>();
+
+        // Strategy parameters for adaptation
+        let cc = (4.0 + mu_eff / nf) / (nf + 4.0 + 2.0 * mu_eff / nf);
+        let cs = (mu_eff + 2.0) / (nf + mu_eff + 5.0);
+        let c1 = 2.0 / ((nf + 1.3).powi(2) + mu_eff);
+        let cmu =
+            (1.0 - c1).min(2.0 * (mu_eff - 2.0 + 1.0 / mu_eff) / ((nf + 2.0).powi(2) + mu_eff));
+        let damps = 1.0 + 2.0 * (((mu_eff - 1.0) / (nf + 1.0)).sqrt() - 1.0).max(0.0) + cs;
+        let chi_n = nf.sqrt() * (1.0 - 1.0 / (4.0 * nf) + 1.0 / (21.0 * nf * nf));
+
+        // Dynamic state
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(self.seed);
+        let mut mean = initial_parameters.to_vec();
+        clamp_to_bounds(&mut mean, &self.bounds);
+        let mut sigma = self.initial_sigma;
+        let mut pc = vec![0.0; n];
+        let mut ps = vec![0.0; n];
+        let mut cov = identity(n);
+        let mut eigenvectors = identity(n);
+        let mut eigenvalues_sqrt = vec![1.0; n];
+
+        let mut best_parameters = mean.clone();
+        let mut best_loss = f64::INFINITY;
+        let mut n_evaluations = 0;
+        let mut history = Vec::new();
+        let mut n_iterations = 0;
+
+        while n_iterations < self.max_iterations {
+            n_iterations += 1;
+
+            // Sample the population x = m + sigma * B * D * z
+            let samples: Vec<Vec<f64>> = (0..lambda)
+                .map(|_| {
+                    let z: Vec<f64> = (0..n).map(|_| standard_normal(&mut rng)).collect();
+                    let mut x: Vec<f64> = (0..n)
+                        .map(|i| {
+                            mean[i]
+                                + sigma
+                                    * (0..n)
+                                        .map(|j| eigenvectors[i][j] * eigenvalues_sqrt[j] * z[j])
+                                        .sum::<f64>()
+                        })
+                        .collect();
+                    clamp_to_bounds(&mut x, &self.bounds);
+                    x
+                })
+                .collect();
+            let losses = run_batch(&samples, |p: &Vec<f64>| loss(p))?;
+            n_evaluations += lambda;
+
+            let mut ranked: Vec<(Vec<f64>, f64)> = samples.into_iter().zip(losses).collect();
+            ranked.sort_by(|a, b| a.1.total_cmp(&b.1));
+            if ranked[0].1 < best_loss {
+                best_loss = ranked[0].1;
+                best_parameters = ranked[0].0.clone();
+            }
+            history.push(best_loss);
+
+            // Update the mean
+            let old_mean = mean.clone();
+            mean = (0..n)
+                .map(|i| {
+                    weights
+                        .iter()
+                        .zip(ranked.iter())
+                        .map(|(w, (x, _))| w * x[i])
+                        .sum()
+                })
+                .collect();
+            let y_w: Vec<f64> = (0..n).map(|i| (mean[i] - old_mean[i]) / sigma).collect();
+
+            // Update the evolution path of sigma with C^{-1/2} y_w
+            let c_inv_sqrt_y = (0..n)
+                .map(|i| {
+                    (0..n)
+                        .map(|k| {
+                            eigenvectors[i][k] / eigenvalues_sqrt[k]
+                                * (0..n).map(|j| eigenvectors[j][k] * y_w[j]).sum::<f64>()
+                        })
+                        .sum::<f64>()
+                })
+                .collect::<Vec<_>>();
+            let cs_factor = (cs * (2.0 - cs) * mu_eff).sqrt();
+            for i in 0..n {
+                ps[i] = (1.0 - cs) * ps[i] + cs_factor * c_inv_sqrt_y[i];
+            }
+            let ps_norm = ps.iter().map(|p| p * p).sum::<f64>().sqrt();
+            let hsig = ps_norm / (1.0 - (1.0 - cs).powi(2 * n_iterations as i32)).sqrt() / chi_n
+                < 1.4 + 2.0 / (nf + 1.0);
+
+            // Update the evolution path and covariance matrix
+            let cc_factor = (cc * (2.0 - cc) * mu_eff).sqrt();
+            let hsig_f = if hsig { 1.0 } else { 0.0 };
+            for i in 0..n {
+                pc[i] = (1.0 - cc) * pc[i] + hsig_f * cc_factor * y_w[i];
+            }
+            let ys: Vec<Vec<f64>> = ranked[..mu]
+                .iter()
+                .map(|(x, _)| (0..n).map(|i| (x[i] - old_mean[i]) / sigma).collect())
+                .collect();
+            for i in 0..n {
+                for j in 0..n {
+                    let rank_mu: f64 = weights
+                        .iter()
+                        .zip(ys.iter())
+                        .map(|(w, y)| w * y[i] * y[j])
+                        .sum();
+                    cov[i][j] = (1.0 - c1 - cmu) * cov[i][j]
+                        + c1 * (pc[i] * pc[j] + (1.0 - hsig_f) * cc * (2.0 - cc) * cov[i][j])
+                        + cmu * rank_mu;
+                }
+            }
+
+            // Update the step size
+            sigma *= ((cs / damps) * (ps_norm / chi_n - 1.0)).exp();
+
+            // Decompose the covariance matrix C = B D^2 B^T
+            let (eigenvalues, vectors) = symmetric_eigen(&cov);
+            eigenvectors = vectors;
+            eigenvalues_sqrt = eigenvalues.iter().map(|e| e.max(1e-300).sqrt()).collect();
+
+            let max_std = (0..n).map(|i| sigma * cov[i][i].sqrt()).fold(0.0, f64::max);
+            if max_std < self.tolerance || !sigma.is_finite() {
+                break;
+            }
+        }
+        Ok(OptimizationResult {
+            best_parameters,
+            best_loss,
+            n_iterations,
+            n_evaluations,
+            history,
+        })
+    }
+}
+
+/// Identity matrix of dimension `n`
+fn identity(n: usize) -> Vec<Vec<f64>> {
+    (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect()
+}
+
+/// Eigenvalues and eigenvectors (stored as columns) of a symmetric matrix.
+///
+/// Uses the cyclic Jacobi method which is sufficiently fast for the small matrices of
+/// calibration problems.
+fn symmetric_eigen(matrix: &[Vec<f64>]) -> (Vec<f64>, Vec<Vec<f64>>) {
+    let n = matrix.len();
+    let mut a = matrix.to_vec();
+    let mut v = identity(n);
+    for _ in 0..100 {
+        let off_diagonal: f64 = (0..n)
+            .flat_map(|i| (0..n).filter(move |j| *j != i).map(move |j| (i, j)))
+            .map(|(i, j)| a[i][j] * a[i][j])
+            .sum();
+        if off_diagonal < 1e-30 {
+            break;
+        }
+        for p in 0..n {
+            for q in p + 1..n {
+                if a[p][q].abs() < 1e-300 {
+                    continue;
+                }
+                let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let t = if theta == 0.0 { 1.0 } else { t };
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                for row in a.iter_mut() {
+                    let akp = row[p];
+                    let akq = row[q];
+                    row[p] = c * akp - s * akq;
+                    row[q] = s * akp + c * akq;
+                }
+                // Since p < q, row p lies in the first and row q in the second half
+                let (head, tail) = a.split_at_mut(q);
+                for (apk, aqk) in head[p].iter_mut().zip(tail[0].iter_mut()) {
+                    let (old_apk, old_aqk) = (*apk, *aqk);
+                    *apk = c * old_apk - s * old_aqk;
+                    *aqk = s * old_apk + c * old_aqk;
+                }
+                for row in v.iter_mut() {
+                    let vkp = row[p];
+                    let vkq = row[q];
+                    row[p] = c * vkp - s * vkq;
+                    row[q] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+    ((0..n).map(|i| a[i][i]).collect(), v)
+}
+
+#[cfg(test)]
+mod test_cma_es {
+    use super::*;
+
+    #[test]
+    fn eigen_decomposition() {
+        let matrix = vec![
+            vec![4.0, 1.0, 0.5],
+            vec![1.0, 3.0, 0.2],
+            vec![0.5, 0.2, 1.0],
+        ];
+        let (values, vectors) = symmetric_eigen(&matrix);
+        for k in 0..3 {
+            for i in 0..3 {
+                let av: f64 = (0..3).map(|j| matrix[i][j] * vectors[j][k]).sum();
+                assert!((av - values[k] * vectors[i][k]).abs() < 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn minimize_rotated_ellipsoid() {
+        let loss = |p: &[f64]| {
+            let u = p[0] + p[1] - 3.0;
+            let v = p[0] - p[1] + 1.0;
+            Ok::<_, String>(u * u + 100.0 * v * v)
+        };
+        let result = CmaEs::default().minimize(&[0.0, 0.0], loss).unwrap();
+        assert!((result.best_parameters[0] - 1.0).abs() < 1e-4);
+        assert!((result.best_parameters[1] - 2.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn reproducible_with_seed() {
+        let loss = |p: &[f64]| Ok::<_, String>(p.iter().map(|x| x.abs()).sum());
+        let optimizer = CmaEs {
+            max_iterations: 20,
+            ..Default::default()
+        };
+        let result1 = optimizer.minimize(&[1.0, -1.0, 2.0], loss).unwrap();
+        let result2 = optimizer.minimize(&[1.0, -1.0, 2.0], loss).unwrap();
+        assert_eq!(result1, result2);
+        assert_eq!(result1.n_evaluations, 20 * 7);
+    }
+}
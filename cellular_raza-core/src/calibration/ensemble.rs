@@ -0,0 +1,200 @@
+use serde::{Deserialize, Serialize};
+
+use super::{run_batch, CalibrationError};
+
+use core::fmt::Display;
+use std::collections::BTreeMap;
+
+/// Observable which is evaluated for every replicate of an [EnsembleRunner]
+type Observable<R> = Box<dyn Fn(&R) -> Vec<f64> + Send + Sync>;
+
+/// Runs replicates of a stochastic simulation with different seeds in parallel.
+///
+/// Every replicate is identified by its index and obtains its own seed which is derived from
+/// the [EnsembleRunner::base_seed].
+/// The user-provided closure receives both such that it can for example store the results of
+/// each replicate in a separate storage location.
+///
+/// Observables are registered by name and calculate a vector of values (such as a single value
+/// or a time series) from the result of each replicate.
+/// Their element-wise mean and variance across all replicates are calculated automatically.
+/// ```
+/// # use cellular_raza_core::calibration::EnsembleRunner;
+/// use rand::{Rng, SeedableRng};
+/// let ensemble = EnsembleRunner::new(100, 42)
+///     .register_observable("final_count", |n_cells: &usize| vec![*n_cells as f64]);
+/// let result = ensemble
+///     .run(|_replicate, seed| {
+///         // A toy simulation in which each of 10 cells divides with probability 0.5
+///         let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(seed);
+///         Ok::<_, String>((0..10).map(|_| 1 + rng.gen_bool(0.5) as usize).sum::<usize>())
+///     })
+///     .unwrap();
+/// assert_eq!(result.replicates.len(), 100);
+/// let stats = &result.statistics["final_count"];
+/// assert!((stats.mean[0] - 15.0).abs() < 1.0);
+/// ```
+pub struct EnsembleRunner<R> {
+    /// Number of replicates
+    pub n_replicates: usize,
+    /// Seed from which the seeds of all replicates are derived
+    pub base_seed: u64,
+    /// Registered observables together with their name
+    observables: Vec<(String, Observable<R>)>,
+}
+
+/// Statistics of one observable across all replicates
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ObservableStatistics {
+    /// Values of the observable for every replicate in order of their index
+    pub values: Vec<Vec<f64>>,
+    /// Element-wise mean
+    pub mean: Vec<f64>,
+    /// Element-wise unbiased sample variance
+    pub variance: Vec<f64>,
+}
+
+/// Results of all replicates of an [EnsembleRunner]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct EnsembleResult<R> {
+    /// Result of every replicate stored under its index
+    pub replicates: BTreeMap<usize, R>,
+    /// Statistics of all registered observables
+    pub statistics: BTreeMap<String, ObservableStatistics>,
+}
+
+impl<R> EnsembleRunner<R> {
+    /// Constructs a new runner without any observables.
+    pub fn new(n_replicates: usize, base_seed: u64) -> Self {
+        Self {
+            n_replicates,
+            base_seed,
+            observables: Vec::new(),
+        }
+    }
+
+    /// Registers an observable under the given name.
+    pub fn register_observable(
+        mut self,
+        name: impl Into<String>,
+        observable: impl Fn(&R) -> Vec<f64> + Send + Sync + 'static,
+    ) -> Self {
+        self.observables.push((name.into(), Box::new(observable)));
+        self
+    }
+
+    /// Seed of the replicate with the given index
+    ///
+    /// Seeds are obtained by scrambling the base seed and index with the SplitMix64 finalizer
+    /// such that neighboring replicates obtain uncorrelated seeds.
+    pub fn seed(&self, replicate: usize) -> u64 {
+        let mut z = self
+            .base_seed
+            .wrapping_add((replicate as u64 + 1).wrapping_mul(0x9E3779B97F4A7C15));
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// Runs all replicates in parallel and aggregates the registered observables.
+    ///
+    /// The closure receives the index and seed of the replicate.
+    pub fn run<F, E>(&self, simulate: F) -> Result<EnsembleResult<R>, CalibrationError>
+    where
+        F: Fn(usize, u64) -> Result<R, E> + Sync,
+        E: Display,
+        R: Send,
+    {
+        if self.n_replicates == 0 {
+            return Err(CalibrationError::SettingsError(
+                "at least one replicate is required".into(),
+            ));
+        }
+        let replicates: Vec<usize> = (0..self.n_replicates).collect();
+        let results = run_batch(&replicates, |replicate: &usize| {
+            simulate(*replicate, self.seed(*replicate))
+        })?;
+
+        let mut statistics = BTreeMap::new();
+        for (name, observable) in self.observables.iter() {
+            let values: Vec<Vec<f64>> = results.iter().map(observable).collect();
+            let n = values[0].len();
+            if values.iter().any(|v| v.len() != n) {
+                return Err(CalibrationError::EvaluationError(format!(
+                    "observable {name} returned values of different lengths"
+                )));
+            }
+            let count = values.len() as f64;
+            let mean: Vec<f64> = (0..n)
+                .map(|i| values.iter().map(|v| v[i]).sum::<f64>() / count)
+                .collect();
+            let variance = (0..n)
+                .map(|i| match values.len() {
+                    1 => 0.0,
+                    _ => {
+                        values.iter().map(|v| (v[i] - mean[i]).powi(2)).sum::<f64>() / (count - 1.0)
+                    }
+                })
+                .collect();
+            statistics.insert(
+                name.clone(),
+                ObservableStatistics {
+                    values,
+                    mean,
+                    variance,
+                },
+            );
+        }
+        Ok(EnsembleResult {
+            replicates: results.into_iter().enumerate().collect(),
+            statistics,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test_ensemble {
+    use super::*;
+
+    #[test]
+    fn distinct_seeds() {
+        let ensemble = EnsembleRunner::<()>::new(1000, 0);
+        let seeds: std::collections::BTreeSet<_> = (0..ensemble.n_replicates)
+            .map(|i| ensemble.seed(i))
+            .collect();
+        assert_eq!(seeds.len(), 1000);
+        assert_eq!(ensemble.seed(3), EnsembleRunner::<()>::new(5, 0).seed(3));
+        assert_ne!(ensemble.seed(3), EnsembleRunner::<()>::new(5, 1).seed(3));
+    }
+
+    #[test]
+    fn aggregate_time_series() {
+        let ensemble = EnsembleRunner::new(4, 0)
+            .register_observable("series", |r: &(usize, u64)| {
+                vec![r.0 as f64, 2.0 * r.0 as f64]
+            })
+            .register_observable("constant", |_: &(usize, u64)| vec![1.0]);
+        let result = ensemble
+            .run(|replicate, seed| Ok::<_, String>((replicate, seed)))
+            .unwrap();
+        for (index, (replicate, seed)) in result.replicates.iter() {
+            assert_eq!(index, replicate);
+            assert_eq!(*seed, ensemble.seed(*index));
+        }
+        let series = &result.statistics["series"];
+        assert_eq!(series.mean, vec![1.5, 3.0]);
+        assert!((series.variance[0] - 5.0 / 3.0).abs() < 1e-12);
+        assert!((series.variance[1] - 20.0 / 3.0).abs() < 1e-12);
+        assert_eq!(result.statistics["constant"].variance, vec![0.0]);
+    }
+
+    #[test]
+    fn propagate_errors() {
+        let ensemble = EnsembleRunner::<usize>::new(10, 0);
+        let result = ensemble.run(|replicate, _| match replicate {
+            7 => Err("replicate failed"),
+            _ => Ok(replicate),
+        });
+        assert!(matches!(result, Err(CalibrationError::EvaluationError(_))));
+    }
+}
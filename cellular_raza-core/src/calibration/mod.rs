@@ -0,0 +1,172 @@
+//! Calibrate parameters of a model by repeatedly running simulations.
+//!
+//! # Overview
+//! Every method in this module treats the simulation as a black box.
+//! Users provide a closure which takes a parameter vector, runs the simulation and returns either
+//! a loss value or summary statistics of the result.
+//! Multiple parameter vectors are evaluated in parallel via [run_batch].
+//!
+//! # Optimization
+//! Gradient-free optimizers search for the parameters which minimize a loss.
+//! - [NelderMead] simplex search for few parameters and smooth losses
+//! - [CmaEs] evolution strategy for noisy losses and larger numbers of parameters
+//!
+//! ```
+//! # use cellular_raza_core::calibration::*;
+//! // Summary statistics which would usually be obtained from a simulation
+//! let simulate = |p: &[f64]| -> Result<Vec<f64>, String> { Ok(vec![p[0] + p[1], p[0] * p[1]]) };
+//! let target = [5.0, 6.0];
+//! let result = NelderMead::default()
+//!     .minimize(&[1.0, 1.0], |p| {
+//!         simulate(p).map(|summary| squared_error(&summary, &target))
+//!     })
+//!     .unwrap();
+//! assert!(result.best_loss < 1e-6);
+//! ```
+//!
+//! # Inference
+//! [AbcSmc] approximates the posterior distribution of parameters given their [Prior]s and
+//! observed summary statistics.
+//!
+//! # Sensitivity Analysis
+//! Global sensitivity analysis helps to prioritize which parameters matter for given outputs.
+//! - [Sobol] estimates first- and total-order variance based indices
+//! - [Morris] screens many parameters cheaply via elementary effects
+//!
+//! # Ensembles
+//! The [EnsembleRunner] executes replicates of stochastic simulations with different seeds and
+//! aggregates observables across them.
+
+/// Approximate Bayesian computation
+mod abc;
+/// Covariance matrix adaptation evolution strategy
+mod cma_es;
+/// Replicates of stochastic simulations
+mod ensemble;
+/// Nelder-Mead simplex optimizer
+mod nelder_mead;
+/// Global sensitivity analysis
+mod sensitivity;
+
+pub use abc::*;
+pub use cma_es::*;
+pub use ensemble::*;
+pub use nelder_mead::*;
+pub use sensitivity::*;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use core::fmt::Display;
+
+/// Errors which can occur while calibrating parameters.
+#[derive(Debug)]
+pub enum CalibrationError {
+    /// The user-provided simulation or loss returned an error.
+    EvaluationError(String),
+    /// The settings of the method are inconsistent.
+    SettingsError(String),
+}
+
+impl Display for CalibrationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CalibrationError::EvaluationError(message) => {
+                write!(f, "evaluation failed: {message}")
+            }
+            CalibrationError::SettingsError(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for CalibrationError {}
+
+/// Evaluates the given closure for all parameter vectors in parallel.
+///
+/// The results are returned in the same order as the parameters.
+/// ```
+/// # use cellular_raza_core::calibration::run_batch;
+/// let parameters = vec![vec![1.0], vec![2.0], vec![3.0]];
+/// let results = run_batch(&parameters, |p| Ok::<_, String>(p[0] * p[0])).unwrap();
+/// assert_eq!(results, vec![1.0, 4.0, 9.0]);
+/// ```
+pub fn run_batch<P, R, E, F>(parameters: &[P], evaluate: F) -> Result<Vec<R>, CalibrationError>
+where
+    P: Sync,
+    R: Send,
+    E: Display,
+    F: Fn(&P) -> Result<R, E> + Sync,
+{
+    use rayon::prelude::*;
+    parameters
+        .par_iter()
+        .map(|p| evaluate(p).map_err(|e| CalibrationError::EvaluationError(format!("{e}"))))
+        .collect()
+}
+
+/// Sum of squared differences between summary statistics and their target values
+pub fn squared_error(summary: &[f64], target: &[f64]) -> f64 {
+    summary
+        .iter()
+        .zip(target.iter())
+        .map(|(s, t)| (s - t).powi(2))
+        .sum()
+}
+
+/// Result of an optimization.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct OptimizationResult {
+    /// Parameters with the smallest loss
+    pub best_parameters: Vec<f64>,
+    /// Smallest loss which was found
+    pub best_loss: f64,
+    /// Number of performed iterations
+    pub n_iterations: usize,
+    /// Number of evaluations of the loss
+    pub n_evaluations: usize,
+    /// Best loss after every iteration
+    pub history: Vec<f64>,
+}
+
+/// Clamps every parameter into its bounds if bounds are given.
+fn clamp_to_bounds(parameters: &mut [f64], bounds: &Option<Vec<(f64, f64)>>) {
+    if let Some(bounds) = bounds {
+        for (p, (low, high)) in parameters.iter_mut().zip(bounds.iter()) {
+            *p = p.clamp(*low, *high);
+        }
+    }
+}
+
+/// Checks that bounds (if given) match the number of parameters.
+fn check_bounds(
+    n_parameters: usize,
+    bounds: &Option<Vec<(f64, f64)>>,
+) -> Result<(), CalibrationError> {
+    if n_parameters == 0 {
+        return Err(CalibrationError::SettingsError(
+            "at least one parameter is required".into(),
+        ));
+    }
+    if let Some(bounds) = bounds {
+        if bounds.len() != n_parameters {
+            return Err(CalibrationError::SettingsError(format!(
+                "number of bounds {} does not match number of parameters {}",
+                bounds.len(),
+                n_parameters
+            )));
+        }
+        if let Some((low, high)) = bounds.iter().find(|(low, high)| low > high) {
+            return Err(CalibrationError::SettingsError(format!(
+                "lower bound {low} is larger than upper bound {high}"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Samples a standard normally distributed value via the Box-Muller transform.
+pub(crate) fn standard_normal<R: Rng>(rng: &mut R) -> f64 {
+    let u1: f64 = 1.0 - rng.gen::<f64>();
+    let u2: f64 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * core::f64::consts::PI * u2).cos()
+}
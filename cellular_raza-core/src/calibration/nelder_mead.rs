@@ -0,0 +1,208 @@
+use serde::{Deserialize, Serialize};
+
+use super::{check_bounds, clamp_to_bounds, run_batch, CalibrationError, OptimizationResult};
+
+use core::fmt::Display;
+
+/// Nelder–Mead simplex search
+///
+/// The simplex consists of $n+1$ parameter vectors for $n$ parameters.
+/// In every iteration, the worst vertex is reflected, expanded or contracted.
+/// If none of these steps improve the simplex, it shrinks towards its best vertex.
+/// The initial simplex and shrinking steps are evaluated in parallel.
+///
+/// The search stops after [NelderMead::max_iterations] or when the losses of all vertices
+/// differ by less than [NelderMead::tolerance].
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct NelderMead {
+    /// Maximum number of iterations
+    pub max_iterations: usize,
+    /// Stop when the losses of the simplex differ by less than this value
+    pub tolerance: f64,
+    /// Relative size of the initial simplex
+    ///
+    /// Parameters which are zero are displaced by this value directly.
+    pub initial_step: f64,
+    /// Optional lower and upper bound for every parameter
+    pub bounds: Option<Vec<(f64, f64)>>,
+    /// Reflection coefficient
+    pub alpha: f64,
+    /// Expansion coefficient
+    pub gamma: f64,
+    /// Contraction coefficient
+    pub rho: f64,
+    /// Shrink coefficient
+    pub sigma: f64,
+}
+
+impl Default for NelderMead {
+    fn default() -> Self {
+        Self {
+            max_iterations: 1000,
+            tolerance: 1e-10,
+            initial_step: 0.05,
+            bounds: None,
+            alpha: 1.0,
+            gamma: 2.0,
+            rho: 0.5,
+            sigma: 0.5,
+        }
+    }
+}
+
+impl NelderMead {
+    /// Minimizes the given loss starting from the initial parameters.
+    pub fn minimize<F, E>(
+        &self,
+        initial_parameters: &[f64],
+        loss: F,
+    ) -> Result<OptimizationResult, CalibrationError>
+    where
+        F: Fn(&[f64]) -> Result<f64, E> + Sync,
+        E: Display,
+    {
+        let n = initial_parameters.len();
+        check_bounds(n, &self.bounds)?;
+        let evaluate = |p: &Vec<f64>| loss(p);
+        let clamped = |mut p: Vec<f64>| {
+            clamp_to_bounds(&mut p, &self.bounds);
+            p
+        };
+
+        // Construct and evaluate the initial simplex
+        let mut vertices = vec![clamped(initial_parameters.to_vec())];
+        for i in 0..n {
+            let mut p = initial_parameters.to_vec();
+            p[i] += match p[i] == 0.0 {
+                true => self.initial_step,
+                false => self.initial_step * p[i],
+            };
+            vertices.push(clamped(p));
+        }
+        let losses = run_batch(&vertices, evaluate)?;
+        let mut simplex: Vec<(Vec<f64>, f64)> = vertices.into_iter().zip(losses).collect();
+        let mut n_evaluations = n + 1;
+        let mut history = Vec::new();
+
+        let mut n_iterations = 0;
+        while n_iterations < self.max_iterations {
+            simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+            history.push(simplex[0].1);
+            if simplex[n].1 - simplex[0].1 <= self.tolerance {
+                break;
+            }
+            n_iterations += 1;
+
+            // Centroid of all but the worst vertex
+            let centroid: Vec<f64> = (0..n)
+                .map(|i| simplex[..n].iter().map(|(p, _)| p[i]).sum::<f64>() / n as f64)
+                .collect();
+            let towards = |coefficient: f64| -> Vec<f64> {
+                clamped(
+                    centroid
+                        .iter()
+                        .zip(simplex[n].0.iter())
+                        .map(|(c, w)| c + coefficient * (c - w))
+                        .collect(),
+                )
+            };
+
+            let reflected = towards(self.alpha);
+            let reflected_loss = evaluate(&reflected)
+                .map_err(|e| CalibrationError::EvaluationError(format!("{e}")))?;
+            n_evaluations += 1;
+
+            if reflected_loss < simplex[0].1 {
+                let expanded = towards(self.alpha * self.gamma);
+                let expanded_loss = evaluate(&expanded)
+                    .map_err(|e| CalibrationError::EvaluationError(format!("{e}")))?;
+                n_evaluations += 1;
+                simplex[n] = match expanded_loss < reflected_loss {
+                    true => (expanded, expanded_loss),
+                    false => (reflected, reflected_loss),
+                };
+                continue;
+            }
+            if reflected_loss < simplex[n - 1].1 {
+                simplex[n] = (reflected, reflected_loss);
+                continue;
+            }
+
+            // Contract outside if the reflection improved the worst vertex and inside otherwise
+            let contracted = match reflected_loss < simplex[n].1 {
+                true => towards(self.alpha * self.rho),
+                false => towards(-self.rho),
+            };
+            let contracted_loss = evaluate(&contracted)
+                .map_err(|e| CalibrationError::EvaluationError(format!("{e}")))?;
+            n_evaluations += 1;
+            if contracted_loss < simplex[n].1.min(reflected_loss) {
+                simplex[n] = (contracted, contracted_loss);
+                continue;
+            }
+
+            // Shrink towards the best vertex
+            let best = simplex[0].0.clone();
+            let shrunk: Vec<Vec<f64>> = simplex[1..]
+                .iter()
+                .map(|(p, _)| {
+                    best.iter()
+                        .zip(p.iter())
+                        .map(|(b, x)| b + self.sigma * (x - b))
+                        .collect()
+                })
+                .collect();
+            let losses = run_batch(&shrunk, evaluate)?;
+            n_evaluations += n;
+            for (vertex, (p, l)) in simplex[1..].iter_mut().zip(shrunk.into_iter().zip(losses)) {
+                *vertex = (p, l);
+            }
+        }
+        simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let (best_parameters, best_loss) = simplex.swap_remove(0);
+        Ok(OptimizationResult {
+            best_parameters,
+            best_loss,
+            n_iterations,
+            n_evaluations,
+            history,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test_nelder_mead {
+    use super::*;
+
+    fn rosenbrock(p: &[f64]) -> Result<f64, String> {
+        Ok((1.0 - p[0]).powi(2) + 100.0 * (p[1] - p[0].powi(2)).powi(2))
+    }
+
+    #[test]
+    fn minimize_rosenbrock() {
+        let result = NelderMead::default()
+            .minimize(&[-1.2, 1.0], rosenbrock)
+            .unwrap();
+        assert!((result.best_parameters[0] - 1.0).abs() < 1e-3);
+        assert!((result.best_parameters[1] - 1.0).abs() < 1e-3);
+        assert!(result.history.windows(2).all(|w| w[1] <= w[0]));
+    }
+
+    #[test]
+    fn respects_bounds() {
+        let optimizer = NelderMead {
+            bounds: Some(vec![(2.0, 3.0), (-1.0, 1.0)]),
+            ..Default::default()
+        };
+        let result = optimizer.minimize(&[2.5, 0.5], rosenbrock).unwrap();
+        assert!((2.0..=3.0).contains(&result.best_parameters[0]));
+        assert!((-1.0..=1.0).contains(&result.best_parameters[1]));
+        assert!(result.best_loss < rosenbrock(&[2.5, 0.5]).unwrap());
+    }
+
+    #[test]
+    fn propagate_errors() {
+        let result = NelderMead::default().minimize(&[0.0], |_| Err::<f64, _>("failed"));
+        assert!(matches!(result, Err(CalibrationError::EvaluationError(_))));
+    }
+}
@@ -0,0 +1,399 @@
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use super::{check_bounds, run_batch, CalibrationError};
+
+use core::fmt::Display;
+
+/// Checks that all outputs have the same number of entries and returns it.
+fn n_outputs(outputs: &[Vec<f64>]) -> Result<usize, CalibrationError> {
+    let n = outputs.first().map_or(0, |o| o.len());
+    match outputs.iter().all(|o| o.len() == n) {
+        true => Ok(n),
+        false => Err(CalibrationError::EvaluationError(
+            "all evaluations need to return the same number of outputs".into(),
+        )),
+    }
+}
+
+/// Parameters of all trajectories together with the index of the changed parameter at every step
+pub type MorrisDesign = (Vec<Vec<f64>>, Vec<Vec<usize>>);
+
+/// Maps a point of the unit cube into the given bounds.
+fn scale_to_bounds(unit: &[f64], bounds: &[(f64, f64)]) -> Vec<f64> {
+    unit.iter()
+        .zip(bounds.iter())
+        .map(|(u, (low, high))| low + u * (high - low))
+        .collect()
+}
+
+/// Variance based global sensitivity analysis
+///
+/// Estimates first-order and total-order Sobol indices of every parameter from
+/// $N(d+2)$ evaluations for $d$ parameters with the estimators of
+/// [Saltelli et al. (2010)](https://doi.org/10.1016/j.cpc.2009.09.018).
+/// The first-order index measures the fraction of the variance of an output which is explained
+/// by the parameter alone while the total-order index includes all interactions with other
+/// parameters.
+///
+/// Parameters are sampled uniformly within their bounds.
+/// ```
+/// # use cellular_raza_core::calibration::Sobol;
+/// let bounds = [(0.0, 1.0), (0.0, 1.0)];
+/// let model = |p: &[f64]| Ok::<_, String>(vec![p[0] + 0.1 * p[1]]);
+/// let indices = Sobol::default().analyze(&bounds, model).unwrap();
+/// // The first parameter is much more important
+/// assert!(indices.total_order[0][0] > 10.0 * indices.total_order[0][1]);
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Sobol {
+    /// Number of base samples $N$
+    pub n_samples: usize,
+    /// Seed of the random number generator
+    pub seed: u64,
+}
+
+impl Default for Sobol {
+    fn default() -> Self {
+        Self {
+            n_samples: 4096,
+            seed: 0,
+        }
+    }
+}
+
+/// Sobol indices for every output and parameter
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SobolIndices {
+    /// First-order indices indexed by `[output][parameter]`
+    pub first_order: Vec<Vec<f64>>,
+    /// Total-order indices indexed by `[output][parameter]`
+    pub total_order: Vec<Vec<f64>>,
+}
+
+impl Sobol {
+    /// Generates all parameters which need to be evaluated.
+    ///
+    /// The design consists of the two sample matrices $A$ and $B$ followed by the matrices
+    /// $A_B^{(i)}$ for every parameter $i$ whose $i$-th column is taken from $B$.
+    pub fn design(&self, bounds: &[(f64, f64)]) -> Result<Vec<Vec<f64>>, CalibrationError> {
+        check_bounds(bounds.len(), &Some(bounds.to_vec()))?;
+        if self.n_samples < 2 {
+            return Err(CalibrationError::SettingsError(
+                "at least 2 samples are required".into(),
+            ));
+        }
+        let d = bounds.len();
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(self.seed);
+        let mut sample = || -> Vec<Vec<f64>> {
+            (0..self.n_samples)
+                .map(|_| (0..d).map(|_| rng.gen()).collect())
+                .collect()
+        };
+        let a = sample();
+        let b = sample();
+        let mut design: Vec<Vec<f64>> = a.iter().chain(b.iter()).cloned().collect();
+        for i in 0..d {
+            design.extend(a.iter().zip(b.iter()).map(|(a_row, b_row)| {
+                let mut row = a_row.clone();
+                row[i] = b_row[i];
+                row
+            }));
+        }
+        Ok(design
+            .into_iter()
+            .map(|unit| scale_to_bounds(&unit, bounds))
+            .collect())
+    }
+
+    /// Calculates the indices from the outputs of all evaluations of the [Sobol::design].
+    pub fn indices(
+        &self,
+        n_parameters: usize,
+        outputs: &[Vec<f64>],
+    ) -> Result<SobolIndices, CalibrationError> {
+        let n = self.n_samples;
+        if outputs.len() != n * (n_parameters + 2) {
+            return Err(CalibrationError::SettingsError(format!(
+                "expected {} outputs but got {}",
+                n * (n_parameters + 2),
+                outputs.len()
+            )));
+        }
+        let n_outputs = n_outputs(outputs)?;
+        let mut first_order = Vec::with_capacity(n_outputs);
+        let mut total_order = Vec::with_capacity(n_outputs);
+        for k in 0..n_outputs {
+            let f_a: Vec<f64> = outputs[..n].iter().map(|o| o[k]).collect();
+            let f_b: Vec<f64> = outputs[n..2 * n].iter().map(|o| o[k]).collect();
+            let mean = f_a.iter().chain(f_b.iter()).sum::<f64>() / (2 * n) as f64;
+            let variance = f_a
+                .iter()
+                .chain(f_b.iter())
+                .map(|f| (f - mean).powi(2))
+                .sum::<f64>()
+                / (2 * n - 1) as f64;
+            let (s, st): (Vec<f64>, Vec<f64>) = (0..n_parameters)
+                .map(|i| {
+                    let f_ab = &outputs[(2 + i) * n..(3 + i) * n];
+                    let (mut s, mut st) = (0.0, 0.0);
+                    for j in 0..n {
+                        s += f_b[j] * (f_ab[j][k] - f_a[j]);
+                        st += (f_a[j] - f_ab[j][k]).powi(2);
+                    }
+                    (s / n as f64 / variance, 0.5 * st / n as f64 / variance)
+                })
+                .unzip();
+            first_order.push(s);
+            total_order.push(st);
+        }
+        Ok(SobolIndices {
+            first_order,
+            total_order,
+        })
+    }
+
+    /// Generates the design, evaluates all parameters in parallel and calculates the indices.
+    pub fn analyze<F, E>(
+        &self,
+        bounds: &[(f64, f64)],
+        model: F,
+    ) -> Result<SobolIndices, CalibrationError>
+    where
+        F: Fn(&[f64]) -> Result<Vec<f64>, E> + Sync,
+        E: Display,
+    {
+        let design = self.design(bounds)?;
+        let outputs = run_batch(&design, |p: &Vec<f64>| model(p))?;
+        self.indices(bounds.len(), &outputs)
+    }
+}
+
+/// Screening of parameters with elementary effects
+///
+/// The method of [Morris (1991)](https://doi.org/10.1080/00401706.1991.10484804) samples
+/// $r$ trajectories of $d+1$ points each on a grid with [Morris::n_levels] levels per
+/// parameter.
+/// Along a trajectory, one parameter after the other is changed by
+/// $\Delta = p/(2(p-1))$ for $p$ levels.
+/// The resulting elementary effects are measured in units of the normalized parameter range.
+/// Their mean $\mu$, mean of absolute values $\mu^*$ and standard deviation $\sigma$ are used to
+/// rank parameters by importance and non-linearity at low cost.
+/// ```
+/// # use cellular_raza_core::calibration::Morris;
+/// let bounds = [(0.0, 1.0), (0.0, 2.0)];
+/// let model = |p: &[f64]| Ok::<_, String>(vec![3.0 * p[0] + p[1]]);
+/// let indices = Morris::default().analyze(&bounds, model).unwrap();
+/// assert!((indices.mu_star[0][0] - 3.0).abs() < 1e-10);
+/// assert!((indices.mu_star[0][1] - 2.0).abs() < 1e-10);
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Morris {
+    /// Number of trajectories $r$
+    pub n_trajectories: usize,
+    /// Number of grid levels $p$ per parameter
+    ///
+    /// Needs to be an even number.
+    pub n_levels: usize,
+    /// Seed of the random number generator
+    pub seed: u64,
+}
+
+impl Default for Morris {
+    fn default() -> Self {
+        Self {
+            n_trajectories: 20,
+            n_levels: 4,
+            seed: 0,
+        }
+    }
+}
+
+/// Statistics of the elementary effects for every output and parameter
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct MorrisIndices {
+    /// Mean $\mu$ of the elementary effects indexed by `[output][parameter]`
+    pub mu: Vec<Vec<f64>>,
+    /// Mean of the absolute values $\mu^*$ indexed by `[output][parameter]`
+    pub mu_star: Vec<Vec<f64>>,
+    /// Standard deviation $\sigma$ indexed by `[output][parameter]`
+    pub sigma: Vec<Vec<f64>>,
+}
+
+impl Morris {
+    /// Step size $\Delta = p / (2(p-1))$ in the unit hypercube for $p$ levels
+    fn delta(&self) -> f64 {
+        self.n_levels as f64 / (2.0 * (self.n_levels as f64 - 1.0))
+    }
+
+    /// Generates all parameters which need to be evaluated.
+    ///
+    /// Returns the parameters of all trajectories after each other together with the index of
+    /// the parameter which was changed at every step of every trajectory.
+    pub fn design(&self, bounds: &[(f64, f64)]) -> Result<MorrisDesign, CalibrationError> {
+        check_bounds(bounds.len(), &Some(bounds.to_vec()))?;
+        if self.n_levels < 2 || !self.n_levels.is_multiple_of(2) {
+            return Err(CalibrationError::SettingsError(format!(
+                "number of levels {} needs to be even and positive",
+                self.n_levels
+            )));
+        }
+        if self.n_trajectories < 2 {
+            return Err(CalibrationError::SettingsError(
+                "at least 2 trajectories are required".into(),
+            ));
+        }
+        let d = bounds.len();
+        let delta = self.delta();
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(self.seed);
+        let mut design = Vec::with_capacity(self.n_trajectories * (d + 1));
+        let mut orders = Vec::with_capacity(self.n_trajectories);
+        for _ in 0..self.n_trajectories {
+            // Base points are chosen such that adding delta stays inside the unit cube
+            let mut point: Vec<f64> = (0..d)
+                .map(|_| rng.gen_range(0..self.n_levels / 2) as f64 / (self.n_levels - 1) as f64)
+                .collect();
+            let mut order: Vec<usize> = (0..d).collect();
+            order.shuffle(&mut rng);
+            design.push(scale_to_bounds(&point, bounds));
+            for &i in order.iter() {
+                point[i] += delta;
+                design.push(scale_to_bounds(&point, bounds));
+            }
+            orders.push(order);
+        }
+        Ok((design, orders))
+    }
+
+    /// Calculates the statistics of the elementary effects from the outputs of all evaluations
+    /// of the [Morris::design].
+    pub fn indices(
+        &self,
+        orders: &[Vec<usize>],
+        outputs: &[Vec<f64>],
+    ) -> Result<MorrisIndices, CalibrationError> {
+        let d = orders.first().map_or(0, |o| o.len());
+        if outputs.len() != orders.len() * (d + 1) {
+            return Err(CalibrationError::SettingsError(format!(
+                "expected {} outputs but got {}",
+                orders.len() * (d + 1),
+                outputs.len()
+            )));
+        }
+        let n_outputs = n_outputs(outputs)?;
+        let delta = self.delta();
+        let r = orders.len() as f64;
+        let mut indices = MorrisIndices {
+            mu: vec![vec![0.0; d]; n_outputs],
+            mu_star: vec![vec![0.0; d]; n_outputs],
+            sigma: vec![vec![0.0; d]; n_outputs],
+        };
+        for k in 0..n_outputs {
+            // Elementary effects indexed by [parameter][trajectory]
+            let mut effects = vec![Vec::with_capacity(orders.len()); d];
+            for (t, order) in orders.iter().enumerate() {
+                let trajectory = &outputs[t * (d + 1)..(t + 1) * (d + 1)];
+                for (step, &i) in order.iter().enumerate() {
+                    effects[i].push((trajectory[step + 1][k] - trajectory[step][k]) / delta);
+                }
+            }
+            for (i, ee) in effects.iter().enumerate() {
+                let mu = ee.iter().sum::<f64>() / r;
+                indices.mu[k][i] = mu;
+                indices.mu_star[k][i] = ee.iter().map(|e| e.abs()).sum::<f64>() / r;
+                indices.sigma[k][i] =
+                    (ee.iter().map(|e| (e - mu).powi(2)).sum::<f64>() / (r - 1.0)).sqrt();
+            }
+        }
+        Ok(indices)
+    }
+
+    /// Generates the design, evaluates all parameters in parallel and calculates the
+    /// statistics of the elementary effects.
+    pub fn analyze<F, E>(
+        &self,
+        bounds: &[(f64, f64)],
+        model: F,
+    ) -> Result<MorrisIndices, CalibrationError>
+    where
+        F: Fn(&[f64]) -> Result<Vec<f64>, E> + Sync,
+        E: Display,
+    {
+        let (design, orders) = self.design(bounds)?;
+        let outputs = run_batch(&design, |p: &Vec<f64>| model(p))?;
+        self.indices(&orders, &outputs)
+    }
+}
+
+#[cfg(test)]
+mod test_sensitivity {
+    use super::*;
+
+    fn ishigami(p: &[f64]) -> Result<Vec<f64>, String> {
+        let (a, b) = (7.0, 0.1);
+        Ok(vec![
+            p[0].sin() + a * p[1].sin().powi(2) + b * p[2].powi(4) * p[0].sin(),
+        ])
+    }
+
+    #[test]
+    fn sobol_ishigami() {
+        use core::f64::consts::PI;
+        let sobol = Sobol {
+            n_samples: 1 << 14,
+            ..Default::default()
+        };
+        let indices = sobol.analyze(&[(-PI, PI); 3], ishigami).unwrap();
+        let first_order = [0.3139, 0.4424, 0.0];
+        let total_order = [0.5576, 0.4424, 0.2437];
+        for i in 0..3 {
+            assert!((indices.first_order[0][i] - first_order[i]).abs() < 0.05);
+            assert!((indices.total_order[0][i] - total_order[i]).abs() < 0.05);
+        }
+    }
+
+    #[test]
+    fn sobol_design_size() {
+        let sobol = Sobol {
+            n_samples: 8,
+            ..Default::default()
+        };
+        let bounds = [(0.0, 1.0), (2.0, 3.0), (-1.0, 0.0)];
+        let design = sobol.design(&bounds).unwrap();
+        assert_eq!(design.len(), 8 * 5);
+        for p in design {
+            for (x, (low, high)) in p.iter().zip(bounds.iter()) {
+                assert!(low <= x && x <= high);
+            }
+        }
+    }
+
+    #[test]
+    fn morris_screening() {
+        let model = |p: &[f64]| Ok::<_, String>(vec![p[0] + p[1] * p[2], 2.0 * p[0]]);
+        let morris = Morris::default();
+        let indices = morris.analyze(&[(0.0, 1.0); 4], model).unwrap();
+        // Linear effect of the first parameter
+        assert!((indices.mu_star[0][0] - 1.0).abs() < 1e-10);
+        assert!(indices.sigma[0][0] < 1e-10);
+        // Interaction between the second and third parameter
+        assert!(indices.sigma[0][1] > 0.0);
+        assert!(indices.sigma[0][2] > 0.0);
+        // Unused parameter
+        assert_eq!(indices.mu_star[0][3], 0.0);
+        assert!((indices.mu[1][0] - 2.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn morris_odd_levels() {
+        let morris = Morris {
+            n_levels: 3,
+            ..Default::default()
+        };
+        assert!(matches!(
+            morris.design(&[(0.0, 1.0)]),
+            Err(CalibrationError::SettingsError(_))
+        ));
+    }
+}
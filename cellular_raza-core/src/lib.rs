@@ -27,13 +27,37 @@
 //! This approach allows to take cells or domain objects and extract information to then
 //! save these in a given format.
 //! The methods needed to do this have not yet been developed and are part of future releases.
+//!
+//! ## Calibration
+//! The [calibration] module provides gradient-free methods to estimate parameters of a model by
+//! running many simulations in parallel.
+//!
+//! ## Plotting
+//! With the `plotting` feature, the [plotting] module draws stored results of the
+//! [backend::chili] backend.
+//! The `rerun` feature additionally provides the `RerunLogger` of the [backend::chili] backend
+//! which streams cells and fields to the interactive [rerun](https://rerun.io) viewer.
+//!
+//! ## Batch
+//! The [batch] module and the accompanying `cr-batch` binary execute campaigns of simulations
+//! described by a directory of recipes and resume them after interruptions.
 
 pub mod backend;
 
+pub mod batch;
+
+pub mod calibration;
+
+#[cfg(feature = "plotting")]
+#[cfg_attr(docsrs, doc(cfg(feature = "plotting")))]
+pub mod plotting;
+
 pub mod storage;
 
 pub mod time;
 
+pub mod units;
+
 #[doc(hidden)]
 pub use rayon;
 
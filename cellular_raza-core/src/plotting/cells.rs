@@ -0,0 +1,526 @@
+use std::path::{Path, PathBuf};
+
+use cellular_raza_concepts::{CellShape, ChartConfig, DrawingError, PlotCell};
+use plotters::coord::cartesian::Cartesian2d;
+use plotters::coord::types::RangedCoordf64;
+use plotters::prelude::{
+    BitMapBackend, DrawingArea, DrawingBackend, IntoDrawingArea, Polygon, Rectangle, SVGBackend,
+};
+use plotters::style::{Color, RGBColor};
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+
+use crate::backend::chili::{CellBox, SimulationError, StorageAccess};
+use crate::storage::{StorageInterfaceLoad, StorageOption};
+
+use super::colormap::{draw_colorbar, ColorbarLabels};
+use super::{CellColoring, Colormap, ImageFormat};
+
+/// Number of vertices used to approximate circles
+const CIRCLE_VERTICES: usize = 32;
+
+/// Determines how cells are drawn by [plot_simulation].
+/// ```
+/// # use cellular_raza_core::plotting::PlotStyle;
+/// let style = PlotStyle {
+///     bounds: Some(([0.0; 2], [100.0; 2])),
+///     outline: None,
+///     ..PlotStyle::default()
+/// };
+/// assert_eq!(style.chart.image_size, 800);
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PlotStyle {
+    /// Folder in which all images are stored.
+    /// Defaults to the `images` folder next to the stored results.
+    pub output_dir: Option<PathBuf>,
+    /// File format of the images
+    pub format: ImageFormat,
+    /// Configuration of the chart.
+    /// Axis labels are only drawn for [ImageFormat::Svg].
+    pub chart: ChartConfig,
+    /// Lower and upper corner of the plotted region.
+    /// If not specified, the region is determined from all stored cells.
+    pub bounds: Option<([f64; 2], [f64; 2])>,
+    /// Color of the outline drawn around every cell
+    pub outline: Option<[u8; 3]>,
+    /// Storage options which were used to store the results
+    pub storage_priority: Vec<StorageOption>,
+}
+
+impl Default for PlotStyle {
+    fn default() -> Self {
+        Self {
+            output_dir: None,
+            format: ImageFormat::Png,
+            chart: ChartConfig::new(800),
+            bounds: None,
+            outline: Some([0; 3]),
+            storage_priority: vec![StorageOption::SerdeJson],
+        }
+    }
+}
+
+/// Vertices of the glyph in the coordinates of the plot
+fn glyph_vertices(shape: CellShape, [x, y]: [f64; 2], radius: f64) -> Vec<(f64, f64)> {
+    let polygon = |n: usize, phase: f64| {
+        (0..n)
+            .map(|i| {
+                let angle = phase + 2.0 * std::f64::consts::PI * i as f64 / n as f64;
+                (x + radius * angle.cos(), y + radius * angle.sin())
+            })
+            .collect()
+    };
+    match shape {
+        CellShape::Circle => polygon(CIRCLE_VERTICES, 0.0),
+        CellShape::Square => vec![
+            (x - radius, y - radius),
+            (x + radius, y - radius),
+            (x + radius, y + radius),
+            (x - radius, y + radius),
+        ],
+        CellShape::Triangle => polygon(3, std::f64::consts::FRAC_PI_2),
+    }
+}
+
+/// Draws a single cell onto the plotting root as described by its [PlotCell] implementation.
+pub fn draw_cell_glyph<Db>(
+    root: &mut DrawingArea<Db, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+    cell: &impl PlotCell,
+    outline: Option<[u8; 3]>,
+) -> Result<(), DrawingError>
+where
+    Db: DrawingBackend,
+{
+    draw_cell_glyph_with_color(root, cell, cell.glyph_color(), outline)
+}
+
+/// Draws a single cell like [draw_cell_glyph] but overrides its
+/// [glyph_color](PlotCell::glyph_color).
+pub fn draw_cell_glyph_with_color<Db>(
+    root: &mut DrawingArea<Db, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+    cell: &impl PlotCell,
+    [r, g, b]: [u8; 3],
+    outline: Option<[u8; 3]>,
+) -> Result<(), DrawingError>
+where
+    Db: DrawingBackend,
+{
+    let position = cell.glyph_position();
+    let radius = cell.glyph_radius();
+    match cell.glyph_shape() {
+        CellShape::Square => {
+            let corners = [
+                (position[0] - radius, position[1] - radius),
+                (position[0] + radius, position[1] + radius),
+            ];
+            root.draw(&Rectangle::new(corners, RGBColor(r, g, b).filled()))?;
+            if let Some([r, g, b]) = outline {
+                root.draw(&Rectangle::new(corners, RGBColor(r, g, b)))?;
+            }
+        }
+        shape => {
+            let vertices = glyph_vertices(shape, position, radius);
+            root.draw(&Polygon::new(vertices.clone(), RGBColor(r, g, b).filled()))?;
+            if let Some([r, g, b]) = outline {
+                let mut closed = vertices;
+                closed.push(closed[0]);
+                root.draw(&plotters::element::PathElement::new(
+                    closed,
+                    RGBColor(r, g, b),
+                ))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Smallest region which contains all glyphs with an additional margin of 5% on every side
+fn glyph_bounds<'a, C: PlotCell + 'a>(
+    cells: impl IntoIterator<Item = &'a C>,
+) -> ([f64; 2], [f64; 2]) {
+    let mut lower = [f64::INFINITY; 2];
+    let mut upper = [f64::NEG_INFINITY; 2];
+    for cell in cells {
+        let (cell_lower, cell_upper) = cell.glyph_bounding_box();
+        for i in 0..2 {
+            lower[i] = lower[i].min(cell_lower[i]);
+            upper[i] = upper[i].max(cell_upper[i]);
+        }
+    }
+    for i in 0..2 {
+        if lower[i] > upper[i] {
+            (lower[i], upper[i]) = (0.0, 1.0);
+        }
+        let margin = 0.05 * (upper[i] - lower[i]).max(f64::EPSILON);
+        lower[i] -= margin;
+        upper[i] += margin;
+    }
+    (lower, upper)
+}
+
+/// Plots the stored cells of a simulation at every saved iteration.
+///
+/// In contrast to [plot_fields_with_cells](super::plot_fields_with_cells), neither the domain
+/// nor the exact type of the stored auxiliary storage and subdomains are required.
+/// Cells are drawn as simple glyphs described by their [PlotCell] implementation.
+/// One image `frame_000000.png, frame_000001.png, ...` is created for every iteration at which
+/// cells were stored.
+///
+/// Since the auxiliary storage is skipped while loading, this function only works with
+/// self-describing storage formats such as [StorageOption::SerdeJson] and
+/// [StorageOption::Ron].
+/// The `storage_path` is the folder returned by [StorageAccess::get_path].
+///
+/// Returns the paths of all created images.
+pub fn plot_simulation<C>(
+    storage_path: impl AsRef<Path>,
+    style: &PlotStyle,
+) -> Result<Vec<PathBuf>, SimulationError>
+where
+    C: PlotCell + Clone + for<'a> Deserialize<'a>,
+{
+    let frames = load_frames::<C>(storage_path.as_ref(), style)?;
+    plot_frames(
+        storage_path.as_ref(),
+        style,
+        &frames,
+        |cell| cell.glyph_color(),
+        None,
+    )
+}
+
+/// Plots the stored cells like [plot_simulation] but colors them by a scalar value.
+///
+/// The [CellColoring] extracts the value from the state of every cell such as an
+/// intracellular concentration, its age or speed and translates it into a color.
+/// If no [value_range](CellColoring::value_range) is given, it is determined from all stored
+/// cells such that every image uses the same colors.
+/// ```no_run
+/// # use cellular_raza_core::plotting::*;
+/// # use cellular_raza_concepts::PlotCell;
+/// # #[derive(Clone, serde::Deserialize)]
+/// # struct MyCell { pos: [f64; 2], age: f64 }
+/// # impl PlotCell for MyCell {
+/// #     fn glyph_position(&self) -> [f64; 2] { self.pos }
+/// #     fn glyph_radius(&self) -> f64 { 1.0 }
+/// # }
+/// let coloring = CellColoring {
+///     colormap: Colormap::Copper,
+///     ..CellColoring::new(|cell: &MyCell| cell.age, "age")
+/// };
+/// let style = PlotStyle::default();
+/// let images = plot_simulation_colored::<MyCell, _>("out/20240101", &style, &coloring)?;
+/// # Ok::<(), cellular_raza_core::backend::chili::SimulationError>(())
+/// ```
+pub fn plot_simulation_colored<C, F>(
+    storage_path: impl AsRef<Path>,
+    style: &PlotStyle,
+    coloring: &CellColoring<F>,
+) -> Result<Vec<PathBuf>, SimulationError>
+where
+    C: PlotCell + Clone + for<'a> Deserialize<'a>,
+    F: Fn(&C) -> f64,
+{
+    let frames = load_frames::<C>(storage_path.as_ref(), style)?;
+    let value_range = coloring.range_of(frames.iter().flatten());
+    let legend = coloring
+        .legend
+        .as_ref()
+        .map(|label| (coloring.colormap, label.as_str(), value_range));
+    plot_frames(
+        storage_path.as_ref(),
+        style,
+        &frames,
+        |cell| coloring.color_of(cell, value_range),
+        legend,
+    )
+}
+
+/// Loads the cells of every saved iteration sorted by their identifiers.
+fn load_frames<C>(storage_path: &Path, style: &PlotStyle) -> Result<Vec<Vec<C>>, SimulationError>
+where
+    C: Clone + for<'a> Deserialize<'a>,
+{
+    let storage = StorageAccess::<(CellBox<C>, IgnoredAny), IgnoredAny>::open(
+        storage_path,
+        style.storage_priority.clone(),
+    )?;
+    let mut iterations = storage.cells.get_all_iterations()?;
+    iterations.sort();
+    let mut frames = Vec::with_capacity(iterations.len());
+    for iteration in iterations {
+        let mut cells: Vec<_> = storage
+            .cells
+            .load_all_elements_at_iteration(iteration)?
+            .into_iter()
+            .collect();
+        // Draw cells in a fixed order such that overlapping glyphs are reproducible
+        cells.sort_by_key(|(identifier, _)| *identifier);
+        frames.push(
+            cells
+                .into_iter()
+                .map(|(_, (cbox, _))| cbox.cell)
+                .collect::<Vec<C>>(),
+        );
+    }
+    Ok(frames)
+}
+
+/// Creates one image per frame and optionally draws a colorbar with the given label and range.
+fn plot_frames<C>(
+    storage_path: &Path,
+    style: &PlotStyle,
+    frames: &[Vec<C>],
+    color: impl Fn(&C) -> [u8; 3],
+    legend: Option<(Colormap, &str, (f64, f64))>,
+) -> Result<Vec<PathBuf>, SimulationError>
+where
+    C: PlotCell,
+{
+    let output_dir = match &style.output_dir {
+        Some(dir) => dir.clone(),
+        None => storage_path.join("images"),
+    };
+    std::fs::create_dir_all(&output_dir)?;
+    let (lower, upper) = match style.bounds {
+        Some(bounds) => bounds,
+        None => glyph_bounds(frames.iter().flatten()),
+    };
+
+    let size = style.chart.chart_size(lower, upper);
+    let mut paths = Vec::with_capacity(frames.len());
+    for (n, cells) in frames.iter().enumerate() {
+        let path = output_dir.join(format!("frame_{n:06}.{}", style.format.extension()));
+        match style.format {
+            ImageFormat::Png => {
+                let area = BitMapBackend::new(&path, size).into_drawing_area();
+                let root = style.chart.build_chart(area, lower, upper, false)?;
+                // Bitmaps do not render text
+                let legend = legend.map(|(colormap, _, _)| (colormap, None));
+                draw_cells(root, cells, style.outline, &color, legend)?;
+            }
+            ImageFormat::Svg => {
+                let area = SVGBackend::new(&path, size).into_drawing_area();
+                let root = style.chart.build_chart(area, lower, upper, true)?;
+                let legend =
+                    legend.map(|(colormap, label, range)| (colormap, Some((label, range))));
+                draw_cells(root, cells, style.outline, &color, legend)?;
+            }
+        }
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+/// Draws all cells and writes the result to the backend.
+fn draw_cells<Db, C>(
+    mut root: DrawingArea<Db, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+    cells: &[C],
+    outline: Option<[u8; 3]>,
+    color: &impl Fn(&C) -> [u8; 3],
+    legend: Option<(Colormap, Option<ColorbarLabels>)>,
+) -> Result<(), DrawingError>
+where
+    Db: DrawingBackend,
+    C: PlotCell,
+{
+    for cell in cells.iter() {
+        draw_cell_glyph_with_color(&mut root, cell, color(cell), outline)?;
+    }
+    if let Some((colormap, labels)) = legend {
+        draw_colorbar(&root, colormap, labels)?;
+    }
+    root.present()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_cell_plotting {
+    use super::*;
+    use plotters::prelude::ChartBuilder;
+
+    struct Glyph([f64; 2], f64, CellShape);
+
+    impl PlotCell for Glyph {
+        fn glyph_position(&self) -> [f64; 2] {
+            self.0
+        }
+
+        fn glyph_radius(&self) -> f64 {
+            self.1
+        }
+
+        fn glyph_shape(&self) -> CellShape {
+            self.2
+        }
+    }
+
+    #[test]
+    fn vertices_lie_on_circle() {
+        for shape in [CellShape::Circle, CellShape::Triangle] {
+            for (x, y) in glyph_vertices(shape, [1.0, -2.0], 3.0) {
+                let r = ((x - 1.0).powi(2) + (y + 2.0).powi(2)).sqrt();
+                assert!((r - 3.0).abs() < 1e-12);
+            }
+        }
+        assert_eq!(glyph_vertices(CellShape::Triangle, [0.0; 2], 1.0).len(), 3);
+        assert_eq!(glyph_vertices(CellShape::Square, [0.0; 2], 1.0).len(), 4);
+    }
+
+    #[test]
+    fn bounds_contain_all_glyphs() {
+        let cells = [
+            Glyph([0.0, 0.0], 1.0, CellShape::Circle),
+            Glyph([10.0, 5.0], 2.0, CellShape::Square),
+        ];
+        let (lower, upper) = glyph_bounds(cells.iter());
+        assert!(lower[0] < -1.0 && lower[1] < -1.0);
+        assert!(upper[0] > 12.0 && upper[1] > 7.0);
+    }
+
+    #[test]
+    fn draw_all_shapes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cells.png");
+        {
+            let area = BitMapBackend::new(&path, (100, 100)).into_drawing_area();
+            let chart = ChartBuilder::on(&area)
+                .build_cartesian_2d(0.0..10.0, 0.0..10.0)
+                .unwrap();
+            let mut root = chart.plotting_area().clone();
+            for (n, shape) in [CellShape::Circle, CellShape::Square, CellShape::Triangle]
+                .into_iter()
+                .enumerate()
+            {
+                let glyph = Glyph([2.0 + 3.0 * n as f64, 5.0], 1.0, shape);
+                draw_cell_glyph(&mut root, &glyph, Some([0; 3])).unwrap();
+            }
+            area.present().unwrap();
+        }
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn draw_colored_cells_with_legend() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cells.svg");
+        let cells: Vec<_> = (0..5)
+            .map(|n| Glyph([1.0 + 2.0 * n as f64, 5.0], 1.0, CellShape::Circle))
+            .collect();
+        let coloring = CellColoring::new(|cell: &Glyph| cell.0[0], "x");
+        let value_range = coloring.range_of(cells.iter());
+        assert_eq!(value_range, (1.0, 9.0));
+        {
+            let area = SVGBackend::new(&path, (200, 200)).into_drawing_area();
+            let root = ChartConfig::new(200)
+                .build_chart(area, [0.0; 2], [10.0; 2], true)
+                .unwrap();
+            let legend = Some((coloring.colormap, Some(("x", value_range))));
+            let color = |cell: &Glyph| coloring.color_of(cell, value_range);
+            draw_cells(root, &cells, None, &color, legend).unwrap();
+        }
+        let svg = std::fs::read_to_string(&path).unwrap();
+        // The first and last cell are drawn with both ends of the colormap
+        for value in [1.0, 9.0] {
+            let [r, g, b] = Colormap::Viridis.color(value, value_range);
+            assert!(svg.contains(&format!("#{r:02X}{g:02X}{b:02X}")));
+        }
+        assert!(svg.contains("9.000"));
+    }
+
+    #[derive(Clone, Deserialize, Serialize)]
+    struct Dot([f64; 2]);
+
+    impl PlotCell for Dot {
+        fn glyph_position(&self) -> [f64; 2] {
+            self.0
+        }
+
+        fn glyph_radius(&self) -> f64 {
+            0.5
+        }
+    }
+
+    /// Stores three cells at two iterations and returns the folder of the results
+    fn store_dots() -> Result<tempfile::TempDir, SimulationError> {
+        use crate::backend::chili::VoxelPlainIndex;
+        use crate::storage::{StorageBuilder, StorageInterfaceStore, StorageManager};
+
+        let dir = tempfile::tempdir()?;
+        let builder = StorageBuilder::new()
+            .location(dir.path())
+            .priority([StorageOption::SerdeJson])
+            .init_with_date(Path::new(""));
+        let mut cells = StorageManager::open_or_create(builder.suffix("cells"), 0)?;
+        for iteration in [0, 10] {
+            for n in 0..3 {
+                let dot = Dot([2.0 * n as f64 + 0.1 * iteration as f64, 1.0]);
+                let cbox = CellBox::new(VoxelPlainIndex::new(0), n, dot, None);
+                cells.store_single_element(iteration, &cbox.identifier, &(cbox.clone(), ()))?;
+            }
+        }
+        Ok(dir)
+    }
+
+    #[test]
+    fn plot_stored_simulation() -> Result<(), SimulationError> {
+        let dir = store_dots()?;
+        let images = plot_simulation::<Dot>(dir.path(), &PlotStyle::default())?;
+        // One frame is rendered for every stored iteration
+        assert_eq!(images.len(), 2);
+        for image in images {
+            assert!(image.starts_with(dir.path().join("images")));
+            assert!(std::fs::metadata(&image)?.len() > 0);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn plot_stored_simulation_as_svg() -> Result<(), SimulationError> {
+        let dir = store_dots()?;
+        let style = PlotStyle {
+            format: ImageFormat::Svg,
+            chart: ChartConfig {
+                axes: true,
+                axis_descriptions: Some(("position x".into(), "position y".into())),
+                ..ChartConfig::new(200)
+            },
+            ..PlotStyle::default()
+        };
+        let images = plot_simulation::<Dot>(dir.path(), &style)?;
+        assert_eq!(images.len(), 2);
+        for image in images {
+            assert_eq!(image.extension(), Some("svg".as_ref()));
+            // Vector graphics contain the axis labels
+            let svg = std::fs::read_to_string(&image)?;
+            assert!(svg.contains("position x"));
+            assert!(svg.contains("position y"));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn plot_stored_simulation_colored() -> Result<(), SimulationError> {
+        let dir = store_dots()?;
+        let style = PlotStyle {
+            format: ImageFormat::Svg,
+            ..PlotStyle::default()
+        };
+        let coloring = CellColoring {
+            colormap: Colormap::Copper,
+            ..CellColoring::new(|dot: &Dot| dot.0[0], "x")
+        };
+        let images = plot_simulation_colored::<Dot, _>(dir.path(), &style, &coloring)?;
+        assert_eq!(images.len(), 2);
+        // The range of values is shared between all frames such that the leftmost cell of the
+        // first frame and the rightmost cell of the last frame have both ends of the colormap
+        for (image, value) in images.iter().zip([0.0, 5.0]) {
+            let [r, g, b] = Colormap::Copper.color(value, (0.0, 5.0));
+            let svg = std::fs::read_to_string(image)?;
+            assert!(svg.contains(&format!("#{r:02X}{g:02X}{b:02X}")));
+            assert!(svg.contains("5.000"));
+        }
+        Ok(())
+    }
+}
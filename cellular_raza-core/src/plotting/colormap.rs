@@ -0,0 +1,212 @@
+use cellular_raza_concepts::DrawingError;
+use plotters::coord::cartesian::Cartesian2d;
+use plotters::coord::types::RangedCoordf64;
+use plotters::prelude::{DrawingArea, DrawingBackend, Rectangle, Text};
+use plotters::style::colors::colormaps::{BlackWhite, Bone, Copper, ViridisRGB, VulcanoHSL};
+use plotters::style::{Color, IntoFont, RGBAColor, RGBColor};
+use serde::{Deserialize, Serialize};
+
+/// Colormaps which translate scalar values into colors.
+///
+/// ```
+/// # use cellular_raza_core::plotting::Colormap;
+/// assert_eq!(Colormap::BlackWhite.color(0.5, (0.0, 1.0)), [128, 128, 128]);
+/// // Values outside of the range are clamped
+/// assert_eq!(Colormap::BlackWhite.color(3.0, (0.0, 1.0)), [255, 255, 255]);
+/// ```
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+pub enum Colormap {
+    /// Perceptually uniform colormap from purple over green to yellow
+    #[default]
+    Viridis,
+    /// Grayscale from black to white
+    BlackWhite,
+    /// Black over blue to white
+    Bone,
+    /// Black over brown to orange
+    Copper,
+    /// Blue over magenta to red
+    Vulcano,
+}
+
+impl Colormap {
+    /// Color of the value after normalizing it to the given range.
+    pub fn color(&self, value: f64, (min, max): (f64, f64)) -> [u8; 3] {
+        let h = match max > min {
+            true => ((value - min) / (max - min)).clamp(0.0, 1.0),
+            false => 0.0,
+        };
+        // NaN values are drawn with the lower end of the colormap
+        let h = if h.is_nan() { 0.0 } else { h };
+        let RGBAColor(r, g, b, _) = match self {
+            Colormap::Viridis => ViridisRGB::get_color(h).to_rgba(),
+            Colormap::BlackWhite => BlackWhite::get_color(h).to_rgba(),
+            Colormap::Bone => Bone::get_color(h).to_rgba(),
+            Colormap::Copper => Copper::get_color(h).to_rgba(),
+            Colormap::Vulcano => VulcanoHSL::get_color(h).to_rgba(),
+        };
+        [r, g, b]
+    }
+}
+
+/// Colors cells by a scalar value which is extracted from their state.
+///
+/// The `scalar` is typically a closure which reads an intracellular concentration, the age or
+/// the speed of the cell.
+/// See [plot_simulation_colored](super::plot_simulation_colored).
+/// ```
+/// # use cellular_raza_core::plotting::{CellColoring, Colormap};
+/// struct Cell {
+///     age: f64,
+/// }
+/// let coloring = CellColoring {
+///     colormap: Colormap::Copper,
+///     value_range: Some((0.0, 10.0)),
+///     ..CellColoring::new(|cell: &Cell| cell.age, "age [h]")
+/// };
+/// assert_eq!(coloring.color_of(&Cell { age: 0.0 }, (0.0, 10.0)), [0, 0, 0]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct CellColoring<F> {
+    /// Extracts the scalar value from a cell
+    pub scalar: F,
+    /// Colormap which translates the scalar into a color
+    pub colormap: Colormap,
+    /// Values which correspond to the lower and upper end of the colormap.
+    /// If not specified, the range is determined from all stored cells.
+    pub value_range: Option<(f64, f64)>,
+    /// Draw a colorbar with the given label at the right edge of every image.
+    /// The label and values are only drawn for backends which can render text.
+    pub legend: Option<String>,
+}
+
+impl<F> CellColoring<F> {
+    /// Colors cells with [Colormap::Viridis] and draws a legend with the given label.
+    pub fn new(scalar: F, label: impl Into<String>) -> Self {
+        Self {
+            scalar,
+            colormap: Colormap::Viridis,
+            value_range: None,
+            legend: Some(label.into()),
+        }
+    }
+
+    /// Color of the given cell when using the specified range of values
+    pub fn color_of<C>(&self, cell: &C, value_range: (f64, f64)) -> [u8; 3]
+    where
+        F: Fn(&C) -> f64,
+    {
+        self.colormap.color((self.scalar)(cell), value_range)
+    }
+
+    /// Smallest range which contains the scalar values of all cells.
+    ///
+    /// Returns the [value_range](CellColoring::value_range) if it was specified.
+    pub fn range_of<'a, C: 'a>(&self, cells: impl IntoIterator<Item = &'a C>) -> (f64, f64)
+    where
+        F: Fn(&C) -> f64,
+    {
+        if let Some(range) = self.value_range {
+            return range;
+        }
+        let range = cells
+            .into_iter()
+            .map(|cell| (self.scalar)(cell))
+            .filter(|value| value.is_finite())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+                (min.min(value), max.max(value))
+            });
+        match range.0 < range.1 {
+            true => range,
+            false if range.0.is_finite() => (range.0, range.0 + 1.0),
+            false => (0.0, 1.0),
+        }
+    }
+}
+
+/// Label and range of values which are written next to a colorbar
+pub(crate) type ColorbarLabels<'a> = (&'a str, (f64, f64));
+
+/// Draws a vertical colorbar at the right edge of the plotting root.
+///
+/// If `labels` are given, the label and the lower and upper end of the value range are written
+/// next to the bar.
+/// Like the axis labels, they should only be drawn for [ImageFormat::Svg](super::ImageFormat).
+pub(crate) fn draw_colorbar<Db>(
+    root: &DrawingArea<Db, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+    colormap: Colormap,
+    labels: Option<ColorbarLabels>,
+) -> Result<(), DrawingError>
+where
+    Db: DrawingBackend,
+{
+    let area = root.strip_coord_spec();
+    let (width, height) = area.dim_in_pixel();
+    let bar_width = (width / 40).max(4) as i32;
+    let margin = (width / 50).max(2) as i32;
+    let x1 = width as i32 - margin;
+    let x0 = x1 - bar_width;
+    let y0 = height as i32 / 4;
+    let y1 = 3 * height as i32 / 4;
+    for y in y0..y1 {
+        let h = (y1 - y) as f64 / (y1 - y0) as f64;
+        let [r, g, b] = colormap.color(h, (0.0, 1.0));
+        area.draw(&Rectangle::new(
+            [(x0, y), (x1, y + 1)],
+            RGBColor(r, g, b).filled(),
+        ))?;
+    }
+    area.draw(&Rectangle::new(
+        [(x0, y0), (x1, y1)],
+        plotters::style::full_palette::BLACK,
+    ))?;
+    if let Some((label, (min, max))) = labels {
+        let font = ("sans-serif", (height / 40).max(10)).into_font();
+        let x = x0 - 4 * bar_width;
+        area.draw(&Text::new(
+            label.to_owned(),
+            (x, y0 - 2 * bar_width),
+            font.clone(),
+        ))?;
+        area.draw(&Text::new(format!("{max:.3}"), (x, y0), font.clone()))?;
+        area.draw(&Text::new(format!("{min:.3}"), (x, y1 - bar_width), font))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_colormap {
+    use super::*;
+
+    #[test]
+    fn colormaps_are_ordered() {
+        // Brightness increases monotonically for all sequential colormaps
+        for colormap in [
+            Colormap::Viridis,
+            Colormap::BlackWhite,
+            Colormap::Bone,
+            Colormap::Copper,
+        ] {
+            let brightness = |h: f64| colormap.color(h, (0.0, 1.0)).map(|c| c as u32).iter().sum();
+            let values: Vec<u32> = (0..=10).map(|n| brightness(n as f64 / 10.0)).collect();
+            assert!(values.windows(2).all(|w| w[0] <= w[1]), "{colormap:?}");
+        }
+    }
+
+    #[test]
+    fn degenerate_ranges() {
+        let coloring = CellColoring::new(|x: &f64| *x, "x");
+        assert_eq!(coloring.range_of(&[2.0, 2.0]), (2.0, 3.0));
+        assert_eq!(coloring.range_of(&[]), (0.0, 1.0));
+        assert_eq!(coloring.range_of(&[f64::NAN, -1.0, 4.0]), (-1.0, 4.0));
+        let colormap = Colormap::Viridis;
+        assert_eq!(
+            colormap.color(f64::NAN, (0.0, 1.0)),
+            colormap.color(0.0, (0.0, 1.0))
+        );
+        assert_eq!(
+            colormap.color(5.0, (1.0, 1.0)),
+            colormap.color(0.0, (0.0, 1.0))
+        );
+    }
+}
@@ -0,0 +1,264 @@
+use std::path::{Path, PathBuf};
+
+use cellular_raza_concepts::{ChartConfig, CreatePlottingRoot, DrawingError, PlotField, PlotSelf};
+use plotters::coord::cartesian::Cartesian2d;
+use plotters::coord::types::RangedCoordf64;
+use plotters::prelude::{DrawingArea, DrawingBackend, Rectangle};
+use plotters::style::{Color, RGBColor};
+use serde::{Deserialize, Serialize};
+
+use crate::backend::chili::{CellBox, SimulationError, StorageAccess};
+use crate::storage::StorageInterfaceLoad;
+
+use super::colormap::draw_colorbar;
+use super::{Colormap, ImageFormat};
+
+/// Determines how extracellular fields and cells are drawn by [plot_fields_with_cells].
+/// ```
+/// # use cellular_raza_core::plotting::FieldPlotSettings;
+/// let settings = FieldPlotSettings {
+///     species: 1,
+///     video_fps: Some(10),
+///     ..FieldPlotSettings::new("out/images")
+/// };
+/// assert_eq!(settings.chart.image_size, 800);
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct FieldPlotSettings {
+    /// Folder in which all images are stored
+    pub output_dir: PathBuf,
+    /// File format of the images
+    pub format: ImageFormat,
+    /// Configuration of the chart.
+    /// Bitmap images only use the [image_size](ChartConfig::image_size).
+    pub chart: ChartConfig,
+    /// Index of the species which is drawn
+    pub species: usize,
+    /// Colormap used to draw the values of the species
+    pub colormap: Colormap,
+    /// Values which correspond to the lower and upper end of the colormap.
+    /// If not specified, the range is determined from all stored values.
+    pub value_range: Option<(f64, f64)>,
+    /// Draw a colorbar at the right edge of every image
+    pub colorbar: bool,
+    /// If specified, combine all images into a video `fields.mp4` with the given frames per
+    /// second by invoking `ffmpeg`.
+    /// This is only supported for [ImageFormat::Png].
+    pub video_fps: Option<u32>,
+}
+
+impl FieldPlotSettings {
+    /// Default settings which store images in the given folder.
+    pub fn new(output_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            output_dir: output_dir.into(),
+            format: ImageFormat::Png,
+            chart: ChartConfig::new(800),
+            species: 0,
+            colormap: Colormap::Viridis,
+            value_range: None,
+            colorbar: true,
+            video_fps: None,
+        }
+    }
+}
+
+/// Draws the rectangles of the field onto the plotting root.
+fn draw_field<Db>(
+    root: &mut DrawingArea<Db, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+    rectangles: &[([f64; 2], [f64; 2], f64)],
+    colormap: Colormap,
+    value_range: (f64, f64),
+) -> Result<(), DrawingError>
+where
+    Db: DrawingBackend,
+{
+    for (lower, upper, value) in rectangles.iter() {
+        let [r, g, b] = colormap.color(*value, value_range);
+        root.draw(&Rectangle::new(
+            [(lower[0], lower[1]), (upper[0], upper[1])],
+            RGBColor(r, g, b).filled(),
+        ))?;
+    }
+    Ok(())
+}
+
+/// Combines the images `frame_000000.png, ...` in the given folder to a video.
+fn create_video(output_dir: &Path, fps: u32) -> Result<PathBuf, SimulationError> {
+    let video = output_dir.join("fields.mp4");
+    let status = std::process::Command::new("ffmpeg")
+        .arg("-y")
+        .args(["-framerate", &fps.to_string()])
+        .arg("-i")
+        .arg(output_dir.join("frame_%06d.png"))
+        // Encoders require images with even dimensions
+        .args(["-vf", "pad=ceil(iw/2)*2:ceil(ih/2)*2"])
+        .args(["-pix_fmt", "yuv420p"])
+        .arg(&video)
+        .status()?;
+    if !status.success() {
+        return Err(SimulationError::IoError(std::io::Error::other(format!(
+            "ffmpeg exited with {status}"
+        ))));
+    }
+    Ok(video)
+}
+
+/// Draws one frame consisting of fields, cells and colorbar.
+fn draw_frame<Db, C, S>(
+    mut root: DrawingArea<Db, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+    subdomains: &[S],
+    cells: &[C],
+    settings: &FieldPlotSettings,
+    value_range: (f64, f64),
+) -> Result<(), DrawingError>
+where
+    Db: DrawingBackend,
+    C: PlotSelf,
+    S: PlotField,
+{
+    for subdomain in subdomains.iter() {
+        draw_field(
+            &mut root,
+            &subdomain.field_rectangles(settings.species),
+            settings.colormap,
+            value_range,
+        )?;
+    }
+    for cell in cells.iter() {
+        cell.plot_self(&mut root)?;
+    }
+    if settings.colorbar {
+        let label = format!("species {}", settings.species);
+        let labels = match settings.format {
+            ImageFormat::Png => None,
+            ImageFormat::Svg => Some((label.as_str(), value_range)),
+        };
+        draw_colorbar(&root, settings.colormap, labels)?;
+    }
+    root.present()?;
+    Ok(())
+}
+
+/// Draws the extracellular fields of all subdomains beneath the cells for every saved
+/// iteration.
+///
+/// One image `frame_000000.png, frame_000001.png, ...` is created for every iteration at which
+/// subdomains were stored.
+/// The file extension is determined by the [FieldPlotSettings::format].
+/// The [PlotField] trait determines how the field of the selected
+/// [species](FieldPlotSettings::species) is drawn while cells draw themselves via the
+/// [PlotSelf] trait.
+/// All images use the same colormap range such that they can be compared to each other.
+///
+/// Returns the paths of all created images followed by the path of the video if
+/// [FieldPlotSettings::video_fps] was specified.
+pub fn plot_fields_with_cells<D, C, A, S, E>(
+    domain: &D,
+    storage: &StorageAccess<(CellBox<C>, A), S, E>,
+    settings: &FieldPlotSettings,
+) -> Result<Vec<PathBuf>, SimulationError>
+where
+    D: CreatePlottingRoot,
+    C: PlotSelf + Clone + for<'a> Deserialize<'a>,
+    A: Clone + for<'a> Deserialize<'a>,
+    S: PlotField + Clone + for<'a> Deserialize<'a>,
+{
+    if settings.video_fps.is_some() && settings.format != ImageFormat::Png {
+        return Err(SimulationError::IoError(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "videos can only be created from png images",
+        )));
+    }
+    std::fs::create_dir_all(&settings.output_dir)?;
+    let mut iterations = storage.subdomains.get_all_iterations()?;
+    iterations.sort();
+
+    // Determine the range of values from all subdomains and iterations
+    let value_range = match settings.value_range {
+        Some(range) => range,
+        None => {
+            let mut range = (f64::INFINITY, f64::NEG_INFINITY);
+            for iteration in iterations.iter() {
+                for (_, subdomain) in storage
+                    .subdomains
+                    .load_all_elements_at_iteration(*iteration)?
+                {
+                    for (_, _, value) in subdomain.field_rectangles(settings.species) {
+                        range = (range.0.min(value), range.1.max(value));
+                    }
+                }
+            }
+            match range.0 < range.1 {
+                true => range,
+                false => (range.0.min(0.0), range.0.max(0.0) + 1.0),
+            }
+        }
+    };
+
+    let mut paths = Vec::with_capacity(iterations.len() + 1);
+    for (n, iteration) in iterations.iter().enumerate() {
+        let path = settings
+            .output_dir
+            .join(format!("frame_{n:06}.{}", settings.format.extension()));
+        let mut subdomains: Vec<_> = storage
+            .subdomains
+            .load_all_elements_at_iteration(*iteration)?
+            .into_iter()
+            .collect();
+        subdomains.sort_by_key(|(index, _)| *index);
+        let subdomains: Vec<_> = subdomains.into_iter().map(|(_, s)| s).collect();
+        let cells: Vec<_> = storage
+            .cells
+            .load_all_elements_at_iteration(*iteration)?
+            .into_values()
+            .map(|(cbox, _)| cbox.cell)
+            .collect();
+        match settings.format {
+            ImageFormat::Png => draw_frame(
+                domain.create_bitmap_root(settings.chart.image_size, &path)?,
+                &subdomains,
+                &cells,
+                settings,
+                value_range,
+            )?,
+            ImageFormat::Svg => draw_frame(
+                domain.create_svg_root(&settings.chart, &path)?,
+                &subdomains,
+                &cells,
+                settings,
+                value_range,
+            )?,
+        }
+        paths.push(path);
+    }
+
+    if let Some(fps) = settings.video_fps {
+        paths.push(create_video(&settings.output_dir, fps)?);
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod test_field_plotting {
+    use super::*;
+    use plotters::prelude::{BitMapBackend, ChartBuilder, IntoDrawingArea};
+
+    #[test]
+    fn draw_field_and_colorbar() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("field.png");
+        {
+            let area = BitMapBackend::new(&path, (100, 100)).into_drawing_area();
+            let chart = ChartBuilder::on(&area)
+                .build_cartesian_2d(0.0..2.0, 0.0..1.0)
+                .unwrap();
+            let mut root = chart.plotting_area().clone();
+            let rectangles = vec![([0.0, 0.0], [1.0, 1.0], 0.0), ([1.0, 0.0], [2.0, 1.0], 3.0)];
+            draw_field(&mut root, &rectangles, Colormap::Viridis, (0.0, 2.0)).unwrap();
+            draw_colorbar(&root, Colormap::Viridis, None).unwrap();
+            root.present().unwrap();
+        }
+        assert!(path.exists());
+    }
+}
@@ -0,0 +1,48 @@
+//! Visualize results of simulations which were run with the [chili](crate::backend::chili)
+//! backend.
+//!
+//! Results are loaded from the [StorageAccess](crate::backend::chili::StorageAccess) of a
+//! simulation and drawn onto a plotting root created by the domain via the
+//! [CreatePlottingRoot](cellular_raza_concepts::CreatePlottingRoot) trait.
+//!
+//! | Function | Description |
+//! | --- | --- |
+//! | [plot_fields_with_cells] | Heatmaps of extracellular fields beneath cells |
+//! | [plot_simulation] | Cells drawn as glyphs via the [PlotCell](cellular_raza_concepts::PlotCell) trait |
+//! | [plot_simulation_colored] | Cells colored by a scalar of their state via [CellColoring] |
+//!
+//! Values are translated into colors by one of the available [Colormap]s.
+//! Images are either stored as bitmaps or as vector graphics depending on the [ImageFormat].
+//!
+//! This module requires the `plotting` feature.
+
+mod cells;
+mod colormap;
+mod fields;
+
+pub use cells::*;
+pub use colormap::*;
+pub use fields::*;
+
+use serde::{Deserialize, Serialize};
+
+/// File format of the images created by this module
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+pub enum ImageFormat {
+    /// Bitmap images
+    #[default]
+    Png,
+    /// Vector graphics which can be converted to PDF by external tools.
+    /// See [CreatePlottingRoot](cellular_raza_concepts::CreatePlottingRoot).
+    Svg,
+}
+
+impl ImageFormat {
+    /// File extension without leading dot
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Svg => "svg",
+        }
+    }
+}
@@ -9,9 +9,14 @@ use uniquevec::UniqueVec;
 use tracing::instrument;
 
 use super::memory_storage::MemoryStorageInterface;
+#[cfg(feature = "s3")]
+use super::remote::RemoteStorageInterface;
 use super::ron::RonStorageInterface;
 use super::serde_json::JsonStorageInterface;
+#[cfg(feature = "sled")]
 use super::sled_database::SledStorageInterface;
+#[cfg(feature = "sqlite")]
+use super::sqlite::SqliteStorageInterface;
 
 /// Error related to storing and reading elements
 #[derive(Debug)]
@@ -25,15 +30,27 @@ pub enum StorageError {
     /// Generic error related to deserialization in the [ron] crate.
     RonSpannedError(ron::error::SpannedError),
     /// Generic error related to the [sled] database.
+    #[cfg(feature = "sled")]
     SledError(sled::Error),
+    /// Generic error related to the [SQLite](https://sqlite.org) database.
+    #[cfg(feature = "sqlite")]
+    SqliteError(rusqlite::Error),
+    /// Error related to remote object storage such as S3.
+    #[cfg(feature = "s3")]
+    ObjectStoreError(object_store::Error),
     /// Generic serialization error thrown by the [bincode] library.
     SerializeError(Box<bincode::ErrorKind>),
-    /// Initialization error mainly used for initialization of databases such as [sled].
+    /// Initialization error mainly used for initialization of databases such as
+    /// [sled](https://docs.rs/sled/latest/sled/).
     InitError(String),
     /// Error when parsing file/folder names.
     ParseIntError(std::num::ParseIntError),
     /// Generic Utf8 error.
     Utf8Error(std::str::Utf8Error),
+    /// Error related to handing off elements to a [WriteBehindStorage](super::WriteBehindStorage).
+    WriteBehindError(String),
+    /// Stored results do not match the expected [SchemaHeader](super::SchemaHeader).
+    SchemaError(String),
 }
 
 impl From<serde_json::Error> for StorageError {
@@ -54,12 +71,27 @@ impl From<ron::Error> for StorageError {
     }
 }
 
+#[cfg(feature = "sled")]
 impl From<sled::Error> for StorageError {
     fn from(err: sled::Error) -> Self {
         StorageError::SledError(err)
     }
 }
 
+#[cfg(feature = "sqlite")]
+impl From<rusqlite::Error> for StorageError {
+    fn from(err: rusqlite::Error) -> Self {
+        StorageError::SqliteError(err)
+    }
+}
+
+#[cfg(feature = "s3")]
+impl From<object_store::Error> for StorageError {
+    fn from(err: object_store::Error) -> Self {
+        StorageError::ObjectStoreError(err)
+    }
+}
+
 impl From<Box<bincode::ErrorKind>> for StorageError {
     fn from(err: Box<bincode::ErrorKind>) -> Self {
         StorageError::SerializeError(err)
@@ -90,12 +122,19 @@ impl Display for StorageError {
             StorageError::SerdeJsonError(message) => write!(f, "{}", message),
             StorageError::RonError(message) => write!(f, "{}", message),
             StorageError::RonSpannedError(message) => write!(f, "{}", message),
+            #[cfg(feature = "sled")]
             StorageError::SledError(message) => write!(f, "{}", message),
+            #[cfg(feature = "sqlite")]
+            StorageError::SqliteError(message) => write!(f, "{}", message),
+            #[cfg(feature = "s3")]
+            StorageError::ObjectStoreError(message) => write!(f, "{}", message),
             StorageError::SerializeError(message) => write!(f, "{}", message),
             StorageError::IoError(message) => write!(f, "{}", message),
             StorageError::InitError(message) => write!(f, "{}", message),
             StorageError::Utf8Error(message) => write!(f, "{}", message),
             StorageError::ParseIntError(message) => write!(f, "{}", message),
+            StorageError::WriteBehindError(message) => write!(f, "{}", message),
+            StorageError::SchemaError(message) => write!(f, "{}", message),
         }
     }
 }
@@ -104,14 +143,18 @@ impl Error for StorageError {}
 
 /// Define how to store results of the simulation.
 ///
-/// We currently support saving results in a [sled] database, or as a json file by using
-/// [serde_json].
+/// We currently support saving results in a [sled](https://docs.rs/sled/latest/sled/) database,
+/// or as a json file by using [serde_json].
+/// The [sled](https://docs.rs/sled/latest/sled/) options are only available when the `sled`
+/// feature is enabled.
+/// Otherwise, trying to open them will return a [StorageError::InitError].
 #[cfg_attr(feature = "pyo3", pyo3::pyclass(eq, eq_int))]
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 pub enum StorageOption {
-    /// Save results as [sled] database.
+    /// Save results as [sled](https://docs.rs/sled/latest/sled/) database.
     Sled,
-    /// Save results as [sled] database but remove them when dropping the struct
+    /// Save results as [sled](https://docs.rs/sled/latest/sled/) database but remove them when
+    /// dropping the struct
     SledTemp,
     /// Save results as [json](https://www.json.org/json-en.html) file.
     SerdeJson,
@@ -121,6 +164,11 @@ pub enum StorageOption {
     Ron,
     /// A [std::collections::HashMap](HashMap) based memory storage.
     Memory,
+    /// Save results in indexed tables of an [SQLite](https://sqlite.org) database.
+    Sqlite,
+    /// Stream results to remote object storage such as S3.
+    /// Requires [StorageBuilder::remote] to be set.
+    Remote,
 }
 
 impl StorageOption {
@@ -147,6 +195,7 @@ pub struct CombinedSaveFormat<Id, Element> {
 /// Define how batches of elements and identifiers are saved when being serialized.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct BatchSaveFormat<Id, Element> {
+    /// All elements of the batch together with their identifiers
     pub(super) data: Vec<CombinedSaveFormat<Id, Element>>,
 }
 
@@ -154,15 +203,35 @@ pub struct BatchSaveFormat<Id, Element> {
 /// It can load resources from one storage aspect and will
 #[derive(Clone, Debug)]
 pub struct StorageManager<Id, Element> {
+    /// Storage options in the order in which they are used for loading
     storage_priority: UniqueVec<StorageOption>,
+    /// Builder which was used to initialize this manager
     builder: StorageBuilder<true>,
+    /// Index of this manager which distinguishes it from other threads
     instance: u64,
+    /// Header of the stored results if they are persistent
+    schema_header: Option<super::SchemaHeader>,
+    /// Elements which were stored previously when encoding deltas
+    delta: super::delta::DeltaState<Id>,
 
+    /// Database which persists results
+    #[cfg(feature = "sled")]
     sled_storage: Option<SledStorageInterface<Id, Element>>,
+    /// Database which is deleted once the simulation has finished
+    #[cfg(feature = "sled")]
     sled_temp_storage: Option<SledStorageInterface<Id, Element, true>>,
+    /// Stores results in json files
     json_storage: Option<StorageWrapper<JsonStorageInterface<Id, Element>>>,
+    /// Stores results in ron files
     ron_storage: Option<StorageWrapper<RonStorageInterface<Id, Element>>>,
+    /// Keeps results in memory
     memory_storage: Option<MemoryStorageInterface<Id, Element>>,
+    /// Stores results in an sqlite database
+    #[cfg(feature = "sqlite")]
+    sqlite_storage: Option<SqliteStorageInterface<Id, Element>>,
+    /// Uploads results to a remote object store
+    #[cfg(feature = "s3")]
+    remote_storage: Option<RemoteStorageInterface<Id, Element>>,
 }
 
 /// Used to construct a [StorageManager]
@@ -182,13 +251,34 @@ pub struct StorageManager<Id, Element> {
 /// ```
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct StorageBuilder<const INIT: bool = false> {
+    /// Folder in which results are stored
     location: std::path::PathBuf,
+    /// Storage options in the order in which they are used for loading
     priority: UniqueVec<StorageOption>,
+    /// Appended to the path of stored results
     suffix: std::path::PathBuf,
+    /// Store results in a folder named by the current date
     #[cfg(feature = "timestamp")]
     add_date: bool,
+    /// Formatted date which is determined when initializing the builder
     #[cfg(feature = "timestamp")]
     date: std::path::PathBuf,
+    /// Stores results on a dedicated thread
+    #[serde(default)]
+    write_behind: Option<super::WriteBehind>,
+    /// Version of the stored types
+    #[serde(default)]
+    schema_version: Option<u32>,
+    /// Open results regardless of their schema
+    #[serde(default)]
+    tolerant: bool,
+    /// Only store elements which have changed since the last keyframe
+    #[serde(default)]
+    delta_encoding: Option<super::DeltaEncoding>,
+    /// Uploads results to a remote object store
+    #[cfg(feature = "s3")]
+    #[serde(default)]
+    remote: Option<super::RemoteStorage>,
 }
 
 impl<const INIT: bool> StorageBuilder<INIT> {
@@ -227,6 +317,95 @@ impl<const INIT: bool> StorageBuilder<INIT> {
     pub fn get_add_date(&self) -> bool {
         self.add_date
     }
+
+    /// Store results on a dedicated thread instead of inside the simulation loop.
+    /// See [WriteBehindStorage](super::WriteBehindStorage).
+    pub fn write_behind(self, write_behind: impl Into<Option<super::WriteBehind>>) -> Self {
+        Self {
+            write_behind: write_behind.into(),
+            ..self
+        }
+    }
+
+    /// Get the settings for storing results on a dedicated thread
+    pub fn get_write_behind(&self) -> Option<super::WriteBehind> {
+        self.write_behind.clone()
+    }
+
+    /// Version of the stored elements which is recorded in the [SchemaHeader](super::SchemaHeader).
+    ///
+    /// This version should be increased whenever the stored types change.
+    /// When opening existing results, their version needs to match unless the reader is
+    /// [tolerant](StorageBuilder::tolerant).
+    pub fn schema_version(self, schema_version: impl Into<Option<u32>>) -> Self {
+        Self {
+            schema_version: schema_version.into(),
+            ..self
+        }
+    }
+
+    /// Get the version of the stored elements
+    pub fn get_schema_version(&self) -> Option<u32> {
+        self.schema_version
+    }
+
+    /// Open results regardless of their [SchemaHeader](super::SchemaHeader).
+    pub fn tolerant(self, tolerant: bool) -> Self {
+        Self { tolerant, ..self }
+    }
+
+    /// Get information if results are opened regardless of their schema
+    pub fn get_tolerant(&self) -> bool {
+        self.tolerant
+    }
+
+    /// Only store elements which changed between keyframes.
+    /// See [DeltaEncoding](super::DeltaEncoding).
+    ///
+    /// Elements are compared when storing batches.
+    /// When loading, all elements at an iteration are reconstructed from the previous keyframe.
+    pub fn delta_encoding(self, delta_encoding: impl Into<Option<super::DeltaEncoding>>) -> Self {
+        Self {
+            delta_encoding: delta_encoding.into(),
+            ..self
+        }
+    }
+
+    /// Get the settings for storing only changed elements
+    pub fn get_delta_encoding(&self) -> Option<super::DeltaEncoding> {
+        self.delta_encoding.clone()
+    }
+
+    /// Location of the object store used by [StorageOption::Remote].
+    ///
+    /// Results are placed below the given url followed by the date and suffix of this builder.
+    /// This setting is only available with the `s3` feature.
+    #[cfg(feature = "s3")]
+    pub fn remote(self, remote: impl Into<Option<super::RemoteStorage>>) -> Self {
+        Self {
+            remote: remote.into(),
+            ..self
+        }
+    }
+
+    /// Get the location of the object store
+    #[cfg(feature = "s3")]
+    pub fn get_remote(&self) -> Option<super::RemoteStorage> {
+        self.remote.clone()
+    }
+
+    /// Checks if any of the [StorageOption]s stores results on disk.
+    pub(super) fn is_persistent(&self) -> bool {
+        self.priority.iter().any(|option| {
+            matches!(
+                option,
+                StorageOption::Sled
+                    | StorageOption::SerdeJson
+                    | StorageOption::Ron
+                    | StorageOption::Sqlite
+            )
+        })
+    }
 }
 
 impl StorageBuilder<false> {
@@ -246,6 +425,12 @@ impl StorageBuilder<false> {
             add_date: true,
             #[cfg(feature = "timestamp")]
             date: "".into(),
+            write_behind: None,
+            schema_version: None,
+            tolerant: false,
+            delta_encoding: None,
+            #[cfg(feature = "s3")]
+            remote: None,
         }
     }
 
@@ -272,6 +457,12 @@ impl StorageBuilder<false> {
             add_date: self.add_date,
             #[cfg(feature = "timestamp")]
             date: date.into(),
+            write_behind: self.write_behind,
+            schema_version: self.schema_version,
+            tolerant: self.tolerant,
+            delta_encoding: self.delta_encoding,
+            #[cfg(feature = "s3")]
+            remote: self.remote,
         }
     }
 
@@ -327,6 +518,12 @@ impl StorageBuilder<true> {
             add_date: self.add_date,
             #[cfg(feature = "timestamp")]
             date: "".into(),
+            write_behind: self.write_behind,
+            schema_version: self.schema_version,
+            tolerant: self.tolerant,
+            delta_encoding: self.delta_encoding,
+            #[cfg(feature = "s3")]
+            remote: self.remote,
         }
     }
 }
@@ -350,12 +547,20 @@ impl<Id, Element> StorageManager<Id, Element> {
         instance: u64,
     ) -> Result<Self, StorageError> {
         let location = storage_builder.get_full_path();
+        let schema_header =
+            super::SchemaHeader::open_or_create::<Element>(&storage_builder, &location, instance)?;
 
+        #[cfg(feature = "sled")]
         let mut sled_storage = None;
+        #[cfg(feature = "sled")]
         let mut sled_temp_storage = None;
         let mut json_storage = None;
         let mut ron_storage = None;
         let mut memory_storage = None;
+        #[cfg(feature = "sqlite")]
+        let mut sqlite_storage = None;
+        #[cfg(feature = "s3")]
+        let mut remote_storage = None;
         for storage_variant in storage_builder.priority.iter() {
             match storage_variant {
                 StorageOption::SerdeJson => {
@@ -368,6 +573,7 @@ impl<Id, Element> StorageManager<Id, Element> {
                         )?,
                     ));
                 }
+                #[cfg(feature = "sled")]
                 StorageOption::Sled => {
                     sled_storage =
                         Some(SledStorageInterface::<Id, Element, false>::open_or_create(
@@ -375,6 +581,7 @@ impl<Id, Element> StorageManager<Id, Element> {
                             instance,
                         )?);
                 }
+                #[cfg(feature = "sled")]
                 StorageOption::SledTemp => {
                     sled_temp_storage =
                         Some(SledStorageInterface::<Id, Element, true>::open_or_create(
@@ -382,6 +589,13 @@ impl<Id, Element> StorageManager<Id, Element> {
                             instance,
                         )?);
                 }
+                #[cfg(not(feature = "sled"))]
+                StorageOption::Sled | StorageOption::SledTemp => {
+                    return Err(StorageError::InitError(format!(
+                        "storage option {:?} requires the \"sled\" feature",
+                        storage_variant
+                    )));
+                }
                 StorageOption::Ron => {
                     ron_storage = Some(StorageWrapper(
                         RonStorageInterface::<Id, Element>::open_or_create(
@@ -398,18 +612,68 @@ impl<Id, Element> StorageManager<Id, Element> {
                         instance,
                     )?);
                 }
+                #[cfg(feature = "sqlite")]
+                StorageOption::Sqlite => {
+                    sqlite_storage = Some(SqliteStorageInterface::<Id, Element>::open_or_create(
+                        &location.to_path_buf().join("sqlite"),
+                        instance,
+                    )?);
+                }
+                #[cfg(not(feature = "sqlite"))]
+                StorageOption::Sqlite => {
+                    return Err(StorageError::InitError(
+                        "storage option Sqlite requires the \"sqlite\" feature".into(),
+                    ));
+                }
+                #[cfg(feature = "s3")]
+                StorageOption::Remote => {
+                    let settings = storage_builder.get_remote().ok_or_else(|| {
+                        StorageError::InitError(
+                            "storage option Remote requires remote settings".into(),
+                        )
+                    })?;
+                    // Use the same relative path as for local results
+                    let path = location
+                        .strip_prefix(&storage_builder.location)
+                        .unwrap_or(&location);
+                    remote_storage = Some(RemoteStorageInterface::<Id, Element>::open(
+                        &settings, path, instance,
+                    )?);
+                }
+                #[cfg(not(feature = "s3"))]
+                StorageOption::Remote => {
+                    return Err(StorageError::InitError(
+                        "storage option Remote requires the \"s3\" feature".into(),
+                    ));
+                }
             }
         }
+        let delta = super::delta::DeltaState::new(
+            storage_builder.get_delta_encoding(),
+            match storage_builder.is_persistent() {
+                true => Some(location.join(super::delta::DeltaState::<Id>::FOLDER)),
+                false => None,
+            },
+            instance,
+        );
         let manager = StorageManager {
             storage_priority: storage_builder.priority.clone(),
             builder: storage_builder.clone(),
             instance,
+            schema_header,
+            delta,
 
+            #[cfg(feature = "sled")]
             sled_storage,
+            #[cfg(feature = "sled")]
             sled_temp_storage,
             json_storage,
             ron_storage,
             memory_storage,
+            #[cfg(feature = "sqlite")]
+            sqlite_storage,
+            #[cfg(feature = "s3")]
+            remote_storage,
         };
 
         Ok(manager)
@@ -428,8 +692,17 @@ impl<Id, Element> StorageManager<Id, Element> {
     pub fn get_instance(&self) -> u64 {
         self.instance
     }
+
+    /// Header of the stored results.
+    ///
+    /// Results which are only stored in memory or which predate the
+    /// [SchemaHeader](super::SchemaHeader) do not have a header.
+    pub fn get_schema_header(&self) -> Option<super::SchemaHeader> {
+        self.schema_header.clone()
+    }
 }
 
+/// Executes the given function for every storage option which is enabled
 macro_rules! exec_for_all_storage_options(
     (@internal $self:ident, $storage_option:ident, $field:ident, $function:ident, $($args:tt)*) => {
         {
@@ -448,20 +721,32 @@ macro_rules! exec_for_all_storage_options(
         }
     };
     (all mut $self:ident, $function:ident, $($args:tt)*) => {
+        #[cfg(feature = "sled")]
         exec_for_all_storage_options!(mut $self, sled_storage, $function, $($args)*);
+        #[cfg(feature = "sled")]
         exec_for_all_storage_options!(mut $self, sled_temp_storage, $function, $($args)*);
         exec_for_all_storage_options!(mut $self, json_storage, $function, $($args)*);
         exec_for_all_storage_options!(mut $self, ron_storage, $function, $($args)*);
         exec_for_all_storage_options!(mut $self, memory_storage, $function, $($args)*);
+        #[cfg(feature = "sqlite")]
+        exec_for_all_storage_options!(mut $self, sqlite_storage, $function, $($args)*);
+        #[cfg(feature = "s3")]
+        exec_for_all_storage_options!(mut $self, remote_storage, $function, $($args)*);
     };
     ($self:ident, $priority:ident, $function:ident, $($args:tt)*) => {
         match $priority {
+            #[cfg(feature = "sled")]
             StorageOption::Sled => exec_for_all_storage_options!(
                 @internal $self, Sled, sled_storage, $function, $($args)*
             ),
+            #[cfg(feature = "sled")]
             StorageOption::SledTemp => exec_for_all_storage_options!(
                 @internal $self, SledTemp, sled_temp_storage, $function, $($args)*
             ),
+            #[cfg(not(feature = "sled"))]
+            StorageOption::Sled | StorageOption::SledTemp => Err(StorageError::InitError(
+                "sled storage requires the \"sled\" feature".into(),
+            ))?,
             StorageOption::SerdeJson => exec_for_all_storage_options!(
                 @internal $self, SerdeJson, json_storage, $function, $($args)*
             ),
@@ -471,6 +756,22 @@ macro_rules! exec_for_all_storage_options(
             StorageOption::Memory => exec_for_all_storage_options!(
                 @internal $self, Memory, memory_storage, $function, $($args)*
             ),
+            #[cfg(feature = "sqlite")]
+            StorageOption::Sqlite => exec_for_all_storage_options!(
+                @internal $self, Sqlite, sqlite_storage, $function, $($args)*
+            ),
+            #[cfg(not(feature = "sqlite"))]
+            StorageOption::Sqlite => Err(StorageError::InitError(
+                "sqlite storage requires the \"sqlite\" feature".into(),
+            ))?,
+            #[cfg(feature = "s3")]
+            StorageOption::Remote => exec_for_all_storage_options!(
+                @internal $self, Remote, remote_storage, $function, $($args)*
+            ),
+            #[cfg(not(feature = "s3"))]
+            StorageOption::Remote => Err(StorageError::InitError(
+                "remote storage requires the \"s3\" feature".into(),
+            ))?,
         }
     }
 );
@@ -510,23 +811,28 @@ where
         Element: 'a + Serialize,
         I: Clone + IntoIterator<Item = (&'a Id, &'a Element)>,
     {
+        // Only keep elements which changed if delta encoding is enabled
+        let identifiers_elements = self
+            .delta
+            .select(iteration, identifiers_elements.into_iter().collect())?;
         exec_for_all_storage_options!(
             all mut self,
             store_batch_elements,
             iteration,
-            identifiers_elements.clone()
+            identifiers_elements.iter().copied()
         );
         Ok(())
     }
 }
 
-impl<Id, Element> StorageInterfaceLoad<Id, Element> for StorageManager<Id, Element>
+impl<Id, Element> StorageManager<Id, Element>
 where
     Id: core::hash::Hash + core::cmp::Eq + Clone,
     Element: Clone,
 {
+    /// Loads the element as it was stored at this iteration without applying delta frames.
     #[allow(unused)]
-    fn load_single_element(
+    fn load_stored_element(
         &self,
         iteration: u64,
         identifier: &Id,
@@ -535,7 +841,7 @@ where
         Id: Serialize + for<'a> Deserialize<'a>,
         Element: for<'a> Deserialize<'a>,
     {
-        for priority in self.storage_priority.iter() {
+        if let Some(priority) = self.storage_priority.iter().next() {
             return exec_for_all_storage_options!(
                 self,
                 priority,
@@ -547,16 +853,17 @@ where
         Ok(None)
     }
 
+    /// Loads all elements which were stored at this iteration without applying delta frames.
     #[allow(unused)]
-    fn load_all_elements_at_iteration(
+    fn load_stored_elements_at_iteration(
         &self,
         iteration: u64,
     ) -> Result<HashMap<Id, Element>, StorageError>
     where
-        Id: std::hash::Hash + std::cmp::Eq + for<'a> Deserialize<'a>,
+        Id: for<'a> Deserialize<'a>,
         Element: for<'a> Deserialize<'a>,
     {
-        for priority in self.storage_priority.iter() {
+        if let Some(priority) = self.storage_priority.iter().next() {
             return exec_for_all_storage_options!(
                 self,
                 priority,
@@ -566,16 +873,74 @@ where
         }
         Ok(HashMap::new())
     }
+}
+
+impl<Id, Element> StorageInterfaceLoad<Id, Element> for StorageManager<Id, Element>
+where
+    Id: core::hash::Hash + core::cmp::Eq + Clone,
+    Element: Clone,
+{
+    fn load_single_element(
+        &self,
+        iteration: u64,
+        identifier: &Id,
+    ) -> Result<Option<Element>, StorageError>
+    where
+        Id: Serialize + for<'a> Deserialize<'a>,
+        Element: for<'a> Deserialize<'a>,
+    {
+        match self.delta.frames_until(iteration)? {
+            // Go back until the element was last stored or removed
+            Some(frames) => {
+                for (iteration, removed) in frames.into_iter().rev() {
+                    if let Some(element) = self.load_stored_element(iteration, identifier)? {
+                        return Ok(Some(element));
+                    }
+                    if removed.contains(identifier) {
+                        return Ok(None);
+                    }
+                }
+                Ok(None)
+            }
+            None => self.load_stored_element(iteration, identifier),
+        }
+    }
+
+    fn load_all_elements_at_iteration(
+        &self,
+        iteration: u64,
+    ) -> Result<HashMap<Id, Element>, StorageError>
+    where
+        Id: std::hash::Hash + std::cmp::Eq + for<'a> Deserialize<'a>,
+        Element: for<'a> Deserialize<'a>,
+    {
+        match self.delta.frames_until(iteration)? {
+            // Apply all frames since the last keyframe
+            Some(frames) => {
+                let mut elements = HashMap::new();
+                for (iteration, removed) in frames {
+                    elements.retain(|identifier, _| !removed.contains(identifier));
+                    elements.extend(self.load_stored_elements_at_iteration(iteration)?);
+                }
+                Ok(elements)
+            }
+            None => self.load_stored_elements_at_iteration(iteration),
+        }
+    }
 
     fn get_all_iterations(&self) -> Result<Vec<u64>, StorageError> {
-        for priority in self.storage_priority.iter() {
-            return exec_for_all_storage_options!(self, priority, get_all_iterations,);
+        let mut iterations = self.delta.iterations()?;
+        if let Some(priority) = self.storage_priority.iter().next() {
+            let stored: Vec<u64> =
+                exec_for_all_storage_options!(self, priority, get_all_iterations,)?;
+            iterations.extend(stored);
         }
-        Ok(Vec::new())
+        Ok(iterations.into_iter().collect())
     }
 }
 
 /// The mode in which to generate paths and store results.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum StorageMode {
     /// Save one element to a single file
     Single,
@@ -584,7 +949,8 @@ pub enum StorageMode {
 }
 
 impl StorageMode {
-    fn to_str(&self) -> &str {
+    /// Prefix of files which were stored in this mode.
+    pub(super) fn to_str(self) -> &'static str {
         match self {
             Self::Single => "single",
             Self::Batch => "batch",
@@ -698,6 +1064,7 @@ pub trait FileBasedStorage<Id, Element> {
     }
 }
 
+/// Implements the storage interfaces for any [FileBasedStorage]
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub(crate) struct StorageWrapper<T>(pub(crate) T);
 
@@ -792,6 +1159,58 @@ pub trait StorageInterfaceStore<Id, Element> {
         I: Clone + IntoIterator<Item = (&'a Id, &'a Element)>;
 }
 
+/// Object-safe destination for elements which are stored during a simulation.
+///
+/// Backends hold their storage as `Box<dyn StorageSink<Id, Element>>` such that they do not
+/// depend on a particular storage solution.
+/// Every type which implements [StorageInterfaceStore] such as the [StorageManager] is also a
+/// [StorageSink].
+///
+/// ```
+/// # use cellular_raza_core::storage::*;
+/// let mut sink: Box<dyn StorageSink<u32, f64>> = Box::new(
+///     MemoryStorageInterface::<u32, f64>::open_or_create(std::path::Path::new(""), 0)?,
+/// );
+/// sink.store_batch(3, &[(&0, &1.0), (&1, &2.0)])?;
+/// let memory = sink
+///     .into_any()
+///     .downcast::<MemoryStorageInterface<u32, f64>>()
+///     .unwrap();
+/// assert_eq!(memory.load_single_element(3, &1)?, Some(2.0));
+/// # Ok::<(), StorageError>(())
+/// ```
+pub trait StorageSink<Id, Element>: Send {
+    /// Stores all given elements with their identifiers at the same iteration.
+    fn store_batch(
+        &mut self,
+        iteration: u64,
+        identifiers_elements: &[(&Id, &Element)],
+    ) -> Result<(), StorageError>;
+
+    /// Converts the sink such that the underlying storage solution can be recovered by
+    /// [downcasting](Box::downcast).
+    fn into_any(self: Box<Self>) -> Box<dyn std::any::Any>;
+}
+
+impl<Id, Element, S> StorageSink<Id, Element> for S
+where
+    Id: Serialize,
+    Element: Serialize,
+    S: StorageInterfaceStore<Id, Element> + Send + 'static,
+{
+    fn store_batch(
+        &mut self,
+        iteration: u64,
+        identifiers_elements: &[(&Id, &Element)],
+    ) -> Result<(), StorageError> {
+        self.store_batch_elements(iteration, identifiers_elements.iter().copied())
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn std::any::Any> {
+        self
+    }
+}
+
 /// Handles loading of elements
 pub trait StorageInterfaceLoad<Id, Element> {
     // TODO decide if these functions should be &mut self instead of &self
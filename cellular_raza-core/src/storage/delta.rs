@@ -0,0 +1,424 @@
+use super::concepts::StorageError;
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::io::{BufRead, Write};
+
+/// Settings for storing only elements which changed since the last keyframe.
+///
+/// Every `keyframe_interval` frames, all elements are stored.
+/// In between, an element is only stored if it is new or if any of its numbers changed by more
+/// than `epsilon` compared to the last time it was stored.
+/// Elements which are no longer present, for example because a cell was removed, are recorded
+/// in a [DeltaFrame].
+/// Loading elements at an iteration transparently reconstructs all of them.
+/// Only batches of elements are delta encoded while single elements are always stored.
+///
+/// See [StorageBuilder::delta_encoding](super::StorageBuilder::delta_encoding).
+/// ```
+/// use cellular_raza_core::storage::*;
+/// let builder = StorageBuilder::new()
+///     .priority([StorageOption::Memory])
+///     .delta_encoding(DeltaEncoding::new(10).epsilon(0.01))
+///     .init();
+/// let mut manager = StorageManager::<usize, f64>::open_or_create(builder, 0)?;
+/// manager.store_batch_elements(0, [(&0, &1.0), (&1, &2.0)])?;
+/// // The first element barely changed and is not stored
+/// manager.store_batch_elements(1, [(&0, &1.001), (&1, &3.0)])?;
+/// assert_eq!(manager.load_single_element(1, &0)?, Some(1.0));
+/// assert_eq!(manager.load_single_element(1, &1)?, Some(3.0));
+/// // Removed elements are not reconstructed
+/// manager.store_batch_elements(2, [(&1, &3.0)])?;
+/// assert_eq!(manager.load_all_elements_at_iteration(2)?.len(), 1);
+/// # Ok::<(), StorageError>(())
+/// ```
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct DeltaEncoding {
+    /// Number of stored frames after which all elements are stored again
+    pub keyframe_interval: usize,
+    /// Largest change of a number which is not considered a change of the element
+    pub epsilon: f64,
+}
+
+impl DeltaEncoding {
+    /// Store all elements every `keyframe_interval` frames and only changed elements in between.
+    pub fn new(keyframe_interval: usize) -> Self {
+        Self {
+            keyframe_interval,
+            epsilon: 0.0,
+        }
+    }
+
+    /// Change the `epsilon` below which numbers are considered to be unchanged.
+    pub fn epsilon(self, epsilon: f64) -> Self {
+        Self { epsilon, ..self }
+    }
+}
+
+/// Describes which elements were stored by one instance at one iteration.
+///
+/// Frames are written as json lines to the `deltas` folder next to the stored results.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct DeltaFrame<Id> {
+    /// Iteration at which the elements were stored
+    pub iteration: u64,
+    /// All elements of the instance were stored
+    pub keyframe: bool,
+    /// Elements which were previously stored but are no longer present
+    pub removed: Vec<Id>,
+}
+
+/// Only used to read the iteration of a [DeltaFrame] without knowing its identifiers.
+#[derive(Deserialize)]
+struct FrameIteration {
+    /// Iteration at which the frame was stored
+    iteration: u64,
+}
+
+/// Frames of all instances merged by their iteration.
+///
+/// An iteration is a keyframe if every instance stored a keyframe.
+type Manifest<Id> = BTreeMap<u64, (bool, HashSet<Id>)>;
+
+/// Iterations together with the elements which were removed at them
+type Frames<Id> = Vec<(u64, HashSet<Id>)>;
+
+/// Keeps track of the elements which were stored by one
+/// [StorageManager](super::StorageManager).
+#[derive(Clone, Debug)]
+pub(super) struct DeltaState<Id> {
+    /// Settings of the encoding, `None` if all elements are stored
+    encoding: Option<DeltaEncoding>,
+    /// Folder which contains the frames of all instances, `None` if results are not persistent
+    path: Option<std::path::PathBuf>,
+    /// Instance of the manager which determines the file of its frames
+    instance: u64,
+    /// Number of frames since the last keyframe
+    n_frames: usize,
+    /// Last stored state of every element
+    written: HashMap<Id, serde_json::Value>,
+    /// Frames of this instance if they are not written to a file
+    frames: Vec<DeltaFrame<Id>>,
+}
+
+/// Compares two elements while ignoring changes of numbers up to `epsilon`.
+fn differs(old: &serde_json::Value, new: &serde_json::Value, epsilon: f64) -> bool {
+    use serde_json::Value;
+    match (old, new) {
+        (Value::Number(x), Value::Number(y)) => match (x.as_f64(), y.as_f64()) {
+            (Some(x), Some(y)) => (x - y).abs() > epsilon,
+            _ => x != y,
+        },
+        (Value::Array(x), Value::Array(y)) => {
+            x.len() != y.len() || x.iter().zip(y).any(|(x, y)| differs(x, y, epsilon))
+        }
+        (Value::Object(x), Value::Object(y)) => {
+            x.len() != y.len()
+                || x.iter()
+                    .any(|(key, x)| y.get(key).is_none_or(|y| differs(x, y, epsilon)))
+        }
+        (x, y) => x != y,
+    }
+}
+
+impl<Id> DeltaState<Id> {
+    /// Name of the folder which contains the frames
+    pub(super) const FOLDER: &'static str = "deltas";
+
+    /// Creates a new state which has not stored any elements yet
+    pub(super) fn new(
+        encoding: Option<DeltaEncoding>,
+        path: Option<std::path::PathBuf>,
+        instance: u64,
+    ) -> Self {
+        Self {
+            encoding,
+            path,
+            instance,
+            n_frames: 0,
+            written: HashMap::new(),
+            frames: Vec::new(),
+        }
+    }
+
+    /// Selects the elements which need to be stored and records the frame.
+    ///
+    /// Without a [DeltaEncoding], all elements are selected.
+    pub(super) fn select<'a, Element>(
+        &mut self,
+        iteration: u64,
+        identifiers_elements: Vec<(&'a Id, &'a Element)>,
+    ) -> Result<Vec<(&'a Id, &'a Element)>, StorageError>
+    where
+        Id: core::hash::Hash + Eq + Clone + Serialize,
+        Element: Serialize,
+    {
+        let encoding = match &self.encoding {
+            Some(encoding) => encoding,
+            None => return Ok(identifiers_elements),
+        };
+        let keyframe = self.n_frames == 0;
+        self.n_frames = (self.n_frames + 1) % encoding.keyframe_interval.max(1);
+
+        let mut present = HashSet::new();
+        let mut selected = Vec::new();
+        for (identifier, element) in identifiers_elements {
+            let value = serde_json::to_value(element)?;
+            let changed = keyframe
+                || self
+                    .written
+                    .get(identifier)
+                    .is_none_or(|old| differs(old, &value, encoding.epsilon));
+            if changed {
+                self.written.insert(identifier.clone(), value);
+                selected.push((identifier, element));
+            }
+            present.insert(identifier);
+        }
+        let removed = match keyframe {
+            true => Vec::new(),
+            false => self
+                .written
+                .keys()
+                .filter(|identifier| !present.contains(identifier))
+                .cloned()
+                .collect(),
+        };
+        self.written
+            .retain(|identifier, _| present.contains(identifier));
+        self.record(DeltaFrame {
+            iteration,
+            keyframe,
+            removed,
+        })?;
+        Ok(selected)
+    }
+
+    /// Appends the frame to the file of this instance or keeps it in memory
+    fn record(&mut self, frame: DeltaFrame<Id>) -> Result<(), StorageError>
+    where
+        Id: Serialize,
+    {
+        match &self.path {
+            Some(path) => {
+                std::fs::create_dir_all(path)?;
+                let mut file = std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path.join(format!("{:03}.jsonl", self.instance)))?;
+                writeln!(file, "{}", serde_json::to_string(&frame)?)?;
+            }
+            None => self.frames.push(frame),
+        }
+        Ok(())
+    }
+
+    /// Visits every line of all files in the folder of frames.
+    fn read_lines(
+        &self,
+        mut visit: impl FnMut(&str) -> Result<(), StorageError>,
+    ) -> Result<(), StorageError> {
+        let path = match &self.path {
+            Some(path) if path.is_dir() => path,
+            _ => return Ok(()),
+        };
+        for entry in std::fs::read_dir(path)? {
+            let file_path = entry?.path();
+            if file_path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+                continue;
+            }
+            for line in std::io::BufReader::new(std::fs::File::open(file_path)?).lines() {
+                let line = line?;
+                if !line.trim().is_empty() {
+                    visit(&line)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// All iterations at which frames were recorded
+    pub(super) fn iterations(&self) -> Result<BTreeSet<u64>, StorageError> {
+        let mut iterations: BTreeSet<u64> = self.frames.iter().map(|f| f.iteration).collect();
+        self.read_lines(|line| {
+            iterations.insert(serde_json::from_str::<FrameIteration>(line)?.iteration);
+            Ok(())
+        })?;
+        Ok(iterations)
+    }
+
+    /// Merges the frames of all instances by their iteration
+    fn manifest(&self) -> Result<Manifest<Id>, StorageError>
+    where
+        Id: core::hash::Hash + Eq + Clone + for<'a> Deserialize<'a>,
+    {
+        let mut manifest = Manifest::new();
+        let mut insert = |frame: DeltaFrame<Id>| {
+            let (keyframe, removed) = manifest
+                .entry(frame.iteration)
+                .or_insert_with(|| (true, HashSet::new()));
+            *keyframe &= frame.keyframe;
+            removed.extend(frame.removed);
+        };
+        self.frames.iter().cloned().for_each(&mut insert);
+        self.read_lines(|line| {
+            insert(serde_json::from_str(line)?);
+            Ok(())
+        })?;
+        Ok(manifest)
+    }
+
+    /// Frames which need to be applied in order to reconstruct all elements at the iteration.
+    ///
+    /// Starts at the last keyframe and contains the iterations together with the removed
+    /// elements.
+    /// Returns `None` if no frame was recorded at this iteration and all elements were stored.
+    pub(super) fn frames_until(&self, iteration: u64) -> Result<Option<Frames<Id>>, StorageError>
+    where
+        Id: core::hash::Hash + Eq + Clone + for<'a> Deserialize<'a>,
+    {
+        let manifest = self.manifest()?;
+        if !manifest.contains_key(&iteration) {
+            return Ok(None);
+        }
+        let start = manifest
+            .range(..=iteration)
+            .rev()
+            .find(|(_, (keyframe, _))| *keyframe)
+            .map_or(0, |(start, _)| *start);
+        Ok(Some(
+            manifest
+                .into_iter()
+                .filter(|(i, _)| (start..=iteration).contains(i))
+                .map(|(i, (_, removed))| (i, removed))
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test_delta {
+    use super::*;
+    use crate::storage::{
+        JsonStorageInterface, StorageBuilder, StorageInterfaceLoad, StorageInterfaceOpen,
+        StorageInterfaceStore, StorageManager, StorageOption,
+    };
+
+    type Cell = (u32, [f64; 2]);
+
+    fn store(manager: &mut StorageManager<u32, Cell>, iteration: u64, cells: &[Cell]) {
+        manager
+            .store_batch_elements(iteration, cells.iter().map(|cell| (&cell.0, cell)))
+            .unwrap();
+    }
+
+    fn run(builder: StorageBuilder<true>) -> StorageManager<u32, Cell> {
+        let mut manager = StorageManager::open_or_create(builder, 0).unwrap();
+        store(&mut manager, 0, &[(0, [0.0; 2]), (1, [5.0; 2])]);
+        // Cell 0 moves slightly
+        store(&mut manager, 1, &[(0, [0.05, 0.0]), (1, [5.0; 2])]);
+        // Cell 0 moves beyond epsilon and divides
+        store(
+            &mut manager,
+            2,
+            &[(0, [0.5, 0.0]), (1, [5.0; 2]), (2, [0.5, 1.0])],
+        );
+        // Cell 1 dies
+        store(&mut manager, 3, &[(0, [0.5, 0.0]), (2, [0.5, 1.0])]);
+        // Keyframe
+        store(&mut manager, 4, &[(0, [1.0, 0.0]), (2, [0.5, 1.0])]);
+        manager
+    }
+
+    #[test]
+    fn compare_elements() {
+        let x = serde_json::json!({"pos": [1.0, 2.0], "name": "a"});
+        assert!(!differs(
+            &x,
+            &serde_json::json!({"pos": [1.05, 2.0], "name": "a"}),
+            0.1
+        ));
+        assert!(differs(
+            &x,
+            &serde_json::json!({"pos": [1.2, 2.0], "name": "a"}),
+            0.1
+        ));
+        assert!(differs(
+            &x,
+            &serde_json::json!({"pos": [1.0, 2.0], "name": "b"}),
+            0.1
+        ));
+        assert!(differs(
+            &x,
+            &serde_json::json!({"pos": [1.0], "name": "a"}),
+            0.1
+        ));
+    }
+
+    fn check_reconstruction(builder: StorageBuilder<true>) {
+        let manager = run(builder.clone());
+        assert_eq!(manager.get_all_iterations().unwrap().len(), 5);
+        let expected = [
+            vec![(0, [0.0; 2]), (1, [5.0; 2])],
+            vec![(0, [0.0; 2]), (1, [5.0; 2])],
+            vec![(0, [0.5, 0.0]), (1, [5.0; 2]), (2, [0.5, 1.0])],
+            vec![(0, [0.5, 0.0]), (2, [0.5, 1.0])],
+            vec![(0, [1.0, 0.0]), (2, [0.5, 1.0])],
+        ];
+        for (iteration, cells) in expected.into_iter().enumerate() {
+            let cells: HashMap<_, _> = cells.into_iter().map(|c| (c.0, c)).collect();
+            assert_eq!(
+                manager
+                    .load_all_elements_at_iteration(iteration as u64)
+                    .unwrap(),
+                cells
+            );
+            for identifier in 0..3 {
+                assert_eq!(
+                    manager
+                        .load_single_element(iteration as u64, &identifier)
+                        .unwrap(),
+                    cells.get(&identifier).cloned()
+                );
+            }
+        }
+        assert_eq!(manager.load_element_history(&1).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn reconstruct_frames_in_memory() {
+        let builder = StorageBuilder::new()
+            .priority([StorageOption::Memory])
+            .delta_encoding(DeltaEncoding::new(4).epsilon(0.1))
+            .init();
+        check_reconstruction(builder);
+    }
+
+    #[test]
+    fn reconstruct_frames_from_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let builder = StorageBuilder::new()
+            .priority([StorageOption::SerdeJson])
+            .location(dir.path())
+            .delta_encoding(DeltaEncoding::new(4).epsilon(0.1))
+            .init_with_date(std::path::Path::new(""));
+        check_reconstruction(builder.clone());
+
+        // Only changed cells were stored
+        let json = JsonStorageInterface::<u32, Cell>::open_or_create(&dir.path().join("json"), 1)
+            .map(super::super::concepts::StorageWrapper)
+            .unwrap();
+        assert_eq!(json.load_all_elements_at_iteration(1).unwrap().len(), 0);
+        assert_eq!(json.load_all_elements_at_iteration(2).unwrap().len(), 2);
+        assert_eq!(json.load_all_elements_at_iteration(4).unwrap().len(), 2);
+
+        // Readers without settings reconstruct frames as well
+        let reader = builder
+            .de_init()
+            .delta_encoding(None)
+            .init_with_date("".as_ref());
+        let reader = StorageManager::<u32, Cell>::open_or_create(reader, 1).unwrap();
+        assert_eq!(reader.load_all_elements_at_iteration(3).unwrap().len(), 2);
+    }
+}
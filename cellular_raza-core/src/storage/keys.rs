@@ -0,0 +1,201 @@
+use super::concepts::{StorageError, StorageMode};
+
+use serde::{Deserialize, Serialize};
+
+/// Structured key under which elements are stored in key-value databases.
+///
+/// Keys are encoded as big-endian bytes in the order `iteration | subdomain | kind`.
+/// The binary encoding therefore sorts keys by their iteration first and their subdomain
+/// second such that all elements of one iteration or of one subdomain at one iteration can be
+/// fetched with a single range scan.
+/// The subdomain corresponds to the storage instance which stored the element.
+/// Identifiers of elements are appended to the encoded key, see
+/// [StorageKey::with_identifier].
+///
+/// ```
+/// use cellular_raza_core::storage::{StorageKey, StorageMode};
+/// let key = StorageKey::new(100, 3, StorageMode::Batch);
+/// let bytes = key.to_bytes();
+/// assert_eq!(StorageKey::from_bytes(&bytes)?, key);
+/// // Keys are ordered by their iteration
+/// assert!(bytes < StorageKey::new(101, 0, StorageMode::Single).to_bytes());
+/// assert!(bytes.starts_with(&StorageKey::iteration_prefix(100)));
+/// # Ok::<(), cellular_raza_core::storage::StorageError>(())
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StorageKey {
+    /// Iteration at which the element was stored
+    pub iteration: u64,
+    /// Subdomain or storage instance which stored the element
+    pub subdomain: u64,
+    /// Whether the element was stored on its own or as part of a batch
+    pub kind: StorageMode,
+}
+
+impl StorageKey {
+    /// Number of bytes of an encoded key
+    pub const SIZE: usize = 17;
+
+    /// Constructs a new key.
+    pub fn new(iteration: u64, subdomain: u64, kind: StorageMode) -> Self {
+        Self {
+            iteration,
+            subdomain,
+            kind,
+        }
+    }
+
+    /// Encodes the key as `iteration | subdomain | kind`.
+    pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+        let mut bytes = [0; Self::SIZE];
+        bytes[..8].copy_from_slice(&self.iteration.to_be_bytes());
+        bytes[8..16].copy_from_slice(&self.subdomain.to_be_bytes());
+        bytes[16] = self.kind.to_byte();
+        bytes
+    }
+
+    /// Decodes the key from the first [StorageKey::SIZE] bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, StorageError> {
+        let (iteration, subdomain, kind) = split_key(bytes)?;
+        Ok(Self {
+            iteration: u64::from_be_bytes(iteration),
+            subdomain: u64::from_be_bytes(subdomain),
+            kind: StorageMode::from_byte(kind)?,
+        })
+    }
+
+    /// Encodes the key as `subdomain | iteration | kind`.
+    ///
+    /// This ordering is used to scan the history of a single subdomain.
+    pub fn to_subdomain_bytes(&self) -> [u8; Self::SIZE] {
+        let mut bytes = [0; Self::SIZE];
+        bytes[..8].copy_from_slice(&self.subdomain.to_be_bytes());
+        bytes[8..16].copy_from_slice(&self.iteration.to_be_bytes());
+        bytes[16] = self.kind.to_byte();
+        bytes
+    }
+
+    /// Decodes the key from bytes generated by [StorageKey::to_subdomain_bytes].
+    pub fn from_subdomain_bytes(bytes: &[u8]) -> Result<Self, StorageError> {
+        let (subdomain, iteration, kind) = split_key(bytes)?;
+        Ok(Self {
+            iteration: u64::from_be_bytes(iteration),
+            subdomain: u64::from_be_bytes(subdomain),
+            kind: StorageMode::from_byte(kind)?,
+        })
+    }
+
+    /// Prefix of all keys stored at the iteration.
+    pub fn iteration_prefix(iteration: u64) -> [u8; 8] {
+        iteration.to_be_bytes()
+    }
+
+    /// Prefix of all keys stored by the subdomain at the iteration.
+    pub fn iteration_subdomain_prefix(iteration: u64, subdomain: u64) -> [u8; 16] {
+        let mut bytes = [0; 16];
+        bytes[..8].copy_from_slice(&iteration.to_be_bytes());
+        bytes[8..].copy_from_slice(&subdomain.to_be_bytes());
+        bytes
+    }
+
+    /// Prefix of all keys which were encoded by [StorageKey::to_subdomain_bytes] for this
+    /// subdomain.
+    pub fn subdomain_prefix(subdomain: u64) -> [u8; 8] {
+        subdomain.to_be_bytes()
+    }
+
+    /// Appends the serialized identifier to the encoded key.
+    pub fn with_identifier<Id: Serialize>(&self, identifier: &Id) -> Result<Vec<u8>, StorageError> {
+        let mut bytes = self.to_bytes().to_vec();
+        bincode::serialize_into(&mut bytes, identifier)?;
+        Ok(bytes)
+    }
+
+    /// Splits bytes generated by [StorageKey::with_identifier] into the key and identifier.
+    pub fn split_identifier<Id>(bytes: &[u8]) -> Result<(Self, Id), StorageError>
+    where
+        Id: for<'a> Deserialize<'a>,
+    {
+        let key = Self::from_bytes(bytes)?;
+        let identifier = bincode::deserialize(&bytes[Self::SIZE..])?;
+        Ok((key, identifier))
+    }
+}
+
+/// Splits the encoded key into its three parts.
+fn split_key(bytes: &[u8]) -> Result<([u8; 8], [u8; 8], u8), StorageError> {
+    if bytes.len() < StorageKey::SIZE {
+        return Err(StorageError::InitError(format!(
+            "storage key needs {} bytes but only {} were given",
+            StorageKey::SIZE,
+            bytes.len()
+        )));
+    }
+    let mut first = [0; 8];
+    let mut second = [0; 8];
+    first.copy_from_slice(&bytes[..8]);
+    second.copy_from_slice(&bytes[8..16]);
+    Ok((first, second, bytes[16]))
+}
+
+impl StorageMode {
+    /// Encodes the mode as the last byte of a [StorageKey].
+    fn to_byte(self) -> u8 {
+        match self {
+            Self::Single => 0,
+            Self::Batch => 1,
+        }
+    }
+
+    /// Decodes the mode from the last byte of a [StorageKey].
+    fn from_byte(byte: u8) -> Result<Self, StorageError> {
+        match byte {
+            0 => Ok(Self::Single),
+            1 => Ok(Self::Batch),
+            _ => Err(StorageError::InitError(format!(
+                "unknown storage mode {byte} in storage key"
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_keys {
+    use super::*;
+
+    #[test]
+    fn encode_decode() {
+        let key = StorageKey::new(u64::MAX - 1, 258, StorageMode::Single);
+        assert_eq!(StorageKey::from_bytes(&key.to_bytes()).unwrap(), key);
+        assert_eq!(
+            StorageKey::from_subdomain_bytes(&key.to_subdomain_bytes()).unwrap(),
+            key
+        );
+        let bytes = key.with_identifier(&(3u64, 4u64)).unwrap();
+        let (decoded, identifier) = StorageKey::split_identifier::<(u64, u64)>(&bytes).unwrap();
+        assert_eq!(decoded, key);
+        assert_eq!(identifier, (3, 4));
+        assert!(StorageKey::from_bytes(&bytes[..10]).is_err());
+    }
+
+    #[test]
+    fn ordering() {
+        let mut keys = vec![
+            StorageKey::new(256, 0, StorageMode::Batch),
+            StorageKey::new(1, 300, StorageMode::Single),
+            StorageKey::new(1, 2, StorageMode::Batch),
+            StorageKey::new(0, 5, StorageMode::Single),
+        ];
+        let mut encoded: Vec<_> = keys.iter().map(|key| key.to_bytes()).collect();
+        keys.sort();
+        encoded.sort();
+        assert_eq!(
+            encoded
+                .iter()
+                .map(|bytes| StorageKey::from_bytes(bytes).unwrap())
+                .collect::<Vec<_>>(),
+            keys
+        );
+        assert!(encoded[1].starts_with(&StorageKey::iteration_subdomain_prefix(1, 2)));
+    }
+}
@@ -25,6 +25,9 @@
 //! ## Sled
 //! Builds an embedded database at the specified location. This database is a key-value storage and
 //! can be accessed via the [sled](https://docs.rs/sled/latest/sled/) crate.
+//! This option requires the `sled` feature which is enabled by default.
+//! Elements are stored under structured [StorageKey]s which allow to load single iterations or
+//! the history of single subdomains with range scans.
 //! See [SledStorageInterface]
 //!
 //! ## Sled (Temp)
@@ -32,17 +35,96 @@
 //! This options is mostly required when performing analysis steps afterwards without saving the
 //! full simulation results.
 //! See [SledStorageInterface]
+//!
+//! ## SQLite
+//! Stores all elements in indexed tables of an [SQLite](https://sqlite.org) database which can be
+//! queried with SQL and read from other languages.
+//! This option requires the `sqlite` feature.
+//! See [SqliteStorageInterface]
+//!
+//! ## Remote
+//! Streams compressed results to object storage such as [S3](https://aws.amazon.com/s3/) so
+//! that large runs on clusters do not fill up local disks.
+//! The bucket is configured with [StorageBuilder::remote].
+//! This option requires the `s3` feature.
+//! See [RemoteStorageInterface]
+//!
+//! # Storing on a Dedicated Thread
+//! Large writes can stall the simulation.
+//! By specifying [StorageBuilder::write_behind], results are handed off to a separate thread via
+//! a bounded queue. See [WriteBehindStorage].
+//!
+//! # Delta Encoding
+//! When results are stored at many iterations, most cells barely change between two of them.
+//! With [StorageBuilder::delta_encoding], only cells which moved, divided or were removed are
+//! stored in between keyframes.
+//! Loading elements reconstructs the full state at every iteration. See [DeltaEncoding].
+//!
+//! # Schema Versioning
+//! Every [StorageManager] records the layout and version of the stored elements in a
+//! [SchemaHeader].
+//! Results stored with a different [StorageBuilder::schema_version] can still be loaded by
+//! tolerant readers or converted with [migrate_storage].
+//!
+//! # Exporting Results
+//! Stored positions and lineages of cells can be converted into formats used by common cell
+//! tracking tools such as [TrackMate](https://imagej.net/plugins/trackmate/) or the
+//! [Cell Tracking Challenge](https://celltrackingchallenge.net/).
+//! See [TrackingExport].
+//! Snapshots can also be written in the MultiCellDS layout used by
+//! [PhysiCell](http://physicell.org/) with [MultiCellDsSnapshot].
+//! Extracellular concentrations of all voxels can be assembled into a global grid and written as
+//! a [Zarr](https://zarr.dev/) store with [ZarrFieldExport] for analysis with xarray.
 
+/// Traits and the manager which combines all storage options.
 mod concepts;
+/// Stores only elements which have changed since the last keyframe.
+mod delta;
+/// Keys which order elements by their iteration and subdomain.
+mod keys;
+/// Keeps results in memory.
 mod memory_storage;
+/// Exports cells as MultiCellDS snapshots.
+mod multicellds;
+/// Uploads results to a remote object store.
+#[cfg(feature = "s3")]
+mod remote;
+/// Stores results in ron files.
 mod ron;
+/// Versioning of stored results.
+mod schema;
+/// Stores results in json files.
 mod serde_json;
+/// Stores results in a sled database.
+#[cfg(feature = "sled")]
 mod sled_database;
+/// Stores results in an sqlite database.
+#[cfg(feature = "sqlite")]
+mod sqlite;
+/// Exports tracks of cells for TrackMate.
+mod tracking;
+/// Stores results on a dedicated thread.
+mod write_behind;
+/// Exports extracellular fields as Zarr stores.
+mod zarr;
 
+/// Tests of all storage options.
 mod test;
 
 pub use concepts::*;
+pub use delta::*;
+pub use keys::*;
 pub use memory_storage::*;
+pub use multicellds::*;
+#[cfg(feature = "s3")]
+pub use remote::*;
 pub use ron::*;
+pub use schema::*;
 pub use serde_json::*;
+#[cfg(feature = "sled")]
 pub use sled_database::*;
+#[cfg(feature = "sqlite")]
+pub use sqlite::*;
+pub use tracking::*;
+pub use write_behind::*;
+pub use zarr::*;
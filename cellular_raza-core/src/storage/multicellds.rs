@@ -0,0 +1,346 @@
+use cellular_raza_concepts::PositionLike;
+use serde::{Deserialize, Serialize};
+
+use std::io::Write;
+
+/// Information about a single cell which is written to a MultiCellDS snapshot.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct MultiCellDsCell {
+    /// Unique numerical identifier of the cell
+    pub id: u64,
+    /// Position of the cell. Missing dimensions are filled with `0.0`.
+    pub position: [f64; 3],
+    /// Total volume of the cell
+    pub total_volume: f64,
+    /// Numerical type of the cell
+    pub cell_type: u64,
+    /// Additional values which are appended to the default variables.
+    /// Their length has to match [MultiCellDsSnapshot::custom_labels].
+    pub custom_data: Vec<f64>,
+}
+
+impl MultiCellDsCell {
+    /// Constructs a cell located at the
+    /// [representative_point](PositionLike::representative_point) of the given position.
+    ///
+    /// Only the first 3 dimensions are used and missing dimensions are filled with `0.0`.
+    /// No custom data is attached.
+    pub fn from_position_like<const D: usize>(
+        id: u64,
+        position: &impl PositionLike<f64, D>,
+        total_volume: f64,
+        cell_type: u64,
+    ) -> Self {
+        Self {
+            id,
+            position: pad_to_3d(position.representative_point()),
+            total_volume,
+            cell_type,
+            custom_data: Vec::new(),
+        }
+    }
+}
+
+/// Takes the first 3 entries and fills missing dimensions with `0.0`.
+fn pad_to_3d<const D: usize>(point: [f64; D]) -> [f64; 3] {
+    let mut padded = [0.0; 3];
+    for (p, x) in padded.iter_mut().zip(point) {
+        *p = x;
+    }
+    padded
+}
+
+/// A single snapshot in the [MultiCellDS](http://multicellds.org/) layout used by
+/// [PhysiCell](http://physicell.org/).
+///
+/// PhysiCell stores every output as a pair of files.
+/// The `outputXXXXXXXX.xml` file contains metadata and describes the layout of the
+/// `outputXXXXXXXX_cells.mat` file which holds all cellular variables in the MATLAB v4 format.
+/// By reproducing these files, tools such as [pcdl](https://github.com/elmbeech/physicelldataloader)
+/// or PhysiCell Studio can be used to analyze results of cellular_raza.
+///
+/// ```
+/// # use cellular_raza_core::storage::*;
+/// let snapshot = MultiCellDsSnapshot {
+///     time: 60.0,
+///     time_units: "min".into(),
+///     space_units: "micron".into(),
+///     bounding_box: [[-100.0, -100.0, -10.0], [100.0, 100.0, 10.0]],
+///     custom_labels: vec!["pressure".into()],
+///     cells: vec![MultiCellDsCell {
+///         id: 0,
+///         position: [1.0, 2.0, 0.0],
+///         total_volume: 2494.0,
+///         cell_type: 0,
+///         custom_data: vec![0.3],
+///     }],
+/// };
+/// let dir = tempfile::tempdir()?;
+/// let (xml_path, mat_path) = snapshot.write_to_dir(dir.path(), 1)?;
+/// assert!(xml_path.ends_with("output00000001.xml"));
+/// assert!(mat_path.ends_with("output00000001_cells.mat"));
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct MultiCellDsSnapshot {
+    /// Current simulation time
+    pub time: f64,
+    /// Units of time, PhysiCell uses `"min"`
+    pub time_units: String,
+    /// Units of space, PhysiCell uses `"micron"`
+    pub space_units: String,
+    /// Lower and upper corner of the simulation domain
+    pub bounding_box: [[f64; 3]; 2],
+    /// Names of the additional variables stored in [MultiCellDsCell::custom_data]
+    pub custom_labels: Vec<String>,
+    /// All cells of this snapshot
+    pub cells: Vec<MultiCellDsCell>,
+}
+
+impl MultiCellDsSnapshot {
+    /// Default variables which are always written in this order.
+    const DEFAULT_LABELS: [(&'static str, usize); 4] = [
+        ("ID", 1),
+        ("position", 3),
+        ("total_volume", 1),
+        ("cell_type", 1),
+    ];
+
+    /// Smallest box which contains the [bounding_box](PositionLike::bounding_box) of all given
+    /// positions.
+    ///
+    /// The result can be used as [MultiCellDsSnapshot::bounding_box] when the extent of the
+    /// simulation domain is not known.
+    pub fn bounding_box_of<'a, P, const D: usize>(
+        positions: impl IntoIterator<Item = &'a P>,
+    ) -> [[f64; 3]; 2]
+    where
+        P: PositionLike<f64, D> + 'a,
+    {
+        let mut lower = [f64::INFINITY; D];
+        let mut upper = [f64::NEG_INFINITY; D];
+        for position in positions {
+            let (l, u) = position.bounding_box();
+            for i in 0..D {
+                lower[i] = lower[i].min(l[i]);
+                upper[i] = upper[i].max(u[i]);
+            }
+        }
+        [pad_to_3d(lower), pad_to_3d(upper)]
+    }
+
+    /// Total number of rows in the `cells` matrix.
+    fn n_variables(&self) -> usize {
+        Self::DEFAULT_LABELS.iter().map(|(_, n)| n).sum::<usize>() + self.custom_labels.len()
+    }
+
+    /// Writes the `outputXXXXXXXX.xml` and `outputXXXXXXXX_cells.mat` files into the given
+    /// directory and returns their paths.
+    pub fn write_to_dir(
+        &self,
+        dir: impl AsRef<std::path::Path>,
+        index: u64,
+    ) -> Result<(std::path::PathBuf, std::path::PathBuf), std::io::Error> {
+        let base = format!("output{:08}", index);
+        let mat_name = format!("{base}_cells.mat");
+        let xml_path = dir.as_ref().join(format!("{base}.xml"));
+        let mat_path = dir.as_ref().join(&mat_name);
+        let mut xml_file = std::io::BufWriter::new(std::fs::File::create(&xml_path)?);
+        self.write_xml(&mut xml_file, &mat_name)?;
+        xml_file.flush()?;
+        let mut mat_file = std::io::BufWriter::new(std::fs::File::create(&mat_path)?);
+        self.write_cells_mat(&mut mat_file)?;
+        mat_file.flush()?;
+        Ok((xml_path, mat_path))
+    }
+
+    /// Writes the xml part of the snapshot which refers to the cell data stored in `mat_filename`.
+    pub fn write_xml(&self, mut writer: impl Write, mat_filename: &str) -> std::io::Result<()> {
+        let [lower, upper] = self.bounding_box;
+        writeln!(writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+        writeln!(
+            writer,
+            "<MultiCellDS version=\"2\" type=\"snapshot/simulation\">"
+        )?;
+        writeln!(writer, " <metadata>")?;
+        writeln!(
+            writer,
+            "  <software><name>cellular_raza</name><version>{}</version></software>",
+            env!("CARGO_PKG_VERSION")
+        )?;
+        writeln!(
+            writer,
+            "  <current_time units=\"{}\">{}</current_time>",
+            self.time_units, self.time
+        )?;
+        writeln!(writer, " </metadata>")?;
+        writeln!(writer, " <microenvironment>")?;
+        writeln!(writer, "  <domain name=\"microenvironment\">")?;
+        writeln!(
+            writer,
+            "   <mesh type=\"Cartesian\" uniform=\"true\" regular=\"true\" units=\"{}\">",
+            self.space_units
+        )?;
+        writeln!(
+            writer,
+            "    <bounding_box type=\"axis-aligned\" units=\"{}\">{} {} {} {} {} {}</bounding_box>",
+            self.space_units, lower[0], lower[1], lower[2], upper[0], upper[1], upper[2]
+        )?;
+        writeln!(writer, "   </mesh>")?;
+        writeln!(writer, "  </domain>")?;
+        writeln!(writer, " </microenvironment>")?;
+        writeln!(writer, " <cellular_information>")?;
+        writeln!(writer, "  <cell_populations>")?;
+        writeln!(writer, "   <cell_population type=\"individual\">")?;
+        writeln!(writer, "    <custom>")?;
+        writeln!(
+            writer,
+            "     <simplified_data type=\"matlab\" source=\"cellular_raza\" data_version=\"2\">"
+        )?;
+        writeln!(writer, "      <labels>")?;
+        let mut index = 0;
+        let labels = Self::DEFAULT_LABELS
+            .iter()
+            .map(|(name, size)| (*name, *size))
+            .chain(self.custom_labels.iter().map(|name| (name.as_str(), 1)));
+        for (name, size) in labels {
+            writeln!(
+                writer,
+                "       <label index=\"{index}\" size=\"{size}\">{name}</label>"
+            )?;
+            index += size;
+        }
+        writeln!(writer, "      </labels>")?;
+        writeln!(writer, "      <filename>{mat_filename}</filename>")?;
+        writeln!(writer, "     </simplified_data>")?;
+        writeln!(writer, "    </custom>")?;
+        writeln!(writer, "   </cell_population>")?;
+        writeln!(writer, "  </cell_populations>")?;
+        writeln!(writer, " </cellular_information>")?;
+        writeln!(writer, "</MultiCellDS>")?;
+        Ok(())
+    }
+
+    /// Writes all cellular variables as a matrix named `cells` in the MATLAB v4 format.
+    ///
+    /// Every column corresponds to one cell and every row to one variable as described by the
+    /// labels in the xml file.
+    pub fn write_cells_mat(&self, mut writer: impl Write) -> std::io::Result<()> {
+        let n_rows = self.n_variables();
+        for cell in self.cells.iter() {
+            if cell.custom_data.len() != self.custom_labels.len() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!(
+                        "cell {} has {} custom values but {} labels were specified",
+                        cell.id,
+                        cell.custom_data.len(),
+                        self.custom_labels.len()
+                    ),
+                ));
+            }
+        }
+        let name = b"cells\0";
+        // Type 0000 means little-endian, double precision, numeric full matrix
+        for header_value in [
+            0,
+            n_rows as i32,
+            self.cells.len() as i32,
+            0,
+            name.len() as i32,
+        ] {
+            writer.write_all(&header_value.to_le_bytes())?;
+        }
+        writer.write_all(name)?;
+        // Data is stored in column-major order
+        for cell in self.cells.iter() {
+            let values = [cell.id as f64]
+                .into_iter()
+                .chain(cell.position)
+                .chain([cell.total_volume, cell.cell_type as f64])
+                .chain(cell.custom_data.iter().copied());
+            for value in values {
+                writer.write_all(&value.to_le_bytes())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_multicellds {
+    use super::*;
+
+    fn snapshot() -> MultiCellDsSnapshot {
+        MultiCellDsSnapshot {
+            time: 30.0,
+            time_units: "min".into(),
+            space_units: "micron".into(),
+            bounding_box: [[0.0; 3], [100.0; 3]],
+            custom_labels: vec!["age".into()],
+            cells: vec![
+                MultiCellDsCell {
+                    id: 3,
+                    position: [1.0, 2.0, 3.0],
+                    total_volume: 10.0,
+                    cell_type: 1,
+                    custom_data: vec![5.0],
+                },
+                MultiCellDsCell {
+                    id: 4,
+                    position: [4.0, 5.0, 6.0],
+                    total_volume: 11.0,
+                    cell_type: 0,
+                    custom_data: vec![6.0],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn mat_layout() {
+        let mut out = Vec::new();
+        snapshot().write_cells_mat(&mut out).unwrap();
+        let header: Vec<i32> = out[..20]
+            .chunks(4)
+            .map(|c| i32::from_le_bytes(c.try_into().unwrap()))
+            .collect();
+        assert_eq!(header, vec![0, 7, 2, 0, 6]);
+        assert_eq!(&out[20..26], b"cells\0");
+        let data: Vec<f64> = out[26..]
+            .chunks(8)
+            .map(|c| f64::from_le_bytes(c.try_into().unwrap()))
+            .collect();
+        assert_eq!(
+            data,
+            vec![3.0, 1.0, 2.0, 3.0, 10.0, 1.0, 5.0, 4.0, 4.0, 5.0, 6.0, 11.0, 0.0, 6.0]
+        );
+    }
+
+    #[test]
+    fn xml_labels() {
+        let mut out = Vec::new();
+        snapshot().write_xml(&mut out, "output_cells.mat").unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("<label index=\"1\" size=\"3\">position</label>"));
+        assert!(out.contains("<label index=\"6\" size=\"1\">age</label>"));
+        assert!(out.contains("<filename>output_cells.mat</filename>"));
+    }
+
+    #[test]
+    fn mismatching_custom_data() {
+        let mut snapshot = snapshot();
+        snapshot.cells[0].custom_data.clear();
+        assert!(snapshot.write_cells_mat(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn position_like_cells() {
+        let positions = [[1.0, 2.0], [-3.0, 5.0]];
+        let cell = MultiCellDsCell::from_position_like(7, &positions[1], 3.0, 2);
+        assert_eq!(cell.position, [-3.0, 5.0, 0.0]);
+        assert!(cell.custom_data.is_empty());
+        let bounding_box = MultiCellDsSnapshot::bounding_box_of(positions.iter());
+        assert_eq!(bounding_box, [[-3.0, 2.0, 0.0], [1.0, 5.0, 0.0]]);
+    }
+}
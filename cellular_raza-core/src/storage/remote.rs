@@ -0,0 +1,425 @@
+use super::concepts::{BatchSaveFormat, CombinedSaveFormat, StorageError, StorageMode};
+use super::concepts::{StorageInterfaceLoad, StorageInterfaceStore};
+
+use futures::TryStreamExt;
+use object_store::path::Path;
+use object_store::ObjectStore;
+use serde::{Deserialize, Serialize};
+
+use std::collections::{BTreeMap, HashMap};
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Settings for streaming results to remote object storage.
+///
+/// The `url` determines the bucket and prefix under which results are stored, for example
+/// `s3://my-bucket/simulations`.
+/// Credentials and regions are read from the usual `AWS_*` environment variables and can be
+/// overwritten by [RemoteStorage::option].
+/// Local paths (`file:///path`) and memory (`memory:///`) are supported as well.
+///
+/// See [StorageBuilder::remote](super::StorageBuilder::remote) and [RemoteStorageInterface].
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct RemoteStorage {
+    /// Location of the bucket and prefix
+    pub url: String,
+    /// Additional configuration of the object store such as `aws_region` or `aws_endpoint`
+    pub options: BTreeMap<String, String>,
+    /// Number of times a failed request is retried
+    pub max_retries: usize,
+    /// Waiting time before the first retry which is doubled after every attempt
+    pub backoff: std::time::Duration,
+    /// Compress stored objects with gzip
+    pub compress: bool,
+}
+
+impl RemoteStorage {
+    /// Stores compressed results at the given url and retries failed requests 5 times.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            options: BTreeMap::new(),
+            max_retries: 5,
+            backoff: std::time::Duration::from_millis(100),
+            compress: true,
+        }
+    }
+
+    /// Set an option of the object store such as `aws_endpoint`.
+    pub fn option(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.options.insert(key.into(), value.into());
+        self
+    }
+
+    /// Change how often and after which time failed requests are retried.
+    pub fn retry(self, max_retries: usize, backoff: std::time::Duration) -> Self {
+        Self {
+            max_retries,
+            backoff,
+            ..self
+        }
+    }
+
+    /// Decide if stored objects are compressed.
+    pub fn compress(self, compress: bool) -> Self {
+        Self { compress, ..self }
+    }
+}
+
+/// Streams results to remote object storage such as [S3](https://aws.amazon.com/s3/).
+///
+/// Cluster runs often produce more results than fit on local scratch disks.
+/// This interface uploads every stored batch directly as a single object.
+/// Objects follow the same layout as the [JsonStorageInterface](super::JsonStorageInterface),
+/// meaning that elements of one iteration are placed under a common prefix
+/// `{iteration}/{batch|single}_{instance}_{counter}.json.gz`.
+/// Failed requests are retried with an exponential backoff given by the [RemoteStorage]
+/// settings.
+/// Results which are only stored remotely do not have a [SchemaHeader](super::SchemaHeader).
+/// This option requires the `s3` feature.
+///
+/// ```
+/// use cellular_raza_core::storage::*;
+/// let builder = StorageBuilder::new()
+///     .priority([StorageOption::Remote])
+///     .remote(RemoteStorage::new("memory:///"))
+///     .init();
+/// let mut manager = StorageManager::<usize, f64>::open_or_create(builder, 0)?;
+/// manager.store_batch_elements(10, [(&0, &1.0), (&1, &2.0)])?;
+/// assert_eq!(manager.get_all_iterations()?, vec![10]);
+/// assert_eq!(manager.load_single_element(10, &1)?, Some(2.0));
+/// # Ok::<(), StorageError>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct RemoteStorageInterface<Id, Element> {
+    store: Arc<dyn ObjectStore>,
+    prefix: Path,
+    settings: RemoteStorage,
+    instance: u64,
+    counter: Arc<AtomicU64>,
+    runtime: Arc<tokio::runtime::Runtime>,
+    id_phantom: PhantomData<Id>,
+    element_phantom: PhantomData<Element>,
+}
+
+impl<Id, Element> RemoteStorageInterface<Id, Element> {
+    /// Connects to the object store given by the settings.
+    ///
+    /// All objects are stored under the prefix of the url extended by the given path.
+    pub fn open(
+        settings: &RemoteStorage,
+        path: &std::path::Path,
+        instance: u64,
+    ) -> Result<Self, StorageError> {
+        let url = url::Url::parse(&settings.url)
+            .map_err(|e| StorageError::InitError(format!("invalid url {}: {e}", settings.url)))?;
+        let options = std::env::vars()
+            .filter(|(key, _)| key.starts_with("AWS_"))
+            .map(|(key, value)| (key.to_lowercase(), value))
+            .chain(settings.options.clone());
+        let (store, prefix) = object_store::parse_url_opts(&url, options)?;
+        let prefix = prefix
+            .parts()
+            .chain(Path::from_iter(path.iter().filter_map(|part| part.to_str())).parts())
+            .collect();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        Ok(Self {
+            store: Arc::from(store),
+            prefix,
+            settings: settings.clone(),
+            instance,
+            counter: Arc::new(AtomicU64::new(0)),
+            runtime: Arc::new(runtime),
+            id_phantom: PhantomData,
+            element_phantom: PhantomData,
+        })
+    }
+
+    /// Runs the request and retries it if it fails.
+    ///
+    /// Objects which were not found are not retried.
+    fn request<T, F, Fut>(&self, mut request: F) -> Result<T, StorageError>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<T, object_store::Error>>,
+    {
+        let mut backoff = self.settings.backoff;
+        let mut attempt = 0;
+        loop {
+            match self.runtime.block_on(request()) {
+                Ok(value) => return Ok(value),
+                Err(object_store::Error::NotFound { path, source }) => {
+                    return Err(object_store::Error::NotFound { path, source }.into())
+                }
+                Err(error) if attempt >= self.settings.max_retries => return Err(error.into()),
+                Err(_) => {
+                    std::thread::sleep(backoff);
+                    backoff *= 2;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    fn iteration_prefix(&self, iteration: u64) -> Path {
+        self.prefix.child(format!("{:020.0}", iteration))
+    }
+
+    /// Serializes the value and uploads it as a new object.
+    fn put<V: Serialize>(
+        &self,
+        iteration: u64,
+        mode: StorageMode,
+        value: &V,
+    ) -> Result<(), StorageError> {
+        let counter = self.counter.fetch_add(1, Ordering::Relaxed);
+        let mut name = format!(
+            "{}_{:020.0}_{:020.0}.json",
+            mode.to_str(),
+            self.instance,
+            counter
+        );
+        let bytes = match self.settings.compress {
+            true => {
+                name.push_str(".gz");
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                serde_json::to_writer(&mut encoder, value)?;
+                encoder.finish()?
+            }
+            false => serde_json::to_vec(value)?,
+        };
+        let location = self.iteration_prefix(iteration).child(name);
+        let payload = object_store::PutPayload::from(bytes);
+        self.request(|| self.store.put(&location, payload.clone()))?;
+        Ok(())
+    }
+
+    /// Downloads and deserializes all objects stored at the iteration.
+    fn get_all<V>(&self, iteration: u64, mode: StorageMode) -> Result<Vec<V>, StorageError>
+    where
+        V: for<'a> Deserialize<'a>,
+    {
+        let prefix = self.iteration_prefix(iteration);
+        let objects: Vec<_> =
+            self.request(|| self.store.list(Some(&prefix)).try_collect::<Vec<_>>())?;
+        objects
+            .into_iter()
+            .filter(|object| {
+                object
+                    .location
+                    .filename()
+                    .is_some_and(|name| name.starts_with(mode.to_str()))
+            })
+            .map(|object| {
+                let bytes = self
+                    .request(|| async { self.store.get(&object.location).await?.bytes().await })?;
+                match object.location.extension() {
+                    Some("gz") => Ok(serde_json::from_reader(flate2::read::GzDecoder::new(
+                        &bytes[..],
+                    ))?),
+                    _ => Ok(serde_json::from_slice(&bytes)?),
+                }
+            })
+            .collect()
+    }
+}
+
+impl<Id, Element> StorageInterfaceStore<Id, Element> for RemoteStorageInterface<Id, Element> {
+    fn store_single_element(
+        &mut self,
+        iteration: u64,
+        identifier: &Id,
+        element: &Element,
+    ) -> Result<(), StorageError>
+    where
+        Id: Serialize,
+        Element: Serialize,
+    {
+        let save_format = CombinedSaveFormat {
+            identifier,
+            element,
+        };
+        self.put(iteration, StorageMode::Single, &save_format)
+    }
+
+    fn store_batch_elements<'a, I>(
+        &'a mut self,
+        iteration: u64,
+        identifiers_elements: I,
+    ) -> Result<(), StorageError>
+    where
+        Id: 'a + Serialize,
+        Element: 'a + Serialize,
+        I: Clone + IntoIterator<Item = (&'a Id, &'a Element)>,
+    {
+        let batch = BatchSaveFormat {
+            data: identifiers_elements
+                .into_iter()
+                .map(|(identifier, element)| CombinedSaveFormat {
+                    identifier,
+                    element,
+                })
+                .collect(),
+        };
+        self.put(iteration, StorageMode::Batch, &batch)
+    }
+}
+
+impl<Id, Element> StorageInterfaceLoad<Id, Element> for RemoteStorageInterface<Id, Element> {
+    fn load_single_element(
+        &self,
+        iteration: u64,
+        identifier: &Id,
+    ) -> Result<Option<Element>, StorageError>
+    where
+        Id: Eq + Serialize + for<'a> Deserialize<'a>,
+        Element: for<'a> Deserialize<'a>,
+    {
+        Ok(self
+            .load_all_elements_at_iteration_unhashed(iteration)?
+            .into_iter()
+            .find(|(id, _)| id == identifier)
+            .map(|(_, element)| element))
+    }
+
+    fn load_all_elements_at_iteration(
+        &self,
+        iteration: u64,
+    ) -> Result<HashMap<Id, Element>, StorageError>
+    where
+        Id: std::hash::Hash + std::cmp::Eq + for<'a> Deserialize<'a>,
+        Element: for<'a> Deserialize<'a>,
+    {
+        Ok(self
+            .load_all_elements_at_iteration_unhashed(iteration)?
+            .into_iter()
+            .collect())
+    }
+
+    fn get_all_iterations(&self) -> Result<Vec<u64>, StorageError> {
+        let result = self.request(|| self.store.list_with_delimiter(Some(&self.prefix)))?;
+        result
+            .common_prefixes
+            .iter()
+            .filter_map(|prefix| prefix.parts().last())
+            .map(|part| Ok(part.as_ref().parse::<u64>()?))
+            .collect()
+    }
+}
+
+impl<Id, Element> RemoteStorageInterface<Id, Element> {
+    /// Loads all elements at the iteration without requiring hashable identifiers.
+    fn load_all_elements_at_iteration_unhashed(
+        &self,
+        iteration: u64,
+    ) -> Result<Vec<(Id, Element)>, StorageError>
+    where
+        Id: for<'a> Deserialize<'a>,
+        Element: for<'a> Deserialize<'a>,
+    {
+        let batches: Vec<BatchSaveFormat<Id, Element>> =
+            self.get_all(iteration, StorageMode::Batch)?;
+        let singles: Vec<CombinedSaveFormat<Id, Element>> =
+            self.get_all(iteration, StorageMode::Single)?;
+        Ok(batches
+            .into_iter()
+            .flat_map(|batch| batch.data)
+            .chain(singles)
+            .map(|save_format| (save_format.identifier, save_format.element))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test_remote {
+    use super::*;
+
+    fn open<E>(dir: &std::path::Path, settings: RemoteStorage) -> RemoteStorageInterface<u32, E> {
+        let url = url::Url::from_directory_path(dir).unwrap();
+        let settings = RemoteStorage {
+            url: url.to_string(),
+            ..settings
+        };
+        RemoteStorageInterface::open(&settings, std::path::Path::new("run/cells"), 0).unwrap()
+    }
+
+    #[test]
+    fn store_load_objects() {
+        for compress in [true, false] {
+            let dir = tempfile::tempdir().unwrap();
+            let mut interface = open(dir.path(), RemoteStorage::new("").compress(compress));
+            let elements = [(1, [0.0, 1.0]), (2, [3.0, 4.0])];
+            interface
+                .store_batch_elements(0, elements.iter().map(|(i, e)| (i, e)))
+                .unwrap();
+            interface.store_single_element(0, &3, &[5.0; 2]).unwrap();
+            interface.store_single_element(20, &1, &[1.0; 2]).unwrap();
+
+            // Objects are placed below the given path
+            let extension = if compress { ".json.gz" } else { ".json" };
+            let object = dir
+                .path()
+                .join("run/cells")
+                .join(format!("{:020}", 20))
+                .join(format!("single_{:020}_{:020}{extension}", 0, 2));
+            assert!(object.is_file());
+
+            let mut iterations = interface.get_all_iterations().unwrap();
+            iterations.sort();
+            assert_eq!(iterations, vec![0, 20]);
+            assert_eq!(
+                interface.load_all_elements_at_iteration(0).unwrap(),
+                HashMap::from([(1, [0.0, 1.0]), (2, [3.0, 4.0]), (3, [5.0; 2])])
+            );
+            assert_eq!(
+                interface.load_single_element(20, &1).unwrap(),
+                Some([1.0; 2])
+            );
+            assert_eq!(interface.load_single_element(20, &2).unwrap(), None);
+        }
+    }
+
+    #[test]
+    fn retry_failed_requests() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = RemoteStorage::new("").retry(2, std::time::Duration::from_millis(1));
+        let interface = open::<f64>(dir.path(), settings);
+        let failure = || object_store::Error::Generic {
+            store: "test",
+            source: "connection reset".into(),
+        };
+        let mut attempts = 0;
+        let result = interface.request(|| {
+            attempts += 1;
+            let result = match attempts {
+                1 | 2 => Err(failure()),
+                _ => Ok(attempts),
+            };
+            async move { result }
+        });
+        assert_eq!(result.unwrap(), 3);
+
+        // Give up after the maximum number of retries
+        let mut attempts = 0;
+        let result = interface.request(|| {
+            attempts += 1;
+            async { Err::<(), _>(failure()) }
+        });
+        assert!(matches!(result, Err(StorageError::ObjectStoreError(_))));
+        assert_eq!(attempts, 3);
+
+        // Missing objects are not retried
+        let missing = Path::from("missing");
+        let mut attempts = 0;
+        let result = interface.request(|| {
+            attempts += 1;
+            interface.store.head(&missing)
+        });
+        assert!(matches!(result, Err(StorageError::ObjectStoreError(_))));
+        assert_eq!(attempts, 1);
+    }
+}
@@ -0,0 +1,303 @@
+use super::concepts::{
+    StorageBuilder, StorageError, StorageInterfaceLoad, StorageInterfaceStore, StorageManager,
+    StorageOption,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Version of the layout in which results are stored.
+///
+/// This version is increased whenever the layout of stored files or databases changes.
+/// Results which were stored without a [SchemaHeader] predate this versioning.
+///
+/// | Version | Changes |
+/// | --- | --- |
+/// | 1 | Initial versioned layout |
+/// | 2 | [sled](https://docs.rs/sled/latest/sled/) databases use structured [StorageKey](super::StorageKey)s |
+pub const STORAGE_FORMAT_VERSION: u32 = 2;
+
+/// Describes the results which were stored by a [StorageManager].
+///
+/// The header is written as `schema.json` next to the folders of the individual
+/// [StorageOption]s when a new [StorageManager] is created.
+/// When opening existing results, the header is compared to the settings of the
+/// [StorageBuilder].
+/// Results whose [StorageBuilder::schema_version] or [STORAGE_FORMAT_VERSION] do not match are
+/// only opened by tolerant readers, see [StorageBuilder::tolerant].
+///
+/// Self-describing formats such as [StorageOption::SerdeJson] and [StorageOption::Ron] ignore
+/// unknown fields when loading elements.
+/// Fields which were added afterwards can be defaulted by annotating them with
+/// `#[serde(default)]`.
+/// Elements in a [sled](https://docs.rs/sled/latest/sled/) database are encoded with [bincode]
+/// which does not store field names.
+/// They need to be converted with [migrate_storage].
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct SchemaHeader {
+    /// Layout of the stored results, see [STORAGE_FORMAT_VERSION]
+    pub format_version: u32,
+    /// User-defined version of the stored elements, see [StorageBuilder::schema_version]
+    pub schema_version: Option<u32>,
+    /// Name of the type of the stored elements
+    pub element_type: String,
+    /// Version of `cellular_raza` which stored the results
+    pub cellular_raza_version: String,
+}
+
+impl SchemaHeader {
+    /// Name of the file which contains the header
+    pub const FILE_NAME: &'static str = "schema.json";
+
+    /// Constructs the header for the current [STORAGE_FORMAT_VERSION].
+    pub fn new<Element>(schema_version: Option<u32>) -> Self {
+        Self {
+            format_version: STORAGE_FORMAT_VERSION,
+            schema_version,
+            element_type: std::any::type_name::<Element>().to_owned(),
+            cellular_raza_version: env!("CARGO_PKG_VERSION").to_owned(),
+        }
+    }
+
+    /// Reads the header from the given location if it exists.
+    pub fn read(location: &std::path::Path) -> Result<Option<Self>, StorageError> {
+        let path = location.join(Self::FILE_NAME);
+        match path.is_file() {
+            true => Ok(Some(serde_json::from_reader(std::fs::File::open(path)?)?)),
+            false => Ok(None),
+        }
+    }
+
+    /// Writes the header to the given location.
+    ///
+    /// The header is first written to a temporary file specific to the instance and then moved
+    /// such that multiple instances can write the header simultaneously.
+    pub fn write(&self, location: &std::path::Path, instance: u64) -> Result<(), StorageError> {
+        std::fs::create_dir_all(location)?;
+        let temp_path = location.join(format!(".{}.{:03}", Self::FILE_NAME, instance));
+        serde_json::to_writer_pretty(std::fs::File::create(&temp_path)?, self)?;
+        std::fs::rename(temp_path, location.join(Self::FILE_NAME))?;
+        Ok(())
+    }
+
+    /// Checks if results with this header can be read with the given settings.
+    ///
+    /// Tolerant readers accept every header.
+    pub fn check(&self, schema_version: Option<u32>, tolerant: bool) -> Result<(), StorageError> {
+        if tolerant {
+            return Ok(());
+        }
+        // Older layouts can still be read
+        if self.format_version > STORAGE_FORMAT_VERSION {
+            return Err(StorageError::SchemaError(format!(
+                "results were stored with format version {} but only versions up to {} are \
+                supported",
+                self.format_version, STORAGE_FORMAT_VERSION
+            )));
+        }
+        match schema_version {
+            Some(version) if self.schema_version != Some(version) => {
+                Err(StorageError::SchemaError(format!(
+                    "stored elements of type {} have schema version {:?} but version {} was \
+                    expected",
+                    self.element_type, self.schema_version, version
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Reads and checks the header of existing results or writes a new one.
+    ///
+    /// Returns `None` if results are only stored in memory or if existing results do not have a
+    /// header.
+    pub(super) fn open_or_create<Element>(
+        builder: &StorageBuilder<true>,
+        location: &std::path::Path,
+        instance: u64,
+    ) -> Result<Option<Self>, StorageError> {
+        let priority = builder.get_priority();
+        let persistent = priority.iter().any(|option| {
+            matches!(
+                option,
+                StorageOption::Sled
+                    | StorageOption::SerdeJson
+                    | StorageOption::Ron
+                    | StorageOption::Sqlite
+            )
+        });
+        if !persistent {
+            return Ok(None);
+        }
+        if let Some(header) = Self::read(location)? {
+            header.check(builder.get_schema_version(), builder.get_tolerant())?;
+            return Ok(Some(header));
+        }
+        // Results which were stored before versioning was introduced
+        let legacy = ["sled", "json", "ron"]
+            .iter()
+            .any(|folder| location.join(folder).exists());
+        match (legacy, builder.get_schema_version(), builder.get_tolerant()) {
+            (true, Some(version), false) => Err(StorageError::SchemaError(format!(
+                "results at {} were stored without schema version but version {} was expected",
+                location.display(),
+                version
+            ))),
+            (true, _, _) => Ok(None),
+            (false, schema_version, _) => {
+                let header = Self::new::<Element>(schema_version);
+                header.write(location, instance)?;
+                Ok(Some(header))
+            }
+        }
+    }
+}
+
+/// Converts stored results into a new type.
+///
+/// All elements are loaded from the `source` and stored at the `target` after applying the
+/// conversion.
+/// The source is always opened by a tolerant reader while the target receives a new
+/// [SchemaHeader] with the [StorageBuilder::schema_version] of its builder.
+/// This is the only way to change the type of elements stored in a
+/// [sled](https://docs.rs/sled/latest/sled/) database.
+/// Returns the number of converted elements.
+///
+/// ```
+/// use cellular_raza_core::storage::*;
+/// # let dir = tempfile::tempdir()?;
+/// let builder = StorageBuilder::new()
+///     .priority([StorageOption::SerdeJson])
+///     .location(dir.path())
+///     .add_date(false);
+/// let mut manager = StorageManager::<usize, f32>::open_or_create(
+///     builder.clone().suffix("old").schema_version(1).init(),
+///     0,
+/// )?;
+/// manager.store_single_element(0, &3, &1.5)?;
+///
+/// // Convert to a new type
+/// let target = builder.suffix("new").schema_version(2).init();
+/// let n_elements = migrate_storage::<usize, f32, (f32, bool), _>(
+///     manager.extract_builder(),
+///     target.clone(),
+///     |value| (value, true),
+/// )?;
+/// assert_eq!(n_elements, 1);
+/// let manager = StorageManager::<usize, (f32, bool)>::open_or_create(target, 0)?;
+/// assert_eq!(manager.load_single_element(0, &3)?, Some((1.5, true)));
+/// assert_eq!(manager.get_schema_header().unwrap().schema_version, Some(2));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn migrate_storage<Id, Old, New, F>(
+    source: StorageBuilder<true>,
+    target: StorageBuilder<true>,
+    mut convert: F,
+) -> Result<usize, StorageError>
+where
+    Id: core::hash::Hash + Eq + Clone + Serialize + for<'a> Deserialize<'a>,
+    Old: Clone + for<'a> Deserialize<'a>,
+    New: Clone + Serialize,
+    F: FnMut(Old) -> New,
+{
+    let source = StorageManager::<Id, Old>::open_or_create(source.tolerant(true), 0)?;
+    let mut target = StorageManager::<Id, New>::open_or_create(target, 0)?;
+    let mut iterations = source.get_all_iterations()?;
+    iterations.sort();
+    let mut n_elements = 0;
+    for iteration in iterations {
+        let elements: Vec<_> = source
+            .load_all_elements_at_iteration(iteration)?
+            .into_iter()
+            .map(|(identifier, element)| (identifier, convert(element)))
+            .collect();
+        n_elements += elements.len();
+        target.store_batch_elements(iteration, elements.iter().map(|(i, e)| (i, e)))?;
+    }
+    Ok(n_elements)
+}
+
+#[cfg(test)]
+mod test_schema {
+    use super::*;
+
+    fn builder(dir: &std::path::Path) -> StorageBuilder<true> {
+        StorageBuilder::new()
+            .priority([StorageOption::SerdeJson])
+            .location(dir)
+            .init_with_date(std::path::Path::new(""))
+    }
+
+    #[test]
+    fn check_schema_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let builder = builder(dir.path()).schema_version(3);
+        let manager = StorageManager::<u32, f64>::open_or_create(builder.clone(), 0).unwrap();
+        let header = manager.get_schema_header().unwrap();
+        assert_eq!(header.format_version, STORAGE_FORMAT_VERSION);
+        assert_eq!(header.schema_version, Some(3));
+        assert_eq!(header.element_type, "f64");
+
+        // Readers without a schema version accept all results
+        assert!(StorageManager::<u32, f64>::open_or_create(
+            builder.clone().schema_version(None),
+            1
+        )
+        .is_ok());
+        let result =
+            StorageManager::<u32, f64>::open_or_create(builder.clone().schema_version(4), 1);
+        assert!(matches!(result, Err(StorageError::SchemaError(_))));
+        let manager =
+            StorageManager::<u32, f64>::open_or_create(builder.schema_version(4).tolerant(true), 1)
+                .unwrap();
+        assert_eq!(manager.get_schema_header().unwrap().schema_version, Some(3));
+    }
+
+    #[test]
+    fn tolerant_reader() {
+        #[derive(Clone, Deserialize, Serialize)]
+        struct OldCell {
+            pos: f64,
+            removed: bool,
+        }
+        #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+        struct NewCell {
+            pos: f64,
+            #[serde(default)]
+            age: f64,
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut manager = StorageManager::<u32, OldCell>::open_or_create(
+            builder(dir.path()).schema_version(1),
+            0,
+        )
+        .unwrap();
+        let cell = OldCell {
+            pos: 2.0,
+            removed: false,
+        };
+        manager.store_single_element(0, &1, &cell).unwrap();
+
+        let builder = builder(dir.path()).schema_version(2);
+        let result = StorageManager::<u32, NewCell>::open_or_create(builder.clone(), 1);
+        assert!(matches!(result, Err(StorageError::SchemaError(_))));
+        let manager =
+            StorageManager::<u32, NewCell>::open_or_create(builder.tolerant(true), 1).unwrap();
+        assert_eq!(
+            manager.load_single_element(0, &1).unwrap(),
+            Some(NewCell { pos: 2.0, age: 0.0 })
+        );
+    }
+
+    #[test]
+    fn legacy_results() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("json")).unwrap();
+        let manager = StorageManager::<u32, f64>::open_or_create(builder(dir.path()), 0).unwrap();
+        assert_eq!(manager.get_schema_header(), None);
+        assert!(!dir.path().join(SchemaHeader::FILE_NAME).exists());
+        let result =
+            StorageManager::<u32, f64>::open_or_create(builder(dir.path()).schema_version(1), 0);
+        assert!(matches!(result, Err(StorageError::SchemaError(_))));
+    }
+}
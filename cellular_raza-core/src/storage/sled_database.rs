@@ -1,5 +1,6 @@
-use super::concepts::StorageError;
+use super::concepts::{StorageError, StorageMode};
 use super::concepts::{StorageInterfaceLoad, StorageInterfaceOpen, StorageInterfaceStore};
+use super::keys::StorageKey;
 
 use serde::{Deserialize, Serialize};
 
@@ -7,20 +8,52 @@ use std::collections::{BTreeMap, HashMap};
 use std::marker::PhantomData;
 
 /// Use the [sled] database to save results to an embedded database.
+///
+/// Elements are stored in the `elements` tree under a [StorageKey] followed by their
+/// identifier.
+/// Since keys are ordered by their iteration, all elements of one iteration are fetched with a
+/// single range scan.
+/// The `subdomains` tree indexes the same elements by their subdomain such that the history of
+/// a single subdomain can be loaded without reading all other subdomains.
+/// See [SledStorageInterface::load_subdomain_history].
+/// The `identifiers` tree maps the iteration and identifier of every element to its
+/// [StorageKey] such that single elements can be loaded with a direct lookup.
+///
+/// Databases which were created by older versions of `cellular_raza` stored every iteration in
+/// a separate tree.
+/// These trees are migrated into the `elements` tree when the database is opened.
 // TODO use custom field for config [](https://docs.rs/sled/latest/sled/struct.Config.html) to let the user control these parameters
 #[derive(Clone, Debug)]
 pub struct SledStorageInterface<Id, Element, const TEMP: bool = false> {
+    /// Opened database which contains all trees
     db: sled::Db,
+    /// Elements keyed by their [StorageKey] followed by their identifier
+    elements: sled::Tree,
+    /// Index of elements ordered by their subdomain, see [StorageKey::to_subdomain_bytes]
+    subdomains: sled::Tree,
+    /// Maps the iteration and identifier of elements to their [StorageKey]
+    identifiers: sled::Tree,
+    /// Storage instance which is used as the subdomain of stored elements
+    instance: u64,
     // TODO use this buffer
     // buffer: StorageBuffer<Id, Element>,
+    /// Marks the type of identifiers
     id_phantom: PhantomData<Id>,
+    /// Marks the type of elements
     element_phantom: PhantomData<Element>,
 }
 
 impl<Id, Element, const TEMP: bool> SledStorageInterface<Id, Element, TEMP> {
-    /// Transform a u64 value to an iteration key which can be given to a sled tree.
-    fn iteration_to_key(iteration: u64) -> [u8; 8] {
-        iteration.to_le_bytes()
+    /// Name of the tree which contains all elements
+    const ELEMENTS_TREE: &'static str = "elements";
+    /// Name of the tree which indexes elements by their subdomain
+    const SUBDOMAINS_TREE: &'static str = "subdomains";
+    /// Name of the tree which maps the iteration and identifier of elements to their key
+    const IDENTIFIERS_TREE: &'static str = "identifiers";
+
+    /// Checks if the tree was named by its iteration in older versions.
+    fn is_legacy_tree(name: &[u8]) -> bool {
+        name.len() == 8 && name != Self::ELEMENTS_TREE.as_bytes()
     }
 
     /// Transform the key given by the tree to the corresponding iteartion u64 value
@@ -29,22 +62,174 @@ impl<Id, Element, const TEMP: bool> SledStorageInterface<Id, Element, TEMP> {
         Ok(iteration)
     }
 
-    /// Get the correct tree of the iteration or create if not currently present.
-    fn open_or_create_tree(&self, iteration: u64) -> Result<sled::Tree, StorageError> {
-        let tree_key = Self::iteration_to_key(iteration);
-        let tree = self.db.open_tree(&tree_key)?;
-        Ok(tree)
+    /// Decodes all entries of a range scan over the `elements` tree.
+    fn decode_elements(
+        entries: impl Iterator<Item = Result<(sled::IVec, sled::IVec), sled::Error>>,
+    ) -> impl Iterator<Item = Result<(StorageKey, Id, Element), StorageError>>
+    where
+        Id: for<'a> Deserialize<'a>,
+        Element: for<'a> Deserialize<'a>,
+    {
+        entries.map(|entry| {
+            let (key, element_serialized) = entry?;
+            let (key, identifier) = StorageKey::split_identifier(&key)?;
+            let element = bincode::deserialize(&element_serialized)?;
+            Ok((key, identifier, element))
+        })
+    }
+
+    /// Loads all elements which were stored by the subdomain at the iteration.
+    pub fn load_subdomain_at_iteration(
+        &self,
+        iteration: u64,
+        subdomain: u64,
+    ) -> Result<HashMap<Id, Element>, StorageError>
+    where
+        Id: std::hash::Hash + std::cmp::Eq + for<'a> Deserialize<'a>,
+        Element: for<'a> Deserialize<'a>,
+    {
+        let prefix = StorageKey::iteration_subdomain_prefix(iteration, subdomain);
+        Self::decode_elements(self.elements.scan_prefix(prefix))
+            .map(|entry| entry.map(|(_, identifier, element)| (identifier, element)))
+            .collect()
+    }
+
+    /// Loads all elements which were stored by the subdomain ordered by their iteration.
+    ///
+    /// The subdomain is given by the storage instance which stored the elements.
+    pub fn load_subdomain_history(
+        &self,
+        subdomain: u64,
+    ) -> Result<BTreeMap<u64, HashMap<Id, Element>>, StorageError>
+    where
+        Id: std::hash::Hash + std::cmp::Eq + for<'a> Deserialize<'a>,
+        Element: for<'a> Deserialize<'a>,
+    {
+        let mut history: BTreeMap<u64, HashMap<Id, Element>> = BTreeMap::new();
+        for entry in self
+            .subdomains
+            .scan_prefix(StorageKey::subdomain_prefix(subdomain))
+        {
+            let (index, _) = entry?;
+            let key = StorageKey::from_subdomain_bytes(&index)?;
+            // The index contains the same identifier as the element
+            let mut element_key = key.to_bytes().to_vec();
+            element_key.extend_from_slice(&index[StorageKey::SIZE..]);
+            if let Some(element_serialized) = self.elements.get(&element_key)? {
+                let identifier = bincode::deserialize(&index[StorageKey::SIZE..])?;
+                let element = bincode::deserialize(&element_serialized)?;
+                history
+                    .entry(key.iteration)
+                    .or_default()
+                    .insert(identifier, element);
+            }
+        }
+        Ok(history)
+    }
+
+    /// Opens the trees of an existing database and migrates trees of older versions.
+    fn from_db(db: sled::Db, instance: u64) -> Result<Self, StorageError> {
+        let elements = db.open_tree(Self::ELEMENTS_TREE)?;
+        let subdomains = db.open_tree(Self::SUBDOMAINS_TREE)?;
+        let identifiers = db.open_tree(Self::IDENTIFIERS_TREE)?;
+        let interface = SledStorageInterface {
+            db,
+            elements,
+            subdomains,
+            identifiers,
+            instance,
+            id_phantom: PhantomData,
+            element_phantom: PhantomData,
+        };
+        interface.migrate_legacy_trees()?;
+        Ok(interface)
+    }
+
+    /// Moves all elements of trees which are named by their iteration into the `elements` tree.
+    ///
+    /// Older versions did not record the subdomain of elements.
+    /// Migrated elements are thus assigned to the subdomain `0`.
+    fn migrate_legacy_trees(&self) -> Result<(), StorageError> {
+        for name in self.db.tree_names() {
+            if !Self::is_legacy_tree(&name) {
+                continue;
+            }
+            let iteration = Self::key_to_iteration(&name)?;
+            let key = StorageKey::new(iteration, 0, StorageMode::Batch);
+            let tree = self.db.open_tree(&name)?;
+            let mut batches = Batches::default();
+            for entry in tree.iter() {
+                let (identifier_serialized, element_serialized) = entry?;
+                batches.insert(key, &identifier_serialized, element_serialized);
+            }
+            self.apply(batches)?;
+            self.db.drop_tree(&name)?;
+        }
+        Ok(())
     }
 
-    fn open_tree(&self, iteration: u64) -> Result<Option<sled::Tree>, StorageError> {
-        let tree_key = Self::iteration_to_key(iteration);
-        if !self.db.tree_names().contains(&sled::IVec::from(&tree_key)) {
-            Ok(None)
-        } else {
-            let tree = self.db.open_tree(tree_key)?;
-            Ok(Some(tree))
+    /// Returns the key under which the element was stored at the iteration if any.
+    fn get_storage_key(
+        &self,
+        iteration: u64,
+        identifier_serialized: &[u8],
+    ) -> Result<Option<StorageKey>, StorageError> {
+        match self
+            .identifiers
+            .get(identifier_key(iteration, identifier_serialized))?
+        {
+            Some(key) => Ok(Some(StorageKey::from_bytes(&key)?)),
+            None => Ok(None),
         }
     }
+
+    /// Stores all batches.
+    fn apply(&self, batches: Batches) -> Result<(), StorageError> {
+        self.elements.apply_batch(batches.elements)?;
+        self.subdomains.apply_batch(batches.subdomains)?;
+        self.identifiers.apply_batch(batches.identifiers)?;
+        Ok(())
+    }
+}
+
+/// Key of the `identifiers` tree given by `iteration | identifier`.
+fn identifier_key(iteration: u64, identifier_serialized: &[u8]) -> Vec<u8> {
+    let mut key = StorageKey::iteration_prefix(iteration).to_vec();
+    key.extend_from_slice(identifier_serialized);
+    key
+}
+
+/// Batches of all trees which are written when storing elements.
+#[derive(Default)]
+struct Batches {
+    /// Batch of the `elements` tree
+    elements: sled::Batch,
+    /// Batch of the `subdomains` tree
+    subdomains: sled::Batch,
+    /// Batch of the `identifiers` tree
+    identifiers: sled::Batch,
+}
+
+impl Batches {
+    /// Stores the element in the `elements` tree and its indices in the `subdomains` and
+    /// `identifiers` trees.
+    fn insert(
+        &mut self,
+        key: StorageKey,
+        identifier_serialized: &[u8],
+        element_serialized: impl Into<sled::IVec>,
+    ) {
+        let mut element_key = key.to_bytes().to_vec();
+        element_key.extend_from_slice(identifier_serialized);
+        let mut index_key = key.to_subdomain_bytes().to_vec();
+        index_key.extend_from_slice(identifier_serialized);
+        self.elements.insert(element_key, element_serialized);
+        self.subdomains.insert(index_key, &[]);
+        self.identifiers.insert(
+            identifier_key(key.iteration, identifier_serialized),
+            &key.to_bytes(),
+        );
+    }
 }
 
 impl<Id, Element, const TEMP: bool> StorageInterfaceOpen
@@ -52,7 +237,7 @@ impl<Id, Element, const TEMP: bool> StorageInterfaceOpen
 {
     fn open_or_create(
         location: &std::path::Path,
-        _storage_instance: u64,
+        storage_instance: u64,
     ) -> Result<Self, StorageError> {
         let config = sled::Config::default()
             .mode(sled::Mode::HighThroughput)
@@ -62,12 +247,7 @@ impl<Id, Element, const TEMP: bool> StorageInterfaceOpen
             .use_compression(false);
 
         let db = config.open()?;
-
-        Ok(SledStorageInterface {
-            db,
-            id_phantom: PhantomData,
-            element_phantom: PhantomData,
-        })
+        Self::from_db(db, storage_instance)
     }
 }
 
@@ -84,19 +264,21 @@ impl<Id, Element, const TEMP: bool> StorageInterfaceStore<Id, Element>
         Id: Serialize,
         Element: Serialize,
     {
-        let tree = self.open_or_create_tree(iteration)?;
-
-        // Serialize the identifier and the element
-        let identifier_serialized = bincode::serialize(&identifier)?;
-        let element_serialized = bincode::serialize(&element)?;
-        match tree.insert(identifier_serialized, element_serialized)? {
-            None => Ok(()),
-            Some(_) => Err(StorageError::InitError(format!(
+        let key = StorageKey::new(iteration, self.instance, StorageMode::Single);
+        let identifier_serialized = bincode::serialize(identifier)?;
+        // Elements may not be stored twice, even by different subdomains
+        if self
+            .get_storage_key(iteration, &identifier_serialized)?
+            .is_some()
+        {
+            return Err(StorageError::InitError(format!(
                 "Element already present at iteration {}",
                 iteration
-            ))),
-        }?;
-        Ok(())
+            )));
+        }
+        let mut batches = Batches::default();
+        batches.insert(key, &identifier_serialized, bincode::serialize(element)?);
+        self.apply(batches)
     }
 
     fn store_batch_elements<'a, I>(
@@ -109,15 +291,22 @@ impl<Id, Element, const TEMP: bool> StorageInterfaceStore<Id, Element>
         Element: 'a + Serialize,
         I: Clone + IntoIterator<Item = (&'a Id, &'a Element)>,
     {
-        let tree = self.open_or_create_tree(iteration)?;
-        let mut batch = sled::Batch::default();
+        let key = StorageKey::new(iteration, self.instance, StorageMode::Batch);
+        let mut batches = Batches::default();
         for (identifier, element) in identifiers_elements.into_iter() {
-            let identifier_serialized = bincode::serialize(&identifier)?;
-            let element_serialized = bincode::serialize(&element)?;
-            batch.insert(identifier_serialized, element_serialized)
+            let identifier_serialized = bincode::serialize(identifier)?;
+            // Storing the same batch again overwrites it but other keys may not be shadowed
+            match self.get_storage_key(iteration, &identifier_serialized)? {
+                Some(other) if other != key => {
+                    return Err(StorageError::InitError(format!(
+                        "Element already present at iteration {} in subdomain {}",
+                        iteration, other.subdomain
+                    )));
+                }
+                _ => batches.insert(key, &identifier_serialized, bincode::serialize(element)?),
+            }
         }
-        tree.apply_batch(batch)?;
-        Ok(())
+        self.apply(batches)
     }
 }
 
@@ -133,104 +322,19 @@ impl<Id, Element, const TEMP: bool> StorageInterfaceLoad<Id, Element>
         Id: Serialize + for<'a> Deserialize<'a>,
         Element: for<'a> Deserialize<'a>,
     {
-        let tree = match self.open_tree(iteration)? {
-            Some(tree) => tree,
+        let identifier_serialized = bincode::serialize(identifier)?;
+        let key = match self.get_storage_key(iteration, &identifier_serialized)? {
+            Some(key) => key,
             None => return Ok(None),
         };
-        let identifier_serialized = bincode::serialize(identifier)?;
-        match tree.get(&identifier_serialized)? {
-            Some(element_serialized) => {
-                let element: Element = bincode::deserialize(&element_serialized)?;
-                Ok(Some(element))
-            }
+        let mut element_key = key.to_bytes().to_vec();
+        element_key.extend_from_slice(&identifier_serialized);
+        match self.elements.get(&element_key)? {
+            Some(element_serialized) => Ok(Some(bincode::deserialize(&element_serialized)?)),
             None => Ok(None),
         }
     }
 
-    fn load_element_history(&self, identifier: &Id) -> Result<HashMap<u64, Element>, StorageError>
-    where
-        Id: Serialize,
-        Element: for<'a> Deserialize<'a>,
-    {
-        // Keep track if the element has not been found in a database.
-        // If so we can either get the current minimal iteration or maximal depending on where it was found else.
-        let mut minimal_iteration = None;
-        let mut maximal_iteration = None;
-        let mut success_iteration = None;
-
-        // Save results in this hashmap
-        let mut accumulator = HashMap::new();
-        // Serialize the identifier
-        let identifier_serialized = bincode::serialize(identifier)?;
-        for iteration_serialized in self.db.tree_names() {
-            // If we are above the maximal or below the minimal iteration, we skip checking
-            let iteration: u64 = bincode::deserialize(&iteration_serialized)?;
-            match minimal_iteration {
-                None => (),
-                Some(min_iter) => {
-                    if iteration < min_iter {
-                        continue;
-                    }
-                }
-            }
-            match maximal_iteration {
-                None => (),
-                Some(max_iter) => {
-                    if max_iter < iteration {
-                        continue;
-                    }
-                }
-            }
-            // Get the tree for a random iteration
-            let tree = self.db.open_tree(iteration_serialized)?;
-            match tree.get(&identifier_serialized)? {
-                // We found and element insert it
-                Some(element_serialized) => {
-                    let element: Element = bincode::deserialize(&element_serialized)?;
-                    accumulator.insert(iteration, element);
-                    success_iteration = Some(iteration);
-                }
-                // We did not find an element. Thus update the helper variables atop.
-                None => match (minimal_iteration, maximal_iteration, success_iteration) {
-                    (None, None, Some(suc_iter)) => {
-                        if iteration > suc_iter {
-                            maximal_iteration = Some(iteration);
-                        }
-                        if iteration < suc_iter {
-                            minimal_iteration = Some(iteration);
-                        }
-                    }
-                    (Some(min_iter), None, Some(suc_iter)) => {
-                        if iteration > suc_iter {
-                            maximal_iteration = Some(iteration);
-                        }
-                        if iteration < suc_iter && iteration > min_iter {
-                            minimal_iteration = Some(iteration);
-                        }
-                    }
-                    (None, Some(max_iter), Some(suc_iter)) => {
-                        if iteration > suc_iter && iteration < max_iter {
-                            maximal_iteration = Some(iteration);
-                        }
-                        if iteration < suc_iter {
-                            minimal_iteration = Some(iteration);
-                        }
-                    }
-                    (Some(min_iter), Some(max_iter), Some(suc_iter)) => {
-                        if iteration > suc_iter && iteration < max_iter {
-                            maximal_iteration = Some(iteration);
-                        }
-                        if iteration < suc_iter && iteration > min_iter {
-                            minimal_iteration = Some(iteration);
-                        }
-                    }
-                    (_, _, None) => (),
-                },
-            };
-        }
-        Ok(accumulator)
-    }
-
     fn load_all_elements_at_iteration(
         &self,
         iteration: u64,
@@ -239,18 +343,12 @@ impl<Id, Element, const TEMP: bool> StorageInterfaceLoad<Id, Element>
         Id: std::hash::Hash + std::cmp::Eq + for<'a> Deserialize<'a>,
         Element: for<'a> Deserialize<'a>,
     {
-        let tree = match self.open_tree(iteration)? {
-            Some(tree) => tree,
-            None => return Ok(HashMap::new()),
-        };
-        tree.iter()
-            .map(|entry_result| {
-                let (identifier_serialized, element_serialized) = entry_result?;
-                let identifier: Id = bincode::deserialize(&identifier_serialized)?;
-                let element: Element = bincode::deserialize(&element_serialized)?;
-                Ok((identifier, element))
-            })
-            .collect::<Result<HashMap<Id, Element>, StorageError>>()
+        Self::decode_elements(
+            self.elements
+                .scan_prefix(StorageKey::iteration_prefix(iteration)),
+        )
+        .map(|entry| entry.map(|(_, identifier, element)| (identifier, element)))
+        .collect()
     }
 
     fn load_all_elements(&self) -> Result<BTreeMap<u64, HashMap<Id, Element>>, StorageError>
@@ -258,41 +356,136 @@ impl<Id, Element, const TEMP: bool> StorageInterfaceLoad<Id, Element>
         Id: std::hash::Hash + std::cmp::Eq + for<'a> Deserialize<'a>,
         Element: for<'a> Deserialize<'a>,
     {
-        self.db
-            .tree_names()
-            .iter()
-            .map(|tree_name_serialized| {
-                let tree = self.db.open_tree(tree_name_serialized)?;
-                let iteration = Self::key_to_iteration(tree_name_serialized)?;
-                let identifier_to_element = tree
-                    .iter()
-                    .map(|entry_result| {
-                        let (identifier_serialized, element_serialized) = entry_result?;
-                        let identifier: Id = bincode::deserialize(&identifier_serialized)?;
-                        let element: Element = bincode::deserialize(&element_serialized)?;
-                        Ok((identifier, element))
-                    })
-                    .collect::<Result<HashMap<Id, Element>, StorageError>>()?;
-                Ok((iteration, identifier_to_element))
-            })
-            .collect::<Result<BTreeMap<u64, HashMap<Id, Element>>, StorageError>>()
+        // Elements are ordered by their iteration such that we only need to iterate once
+        let mut all_elements: BTreeMap<u64, HashMap<Id, Element>> = BTreeMap::new();
+        for entry in Self::decode_elements(self.elements.iter()) {
+            let (key, identifier, element) = entry?;
+            all_elements
+                .entry(key.iteration)
+                .or_default()
+                .insert(identifier, element);
+        }
+        Ok(all_elements)
     }
 
     fn get_all_iterations(&self) -> Result<Vec<u64>, StorageError> {
-        let iterations = self
+        // Jump from one iteration to the next instead of reading every key
+        let mut iterations = Vec::new();
+        let mut start = StorageKey::iteration_prefix(0).to_vec();
+        while let Some((key, _)) = self.elements.range(start..).next().transpose()? {
+            let iteration = StorageKey::from_bytes(&key)?.iteration;
+            iterations.push(iteration);
+            match iteration.checked_add(1) {
+                Some(next) => start = StorageKey::iteration_prefix(next).to_vec(),
+                None => break,
+            }
+        }
+        Ok(iterations)
+    }
+}
+
+#[cfg(test)]
+mod test_sled {
+    use super::*;
+
+    type Interface = SledStorageInterface<(u64, u64), f64, true>;
+
+    #[test]
+    fn store_load_structured_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut interface_0 = Interface::open_or_create(dir.path(), 0).unwrap();
+        let mut interface_1 = interface_0.clone();
+        interface_1.instance = 1;
+        for iteration in [0, 10, 300] {
+            let values = [((0, iteration), 1.0), ((0, 1), iteration as f64)];
+            interface_0
+                .store_batch_elements(iteration, values.iter().map(|(i, e)| (i, e)))
+                .unwrap();
+            interface_1
+                .store_single_element(iteration, &(1, 0), &2.0)
+                .unwrap();
+        }
+        assert!(interface_1.store_single_element(0, &(1, 0), &2.0).is_err());
+        // Identifiers are unique across subdomains
+        assert!(interface_0.store_single_element(0, &(1, 0), &2.0).is_err());
+        assert!(interface_0
+            .store_batch_elements(10, [(&(1, 0), &3.0)])
+            .is_err());
+
+        assert_eq!(interface_0.get_all_iterations().unwrap(), vec![0, 10, 300]);
+        assert_eq!(
+            interface_1.load_all_elements_at_iteration(10).unwrap(),
+            HashMap::from([((0, 10), 1.0), ((0, 1), 10.0), ((1, 0), 2.0)])
+        );
+        assert_eq!(
+            interface_0.load_single_element(300, &(1, 0)).unwrap(),
+            Some(2.0)
+        );
+        assert_eq!(
+            interface_0.load_single_element(300, &(0, 10)).unwrap(),
+            None
+        );
+        assert_eq!(
+            interface_0.load_element_history(&(0, 1)).unwrap(),
+            HashMap::from([(0, 0.0), (10, 10.0), (300, 300.0)])
+        );
+        assert_eq!(interface_0.load_all_elements().unwrap().len(), 3);
+
+        // Range scans over single subdomains
+        assert_eq!(
+            interface_0.load_subdomain_at_iteration(300, 1).unwrap(),
+            HashMap::from([((1, 0), 2.0)])
+        );
+        let history = interface_0.load_subdomain_history(0).unwrap();
+        assert_eq!(
+            history.keys().copied().collect::<Vec<_>>(),
+            vec![0, 10, 300]
+        );
+        assert_eq!(
+            history[&10],
+            HashMap::from([((0, 10), 1.0), ((0, 1), 10.0)])
+        );
+    }
+
+    #[test]
+    fn read_legacy_trees() {
+        let db = sled::Config::default().temporary(true).open().unwrap();
+        let tree = db.open_tree(5u64.to_le_bytes()).unwrap();
+        let identifier = bincode::serialize(&(0u64, 1u64)).unwrap();
+        tree.insert(identifier, bincode::serialize(&3.0).unwrap())
+            .unwrap();
+        let mut interface = Interface::from_db(db, 1).unwrap();
+        // The legacy tree was migrated
+        assert!(!interface
             .db
             .tree_names()
             .iter()
-            // TODO this should not be here! Fix it properly (I asked on sled discord)
-            .filter(|key| {
-                **key
-                    != sled::IVec::from(&[
-                        95, 95, 115, 108, 101, 100, 95, 95, 100, 101, 102, 97, 117, 108, 116,
-                    ])
-            })
-            .map(|tree_name_serialized| Self::key_to_iteration(tree_name_serialized))
-            .collect::<Result<Vec<_>, StorageError>>()?;
+            .any(|name| Interface::is_legacy_tree(name)));
+        assert_eq!(interface.get_all_iterations().unwrap(), vec![5]);
+        assert_eq!(
+            interface.load_single_element(5, &(0, 1)).unwrap(),
+            Some(3.0)
+        );
+        assert_eq!(interface.load_all_elements().unwrap()[&5].len(), 1);
 
-        Ok(iterations)
+        // Elements stored after opening the database can be read together with old ones
+        interface.store_single_element(5, &(1, 1), &4.0).unwrap();
+        interface
+            .store_batch_elements(6, [(&(0, 1), &5.0)])
+            .unwrap();
+        assert!(interface.store_single_element(5, &(0, 1), &6.0).is_err());
+        assert_eq!(interface.get_all_iterations().unwrap(), vec![5, 6]);
+        assert_eq!(
+            interface.load_all_elements_at_iteration(5).unwrap(),
+            HashMap::from([((0, 1), 3.0), ((1, 1), 4.0)])
+        );
+        assert_eq!(
+            interface.load_single_element(5, &(1, 1)).unwrap(),
+            Some(4.0)
+        );
+        assert_eq!(
+            interface.load_element_history(&(0, 1)).unwrap(),
+            HashMap::from([(5, 3.0), (6, 5.0)])
+        );
     }
 }
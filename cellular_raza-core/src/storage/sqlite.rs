@@ -0,0 +1,325 @@
+use super::concepts::StorageError;
+use super::concepts::{StorageInterfaceLoad, StorageInterfaceOpen, StorageInterfaceStore};
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+/// Statements which create all tables and indices
+const CREATE_TABLES: &str = "
+CREATE TABLE IF NOT EXISTS elements (
+    iteration INTEGER NOT NULL,
+    identifier TEXT NOT NULL,
+    parent TEXT,
+    position TEXT,
+    element TEXT NOT NULL,
+    PRIMARY KEY (iteration, identifier)
+);
+CREATE INDEX IF NOT EXISTS elements_identifier ON elements (identifier);
+CREATE INDEX IF NOT EXISTS elements_parent ON elements (parent);
+CREATE VIEW IF NOT EXISTS iterations AS SELECT DISTINCT iteration FROM elements;
+";
+
+/// Store results in an [SQLite](https://sqlite.org) database.
+///
+/// All elements are stored in a single `elements` table inside the `sqlite/results.db` file.
+/// Every row contains the iteration, the identifier and the element itself.
+/// Identifiers and elements are encoded as json such that they can be queried with the
+/// [json functions](https://sqlite.org/json1.html) of SQLite and read by other languages
+/// without loading the whole results.
+/// For cells, the identifier of the parent and the position are additionally extracted into
+/// the `parent` and `position` columns.
+/// The position is given by the first field named `pos` or `position` inside the cell.
+/// Rows are indexed by their iteration, identifier and parent.
+///
+/// ```sql
+/// -- Number of cells at every iteration
+/// SELECT iteration, COUNT(*) FROM elements GROUP BY iteration;
+/// -- Trajectory of a single cell
+/// SELECT iteration, position FROM elements WHERE identifier = '[0,3]' ORDER BY iteration;
+/// ```
+///
+/// Multiple instances can write to the same database simultaneously.
+/// This option requires the `sqlite` feature.
+#[derive(Clone, Debug)]
+pub struct SqliteStorageInterface<Id, Element> {
+    /// Connection to the database which is shared by all clones
+    connection: Arc<Mutex<rusqlite::Connection>>,
+    /// Marks the type of identifiers
+    id_phantom: PhantomData<Id>,
+    /// Marks the type of elements
+    element_phantom: PhantomData<Element>,
+}
+
+impl<Id, Element> SqliteStorageInterface<Id, Element> {
+    /// Name of the database file inside the storage location
+    pub const FILE_NAME: &'static str = "results.db";
+
+    /// Locks the connection to the database
+    fn connection(&self) -> Result<std::sync::MutexGuard<'_, rusqlite::Connection>, StorageError> {
+        self.connection
+            .lock()
+            .map_err(|e| StorageError::InitError(e.to_string()))
+    }
+}
+
+/// Finds the first field named `pos` or `position` in the json value.
+fn find_position(value: &serde_json::Value) -> Option<&serde_json::Value> {
+    match value {
+        serde_json::Value::Object(map) => map
+            .get("pos")
+            .or_else(|| map.get("position"))
+            .or_else(|| map.values().find_map(find_position)),
+        serde_json::Value::Array(values) => values.iter().find_map(find_position),
+        _ => None,
+    }
+}
+
+/// Serializes the element and extracts the parent and position of cells.
+///
+/// Cells are stored together with their auxiliary storage as `(CellBox, AuxStorage)`.
+fn element_columns<Element: Serialize>(
+    element: &Element,
+) -> Result<(Option<String>, Option<String>, String), StorageError> {
+    let value = serde_json::to_value(element)?;
+    let cell_box = value
+        .pointer("/0")
+        .filter(|cbox| cbox.get("cell").is_some());
+    let parent = cell_box
+        .and_then(|cbox| cbox.get("parent"))
+        .filter(|parent| !parent.is_null())
+        .map(|parent| parent.to_string());
+    let position = cell_box
+        .and_then(|cbox| find_position(&cbox["cell"]))
+        .map(|position| position.to_string());
+    Ok((parent, position, value.to_string()))
+}
+
+impl<Id, Element> StorageInterfaceOpen for SqliteStorageInterface<Id, Element> {
+    fn open_or_create(
+        location: &std::path::Path,
+        _storage_instance: u64,
+    ) -> Result<Self, StorageError> {
+        std::fs::create_dir_all(location)?;
+        let connection = rusqlite::Connection::open(location.join(Self::FILE_NAME))?;
+        // Wait for other instances which are currently writing
+        connection.busy_timeout(std::time::Duration::from_secs(60))?;
+        connection.query_row("PRAGMA journal_mode=WAL", [], |_| Ok(()))?;
+        connection.execute_batch(CREATE_TABLES)?;
+        Ok(SqliteStorageInterface {
+            connection: Arc::new(Mutex::new(connection)),
+            id_phantom: PhantomData,
+            element_phantom: PhantomData,
+        })
+    }
+}
+
+impl<Id, Element> StorageInterfaceStore<Id, Element> for SqliteStorageInterface<Id, Element> {
+    fn store_single_element(
+        &mut self,
+        iteration: u64,
+        identifier: &Id,
+        element: &Element,
+    ) -> Result<(), StorageError>
+    where
+        Id: Serialize,
+        Element: Serialize,
+    {
+        self.store_batch_elements(iteration, [(identifier, element)])
+    }
+
+    fn store_batch_elements<'a, I>(
+        &'a mut self,
+        iteration: u64,
+        identifiers_elements: I,
+    ) -> Result<(), StorageError>
+    where
+        Id: 'a + Serialize,
+        Element: 'a + Serialize,
+        I: Clone + IntoIterator<Item = (&'a Id, &'a Element)>,
+    {
+        let mut connection = self.connection()?;
+        let transaction = connection.transaction()?;
+        {
+            let mut statement = transaction.prepare_cached(
+                "INSERT OR REPLACE INTO elements (iteration, identifier, parent, position, element)
+                VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?;
+            for (identifier, element) in identifiers_elements.into_iter() {
+                let (parent, position, element) = element_columns(element)?;
+                statement.execute(rusqlite::params![
+                    iteration as i64,
+                    serde_json::to_string(identifier)?,
+                    parent,
+                    position,
+                    element,
+                ])?;
+            }
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+}
+
+impl<Id, Element> StorageInterfaceLoad<Id, Element> for SqliteStorageInterface<Id, Element> {
+    fn load_single_element(
+        &self,
+        iteration: u64,
+        identifier: &Id,
+    ) -> Result<Option<Element>, StorageError>
+    where
+        Id: Serialize + for<'a> Deserialize<'a>,
+        Element: for<'a> Deserialize<'a>,
+    {
+        use rusqlite::OptionalExtension;
+        let element: Option<String> = self
+            .connection()?
+            .prepare_cached(
+                "SELECT element FROM elements WHERE iteration = ?1 AND identifier = ?2",
+            )?
+            .query_row(
+                rusqlite::params![iteration as i64, serde_json::to_string(identifier)?],
+                |row| row.get(0),
+            )
+            .optional()?;
+        match element {
+            Some(element) => Ok(Some(serde_json::from_str(&element)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn load_element_history(&self, identifier: &Id) -> Result<HashMap<u64, Element>, StorageError>
+    where
+        Id: Serialize,
+        Element: for<'a> Deserialize<'a>,
+    {
+        let connection = self.connection()?;
+        let mut statement = connection
+            .prepare_cached("SELECT iteration, element FROM elements WHERE identifier = ?1")?;
+        let rows = statement.query_map([serde_json::to_string(identifier)?], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?;
+        rows.map(|row| {
+            let (iteration, element) = row?;
+            Ok((iteration as u64, serde_json::from_str(&element)?))
+        })
+        .collect()
+    }
+
+    fn load_all_elements_at_iteration(
+        &self,
+        iteration: u64,
+    ) -> Result<HashMap<Id, Element>, StorageError>
+    where
+        Id: std::hash::Hash + std::cmp::Eq + for<'a> Deserialize<'a>,
+        Element: for<'a> Deserialize<'a>,
+    {
+        let connection = self.connection()?;
+        let mut statement = connection
+            .prepare_cached("SELECT identifier, element FROM elements WHERE iteration = ?1")?;
+        let rows = statement.query_map([iteration as i64], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        rows.map(|row| {
+            let (identifier, element) = row?;
+            Ok((
+                serde_json::from_str(&identifier)?,
+                serde_json::from_str(&element)?,
+            ))
+        })
+        .collect()
+    }
+
+    fn get_all_iterations(&self) -> Result<Vec<u64>, StorageError> {
+        let connection = self.connection()?;
+        let mut statement =
+            connection.prepare_cached("SELECT iteration FROM iterations ORDER BY iteration")?;
+        let iterations = statement
+            .query_map([], |row| row.get::<_, i64>(0))?
+            .map(|iteration| Ok(iteration? as u64))
+            .collect::<Result<Vec<_>, StorageError>>()?;
+        Ok(iterations)
+    }
+}
+
+#[cfg(test)]
+mod test_sqlite {
+    use super::*;
+
+    type Cell = (serde_json::Value, ());
+
+    fn cell(counter: u64, parent: Option<u64>, pos: [f64; 2]) -> Cell {
+        let cbox = serde_json::json!({
+            "identifier": [0, counter],
+            "parent": parent.map(|p| [0, p]),
+            "cell": {"mechanics": {"pos": pos, "vel": [0.0, 0.0]}, "radius": 1.0},
+        });
+        (cbox, ())
+    }
+
+    #[test]
+    fn store_load_cells() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut interface =
+            SqliteStorageInterface::<(u64, u64), Cell>::open_or_create(dir.path(), 0).unwrap();
+        let cells = [
+            ((0, 1), cell(1, None, [1.0, 2.0])),
+            ((0, 2), cell(2, Some(1), [3.0, 4.0])),
+        ];
+        interface
+            .store_batch_elements(10, cells.iter().map(|(i, c)| (i, c)))
+            .unwrap();
+        interface
+            .store_single_element(20, &cells[0].0, &cells[0].1)
+            .unwrap();
+
+        assert_eq!(interface.get_all_iterations().unwrap(), vec![10, 20]);
+        assert_eq!(
+            interface.load_all_elements_at_iteration(10).unwrap().len(),
+            2
+        );
+        assert_eq!(
+            interface.load_single_element(10, &(0, 2)).unwrap(),
+            Some(cells[1].1.clone())
+        );
+        assert_eq!(interface.load_element_history(&(0, 1)).unwrap().len(), 2);
+
+        // Query extracted columns directly
+        let connection = interface.connection().unwrap();
+        let (parent, position): (String, String) = connection
+            .query_row(
+                "SELECT parent, position FROM elements WHERE identifier = '[0,2]'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(parent, "[0,1]");
+        assert_eq!(position, "[3.0,4.0]");
+    }
+
+    #[test]
+    fn multiple_instances() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut interface_0 =
+            SqliteStorageInterface::<usize, f64>::open_or_create(dir.path(), 0).unwrap();
+        let mut interface_1 =
+            SqliteStorageInterface::<usize, f64>::open_or_create(dir.path(), 1).unwrap();
+        interface_0.store_single_element(0, &0, &1.0).unwrap();
+        interface_1.store_single_element(0, &1, &2.0).unwrap();
+        assert_eq!(
+            interface_0.load_all_elements_at_iteration(0).unwrap(),
+            HashMap::from([(0, 1.0), (1, 2.0)])
+        );
+        let connection = interface_1.connection().unwrap();
+        let parent: Option<String> = connection
+            .query_row(
+                "SELECT parent FROM elements WHERE identifier = '0'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(parent, None);
+    }
+}
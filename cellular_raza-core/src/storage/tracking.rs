@@ -0,0 +1,363 @@
+use super::concepts::{StorageError, StorageInterfaceLoad};
+use cellular_raza_concepts::PositionLike;
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+
+/// A single continuous track of one cell until it divides or disappears.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct TrackSegment<Id> {
+    /// Identifier of the parent cell if the track was created by division
+    pub parent: Option<Id>,
+    /// Positions of the cell at each saved iteration
+    pub positions: BTreeMap<u64, Vec<f64>>,
+}
+
+/// Collection of cell trajectories which can be exported in formats commonly used for cell
+/// tracking.
+///
+/// The exported files can be analyzed with the same pipelines as experimental data.
+/// We currently support
+/// - [TrackMate](https://imagej.net/plugins/trackmate/) xml files
+///   (see [write_trackmate_xml](TrackingExport::write_trackmate_xml))
+/// - The lineage text format of the [Cell Tracking Challenge](https://celltrackingchallenge.net/)
+///   (see [write_ctc_tracks](TrackingExport::write_ctc_tracks))
+///
+/// ```
+/// # use cellular_raza_core::storage::TrackingExport;
+/// let export = TrackingExport::from_elements([
+///     (0, 1, None, vec![0.0, 0.0]),
+///     (1, 1, None, vec![1.0, 0.0]),
+///     (2, 2, Some(1), vec![2.0, 0.5]),
+///     (2, 3, Some(1), vec![2.0, -0.5]),
+/// ]);
+/// let mut ctc = Vec::new();
+/// export.write_ctc_tracks(&mut ctc)?;
+/// assert_eq!(String::from_utf8(ctc).unwrap(), "1 0 1 0\n2 2 2 1\n3 2 2 1\n");
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct TrackingExport<Id: Ord> {
+    /// All iterations at which results were stored. Their position in this list determines the
+    /// frame number in the exported files.
+    pub iterations: BTreeSet<u64>,
+    /// Individual tracks ordered by their identifier
+    pub tracks: BTreeMap<Id, TrackSegment<Id>>,
+}
+
+impl<Id> TrackingExport<Id>
+where
+    Id: Clone + Ord,
+{
+    /// Constructs the export from entries of the form `(iteration, identifier, parent, position)`.
+    pub fn from_elements(
+        elements: impl IntoIterator<Item = (u64, Id, Option<Id>, Vec<f64>)>,
+    ) -> Self {
+        let mut iterations = BTreeSet::new();
+        let mut tracks = BTreeMap::<Id, TrackSegment<Id>>::new();
+        for (iteration, identifier, parent, position) in elements {
+            iterations.insert(iteration);
+            tracks
+                .entry(identifier)
+                .or_insert_with(|| TrackSegment {
+                    parent,
+                    positions: BTreeMap::new(),
+                })
+                .positions
+                .insert(iteration, position);
+        }
+        Self { iterations, tracks }
+    }
+
+    /// Loads all stored elements and extracts parent and position with the given function.
+    pub fn from_storage<Element, S, F>(storage: &S, extract: F) -> Result<Self, StorageError>
+    where
+        S: StorageInterfaceLoad<Id, Element>,
+        Id: std::hash::Hash + std::cmp::Eq + for<'a> Deserialize<'a>,
+        Element: for<'a> Deserialize<'a>,
+        F: Fn(&Element) -> (Option<Id>, Vec<f64>),
+    {
+        let all_elements = storage.load_all_elements()?;
+        let extract = &extract;
+        Ok(Self::from_elements(all_elements.into_iter().flat_map(
+            |(iteration, elements)| {
+                elements.into_iter().map(move |(identifier, element)| {
+                    let (parent, position) = extract(&element);
+                    (iteration, identifier, parent, position)
+                })
+            },
+        )))
+    }
+
+    /// Loads all stored elements and uses the
+    /// [representative_point](PositionLike::representative_point) of the extracted position.
+    ///
+    /// This allows to track cells whose position consists of multiple points such as rods or
+    /// vertex models.
+    pub fn from_storage_position_like<Element, S, F, P, const D: usize>(
+        storage: &S,
+        extract: F,
+    ) -> Result<Self, StorageError>
+    where
+        S: StorageInterfaceLoad<Id, Element>,
+        Id: std::hash::Hash + std::cmp::Eq + for<'a> Deserialize<'a>,
+        Element: for<'a> Deserialize<'a>,
+        F: Fn(&Element) -> (Option<Id>, P),
+        P: PositionLike<f64, D>,
+    {
+        Self::from_storage(storage, |element| {
+            let (parent, position) = extract(element);
+            (parent, position.representative_point().to_vec())
+        })
+    }
+
+    /// Frame number of every stored iteration
+    fn frames(&self) -> BTreeMap<u64, usize> {
+        self.iterations
+            .iter()
+            .enumerate()
+            .map(|(frame, iteration)| (*iteration, frame))
+            .collect()
+    }
+
+    /// Splits every track at the divisions of its cell.
+    ///
+    /// A cell which keeps its identifier after dividing continues in a new segment whose parent
+    /// is the segment before the division.
+    /// Every segment is given by its first and last iteration and the index of its parent.
+    fn lineage_segments(&self) -> Vec<(u64, u64, Option<usize>)> {
+        let mut divisions = BTreeMap::<&Id, BTreeSet<u64>>::new();
+        for track in self.tracks.values() {
+            if let (Some(parent), Some(first)) = (&track.parent, track.positions.keys().next()) {
+                divisions.entry(parent).or_default().insert(*first);
+            }
+        }
+
+        let mut segments: Vec<(u64, u64, Option<usize>)> = Vec::new();
+        let mut segments_of = BTreeMap::<&Id, Vec<usize>>::new();
+        for (id, track) in self.tracks.iter() {
+            let mut boundaries = divisions.get(id).into_iter().flatten().peekable();
+            let indices = segments_of.entry(id).or_default();
+            for iteration in track.positions.keys() {
+                let mut divided = false;
+                while boundaries.next_if(|d| *d <= iteration).is_some() {
+                    divided = true;
+                }
+                match indices.last() {
+                    Some(&n) if !divided => segments[n].1 = *iteration,
+                    previous => {
+                        segments.push((*iteration, *iteration, previous.copied()));
+                        indices.push(segments.len() - 1);
+                    }
+                }
+            }
+        }
+
+        // Daughters descend from the last segment of their parent before they appeared
+        for (id, track) in self.tracks.iter() {
+            if let (Some(parent), Some(first)) = (&track.parent, track.positions.keys().next()) {
+                let parent_segment = segments_of
+                    .get(parent)
+                    .and_then(|indices| indices.iter().rev().find(|n| segments[**n].1 < *first))
+                    .copied();
+                segments[segments_of[id][0]].2 = parent_segment;
+            }
+        }
+        segments
+    }
+
+    /// Writes the lineage in the format of the Cell Tracking Challenge (`res_track.txt`).
+    ///
+    /// Every line has the form `L B E P` where `L` is the label of the track, `B` and `E` are the
+    /// first and last frame and `P` is the label of the parent (`0` if no parent exists).
+    /// Tracks end when the cell divides.
+    /// If the dividing cell keeps its identifier, it continues in a new track which has the
+    /// previous one as its parent.
+    pub fn write_ctc_tracks(&self, mut writer: impl Write) -> Result<(), std::io::Error> {
+        let frames = self.frames();
+        for (n, (first, last, parent)) in self.lineage_segments().into_iter().enumerate() {
+            let parent = parent.map_or(0, |p| p + 1);
+            writeln!(
+                writer,
+                "{} {} {} {}",
+                n + 1,
+                frames[&first],
+                frames[&last],
+                parent
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Writes all spots and tracks as a TrackMate xml file.
+    ///
+    /// Each stored position becomes one spot.
+    /// Consecutive positions of the same cell are connected by edges and daughter cells are
+    /// linked to the last spot of their parent such that a complete lineage forms one track.
+    /// The `time_interval` is used to calculate the `POSITION_T` value of each frame.
+    pub fn write_trackmate_xml(
+        &self,
+        mut writer: impl Write,
+        time_interval: f64,
+        radius: f64,
+    ) -> Result<(), std::io::Error> {
+        // Assign unique spot ids
+        let frames = self.frames();
+        let mut spot_ids = BTreeMap::new();
+        let mut spots_in_frame = BTreeMap::<usize, Vec<_>>::new();
+        for (id, segment) in self.tracks.iter() {
+            for (iteration, position) in segment.positions.iter() {
+                let spot_id = spot_ids.len();
+                spot_ids.insert((id, *iteration), spot_id);
+                spots_in_frame
+                    .entry(frames[iteration])
+                    .or_default()
+                    .push((spot_id, position));
+            }
+        }
+
+        // Determine the root of every lineage such that all descendants form one track
+        let root_of = |id: &Id| -> Id {
+            let mut current = id.clone();
+            while let Some(parent) = self.tracks.get(&current).and_then(|s| s.parent.clone()) {
+                if !self.tracks.contains_key(&parent) {
+                    break;
+                }
+                current = parent;
+            }
+            current
+        };
+        let mut edges = BTreeMap::<Id, Vec<(usize, usize)>>::new();
+        for (id, segment) in self.tracks.iter() {
+            let root = root_of(id);
+            let track_edges = edges.entry(root).or_default();
+            let iterations: Vec<_> = segment.positions.keys().collect();
+            for window in iterations.windows(2) {
+                track_edges.push((spot_ids[&(id, *window[0])], spot_ids[&(id, *window[1])]));
+            }
+            if let (Some(parent), Some(first)) = (&segment.parent, iterations.first()) {
+                let parent_last = self
+                    .tracks
+                    .get(parent)
+                    .and_then(|p| p.positions.range(..**first).next_back());
+                if let Some((parent_iteration, _)) = parent_last {
+                    track_edges.push((
+                        spot_ids[&(parent, *parent_iteration)],
+                        spot_ids[&(id, **first)],
+                    ));
+                }
+            }
+        }
+
+        writeln!(writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+        writeln!(writer, "<TrackMate version=\"7.0.0\">")?;
+        writeln!(
+            writer,
+            "  <Model spatialunits=\"pixel\" timeunits=\"frame\">"
+        )?;
+        writeln!(writer, "    <AllSpots nspots=\"{}\">", spot_ids.len())?;
+        for (frame, spots) in spots_in_frame.iter() {
+            writeln!(writer, "      <SpotsInFrame frame=\"{frame}\">")?;
+            for (spot_id, position) in spots {
+                let coord = |i: usize| position.get(i).copied().unwrap_or(0.0);
+                writeln!(
+                    writer,
+                    "        <Spot ID=\"{spot_id}\" name=\"ID{spot_id}\" QUALITY=\"1.0\" \
+                    POSITION_X=\"{}\" POSITION_Y=\"{}\" POSITION_Z=\"{}\" POSITION_T=\"{}\" \
+                    FRAME=\"{frame}\" RADIUS=\"{radius}\" VISIBILITY=\"1\" />",
+                    coord(0),
+                    coord(1),
+                    coord(2),
+                    *frame as f64 * time_interval,
+                )?;
+            }
+            writeln!(writer, "      </SpotsInFrame>")?;
+        }
+        writeln!(writer, "    </AllSpots>")?;
+        writeln!(writer, "    <AllTracks>")?;
+        for (track_id, (_, track_edges)) in edges.iter().enumerate() {
+            writeln!(
+                writer,
+                "      <Track name=\"Track_{track_id}\" TRACK_ID=\"{track_id}\" \
+                NUMBER_SPOTS=\"{}\">",
+                track_edges.len() + 1
+            )?;
+            for (source, target) in track_edges {
+                writeln!(
+                    writer,
+                    "        <Edge SPOT_SOURCE_ID=\"{source}\" SPOT_TARGET_ID=\"{target}\" />"
+                )?;
+            }
+            writeln!(writer, "      </Track>")?;
+        }
+        writeln!(writer, "    </AllTracks>")?;
+        writeln!(writer, "    <FilteredTracks>")?;
+        for track_id in 0..edges.len() {
+            writeln!(writer, "      <TrackID TRACK_ID=\"{track_id}\" />")?;
+        }
+        writeln!(writer, "    </FilteredTracks>")?;
+        writeln!(writer, "  </Model>")?;
+        writeln!(writer, "</TrackMate>")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_tracking_export {
+    use super::*;
+
+    fn division_export() -> TrackingExport<u64> {
+        TrackingExport::from_elements([
+            (10, 1, None, vec![0.0, 0.0]),
+            (20, 1, None, vec![1.0, 0.0]),
+            (30, 2, Some(1), vec![2.0, 1.0]),
+            (30, 3, Some(1), vec![2.0, -1.0]),
+            (40, 3, Some(1), vec![3.0, -1.0]),
+            (40, 4, None, vec![5.0, 5.0]),
+        ])
+    }
+
+    #[test]
+    fn ctc_lineage() {
+        let mut out = Vec::new();
+        division_export().write_ctc_tracks(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out, "1 0 1 0\n2 2 2 1\n3 2 3 1\n4 3 3 0\n");
+    }
+
+    #[test]
+    fn ctc_mother_keeps_identifier() {
+        let export = TrackingExport::from_elements([
+            (0, 1, None, vec![0.0]),
+            (1, 1, None, vec![0.0]),
+            (2, 1, None, vec![-1.0]),
+            (2, 2, Some(1), vec![1.0]),
+            (3, 1, None, vec![-2.0]),
+            (3, 2, Some(1), vec![2.0]),
+            (4, 2, Some(1), vec![3.0]),
+            (4, 3, Some(2), vec![3.0]),
+        ]);
+        let mut out = Vec::new();
+        export.write_ctc_tracks(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        // The mother continues in track 2 and the daughter 2 divides again in frame 4
+        assert_eq!(out, "1 0 1 0\n2 2 3 1\n3 2 3 1\n4 4 4 3\n5 4 4 3\n");
+    }
+
+    #[test]
+    fn trackmate_spots_and_edges() {
+        let mut out = Vec::new();
+        division_export()
+            .write_trackmate_xml(&mut out, 0.5, 1.0)
+            .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("<AllSpots nspots=\"6\">"));
+        // Lineage of cell 1 forms one track, cell 4 another one
+        assert_eq!(out.matches("<Track ").count(), 2);
+        // 1 edge for cell 1, 1 edge for cell 3 and 2 division edges
+        assert_eq!(out.matches("<Edge ").count(), 4);
+        assert!(out.contains("POSITION_T=\"1.5\""));
+    }
+}
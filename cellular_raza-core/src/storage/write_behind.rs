@@ -0,0 +1,334 @@
+use super::concepts::{StorageError, StorageInterfaceStore, StorageManager};
+
+use serde::{Deserialize, Serialize};
+
+/// Determines what happens when the queue of a [WriteBehindStorage] is full.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub enum Backpressure {
+    /// Wait until the storage thread has written enough frames to free up space in the queue.
+    Block,
+    /// Return a [StorageError::WriteBehindError] instead of waiting.
+    Error,
+}
+
+/// Settings for storing results on a dedicated thread.
+///
+/// See [StorageBuilder::write_behind](super::StorageBuilder::write_behind).
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct WriteBehind {
+    /// Maximum number of frames which can be queued before [Backpressure] is applied.
+    /// [WriteBehind::new] ensures that at least one frame can be queued.
+    pub capacity: usize,
+    /// Behaviour when the queue is full
+    pub backpressure: Backpressure,
+}
+
+impl WriteBehind {
+    /// Queue up to `capacity` frames and block when the queue is full.
+    ///
+    /// The queue holds at least one frame such that a `capacity` of zero is increased to one.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            backpressure: Backpressure::Block,
+        }
+    }
+
+    /// Change the [Backpressure] setting.
+    pub fn backpressure(self, backpressure: Backpressure) -> Self {
+        Self {
+            backpressure,
+            ..self
+        }
+    }
+}
+
+/// All elements stored at one iteration.
+type Frame<Id, Element> = (u64, Vec<(Id, Element)>);
+
+/// Current state of a [WriteBehindStorage]
+enum Mode<Id, Element> {
+    /// Store elements without a dedicated thread
+    Direct(Box<StorageManager<Id, Element>>),
+    /// Hand off elements to a running storage thread
+    Thread {
+        /// Sending end of the bounded queue
+        sender: crossbeam_channel::Sender<Frame<Id, Element>>,
+        /// Handle of the storage thread which returns the manager when finished
+        handle: std::thread::JoinHandle<Result<StorageManager<Id, Element>, StorageError>>,
+        /// Behaviour when the queue is full
+        backpressure: Backpressure,
+    },
+    /// The storage thread has been joined
+    Stopped,
+}
+
+/// Hands off elements to a dedicated storage thread such that the simulation does not need to
+/// wait for writes to finish.
+///
+/// Whether a thread is used is determined by the [WriteBehind] settings of the
+/// [StorageBuilder](super::StorageBuilder) which was used to create the [StorageManager].
+/// Without these settings, all elements are stored directly.
+/// Elements are cloned into owned frames and serialized on the storage thread.
+/// The queue is flushed when calling [WriteBehindStorage::finish] or when dropping this struct.
+///
+/// ```
+/// use cellular_raza_core::storage::*;
+/// let builder = StorageBuilder::new()
+///     .priority([StorageOption::Memory])
+///     .write_behind(WriteBehind::new(4))
+///     .init();
+/// let manager = StorageManager::<usize, f64>::open_or_create(builder, 0)?;
+/// let mut storage = WriteBehindStorage::from_manager(manager)?;
+/// for iteration in 0..10 {
+///     storage.store_batch_elements(iteration, [(&0, &1.0), (&1, &2.0)])?;
+/// }
+/// let manager = storage.finish()?;
+/// assert_eq!(manager.get_all_iterations()?.len(), 10);
+/// # Ok::<(), StorageError>(())
+/// ```
+pub struct WriteBehindStorage<Id, Element> {
+    /// Whether elements are stored directly or by the storage thread
+    mode: Mode<Id, Element>,
+}
+
+impl<Id, Element> WriteBehindStorage<Id, Element>
+where
+    Id: 'static + Send + Serialize + Clone + core::hash::Hash + core::cmp::Eq,
+    Element: 'static + Send + Serialize + Clone,
+{
+    /// Spawns a storage thread if the [StorageBuilder](super::StorageBuilder) of the manager
+    /// requested it.
+    pub fn from_manager(manager: StorageManager<Id, Element>) -> Result<Self, StorageError> {
+        let write_behind = match manager.extract_builder().get_write_behind() {
+            Some(write_behind) => write_behind,
+            None => {
+                return Ok(Self {
+                    mode: Mode::Direct(Box::new(manager)),
+                })
+            }
+        };
+        let (sender, receiver) =
+            crossbeam_channel::bounded::<Frame<Id, Element>>(write_behind.capacity);
+        let mut manager = manager;
+        let handle = std::thread::Builder::new()
+            .name(format!(
+                "cellular_raza-storage_thread-{:03.0}",
+                manager.get_instance()
+            ))
+            .spawn(
+                move || -> Result<StorageManager<Id, Element>, StorageError> {
+                    for (iteration, elements) in receiver {
+                        manager.store_batch_elements(
+                            iteration,
+                            elements.iter().map(|(id, element)| (id, element)),
+                        )?;
+                    }
+                    Ok(manager)
+                },
+            )?;
+        Ok(Self {
+            mode: Mode::Thread {
+                sender,
+                handle,
+                backpressure: write_behind.backpressure,
+            },
+        })
+    }
+}
+
+impl<Id, Element> WriteBehindStorage<Id, Element> {
+    /// Waits for the storage thread to finish.
+    fn join(
+        handle: std::thread::JoinHandle<Result<StorageManager<Id, Element>, StorageError>>,
+    ) -> Result<StorageManager<Id, Element>, StorageError> {
+        handle
+            .join()
+            .map_err(|_| StorageError::WriteBehindError("storage thread panicked".to_owned()))?
+    }
+
+    /// Waits until all queued elements have been written and returns the underlying
+    /// [StorageManager].
+    pub fn finish(mut self) -> Result<StorageManager<Id, Element>, StorageError> {
+        match std::mem::replace(&mut self.mode, Mode::Stopped) {
+            Mode::Direct(manager) => Ok(*manager),
+            Mode::Thread { sender, handle, .. } => {
+                drop(sender);
+                Self::join(handle)
+            }
+            Mode::Stopped => Err(StorageError::WriteBehindError(
+                "storage thread has already stopped".to_owned(),
+            )),
+        }
+    }
+
+    /// Sends a frame to the storage thread while respecting the [Backpressure] setting.
+    fn send(&mut self, frame: Frame<Id, Element>) -> Result<(), StorageError> {
+        let (sender, backpressure) = match &self.mode {
+            Mode::Thread {
+                sender,
+                backpressure,
+                ..
+            } => (sender, backpressure),
+            _ => {
+                return Err(StorageError::WriteBehindError(
+                    "storage thread has already stopped".to_owned(),
+                ))
+            }
+        };
+        let disconnected = match backpressure {
+            Backpressure::Block => sender.send(frame).is_err(),
+            Backpressure::Error => match sender.try_send(frame) {
+                Ok(()) => false,
+                Err(crossbeam_channel::TrySendError::Full(_)) => {
+                    return Err(StorageError::WriteBehindError(
+                        "queue of storage thread is full".to_owned(),
+                    ))
+                }
+                Err(crossbeam_channel::TrySendError::Disconnected(_)) => true,
+            },
+        };
+        // The storage thread only stops early if it encountered an error
+        if disconnected {
+            if let Mode::Thread { handle, .. } = std::mem::replace(&mut self.mode, Mode::Stopped) {
+                Self::join(handle)?;
+            }
+            return Err(StorageError::WriteBehindError(
+                "storage thread has already stopped".to_owned(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl<Id, Element> Drop for WriteBehindStorage<Id, Element> {
+    fn drop(&mut self) {
+        if let Mode::Thread { sender, handle, .. } =
+            std::mem::replace(&mut self.mode, Mode::Stopped)
+        {
+            drop(sender);
+            if let Err(e) = Self::join(handle) {
+                let message = format!("storage thread did not finish writing: {e}");
+                #[cfg(feature = "tracing")]
+                tracing::error!("{message}");
+                #[cfg(not(feature = "tracing"))]
+                eprintln!("Error: {message}");
+            }
+        }
+    }
+}
+
+impl<Id, Element> StorageInterfaceStore<Id, Element> for WriteBehindStorage<Id, Element>
+where
+    Id: core::hash::Hash + core::cmp::Eq + Clone,
+    Element: Clone,
+{
+    fn store_single_element(
+        &mut self,
+        iteration: u64,
+        identifier: &Id,
+        element: &Element,
+    ) -> Result<(), StorageError>
+    where
+        Id: Serialize,
+        Element: Serialize,
+    {
+        match &mut self.mode {
+            Mode::Direct(manager) => manager.store_single_element(iteration, identifier, element),
+            _ => self.send((iteration, vec![(identifier.clone(), element.clone())])),
+        }
+    }
+
+    fn store_batch_elements<'a, I>(
+        &'a mut self,
+        iteration: u64,
+        identifiers_elements: I,
+    ) -> Result<(), StorageError>
+    where
+        Id: 'a + Serialize,
+        Element: 'a + Serialize,
+        I: Clone + IntoIterator<Item = (&'a Id, &'a Element)>,
+    {
+        // Only borrow the manager in the first arm since it is borrowed for the lifetime 'a
+        match self.mode {
+            Mode::Direct(ref mut manager) => {
+                manager.store_batch_elements(iteration, identifiers_elements)
+            }
+            _ => {
+                let elements = identifiers_elements
+                    .into_iter()
+                    .map(|(id, element)| (id.clone(), element.clone()))
+                    .collect();
+                self.send((iteration, elements))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_write_behind {
+    use super::*;
+    use crate::storage::{StorageBuilder, StorageInterfaceLoad, StorageOption};
+
+    fn manager(
+        write_behind: Option<WriteBehind>,
+    ) -> (tempfile::TempDir, StorageManager<u32, String>) {
+        let dir = tempfile::tempdir().unwrap();
+        let builder = StorageBuilder::new()
+            .priority([StorageOption::SerdeJson])
+            .location(dir.path())
+            .write_behind(write_behind)
+            .init();
+        let manager = StorageManager::open_or_create(builder, 0).unwrap();
+        (dir, manager)
+    }
+
+    #[test]
+    fn flush_on_finish() {
+        let (_dir, manager) = manager(Some(WriteBehind::new(1)));
+        let mut storage = WriteBehindStorage::from_manager(manager).unwrap();
+        let elements: Vec<_> = (0..20).map(|i| (i, format!("cell {i}"))).collect();
+        for iteration in 0..5 {
+            storage
+                .store_batch_elements(iteration, elements.iter().map(|(i, e)| (i, e)))
+                .unwrap();
+        }
+        let manager = storage.finish().unwrap();
+        // Iterations are not returned in a particular order
+        let mut iterations = manager.get_all_iterations().unwrap();
+        iterations.sort();
+        assert_eq!(iterations, vec![0, 1, 2, 3, 4]);
+        assert_eq!(
+            manager.load_single_element(3, &7).unwrap(),
+            Some("cell 7".to_owned())
+        );
+    }
+
+    #[test]
+    fn zero_capacity_holds_one_frame() {
+        let write_behind = WriteBehind::new(0).backpressure(Backpressure::Error);
+        assert_eq!(write_behind.capacity, 1);
+        let (_dir, manager) = manager(Some(write_behind));
+        let mut storage = WriteBehindStorage::from_manager(manager).unwrap();
+        storage
+            .store_batch_elements(0, [(&1, &"cell 1".to_owned())])
+            .unwrap();
+        let manager = storage.finish().unwrap();
+        assert_eq!(
+            manager.load_single_element(0, &1).unwrap(),
+            Some("cell 1".to_owned())
+        );
+    }
+
+    #[test]
+    fn direct_without_settings() {
+        let (_dir, manager) = manager(None);
+        let mut storage = WriteBehindStorage::from_manager(manager).unwrap();
+        assert!(matches!(storage.mode, Mode::Direct(_)));
+        storage
+            .store_single_element(0, &1, &"a".to_owned())
+            .unwrap();
+        let manager = storage.finish().unwrap();
+        assert_eq!(manager.get_all_iterations().unwrap(), vec![0]);
+    }
+}
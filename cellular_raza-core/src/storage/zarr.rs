@@ -0,0 +1,436 @@
+use std::path::{Path, PathBuf};
+
+/// Errors which can occur while exporting extracellular fields with [ZarrFieldExport].
+#[derive(Debug)]
+pub enum FieldExportError {
+    /// Writing to the underlying files failed.
+    IoError(std::io::Error),
+    /// Metadata could not be serialized.
+    SerdeJsonError(serde_json::Error),
+    /// A voxel index lies outside of the grid.
+    IndexOutOfBounds {
+        /// Index of the voxel
+        index: Vec<usize>,
+        /// Number of voxels in every dimension
+        shape: Vec<usize>,
+    },
+    /// Only up to 3 spatial dimensions can be exported.
+    TooManyDimensions(usize),
+    /// The number of concentrations of a voxel does not match the number of species.
+    SpeciesMismatch {
+        /// Number of species of the exporter
+        expected: usize,
+        /// Number of values given for the voxel
+        found: usize,
+    },
+}
+
+impl core::fmt::Display for FieldExportError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            FieldExportError::IoError(e) => write!(f, "{e}"),
+            FieldExportError::SerdeJsonError(e) => write!(f, "{e}"),
+            FieldExportError::IndexOutOfBounds { index, shape } => {
+                write!(
+                    f,
+                    "voxel index {index:?} is out of bounds for grid {shape:?}"
+                )
+            }
+            FieldExportError::TooManyDimensions(d) => {
+                write!(f, "can not export fields with {d} spatial dimensions")
+            }
+            FieldExportError::SpeciesMismatch { expected, found } => {
+                write!(
+                    f,
+                    "expected {expected} species but got {found} concentrations"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for FieldExportError {}
+
+impl From<std::io::Error> for FieldExportError {
+    fn from(err: std::io::Error) -> Self {
+        FieldExportError::IoError(err)
+    }
+}
+
+impl From<serde_json::Error> for FieldExportError {
+    fn from(err: serde_json::Error) -> Self {
+        FieldExportError::SerdeJsonError(err)
+    }
+}
+
+/// Writes extracellular concentrations on the global grid as a
+/// [Zarr (v2)](https://zarr-specs.readthedocs.io/en/latest/v2/v2.0.html) store.
+///
+/// Concentrations of individual voxels are collected from all subdomains and assembled into a
+/// single array with dimensions `(t, x, y, z, species)` for every save point.
+/// Spatial dimensions which are not used are omitted.
+/// Coordinates are the middle points of the voxels and the names of the species.
+/// Dimension names are stored in the `_ARRAY_DIMENSIONS` attribute such that the store can be
+/// opened directly with [xarray](https://docs.xarray.dev).
+/// Chunks are written uncompressed with one chunk per save point.
+/// ```python
+/// import xarray as xr
+/// ds = xr.open_zarr("fields.zarr")
+/// ds.concentrations.sel(species="oxygen").isel(t=-1).plot()
+/// # Conversion to NetCDF is done via xarray
+/// ds.to_netcdf("fields.nc")
+/// ```
+/// Voxels which were not given are filled with `NaN`.
+/// ```
+/// # use cellular_raza_core::storage::*;
+/// let dir = tempfile::tempdir()?;
+/// let mut export = ZarrFieldExport::create(
+///     dir.path().join("fields.zarr"),
+///     [0.0, 0.0],
+///     [2.0, 1.0],
+///     [2, 1],
+///     vec!["oxygen".into(), "glucose".into()],
+/// )?;
+/// export.write_time_point(0.0, [([0, 0], [1.0, 2.0]), ([1, 0], [3.0, 4.0])])?;
+/// export.write_time_point(0.5, [([0, 0], [1.5, 2.5]), ([1, 0], [3.5, 4.5])])?;
+/// assert_eq!(export.n_time_points(), 2);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct ZarrFieldExport<const D: usize> {
+    /// Folder of the Zarr store
+    path: PathBuf,
+    /// Lower corner of the grid
+    min: [f64; D],
+    /// Upper corner of the grid
+    max: [f64; D],
+    /// Number of voxels along every dimension
+    shape: [usize; D],
+    /// Names of the exported species
+    species: Vec<String>,
+    /// Number of time points which have been written
+    n_time_points: usize,
+}
+
+impl<const D: usize> ZarrFieldExport<D> {
+    /// Names of the spatial dimensions
+    const SPATIAL_DIMENSIONS: [&'static str; 3] = ["x", "y", "z"];
+
+    /// Creates a new store at the given path and writes the coordinates of the grid.
+    ///
+    /// The grid spans the rectangle between `min` and `max` and contains `shape[i]` voxels in
+    /// the `i`th dimension.
+    /// At most 3 spatial dimensions are supported.
+    pub fn create(
+        path: impl AsRef<Path>,
+        min: [f64; D],
+        max: [f64; D],
+        shape: [usize; D],
+        species: Vec<String>,
+    ) -> Result<Self, FieldExportError> {
+        if D > Self::SPATIAL_DIMENSIONS.len() {
+            return Err(FieldExportError::TooManyDimensions(D));
+        }
+        let path = path.as_ref().to_path_buf();
+        std::fs::create_dir_all(&path)?;
+        write_json(
+            &path.join(".zgroup"),
+            &serde_json::json!({"zarr_format": 2}),
+        )?;
+        write_json(
+            &path.join(".zattrs"),
+            &serde_json::json!({
+                "source": "cellular_raza",
+                "version": env!("CARGO_PKG_VERSION"),
+            }),
+        )?;
+
+        // Coordinates of the spatial dimensions
+        for i in 0..D {
+            let dx = (max[i] - min[i]) / shape[i] as f64;
+            let middles = (0..shape[i])
+                .map(|n| min[i] + (n as f64 + 0.5) * dx)
+                .collect::<Vec<_>>();
+            let name = Self::SPATIAL_DIMENSIONS[i];
+            write_array(&path, name, &[name], &[shape[i]], &[shape[i]], "<f8")?;
+            std::fs::write(path.join(name).join("0"), f64_bytes(&middles))?;
+        }
+
+        // Names of the species are stored as fixed-length unicode strings
+        let n_chars = species
+            .iter()
+            .map(|s| s.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let species_bytes = species
+            .iter()
+            .flat_map(|s| {
+                s.chars()
+                    .map(|c| c as u32)
+                    .chain(core::iter::repeat(0))
+                    .take(n_chars)
+                    .flat_map(u32::to_le_bytes)
+            })
+            .collect::<Vec<_>>();
+        let n_species = species.len();
+        write_array(
+            &path,
+            "species",
+            &["species"],
+            &[n_species],
+            &[n_species.max(1)],
+            &format!("<U{n_chars}"),
+        )?;
+        std::fs::write(path.join("species").join("0"), species_bytes)?;
+
+        let export = Self {
+            path,
+            min,
+            max,
+            shape,
+            species,
+            n_time_points: 0,
+        };
+        export.write_time_dependent_metadata()?;
+        Ok(export)
+    }
+
+    /// Number of save points which have been written
+    pub fn n_time_points(&self) -> usize {
+        self.n_time_points
+    }
+
+    /// Location of the store
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Lower and upper corner of the grid
+    pub fn bounds(&self) -> ([f64; D], [f64; D]) {
+        (self.min, self.max)
+    }
+
+    /// Assembles the concentrations of the given voxels into the global grid.
+    ///
+    /// The result is ordered such that the species changes fastest followed by the last spatial
+    /// dimension.
+    pub fn assemble<C>(
+        &self,
+        voxels: impl IntoIterator<Item = ([usize; D], C)>,
+    ) -> Result<Vec<f64>, FieldExportError>
+    where
+        C: AsRef<[f64]>,
+    {
+        let n_species = self.species.len();
+        let n_voxels = self.shape.iter().product::<usize>();
+        let mut grid = vec![f64::NAN; n_voxels * n_species];
+        for (index, concentrations) in voxels {
+            let concentrations = concentrations.as_ref();
+            if concentrations.len() != n_species {
+                return Err(FieldExportError::SpeciesMismatch {
+                    expected: n_species,
+                    found: concentrations.len(),
+                });
+            }
+            let mut plain_index = 0;
+            for i in 0..D {
+                if index[i] >= self.shape[i] {
+                    return Err(FieldExportError::IndexOutOfBounds {
+                        index: index.to_vec(),
+                        shape: self.shape.to_vec(),
+                    });
+                }
+                plain_index = plain_index * self.shape[i] + index[i];
+            }
+            grid[plain_index * n_species..(plain_index + 1) * n_species]
+                .copy_from_slice(concentrations);
+        }
+        Ok(grid)
+    }
+
+    /// Assembles the given voxels and appends them as a new save point to the store.
+    pub fn write_time_point<C>(
+        &mut self,
+        time: f64,
+        voxels: impl IntoIterator<Item = ([usize; D], C)>,
+    ) -> Result<(), FieldExportError>
+    where
+        C: AsRef<[f64]>,
+    {
+        let grid = self.assemble(voxels)?;
+        let t = self.n_time_points;
+        let chunk_key = core::iter::once(t.to_string())
+            .chain(core::iter::repeat_n("0".to_owned(), D + 1))
+            .collect::<Vec<_>>()
+            .join(".");
+        std::fs::write(
+            self.path.join("concentrations").join(chunk_key),
+            f64_bytes(&grid),
+        )?;
+        std::fs::write(self.path.join("t").join(t.to_string()), f64_bytes(&[time]))?;
+        self.n_time_points += 1;
+        self.write_time_dependent_metadata()
+    }
+
+    /// Updates the shape of the arrays which grow with every save point.
+    fn write_time_dependent_metadata(&self) -> Result<(), FieldExportError> {
+        write_array(&self.path, "t", &["t"], &[self.n_time_points], &[1], "<f8")?;
+        let dimensions = core::iter::once("t")
+            .chain(Self::SPATIAL_DIMENSIONS[..D].iter().copied())
+            .chain(core::iter::once("species"))
+            .collect::<Vec<_>>();
+        let shape = core::iter::once(self.n_time_points)
+            .chain(self.shape)
+            .chain(core::iter::once(self.species.len()))
+            .collect::<Vec<_>>();
+        let chunks = core::iter::once(1)
+            .chain(self.shape)
+            .chain(core::iter::once(self.species.len().max(1)))
+            .collect::<Vec<_>>();
+        write_array(
+            &self.path,
+            "concentrations",
+            &dimensions,
+            &shape,
+            &chunks,
+            "<f8",
+        )
+    }
+}
+
+/// Writes the `.zarray` and `.zattrs` metadata of an uncompressed array.
+fn write_array(
+    path: &Path,
+    name: &str,
+    dimensions: &[&str],
+    shape: &[usize],
+    chunks: &[usize],
+    dtype: &str,
+) -> Result<(), FieldExportError> {
+    let path = path.join(name);
+    std::fs::create_dir_all(&path)?;
+    let fill_value = if dtype.starts_with("<f") {
+        serde_json::json!("NaN")
+    } else {
+        serde_json::Value::Null
+    };
+    write_json(
+        &path.join(".zarray"),
+        &serde_json::json!({
+            "zarr_format": 2,
+            "shape": shape,
+            "chunks": chunks,
+            "dtype": dtype,
+            "compressor": null,
+            "fill_value": fill_value,
+            "order": "C",
+            "filters": null,
+        }),
+    )?;
+    write_json(
+        &path.join(".zattrs"),
+        &serde_json::json!({"_ARRAY_DIMENSIONS": dimensions}),
+    )
+}
+
+/// Writes the value as pretty-printed json
+fn write_json(path: &Path, value: &serde_json::Value) -> Result<(), FieldExportError> {
+    std::fs::write(path, serde_json::to_string_pretty(value)?)?;
+    Ok(())
+}
+
+/// Converts the values into little-endian bytes
+fn f64_bytes(values: &[f64]) -> Vec<u8> {
+    values.iter().flat_map(|x| x.to_le_bytes()).collect()
+}
+
+#[cfg(test)]
+mod test_zarr {
+    use super::*;
+
+    fn read_f64(path: &Path) -> Vec<f64> {
+        std::fs::read(path)
+            .unwrap()
+            .chunks_exact(8)
+            .map(|c| f64::from_le_bytes(c.try_into().unwrap()))
+            .collect()
+    }
+
+    fn read_json(path: &Path) -> serde_json::Value {
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn layout_3d() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut export = ZarrFieldExport::create(
+            dir.path(),
+            [0.0; 3],
+            [4.0, 2.0, 1.0],
+            [2, 2, 1],
+            vec!["a".into()],
+        )
+        .unwrap();
+        let voxels = (0..2).flat_map(|i| (0..2).map(move |j| ([i, j, 0], [(2 * i + j) as f64])));
+        export.write_time_point(1.0, voxels).unwrap();
+
+        let zarray = read_json(&dir.path().join("concentrations/.zarray"));
+        assert_eq!(zarray["shape"], serde_json::json!([1, 2, 2, 1, 1]));
+        assert_eq!(zarray["chunks"], serde_json::json!([1, 2, 2, 1, 1]));
+        let zattrs = read_json(&dir.path().join("concentrations/.zattrs"));
+        assert_eq!(
+            zattrs["_ARRAY_DIMENSIONS"],
+            serde_json::json!(["t", "x", "y", "z", "species"])
+        );
+        assert_eq!(
+            read_f64(&dir.path().join("concentrations/0.0.0.0.0")),
+            vec![0.0, 1.0, 2.0, 3.0]
+        );
+        assert_eq!(read_f64(&dir.path().join("x/0")), vec![1.0, 3.0]);
+        assert_eq!(read_f64(&dir.path().join("y/0")), vec![0.5, 1.5]);
+        assert_eq!(read_f64(&dir.path().join("t/0")), vec![1.0]);
+    }
+
+    #[test]
+    fn append_time_points() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut export =
+            ZarrFieldExport::create(dir.path(), [0.0], [1.0], [2], vec!["a".into(), "bc".into()])
+                .unwrap();
+        for n in 0..3 {
+            export
+                .write_time_point(n as f64, [([1], [n as f64, 1.0])])
+                .unwrap();
+        }
+        let zarray = read_json(&dir.path().join("concentrations/.zarray"));
+        assert_eq!(zarray["shape"], serde_json::json!([3, 2, 2]));
+        let zarray = read_json(&dir.path().join("t/.zarray"));
+        assert_eq!(zarray["shape"], serde_json::json!([3]));
+        let values = read_f64(&dir.path().join("concentrations/2.0.0"));
+        assert!(values[0].is_nan() && values[1].is_nan());
+        assert_eq!(values[2..], [2.0, 1.0]);
+        // Species names are stored as UTF-32 strings of equal length
+        let zarray = read_json(&dir.path().join("species/.zarray"));
+        assert_eq!(zarray["dtype"], "<U2");
+        let species = std::fs::read(dir.path().join("species/0")).unwrap();
+        assert_eq!(species.len(), 2 * 2 * 4);
+        assert_eq!(species[8], b'b');
+    }
+
+    #[test]
+    fn reject_invalid_voxels() {
+        let dir = tempfile::tempdir().unwrap();
+        let export =
+            ZarrFieldExport::create(dir.path(), [0.0; 2], [1.0; 2], [2, 2], vec!["a".into()])
+                .unwrap();
+        assert!(matches!(
+            export.assemble([([2, 0], [1.0])]),
+            Err(FieldExportError::IndexOutOfBounds { .. })
+        ));
+        assert!(matches!(
+            export.assemble([([0, 0], vec![1.0, 2.0])]),
+            Err(FieldExportError::SpeciesMismatch { .. })
+        ));
+    }
+}
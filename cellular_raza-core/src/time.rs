@@ -35,6 +35,117 @@ pub struct NextTimePoint<F> {
     pub event: Option<TimeEvent>,
 }
 
+/// Specifies at which simulated times results should be saved.
+///
+/// Schedules are independent of the chosen time increment.
+/// Every save point is mapped onto the iteration closest to it.
+/// ```
+/// # use cellular_raza_core::time::SaveSchedule;
+/// let schedule = SaveSchedule::Combined(vec![
+///     SaveSchedule::EveryDt(5.0),
+///     SaveSchedule::ExplicitList(vec![1.0, 12.5]),
+/// ]);
+/// let save_points = schedule.save_points(0.0, 20.0)?;
+/// assert_eq!(save_points, vec![0.0, 1.0, 5.0, 10.0, 12.5, 15.0, 20.0]);
+/// # Ok::<(), cellular_raza_concepts::TimeError>(())
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum SaveSchedule<F> {
+    /// Save every `dt` time units starting at the initial time.
+    /// The final time needs to be finite.
+    EveryDt(F),
+    /// Logarithmically spaced save points.
+    /// The first point lies `first` time units after the initial time and the last point at the
+    /// final time.
+    Log {
+        /// Offset of the first save point from the initial time
+        first: F,
+        /// Total number of save points
+        n_points: usize,
+    },
+    /// Save at explicitly given time points.
+    ExplicitList(Vec<F>),
+    /// Union of multiple schedules
+    Combined(Vec<SaveSchedule<F>>),
+}
+
+impl<F> SaveSchedule<F>
+where
+    F: num::Float + num::FromPrimitive,
+{
+    /// Calculates all sorted and unique save points in the interval `[t0, t_max]`.
+    pub fn save_points(&self, t0: F, t_max: F) -> Result<Vec<F>, TimeError> {
+        let mut save_points = self.unsorted_save_points(t0, t_max)?;
+        save_points.retain(|t| *t >= t0 && *t <= t_max);
+        save_points.sort_by(|x, y| x.partial_cmp(y).unwrap());
+        save_points.dedup();
+        Ok(save_points)
+    }
+
+    /// Calculates all save points without sorting or removing duplicates.
+    fn unsorted_save_points(&self, t0: F, t_max: F) -> Result<Vec<F>, TimeError> {
+        let from_usize = |n: usize| {
+            F::from_usize(n).ok_or(TimeError(format!(
+                "Could not convert usize {} to type {}",
+                n,
+                std::any::type_name::<F>()
+            )))
+        };
+        match self {
+            SaveSchedule::EveryDt(dt) => {
+                if *dt <= F::zero() || !dt.is_finite() {
+                    return Err(TimeError(
+                        "Time between save points must be positive and finite".to_owned(),
+                    ));
+                }
+                if !(t_max - t0).is_finite() {
+                    return Err(TimeError(
+                        "Saving every dt requires a finite simulated interval".to_owned(),
+                    ));
+                }
+                let mut save_points = vec![];
+                let mut n = 0;
+                loop {
+                    let t = t0 + from_usize(n)? * *dt;
+                    if t > t_max {
+                        break;
+                    }
+                    save_points.push(t);
+                    n += 1;
+                }
+                Ok(save_points)
+            }
+            SaveSchedule::Log { first, n_points } => {
+                if *first <= F::zero() || *first > t_max - t0 {
+                    return Err(TimeError(
+                        "First logarithmic save point must lie inside the simulated interval"
+                            .to_owned(),
+                    ));
+                }
+                match n_points {
+                    0 => Ok(vec![]),
+                    1 => Ok(vec![t0 + *first]),
+                    _ => {
+                        let ratio = (t_max - t0) / *first;
+                        let n_intervals = from_usize(n_points - 1)?;
+                        (0..*n_points)
+                            .map(|i| Ok(t0 + *first * ratio.powf(from_usize(i)? / n_intervals)))
+                            .collect()
+                    }
+                }
+            }
+            SaveSchedule::ExplicitList(save_points) => Ok(save_points.clone()),
+            SaveSchedule::Combined(schedules) => Ok(schedules
+                .iter()
+                .map(|schedule| schedule.unsorted_save_points(t0, t_max))
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .flatten()
+                .collect()),
+        }
+    }
+}
+
 /// Increments time of the simulation
 ///
 /// In the future we hope to add adaptive steppers depending on a specified accuracy function.
@@ -54,6 +165,14 @@ pub trait TimeStepper<F> {
     /// Update a given bar to show the current simulation state
     #[allow(unused)]
     fn update_bar(&self, bar: &mut kdam::Bar) -> Result<(), std::io::Error>;
+
+    /// Temporarily reduces the time increment for the next `n_steps` steps.
+    ///
+    /// Returns `false` if the stepper does not support changing its increment.
+    #[allow(unused)]
+    fn reduce_increment(&mut self, n_steps: usize) -> bool {
+        false
+    }
 }
 
 /// Time stepping with a fixed time length
@@ -162,6 +281,32 @@ where
         })
     }
 
+    /// Construct the stepper from a [SaveSchedule].
+    ///
+    /// The simulation ends at the last save point which is smaller or equal to `t_max`.
+    /// ```
+    /// # use cellular_raza_core::time::*;
+    /// let schedule = SaveSchedule::Log {
+    ///     first: 0.1,
+    ///     n_points: 4,
+    /// };
+    /// let mut stepper = FixedStepsize::from_save_schedule(0.0, 0.05, 100.0, &schedule)?;
+    /// let n_saves = std::iter::from_fn(|| stepper.advance().unwrap())
+    ///     .filter(|next| next.event.is_some())
+    ///     .count();
+    /// assert_eq!(n_saves, 4);
+    /// # Ok::<(), cellular_raza_concepts::TimeError>(())
+    /// ```
+    #[cfg_attr(feature = "tracing", instrument(skip_all))]
+    pub fn from_save_schedule(
+        t0: F,
+        dt: F,
+        t_max: F,
+        schedule: &SaveSchedule<F>,
+    ) -> Result<Self, TimeError> {
+        Self::from_partial_save_points(t0, dt, schedule.save_points(t0, t_max)?)
+    }
+
     /// Simple function to construct the stepper from an initial time point, the time increment and
     /// the time points at which the simulation should be saved. Notice that these saves do not
     /// cover [FullSaves](TimeEvent::FullSave) but only [PartialSaves](TimeEvent::PartialSave).
@@ -230,6 +375,75 @@ where
     }
 }
 
+impl FixedStepsize<f64> {
+    /// Similar to [Self::from_partial_save_interval] but takes values with explicit
+    /// [units](crate::units).
+    ///
+    /// All values are converted into the given `unit` which is then used by the simulation.
+    /// In addition, the time increment is checked to be positive and no larger than the save
+    /// interval and the simulated time span.
+    /// ```
+    /// # use cellular_raza_core::time::FixedStepsize;
+    /// # use cellular_raza_core::units::*;
+    /// let t0 = Time::new(0.0, TimeUnit::Hours);
+    /// let t_max = Time::new(2.0, TimeUnit::Hours);
+    /// let save_interval = Time::new(10.0, TimeUnit::Minutes);
+    ///
+    /// // Works fine
+    /// let dt = Time::new(30.0, TimeUnit::Seconds);
+    /// let stepper = FixedStepsize::from_units(t0, dt, t_max, save_interval, TimeUnit::Minutes);
+    /// assert!(stepper.is_ok());
+    ///
+    /// // This increment is larger than the save interval
+    /// let dt = Time::new(0.5, TimeUnit::Hours);
+    /// let stepper = FixedStepsize::from_units(t0, dt, t_max, save_interval, TimeUnit::Minutes);
+    /// assert!(stepper.is_err());
+    /// ```
+    pub fn from_units(
+        t0: crate::units::Time,
+        dt: crate::units::Time,
+        t_max: crate::units::Time,
+        save_interval: crate::units::Time,
+        unit: crate::units::TimeUnit,
+    ) -> Result<Self, TimeError> {
+        let zero = crate::units::Time::new(0.0, unit);
+        if dt <= zero {
+            return Err(TimeError(format!(
+                "Time increment must be positive but is {} {:?}",
+                dt.value_in(unit),
+                unit
+            )));
+        }
+        if t_max <= t0 {
+            return Err(TimeError(format!(
+                "Final time {} {:?} must be larger than initial time {} {:?}",
+                t_max.value_in(unit),
+                unit,
+                t0.value_in(unit),
+                unit
+            )));
+        }
+        if dt > save_interval || dt > t_max - t0 {
+            return Err(TimeError(format!(
+                "Time increment {} {:?} is larger than the save interval {} {:?} or \
+                simulated time span {} {:?}",
+                dt.value_in(unit),
+                unit,
+                save_interval.value_in(unit),
+                unit,
+                (t_max - t0).value_in(unit),
+                unit,
+            )));
+        }
+        Self::from_partial_save_interval(
+            t0.value_in(unit),
+            dt.value_in(unit),
+            t_max.value_in(unit),
+            save_interval.value_in(unit),
+        )
+    }
+}
+
 impl<F> TimeStepper<F> for FixedStepsize<F>
 where
     F: num::Float + num::FromPrimitive,
@@ -292,6 +506,171 @@ where
     }
 }
 
+/// Time stepping which can temporarily reduce its increment
+///
+/// By default, time is advanced by the increment `dt`.
+/// Calling [TimeStepper::reduce_increment] halves the increment for the given number of steps.
+/// Repeated calls while the increment is still reduced halve it again.
+/// Steps are shortened such that every save point is hit exactly.
+/// Consequently, the number of iterations is not known in advance.
+/// ```
+/// # use cellular_raza_core::time::*;
+/// let mut stepper = AdaptiveStepsize::from_save_schedule(
+///     0.0,
+///     0.5,
+///     4.0,
+///     &SaveSchedule::EveryDt(1.0),
+/// )?;
+/// let first = stepper.advance()?.unwrap();
+/// assert_eq!(first.increment, 0.5);
+///
+/// // Use a smaller increment for the next 2 steps
+/// stepper.reduce_increment(2);
+/// let next = stepper.advance()?.unwrap();
+/// assert_eq!(next.increment, 0.25);
+/// assert_eq!(next.time, 0.75);
+/// # Ok::<(), cellular_raza_concepts::TimeError>(())
+/// ```
+#[derive(Clone, Deserialize, Serialize)]
+pub struct AdaptiveStepsize<F> {
+    /// The default increment
+    dt: F,
+    /// Initial time point
+    t0: F,
+    /// Final time point
+    t_max: F,
+    /// Sorted save points which have not been reached yet
+    save_points: std::collections::VecDeque<F>,
+    /// Current time point
+    current_time: F,
+    /// Current iteration
+    current_iteration: usize,
+    /// Number of times the increment has been halved
+    reduction_level: i32,
+    /// Number of remaining steps with a reduced increment
+    reduced_steps_remaining: usize,
+}
+
+impl<F> AdaptiveStepsize<F>
+where
+    F: num::Float + num::FromPrimitive,
+{
+    /// Construct the stepper from initial time, default increment, final time and the times at
+    /// which results should be saved.
+    #[cfg_attr(feature = "tracing", instrument(skip_all))]
+    pub fn from_save_schedule(
+        t0: F,
+        dt: F,
+        t_max: F,
+        schedule: &SaveSchedule<F>,
+    ) -> Result<Self, TimeError> {
+        if dt <= F::zero() || !dt.is_finite() {
+            return Err(TimeError(
+                "Time increment must be positive and finite".to_owned(),
+            ));
+        }
+        if t_max <= t0 {
+            return Err(TimeError(
+                "Final time must be larger than initial time".to_owned(),
+            ));
+        }
+        let save_points = schedule
+            .save_points(t0, t_max)?
+            .into_iter()
+            .filter(|t| *t > t0)
+            .collect();
+        Ok(Self {
+            dt,
+            t0,
+            t_max,
+            save_points,
+            current_time: t0,
+            current_iteration: 0,
+            reduction_level: 0,
+            reduced_steps_remaining: 0,
+        })
+    }
+
+    /// Tolerance used to compare time points
+    fn tolerance(&self) -> F {
+        self.dt * F::from_f64(1e-9).unwrap_or(F::epsilon())
+    }
+}
+
+impl<F> TimeStepper<F> for AdaptiveStepsize<F>
+where
+    F: num::Float + num::FromPrimitive,
+{
+    #[cfg_attr(feature = "tracing", instrument(skip_all))]
+    fn advance(&mut self) -> Result<Option<NextTimePoint<F>>, TimeError> {
+        let tolerance = self.tolerance();
+        if self.current_time >= self.t_max - tolerance {
+            return Ok(None);
+        }
+        let mut increment = self.dt;
+        if self.reduced_steps_remaining > 0 {
+            self.reduced_steps_remaining -= 1;
+            increment = self.dt / F::from_f64(2.0f64.powi(self.reduction_level)).unwrap();
+        } else {
+            self.reduction_level = 0;
+        }
+        // Do not step over the next save point or the final time
+        let next_stop = self.save_points.front().copied().unwrap_or(self.t_max);
+        if self.current_time + increment > next_stop - tolerance {
+            increment = next_stop - self.current_time;
+        }
+        self.current_time = self.current_time + increment;
+        self.current_iteration += 1;
+        let event = match self.save_points.front() {
+            Some(t_save) if (*t_save - self.current_time).abs() <= tolerance => {
+                self.save_points.pop_front();
+                Some(TimeEvent::PartialSave)
+            }
+            _ => None,
+        };
+        Ok(Some(NextTimePoint {
+            increment,
+            time: self.current_time,
+            iteration: self.current_iteration,
+            event,
+        }))
+    }
+
+    fn get_last_full_save(&self) -> Option<(F, usize)> {
+        None
+    }
+
+    #[cfg_attr(feature = "tracing", instrument(skip_all))]
+    fn initialize_bar(&self) -> Result<kdam::Bar, TimeError> {
+        let bar_format = "\
+        {desc}{percentage:3.0}%|{animation}| \
+        [{elapsed}, \
+        {rate:.2}{unit}/s{postfix}]";
+        Ok(kdam::BarBuilder::default()
+            .total(1000)
+            .bar_format(bar_format)
+            .dynamic_ncols(true)
+            .build()?)
+    }
+
+    #[cfg_attr(feature = "tracing", instrument(skip_all))]
+    fn update_bar(&self, bar: &mut kdam::Bar) -> Result<(), std::io::Error> {
+        let progress = (self.current_time - self.t0) / (self.t_max - self.t0);
+        let n = (progress * F::from_usize(1000).unwrap())
+            .to_usize()
+            .unwrap_or(0);
+        let _ = bar.update_to(n)?;
+        Ok(())
+    }
+
+    fn reduce_increment(&mut self, n_steps: usize) -> bool {
+        // Limit the reduction to avoid stalling the simulation
+        self.reduction_level = (self.reduction_level + 1).min(16);
+        self.reduced_steps_remaining = n_steps;
+        true
+    }
+}
+
 #[cfg(test)]
 mod test_time_stepper {
     use rand::Rng;
@@ -411,6 +790,47 @@ mod test_time_stepper {
         test_stepping(3);
     }
 
+    #[test]
+    fn save_schedule_every_dt() {
+        let points = SaveSchedule::EveryDt(0.5).save_points(1.0, 3.2).unwrap();
+        assert_eq!(points, vec![1.0, 1.5, 2.0, 2.5, 3.0]);
+        assert!(SaveSchedule::EveryDt(0.0).save_points(0.0, 1.0).is_err());
+        assert!(SaveSchedule::EveryDt(1.0)
+            .save_points(0.0, f64::INFINITY)
+            .is_err());
+        assert!(SaveSchedule::EveryDt(1.0)
+            .save_points(0.0, f64::NAN)
+            .is_err());
+    }
+
+    #[test]
+    fn save_schedule_log() {
+        let schedule = SaveSchedule::Log {
+            first: 0.01,
+            n_points: 5,
+        };
+        let points = schedule.save_points(0.0, 100.0).unwrap();
+        let expected = [0.01_f64, 0.1, 1.0, 10.0, 100.0];
+        assert_eq!(points.len(), expected.len());
+        for (p, e) in points.iter().zip(expected) {
+            assert!((p - e).abs() / e < 1e-10);
+        }
+    }
+
+    #[test]
+    fn save_schedule_combined_stepper() {
+        let schedule = SaveSchedule::Combined(vec![
+            SaveSchedule::EveryDt(1.0),
+            SaveSchedule::ExplicitList(vec![0.5, 1.0, 7.0]),
+        ]);
+        let mut stepper = FixedStepsize::from_save_schedule(0.0, 0.1, 3.0, &schedule).unwrap();
+        let saved: Vec<_> = std::iter::from_fn(move || stepper.advance().unwrap())
+            .filter(|next| next.event.is_some())
+            .map(|next| next.iteration)
+            .collect();
+        assert_eq!(saved, vec![5, 10, 20, 30]);
+    }
+
     #[test]
     fn produce_correct_increments() {
         let t0 = 10.0;
@@ -428,4 +848,34 @@ mod test_time_stepper {
             }
         }
     }
+
+    #[test]
+    fn adaptive_hits_save_points() {
+        let schedule = SaveSchedule::ExplicitList(vec![0.35, 1.0, 2.0]);
+        let mut stepper = AdaptiveStepsize::from_save_schedule(0.0, 0.1, 2.0, &schedule).unwrap();
+        let mut saved = vec![];
+        let mut n_steps = 0;
+        while let Some(next) = stepper.advance().unwrap() {
+            if next.iteration == 5 {
+                assert!(stepper.reduce_increment(4));
+            }
+            if next.event.is_some() {
+                saved.push(next.time);
+            }
+            n_steps += 1;
+        }
+        assert_eq!(saved.len(), 3);
+        for (t, t_save) in saved.into_iter().zip([0.35_f64, 1.0, 2.0]) {
+            assert!((t - t_save).abs() < 1e-9);
+        }
+        // 20 regular steps, one additional step for the save point at 0.35 and
+        // 2 more steps due to the reduced increment
+        assert_eq!(n_steps, 23);
+    }
+
+    #[test]
+    fn fixed_stepsize_cannot_reduce() {
+        let mut stepper = FixedStepsize::from_partial_save_steps(0.0, 0.1, 10, 5).unwrap();
+        assert!(!stepper.reduce_increment(3));
+    }
 }
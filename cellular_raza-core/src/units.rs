@@ -0,0 +1,242 @@
+//! Lightweight units for specifying settings of a simulation
+//!
+//! Simulations internally operate on plain floating point values.
+//! When setting up a simulation, it is easy to mix up units such as specifying the time increment
+//! in minutes while the final time is given in seconds.
+//! The types in this module store every value in SI base units and only allow conversion to
+//! plain floats by explicitly naming the desired unit.
+//! ```
+//! # use cellular_raza_core::units::*;
+//! let dt = Time::new(30.0, TimeUnit::Seconds);
+//! let t_max = Time::new(2.0, TimeUnit::Hours);
+//! assert_eq!(t_max / dt, 240.0);
+//! assert_eq!(dt.value_in(TimeUnit::Minutes), 0.5);
+//! ```
+//! Builders such as [FixedStepsize::from_units](crate::time::FixedStepsize::from_units) accept
+//! these types directly and convert them into the unit used by the simulation.
+//! The inner loop of the simulation stays unit-free.
+
+use serde::{Deserialize, Serialize};
+
+/// Defines a newtype of a physical quantity together with its units
+macro_rules! define_quantity(
+    (
+        $(#[$quantity_meta:meta])*
+        $quantity:ident,
+        $(#[$unit_meta:meta])*
+        $unit:ident,
+        $($(#[$variant_meta:meta])* $variant:ident = $factor:expr),+ $(,)?
+    ) => {
+        $(#[$unit_meta])*
+        #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+        pub enum $unit {
+            $(
+                $(#[$variant_meta])*
+                $variant,
+            )+
+        }
+
+        impl $unit {
+            /// Conversion factor of this unit into the corresponding SI unit
+            pub fn to_si(&self) -> f64 {
+                match self {
+                    $($unit::$variant => $factor,)+
+                }
+            }
+        }
+
+        $(#[$quantity_meta])*
+        #[derive(Clone, Copy, Debug, Deserialize, PartialEq, PartialOrd, Serialize)]
+        pub struct $quantity {
+            /// Value in SI units
+            value_si: f64,
+        }
+
+        impl $quantity {
+            /// Constructs a new quantity from a value given in the specified unit.
+            pub fn new(value: f64, unit: $unit) -> Self {
+                Self {
+                    value_si: value * unit.to_si(),
+                }
+            }
+
+            /// Returns the value of the quantity measured in the specified unit.
+            pub fn value_in(&self, unit: $unit) -> f64 {
+                self.value_si / unit.to_si()
+            }
+
+            /// Returns the value of the quantity in SI units.
+            pub fn value_si(&self) -> f64 {
+                self.value_si
+            }
+        }
+
+        impl core::ops::Add for $quantity {
+            type Output = Self;
+            fn add(self, rhs: Self) -> Self {
+                Self {
+                    value_si: self.value_si + rhs.value_si,
+                }
+            }
+        }
+
+        impl core::ops::Sub for $quantity {
+            type Output = Self;
+            fn sub(self, rhs: Self) -> Self {
+                Self {
+                    value_si: self.value_si - rhs.value_si,
+                }
+            }
+        }
+
+        impl core::ops::Mul<f64> for $quantity {
+            type Output = Self;
+            fn mul(self, rhs: f64) -> Self {
+                Self {
+                    value_si: self.value_si * rhs,
+                }
+            }
+        }
+
+        impl core::ops::Div<f64> for $quantity {
+            type Output = Self;
+            fn div(self, rhs: f64) -> Self {
+                Self {
+                    value_si: self.value_si / rhs,
+                }
+            }
+        }
+
+        /// The ratio of two quantities of the same dimension is dimensionless.
+        impl core::ops::Div for $quantity {
+            type Output = f64;
+            fn div(self, rhs: Self) -> f64 {
+                self.value_si / rhs.value_si
+            }
+        }
+    }
+);
+
+define_quantity!(
+    /// A duration or point in time
+    Time,
+    /// Units of [Time]
+    TimeUnit,
+    /// SI unit of time
+    Seconds = 1.0,
+    /// 60 seconds
+    Minutes = 60.0,
+    /// 3600 seconds
+    Hours = 3600.0,
+    /// 86400 seconds
+    Days = 86400.0,
+);
+
+define_quantity!(
+    /// A distance
+    Length,
+    /// Units of [Length]
+    LengthUnit,
+    /// SI unit of length
+    Meters = 1.0,
+    /// $10^{-3}$ meters
+    Millimeters = 1e-3,
+    /// $10^{-6}$ meters
+    Micrometers = 1e-6,
+    /// $10^{-9}$ meters
+    Nanometers = 1e-9,
+);
+
+define_quantity!(
+    /// A force
+    Force,
+    /// Units of [Force]
+    ForceUnit,
+    /// SI unit of force
+    Newtons = 1.0,
+    /// $10^{-9}$ Newtons
+    Nanonewtons = 1e-9,
+    /// $10^{-12}$ Newtons
+    Piconewtons = 1e-12,
+);
+
+define_quantity!(
+    /// Amount of substance per volume
+    Concentration,
+    /// Units of [Concentration]
+    ConcentrationUnit,
+    /// SI unit $\text{mol}/\text{m}^3$
+    MolesPerCubicMeter = 1.0,
+    /// $\text{mol}/\text{L}$
+    Molar = 1e3,
+    /// $10^{-3}\text{mol}/\text{L}$
+    Millimolar = 1.0,
+    /// $10^{-6}\text{mol}/\text{L}$
+    Micromolar = 1e-3,
+    /// $10^{-9}\text{mol}/\text{L}$
+    Nanomolar = 1e-6,
+);
+
+/// Distance travelled per time
+impl core::ops::Div<Time> for Length {
+    type Output = Velocity;
+    fn div(self, rhs: Time) -> Velocity {
+        Velocity {
+            value_si: self.value_si / rhs.value_si,
+        }
+    }
+}
+
+/// Velocity stored in $\text{m}/\text{s}$.
+///
+/// Obtained by dividing a [Length] by a [Time].
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, PartialOrd, Serialize)]
+pub struct Velocity {
+    /// Value in SI units
+    value_si: f64,
+}
+
+impl Velocity {
+    /// Returns the value measured in the given units of length per time.
+    pub fn value_in(&self, length_unit: LengthUnit, time_unit: TimeUnit) -> f64 {
+        self.value_si * time_unit.to_si() / length_unit.to_si()
+    }
+}
+
+/// Distance travelled in a given time
+impl core::ops::Mul<Time> for Velocity {
+    type Output = Length;
+    fn mul(self, rhs: Time) -> Length {
+        Length {
+            value_si: self.value_si * rhs.value_si,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_units {
+    use super::*;
+
+    #[test]
+    fn time_conversion() {
+        let t = Time::new(1.5, TimeUnit::Hours);
+        assert_eq!(t.value_in(TimeUnit::Minutes), 90.0);
+        assert_eq!(t.value_si(), 5400.0);
+        assert!(Time::new(59.0, TimeUnit::Seconds) < Time::new(1.0, TimeUnit::Minutes));
+    }
+
+    #[test]
+    fn concentration_conversion() {
+        let c = Concentration::new(2.0, ConcentrationUnit::Micromolar);
+        assert!((c.value_in(ConcentrationUnit::Nanomolar) - 2000.0).abs() < 1e-9);
+        assert!((c.value_si() - 2e-3).abs() < 1e-15);
+    }
+
+    #[test]
+    fn velocity() {
+        let v = Length::new(10.0, LengthUnit::Micrometers) / Time::new(1.0, TimeUnit::Minutes);
+        assert!((v.value_in(LengthUnit::Micrometers, TimeUnit::Hours) - 600.0).abs() < 1e-9);
+        let l = v * Time::new(30.0, TimeUnit::Seconds);
+        assert!((l.value_in(LengthUnit::Micrometers) - 5.0).abs() < 1e-12);
+    }
+}
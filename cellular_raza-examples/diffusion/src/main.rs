@@ -376,6 +376,14 @@ fn main() {
     let mut subdomains_agents = vec![(subdomain0, agents), (subdomain1, vec![])];
 
     let dt = 0.01;
+    // Detect time increments which lead to negative concentrations before running the simulation
+    if let Err(e) = cellular_raza::building_blocks::check_diffusion_stability(
+        [subdomains_agents[0].0.diffusion_constant],
+        subdomains_agents[0].0.dx,
+        dt,
+    ) {
+        panic!("{e}");
+    }
     let start = std::time::Instant::now();
     for n in 0..1_000 {
         if n % 20 == 0 {
@@ -62,3 +62,39 @@ pub mod elli {
     pub use cellular_raza_core::time::*;
     pub use cellular_raza_core::*;
 }
+
+/// See [cellular_raza_core::backend::metropolis]
+///
+/// In order to use this backend, import it with
+/// ```
+/// use cellular_raza::prelude::metropolis::*;
+/// ```
+#[cfg(feature = "metropolis")]
+#[cfg_attr(docsrs, doc(cfg(feature = "metropolis")))]
+pub mod metropolis {
+    pub use cellular_raza_core::backend::metropolis::*;
+
+    pub use cellular_raza_building_blocks::*;
+    pub use cellular_raza_concepts::*;
+    pub use cellular_raza_core::storage::*;
+    pub use cellular_raza_core::time::*;
+    pub use cellular_raza_core::*;
+}
+
+/// See [cellular_raza_core::backend::cpm]
+///
+/// In order to use this backend, import it with
+/// ```
+/// use cellular_raza::prelude::cpm::*;
+/// ```
+#[cfg(feature = "cpm")]
+#[cfg_attr(docsrs, doc(cfg(feature = "cpm")))]
+pub mod cpm {
+    pub use cellular_raza_core::backend::cpm::*;
+
+    pub use cellular_raza_building_blocks::*;
+    pub use cellular_raza_concepts::*;
+    pub use cellular_raza_core::storage::*;
+    pub use cellular_raza_core::time::*;
+    pub use cellular_raza_core::*;
+}
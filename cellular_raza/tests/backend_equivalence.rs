@@ -0,0 +1,206 @@
+#![cfg(all(feature = "chili", feature = "cpu_os_threads"))]
+
+use cellular_raza::building_blocks::cartesian_cuboid_n_old::CartesianCuboid2;
+use cellular_raza::building_blocks::*;
+use cellular_raza::concepts::reactions_old::CellularReactions;
+use cellular_raza::concepts::*;
+use cellular_raza::core::backend::cpu_os_threads::{
+    CellStorageSink, SimulationMetaParams, SimulationSetup, SimulationSupervisor, StorageSinks,
+    Strategies, TimeSetup, VoxelStorageSink,
+};
+use cellular_raza::core::backend::equivalence::*;
+use cellular_raza::core::storage::*;
+use nalgebra::{Vector1, Vector2};
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// The [cpu_os_threads](cellular_raza::core::backend::cpu_os_threads) backend requires
+/// intracellular reactions which match the extracellular concentrations of its voxels.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct NoReactions;
+
+impl CellularReactions<Vector1<f64>, Vector1<f64>> for NoReactions {
+    fn calculate_intra_and_extracellular_reaction_increment(
+        &self,
+        _: &Vector1<f64>,
+        _: &Vector1<f64>,
+    ) -> Result<(Vector1<f64>, Vector1<f64>), CalcError> {
+        Ok((Vector1::zeros(), Vector1::zeros()))
+    }
+
+    fn get_intracellular(&self) -> Vector1<f64> {
+        Vector1::zeros()
+    }
+
+    fn set_intracellular(&mut self, _: Vector1<f64>) {}
+}
+
+type Agent = ModularCell<
+    NewtonDamped2D,
+    NoInteraction,
+    NoCycle,
+    NoReactions,
+    NoExtracellularGradientSensing,
+>;
+
+const DT: f64 = 0.01;
+const N_STEPS: u64 = 200;
+const SAVE_INTERVAL: u64 = 20;
+
+fn agents() -> Vec<Agent> {
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(3);
+    (0..10)
+        .map(|_| ModularCell {
+            mechanics: NewtonDamped2D {
+                pos: Vector2::from([rng.gen_range(30.0..70.0), rng.gen_range(30.0..70.0)]),
+                vel: Vector2::from([rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)]),
+                damping_constant: 0.5,
+                mass: 1.0,
+            },
+            interaction: NoInteraction,
+            interaction_extracellular: NoExtracellularGradientSensing,
+            cycle: NoCycle,
+            cellular_reactions: NoReactions,
+            volume: 1.0,
+        })
+        .collect()
+}
+
+fn run_chili() -> Result<Trajectory<Agent>, Box<dyn std::error::Error>> {
+    let domain =
+        CartesianCuboid::from_boundaries_and_interaction_range([0.0; 2], [100.0; 2], 20.0)?;
+    let settings = cellular_raza::core::backend::chili::Settings {
+        n_threads: 1.try_into().unwrap(),
+        show_progressbar: false,
+        storage: StorageBuilder::new().priority([StorageOption::Memory]),
+        time: cellular_raza::core::time::FixedStepsize::from_partial_save_steps(
+            0.0,
+            DT,
+            N_STEPS,
+            SAVE_INTERVAL,
+        )?,
+    };
+    let agents = agents();
+    let storage = cellular_raza::core::backend::chili::run_simulation!(
+        domain: domain,
+        agents: agents,
+        settings: settings,
+        aspects: [Mechanics],
+    )?;
+    Ok(trajectory_from_chili(&storage)?)
+}
+
+fn cpu_os_threads_setup(
+) -> Result<SimulationSetup<CartesianCuboid2, Agent>, Box<dyn std::error::Error>> {
+    let domain =
+        CartesianCuboid2::from_boundaries_and_interaction_ranges([0.0; 2], [100.0; 2], [20.0; 2])?;
+    let time = TimeSetup {
+        t_start: 0.0,
+        t_eval: (0..=N_STEPS)
+            .map(|i| (i as f64 * DT, i % SAVE_INTERVAL == 0))
+            .collect(),
+    };
+    let setup = SimulationSetup::new(
+        domain,
+        agents(),
+        time,
+        SimulationMetaParams::default(),
+        // Results are handed off to storage threads which are finished after the simulation
+        StorageBuilder::new()
+            .priority([StorageOption::Memory])
+            .write_behind(WriteBehind::new(2))
+            .init(),
+        (),
+    );
+    Ok(setup)
+}
+
+fn run_cpu_os_threads() -> Result<Trajectory<Agent>, Box<dyn std::error::Error>> {
+    let setup = cpu_os_threads_setup()?;
+    let mut supervisor = SimulationSupervisor::initialize_from_setup(setup);
+    supervisor.config.show_progressbar = false;
+    let result = supervisor.run_full_sim()?;
+    Ok(trajectory_from_cpu_os_threads(&result.storage_cells)?)
+}
+
+#[test]
+fn damped_motion_agrees_between_backends() -> Result<(), Box<dyn std::error::Error>> {
+    let position = |cell: &Agent| cell.mechanics.pos.as_slice().to_vec();
+    let trajectory_chili = run_chili()?;
+    let mut trajectory_cpu_os_threads = run_cpu_os_threads()?;
+
+    // The chili backend starts storing after the first step while the cpu_os_threads backend
+    // also stores the initial state. This state has to agree with the given agents.
+    assert!(!trajectory_chili.contains_key(&0));
+    let initial_state = trajectory_cpu_os_threads.pop_first();
+    assert_eq!(
+        initial_state.as_ref().map(|(iteration, _)| *iteration),
+        Some(0)
+    );
+    assert_trajectories_agree(
+        &Trajectory::from([(0, agents())]),
+        &Trajectory::from_iter(initial_state),
+        position,
+        position,
+        0.0,
+    )?;
+
+    // Both backends use different solvers which agree up to the discretization error
+    assert_trajectories_agree(
+        &trajectory_chili,
+        &trajectory_cpu_os_threads,
+        position,
+        position,
+        0.05,
+    )?;
+    Ok(())
+}
+
+#[test]
+fn cpu_os_threads_stores_in_custom_sinks() -> Result<(), Box<dyn std::error::Error>> {
+    type CellMemory = MemoryStorageInterface<CellularIdentifier, CellAgentBox<Agent>>;
+    let cells = |instance| -> Result<CellStorageSink<Agent>, StorageError> {
+        Ok(Box::new(CellMemory::open_or_create(
+            std::path::Path::new(""),
+            instance,
+        )?))
+    };
+    let voxels = |instance| -> Result<VoxelStorageSink<_>, StorageError> {
+        Ok(Box::new(MemoryStorageInterface::open_or_create(
+            std::path::Path::new(""),
+            instance,
+        )?))
+    };
+    let no_strategy = |_: &mut _| {};
+    let new_supervisor = || -> Result<_, Box<dyn std::error::Error>> {
+        let mut supervisor = SimulationSupervisor::initialize_with_storage_sinks(
+            cpu_os_threads_setup()?,
+            Strategies {
+                voxel_definition_strategies: &no_strategy,
+            },
+            StorageSinks {
+                cells: &cells,
+                voxels: &voxels,
+            },
+        )?;
+        supervisor.config.show_progressbar = false;
+        Ok(supervisor)
+    };
+
+    // Results which were not stored by a StorageManager can not be loaded
+    assert!(new_supervisor()?.run_full_sim().is_err());
+
+    let mut iterations = std::collections::BTreeSet::new();
+    for worker_storage in new_supervisor()?.run_full_sim_into_sinks()? {
+        let memory = worker_storage
+            .storage_cells
+            .into_any()
+            .downcast::<CellMemory>()
+            .unwrap();
+        iterations.extend(memory.get_all_iterations()?);
+    }
+    let expected: std::collections::BTreeSet<_> =
+        (0..=N_STEPS).step_by(SAVE_INTERVAL as usize).collect();
+    assert_eq!(iterations, expected);
+    Ok(())
+}
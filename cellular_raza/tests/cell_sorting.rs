@@ -0,0 +1,54 @@
+#![cfg(feature = "chili")]
+
+use cellular_raza::building_blocks::*;
+use cellular_raza::core::storage::*;
+use serde::{Deserialize, Serialize};
+
+#[test]
+fn differential_adhesion_increases_homotypic_contacts() -> Result<(), Box<dyn std::error::Error>> {
+    let scenario = CellSortingScenario {
+        n_cells: [40, 40],
+        adhesion: [[1.0, 0.1], [0.1, 1.0]],
+        domain_size: 40.0,
+        n_steps: 5_000,
+        save_interval: 1_000,
+        ..Default::default()
+    };
+    let setup = scenario.build_2d()?;
+    let initial = scenario.measure(setup.agents.iter());
+
+    let settings = cellular_raza::core::backend::chili::Settings {
+        n_threads: 1.try_into().unwrap(),
+        show_progressbar: false,
+        storage: StorageBuilder::new().priority([StorageOption::Memory]),
+        time: cellular_raza::core::time::FixedStepsize::from_partial_save_steps(
+            0.0,
+            setup.dt,
+            setup.n_steps,
+            setup.save_interval,
+        )?,
+    };
+    let domain = setup.domain;
+    let agents = setup.agents;
+    let storage = cellular_raza::core::backend::chili::run_simulation!(
+        domain: domain,
+        agents: agents,
+        settings: settings,
+        aspects: [Mechanics, Interaction],
+    )?;
+    let last_iteration = storage
+        .cells
+        .get_all_iterations()?
+        .into_iter()
+        .max()
+        .unwrap();
+    let cells = storage
+        .cells
+        .load_all_elements_at_iteration(last_iteration)?;
+    let last = scenario.measure(cells.values().map(|(cbox, _)| &cbox.cell));
+
+    assert_eq!(cells.len(), 80);
+    assert!(initial.homotypic_fraction < scenario.random_homotypic_fraction() + 0.1);
+    assert!(last.homotypic_fraction > initial.homotypic_fraction);
+    Ok(())
+}
@@ -0,0 +1,146 @@
+#![cfg(feature = "chili")]
+
+use cellular_raza::building_blocks::*;
+use cellular_raza::concepts::*;
+use cellular_raza::core::backend::chili::{
+    CellBox, CellIdentifier, DivisionRelaxation, DivisionThrottle, OverlapMeasure, Settings,
+};
+use cellular_raza::core::storage::*;
+use cellular_raza::core::time::FixedStepsize;
+use nalgebra::Vector2;
+use serde::{Deserialize, Serialize};
+
+#[derive(CellAgent, Clone, Debug, Deserialize, Serialize)]
+struct Cell {
+    #[Mechanics]
+    mechanics: NewtonDamped2D,
+    #[Interaction]
+    interaction: MorsePotential,
+    divided: bool,
+}
+
+impl Cycle<Cell> for Cell {
+    fn update_cycle(
+        _rng: &mut rand_chacha::ChaCha8Rng,
+        _dt: &f64,
+        cell: &mut Cell,
+    ) -> Option<CycleEvent> {
+        (!cell.divided).then_some(CycleEvent::Division)
+    }
+
+    fn divide(_rng: &mut rand_chacha::ChaCha8Rng, cell: &mut Cell) -> Result<Cell, DivisionError> {
+        // Daughter cells are placed almost on top of each other
+        cell.divided = true;
+        let mut daughter = cell.clone();
+        cell.mechanics.pos.x -= 0.05;
+        daughter.mechanics.pos.x += 0.05;
+        Ok(daughter)
+    }
+}
+
+impl OverlapMeasure<Vector2<f64>, f64> for Cell {
+    fn overlap_radius(&self) -> f64 {
+        self.interaction.radius
+    }
+
+    fn distance(pos1: &Vector2<f64>, pos2: &Vector2<f64>) -> f64 {
+        (pos1 - pos2).norm()
+    }
+}
+
+type Setup = (
+    CartesianCuboid<f64, 2>,
+    Vec<Cell>,
+    Settings<FixedStepsize<f64>, false>,
+);
+
+fn setup(location: &std::path::Path) -> Result<Setup, Box<dyn std::error::Error>> {
+    // All cells start in the first voxel such that they cause a single mass division event
+    let domain = CartesianCuboid::from_boundaries_and_n_voxels([0.0; 2], [40.0, 10.0], [4, 1])?;
+    let agents = [2.0, 5.0, 8.0]
+        .into_iter()
+        .flat_map(|x| [2.5, 7.5].map(|y| (x, y)))
+        .map(|(x, y)| Cell {
+            mechanics: NewtonDamped2D {
+                pos: Vector2::from([x, y]),
+                vel: Vector2::zeros(),
+                damping_constant: 1.0,
+                mass: 1.0,
+            },
+            interaction: MorsePotential {
+                radius: 1.0,
+                potential_stiffness: 0.5,
+                cutoff: 2.5,
+                strength: 1.0,
+            },
+            divided: false,
+        })
+        .collect();
+    let settings = Settings {
+        n_threads: 2.try_into().unwrap(),
+        show_progressbar: false,
+        // Results of both subdomains are stored in the same location
+        storage: StorageBuilder::new()
+            .location(location)
+            .priority([StorageOption::SerdeJson]),
+        time: FixedStepsize::from_partial_save_steps(0.0, 0.01, 3, 1)?,
+    };
+    Ok((domain, agents, settings))
+}
+
+/// Smallest distance between any two cells at the last save point
+fn min_distance<A>(
+    cells: &StorageManager<CellIdentifier, (CellBox<Cell>, A)>,
+) -> Result<f64, Box<dyn std::error::Error>>
+where
+    A: Clone + for<'a> Deserialize<'a> + Serialize,
+{
+    let iteration = cells.get_all_iterations()?.into_iter().max().unwrap();
+    let positions: Vec<_> = cells
+        .load_all_elements_at_iteration(iteration)?
+        .into_values()
+        .map(|(cbox, _)| cbox.cell.mechanics.pos)
+        .collect();
+    assert_eq!(positions.len(), 12);
+    let mut min = f64::INFINITY;
+    for (n, p1) in positions.iter().enumerate() {
+        for p2 in positions.iter().skip(n + 1) {
+            min = min.min((p1 - p2).norm());
+        }
+    }
+    Ok(min)
+}
+
+#[test]
+fn overlapping_daughters_relax_after_mass_division() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp_dir = tempfile::TempDir::new()?;
+    let (domain, agents, settings) = setup(&tmp_dir.path().join("unrelaxed"))?;
+    let storage = cellular_raza::core::backend::chili::run_simulation!(
+        domain: domain,
+        agents: agents,
+        settings: settings,
+        aspects: [Mechanics, Interaction, Cycle],
+    )?;
+    let distance_unrelaxed = min_distance(&storage.cells)?;
+
+    let (domain, agents, settings) = setup(&tmp_dir.path().join("relaxed"))?;
+    let throttle = DivisionThrottle {
+        mass_division_threshold: Some(4),
+        ..Default::default()
+    };
+    let relaxation = DivisionRelaxation::new(100, 0.05).max_displacement(0.2);
+    let storage = cellular_raza::core::backend::chili::run_simulation!(
+        domain: domain,
+        agents: agents,
+        settings: settings,
+        aspects: [Mechanics, Interaction, Cycle],
+        division_throttle: throttle,
+        division_relaxation: relaxation,
+    )?;
+    let distance_relaxed = min_distance(&storage.cells)?;
+
+    // Only the first step contains divisions and both subdomains performed the sub-steps
+    assert!(distance_unrelaxed < 0.2);
+    assert!(distance_relaxed > 1.0, "{distance_relaxed}");
+    Ok(())
+}
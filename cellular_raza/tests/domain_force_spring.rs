@@ -1,7 +1,9 @@
 use cellular_raza::building_blocks::{CartesianCuboid, NewtonDamped2D};
 use cellular_raza::concepts::*;
 use cellular_raza_building_blocks::CartesianSubDomain;
-use cellular_raza_core::backend::chili::{Settings, SimulationError};
+use cellular_raza_core::backend::chili::{
+    ForceMeasure, MechanicsClamp, OverlapMeasure, Settings, SimulationError,
+};
 use cellular_raza_core::storage::{StorageBuilder, StorageInterfaceLoad, StorageOption};
 use cellular_raza_core::time::FixedStepsize;
 
@@ -107,3 +109,73 @@ fn spring_single_particle() -> Result<(), SimulationError> {
     }
     Ok(())
 }
+
+#[derive(CellAgent, Clone, Debug, Deserialize, Serialize)]
+struct ClampedAgent {
+    #[Mechanics]
+    mechanics: NewtonDamped2D,
+}
+
+impl ForceMeasure<Vector2<f64>, f64> for ClampedAgent {
+    fn force_magnitude(force: &Vector2<f64>) -> f64 {
+        force.norm()
+    }
+}
+
+impl OverlapMeasure<Vector2<f64>, f64> for ClampedAgent {
+    fn overlap_radius(&self) -> f64 {
+        0.0
+    }
+
+    fn distance(pos1: &Vector2<f64>, pos2: &Vector2<f64>) -> f64 {
+        (pos1 - pos2).norm()
+    }
+}
+
+#[test]
+fn spring_force_is_clamped() -> Result<(), SimulationError> {
+    let spring_strength = 0.0032;
+    let mass = 0.5;
+    let dt = 0.001;
+    let domain = MyDomain {
+        cuboid: CartesianCuboid::from_boundaries_and_n_voxels([-77.0; 2], [77.0; 2], [3; 2])?,
+        spring_strength,
+    };
+    let time = FixedStepsize::from_partial_save_interval(0.0, dt, 1.0, 0.1)?;
+    let storage = StorageBuilder::new().priority([StorageOption::Memory]);
+    let settings = Settings {
+        time,
+        storage,
+        n_threads: 1.try_into().unwrap(),
+        show_progressbar: false,
+    };
+    let x0 = 10.0;
+    let agents = [ClampedAgent {
+        mechanics: NewtonDamped2D {
+            pos: [x0, 0.0].into(),
+            vel: [0.0, 0.0].into(),
+            damping_constant: 0.0,
+            mass,
+        },
+    }];
+    // The spring exerts a force of about 0.032 which is limited throughout the simulation
+    let max_force = 0.01;
+    let clamp = MechanicsClamp::new().max_force(max_force);
+    let storager = cellular_raza::core::backend::chili::run_simulation!(
+        agents: agents,
+        domain: domain,
+        settings: settings,
+        aspects: [Mechanics, DomainForce],
+        mechanics_clamp: clamp,
+    )?;
+    let hists = storager.cells.load_all_element_histories()?;
+    let (_, history) = hists.into_iter().next().unwrap();
+    for (iter, (cbox, _)) in history {
+        // Constant acceleration towards the origin
+        let t = iter as f64 * dt;
+        let exact = x0 - 0.5 * max_force / mass * t.powi(2);
+        assert!((cbox.cell.mechanics.pos.x - exact).abs() < 1e-3);
+    }
+    assert_eq!(clamp.n_force_clamps(), 1000);
+    Ok(())
+}
@@ -0,0 +1,121 @@
+#![cfg(feature = "chili")]
+
+use cellular_raza::building_blocks::*;
+use cellular_raza::core::storage::*;
+use serde::{Deserialize, Serialize};
+
+#[test]
+fn contact_inhibited_monolayer_expands() -> Result<(), Box<dyn std::error::Error>> {
+    let scenario = MonolayerScenario {
+        domain_size: 100.0,
+        n_steps: 4_000,
+        save_interval: 200,
+        ..Default::default()
+    };
+    let setup = scenario.build()?;
+
+    let settings = cellular_raza::core::backend::chili::Settings {
+        n_threads: 1.try_into().unwrap(),
+        show_progressbar: false,
+        storage: StorageBuilder::new().priority([StorageOption::Memory]),
+        time: cellular_raza::core::time::FixedStepsize::from_partial_save_steps(
+            0.0,
+            setup.dt,
+            setup.n_steps,
+            setup.save_interval,
+        )?,
+    };
+    let domain = setup.domain;
+    let agents = setup.agents;
+    let storage = cellular_raza::core::backend::chili::run_simulation!(
+        domain: domain,
+        agents: agents,
+        settings: settings,
+        aspects: [Mechanics, Interaction, Cycle],
+    )?;
+    let save_points: Vec<(u64, Vec<MonolayerCell>)> = storage
+        .cells
+        .load_all_elements()?
+        .into_iter()
+        .map(|(iteration, cells)| {
+            let cells = cells.into_values().map(|(cbox, _)| cbox.cell).collect();
+            (iteration, cells)
+        })
+        .collect();
+    let analysis = scenario.analyze(
+        save_points
+            .iter()
+            .map(|(iteration, cells)| (*iteration, cells.iter())),
+    );
+
+    let first = analysis.snapshots.first().unwrap();
+    let last = analysis.snapshots.last().unwrap();
+    assert_eq!(first.n_cells, 1);
+    assert!(last.n_cells > 16);
+    assert!(last.front_radius > first.front_radius);
+    assert!(analysis.front_speed > 0.0);
+
+    // Every division is recorded exactly once
+    let last_iteration = save_points.last().unwrap().0;
+    let divisions: Vec<_> = storage
+        .divisions
+        .as_ref()
+        .expect("divisions are stored with the Cycle aspect")
+        .load_all_elements()?
+        .into_iter()
+        .filter(|(iteration, _)| *iteration <= last_iteration)
+        .flat_map(|(_, events)| events.into_values())
+        .collect();
+    assert_eq!(divisions.len(), last.n_cells - first.n_cells);
+    for event in divisions.iter() {
+        assert_ne!(event.daughters[0], event.daughters[1]);
+        assert!(event.positions.iter().all(|pos| pos.norm().is_finite()));
+    }
+    Ok(())
+}
+
+#[test]
+fn monolayer_stops_at_max_cells() -> Result<(), Box<dyn std::error::Error>> {
+    use cellular_raza::core::backend::chili::{StopReason, StoppingCriteria};
+    let scenario = MonolayerScenario {
+        domain_size: 100.0,
+        n_steps: 4_000,
+        save_interval: 1_000,
+        ..Default::default()
+    };
+    let setup = scenario.build()?;
+
+    let settings = cellular_raza::core::backend::chili::Settings {
+        n_threads: 1.try_into().unwrap(),
+        show_progressbar: false,
+        storage: StorageBuilder::new().priority([StorageOption::Memory]),
+        time: cellular_raza::core::time::FixedStepsize::from_partial_save_steps(
+            0.0,
+            setup.dt,
+            setup.n_steps,
+            setup.save_interval,
+        )?,
+    };
+    let domain = setup.domain;
+    let agents = setup.agents;
+    let criteria = StoppingCriteria::new().max_cells(8);
+    let storage = cellular_raza::core::backend::chili::run_simulation!(
+        domain: domain,
+        agents: agents,
+        settings: settings,
+        aspects: [Mechanics, Interaction, Cycle],
+        stopping_criteria: criteria,
+    )?;
+    let record = criteria.stop_record().unwrap();
+    assert_eq!(record.reason, StopReason::MaxCells);
+    assert!(record.observables.n_cells >= 8);
+    assert!(record.observables.iteration < setup.n_steps as usize);
+
+    // The final state is saved although it is no regular save point
+    let iterations = storage.cells.get_all_iterations()?;
+    assert_eq!(
+        iterations.last(),
+        Some(&(record.observables.iteration as u64))
+    );
+    Ok(())
+}
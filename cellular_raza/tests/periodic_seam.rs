@@ -0,0 +1,111 @@
+use cellular_raza::building_blocks::{CartesianCuboid, NewtonDamped2D};
+use cellular_raza::concepts::*;
+use cellular_raza::core::backend::chili::Settings;
+use cellular_raza::core::storage::{StorageBuilder, StorageInterfaceLoad, StorageOption};
+use cellular_raza::core::time::FixedStepsize;
+
+use nalgebra::Vector2;
+use serde::{Deserialize, Serialize};
+
+/// Linear spring which only acts within its cutoff
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct Spring {
+    strength: f64,
+    cutoff: f64,
+}
+
+impl Interaction<Vector2<f64>, Vector2<f64>, Vector2<f64>> for Spring {
+    fn get_interaction_information(&self) {}
+
+    fn calculate_force_between(
+        &self,
+        own_pos: &Vector2<f64>,
+        _own_vel: &Vector2<f64>,
+        ext_pos: &Vector2<f64>,
+        _ext_vel: &Vector2<f64>,
+        _ext_info: &(),
+    ) -> Result<(Vector2<f64>, Vector2<f64>), CalcError> {
+        let dir = ext_pos - own_pos;
+        let force = match dir.norm() < self.cutoff {
+            true => self.strength * dir,
+            false => Vector2::zeros(),
+        };
+        Ok((force, -force))
+    }
+}
+
+#[derive(CellAgent, Clone, Debug, Deserialize, Serialize)]
+struct Agent {
+    #[Mechanics]
+    mechanics: NewtonDamped2D,
+    #[Interaction]
+    interaction: Spring,
+}
+
+/// Runs two cells which are placed at opposite sides of the domain and returns their final
+/// positions.
+fn run_pair(periodic: bool) -> Result<Vec<Vector2<f64>>, Box<dyn std::error::Error>> {
+    // Both ends of the domain belong to different subdomains
+    let domain = CartesianCuboid::from_boundaries_and_n_voxels([0.0; 2], [10.0, 2.0], [5, 1])?
+        .periodic(periodic);
+    let agents = [0.5, 9.5].map(|x| Agent {
+        mechanics: NewtonDamped2D {
+            pos: Vector2::from([x, 1.0]),
+            vel: Vector2::zeros(),
+            damping_constant: 1.0,
+            mass: 1.0,
+        },
+        interaction: Spring {
+            strength: 1.0,
+            cutoff: 2.0,
+        },
+    });
+    let tmp_dir = tempfile::TempDir::new()?;
+    let settings = Settings {
+        n_threads: 2.try_into().unwrap(),
+        time: FixedStepsize::from_partial_save_steps(0.0, 0.01, 20, 20)?,
+        // Results of both subdomains are stored in the same location
+        storage: StorageBuilder::new()
+            .location(tmp_dir.path())
+            .priority([StorageOption::SerdeJson]),
+        show_progressbar: false,
+    };
+    let storage = cellular_raza::core::backend::chili::run_simulation!(
+        agents: agents,
+        domain: domain,
+        settings: settings,
+        aspects: [Mechanics, Interaction],
+    )?;
+    let cells = storage.cells.load_all_elements_at_iteration(20)?;
+    Ok(cells
+        .into_values()
+        .map(|(cbox, _)| cbox.cell.mechanics.pos)
+        .collect())
+}
+
+#[test]
+fn cells_attract_across_periodic_seam() -> Result<(), Box<dyn std::error::Error>> {
+    // Without periodic boundaries, the cells are too far apart to interact
+    let positions = run_pair(false)?;
+    assert_eq!(positions.len(), 2);
+    for pos in positions.iter() {
+        assert!(pos.x == 0.5 || pos.x == 9.5);
+    }
+
+    let positions = run_pair(true)?;
+    assert_eq!(positions.len(), 2);
+    let diff = positions[0].x - positions[1].x;
+    let distance = (diff - 10.0 * (diff / 10.0).round()).abs();
+    assert!(distance < 0.99, "{distance}");
+    // Both cells moved symmetrically towards the seam
+    let seam_offsets: Vec<_> = positions
+        .iter()
+        .map(|pos| (pos.x - 10.0 * (pos.x / 10.0).round()).abs())
+        .collect();
+    assert!((seam_offsets[0] - seam_offsets[1]).abs() < 1e-12);
+    for pos in positions.iter() {
+        assert!(pos.x >= 0.0 && pos.x < 10.0);
+        assert_eq!(pos.y, 1.0);
+    }
+    Ok(())
+}
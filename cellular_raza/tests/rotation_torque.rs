@@ -0,0 +1,153 @@
+#![cfg(feature = "chili")]
+
+use cellular_raza::building_blocks::{CartesianCuboid, NewtonDamped2D};
+use cellular_raza::concepts::*;
+use cellular_raza::core::backend::chili::Settings;
+use cellular_raza::core::storage::{StorageBuilder, StorageInterfaceLoad, StorageOption};
+use cellular_raza::core::time::FixedStepsize;
+
+use nalgebra::Vector2;
+use serde::{Deserialize, Serialize};
+
+/// Does not exert any forces such that only the orientation of cells changes
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct NoForce;
+
+impl Interaction<Vector2<f64>, Vector2<f64>, Vector2<f64>> for NoForce {
+    fn get_interaction_information(&self) {}
+
+    fn calculate_force_between(
+        &self,
+        _own_pos: &Vector2<f64>,
+        _own_vel: &Vector2<f64>,
+        _ext_pos: &Vector2<f64>,
+        _ext_vel: &Vector2<f64>,
+        _ext_info: &(),
+    ) -> Result<(Vector2<f64>, Vector2<f64>), CalcError> {
+        Ok((Vector2::zeros(), Vector2::zeros()))
+    }
+}
+
+#[derive(CellAgent, Clone, Debug, Deserialize, Serialize)]
+struct Agent {
+    #[Mechanics]
+    mechanics: NewtonDamped2D,
+    #[Interaction]
+    interaction: NoForce,
+    angle: f64,
+    angular_velocity: f64,
+    /// Strength of the torque which aligns neighboring cells
+    alignment: f64,
+    rotational_damping: f64,
+}
+
+impl Orientation<f64> for Agent {
+    fn orientation(&self) -> f64 {
+        self.angle
+    }
+
+    fn set_orientation(&mut self, orientation: &f64) {
+        self.angle = *orientation;
+    }
+}
+
+impl AngularVelocity<f64> for Agent {
+    fn angular_velocity(&self) -> f64 {
+        self.angular_velocity
+    }
+
+    fn set_angular_velocity(&mut self, angular_velocity: &f64) {
+        self.angular_velocity = *angular_velocity;
+    }
+}
+
+impl InteractionTorque<Vector2<f64>, f64, f64> for Agent {
+    fn calculate_torque_between(
+        &self,
+        _own_pos: &Vector2<f64>,
+        own_ang: &f64,
+        _ext_pos: &Vector2<f64>,
+        ext_ang: &f64,
+        _ext_info: &(),
+    ) -> Result<(f64, f64), CalcError> {
+        let torque = self.alignment * (ext_ang - own_ang).sin();
+        Ok((torque, -torque))
+    }
+}
+
+/// Overdamped rotation without any random contributions
+impl RotationalMechanics<f64, f64, f64> for Agent {
+    fn get_random_rotational_contribution(
+        &self,
+        _rng: &mut rand_chacha::ChaCha8Rng,
+        _dt: f64,
+    ) -> Result<(f64, f64), RngError> {
+        Ok((0.0, 0.0))
+    }
+
+    fn calculate_rotational_increment(&self, torque: f64) -> Result<(f64, f64), CalcError> {
+        Ok((torque / self.rotational_damping, 0.0))
+    }
+}
+
+#[test]
+fn torque_aligns_cells_across_subdomains() -> Result<(), Box<dyn std::error::Error>> {
+    // Both cells are placed in neighboring voxels which belong to different subdomains
+    let domain = CartesianCuboid::from_boundaries_and_n_voxels([0.0; 2], [20.0, 5.0], [4, 1])?;
+    let agents = [(9.0, 0.0), (11.0, 1.0)].map(|(x, angle)| Agent {
+        mechanics: NewtonDamped2D {
+            pos: Vector2::from([x, 2.5]),
+            vel: Vector2::zeros(),
+            damping_constant: 1.0,
+            mass: 1.0,
+        },
+        interaction: NoForce,
+        angle,
+        angular_velocity: 0.0,
+        alignment: 1.0,
+        rotational_damping: 1.0,
+    });
+    let tmp_dir = tempfile::TempDir::new()?;
+    let settings = Settings {
+        n_threads: 2.try_into().unwrap(),
+        time: FixedStepsize::from_partial_save_steps(0.0, 0.01, 500, 250)?,
+        // Results of both subdomains are stored in the same location
+        storage: StorageBuilder::new()
+            .location(tmp_dir.path())
+            .priority([StorageOption::SerdeJson]),
+        show_progressbar: false,
+    };
+    let storage = cellular_raza::core::backend::chili::run_simulation!(
+        agents: agents,
+        domain: domain,
+        settings: settings,
+        aspects: [Mechanics, Interaction, RotationalMechanics],
+    )?;
+
+    let angles = |iteration| -> Result<Vec<f64>, Box<dyn std::error::Error>> {
+        let mut cells: Vec<_> = storage
+            .cells
+            .load_all_elements_at_iteration(iteration)?
+            .into_values()
+            .map(|(cbox, _)| cbox.cell)
+            .collect();
+        cells.sort_by(|c1, c2| c1.mechanics.pos.x.total_cmp(&c2.mechanics.pos.x));
+        // Positions are not changed by the rotation
+        assert_eq!(cells[0].mechanics.pos, Vector2::from([9.0, 2.5]));
+        assert_eq!(cells[1].mechanics.pos, Vector2::from([11.0, 2.5]));
+        Ok(cells.into_iter().map(|cell| cell.angle).collect())
+    };
+
+    // The torques are opposite and equal such that the total angle is conserved
+    let halfway = angles(250)?;
+    assert!(halfway[0] > 0.0 && halfway[1] < 1.0);
+    assert!(halfway[0] < halfway[1]);
+    assert!((halfway[0] + halfway[1] - 1.0).abs() < 1e-12);
+
+    // Both cells are aligned at the end of the simulation
+    let end = angles(500)?;
+    assert!((end[0] - 0.5).abs() < 1e-3, "{}", end[0]);
+    assert!((end[1] - 0.5).abs() < 1e-3, "{}", end[1]);
+    assert!(end[1] - end[0] < halfway[1] - halfway[0]);
+    Ok(())
+}
@@ -0,0 +1,99 @@
+#![cfg(feature = "chili")]
+
+use std::sync::{Arc, Mutex};
+
+use cellular_raza::building_blocks::*;
+use cellular_raza::concepts::*;
+use cellular_raza::core::backend::chili::{Settings, SimulationSnapshot, SnapshotHook};
+use cellular_raza::core::storage::*;
+use cellular_raza::core::time::FixedStepsize;
+use nalgebra::Vector2;
+use serde::{Deserialize, Serialize};
+
+#[derive(CellAgent, Clone, Debug, Deserialize, Serialize)]
+struct Agent {
+    #[Mechanics]
+    mechanics: NewtonDamped2D,
+    #[Interaction]
+    interaction: MorsePotential,
+}
+
+type Snapshot = SimulationSnapshot<usize, CartesianSubDomain<f64, 2>, Agent>;
+
+#[test]
+fn snapshots_of_all_subdomains_at_save_points() -> Result<(), Box<dyn std::error::Error>> {
+    let domain = CartesianCuboid::from_boundaries_and_n_voxels([0.0; 2], [40.0, 10.0], [4, 1])?;
+    let agents: Vec<_> = (0..8)
+        .map(|n| Agent {
+            mechanics: NewtonDamped2D {
+                pos: Vector2::from([2.5 + 5.0 * n as f64, 5.0]),
+                vel: Vector2::from([0.1, 0.0]),
+                damping_constant: 0.0,
+                mass: 1.0,
+            },
+            interaction: MorsePotential {
+                radius: 1.0,
+                potential_stiffness: 0.5,
+                cutoff: 2.5,
+                strength: 1.0,
+            },
+        })
+        .collect();
+    let tmp_dir = tempfile::TempDir::new()?;
+    let settings = Settings {
+        n_threads: 2.try_into().unwrap(),
+        time: FixedStepsize::from_partial_save_steps(0.0, 0.1, 20, 5)?,
+        // Results of both subdomains are stored in the same location
+        storage: StorageBuilder::new()
+            .location(tmp_dir.path())
+            .priority([StorageOption::SerdeJson]),
+        show_progressbar: false,
+    };
+
+    let snapshots = Arc::new(Mutex::new(Vec::new()));
+    let snapshots_hook = snapshots.clone();
+    let hook = SnapshotHook::new(move |iteration, snapshot: &Snapshot| {
+        snapshots_hook
+            .lock()
+            .unwrap()
+            .push((iteration, snapshot.clone()));
+    });
+    let storage = cellular_raza::core::backend::chili::run_simulation!(
+        agents: agents,
+        domain: domain,
+        settings: settings,
+        aspects: [Mechanics, Interaction],
+        snapshot_hook: hook,
+    )?;
+
+    // The callback was executed once for every save point
+    let snapshots = snapshots.lock().unwrap();
+    let iterations: Vec<_> = snapshots
+        .iter()
+        .map(|(iteration, _)| *iteration as u64)
+        .collect();
+    let mut stored_iterations = storage.cells.get_all_iterations()?;
+    stored_iterations.sort();
+    assert_eq!(
+        iterations,
+        stored_iterations
+            .into_iter()
+            .filter(|iteration| *iteration > 0)
+            .collect::<Vec<_>>()
+    );
+
+    // Every snapshot contains the same state as the storage
+    for (iteration, snapshot) in snapshots.iter() {
+        assert_eq!(snapshot.cells.len(), 8);
+        assert_eq!(snapshot.subdomains.len(), 2);
+        let stored = storage
+            .cells
+            .load_all_elements_at_iteration(*iteration as u64)?;
+        for (identifier, cbox) in snapshot.cells.iter() {
+            let (stored_cbox, _) = &stored[identifier];
+            // Positions may differ in the last digit due to the json format
+            assert!((cbox.cell.mechanics.pos - stored_cbox.cell.mechanics.pos).norm() < 1e-12);
+        }
+    }
+    Ok(())
+}
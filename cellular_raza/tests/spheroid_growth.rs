@@ -0,0 +1,57 @@
+#![cfg(feature = "chili")]
+
+use cellular_raza::building_blocks::*;
+use cellular_raza::core::storage::*;
+use serde::{Deserialize, Serialize};
+
+#[test]
+fn spheroid_develops_necrotic_core() -> Result<(), Box<dyn std::error::Error>> {
+    let scenario = SpheroidScenario::default();
+    let setup = scenario.build()?;
+
+    let settings = cellular_raza::core::backend::chili::Settings {
+        n_threads: 1.try_into().unwrap(),
+        show_progressbar: false,
+        storage: StorageBuilder::new().priority([StorageOption::Memory]),
+        time: cellular_raza::core::time::FixedStepsize::from_partial_save_steps(
+            0.0,
+            setup.dt,
+            setup.n_steps,
+            setup.save_interval,
+        )?,
+    };
+    let domain = setup.domain;
+    let agents = setup.agents;
+    let storage = cellular_raza::core::backend::chili::run_simulation!(
+        domain: domain,
+        agents: agents,
+        settings: settings,
+        aspects: [Mechanics, Interaction, Cycle, ReactionsExtra],
+    )?;
+    let save_points: Vec<(u64, Vec<SpheroidCell>)> = storage
+        .cells
+        .load_all_elements()?
+        .into_iter()
+        .map(|(iteration, cells)| {
+            let cells = cells.into_values().map(|(cbox, _)| cbox.cell).collect();
+            (iteration, cells)
+        })
+        .collect();
+    let analysis = scenario.analyze(
+        save_points
+            .iter()
+            .map(|(iteration, cells)| (*iteration, cells.iter())),
+    );
+
+    let first = analysis.snapshots.first().unwrap();
+    let last = analysis.snapshots.last().unwrap();
+    assert_eq!(first.n_necrotic, 0);
+    assert!(analysis.onset_of_necrosis.is_some());
+    // Proliferating rim around a quiescent layer and a necrotic core
+    assert!(last.n_proliferating > 0);
+    assert!(last.n_quiescent > 0);
+    assert!(last.n_necrotic > 0);
+    assert!(last.necrotic_radius < last.radius);
+    assert!(last.n_cells() > first.n_cells());
+    Ok(())
+}
@@ -44,6 +44,9 @@ fn main_sim(
         aspects: [Mechanics],
         determinism: true,
     )?;
+    // Agents without the Cycle aspect can not divide and thus no divisions are stored
+    assert!(storager.divisions.is_none());
+    assert!(!storager.get_path()?.join("divisions").exists());
     Ok(storager
         .cells
         .load_all_elements()?
@@ -80,6 +83,7 @@ fn storage_ron() -> Result<(), SimulationError> {
     Ok(())
 }
 
+#[cfg(feature = "sled")]
 #[test]
 fn storage_sled() -> Result<(), SimulationError> {
     let r1 = main_sim([Sled])?;
@@ -90,6 +94,7 @@ fn storage_sled() -> Result<(), SimulationError> {
     Ok(())
 }
 
+#[cfg(feature = "sled")]
 #[test]
 fn storage_sled_temp() -> Result<(), SimulationError> {
     let r1 = main_sim([SledTemp])?;
@@ -100,6 +105,17 @@ fn storage_sled_temp() -> Result<(), SimulationError> {
     Ok(())
 }
 
+#[cfg(feature = "sqlite")]
+#[test]
+fn storage_sqlite() -> Result<(), SimulationError> {
+    let r1 = main_sim([Sqlite])?;
+    let r2 = main_sim([Sqlite])?;
+    let r3 = main_sim([Sqlite])?;
+    assert_eq!(r1, r2);
+    assert_eq!(r2, r3);
+    Ok(())
+}
+
 #[test]
 fn storage_memory() -> Result<(), SimulationError> {
     let r1 = main_sim([Memory])?;